@@ -0,0 +1,86 @@
+//! Command to decode canonical ABI lift/lower traces
+//!
+//! Reads a trace produced by the `wrt-intercept` logging strategy with
+//! canonical tracing enabled and pretty-prints each `LIFT:`/`LOWER:` entry
+//! using the function signatures from a WIT file.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_build_core::abi_trace::TraceEntry;
+use wrt_format::wit_parser::WitDocument;
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the abi-trace command
+#[derive(Debug, Args)]
+pub struct AbiTraceArgs {
+    /// Path to the trace file
+    #[arg(help = "Path to the canonical ABI trace file")]
+    pub trace_file: PathBuf,
+
+    /// Path to the WIT file describing the traced functions
+    #[arg(
+        short = 'w',
+        long = "wit",
+        help = "WIT file with the signatures of the traced functions"
+    )]
+    pub wit_file: PathBuf,
+
+    /// Only decode entries for this function
+    #[arg(
+        short = 'f',
+        long = "function",
+        help = "Only decode entries for this function"
+    )]
+    pub function: Option<String>,
+}
+
+/// Execute the abi-trace command
+pub fn execute(args: AbiTraceArgs, output: &OutputManager) -> Result<()> {
+    let wit_source = fs::read_to_string(&args.wit_file)
+        .context(format!("Failed to read {}", args.wit_file.display()))?;
+    let document = WitDocument::parse(&wit_source).map_err(|e| {
+        anyhow::anyhow!("Failed to parse WIT file {}: {}", args.wit_file.display(), e)
+    })?;
+
+    let trace = fs::read_to_string(&args.trace_file)
+        .context(format!("Failed to read {}", args.trace_file.display()))?;
+
+    let mut decoded = 0usize;
+    let mut failed = 0usize;
+    for (line_number, line) in trace.lines().enumerate() {
+        let Some(entry) = TraceEntry::parse(line.trim()) else {
+            continue;
+        };
+
+        let result = entry.and_then(|entry| {
+            if args.function.as_ref().is_some_and(|f| *f != entry.function) {
+                return Ok(None);
+            }
+            entry.decode(&document).map(Some)
+        });
+
+        match result {
+            Ok(Some(entry)) => {
+                output.output_result(&entry)?;
+                decoded += 1;
+            },
+            Ok(None) => {},
+            Err(e) => {
+                output.error(&format!("line {}: {}", line_number + 1, e));
+                failed += 1;
+            },
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} trace entries could not be decoded", failed, decoded + failed);
+    }
+
+    if !output.is_json_mode() {
+        output.success(&format!("Decoded {} trace entries", decoded));
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Command to generate AUTOSAR Adaptive ara::com bridges for components
+//!
+//! Reads a WIT world and writes a Rust host shim that offers every exported
+//! interface as an ara::com service skeleton and sends every imported
+//! interface to an ara::com service proxy, so integrators can expose WASM
+//! components as Adaptive AUTOSAR services.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_build_core::ara_com::{AraComConfig, AraComPlan, ServiceRole};
+use wrt_format::wit_parser::WitDocument;
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the ara-com command
+#[derive(Debug, Args)]
+pub struct AraComArgs {
+    /// Path to the WIT file declaring the world
+    #[arg(help = "WIT file declaring the world to bridge")]
+    pub wit_file: PathBuf,
+
+    /// World to bridge
+    #[arg(short = 'w', long = "world", help = "World to bridge (the only world if omitted)")]
+    pub world: Option<String>,
+
+    /// Service ID of the first service
+    #[arg(long = "service-id-base", default_value = "0x1000", value_parser = parse_id)]
+    pub service_id_base: u16,
+
+    /// Instance ID the services are offered and looked up under
+    #[arg(long = "instance-id", default_value = "1", value_parser = parse_id)]
+    pub instance_id: u16,
+
+    /// File to write the generated shim to
+    #[arg(long = "out-file", help = "Write the shim to this file instead of stdout")]
+    pub output_file: Option<PathBuf>,
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal service or instance ID
+pub fn parse_id(value: &str) -> std::result::Result<u16, String> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|e| format!("invalid ID '{}': {}", value, e))
+}
+
+/// Execute the ara-com command
+pub fn execute(args: AraComArgs, output: &OutputManager) -> Result<()> {
+    let wit_source = fs::read_to_string(&args.wit_file)
+        .context(format!("Failed to read {}", args.wit_file.display()))?;
+    let document = WitDocument::parse(&wit_source).map_err(|e| {
+        anyhow::anyhow!("Failed to parse WIT file {}: {}", args.wit_file.display(), e)
+    })?;
+    let config = AraComConfig {
+        service_id_base: args.service_id_base,
+        instance_id: args.instance_id,
+    };
+    let plan = AraComPlan::new(&document, args.world.as_deref(), config)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    for skipped in &plan.skipped {
+        output.warning(&format!("Skipping external interface {}", skipped));
+    }
+
+    let source = plan.to_rust();
+    match &args.output_file {
+        Some(path) => {
+            fs::write(path, &source).context(format!("Failed to write {}", path.display()))?;
+            if output.is_json_mode() {
+                println!("{}", serde_json::to_string_pretty(&plan)?);
+            } else {
+                for service in &plan.services {
+                    let role = match service.role {
+                        ServiceRole::Skeleton => "skeleton",
+                        ServiceRole::Proxy => "proxy",
+                    };
+                    output.indent(&format!(
+                        "{} -> {} {:#06x} v{}.{} ({} methods)",
+                        service.module,
+                        role,
+                        service.service_id,
+                        service.major_version,
+                        service.minor_version,
+                        service.methods.len()
+                    ));
+                }
+                output.success(&format!(
+                    "Wrote ara::com bridge for world '{}' with {} services to {}",
+                    plan.world,
+                    plan.services.len(),
+                    path.display()
+                ));
+            }
+        },
+        None if output.is_json_mode() => println!("{}", serde_json::to_string_pretty(&plan)?),
+        None => print!("{}", source),
+    }
+    Ok(())
+}
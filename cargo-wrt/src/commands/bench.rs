@@ -0,0 +1,109 @@
+//! Command to run benchmarks and gate the latency-critical paths
+//!
+//! Without `--critical` all workspace benchmarks run. With `--critical` only
+//! the critical interpreter paths run, and the command fails when one of
+//! them regressed beyond the tolerance against the baseline.
+
+use std::{path::PathBuf, process::Command};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_build_core::{
+    BuildSystem,
+    bench::{BASELINE_FILE, CriticalBenchOptions, DEFAULT_TOLERANCE_PERCENT},
+};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the bench command
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Run and gate only the latency-critical benchmarks
+    #[arg(long, help = "Run and gate the latency-critical benchmarks")]
+    pub critical: bool,
+
+    /// Baseline the critical benchmarks are compared against
+    #[arg(long, default_value = BASELINE_FILE, help = "Baseline of critical benchmark means")]
+    pub baseline: PathBuf,
+
+    /// Allowed slowdown of a critical benchmark in percent
+    #[arg(
+        long,
+        default_value_t = DEFAULT_TOLERANCE_PERCENT,
+        help = "Allowed slowdown in percent"
+    )]
+    pub tolerance: f64,
+
+    /// Accept the measured means as the new baseline
+    #[arg(long = "update-baseline", help = "Accept the measured means")]
+    pub update_baseline: bool,
+}
+
+/// Execute the bench command
+pub fn execute(build_system: &BuildSystem, args: BenchArgs, output: &OutputManager) -> Result<()> {
+    let workspace_root = build_system.workspace_root();
+    if !args.critical {
+        let status = Command::new("cargo")
+            .args(["bench", "--workspace"])
+            .current_dir(workspace_root)
+            .status()
+            .context("Failed to run cargo bench")?;
+        if !status.success() {
+            anyhow::bail!("Benchmarks failed");
+        }
+        return Ok(());
+    }
+
+    let baseline_path = workspace_root.join(&args.baseline);
+    let options = CriticalBenchOptions {
+        baseline: Some(baseline_path.clone()),
+        tolerance_percent: args.tolerance,
+    };
+    let report = build_system
+        .critical_benchmarks(&options)
+        .context("Critical benchmarks failed")?;
+
+    if args.update_baseline {
+        report.write_baseline(&baseline_path)?;
+    }
+
+    if output.is_json_mode() {
+        println!("{}", report.to_json()?);
+    } else {
+        output.header("Latency-Critical Benchmarks");
+        for result in &report.results {
+            let mean = result.mean_ns.map_or("missing".to_string(), |ns| format!("{:.1} ns", ns));
+            let change = result
+                .change_percent
+                .map(|change| format!(" ({:+.1}% against baseline)", change))
+                .unwrap_or_default();
+            output.indent(&format!("{}: {}{}", result.name, mean, change));
+        }
+        if report.baseline.is_none() {
+            output.info("No baseline found, run with --update-baseline to record one");
+        }
+        if args.update_baseline {
+            output.success(&format!("Baseline written to {}", baseline_path.display()));
+        }
+    }
+
+    if !args.update_baseline && !report.passed() {
+        for result in report.regressions() {
+            match result.change_percent {
+                Some(change) => output.error(&format!(
+                    "{} regressed by {:.1}% (tolerance {:.1}%)",
+                    result.name, change, report.tolerance_percent
+                )),
+                None => output.error(&format!(
+                    "{} is in the baseline but did not run",
+                    result.name
+                )),
+            }
+        }
+        anyhow::bail!(
+            "{} critical benchmarks regressed",
+            report.regressions().count()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Command to extract the inter-component call graph of a composition
+//!
+//! Registers every component with the component linker, resolves their
+//! imports without instantiating anything and writes the resulting
+//! import-to-export bindings as Graphviz DOT or JSON.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use wrt_component::components::ComponentLinker;
+
+use crate::helpers::OutputManager;
+
+/// Output format of the call graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CallGraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// JSON document with components, bindings and unresolved imports
+    Json,
+}
+
+/// Arguments for the call-graph command
+#[derive(Debug, Args)]
+pub struct CallGraphArgs {
+    /// Component binaries, optionally named as `name=path`
+    #[arg(required = true, help = "Component binaries, optionally named as name=path")]
+    pub components: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "dot", help = "Output format")]
+    pub format: CallGraphFormat,
+
+    /// File to write the graph to
+    #[arg(long = "out-file", help = "Write the graph to this file instead of stdout")]
+    pub output_file: Option<PathBuf>,
+
+    /// Fail if any import cannot be bound
+    #[arg(long = "deny-unresolved", help = "Fail if any import cannot be bound")]
+    pub deny_unresolved: bool,
+}
+
+/// Split a `name=path` argument, naming the component after the file stem
+/// when no name is given
+fn parse_component_arg(arg: &str) -> (String, PathBuf) {
+    match arg.split_once('=') {
+        Some((name, path)) if !name.is_empty() => (name.to_string(), PathBuf::from(path)),
+        _ => {
+            let path = PathBuf::from(arg);
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| arg.to_string());
+            (name, path)
+        },
+    }
+}
+
+/// Execute the call-graph command
+pub fn execute(args: CallGraphArgs, output: &OutputManager) -> Result<()> {
+    let mut linker = ComponentLinker::new();
+    for arg in &args.components {
+        let (name, path) = parse_component_arg(arg);
+        let binary = fs::read(&path).context(format!("Failed to read {}", path.display()))?;
+        linker.add_component(name.clone(), &binary).map_err(|e| {
+            anyhow::anyhow!("Failed to register component {} ({}): {}", name, path.display(), e)
+        })?;
+    }
+
+    let graph = linker.resolve_call_graph();
+    for unresolved in graph.unresolved() {
+        output.warning(&format!(
+            "Unresolved import {} in component {}",
+            unresolved.import, unresolved.importer
+        ));
+    }
+
+    let format = if output.is_json_mode() { CallGraphFormat::Json } else { args.format };
+    let rendered = match format {
+        CallGraphFormat::Dot => graph.to_dot(),
+        CallGraphFormat::Json => graph.to_json(),
+    };
+
+    match &args.output_file {
+        Some(path) => {
+            fs::write(path, &rendered).context(format!("Failed to write {}", path.display()))?;
+            if !output.is_json_mode() {
+                output.success(&format!(
+                    "Wrote call graph of {} components with {} bindings to {}",
+                    graph.components().len(),
+                    graph.bindings().len(),
+                    path.display()
+                ));
+            }
+        },
+        None => println!("{}", rendered.trim_end()),
+    }
+
+    if args.deny_unresolved && !graph.unresolved().is_empty() {
+        anyhow::bail!("{} imports could not be bound", graph.unresolved().len());
+    }
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Command to resolve a composition and pin its inputs in a lockfile
+//!
+//! Wires the components by import and export names, checks the wires and
+//! computes the instantiation order. The digests of the component binaries
+//! and WIT packages and the resulting wires are written to a lockfile, or,
+//! with `--locked`, checked against an existing one so that a deployment
+//! composes exactly the audited inputs.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_component::components::{CompositionGraph, CompositionLock, LOCKFILE_NAME};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the compose command
+#[derive(Debug, Args)]
+pub struct ComposeArgs {
+    /// Component binaries, optionally named as `name=path`
+    #[arg(required = true, help = "Component binaries, optionally named as name=path")]
+    pub components: Vec<String>,
+
+    /// WIT packages the components were built against
+    #[arg(long = "wit", help = "WIT package file or directory to lock")]
+    pub wit: Vec<PathBuf>,
+
+    /// Lockfile to write or verify
+    #[arg(long, default_value = LOCKFILE_NAME, help = "Lockfile to write or verify")]
+    pub lockfile: PathBuf,
+
+    /// Verify the composition against the lockfile instead of writing it
+    #[arg(long, help = "Fail if the composition differs from the lockfile")]
+    pub locked: bool,
+
+    /// Reject wires whose export declares no type
+    #[arg(long, help = "Reject wires whose export declares no type")]
+    pub strict: bool,
+}
+
+/// Split a `name=path` argument, naming the component after the file stem
+/// when no name is given
+fn parse_component_arg(arg: &str) -> (String, PathBuf) {
+    match arg.split_once('=') {
+        Some((name, path)) if !name.is_empty() => (name.to_string(), PathBuf::from(path)),
+        _ => {
+            let path = PathBuf::from(arg);
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| arg.to_string());
+            (name, path)
+        },
+    }
+}
+
+/// Collect the `.wit` files below `path` in a stable order
+fn wit_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .context(format!("Failed to read {}", path.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "wit") {
+                wit_files(&entry, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Name and sources of the WIT package at `path`
+///
+/// The package is named by its `package` declaration, or after the path
+/// when it has none. The sources of a directory are the contents of its
+/// `.wit` files, each preceded by its path relative to the directory.
+fn read_wit_package(path: &Path) -> Result<(String, Vec<u8>)> {
+    let mut files = Vec::new();
+    wit_files(path, &mut files)?;
+
+    let mut name = None;
+    let mut sources = Vec::new();
+    for file in &files {
+        let text =
+            fs::read_to_string(file).context(format!("Failed to read {}", file.display()))?;
+        if name.is_none() {
+            name = text.lines().find_map(|line| {
+                let package = line.trim().strip_prefix("package ")?;
+                Some(package.trim_end_matches(';').trim().to_string())
+            });
+        }
+        let relative = file.strip_prefix(path).unwrap_or(file);
+        sources.extend_from_slice(relative.to_string_lossy().as_bytes());
+        sources.push(0);
+        sources.extend_from_slice(text.as_bytes());
+        sources.push(0);
+    }
+    let name = name.unwrap_or_else(|| path.display().to_string());
+    Ok((name, sources))
+}
+
+/// Execute the compose command
+pub fn execute(args: ComposeArgs, output: &OutputManager) -> Result<()> {
+    let mut graph = CompositionGraph::new().with_strict_typing(args.strict);
+    let mut lock = CompositionLock::new();
+    for arg in &args.components {
+        let (name, path) = parse_component_arg(arg);
+        let binary = fs::read(&path).context(format!("Failed to read {}", path.display()))?;
+        graph.add_component_binary(&name, &binary).map_err(|e| {
+            anyhow::anyhow!("Failed to add component {} ({}): {}", name, path.display(), e)
+        })?;
+        lock.lock_component(&name, &binary);
+    }
+    for path in &args.wit {
+        let (name, sources) = read_wit_package(path)?;
+        lock.lock_package(&name, &sources);
+    }
+
+    graph.connect_by_name().map_err(|e| anyhow::anyhow!("Failed to wire components: {}", e))?;
+    let plan = match graph.resolve() {
+        Ok(plan) => plan,
+        Err(e) => {
+            for mismatch in graph.mismatches() {
+                let wire = &mismatch.wire;
+                output.warning(&format!(
+                    "{}.{} cannot be served by {}.{}: {:?}",
+                    graph.name(wire.importer).unwrap_or_default(),
+                    wire.import,
+                    graph.name(wire.exporter).unwrap_or_default(),
+                    wire.export,
+                    mismatch.reason
+                ));
+            }
+            anyhow::bail!("Failed to resolve composition: {}", e);
+        },
+    };
+    lock.lock_wires(&graph);
+
+    let order: Vec<&str> = plan.order.iter().filter_map(|&node| graph.name(node)).collect();
+    let open_imports: Vec<String> = plan
+        .open_imports
+        .iter()
+        .map(|open| format!("{}.{}", graph.name(open.importer).unwrap_or_default(), open.import))
+        .collect();
+
+    let differences = if args.locked {
+        let text = fs::read_to_string(&args.lockfile)
+            .context(format!("Failed to read {}", args.lockfile.display()))?;
+        let locked = CompositionLock::from_lockfile(&text)
+            .map_err(|e| anyhow::anyhow!("Invalid lockfile {}: {}", args.lockfile.display(), e))?;
+        locked.verify(&lock)
+    } else {
+        fs::write(&args.lockfile, lock.to_lockfile())
+            .context(format!("Failed to write {}", args.lockfile.display()))?;
+        Vec::new()
+    };
+
+    if output.is_json_mode() {
+        let report = serde_json::json!({
+            "order": order,
+            "open_imports": open_imports,
+            "wires": lock.wires.len(),
+            "packages": lock.packages.iter().map(|package| &package.name).collect::<Vec<_>>(),
+            "lockfile": args.lockfile.display().to_string(),
+            "locked": args.locked,
+            "differences": differences.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        output.header("Composition");
+        output.indent(&format!("Instantiation order: {}", order.join(", ")));
+        output.indent(&format!("Wires: {}", lock.wires.len()));
+        for import in &open_imports {
+            output.indent(&format!("Host import: {import}"));
+        }
+        for difference in &differences {
+            output.warning(&difference.to_string());
+        }
+        if differences.is_empty() {
+            let action = if args.locked { "Verified" } else { "Wrote" };
+            output.success(&format!(
+                "{} {} ({} components, {} WIT packages)",
+                action,
+                args.lockfile.display(),
+                lock.components.len(),
+                lock.packages.len()
+            ));
+        }
+    }
+
+    if !differences.is_empty() {
+        anyhow::bail!(
+            "Composition differs from {} in {} places",
+            args.lockfile.display(),
+            differences.len()
+        );
+    }
+    Ok(())
+}
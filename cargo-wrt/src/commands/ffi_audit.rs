@@ -0,0 +1,127 @@
+//! Command to audit unsafe code and FFI boundaries across the workspace
+//!
+//! Enumerates unsafe blocks, extern boundaries and syscall wrappers, writes
+//! the audit table for assessors and fails when an unsafe block without
+//! justification was added since the accepted baseline.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_build_core::{
+    BuildSystem,
+    ffi_audit::{BASELINE_FILE, FfiAuditOptions},
+    verify::AllowedUnsafeConfig,
+};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the ffi-audit command
+#[derive(Debug, Args)]
+pub struct FfiAuditArgs {
+    /// Directory for ffi-audit.json and ffi-audit.md
+    #[arg(
+        long,
+        default_value = "target/ffi-audit",
+        help = "Directory for the audit table"
+    )]
+    pub output_dir: PathBuf,
+
+    /// Baseline of accepted unjustified unsafe blocks
+    #[arg(long, default_value = BASELINE_FILE, help = "Baseline of accepted unjustified blocks")]
+    pub baseline: PathBuf,
+
+    /// Accept the current unjustified unsafe blocks as the new baseline
+    #[arg(
+        long = "update-baseline",
+        help = "Accept the current unjustified blocks"
+    )]
+    pub update_baseline: bool,
+
+    /// Path to allowed unsafe configuration file
+    #[arg(
+        long,
+        default_value = "allowed-unsafe.toml",
+        help = "Allowed unsafe configuration"
+    )]
+    pub allowed_unsafe: PathBuf,
+}
+
+/// Execute the ffi-audit command
+pub fn execute(
+    build_system: &BuildSystem,
+    args: FfiAuditArgs,
+    output: &OutputManager,
+) -> Result<()> {
+    let workspace_root = build_system.workspace_root();
+    let allowed_unsafe_path = workspace_root.join(&args.allowed_unsafe);
+    let allowed_unsafe = if allowed_unsafe_path.exists() {
+        Some(
+            AllowedUnsafeConfig::load_from_file(&allowed_unsafe_path)
+                .context(format!("Failed to load {}", allowed_unsafe_path.display()))?,
+        )
+    } else {
+        None
+    };
+
+    let baseline_path = workspace_root.join(&args.baseline);
+    let options = FfiAuditOptions {
+        baseline: Some(baseline_path.clone()),
+        allowed_unsafe,
+    };
+    let report = build_system.ffi_audit(&options).context("FFI audit failed")?;
+    let outputs = report.write(&workspace_root.join(&args.output_dir))?;
+
+    if args.update_baseline {
+        report.write_baseline(&baseline_path)?;
+    }
+
+    if output.is_json_mode() {
+        println!("{}", report.to_json()?);
+    } else {
+        let summary = &report.summary;
+        output.header("Unsafe and FFI Audit");
+        for counts in &report.crates {
+            output.indent(&format!(
+                "{}: {} unsafe blocks, {} unsafe items, {} extern items, {} syscall wrappers, {} \
+                 unjustified",
+                counts.crate_name,
+                counts.unsafe_blocks,
+                counts.unsafe_items,
+                counts.extern_items,
+                counts.syscall_wrappers,
+                counts.unjustified
+            ));
+        }
+        output.info(&format!(
+            "{} sites, {} justified, {} unjustified",
+            summary.total, summary.justified, summary.unjustified
+        ));
+        for path in &outputs {
+            output.success(&format!("Wrote {}", path.display()));
+        }
+        if args.update_baseline {
+            output.success(&format!(
+                "Accepted baseline written to {}",
+                baseline_path.display()
+            ));
+        }
+    }
+
+    if !args.update_baseline && !report.passed() {
+        for site in &report.new_unjustified {
+            output.error(&format!(
+                "{}:{}: unsafe block without SAFETY justification",
+                site.file, site.line
+            ));
+        }
+        if report.baseline.is_none() {
+            output.info("No baseline found, run with --update-baseline to accept existing blocks");
+        }
+        anyhow::bail!(
+            "{} new unsafe blocks lack justification",
+            report.new_unjustified.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! Command to run declarative test fixtures
+//!
+//! Loads the TOML fixtures at the given paths, runs each against a fresh
+//! instance of its module and fails if any call differs from its expected
+//! results or trap.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use wrt_build_core::fixtures::{FixtureReport, run_fixtures};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the fixtures command
+#[derive(Debug, Args)]
+pub struct FixturesArgs {
+    /// Fixture files or directories containing them
+    #[arg(required = true, help = "Fixture files or directories of fixtures")]
+    pub paths: Vec<PathBuf>,
+}
+
+/// Execute the fixtures command
+pub fn execute(args: FixturesArgs, output: &OutputManager) -> Result<()> {
+    let mut reports: Vec<FixtureReport> = Vec::new();
+    for path in &args.paths {
+        reports.extend(run_fixtures(path)?);
+    }
+
+    let failed = reports.iter().filter(|report| !report.passed()).count();
+    if output.is_json_mode() {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        output.header("Test Fixtures");
+        for report in &reports {
+            if report.passed() {
+                output.indent(&report.to_string());
+            } else {
+                output.error(&report.to_string());
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} fixtures failed", failed, reports.len());
+    }
+    if !output.is_json_mode() {
+        output.success(&format!("{} fixtures passed", reports.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Command to report which imports of a module a workload actually calls
+//!
+//! Compares the function imports declared by a core module with the calls
+//! recorded in one or more interceptor traces. The declared but never
+//! called imports are candidates for removal, and the called ones can be
+//! written as a deny-by-default policy that allows exactly those calls.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_format::module::ImportDesc;
+use wrt_intercept::{strategies::Trace, usage::ImportUsage};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the import-usage command
+#[derive(Debug, Args)]
+pub struct ImportUsageArgs {
+    /// Path to the module binary
+    #[arg(help = "Path to the WebAssembly module")]
+    pub module: PathBuf,
+
+    /// Recorded traces of the workload
+    #[arg(long = "trace", required = true, help = "Recorded interceptor trace of the workload")]
+    pub traces: Vec<PathBuf>,
+
+    /// Name of the module in the traces
+    #[arg(long, default_value = "*", help = "Name or glob of the module in the traces")]
+    pub component: String,
+
+    /// File to write the usage report to
+    #[arg(long = "report-file", help = "Write the usage report to this file")]
+    pub report_file: Option<PathBuf>,
+
+    /// File to write the allow-list policy to
+    #[arg(long = "policy-file", help = "Write a policy allowing only the used imports")]
+    pub policy_file: Option<PathBuf>,
+
+    /// Fail if any declared import was never called
+    #[arg(long = "deny-unused", help = "Fail if any declared import was never called")]
+    pub deny_unused: bool,
+}
+
+/// Execute the import-usage command
+pub fn execute(args: ImportUsageArgs, output: &OutputManager) -> Result<()> {
+    let binary =
+        fs::read(&args.module).context(format!("Failed to read {}", args.module.display()))?;
+    let module = wrt_decoder::decoder::decode_module(&binary)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {}", args.module.display(), e))?;
+
+    let mut usage = ImportUsage::new(&args.component).with_imports(
+        module
+            .imports
+            .iter()
+            .filter(|import| matches!(import.desc, ImportDesc::Function(_)))
+            .map(|import| (import.module.as_str(), import.name.as_str())),
+    );
+    for path in &args.traces {
+        let text =
+            fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+        let trace = Trace::parse(&text)
+            .map_err(|e| anyhow::anyhow!("Invalid trace {}: {}", path.display(), e))?;
+        usage.observe(&trace);
+    }
+
+    if let Some(path) = &args.report_file {
+        fs::write(path, usage.to_text()).context(format!("Failed to write {}", path.display()))?;
+    }
+    if let Some(path) = &args.policy_file {
+        fs::write(path, usage.allow_list().to_text())
+            .context(format!("Failed to write {}", path.display()))?;
+    }
+
+    let (used, unused, undeclared) = (usage.used(), usage.unused(), usage.undeclared());
+    if output.is_json_mode() {
+        let imports = |imports: &[wrt_intercept::usage::ImportCalls]| {
+            imports
+                .iter()
+                .map(|import| {
+                    serde_json::json!({
+                        "module": import.module,
+                        "name": import.name,
+                        "calls": import.calls,
+                        "errors": import.errors,
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        let report = serde_json::json!({
+            "component": usage.component(),
+            "coverage": usage.coverage(),
+            "used": imports(&used),
+            "unused": imports(&unused),
+            "undeclared": imports(&undeclared),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        output.header("Import usage");
+        for import in &used {
+            output.indent(&format!(
+                "used:       {}.{} ({} calls, {} failed)",
+                import.module, import.name, import.calls, import.errors
+            ));
+        }
+        for import in &unused {
+            output.indent(&format!("unused:     {}.{}", import.module, import.name));
+        }
+        for import in &undeclared {
+            output.warning(&format!(
+                "Called but not imported: {}.{} ({} calls)",
+                import.module, import.name, import.calls
+            ));
+        }
+        output.success(&format!(
+            "{} of {} imports used",
+            used.len(),
+            used.len() + unused.len()
+        ));
+    }
+
+    if args.deny_unused && !unused.is_empty() {
+        anyhow::bail!("{} imports were never called", unused.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Command to inspect a core WebAssembly module
+//!
+//! Prints a summary of the decoded module and, with `--lint`, runs the
+//! runtime's lint pass and reports each warning with a suggested fix. With
+//! `--call-depth` it reports the deepest call chain and the recursion
+//! cycles, to size the frame store of a deployment.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_runtime::{
+    call_depth::{self, CallDepthReport},
+    lint::{self, LintConfig, LintWarning},
+};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the inspect command
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    /// Path to the module binary
+    #[arg(help = "Path to the WebAssembly module")]
+    pub module: PathBuf,
+
+    /// Run the lint pass
+    #[arg(long, help = "Report suspicious constructs with suggested fixes")]
+    pub lint: bool,
+
+    /// Number of locals above which a function is reported
+    #[arg(
+        long = "max-locals",
+        default_value_t = lint::DEFAULT_MAX_LOCALS,
+        help = "Number of locals above which a function is reported"
+    )]
+    pub max_locals: usize,
+
+    /// Fail if the lint pass reports any warning
+    #[arg(long = "deny-warnings", help = "Fail if the lint pass reports any warning")]
+    pub deny_warnings: bool,
+
+    /// Report the deepest call chain and recursion cycles
+    #[arg(long = "call-depth", help = "Report the deepest call chain and recursion cycles")]
+    pub call_depth: bool,
+
+    /// Frames a call chain may need before the lint pass reports it
+    #[arg(
+        long = "max-call-depth",
+        help = "Frames a call chain may need before the lint pass reports it"
+    )]
+    pub max_call_depth: Option<u32>,
+}
+
+fn warning_json(warning: &LintWarning) -> serde_json::Value {
+    serde_json::json!({
+        "code": warning.code.as_str(),
+        "function": warning.function,
+        "message": warning.message,
+        "help": warning.help(),
+    })
+}
+
+fn call_depth_json(report: &CallDepthReport) -> serde_json::Value {
+    let deepest = report.deepest();
+    serde_json::json!({
+        "max_depth": report.max_depth(),
+        "deepest_function": deepest.map(|(function, _)| function),
+        "bounded": report.is_bounded(),
+        "complete": report.complete,
+        "recursion_cycles": report.cycles.iter().map(|c| &c.functions).collect::<Vec<_>>(),
+    })
+}
+
+fn print_call_depth(report: &CallDepthReport, output: &OutputManager) {
+    output.subheader("Call depth");
+    match report.deepest() {
+        Some((function, depth)) => output.indent(&format!(
+            "max depth:     {} frames (from function {}){}",
+            depth.depth,
+            function,
+            if depth.recursive { ", excluding recursion" } else { "" }
+        )),
+        None => output.indent("max depth:     0 frames (no functions)"),
+    }
+    for cycle in &report.cycles {
+        if cycle.is_direct() {
+            output.warning(&format!(
+                "Unbounded recursion: function {} calls itself",
+                cycle.functions[0]
+            ));
+        } else {
+            output.warning(&format!(
+                "Unbounded recursion: functions {:?} call each other",
+                cycle.functions
+            ));
+        }
+    }
+    if !report.complete {
+        output.warning("Some function bodies could not be parsed; depths may be too low");
+    }
+}
+
+/// Execute the inspect command
+pub fn execute(args: InspectArgs, output: &OutputManager) -> Result<()> {
+    let binary =
+        fs::read(&args.module).context(format!("Failed to read {}", args.module.display()))?;
+    let module = wrt_decoder::decoder::decode_module(&binary)
+        .map_err(|e| anyhow::anyhow!("Failed to decode {}: {}", args.module.display(), e))?;
+
+    let imported = module.functions.iter().filter(|f| f.code.is_empty()).count();
+    let warnings = if args.lint {
+        let mut config = LintConfig::default().with_max_locals(args.max_locals);
+        if let Some(max_call_depth) = args.max_call_depth {
+            config = config.with_max_call_depth(max_call_depth);
+        }
+        lint::lint_module(&module, &config)
+    } else {
+        Vec::new()
+    };
+    let depth_report = args.call_depth.then(|| call_depth::analyze_module(&module));
+
+    if output.is_json_mode() {
+        let mut report = serde_json::json!({
+            "module": args.module.display().to_string(),
+            "types": module.types.len(),
+            "imports": module.imports.len(),
+            "functions": module.functions.len() - imported,
+            "exports": module.exports.len(),
+            "memories": module.memories.len(),
+            "globals": module.globals.len(),
+            "data_segments": module.data.len(),
+            "start": module.start,
+            "warnings": warnings.iter().map(warning_json).collect::<Vec<_>>(),
+        });
+        if let Some(depth_report) = &depth_report {
+            report["call_depth"] = call_depth_json(depth_report);
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        output.header(&format!("Module {}", args.module.display()));
+        output.indent(&format!("types:         {}", module.types.len()));
+        output.indent(&format!("imports:       {}", module.imports.len()));
+        output.indent(&format!("functions:     {}", module.functions.len() - imported));
+        output.indent(&format!("exports:       {}", module.exports.len()));
+        output.indent(&format!("memories:      {}", module.memories.len()));
+        output.indent(&format!("globals:       {}", module.globals.len()));
+        output.indent(&format!("data segments: {}", module.data.len()));
+        if let Some(start) = module.start {
+            output.indent(&format!("start:         function {}", start));
+        }
+
+        if let Some(depth_report) = &depth_report {
+            print_call_depth(depth_report, output);
+        }
+
+        if args.lint {
+            for warning in &warnings {
+                output.warning(&warning.to_string());
+                output.indent(&format!("help: {}", warning.help()));
+            }
+            if warnings.is_empty() {
+                output.success("No lint warnings");
+            }
+        }
+    }
+
+    if args.deny_warnings && !warnings.is_empty() {
+        anyhow::bail!("{} lint warnings", warnings.len());
+    }
+    Ok(())
+}
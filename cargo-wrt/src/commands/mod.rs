@@ -3,8 +3,30 @@
 //! This module contains command-specific implementations that use
 //! the standardized command framework and helper modules.
 
+pub mod abi_trace;
+pub mod ara_com;
+pub mod bench;
+pub mod call_graph;
+pub mod compose;
 pub mod embed_limits;
+pub mod ffi_audit;
+pub mod fixtures;
+pub mod import_usage;
+pub mod inspect;
+pub mod proxy;
+pub mod sign;
 pub mod test_validate;
 
+pub use abi_trace::execute as cmd_abi_trace;
+pub use ara_com::execute as cmd_ara_com;
+pub use bench::execute as cmd_bench;
+pub use call_graph::execute as cmd_call_graph;
+pub use compose::execute as cmd_compose;
 pub use embed_limits::execute as cmd_embed_limits;
+pub use ffi_audit::execute as cmd_ffi_audit;
+pub use fixtures::execute as cmd_fixtures;
+pub use import_usage::execute as cmd_import_usage;
+pub use inspect::execute as cmd_inspect;
+pub use proxy::execute as cmd_proxy;
+pub use sign::execute as cmd_sign;
 pub use test_validate::{TestValidateArgs, execute_test_validate};
@@ -0,0 +1,83 @@
+//! Command to generate world proxies for legacy native plugin APIs
+//!
+//! Reads a WIT world and writes a Rust host shim that forwards every function
+//! the world imports to the legacy native host function implementing it, so
+//! components and legacy modules can share one implementation during a
+//! migration.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_build_core::world_proxy::WorldProxyPlan;
+use wrt_format::wit_parser::WitDocument;
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the proxy command
+#[derive(Debug, Args)]
+pub struct ProxyArgs {
+    /// Path to the WIT file declaring the world
+    #[arg(help = "WIT file declaring the world to proxy")]
+    pub wit_file: PathBuf,
+
+    /// World to proxy
+    #[arg(short = 'w', long = "world", help = "World to proxy (the only world if omitted)")]
+    pub world: Option<String>,
+
+    /// Module the legacy host functions are registered in
+    #[arg(
+        long = "legacy-module",
+        default_value = "env",
+        help = "Module the legacy host functions are registered in"
+    )]
+    pub legacy_module: String,
+
+    /// File to write the generated shim to
+    #[arg(long = "out-file", help = "Write the shim to this file instead of stdout")]
+    pub output_file: Option<PathBuf>,
+}
+
+/// Execute the proxy command
+pub fn execute(args: ProxyArgs, output: &OutputManager) -> Result<()> {
+    let wit_source = fs::read_to_string(&args.wit_file)
+        .context(format!("Failed to read {}", args.wit_file.display()))?;
+    let document = WitDocument::parse(&wit_source).map_err(|e| {
+        anyhow::anyhow!("Failed to parse WIT file {}: {}", args.wit_file.display(), e)
+    })?;
+    let plan = WorldProxyPlan::new(&document, args.world.as_deref(), &args.legacy_module)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    for skipped in &plan.skipped {
+        output.warning(&format!("Skipping import of external interface {}", skipped));
+    }
+
+    let source = plan.to_rust();
+    match &args.output_file {
+        Some(path) => {
+            fs::write(path, &source).context(format!("Failed to write {}", path.display()))?;
+            if output.is_json_mode() {
+                println!("{}", serde_json::to_string_pretty(&plan)?);
+            } else {
+                for route in &plan.routes {
+                    output.indent(&format!(
+                        "{}::{} -> {}::{}",
+                        route.import_module,
+                        route.import_function,
+                        plan.legacy_module,
+                        route.legacy_function
+                    ));
+                }
+                output.success(&format!(
+                    "Wrote proxy for world '{}' forwarding {} imports to {}",
+                    plan.world,
+                    plan.routes.len(),
+                    path.display()
+                ));
+            }
+        },
+        None if output.is_json_mode() => println!("{}", serde_json::to_string_pretty(&plan)?),
+        None => print!("{}", source),
+    }
+    Ok(())
+}
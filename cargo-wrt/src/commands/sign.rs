@@ -0,0 +1,101 @@
+//! Command to sign WebAssembly binaries and verify their signatures
+//!
+//! Signing appends a `wrt-signature` custom section with an Ed25519
+//! signature over the binary's sections; signing a signed binary adds a
+//! further signature. Engines with a signature policy only load binaries
+//! signed by one of their trusted public keys. Keys are files holding 32
+//! raw bytes or 64 hex digits.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use wrt_format::signing::{ModuleSigner, TrustAnchor, verify};
+
+use crate::helpers::OutputManager;
+
+/// Arguments for the sign command
+#[derive(Debug, Args)]
+pub struct SignArgs {
+    /// Module or component to sign or verify
+    pub input: PathBuf,
+
+    /// Ed25519 secret key to sign with
+    pub key: Option<PathBuf>,
+
+    /// Write the signed binary to this file instead of in place
+    pub output_file: Option<PathBuf>,
+
+    /// Public keys to verify the input against instead of signing it
+    pub verify: Vec<PathBuf>,
+}
+
+/// Execute the sign command
+pub fn execute(args: SignArgs, output: &OutputManager) -> Result<()> {
+    let binary = fs::read(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+
+    if !args.verify.is_empty() {
+        let anchors = args
+            .verify
+            .iter()
+            .map(|path| {
+                TrustAnchor::from_bytes(&read_key(path)?)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let verified = verify(&binary, &anchors)
+            .map_err(|e| anyhow::anyhow!("{}: {}", args.input.display(), e))?;
+        output.success(&format!(
+            "{} is signed by {} over {} sections",
+            args.input.display(),
+            args.verify[verified.anchor].display(),
+            verified.sections
+        ));
+        return Ok(());
+    }
+
+    let key_path = args
+        .key
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Signing requires --key, verifying --verify"))?;
+    let signer = ModuleSigner::from_seed(&read_key(key_path)?);
+    let signed = signer
+        .sign(&binary)
+        .map_err(|e| anyhow::anyhow!("Failed to sign {}: {}", args.input.display(), e))?;
+
+    let output_path = args.output_file.as_ref().unwrap_or(&args.input);
+    fs::write(output_path, &signed)
+        .with_context(|| format!("Failed to write {}", output_path.display()))?;
+    output.success(&format!("Signed {}", output_path.display()));
+    output.info(&format!("Public key: {}", to_hex(&signer.trust_anchor().to_bytes())));
+    Ok(())
+}
+
+/// Read a 32-byte key stored raw or as hex digits
+fn read_key(path: &Path) -> Result<[u8; 32]> {
+    let contents =
+        fs::read(path).with_context(|| format!("Failed to read key {}", path.display()))?;
+    if let Ok(key) = <[u8; 32]>::try_from(contents.as_slice()) {
+        return Ok(key);
+    }
+    let text = String::from_utf8_lossy(&contents);
+    let digits = text.trim();
+    if digits.len() != 64 {
+        anyhow::bail!("{}: expected 32 bytes or 64 hex digits", path.display());
+    }
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(digits.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).unwrap_or("");
+        *byte = u8::from_str_radix(pair, 16)
+            .with_context(|| format!("{}: invalid hex digits", path.display()))?;
+    }
+    Ok(key)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
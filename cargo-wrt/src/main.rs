@@ -5,7 +5,10 @@
 //! of justfile, xtask, and shell scripts with a single, AI-friendly tool.
 
 // Standard library imports
-use std::{path::PathBuf, process};
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
 
 // External crates
 use anyhow::{Context, Result};
@@ -17,6 +20,7 @@
     BuildConfig, BuildSystem,
     cache::CacheManager,
     config::{AsilLevel, BuildProfile},
+    coverage::{CoverageDiffOptions, write_coverage_diff},
     diagnostics::{DiagnosticCollection, Severity},
     filtering::{FilterOptionsBuilder, GroupBy, SortBy, SortDirection},
     formatters::{FormatterFactory, OutputFormat},
@@ -32,7 +36,11 @@
 #[cfg(test)]
 mod testing;
 
-use commands::{TestValidateArgs, cmd_embed_limits, execute_test_validate};
+use commands::{
+    TestValidateArgs, cmd_abi_trace, cmd_ara_com, cmd_bench, cmd_call_graph, cmd_compose, cmd_embed_limits,
+    cmd_ffi_audit, cmd_fixtures, cmd_import_usage, cmd_inspect, cmd_proxy, cmd_sign,
+    execute_test_validate,
+};
 use helpers::{
     AutoFixManager, GlobalArgs, OutputManager, TestConfig, output_diagnostics, run_asil_tests,
     run_no_std_tests,
@@ -70,6 +78,9 @@
   # Show only new/changed diagnostics
   cargo-wrt build --cache --diff-only
   
+  # Inspect or clear the caches
+  cargo-wrt cache show
+  
   # Group diagnostics by file
   cargo-wrt build --output json --group-by file
   
@@ -118,11 +129,12 @@ struct Cli {
     #[arg(long, global = true, value_enum, default_value = "human")]
     output: OutputFormatArg,
 
-    /// Enable diagnostic caching for faster incremental builds
+    /// Enable diagnostic caching and skip build, test and verify steps whose
+    /// inputs are unchanged
     #[arg(long, global = true)]
     cache: bool,
 
-    /// Clear diagnostic cache before running
+    /// Clear diagnostic and build step caches before running
     #[arg(long, global = true)]
     clear_cache: bool,
 
@@ -327,6 +339,10 @@ enum Commands {
         #[arg(long)]
         detailed: bool,
 
+        /// Generate SBOMs and release provenance as part of the report
+        #[arg(long)]
+        sbom: bool,
+
         /// Path to allowed unsafe configuration file
         #[arg(long, default_value = "allowed-unsafe.toml")]
         allowed_unsafe: String,
@@ -369,6 +385,24 @@ enum Commands {
         /// Continue on errors and generate coverage for what works
         #[arg(long)]
         best_effort: bool,
+
+        /// Compare against a baseline llvm-cov JSON export (e.g. main.json)
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Use this llvm-cov JSON export instead of measuring coverage
+        #[arg(long, requires = "baseline")]
+        current: Option<PathBuf>,
+
+        /// Coverage loss, in percentage points, tolerated for safety-tagged
+        /// modules
+        #[arg(long, default_value_t = wrt_build_core::coverage::DEFAULT_MAX_REGRESSION)]
+        max_regression: f64,
+
+        /// Treat files under this workspace-relative path as safety-tagged
+        /// (repeatable)
+        #[arg(long = "safety-path")]
+        safety_paths: Vec<String>,
     },
 
     /// Run static analysis (clippy + formatting)
@@ -441,6 +475,29 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Build and test every crate under each ASIL feature
+    Matrix {
+        /// ASIL levels to build (comma-separated: qm,a,b,c,d or all)
+        #[arg(long, default_value = "all")]
+        asil: String,
+
+        /// Crates to build (defaults to all crates with ASIL features)
+        #[arg(long = "package", short = 'p')]
+        packages: Vec<String>,
+
+        /// Only build, without running tests
+        #[arg(long)]
+        no_tests: bool,
+
+        /// Write the results as JSON and a summary table
+        #[arg(long)]
+        report: bool,
+
+        /// Output directory for reports
+        #[arg(long, default_value = "target/asil-matrix")]
+        output_dir: String,
+    },
+
     /// Simulate CI workflow for local testing
     SimulateCi {
         /// Show verbose output
@@ -489,6 +546,10 @@ enum Commands {
         #[arg(long)]
         audit_docs: bool,
 
+        /// Check glob re-exports against the wrt-prelude policy
+        #[arg(long)]
+        check_reexports: bool,
+
         /// Run all validation checks
         #[arg(long)]
         all: bool,
@@ -548,6 +609,14 @@ enum Commands {
         /// Package to fuzz
         #[arg(long, short)]
         package: Option<String>,
+
+        /// Minimize each target's corpus after fuzzing
+        #[arg(long)]
+        minimize_corpus: bool,
+
+        /// Compare execution against the wasmi reference interpreter
+        #[arg(long)]
+        differential: bool,
     },
 
     /// Test feature combinations
@@ -660,6 +729,251 @@ enum Commands {
         replace: bool,
     },
 
+    /// Decode canonical ABI lift/lower traces using WIT signatures
+    AbiTrace {
+        /// Path to the trace file
+        trace_file: PathBuf,
+
+        /// Path to the WIT file describing the traced functions
+        #[arg(short = 'w', long = "wit")]
+        wit_file: PathBuf,
+
+        /// Only decode entries for this function
+        #[arg(short = 'f', long = "function")]
+        function: Option<String>,
+    },
+
+    /// Run declarative TOML test fixtures against the runtime
+    Fixtures {
+        /// Fixture files or directories containing them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Sign a module or component with Ed25519, or verify its signature
+    Sign {
+        /// Module or component to sign or verify
+        input: PathBuf,
+
+        /// Ed25519 secret key file, 32 bytes or 64 hex digits
+        #[arg(long, required_unless_present = "verify")]
+        key: Option<PathBuf>,
+
+        /// Write the signed binary to this file instead of in place
+        #[arg(long = "out-file")]
+        output_file: Option<PathBuf>,
+
+        /// Verify against these public key files instead of signing
+        #[arg(long, value_name = "PUBLIC_KEY", conflicts_with_all = ["key", "output_file"])]
+        verify: Vec<PathBuf>,
+    },
+
+    /// Extract the inter-component call graph of a composition as DOT or JSON
+    CallGraph {
+        /// Component binaries, optionally named as `name=path`
+        #[arg(required = true)]
+        components: Vec<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: commands::call_graph::CallGraphFormat,
+
+        /// Write the graph to this file instead of stdout
+        #[arg(long = "out-file")]
+        output_file: Option<PathBuf>,
+
+        /// Fail if any import cannot be bound
+        #[arg(long = "deny-unresolved")]
+        deny_unresolved: bool,
+    },
+
+    /// Resolve a composition and write or verify its lockfile
+    Compose {
+        /// Component binaries, optionally named as `name=path`
+        #[arg(required = true)]
+        components: Vec<String>,
+
+        /// WIT package file or directory to lock
+        #[arg(long = "wit")]
+        wit: Vec<PathBuf>,
+
+        /// Lockfile to write or verify
+        #[arg(long, default_value = wrt_component::components::LOCKFILE_NAME)]
+        lockfile: PathBuf,
+
+        /// Fail if the composition differs from the lockfile
+        #[arg(long)]
+        locked: bool,
+
+        /// Reject wires whose export declares no type
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Report which imports of a module a recorded workload actually calls
+    ImportUsage {
+        /// Path to the WebAssembly module
+        module: PathBuf,
+
+        /// Recorded interceptor trace of the workload
+        #[arg(long = "trace", required = true)]
+        traces: Vec<PathBuf>,
+
+        /// Name or glob of the module in the traces
+        #[arg(long, default_value = "*")]
+        component: String,
+
+        /// Write the usage report to this file
+        #[arg(long = "report-file")]
+        report_file: Option<PathBuf>,
+
+        /// Write a policy allowing only the used imports
+        #[arg(long = "policy-file")]
+        policy_file: Option<PathBuf>,
+
+        /// Fail if any declared import was never called
+        #[arg(long = "deny-unused")]
+        deny_unused: bool,
+    },
+
+    /// Audit unsafe code, extern boundaries and syscall wrappers across the workspace
+    FfiAudit {
+        /// Directory for ffi-audit.json and ffi-audit.md
+        #[arg(long, default_value = "target/ffi-audit")]
+        output_dir: PathBuf,
+
+        /// Baseline of accepted unjustified unsafe blocks
+        #[arg(long, default_value = wrt_build_core::ffi_audit::BASELINE_FILE)]
+        baseline: PathBuf,
+
+        /// Accept the current unjustified unsafe blocks as the new baseline
+        #[arg(long = "update-baseline")]
+        update_baseline: bool,
+
+        /// Path to allowed unsafe configuration file
+        #[arg(long, default_value = "allowed-unsafe.toml")]
+        allowed_unsafe: PathBuf,
+    },
+
+    /// Run benchmarks, or gate the latency-critical ones against a baseline
+    Bench {
+        /// Run and gate only the latency-critical benchmarks
+        #[arg(long)]
+        critical: bool,
+
+        /// Baseline the critical benchmarks are compared against
+        #[arg(long, default_value = wrt_build_core::bench::BASELINE_FILE)]
+        baseline: PathBuf,
+
+        /// Allowed slowdown of a critical benchmark in percent
+        #[arg(long, default_value_t = wrt_build_core::bench::DEFAULT_TOLERANCE_PERCENT)]
+        tolerance: f64,
+
+        /// Accept the measured means as the new baseline
+        #[arg(long = "update-baseline")]
+        update_baseline: bool,
+    },
+
+    /// Summarize a core module and optionally lint it or analyze its call depth
+    Inspect {
+        /// Path to the WebAssembly module
+        module: PathBuf,
+
+        /// Report suspicious constructs with suggested fixes
+        #[arg(long)]
+        lint: bool,
+
+        /// Number of locals above which a function is reported
+        #[arg(long = "max-locals", default_value_t = wrt_runtime::lint::DEFAULT_MAX_LOCALS)]
+        max_locals: usize,
+
+        /// Fail if the lint pass reports any warning
+        #[arg(long = "deny-warnings")]
+        deny_warnings: bool,
+
+        /// Report the deepest call chain and recursion cycles
+        #[arg(long = "call-depth")]
+        call_depth: bool,
+
+        /// Frames a call chain may need before the lint pass reports it
+        #[arg(long = "max-call-depth")]
+        max_call_depth: Option<u32>,
+    },
+
+    /// Generate a host shim forwarding a WIT world's imports to legacy host functions
+    Proxy {
+        /// Path to the WIT file declaring the world
+        wit_file: PathBuf,
+
+        /// World to proxy (the only world if omitted)
+        #[arg(short = 'w', long = "world")]
+        world: Option<String>,
+
+        /// Module the legacy host functions are registered in
+        #[arg(long = "legacy-module", default_value = "env")]
+        legacy_module: String,
+
+        /// Write the shim to this file instead of stdout
+        #[arg(long = "out-file")]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Generate an ara::com bridge exposing a WIT world as AUTOSAR Adaptive services
+    AraCom {
+        /// Path to the WIT file declaring the world
+        wit_file: PathBuf,
+
+        /// World to bridge (the only world if omitted)
+        #[arg(short = 'w', long = "world")]
+        world: Option<String>,
+
+        /// Service ID of the first service
+        #[arg(
+            long = "service-id-base",
+            default_value = "0x1000",
+            value_parser = commands::ara_com::parse_id
+        )]
+        service_id_base: u16,
+
+        /// Instance ID the services are offered and looked up under
+        #[arg(
+            long = "instance-id",
+            default_value = "1",
+            value_parser = commands::ara_com::parse_id
+        )]
+        instance_id: u16,
+
+        /// Write the shim to this file instead of stdout
+        #[arg(long = "out-file")]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Generate SBOMs and SLSA provenance for release artifacts
+    Sbom {
+        /// SBOM format to generate
+        #[arg(long, value_enum, default_value = "all")]
+        format: SbomFormatArg,
+
+        /// Output directory (defaults to target/sbom)
+        #[arg(long, short)]
+        output_dir: Option<PathBuf>,
+
+        /// Release artifact to record in the provenance (repeatable, defaults
+        /// to the workspace binaries in target/release)
+        #[arg(long = "artifact")]
+        artifacts: Vec<PathBuf>,
+
+        /// Skip provenance generation
+        #[arg(long)]
+        no_provenance: bool,
+    },
+
+    /// Inspect and clear the diagnostic and build step caches
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
     /// Show comprehensive diagnostic system help
     #[command(name = "help-diagnostics", hide = true)]
     HelpDiagnostics,
@@ -743,6 +1057,21 @@ enum RequirementsCommand {
         output: Option<String>,
     },
 
+    /// Trace SW-REQ-ID annotations to requirements, tests and Kani proofs
+    Trace {
+        /// Path to requirements file (requirements.toml if it exists)
+        #[arg(long)]
+        path: Option<String>,
+
+        /// Directory for traceability.json and traceability.html
+        #[arg(long, default_value = "target/traceability")]
+        output_dir: String,
+
+        /// Fail if any requirement is uncovered or orphaned
+        #[arg(long)]
+        strict: bool,
+    },
+
     /// List requirements needing attention
     Missing {
         /// Path to requirements file
@@ -1100,6 +1429,36 @@ enum ToolVersionCommand {
     },
 }
 
+/// SBOM formats for CLI
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum SbomFormatArg {
+    /// CycloneDX 1.5 JSON
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+    /// SPDX 2.3 JSON
+    Spdx,
+    /// Both CycloneDX and SPDX
+    All,
+}
+
+/// Cache management subcommands
+#[derive(Subcommand, Clone)]
+enum CacheCommand {
+    /// Show cached build steps and diagnostic cache statistics
+    Show,
+
+    /// Remove cached results
+    Clear {
+        /// Clear only the build step cache
+        #[arg(long, conflicts_with = "diagnostics")]
+        steps: bool,
+
+        /// Clear only the diagnostic cache
+        #[arg(long)]
+        diagnostics: bool,
+    },
+}
+
 /// WRTD runtime variants
 #[derive(clap::ValueEnum, Clone, Copy, Debug)]
 enum WrtdVariant {
@@ -1270,10 +1629,15 @@ async fn main() -> Result<()> {
     config.dry_run = global.dry_run;
     config.trace_commands = global.trace_commands;
     config.features = global.features.clone();
+    config.step_cache = global.cache;
 
     let mut build_system = build_system;
     build_system.set_config(config);
 
+    if global.cache && global.clear_cache {
+        build_system.clear_step_cache().context("Failed to clear build step cache")?;
+    }
+
     // Execute command
     let result = match &cli.command {
         Commands::Build {
@@ -1340,6 +1704,7 @@ async fn main() -> Result<()> {
             no_kani,
             no_miri,
             detailed,
+            sbom,
             allowed_unsafe: _,
         } => {
             let output_format = global.output_format.clone();
@@ -1350,6 +1715,7 @@ async fn main() -> Result<()> {
                 *no_kani,
                 *no_miri,
                 *detailed,
+                *sbom,
                 &output_format,
                 use_colors,
                 &cli,
@@ -1377,7 +1743,21 @@ async fn main() -> Result<()> {
             open,
             format,
             best_effort,
-        } => cmd_coverage(&build_system, *html, *open, format.clone(), *best_effort).await,
+            baseline,
+            current,
+            max_regression,
+            safety_paths,
+        } => match baseline {
+            Some(baseline) => {
+                let options = CoverageDiffOptions {
+                    max_regression: *max_regression,
+                    safety_paths: safety_paths.clone(),
+                };
+                cmd_coverage_diff(&build_system, baseline, current.as_deref(), &options, &global)
+                    .await
+            },
+            None => cmd_coverage(&build_system, *html, *open, format.clone(), *best_effort).await,
+        },
         Commands::Check { strict, fix } => {
             cmd_check(&build_system, *strict, *fix, &mut global).await
         },
@@ -1397,6 +1777,25 @@ async fn main() -> Result<()> {
             output_dir,
             verbose,
         } => cmd_verify_matrix(&build_system, *report, output_dir.clone(), *verbose).await,
+        Commands::Matrix {
+            asil,
+            packages,
+            no_tests,
+            report,
+            output_dir,
+        } => {
+            cmd_asil_matrix(
+                &build_system,
+                asil,
+                packages.clone(),
+                *no_tests,
+                *report,
+                output_dir.clone(),
+                &global.output,
+                global.verbose,
+            )
+            .await
+        },
         Commands::SimulateCi {
             verbose,
             output_dir,
@@ -1422,6 +1821,7 @@ async fn main() -> Result<()> {
             check_test_files,
             check_docs,
             audit_docs,
+            check_reexports,
             all,
             verbose,
         } => {
@@ -1430,6 +1830,7 @@ async fn main() -> Result<()> {
                 *check_test_files,
                 *check_docs,
                 *audit_docs,
+                *check_reexports,
                 *all,
                 *verbose,
             )
@@ -1444,6 +1845,22 @@ async fn main() -> Result<()> {
         Commands::ToolVersions { command } => {
             cmd_tool_versions(&build_system, command.clone()).await
         },
+        Commands::Sbom {
+            format,
+            output_dir,
+            artifacts,
+            no_provenance,
+        } => {
+            cmd_sbom(
+                &build_system,
+                *format,
+                output_dir.clone(),
+                artifacts.clone(),
+                *no_provenance,
+            )
+            .await
+        },
+        Commands::Cache { command } => cmd_cache(&build_system, command.clone()).await,
         Commands::Fuzz {
             target,
             duration,
@@ -1451,6 +1868,8 @@ async fn main() -> Result<()> {
             runs,
             list,
             package,
+            minimize_corpus,
+            differential,
         } => {
             cmd_fuzz(
                 &build_system,
@@ -1460,6 +1879,8 @@ async fn main() -> Result<()> {
                 *runs,
                 *list,
                 package.clone(),
+                *minimize_corpus,
+                *differential,
             )
             .await
         },
@@ -1556,6 +1977,162 @@ async fn main() -> Result<()> {
             };
             cmd_embed_limits(args, &global.output)
         },
+        Commands::AbiTrace {
+            trace_file,
+            wit_file,
+            function,
+        } => {
+            let args = commands::abi_trace::AbiTraceArgs {
+                trace_file: trace_file.clone(),
+                wit_file: wit_file.clone(),
+                function: function.clone(),
+            };
+            cmd_abi_trace(args, &global.output)
+        },
+        Commands::Fixtures { paths } => {
+            let args = commands::fixtures::FixturesArgs {
+                paths: paths.clone(),
+            };
+            cmd_fixtures(args, &global.output)
+        },
+        Commands::Sign {
+            input,
+            key,
+            output_file,
+            verify,
+        } => {
+            let args = commands::sign::SignArgs {
+                input: input.clone(),
+                key: key.clone(),
+                output_file: output_file.clone(),
+                verify: verify.clone(),
+            };
+            cmd_sign(args, &global.output)
+        },
+        Commands::CallGraph {
+            components,
+            format,
+            output_file,
+            deny_unresolved,
+        } => {
+            let args = commands::call_graph::CallGraphArgs {
+                components: components.clone(),
+                format: *format,
+                output_file: output_file.clone(),
+                deny_unresolved: *deny_unresolved,
+            };
+            cmd_call_graph(args, &global.output)
+        },
+        Commands::Compose {
+            components,
+            wit,
+            lockfile,
+            locked,
+            strict,
+        } => {
+            let args = commands::compose::ComposeArgs {
+                components: components.clone(),
+                wit: wit.clone(),
+                lockfile: lockfile.clone(),
+                locked: *locked,
+                strict: *strict,
+            };
+            cmd_compose(args, &global.output)
+        },
+        Commands::ImportUsage {
+            module,
+            traces,
+            component,
+            report_file,
+            policy_file,
+            deny_unused,
+        } => {
+            let args = commands::import_usage::ImportUsageArgs {
+                module: module.clone(),
+                traces: traces.clone(),
+                component: component.clone(),
+                report_file: report_file.clone(),
+                policy_file: policy_file.clone(),
+                deny_unused: *deny_unused,
+            };
+            cmd_import_usage(args, &global.output)
+        },
+        Commands::FfiAudit {
+            output_dir,
+            baseline,
+            update_baseline,
+            allowed_unsafe,
+        } => {
+            let args = commands::ffi_audit::FfiAuditArgs {
+                output_dir: output_dir.clone(),
+                baseline: baseline.clone(),
+                update_baseline: *update_baseline,
+                allowed_unsafe: allowed_unsafe.clone(),
+            };
+            cmd_ffi_audit(&build_system, args, &global.output)
+        },
+        Commands::Bench {
+            critical,
+            baseline,
+            tolerance,
+            update_baseline,
+        } => {
+            let args = commands::bench::BenchArgs {
+                critical: *critical,
+                baseline: baseline.clone(),
+                tolerance: *tolerance,
+                update_baseline: *update_baseline,
+            };
+            cmd_bench(&build_system, args, &global.output)
+        },
+        Commands::Inspect {
+            module,
+            lint,
+            max_locals,
+            deny_warnings,
+            call_depth,
+            max_call_depth,
+        } => {
+            let args = commands::inspect::InspectArgs {
+                module: module.clone(),
+                lint: *lint,
+                max_locals: *max_locals,
+                deny_warnings: *deny_warnings,
+                call_depth: *call_depth,
+                max_call_depth: *max_call_depth,
+            };
+            cmd_inspect(args, &global.output)
+        },
+        Commands::Proxy {
+            wit_file,
+            world,
+            legacy_module,
+            output_file,
+        } => {
+            let args = commands::proxy::ProxyArgs {
+                wit_file: wit_file.clone(),
+                world: world.clone(),
+                legacy_module: legacy_module.clone(),
+                output_file: output_file.clone(),
+            };
+            cmd_proxy(args, &global.output)
+        },
+        Commands::AraCom {
+            wit_file,
+            world,
+            service_id_base,
+            instance_id,
+            output_file,
+        } => {
+            let args = commands::ara_com::AraComArgs {
+                wit_file: wit_file.clone(),
+                world: world.clone(),
+                service_id_base: *service_id_base,
+                instance_id: *instance_id,
+                output_file: output_file.clone(),
+            };
+            cmd_ara_com(args, &global.output)
+        },
         Commands::HelpDiagnostics => {
             print_diagnostic_help();
             Ok(())
@@ -1959,6 +2536,7 @@ async fn cmd_verify(
     no_kani: bool,
     no_miri: bool,
     detailed: bool,
+    sbom: bool,
     output_format: &OutputFormat,
     use_colors: bool,
     cli: &Cli,
@@ -1969,6 +2547,7 @@ async fn cmd_verify(
     options.kani = !no_kani;
     options.miri = !no_miri;
     options.detailed_reports = detailed;
+    options.sbom = sbom;
 
     // Load allowed unsafe configuration if it exists
     let allowed_unsafe_path = build_system.workspace_root().join("allowed-unsafe.toml");
@@ -2219,6 +2798,67 @@ async fn cmd_coverage(
     Ok(())
 }
 
+/// Coverage baseline comparison implementation
+async fn cmd_coverage_diff(
+    build_system: &BuildSystem,
+    baseline: &Path,
+    current: Option<&Path>,
+    options: &CoverageDiffOptions,
+    global: &GlobalArgs,
+) -> Result<()> {
+    let start = std::time::Instant::now();
+    let diff = build_system
+        .coverage_diff(baseline, current, options)
+        .context("Coverage comparison failed")?;
+    let output_dir = build_system.workspace_root().join("target/coverage");
+    let report = write_coverage_diff(&diff, &output_dir)?;
+
+    if global.output.is_json_mode() {
+        let mut collection = DiagnosticCollection::new(
+            build_system.workspace_root().to_path_buf(),
+            "coverage".to_string(),
+        );
+        collection.add_diagnostics(diff.to_diagnostics());
+        let collection = collection.finalize(start.elapsed().as_millis() as u64);
+        let formatter = FormatterFactory::create_with_options(
+            global.output.format().clone(),
+            true,
+            global.output.is_colored(),
+        );
+        print!("{}", formatter.format_collection(&collection));
+    } else {
+        println!(
+            "{} Line coverage {:.2}% -> {:.2}% ({:+.2})",
+            "📊".bright_blue(),
+            diff.baseline_total,
+            diff.current_total,
+            diff.current_total - diff.baseline_total
+        );
+        for krate in diff.crates.iter().filter(|c| c.delta() != 0.0) {
+            println!("  {:<30} {:+.2}", krate.name, krate.delta());
+        }
+        for file in &diff.regressions {
+            println!(
+                "  {} {}: {:.2}% -> {:.2}%",
+                "✗".bright_red(),
+                file.name,
+                file.baseline.unwrap_or_default(),
+                file.current.unwrap_or_default()
+            );
+        }
+        global.output.info(&format!("Coverage delta report: {}", report.display()));
+    }
+
+    if !diff.passed() {
+        anyhow::bail!(
+            "Coverage of {} safety-tagged file(s) regressed by more than {:.2} points",
+            diff.regressions.len(),
+            diff.max_regression
+        );
+    }
+    Ok(())
+}
+
 /// Check command implementation
 async fn cmd_check(
     build_system: &BuildSystem,
@@ -2525,6 +3165,48 @@ async fn cmd_verify_matrix(
     Ok(())
 }
 
+/// ASIL matrix command implementation
+#[allow(clippy::too_many_arguments)]
+async fn cmd_asil_matrix(
+    build_system: &BuildSystem,
+    asil: &str,
+    packages: Vec<String>,
+    no_tests: bool,
+    report: bool,
+    output_dir: String,
+    output: &OutputManager,
+    verbose: bool,
+) -> Result<()> {
+    use wrt_build_core::matrix::{parse_asil_levels, AsilMatrix, AsilMatrixOptions};
+
+    let options = AsilMatrixOptions {
+        levels: parse_asil_levels(asil)?,
+        packages,
+        run_tests: !no_tests,
+        target_root: None,
+    };
+    let matrix = AsilMatrix::new(build_system.workspace_root().to_path_buf(), options, verbose);
+    let results = matrix.run().context("ASIL matrix failed")?;
+
+    if output.is_json_mode() {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        matrix.print_summary(&results);
+    }
+
+    if report {
+        let output_path = build_system.workspace_root().join(&output_dir);
+        matrix.generate_report(&results, &output_path)?;
+        output.info(&format!("Matrix report written to {}", output_path.display()));
+    }
+
+    if !results.all_passed() {
+        anyhow::bail!("{} ASIL matrix cells failed", results.failures().len());
+    }
+
+    Ok(())
+}
+
 /// Simulate CI command implementation
 async fn cmd_simulate_ci(
     build_system: &BuildSystem,
@@ -2598,6 +3280,7 @@ async fn cmd_validate(
     check_test_files: bool,
     check_docs: bool,
     audit_docs: bool,
+    check_reexports: bool,
     all: bool,
     verbose: bool,
 ) -> Result<()> {
@@ -2656,7 +3339,18 @@ async fn cmd_validate(
         }
     }
 
-    if !all && !check_test_files && !check_docs && !audit_docs {
+    if all || check_reexports {
+        println!();
+        let result = validator
+            .check_glob_reexports()
+            .context("Failed to check glob re-exports")?;
+
+        if !result.success {
+            any_failed = true;
+        }
+    }
+
+    if !all && !check_test_files && !check_docs && !audit_docs && !check_reexports {
         // If no specific checks requested, run all
         let all_passed = wrt_build_core::validation::run_all_validations(&workspace_root, verbose)
             .context("Failed to run validation checks")?;
@@ -2890,6 +3584,107 @@ async fn cmd_tool_versions(build_system: &BuildSystem, command: ToolVersionComma
     Ok(())
 }
 
+/// SBOM command implementation
+async fn cmd_sbom(
+    build_system: &BuildSystem,
+    format: SbomFormatArg,
+    output_dir: Option<PathBuf>,
+    artifacts: Vec<PathBuf>,
+    no_provenance: bool,
+) -> Result<()> {
+    use wrt_build_core::sbom::{SbomFormat, SbomOptions};
+
+    let formats = match format {
+        SbomFormatArg::CycloneDx => vec![SbomFormat::CycloneDx],
+        SbomFormatArg::Spdx => vec![SbomFormat::Spdx],
+        SbomFormatArg::All => vec![SbomFormat::CycloneDx, SbomFormat::Spdx],
+    };
+    let options = SbomOptions {
+        formats,
+        output_dir,
+        provenance: !no_provenance,
+        artifacts,
+    };
+
+    let results = build_system.generate_sbom(&options).context("SBOM generation failed")?;
+
+    for document in &results.documents {
+        println!("  📄 {}", document.display());
+    }
+    if let Some(provenance) = &results.provenance {
+        println!(
+            "  🔏 {} ({} artifacts)",
+            provenance.display(),
+            results.subjects.len()
+        );
+    }
+    if !results.unlicensed.is_empty() {
+        println!(
+            "  {} {} packages without a declared license: {}",
+            "⚠️".bright_yellow(),
+            results.unlicensed.len(),
+            results.unlicensed.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Cache command implementation
+async fn cmd_cache(build_system: &BuildSystem, command: CacheCommand) -> Result<()> {
+    let workspace_root = build_system.workspace_root();
+    let diagnostics =
+        CacheManager::new(workspace_root.to_path_buf(), get_cache_path(workspace_root), true)?;
+
+    match command {
+        CacheCommand::Show => {
+            let steps = build_system.step_cache().context("Failed to load build step cache")?;
+
+            println!("{} Build step cache", "♻️".bright_blue());
+            println!("  📁 {}", build_system.step_cache_path().display());
+            if steps.is_empty() {
+                println!("  No cached steps");
+            }
+            for (step, entry) in &steps.entries {
+                let recorded: chrono::DateTime<chrono::Local> = entry.recorded_at.into();
+                println!(
+                    "  {} {} (recorded {}, inputs {})",
+                    "✅".bright_green(),
+                    step.bright_cyan(),
+                    recorded.format("%Y-%m-%d %H:%M:%S"),
+                    &entry.fingerprint[..12.min(entry.fingerprint.len())]
+                );
+            }
+
+            let info = diagnostics.info();
+            println!();
+            println!("{} Diagnostic cache", "📊".bright_blue());
+            println!("  📁 {}", get_cache_path(workspace_root).display());
+            println!("  Files: {}", info.total_files);
+            println!("  Diagnostics: {}", info.total_diagnostics);
+            println!(
+                "  Hits: {}, misses: {}, invalidations: {}",
+                info.cache_hits, info.cache_misses, info.invalidations
+            );
+            println!("  Size: ~{} bytes", info.estimated_size_bytes);
+        },
+
+        CacheCommand::Clear { steps, diagnostics: diagnostics_only } => {
+            if !diagnostics_only {
+                build_system.clear_step_cache().context("Failed to clear build step cache")?;
+                println!("  ✅ Cleared build step cache");
+            }
+            if !steps {
+                let mut diagnostics = diagnostics;
+                diagnostics.clear().context("Failed to clear diagnostic cache")?;
+                println!("  ✅ Cleared diagnostic cache");
+            }
+        },
+    }
+
+    Ok(())
+}
+
 /// Fuzz command implementation
 async fn cmd_fuzz(
     build_system: &BuildSystem,
@@ -2899,8 +3694,13 @@ async fn cmd_fuzz(
     runs: Option<u64>,
     list: bool,
     package: Option<String>,
+    minimize_corpus: bool,
+    differential: bool,
 ) -> Result<()> {
-    use wrt_build_core::fuzz::FuzzOptions;
+    use wrt_build_core::fuzz::{
+        DivergenceStatus,
+        FuzzOptions,
+    };
 
     if list {
         println!("{} Available fuzz targets:", "🎯".bright_blue());
@@ -2930,6 +3730,8 @@ async fn cmd_fuzz(
         runs,
         targets: if target == "all" { vec![] } else { vec![target.clone()] },
         coverage: false,
+        minimize_corpus,
+        differential,
     };
 
     if let Some(pkg) = package {
@@ -2956,6 +3758,30 @@ async fn cmd_fuzz(
                     println!("    - {}", target);
                 }
             }
+            if !results.divergences.is_empty() {
+                println!(
+                    "{} {} divergences from the reference interpreter:",
+                    "⚠️".bright_yellow(),
+                    results.divergences.len()
+                );
+                for divergence in &results.divergences {
+                    let module = divergence.module.as_ref().unwrap_or(&divergence.input);
+                    println!("    - {}", module.display());
+                    match &divergence.status {
+                        DivergenceStatus::Reproduced(description) => {
+                            if let Some(summary) = description.lines().last() {
+                                println!("      {}", summary);
+                            }
+                        },
+                        DivergenceStatus::NotReproduced => {
+                            println!("      did not reproduce on replay");
+                        },
+                    }
+                    if !divergence.minimized {
+                        println!("      not minimized");
+                    }
+                }
+            }
         },
         Err(e) => {
             anyhow::bail!("Fuzzing failed: {}", e);
@@ -3481,6 +4307,61 @@ async fn cmd_requirements(
             Ok(())
         },
 
+        RequirementsCommand::Trace {
+            path,
+            output_dir,
+            strict,
+        } => {
+            use wrt_build_core::requirements::TraceStatus;
+
+            let req_path = path.map(|path| workspace_root.join(path));
+            let matrix = build_system.traceability_matrix(req_path.as_deref())?;
+            let outputs = matrix.write(&workspace_root.join(&output_dir))?;
+
+            match output_format {
+                OutputFormat::Json | OutputFormat::JsonLines => {
+                    println!("{}", matrix.to_json()?);
+                },
+                OutputFormat::Human => {
+                    let summary = &matrix.summary;
+                    println!("{} Requirements Traceability", "📊".bright_blue());
+                    println!("  Requirements: {}", summary.total);
+                    println!("  Covered: {}", summary.covered);
+                    println!("  Partially covered: {}", summary.partial);
+                    println!("  Uncovered: {}", summary.uncovered);
+                    println!("  Orphaned: {}", summary.orphaned);
+                    println!("  Coverage: {:.1}%", summary.coverage_percentage);
+
+                    for (status, label) in [
+                        (TraceStatus::Uncovered, "Uncovered Requirements"),
+                        (TraceStatus::Orphaned, "Orphaned Annotations"),
+                    ] {
+                        let entries: Vec<_> = matrix.with_status(status).collect();
+                        if !entries.is_empty() {
+                            println!("\n{} {}:", "⚠️ ".yellow(), label);
+                            for entry in entries {
+                                println!("  - {}", entry.id);
+                            }
+                        }
+                    }
+
+                    println!();
+                    for output in &outputs {
+                        println!("{} Wrote {}", "✅".bright_green(), output.display());
+                    }
+                },
+            }
+
+            if strict && (matrix.summary.uncovered > 0 || matrix.summary.orphaned > 0) {
+                anyhow::bail!(
+                    "{} uncovered and {} orphaned requirements",
+                    matrix.summary.uncovered,
+                    matrix.summary.orphaned
+                );
+            }
+            Ok(())
+        },
+
         RequirementsCommand::Missing {
             path,
             implementation,
@@ -4736,8 +5617,8 @@ fn print_diagnostic_help() {
     --output json-lines     Streaming JSON (one diagnostic per line)
 
   {} Caching Control:
-    --cache                 Enable diagnostic caching for faster builds
-    --clear-cache          Clear cache before running
+    --cache                 Enable diagnostic caching and skip unchanged steps
+    --clear-cache          Clear caches before running
     --diff-only            Show only new/changed diagnostics (requires --cache)
 
   {} Filtering Options:
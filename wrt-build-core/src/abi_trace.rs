@@ -0,0 +1,463 @@
+//! Canonical ABI trace decoding
+//!
+//! Decodes the canonical lift/lower trace written by the `wrt-intercept`
+//! `LoggingStrategy` (with `log_canonical` enabled) into typed values, using
+//! the function signatures of a [`WitDocument`]. Trace lines look like:
+//!
+//! ```text
+//! LIFT: guest->host::log addr=0x00001000 bytes=0200000010200000...
+//! LOWER: guest->host::read addr=0x00002000 bytes=00000000...
+//! ```
+//!
+//! `LIFT` bytes are decoded as the function's parameters and `LOWER` bytes as
+//! its results, both laid out in linear memory as a tuple following the
+//! canonical ABI size and alignment rules. Strings and lists are shown as the
+//! `(ptr, len)` pair stored in the flattened value, since the trace does not
+//! capture the memory they point to.
+
+use std::fmt;
+
+use serde::Serialize;
+use wrt_format::wit_parser::{discriminant_size, WitDocument, WitType};
+
+use crate::error::{BuildError, BuildResult};
+
+/// Decode a value of type `ty` stored at `offset` in `bytes`
+pub fn decode(
+    doc: &WitDocument,
+    ty: &WitType,
+    bytes: &[u8],
+    offset: usize,
+) -> BuildResult<DecodedValue> {
+    let resolved = doc.resolve_type(ty)?;
+    let (size, _) = doc.layout(resolved)?;
+    let raw = bytes.get(offset..offset + size).ok_or_else(|| {
+        BuildError::Verification(format!(
+            "Trace captured {} bytes but a {} at offset {} needs {}; raise max_canonical_bytes",
+            bytes.len(),
+            ty,
+            offset,
+            offset + size
+        ))
+    })?;
+
+    Ok(match resolved {
+        WitType::Bool => DecodedValue::Bool(raw[0] != 0),
+        WitType::U8 => DecodedValue::Unsigned(u64::from(raw[0])),
+        WitType::U16 => DecodedValue::Unsigned(read_le(raw)),
+        WitType::U32 => DecodedValue::Unsigned(read_le(raw)),
+        WitType::U64 => DecodedValue::Unsigned(read_le(raw)),
+        WitType::S8 => DecodedValue::Signed(i64::from(raw[0] as i8)),
+        WitType::S16 => DecodedValue::Signed(i64::from(read_le(raw) as u16 as i16)),
+        WitType::S32 => DecodedValue::Signed(i64::from(read_le(raw) as u32 as i32)),
+        WitType::S64 => DecodedValue::Signed(read_le(raw) as i64),
+        WitType::F32 => DecodedValue::Float(f64::from(f32::from_bits(read_le(raw) as u32))),
+        WitType::F64 => DecodedValue::Float(f64::from_bits(read_le(raw))),
+        WitType::Char => {
+            let scalar = read_le(raw) as u32;
+            DecodedValue::Char(char::from_u32(scalar).ok_or_else(|| {
+                BuildError::Verification(format!("Invalid char scalar value {:#x}", scalar))
+            })?)
+        },
+        WitType::String => DecodedValue::String {
+            ptr: read_le(&raw[..4]) as u32,
+            len: read_le(&raw[4..]) as u32,
+        },
+        WitType::List(_) => DecodedValue::List {
+            ptr: read_le(&raw[..4]) as u32,
+            len: read_le(&raw[4..]) as u32,
+        },
+        WitType::Handle(_) => DecodedValue::Handle(read_le(raw) as u32),
+        WitType::Tuple(items) => {
+            let mut values = Vec::with_capacity(items.len());
+            let mut field_offset = offset;
+            for item in items {
+                let (item_size, item_align) = doc.layout(item)?;
+                field_offset = align_to(field_offset, item_align);
+                values.push(decode(doc, item, bytes, field_offset)?);
+                field_offset += item_size;
+            }
+            DecodedValue::Tuple(values)
+        },
+        WitType::Record(fields) => {
+            let mut values = Vec::with_capacity(fields.len());
+            let mut field_offset = offset;
+            for (name, field) in fields {
+                let (field_size, field_align) = doc.layout(field)?;
+                field_offset = align_to(field_offset, field_align);
+                let value = decode(doc, field, bytes, field_offset)?;
+                values.push((name.clone(), value));
+                field_offset += field_size;
+            }
+            DecodedValue::Record(values)
+        },
+        WitType::Enum(cases) => {
+            let index = read_discriminant(raw, cases.len())?;
+            DecodedValue::Enum(cases[index].clone())
+        },
+        WitType::Flags(flags) => {
+            let set = flags
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| raw[bit / 8] & (1 << (bit % 8)) != 0)
+                .map(|(_, name)| name.clone())
+                .collect();
+            DecodedValue::Flags(set)
+        },
+        WitType::Option(inner) => {
+            let (case, payload) =
+                decode_variant(doc, &[None, Some(inner.as_ref())], bytes, offset)?;
+            DecodedValue::Option(if case == 0 { None } else { payload.map(Box::new) })
+        },
+        WitType::Result { ok, err } => {
+            let (case, payload) =
+                decode_variant(doc, &[ok.as_deref(), err.as_deref()], bytes, offset)?;
+            DecodedValue::Result {
+                ok: case == 0,
+                payload: payload.map(Box::new),
+            }
+        },
+        WitType::Variant(cases) => {
+            let payloads: Vec<Option<&WitType>> =
+                cases.iter().map(|(_, payload)| payload.as_ref()).collect();
+            let (case, payload) = decode_variant(doc, &payloads, bytes, offset)?;
+            DecodedValue::Variant {
+                case: cases[case].0.clone(),
+                payload: payload.map(Box::new),
+            }
+        },
+        WitType::Named(_) => unreachable!("resolve_type() never returns a named type"),
+    })
+}
+
+/// Decode the discriminant and payload of a variant-like value
+fn decode_variant(
+    doc: &WitDocument,
+    cases: &[Option<&WitType>],
+    bytes: &[u8],
+    offset: usize,
+) -> BuildResult<(usize, Option<DecodedValue>)> {
+    let disc = discriminant_size(cases.len());
+    let case = read_discriminant(&bytes[offset..offset + disc], cases.len())?;
+    let (_, payload_align) = doc.payload_layout_of(cases)?;
+    let payload = match cases[case] {
+        Some(ty) => Some(decode(
+            doc,
+            ty,
+            bytes,
+            align_to(offset + disc, payload_align),
+        )?),
+        None => None,
+    };
+    Ok((case, payload))
+}
+
+fn read_discriminant(raw: &[u8], cases: usize) -> BuildResult<usize> {
+    let index = read_le(&raw[..discriminant_size(cases)]) as usize;
+    if index >= cases {
+        return Err(BuildError::Verification(format!(
+            "Invalid discriminant {} for a type with {} cases",
+            index, cases
+        )));
+    }
+    Ok(index)
+}
+
+fn read_le(raw: &[u8]) -> u64 {
+    raw.iter().rev().fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte))
+}
+
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+/// A decoded canonical ABI value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodedValue {
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Char(char),
+    String { ptr: u32, len: u32 },
+    List { ptr: u32, len: u32 },
+    Handle(u32),
+    Tuple(Vec<DecodedValue>),
+    Record(Vec<(String, DecodedValue)>),
+    Enum(String),
+    Flags(Vec<String>),
+    Option(Option<Box<DecodedValue>>),
+    Result {
+        ok: bool,
+        payload: Option<Box<DecodedValue>>,
+    },
+    Variant {
+        case: String,
+        payload: Option<Box<DecodedValue>>,
+    },
+}
+
+impl fmt::Display for DecodedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedValue::Bool(v) => write!(f, "{}", v),
+            DecodedValue::Unsigned(v) => write!(f, "{}", v),
+            DecodedValue::Signed(v) => write!(f, "{}", v),
+            DecodedValue::Float(v) => write!(f, "{}", v),
+            DecodedValue::Char(v) => write!(f, "{:?}", v),
+            DecodedValue::String { ptr, len } => write!(f, "string(ptr={:#x}, len={})", ptr, len),
+            DecodedValue::List { ptr, len } => write!(f, "list(ptr={:#x}, len={})", ptr, len),
+            DecodedValue::Handle(v) => write!(f, "handle({})", v),
+            DecodedValue::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            },
+            DecodedValue::Record(fields) => {
+                write!(f, "{{ ")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, value)?;
+                }
+                write!(f, " }}")
+            },
+            DecodedValue::Enum(case) => write!(f, "{}", case),
+            DecodedValue::Flags(set) => write!(f, "{{{}}}", set.join(", ")),
+            DecodedValue::Option(None) => write!(f, "none"),
+            DecodedValue::Option(Some(v)) => write!(f, "some({})", v),
+            DecodedValue::Result { ok, payload } => {
+                let case = if *ok { "ok" } else { "err" };
+                match payload {
+                    Some(v) => write!(f, "{}({})", case, v),
+                    None => write!(f, "{}", case),
+                }
+            },
+            DecodedValue::Variant { case, payload } => match payload {
+                Some(v) => write!(f, "{}({})", case, v),
+                None => write!(f, "{}", case),
+            },
+        }
+    }
+}
+
+/// Direction of a traced canonical ABI operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceDirection {
+    /// Parameters lifted out of guest memory
+    Lift,
+    /// Results lowered into guest memory
+    Lower,
+}
+
+impl fmt::Display for TraceDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceDirection::Lift => write!(f, "LIFT"),
+            TraceDirection::Lower => write!(f, "LOWER"),
+        }
+    }
+}
+
+/// One parsed `LIFT:`/`LOWER:` trace line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Lift or lower
+    pub direction: TraceDirection,
+    /// Call label, `source->target::function`
+    pub call: String,
+    /// Function name taken from the call label
+    pub function: String,
+    /// Linear memory address of the operation
+    pub addr: u32,
+    /// Captured bytes
+    pub bytes: Vec<u8>,
+}
+
+impl TraceEntry {
+    /// Parse a trace line; returns `None` for lines that are not canonical
+    /// lift/lower entries (for example `CALL:`/`RETURN:` lines)
+    pub fn parse(line: &str) -> Option<BuildResult<Self>> {
+        let (direction, rest) = if let Some(rest) = line.strip_prefix("LIFT: ") {
+            (TraceDirection::Lift, rest)
+        } else if let Some(rest) = line.strip_prefix("LOWER: ") {
+            (TraceDirection::Lower, rest)
+        } else {
+            return None;
+        };
+        Some(Self::parse_body(direction, rest))
+    }
+
+    fn parse_body(direction: TraceDirection, rest: &str) -> BuildResult<Self> {
+        let malformed =
+            || BuildError::Verification(format!("Malformed {} trace line: {}", direction, rest));
+
+        let (call, rest) = rest.rsplit_once(" addr=").ok_or_else(malformed)?;
+        let (addr, bytes) = rest.split_once(" bytes=").ok_or_else(malformed)?;
+        let addr = u32::from_str_radix(addr.trim_start_matches("0x"), 16)
+            .map_err(|_| malformed())?;
+        let bytes = decode_hex(bytes.trim()).ok_or_else(malformed)?;
+        let function = call.rsplit_once("::").map_or(call, |(_, function)| function);
+
+        Ok(Self {
+            direction,
+            call: call.to_string(),
+            function: function.to_string(),
+            addr,
+            bytes,
+        })
+    }
+
+    /// Decode this entry against the function signatures of `doc`
+    pub fn decode(&self, doc: &WitDocument) -> BuildResult<DecodedEntry> {
+        let function = doc.function(&self.function)?;
+        let fields = match self.direction {
+            TraceDirection::Lift => &function.params,
+            TraceDirection::Lower => &function.results,
+        };
+
+        let tuple = WitType::Tuple(fields.iter().map(|(_, ty)| ty.clone()).collect());
+        let values = match decode(doc, &tuple, &self.bytes, 0)? {
+            DecodedValue::Tuple(values) => values,
+            _ => unreachable!("tuples decode to tuples"),
+        };
+
+        Ok(DecodedEntry {
+            direction: self.direction,
+            call: self.call.clone(),
+            addr: self.addr,
+            fields: fields
+                .iter()
+                .zip(values)
+                .map(|((name, ty), value)| DecodedField {
+                    name: name.clone(),
+                    wit_type: ty.to_string(),
+                    value,
+                })
+                .collect(),
+        })
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A named, decoded parameter or result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedField {
+    pub name: String,
+    pub wit_type: String,
+    pub value: DecodedValue,
+}
+
+/// A trace entry decoded against its WIT signature
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedEntry {
+    pub direction: TraceDirection,
+    pub call: String,
+    pub addr: u32,
+    pub fields: Vec<DecodedField>,
+}
+
+impl fmt::Display for DecodedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {} @ {:#010x}", self.direction, self.call, self.addr)?;
+        for field in &self.fields {
+            writeln!(f, "    {}: {} = {}", field.name, field.wit_type, field.value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIT: &str = r#"
+        package example:logging@0.1.0;
+
+        interface logger {
+            enum level { trace, debug, info, warn, error }
+
+            record entry {
+                level: level,
+                code: u16,
+                message: string,
+            }
+
+            flags mode { read, write, exec }
+
+            /// Log a message
+            log: func(level: level, message: string);
+            submit: func(entry: entry, retry: option<u32>) -> result<u64, level>;
+            set-mode: func(mode: mode);
+        }
+
+        world host {
+            import logger;
+            export run: func() -> s32;
+        }
+    "#;
+
+    #[test]
+    fn test_decode_lift_entry() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        // level @0 (u8), message @4 (ptr, len)
+        let line = "LIFT: guest->host::log addr=0x00001000 bytes=03000000002000000c000000";
+
+        let entry = TraceEntry::parse(line).unwrap().unwrap();
+        assert_eq!(entry.function, "log");
+        assert_eq!(entry.addr, 0x1000);
+
+        let decoded = entry.decode(&doc).unwrap();
+        assert_eq!(decoded.fields[0].value, DecodedValue::Enum("warn".to_string()));
+        assert_eq!(
+            decoded.fields[1].value,
+            DecodedValue::String {
+                ptr: 0x2000,
+                len: 12
+            }
+        );
+        assert!(decoded.to_string().contains("message: string = string(ptr=0x2000, len=12)"));
+    }
+
+    #[test]
+    fn test_decode_lower_result_and_flags() {
+        let doc = WitDocument::parse(WIT).unwrap();
+
+        let entry = TraceEntry::parse(
+            "LOWER: guest->host::submit addr=0x00000010 bytes=01000000000000000200000000000000",
+        )
+        .unwrap()
+        .unwrap();
+        let decoded = entry.decode(&doc).unwrap();
+        assert_eq!(decoded.fields[0].value.to_string(), "err(info)");
+
+        let flags = WitType::Named("mode".to_string());
+        assert_eq!(decode(&doc, &flags, &[0b101], 0).unwrap().to_string(), "{read, exec}");
+    }
+
+    #[test]
+    fn test_decode_reports_short_capture() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let entry = TraceEntry::parse("LIFT: a->b::log addr=0x0 bytes=03").unwrap().unwrap();
+        assert!(entry.decode(&doc).is_err());
+        assert!(TraceEntry::parse("CALL: a->b::log").is_none());
+        assert!(TraceEntry::parse("LIFT: a->b::log addr=zz bytes=").unwrap().is_err());
+    }
+}
@@ -0,0 +1,439 @@
+//! AUTOSAR Adaptive `ara::com` bridge generation
+//!
+//! Generates a Rust host shim that exposes the interfaces of a WIT world as
+//! `ara::com` services through `wrt_host::ara_com`. Every interface the world
+//! exports becomes a service skeleton dispatching method requests to the
+//! component, and every interface it imports becomes a service proxy the
+//! component's imports are sent through:
+//!
+//! ```text
+//! export example:nav/route@1.2.0  ->  skeleton, service 0x1000 v1.2
+//! import example:nav/gnss@1.2.0   ->  proxy,    service 0x1001 v1.2
+//! ```
+//!
+//! Service IDs are assigned in declaration order from a configurable base,
+//! exports first, and method IDs in declaration order from `0x0001`.
+//! Interface versions follow the package version. Functions declared
+//! directly in the world form one service named after the world. Interfaces
+//! of other packages have no signatures in the document and are skipped.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use wrt_format::wit_parser::{WitDocument, WitFunction, WitWorldItem};
+
+use crate::{
+    error::{BuildError, BuildResult},
+    world_proxy::{lowered_arity, select_world, signature, snake_case},
+};
+
+/// Module world-level functions are imported from and exported to
+const ROOT_MODULE: &str = "$root";
+
+/// First method ID of a service
+const FIRST_METHOD_ID: u16 = 0x0001;
+
+/// Service and instance IDs of the generated bridge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AraComConfig {
+    /// Service ID of the first service; later services count up from it
+    pub service_id_base: u16,
+    /// Instance ID every service is offered or looked up under
+    pub instance_id: u16,
+}
+
+impl Default for AraComConfig {
+    fn default() -> Self {
+        Self {
+            service_id_base: 0x1000,
+            instance_id: 1,
+        }
+    }
+}
+
+/// Which side of a service the component is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceRole {
+    /// The component exports the interface and offers the service
+    Skeleton,
+    /// The component imports the interface and consumes the service
+    Proxy,
+}
+
+/// Service method mapped from a WIT function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AraComMethod {
+    /// Name of the WIT function
+    pub function: String,
+    /// Method ID within the service
+    pub method_id: u16,
+    /// Number of core values the method is called with
+    pub params: usize,
+    /// Number of core values the method returns
+    pub results: usize,
+    /// WIT signature of the function
+    pub signature: String,
+}
+
+/// Service mapped from a WIT interface
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AraComService {
+    /// Short name of the service interface, the WIT interface name
+    pub name: String,
+    /// Module the component imports or exports the functions under
+    pub module: String,
+    /// Whether the service is offered or consumed
+    pub role: ServiceRole,
+    /// Service ID
+    pub service_id: u16,
+    /// Major interface version
+    pub major_version: u8,
+    /// Minor interface version
+    pub minor_version: u32,
+    /// Methods in declaration order
+    pub methods: Vec<AraComMethod>,
+}
+
+/// Services of all interfaces of a world
+#[derive(Debug, Clone, Serialize)]
+pub struct AraComPlan {
+    /// World the interfaces belong to
+    pub world: String,
+    /// Instance ID every service is offered or looked up under
+    pub instance_id: u16,
+    /// Offered services followed by consumed services
+    pub services: Vec<AraComService>,
+    /// Interfaces of other packages, which are not mapped
+    pub skipped: Vec<String>,
+}
+
+impl AraComPlan {
+    /// Plan the bridge for `world`, or the only world of the document
+    pub fn new(doc: &WitDocument, world: Option<&str>, config: AraComConfig) -> BuildResult<Self> {
+        let world = select_world(doc, world)?;
+        let (major_version, minor_version) = package_version(doc);
+
+        let mut services = Vec::new();
+        let mut skipped = Vec::new();
+        let mut service_ids: HashMap<String, u16> = HashMap::new();
+        let sides = [
+            (ServiceRole::Skeleton, &world.exports),
+            (ServiceRole::Proxy, &world.imports),
+        ];
+        for (role, items) in sides {
+            let export = role == ServiceRole::Skeleton;
+            let mut root_functions = Vec::new();
+            let mut groups: Vec<(String, String, Vec<&WitFunction>)> = Vec::new();
+            for item in items {
+                match item {
+                    WitWorldItem::Interface(interface) => groups.push((
+                        interface.clone(),
+                        doc.qualified_interface(interface),
+                        doc.functions()
+                            .iter()
+                            .filter(|f| f.interface.as_deref() == Some(interface.as_str()))
+                            .collect(),
+                    )),
+                    WitWorldItem::Function(name) => {
+                        root_functions.push(
+                            doc.functions()
+                                .iter()
+                                .find(|f| f.interface.is_none() && f.name == *name)
+                                .ok_or_else(|| {
+                                    BuildError::Verification(format!(
+                                        "No signature for world function '{}'",
+                                        name
+                                    ))
+                                })?,
+                        );
+                    },
+                    WitWorldItem::External(name) => skipped.push(name.clone()),
+                }
+            }
+            if !root_functions.is_empty() {
+                groups.push((world.name.clone(), ROOT_MODULE.to_string(), root_functions));
+            }
+
+            for (name, module, functions) in groups {
+                let next_id = config.service_id_base as usize + service_ids.len();
+                let service_id = match service_ids.get(&name) {
+                    Some(&id) => id,
+                    None => {
+                        let id = u16::try_from(next_id).map_err(|_| {
+                            BuildError::Verification(
+                                "Service IDs exceed the 16-bit range".to_string(),
+                            )
+                        })?;
+                        service_ids.insert(name.clone(), id);
+                        id
+                    },
+                };
+
+                let mut methods = Vec::with_capacity(functions.len());
+                for (method_id, function) in (FIRST_METHOD_ID..).zip(functions) {
+                    let (params, results) = lowered_arity(doc, function, export)?;
+                    methods.push(AraComMethod {
+                        function: function.name.clone(),
+                        method_id,
+                        params,
+                        results,
+                        signature: signature(function),
+                    });
+                }
+                services.push(AraComService {
+                    name,
+                    module,
+                    role,
+                    service_id,
+                    major_version,
+                    minor_version,
+                    methods,
+                });
+            }
+        }
+
+        Ok(Self {
+            world: world.name.clone(),
+            instance_id: config.instance_id,
+            services,
+            skipped,
+        })
+    }
+
+    /// Services the component offers or consumes
+    pub fn services_with_role(&self, role: ServiceRole) -> impl Iterator<Item = &AraComService> {
+        self.services.iter().filter(move |service| service.role == role)
+    }
+
+    /// Render the host shim offering and consuming the services
+    pub fn to_rust(&self) -> String {
+        let ident = snake_case(&self.world);
+        let mut source = String::new();
+
+        source.push_str(&format!(
+            "//! ara::com bridge exposing the interfaces of WIT world `{}` as AUTOSAR\n//! \
+             Adaptive services.\n//!\n//! Generated by `cargo-wrt ara-com`; regenerate it \
+             instead of editing.\n\n",
+            self.world
+        ));
+        source.push_str("use std::sync::Arc;\n\n");
+        source.push_str(
+            "use wrt_host::{\n    AraComBinding, CallbackRegistry, Result, ServiceInterface, \
+             ServiceProxy, ServiceSkeleton,\n};\n\n",
+        );
+
+        for service in &self.services {
+            source.push_str(&format!("/// Service interface `{}`\n", service.name));
+            source.push_str(&format!(
+                "pub fn {}() -> ServiceInterface {{\n",
+                self.service_fn(service)
+            ));
+            source.push_str(&format!(
+                "    ServiceInterface::new({:?}, {:#06x}, {}, {})\n",
+                service.name, service.service_id, service.major_version, service.minor_version
+            ));
+            for method in &service.methods {
+                source.push_str(&format!("        // {}\n", method.signature));
+                source.push_str(&format!(
+                    "        .method({:?}, {:#06x}, {}, {})\n",
+                    method.function, method.method_id, method.params, method.results
+                ));
+            }
+            source.push_str("}\n\n");
+        }
+
+        source.push_str(&format!(
+            "/// Skeletons for the services world `{}` offers\n",
+            self.world
+        ));
+        source.push_str(&format!(
+            "pub fn {}_skeletons() -> Vec<ServiceSkeleton> {{\n",
+            ident
+        ));
+        source.push_str("    vec![\n");
+        for service in self.services_with_role(ServiceRole::Skeleton) {
+            source.push_str(&format!(
+                "        ServiceSkeleton::new({}(), {}, {:?}),\n",
+                self.service_fn(service),
+                self.instance_id,
+                service.module
+            ));
+        }
+        source.push_str("    ]\n}\n\n");
+
+        source.push_str(&format!(
+            "/// Proxies for the services world `{}` consumes\n",
+            self.world
+        ));
+        source.push_str(&format!(
+            "pub fn {}_proxies() -> Vec<ServiceProxy> {{\n",
+            ident
+        ));
+        source.push_str("    vec![\n");
+        for service in self.services_with_role(ServiceRole::Proxy) {
+            source.push_str(&format!(
+                "        ServiceProxy::new({}(), {}, {:?}),\n",
+                self.service_fn(service),
+                self.instance_id,
+                service.module
+            ));
+        }
+        source.push_str("    ]\n}\n\n");
+
+        source.push_str(&format!(
+            "/// Offer the services of world `{}` through `binding`\n",
+            self.world
+        ));
+        source.push_str(&format!(
+            "pub fn offer_{}_services(binding: &dyn AraComBinding) -> Result<()> {{\n",
+            ident
+        ));
+        source.push_str(&format!(
+            "    for skeleton in {}_skeletons() {{\n        skeleton.offer(binding)?;\n    \
+             }}\n    Ok(())\n}}\n\n",
+            ident
+        ));
+
+        source.push_str(&format!(
+            "/// Send the imports of world `{}` to their services through `binding`\n",
+            self.world
+        ));
+        source.push_str(&format!(
+            "pub fn install_{}_proxies(\n    registry: &mut CallbackRegistry,\n    binding: \
+             Arc<dyn AraComBinding>,\n) -> Result<usize> {{\n",
+            ident
+        ));
+        source.push_str(&format!(
+            "    let mut installed = 0;\n    for proxy in {}_proxies() {{\n        installed += \
+             proxy.install(registry, Arc::clone(&binding))?;\n    }}\n    Ok(installed)\n}}\n",
+            ident
+        ));
+        source
+    }
+
+    /// Function rendering the interface of `service`
+    ///
+    /// An interface the world both imports and exports is lowered differently
+    /// on each side, so both functions are suffixed with the role.
+    fn service_fn(&self, service: &AraComService) -> String {
+        let name = snake_case(&service.name);
+        if self.services.iter().filter(|other| other.name == service.name).count() > 1 {
+            match service.role {
+                ServiceRole::Skeleton => format!("{}_skeleton_service", name),
+                ServiceRole::Proxy => format!("{}_proxy_service", name),
+            }
+        } else {
+            format!("{}_service", name)
+        }
+    }
+}
+
+/// Major and minor interface version from the package version, `1.0` if the
+/// package is unversioned
+fn package_version(doc: &WitDocument) -> (u8, u32) {
+    let version = doc.package().and_then(|package| package.split_once('@')).map(|(_, v)| v);
+    let mut parts = version.unwrap_or("1.0.0").split('.');
+    let major = parts.next().and_then(|part| part.parse().ok()).unwrap_or(1);
+    let minor = parts.next().and_then(|part| part.parse().ok()).unwrap_or(0);
+    (major, minor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIT: &str = r#"
+        package example:nav@2.3.0;
+
+        interface route {
+            record waypoint { lat: f64, lon: f64 }
+            plan: func(start: waypoint, goal: waypoint) -> list<waypoint>;
+            cancel: func();
+        }
+
+        interface gnss {
+            position: func() -> tuple<f64, f64>;
+        }
+
+        world navigator {
+            import gnss;
+            import wasi:clocks/monotonic-clock@0.2.0;
+            import log: func(message: string);
+            export route;
+        }
+    "#;
+
+    #[test]
+    fn test_ara_com_plan() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let plan = AraComPlan::new(&doc, None, AraComConfig::default()).unwrap();
+
+        let services: Vec<_> = plan
+            .services
+            .iter()
+            .map(|s| (s.name.as_str(), s.module.as_str(), s.role, s.service_id))
+            .collect();
+        assert_eq!(
+            services,
+            vec![
+                (
+                    "route",
+                    "example:nav/route@2.3.0",
+                    ServiceRole::Skeleton,
+                    0x1000
+                ),
+                ("gnss", "example:nav/gnss@2.3.0", ServiceRole::Proxy, 0x1001),
+                ("navigator", "$root", ServiceRole::Proxy, 0x1002),
+            ]
+        );
+        assert_eq!(
+            (
+                plan.services[0].major_version,
+                plan.services[0].minor_version
+            ),
+            (2, 3)
+        );
+        assert_eq!(plan.skipped, vec!["wasi:clocks/monotonic-clock@0.2.0"]);
+
+        let methods: Vec<_> = plan.services[0]
+            .methods
+            .iter()
+            .map(|m| (m.function.as_str(), m.method_id, m.params, m.results))
+            .collect();
+        // An exported list is returned through a single pointer
+        assert_eq!(methods, vec![("plan", 1, 4, 1), ("cancel", 2, 0, 0)]);
+        // An imported tuple is written through an extra pointer parameter
+        assert_eq!(plan.services[1].methods[0].params, 1);
+        assert_eq!(plan.services[1].methods[0].results, 0);
+
+        let config = AraComConfig {
+            service_id_base: 0xFFFF,
+            instance_id: 1,
+        };
+        assert!(AraComPlan::new(&doc, None, config).is_err());
+        assert!(AraComPlan::new(&doc, Some("missing"), AraComConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_ara_com_rust_source() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let config = AraComConfig {
+            service_id_base: 0x4000,
+            instance_id: 7,
+        };
+        let source = AraComPlan::new(&doc, Some("navigator"), config).unwrap().to_rust();
+
+        assert!(source.contains("pub fn route_service() -> ServiceInterface {"));
+        assert!(source.contains("ServiceInterface::new(\"route\", 0x4000, 2, 3)"));
+        assert!(source.contains(".method(\"plan\", 0x0001, 4, 1)"));
+        assert!(source.contains("// cancel: func()"));
+        assert!(
+            source
+                .contains("ServiceSkeleton::new(route_service(), 7, \"example:nav/route@2.3.0\"),")
+        );
+        assert!(source.contains("ServiceProxy::new(navigator_service(), 7, \"$root\"),"));
+        assert!(source.contains("pub fn offer_navigator_services(binding: &dyn AraComBinding)"));
+        assert!(source.contains("pub fn install_navigator_proxies("));
+    }
+}
@@ -0,0 +1,328 @@
+//! Latency-critical benchmark gate
+//!
+//! The interpreter hot paths (instruction dispatch, memory load/store,
+//! call/return and canonical string lift) are benchmarked in the `critical`
+//! criterion group of the benches listed in [`CRITICAL_BENCHES`]. Running
+//! them records the mean time of each benchmark and compares it with a
+//! baseline; a benchmark whose mean grew by more than the tolerance, or
+//! that disappeared, fails the gate.
+//!
+//! Absolute timings only compare on the same machine, so CI records the
+//! baseline from the merge base and measures the change on the same runner
+//! right after.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    build::BuildSystem,
+    error::{BuildError, BuildResult},
+};
+
+/// Default baseline file in the workspace root
+pub const BASELINE_FILE: &str = "critical-benchmarks.json";
+
+/// Criterion group holding the critical benchmarks
+pub const CRITICAL_GROUP: &str = "critical";
+
+/// Default allowed slowdown of a benchmark's mean, in percent
+pub const DEFAULT_TOLERANCE_PERCENT: f64 = 10.0;
+
+/// Bench target containing critical benchmarks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CriticalBench {
+    /// Package of the bench target
+    pub package: &'static str,
+    /// Name of the bench target
+    pub bench: &'static str,
+    /// Features the bench target requires
+    pub features: &'static str,
+}
+
+/// Bench targets run by the gate
+pub const CRITICAL_BENCHES: &[CriticalBench] = &[
+    CriticalBench {
+        package: "wrt-runtime",
+        bench: "critical_path",
+        features: "std",
+    },
+    CriticalBench {
+        package: "wrt-component",
+        bench: "critical_path",
+        features: "std",
+    },
+];
+
+/// Mean times the gate compares against
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CriticalBaseline {
+    /// When the baseline was recorded
+    pub generated_at: String,
+    /// Mean time in nanoseconds per benchmark
+    pub benchmarks: BTreeMap<String, f64>,
+}
+
+impl CriticalBaseline {
+    /// Load a baseline written by [`CriticalBenchReport::write_baseline`]
+    pub fn load(path: &Path) -> BuildResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            BuildError::Verification(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            BuildError::Verification(format!("Failed to parse {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Measurement of one critical benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriticalBenchResult {
+    /// Benchmark name within the critical group
+    pub name: String,
+    /// Mean time in nanoseconds, if the benchmark ran
+    pub mean_ns: Option<f64>,
+    /// Mean time in the baseline, if the benchmark is in it
+    pub baseline_ns: Option<f64>,
+    /// Change of the mean against the baseline, in percent
+    pub change_percent: Option<f64>,
+    /// Whether the benchmark regressed beyond the tolerance or is missing
+    pub regressed: bool,
+}
+
+/// Result of a critical benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalBenchReport {
+    /// When the report was generated
+    pub generated_at: String,
+    /// Baseline the means were compared against
+    pub baseline: Option<String>,
+    /// Allowed slowdown in percent
+    pub tolerance_percent: f64,
+    /// Benchmarks ordered by name
+    pub results: Vec<CriticalBenchResult>,
+}
+
+impl CriticalBenchReport {
+    /// Compare `measured` mean times with `baseline`
+    ///
+    /// Benchmarks missing from the baseline pass; baseline benchmarks that
+    /// were not measured regress.
+    pub fn build(
+        measured: &BTreeMap<String, f64>,
+        baseline: Option<&CriticalBaseline>,
+        tolerance_percent: f64,
+    ) -> Self {
+        let empty = BTreeMap::new();
+        let accepted = baseline.map_or(&empty, |baseline| &baseline.benchmarks);
+        let mut names: Vec<&String> = measured.keys().chain(accepted.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        let results = names
+            .into_iter()
+            .map(|name| {
+                let mean_ns = measured.get(name).copied();
+                let baseline_ns = accepted.get(name).copied();
+                let change_percent = match (mean_ns, baseline_ns) {
+                    (Some(mean), Some(base)) if base > 0.0 => Some((mean - base) / base * 100.0),
+                    _ => None,
+                };
+                let regressed = match (mean_ns, change_percent) {
+                    (None, _) => true,
+                    (Some(_), Some(change)) => change > tolerance_percent,
+                    (Some(_), None) => false,
+                };
+                CriticalBenchResult {
+                    name: name.clone(),
+                    mean_ns,
+                    baseline_ns,
+                    change_percent,
+                    regressed,
+                }
+            })
+            .collect();
+
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            baseline: None,
+            tolerance_percent,
+            results,
+        }
+    }
+
+    /// Whether no benchmark regressed
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| !result.regressed)
+    }
+
+    /// Benchmarks that regressed
+    pub fn regressions(&self) -> impl Iterator<Item = &CriticalBenchResult> {
+        self.results.iter().filter(|result| result.regressed)
+    }
+
+    /// Baseline accepting the measured means of this report
+    pub fn to_baseline(&self) -> CriticalBaseline {
+        CriticalBaseline {
+            generated_at: self.generated_at.clone(),
+            benchmarks: self
+                .results
+                .iter()
+                .filter_map(|result| Some((result.name.clone(), result.mean_ns?)))
+                .collect(),
+        }
+    }
+
+    /// Write the baseline accepting the measured means
+    pub fn write_baseline(&self, path: &Path) -> BuildResult<()> {
+        let json = serde_json::to_string_pretty(&self.to_baseline()).map_err(|e| {
+            BuildError::Verification(format!("Failed to serialize benchmark baseline: {}", e))
+        })?;
+        fs::write(path, json).map_err(|e| {
+            BuildError::Verification(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Render the report as JSON
+    pub fn to_json(&self) -> BuildResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            BuildError::Verification(format!("Failed to serialize benchmark report: {}", e))
+        })
+    }
+}
+
+/// Options of a critical benchmark run
+#[derive(Debug, Clone)]
+pub struct CriticalBenchOptions {
+    /// Baseline to compare against, if any
+    pub baseline: Option<PathBuf>,
+    /// Allowed slowdown in percent
+    pub tolerance_percent: f64,
+}
+
+impl Default for CriticalBenchOptions {
+    fn default() -> Self {
+        Self {
+            baseline: None,
+            tolerance_percent: DEFAULT_TOLERANCE_PERCENT,
+        }
+    }
+}
+
+impl BuildSystem {
+    /// Run the critical benchmarks and compare them with the baseline
+    pub fn critical_benchmarks(
+        &self,
+        options: &CriticalBenchOptions,
+    ) -> BuildResult<CriticalBenchReport> {
+        let criterion_dir = self.workspace.root.join("target").join("criterion");
+        let group_dir = criterion_dir.join(CRITICAL_GROUP);
+        // Results of removed benchmarks must not be picked up
+        if group_dir.exists() {
+            fs::remove_dir_all(&group_dir)?;
+        }
+
+        for target in CRITICAL_BENCHES {
+            let output = Command::new("cargo")
+                .args(["bench", "-p", target.package, "--bench", target.bench])
+                .args(["--features", target.features])
+                .env("CRITERION_HOME", &criterion_dir)
+                .current_dir(&self.workspace.root)
+                .output()
+                .map_err(|e| BuildError::Tool(format!("Failed to run cargo bench: {}", e)))?;
+            if !output.status.success() {
+                return Err(BuildError::Test(format!(
+                    "Benchmark {}/{} failed: {}",
+                    target.package,
+                    target.bench,
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+        }
+
+        let baseline = match &options.baseline {
+            Some(path) if path.exists() => Some(CriticalBaseline::load(path)?),
+            _ => None,
+        };
+        let measured = read_group_means(&group_dir)?;
+        let mut report =
+            CriticalBenchReport::build(&measured, baseline.as_ref(), options.tolerance_percent);
+        if baseline.is_some() {
+            report.baseline = options.baseline.as_ref().map(|path| path.display().to_string());
+        }
+        Ok(report)
+    }
+}
+
+/// Mean time in nanoseconds of every benchmark criterion recorded in
+/// `group_dir`
+pub fn read_group_means(group_dir: &Path) -> BuildResult<BTreeMap<String, f64>> {
+    let mut means = BTreeMap::new();
+    if !group_dir.exists() {
+        return Ok(means);
+    }
+    for entry in fs::read_dir(group_dir)? {
+        let entry = entry?;
+        let estimates = entry.path().join("new").join("estimates.json");
+        if !estimates.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&estimates)?;
+        let mean = parse_mean(&content).ok_or_else(|| {
+            BuildError::Verification(format!("No mean estimate in {}", estimates.display()))
+        })?;
+        means.insert(entry.file_name().to_string_lossy().into_owned(), mean);
+    }
+    Ok(means)
+}
+
+/// Point estimate of the mean in a criterion `estimates.json`
+fn parse_mean(estimates: &str) -> Option<f64> {
+    let json: serde_json::Value = serde_json::from_str(estimates).ok()?;
+    json.get("mean")?.get("point_estimate")?.as_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_flags_regressions_and_missing_benchmarks() {
+        let baseline = CriticalBaseline {
+            generated_at: String::new(),
+            benchmarks: BTreeMap::from([
+                ("call_return".to_string(), 100.0),
+                ("dispatch".to_string(), 100.0),
+                ("removed".to_string(), 50.0),
+            ]),
+        };
+        let measured = BTreeMap::from([
+            ("call_return".to_string(), 109.0),
+            ("dispatch".to_string(), 125.0),
+            ("new_path".to_string(), 10.0),
+        ]);
+
+        let report = CriticalBenchReport::build(&measured, Some(&baseline), 10.0);
+        let regressed: Vec<_> = report.regressions().map(|result| result.name.as_str()).collect();
+        assert_eq!(regressed, ["dispatch", "removed"]);
+        assert!(!report.passed());
+        assert_eq!(report.to_baseline().benchmarks.len(), 3);
+
+        let report = CriticalBenchReport::build(&measured, None, 10.0);
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn test_parse_criterion_estimates() {
+        let estimates = r#"{"mean":{"confidence_interval":{"confidence_level":0.95,
+            "lower_bound":10.0,"upper_bound":12.0},"point_estimate":11.5,
+            "standard_error":0.5},"median":{"point_estimate":11.0}}"#;
+        assert_eq!(parse_mean(estimates), Some(11.5));
+        assert_eq!(parse_mean("{}"), None);
+    }
+}
@@ -12,8 +12,10 @@
 };
 
 use colored::Colorize;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
+    build_cache::{self, StepCache},
     config::{BuildConfig, WorkspaceConfig},
     diagnostics::{Diagnostic, DiagnosticCollection, Range, Severity, ToolOutputParser},
     error::{BuildError, BuildResult},
@@ -817,7 +819,7 @@ pub struct BuildSystem {
 
 /// Build results and artifacts
 #[cfg(feature = "std")]
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct BuildResults {
     /// Whether the build succeeded
     pub success: bool,
@@ -855,6 +857,15 @@ pub fn with_config(workspace_root: PathBuf, config: BuildConfig) -> BuildResult<
 
     /// Build all components in the workspace
     pub fn build_all(&self) -> BuildResult<BuildResults> {
+        let options = format!(
+            "clippy={} format_check={}",
+            self.config.clippy, self.config.format_check
+        );
+        self.run_cached_step("build", &options, || self.build_all_uncached(), |r| r.success)
+    }
+
+    /// Build all components without consulting the step cache
+    fn build_all_uncached(&self) -> BuildResult<BuildResults> {
         println!("{} Building all WRT components...", "🔨".bright_blue());
 
         let start_time = std::time::Instant::now();
@@ -1150,6 +1161,34 @@ pub fn check_requirements(&self, requirements_file: Option<&Path>) -> BuildResul
         Ok(())
     }
 
+    /// Trace `SW-REQ-ID` annotations in the workspace to requirements
+    ///
+    /// Annotations are matched against `requirements_file`, or
+    /// `requirements.toml` in the workspace root if it exists. Without a
+    /// requirements file every annotated ID counts as declared.
+    pub fn traceability_matrix(
+        &self,
+        requirements_file: Option<&Path>,
+    ) -> BuildResult<crate::requirements::TraceabilityMatrix> {
+        use crate::requirements::{traceability, DeclaredRequirement, TraceabilityMatrix};
+
+        let req_path = requirements_file
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| self.workspace.root.join("requirements.toml"));
+        let requirements = if requirements_file.is_some() || req_path.exists() {
+            Some(DeclaredRequirement::load(&req_path)?)
+        } else {
+            None
+        };
+
+        let links = traceability::scan_annotations(&self.workspace.root)?;
+        let mut matrix = TraceabilityMatrix::build(links, requirements.as_deref());
+        if requirements.is_some() {
+            matrix.requirements_file = Some(req_path.display().to_string());
+        }
+        Ok(matrix)
+    }
+
     /// Initialize sample requirements file
     pub fn init_requirements(&self, path: Option<&Path>) -> BuildResult<()> {
         use crate::requirements::Requirements;
@@ -1377,6 +1416,16 @@ pub fn build_all_with_diagnostics(&self) -> BuildResult<DiagnosticCollection> {
 
     /// Build a specific package by name
     pub fn build_package(&self, package_name: &str) -> BuildResult<BuildResults> {
+        self.run_cached_step(
+            &format!("build:{}", package_name),
+            "",
+            || self.build_package_uncached(package_name),
+            |r| r.success,
+        )
+    }
+
+    /// Build a package without consulting the step cache
+    fn build_package_uncached(&self, package_name: &str) -> BuildResult<BuildResults> {
         if self.config.verbose {
             println!(
                 "  {} Building package: {}",
@@ -1440,6 +1489,16 @@ pub fn build_package(&self, package_name: &str) -> BuildResult<BuildResults> {
 
     /// Test a specific package by name
     pub fn test_package(&self, package_name: &str) -> BuildResult<BuildResults> {
+        self.run_cached_step(
+            &format!("test:{}", package_name),
+            "",
+            || self.test_package_uncached(package_name),
+            |r| r.success,
+        )
+    }
+
+    /// Test a package without consulting the step cache
+    fn test_package_uncached(&self, package_name: &str) -> BuildResult<BuildResults> {
         if self.config.verbose {
             println!("  {} Testing package: {}", "🧪".bright_cyan(), package_name);
         }
@@ -1586,6 +1645,97 @@ pub fn workspace(&self) -> &WorkspaceConfig {
         &self.workspace
     }
 
+    /// Path of the build step cache
+    pub fn step_cache_path(&self) -> PathBuf {
+        self.workspace.root.join("target").join("wrt-cache").join("steps.json")
+    }
+
+    /// Load the build step cache
+    pub fn step_cache(&self) -> BuildResult<StepCache> {
+        StepCache::load(self.step_cache_path())
+    }
+
+    /// Remove all cached step results
+    pub fn clear_step_cache(&self) -> BuildResult<()> {
+        let cache_path = self.step_cache_path();
+        if cache_path.exists() {
+            std::fs::remove_file(&cache_path)
+                .map_err(|e| BuildError::Tool(format!("Failed to remove step cache: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Fingerprint of a step's inputs under the current configuration
+    ///
+    /// Covers the workspace sources, enabled features, build profile, Rust
+    /// toolchain, build environment and the step-specific `options`.
+    pub fn step_fingerprint(&self, options: &str) -> BuildResult<String> {
+        let root = &self.workspace.root;
+        let dep_info = build_cache::DepInfo::scan(&build_cache::target_dir(root))?;
+        let sources = build_cache::hash_sources(root, &dep_info)?;
+        let environment = build_cache::hash_environment(&dep_info);
+        let toolchain = build_cache::toolchain_id(root)?;
+        let mut features = self.config.features.clone();
+        features.sort();
+        features.dedup();
+        let features = features.join(",");
+        let profile = format!("{:?}", self.config.profile);
+
+        Ok(build_cache::fingerprint(&[
+            &sources,
+            &environment,
+            &toolchain,
+            &features,
+            &profile,
+            options,
+        ]))
+    }
+
+    /// Run a step, or return its cached result if its inputs are unchanged
+    ///
+    /// Only results for which `succeeded` holds are cached; a failed run
+    /// forgets any earlier result of the step. Caching is skipped unless
+    /// [`BuildConfig::step_cache`] is set, and in dry runs.
+    pub fn run_cached_step<T, F, S>(
+        &self,
+        step: &str,
+        options: &str,
+        run: F,
+        succeeded: S,
+    ) -> BuildResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> BuildResult<T>,
+        S: FnOnce(&T) -> bool,
+    {
+        if !self.config.step_cache || self.config.dry_run {
+            return run();
+        }
+
+        let cache_path = self.step_cache_path();
+        let fingerprint = self.step_fingerprint(options)?;
+        let mut cache = StepCache::load(&cache_path)?;
+
+        if let Some(result) = cache.lookup(step, &fingerprint) {
+            println!(
+                "{} Skipping {}: inputs unchanged since last successful run",
+                "♻️".bright_green(),
+                step
+            );
+            return Ok(result);
+        }
+
+        let result = run();
+        match &result {
+            Ok(value) if succeeded(value) => cache.record(step, &fingerprint, value)?,
+            _ => {
+                cache.invalidate(step);
+            },
+        }
+        cache.save(&cache_path)?;
+        result
+    }
+
     /// List available fuzzing targets (delegated to fuzz module)
     pub fn list_fuzz_targets(&self) -> BuildResult<Vec<String>> {
         use crate::fuzz::*;
@@ -0,0 +1,550 @@
+//! Incremental caching of build, test and verification steps
+//!
+//! A step is fingerprinted by hashing the workspace sources together with the
+//! enabled features, build profile, Rust toolchain, build environment and the
+//! step's own options. When the fingerprint matches the last successful run of
+//! the step, the step is skipped and its recorded result is returned instead.
+//!
+//! Only files that can affect compilation or tests are hashed, so
+//! documentation changes leave the cache valid. Those are the Rust, manifest
+//! and WebAssembly sources of the workspace, plus every workspace file the
+//! compiler read in an earlier build according to the dep-info files cargo
+//! writes under the target directory. The latter catches files pulled in with
+//! `include_str!` or `include_bytes!`, whatever their extension.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use walkdir::WalkDir;
+
+use crate::error::{BuildError, BuildResult};
+
+/// File extensions hashed as step inputs
+const INPUT_EXTENSIONS: &[&str] = &["rs", "toml", "lock", "wasm", "wat", "wast", "wit"];
+
+/// Directories that are never hashed
+const SKIPPED_DIRS: &[&str] = &["target", ".git", "docs", "node_modules"];
+
+/// Directories of the target directory that never hold dep-info files
+const SKIPPED_TARGET_DIRS: &[&str] = &["incremental", ".fingerprint"];
+
+/// Environment variables that change how cargo compiles the workspace
+const BUILD_ENV_VARS: &[&str] = &[
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_BUILD_RUSTFLAGS",
+    "RUSTDOCFLAGS",
+    "CARGO_ENCODED_RUSTDOCFLAGS",
+    "RUSTC",
+    "RUSTC_WRAPPER",
+    "RUSTC_WORKSPACE_WRAPPER",
+    "RUSTUP_TOOLCHAIN",
+    "CARGO_BUILD_TARGET",
+];
+
+/// Recorded result of the last successful run of a step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepCacheEntry {
+    /// Fingerprint of the inputs the step ran with
+    pub fingerprint: String,
+    /// Result the step returned
+    pub result: serde_json::Value,
+    /// When the step ran
+    pub recorded_at: SystemTime,
+}
+
+/// Cache of step results, keyed by step name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepCache {
+    /// Cache format version for compatibility
+    pub version: u32,
+    /// Last successful run of each step
+    pub entries: BTreeMap<String, StepCacheEntry>,
+}
+
+impl StepCache {
+    /// Cache format version
+    const VERSION: u32 = 1;
+
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self {
+            version: Self::VERSION,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache from disk, starting empty if it does not exist
+    pub fn load<P: AsRef<Path>>(cache_path: P) -> BuildResult<Self> {
+        let cache_path = cache_path.as_ref();
+
+        if !cache_path.exists() {
+            return Ok(Self::new());
+        }
+
+        let cache_content = fs::read_to_string(cache_path)
+            .map_err(|e| BuildError::Tool(format!("Failed to read step cache: {}", e)))?;
+
+        let cache: StepCache = serde_json::from_str(&cache_content)
+            .map_err(|e| BuildError::Tool(format!("Failed to parse step cache: {}", e)))?;
+
+        if cache.version != Self::VERSION {
+            return Ok(Self::new());
+        }
+
+        Ok(cache)
+    }
+
+    /// Save the cache to disk
+    pub fn save<P: AsRef<Path>>(&self, cache_path: P) -> BuildResult<()> {
+        let cache_path = cache_path.as_ref();
+
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                BuildError::Tool(format!("Failed to create cache directory: {}", e))
+            })?;
+        }
+
+        let cache_content = serde_json::to_string_pretty(self)
+            .map_err(|e| BuildError::Tool(format!("Failed to serialize step cache: {}", e)))?;
+
+        fs::write(cache_path, cache_content)
+            .map_err(|e| BuildError::Tool(format!("Failed to write step cache: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Cached result of `step`, if it last succeeded with the same inputs
+    pub fn lookup<T: DeserializeOwned>(&self, step: &str, fingerprint: &str) -> Option<T> {
+        let entry = self.entries.get(step)?;
+        if entry.fingerprint != fingerprint {
+            return None;
+        }
+        serde_json::from_value(entry.result.clone()).ok()
+    }
+
+    /// Record a successful run of `step`, replacing any earlier run
+    pub fn record<T: Serialize>(
+        &mut self,
+        step: &str,
+        fingerprint: &str,
+        result: &T,
+    ) -> BuildResult<()> {
+        let result = serde_json::to_value(result)
+            .map_err(|e| BuildError::Tool(format!("Failed to serialize step result: {}", e)))?;
+
+        self.entries.insert(
+            step.to_string(),
+            StepCacheEntry {
+                fingerprint: fingerprint.to_string(),
+                result,
+                recorded_at: SystemTime::now(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Forget the result of `step`, returning whether it was cached
+    pub fn invalidate(&mut self, step: &str) -> bool {
+        self.entries.remove(step).is_some()
+    }
+
+    /// Forget all results
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached steps
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no steps are cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for StepCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inputs of earlier builds, read from cargo's dep-info files
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepInfo {
+    /// Files the compiler read, as written in the dep-info files
+    pub files: BTreeSet<PathBuf>,
+    /// Environment variables the compiled code reads with `env!`
+    pub env: BTreeSet<String>,
+}
+
+impl DepInfo {
+    /// Collect the dep-info files under `target_dir`
+    ///
+    /// A missing target directory yields no inputs: nothing has been built
+    /// yet.
+    pub fn scan(target_dir: &Path) -> BuildResult<Self> {
+        let mut dep_info = Self::default();
+        if !target_dir.exists() {
+            return Ok(dep_info);
+        }
+
+        let walker = WalkDir::new(target_dir).into_iter().filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_TARGET_DIRS.contains(&name)))
+        });
+        for entry in walker {
+            let entry = entry
+                .map_err(|e| BuildError::Tool(format!("Failed to scan target directory: {}", e)))?;
+            if entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "d")
+            {
+                let content = fs::read_to_string(entry.path()).map_err(|e| {
+                    BuildError::Tool(format!("Failed to read {}: {}", entry.path().display(), e))
+                })?;
+                dep_info.parse(&content);
+            }
+        }
+        Ok(dep_info)
+    }
+
+    /// Add the inputs listed in the dep-info `content`
+    ///
+    /// Each rule lists the files an output depends on, separated by spaces,
+    /// with spaces in paths escaped by a backslash. `# env-dep:NAME=value`
+    /// comments name the environment variables read by the crate.
+    pub fn parse(&mut self, content: &str) {
+        for line in content.lines() {
+            if let Some(env) = line.strip_prefix("# env-dep:") {
+                let name = env.split_once('=').map_or(env, |(name, _)| name);
+                self.env.insert(name.to_string());
+                continue;
+            }
+            let Some((_, deps)) = line.split_once(": ") else {
+                continue;
+            };
+
+            let mut path = String::new();
+            let mut chars = deps.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => path.extend(chars.next()),
+                    ' ' => {
+                        if !path.is_empty() {
+                            self.files.insert(PathBuf::from(std::mem::take(&mut path)));
+                        }
+                    },
+                    c => path.push(c),
+                }
+            }
+            if !path.is_empty() {
+                self.files.insert(PathBuf::from(path));
+            }
+        }
+    }
+}
+
+/// Target directory cargo builds `workspace_root` into
+pub fn target_dir(workspace_root: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(|| workspace_root.join("target"), |dir| workspace_root.join(dir))
+}
+
+/// Resolve `.` and `..` in `path` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Hash every step input under `workspace_root`
+///
+/// Files are hashed in path order together with their workspace-relative
+/// paths, so renames and deletions change the hash as well as edits. Files
+/// named in `dep_info` count as inputs when they lie in the workspace outside
+/// the target directory; the ones that no longer exist are hashed as
+/// missing.
+pub fn hash_sources(workspace_root: &Path, dep_info: &DepInfo) -> BuildResult<String> {
+    let mut files = BTreeSet::new();
+    let walker = WalkDir::new(workspace_root).into_iter().filter_entry(|entry| {
+        !(entry.file_type().is_dir()
+            && entry.depth() > 0
+            && entry.file_name().to_str().is_some_and(|name| SKIPPED_DIRS.contains(&name)))
+    });
+
+    for entry in walker {
+        let entry = entry
+            .map_err(|e| BuildError::Tool(format!("Failed to scan workspace sources: {}", e)))?;
+        let is_input = entry.file_type().is_file()
+            && entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| INPUT_EXTENSIONS.contains(&ext));
+        if is_input {
+            files.insert(normalize(entry.path()));
+        }
+    }
+
+    let root = normalize(workspace_root);
+    let target = normalize(&target_dir(workspace_root));
+    files.extend(
+        dep_info
+            .files
+            .iter()
+            .map(|file| normalize(&workspace_root.join(file)))
+            .filter(|file| file.starts_with(&root) && !file.starts_with(&target)),
+    );
+
+    let mut context = md5::Context::new();
+    for file in &files {
+        let relative = file.strip_prefix(&root).unwrap_or(file);
+        context.consume(relative.to_string_lossy().as_bytes());
+        context.consume(b"\0");
+        match fs::read(file) {
+            Ok(content) => context.consume(md5::compute(&content).0),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => context.consume(b"missing"),
+            Err(e) => {
+                return Err(BuildError::Tool(format!("Failed to read {}: {}", file.display(), e)));
+            },
+        }
+    }
+
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// Hash the build environment: the variables in [`BUILD_ENV_VARS`] and the
+/// ones the compiled code reads according to `dep_info`
+pub fn hash_environment(dep_info: &DepInfo) -> String {
+    hash_environment_with(dep_info, |name| std::env::var_os(name))
+}
+
+fn hash_environment_with(
+    dep_info: &DepInfo,
+    lookup: impl Fn(&str) -> Option<std::ffi::OsString>,
+) -> String {
+    let names: BTreeSet<&str> = BUILD_ENV_VARS
+        .iter()
+        .copied()
+        .chain(dep_info.env.iter().map(String::as_str))
+        .collect();
+
+    let mut context = md5::Context::new();
+    for name in names {
+        context.consume(name.as_bytes());
+        match lookup(name) {
+            Some(value) => {
+                context.consume(b"=");
+                context.consume(value.as_encoded_bytes());
+            },
+            None => context.consume(b" unset"),
+        }
+        context.consume(b"\0");
+    }
+    format!("{:x}", context.compute())
+}
+
+/// Identify the Rust toolchain used in `workspace_root`
+///
+/// Queries the compiler cargo would use: `$RUSTC` if set, otherwise the
+/// `rustc` selected for the workspace.
+pub fn toolchain_id(workspace_root: &Path) -> BuildResult<String> {
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = Command::new(rustc)
+        .arg("-vV")
+        .current_dir(workspace_root)
+        .output()
+        .map_err(|e| BuildError::Tool(format!("Failed to query rustc version: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BuildError::Tool(format!(
+            "rustc -vV failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Combine the inputs of a step into its fingerprint
+pub fn fingerprint(parts: &[&str]) -> String {
+    let mut context = md5::Context::new();
+    for part in parts {
+        context.consume(part.as_bytes());
+        context.consume(b"\0");
+    }
+    format!("{:x}", context.compute())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_step_cache_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let cache_path = temp_dir.path().join("steps.json");
+
+        let mut cache = StepCache::new();
+        cache.record("build", "abc", &vec![1u32, 2])?;
+        cache.save(&cache_path)?;
+
+        let cache = StepCache::load(&cache_path)?;
+        assert_eq!(cache.lookup::<Vec<u32>>("build", "abc"), Some(vec![1, 2]));
+        assert_eq!(cache.lookup::<Vec<u32>>("build", "def"), None);
+        assert_eq!(cache.lookup::<Vec<u32>>("test", "abc"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_hash_ignores_docs() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("target"))?;
+        fs::write(root.join("Cargo.toml"), "[workspace]\n")?;
+        fs::write(root.join("src/lib.rs"), "pub fn f() {}")?;
+
+        let initial = hash_sources(root, &DepInfo::default())?;
+
+        fs::write(root.join("README.md"), "# Docs")?;
+        fs::write(root.join("target/out.rs"), "generated")?;
+        assert_eq!(hash_sources(root, &DepInfo::default())?, initial);
+
+        fs::write(root.join("src/lib.rs"), "pub fn g() {}")?;
+        assert_ne!(hash_sources(root, &DepInfo::default())?, initial);
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_hash_covers_dep_info_inputs() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/templates dir"))?;
+        fs::create_dir_all(root.join("target/debug/deps"))?;
+        fs::create_dir_all(root.join("target/debug/incremental"))?;
+        fs::write(root.join("src/lib.rs"), "include_str!(\"templates dir/style.css\");")?;
+        fs::write(root.join("src/templates dir/style.css"), "body {}")?;
+        fs::write(root.join("README.md"), "# Docs")?;
+        fs::write(
+            root.join("target/debug/deps/lib-0123.d"),
+            "/out/lib.rlib: src/lib.rs src/templates\\ dir/style.css src/../README.md\n\n\
+             src/lib.rs:\n\n# env-dep:APP_NAME=demo\n",
+        )?;
+        fs::write(root.join("target/debug/incremental/stale.d"), "x: src/ignored.txt\n")?;
+
+        let dep_info = DepInfo::scan(&root.join("target"))?;
+        assert_eq!(
+            dep_info.files,
+            BTreeSet::from([
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("src/templates dir/style.css"),
+                PathBuf::from("src/../README.md"),
+            ])
+        );
+        assert_eq!(dep_info.env, BTreeSet::from(["APP_NAME".to_string()]));
+
+        // Included files count as inputs whatever their extension
+        let initial = hash_sources(root, &dep_info)?;
+        fs::write(root.join("src/templates dir/style.css"), "body { color: red }")?;
+        let restyled = hash_sources(root, &dep_info)?;
+        assert_ne!(restyled, initial);
+        fs::write(root.join("README.md"), "# Changed docs")?;
+        assert_ne!(hash_sources(root, &dep_info)?, restyled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_environment_hash_covers_build_env() {
+        let dep_info = DepInfo {
+            files: BTreeSet::new(),
+            env:   BTreeSet::from(["APP_NAME".to_string()]),
+        };
+        let hash = |vars: &[(&str, &str)]| {
+            let vars: BTreeMap<String, String> =
+                vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+            hash_environment_with(&dep_info, |name| vars.get(name).map(Into::into))
+        };
+
+        let initial = hash(&[]);
+        assert_eq!(hash(&[("UNRELATED", "1")]), initial);
+        assert_ne!(hash(&[("RUSTFLAGS", "-C target-cpu=native")]), initial);
+        assert_ne!(hash(&[("RUSTUP_TOOLCHAIN", "nightly")]), initial);
+        assert_ne!(hash(&[("APP_NAME", "demo")]), initial);
+        assert_ne!(hash(&[("RUSTFLAGS", "")]), initial);
+    }
+
+    #[test]
+    fn test_unchanged_steps_are_skipped() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = []\n")?;
+        fs::write(root.join("src/lib.rs"), "pub fn f() {}")?;
+
+        let mut build_system = crate::BuildSystem::new(root.to_path_buf())?;
+        let mut config = build_system.build_config().clone();
+        config.step_cache = true;
+        build_system.set_config(config);
+
+        let runs = std::cell::Cell::new(0u32);
+        let step = |build_system: &crate::BuildSystem, succeeded: bool| {
+            build_system.run_cached_step(
+                "build",
+                "",
+                || {
+                    runs.set(runs.get() + 1);
+                    Ok(succeeded)
+                },
+                |ok| *ok,
+            )
+        };
+
+        step(&build_system, true)?;
+        step(&build_system, true)?;
+        fs::write(root.join("README.md"), "# Docs")?;
+        step(&build_system, true)?;
+        assert_eq!(runs.get(), 1);
+
+        fs::write(root.join("src/lib.rs"), "pub fn g() {}")?;
+        assert!(!step(&build_system, false)?);
+        assert!(!step(&build_system, false)?);
+        assert_eq!(runs.get(), 3);
+
+        build_system.add_feature("std".to_string());
+        step(&build_system, true)?;
+        step(&build_system, true)?;
+        assert_eq!(runs.get(), 4);
+        assert_eq!(build_system.step_cache()?.len(), 1);
+
+        build_system.clear_step_cache()?;
+        assert!(build_system.step_cache()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_separates_parts() {
+        assert_ne!(fingerprint(&["ab", "c"]), fingerprint(&["a", "bc"]));
+        assert_eq!(fingerprint(&["a", "b"]), fingerprint(&["a", "b"]));
+    }
+}
@@ -27,6 +27,9 @@ pub struct BuildConfig {
     pub dry_run: bool,
     /// Trace all external commands being executed
     pub trace_commands: bool,
+    /// Skip build, test and verify steps whose inputs are unchanged
+    #[serde(default)]
+    pub step_cache: bool,
 }
 
 /// Build profiles available
@@ -58,6 +61,7 @@ fn default() -> Self {
             format_check: true,
             dry_run: false,
             trace_commands: false,
+            step_cache: false,
         }
     }
 }
@@ -0,0 +1,548 @@
+//! Coverage snapshots and baseline comparison
+//!
+//! A [`CoverageSnapshot`] holds per-file line and function coverage read from
+//! an `llvm-cov export` JSON document, as written by
+//! `cargo llvm-cov --json`. Comparing a snapshot with a baseline, usually the
+//! one recorded on `main`, gives per-crate and per-file deltas. Files of
+//! safety-tagged modules may not lose more coverage than a threshold; each
+//! such regression becomes an error diagnostic naming the file, so CI can
+//! annotate it.
+//!
+//! A file is safety-tagged if its module documentation mentions an ASIL
+//! level, if it carries a `SW-REQ-ID` annotation, or if it lies under one of
+//! the configured safety paths.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    build::BuildSystem,
+    diagnostics::{Diagnostic, Range, Severity},
+    error::{BuildError, BuildResult},
+};
+
+/// Coverage loss, in percentage points, tolerated for safety-tagged files
+pub const DEFAULT_MAX_REGRESSION: f64 = 0.5;
+
+/// Line and function coverage of one file or crate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageCounts {
+    /// Instrumented lines
+    pub lines: u64,
+    /// Lines executed by tests
+    pub covered_lines: u64,
+    /// Instrumented functions
+    pub functions: u64,
+    /// Functions executed by tests
+    pub covered_functions: u64,
+}
+
+impl CoverageCounts {
+    /// Line coverage in percent; files without instrumented lines count as
+    /// fully covered
+    pub fn line_percent(&self) -> f64 {
+        if self.lines == 0 {
+            100.0
+        } else {
+            self.covered_lines as f64 * 100.0 / self.lines as f64
+        }
+    }
+
+    fn add(&mut self, other: &CoverageCounts) {
+        self.lines += other.lines;
+        self.covered_lines += other.covered_lines;
+        self.functions += other.functions;
+        self.covered_functions += other.covered_functions;
+    }
+}
+
+/// Per-file coverage of a workspace
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageSnapshot {
+    /// Coverage by file path relative to the workspace root
+    pub files: BTreeMap<String, CoverageCounts>,
+}
+
+impl CoverageSnapshot {
+    /// Load an `llvm-cov export` JSON document
+    pub fn load(path: &Path, workspace_root: &Path) -> BuildResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            BuildError::Tool(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+            BuildError::Tool(format!("Failed to parse {}: {}", path.display(), e))
+        })?;
+        Self::from_llvm_cov(&json, workspace_root)
+    }
+
+    /// Read the file summaries of an `llvm-cov export` JSON document
+    ///
+    /// Files outside the workspace, such as dependencies and the standard
+    /// library, are ignored.
+    pub fn from_llvm_cov(json: &serde_json::Value, workspace_root: &Path) -> BuildResult<Self> {
+        let data = json["data"].as_array().ok_or_else(|| {
+            BuildError::Tool("Coverage report is not an llvm-cov JSON export".to_string())
+        })?;
+
+        let mut files = BTreeMap::new();
+        for file in data.iter().filter_map(|d| d["files"].as_array()).flatten() {
+            let Some(filename) = file["filename"].as_str() else {
+                continue;
+            };
+            let path = Path::new(filename);
+            let relative = if path.is_absolute() {
+                match path.strip_prefix(workspace_root) {
+                    Ok(relative) => relative,
+                    Err(_) => continue,
+                }
+            } else {
+                path
+            };
+
+            let summary = &file["summary"];
+            let count = |kind: &str, field: &str| summary[kind][field].as_u64().unwrap_or(0);
+            files.insert(
+                relative.to_string_lossy().replace('\\', "/"),
+                CoverageCounts {
+                    lines: count("lines", "count"),
+                    covered_lines: count("lines", "covered"),
+                    functions: count("functions", "count"),
+                    covered_functions: count("functions", "covered"),
+                },
+            );
+        }
+        Ok(Self { files })
+    }
+
+    /// Coverage summed per crate, named after the file's top-level directory
+    pub fn crates(&self) -> BTreeMap<String, CoverageCounts> {
+        let mut crates: BTreeMap<String, CoverageCounts> = BTreeMap::new();
+        for (file, counts) in &self.files {
+            crates.entry(crate_of(file).to_string()).or_default().add(counts);
+        }
+        crates
+    }
+
+    /// Coverage of the whole workspace
+    pub fn total(&self) -> CoverageCounts {
+        let mut total = CoverageCounts::default();
+        for counts in self.files.values() {
+            total.add(counts);
+        }
+        total
+    }
+}
+
+/// Crate a workspace-relative file belongs to
+fn crate_of(file: &str) -> &str {
+    file.split('/').next().unwrap_or(file)
+}
+
+/// Options for comparing coverage with a baseline
+#[derive(Debug, Clone)]
+pub struct CoverageDiffOptions {
+    /// Coverage loss, in percentage points, tolerated for safety-tagged files
+    pub max_regression: f64,
+    /// Workspace-relative path prefixes whose files are safety-tagged
+    pub safety_paths: Vec<String>,
+}
+
+impl Default for CoverageDiffOptions {
+    fn default() -> Self {
+        Self {
+            max_regression: DEFAULT_MAX_REGRESSION,
+            safety_paths: Vec::new(),
+        }
+    }
+}
+
+/// Line coverage of one crate or file in the baseline and now
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoverageChange {
+    /// Crate name or workspace-relative file path
+    pub name: String,
+    /// Line coverage in the baseline, if the item existed
+    pub baseline: Option<f64>,
+    /// Line coverage now, if the item still exists
+    pub current: Option<f64>,
+    /// Whether the file belongs to a safety-tagged module
+    pub safety_tagged: bool,
+}
+
+impl CoverageChange {
+    /// Change in percentage points; zero unless the item exists in both
+    pub fn delta(&self) -> f64 {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) => current - baseline,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Coverage changes against a baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageDiff {
+    /// Workspace line coverage in the baseline
+    pub baseline_total: f64,
+    /// Workspace line coverage now
+    pub current_total: f64,
+    /// Per-crate changes
+    pub crates: Vec<CoverageChange>,
+    /// Per-file changes, only for files whose coverage changed
+    pub files: Vec<CoverageChange>,
+    /// Safety-tagged files that lost more coverage than allowed
+    pub regressions: Vec<CoverageChange>,
+    /// Coverage loss tolerated for safety-tagged files
+    pub max_regression: f64,
+}
+
+impl CoverageDiff {
+    /// Compare `current` with `baseline`
+    ///
+    /// `is_safety_tagged` decides, for workspace-relative file paths not
+    /// covered by the configured safety paths, whether they belong to a
+    /// safety-tagged module.
+    pub fn compute(
+        baseline: &CoverageSnapshot,
+        current: &CoverageSnapshot,
+        options: &CoverageDiffOptions,
+        is_safety_tagged: impl Fn(&str) -> bool,
+    ) -> Self {
+        let change = |name: &str,
+                      before: Option<&CoverageCounts>,
+                      after: Option<&CoverageCounts>,
+                      safety_tagged| CoverageChange {
+            name: name.to_string(),
+            baseline: before.map(CoverageCounts::line_percent),
+            current: after.map(CoverageCounts::line_percent),
+            safety_tagged,
+        };
+
+        let (crates_before, crates_after) = (baseline.crates(), current.crates());
+        let crate_names: BTreeSet<&String> = crates_before.keys().chain(crates_after.keys()).collect();
+        let crates = crate_names
+            .into_iter()
+            .map(|name| change(name, crates_before.get(name), crates_after.get(name), false))
+            .collect();
+
+        let file_names: BTreeSet<&String> =
+            baseline.files.keys().chain(current.files.keys()).collect();
+        let mut files = Vec::new();
+        let mut regressions = Vec::new();
+        for name in file_names {
+            let (before, after) = (baseline.files.get(name), current.files.get(name));
+            if before == after {
+                continue;
+            }
+            let safety_tagged = options.safety_paths.iter().any(|p| name.starts_with(p.as_str()))
+                || is_safety_tagged(name);
+            let file = change(name, before, after, safety_tagged);
+            if safety_tagged && -file.delta() > options.max_regression {
+                regressions.push(file.clone());
+            }
+            files.push(file);
+        }
+
+        Self {
+            baseline_total: baseline.total().line_percent(),
+            current_total: current.total().line_percent(),
+            crates,
+            files,
+            regressions,
+            max_regression: options.max_regression,
+        }
+    }
+
+    /// Whether no safety-tagged file regressed beyond the threshold
+    pub fn passed(&self) -> bool {
+        self.regressions.is_empty()
+    }
+
+    /// Diagnostics for CI annotation: an error per regressed safety-tagged
+    /// file and a warning per other file that lost coverage
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![Diagnostic::new(
+            "<coverage>".to_string(),
+            Range::entire_line(0),
+            Severity::Info,
+            format!(
+                "Line coverage {:.2}% ({:+.2} against baseline)",
+                self.current_total,
+                self.current_total - self.baseline_total
+            ),
+            "coverage".to_string(),
+        )
+        .with_code("COV010".to_string())];
+
+        for file in self.files.iter().filter(|f| f.delta() < 0.0) {
+            let regressed = self.regressions.iter().any(|r| r.name == file.name);
+            let (severity, code) = if regressed {
+                (Severity::Error, "COV011")
+            } else {
+                (Severity::Warning, "COV012")
+            };
+            let mut message = format!(
+                "Line coverage dropped from {:.2}% to {:.2}%",
+                file.baseline.unwrap_or_default(),
+                file.current.unwrap_or_default()
+            );
+            if regressed {
+                message.push_str(&format!(
+                    " in a safety-tagged module (at most {:.2} points allowed)",
+                    self.max_regression
+                ));
+            }
+            diagnostics.push(
+                Diagnostic::new(
+                    file.name.clone(),
+                    Range::entire_line(0),
+                    severity,
+                    message,
+                    "coverage".to_string(),
+                )
+                .with_code(code.to_string()),
+            );
+        }
+        diagnostics
+    }
+
+    /// Markdown report with per-crate and per-file deltas
+    pub fn to_markdown(&self) -> String {
+        let percent = |value: Option<f64>| match value {
+            Some(value) => format!("{:.2}%", value),
+            None => "-".to_string(),
+        };
+
+        let mut report = String::from("# Coverage Delta\n\n");
+        report.push_str(&format!(
+            "**Total**: {:.2}% -> {:.2}% ({:+.2})\n\n",
+            self.baseline_total,
+            self.current_total,
+            self.current_total - self.baseline_total
+        ));
+
+        if self.regressions.is_empty() {
+            report.push_str("No safety-tagged module regressed.\n\n");
+        } else {
+            report.push_str(&format!(
+                "**Safety-tagged regressions** (more than {:.2} points):\n",
+                self.max_regression
+            ));
+            for file in &self.regressions {
+                report.push_str(&format!(
+                    "- {}: {} -> {}\n",
+                    file.name,
+                    percent(file.baseline),
+                    percent(file.current)
+                ));
+            }
+            report.push('\n');
+        }
+
+        report.push_str("## Crates\n\n| Crate | Baseline | Current | Delta |\n|---|---|---|---|\n");
+        for krate in &self.crates {
+            report.push_str(&format!(
+                "| {} | {} | {} | {:+.2} |\n",
+                krate.name,
+                percent(krate.baseline),
+                percent(krate.current),
+                krate.delta()
+            ));
+        }
+
+        if !self.files.is_empty() {
+            report.push_str(
+                "\n## Changed Files\n\n| File | Safety | Baseline | Current | Delta |\n\
+                 |---|---|---|---|---|\n",
+            );
+            for file in &self.files {
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} | {:+.2} |\n",
+                    file.name,
+                    if file.safety_tagged { "yes" } else { "" },
+                    percent(file.baseline),
+                    percent(file.current),
+                    file.delta()
+                ));
+            }
+        }
+        report
+    }
+}
+
+/// Whether a source file belongs to a safety-tagged module
+///
+/// Reads the file's module documentation for an ASIL level and the whole
+/// file for a `SW-REQ-ID` annotation.
+pub fn is_safety_tagged_source(source: &str) -> bool {
+    source.contains("SW-REQ-ID")
+        || source
+            .lines()
+            .map(str::trim_start)
+            .take_while(|line| line.starts_with("//!") || line.is_empty())
+            .any(|line| line.contains("ASIL"))
+}
+
+impl BuildSystem {
+    /// Measure coverage with `cargo llvm-cov`, writing the export to `output`
+    pub fn coverage_snapshot(&self, output: &Path) -> BuildResult<CoverageSnapshot> {
+        println!("{} Measuring coverage with cargo llvm-cov...", "📊".bright_blue());
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                BuildError::Tool(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+
+        let result = Command::new("cargo")
+            .args(["llvm-cov", "--workspace", "--json", "--summary-only", "--output-path"])
+            .arg(output)
+            .current_dir(&self.workspace.root)
+            .output()
+            .map_err(|e| BuildError::Tool(format!("Failed to run cargo llvm-cov: {}", e)))?;
+        if !result.status.success() {
+            return Err(BuildError::Tool(format!(
+                "cargo llvm-cov failed (install with: cargo install cargo-llvm-cov): {}",
+                String::from_utf8_lossy(&result.stderr)
+            )));
+        }
+        CoverageSnapshot::load(output, &self.workspace.root)
+    }
+
+    /// Compare coverage with a baseline
+    ///
+    /// Uses `current` if given, otherwise measures coverage into
+    /// `target/coverage/coverage.json`.
+    pub fn coverage_diff(
+        &self,
+        baseline: &Path,
+        current: Option<&Path>,
+        options: &CoverageDiffOptions,
+    ) -> BuildResult<CoverageDiff> {
+        let root = &self.workspace.root;
+        let baseline = CoverageSnapshot::load(baseline, root)?;
+        let current = match current {
+            Some(path) => CoverageSnapshot::load(path, root)?,
+            None => self.coverage_snapshot(&root.join("target/coverage/coverage.json"))?,
+        };
+
+        Ok(CoverageDiff::compute(&baseline, &current, options, |file| {
+            fs::read_to_string(root.join(file))
+                .map(|source| is_safety_tagged_source(&source))
+                .unwrap_or(false)
+        }))
+    }
+}
+
+/// Write the markdown and JSON coverage delta reports to `output_dir`
+pub fn write_coverage_diff(diff: &CoverageDiff, output_dir: &Path) -> BuildResult<PathBuf> {
+    fs::create_dir_all(output_dir).map_err(|e| {
+        BuildError::Tool(format!("Failed to create {}: {}", output_dir.display(), e))
+    })?;
+    let json = serde_json::to_string_pretty(diff)
+        .map_err(|e| BuildError::Tool(format!("Failed to serialize coverage delta: {}", e)))?;
+    let markdown = output_dir.join("coverage-delta.md");
+    for (path, contents) in [
+        (output_dir.join("coverage-delta.json"), json),
+        (markdown.clone(), diff.to_markdown()),
+    ] {
+        fs::write(&path, contents)
+            .map_err(|e| BuildError::Tool(format!("Failed to write {}: {}", path.display(), e)))?;
+    }
+    Ok(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(files: &[(&str, u64, u64)]) -> serde_json::Value {
+        let files: Vec<serde_json::Value> = files
+            .iter()
+            .map(|(name, count, covered)| {
+                serde_json::json!({
+                    "filename": name,
+                    "summary": {
+                        "lines": { "count": count, "covered": covered },
+                        "functions": { "count": 1, "covered": 1 }
+                    }
+                })
+            })
+            .collect();
+        serde_json::json!({ "type": "llvm.coverage.json.export", "data": [{ "files": files }] })
+    }
+
+    #[test]
+    fn test_snapshot_from_llvm_cov() {
+        let json = export(&[
+            ("/ws/wrt-runtime/src/memory.rs", 100, 80),
+            ("/ws/wrt-runtime/src/table.rs", 100, 40),
+            ("/home/user/.cargo/registry/src/dep/lib.rs", 10, 0),
+        ]);
+        let snapshot = CoverageSnapshot::from_llvm_cov(&json, Path::new("/ws")).unwrap();
+
+        assert_eq!(snapshot.files.len(), 2);
+        assert_eq!(snapshot.files["wrt-runtime/src/memory.rs"].covered_lines, 80);
+        assert_eq!(snapshot.crates()["wrt-runtime"].line_percent(), 60.0);
+        assert!(CoverageSnapshot::from_llvm_cov(&serde_json::json!({}), Path::new("/ws")).is_err());
+    }
+
+    #[test]
+    fn test_safety_tagged_regression_fails() {
+        let root = Path::new("/ws");
+        let baseline = CoverageSnapshot::from_llvm_cov(
+            &export(&[
+                ("/ws/wrt-runtime/src/memory.rs", 100, 90),
+                ("/ws/wrt-host/src/host.rs", 100, 50),
+                ("/ws/wrt-host/src/proxy.rs", 100, 50),
+            ]),
+            root,
+        )
+        .unwrap();
+        let current = CoverageSnapshot::from_llvm_cov(
+            &export(&[
+                ("/ws/wrt-runtime/src/memory.rs", 100, 80),
+                ("/ws/wrt-host/src/host.rs", 100, 60),
+                ("/ws/wrt-host/src/proxy.rs", 100, 40),
+            ]),
+            root,
+        )
+        .unwrap();
+
+        let options = CoverageDiffOptions::default();
+        let diff = CoverageDiff::compute(&baseline, &current, &options, |file| {
+            file.ends_with("memory.rs")
+        });
+        assert!(!diff.passed());
+        assert_eq!(diff.regressions.len(), 1);
+        assert_eq!(diff.regressions[0].name, "wrt-runtime/src/memory.rs");
+        assert_eq!(diff.crates.iter().find(|c| c.name == "wrt-host").unwrap().delta(), 0.0);
+
+        let diagnostics = diff.to_diagnostics();
+        let errors: Vec<_> =
+            diagnostics.iter().filter(|d| matches!(d.severity, Severity::Error)).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file, "wrt-runtime/src/memory.rs");
+        assert!(diagnostics.iter().any(|d| d.file == "wrt-host/src/proxy.rs"));
+        assert!(diff.to_markdown().contains("| wrt-runtime | 90.00% | 80.00% | -10.00 |"));
+
+        let lenient = CoverageDiffOptions {
+            max_regression: 20.0,
+            ..CoverageDiffOptions::default()
+        };
+        assert!(CoverageDiff::compute(&baseline, &current, &lenient, |_| true).passed());
+    }
+
+    #[test]
+    fn test_safety_tag_detection() {
+        assert!(is_safety_tagged_source("//! Memory for ASIL-D builds\n\nfn f() {}\n"));
+        assert!(is_safety_tagged_source("fn f() {}\n// SW-REQ-ID: REQ_MEM_001\n"));
+        assert!(!is_safety_tagged_source("//! Helpers\n\nfn f() {} // not ASIL\n"));
+    }
+}
@@ -57,6 +57,12 @@ fn from(err: std::io::Error) -> Self {
     }
 }
 
+impl From<wrt_format::wit_parser::WitError> for BuildError {
+    fn from(err: wrt_format::wit_parser::WitError) -> Self {
+        BuildError::Verification(err.to_string())
+    }
+}
+
 impl From<anyhow::Error> for BuildError {
     fn from(err: anyhow::Error) -> Self {
         BuildError::Other(err)
@@ -0,0 +1,941 @@
+//! Workspace audit of unsafe code and foreign function boundaries
+//!
+//! The scanner enumerates every site an assessor has to review:
+//! - `unsafe` blocks, functions, impls and traits
+//! - `extern` blocks and functions with a foreign ABI
+//! - platform syscall wrappers, i.e. functions issuing inline assembly,
+//!   `libc` calls or raw `syscall`s
+//!
+//! Each site is cross-referenced with its justification: a `SAFETY:` comment
+//! on or just above it, a `# Safety` doc section for unsafe
+//! functions and traits, or an entry in `allowed-unsafe.toml`.
+//!
+//! Unjustified unsafe blocks are compared against an accepted baseline so the
+//! audit fails only for blocks introduced after the baseline was written.
+//! Sites are matched by file, enclosing item and code rather than line, so
+//! unrelated edits moving a block do not make it new.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    build::BuildSystem,
+    error::{BuildError, BuildResult},
+    verify::AllowedUnsafeConfig,
+};
+
+/// Default baseline file in the workspace root
+pub const BASELINE_FILE: &str = "ffi-audit-baseline.json";
+
+/// Directories that are never scanned
+const SKIPPED_DIRS: &[&str] = &["target", ".git", "node_modules"];
+
+/// Lines above a site searched for a `SAFETY:` comment
+const JUSTIFICATION_WINDOW: usize = 5;
+
+/// Markers introducing a justification comment
+const SAFETY_MARKERS: &[&str] = &["SAFETY:", "Safety:"];
+
+/// Headings of a safety section in doc comments
+const SAFETY_HEADINGS: &[&str] = &["# Safety", "## Safety"];
+
+/// Kind of audited site
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteKind {
+    /// `unsafe { ... }` block
+    UnsafeBlock,
+    /// `unsafe fn` declaration
+    UnsafeFn,
+    /// `unsafe impl` of an unsafe trait
+    UnsafeImpl,
+    /// `unsafe trait` declaration
+    UnsafeTrait,
+    /// `extern` block importing foreign symbols
+    ExternBlock,
+    /// Function with a foreign ABI
+    ExternFn,
+    /// Function issuing inline assembly, `libc` calls or raw syscalls
+    SyscallWrapper,
+}
+
+impl SiteKind {
+    /// Whether the site declares or contains unsafe code
+    pub fn is_unsafe(self) -> bool {
+        matches!(
+            self,
+            Self::UnsafeBlock | Self::UnsafeFn | Self::UnsafeImpl | Self::UnsafeTrait
+        )
+    }
+}
+
+impl std::fmt::Display for SiteKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsafeBlock => write!(f, "unsafe block"),
+            Self::UnsafeFn => write!(f, "unsafe fn"),
+            Self::UnsafeImpl => write!(f, "unsafe impl"),
+            Self::UnsafeTrait => write!(f, "unsafe trait"),
+            Self::ExternBlock => write!(f, "extern block"),
+            Self::ExternFn => write!(f, "extern fn"),
+            Self::SyscallWrapper => write!(f, "syscall wrapper"),
+        }
+    }
+}
+
+/// Where a justification was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JustificationSource {
+    /// `SAFETY:` comment on or above the site
+    SafetyComment,
+    /// `# Safety` section in the item's doc comment
+    SafetyDoc,
+    /// Entry in `allowed-unsafe.toml`
+    Allowlist,
+}
+
+impl std::fmt::Display for JustificationSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SafetyComment => write!(f, "SAFETY comment"),
+            Self::SafetyDoc => write!(f, "# Safety doc"),
+            Self::Allowlist => write!(f, "allowlist"),
+        }
+    }
+}
+
+/// Justification of an audited site
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Justification {
+    /// Where the justification was found
+    pub source: JustificationSource,
+    /// Justification text
+    pub text: String,
+}
+
+/// Site an assessor has to review
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSite {
+    /// Kind of site
+    pub kind: SiteKind,
+    /// Crate the site belongs to
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// Workspace-relative path of the file
+    pub file: String,
+    /// Line of the site, starting at 1
+    pub line: usize,
+    /// Enclosing or declared item, if any
+    pub item: Option<String>,
+    /// Code of the site, trimmed to its line
+    pub code: String,
+    /// Justification, or `None` if the site is unjustified
+    pub justification: Option<Justification>,
+}
+
+impl AuditSite {
+    /// Whether the site has a justification
+    pub fn is_justified(&self) -> bool {
+        self.justification.is_some()
+    }
+
+    /// Line-independent identity used to match sites against the baseline
+    fn fingerprint(&self) -> (String, SiteKind, Option<String>, String) {
+        (
+            self.file.clone(),
+            self.kind,
+            self.item.clone(),
+            self.code.clone(),
+        )
+    }
+}
+
+/// Site counts of one crate
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateAuditSummary {
+    /// Crate name
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// `unsafe` blocks
+    pub unsafe_blocks: usize,
+    /// `unsafe` functions, impls and traits
+    pub unsafe_items: usize,
+    /// `extern` blocks and functions
+    pub extern_items: usize,
+    /// Syscall wrappers
+    pub syscall_wrappers: usize,
+    /// Sites without justification
+    pub unjustified: usize,
+}
+
+/// Site counts of the workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FfiAuditSummary {
+    /// Audited sites
+    pub total: usize,
+    /// Sites with a justification
+    pub justified: usize,
+    /// Sites without justification
+    pub unjustified: usize,
+    /// Unjustified unsafe blocks that are not in the baseline
+    pub new_unjustified_blocks: usize,
+}
+
+/// Accepted unjustified unsafe blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FfiAuditBaseline {
+    /// When the baseline was written
+    pub generated_at: String,
+    /// Unjustified unsafe blocks accepted at that time
+    pub accepted: Vec<AuditSite>,
+}
+
+impl FfiAuditBaseline {
+    /// Load a baseline written by [`FfiAuditReport::write_baseline`]
+    pub fn load(path: &Path) -> BuildResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            BuildError::Verification(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            BuildError::Verification(format!("Failed to parse {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Unsafe and FFI audit report of the workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiAuditReport {
+    /// When the report was generated
+    pub generated_at: String,
+    /// Baseline the unjustified blocks were compared against
+    pub baseline: Option<String>,
+    /// Audited sites, ordered by file and line
+    pub sites: Vec<AuditSite>,
+    /// Site counts per crate, ordered by crate name
+    pub crates: Vec<CrateAuditSummary>,
+    /// Site counts of the workspace
+    pub summary: FfiAuditSummary,
+    /// Unjustified unsafe blocks that are not in the baseline
+    pub new_unjustified: Vec<AuditSite>,
+}
+
+impl FfiAuditReport {
+    /// Build the report, comparing unjustified unsafe blocks with `baseline`
+    ///
+    /// Without a baseline every unjustified unsafe block is new.
+    pub fn build(mut sites: Vec<AuditSite>, baseline: Option<&FfiAuditBaseline>) -> Self {
+        sites.sort_by(|a, b| (&a.file, a.line, a.kind).cmp(&(&b.file, b.line, b.kind)));
+
+        // Identical blocks may occur several times in one item, so the
+        // baseline is matched as a multiset
+        let mut accepted: HashMap<_, usize> = HashMap::new();
+        for site in baseline.map(|baseline| baseline.accepted.as_slice()).unwrap_or_default() {
+            *accepted.entry(site.fingerprint()).or_default() += 1;
+        }
+
+        let mut crates: BTreeMap<String, CrateAuditSummary> = BTreeMap::new();
+        let mut summary = FfiAuditSummary::default();
+        let mut new_unjustified = Vec::new();
+        for site in &sites {
+            let counts =
+                crates.entry(site.crate_name.clone()).or_insert_with(|| CrateAuditSummary {
+                    crate_name: site.crate_name.clone(),
+                    ..Default::default()
+                });
+            match site.kind {
+                SiteKind::UnsafeBlock => counts.unsafe_blocks += 1,
+                SiteKind::UnsafeFn | SiteKind::UnsafeImpl | SiteKind::UnsafeTrait => {
+                    counts.unsafe_items += 1
+                },
+                SiteKind::ExternBlock | SiteKind::ExternFn => counts.extern_items += 1,
+                SiteKind::SyscallWrapper => counts.syscall_wrappers += 1,
+            }
+
+            summary.total += 1;
+            if site.is_justified() {
+                summary.justified += 1;
+                continue;
+            }
+            summary.unjustified += 1;
+            counts.unjustified += 1;
+
+            if site.kind == SiteKind::UnsafeBlock {
+                match accepted.get_mut(&site.fingerprint()) {
+                    Some(remaining) if *remaining > 0 => *remaining -= 1,
+                    _ => new_unjustified.push(site.clone()),
+                }
+            }
+        }
+        summary.new_unjustified_blocks = new_unjustified.len();
+
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            baseline: None,
+            sites,
+            crates: crates.into_values().collect(),
+            summary,
+            new_unjustified,
+        }
+    }
+
+    /// Whether no unjustified unsafe block was introduced since the baseline
+    pub fn passed(&self) -> bool {
+        self.new_unjustified.is_empty()
+    }
+
+    /// Sites without justification
+    pub fn unjustified(&self) -> impl Iterator<Item = &AuditSite> {
+        self.sites.iter().filter(|site| !site.is_justified())
+    }
+
+    /// Baseline accepting every unjustified unsafe block of this report
+    pub fn to_baseline(&self) -> FfiAuditBaseline {
+        FfiAuditBaseline {
+            generated_at: self.generated_at.clone(),
+            accepted: self
+                .unjustified()
+                .filter(|site| site.kind == SiteKind::UnsafeBlock)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Write the baseline accepting the current unjustified unsafe blocks
+    pub fn write_baseline(&self, path: &Path) -> BuildResult<()> {
+        let json = serde_json::to_string_pretty(&self.to_baseline()).map_err(|e| {
+            BuildError::Verification(format!("Failed to serialize FFI audit baseline: {}", e))
+        })?;
+        fs::write(path, json).map_err(|e| {
+            BuildError::Verification(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Render the report as JSON
+    pub fn to_json(&self) -> BuildResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            BuildError::Verification(format!("Failed to serialize FFI audit report: {}", e))
+        })
+    }
+
+    /// Render the audit table as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# WRT Unsafe and FFI Audit\n\n");
+        md.push_str(&format!("Generated: {}\n\n", self.generated_at));
+        if let Some(baseline) = &self.baseline {
+            md.push_str(&format!("Baseline: {}\n\n", baseline));
+        }
+
+        let summary = &self.summary;
+        md.push_str(&format!(
+            "{} sites: {} justified, {} unjustified, {} new unjustified unsafe blocks\n\n",
+            summary.total, summary.justified, summary.unjustified, summary.new_unjustified_blocks
+        ));
+
+        md.push_str("## Summary by Crate\n\n");
+        md.push_str(
+            "| Crate | Unsafe blocks | Unsafe items | Extern items | Syscall wrappers | \
+             Unjustified |\n",
+        );
+        md.push_str("|---|---|---|---|---|---|\n");
+        for counts in &self.crates {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                counts.crate_name,
+                counts.unsafe_blocks,
+                counts.unsafe_items,
+                counts.extern_items,
+                counts.syscall_wrappers,
+                counts.unjustified
+            ));
+        }
+
+        md.push_str("\n## Sites\n\n");
+        md.push_str("| Location | Kind | Item | Code | Justification |\n");
+        md.push_str("|---|---|---|---|---|\n");
+        for site in &self.sites {
+            let justification = match &site.justification {
+                Some(justification) => {
+                    format!(
+                        "{}: {}",
+                        justification.source,
+                        escape_markdown(&justification.text)
+                    )
+                },
+                None => "**missing**".to_string(),
+            };
+            md.push_str(&format!(
+                "| {}:{} | {} | {} | `{}` | {} |\n",
+                site.file,
+                site.line,
+                site.kind,
+                site.item.as_deref().map(escape_markdown).unwrap_or_default(),
+                site.code.replace('`', "'").replace('|', "\\|"),
+                justification
+            ));
+        }
+        md
+    }
+
+    /// Write `ffi-audit.json` and `ffi-audit.md` to `output_dir`
+    pub fn write(&self, output_dir: &Path) -> BuildResult<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| {
+            BuildError::Verification(format!("Failed to create {}: {}", output_dir.display(), e))
+        })?;
+
+        let outputs = [
+            (output_dir.join("ffi-audit.json"), self.to_json()?),
+            (output_dir.join("ffi-audit.md"), self.to_markdown()),
+        ];
+        for (path, content) in &outputs {
+            fs::write(path, content).map_err(|e| {
+                BuildError::Verification(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+        }
+        Ok(outputs.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// Options of an FFI audit run
+#[derive(Debug, Clone, Default)]
+pub struct FfiAuditOptions {
+    /// Baseline of accepted unjustified blocks, if any
+    pub baseline: Option<PathBuf>,
+    /// Allowed unsafe configuration justifying allowlisted sites
+    pub allowed_unsafe: Option<AllowedUnsafeConfig>,
+}
+
+impl BuildSystem {
+    /// Audit unsafe code and FFI boundaries across the workspace
+    pub fn ffi_audit(&self, options: &FfiAuditOptions) -> BuildResult<FfiAuditReport> {
+        let sites = scan_workspace(&self.workspace.root, options.allowed_unsafe.as_ref())?;
+
+        let baseline = match &options.baseline {
+            Some(path) if path.exists() => Some(FfiAuditBaseline::load(path)?),
+            _ => None,
+        };
+        let mut report = FfiAuditReport::build(sites, baseline.as_ref());
+        if baseline.is_some() {
+            report.baseline = options.baseline.as_ref().map(|path| path.display().to_string());
+        }
+        Ok(report)
+    }
+}
+
+/// Scan every Rust source under `workspace_root` for audited sites
+///
+/// Sites without a justification comment are justified by `allowed`, if
+/// given.
+pub fn scan_workspace(
+    workspace_root: &Path,
+    allowed: Option<&AllowedUnsafeConfig>,
+) -> BuildResult<Vec<AuditSite>> {
+    let walker = WalkDir::new(workspace_root).into_iter().filter_entry(|entry| {
+        !(entry.file_type().is_dir()
+            && entry.depth() > 0
+            && entry.file_name().to_str().is_some_and(|name| SKIPPED_DIRS.contains(&name)))
+    });
+
+    let mut sites = Vec::new();
+    for entry in walker {
+        let entry = entry
+            .map_err(|e| BuildError::Verification(format!("Failed to scan workspace: {}", e)))?;
+        if !entry.file_type().is_file()
+            || entry.path().extension().and_then(|ext| ext.to_str()) != Some("rs")
+        {
+            continue;
+        }
+
+        // Sources that are not UTF-8 are not compiled either
+        let Ok(source) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let relative = entry.path().strip_prefix(workspace_root).unwrap_or(entry.path());
+        let mut file_sites = scan_source(relative, &source);
+
+        if let Some(allowed) = allowed {
+            for site in file_sites.iter_mut().filter(|site| !site.is_justified()) {
+                if let Some(block) = allowed.is_allowed(relative, site.line) {
+                    site.justification = Some(Justification {
+                        source: JustificationSource::Allowlist,
+                        text: block.asil_justification.clone().unwrap_or(block.reason.clone()),
+                    });
+                }
+            }
+        }
+        sites.extend(file_sites);
+    }
+    Ok(sites)
+}
+
+/// Patterns recognizing audited sites in code with comments and string
+/// contents removed
+struct SitePatterns {
+    unsafe_block: Regex,
+    unsafe_fn: Regex,
+    unsafe_impl: Regex,
+    unsafe_trait: Regex,
+    extern_fn: Regex,
+    extern_block: Regex,
+    syscall: Regex,
+    fn_name: Regex,
+}
+
+impl SitePatterns {
+    fn new() -> Self {
+        let compile = |pattern: &str| Regex::new(pattern).expect("audit patterns are valid");
+        Self {
+            unsafe_block: compile(r"\bunsafe\s*(\{|$)"),
+            unsafe_fn: compile(r"\bunsafe\s+fn\s+([A-Za-z_]\w*)"),
+            unsafe_impl: compile(r"\bunsafe\s+impl\b"),
+            unsafe_trait: compile(r"\bunsafe\s+(?:auto\s+)?trait\s+([A-Za-z_]\w*)"),
+            extern_fn: compile(r#"\bextern\s*(?:"(\w*)")?\s*fn\s+([A-Za-z_]\w*)"#),
+            extern_block: compile(r#"\bextern\s*(?:"(\w*)")?\s*\{"#),
+            syscall: compile(
+                r"\b(?:(?:core|std)::arch::)?(?:global_)?asm!|\blibc::[a-z_][a-z0-9_]*\s*\(|\bsyscall\s*\(",
+            ),
+            fn_name: compile(r"\bfn\s+([A-Za-z_]\w*)"),
+        }
+    }
+}
+
+/// Find the audited sites in one source file
+///
+/// Justifications come from comments only; allowlisted sites are resolved by
+/// [`scan_workspace`].
+pub fn scan_source(path: &Path, source: &str) -> Vec<AuditSite> {
+    let patterns = SitePatterns::new();
+    let file = path.to_string_lossy().replace('\\', "/");
+    let crate_name = match path.components().next() {
+        Some(first) if path.components().count() > 1 => first.as_os_str().to_string_lossy().into(),
+        _ => "(workspace)".to_string(),
+    };
+    let lines: Vec<&str> = source.lines().collect();
+
+    let mut lexer = LexState::default();
+    let mut sites = Vec::new();
+    let mut current_fn: Option<String> = None;
+    // Syscall wrappers are reported once per function, pointing at the first
+    // call and listing every distinct call
+    let mut wrappers: HashMap<Option<String>, usize> = HashMap::new();
+
+    for (index, raw) in lines.iter().enumerate() {
+        let code = lexer.code(raw);
+        if code.trim().is_empty() {
+            continue;
+        }
+
+        let site = |kind: SiteKind, item: Option<String>| AuditSite {
+            kind,
+            crate_name: crate_name.clone(),
+            file: file.clone(),
+            line: index + 1,
+            item,
+            code: raw.trim().to_string(),
+            justification: justification(&lines, index, kind),
+        };
+
+        let mut declares_extern = false;
+        for captures in patterns.extern_fn.captures_iter(&code) {
+            if captures.get(1).is_some_and(|abi| abi.as_str() == "Rust") {
+                continue;
+            }
+            declares_extern = true;
+            sites.push(site(SiteKind::ExternFn, Some(captures[2].to_string())));
+        }
+        for captures in patterns.extern_block.captures_iter(&code) {
+            if captures.get(1).is_some_and(|abi| abi.as_str() == "Rust") {
+                continue;
+            }
+            sites.push(site(SiteKind::ExternBlock, None));
+        }
+        if !declares_extern {
+            for captures in patterns.unsafe_fn.captures_iter(&code) {
+                sites.push(site(SiteKind::UnsafeFn, Some(captures[1].to_string())));
+            }
+        }
+        for captures in patterns.unsafe_trait.captures_iter(&code) {
+            sites.push(site(SiteKind::UnsafeTrait, Some(captures[1].to_string())));
+        }
+        if patterns.unsafe_impl.is_match(&code) {
+            let item = code.trim().trim_end_matches(['{', '}', ' ']).trim_start_matches("unsafe ");
+            sites.push(site(SiteKind::UnsafeImpl, Some(item.to_string())));
+        }
+
+        if let Some(captures) = patterns.fn_name.captures(&code) {
+            current_fn = Some(captures[1].to_string());
+        }
+        for _ in patterns.unsafe_block.find_iter(&code) {
+            sites.push(site(SiteKind::UnsafeBlock, current_fn.clone()));
+        }
+
+        for call in patterns.syscall.find_iter(&code) {
+            let call = call.as_str().trim_end_matches(['(', ' ']).to_string();
+            match wrappers.get(&current_fn) {
+                Some(&wrapper) => {
+                    let existing: &mut AuditSite = &mut sites[wrapper];
+                    if !existing.code.split(", ").any(|c| c == call) {
+                        existing.code = format!("{}, {}", existing.code, call);
+                    }
+                },
+                None => {
+                    let mut wrapper = site(SiteKind::SyscallWrapper, current_fn.clone());
+                    wrapper.code = call;
+                    wrappers.insert(current_fn.clone(), sites.len());
+                    sites.push(wrapper);
+                },
+            }
+        }
+    }
+    sites
+}
+
+/// Justification of the site on line `index`
+fn justification(lines: &[&str], index: usize, kind: SiteKind) -> Option<Justification> {
+    if let Some(text) = safety_comment(lines[index]) {
+        return Some(Justification {
+            source: JustificationSource::SafetyComment,
+            text,
+        });
+    }
+
+    // The window ends at blank lines and closing braces so a comment does not
+    // justify sites in the next item
+    for offset in 1..=JUSTIFICATION_WINDOW.min(index) {
+        let line = lines[index - offset];
+        if line.trim().is_empty() || line.trim() == "}" {
+            break;
+        }
+        if let Some(text) = safety_comment(line) {
+            return Some(Justification {
+                source: JustificationSource::SafetyComment,
+                text,
+            });
+        }
+    }
+
+    // Doc comments of unsafe functions and traits may be arbitrarily long, so
+    // the whole comment and attribute block above the item is searched
+    if matches!(
+        kind,
+        SiteKind::UnsafeFn | SiteKind::UnsafeTrait | SiteKind::ExternFn
+    ) {
+        let mut line = index;
+        while line > 0 {
+            line -= 1;
+            let trimmed = lines[line].trim();
+            if !(trimmed.starts_with("//") || trimmed.starts_with("#[")) {
+                break;
+            }
+            if SAFETY_HEADINGS
+                .iter()
+                .any(|heading| trimmed.trim_start_matches('/').trim() == *heading)
+            {
+                let text = lines[line + 1..index]
+                    .iter()
+                    .map(|doc| doc.trim().trim_start_matches('/').trim())
+                    .skip_while(|doc| doc.is_empty())
+                    .take_while(|doc| !doc.is_empty() && !doc.starts_with('#'))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                return Some(Justification {
+                    source: JustificationSource::SafetyDoc,
+                    text,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Text of a `SAFETY:` comment on `line`
+fn safety_comment(line: &str) -> Option<String> {
+    let comment = &line[line.find("//")?..];
+    SAFETY_MARKERS.iter().find_map(|marker| {
+        comment
+            .find(marker)
+            .map(|start| comment[start + marker.len()..].trim().to_string())
+    })
+}
+
+/// Lexer state carried across lines
+#[derive(Debug, Default)]
+struct LexState {
+    /// Nesting depth of block comments
+    block_comment: usize,
+    /// Open string literal, with the number of `#`s of a raw string
+    string: Option<Option<usize>>,
+}
+
+impl LexState {
+    /// Code of `line` with comments and the contents of string and character
+    /// literals removed
+    ///
+    /// The ABI string of an `extern` declaration is kept so foreign and Rust
+    /// ABIs can be told apart.
+    fn code(&mut self, line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = String::new();
+        let mut keep_string = false;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+
+            if self.block_comment > 0 {
+                match (c, next) {
+                    ('*', Some('/')) => {
+                        self.block_comment -= 1;
+                        i += 2;
+                    },
+                    ('/', Some('*')) => {
+                        self.block_comment += 1;
+                        i += 2;
+                    },
+                    _ => i += 1,
+                }
+                continue;
+            }
+
+            if let Some(raw) = self.string {
+                match (c, raw) {
+                    ('\\', None) => i += 2,
+                    ('"', None) => {
+                        self.string = None;
+                        code.push('"');
+                        i += 1;
+                    },
+                    ('"', Some(hashes))
+                        if chars[i + 1..].iter().take(hashes).filter(|h| **h == '#').count()
+                            == hashes =>
+                    {
+                        self.string = None;
+                        code.push('"');
+                        i += 1 + hashes;
+                    },
+                    _ => {
+                        if keep_string {
+                            code.push(c);
+                        }
+                        i += 1;
+                    },
+                }
+                continue;
+            }
+
+            match (c, next) {
+                ('/', Some('/')) => break,
+                ('/', Some('*')) => {
+                    self.block_comment = 1;
+                    i += 2;
+                },
+                ('"', _) => {
+                    keep_string = code.trim_end().ends_with("extern");
+                    self.string = Some(None);
+                    code.push('"');
+                    i += 1;
+                },
+                ('r', Some('"' | '#'))
+                    if !chars[..i].last().is_some_and(|p| p.is_alphanumeric() || *p == '_') =>
+                {
+                    let hashes = chars[i + 1..].iter().take_while(|h| **h == '#').count();
+                    if chars.get(i + 1 + hashes) == Some(&'"') {
+                        keep_string = false;
+                        self.string = Some(Some(hashes));
+                        code.push('"');
+                        i += 2 + hashes;
+                    } else {
+                        code.push(c);
+                        i += 1;
+                    }
+                },
+                ('\'', _) => {
+                    // Character literals; lifetimes have no closing quote
+                    let end = match next {
+                        Some('\\') => {
+                            chars[i + 2..].iter().position(|q| *q == '\'').map(|p| p + i + 2)
+                        },
+                        Some(_) if chars.get(i + 2) == Some(&'\'') => Some(i + 2),
+                        _ => None,
+                    };
+                    match end {
+                        Some(end) => {
+                            code.push_str("' '");
+                            i = end + 1;
+                        },
+                        None => {
+                            code.push(c);
+                            i += 1;
+                        },
+                    }
+                },
+                _ => {
+                    code.push(c);
+                    i += 1;
+                },
+            }
+        }
+        code
+    }
+}
+
+/// Escape text for a Markdown table cell
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const SOURCE: &str = r#"use core::ptr;
+
+/// Reads a value
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads.
+pub unsafe fn read(ptr: *const u8) -> u8 {
+    // SAFETY: the caller guarantees `ptr` is valid
+    unsafe { ptr::read(ptr) }
+}
+
+fn write(ptr: *mut u8) {
+    unsafe { ptr::write(ptr, 0) }
+    let message = "unsafe { not code }"; // unsafe in a comment
+}
+
+unsafe extern "C" {
+    fn getpid() -> i32;
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wrt_entry() {}
+
+fn page_size() -> usize {
+    // SAFETY: sysconf has no preconditions
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    + unsafe { libc::getpagesize() } as usize
+}
+
+struct Handle;
+// SAFETY: the handle is only an index
+unsafe impl Send for Handle {}
+"#;
+
+    #[test]
+    fn test_sites_are_found_and_justified() {
+        let sites = scan_source(Path::new("wrt-platform/src/lib.rs"), SOURCE);
+
+        let summary: Vec<_> = sites
+            .iter()
+            .map(|site| {
+                (
+                    site.kind,
+                    site.line,
+                    site.item.as_deref(),
+                    site.is_justified(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SiteKind::UnsafeFn, 8, Some("read"), true),
+                (SiteKind::UnsafeBlock, 10, Some("read"), true),
+                (SiteKind::UnsafeBlock, 14, Some("write"), false),
+                (SiteKind::ExternBlock, 18, None, false),
+                (SiteKind::ExternFn, 23, Some("wrt_entry"), false),
+                (SiteKind::UnsafeBlock, 27, Some("page_size"), true),
+                (SiteKind::SyscallWrapper, 27, Some("page_size"), true),
+                (SiteKind::UnsafeBlock, 28, Some("page_size"), true),
+                (SiteKind::UnsafeImpl, 33, Some("impl Send for Handle"), true),
+            ]
+        );
+        assert_eq!(
+            sites[0].justification.as_ref().unwrap().source,
+            JustificationSource::SafetyDoc
+        );
+        assert_eq!(
+            sites[0].justification.as_ref().unwrap().text,
+            "`ptr` must be valid for reads."
+        );
+        assert_eq!(sites[6].code, "libc::sysconf, libc::getpagesize");
+        assert!(sites.iter().all(|site| site.crate_name == "wrt-platform"));
+    }
+
+    #[test]
+    fn test_lexer_removes_comments_and_strings() {
+        let mut lexer = LexState::default();
+        assert_eq!(
+            lexer.code(r#"let s = "unsafe {"; // unsafe {"#),
+            r#"let s = ""; "#
+        );
+        assert_eq!(
+            lexer.code(r##"let r = r#"a " unsafe"#;"##),
+            r#"let r = "";"#
+        );
+        assert_eq!(
+            lexer.code("let c = '\"'; unsafe { }"),
+            "let c = ' '; unsafe { }"
+        );
+        assert_eq!(lexer.code("/* unsafe {"), "");
+        assert_eq!(lexer.code("still */ fn f<'a>() {}"), " fn f<'a>() {}");
+        assert_eq!(
+            lexer.code(r#"extern "C" fn f() {}"#),
+            r#"extern "C" fn f() {}"#
+        );
+    }
+
+    #[test]
+    fn test_baseline_only_fails_new_blocks() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("wrt-sync/src"))?;
+        fs::create_dir_all(root.join("target"))?;
+        fs::write(
+            root.join("wrt-sync/src/lib.rs"),
+            "fn a() {\n    unsafe { f() }\n}\n",
+        )?;
+        fs::write(
+            root.join("target/generated.rs"),
+            "fn g() { unsafe { f() } }\n",
+        )?;
+
+        let report = FfiAuditReport::build(scan_workspace(root, None)?, None);
+        assert_eq!(report.sites.len(), 1);
+        assert!(!report.passed());
+        report.write_baseline(&root.join(BASELINE_FILE))?;
+
+        // Moving the accepted block does not make it new, a second one does
+        fs::write(
+            root.join("wrt-sync/src/lib.rs"),
+            "\nfn a() {\n    unsafe { f() }\n    unsafe { f() }\n}\n",
+        )?;
+        let baseline = FfiAuditBaseline::load(&root.join(BASELINE_FILE))?;
+        let report = FfiAuditReport::build(scan_workspace(root, None)?, Some(&baseline));
+        assert_eq!(report.summary.unjustified, 2);
+        assert_eq!(report.new_unjustified.len(), 1);
+        assert_eq!(report.new_unjustified[0].line, 4);
+
+        let allowed: AllowedUnsafeConfig = toml::from_str(
+            "[[allowed]]\nfile = \"wrt-sync/src/lib.rs\"\nline = 4\nreason = \"reviewed\"\n",
+        )?;
+        let report = FfiAuditReport::build(scan_workspace(root, Some(&allowed))?, Some(&baseline));
+        assert!(report.passed());
+        assert_eq!(report.crates[0].unsafe_blocks, 2);
+
+        let outputs = report.write(&root.join("out"))?;
+        assert!(fs::read_to_string(&outputs[1])?.contains("allowlist: reviewed"));
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+//! Declarative test fixtures
+//!
+//! A fixture is a TOML file describing a module, the WASI capabilities it is
+//! given and a sequence of calls with their expected results or traps:
+//!
+//! ```toml
+//! name = "arithmetic"
+//! preset = "qm"
+//! module = "arith.wat"
+//!
+//! [wasi]
+//! args = ["arith", "--verbose"]
+//! env = { MODE = "test" }
+//!
+//! [[call]]
+//! function = "add"
+//! args = ["i32:1", "i32:2"]
+//! expect = ["i32:3"]
+//!
+//! [[call]]
+//! function = "div"
+//! args = ["i32:1", "i32:0"]
+//! trap = "divide by zero"
+//! ```
+//!
+//! `module` is a `.wat` or `.wasm` path relative to the fixture file;
+//! small modules can be given inline as `wat` instead. Values are written as
+//! `type:value` with the types `i32`, `i64`, `f32` and `f64`. Calls run in
+//! order against one instance, so later calls see the state earlier ones
+//! left behind. Integration tests of host interfaces then become fixture
+//! files run by [`run_fixture`] or `cargo-wrt fixtures`, instead of bespoke
+//! Rust.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use wrt_foundation::values::{FloatBits32, FloatBits64, Value};
+use wrt_runtime::engine::{CapabilityAwareEngine, CapabilityEngine, EnginePreset};
+
+use crate::{
+    error::{BuildError, BuildResult},
+    wast_values::{is_expected_trap, values_equal},
+};
+
+/// File extension of fixture files
+pub const FIXTURE_EXTENSION: &str = "toml";
+
+/// WASI capabilities granted to a fixture's module
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WasiFixture {
+    /// Command-line arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// One call of a fixture and its expected outcome
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureCall {
+    /// Exported function to call
+    pub function: String,
+    /// Arguments as `type:value`
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Expected results as `type:value`; checked unless a trap is expected
+    #[serde(default)]
+    pub expect: Vec<String>,
+    /// Expected trap message; an empty string accepts any trap
+    #[serde(default)]
+    pub trap: Option<String>,
+}
+
+/// A fixture file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    /// Name shown in reports; defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    /// Engine preset: `qm`, `asil-a`, `asil-b`, `asil-c` or `asil-d`
+    #[serde(default = "default_preset")]
+    pub preset: String,
+    /// Path of the module, relative to the fixture file
+    #[serde(default)]
+    pub module: Option<PathBuf>,
+    /// Module as inline WAT
+    #[serde(default)]
+    pub wat: Option<String>,
+    /// WASI capabilities; WASI stays disabled without this table
+    #[serde(default)]
+    pub wasi: Option<WasiFixture>,
+    /// Calls in order
+    #[serde(default, rename = "call")]
+    pub calls: Vec<FixtureCall>,
+    /// Directory `module` is relative to
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+fn default_preset() -> String {
+    "qm".to_string()
+}
+
+impl Fixture {
+    /// Parse a fixture whose module path is relative to `base_dir`
+    pub fn parse(source: &str, base_dir: &Path) -> BuildResult<Self> {
+        let mut fixture: Self = toml::from_str(source)
+            .map_err(|e| BuildError::Config(format!("Invalid fixture: {}", e)))?;
+        if fixture.module.is_some() == fixture.wat.is_some() {
+            return Err(BuildError::Config(
+                "Fixture needs exactly one of `module` and `wat`".to_string(),
+            ));
+        }
+        fixture.base_dir = base_dir.to_path_buf();
+        Ok(fixture)
+    }
+
+    /// Load a fixture file
+    pub fn load(path: &Path) -> BuildResult<Self> {
+        let source = fs::read_to_string(path).map_err(|e| {
+            BuildError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut fixture = Self::parse(&source, base_dir)
+            .map_err(|e| BuildError::Config(format!("{}: {}", path.display(), e)))?;
+        if fixture.name.is_empty() {
+            fixture.name = path
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        }
+        Ok(fixture)
+    }
+
+    /// Binary of the fixture's module
+    pub fn module_binary(&self) -> BuildResult<Vec<u8>> {
+        if let Some(wat) = &self.wat {
+            return wat::parse_str(wat)
+                .map_err(|e| BuildError::Config(format!("Invalid inline WAT: {}", e)));
+        }
+        let path = self.base_dir.join(self.module.as_deref().unwrap_or(Path::new("")));
+        if path.extension().is_some_and(|ext| ext == "wat") {
+            wat::parse_file(&path)
+                .map_err(|e| BuildError::Config(format!("Invalid WAT in {}: {}", path.display(), e)))
+        } else {
+            fs::read(&path).map_err(|e| {
+                BuildError::Config(format!("Failed to read {}: {}", path.display(), e))
+            })
+        }
+    }
+
+    /// Engine preset of the fixture
+    pub fn engine_preset(&self) -> BuildResult<EnginePreset> {
+        match self.preset.to_ascii_lowercase().as_str() {
+            "qm" => Ok(EnginePreset::QM),
+            "asil-a" => Ok(EnginePreset::AsilA),
+            "asil-b" => Ok(EnginePreset::AsilB),
+            "asil-c" => Ok(EnginePreset::AsilC),
+            "asil-d" => Ok(EnginePreset::AsilD),
+            other => Err(BuildError::Config(format!("Unknown preset '{}'", other))),
+        }
+    }
+}
+
+/// Parse a `type:value` fixture value
+pub fn parse_value(text: &str) -> BuildResult<Value> {
+    let invalid = || BuildError::Config(format!("Invalid value '{}'", text));
+    let (ty, value) = text.split_once(':').ok_or_else(invalid)?;
+    let value = value.trim();
+    match ty.trim() {
+        "i32" => parse_int(value)
+            .and_then(|v| i32::try_from(v).ok().or_else(|| u32::try_from(v).ok().map(|v| v as i32)))
+            .map(Value::I32),
+        "i64" => parse_int(value)
+            .and_then(|v| i64::try_from(v).ok().or_else(|| u64::try_from(v).ok().map(|v| v as i64)))
+            .map(Value::I64),
+        "f32" => value.parse::<f32>().ok().map(|v| Value::F32(FloatBits32::from_float(v))),
+        "f64" => value.parse::<f64>().ok().map(|v| Value::F64(FloatBits64::from_float(v))),
+        _ => None,
+    }
+    .ok_or_else(invalid)
+}
+
+/// Parse a decimal or `0x` hexadecimal integer
+fn parse_int(text: &str) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let digits = digits.replace('_', "");
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i128>().ok()?,
+    };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+/// Outcome of one call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallReport {
+    /// Function called
+    pub function: String,
+    /// Whether the outcome matched the expectation
+    pub passed: bool,
+    /// What differed, for failed calls
+    pub message: Option<String>,
+}
+
+/// Outcome of a fixture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FixtureReport {
+    /// Fixture name
+    pub name: String,
+    /// Error that kept the fixture from running its calls
+    pub error: Option<String>,
+    /// Outcome of each call run
+    pub calls: Vec<CallReport>,
+}
+
+impl FixtureReport {
+    /// Whether the fixture ran and all calls matched
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.calls.iter().all(|call| call.passed)
+    }
+}
+
+impl fmt::Display for FixtureReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.passed() { "ok" } else { "FAILED" };
+        write!(f, "{}: {}", self.name, status)?;
+        if let Some(error) = &self.error {
+            write!(f, "\n  {}", error)?;
+        }
+        for call in self.calls.iter().filter(|call| !call.passed) {
+            write!(f, "\n  {}: {}", call.function, call.message.as_deref().unwrap_or(""))?;
+        }
+        Ok(())
+    }
+}
+
+/// Run a fixture's calls against a fresh instance of its module
+pub fn run_fixture(fixture: &Fixture) -> FixtureReport {
+    let mut report = FixtureReport {
+        name: fixture.name.clone(),
+        error: None,
+        calls: Vec::new(),
+    };
+    if let Err(e) = run_calls(fixture, &mut report.calls) {
+        report.error = Some(e.to_string());
+    }
+    report
+}
+
+fn run_calls(fixture: &Fixture, reports: &mut Vec<CallReport>) -> BuildResult<()> {
+    let binary = fixture.module_binary()?;
+    let mut engine = CapabilityAwareEngine::with_preset(fixture.engine_preset()?)
+        .map_err(|e| BuildError::Test(format!("Failed to create engine: {}", e)))?;
+
+    if let Some(wasi) = &fixture.wasi {
+        engine
+            .enable_wasi()
+            .map_err(|e| BuildError::Test(format!("WASI not available: {}", e)))?;
+        apply_wasi(&mut engine, wasi)?;
+    }
+
+    let module = engine
+        .load_module(&binary)
+        .map_err(|e| BuildError::Test(format!("Failed to load module: {}", e)))?;
+    let instance = engine
+        .instantiate(module)
+        .map_err(|e| BuildError::Test(format!("Failed to instantiate module: {}", e)))?;
+
+    for call in &fixture.calls {
+        let args = call.args.iter().map(|arg| parse_value(arg)).collect::<BuildResult<Vec<_>>>()?;
+        let result = engine.execute(instance, &call.function, &args);
+        let message = match (&call.trap, result) {
+            (Some(_), Ok(results)) => Some(format!("expected a trap, returned {:?}", results)),
+            (Some(expected), Err(e)) => (!expected.is_empty()
+                && !is_expected_trap(&e.to_string(), expected))
+            .then(|| format!("expected trap '{}', got '{}'", expected, e)),
+            (None, Err(e)) => Some(format!("trapped: {}", e)),
+            (None, Ok(results)) => {
+                let expected =
+                    call.expect.iter().map(|value| parse_value(value)).collect::<BuildResult<Vec<_>>>()?;
+                let matches = results.len() == expected.len()
+                    && results.iter().zip(&expected).all(|(actual, expected)| values_equal(actual, expected));
+                (!matches).then(|| format!("expected {:?}, returned {:?}", expected, results))
+            },
+        };
+        reports.push(CallReport {
+            function: call.function.clone(),
+            passed: message.is_none(),
+            message,
+        });
+    }
+    Ok(())
+}
+
+/// Hand the fixture's WASI arguments and environment to the engine
+#[cfg(feature = "wasi")]
+fn apply_wasi(engine: &mut CapabilityAwareEngine, wasi: &WasiFixture) -> BuildResult<()> {
+    engine.set_wasi_args(wasi.args.clone());
+    engine.set_wasi_env(wasi.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
+    Ok(())
+}
+
+/// Without WASI support only fixtures without arguments or environment run
+#[cfg(not(feature = "wasi"))]
+fn apply_wasi(_engine: &mut CapabilityAwareEngine, wasi: &WasiFixture) -> BuildResult<()> {
+    if wasi.args.is_empty() && wasi.env.is_empty() {
+        return Ok(());
+    }
+    Err(BuildError::Config(
+        "WASI arguments and environment require the `wasi` feature".to_string(),
+    ))
+}
+
+/// Fixture files at `path`: the file itself, or the fixture files in the
+/// directory and its subdirectories, sorted by path
+pub fn discover_fixtures(path: &Path) -> BuildResult<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == FIXTURE_EXTENSION))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Load and run the fixtures at `path`
+///
+/// A fixture file that fails to load is reported as a failed fixture named
+/// after the file.
+pub fn run_fixtures(path: &Path) -> BuildResult<Vec<FixtureReport>> {
+    let files = discover_fixtures(path)?;
+    if files.is_empty() {
+        return Err(BuildError::Config(format!("No fixtures found at {}", path.display())));
+    }
+    Ok(files
+        .iter()
+        .map(|file| match Fixture::load(file) {
+            Ok(fixture) => run_fixture(&fixture),
+            Err(e) => FixtureReport {
+                name: file.display().to_string(),
+                error: Some(e.to_string()),
+                calls: Vec::new(),
+            },
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARITH: &str = r#"
+name = "arith"
+wat = '''
+(module
+  (global $total (mut i32) (i32.const 0))
+  (func (export "add") (param i32 i32) (result i32)
+    local.get 0 local.get 1 i32.add)
+  (func (export "accumulate") (param i32) (result i32)
+    global.get $total local.get 0 i32.add global.set $total global.get $total)
+  (func (export "div") (param i32 i32) (result i32)
+    local.get 0 local.get 1 i32.div_s))
+'''
+
+[[call]]
+function = "add"
+args = ["i32:1", "i32:0x2"]
+expect = ["i32:3"]
+
+[[call]]
+function = "accumulate"
+args = ["i32:5"]
+expect = ["i32:5"]
+
+[[call]]
+function = "accumulate"
+args = ["i32:-1"]
+expect = ["i32:4"]
+
+[[call]]
+function = "div"
+args = ["i32:1", "i32:0"]
+trap = "divide by zero"
+"#;
+
+    #[test]
+    fn test_fixture_calls_share_instance_state() {
+        let fixture = Fixture::parse(ARITH, Path::new(".")).unwrap();
+        let report = run_fixture(&fixture);
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.calls.len(), 4);
+    }
+
+    #[test]
+    fn test_mismatches_are_reported_per_call() {
+        let source = ARITH.replace(r#"expect = ["i32:3"]"#, r#"expect = ["i32:4"]"#);
+        let report = run_fixture(&Fixture::parse(&source, Path::new(".")).unwrap());
+        assert!(!report.passed());
+        assert_eq!(report.calls.iter().filter(|call| !call.passed).count(), 1);
+        assert!(!report.calls[0].passed);
+
+        assert!(Fixture::parse("name = \"empty\"", Path::new(".")).is_err());
+        assert_eq!(parse_value("i64:-0x10").unwrap(), Value::I64(-16));
+        assert_eq!(parse_value("i32:4294967295").unwrap(), Value::I32(-1));
+        assert!(parse_value("v128:0").is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! of WebAssembly parsing, component models, and runtime operations.
 
 use std::{
+    fs,
     path::{Path, PathBuf},
     process::Command,
 };
@@ -28,6 +29,10 @@ pub struct FuzzOptions {
     pub targets: Vec<String>,
     /// Generate corpus coverage report
     pub coverage: bool,
+    /// Minimize each target's corpus after fuzzing
+    pub minimize_corpus: bool,
+    /// Run the differential target against the reference interpreter
+    pub differential: bool,
 }
 
 impl Default for FuzzOptions {
@@ -38,6 +43,8 @@ fn default() -> Self {
             runs: None,
             targets: Vec::new(),
             coverage: false,
+            minimize_corpus: false,
+            differential: false,
         }
     }
 }
@@ -55,6 +62,194 @@ pub struct FuzzResults {
     pub duration_ms: u64,
     /// Detailed fuzzing report
     pub report: String,
+    /// Minimized divergences found by the differential target
+    pub divergences: Vec<Divergence>,
+}
+
+/// Input on which WRT and the reference interpreter disagree
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Name of the crash artifact the divergence was minimized from
+    pub name: String,
+    /// Minimized fuzzer input
+    pub input: PathBuf,
+    /// Whether minimization shrank the input; if not, `input` is a copy of
+    /// the original crash artifact
+    pub minimized: bool,
+    /// Module generated from the input, if the target wrote it
+    pub module: Option<PathBuf>,
+    /// Result of replaying the input
+    pub status: DivergenceStatus,
+}
+
+/// Result of replaying a divergence input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivergenceStatus {
+    /// The engines disagreed again; holds the calls made and where they
+    /// disagreed
+    Reproduced(String),
+    /// The replay finished without the target reporting a divergence
+    NotReproduced,
+}
+
+/// Alias accepted in place of [`CANONICAL_ABI_TARGET`]
+pub const CANONICAL_ABI_ALIAS: &str = "canonical-abi";
+
+/// Name of the generated canonical ABI fuzz target
+pub const CANONICAL_ABI_TARGET: &str = "fuzz_canonical_abi";
+
+/// Crate whose fuzz directory hosts the canonical ABI target
+const CANONICAL_ABI_CRATE: &str = "wrt-component";
+
+/// Source of the canonical ABI fuzz target, restored in checkouts where it
+/// is missing or stale
+const CANONICAL_ABI_TARGET_SOURCE: &str =
+    include_str!("../../wrt-component/fuzz/fuzz_targets/fuzz_canonical_abi.rs");
+
+/// Alias accepted in place of [`DIFFERENTIAL_TARGET`]
+pub const DIFFERENTIAL_ALIAS: &str = "differential";
+
+/// Name of the generated differential fuzz target
+pub const DIFFERENTIAL_TARGET: &str = "fuzz_differential";
+
+/// Crate whose fuzz directory hosts the differential target
+const DIFFERENTIAL_CRATE: &str = "wrt-runtime";
+
+/// Source of the differential fuzz target, restored in checkouts where it is
+/// missing or stale
+const DIFFERENTIAL_TARGET_SOURCE: &str =
+    include_str!("../../wrt-runtime/fuzz/fuzz_targets/fuzz_differential.rs");
+
+/// Fuzz crate features enabling the reference interpreter
+const DIFFERENTIAL_FEATURES: &[&str] = &["wasmi"];
+
+/// Directory, inside the fuzz directory, receiving minimized divergences
+const DIVERGENCES_DIR: &str = "divergences";
+
+/// Environment variable the differential target dumps divergences to
+const DIFFERENTIAL_DUMP_ENV: &str = "WRT_DIFFERENTIAL_DUMP";
+
+/// Resolve a target alias to the fuzz target name
+pub fn resolve_fuzz_target(target: &str) -> &str {
+    match target {
+        CANONICAL_ABI_ALIAS => CANONICAL_ABI_TARGET,
+        DIFFERENTIAL_ALIAS => DIFFERENTIAL_TARGET,
+        other => other,
+    }
+}
+
+/// Fuzz crate features a target needs to build
+fn target_features(target: &str) -> &'static [&'static str] {
+    match target {
+        DIFFERENTIAL_TARGET => DIFFERENTIAL_FEATURES,
+        _ => &[],
+    }
+}
+
+/// Seed inputs for the canonical ABI target
+///
+/// Each input is a prefix-encoded type tree followed by memory contents, in
+/// the layout documented in the generated target.
+pub fn canonical_abi_seed_inputs() -> Vec<(String, Vec<u8>)> {
+    let names = [
+        "bool", "s8", "u8", "s16", "u16", "s32", "u32", "s64", "u64", "f32", "f64", "char",
+    ];
+    let mut seeds: Vec<(String, Vec<u8>)> = names
+        .iter()
+        .enumerate()
+        .map(|(tag, name)| {
+            let mut input = vec![tag as u8];
+            input.extend_from_slice(&[0; 16]);
+            (format!("seed-{}", name), input)
+        })
+        .collect();
+
+    // string "wrt": (ptr 8, len 3) followed by its bytes
+    seeds.push((
+        "seed-string".to_string(),
+        vec![12, 8, 0, 0, 0, 3, 0, 0, 0, b'w', b'r', b't'],
+    ));
+    // list<u8> [1, 2, 3]
+    seeds.push((
+        "seed-list".to_string(),
+        vec![13, 2, 8, 0, 0, 0, 3, 0, 0, 0, 1, 2, 3],
+    ));
+    // record { f0: u8, f1: u32 }
+    seeds.push(("seed-record".to_string(), vec![14, 1, 2, 6, 7, 0, 0, 0, 42, 0, 0, 0]));
+    // option<u32> = some(5)
+    seeds.push(("seed-option".to_string(), vec![18, 6, 1, 5, 0, 0, 0]));
+    // result<u8, u16> = err(9)
+    seeds.push(("seed-result".to_string(), vec![19, 3, 2, 4, 1, 0, 0, 0, 9, 0]));
+    seeds
+}
+
+/// Generate the canonical ABI fuzz target implementation
+///
+/// Writes the target source into the component crate's fuzz directory,
+/// registers its binary in the fuzz manifest and seeds its corpus. Existing
+/// corpus entries are kept. Returns the fuzz directory.
+pub fn generate_canonical_abi_target_impl(build_system: &BuildSystem) -> BuildResult<PathBuf> {
+    let fuzz_dir = write_generated_target_impl(
+        build_system,
+        CANONICAL_ABI_CRATE,
+        CANONICAL_ABI_TARGET,
+        CANONICAL_ABI_TARGET_SOURCE,
+    )?;
+
+    let seeded = seed_corpus_impl(
+        &corpus_dir_impl(&fuzz_dir, CANONICAL_ABI_TARGET),
+        &canonical_abi_seed_inputs(),
+    )?;
+    if seeded > 0 {
+        println!("  Seeded corpus with {} inputs", seeded);
+    }
+
+    Ok(fuzz_dir)
+}
+
+/// Generate the differential fuzz target implementation
+///
+/// Writes the target source into the runtime crate's fuzz directory and
+/// registers its binary in the fuzz manifest. Returns the fuzz directory.
+pub fn generate_differential_target_impl(build_system: &BuildSystem) -> BuildResult<PathBuf> {
+    write_generated_target_impl(
+        build_system,
+        DIFFERENTIAL_CRATE,
+        DIFFERENTIAL_TARGET,
+        DIFFERENTIAL_TARGET_SOURCE,
+    )
+}
+
+/// Write a generated fuzz target and register its binary implementation
+fn write_generated_target_impl(
+    build_system: &BuildSystem,
+    crate_name: &str,
+    target: &str,
+    source: &str,
+) -> BuildResult<PathBuf> {
+    let fuzz_dir = build_system.workspace.root.join(crate_name).join("fuzz");
+    if !fuzz_dir.join("Cargo.toml").exists() {
+        return Err(BuildError::Tool(format!(
+            "No fuzz crate found at {}. Run 'cargo fuzz init' in {} first.",
+            fuzz_dir.display(),
+            crate_name
+        )));
+    }
+
+    let target_file = fuzz_dir.join("fuzz_targets").join(format!("{}.rs", target));
+    let current = fs::read_to_string(&target_file).unwrap_or_default();
+    if current != source {
+        fs::create_dir_all(fuzz_dir.join("fuzz_targets"))
+            .map_err(|e| BuildError::Tool(format!("Failed to create fuzz_targets: {}", e)))?;
+        fs::write(&target_file, source).map_err(|e| {
+            BuildError::Tool(format!("Failed to write {}: {}", target_file.display(), e))
+        })?;
+        println!("{} Generated fuzz target {}", "📝".bright_blue(), target);
+    }
+
+    ensure_fuzz_bin_impl(&fuzz_dir, target, target_features(target))?;
+
+    Ok(fuzz_dir)
 }
 
 /// List available fuzzing targets implementation
@@ -88,12 +283,26 @@ pub fn run_fuzz_with_options_impl(
     let start_time = std::time::Instant::now();
     let mut targets_run = Vec::new();
     let mut crashed_targets = Vec::new();
+    let mut divergences = Vec::new();
     let mut success = true;
 
     // Determine targets to run
-    let all_targets = list_fuzz_targets_impl(build_system)?;
-    let targets_to_run =
-        if options.targets.is_empty() { all_targets } else { options.targets.clone() };
+    let mut targets_to_run: Vec<String> = if options.targets.is_empty() && !options.differential
+    {
+        list_fuzz_targets_impl(build_system)?
+    } else {
+        options.targets.iter().map(|t| resolve_fuzz_target(t).to_string()).collect()
+    };
+    if options.differential && !targets_to_run.iter().any(|t| t == DIFFERENTIAL_TARGET) {
+        targets_to_run.push(DIFFERENTIAL_TARGET.to_string());
+    }
+
+    if targets_to_run.iter().any(|t| t == CANONICAL_ABI_TARGET) {
+        generate_canonical_abi_target_impl(build_system)?;
+    }
+    if targets_to_run.iter().any(|t| t == DIFFERENTIAL_TARGET) {
+        generate_differential_target_impl(build_system)?;
+    }
 
     if targets_to_run.is_empty() {
         return Err(BuildError::Tool(
@@ -121,6 +330,10 @@ pub fn run_fuzz_with_options_impl(
                     crashed_targets.push(target.clone());
                     success = false;
                     println!("{} {} found crashes or failed", "⚠️".bright_red(), target);
+                    if target == DIFFERENTIAL_TARGET {
+                        let fuzz_dir = find_fuzz_target_dir_impl(build_system, target)?;
+                        divergences.extend(collect_divergences_impl(&fuzz_dir)?);
+                    }
                 } else {
                     println!("{} {} completed successfully", "✅".bright_green(), target);
                 }
@@ -152,7 +365,8 @@ pub fn run_fuzz_with_options_impl(
     }
 
     // Generate report
-    let report = generate_fuzz_report_impl(&targets_run, &crashed_targets, duration)?;
+    let report =
+        generate_fuzz_report_impl(&targets_run, &crashed_targets, &divergences, duration)?;
 
     Ok(FuzzResults {
         success,
@@ -160,6 +374,7 @@ pub fn run_fuzz_with_options_impl(
         crashed_targets,
         duration_ms: duration.as_millis() as u64,
         report,
+        divergences,
     })
 }
 
@@ -172,11 +387,15 @@ fn run_single_fuzz_target_impl(
     // Find the crate containing this fuzz target
     let fuzz_dir = find_fuzz_target_dir_impl(build_system, target)?;
 
+    let corpus_dir = corpus_dir_impl(&fuzz_dir, target);
+    fs::create_dir_all(&corpus_dir)
+        .map_err(|e| BuildError::Tool(format!("Failed to create corpus directory: {}", e)))?;
+    let corpus_before = count_corpus_entries_impl(&corpus_dir);
+
     let mut cmd = Command::new("cargo");
-    cmd.arg("+nightly")
-        .arg("fuzz")
-        .arg("run")
-        .arg(target)
+    cmd.arg("+nightly").arg("fuzz").arg("run").arg(target);
+    add_target_features(&mut cmd, target);
+    cmd.arg(&corpus_dir)
         .arg("--")
         .arg(format!("-workers={}", options.workers))
         .current_dir(&fuzz_dir);
@@ -197,9 +416,218 @@ fn run_single_fuzz_target_impl(
     let has_crashes = artifacts_dir.exists()
         && artifacts_dir.read_dir().map(|entries| entries.count() > 0).unwrap_or(false);
 
+    if options.minimize_corpus {
+        let mut cmd = Command::new("cargo");
+        cmd.arg("+nightly").arg("fuzz").arg("cmin").arg(target);
+        add_target_features(&mut cmd, target);
+        let status = cmd
+            .arg(&corpus_dir)
+            .current_dir(&fuzz_dir)
+            .output()
+            .map_err(|e| {
+                BuildError::Tool(format!("Failed to minimize corpus of {}: {}", target, e))
+            })?
+            .status;
+        if !status.success() {
+            println!("  Corpus minimization failed for {}", target);
+        }
+    }
+
+    println!(
+        "  Corpus: {} -> {} inputs",
+        corpus_before,
+        count_corpus_entries_impl(&corpus_dir)
+    );
+
     Ok(output.status.success() && !has_crashes)
 }
 
+/// Pass the features a target needs to a cargo-fuzz command
+fn add_target_features(cmd: &mut Command, target: &str) {
+    let features = target_features(target);
+    if !features.is_empty() {
+        cmd.arg("--features").arg(features.join(","));
+    }
+}
+
+/// Crash artifacts of the differential target without a minimized divergence
+fn pending_divergences_impl(fuzz_dir: &Path) -> Vec<PathBuf> {
+    let artifacts_dir = fuzz_dir.join("artifacts").join(DIFFERENTIAL_TARGET);
+    let divergences_dir = fuzz_dir.join(DIVERGENCES_DIR);
+
+    let mut pending: Vec<PathBuf> = artifacts_dir
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with("crash-")
+                        && !divergences_dir.join(format!("{}.input", name)).exists()
+                })
+        })
+        .collect();
+    pending.sort();
+    pending
+}
+
+/// Minimize new differential crashes into divergence test cases implementation
+///
+/// Each crash artifact is minimized with `cargo fuzz tmin`, then replayed
+/// so the target writes the generated module and a description of the
+/// divergence next to the minimized input.
+fn collect_divergences_impl(fuzz_dir: &Path) -> BuildResult<Vec<Divergence>> {
+    let divergences_dir = fuzz_dir.join(DIVERGENCES_DIR);
+    fs::create_dir_all(&divergences_dir).map_err(|e| {
+        BuildError::Tool(format!("Failed to create divergences directory: {}", e))
+    })?;
+
+    let mut divergences = Vec::new();
+    for artifact in pending_divergences_impl(fuzz_dir) {
+        let name = artifact
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let input = divergences_dir.join(format!("{}.input", name));
+        println!("{} Minimizing divergence {}", "🔍".bright_cyan(), name);
+
+        let mut cmd = Command::new("cargo");
+        cmd.arg("+nightly").arg("fuzz").arg("tmin").arg(DIFFERENTIAL_TARGET);
+        add_target_features(&mut cmd, DIFFERENTIAL_TARGET);
+        let output = cmd
+            .arg(&artifact)
+            .arg("--")
+            .arg(format!("-exact_artifact_path={}", input.display()))
+            .current_dir(fuzz_dir)
+            .output()
+            .map_err(|e| BuildError::Tool(format!("Failed to minimize {}: {}", name, e)))?;
+        if !output.status.success() {
+            return Err(BuildError::Tool(format!(
+                "Failed to minimize {}: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )));
+        }
+        let minimized = input.exists();
+        if !minimized {
+            // Minimization made no progress; report the original input as such
+            println!("{} {} could not be minimized", "⚠️".bright_yellow(), name);
+            fs::copy(&artifact, &input)
+                .map_err(|e| BuildError::Tool(format!("Failed to copy {}: {}", name, e)))?;
+        }
+
+        // Replay the minimized input so the target dumps the module
+        let prefix = divergences_dir.join(&name);
+        let mut cmd = Command::new("cargo");
+        cmd.arg("+nightly").arg("fuzz").arg("run").arg(DIFFERENTIAL_TARGET);
+        add_target_features(&mut cmd, DIFFERENTIAL_TARGET);
+        cmd.arg(&input)
+            .env(DIFFERENTIAL_DUMP_ENV, &prefix)
+            .current_dir(fuzz_dir)
+            .output()
+            .map_err(|e| BuildError::Tool(format!("Failed to replay {}: {}", name, e)))?;
+
+        let module = prefix.with_extension("wasm");
+        let status = match fs::read_to_string(prefix.with_extension("txt")) {
+            Ok(description) => DivergenceStatus::Reproduced(description),
+            // The target only writes a description when the engines disagree
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("{} {} did not reproduce", "⚠️".bright_yellow(), name);
+                DivergenceStatus::NotReproduced
+            },
+            Err(e) => {
+                return Err(BuildError::Tool(format!(
+                    "Failed to read divergence description of {}: {}",
+                    name, e
+                )))
+            },
+        };
+        divergences.push(Divergence {
+            name,
+            input,
+            minimized,
+            module: module.exists().then_some(module),
+            status,
+        });
+    }
+
+    Ok(divergences)
+}
+
+/// Corpus directory of a fuzz target, as used by cargo-fuzz
+fn corpus_dir_impl(fuzz_dir: &Path, target: &str) -> PathBuf {
+    fuzz_dir.join("corpus").join(target)
+}
+
+/// Count the inputs in a corpus directory
+fn count_corpus_entries_impl(corpus_dir: &Path) -> usize {
+    corpus_dir.read_dir().map(|entries| entries.count()).unwrap_or(0)
+}
+
+/// Write seed inputs that are not yet in the corpus implementation
+///
+/// Returns the number of seeds written.
+fn seed_corpus_impl(corpus_dir: &Path, seeds: &[(String, Vec<u8>)]) -> BuildResult<usize> {
+    fs::create_dir_all(corpus_dir)
+        .map_err(|e| BuildError::Tool(format!("Failed to create corpus directory: {}", e)))?;
+
+    let mut written = 0;
+    for (name, input) in seeds {
+        let path = corpus_dir.join(name);
+        if path.exists() {
+            continue;
+        }
+        fs::write(&path, input)
+            .map_err(|e| BuildError::Tool(format!("Failed to write seed {}: {}", name, e)))?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// Register a fuzz target binary in the fuzz manifest implementation
+fn ensure_fuzz_bin_impl(
+    fuzz_dir: &Path,
+    target: &str,
+    required_features: &[&str],
+) -> BuildResult<()> {
+    let manifest_path = fuzz_dir.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| BuildError::Tool(format!("Failed to read fuzz manifest: {}", e)))?;
+    let parsed: toml::Value = toml::from_str(&manifest)
+        .map_err(|e| BuildError::Tool(format!("Failed to parse fuzz manifest: {}", e)))?;
+
+    let registered = parsed
+        .get("bin")
+        .and_then(|bins| bins.as_array())
+        .is_some_and(|bins| {
+            bins.iter().any(|bin| bin.get("name").and_then(|n| n.as_str()) == Some(target))
+        });
+    if registered {
+        return Ok(());
+    }
+
+    // Append rather than re-serialize so the manifest keeps its layout
+    let mut updated = manifest.trim_end().to_string();
+    updated.push_str(&format!(
+        "\n\n[[bin]]\nname = \"{0}\"\npath = \"fuzz_targets/{0}.rs\"\ntest = false\n",
+        target
+    ));
+    updated.push_str("doc = false\n");
+    if !required_features.is_empty() {
+        let features: Vec<String> =
+            required_features.iter().map(|feature| format!("\"{}\"", feature)).collect();
+        updated.push_str(&format!("required-features = [{}]\n", features.join(", ")));
+    }
+    fs::write(&manifest_path, updated)
+        .map_err(|e| BuildError::Tool(format!("Failed to update fuzz manifest: {}", e)))?;
+
+    Ok(())
+}
+
 /// Check if cargo-fuzz is available implementation
 fn is_cargo_fuzz_available_impl() -> BuildResult<bool> {
     use crate::tools::ensure_tool_available;
@@ -259,6 +687,7 @@ fn find_fuzz_target_dir_impl(build_system: &BuildSystem, target: &str) -> BuildR
 fn generate_fuzz_report_impl(
     targets_run: &[String],
     crashed_targets: &[String],
+    divergences: &[Divergence],
     duration: std::time::Duration,
 ) -> BuildResult<String> {
     let mut report = String::new();
@@ -300,6 +729,28 @@ fn generate_fuzz_report_impl(
         }
     }
 
+    if !divergences.is_empty() {
+        report.push_str("\n## Divergences\n\n");
+        for divergence in divergences {
+            report.push_str(&format!("### {}\n", divergence.name));
+            report.push_str(&format!("- **Input:** {}\n", divergence.input.display()));
+            if !divergence.minimized {
+                report.push_str("- **Minimized:** no, the input is the original crash artifact\n");
+            }
+            if let Some(module) = &divergence.module {
+                report.push_str(&format!("- **Module:** {}\n", module.display()));
+            }
+            match &divergence.status {
+                DivergenceStatus::Reproduced(description) => {
+                    report.push_str(&format!("\n```\n{}\n```\n\n", description.trim_end()));
+                },
+                DivergenceStatus::NotReproduced => {
+                    report.push_str("- **Status:** did not reproduce on replay\n\n");
+                },
+            }
+        }
+    }
+
     Ok(report)
 }
 
@@ -323,10 +774,97 @@ fn test_fuzz_results() {
             crashed_targets: vec![],
             duration_ms: 1000,
             report: "Test report".to_string(),
+            divergences: vec![],
         };
 
         assert!(results.success);
         assert_eq!(results.targets_run.len(), 1);
         assert!(results.crashed_targets.is_empty());
     }
+
+    #[test]
+    fn test_report_marks_unminimized_and_unreproduced_divergences() {
+        let divergences = [
+            Divergence {
+                name:      "crash-a".to_string(),
+                input:     PathBuf::from("divergences/crash-a.input"),
+                minimized: true,
+                module:    None,
+                status:    DivergenceStatus::Reproduced("call f: wrt 1, reference 2".to_string()),
+            },
+            Divergence {
+                name:      "crash-b".to_string(),
+                input:     PathBuf::from("divergences/crash-b.input"),
+                minimized: false,
+                module:    None,
+                status:    DivergenceStatus::NotReproduced,
+            },
+        ];
+        let targets = [DIFFERENTIAL_TARGET.to_string()];
+        let report = generate_fuzz_report_impl(
+            &targets,
+            &targets,
+            &divergences,
+            std::time::Duration::from_secs(1),
+        )
+        .unwrap();
+
+        let (first, second) = report.split_once("### crash-b").unwrap();
+        assert!(first.contains("call f: wrt 1, reference 2"));
+        assert!(!first.contains("Minimized:"));
+        assert!(second.contains("- **Minimized:** no"));
+        assert!(second.contains("did not reproduce on replay"));
+    }
+
+    #[test]
+    fn test_resolve_fuzz_target() {
+        assert_eq!(resolve_fuzz_target("canonical-abi"), CANONICAL_ABI_TARGET);
+        assert_eq!(resolve_fuzz_target("differential"), DIFFERENTIAL_TARGET);
+        assert_eq!(resolve_fuzz_target("fuzz_wit_parser"), "fuzz_wit_parser");
+    }
+
+    #[test]
+    fn test_pending_divergences_skip_minimized_crashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifacts = dir.path().join("artifacts").join(DIFFERENTIAL_TARGET);
+        fs::create_dir_all(&artifacts).unwrap();
+        fs::create_dir_all(dir.path().join(DIVERGENCES_DIR)).unwrap();
+        for name in ["crash-b", "crash-a", "timeout-c"] {
+            fs::write(artifacts.join(name), [0]).unwrap();
+        }
+        fs::write(dir.path().join(DIVERGENCES_DIR).join("crash-b.input"), [0]).unwrap();
+
+        assert_eq!(pending_divergences_impl(dir.path()), vec![artifacts.join("crash-a")]);
+    }
+
+    #[test]
+    fn test_seed_corpus_keeps_existing_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let corpus = dir.path().join("corpus");
+        let seeds = canonical_abi_seed_inputs();
+
+        assert_eq!(seed_corpus_impl(&corpus, &seeds).unwrap(), seeds.len());
+        fs::write(corpus.join("seed-bool"), [0xff]).unwrap();
+        assert_eq!(seed_corpus_impl(&corpus, &seeds).unwrap(), 0);
+        assert_eq!(fs::read(corpus.join("seed-bool")).unwrap(), vec![0xff]);
+        assert_eq!(count_corpus_entries_impl(&corpus), seeds.len());
+    }
+
+    #[test]
+    fn test_ensure_fuzz_bin_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&manifest, "[package]\nname = \"fuzz\"\n").unwrap();
+
+        ensure_fuzz_bin_impl(dir.path(), CANONICAL_ABI_TARGET, &[]).unwrap();
+        ensure_fuzz_bin_impl(dir.path(), CANONICAL_ABI_TARGET, &[]).unwrap();
+        ensure_fuzz_bin_impl(dir.path(), DIFFERENTIAL_TARGET, DIFFERENTIAL_FEATURES).unwrap();
+
+        let parsed: toml::Value = toml::from_str(&fs::read_to_string(manifest).unwrap()).unwrap();
+        let bins = parsed["bin"].as_array().unwrap();
+        assert_eq!(bins.len(), 2);
+        assert_eq!(bins[0]["name"].as_str(), Some(CANONICAL_ABI_TARGET));
+        assert!(bins[0].get("required-features").is_none());
+        assert_eq!(bins[1]["required-features"][0].as_str(), Some("wasmi"));
+    }
 }
@@ -18,6 +18,9 @@
 use crate::{
     config::AsilLevel,
     error::{BuildError, BuildResult},
+    kani_results::{
+        parse_harness_results, HarnessResult, KaniDelta, KaniResultsDb, KaniRun, RESULTS_DB_FILE,
+    },
 };
 
 /// KANI verification configuration
@@ -62,6 +65,8 @@ pub struct PackageVerificationResult {
     pub duration_ms: u64,
     /// Failure messages
     pub failures: Vec<String>,
+    /// Outcome of each harness
+    pub harnesses: Vec<HarnessResult>,
     /// Log file path
     pub log_file: PathBuf,
     /// Raw output
@@ -89,6 +94,8 @@ pub struct KaniVerificationResults {
     pub report_file: PathBuf,
     /// Coverage report (if generated)
     pub coverage_report: Option<String>,
+    /// Changes since the previous run
+    pub delta: KaniDelta,
 }
 
 /// KANI verifier
@@ -148,12 +155,16 @@ pub fn run_verification(&self) -> BuildResult<KaniVerificationResults> {
             0.0
         };
 
+        // Record harness outcomes and compare with the previous run
+        let delta = self.record_results(&package_results)?;
+
         // Generate main report
         let report_file = self.generate_report(
             &package_results,
             total_packages,
             passed_packages,
             success_rate,
+            &delta,
         )?;
 
         // Generate coverage report for ASIL-D
@@ -193,9 +204,27 @@ pub fn run_verification(&self) -> BuildResult<KaniVerificationResults> {
             success_rate,
             report_file,
             coverage_report,
+            delta,
         })
     }
 
+    /// Record the harness outcomes in the results database, returning the
+    /// changes since the previous run
+    fn record_results(
+        &self,
+        package_results: &[PackageVerificationResult],
+    ) -> BuildResult<KaniDelta> {
+        let db_path = self.report_dir.join(RESULTS_DB_FILE);
+        let mut db = KaniResultsDb::load(&db_path)?;
+        let delta = db.record(KaniRun {
+            timestamp: self.timestamp.clone(),
+            profile: self.config.profile,
+            harnesses: package_results.iter().flat_map(|r| r.harnesses.clone()).collect(),
+        });
+        db.save(&db_path)?;
+        Ok(delta)
+    }
+
     /// Run KANI on a specific package
     fn run_kani_package(&self, package: &str) -> BuildResult<PackageVerificationResult> {
         println!(
@@ -268,6 +297,7 @@ fn run_kani_package(&self, package: &str) -> BuildResult<PackageVerificationResu
         let total_checks = self.count_checks(&output_string);
         let passed_checks = self.count_passed_checks(&output_string);
         let failures = if !passed { self.extract_failures(&output_string) } else { Vec::new() };
+        let harnesses = parse_harness_results(package, &output_string);
 
         let duration = start_time.elapsed();
 
@@ -284,6 +314,7 @@ fn run_kani_package(&self, package: &str) -> BuildResult<PackageVerificationResu
             passed_checks,
             duration_ms: duration.as_millis() as u64,
             failures,
+            harnesses,
             log_file,
             output: output_string,
         })
@@ -372,6 +403,7 @@ fn generate_report(
         total_packages: usize,
         passed_packages: usize,
         success_rate: f64,
+        delta: &KaniDelta,
     ) -> BuildResult<PathBuf> {
         let report_file =
             self.report_dir.join(format!("verification_report_{}.md", self.timestamp));
@@ -431,6 +463,7 @@ fn generate_report(
             total_packages - passed_packages,
             success_rate
         ));
+        content.push_str(&delta.to_markdown());
 
         fs::write(&report_file, content)
             .map_err(|e| BuildError::Tool(format!("Failed to write report: {}", e)))?;
@@ -518,6 +551,22 @@ pub fn print_summary(&self, results: &KaniVerificationResults) {
             println!("{} Some verifications failed", "⚠️".bright_yellow());
         }
 
+        for harness in &results.delta.newly_failing {
+            println!("{} Newly failing: {}", "❌".bright_red(), harness);
+        }
+        for slowdown in &results.delta.newly_slow {
+            println!(
+                "{} Newly slow: {} ({}ms -> {}ms)",
+                "⚠️".bright_yellow(),
+                slowdown.harness,
+                slowdown.previous_ms,
+                slowdown.current_ms
+            );
+        }
+        for harness in &results.delta.fixed {
+            println!("{} Fixed: {}", "✓".bright_green(), harness);
+        }
+
         println!();
         println!("Report: {}", results.report_file.display());
         if let Some(ref coverage) = results.coverage_report {
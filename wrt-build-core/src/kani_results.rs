@@ -0,0 +1,439 @@
+//! KANI proof results database and regression tracking
+//!
+//! Every KANI run records the outcome of each harness, how long it took and
+//! what the solver reported in `target/kani-reports/kani-results.json`.
+//! Comparing a run with the previous one shows proofs that started failing,
+//! proofs that were fixed, and proofs that became markedly slower, which
+//! usually means a change made the verification problem harder.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AsilLevel,
+    error::{BuildError, BuildResult},
+};
+
+/// File name of the results database inside the KANI report directory
+pub const RESULTS_DB_FILE: &str = "kani-results.json";
+
+/// Number of runs the database keeps
+const MAX_RUNS: usize = 50;
+
+/// Factor by which a harness must slow down to count as a regression
+const SLOWDOWN_FACTOR: f64 = 1.5;
+
+/// Slowdowns below this many milliseconds are treated as noise
+const MIN_SLOWDOWN_MS: u64 = 1000;
+
+/// Statistics the solver reported for one harness
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolverStats {
+    /// Solver used, as reported by CBMC
+    pub solver: Option<String>,
+    /// Time spent in symbolic execution, in milliseconds
+    pub symex_ms: Option<u64>,
+    /// Time spent in the solver, in milliseconds
+    pub solver_ms: Option<u64>,
+    /// Number of propositional variables
+    pub variables: Option<u64>,
+    /// Number of clauses
+    pub clauses: Option<u64>,
+}
+
+/// Outcome of one harness in one run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarnessResult {
+    /// Package the harness belongs to
+    pub package: String,
+    /// Harness name
+    pub harness: String,
+    /// Whether the harness verified successfully
+    pub passed: bool,
+    /// Verification time, in milliseconds
+    pub duration_ms: u64,
+    /// Number of checks
+    pub total_checks: usize,
+    /// Number of failed checks
+    pub failed_checks: usize,
+    /// Solver statistics
+    pub solver: SolverStats,
+}
+
+impl HarnessResult {
+    /// Name identifying the harness across runs
+    pub fn key(&self) -> String {
+        format!("{}::{}", self.package, self.harness)
+    }
+}
+
+/// Harness outcomes of one KANI run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KaniRun {
+    /// Timestamp of the run
+    pub timestamp: String,
+    /// ASIL profile the run used
+    pub profile: AsilLevel,
+    /// Outcome of every harness that ran
+    pub harnesses: Vec<HarnessResult>,
+}
+
+/// Harness that became slower
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Slowdown {
+    /// Harness key
+    pub harness: String,
+    /// Duration in the previous run, in milliseconds
+    pub previous_ms: u64,
+    /// Duration in this run, in milliseconds
+    pub current_ms: u64,
+}
+
+/// Changes between a run and the previous one
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct KaniDelta {
+    /// Timestamp of the run compared against, if any
+    pub baseline: Option<String>,
+    /// Harnesses that passed before and fail now
+    pub newly_failing: Vec<String>,
+    /// Harnesses that failed before and pass now
+    pub fixed: Vec<String>,
+    /// Harnesses that became markedly slower
+    pub newly_slow: Vec<Slowdown>,
+    /// Harnesses that did not run before
+    pub added: Vec<String>,
+    /// Harnesses of the verified packages that no longer run
+    pub removed: Vec<String>,
+}
+
+impl KaniDelta {
+    /// Whether any proof started failing or became markedly slower
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty() || !self.newly_slow.is_empty()
+    }
+
+    /// Markdown section describing the delta
+    pub fn to_markdown(&self) -> String {
+        let mut section = String::from("\n## KANI Proof Delta\n\n");
+        match &self.baseline {
+            Some(baseline) => section.push_str(&format!("Compared with the run of {}.\n\n", baseline)),
+            None => {
+                section.push_str("No previous run recorded; this run is the baseline.\n");
+                return section;
+            },
+        }
+
+        if self.newly_failing.is_empty()
+            && self.fixed.is_empty()
+            && self.newly_slow.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+        {
+            section.push_str("No changes.\n");
+            return section;
+        }
+
+        let lists = [
+            ("Newly failing", &self.newly_failing),
+            ("Fixed", &self.fixed),
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+        ];
+        for (title, harnesses) in lists {
+            if harnesses.is_empty() {
+                continue;
+            }
+            section.push_str(&format!("**{}**:\n", title));
+            for harness in harnesses {
+                section.push_str(&format!("- {}\n", harness));
+            }
+            section.push('\n');
+        }
+
+        if !self.newly_slow.is_empty() {
+            section.push_str("**Newly slow**:\n");
+            for slowdown in &self.newly_slow {
+                section.push_str(&format!(
+                    "- {}: {}ms -> {}ms\n",
+                    slowdown.harness, slowdown.previous_ms, slowdown.current_ms
+                ));
+            }
+            section.push('\n');
+        }
+        section
+    }
+}
+
+/// History of KANI runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KaniResultsDb {
+    /// Recorded runs, oldest first
+    pub runs: Vec<KaniRun>,
+}
+
+impl KaniResultsDb {
+    /// Load the database, or start an empty one if it does not exist
+    pub fn load(path: &Path) -> BuildResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| {
+            BuildError::Tool(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            BuildError::Tool(format!("Failed to parse {}: {}", path.display(), e))
+        })
+    }
+
+    /// Write the database
+    pub fn save(&self, path: &Path) -> BuildResult<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                BuildError::Tool(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| BuildError::Tool(format!("Failed to serialize KANI results: {}", e)))?;
+        fs::write(path, content)
+            .map_err(|e| BuildError::Tool(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Most recent run
+    pub fn latest(&self) -> Option<&KaniRun> {
+        self.runs.last()
+    }
+
+    /// Compare a run with the most recent recorded one and record it
+    pub fn record(&mut self, run: KaniRun) -> KaniDelta {
+        let delta = match self.latest() {
+            Some(previous) => compare_runs(previous, &run),
+            None => KaniDelta::default(),
+        };
+        self.runs.push(run);
+        if self.runs.len() > MAX_RUNS {
+            let excess = self.runs.len() - MAX_RUNS;
+            self.runs.drain(..excess);
+        }
+        delta
+    }
+}
+
+/// Changes from `previous` to `current`
+///
+/// Harnesses of packages `current` did not verify are not reported as
+/// removed, so verifying a single package or harness does not hide the rest.
+pub fn compare_runs(previous: &KaniRun, current: &KaniRun) -> KaniDelta {
+    let before: BTreeMap<String, &HarnessResult> =
+        previous.harnesses.iter().map(|h| (h.key(), h)).collect();
+    let after: BTreeMap<String, &HarnessResult> =
+        current.harnesses.iter().map(|h| (h.key(), h)).collect();
+    let packages: BTreeSet<&str> = current.harnesses.iter().map(|h| h.package.as_str()).collect();
+
+    let mut delta = KaniDelta {
+        baseline: Some(previous.timestamp.clone()),
+        ..KaniDelta::default()
+    };
+    for (key, result) in &after {
+        let Some(old) = before.get(key) else {
+            delta.added.push(key.clone());
+            continue;
+        };
+        match (old.passed, result.passed) {
+            (true, false) => delta.newly_failing.push(key.clone()),
+            (false, true) => delta.fixed.push(key.clone()),
+            _ => {},
+        }
+        if result.passed
+            && old.passed
+            && result.duration_ms as f64 > old.duration_ms as f64 * SLOWDOWN_FACTOR
+            && result.duration_ms.saturating_sub(old.duration_ms) >= MIN_SLOWDOWN_MS
+        {
+            delta.newly_slow.push(Slowdown {
+                harness: key.clone(),
+                previous_ms: old.duration_ms,
+                current_ms: result.duration_ms,
+            });
+        }
+    }
+    delta.removed = before
+        .iter()
+        .filter(|(key, old)| packages.contains(old.package.as_str()) && !after.contains_key(*key))
+        .map(|(key, _)| key.clone())
+        .collect();
+    delta
+}
+
+/// Harness outcomes in the output of `cargo kani` for one package
+pub fn parse_harness_results(package: &str, output: &str) -> Vec<HarnessResult> {
+    let mut results = Vec::new();
+    let mut current: Option<HarnessResult> = None;
+
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Checking harness ") {
+            results.extend(current.take());
+            current = Some(HarnessResult {
+                package: package.to_string(),
+                harness: name.trim_end_matches('.').to_string(),
+                passed: false,
+                duration_ms: 0,
+                total_checks: 0,
+                failed_checks: 0,
+                solver: SolverStats::default(),
+            });
+            continue;
+        }
+        let Some(result) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with("VERIFICATION:") {
+            result.passed = line.contains("SUCCESSFUL");
+        } else if let Some(time) = line.strip_prefix("Verification Time:") {
+            result.duration_ms = parse_seconds(time).unwrap_or(0);
+        } else if let Some(time) = line.strip_prefix("Runtime Symex:") {
+            result.solver.symex_ms = parse_seconds(time);
+        } else if let Some(time) = line.strip_prefix("Runtime Solver:") {
+            result.solver.solver_ms = parse_seconds(time);
+        } else if let Some(solver) = line.strip_prefix("Solving with ") {
+            result.solver.solver = Some(solver.to_string());
+        } else if let Some(summary) = line.strip_prefix("** ") {
+            // "** 0 of 123 failed"
+            let numbers: Vec<usize> =
+                summary.split_whitespace().filter_map(|word| word.parse().ok()).collect();
+            if let [failed, total] = numbers[..] {
+                result.failed_checks = failed;
+                result.total_checks = total;
+            }
+        } else if line.ends_with(" clauses") && line.contains(" variables, ") {
+            // "1234 variables, 5678 clauses"
+            let numbers: Vec<u64> =
+                line.split_whitespace().filter_map(|word| word.parse().ok()).collect();
+            if let [variables, clauses] = numbers[..] {
+                result.solver.variables = Some(variables);
+                result.solver.clauses = Some(clauses);
+            }
+        }
+    }
+    results.extend(current);
+    results
+}
+
+/// Parse a duration such as `1.234s` into milliseconds
+fn parse_seconds(text: &str) -> Option<u64> {
+    let seconds: f64 = text.trim().trim_end_matches('s').parse().ok()?;
+    Some((seconds * 1000.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+Checking harness verify_bounded_push...
+Solving with CaDiCaL 1.7.2
+Runtime Symex: 0.512s
+1234 variables, 5678 clauses
+Runtime Solver: 1.25s
+
+SUMMARY:
+ ** 0 of 42 failed
+
+VERIFICATION:- SUCCESSFUL
+Verification Time: 2.5s
+
+Checking harness verify_checksum...
+
+SUMMARY:
+ ** 1 of 7 failed
+Failed Checks: assertion failed
+
+VERIFICATION:- FAILED
+Verification Time: 0.4s
+";
+
+    fn run(timestamp: &str, harnesses: Vec<HarnessResult>) -> KaniRun {
+        KaniRun {
+            timestamp: timestamp.to_string(),
+            profile: AsilLevel::D,
+            harnesses,
+        }
+    }
+
+    #[test]
+    fn test_parse_harness_results() {
+        let results = parse_harness_results("wrt-foundation", OUTPUT);
+        assert_eq!(results.len(), 2);
+
+        let push = &results[0];
+        assert_eq!(push.harness, "verify_bounded_push");
+        assert!(push.passed);
+        assert_eq!((push.failed_checks, push.total_checks), (0, 42));
+        assert_eq!(push.duration_ms, 2500);
+        assert_eq!(push.solver.solver.as_deref(), Some("CaDiCaL 1.7.2"));
+        assert_eq!(push.solver.symex_ms, Some(512));
+        assert_eq!(push.solver.solver_ms, Some(1250));
+        assert_eq!((push.solver.variables, push.solver.clauses), (Some(1234), Some(5678)));
+
+        let checksum = &results[1];
+        assert!(!checksum.passed);
+        assert_eq!((checksum.failed_checks, checksum.total_checks), (1, 7));
+        assert_eq!(checksum.key(), "wrt-foundation::verify_checksum");
+    }
+
+    #[test]
+    fn test_delta_between_runs() {
+        let harness = |name: &str, passed, duration_ms| HarnessResult {
+            package: "wrt-foundation".to_string(),
+            harness: name.to_string(),
+            passed,
+            duration_ms,
+            total_checks: 1,
+            failed_checks: usize::from(!passed),
+            solver: SolverStats::default(),
+        };
+
+        let mut db = KaniResultsDb::default();
+        let first = db.record(run(
+            "1",
+            vec![
+                harness("a", true, 1000),
+                harness("b", true, 1000),
+                harness("c", false, 100),
+                harness("d", true, 100),
+            ],
+        ));
+        assert!(first.baseline.is_none() && !first.has_regressions());
+
+        let delta = db.record(run(
+            "2",
+            vec![
+                harness("a", false, 1000),
+                harness("b", true, 4000),
+                harness("c", true, 100),
+                harness("e", true, 100),
+            ],
+        ));
+        assert_eq!(delta.baseline.as_deref(), Some("1"));
+        assert_eq!(delta.newly_failing, vec!["wrt-foundation::a"]);
+        assert_eq!(delta.fixed, vec!["wrt-foundation::c"]);
+        assert_eq!(delta.newly_slow[0].harness, "wrt-foundation::b");
+        assert_eq!(delta.added, vec!["wrt-foundation::e"]);
+        assert_eq!(delta.removed, vec!["wrt-foundation::d"]);
+        assert!(delta.has_regressions());
+        assert!(delta.to_markdown().contains("- wrt-foundation::b: 1000ms -> 4000ms"));
+
+        // A run of another package does not report these harnesses as removed
+        let other = HarnessResult {
+            package: "wrt-runtime".to_string(),
+            ..harness("x", true, 10)
+        };
+        let delta = db.record(run("3", vec![other]));
+        assert!(delta.removed.is_empty());
+        assert_eq!(db.runs.len(), 3);
+    }
+}
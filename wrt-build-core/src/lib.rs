@@ -41,20 +41,29 @@
 pub use anyhow::{Context, Result};
 
 // Core modules
+pub mod abi_trace;
+pub mod ara_com;
+pub mod bench;
 pub mod build;
+pub mod build_cache;
 pub mod cache;
 pub mod ci;
 pub mod config;
+pub mod coverage;
 pub mod diagnostics;
 pub mod error;
+pub mod ffi_audit;
 pub mod filtering;
+pub mod fixtures;
 pub mod formatters;
 pub mod fuzz;
 pub mod kani;
+pub mod kani_results;
 pub mod matrix;
 pub mod memory;
 pub mod parsers;
 pub mod requirements;
+pub mod sbom;
 pub mod test;
 pub mod text_search;
 pub mod tool_versions;
@@ -67,6 +76,7 @@
 pub mod wast_execution;
 pub mod wast_validator;
 pub mod wast_values;
+pub mod world_proxy;
 
 // Public API
 pub use build::BuildSystem;
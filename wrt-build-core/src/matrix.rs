@@ -5,7 +5,7 @@
 //! architectural issues that could impact ASIL compliance.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     io::Write,
     path::{Path, PathBuf},
     process::Command,
@@ -16,7 +16,10 @@
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 
-use crate::error::{BuildError, BuildResult};
+use crate::{
+    config,
+    error::{BuildError, BuildResult},
+};
 
 /// Build configuration for matrix testing
 #[derive(Debug, Clone)]
@@ -836,3 +839,473 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
+
+/// Crate features selecting each ASIL level, lowest level first
+pub const ASIL_FEATURES: [(config::AsilLevel, &str); 5] = [
+    (config::AsilLevel::QM, "qm"),
+    (config::AsilLevel::A, "asil-a"),
+    (config::AsilLevel::B, "asil-b"),
+    (config::AsilLevel::C, "asil-c"),
+    (config::AsilLevel::D, "asil-d"),
+];
+
+/// Parse a comma-separated list of ASIL levels, or `all`
+pub fn parse_asil_levels(spec: &str) -> BuildResult<Vec<config::AsilLevel>> {
+    let mut selected = Vec::new();
+    for name in spec.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let levels: Vec<config::AsilLevel> = match name.to_ascii_lowercase().as_str() {
+            "all" => ASIL_FEATURES.iter().map(|(level, _)| *level).collect(),
+            "qm" => vec![config::AsilLevel::QM],
+            "a" | "asil-a" => vec![config::AsilLevel::A],
+            "b" | "asil-b" => vec![config::AsilLevel::B],
+            "c" | "asil-c" => vec![config::AsilLevel::C],
+            "d" | "asil-d" => vec![config::AsilLevel::D],
+            _ => {
+                return Err(BuildError::Workspace(format!(
+                    "Unknown ASIL level '{}' (expected qm, a, b, c, d or all)",
+                    name
+                )));
+            },
+        };
+        selected.extend(levels);
+    }
+    if selected.is_empty() {
+        return Err(BuildError::Workspace("No ASIL levels selected".to_string()));
+    }
+    Ok(ASIL_FEATURES
+        .iter()
+        .map(|(level, _)| *level)
+        .filter(|level| selected.contains(level))
+        .collect())
+}
+
+/// Crate feature selecting an ASIL level
+pub fn asil_feature(level: config::AsilLevel) -> &'static str {
+    ASIL_FEATURES
+        .iter()
+        .find(|(candidate, _)| *candidate == level)
+        .map(|(_, feature)| *feature)
+        .unwrap_or("qm")
+}
+
+/// Options for the per-ASIL build matrix
+#[derive(Debug, Clone)]
+pub struct AsilMatrixOptions {
+    /// Levels to build, lowest first
+    pub levels: Vec<config::AsilLevel>,
+    /// Crates to build; all crates declaring an ASIL feature if empty
+    pub packages: Vec<String>,
+    /// Run each crate's tests after it builds
+    pub run_tests: bool,
+    /// Directory holding one target directory per level
+    pub target_root: Option<PathBuf>,
+}
+
+impl Default for AsilMatrixOptions {
+    fn default() -> Self {
+        Self {
+            levels: ASIL_FEATURES.iter().map(|(level, _)| *level).collect(),
+            packages: Vec::new(),
+            run_tests: true,
+            target_root: None,
+        }
+    }
+}
+
+/// Outcome of building and testing one crate at one level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CellStatus {
+    /// Crate builds and its tests pass
+    Passed,
+    /// Crate builds; tests were not run
+    Built,
+    /// Crate builds but its tests fail
+    TestsFailed,
+    /// Crate does not build
+    BuildFailed,
+    /// Crate declares no feature for the level
+    Unsupported,
+}
+
+impl CellStatus {
+    /// Whether the cell counts as a failure
+    pub fn is_failure(self) -> bool {
+        matches!(self, CellStatus::TestsFailed | CellStatus::BuildFailed)
+    }
+
+    /// Short label used in the summary table
+    fn label(self) -> &'static str {
+        match self {
+            CellStatus::Passed => "pass",
+            CellStatus::Built => "build",
+            CellStatus::TestsFailed => "test-fail",
+            CellStatus::BuildFailed => "build-fail",
+            CellStatus::Unsupported => "-",
+        }
+    }
+}
+
+/// Result of one crate at one level
+#[derive(Debug, Clone, Serialize)]
+pub struct AsilCell {
+    /// Level the crate was built for
+    pub level: config::AsilLevel,
+    /// Outcome
+    pub status: CellStatus,
+    /// Time spent building and testing, in seconds
+    pub duration_secs: f64,
+    /// Tail of the failing command's stderr
+    pub error_output: Option<String>,
+}
+
+/// Results of one crate across all levels
+#[derive(Debug, Clone, Serialize)]
+pub struct AsilCrateRow {
+    /// Crate name
+    pub package: String,
+    /// One cell per level, in level order
+    pub cells: Vec<AsilCell>,
+}
+
+/// Results of the per-ASIL build matrix
+#[derive(Debug, Clone, Serialize)]
+pub struct AsilMatrixResults {
+    /// Levels built, lowest first
+    pub levels: Vec<config::AsilLevel>,
+    /// One row per crate
+    pub rows: Vec<AsilCrateRow>,
+    /// Timestamp of the run
+    pub timestamp: String,
+}
+
+impl AsilMatrixResults {
+    /// Whether no crate failed to build or test at any level
+    pub fn all_passed(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Crate and level of every failing cell
+    pub fn failures(&self) -> Vec<(&str, &AsilCell)> {
+        self.rows
+            .iter()
+            .flat_map(|row| {
+                row.cells
+                    .iter()
+                    .filter(|cell| cell.status.is_failure())
+                    .map(move |cell| (row.package.as_str(), cell))
+            })
+            .collect()
+    }
+
+    /// Summary table with one row per crate and one column per level
+    pub fn to_table(&self) -> String {
+        let width =
+            self.rows.iter().map(|row| row.package.len()).max().unwrap_or(0).max("crate".len());
+        let column = 10;
+
+        let mut table = format!("{:<width$}", "crate", width = width);
+        for level in &self.levels {
+            table.push_str(&format!(" {:>column$}", level.to_string(), column = column));
+        }
+        table.push('\n');
+        table.push_str(&"-".repeat(width + self.levels.len() * (column + 1)));
+        table.push('\n');
+
+        for row in &self.rows {
+            table.push_str(&format!("{:<width$}", row.package, width = width));
+            for cell in &row.cells {
+                table.push_str(&format!(" {:>column$}", cell.status.label(), column = column));
+            }
+            table.push('\n');
+        }
+        table
+    }
+}
+
+/// Builds and tests every crate under each ASIL feature
+///
+/// Each crate is built on its own with `-p`, so the features of other
+/// workspace members are not unified into it, and each level uses its own
+/// target directory, so artifacts of one level are never reused by another.
+/// A failing crate or level is recorded and the matrix moves on.
+pub struct AsilMatrix {
+    workspace_root: PathBuf,
+    options: AsilMatrixOptions,
+    verbose: bool,
+}
+
+impl AsilMatrix {
+    /// Create a matrix for the workspace at `workspace_root`
+    pub fn new(workspace_root: PathBuf, options: AsilMatrixOptions, verbose: bool) -> Self {
+        Self {
+            workspace_root,
+            options,
+            verbose,
+        }
+    }
+
+    /// Run every cell of the matrix
+    pub fn run(&self) -> BuildResult<AsilMatrixResults> {
+        println!("{} Starting per-ASIL build matrix", "🔍".bright_blue());
+
+        let features = self.asil_packages()?;
+        let packages: Vec<&String> = if self.options.packages.is_empty() {
+            features.keys().collect()
+        } else {
+            for package in &self.options.packages {
+                if !features.contains_key(package) {
+                    return Err(BuildError::Workspace(format!(
+                        "Package '{}' declares no ASIL features",
+                        package
+                    )));
+                }
+            }
+            self.options.packages.iter().collect()
+        };
+        let target_root = self
+            .options
+            .target_root
+            .clone()
+            .unwrap_or_else(|| self.workspace_root.join("target").join("asil-matrix"));
+
+        let mut rows = Vec::with_capacity(packages.len());
+        for package in packages {
+            println!("{} {}", "📦".bright_blue(), package);
+            let declared = &features[package];
+            let cells = self
+                .options
+                .levels
+                .iter()
+                .map(|&level| {
+                    let feature = asil_feature(level);
+                    if !declared.contains(feature) {
+                        return AsilCell {
+                            level,
+                            status: CellStatus::Unsupported,
+                            duration_secs: 0.0,
+                            error_output: None,
+                        };
+                    }
+                    self.run_cell(package, level, &target_root.join(feature))
+                })
+                .collect();
+            rows.push(AsilCrateRow {
+                package: package.clone(),
+                cells,
+            });
+        }
+
+        Ok(AsilMatrixResults {
+            levels: self.options.levels.clone(),
+            rows,
+            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+    }
+
+    /// Build and test one crate at one level
+    fn run_cell(&self, package: &str, level: config::AsilLevel, target_dir: &Path) -> AsilCell {
+        let start = Instant::now();
+        print!("  {}... ", level);
+        let _ = std::io::stdout().flush();
+
+        let feature = asil_feature(level);
+        let (status, error_output) = match self.cargo("build", package, feature, target_dir) {
+            Err(stderr) => (CellStatus::BuildFailed, Some(stderr)),
+            Ok(()) if !self.options.run_tests => (CellStatus::Built, None),
+            Ok(()) => match self.cargo("test", package, feature, target_dir) {
+                Ok(()) => (CellStatus::Passed, None),
+                Err(stderr) => (CellStatus::TestsFailed, Some(stderr)),
+            },
+        };
+
+        if status.is_failure() {
+            println!("{}", status.label().bright_red());
+            if self.verbose {
+                if let Some(stderr) = &error_output {
+                    println!("{}", stderr);
+                }
+            }
+        } else {
+            println!("{}", status.label().bright_green());
+        }
+
+        AsilCell {
+            level,
+            status,
+            duration_secs: start.elapsed().as_secs_f64(),
+            error_output,
+        }
+    }
+
+    /// Run a cargo subcommand for one crate, returning the tail of stderr on
+    /// failure
+    fn cargo(
+        &self,
+        subcommand: &str,
+        package: &str,
+        feature: &str,
+        target_dir: &Path,
+    ) -> Result<(), String> {
+        let output = Command::new("cargo")
+            .arg(subcommand)
+            .arg("-p")
+            .arg(package)
+            .arg("--features")
+            .arg(feature)
+            .arg("--target-dir")
+            .arg(target_dir)
+            .current_dir(&self.workspace_root)
+            .output()
+            .map_err(|e| format!("Failed to run cargo {}: {}", subcommand, e))?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().collect();
+        Err(lines[lines.len().saturating_sub(ERROR_TAIL_LINES)..].join("\n"))
+    }
+
+    /// ASIL features declared by each workspace crate
+    fn asil_packages(&self) -> BuildResult<BTreeMap<String, HashSet<String>>> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .current_dir(&self.workspace_root)
+            .output()
+            .map_err(|e| BuildError::Tool(format!("Failed to run cargo metadata: {}", e)))?;
+        if !output.status.success() {
+            return Err(BuildError::Tool(format!(
+                "cargo metadata failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| BuildError::Tool(format!("Failed to parse cargo metadata: {}", e)))?;
+        Ok(asil_features_from_metadata(&metadata))
+    }
+
+    /// Write the results as JSON and the summary table to `output_dir`
+    pub fn generate_report(
+        &self,
+        results: &AsilMatrixResults,
+        output_dir: &Path,
+    ) -> BuildResult<()> {
+        std::fs::create_dir_all(output_dir).map_err(|e| {
+            BuildError::Tool(format!("Failed to create {}: {}", output_dir.display(), e))
+        })?;
+        let json = serde_json::to_string_pretty(results)
+            .map_err(|e| BuildError::Tool(format!("Failed to serialize matrix: {}", e)))?;
+        for (name, contents) in
+            [("asil-matrix.json", json), ("asil-matrix.txt", results.to_table())]
+        {
+            let path = output_dir.join(name);
+            std::fs::write(&path, contents).map_err(|e| {
+                BuildError::Tool(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Print the summary table and every failure
+    pub fn print_summary(&self, results: &AsilMatrixResults) {
+        println!();
+        println!("{}", "=== ASIL Matrix Summary ===".bright_blue());
+        print!("{}", results.to_table());
+
+        let failures = results.failures();
+        if failures.is_empty() {
+            println!("{} All crates build and pass at every level!", "✅".bright_green());
+            return;
+        }
+        println!("{} {} failing cells:", "❌".bright_red(), failures.len());
+        for (package, cell) in failures {
+            println!("  - {} at {}: {}", package, cell.level, cell.status.label());
+        }
+    }
+}
+
+/// Lines of stderr kept for a failing cell
+const ERROR_TAIL_LINES: usize = 40;
+
+/// ASIL features of each workspace crate in `cargo metadata` output
+fn asil_features_from_metadata(
+    metadata: &serde_json::Value,
+) -> BTreeMap<String, HashSet<String>> {
+    let mut packages = BTreeMap::new();
+    for package in metadata["packages"].as_array().into_iter().flatten() {
+        let (Some(name), Some(features)) =
+            (package["name"].as_str(), package["features"].as_object())
+        else {
+            continue;
+        };
+        let declared: HashSet<String> = ASIL_FEATURES
+            .iter()
+            .map(|(_, feature)| *feature)
+            .filter(|feature| features.contains_key(*feature))
+            .map(str::to_string)
+            .collect();
+        if !declared.is_empty() {
+            packages.insert(name.to_string(), declared);
+        }
+    }
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_asil_levels() {
+        assert_eq!(parse_asil_levels("all").unwrap().len(), 5);
+        assert_eq!(
+            parse_asil_levels("d, b,asil-b").unwrap(),
+            vec![config::AsilLevel::B, config::AsilLevel::D]
+        );
+        assert!(parse_asil_levels("e").is_err());
+        assert!(parse_asil_levels("").is_err());
+    }
+
+    #[test]
+    fn test_asil_features_from_metadata() {
+        let metadata = serde_json::json!({
+            "packages": [
+                { "name": "wrt-math", "features": { "qm": [], "asil-d": [], "std": [] } },
+                { "name": "wrt-build-core", "features": { "std": [] } }
+            ]
+        });
+        let packages = asil_features_from_metadata(&metadata);
+        assert_eq!(packages.len(), 1);
+        assert!(packages["wrt-math"].contains("asil-d"));
+        assert!(!packages["wrt-math"].contains("asil-a"));
+    }
+
+    #[test]
+    fn test_asil_matrix_table() {
+        let cell = |level, status| AsilCell {
+            level,
+            status,
+            duration_secs: 0.0,
+            error_output: None,
+        };
+        let results = AsilMatrixResults {
+            levels: vec![config::AsilLevel::QM, config::AsilLevel::D],
+            rows: vec![AsilCrateRow {
+                package: "wrt-math".to_string(),
+                cells: vec![
+                    cell(config::AsilLevel::QM, CellStatus::Passed),
+                    cell(config::AsilLevel::D, CellStatus::BuildFailed),
+                ],
+            }],
+            timestamp: String::new(),
+        };
+
+        let table = results.to_table();
+        assert!(table.starts_with("crate   "));
+        assert!(table.contains("ASIL-D"));
+        assert!(table.lines().nth(2).unwrap().ends_with("build-fail"));
+        assert!(!results.all_passed());
+        assert_eq!(results.failures()[0].0, "wrt-math");
+    }
+}
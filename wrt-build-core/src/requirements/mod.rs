@@ -10,6 +10,7 @@
 pub mod model;
 pub mod platform;
 pub mod safety;
+pub mod traceability;
 
 // Export documentation verification framework
 pub use documentation::{
@@ -38,3 +39,7 @@
     FileCoverage, PlatformSummary, PlatformVerification, SafetyReport, SafetyVerificationFramework,
     TestCoverageType, TestResult, TestSummary, ViolationSeverity, ViolationType,
 };
+// Export annotation-based traceability
+pub use traceability::{
+    DeclaredRequirement, TraceEntry, TraceKind, TraceLink, TraceStatus, TraceabilityMatrix, TraceabilitySummary,
+};
@@ -0,0 +1,898 @@
+//! Software bill of materials and build provenance
+//!
+//! Resolves the workspace dependency graph with `cargo metadata` and emits it
+//! as CycloneDX 1.5 and SPDX 2.3 JSON documents, together with an in-toto
+//! statement carrying SLSA v1 provenance for release artifacts. Dependencies
+//! only used by tests and benchmarks (dev-dependencies) are not shipped and are
+//! left out. Crate checksums are taken from `Cargo.lock`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    build::BuildSystem,
+    build_cache,
+    error::{BuildError, BuildResult},
+};
+
+/// Source prefix of packages published on crates.io
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+/// Build type recorded in provenance statements
+const BUILD_TYPE: &str = "https://github.com/pulseengine/wrt/cargo-wrt/build@v1";
+
+/// SBOM document formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    CycloneDx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
+impl SbomFormat {
+    /// File name suffix of documents in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+        }
+    }
+}
+
+/// Package in the shipped dependency graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SbomPackage {
+    /// Cargo package ID
+    pub id: String,
+    /// Package name
+    pub name: String,
+    /// Package version
+    pub version: String,
+    /// Package source, `None` for local path packages
+    pub source: Option<String>,
+    /// Declared SPDX license expression
+    pub license: Option<String>,
+    /// SHA-256 of the published crate, from `Cargo.lock`
+    pub checksum: Option<String>,
+    /// Whether the package is a member of the workspace
+    pub workspace_member: bool,
+    /// IDs of the packages this one depends on
+    pub dependencies: Vec<String>,
+}
+
+impl SbomPackage {
+    /// Package URL of the package
+    pub fn purl(&self) -> String {
+        format!("pkg:cargo/{}@{}", self.name, self.version)
+    }
+
+    /// Download location of the package, if published on crates.io
+    fn download_location(&self) -> Option<String> {
+        (self.source.as_deref() == Some(CRATES_IO_SOURCE)).then(|| {
+            format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                self.name, self.version
+            )
+        })
+    }
+}
+
+/// Dependency graph of the workspace as shipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    /// Name of the workspace
+    pub name: String,
+    /// Version of the workspace
+    pub version: String,
+    /// Packages reachable from workspace members through normal and build
+    /// dependencies, sorted by name and version
+    pub packages: Vec<SbomPackage>,
+    /// Names of the binary targets built by workspace members
+    pub binaries: Vec<String>,
+    /// Cargo target directory
+    pub target_directory: PathBuf,
+}
+
+impl DependencyGraph {
+    /// Build the graph from `cargo metadata` output and `Cargo.lock` contents
+    pub fn from_metadata(metadata: &Value, lockfile: Option<&str>) -> BuildResult<Self> {
+        let invalid = |what: &str| BuildError::Tool(format!("Invalid cargo metadata: {}", what));
+
+        let packages = metadata["packages"].as_array().ok_or_else(|| invalid("packages"))?;
+        let members: BTreeSet<&str> = metadata["workspace_members"]
+            .as_array()
+            .ok_or_else(|| invalid("workspace_members"))?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        let nodes = metadata["resolve"]["nodes"].as_array().ok_or_else(|| invalid("resolve"))?;
+        let checksums = lockfile.map(lockfile_checksums).transpose()?.unwrap_or_default();
+
+        // Edges that end up in the shipped artifacts
+        let mut edges: HashMap<&str, Vec<String>> = HashMap::new();
+        for node in nodes {
+            let id = node["id"].as_str().ok_or_else(|| invalid("node id"))?;
+            let deps = node["deps"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let shipped = deps
+                .iter()
+                .filter(|dep| {
+                    dep["dep_kinds"].as_array().is_some_and(|kinds| {
+                        kinds.iter().any(|kind| kind["kind"].as_str() != Some("dev"))
+                    })
+                })
+                .filter_map(|dep| dep["pkg"].as_str().map(str::to_string))
+                .collect();
+            edges.insert(id, shipped);
+        }
+
+        let mut reachable: BTreeSet<&str> = members.clone();
+        let mut queue: VecDeque<&str> = members.iter().copied().collect();
+        while let Some(id) = queue.pop_front() {
+            for dep in edges.get(id).into_iter().flatten() {
+                if reachable.insert(dep.as_str()) {
+                    queue.push_back(dep.as_str());
+                }
+            }
+        }
+
+        let mut graph_packages = Vec::new();
+        let mut binaries = BTreeSet::new();
+        let mut version = None;
+        let mut repository = None;
+        for package in packages {
+            let id = package["id"].as_str().ok_or_else(|| invalid("package id"))?;
+            if !reachable.contains(id) {
+                continue;
+            }
+            let name = package["name"].as_str().ok_or_else(|| invalid("package name"))?;
+            let package_version =
+                package["version"].as_str().ok_or_else(|| invalid("package version"))?;
+            let workspace_member = members.contains(id);
+
+            if workspace_member {
+                version.get_or_insert_with(|| package_version.to_string());
+                if let Some(url) = package["repository"].as_str() {
+                    repository.get_or_insert(url);
+                }
+                for target in package["targets"].as_array().into_iter().flatten() {
+                    let is_bin = target["kind"]
+                        .as_array()
+                        .is_some_and(|kinds| kinds.iter().any(|k| k.as_str() == Some("bin")));
+                    if let (true, Some(name)) = (is_bin, target["name"].as_str()) {
+                        binaries.insert(name.to_string());
+                    }
+                }
+            }
+
+            let mut dependencies = edges.get(id).cloned().unwrap_or_default();
+            dependencies.sort();
+            dependencies.dedup();
+
+            graph_packages.push(SbomPackage {
+                id: id.to_string(),
+                name: name.to_string(),
+                version: package_version.to_string(),
+                source: package["source"].as_str().map(str::to_string),
+                license: package["license"].as_str().map(str::to_string),
+                checksum: checksums
+                    .get(&(name.to_string(), package_version.to_string()))
+                    .cloned(),
+                workspace_member,
+                dependencies,
+            });
+        }
+        graph_packages
+            .sort_by(|a, b| (&a.name, &a.version, &a.id).cmp(&(&b.name, &b.version, &b.id)));
+
+        // Name the workspace after its repository, falling back to the
+        // checkout directory
+        let workspace_root = PathBuf::from(metadata["workspace_root"].as_str().unwrap_or_default());
+        let name = repository
+            .and_then(|url| url.trim_end_matches('/').trim_end_matches(".git").rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .or_else(|| workspace_root.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "workspace".to_string());
+        let target_directory = metadata["target_directory"]
+            .as_str()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| workspace_root.join("target"));
+
+        Ok(Self {
+            name,
+            version: version.unwrap_or_else(|| "0.0.0".to_string()),
+            packages: graph_packages,
+            binaries: binaries.into_iter().collect(),
+            target_directory,
+        })
+    }
+
+    /// Packages without a declared license
+    pub fn unlicensed(&self) -> Vec<&SbomPackage> {
+        self.packages.iter().filter(|package| package.license.is_none()).collect()
+    }
+
+    /// Deterministic serial number derived from the graph contents
+    fn serial_number(&self) -> String {
+        let mut hasher = Sha256::new();
+        for package in &self.packages {
+            hasher.update(package.id.as_bytes());
+            hasher.update(b"\0");
+        }
+        let digest = hasher.finalize();
+        let hex = hex_string(&digest[..16]);
+        // Version 4 / RFC 4122 variant layout so the value parses as a UUID
+        format!(
+            "{}-{}-4{}-8{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[13..16],
+            &hex[17..20],
+            &hex[20..32]
+        )
+    }
+
+    /// Render the graph as a CycloneDX 1.5 document
+    pub fn to_cyclonedx(&self, timestamp: &str) -> Value {
+        let root_ref = format!("{}@{}", self.name, self.version);
+
+        let components: Vec<Value> = self
+            .packages
+            .iter()
+            .map(|package| {
+                let mut component = json!({
+                    "type": "library",
+                    "bom-ref": package.id,
+                    "name": package.name,
+                    "version": package.version,
+                    "purl": package.purl(),
+                });
+                if let Some(license) = &package.license {
+                    component["licenses"] = json!([{ "expression": license }]);
+                }
+                if let Some(checksum) = &package.checksum {
+                    component["hashes"] = json!([{ "alg": "SHA-256", "content": checksum }]);
+                }
+                component
+            })
+            .collect();
+
+        let members: Vec<&str> = self
+            .packages
+            .iter()
+            .filter(|package| package.workspace_member)
+            .map(|package| package.id.as_str())
+            .collect();
+        let mut dependencies = vec![json!({ "ref": root_ref, "dependsOn": members })];
+        dependencies.extend(self.packages.iter().map(|package| {
+            json!({ "ref": package.id, "dependsOn": package.dependencies })
+        }));
+
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "serialNumber": format!("urn:uuid:{}", self.serial_number()),
+            "version": 1,
+            "metadata": {
+                "timestamp": timestamp,
+                "tools": {
+                    "components": [{
+                        "type": "application",
+                        "name": "cargo-wrt",
+                        "version": crate::VERSION,
+                    }],
+                },
+                "component": {
+                    "type": "application",
+                    "bom-ref": root_ref,
+                    "name": self.name,
+                    "version": self.version,
+                },
+            },
+            "components": components,
+            "dependencies": dependencies,
+        })
+    }
+
+    /// Render the graph as an SPDX 2.3 document
+    pub fn to_spdx(&self, timestamp: &str) -> Value {
+        let root_id = "SPDXRef-Package-workspace".to_string();
+        let mut used = BTreeSet::from([root_id.clone()]);
+        let spdx_ids: HashMap<&str, String> = self
+            .packages
+            .iter()
+            .map(|package| {
+                let base = format!(
+                    "SPDXRef-Package-{}",
+                    spdx_id_fragment(&format!("{}-{}", package.name, package.version))
+                );
+                let mut id = base.clone();
+                let mut suffix = 1;
+                while !used.insert(id.clone()) {
+                    suffix += 1;
+                    id = format!("{}-{}", base, suffix);
+                }
+                (package.id.as_str(), id)
+            })
+            .collect();
+
+        let mut packages = vec![json!({
+            "SPDXID": root_id,
+            "name": self.name,
+            "versionInfo": self.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "copyrightText": "NOASSERTION",
+        })];
+        let mut relationships = vec![json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": root_id,
+        })];
+
+        for package in &self.packages {
+            let spdx_id = &spdx_ids[package.id.as_str()];
+            let mut entry = json!({
+                "SPDXID": spdx_id,
+                "name": package.name,
+                "versionInfo": package.version,
+                "downloadLocation": package
+                    .download_location()
+                    .unwrap_or_else(|| "NOASSERTION".to_string()),
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": package.license.as_deref().unwrap_or("NOASSERTION"),
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": package.purl(),
+                }],
+            });
+            if let Some(checksum) = &package.checksum {
+                entry["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": checksum }]);
+            }
+            packages.push(entry);
+
+            if package.workspace_member {
+                relationships.push(json!({
+                    "spdxElementId": root_id,
+                    "relationshipType": "CONTAINS",
+                    "relatedSpdxElement": spdx_id,
+                }));
+            }
+            for dep in &package.dependencies {
+                if let Some(dep_id) = spdx_ids.get(dep.as_str()) {
+                    relationships.push(json!({
+                        "spdxElementId": spdx_id,
+                        "relationshipType": "DEPENDS_ON",
+                        "relatedSpdxElement": dep_id,
+                    }));
+                }
+            }
+        }
+
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": format!("{}-{}", self.name, self.version),
+            "documentNamespace": format!(
+                "https://spdx.org/spdxdocs/{}-{}-{}",
+                self.name,
+                self.version,
+                self.serial_number()
+            ),
+            "creationInfo": {
+                "created": timestamp,
+                "creators": [format!("Tool: cargo-wrt-{}", crate::VERSION)],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+}
+
+/// Inputs and environment of a build, recorded in provenance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Source repository URL
+    pub repository: Option<String>,
+    /// Commit the artifacts were built from
+    pub commit: Option<String>,
+    /// SHA-256 of `Cargo.lock`
+    pub lockfile_sha256: Option<String>,
+    /// Output of `rustc -vV`
+    pub toolchain: String,
+    /// Enabled features
+    pub features: Vec<String>,
+    /// Build profile
+    pub profile: String,
+    /// When provenance generation started
+    pub started_on: String,
+    /// When provenance generation finished
+    pub finished_on: String,
+}
+
+/// Release artifact described by a provenance statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceSubject {
+    /// Artifact file name
+    pub name: String,
+    /// SHA-256 of the artifact
+    pub sha256: String,
+}
+
+impl ProvenanceSubject {
+    /// Describe the artifact at `path`
+    pub fn from_file(path: &Path) -> BuildResult<Self> {
+        let content = fs::read(path).map_err(|e| {
+            BuildError::Tool(format!("Failed to read artifact {}: {}", path.display(), e))
+        })?;
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            sha256: sha256_hex(&content),
+        })
+    }
+}
+
+/// In-toto statement carrying SLSA v1 provenance for `subjects`
+pub fn provenance_statement(subjects: &[ProvenanceSubject], build: &BuildInfo) -> Value {
+    let subjects: Vec<Value> = subjects
+        .iter()
+        .map(|subject| json!({ "name": subject.name, "digest": { "sha256": subject.sha256 } }))
+        .collect();
+
+    let mut dependencies = Vec::new();
+    if let (Some(repository), Some(commit)) = (&build.repository, &build.commit) {
+        dependencies.push(json!({
+            "uri": format!("git+{}@{}", repository, commit),
+            "digest": { "gitCommit": commit },
+        }));
+    }
+    if let Some(lockfile) = &build.lockfile_sha256 {
+        dependencies.push(json!({ "name": "Cargo.lock", "digest": { "sha256": lockfile } }));
+    }
+
+    json!({
+        "_type": "https://in-toto.io/Statement/v1",
+        "subject": subjects,
+        "predicateType": "https://slsa.dev/provenance/v1",
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": {
+                    "features": build.features,
+                    "profile": build.profile,
+                },
+                "internalParameters": {
+                    "toolchain": build.toolchain,
+                },
+                "resolvedDependencies": dependencies,
+            },
+            "runDetails": {
+                "builder": { "id": format!("cargo-wrt@{}", crate::VERSION) },
+                "metadata": {
+                    "startedOn": build.started_on,
+                    "finishedOn": build.finished_on,
+                },
+            },
+        },
+    })
+}
+
+/// SBOM generation options
+#[derive(Debug, Clone)]
+pub struct SbomOptions {
+    /// Document formats to write
+    pub formats: Vec<SbomFormat>,
+    /// Output directory, defaults to `<target>/sbom`
+    pub output_dir: Option<PathBuf>,
+    /// Write a provenance statement for the release artifacts
+    pub provenance: bool,
+    /// Release artifacts; defaults to the workspace binaries in
+    /// `<target>/release`
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl Default for SbomOptions {
+    fn default() -> Self {
+        Self {
+            formats: vec![SbomFormat::CycloneDx, SbomFormat::Spdx],
+            output_dir: None,
+            provenance: true,
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+/// Files written by SBOM generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SbomResults {
+    /// SBOM documents
+    pub documents: Vec<PathBuf>,
+    /// Provenance statement, if any artifacts were found
+    pub provenance: Option<PathBuf>,
+    /// Number of packages in the dependency graph
+    pub packages: usize,
+    /// Packages without a declared license, as `name@version`
+    pub unlicensed: Vec<String>,
+    /// Artifacts covered by the provenance statement
+    pub subjects: Vec<ProvenanceSubject>,
+}
+
+impl SbomResults {
+    /// Markdown section for the verification report
+    pub fn report_section(&self) -> String {
+        let mut section = String::from("## Software Bill of Materials\n\n");
+        section.push_str(&format!("- **Packages:** {}\n", self.packages));
+        for document in &self.documents {
+            section.push_str(&format!("- **Document:** {}\n", document.display()));
+        }
+        match &self.provenance {
+            Some(path) => section.push_str(&format!(
+                "- **Provenance:** {} ({} artifacts)\n",
+                path.display(),
+                self.subjects.len()
+            )),
+            None => section.push_str("- **Provenance:** no release artifacts found\n"),
+        }
+        if !self.unlicensed.is_empty() {
+            section.push_str(&format!(
+                "- **Without declared license:** {}\n",
+                self.unlicensed.join(", ")
+            ));
+        }
+        section.push('\n');
+        section
+    }
+}
+
+impl BuildSystem {
+    /// Resolve the shipped dependency graph of the workspace
+    pub fn dependency_graph(&self) -> BuildResult<DependencyGraph> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--locked"])
+            .current_dir(&self.workspace.root)
+            .output()
+            .map_err(|e| BuildError::Tool(format!("Failed to run cargo metadata: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(BuildError::Tool(format!(
+                "cargo metadata failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let metadata: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| BuildError::Tool(format!("Failed to parse cargo metadata: {}", e)))?;
+        let lockfile = fs::read_to_string(self.workspace.root.join("Cargo.lock")).ok();
+
+        DependencyGraph::from_metadata(&metadata, lockfile.as_deref())
+    }
+
+    /// Write SBOM documents and release provenance
+    pub fn generate_sbom(&self, options: &SbomOptions) -> BuildResult<SbomResults> {
+        println!("{} Generating software bill of materials...", "📦".bright_blue());
+
+        let started_on = timestamp();
+        let graph = self.dependency_graph()?;
+        let output_dir =
+            options.output_dir.clone().unwrap_or_else(|| graph.target_directory.join("sbom"));
+        fs::create_dir_all(&output_dir).map_err(|e| {
+            BuildError::Tool(format!("Failed to create {}: {}", output_dir.display(), e))
+        })?;
+        let stem = format!("{}-{}", graph.name, graph.version);
+
+        let mut documents = Vec::new();
+        for format in &options.formats {
+            let document = match format {
+                SbomFormat::CycloneDx => graph.to_cyclonedx(&started_on),
+                SbomFormat::Spdx => graph.to_spdx(&started_on),
+            };
+            let path = output_dir.join(format!("{}.{}", stem, format.extension()));
+            write_json(&path, &document, true)?;
+            documents.push(path);
+        }
+
+        let mut subjects = Vec::new();
+        let mut provenance = None;
+        if options.provenance {
+            let artifacts = if options.artifacts.is_empty() {
+                graph
+                    .binaries
+                    .iter()
+                    .map(|name| {
+                        graph
+                            .target_directory
+                            .join("release")
+                            .join(format!("{}{}", name, std::env::consts::EXE_SUFFIX))
+                    })
+                    .filter(|path| path.is_file())
+                    .collect()
+            } else {
+                options.artifacts.clone()
+            };
+            subjects = artifacts
+                .iter()
+                .map(|path| ProvenanceSubject::from_file(path))
+                .collect::<BuildResult<Vec<_>>>()?;
+
+            if subjects.is_empty() {
+                println!(
+                    "  {} No release artifacts found, skipping provenance (run a release \
+                     build or pass --artifact)",
+                    "⚠️".bright_yellow()
+                );
+            } else {
+                let build = self.build_info(started_on.clone())?;
+                let path = output_dir.join(format!("{}.intoto.jsonl", stem));
+                write_json(&path, &provenance_statement(&subjects, &build), false)?;
+                provenance = Some(path);
+            }
+        }
+
+        let unlicensed = graph
+            .unlicensed()
+            .iter()
+            .map(|package| format!("{}@{}", package.name, package.version))
+            .collect();
+
+        println!(
+            "{} SBOM covers {} packages, written to {}",
+            "✅".bright_green(),
+            graph.packages.len(),
+            output_dir.display()
+        );
+
+        Ok(SbomResults {
+            documents,
+            provenance,
+            packages: graph.packages.len(),
+            unlicensed,
+            subjects,
+        })
+    }
+
+    /// Collect the build inputs recorded in provenance
+    fn build_info(&self, started_on: String) -> BuildResult<BuildInfo> {
+        let root = &self.workspace.root;
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Ok(BuildInfo {
+            repository: git(&["config", "--get", "remote.origin.url"]),
+            commit: git(&["rev-parse", "HEAD"]),
+            lockfile_sha256: fs::read(root.join("Cargo.lock")).ok().map(|lock| sha256_hex(&lock)),
+            toolchain: build_cache::toolchain_id(root)?,
+            features: self.config.features.clone(),
+            profile: format!("{:?}", self.config.profile),
+            started_on,
+            finished_on: timestamp(),
+        })
+    }
+}
+
+/// Checksums of registry packages in `Cargo.lock`, keyed by name and version
+fn lockfile_checksums(lockfile: &str) -> BuildResult<BTreeMap<(String, String), String>> {
+    #[derive(Deserialize)]
+    struct LockPackage {
+        name: String,
+        version: String,
+        checksum: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct Lockfile {
+        #[serde(default)]
+        package: Vec<LockPackage>,
+    }
+
+    let lock: Lockfile = toml::from_str(lockfile)
+        .map_err(|e| BuildError::Tool(format!("Failed to parse Cargo.lock: {}", e)))?;
+    Ok(lock
+        .package
+        .into_iter()
+        .filter_map(|package| Some(((package.name, package.version), package.checksum?)))
+        .collect())
+}
+
+/// Replace characters not allowed in SPDX identifiers
+fn spdx_id_fragment(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+        .collect()
+}
+
+/// Current time in RFC 3339 format, as used by SBOM and provenance documents
+fn timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Lowercase hex SHA-256 of `data`
+fn sha256_hex(data: &[u8]) -> String {
+    hex_string(&Sha256::digest(data))
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Write a JSON document, pretty-printed or on a single line
+fn write_json(path: &Path, value: &Value, pretty: bool) -> BuildResult<()> {
+    let mut content = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+    .map_err(|e| BuildError::Tool(format!("Failed to serialize {}: {}", path.display(), e)))?;
+    content.push('\n');
+    fs::write(path, content)
+        .map_err(|e| BuildError::Tool(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WRTD: &str = "path+file:///src/wrt/wrtd#0.2.0";
+    const WRT: &str = "path+file:///src/wrt/wrt#0.2.0";
+    const LOG: &str = "registry+https://github.com/rust-lang/crates.io-index#log@0.4.22";
+    const PROPTEST: &str = "registry+https://github.com/rust-lang/crates.io-index#proptest@1.5.0";
+
+    fn metadata() -> Value {
+        let dep = |pkg: &str, kind: Option<&str>| {
+            json!({ "pkg": pkg, "dep_kinds": [{ "kind": kind, "target": null }] })
+        };
+        json!({
+            "workspace_root": "/src/checkout",
+            "target_directory": "/src/wrt/target",
+            "workspace_members": [WRTD, WRT],
+            "packages": [
+                {
+                    "id": WRTD, "name": "wrtd", "version": "0.2.0",
+                    "source": null, "license": "MIT",
+                    "targets": [{ "kind": ["bin"], "name": "wrtd" }],
+                },
+                {
+                    "id": WRT, "name": "wrt", "version": "0.2.0",
+                    "source": null, "license": "MIT",
+                    "repository": "https://github.com/pulseengine/wrt.git",
+                    "targets": [{ "kind": ["lib"], "name": "wrt" }],
+                },
+                {
+                    "id": LOG,
+                    "name": "log", "version": "0.4.22", "source": CRATES_IO_SOURCE,
+                    "license": "MIT OR Apache-2.0", "targets": [],
+                },
+                {
+                    "id": PROPTEST,
+                    "name": "proptest", "version": "1.5.0", "source": CRATES_IO_SOURCE,
+                    "license": null, "targets": [],
+                },
+            ],
+            "resolve": {
+                "root": null,
+                "nodes": [
+                    { "id": WRTD, "deps": [dep(WRT, None)] },
+                    { "id": WRT, "deps": [dep(LOG, None), dep(PROPTEST, Some("dev"))] },
+                    { "id": LOG, "deps": [] },
+                    { "id": PROPTEST, "deps": [] },
+                ],
+            },
+        })
+    }
+
+    const LOCKFILE: &str = r#"
+version = 4
+
+[[package]]
+name = "log"
+version = "0.4.22"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "a7a70ba024b9dc04c27ea2f0c0548feb474ec5c54bba33a7f72f873a39d07b24"
+
+[[package]]
+name = "wrt"
+version = "0.2.0"
+"#;
+
+    #[test]
+    fn test_dependency_graph_excludes_dev_dependencies() -> BuildResult<()> {
+        let graph = DependencyGraph::from_metadata(&metadata(), Some(LOCKFILE))?;
+
+        let names: Vec<&str> = graph.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["log", "wrt", "wrtd"]);
+        assert_eq!(graph.name, "wrt");
+        assert_eq!(graph.version, "0.2.0");
+        assert_eq!(graph.binaries, ["wrtd"]);
+        assert!(graph.unlicensed().is_empty());
+
+        let log = &graph.packages[0];
+        assert!(!log.workspace_member);
+        assert_eq!(log.purl(), "pkg:cargo/log@0.4.22");
+        assert_eq!(
+            log.checksum.as_deref(),
+            Some("a7a70ba024b9dc04c27ea2f0c0548feb474ec5c54bba33a7f72f873a39d07b24")
+        );
+        assert_eq!(graph.packages[1].dependencies, [log.id.clone()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sbom_documents() -> BuildResult<()> {
+        let graph = DependencyGraph::from_metadata(&metadata(), Some(LOCKFILE))?;
+
+        let cdx = graph.to_cyclonedx("2025-01-01T00:00:00Z");
+        assert_eq!(cdx["specVersion"], "1.5");
+        assert_eq!(cdx["components"].as_array().map(Vec::len), Some(3));
+        assert_eq!(cdx["components"][0]["hashes"][0]["alg"], "SHA-256");
+        assert_eq!(cdx["dependencies"][0]["dependsOn"].as_array().map(Vec::len), Some(2));
+        assert_eq!(cdx["serialNumber"], graph.to_cyclonedx("later")["serialNumber"]);
+
+        let spdx = graph.to_spdx("2025-01-01T00:00:00Z");
+        assert_eq!(spdx["spdxVersion"], "SPDX-2.3");
+        assert_eq!(spdx["packages"][1]["SPDXID"], "SPDXRef-Package-log-0.4.22");
+        assert_eq!(
+            spdx["packages"][1]["downloadLocation"],
+            "https://crates.io/api/v1/crates/log/0.4.22/download"
+        );
+        let depends_on = spdx["relationships"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|r| r["relationshipType"] == "DEPENDS_ON")
+            .count();
+        assert_eq!(depends_on, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_provenance_statement() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let artifact = temp_dir.path().join("wrtd");
+        fs::write(&artifact, b"abc")?;
+        let subject = ProvenanceSubject::from_file(&artifact)?;
+        assert_eq!(
+            subject.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let build = BuildInfo {
+            repository: Some("https://github.com/pulseengine/wrt".to_string()),
+            commit: Some("0123abc".to_string()),
+            ..BuildInfo::default()
+        };
+        let statement = provenance_statement(&[subject], &build);
+        assert_eq!(statement["predicateType"], "https://slsa.dev/provenance/v1");
+        assert_eq!(statement["subject"][0]["name"], "wrtd");
+        assert_eq!(
+            statement["predicate"]["buildDefinition"]["resolvedDependencies"][0]["uri"],
+            "git+https://github.com/pulseengine/wrt@0123abc"
+        );
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@
 use std::{path::Path, process::Command};
 
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     build::BuildSystem,
@@ -12,7 +13,7 @@
 };
 
 /// Test execution results
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TestResults {
     /// Whether all tests passed
     pub success: bool,
@@ -174,6 +175,16 @@ pub fn run_tests_with_diagnostics(
 
     /// Run tests with specific options
     pub fn run_tests_with_options(&self, options: &TestOptions) -> BuildResult<TestResults> {
+        self.run_cached_step(
+            "test",
+            &format!("{:?}", options),
+            || self.run_tests_uncached(options),
+            |r| r.success,
+        )
+    }
+
+    /// Run tests without consulting the step cache
+    fn run_tests_uncached(&self, options: &TestOptions) -> BuildResult<TestResults> {
         println!("{} Running WRT test suite...", "🧪".bright_blue());
 
         let start_time = std::time::Instant::now();
@@ -201,21 +201,7 @@ pub fn audit_crate_documentation(&self) -> BuildResult<ValidationResults> {
         let start = std::time::Instant::now();
 
         // Find all crate directories
-        let mut crates = Vec::new();
-        for entry in fs::read_dir(&self.workspace_root)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                let cargo_toml = path.join("Cargo.toml");
-                if cargo_toml.exists() {
-                    // Check if it's a crate (has [package] section)
-                    let content = fs::read_to_string(&cargo_toml)?;
-                    if content.contains("[package]") {
-                        crates.push(path);
-                    }
-                }
-            }
-        }
+        let crates = self.find_package_dirs()?;
 
         println!("    Found {} crates to audit", crates.len());
 
@@ -373,6 +359,129 @@ pub fn audit_crate_documentation(&self) -> BuildResult<ValidationResults> {
         Ok(results)
     }
 
+    /// Check that crates using `wrt-prelude` follow its re-export policy
+    ///
+    /// In those crates a glob re-export (`pub use path::*;`) is only allowed
+    /// for:
+    /// - one `wrt_prelude` tier per file
+    /// - the crate's own prelude, re-exported from `src/lib.rs`
+    /// - a child module declared in the same file
+    ///
+    /// A crate with its own `src/prelude.rs` that does not build on
+    /// `wrt-prelude` is an error, unless `wrt-prelude` itself depends on it.
+    pub fn check_glob_reexports(&self) -> BuildResult<ValidationResults> {
+        println!(
+            "{} Checking glob re-exports against the prelude policy...",
+            "🔍".bright_blue()
+        );
+
+        let mut errors = Vec::new();
+
+        // Dependencies of wrt-prelude cannot use it themselves
+        let prelude_dependencies = self.prelude_dependencies()?;
+
+        for crate_path in self.find_package_dirs()? {
+            let crate_name = package_dir_name(&crate_path)?;
+            if crate_name == "wrt-prelude"
+                || prelude_dependencies.iter().any(|dep| dep == crate_name)
+            {
+                continue;
+            }
+
+            let manifest = fs::read_to_string(crate_path.join("Cargo.toml"))?;
+            let uses_prelude = dependency_names(&manifest).any(|dep| dep == "wrt-prelude");
+            let src_dir = crate_path.join("src");
+            if !src_dir.exists() {
+                continue;
+            }
+            if !uses_prelude {
+                let prelude = src_dir.join("prelude.rs");
+                if prelude.exists() {
+                    errors.push(ValidationError::new(
+                        "prelude-migration",
+                        prelude,
+                        "crate prelude does not build on a wrt_prelude tier".to_string(),
+                    ));
+                }
+                continue;
+            }
+
+            let mut files = Vec::new();
+            collect_rust_files(&src_dir, &mut files)?;
+            for file in files {
+                let content = fs::read_to_string(&file)?;
+                let is_crate_root = file == src_dir.join("lib.rs");
+                for message in glob_reexport_violations(&content, is_crate_root) {
+                    errors.push(ValidationError::new("glob-reexport", file.clone(), message));
+                }
+            }
+        }
+
+        let success = errors.is_empty();
+
+        if success {
+            println!("{} All crate preludes follow the re-export policy", "✅".bright_green());
+        } else {
+            println!(
+                "{} Found {} re-export policy violations",
+                "❌".bright_red(),
+                errors.len()
+            );
+            for error in &errors {
+                println!("  - {}: {}", error.file.display(), error.message);
+            }
+        }
+
+        Ok(ValidationResults {
+            success,
+            errors,
+            warnings: Vec::new(),
+            duration: std::time::Duration::default(),
+        })
+    }
+
+    /// Workspace crates `wrt-prelude` depends on, directly or transitively
+    fn prelude_dependencies(&self) -> BuildResult<Vec<String>> {
+        let mut dependencies = Vec::new();
+        let mut pending = vec!["wrt-prelude".to_string()];
+        while let Some(name) = pending.pop() {
+            let manifest_path = self.workspace_root.join(&name).join("Cargo.toml");
+            if !manifest_path.exists() {
+                continue;
+            }
+            let manifest = fs::read_to_string(&manifest_path)?;
+            for dep in dependency_names(&manifest) {
+                if dep.starts_with("wrt-") && !dependencies.iter().any(|known| known == dep) {
+                    dependencies.push(dep.to_string());
+                    pending.push(dep.to_string());
+                }
+            }
+        }
+        Ok(dependencies)
+    }
+
+    /// Find all crate directories (with a `[package]` section) in the
+    /// workspace root
+    fn find_package_dirs(&self) -> BuildResult<Vec<PathBuf>> {
+        let mut crates = Vec::new();
+        for entry in fs::read_dir(&self.workspace_root)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                let cargo_toml = path.join("Cargo.toml");
+                if cargo_toml.exists() {
+                    // Check if it's a crate (has [package] section)
+                    let content = fs::read_to_string(&cargo_toml)?;
+                    if content.contains("[package]") {
+                        crates.push(path);
+                    }
+                }
+            }
+        }
+        crates.sort();
+        Ok(crates)
+    }
+
     /// Find all workspace crates
     fn find_workspace_crates(&self) -> BuildResult<Vec<PathBuf>> {
         let mut crates = vec![self.workspace_root.clone()];
@@ -538,6 +647,90 @@ fn check_directory_documentation(
     }
 }
 
+/// Name of a crate directory found by `find_package_dirs`
+fn package_dir_name(path: &Path) -> BuildResult<&str> {
+    path.file_name().and_then(|name| name.to_str()).ok_or_else(|| {
+        BuildError::Workspace(format!("Invalid crate directory name: {}", path.display()))
+    })
+}
+
+/// Names of the crates listed in a manifest's `[dependencies]` table
+fn dependency_names(manifest: &str) -> impl Iterator<Item = &str> {
+    let mut in_dependencies = false;
+    manifest.lines().filter_map(move |line| {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_dependencies = line == "[dependencies]";
+            return None;
+        }
+        if !in_dependencies || line.starts_with('#') {
+            return None;
+        }
+        let name = line.split(['=', '.', ' ']).next()?;
+        (!name.is_empty()).then_some(name)
+    })
+}
+
+/// Recursively collect the `.rs` files below `dir`
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> BuildResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rust_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Glob re-exports in `content` that break the prelude policy
+fn glob_reexport_violations(content: &str, is_crate_root: bool) -> Vec<String> {
+    let declares_module = |name: &str| {
+        content.lines().any(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix("pub ").or_else(|| line.strip_prefix("pub(crate) ")).unwrap_or(line);
+            line.strip_prefix("mod ")
+                .and_then(|rest| rest.strip_prefix(name))
+                .is_some_and(|rest| rest.starts_with(';') || rest.trim_start().starts_with('{'))
+        })
+    };
+
+    let mut violations = Vec::new();
+    let mut tiers = 0;
+    for line in content.lines() {
+        let Some(path) = line
+            .trim()
+            .strip_prefix("pub use ")
+            .and_then(|rest| rest.split(';').next())
+            .and_then(|rest| rest.trim().strip_suffix("::*"))
+        else {
+            continue;
+        };
+
+        let allowed = if path.starts_with("wrt_prelude::") {
+            tiers += 1;
+            if tiers > 1 {
+                violations.push(format!("more than one wrt_prelude tier re-exported (`{}::*`)", path));
+            }
+            true
+        } else if path == "prelude" || path == "crate::prelude" {
+            is_crate_root
+        } else {
+            let first = path.strip_prefix("self::").unwrap_or(path);
+            !first.contains("::") && declares_module(first)
+        };
+
+        if !allowed {
+            violations.push(format!(
+                "ad-hoc glob re-export `pub use {}::*;`; re-export items by name",
+                path
+            ));
+        }
+    }
+    violations
+}
+
 /// Run all validation checks
 pub fn run_all_validations(workspace_root: &Path, verbose: bool) -> BuildResult<bool> {
     let validator = CodeValidator::new(workspace_root.to_path_buf(), verbose);
@@ -571,6 +764,14 @@ pub fn run_all_validations(workspace_root: &Path, verbose: bool) -> BuildResult<
 
     println!();
 
+    // Check glob re-exports against the prelude policy
+    let reexport_result = validator.check_glob_reexports()?;
+    if !reexport_result.success {
+        all_passed = false;
+    }
+
+    println!();
+
     if all_passed {
         println!("{} All validation checks passed!", "✅".bright_green());
     } else {
@@ -579,3 +780,42 @@ pub fn run_all_validations(workspace_root: &Path, verbose: bool) -> BuildResult<
 
     Ok(all_passed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_reexport_policy() {
+        let prelude = "pub use wrt_prelude::host::*;\npub use wrt_foundation::prelude::*;\n";
+        let violations = glob_reexport_violations(prelude, false);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("wrt_foundation::prelude"));
+
+        let tiers = "pub use wrt_prelude::core::*;\npub use wrt_prelude::runtime::*;\n";
+        assert_eq!(glob_reexport_violations(tiers, false).len(), 1);
+
+        let lib = "pub mod prelude;\npub mod ops;\npub use prelude::*;\npub use ops::*;\n";
+        assert!(glob_reexport_violations(lib, true).is_empty());
+        assert_eq!(glob_reexport_violations("pub use crate::prelude::*;", false).len(), 1);
+    }
+
+    #[test]
+    fn test_dependency_names() {
+        let manifest = r#"
+[package]
+name = "wrt-host"
+
+[dependencies]
+# Core
+wrt-error = { workspace = true }
+wrt-prelude.workspace = true
+log = "0.4"
+
+[dev-dependencies]
+wrt-test = { path = "../wrt-test" }
+"#;
+        let names: Vec<_> = dependency_names(manifest).collect();
+        assert_eq!(names, ["wrt-error", "wrt-prelude", "log"]);
+    }
+}
@@ -8,13 +8,16 @@
 
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use wrt_decoder::ComplexityPolicy;
 
 use crate::{
     build::BuildSystem,
     config::AsilLevel,
     diagnostics::{Diagnostic, DiagnosticCollection, Position, Range, Severity, ToolOutputParser},
     error::{BuildError, BuildResult},
+    kani::{KaniConfig, KaniVerifier, is_kani_available},
     parsers::{CargoAuditOutputParser, CargoOutputParser, KaniOutputParser, MiriOutputParser},
+    sbom::SbomOptions,
     text_search::{SearchMatch, TextSearcher, count_production_matches},
 };
 
@@ -72,7 +75,7 @@ pub fn is_allowed(&self, file_path: &Path, line_number: usize) -> Option<&Allowe
 }
 
 /// Safety verification results
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VerificationResults {
     /// Overall verification success
     pub success: bool,
@@ -87,7 +90,7 @@ pub struct VerificationResults {
 }
 
 /// Individual verification check result
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VerificationCheck {
     /// Name of the check
     pub name: String,
@@ -100,7 +103,7 @@ pub struct VerificationCheck {
 }
 
 /// Verification check severity levels
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VerificationSeverity {
     /// Critical safety violation
     Critical,
@@ -129,6 +132,8 @@ pub struct VerificationOptions {
     pub detailed_reports: bool,
     /// Allowed unsafe blocks configuration
     pub allowed_unsafe: Option<AllowedUnsafeConfig>,
+    /// Generate SBOMs and release provenance
+    pub sbom: bool,
 }
 
 impl Default for VerificationOptions {
@@ -151,6 +156,7 @@ fn default() -> Self {
             audit: true,
             detailed_reports: true,
             allowed_unsafe,
+            sbom: false,
         }
     }
 }
@@ -237,6 +243,19 @@ pub fn verify_safety_with_diagnostics(
     pub fn verify_safety_with_options(
         &self,
         options: &VerificationOptions,
+    ) -> BuildResult<VerificationResults> {
+        self.run_cached_step(
+            "verify",
+            &format!("{:?}", options),
+            || self.verify_safety_uncached(options),
+            |r| r.success,
+        )
+    }
+
+    /// Run safety verification without consulting the step cache
+    fn verify_safety_uncached(
+        &self,
+        options: &VerificationOptions,
     ) -> BuildResult<VerificationResults> {
         println!(
             "{} Running SCORE-inspired safety verification...",
@@ -255,9 +274,15 @@ pub fn verify_safety_with_options(
             checks.extend(self.run_memory_safety_checks()?);
         }
 
-        // 3. Kani formal verification
+        // 3. Kani formal verification; ASIL-D runs the proofs and tracks
+        // their results across runs
         if options.kani {
-            match self.run_kani_verification() {
+            let kani = if options.target_asil == AsilLevel::D {
+                self.run_kani_proofs(&mut report_sections)
+            } else {
+                self.run_kani_verification()
+            };
+            match kani {
                 Ok(mut kani_checks) => checks.append(&mut kani_checks),
                 Err(e) => {
                     checks.push(VerificationCheck {
@@ -300,6 +325,50 @@ pub fn verify_safety_with_options(
             }
         }
 
+        // 6. SBOM and release provenance
+        if options.sbom {
+            match self.generate_sbom(&SbomOptions::default()) {
+                Ok(sbom) => {
+                    checks.push(VerificationCheck {
+                        name: "SBOM Generation".to_string(),
+                        passed: true,
+                        details: format!(
+                            "{} packages, {} without a declared license",
+                            sbom.packages,
+                            sbom.unlicensed.len()
+                        ),
+                        severity: VerificationSeverity::Info,
+                    });
+                    report_sections.push(sbom.report_section());
+                },
+                Err(e) => {
+                    checks.push(VerificationCheck {
+                        name: "SBOM Generation".to_string(),
+                        passed: false,
+                        details: format!("SBOM generation failed: {}", e),
+                        severity: VerificationSeverity::Minor,
+                    });
+                },
+            }
+        }
+
+        // 7. Module complexity caps overridden in wrt.toml
+        match self.check_complexity_policy() {
+            Ok(Some((check, note))) => {
+                checks.push(check);
+                report_sections.push(note);
+            },
+            Ok(None) => {},
+            Err(e) => {
+                checks.push(VerificationCheck {
+                    name: "Module Complexity Policy".to_string(),
+                    passed: false,
+                    details: format!("Invalid complexity policy: {}", e),
+                    severity: VerificationSeverity::Major,
+                });
+            },
+        }
+
         // Calculate overall results
         let duration = start_time.elapsed();
         let critical_failures = checks
@@ -316,7 +385,10 @@ pub fn verify_safety_with_options(
         let achieved_asil = self.calculate_asil_level(&checks, &options.target_asil);
 
         // Generate report
-        let report = self.generate_verification_report(&checks, &achieved_asil, duration)?;
+        let mut report = self.generate_verification_report(&checks, &achieved_asil, duration)?;
+        for section in &report_sections {
+            report.push_str(section);
+        }
 
         if success {
             println!(
@@ -482,6 +554,68 @@ fn run_kani_verification(&self) -> BuildResult<Vec<VerificationCheck>> {
         }])
     }
 
+    /// Run the KANI proofs, record their results and report the changes
+    /// since the previous run
+    fn run_kani_proofs(
+        &self,
+        report_sections: &mut Vec<String>,
+    ) -> BuildResult<Vec<VerificationCheck>> {
+        if !is_kani_available() {
+            return Ok(vec![VerificationCheck {
+                name: "Kani Formal Verification".to_string(),
+                passed: false,
+                details: "Kani not available. Install with: cargo install --locked kani-verifier"
+                    .to_string(),
+                severity: VerificationSeverity::Minor,
+            }]);
+        }
+
+        let config = KaniConfig {
+            profile: AsilLevel::D,
+            ..KaniConfig::default()
+        };
+        let results = KaniVerifier::new(self.workspace.root.clone(), config).run_verification()?;
+        let delta = &results.delta;
+        report_sections.push(delta.to_markdown());
+
+        let slowdowns: Vec<String> = delta
+            .newly_slow
+            .iter()
+            .map(|s| format!("{} ({}ms -> {}ms)", s.harness, s.previous_ms, s.current_ms))
+            .collect();
+        Ok(vec![
+            VerificationCheck {
+                name: "Kani Formal Verification".to_string(),
+                passed: results.passed_packages == results.total_packages,
+                details: format!(
+                    "{}/{} packages verified",
+                    results.passed_packages, results.total_packages
+                ),
+                severity: VerificationSeverity::Major,
+            },
+            VerificationCheck {
+                name: "Kani Proof Regressions".to_string(),
+                passed: delta.newly_failing.is_empty(),
+                details: if delta.newly_failing.is_empty() {
+                    "No newly failing proofs".to_string()
+                } else {
+                    format!("Newly failing: {}", delta.newly_failing.join(", "))
+                },
+                severity: VerificationSeverity::Major,
+            },
+            VerificationCheck {
+                name: "Kani Proof Performance".to_string(),
+                passed: slowdowns.is_empty(),
+                details: if slowdowns.is_empty() {
+                    "No proofs became markedly slower".to_string()
+                } else {
+                    format!("Newly slow: {}", slowdowns.join(", "))
+                },
+                severity: VerificationSeverity::Minor,
+            },
+        ])
+    }
+
     /// Run MIRI checks
     fn run_miri_checks(&self) -> BuildResult<Vec<VerificationCheck>> {
         // Placeholder for MIRI verification
@@ -776,6 +910,34 @@ fn calculate_asil_level(&self, checks: &[VerificationCheck], target: &AsilLevel)
         }
     }
 
+    /// Load the module complexity policy from `wrt.toml`
+    ///
+    /// Returns a check and a compliance note for the report when the policy
+    /// overrides the caps of its ASIL level, `None` if there is no
+    /// `wrt.toml` or it keeps the defaults.
+    fn check_complexity_policy(&self) -> BuildResult<Option<(VerificationCheck, String)>> {
+        let path = self.workspace.root.join("wrt.toml");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let policy = ComplexityPolicy::from_file(&path)
+            .map_err(|e| BuildError::Verification(format!("{}: {}", path.display(), e)))?;
+        let Some(note) = policy.compliance_note() else {
+            return Ok(None);
+        };
+        let check = VerificationCheck {
+            name: "Module Complexity Policy".to_string(),
+            passed: true,
+            details: format!(
+                "{} complexity caps overridden for {}",
+                policy.overrides().count(),
+                policy.asil
+            ),
+            severity: VerificationSeverity::Info,
+        };
+        Ok(Some((check, format!("## Module Complexity Policy\n\n{}\n", note))))
+    }
+
     /// Generate verification report
     fn generate_verification_report(
         &self,
@@ -1886,6 +1886,32 @@ fn test_resource_limits_default() {
         assert_eq!(limits.max_memory_size, 64 * 1024 * 1024);
     }
 
+    /// Runs the WAST scripts kept in the repository, which are laid out like
+    /// the spec testsuite
+    #[test]
+    fn test_repository_wast_suite() -> Result<()> {
+        let mut runner = WastTestRunner::new(WastConfig {
+            test_directory: Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/wast"),
+            file_filter: None,
+            per_assertion: true,
+            report_format: ReportFormat::Human,
+            test_timeout_ms: 30_000,
+        })?;
+        let results = runner.run_all_tests()?;
+        assert!(!results.is_empty());
+        for result in &results {
+            let failures: Vec<_> = result
+                .directive_results
+                .iter()
+                .filter(|info| info.result != TestResult::Passed)
+                .map(|info| (&info.directive_name, &info.error_message))
+                .collect();
+            assert!(failures.is_empty(), "{}: failed directives: {:?}", result.file_path, failures);
+            assert_eq!(result.status, TestResult::Passed, "{}", result.file_path);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_error_keyword_detection() {
         assert!(contains_trap_keyword("divide by zero"));
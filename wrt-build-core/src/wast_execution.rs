@@ -319,6 +319,7 @@ fn resolve_spectest_memory_imports(
                     let core_mem_type = CoreMemoryType {
                         limits: mem_type.limits,
                         shared: mem_type.shared,
+                        memory64: mem_type.memory64,
                     };
 
                     let memory = Memory::new(core_mem_type).map_err(|e| {
@@ -433,7 +434,7 @@ fn resolve_registered_module_imports(
                 }
 
                 // Look up the registered module
-                if let Some(source_module) = self.modules.get(mod_name) {
+                if let Some(source_module) = self.modules.get(mod_name.as_str()) {
                     // Find the exported global in the source module
                     let bounded_field =
                         wrt_foundation::bounded::BoundedString::<256>::from_str_truncate(
@@ -512,9 +513,9 @@ fn link_function_imports(&mut self, module: &Module, instance_id: usize) -> Resu
             // Check if this is a function import
             if let Some(RuntimeImportDesc::Function(_type_idx)) = import_types.get(i) {
                 // Check if we have a registered module with this name
-                if let Some(&source_instance_id) = self.instance_ids.get(mod_name) {
+                if let Some(&source_instance_id) = self.instance_ids.get(mod_name.as_str()) {
                     // Check if the source module exports this function
-                    if let Some(source_module) = self.modules.get(mod_name) {
+                    if let Some(source_module) = self.modules.get(mod_name.as_str()) {
                         let bounded_field =
                             wrt_foundation::bounded::BoundedString::<256>::from_str_truncate(
                                 field_name,
@@ -526,10 +527,10 @@ fn link_function_imports(&mut self, module: &Module, instance_id: usize) -> Resu
                                 // Set up the import link
                                 self.engine.register_import_link(
                                     instance_id,
-                                    mod_name.clone(),
-                                    field_name.clone(),
+                                    mod_name.to_string(),
+                                    field_name.to_string(),
                                     source_instance_id,
-                                    field_name.clone(),
+                                    field_name.to_string(),
                                 );
                             }
                         }
@@ -14,7 +14,7 @@
 use std::collections::HashSet;
 use wrt_format::module::{Function, Global, ImportDesc, Module};
 use wrt_format::pure_format_types::{PureElementInit, PureElementMode, PureElementSegment};
-use wrt_format::types::RefType;
+use wrt_format::types::{MemoryIndexType, RefType};
 use wrt_foundation::ValueType;
 
 /// Type of a value on the stack
@@ -381,14 +381,17 @@ fn validate_memory_limits(module: &Module) -> Result<()> {
                         return Err(anyhow!("size minimum must not be greater than maximum"));
                     }
                 }
-                // Check memory size bounds (65536 pages max)
-                if memory.limits.min > WASM_MAX_MEMORY_PAGES {
-                    return Err(anyhow!("memory size"));
-                }
-                if let Some(max) = memory.limits.max {
-                    if max > WASM_MAX_MEMORY_PAGES {
+                // Check memory size bounds (65536 pages max); memory64 bounds
+                // are checked when decoding
+                if !memory.memory64 {
+                    if memory.limits.min > WASM_MAX_MEMORY_PAGES {
                         return Err(anyhow!("memory size"));
                     }
+                    if let Some(max) = memory.limits.max {
+                        if max > WASM_MAX_MEMORY_PAGES {
+                            return Err(anyhow!("memory size"));
+                        }
+                    }
                 }
             }
         }
@@ -401,7 +404,11 @@ fn validate_memory_limits(module: &Module) -> Result<()> {
                     return Err(anyhow!("size minimum must not be greater than maximum"));
                 }
             }
-            // Check memory size bounds (65536 pages max)
+            // Check memory size bounds (65536 pages max); memory64 bounds are
+            // checked when decoding
+            if memory.memory64 {
+                continue;
+            }
             if memory.limits.min > WASM_MAX_MEMORY_PAGES {
                 return Err(anyhow!("memory size"));
             }
@@ -1442,7 +1449,7 @@ fn validate_function_body(
                 // Memory operations - Load instructions
                 // All memory operations require at least one memory to be defined
                 0x28 => {
-                    // i32.load - pop i32 address, push i32 value
+                    // i32.load - pop address, push i32 value
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1452,9 +1459,10 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1463,7 +1471,7 @@ fn validate_function_body(
                     stack.push(StackType::I32);
                 },
                 0x29 => {
-                    // i64.load - pop i32 address, push i64 value
+                    // i64.load - pop address, push i64 value
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1473,9 +1481,10 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1484,7 +1493,7 @@ fn validate_function_body(
                     stack.push(StackType::I64);
                 },
                 0x2A => {
-                    // f32.load - pop i32 address, push f32 value
+                    // f32.load - pop address, push f32 value
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1494,9 +1503,10 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1505,7 +1515,7 @@ fn validate_function_body(
                     stack.push(StackType::F32);
                 },
                 0x2B => {
-                    // f64.load - pop i32 address, push f64 value
+                    // f64.load - pop address, push f64 value
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1515,9 +1525,10 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1527,7 +1538,7 @@ fn validate_function_body(
                 },
                 0x2C..=0x35 => {
                     // Extended load operations (load8, load16, load32, etc.)
-                    // All take i32 address and return the loaded value type
+                    // All take an address and return the loaded value type
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1537,9 +1548,10 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1554,7 +1566,7 @@ fn validate_function_body(
                     stack.push(result_type);
                 },
                 0x36 => {
-                    // i32.store - pop i32 value and i32 address
+                    // i32.store - pop i32 value and address
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1564,6 +1576,7 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
                         StackType::I32,
@@ -1574,7 +1587,7 @@ fn validate_function_body(
                     }
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1582,7 +1595,7 @@ fn validate_function_body(
                     }
                 },
                 0x37 => {
-                    // i64.store - pop i64 value and i32 address
+                    // i64.store - pop i64 value and address
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1592,6 +1605,7 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
                         StackType::I64,
@@ -1602,7 +1616,7 @@ fn validate_function_body(
                     }
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1610,7 +1624,7 @@ fn validate_function_body(
                     }
                 },
                 0x38 => {
-                    // f32.store - pop f32 value and i32 address
+                    // f32.store - pop f32 value and address
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1620,6 +1634,7 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
                         StackType::F32,
@@ -1630,7 +1645,7 @@ fn validate_function_body(
                     }
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1638,7 +1653,7 @@ fn validate_function_body(
                     }
                 },
                 0x39 => {
-                    // f64.store - pop f64 value and i32 address
+                    // f64.store - pop f64 value and address
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1648,6 +1663,7 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
                         StackType::F64,
@@ -1658,7 +1674,7 @@ fn validate_function_body(
                     }
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1676,6 +1692,7 @@ fn validate_function_body(
                     let (_, new_offset) = Self::parse_varuint32(code, offset)?;
                     offset = new_offset;
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     // Pop value type based on opcode
                     let value_type = match opcode {
                         0x3A | 0x3B => StackType::I32,        // i32.store8, i32.store16
@@ -1692,7 +1709,7 @@ fn validate_function_body(
                     }
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
@@ -1700,7 +1717,7 @@ fn validate_function_body(
                     }
                 },
                 0x3F => {
-                    // memory.size - push i32 (current memory size in pages)
+                    // memory.size - push the size in pages, i64 for memory64
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1708,10 +1725,11 @@ fn validate_function_body(
                     if offset < code.len() {
                         offset += 1;
                     }
-                    stack.push(StackType::I32);
+                    stack.push(Self::memory_address_type(module));
                 },
                 0x40 => {
-                    // memory.grow - pop i32 (delta pages), push i32 (previous size or -1)
+                    // memory.grow - pop delta pages, push previous size or -1, both i64
+                    // for memory64
                     if !Self::has_memory(module) {
                         return Err(anyhow!("unknown memory"));
                     }
@@ -1720,15 +1738,16 @@ fn validate_function_body(
                         offset += 1;
                     }
                     let frame_height = Self::current_frame_height(&frames);
+                    let address_type = Self::memory_address_type(module);
                     if !Self::pop_type(
                         &mut stack,
-                        StackType::I32,
+                        address_type,
                         frame_height,
                         Self::is_unreachable(&frames),
                     ) {
                         return Err(anyhow!("type mismatch"));
                     }
-                    stack.push(StackType::I32);
+                    stack.push(address_type);
                 },
 
                 // Variable operations
@@ -2614,7 +2633,7 @@ fn validate_function_body(
                             }
                             stack.push(StackType::I64);
                         },
-                        // memory.init (0x08): [i32, i32, i32] -> []
+                        // memory.init (0x08): [addr, i32, i32] -> []
                         0x08 => {
                             // Skip data_idx and mem_idx
                             let (_data_idx, new_offset) = Self::parse_varuint32(code, offset)?;
@@ -2640,7 +2659,7 @@ fn validate_function_body(
                             }
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                Self::memory_address_type(module),
                                 frame_height,
                                 unreachable,
                             ) {
@@ -2652,16 +2671,17 @@ fn validate_function_body(
                             let (_data_idx, new_offset) = Self::parse_varuint32(code, offset)?;
                             offset = new_offset;
                         },
-                        // memory.copy (0x0A): [i32, i32, i32] -> []
+                        // memory.copy (0x0A): [addr, addr, addr] -> []
                         0x0A => {
                             let (_dst_mem, new_offset) = Self::parse_varuint32(code, offset)?;
                             offset = new_offset;
                             let (_src_mem, new_offset) = Self::parse_varuint32(code, offset)?;
                             offset = new_offset;
+                            let address_type = Self::memory_address_type(module);
                             // Pop n (length), s (source), d (dest) in reverse
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                address_type,
                                 frame_height,
                                 unreachable,
                             ) {
@@ -2669,7 +2689,7 @@ fn validate_function_body(
                             }
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                address_type,
                                 frame_height,
                                 unreachable,
                             ) {
@@ -2677,21 +2697,22 @@ fn validate_function_body(
                             }
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                address_type,
                                 frame_height,
                                 unreachable,
                             ) {
                                 return Err(anyhow!("type mismatch"));
                             }
                         },
-                        // memory.fill (0x0B): [i32, i32, i32] -> []
+                        // memory.fill (0x0B): [addr, i32, addr] -> []
                         0x0B => {
                             let (_mem_idx, new_offset) = Self::parse_varuint32(code, offset)?;
                             offset = new_offset;
+                            let address_type = Self::memory_address_type(module);
                             // Pop n (length), val (value), d (dest) in reverse
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                address_type,
                                 frame_height,
                                 unreachable,
                             ) {
@@ -2707,7 +2728,7 @@ fn validate_function_body(
                             }
                             if !Self::pop_type(
                                 &mut stack,
-                                StackType::I32,
+                                address_type,
                                 frame_height,
                                 unreachable,
                             ) {
@@ -2888,6 +2909,46 @@ fn validate_function_body(
                     }
                 },
 
+                // Threads prefix (0xFE) - wait/notify and fence
+                0xFE => {
+                    let (sub_opcode, new_offset) = Self::parse_varuint32(code, offset)?;
+                    offset = new_offset;
+
+                    let frame_height = Self::current_frame_height(&frames);
+                    let unreachable = Self::is_unreachable(&frames);
+
+                    // Operand types after the address, in pop order
+                    let operands: &[StackType] = match sub_opcode {
+                        // memory.atomic.notify: [addr, i32] -> [i32]
+                        0x00 => &[StackType::I32],
+                        // memory.atomic.wait32: [addr, i32, i64] -> [i32]
+                        0x01 => &[StackType::I64, StackType::I32],
+                        // memory.atomic.wait64: [addr, i64, i64] -> [i32]
+                        0x02 => &[StackType::I64, StackType::I64],
+                        // atomic.fence: reserved byte, no stack effect
+                        0x03 => {
+                            offset += 1;
+                            continue;
+                        },
+                        // Other atomic sub-opcodes - skip
+                        _ => continue,
+                    };
+                    if !Self::has_memory(module) {
+                        return Err(anyhow!("unknown memory"));
+                    }
+                    let (_, new_offset) = Self::parse_varuint32(code, offset)?;
+                    offset = new_offset;
+                    let (_, new_offset) = Self::parse_varuint32(code, offset)?;
+                    offset = new_offset;
+                    let address_type = Self::memory_address_type(module);
+                    for operand in operands.iter().chain(core::iter::once(&address_type)) {
+                        if !Self::pop_type(&mut stack, *operand, frame_height, unreachable) {
+                            return Err(anyhow!("type mismatch"));
+                        }
+                    }
+                    stack.push(StackType::I32);
+                },
+
                 // SIMD prefix (0xFD)
                 0xFD => {
                     // SIMD instructions have a LEB128 opcode following the 0xFD prefix
@@ -3217,6 +3278,20 @@ fn has_memory(module: &Module) -> bool {
         Self::total_memories(module) > 0
     }
 
+    /// Type of addresses, sizes and page counts of the module's memory:
+    /// i64 for a memory64 memory, i32 otherwise
+    fn memory_address_type(module: &Module) -> StackType {
+        let imported = module.imports.iter().find_map(|import| match &import.desc {
+            ImportDesc::Memory(memory) => Some(memory),
+            _ => None,
+        });
+        imported
+            .or_else(|| module.memories.first())
+            .map_or(StackType::I32, |memory| {
+                StackType::from_value_type(MemoryIndexType::of(memory).address_type())
+            })
+    }
+
     /// Count the number of table imports in a module
     fn count_table_imports(module: &Module) -> usize {
         module
@@ -0,0 +1,322 @@
+//! World proxy generation for incremental migration of native plugin APIs
+//!
+//! Generates a Rust host shim that installs a `wrt_host::WorldProxy` for a
+//! WIT world. The proxy registers every function the world imports under
+//! the name components import it by, forwarding each call to the legacy
+//! native host function that already implements it:
+//!
+//! ```text
+//! example:plugin/logging@1.0.0::log  ->  env::log
+//! $root::flush                       ->  env::flush
+//! ```
+//!
+//! Legacy functions are named after the imported function in snake case.
+//! If two imported interfaces declare functions of the same name, both are
+//! prefixed with their interface name instead (`logging_log`, `audit_log`).
+//! Imports of interfaces from other packages have no signatures in the
+//! document and are skipped.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use wrt_format::wit_parser::{WitDocument, WitFunction, WitWorld, WitWorldItem};
+
+use crate::error::{BuildError, BuildResult};
+
+/// Most core parameters a function is lowered to before they are passed in
+/// linear memory
+const MAX_FLAT_PARAMS: usize = 16;
+
+/// Most core results a function is lowered to before they are returned in
+/// linear memory
+const MAX_FLAT_RESULTS: usize = 1;
+
+/// Module world-level functions are imported from
+const ROOT_MODULE: &str = "$root";
+
+/// Forwarding of one imported function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProxyRoute {
+    /// Module the component imports the function from
+    pub import_module: String,
+    /// Name the component imports the function under
+    pub import_function: String,
+    /// Legacy host function the import is forwarded to
+    pub legacy_function: String,
+    /// Number of core values the import is called with
+    pub params: usize,
+    /// Number of core values the import returns
+    pub results: usize,
+    /// WIT signature of the import
+    pub signature: String,
+}
+
+/// Forwarding of all imports of a world
+#[derive(Debug, Clone, Serialize)]
+pub struct WorldProxyPlan {
+    /// World the imports belong to
+    pub world: String,
+    /// Module the legacy host functions are registered in
+    pub legacy_module: String,
+    /// Forwarded imports, in declaration order
+    pub routes: Vec<ProxyRoute>,
+    /// Imported interfaces of other packages, which are not forwarded
+    pub skipped: Vec<String>,
+}
+
+impl WorldProxyPlan {
+    /// Plan the proxy for `world`, or the only world of the document
+    pub fn new(doc: &WitDocument, world: Option<&str>, legacy_module: &str) -> BuildResult<Self> {
+        let world = select_world(doc, world)?;
+
+        let mut imports: Vec<(Option<&str>, &WitFunction)> = Vec::new();
+        let mut skipped = Vec::new();
+        for item in &world.imports {
+            match item {
+                WitWorldItem::Interface(interface) => imports.extend(
+                    doc.functions()
+                        .iter()
+                        .filter(|f| f.interface.as_deref() == Some(interface.as_str()))
+                        .map(|f| (Some(interface.as_str()), f)),
+                ),
+                WitWorldItem::Function(name) => {
+                    let function = doc
+                        .functions()
+                        .iter()
+                        .find(|f| f.interface.is_none() && f.name == *name)
+                        .ok_or_else(|| {
+                            BuildError::Verification(format!("No signature for import '{}'", name))
+                        })?;
+                    imports.push((None, function));
+                },
+                WitWorldItem::External(name) => skipped.push(name.clone()),
+            }
+        }
+
+        let mut name_counts: HashMap<&str, usize> = HashMap::new();
+        for (_, function) in &imports {
+            *name_counts.entry(function.name.as_str()).or_default() += 1;
+        }
+
+        let mut routes = Vec::with_capacity(imports.len());
+        for (interface, function) in imports {
+            let legacy_function = match interface {
+                Some(interface) if name_counts[function.name.as_str()] > 1 => {
+                    format!("{}_{}", snake_case(interface), snake_case(&function.name))
+                },
+                _ => snake_case(&function.name),
+            };
+            let (params, results) = lowered_arity(doc, function, false)?;
+            routes.push(ProxyRoute {
+                import_module: interface
+                    .map(|interface| doc.qualified_interface(interface))
+                    .unwrap_or_else(|| ROOT_MODULE.to_string()),
+                import_function: function.name.clone(),
+                legacy_function,
+                params,
+                results,
+                signature: signature(function),
+            });
+        }
+
+        Ok(Self {
+            world: world.name.clone(),
+            legacy_module: legacy_module.to_string(),
+            routes,
+            skipped,
+        })
+    }
+
+    /// Render the host shim installing the proxy
+    pub fn to_rust(&self) -> String {
+        let ident = snake_case(&self.world);
+        let mut source = String::new();
+
+        source.push_str(&format!(
+            "//! Host shim forwarding the imports of WIT world `{}` to the legacy\n//! native \
+             implementation in module `{}`.\n//!\n//! Generated by `cargo-wrt proxy`; \
+             regenerate it instead of editing.\n\n",
+            self.world, self.legacy_module
+        ));
+        source.push_str("use wrt_host::{CallbackRegistry, Result, WorldProxy};\n\n");
+
+        source.push_str(&format!("/// Proxy for the imports of world `{}`\n", self.world));
+        source.push_str(&format!("pub fn {}_proxy() -> WorldProxy {{\n", ident));
+        source.push_str(&format!(
+            "    WorldProxy::new({:?}, {:?})\n",
+            self.world, self.legacy_module
+        ));
+        for route in &self.routes {
+            source.push_str(&format!("        // {}\n", route.signature));
+            source.push_str(&format!(
+                "        .route({:?}, {:?}, {:?}, {}, {})\n",
+                route.import_module,
+                route.import_function,
+                route.legacy_function,
+                route.params,
+                route.results
+            ));
+        }
+        source.push_str("}\n\n");
+
+        source.push_str(&format!(
+            "/// Register the proxy for world `{}` with `registry`\n",
+            self.world
+        ));
+        source.push_str(&format!(
+            "pub fn install_{}_proxy(registry: &mut CallbackRegistry) -> Result<usize> {{\n",
+            ident
+        ));
+        source.push_str(&format!("    {}_proxy().install(registry)\n}}\n", ident));
+        source
+    }
+}
+
+/// World named `world`, or the only world of the document
+pub(crate) fn select_world<'a>(
+    doc: &'a WitDocument,
+    world: Option<&str>,
+) -> BuildResult<&'a WitWorld> {
+    match world {
+        Some(name) => doc.worlds().iter().find(|w| w.name == name).ok_or_else(|| {
+            BuildError::Verification(format!("No world '{}' in WIT document", name))
+        }),
+        None => match doc.worlds() {
+            [world] => Ok(world),
+            [] => Err(BuildError::Verification("WIT document declares no world".to_string())),
+            _ => Err(BuildError::Verification(
+                "WIT document declares several worlds; choose one".to_string(),
+            )),
+        },
+    }
+}
+
+/// Number of core values a function is called with and returns
+///
+/// Functions lowered to more than [`MAX_FLAT_PARAMS`] values take a single
+/// pointer instead. Imports with more than [`MAX_FLAT_RESULTS`] results take
+/// an extra pointer to write them to and return nothing, while exports
+/// return a single pointer to them.
+pub(crate) fn lowered_arity(
+    doc: &WitDocument,
+    function: &WitFunction,
+    export: bool,
+) -> BuildResult<(usize, usize)> {
+    let mut params = 0;
+    for (_, ty) in &function.params {
+        params += doc.flat_count(ty)?;
+    }
+    let mut results = 0;
+    for (_, ty) in &function.results {
+        results += doc.flat_count(ty)?;
+    }
+
+    if params > MAX_FLAT_PARAMS {
+        params = 1;
+    }
+    if results > MAX_FLAT_RESULTS {
+        if export {
+            results = 1;
+        } else {
+            params += 1;
+            results = 0;
+        }
+    }
+    Ok((params, results))
+}
+
+/// WIT signature of a function, as declared
+pub(crate) fn signature(function: &WitFunction) -> String {
+    let params: Vec<String> =
+        function.params.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+    let mut signature = format!("{}: func({})", function.name, params.join(", "));
+    match function.results.as_slice() {
+        [] => {},
+        [(name, ty)] if name == "result" => signature.push_str(&format!(" -> {}", ty)),
+        results => {
+            let results: Vec<String> =
+                results.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+            signature.push_str(&format!(" -> ({})", results.join(", ")));
+        },
+    }
+    signature
+}
+
+/// Convert a WIT kebab-case name to a Rust snake-case identifier
+pub(crate) fn snake_case(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIT: &str = r#"
+        package example:plugin@1.0.0;
+
+        interface logging {
+            enum level { debug, info, error }
+            log: func(level: level, message: string);
+        }
+
+        interface audit {
+            record event { id: u64, actor: string, detail: string }
+            log: func(event: event) -> result<u64, string>;
+        }
+
+        world plugin {
+            import logging;
+            import audit;
+            import wasi:clocks/wall-clock@0.2.0;
+            import flush-all: func() -> bool;
+            export run: func();
+        }
+    "#;
+
+    #[test]
+    fn test_world_proxy_plan() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let plan = WorldProxyPlan::new(&doc, None, "env").unwrap();
+
+        let routes: Vec<_> = plan
+            .routes
+            .iter()
+            .map(|r| {
+                (
+                    r.import_module.as_str(),
+                    r.import_function.as_str(),
+                    r.legacy_function.as_str(),
+                    r.params,
+                    r.results,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("example:plugin/logging@1.0.0", "log", "logging_log", 3, 0),
+                // event flattens to 5 values; the result is returned through a pointer
+                ("example:plugin/audit@1.0.0", "log", "audit_log", 6, 0),
+                ("$root", "flush-all", "flush_all", 0, 1),
+            ]
+        );
+        assert_eq!(plan.skipped, vec!["wasi:clocks/wall-clock@0.2.0"]);
+        assert_eq!(plan.routes[2].signature, "flush-all: func() -> bool");
+
+        assert!(WorldProxyPlan::new(&doc, Some("missing"), "env").is_err());
+    }
+
+    #[test]
+    fn test_world_proxy_rust_source() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let source = WorldProxyPlan::new(&doc, Some("plugin"), "legacy").unwrap().to_rust();
+
+        assert!(source.contains("pub fn plugin_proxy() -> WorldProxy {"));
+        assert!(source.contains("WorldProxy::new(\"plugin\", \"legacy\")"));
+        assert!(source.contains(
+            ".route(\"example:plugin/logging@1.0.0\", \"log\", \"logging_log\", 3, 0)"
+        ));
+        assert!(source.contains("// log: func(level: level, message: string)"));
+        assert!(source.contains("pub fn install_plugin_proxy(registry: &mut CallbackRegistry)"));
+    }
+}
@@ -0,0 +1,32 @@
+;; Memory64 proposal: i64 addresses, offsets, page counts and data segment
+;; offsets, and address types in validation
+(module
+  (memory i64 1 4)
+  (data (i64.const 8) "\2a\00\00\00")
+  (func (export "load") (param i64) (result i32) (i32.load (local.get 0)))
+  (func (export "load64") (param i64) (result i64) (i64.load offset=8 (local.get 0)))
+  (func (export "store") (param i64 i64) (i64.store (local.get 0) (local.get 1)))
+  (func (export "fill") (param i64 i32 i64) (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "size") (result i64) (memory.size))
+  (func (export "grow") (param i64) (result i64) (memory.grow (local.get 0)))
+)
+(assert_return (invoke "load" (i64.const 8)) (i32.const 42))
+(assert_return (invoke "store" (i64.const 16) (i64.const 0x1_0000_0001)))
+(assert_return (invoke "load64" (i64.const 8)) (i64.const 0x1_0000_0001))
+(assert_return (invoke "fill" (i64.const 16) (i32.const 0) (i64.const 8)))
+(assert_return (invoke "load64" (i64.const 8)) (i64.const 0))
+(assert_trap (invoke "load" (i64.const 65533)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const 0x1_0000_0000)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const -1)) "out of bounds memory access")
+(assert_return (invoke "size") (i64.const 1))
+(assert_return (invoke "grow" (i64.const 2)) (i64.const 1))
+(assert_return (invoke "size") (i64.const 3))
+(assert_return (invoke "grow" (i64.const 2)) (i64.const -1))
+(assert_return (invoke "grow" (i64.const 0x1_0000_0000)) (i64.const -1))
+(assert_invalid
+  (module (memory i64 1) (func (drop (i32.load (i32.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 1) (func (result i32) (memory.size)))
+  "type mismatch")
+(assert_invalid (module (memory i64 0x1_0000_0000_0001)) "memory size")
@@ -0,0 +1,42 @@
+;; Tail-call proposal: deep return_call chains run in constant stack and
+;; return_call_indirect checks the callee's type
+(module
+  (type $binary (func (param i64 i64) (result i64)))
+  (table funcref (elem $fac-acc $even))
+  (func $fac-acc (export "fac-acc") (param i64 i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else
+        (return_call $fac-acc
+          (i64.sub (local.get 0) (i64.const 1))
+          (i64.mul (local.get 0) (local.get 1))))))
+  (func $count (export "count") (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 0))
+      (else (return_call $count (i64.sub (local.get 0) (i64.const 1))))))
+  (func $even (export "even") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 44))
+      (else (return_call $odd (i32.sub (local.get 0) (i32.const 1))))))
+  (func $odd (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 99))
+      (else (return_call $even (i32.sub (local.get 0) (i32.const 1))))))
+  (func (export "dispatch") (param i32 i64) (result i64)
+    (return_call_indirect (type $binary) (local.get 1) (i64.const 1) (local.get 0)))
+  (func $pair (param i32) (result i32 i64)
+    (local.get 0) (i64.extend_i32_u (local.get 0)))
+  (func (export "pair") (param i32) (result i32 i64)
+    (return_call $pair (local.get 0)))
+)
+(assert_return (invoke "fac-acc" (i64.const 20) (i64.const 1)) (i64.const 2432902008176640000))
+(assert_return (invoke "count" (i64.const 100000)) (i64.const 0))
+(assert_return (invoke "even" (i32.const 100001)) (i32.const 99))
+(assert_return (invoke "dispatch" (i32.const 0) (i64.const 5)) (i64.const 120))
+(assert_trap (invoke "dispatch" (i32.const 1) (i64.const 5)) "indirect call type mismatch")
+(assert_trap (invoke "dispatch" (i32.const 2) (i64.const 5)) "undefined element")
+(assert_return (invoke "pair" (i32.const 7)) (i32.const 7) (i64.const 7))
+(assert_invalid
+  (module (func $f (result i32) (i32.const 1))
+    (func (result i64) (return_call $f)))
+  "type mismatch")
@@ -0,0 +1,42 @@
+;; Threads proposal: memory.atomic.wait / memory.atomic.notify results on a
+;; single thread, alignment and bounds traps, and waits on unshared memory
+(module
+  (memory 1 1 shared)
+  (data (i32.const 0) "\2a\00\00\00\00\00\00\00\07\00\00\00\00\00\00\00")
+  (func (export "wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "wait64") (param i32 i64 i64) (result i32)
+    (memory.atomic.wait64 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "notify") (param i32 i32) (result i32)
+    (memory.atomic.notify (local.get 0) (local.get 1))))
+
+;; Value differs from the expected one: "not-equal"
+(assert_return (invoke "wait32" (i32.const 0) (i32.const 0) (i64.const -1)) (i32.const 1))
+(assert_return (invoke "wait64" (i32.const 8) (i64.const 0) (i64.const -1)) (i32.const 1))
+
+;; Value matches and nobody notifies: "timed-out"
+(assert_return (invoke "wait32" (i32.const 0) (i32.const 42) (i64.const 0)) (i32.const 2))
+(assert_return (invoke "wait32" (i32.const 0) (i32.const 42) (i64.const 1000)) (i32.const 2))
+(assert_return (invoke "wait64" (i32.const 8) (i64.const 7) (i64.const 1000)) (i32.const 2))
+
+;; Notify with no waiters wakes nobody
+(assert_return (invoke "notify" (i32.const 0) (i32.const 1)) (i32.const 0))
+(assert_return (invoke "notify" (i32.const 0) (i32.const -1)) (i32.const 0))
+
+(assert_trap (invoke "wait32" (i32.const 1) (i32.const 0) (i64.const 0)) "unaligned atomic")
+(assert_trap (invoke "wait64" (i32.const 4) (i64.const 0) (i64.const 0)) "unaligned atomic")
+(assert_trap (invoke "notify" (i32.const 2) (i32.const 1)) "unaligned atomic")
+(assert_trap (invoke "wait32" (i32.const 65536) (i32.const 0) (i64.const 0)) "out of bounds memory access")
+(assert_trap (invoke "wait64" (i32.const 65536) (i64.const 0) (i64.const 0)) "out of bounds memory access")
+(assert_trap (invoke "notify" (i32.const 65536) (i32.const 1)) "out of bounds memory access")
+
+(module
+  (memory 1)
+  (func (export "wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "notify") (param i32 i32) (result i32)
+    (memory.atomic.notify (local.get 0) (local.get 1))))
+
+;; Unshared memory can be notified but never waited on
+(assert_return (invoke "notify" (i32.const 0) (i32.const 1)) (i32.const 0))
+(assert_trap (invoke "wait32" (i32.const 0) (i32.const 0) (i64.const 0)) "expected shared memory")
@@ -0,0 +1,36 @@
+//! Latency-critical canonical ABI paths
+//!
+//! Part of the `critical` benchmark group gated in CI by
+//! `cargo-wrt bench --critical`; see `wrt-runtime/benches/critical_path.rs`.
+//!
+//! - `canonical_string_lift`: lift of a 290 byte UTF-8 string from linear
+//!   memory
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use wrt_component::canonical_abi::canonical_abi::{CanonicalABI, CanonicalMemory, SimpleMemory};
+
+/// Offset of the string's (ptr, len) pair
+const STRING_SLOT: u32 = 0;
+
+/// Offset of the string data
+const STRING_DATA: u32 = 64;
+
+fn bench_canonical_string_lift(c: &mut Criterion) {
+    let text = "latency-critical ünïcödé ".repeat(10);
+    let mut memory = SimpleMemory::new(4096);
+    memory.write_bytes(STRING_DATA, text.as_bytes()).expect("string fits");
+    memory.write_u32_le(STRING_SLOT, STRING_DATA).expect("slot fits");
+    memory.write_u32_le(STRING_SLOT + 4, text.len() as u32).expect("slot fits");
+    let abi = CanonicalABI::new();
+
+    let mut group = c.benchmark_group("critical");
+    group.bench_function("canonical_string_lift", |b| {
+        b.iter(|| abi.lift_string(black_box(&memory), black_box(STRING_SLOT)).expect("lift"))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_canonical_string_lift);
+criterion_main!(benches);
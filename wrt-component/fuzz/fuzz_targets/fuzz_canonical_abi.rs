@@ -0,0 +1,252 @@
+// Embedded in cargo-wrt, which writes it back with
+// `cargo-wrt fuzz --target canonical-abi` when it is missing or stale.
+
+//! Canonical ABI lift/lower fuzz target
+//!
+//! Each input describes a component type tree followed by raw memory
+//! contents. The type is lifted from memory at offset 0; when lifting
+//! succeeds the value must conform to the type, lower back into a fresh
+//! memory and lift to a bit-identical value.
+//!
+//! Input layout:
+//! - type tree, prefix-encoded: one tag byte per node (`tag % 21`, see
+//!   `next_type`), compound nodes followed by an arity byte and their children
+//! - remaining bytes: initial memory contents, zero-padded to `MIN_MEMORY`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wrt_component::canonical_abi::{
+    CanonicalABI, CanonicalMemory, ComponentType, ComponentValue,
+};
+use wrt_error::{Error, Result};
+
+/// Maximum nesting depth of generated type trees
+const MAX_DEPTH: usize = 4;
+/// Maximum number of fields, elements or cases of a compound type
+const MAX_ARITY: u8 = 4;
+/// Minimum memory size, so small inputs still lift scalar types
+const MIN_MEMORY: usize = 1024;
+/// Maximum memory size taken from the input
+const MAX_MEMORY: usize = 64 * 1024;
+
+/// Number of leaf type tags (`Bool` through `String`)
+const LEAF_TAGS: u8 = 13;
+/// Number of type tags in total
+const TYPE_TAGS: u8 = 21;
+
+/// Byte-addressed linear memory backed by a vector
+struct FuzzMemory {
+    data: Vec<u8>,
+}
+
+impl CanonicalMemory for FuzzMemory {
+    fn read_bytes(&self, offset: u32, len: u32) -> Result<Vec<u8>> {
+        let start = offset as usize;
+        let end = start
+            .checked_add(len as usize)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::memory_out_of_bounds("Fuzz memory read out of bounds"))?;
+        Ok(self.data[start..end].to_vec())
+    }
+
+    fn write_bytes(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        let start = offset as usize;
+        let end = start
+            .checked_add(data.len())
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::memory_out_of_bounds("Fuzz memory write out of bounds"))?;
+        self.data[start..end].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn size(&self) -> u32 {
+        self.data.len() as u32
+    }
+}
+
+/// Cursor over the input bytes; reads past the end yield zero
+struct Input<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn next(&mut self) -> u8 {
+        let byte = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        byte
+    }
+
+    fn arity(&mut self) -> u8 {
+        self.next() % MAX_ARITY + 1
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data.get(self.pos..).unwrap_or(&[])
+    }
+}
+
+/// Decode the next type tree node
+fn next_type(input: &mut Input<'_>, depth: usize) -> ComponentType {
+    let tag = if depth >= MAX_DEPTH { input.next() % LEAF_TAGS } else { input.next() % TYPE_TAGS };
+    match tag {
+        0 => ComponentType::Bool,
+        1 => ComponentType::S8,
+        2 => ComponentType::U8,
+        3 => ComponentType::S16,
+        4 => ComponentType::U16,
+        5 => ComponentType::S32,
+        6 => ComponentType::U32,
+        7 => ComponentType::S64,
+        8 => ComponentType::U64,
+        9 => ComponentType::F32,
+        10 => ComponentType::F64,
+        11 => ComponentType::Char,
+        12 => ComponentType::String,
+        13 => ComponentType::List(Box::new(next_type(input, depth + 1))),
+        14 => ComponentType::Record(
+            (0..input.arity())
+                .map(|i| (format!("f{i}"), next_type(input, depth + 1)))
+                .collect(),
+        ),
+        15 => ComponentType::Tuple((0..input.arity()).map(|_| next_type(input, depth + 1)).collect()),
+        16 => ComponentType::Variant(
+            (0..input.arity())
+                .map(|i| {
+                    let payload = (input.next() & 1 != 0).then(|| next_type(input, depth + 1));
+                    (format!("c{i}"), payload)
+                })
+                .collect(),
+        ),
+        17 => ComponentType::Enum((0..input.arity()).map(|i| format!("e{i}")).collect()),
+        18 => ComponentType::Option(Box::new(next_type(input, depth + 1))),
+        19 => {
+            let present = input.next();
+            let ok = (present & 1 != 0).then(|| Box::new(next_type(input, depth + 1)));
+            let err = (present & 2 != 0).then(|| Box::new(next_type(input, depth + 1)));
+            ComponentType::Result(ok, err)
+        },
+        _ => ComponentType::Flags((0..input.arity() * 3).map(|i| format!("x{i}")).collect()),
+    }
+}
+
+/// Check that a lifted value has the shape of its type
+fn conforms(value: &ComponentValue, ty: &ComponentType) -> bool {
+    match (value, ty) {
+        (ComponentValue::Bool(_), ComponentType::Bool)
+        | (ComponentValue::S8(_), ComponentType::S8)
+        | (ComponentValue::U8(_), ComponentType::U8)
+        | (ComponentValue::S16(_), ComponentType::S16)
+        | (ComponentValue::U16(_), ComponentType::U16)
+        | (ComponentValue::S32(_), ComponentType::S32)
+        | (ComponentValue::U32(_), ComponentType::U32)
+        | (ComponentValue::S64(_), ComponentType::S64)
+        | (ComponentValue::U64(_), ComponentType::U64)
+        | (ComponentValue::F32(_), ComponentType::F32)
+        | (ComponentValue::F64(_), ComponentType::F64)
+        | (ComponentValue::Char(_), ComponentType::Char)
+        | (ComponentValue::String(_), ComponentType::String) => true,
+        (ComponentValue::List(items), ComponentType::List(elem)) => {
+            items.iter().all(|item| conforms(item, elem))
+        },
+        (ComponentValue::Record(fields), ComponentType::Record(types)) => {
+            fields.len() == types.len()
+                && fields
+                    .iter()
+                    .zip(types)
+                    .all(|((name, v), (ty_name, t))| name == ty_name && conforms(v, t))
+        },
+        (ComponentValue::Tuple(items), ComponentType::Tuple(types)) => {
+            items.len() == types.len() && items.iter().zip(types).all(|(v, t)| conforms(v, t))
+        },
+        (ComponentValue::Variant(case, payload), ComponentType::Variant(cases)) => {
+            match cases.iter().find(|(name, _)| name == case) {
+                Some((_, Some(t))) => payload.as_deref().is_some_and(|v| conforms(v, t)),
+                Some((_, None)) => payload.is_none(),
+                None => false,
+            }
+        },
+        (ComponentValue::Enum(case), ComponentType::Enum(cases)) => cases.contains(case),
+        (ComponentValue::Option(v), ComponentType::Option(t)) => {
+            v.as_deref().is_none_or(|v| conforms(v, t))
+        },
+        (ComponentValue::Result(v), ComponentType::Result(ok, err)) => {
+            let (payload, ty) = match v {
+                Ok(payload) => (payload, ok),
+                Err(payload) => (payload, err),
+            };
+            match (payload.as_deref(), ty.as_deref()) {
+                (Some(v), Some(t)) => conforms(v, t),
+                (None, None) => true,
+                _ => false,
+            }
+        },
+        (ComponentValue::Flags(active), ComponentType::Flags(all)) => {
+            active.iter().all(|flag| all.contains(flag))
+        },
+        _ => false,
+    }
+}
+
+/// Structural equality that compares floats by bit pattern
+fn identical(a: &ComponentValue, b: &ComponentValue) -> bool {
+    let boxed = |a: &Option<Box<ComponentValue>>, b: &Option<Box<ComponentValue>>| match (a, b) {
+        (Some(a), Some(b)) => identical(a, b),
+        (None, None) => true,
+        _ => false,
+    };
+    match (a, b) {
+        (ComponentValue::F32(a), ComponentValue::F32(b)) => a.to_bits() == b.to_bits(),
+        (ComponentValue::F64(a), ComponentValue::F64(b)) => a.to_bits() == b.to_bits(),
+        (ComponentValue::List(a), ComponentValue::List(b))
+        | (ComponentValue::Tuple(a), ComponentValue::Tuple(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| identical(a, b))
+        },
+        (ComponentValue::Record(a), ComponentValue::Record(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|((na, a), (nb, b))| na == nb && identical(a, b))
+        },
+        (ComponentValue::Variant(ca, a), ComponentValue::Variant(cb, b)) => {
+            ca == cb && boxed(a, b)
+        },
+        (ComponentValue::Option(a), ComponentValue::Option(b)) => boxed(a, b),
+        (ComponentValue::Result(Ok(a)), ComponentValue::Result(Ok(b)))
+        | (ComponentValue::Result(Err(a)), ComponentValue::Result(Err(b))) => boxed(a, b),
+        (a, b) => a == b,
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Input { data, pos: 0 };
+    let ty = next_type(&mut input, 0);
+
+    let mut contents = input.rest()[..input.rest().len().min(MAX_MEMORY)].to_vec();
+    contents.resize(contents.len().max(MIN_MEMORY), 0);
+    let memory_size = contents.len();
+    let memory = FuzzMemory { data: contents };
+
+    let abi = CanonicalABI::new();
+    // Layout queries are total over well-formed types
+    let _ = abi.size_of(&ty);
+    let _ = abi.align_of(&ty);
+
+    // Arbitrary memory may be rejected, but never by panicking
+    let Ok(value) = abi.lift(&memory, &ty, 0) else {
+        return;
+    };
+    assert!(conforms(&value, &ty), "lifted {value:?} does not conform to {ty:?}");
+
+    // Lowering writes out-of-line data after the value, so give it the
+    // original contents' worth of room twice over
+    let mut lowered = FuzzMemory { data: vec![0; memory_size * 2] };
+    abi.lower_typed(&mut lowered, &value, &ty, 0)
+        .unwrap_or_else(|e| panic!("failed to lower {value:?} as {ty:?}: {e}"));
+    let relifted = abi
+        .lift(&lowered, &ty, 0)
+        .unwrap_or_else(|e| panic!("failed to lift lowered {value:?} as {ty:?}: {e}"));
+    assert!(
+        identical(&value, &relifted),
+        "round trip of {ty:?} changed {value:?} into {relifted:?}"
+    );
+});
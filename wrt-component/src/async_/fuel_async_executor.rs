@@ -3530,7 +3530,8 @@ fn resume_from_yield_point(
             Ok(wrt_runtime::stackless::ExecutionResult::Completed(values)) => {
                 // Function completed - clear yield point
                 task.execution_context.last_yield_point = None;
-                let result_bytes = self.serialize_values(values.as_slice()?)?;
+                let values: Vec<wrt_foundation::Value> = values.iter().cloned().collect();
+                let result_bytes = self.serialize_values(&values)?;
                 Ok(ExecutionStepResult::Completed(result_bytes))
             },
             Ok(wrt_runtime::stackless::ExecutionResult::Yielded(yield_info)) => {
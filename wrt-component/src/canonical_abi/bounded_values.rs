@@ -0,0 +1,1089 @@
+//! Canonical ABI lifting and lowering without a heap
+//!
+//! The [`CanonicalABI`](super::CanonicalABI) builds owned values with `Vec`,
+//! `Box` and `String`. This module lifts and lowers the same canonical memory
+//! layout into fixed capacity tables allocated with `safe_managed_alloc!`, so
+//! embedded targets can call components taking and returning records,
+//! variants, enums, flags, options, results and lists.
+//!
+//! Types are built bottom-up in a [`BoundedTypes`] table. The size limits of
+//! every type are compile-time constants: records, tuples, variants, enums
+//! and flags take their arity from the array they are built from, strings and
+//! lists declare their maximum length as a const parameter, and a type
+//! exceeding the limits of this module does not compile. A guest passing a
+//! longer string or list than its type allows fails to lift.
+//!
+//! Values live in a [`BoundedValues`] table and compound values refer to
+//! their elements by [`ValueIdx`]. Lifting appends the value and all of its
+//! elements to the table; clear it between calls.
+//!
+//! Strings are UTF-8.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    bounded::BoundedVec,
+    budget_aware_provider::CrateId,
+    safe_managed_alloc,
+    Checksummable,
+    FromBytes,
+    ToBytes,
+};
+
+use crate::bounded_component_infra::ComponentProvider;
+
+/// Maximum number of types in a [`BoundedTypes`] table
+pub const MAX_TYPES: usize = 64;
+
+/// Maximum number of fields, elements and cases of all types in a table
+pub const MAX_TYPE_MEMBERS: usize = 128;
+
+/// Maximum number of fields of a record or elements of a tuple
+pub const MAX_RECORD_FIELDS: usize = 16;
+
+/// Maximum number of cases of a variant or enum
+pub const MAX_VARIANT_CASES: usize = 32;
+
+/// Maximum number of flags of a flags type
+pub const MAX_FLAGS: usize = 32;
+
+/// Maximum number of items of a list type
+pub const MAX_LIST_ITEMS: u32 = 64;
+
+/// Maximum length in bytes of a string type
+pub const MAX_STRING_BYTES: u32 = 2048;
+
+/// Maximum number of values in a [`BoundedValues`] table
+pub const MAX_VALUES: usize = 128;
+
+/// Size of the provider backing each table
+const TABLE_PROVIDER_SIZE: usize = 4096;
+
+/// Member of a variant case or result without a payload
+const NO_TYPE: u32 = u32::MAX;
+
+/// Kind of a type and of the values of that type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+pub enum TypeKind {
+    /// `bool`
+    #[default]
+    Bool,
+    /// `s8`
+    S8,
+    /// `u8`
+    U8,
+    /// `s16`
+    S16,
+    /// `u16`
+    U16,
+    /// `s32`
+    S32,
+    /// `u32`
+    U32,
+    /// `s64`
+    S64,
+    /// `u64`
+    U64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// `char`
+    Char,
+    /// `string`
+    String,
+    /// `list<T>`
+    List,
+    /// `record`
+    Record,
+    /// `tuple`
+    Tuple,
+    /// `variant`
+    Variant,
+    /// `enum`
+    Enum,
+    /// `flags`
+    Flags,
+    /// `option<T>`
+    Option,
+    /// `result<T, E>`
+    Result,
+    /// `own<R>`
+    Own,
+    /// `borrow<R>`
+    Borrow,
+}
+
+impl TypeKind {
+    /// Whether the kind is a type on its own, without members or limits
+    pub fn is_primitive(self) -> bool {
+        !matches!(
+            self,
+            Self::String
+                | Self::List
+                | Self::Record
+                | Self::Tuple
+                | Self::Variant
+                | Self::Enum
+                | Self::Flags
+                | Self::Option
+                | Self::Result
+        )
+    }
+}
+
+/// A type of a [`BoundedTypes`] table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeIdx(u32);
+
+/// A value of a [`BoundedValues`] table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValueIdx(u32);
+
+/// Size and alignment of a type in linear memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// Size in bytes
+    pub size:  u32,
+    /// Alignment in bytes
+    pub align: u32,
+}
+
+/// Entry of a [`BoundedTypes`] table
+///
+/// `first` is the element type of lists and options, and the index of the
+/// first member of records, tuples, variants and results. `count` is the
+/// number of members, cases or flags, `limit` the maximum length of strings
+/// and lists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+struct TypeNode {
+    kind:  TypeKind,
+    first: u32,
+    count: u32,
+    limit: u32,
+}
+
+/// Entry of a [`BoundedValues`] table
+///
+/// `bits` holds scalars, handles, flags and the case of variants, enums and
+/// results. `first` and `count` are the byte range of strings, the element
+/// range of lists, records and tuples, and the payload of variants, options
+/// and results when `count` is 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+struct ValueNode {
+    kind:  TypeKind,
+    bits:  u64,
+    first: u32,
+    count: u32,
+}
+
+fn table_full() -> Error {
+    Error::capacity_limit_exceeded("Bounded component value table full")
+}
+
+fn invalid_type() -> Error {
+    Error::validation_invalid_type("Invalid type index")
+}
+
+fn invalid_value() -> Error {
+    Error::invalid_value("Invalid value index")
+}
+
+fn type_mismatch() -> Error {
+    Error::runtime_type_mismatch("Value does not match its component type")
+}
+
+fn align_to(offset: u32, align: u32) -> Result<u32> {
+    offset
+        .checked_next_multiple_of(align)
+        .ok_or_else(|| Error::runtime_out_of_bounds("Component type too large"))
+}
+
+/// Size of the discriminant of a variant with `cases` cases
+fn discriminant_size(cases: u32) -> u32 {
+    match cases {
+        0..=0x100 => 1,
+        0x101..=0x1_0000 => 2,
+        _ => 4,
+    }
+}
+
+/// Types whose values can be lifted and lowered
+#[derive(Debug)]
+pub struct BoundedTypes {
+    nodes:   BoundedVec<TypeNode, MAX_TYPES, ComponentProvider>,
+    members: BoundedVec<u32, MAX_TYPE_MEMBERS, ComponentProvider>,
+}
+
+impl BoundedTypes {
+    /// Create an empty table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table memory cannot be allocated.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            nodes:   BoundedVec::new(safe_managed_alloc!(TABLE_PROVIDER_SIZE, CrateId::Component)?)?,
+            members: BoundedVec::new(safe_managed_alloc!(TABLE_PROVIDER_SIZE, CrateId::Component)?)?,
+        })
+    }
+
+    /// Add a type without members, e.g. `u32`, `char` or `own<R>`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `kind` needs members or limits, or the table is
+    /// full.
+    pub fn primitive(&mut self, kind: TypeKind) -> Result<TypeIdx> {
+        if !kind.is_primitive() {
+            return Err(Error::validation_invalid_argument("Type kind is not primitive"));
+        }
+        self.push(kind, 0, 0, 0)
+    }
+
+    /// Add `string` holding at most `MAX_BYTES` bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full.
+    pub fn string<const MAX_BYTES: u32>(&mut self) -> Result<TypeIdx> {
+        const { assert!(MAX_BYTES <= MAX_STRING_BYTES, "String type exceeds MAX_STRING_BYTES") };
+        self.push(TypeKind::String, 0, 0, MAX_BYTES)
+    }
+
+    /// Add `list<element>` holding at most `MAX_ITEMS` items
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `element` is not in the table or the table is
+    /// full.
+    pub fn list<const MAX_ITEMS: u32>(&mut self, element: TypeIdx) -> Result<TypeIdx> {
+        const { assert!(MAX_ITEMS <= MAX_LIST_ITEMS, "List type exceeds MAX_LIST_ITEMS") };
+        self.node(element)?;
+        self.push(TypeKind::List, element.0, 0, MAX_ITEMS)
+    }
+
+    /// Add a record with `fields`, in declaration order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field type is not in the table or the table is
+    /// full.
+    pub fn record<const N: usize>(&mut self, fields: [TypeIdx; N]) -> Result<TypeIdx> {
+        const {
+            assert!(N > 0 && N <= MAX_RECORD_FIELDS, "Record needs 1 to MAX_RECORD_FIELDS fields");
+        }
+        let first = self.push_members(fields.map(Some))?;
+        self.push(TypeKind::Record, first, N as u32, 0)
+    }
+
+    /// Add a tuple of `elements`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an element type is not in the table or the table
+    /// is full.
+    pub fn tuple<const N: usize>(&mut self, elements: [TypeIdx; N]) -> Result<TypeIdx> {
+        const {
+            assert!(N > 0 && N <= MAX_RECORD_FIELDS, "Tuple needs 1 to MAX_RECORD_FIELDS elements");
+        }
+        let first = self.push_members(elements.map(Some))?;
+        self.push(TypeKind::Tuple, first, N as u32, 0)
+    }
+
+    /// Add a variant whose cases carry the given payloads
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a payload type is not in the table or the table is
+    /// full.
+    pub fn variant<const N: usize>(&mut self, cases: [Option<TypeIdx>; N]) -> Result<TypeIdx> {
+        const {
+            assert!(N > 0 && N <= MAX_VARIANT_CASES, "Variant needs 1 to MAX_VARIANT_CASES cases");
+        }
+        let first = self.push_members(cases)?;
+        self.push(TypeKind::Variant, first, N as u32, 0)
+    }
+
+    /// Add an enum with `N` cases
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full.
+    pub fn enumeration<const N: usize>(&mut self) -> Result<TypeIdx> {
+        const {
+            assert!(N > 0 && N <= MAX_VARIANT_CASES, "Enum needs 1 to MAX_VARIANT_CASES cases");
+        }
+        self.push(TypeKind::Enum, 0, N as u32, 0)
+    }
+
+    /// Add flags with `N` flags
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full.
+    pub fn flags<const N: usize>(&mut self) -> Result<TypeIdx> {
+        const { assert!(N > 0 && N <= MAX_FLAGS, "Flags need 1 to MAX_FLAGS flags") };
+        self.push(TypeKind::Flags, 0, N as u32, 0)
+    }
+
+    /// Add `option<payload>`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `payload` is not in the table or the table is
+    /// full.
+    pub fn option(&mut self, payload: TypeIdx) -> Result<TypeIdx> {
+        self.node(payload)?;
+        self.push(TypeKind::Option, payload.0, 1, 0)
+    }
+
+    /// Add `result<ok, err>`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a payload type is not in the table or the table is
+    /// full.
+    pub fn result(&mut self, ok: Option<TypeIdx>, err: Option<TypeIdx>) -> Result<TypeIdx> {
+        let first = self.push_members([ok, err])?;
+        self.push(TypeKind::Result, first, 2, 0)
+    }
+
+    /// Kind of `ty`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ty` is not in the table.
+    pub fn kind(&self, ty: TypeIdx) -> Result<TypeKind> {
+        Ok(self.node(ty)?.kind)
+    }
+
+    /// Size and alignment of values of `ty` in linear memory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ty` is not in the table.
+    pub fn layout(&self, ty: TypeIdx) -> Result<Layout> {
+        let node = self.node(ty)?;
+        let scalar = |size| Ok(Layout { size, align: size });
+        match node.kind {
+            TypeKind::Bool | TypeKind::S8 | TypeKind::U8 => scalar(1),
+            TypeKind::S16 | TypeKind::U16 => scalar(2),
+            TypeKind::S32 | TypeKind::U32 | TypeKind::F32 | TypeKind::Char => scalar(4),
+            TypeKind::Own | TypeKind::Borrow => scalar(4),
+            TypeKind::S64 | TypeKind::U64 | TypeKind::F64 => scalar(8),
+            TypeKind::String | TypeKind::List => Ok(Layout { size: 8, align: 4 }),
+            TypeKind::Record | TypeKind::Tuple => {
+                let mut size = 0;
+                let mut align = 1;
+                for i in 0..node.count {
+                    let field = self.layout(self.member(node, i)?.ok_or_else(invalid_type)?)?;
+                    size = align_to(size, field.align)? + field.size;
+                    align = align.max(field.align);
+                }
+                Ok(Layout { size: align_to(size, align)?, align })
+            },
+            TypeKind::Enum => scalar(discriminant_size(node.count)),
+            TypeKind::Flags => scalar(match node.count {
+                0..=8 => 1,
+                9..=16 => 2,
+                _ => 4,
+            }),
+            TypeKind::Variant | TypeKind::Option | TypeKind::Result => {
+                let cases = self.cases(node);
+                let discriminant = discriminant_size(cases);
+                let mut payload = Layout { size: 0, align: 1 };
+                for case in 0..cases {
+                    if let Some(case_ty) = self.case(node, case)? {
+                        let case = self.layout(case_ty)?;
+                        payload.size = payload.size.max(case.size);
+                        payload.align = payload.align.max(case.align);
+                    }
+                }
+                let align = discriminant.max(payload.align);
+                let size = align_to(align_to(discriminant, payload.align)? + payload.size, align)?;
+                Ok(Layout { size, align })
+            },
+        }
+    }
+
+    /// Offset of the payload of a variant, option or result
+    fn payload_offset(&self, node: TypeNode) -> Result<u32> {
+        let mut align = 1;
+        for case in 0..self.cases(node) {
+            if let Some(case_ty) = self.case(node, case)? {
+                align = self.layout(case_ty)?.align.max(align);
+            }
+        }
+        align_to(discriminant_size(self.cases(node)), align)
+    }
+
+    fn cases(&self, node: TypeNode) -> u32 {
+        match node.kind {
+            TypeKind::Option => 2,
+            _ => node.count,
+        }
+    }
+
+    /// Payload type of `case` of a variant, option or result
+    fn case(&self, node: TypeNode, case: u32) -> Result<Option<TypeIdx>> {
+        match node.kind {
+            TypeKind::Option => Ok((case == 1).then_some(TypeIdx(node.first))),
+            _ => self.member(node, case),
+        }
+    }
+
+    fn member(&self, node: TypeNode, i: u32) -> Result<Option<TypeIdx>> {
+        let member = self.members.get((node.first + i) as usize)?;
+        Ok((member != NO_TYPE).then_some(TypeIdx(member)))
+    }
+
+    fn node(&self, ty: TypeIdx) -> Result<TypeNode> {
+        self.nodes.get(ty.0 as usize).map_err(|_| invalid_type())
+    }
+
+    fn push(&mut self, kind: TypeKind, first: u32, count: u32, limit: u32) -> Result<TypeIdx> {
+        let idx = self.nodes.len() as u32;
+        self.nodes
+            .push(TypeNode {
+                kind,
+                first,
+                count,
+                limit,
+            })
+            .map_err(|_| table_full())?;
+        Ok(TypeIdx(idx))
+    }
+
+    fn push_members<const N: usize>(&mut self, members: [Option<TypeIdx>; N]) -> Result<u32> {
+        for member in members.iter().flatten() {
+            self.node(*member)?;
+        }
+        let first = self.members.len() as u32;
+        for member in members {
+            self.members.push(member.map_or(NO_TYPE, |ty| ty.0)).map_err(|_| table_full())?;
+        }
+        Ok(first)
+    }
+}
+
+/// Consecutive values of a [`BoundedValues`] table: the items of a list or
+/// the fields of a record or tuple
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueRange {
+    first: u32,
+    len:   u32,
+}
+
+impl ValueRange {
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether the range is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The `i`-th value
+    pub fn get(&self, i: usize) -> Option<ValueIdx> {
+        (i < self.len()).then(|| ValueIdx(self.first + i as u32))
+    }
+
+    /// The values in order
+    pub fn iter(&self) -> impl Iterator<Item = ValueIdx> {
+        (self.first..self.first + self.len).map(ValueIdx)
+    }
+}
+
+/// A value of a [`BoundedValues`] table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundedValue<'a> {
+    /// `bool`
+    Bool(bool),
+    /// `s8`
+    S8(i8),
+    /// `u8`
+    U8(u8),
+    /// `s16`
+    S16(i16),
+    /// `u16`
+    U16(u16),
+    /// `s32`
+    S32(i32),
+    /// `u32`
+    U32(u32),
+    /// `s64`
+    S64(i64),
+    /// `u64`
+    U64(u64),
+    /// `f32`
+    F32(f32),
+    /// `f64`
+    F64(f64),
+    /// `char`
+    Char(char),
+    /// `string`
+    String(&'a str),
+    /// Items of a `list`
+    List(ValueRange),
+    /// Fields of a `record`
+    Record(ValueRange),
+    /// Elements of a `tuple`
+    Tuple(ValueRange),
+    /// Case index and payload of a `variant`
+    Variant(u32, Option<ValueIdx>),
+    /// Case index of an `enum`
+    Enum(u32),
+    /// Set `flags`, flag `i` in bit `i`
+    Flags(u32),
+    /// `option`
+    Option(Option<ValueIdx>),
+    /// `result` with the payload of its case
+    Result(core::result::Result<Option<ValueIdx>, Option<ValueIdx>>),
+    /// `own` handle
+    Own(u32),
+    /// `borrow` handle
+    Borrow(u32),
+}
+
+/// Component values lifted from or to be lowered into linear memory
+#[derive(Debug)]
+pub struct BoundedValues {
+    nodes:   BoundedVec<ValueNode, MAX_VALUES, ComponentProvider>,
+    strings: BoundedVec<u8, { MAX_STRING_BYTES as usize }, ComponentProvider>,
+}
+
+impl BoundedValues {
+    /// Create an empty table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table memory cannot be allocated.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            nodes:   BoundedVec::new(safe_managed_alloc!(TABLE_PROVIDER_SIZE, CrateId::Component)?)?,
+            strings: BoundedVec::new(safe_managed_alloc!(TABLE_PROVIDER_SIZE, CrateId::Component)?)?,
+        })
+    }
+
+    /// Number of values, including the elements of compound values
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Remove all values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table memory cannot be reset.
+    pub fn clear(&mut self) -> Result<()> {
+        self.nodes.clear()?;
+        self.strings.clear()?;
+        Ok(())
+    }
+
+    /// Add `value`, copying strings into the table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` refers to values not in the table, or the
+    /// table is full.
+    pub fn add(&mut self, value: BoundedValue<'_>) -> Result<ValueIdx> {
+        let node = self.value_node(value)?;
+        self.push(node)
+    }
+
+    /// Copy `values` to consecutive entries, for use as the items of a list
+    /// or the fields of a record or tuple
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not in the table or the table is full.
+    pub fn sequence(&mut self, values: &[ValueIdx]) -> Result<ValueRange> {
+        let first = self.nodes.len() as u32;
+        for value in values {
+            let node = self.node(*value)?;
+            self.push(node)?;
+        }
+        Ok(ValueRange {
+            first,
+            len: values.len() as u32,
+        })
+    }
+
+    /// Read `value`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` is not in the table.
+    pub fn get(&self, value: ValueIdx) -> Result<BoundedValue<'_>> {
+        let node = self.node(value)?;
+        let range = ValueRange {
+            first: node.first,
+            len:   node.count,
+        };
+        let payload = (node.count == 1).then_some(ValueIdx(node.first));
+        Ok(match node.kind {
+            TypeKind::Bool => BoundedValue::Bool(node.bits != 0),
+            TypeKind::S8 => BoundedValue::S8(node.bits as i8),
+            TypeKind::U8 => BoundedValue::U8(node.bits as u8),
+            TypeKind::S16 => BoundedValue::S16(node.bits as i16),
+            TypeKind::U16 => BoundedValue::U16(node.bits as u16),
+            TypeKind::S32 => BoundedValue::S32(node.bits as i32),
+            TypeKind::U32 => BoundedValue::U32(node.bits as u32),
+            TypeKind::S64 => BoundedValue::S64(node.bits as i64),
+            TypeKind::U64 => BoundedValue::U64(node.bits),
+            TypeKind::F32 => BoundedValue::F32(f32::from_bits(node.bits as u32)),
+            TypeKind::F64 => BoundedValue::F64(f64::from_bits(node.bits)),
+            TypeKind::Char => {
+                BoundedValue::Char(char::from_u32(node.bits as u32).ok_or_else(invalid_value)?)
+            },
+            TypeKind::String => BoundedValue::String(self.string(node)?),
+            TypeKind::List => BoundedValue::List(range),
+            TypeKind::Record => BoundedValue::Record(range),
+            TypeKind::Tuple => BoundedValue::Tuple(range),
+            TypeKind::Variant => BoundedValue::Variant(node.bits as u32, payload),
+            TypeKind::Enum => BoundedValue::Enum(node.bits as u32),
+            TypeKind::Flags => BoundedValue::Flags(node.bits as u32),
+            TypeKind::Option => BoundedValue::Option(payload),
+            TypeKind::Result if node.bits == 0 => BoundedValue::Result(Ok(payload)),
+            TypeKind::Result => BoundedValue::Result(Err(payload)),
+            TypeKind::Own => BoundedValue::Own(node.bits as u32),
+            TypeKind::Borrow => BoundedValue::Borrow(node.bits as u32),
+        })
+    }
+
+    /// Lift a value of type `ty` stored at `addr` in `memory`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is out of bounds or misaligned, is not a
+    /// valid value of `ty`, or does not fit in the table.
+    pub fn lift(
+        &mut self,
+        types: &BoundedTypes,
+        ty: TypeIdx,
+        memory: &[u8],
+        addr: u32,
+    ) -> Result<ValueIdx> {
+        let node = self.load(types, ty, memory, addr)?;
+        self.push(node)
+    }
+
+    /// Lower `value` of type `ty` to `addr` in `memory`
+    ///
+    /// Strings and list items are stored in memory allocated with
+    /// `realloc(align, size)`, which returns the address of the allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` does not match `ty`, a string or list is
+    /// longer than `ty` allows, or memory is out of bounds or misaligned.
+    pub fn lower(
+        &self,
+        types: &BoundedTypes,
+        ty: TypeIdx,
+        value: ValueIdx,
+        memory: &mut [u8],
+        addr: u32,
+        realloc: &mut dyn FnMut(u32, u32) -> Result<u32>,
+    ) -> Result<()> {
+        self.store(types, ty, self.node(value)?, memory, addr, realloc)
+    }
+
+    fn load(
+        &mut self,
+        types: &BoundedTypes,
+        ty: TypeIdx,
+        memory: &[u8],
+        addr: u32,
+    ) -> Result<ValueNode> {
+        let ty_node = types.node(ty)?;
+        let layout = types.layout(ty)?;
+        if addr % layout.align != 0 {
+            return Err(Error::runtime_trap("Misaligned component value"));
+        }
+        let bytes = read(memory, addr, layout.size)?;
+        let mut node = ValueNode {
+            kind: ty_node.kind,
+            ..ValueNode::default()
+        };
+        match ty_node.kind {
+            TypeKind::Bool => node.bits = u64::from(bytes[0] != 0),
+            TypeKind::S8 => node.bits = bytes[0] as i8 as u64,
+            TypeKind::S16 => node.bits = i16::from_le_bytes([bytes[0], bytes[1]]) as u64,
+            TypeKind::S32 => node.bits = read_u32(bytes, 0) as i32 as u64,
+            TypeKind::Char => {
+                let c = read_u32(bytes, 0);
+                char::from_u32(c).ok_or_else(|| Error::runtime_trap("Invalid char value"))?;
+                node.bits = u64::from(c);
+            },
+            TypeKind::Flags => {
+                node.bits = read_uint(bytes) & ((1u64 << ty_node.count) - 1);
+            },
+            TypeKind::Enum => {
+                node.bits = read_uint(bytes);
+                if node.bits >= u64::from(ty_node.count) {
+                    return Err(Error::runtime_trap("Invalid enum case"));
+                }
+            },
+            TypeKind::String => {
+                let (ptr, len) = (read_u32(bytes, 0), read_u32(bytes, 4));
+                if len > ty_node.limit {
+                    return Err(Error::runtime_trap("String exceeds its type limit"));
+                }
+                let s = core::str::from_utf8(read(memory, ptr, len)?)
+                    .map_err(|_| Error::runtime_trap("Invalid UTF-8 string"))?;
+                node = self.value_node(BoundedValue::String(s))?;
+            },
+            TypeKind::List => {
+                let (ptr, len) = (read_u32(bytes, 0), read_u32(bytes, 4));
+                if len > ty_node.limit {
+                    return Err(Error::runtime_trap("List exceeds its type limit"));
+                }
+                let element = TypeIdx(ty_node.first);
+                let size = types.layout(element)?.size;
+                (node.first, node.count) = (self.reserve(len)?, len);
+                for i in 0..len {
+                    let item_addr = ptr
+                        .checked_add(i * size)
+                        .ok_or_else(|| Error::runtime_out_of_bounds("List out of bounds"))?;
+                    let item = self.load(types, element, memory, item_addr)?;
+                    self.nodes.set((node.first + i) as usize, item)?;
+                }
+            },
+            TypeKind::Record | TypeKind::Tuple => {
+                (node.first, node.count) = (self.reserve(ty_node.count)?, ty_node.count);
+                let mut offset = 0;
+                for i in 0..ty_node.count {
+                    let field = types.member(ty_node, i)?.ok_or_else(invalid_type)?;
+                    let field_layout = types.layout(field)?;
+                    offset = align_to(offset, field_layout.align)?;
+                    let value = self.load(types, field, memory, addr + offset)?;
+                    self.nodes.set((node.first + i) as usize, value)?;
+                    offset += field_layout.size;
+                }
+            },
+            TypeKind::Variant | TypeKind::Option | TypeKind::Result => {
+                let cases = types.cases(ty_node);
+                let case = read_uint(&bytes[..discriminant_size(cases) as usize]);
+                if case >= u64::from(cases) {
+                    return Err(Error::runtime_trap("Invalid variant case"));
+                }
+                node.bits = case;
+                if let Some(payload) = types.case(ty_node, case as u32)? {
+                    let offset = types.payload_offset(ty_node)?;
+                    let value = self.load(types, payload, memory, addr + offset)?;
+                    (node.first, node.count) = (self.push(value)?.0, 1);
+                }
+            },
+            _ => node.bits = read_uint(bytes),
+        }
+        Ok(node)
+    }
+
+    fn store(
+        &self,
+        types: &BoundedTypes,
+        ty: TypeIdx,
+        node: ValueNode,
+        memory: &mut [u8],
+        addr: u32,
+        realloc: &mut dyn FnMut(u32, u32) -> Result<u32>,
+    ) -> Result<()> {
+        let ty_node = types.node(ty)?;
+        if node.kind != ty_node.kind {
+            return Err(type_mismatch());
+        }
+        let layout = types.layout(ty)?;
+        if addr % layout.align != 0 {
+            return Err(Error::runtime_trap("Misaligned component value"));
+        }
+        read(memory, addr, layout.size)?;
+        match ty_node.kind {
+            TypeKind::String | TypeKind::List => {
+                if node.count > ty_node.limit {
+                    return Err(Error::runtime_out_of_bounds("Value exceeds its type limit"));
+                }
+                let ptr = if ty_node.kind == TypeKind::String {
+                    let ptr = realloc(1, node.count)?;
+                    write(memory, ptr, self.string(node)?.as_bytes())?;
+                    ptr
+                } else {
+                    let element = TypeIdx(ty_node.first);
+                    let element_layout = types.layout(element)?;
+                    let ptr = realloc(element_layout.align, node.count * element_layout.size)?;
+                    for i in 0..node.count {
+                        let item = self.node(ValueIdx(node.first + i))?;
+                        let item_addr = ptr
+                            .checked_add(i * element_layout.size)
+                            .ok_or_else(|| Error::runtime_out_of_bounds("List out of bounds"))?;
+                        self.store(types, element, item, memory, item_addr, realloc)?;
+                    }
+                    ptr
+                };
+                write(memory, addr, &ptr.to_le_bytes())?;
+                write(memory, addr + 4, &node.count.to_le_bytes())
+            },
+            TypeKind::Record | TypeKind::Tuple => {
+                if node.count != ty_node.count {
+                    return Err(type_mismatch());
+                }
+                let mut offset = 0;
+                for i in 0..ty_node.count {
+                    let field = types.member(ty_node, i)?.ok_or_else(invalid_type)?;
+                    let field_layout = types.layout(field)?;
+                    offset = align_to(offset, field_layout.align)?;
+                    let value = self.node(ValueIdx(node.first + i))?;
+                    self.store(types, field, value, memory, addr + offset, realloc)?;
+                    offset += field_layout.size;
+                }
+                Ok(())
+            },
+            TypeKind::Variant | TypeKind::Option | TypeKind::Result => {
+                let cases = types.cases(ty_node);
+                let case = match ty_node.kind {
+                    TypeKind::Option => u64::from(node.count),
+                    _ => node.bits,
+                };
+                if case >= u64::from(cases) {
+                    return Err(type_mismatch());
+                }
+                let size = discriminant_size(cases) as usize;
+                write(memory, addr, &case.to_le_bytes()[..size])?;
+                match (types.case(ty_node, case as u32)?, node.count) {
+                    (Some(payload), 1) => {
+                        let offset = types.payload_offset(ty_node)?;
+                        let value = self.node(ValueIdx(node.first))?;
+                        self.store(types, payload, value, memory, addr + offset, realloc)
+                    },
+                    (None, 0) => Ok(()),
+                    _ => Err(type_mismatch()),
+                }
+            },
+            TypeKind::Enum if node.bits >= u64::from(ty_node.count) => Err(type_mismatch()),
+            TypeKind::Flags if node.bits >> ty_node.count != 0 => Err(type_mismatch()),
+            _ => write(memory, addr, &node.bits.to_le_bytes()[..layout.size as usize]),
+        }
+    }
+
+    fn value_node(&mut self, value: BoundedValue<'_>) -> Result<ValueNode> {
+        let scalar = |kind, bits| ValueNode {
+            kind,
+            bits,
+            first: 0,
+            count: 0,
+        };
+        let with_payload = |kind, bits, payload: Option<ValueIdx>| ValueNode {
+            kind,
+            bits,
+            first: payload.map_or(0, |p| p.0),
+            count: u32::from(payload.is_some()),
+        };
+        let with_range = |kind, range: ValueRange| ValueNode {
+            kind,
+            bits: 0,
+            first: range.first,
+            count: range.len,
+        };
+        let node = match value {
+            BoundedValue::Bool(v) => scalar(TypeKind::Bool, u64::from(v)),
+            BoundedValue::S8(v) => scalar(TypeKind::S8, v as u64),
+            BoundedValue::U8(v) => scalar(TypeKind::U8, u64::from(v)),
+            BoundedValue::S16(v) => scalar(TypeKind::S16, v as u64),
+            BoundedValue::U16(v) => scalar(TypeKind::U16, u64::from(v)),
+            BoundedValue::S32(v) => scalar(TypeKind::S32, v as u64),
+            BoundedValue::U32(v) => scalar(TypeKind::U32, u64::from(v)),
+            BoundedValue::S64(v) => scalar(TypeKind::S64, v as u64),
+            BoundedValue::U64(v) => scalar(TypeKind::U64, v),
+            BoundedValue::F32(v) => scalar(TypeKind::F32, u64::from(v.to_bits())),
+            BoundedValue::F64(v) => scalar(TypeKind::F64, v.to_bits()),
+            BoundedValue::Char(v) => scalar(TypeKind::Char, u64::from(v)),
+            BoundedValue::String(s) => {
+                let first = self.strings.len() as u32;
+                self.strings.extend_from_slice(s.as_bytes()).map_err(|_| table_full())?;
+                ValueNode {
+                    kind: TypeKind::String,
+                    bits: 0,
+                    first,
+                    count: s.len() as u32,
+                }
+            },
+            BoundedValue::List(range) => with_range(TypeKind::List, range),
+            BoundedValue::Record(range) => with_range(TypeKind::Record, range),
+            BoundedValue::Tuple(range) => with_range(TypeKind::Tuple, range),
+            BoundedValue::Variant(case, payload) => {
+                with_payload(TypeKind::Variant, u64::from(case), payload)
+            },
+            BoundedValue::Enum(case) => scalar(TypeKind::Enum, u64::from(case)),
+            BoundedValue::Flags(flags) => scalar(TypeKind::Flags, u64::from(flags)),
+            BoundedValue::Option(payload) => with_payload(TypeKind::Option, 0, payload),
+            BoundedValue::Result(Ok(payload)) => with_payload(TypeKind::Result, 0, payload),
+            BoundedValue::Result(Err(payload)) => with_payload(TypeKind::Result, 1, payload),
+            BoundedValue::Own(handle) => scalar(TypeKind::Own, u64::from(handle)),
+            BoundedValue::Borrow(handle) => scalar(TypeKind::Borrow, u64::from(handle)),
+        };
+        let refers_to = match node.kind {
+            TypeKind::String => 0,
+            _ => node.first as usize + node.count as usize,
+        };
+        if refers_to > self.nodes.len() {
+            return Err(invalid_value());
+        }
+        Ok(node)
+    }
+
+    fn string(&self, node: ValueNode) -> Result<&str> {
+        let bytes = self.strings.as_slice()?;
+        let s = bytes
+            .get(node.first as usize..(node.first + node.count) as usize)
+            .ok_or_else(invalid_value)?;
+        core::str::from_utf8(s).map_err(|_| invalid_value())
+    }
+
+    fn node(&self, value: ValueIdx) -> Result<ValueNode> {
+        self.nodes.get(value.0 as usize).map_err(|_| invalid_value())
+    }
+
+    fn push(&mut self, node: ValueNode) -> Result<ValueIdx> {
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(node).map_err(|_| table_full())?;
+        Ok(ValueIdx(idx))
+    }
+
+    /// Reserve `count` consecutive entries, returning the first
+    fn reserve(&mut self, count: u32) -> Result<u32> {
+        let first = self.nodes.len() as u32;
+        for _ in 0..count {
+            self.push(ValueNode::default())?;
+        }
+        Ok(first)
+    }
+}
+
+fn read(memory: &[u8], addr: u32, len: u32) -> Result<&[u8]> {
+    let start = addr as usize;
+    memory
+        .get(start..start.saturating_add(len as usize))
+        .ok_or_else(|| Error::memory_out_of_bounds("Component value out of bounds"))
+}
+
+fn write(memory: &mut [u8], addr: u32, bytes: &[u8]) -> Result<()> {
+    let start = addr as usize;
+    memory
+        .get_mut(start..start.saturating_add(bytes.len()))
+        .ok_or_else(|| Error::memory_out_of_bounds("Component value out of bounds"))?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+/// Zero-extend the little endian integer in `bytes`
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |acc, &b| (acc << 8) | u64::from(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compound_values_round_trip() {
+        let mut types = BoundedTypes::new().unwrap();
+        let u8_ty = types.primitive(TypeKind::U8).unwrap();
+        let u32_ty = types.primitive(TypeKind::U32).unwrap();
+        let name_ty = types.string::<16>().unwrap();
+        let point_ty = types.record([u8_ty, u32_ty]).unwrap();
+        let shape_ty = types.variant([None, Some(point_ty), Some(name_ty)]).unwrap();
+        let color_ty = types.enumeration::<3>().unwrap();
+        let perms_ty = types.flags::<10>().unwrap();
+        let maybe_ty = types.option(u32_ty).unwrap();
+        let status_ty = types.result(Some(u8_ty), None).unwrap();
+        let points_ty = types.list::<4>(point_ty).unwrap();
+        let all_ty = types
+            .tuple([shape_ty, color_ty, perms_ty, maybe_ty, status_ty, points_ty])
+            .unwrap();
+
+        assert_eq!(types.layout(point_ty).unwrap(), Layout { size: 8, align: 4 });
+        assert_eq!(types.layout(shape_ty).unwrap(), Layout { size: 12, align: 4 });
+        assert_eq!(types.layout(perms_ty).unwrap(), Layout { size: 2, align: 2 });
+        assert_eq!(types.layout(all_ty).unwrap(), Layout { size: 36, align: 4 });
+
+        let mut values = BoundedValues::new().unwrap();
+        let name = values.add(BoundedValue::String("circle")).unwrap();
+        let shape = values.add(BoundedValue::Variant(2, Some(name))).unwrap();
+        let color = values.add(BoundedValue::Enum(1)).unwrap();
+        let perms = values.add(BoundedValue::Flags(0b10_0000_0101)).unwrap();
+        let some = values.add(BoundedValue::U32(7)).unwrap();
+        let maybe = values.add(BoundedValue::Option(Some(some))).unwrap();
+        let status = values.add(BoundedValue::Result(Err(None))).unwrap();
+        let mut points = [ValueIdx(0); 2];
+        for (i, point) in points.iter_mut().enumerate() {
+            let fields = [
+                values.add(BoundedValue::U8(i as u8)).unwrap(),
+                values.add(BoundedValue::U32(100 + i as u32)).unwrap(),
+            ];
+            let fields = values.sequence(&fields).unwrap();
+            *point = values.add(BoundedValue::Record(fields)).unwrap();
+        }
+        let points = values.sequence(&points).unwrap();
+        let points = values.add(BoundedValue::List(points)).unwrap();
+        let all = values.sequence(&[shape, color, perms, maybe, status, points]).unwrap();
+        let all = values.add(BoundedValue::Tuple(all)).unwrap();
+
+        let mut memory = [0u8; 256];
+        let mut next = 64u32;
+        let mut realloc = |align: u32, size: u32| {
+            let ptr = next.next_multiple_of(align);
+            next = ptr + size;
+            Ok(ptr)
+        };
+        values.lower(&types, all_ty, all, &mut memory, 0, &mut realloc).unwrap();
+
+        let mut lifted = BoundedValues::new().unwrap();
+        let all = lifted.lift(&types, all_ty, &memory, 0).unwrap();
+        let BoundedValue::Tuple(fields) = lifted.get(all).unwrap() else {
+            panic!("expected a tuple");
+        };
+        let field = |i| lifted.get(fields.get(i).unwrap()).unwrap();
+        let BoundedValue::Variant(2, Some(name)) = field(0) else {
+            panic!("expected the name case");
+        };
+        assert_eq!(lifted.get(name).unwrap(), BoundedValue::String("circle"));
+        assert_eq!(field(1), BoundedValue::Enum(1));
+        assert_eq!(field(2), BoundedValue::Flags(0b10_0000_0101));
+        let BoundedValue::Option(Some(some)) = field(3) else {
+            panic!("expected some");
+        };
+        assert_eq!(lifted.get(some).unwrap(), BoundedValue::U32(7));
+        assert_eq!(field(4), BoundedValue::Result(Err(None)));
+        let BoundedValue::List(points) = field(5) else {
+            panic!("expected a list");
+        };
+        assert_eq!(points.len(), 2);
+        let BoundedValue::Record(point) = lifted.get(points.get(1).unwrap()).unwrap() else {
+            panic!("expected a record");
+        };
+        assert_eq!(lifted.get(point.get(1).unwrap()).unwrap(), BoundedValue::U32(101));
+
+        // Guest values beyond the limits of their type do not lift
+        memory[0..4].copy_from_slice(&5u32.to_le_bytes());
+        assert!(lifted.lift(&types, shape_ty, &memory, 0).is_err());
+        memory[0] = 2;
+        memory[8..12].copy_from_slice(&17u32.to_le_bytes());
+        assert!(lifted.lift(&types, shape_ty, &memory, 0).is_err());
+        assert!(values.lower(&types, point_ty, all, &mut memory, 0, &mut realloc).is_err());
+    }
+}
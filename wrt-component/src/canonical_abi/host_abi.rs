@@ -30,7 +30,7 @@
 use alloc::string::String;
 
 use wrt_error::{Error, Result};
-use wrt_foundation::values::Value as FoundationValue;
+use wrt_foundation::{value_interop::ValueInterop, values::Value as FoundationValue};
 
 use super::{
     CanonicalABI, CanonicalMemory, ComponentType, ComponentValue,
@@ -108,7 +108,7 @@ pub fn lower_result<M: CanonicalMemory>(
 
             // Convert result to ComponentValue and lower to memory
             if let Some(foundation_val) = result.first() {
-                let component_val = self.foundation_to_component(foundation_val)?;
+                let component_val = ComponentValue::try_from_value(foundation_val.clone())?;
 
                 // Lower the component value to memory at retptr
                 self.abi.lower(memory, &component_val, retptr)?;
@@ -148,47 +148,6 @@ fn result_needs_retptr(&self) -> bool {
         }
     }
 
-    /// Convert a FoundationValue to a ComponentValue.
-    fn foundation_to_component(&self, val: &FoundationValue) -> Result<ComponentValue> {
-        match val {
-            FoundationValue::Bool(b) => Ok(ComponentValue::Bool(*b)),
-            FoundationValue::S8(v) => Ok(ComponentValue::S8(*v)),
-            FoundationValue::U8(v) => Ok(ComponentValue::U8(*v)),
-            FoundationValue::S16(v) => Ok(ComponentValue::S16(*v)),
-            FoundationValue::U16(v) => Ok(ComponentValue::U16(*v)),
-            FoundationValue::S32(v) => Ok(ComponentValue::S32(*v)),
-            FoundationValue::U32(v) => Ok(ComponentValue::U32(*v)),
-            FoundationValue::I32(v) => Ok(ComponentValue::S32(*v)),
-            FoundationValue::S64(v) => Ok(ComponentValue::S64(*v)),
-            FoundationValue::U64(v) => Ok(ComponentValue::U64(*v)),
-            FoundationValue::I64(v) => Ok(ComponentValue::S64(*v)),
-            FoundationValue::Char(c) => Ok(ComponentValue::Char(*c)),
-            FoundationValue::String(s) => Ok(ComponentValue::String(s.clone())),
-            FoundationValue::Tuple(items) => {
-                let mut converted = Vec::new();
-                for item in items {
-                    converted.push(self.foundation_to_component(item)?);
-                }
-                Ok(ComponentValue::Tuple(converted))
-            }
-            FoundationValue::Record(fields) => {
-                let mut converted = Vec::new();
-                for (name, val) in fields {
-                    converted.push((name.clone(), self.foundation_to_component(val)?));
-                }
-                Ok(ComponentValue::Record(converted))
-            }
-            FoundationValue::List(items) => {
-                let mut converted = Vec::new();
-                for item in items {
-                    converted.push(self.foundation_to_component(item)?);
-                }
-                Ok(ComponentValue::List(converted))
-            }
-            _ => Err(Error::validation_error("Unsupported value type for component conversion")),
-        }
-    }
-
     /// Flatten a FoundationValue to core WASM values for stack return.
     fn flatten_to_core(&self, val: &FoundationValue, _ty: &ComponentType) -> Result<Vec<FoundationValue>> {
         match val {
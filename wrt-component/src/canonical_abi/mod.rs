@@ -4,11 +4,13 @@
 //! WebAssembly Component Model, including lifting, lowering, and memory
 //! allocation functions.
 
+pub mod bounded_values;
 pub mod canonical;
 pub mod canonical_abi;
 pub mod canonical_options;
 pub mod canonical_realloc;
 pub mod post_return;
+mod value_interop;
 
 // Re-export from canonical module (primary implementation)
 pub use canonical::*;
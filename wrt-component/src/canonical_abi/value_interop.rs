@@ -0,0 +1,159 @@
+//! Conversion between canonical ABI component values and foundation values
+//!
+//! [`ComponentValue`] converts losslessly to `wrt_foundation::values::Value`
+//! through [`ValueInterop`]. A `result` without a payload maps to
+//! `Value::Void` inside `Value::Result`, which has no other meaning for
+//! component values and so round-trips exactly.
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use std::boxed::Box;
+
+use wrt_error::Result;
+use wrt_foundation::{
+    float_repr::{FloatBits32, FloatBits64},
+    value_interop::{
+        normalize_core_value, unrepresentable_value, values_from, values_into, ValueInterop,
+    },
+    values::Value,
+};
+
+use super::ComponentValue;
+
+/// Convert an optional `result` payload, using `Value::Void` for "none"
+fn payload_into_value(payload: Option<Box<ComponentValue>>) -> Value {
+    payload.map_or(Value::Void, |v| v.into_value())
+}
+
+fn payload_from_value(payload: Value) -> Result<Option<Box<ComponentValue>>> {
+    match payload {
+        Value::Void => Ok(None),
+        value => Ok(Some(Box::new(ComponentValue::try_from_value(value)?))),
+    }
+}
+
+impl ValueInterop for ComponentValue {
+    fn into_value(self) -> Value {
+        match self {
+            ComponentValue::Bool(v) => Value::Bool(v),
+            ComponentValue::S8(v) => Value::S8(v),
+            ComponentValue::U8(v) => Value::U8(v),
+            ComponentValue::S16(v) => Value::S16(v),
+            ComponentValue::U16(v) => Value::U16(v),
+            ComponentValue::S32(v) => Value::S32(v),
+            ComponentValue::U32(v) => Value::U32(v),
+            ComponentValue::S64(v) => Value::S64(v),
+            ComponentValue::U64(v) => Value::U64(v),
+            ComponentValue::F32(v) => Value::F32(FloatBits32::from_float(v)),
+            ComponentValue::F64(v) => Value::F64(FloatBits64::from_float(v)),
+            ComponentValue::Char(v) => Value::Char(v),
+            ComponentValue::String(v) => Value::String(v),
+            ComponentValue::List(items) => Value::List(values_into(items)),
+            ComponentValue::Record(fields) => Value::Record(
+                fields.into_iter().map(|(name, value)| (name, value.into_value())).collect(),
+            ),
+            ComponentValue::Tuple(items) => Value::Tuple(values_into(items)),
+            ComponentValue::Variant(case, payload) => {
+                Value::Variant(case, payload.map(|v| Box::new(v.into_value())))
+            }
+            ComponentValue::Enum(case) => Value::Enum(case),
+            ComponentValue::Option(v) => Value::Option(v.map(|v| Box::new(v.into_value()))),
+            ComponentValue::Result(v) => Value::Result(match v {
+                Ok(payload) => Ok(Box::new(payload_into_value(payload))),
+                Err(payload) => Err(Box::new(payload_into_value(payload))),
+            }),
+            ComponentValue::Flags(names) => Value::Flags(names),
+        }
+    }
+
+    fn try_from_value(value: Value) -> Result<Self> {
+        let boxed = |v: Box<Value>| Self::try_from_value(*v).map(Box::new);
+        Ok(match normalize_core_value(value) {
+            Value::Bool(v) => ComponentValue::Bool(v),
+            Value::S8(v) => ComponentValue::S8(v),
+            Value::U8(v) => ComponentValue::U8(v),
+            Value::S16(v) => ComponentValue::S16(v),
+            Value::U16(v) => ComponentValue::U16(v),
+            Value::S32(v) => ComponentValue::S32(v),
+            Value::U32(v) => ComponentValue::U32(v),
+            Value::S64(v) => ComponentValue::S64(v),
+            Value::U64(v) => ComponentValue::U64(v),
+            Value::F32(v) => ComponentValue::F32(f32::from_bits(v.0)),
+            Value::F64(v) => ComponentValue::F64(f64::from_bits(v.0)),
+            Value::Char(v) => ComponentValue::Char(v),
+            Value::String(v) => ComponentValue::String(v),
+            Value::List(items) => ComponentValue::List(values_from(items)?),
+            Value::Record(fields) => ComponentValue::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, Self::try_from_value(value)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Tuple(items) => ComponentValue::Tuple(values_from(items)?),
+            Value::Variant(case, payload) => {
+                ComponentValue::Variant(case, payload.map(boxed).transpose()?)
+            }
+            Value::Enum(case) => ComponentValue::Enum(case),
+            Value::Option(v) => ComponentValue::Option(v.map(boxed).transpose()?),
+            Value::Result(v) => ComponentValue::Result(match v {
+                Ok(payload) => Ok(payload_from_value(*payload)?),
+                Err(payload) => Err(payload_from_value(*payload)?),
+            }),
+            Value::Flags(names) => ComponentValue::Flags(names),
+            _ => return Err(unrepresentable_value()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    use super::*;
+
+    fn round_trip(value: ComponentValue) {
+        let back = ComponentValue::try_from_value(value.clone().into_value()).unwrap();
+        assert_eq!(back, value);
+    }
+
+    #[test]
+    fn test_component_value_round_trip() {
+        round_trip(ComponentValue::U32(7));
+        round_trip(ComponentValue::F64(-0.5));
+        round_trip(ComponentValue::Char('ß'));
+        round_trip(ComponentValue::Record(vec![
+            ("name".into(), ComponentValue::String("wrt".into())),
+            ("tags".into(), ComponentValue::List(vec![ComponentValue::Enum("a".into())])),
+        ]));
+        round_trip(ComponentValue::Variant(
+            "some".into(),
+            Some(Box::new(ComponentValue::Bool(true))),
+        ));
+        round_trip(ComponentValue::Option(None));
+        round_trip(ComponentValue::Result(Ok(None)));
+        round_trip(ComponentValue::Result(Err(Some(Box::new(ComponentValue::S8(-1))))));
+        round_trip(ComponentValue::Flags(vec!["read".into()]));
+
+        let nan = ComponentValue::F32(f32::from_bits(0x7fc0_0001)).into_value();
+        match ComponentValue::try_from_value(nan).unwrap() {
+            ComponentValue::F32(v) => assert_eq!(v.to_bits(), 0x7fc0_0001),
+            other => panic!("unexpected value {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_core_values_and_unrepresentable() {
+        assert_eq!(
+            ComponentValue::try_from_value(Value::I32(-3)).unwrap(),
+            ComponentValue::S32(-3)
+        );
+        assert_eq!(
+            ComponentValue::try_from_value(Value::I64(9)).unwrap(),
+            ComponentValue::S64(9)
+        );
+        assert!(ComponentValue::try_from_value(Value::Own(1)).is_err());
+        assert!(ComponentValue::try_from_value(Value::List(vec![Value::Void])).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Inter-component call graph
+//!
+//! Records which import of which component is bound to which export of which
+//! provider while the [`ComponentLinker`](super::ComponentLinker) resolves a
+//! composition. The graph can be queried directly or rendered as Graphviz DOT
+//! or JSON to audit large compositions.
+
+use std::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::component_linker::ComponentId;
+
+/// Kind of item bound across a component boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    /// Function import bound to a function export
+    Function,
+    /// Memory import bound to a memory export
+    Memory,
+    /// Table binding
+    Table,
+    /// Global binding
+    Global,
+    /// Type binding
+    Type,
+    /// Whole interface instance provided by the host
+    Instance,
+}
+
+impl BindingKind {
+    /// Lower-case name used in DOT and JSON output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Memory => "memory",
+            Self::Table => "table",
+            Self::Global => "global",
+            Self::Type => "type",
+            Self::Instance => "instance",
+        }
+    }
+}
+
+/// Provider that satisfies an import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingProvider {
+    /// Export of another registered component
+    Component(ComponentId),
+    /// Host interface (e.g. WASI)
+    Host,
+}
+
+/// One import-to-export binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallBinding {
+    /// Component that declares the import
+    pub importer: ComponentId,
+    /// Import name
+    pub import:   String,
+    /// Provider the import is bound to
+    pub provider: BindingProvider,
+    /// Name of the export that satisfies the import
+    pub export:   String,
+    /// Kind of the bound item
+    pub kind:     BindingKind,
+}
+
+/// Import that no provider could satisfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedImport {
+    /// Component that declares the import
+    pub importer: ComponentId,
+    /// Import name
+    pub import:   String,
+}
+
+/// Call graph between the components of a composition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    components: Vec<ComponentId>,
+    bindings:   Vec<CallBinding>,
+    unresolved: Vec<UnresolvedImport>,
+}
+
+impl CallGraph {
+    /// Create an empty call graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a component node; adding the same component twice is a no-op
+    pub fn add_component(&mut self, component_id: &str) {
+        if !self.components.iter().any(|c| c == component_id) {
+            self.components.push(component_id.to_string());
+        }
+    }
+
+    /// Remove a component together with every binding that involves it
+    pub fn remove_component(&mut self, component_id: &str) {
+        self.components.retain(|c| c != component_id);
+        self.bindings.retain(|b| {
+            b.importer != component_id
+                && b.provider != BindingProvider::Component(component_id.to_string())
+        });
+        self.unresolved.retain(|u| u.importer != component_id);
+    }
+
+    /// Replace the bindings recorded for `importer`
+    ///
+    /// Called each time the linker resolves the imports of a component so
+    /// that re-linking never leaves stale edges behind.
+    pub fn set_bindings(
+        &mut self,
+        importer: &str,
+        bindings: Vec<CallBinding>,
+        unresolved: Vec<UnresolvedImport>,
+    ) {
+        self.add_component(importer);
+        self.bindings.retain(|b| b.importer != importer);
+        self.unresolved.retain(|u| u.importer != importer);
+        self.bindings.extend(bindings);
+        self.unresolved.extend(unresolved);
+    }
+
+    /// Component nodes in registration order
+    pub fn components(&self) -> &[ComponentId] {
+        &self.components
+    }
+
+    /// All recorded bindings
+    pub fn bindings(&self) -> &[CallBinding] {
+        &self.bindings
+    }
+
+    /// Imports that could not be bound to any provider
+    pub fn unresolved(&self) -> &[UnresolvedImport] {
+        &self.unresolved
+    }
+
+    /// Bindings whose imports are declared by `importer`
+    pub fn imports_of<'a>(&'a self, importer: &'a str) -> impl Iterator<Item = &'a CallBinding> {
+        self.bindings.iter().filter(move |b| b.importer == importer)
+    }
+
+    /// Bindings served by exports of `provider`
+    pub fn callers_of<'a>(&'a self, provider: &'a str) -> impl Iterator<Item = &'a CallBinding> {
+        self.bindings
+            .iter()
+            .filter(move |b| matches!(&b.provider, BindingProvider::Component(p) if p == provider))
+    }
+
+    /// Whether any import is bound to the host
+    pub fn uses_host(&self) -> bool {
+        self.bindings.iter().any(|b| b.provider == BindingProvider::Host)
+    }
+
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// Components are boxes, the host is a single dashed node and unresolved
+    /// imports point at red placeholder nodes. Each edge is labelled with the
+    /// import name, plus the export name when the two differ.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph components {\n    rankdir=LR;\n");
+        out.push_str("    node [shape=box];\n");
+        for component in &self.components {
+            out.push_str(&format!("    {};\n", dot_id(component)));
+        }
+        if self.uses_host() {
+            out.push_str("    \"<host>\" [shape=ellipse, style=dashed];\n");
+        }
+
+        for binding in &self.bindings {
+            let target = match &binding.provider {
+                BindingProvider::Component(id) => dot_id(id),
+                BindingProvider::Host => dot_id("<host>"),
+            };
+            let label = if binding.import == binding.export {
+                binding.import.clone()
+            } else {
+                format!("{} -> {}", binding.import, binding.export)
+            };
+            out.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                dot_id(&binding.importer),
+                target,
+                dot_id(&label)
+            ));
+        }
+
+        for (index, unresolved) in self.unresolved.iter().enumerate() {
+            let node = format!("<unresolved {}>", index);
+            out.push_str(&format!(
+                "    {} [label={}, shape=plaintext, fontcolor=red];\n",
+                dot_id(&node),
+                dot_id(&unresolved.import)
+            ));
+            out.push_str(&format!(
+                "    {} -> {} [style=dashed, color=red];\n",
+                dot_id(&unresolved.importer),
+                dot_id(&node)
+            ));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph as a JSON document
+    ///
+    /// The document has `components`, `bindings` and `unresolved` arrays. A
+    /// binding's `provider` is `null` when the host serves the import.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"components\":[");
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            push_json_string(&mut out, component);
+        }
+
+        out.push_str("],\"bindings\":[");
+        for (i, binding) in self.bindings.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"importer\":");
+            push_json_string(&mut out, &binding.importer);
+            out.push_str(",\"import\":");
+            push_json_string(&mut out, &binding.import);
+            out.push_str(",\"provider\":");
+            match &binding.provider {
+                BindingProvider::Component(id) => push_json_string(&mut out, id),
+                BindingProvider::Host => out.push_str("null"),
+            }
+            out.push_str(",\"export\":");
+            push_json_string(&mut out, &binding.export);
+            out.push_str(",\"kind\":");
+            push_json_string(&mut out, binding.kind.as_str());
+            out.push('}');
+        }
+
+        out.push_str("],\"unresolved\":[");
+        for (i, unresolved) in self.unresolved.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"importer\":");
+            push_json_string(&mut out, &unresolved.importer);
+            out.push_str(",\"import\":");
+            push_json_string(&mut out, &unresolved.import);
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+/// Quote a string as a DOT identifier
+fn dot_id(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Append `value` as a JSON string literal
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CallGraph {
+        let mut graph = CallGraph::new();
+        graph.add_component("app");
+        graph.add_component("db");
+        graph.set_bindings(
+            "app",
+            vec![
+                CallBinding {
+                    importer: "app".to_string(),
+                    import:   "query".to_string(),
+                    provider: BindingProvider::Component("db".to_string()),
+                    export:   "query".to_string(),
+                    kind:     BindingKind::Function,
+                },
+                CallBinding {
+                    importer: "app".to_string(),
+                    import:   "wasi:cli/stdout".to_string(),
+                    provider: BindingProvider::Host,
+                    export:   "wasi:cli/stdout".to_string(),
+                    kind:     BindingKind::Instance,
+                },
+            ],
+            vec![UnresolvedImport {
+                importer: "app".to_string(),
+                import:   "metrics".to_string(),
+            }],
+        );
+        graph
+    }
+
+    #[test]
+    fn test_queries_and_rebinding() {
+        let mut graph = sample();
+        assert_eq!(graph.imports_of("app").count(), 2);
+        assert_eq!(graph.callers_of("db").count(), 1);
+        assert!(graph.uses_host());
+
+        graph.set_bindings("app", Vec::new(), Vec::new());
+        assert!(graph.bindings().is_empty());
+        assert!(graph.unresolved().is_empty());
+
+        let mut graph = sample();
+        graph.remove_component("db");
+        assert_eq!(graph.components(), &["app".to_string()]);
+        assert_eq!(graph.bindings().len(), 1);
+    }
+
+    #[test]
+    fn test_dot_and_json_rendering() {
+        let graph = sample();
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph components {"));
+        assert!(dot.contains("\"app\" -> \"db\" [label=\"query\"];"));
+        assert!(dot.contains("\"app\" -> \"<host>\""));
+        assert!(dot.contains("fontcolor=red"));
+
+        let json = graph.to_json();
+        assert!(json.starts_with("{\"components\":[\"app\",\"db\"]"));
+        assert!(json.contains("\"provider\":\"db\""));
+        assert!(json.contains("\"provider\":null"));
+        assert!(json.contains("\"unresolved\":[{\"importer\":\"app\",\"import\":\"metrics\"}]"));
+
+        let mut quoted = CallGraph::new();
+        quoted.add_component("a\"b");
+        assert_eq!(
+            quoted.to_json(),
+            "{\"components\":[\"a\\\"b\"],\"bindings\":[],\"unresolved\":[]}"
+        );
+    }
+}
@@ -640,6 +640,7 @@ pub fn new(ty: MemoryType) -> Result<Self> {
         let core_ty = wrt_runtime::CoreMemoryType {
             limits: ty.limits,
             shared: ty.shared,
+            memory64: ty.memory64,
         };
         let memory = Memory::new(core_ty)?;
         Ok(Self {
@@ -667,6 +668,7 @@ pub fn new_with_name(ty: MemoryType, name: &str) -> Result<Self> {
         let core_ty = wrt_runtime::CoreMemoryType {
             limits: ty.limits,
             shared: ty.shared,
+            memory64: ty.memory64,
         };
         let memory = Memory::new_with_name(core_ty, name)?;
         Ok(Self {
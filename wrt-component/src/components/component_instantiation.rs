@@ -836,6 +836,24 @@ pub fn from_parsed(
         id: InstanceId,
         parsed: &mut wrt_format::component::Component,
         host_registry: Option<std::sync::Arc<wrt_host::CallbackRegistry>>,
+    ) -> Result<Self> {
+        use crate::components::component_linker::ComponentLinker;
+        Self::from_parsed_with_linker(id, parsed, host_registry, &mut ComponentLinker::new())
+    }
+
+    /// Create and initialize a component instance, resolving its imports
+    /// through `linker`
+    ///
+    /// Imports are first matched against the exports of the components
+    /// registered with `linker`, then against the WASI host provider. This
+    /// is how a component whose imports are satisfied by another component
+    /// is instantiated; see [`CompositionGraph`](super::CompositionGraph).
+    #[cfg(feature = "std")]
+    pub fn from_parsed_with_linker(
+        id: InstanceId,
+        parsed: &mut wrt_format::component::Component,
+        host_registry: Option<std::sync::Arc<wrt_host::CallbackRegistry>>,
+        linker: &mut crate::components::component_linker::ComponentLinker,
     ) -> Result<Self> {
         #[cfg(feature = "tracing")]
         trace!("from_parsed: ENTERED, Component passed by reference");
@@ -899,10 +917,6 @@ pub fn from_parsed(
         // 2. WASI host provider imports
         // 3. Fails loud on unresolved imports
         #[cfg(feature = "tracing")]
-        trace!("from_parsed: About to create ComponentLinker (unified)");
-        use crate::components::component_linker::ComponentLinker;
-        let mut linker = ComponentLinker::new();
-        #[cfg(feature = "tracing")]
         trace!("from_parsed: About to link_imports");
         let resolved_imports = linker.link_imports(&parsed.imports)?;
         #[cfg(feature = "tracing")]
@@ -1183,6 +1197,9 @@ pub fn from_parsed(
             // For proper handling, we track all function alias dest_idx values and assign
             // canon.lower indices to the remaining slots.
             let mut canon_lower_map: HashMap<u32, (u32, String, String)> = HashMap::new();
+            // Canonical options of each canon.lower, by the core function index it creates
+            let mut canon_lower_options: HashMap<u32, wrt_format::component::LowerOptions> =
+                HashMap::new();
             {
                 use wrt_format::component::CanonOperation;
 
@@ -1219,11 +1236,12 @@ pub fn from_parsed(
                 let mut canon_core_idx = 0u32;
                 for canon in &parsed.canonicals {
                     match &canon.operation {
-                        CanonOperation::Lower { func_idx, .. } => {
+                        CanonOperation::Lower { func_idx, options } => {
                             // Skip indices used by aliases
                             while alias_func_indices.contains(&canon_core_idx) {
                                 canon_core_idx += 1;
                             }
+                            canon_lower_options.insert(canon_core_idx, options.clone());
 
                             // Look up the component function to get WASI name
                             // Try: 1) Direct import map, 2) Component alias map (InstanceExport aliases)
@@ -1777,7 +1795,7 @@ pub fn from_parsed(
                                                             );
                                                             for (
                                                                 i,
-                                                                (_name, _idx, interface, function),
+                                                                (_name, core_func_idx, interface, function),
                                                             ) in
                                                                 lowered_exports.iter().enumerate()
                                                             {
@@ -1788,13 +1806,28 @@ pub fn from_parsed(
                                                                     "    │  ├─ Lowered func[{}] = {}::{}",
                                                                     func_idx, interface, function
                                                                 );
+                                                                let options = canon_lower_options
+                                                                    .get(core_func_idx)
+                                                                    .ok_or_else(|| {
+                                                                        Error::runtime_error(
+                                                                            "Lowered function has no canonical options",
+                                                                        )
+                                                                    })?;
+                                                                let options = Self::resolve_canon_options(
+                                                                    &engine,
+                                                                    &alias_map,
+                                                                    &core_instances_map,
+                                                                    options.memory_idx,
+                                                                    options.realloc_func_idx,
+                                                                    None,
+                                                                    options.string_encoding.as_ref(),
+                                                                )?;
                                                                 engine.register_lowered_function(
                                                                     instance_handle.index(),
                                                                     func_idx,
                                                                     interface.clone(),
                                                                     function.clone(),
-                                                                    None, // memory_idx
-                                                                    None, // realloc_idx
+                                                                    options,
                                                                 );
                                                             }
                                                         }
@@ -2022,6 +2055,36 @@ pub fn from_parsed(
                     },
                 }
             }
+
+            // Register the canonical options of lifted functions so that calls
+            // to them schedule their post-return
+            for canon in &parsed.canonicals {
+                let wrt_format::component::CanonOperation::Lift { func_idx, options, .. } =
+                    &canon.operation
+                else {
+                    continue;
+                };
+                let (handle, export_name) = alias_map
+                    .get(&(CoreSort::Function, *func_idx))
+                    .and_then(|(instance_idx, name)| {
+                        core_instances_map.get(&(*instance_idx as usize)).map(|h| (*h, name))
+                    })
+                    .ok_or_else(|| {
+                        Error::runtime_error("Lifted function is not exported by a core instance")
+                    })?;
+                let options = Self::resolve_canon_options(
+                    &engine,
+                    &alias_map,
+                    &core_instances_map,
+                    options.memory_idx,
+                    options.realloc_func_idx,
+                    options.post_return_func_idx,
+                    options.string_encoding.as_ref(),
+                )?;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(export = %export_name, "Registering lifted function");
+                engine.register_lifted_function(handle, export_name, options)?;
+            }
             println!();
         }
 
@@ -2693,6 +2756,52 @@ pub fn resolve_type_safe(&self, _idx: u32) -> Result<()> {
         Ok(())
     }
 
+    /// Resolve canonical options to the engine instances exporting their
+    /// memory and functions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option refers to an item no instantiated core
+    /// instance exports.
+    #[cfg(feature = "std")]
+    fn resolve_canon_options(
+        engine: &wrt_runtime::engine::CapabilityAwareEngine,
+        alias_map: &HashMap<(wrt_format::component::CoreSort, u32), (u32, String)>,
+        core_instances: &std::collections::BTreeMap<usize, wrt_runtime::engine::InstanceHandle>,
+        memory_idx: Option<u32>,
+        realloc_idx: Option<u32>,
+        post_return_idx: Option<u32>,
+        string_encoding: Option<&wrt_format::component::StringEncoding>,
+    ) -> Result<wrt_runtime::stackless::canon::CanonOptions> {
+        use wrt_format::component::CoreSort;
+
+        let exported = |sort: CoreSort, idx: u32| {
+            alias_map
+                .get(&(sort, idx))
+                .and_then(|(instance_idx, name)| {
+                    core_instances.get(&(*instance_idx as usize)).map(|handle| (*handle, name))
+                })
+                .ok_or_else(|| {
+                    Error::runtime_error("Canonical option refers to an item no core instance exports")
+                })
+        };
+        let function = |idx: Option<u32>| {
+            idx.map(|idx| {
+                let (handle, name) = exported(CoreSort::Function, idx)?;
+                engine.core_func_ref(handle, name)
+            })
+            .transpose()
+        };
+        Ok(wrt_runtime::stackless::canon::CanonOptions {
+            memory_instance: memory_idx
+                .map(|idx| exported(CoreSort::Memory, idx).map(|(handle, _)| handle.index()))
+                .transpose()?,
+            realloc:         function(realloc_idx)?,
+            post_return:     function(post_return_idx)?,
+            string_encoding: string_encoding.map(Into::into).unwrap_or_default(),
+        })
+    }
+
     /// Validate component against ASIL-D safety limits
     ///
     /// Fails fast if any limit is exceeded, preventing resource exhaustion.
@@ -4550,11 +4659,11 @@ fn call_native_function(
                     })
                     .collect();
 
-                // Try _initialize first (important for TinyGo components)
-                println!("[CALL_NATIVE] Attempting to call _initialize...");
-                match engine.execute(instance_handle, "_initialize", &[]) {
-                    Ok(_) => println!("[CALL_NATIVE] ✓ _initialize completed"),
-                    Err(e) => println!("[CALL_NATIVE] ⚠ _initialize skipped: {:?}", e),
+                // Run _initialize first (important for TinyGo components)
+                if engine.has_function(instance_handle, "_initialize")? {
+                    println!("[CALL_NATIVE] Calling _initialize...");
+                    engine.execute(instance_handle, "_initialize", &[])?;
+                    println!("[CALL_NATIVE] ✓ _initialize completed");
                 }
 
                 // Try entry point functions in order of preference:
@@ -4573,54 +4682,49 @@ fn call_native_function(
                     "run",
                     "main",
                 ];
-                let mut last_error = None;
-
+                // Only a missing export moves on to the next entry point; an
+                // entry point that fails reports its own error
                 for entry_point in entry_points {
-                    println!("[CALL_NATIVE] Trying entry point: {}...", entry_point);
-                    match engine.execute(instance_handle, entry_point, &wasm_args) {
-                        Ok(results) => {
-                            println!(
-                                "[CALL_NATIVE] ✓ {} completed with {} results",
-                                entry_point,
-                                results.len()
-                            );
-                            return Ok(vec![]); // wasi:cli/run returns nothing on success
-                        },
-                        Err(e) => {
-                            println!(
-                                "[CALL_NATIVE] ⚠ {} not found or failed: {:?}",
-                                entry_point, e
-                            );
-                            last_error = Some(e);
-                        },
+                    if !engine.has_function(instance_handle, entry_point)? {
+                        println!("[CALL_NATIVE] ⚠ {} not found", entry_point);
+                        continue;
                     }
+                    println!("[CALL_NATIVE] Calling entry point: {}...", entry_point);
+                    let results = engine.execute(instance_handle, entry_point, &wasm_args)?;
+                    println!(
+                        "[CALL_NATIVE] ✓ {} completed with {} results",
+                        entry_point,
+                        results.len()
+                    );
+                    // The results are not lifted any further, so the
+                    // post-return can run right away
+                    engine.run_post_return()?;
+                    return Ok(vec![]); // wasi:cli/run returns nothing on success
                 }
 
-                // All entry points failed - check available exports for debugging
-                if let Some(e) = last_error {
-                    println!("[CALL_NATIVE] ✗ All entry points failed");
-                    // Debug: check for common exports (core module exports only)
-                    let common_exports = [
-                        "_start",
-                        "_initialize",
-                        "run",
-                        "main",
-                        "memory",
-                        "__heap_base",
-                        "__data_end",
-                        "cabi_realloc",
-                        "__wasm_call_ctors",
-                    ];
-                    println!("[CALL_NATIVE] Checking for common exports:");
-                    for name in common_exports {
-                        match engine.has_function(instance_handle, name) {
-                            Ok(true) => println!("    ✓ {} - EXISTS", name),
-                            Ok(false) => println!("    ✗ {} - not found", name),
-                            Err(_) => println!("    ? {} - error checking", name),
-                        }
+                // No entry point exported - check available exports for debugging
+                println!("[CALL_NATIVE] ✗ No entry point found");
+                // Debug: check for common exports (core module exports only)
+                let common_exports = [
+                    "_start",
+                    "_initialize",
+                    "run",
+                    "main",
+                    "memory",
+                    "__heap_base",
+                    "__data_end",
+                    "cabi_realloc",
+                    "__wasm_call_ctors",
+                ];
+                println!("[CALL_NATIVE] Checking for common exports:");
+                for name in common_exports {
+                    match engine.has_function(instance_handle, name) {
+                        Ok(true) => println!("    ✓ {} - EXISTS", name),
+                        Ok(false) => println!("    ✗ {} - not found", name),
+                        Err(_) => println!("    ? {} - error checking", name),
                     }
-                    return Err(e);
                 }
+                return Err(Error::runtime_function_not_found("No entry point exported"));
             } else {
                 println!(
                     "[CALL_NATIVE] No stored engine/instance, falling back to fresh instantiation"
@@ -4666,6 +4770,8 @@ fn call_native_function(
                         })?,
                         #[cfg(feature = "std")]
                         import_order: Vec::new(),
+                        #[cfg(feature = "std")]
+                        names: wrt_format::StringPool::new(),
                         #[cfg(not(feature = "std"))]
                         import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone()).map_err(|e| {
                             println!("[TEST-THREAD] ✗ Failed to create import_order: {:?}", e);
@@ -30,6 +30,10 @@
 #[cfg(not(feature = "std"))]
 type HashMap<K, V> = wrt_foundation::collections::StaticMap<K, V, 64>;
 
+#[cfg(feature = "std")]
+use crate::components::call_graph::{
+    BindingKind, BindingProvider, CallBinding, CallGraph, UnresolvedImport,
+};
 use crate::{
     components::{
         component::Component,
@@ -70,6 +74,9 @@ pub struct ComponentLinker {
     /// WASI instance provider for host imports
     #[cfg(feature = "std")]
     wasi_provider: Option<crate::linker::WasiInstanceProvider>,
+    /// Import-to-export bindings recorded while linking
+    #[cfg(feature = "std")]
+    call_graph: CallGraph,
 }
 
 /// Component definition in the linker
@@ -255,6 +262,8 @@ pub fn with_config(config: LinkerConfig) -> Self {
             stats: LinkingStats::default(),
             #[cfg(feature = "std")]
             wasi_provider: crate::linker::WasiInstanceProvider::new().ok(),
+            #[cfg(feature = "std")]
+            call_graph: CallGraph::new(),
         }
     }
 
@@ -492,17 +501,51 @@ pub fn add_component(&mut self, id: ComponentId, binary: &[u8]) -> Result<()> {
             vec
         };
 
-        let definition = ComponentDefinition {
-            id: id.clone(),
+        self.register_component(ComponentDefinition {
+            id,
             binary: binary_vec,
             exports,
             imports,
             metadata,
-        };
+        })
+    }
+
+    /// Add an already decoded component to the linker
+    ///
+    /// Its exports become available to the imports of components
+    /// instantiated through this linker afterwards.
+    #[cfg(feature = "std")]
+    pub fn add_parsed_component(
+        &mut self,
+        id: ComponentId,
+        component: &wrt_format::component::Component,
+    ) -> Result<()> {
+        if self.components.len() >= MAX_LINKED_COMPONENTS {
+            return Err(Error::resource_exhausted(
+                "Maximum number of components reached",
+            ));
+        }
+
+        let (exports, imports, metadata) = self.convert_parsed_component(component);
+        self.register_component(ComponentDefinition {
+            id,
+            binary: component.binary.clone().unwrap_or_default(),
+            exports,
+            imports,
+            metadata,
+        })
+    }
+
+    /// Record a component definition and its node in the dependency graph
+    fn register_component(&mut self, definition: ComponentDefinition) -> Result<()> {
+        let id = definition.id.clone();
 
         // Add to components map
         let _ = self.components.insert(id.clone(), definition);
 
+        #[cfg(feature = "std")]
+        self.call_graph.add_component(&id);
+
         // Update dependency graph
         self.link_graph.add_component(id)?;
 
@@ -555,6 +598,8 @@ pub fn remove_component(&mut self, id: &ComponentId) -> Result<()> {
         // Remove from components and graph
         self.components.remove(id);
         self.link_graph.remove_component(id)?;
+        #[cfg(feature = "std")]
+        self.call_graph.remove_component(id);
 
         Ok(())
     }
@@ -732,6 +777,54 @@ pub fn get_stats(&self) -> &LinkingStats {
         &self.stats
     }
 
+    /// Get the call graph recorded by the instantiations performed so far
+    #[cfg(feature = "std")]
+    pub fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
+    /// Resolve the imports of every registered component without
+    /// instantiating anything and return the resulting call graph
+    ///
+    /// Uses the same resolution order as linking: registered components
+    /// first, then the WASI host provider. Imports neither can satisfy are
+    /// reported as unresolved instead of failing.
+    #[cfg(feature = "std")]
+    pub fn resolve_call_graph(&mut self) -> &CallGraph {
+        let mut component_ids: Vec<&ComponentId> = self.components.keys().collect();
+        component_ids.sort();
+
+        let mut graph = CallGraph::new();
+        for component_id in component_ids {
+            graph.add_component(component_id);
+            let component = &self.components[component_id];
+            let mut bindings = Vec::new();
+            let mut unresolved = Vec::new();
+            for import in &component.imports {
+                if let Some(binding) = self.bind_import(component_id, import) {
+                    bindings.push(binding);
+                } else if self.wasi_provider.is_some() && is_wasi_import(import) {
+                    bindings.push(CallBinding {
+                        importer: component_id.clone(),
+                        import:   import.name.clone(),
+                        provider: BindingProvider::Host,
+                        export:   import.name.clone(),
+                        kind:     BindingKind::Instance,
+                    });
+                } else {
+                    unresolved.push(UnresolvedImport {
+                        importer: component_id.clone(),
+                        import:   import.name.clone(),
+                    });
+                }
+            }
+            graph.set_bindings(component_id, bindings, unresolved);
+        }
+
+        self.call_graph = graph;
+        &self.call_graph
+    }
+
     // Private helper methods
 
     /// Parse a component binary to extract exports, imports, and metadata.
@@ -787,15 +880,30 @@ fn parse_component_with_decoder(
         ),
         Error,
     > {
-        use wrt_format::component::{CoreSort, Sort};
-
         // Decode the component binary
         let decoded = wrt_decoder::component::decode_component(binary)?;
+        Ok(self.convert_parsed_component(&decoded))
+    }
 
+    /// Extract the exports, imports and metadata of a decoded component
+    #[cfg(feature = "std")]
+    fn convert_parsed_component(
+        &self,
+        decoded: &wrt_format::component::Component,
+    ) -> (
+        Vec<ComponentExport>,
+        Vec<ComponentImport>,
+        ComponentMetadata,
+    ) {
         // Convert decoded exports to ComponentExport
         let mut exports = Vec::with_capacity(decoded.exports.len());
         for export in &decoded.exports {
-            let export_type = self.sort_to_export_type(&export.sort, &export.ty);
+            let ty = export
+                .ty
+                .as_ref()
+                .and_then(|ty| self.resolve_function_type_ref(&decoded.types, ty))
+                .or_else(|| export.ty.clone());
+            let export_type = self.sort_to_export_type(&export.sort, &ty);
             let name = export.name.name.clone();
             let index = export.idx;
 
@@ -812,7 +920,10 @@ fn parse_component_with_decoder(
         // Convert decoded imports to ComponentImport
         let mut imports = Vec::with_capacity(decoded.imports.len());
         for import in &decoded.imports {
-            let import_type = self.extern_type_to_import_type(&import.ty);
+            let import_type = match self.resolve_function_type_ref(&decoded.types, &import.ty) {
+                Some(ty) => self.extern_type_to_import_type(&ty),
+                None => self.extern_type_to_import_type(&import.ty),
+            };
             let name = import.name.name.clone();
             let module = import.name.namespace.clone();
 
@@ -828,18 +939,44 @@ fn parse_component_with_decoder(
 
         // Extract metadata from component
         let metadata = ComponentMetadata {
-            name: decoded.name.unwrap_or_default(),
+            name: decoded.name.clone().unwrap_or_default(),
             version: "1.0.0".to_owned(),
             description: String::new(),
             author: String::new(),
             compiled_at: 0,
         };
 
-        Ok((exports, imports, metadata))
+        (exports, imports, metadata)
+    }
+
+    /// Resolve a type-index reference to the function type it names
+    ///
+    /// Function imports and ascribed function exports refer to their
+    /// signature by type index rather than carrying it inline.
+    #[cfg(feature = "std")]
+    fn resolve_function_type_ref(
+        &self,
+        types: &[wrt_format::component::ComponentType],
+        ty: &wrt_format::component::ExternType,
+    ) -> Option<wrt_format::component::ExternType> {
+        use wrt_format::component::{ComponentTypeDefinition, ExternType as FormatExternType};
+
+        let FormatExternType::Type(idx) = ty else {
+            return None;
+        };
+        match &types.get(*idx as usize)?.definition {
+            ComponentTypeDefinition::Function { params, results } => {
+                Some(FormatExternType::Function {
+                    params:  params.clone(),
+                    results: results.clone(),
+                })
+            },
+            _ => None,
+        }
     }
 
     /// Convert Sort to ExportType
-    #[cfg(all(feature = "std", feature = "decoder"))]
+    #[cfg(feature = "std")]
     fn sort_to_export_type(
         &self,
         sort: &wrt_format::component::Sort,
@@ -896,7 +1033,7 @@ fn sort_to_export_type(
     }
 
     /// Convert ExternType to ImportType
-    #[cfg(all(feature = "std", feature = "decoder"))]
+    #[cfg(feature = "std")]
     fn extern_type_to_import_type(&self, ty: &wrt_format::component::ExternType) -> ImportType {
         use wrt_format::component::ExternType as FormatExternType;
 
@@ -924,7 +1061,7 @@ fn extern_type_to_import_type(&self, ty: &wrt_format::component::ExternType) ->
     }
 
     /// Convert function type from format to instantiation types
-    #[cfg(all(feature = "std", feature = "decoder"))]
+    #[cfg(feature = "std")]
     fn convert_function_type(
         &self,
         params: &[(String, wrt_format::component::FormatValType)],
@@ -945,7 +1082,7 @@ fn convert_function_type(
     }
 
     /// Convert FormatValType from format to canonical_abi ComponentType
-    #[cfg(all(feature = "std", feature = "decoder"))]
+    #[cfg(feature = "std")]
     fn format_val_type_to_component_type(
         &self,
         vt: &wrt_format::component::FormatValType,
@@ -1016,12 +1153,21 @@ fn resolve_imports(
         imports: &[ComponentImport],
     ) -> Result<Vec<crate::instantiation::ResolvedImport>> {
         let mut resolved = Vec::new();
+        #[cfg(feature = "std")]
+        let mut bindings = Vec::new();
 
         for import in imports {
             let resolution = self.resolve_single_import(component_id, import)?;
             let _ = resolved.push(resolution);
+            #[cfg(feature = "std")]
+            if let Some(binding) = self.bind_import(component_id, import) {
+                bindings.push(binding);
+            }
         }
 
+        #[cfg(feature = "std")]
+        self.call_graph.set_bindings(component_id, bindings, Vec::new());
+
         self.stats.links_resolved += resolved.len() as u32;
         Ok(resolved)
     }
@@ -1032,18 +1178,50 @@ fn resolve_single_import(
         import: &ComponentImport,
     ) -> Result<crate::instantiation::ResolvedImport> {
         // Find a component that exports what we need
+        if self.find_provider(import)?.is_some() {
+            // Return a placeholder resolved import (actual resolution would be more complex)
+            return Ok(crate::instantiation::ResolvedImport::Value(
+                crate::prelude::WrtComponentValue::Unit,
+            ));
+        }
+
+        Err(Error::component_not_found("Component not found"))
+    }
+
+    /// Find the registered component and export that satisfy an import
+    fn find_provider(
+        &self,
+        import: &ComponentImport,
+    ) -> Result<Option<(&ComponentId, &ComponentExport)>> {
         for (provider_id, component) in &self.components {
             for export in &component.exports {
                 if self.is_compatible_import_export(import, export)? {
-                    // Return a placeholder resolved import (actual resolution would be more complex)
-                    return Ok(crate::instantiation::ResolvedImport::Value(
-                        crate::prelude::WrtComponentValue::Unit,
-                    ));
+                    return Ok(Some((provider_id, export)));
                 }
             }
         }
+        Ok(None)
+    }
 
-        Err(Error::component_not_found("Component not found"))
+    /// Build the call graph edge for an import satisfied by a registered
+    /// component
+    #[cfg(feature = "std")]
+    fn bind_import(&self, importer: &ComponentId, import: &ComponentImport) -> Option<CallBinding> {
+        let (provider_id, export) = self.find_provider(import).ok().flatten()?;
+        let kind = match export.export_type {
+            ExportType::Function(_) => BindingKind::Function,
+            ExportType::Memory(_) => BindingKind::Memory,
+            ExportType::Table { .. } => BindingKind::Table,
+            ExportType::Global { .. } => BindingKind::Global,
+            ExportType::Type(_) => BindingKind::Type,
+        };
+        Some(CallBinding {
+            importer: importer.clone(),
+            import:   import.name.clone(),
+            provider: BindingProvider::Component(provider_id.clone()),
+            export:   export.name.clone(),
+            kind,
+        })
     }
 
     fn is_compatible_import_export(
@@ -1087,6 +1265,12 @@ fn is_compatible_memory_config(
     }
 }
 
+/// Whether an import names a WASI interface served by the host provider
+#[cfg(feature = "std")]
+fn is_wasi_import(import: &ComponentImport) -> bool {
+    import.name.contains("wasi:") || import.module.starts_with("wasi:")
+}
+
 impl Default for LinkGraph {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,1200 @@
+//! Composition of components
+//!
+//! A [`CompositionGraph`] holds several decoded components and wires the
+//! imports of one component to the exports of another. Resolving the graph
+//! checks that every wired import and export have compatible types,
+//! including the identity of the resource types passed between them, and
+//! computes the order in which the components are instantiated: providers
+//! before the components importing from them. Imports left unwired are
+//! served by the host, typically WASI.
+//!
+//! Export types are checked where the exporting component declares them.
+//! Exports without a declared type are only checked for their kind and are
+//! listed in [`CompositionPlan::unchecked`]; a graph with strict typing
+//! rejects them instead.
+//!
+//! With `wrt-execution`, [`CompositionGraph::instantiate`] creates the
+//! instances in that order and returns a [`ComposedInstance`] in which a
+//! call to a wired import runs the export of the providing instance.
+
+use std::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "wrt-execution")]
+use std::sync::{Arc, Mutex};
+
+use wrt_error::{Error, ErrorCategory, Result, codes};
+use wrt_format::component::{
+    Component, ComponentType, ComponentTypeDefinition, CoreSort, Export, ExternType,
+    FormatValType, Import, Sort,
+};
+
+#[cfg(feature = "wrt-execution")]
+use super::{component_instantiation::ComponentInstance, component_linker::ComponentLinker};
+
+/// Index of a component in a [`CompositionGraph`]
+pub type NodeId = usize;
+
+/// Maximum number of components in a composition
+pub const MAX_COMPOSED_COMPONENTS: usize = 64;
+
+/// Maximum nesting of types compared across a wire
+const MAX_TYPE_DEPTH: usize = 64;
+
+/// Import of one component satisfied by an export of another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wire {
+    /// Component declaring the import
+    pub importer: NodeId,
+    /// Import name, e.g. `docs:adder/add@0.1.0`
+    pub import:   String,
+    /// Component providing the export
+    pub exporter: NodeId,
+    /// Export name
+    pub export:   String,
+}
+
+/// Import left to the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenImport {
+    /// Component declaring the import
+    pub importer: NodeId,
+    /// Import name
+    pub import:   String,
+}
+
+/// Reason an export cannot satisfy the import wired to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeMismatch {
+    /// Import and export are different kinds of item
+    Kind,
+    /// The functions take a different number of parameters
+    ParamCount,
+    /// The functions return a different number of results
+    ResultCount,
+    /// Two value types differ
+    Value,
+    /// The exported instance lacks a member the import requires
+    MissingExport(String),
+    /// A resource type is paired with two different resource types
+    Resource,
+    /// A type index does not name a type of the component
+    UnknownType(u32),
+    /// The export declares no type and the graph uses strict typing
+    UndeclaredType,
+}
+
+/// Wire whose import and export types are incompatible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkMismatch {
+    /// The offending wire
+    pub wire:   Wire,
+    /// Why the types are incompatible
+    pub reason: TypeMismatch,
+}
+
+/// Result of resolving a composition graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionPlan {
+    /// Components in instantiation order, providers first
+    pub order:        Vec<NodeId>,
+    /// Wired imports
+    pub wires:        Vec<Wire>,
+    /// Imports left to the host
+    pub open_imports: Vec<OpenImport>,
+    /// Wires whose export declares no type and was only checked for its kind
+    pub unchecked:    Vec<Wire>,
+}
+
+/// Component added to a composition
+#[derive(Debug)]
+struct CompositionNode {
+    name:      String,
+    component: Component,
+}
+
+/// Graph of components wired through their imports and exports
+#[derive(Debug, Default)]
+pub struct CompositionGraph {
+    nodes:         Vec<CompositionNode>,
+    wires:         Vec<Wire>,
+    strict_typing: bool,
+}
+
+impl CompositionGraph {
+    /// Create an empty composition
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject wires whose export declares no type
+    #[must_use]
+    pub fn with_strict_typing(mut self, strict: bool) -> Self {
+        self.strict_typing = strict;
+        self
+    }
+
+    /// Add a decoded component under a unique `name`
+    pub fn add_component(&mut self, name: &str, component: Component) -> Result<NodeId> {
+        if self.nodes.len() >= MAX_COMPOSED_COMPONENTS {
+            return Err(Error::resource_exhausted(
+                "Maximum number of composed components reached",
+            ));
+        }
+        if self.node(name).is_some() {
+            return Err(Error::validation_error(
+                "Component name already used in composition",
+            ));
+        }
+
+        self.nodes.push(CompositionNode {
+            name: name.to_string(),
+            component,
+        });
+        Ok(self.nodes.len() - 1)
+    }
+
+    /// Decode a component binary and add it under a unique `name`
+    #[cfg(feature = "decoder")]
+    pub fn add_component_binary(&mut self, name: &str, binary: &[u8]) -> Result<NodeId> {
+        let component = wrt_decoder::component::decode_component(binary)?;
+        self.add_component(name, component)
+    }
+
+    /// Node of the component added under `name`
+    pub fn node(&self, name: &str) -> Option<NodeId> {
+        self.nodes.iter().position(|node| node.name == name)
+    }
+
+    /// Name of a component
+    pub fn name(&self, node: NodeId) -> Option<&str> {
+        self.nodes.get(node).map(|node| node.name.as_str())
+    }
+
+    /// Number of components
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether no component was added
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Satisfy `import` of `importer` with `export` of `exporter`
+    pub fn connect(
+        &mut self,
+        importer: NodeId,
+        import: &str,
+        exporter: NodeId,
+        export: &str,
+    ) -> Result<()> {
+        let (Some(importing), Some(exporting)) =
+            (self.nodes.get(importer), self.nodes.get(exporter))
+        else {
+            return Err(Error::component_not_found(
+                "Component not found in composition",
+            ));
+        };
+        if importer == exporter {
+            return Err(Error::validation_error(
+                "A component cannot satisfy its own import",
+            ));
+        }
+        if find_import(&importing.component, import).is_none() {
+            return Err(Error::new(
+                ErrorCategory::Component,
+                codes::COMPONENT_LINKING_ERROR,
+                "Import not declared by the component",
+            ));
+        }
+        if find_export(&exporting.component, export).is_none() {
+            return Err(Error::new(
+                ErrorCategory::Component,
+                codes::COMPONENT_LINKING_ERROR,
+                "Export not provided by the component",
+            ));
+        }
+        if self.wire(importer, import).is_some() {
+            return Err(Error::validation_error("Import is already wired"));
+        }
+
+        self.wires.push(Wire {
+            importer,
+            import: import.to_string(),
+            exporter,
+            export: export.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Wire every unwired import to the export of the same name
+    ///
+    /// Imports no component exports are left to the host. Returns the
+    /// number of wires added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if several components export the name of an import.
+    pub fn connect_by_name(&mut self) -> Result<usize> {
+        let mut added = 0;
+        for importer in 0..self.nodes.len() {
+            let names: Vec<String> =
+                self.nodes[importer].component.imports.iter().map(import_name).collect();
+            for name in names {
+                if self.wire(importer, &name).is_some() {
+                    continue;
+                }
+                let mut providers = (0..self.nodes.len()).filter(|&exporter| {
+                    exporter != importer
+                        && find_export(&self.nodes[exporter].component, &name).is_some()
+                });
+                let Some(exporter) = providers.next() else {
+                    continue;
+                };
+                if providers.next().is_some() {
+                    return Err(Error::new(
+                        ErrorCategory::Component,
+                        codes::COMPONENT_LINKING_ERROR,
+                        "Import is exported by several components",
+                    ));
+                }
+                self.connect(importer, &name, exporter, &name)?;
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Wired imports
+    pub fn wires(&self) -> &[Wire] {
+        &self.wires
+    }
+
+    /// Imports not wired to any component
+    pub fn open_imports(&self) -> Vec<OpenImport> {
+        let mut open = Vec::new();
+        for (importer, node) in self.nodes.iter().enumerate() {
+            for import in &node.component.imports {
+                let import = import_name(import);
+                if self.wire(importer, &import).is_none() {
+                    open.push(OpenImport { importer, import });
+                }
+            }
+        }
+        open
+    }
+
+    /// Order in which the components are instantiated, providers first
+    ///
+    /// Components that do not depend on each other keep the order in which
+    /// they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the wires form a cycle.
+    pub fn instantiation_order(&self) -> Result<Vec<NodeId>> {
+        let mut pending: Vec<usize> = vec![0; self.nodes.len()];
+        for wire in &self.wires {
+            pending[wire.importer] += 1;
+        }
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut placed = vec![false; self.nodes.len()];
+        while order.len() < self.nodes.len() {
+            let next = (0..self.nodes.len())
+                .find(|&node| !placed[node] && pending[node] == 0)
+                .ok_or_else(|| {
+                    Error::validation_error("Circular dependency between composed components")
+                })?;
+            placed[next] = true;
+            order.push(next);
+            for wire in self.wires.iter().filter(|wire| wire.exporter == next) {
+                pending[wire.importer] -= 1;
+            }
+        }
+        Ok(order)
+    }
+
+    /// Wires whose import and export types are incompatible
+    pub fn mismatches(&self) -> Vec<LinkMismatch> {
+        let order = self.instantiation_order().unwrap_or_else(|_| (0..self.nodes.len()).collect());
+        self.check_wires(&order).0
+    }
+
+    /// Check the wires and compute the instantiation order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the wires form a cycle or a wired export cannot
+    /// satisfy its import; [`mismatches`](Self::mismatches) tells which.
+    pub fn resolve(&self) -> Result<CompositionPlan> {
+        let order = self.instantiation_order()?;
+        let (mismatches, unchecked) = self.check_wires(&order);
+        if !mismatches.is_empty() {
+            return Err(Error::new(
+                ErrorCategory::Component,
+                codes::COMPONENT_TYPE_MISMATCH,
+                "Wired import and export types are incompatible",
+            ));
+        }
+
+        Ok(CompositionPlan {
+            order,
+            wires: self.wires.clone(),
+            open_imports: self.open_imports(),
+            unchecked,
+        })
+    }
+
+    /// Type-check the wires, importers in `order`
+    ///
+    /// Providers are checked first so that a resource type re-exported
+    /// through several components is traced back to its definition.
+    fn check_wires(&self, order: &[NodeId]) -> (Vec<LinkMismatch>, Vec<Wire>) {
+        let mut resources = ResourcePairs::default();
+        let mut mismatches = Vec::new();
+        let mut unchecked = Vec::new();
+
+        for &importer in order {
+            for wire in self.wires.iter().filter(|wire| wire.importer == importer) {
+                match self.check_wire(wire, &mut resources) {
+                    Ok(true) => {},
+                    Ok(false) if self.strict_typing => mismatches.push(LinkMismatch {
+                        wire:   wire.clone(),
+                        reason: TypeMismatch::UndeclaredType,
+                    }),
+                    Ok(false) => unchecked.push(wire.clone()),
+                    Err(reason) => mismatches.push(LinkMismatch {
+                        wire: wire.clone(),
+                        reason,
+                    }),
+                }
+            }
+        }
+        (mismatches, unchecked)
+    }
+
+    /// Check one wire, returning whether the export type was known
+    fn check_wire(
+        &self,
+        wire: &Wire,
+        resources: &mut ResourcePairs,
+    ) -> core::result::Result<bool, TypeMismatch> {
+        let importing = &self.nodes[wire.importer].component;
+        let exporting = &self.nodes[wire.exporter].component;
+        let import = find_import(importing, &wire.import).ok_or(TypeMismatch::Kind)?;
+        let export = find_export(exporting, &wire.export).ok_or(TypeMismatch::Kind)?;
+
+        let mut checker = TypeChecker {
+            importer: Side {
+                node:  wire.importer,
+                types: &importing.types,
+            },
+            exporter: Side {
+                node:  wire.exporter,
+                types: &exporting.types,
+            },
+            resources,
+            depth: 0,
+        };
+        match &export.ty {
+            Some(ty) => checker.extern_types(&import.ty, ty).map(|()| true),
+            None => {
+                let kind = checker.importer.resolve(&import.ty)?;
+                if kind.matches_sort(&export.sort) {
+                    Ok(false)
+                } else {
+                    Err(TypeMismatch::Kind)
+                }
+            },
+        }
+    }
+
+    fn wire(&self, importer: NodeId, import: &str) -> Option<&Wire> {
+        self.wires.iter().find(|wire| wire.importer == importer && wire.import == import)
+    }
+}
+
+/// Full name of an import, `namespace:name` when it has a namespace
+fn import_name(import: &Import) -> String {
+    if import.name.namespace.is_empty() {
+        import.name.name.clone()
+    } else {
+        format!("{}:{}", import.name.namespace, import.name.name)
+    }
+}
+
+fn find_import<'a>(component: &'a Component, name: &str) -> Option<&'a Import> {
+    component.imports.iter().find(|import| import_name(import) == name)
+}
+
+fn find_export<'a>(component: &'a Component, name: &str) -> Option<&'a Export> {
+    component.exports.iter().find(|export| export.name.name == name)
+}
+
+/// Resource types of importers paired with the resource types providing
+/// them
+#[derive(Debug, Default)]
+struct ResourcePairs {
+    providers: BTreeMap<(NodeId, u32), (NodeId, u32)>,
+}
+
+impl ResourcePairs {
+    /// Resource type `resource` is ultimately defined by
+    fn definition(&self, mut resource: (NodeId, u32)) -> (NodeId, u32) {
+        // Providers are acyclic as the wires are, the bound only guards
+        // against a malformed graph
+        for _ in 0..MAX_COMPOSED_COMPONENTS {
+            match self.providers.get(&resource) {
+                Some(&provider) => resource = provider,
+                None => break,
+            }
+        }
+        resource
+    }
+
+    /// Pair an imported resource type with the one providing it, returning
+    /// whether this agrees with the pairings made so far
+    fn pair(&mut self, import: (NodeId, u32), export: (NodeId, u32)) -> bool {
+        let export = self.definition(export);
+        match self.providers.get(&import) {
+            Some(&paired) => self.definition(paired) == export,
+            None => {
+                self.providers.insert(import, export);
+                true
+            },
+        }
+    }
+}
+
+/// Type space of one end of a wire
+#[derive(Debug, Clone, Copy)]
+struct Side<'a> {
+    node:  NodeId,
+    types: &'a [ComponentType],
+}
+
+/// Extern type with type references resolved
+enum Resolved<'a> {
+    Function {
+        params:  &'a [(String, FormatValType)],
+        results: &'a [FormatValType],
+    },
+    Instance(&'a [(String, ExternType)]),
+    Value(&'a FormatValType),
+    Resource(u32),
+    Component,
+    Module,
+}
+
+impl Resolved<'_> {
+    /// Whether an export of `sort` can be this kind of item
+    fn matches_sort(&self, sort: &Sort) -> bool {
+        matches!(
+            (self, sort),
+            (Self::Function { .. }, Sort::Function)
+                | (Self::Instance(_), Sort::Instance)
+                | (Self::Value(_), Sort::Value)
+                | (Self::Resource(_), Sort::Type)
+                | (Self::Component, Sort::Component)
+                | (Self::Module, Sort::Core(CoreSort::Module))
+        )
+    }
+}
+
+impl<'a> Side<'a> {
+    fn resolve(self, ty: &'a ExternType) -> core::result::Result<Resolved<'a>, TypeMismatch> {
+        Ok(match ty {
+            ExternType::Function { params, results } => Resolved::Function { params, results },
+            ExternType::Instance { exports } => Resolved::Instance(exports),
+            ExternType::Value(value) => Resolved::Value(value),
+            ExternType::Component { .. } => Resolved::Component,
+            ExternType::Module { .. } => Resolved::Module,
+            ExternType::Type(idx) => match &self.definition(*idx)?.definition {
+                ComponentTypeDefinition::Function { params, results } => {
+                    Resolved::Function { params, results }
+                },
+                ComponentTypeDefinition::Instance { exports } => Resolved::Instance(exports),
+                ComponentTypeDefinition::Value(value) => Resolved::Value(value),
+                ComponentTypeDefinition::Resource { .. } => Resolved::Resource(*idx),
+                ComponentTypeDefinition::Component { .. } => Resolved::Component,
+            },
+        })
+    }
+
+    fn definition(self, idx: u32) -> core::result::Result<&'a ComponentType, TypeMismatch> {
+        self.types.get(idx as usize).ok_or(TypeMismatch::UnknownType(idx))
+    }
+
+    /// Value type named by a type reference
+    fn value(self, idx: u32) -> core::result::Result<&'a FormatValType, TypeMismatch> {
+        match &self.definition(idx)?.definition {
+            ComponentTypeDefinition::Value(value) => Ok(value),
+            _ => Err(TypeMismatch::Value),
+        }
+    }
+}
+
+/// Structural comparison of the types on both ends of a wire
+struct TypeChecker<'a, 'r> {
+    importer:  Side<'a>,
+    exporter:  Side<'a>,
+    resources: &'r mut ResourcePairs,
+    depth:     usize,
+}
+
+impl<'a> TypeChecker<'a, '_> {
+    fn extern_types(
+        &mut self,
+        import: &'a ExternType,
+        export: &'a ExternType,
+    ) -> core::result::Result<(), TypeMismatch> {
+        self.descend()?;
+        let result = match (self.importer.resolve(import)?, self.exporter.resolve(export)?) {
+            (
+                Resolved::Function {
+                    params: import_params,
+                    results: import_results,
+                },
+                Resolved::Function {
+                    params: export_params,
+                    results: export_results,
+                },
+            ) => self.functions(import_params, import_results, export_params, export_results),
+            (Resolved::Instance(import_members), Resolved::Instance(export_members)) => {
+                // The export may provide more than the import requires
+                import_members.iter().try_for_each(|(name, import_ty)| {
+                    let (_, export_ty) = export_members
+                        .iter()
+                        .find(|(export_name, _)| export_name == name)
+                        .ok_or_else(|| TypeMismatch::MissingExport(name.clone()))?;
+                    self.extern_types(import_ty, export_ty)
+                })
+            },
+            (Resolved::Value(import_value), Resolved::Value(export_value)) => {
+                self.values(import_value, export_value)
+            },
+            (Resolved::Resource(import_idx), Resolved::Resource(export_idx)) => {
+                self.resource(import_idx, export_idx)
+            },
+            (Resolved::Component, Resolved::Component) | (Resolved::Module, Resolved::Module) => {
+                Ok(())
+            },
+            _ => Err(TypeMismatch::Kind),
+        };
+        self.depth -= 1;
+        result
+    }
+
+    fn functions(
+        &mut self,
+        import_params: &'a [(String, FormatValType)],
+        import_results: &'a [FormatValType],
+        export_params: &'a [(String, FormatValType)],
+        export_results: &'a [FormatValType],
+    ) -> core::result::Result<(), TypeMismatch> {
+        if import_params.len() != export_params.len() {
+            return Err(TypeMismatch::ParamCount);
+        }
+        if import_results.len() != export_results.len() {
+            return Err(TypeMismatch::ResultCount);
+        }
+        for ((_, import), (_, export)) in import_params.iter().zip(export_params) {
+            self.values(import, export)?;
+        }
+        for (import, export) in import_results.iter().zip(export_results) {
+            self.values(import, export)?;
+        }
+        Ok(())
+    }
+
+    fn values(
+        &mut self,
+        import: &'a FormatValType,
+        export: &'a FormatValType,
+    ) -> core::result::Result<(), TypeMismatch> {
+        self.descend()?;
+        let result = self.compare_values(import, export);
+        self.depth -= 1;
+        result
+    }
+
+    fn compare_values(
+        &mut self,
+        import: &'a FormatValType,
+        export: &'a FormatValType,
+    ) -> core::result::Result<(), TypeMismatch> {
+        use FormatValType as V;
+
+        match (import, export) {
+            (V::Ref(idx), _) => self.values(self.importer.value(*idx)?, export),
+            (_, V::Ref(idx)) => self.values(import, self.exporter.value(*idx)?),
+            (V::Record(import_fields), V::Record(export_fields)) => {
+                if import_fields.len() != export_fields.len() {
+                    return Err(TypeMismatch::Value);
+                }
+                for ((import_name, import), (export_name, export)) in
+                    import_fields.iter().zip(export_fields)
+                {
+                    if import_name != export_name {
+                        return Err(TypeMismatch::Value);
+                    }
+                    self.values(import, export)?;
+                }
+                Ok(())
+            },
+            (V::Variant(import_cases), V::Variant(export_cases)) => {
+                if import_cases.len() != export_cases.len() {
+                    return Err(TypeMismatch::Value);
+                }
+                for ((import_name, import), (export_name, export)) in
+                    import_cases.iter().zip(export_cases)
+                {
+                    if import_name != export_name {
+                        return Err(TypeMismatch::Value);
+                    }
+                    match (import, export) {
+                        (Some(import), Some(export)) => self.values(import, export)?,
+                        (None, None) => {},
+                        _ => return Err(TypeMismatch::Value),
+                    }
+                }
+                Ok(())
+            },
+            (V::Tuple(import_items), V::Tuple(export_items)) => {
+                if import_items.len() != export_items.len() {
+                    return Err(TypeMismatch::Value);
+                }
+                import_items
+                    .iter()
+                    .zip(export_items)
+                    .try_for_each(|(import, export)| self.values(import, export))
+            },
+            (V::List(import), V::List(export))
+            | (V::Option(import), V::Option(export))
+            | (V::Result(import), V::Result(export)) => self.values(import, export),
+            (V::FixedList(import, import_len), V::FixedList(export, export_len))
+                if import_len == export_len =>
+            {
+                self.values(import, export)
+            },
+            (V::Own(import_idx), V::Own(export_idx))
+            | (V::Borrow(import_idx), V::Borrow(export_idx)) => {
+                self.resource(*import_idx, *export_idx)
+            },
+            (V::Flags(_) | V::Enum(_), _) if import == export => Ok(()),
+            (
+                V::Bool
+                | V::S8
+                | V::U8
+                | V::S16
+                | V::U16
+                | V::S32
+                | V::U32
+                | V::S64
+                | V::U64
+                | V::F32
+                | V::F64
+                | V::Char
+                | V::String
+                | V::Void
+                | V::ErrorContext,
+                _,
+            ) if import == export => Ok(()),
+            _ => Err(TypeMismatch::Value),
+        }
+    }
+
+    fn resource(
+        &mut self,
+        import_idx: u32,
+        export_idx: u32,
+    ) -> core::result::Result<(), TypeMismatch> {
+        let import = (self.importer.node, import_idx);
+        let export = (self.exporter.node, export_idx);
+        if self.resources.pair(import, export) {
+            Ok(())
+        } else {
+            Err(TypeMismatch::Resource)
+        }
+    }
+
+    fn descend(&mut self) -> core::result::Result<(), TypeMismatch> {
+        if self.depth >= MAX_TYPE_DEPTH {
+            return Err(TypeMismatch::Value);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+}
+
+/// Maximum number of flat parameters passed directly between instances
+#[cfg(feature = "wrt-execution")]
+const MAX_FLAT_PARAMS: usize = 16;
+
+/// Import of an instance served by an export of another instance
+#[cfg(feature = "wrt-execution")]
+struct ImportRoute {
+    /// Core import module, the interface name or `$root`
+    module:   String,
+    /// Core import field, the function name
+    function: String,
+    /// Instance providing the function
+    target:   Arc<Mutex<ComponentInstance>>,
+    /// Core export of the provider running the function
+    export:   String,
+}
+
+/// Host handler of a composed instance
+///
+/// Calls to wired imports run the export of the providing instance, all
+/// other imports go to the host handler of the component.
+#[cfg(feature = "wrt-execution")]
+struct CompositionRouter {
+    routes: Vec<ImportRoute>,
+    host:   Option<Box<dyn wrt_foundation::HostImportHandler>>,
+}
+
+#[cfg(feature = "wrt-execution")]
+impl wrt_foundation::HostImportHandler for CompositionRouter {
+    fn call_import(
+        &mut self,
+        module: &str,
+        function: &str,
+        args: &[wrt_foundation::Value],
+        memory: Option<&dyn wrt_foundation::MemoryAccessor>,
+    ) -> Result<Vec<wrt_foundation::Value>> {
+        if let Some(route) =
+            self.routes.iter().find(|route| route.module == module && route.function == function)
+        {
+            let mut target = route
+                .target
+                .lock()
+                .map_err(|_| Error::runtime_error("Composed instance lock poisoned"))?;
+            return call_core_export(&mut target, &route.export, args);
+        }
+        match self.host.as_mut() {
+            Some(host) => host.call_import(module, function, args, memory),
+            None => Err(Error::new(
+                ErrorCategory::Component,
+                codes::COMPONENT_LINKING_ERROR,
+                "Import is neither wired nor provided by the host",
+            )),
+        }
+    }
+
+    fn set_args_allocation(&mut self, list_ptr: u32, string_ptrs: Vec<(u32, u32)>) {
+        if let Some(host) = self.host.as_mut() {
+            host.set_args_allocation(list_ptr, string_ptrs);
+        }
+    }
+}
+
+/// Run a core export of the main module of an instance
+#[cfg(feature = "wrt-execution")]
+fn call_core_export(
+    instance: &mut ComponentInstance,
+    export: &str,
+    args: &[wrt_foundation::Value],
+) -> Result<Vec<wrt_foundation::Value>> {
+    use wrt_runtime::engine::CapabilityEngine;
+
+    match (instance.runtime_engine.as_mut(), instance.main_instance_handle) {
+        (Some(engine), Some(handle)) => engine.execute(handle, export, args),
+        _ => Err(Error::runtime_error(
+            "Component instance has no execution engine",
+        )),
+    }
+}
+
+/// Components instantiated from a [`CompositionGraph`]
+#[cfg(feature = "wrt-execution")]
+pub struct ComposedInstance {
+    names:     Vec<String>,
+    instances: Vec<Arc<Mutex<ComponentInstance>>>,
+    order:     Vec<NodeId>,
+}
+
+#[cfg(feature = "wrt-execution")]
+impl core::fmt::Debug for ComposedInstance {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ComposedInstance")
+            .field("names", &self.names)
+            .field("order", &self.order)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "wrt-execution")]
+impl CompositionGraph {
+    /// Instantiate the components in dependency order and wire them
+    ///
+    /// `host` is called with the name of each component and returns the
+    /// handler for its imports left to the host, such as a WASI dispatcher.
+    /// Calls across a wire pass core values unchanged, so wired functions
+    /// are limited to scalar parameters and at most one scalar result;
+    /// resource handles are passed as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph does not resolve, a wired function
+    /// passes values through linear memory, or a component fails to
+    /// instantiate.
+    pub fn instantiate<F>(
+        self,
+        host_registry: Option<std::sync::Arc<wrt_host::CallbackRegistry>>,
+        mut host: F,
+    ) -> Result<ComposedInstance>
+    where
+        F: FnMut(&str) -> Option<Box<dyn wrt_foundation::HostImportHandler>>,
+    {
+        let plan = self.resolve()?;
+        let mut routes: Vec<Vec<(String, String, NodeId, String)>> =
+            self.nodes.iter().map(|_| Vec::new()).collect();
+        for wire in &plan.wires {
+            routes[wire.importer].extend(self.wire_routes(wire)?);
+        }
+
+        let names: Vec<String> = self.nodes.iter().map(|node| node.name.clone()).collect();
+        let mut components: Vec<Component> =
+            self.nodes.into_iter().map(|node| node.component).collect();
+        let mut instances: Vec<Option<Arc<Mutex<ComponentInstance>>>> =
+            names.iter().map(|_| None).collect();
+
+        for &node in &plan.order {
+            // Only the providers of this component can satisfy its imports
+            let mut linker = ComponentLinker::new();
+            for wire in plan.wires.iter().filter(|wire| wire.importer == node) {
+                let exporter = wire.exporter;
+                linker.add_parsed_component(names[exporter].clone(), &components[exporter])?;
+            }
+
+            let mut instance = ComponentInstance::from_parsed_with_linker(
+                node as u32,
+                &mut components[node],
+                host_registry.clone(),
+                &mut linker,
+            )?;
+
+            let mut router = CompositionRouter {
+                routes: Vec::new(),
+                host:   host(&names[node]),
+            };
+            for (module, function, exporter, export) in routes[node].drain(..) {
+                let target = instances[exporter].clone().ok_or_else(|| {
+                    Error::validation_error("Provider instantiated after its importer")
+                })?;
+                router.routes.push(ImportRoute {
+                    module,
+                    function,
+                    target,
+                    export,
+                });
+            }
+            if router.routes.is_empty() {
+                if let Some(host) = router.host {
+                    instance.set_host_handler(host);
+                }
+            } else {
+                instance.set_host_handler(Box::new(router));
+            }
+
+            instances[node] = Some(Arc::new(Mutex::new(instance)));
+        }
+
+        Ok(ComposedInstance {
+            names,
+            instances: instances.into_iter().flatten().collect(),
+            order: plan.order,
+        })
+    }
+
+    /// Core imports of the importer served by a wire, with the core export
+    /// of the provider running each
+    ///
+    /// A wired instance is called through `interface`/`function` and served
+    /// by `interface#function`, a wired function through `$root`/`name`.
+    fn wire_routes(&self, wire: &Wire) -> Result<Vec<(String, String, NodeId, String)>> {
+        let importing = &self.nodes[wire.importer].component;
+        let side = Side {
+            node:  wire.importer,
+            types: &importing.types,
+        };
+        let import = find_import(importing, &wire.import)
+            .ok_or_else(|| Error::component_not_found("Import not found in composition"))?;
+
+        let mut routes = Vec::new();
+        match side.resolve(&import.ty).map_err(|_| wire_type_error())? {
+            Resolved::Function { params, results } => {
+                check_flat(side, params, results)?;
+                routes.push((
+                    "$root".to_string(),
+                    wire.import.clone(),
+                    wire.exporter,
+                    wire.export.clone(),
+                ));
+            },
+            Resolved::Instance(members) => {
+                for (function, ty) in members {
+                    let Ok(Resolved::Function { params, results }) = side.resolve(ty) else {
+                        // Types and resources are not called
+                        continue;
+                    };
+                    check_flat(side, params, results)?;
+                    routes.push((
+                        wire.import.clone(),
+                        function.clone(),
+                        wire.exporter,
+                        format!("{}#{}", wire.export, function),
+                    ));
+                }
+            },
+            _ => return Err(wire_type_error()),
+        }
+        Ok(routes)
+    }
+}
+
+#[cfg(feature = "wrt-execution")]
+fn wire_type_error() -> Error {
+    Error::new(
+        ErrorCategory::Component,
+        codes::COMPONENT_LINKING_ERROR,
+        "Only function and instance imports can be wired between instances",
+    )
+}
+
+/// Ensure a wired function passes its values without linear memory
+#[cfg(feature = "wrt-execution")]
+fn check_flat(
+    side: Side<'_>,
+    params: &[(String, FormatValType)],
+    results: &[FormatValType],
+) -> Result<()> {
+    let flat = params.len() <= MAX_FLAT_PARAMS
+        && results.len() <= 1
+        && params.iter().all(|(_, ty)| is_scalar(side, ty, 0))
+        && results.iter().all(|ty| is_scalar(side, ty, 0));
+    if flat {
+        Ok(())
+    } else {
+        Err(Error::runtime_not_implemented(
+            "Wired functions must take and return scalar values only",
+        ))
+    }
+}
+
+/// Whether a value flattens to a single core value
+#[cfg(feature = "wrt-execution")]
+fn is_scalar(side: Side<'_>, ty: &FormatValType, depth: usize) -> bool {
+    use FormatValType as V;
+
+    match ty {
+        V::Bool
+        | V::S8
+        | V::U8
+        | V::S16
+        | V::U16
+        | V::S32
+        | V::U32
+        | V::S64
+        | V::U64
+        | V::F32
+        | V::F64
+        | V::Char
+        | V::Enum(_)
+        | V::Own(_)
+        | V::Borrow(_) => true,
+        V::Flags(flags) => flags.len() <= 32,
+        V::Ref(idx) => {
+            depth < MAX_TYPE_DEPTH
+                && side.value(*idx).is_ok_and(|value| is_scalar(side, value, depth + 1))
+        },
+        _ => false,
+    }
+}
+
+#[cfg(feature = "wrt-execution")]
+impl ComposedInstance {
+    /// Names of the components, in the order they were added
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Order in which the components were instantiated
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// Instance of the component added under `name`
+    pub fn instance(&self, name: &str) -> Option<&Arc<Mutex<ComponentInstance>>> {
+        let node = self.names.iter().position(|n| n == name)?;
+        self.instances.get(node)
+    }
+
+    /// Run a core export of a component, e.g. `wasi:cli/run@0.2.0#run`
+    pub fn call(
+        &self,
+        component: &str,
+        export: &str,
+        args: &[wrt_foundation::Value],
+    ) -> Result<Vec<wrt_foundation::Value>> {
+        let instance = self
+            .instance(component)
+            .ok_or_else(|| Error::component_not_found("Component not found in composition"))?;
+        let mut instance = instance
+            .lock()
+            .map_err(|_| Error::runtime_error("Composed instance lock poisoned"))?;
+        call_core_export(&mut instance, export, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_format::component::{ExportName, ImportName, ResourceRepresentation};
+
+    use super::*;
+
+    fn instance_type(members: Vec<(&str, ExternType)>) -> ExternType {
+        ExternType::Instance {
+            exports: members.into_iter().map(|(name, ty)| (name.to_string(), ty)).collect(),
+        }
+    }
+
+    fn function(params: Vec<FormatValType>, results: Vec<FormatValType>) -> ExternType {
+        ExternType::Function {
+            params: params.into_iter().map(|ty| (String::new(), ty)).collect(),
+            results,
+        }
+    }
+
+    fn resource() -> ComponentType {
+        ComponentType {
+            definition: ComponentTypeDefinition::Resource {
+                representation: ResourceRepresentation::Handle32,
+                nullable:       false,
+            },
+        }
+    }
+
+    fn exporting(name: &str, ty: Option<ExternType>) -> Component {
+        let mut component = Component::new();
+        component.exports.push(Export {
+            name: ExportName::new(name.to_string()),
+            sort: Sort::Instance,
+            idx: 0,
+            ty,
+        });
+        component
+    }
+
+    fn importing(name: &str, ty: ExternType) -> Component {
+        let mut component = Component::new();
+        component.imports.push(Import {
+            name: ImportName::new(String::new(), name.to_string()),
+            ty,
+        });
+        component
+    }
+
+    #[test]
+    fn test_wires_by_name_and_orders_providers_first() -> Result<()> {
+        let adder = "docs:adder/add@0.1.0";
+        let add = || {
+            let add = function(vec![FormatValType::U32; 2], vec![FormatValType::U32]);
+            instance_type(vec![("add", add)])
+        };
+
+        let mut app = importing(adder, add());
+        app.imports.push(Import {
+            name: ImportName::new(String::new(), "wasi:cli/stdout@0.2.0".to_string()),
+            ty:   instance_type(vec![]),
+        });
+
+        let mut graph = CompositionGraph::new();
+        let app = graph.add_component("app", app)?;
+        let lib = graph.add_component("lib", exporting(adder, Some(add())))?;
+        assert!(graph.add_component("lib", Component::new()).is_err());
+        assert_eq!(graph.connect_by_name()?, 1);
+        assert!(graph.connect(app, adder, lib, adder).is_err());
+
+        let plan = graph.resolve()?;
+        assert_eq!(plan.order, vec![lib, app]);
+        assert_eq!(plan.open_imports, vec![OpenImport {
+            importer: app,
+            import:   "wasi:cli/stdout@0.2.0".to_string(),
+        }]);
+        assert!(plan.unchecked.is_empty());
+
+        // An export without a declared type is only checked for its kind
+        let mut graph = CompositionGraph::new();
+        let app = graph.add_component("app", importing(adder, add()))?;
+        let lib = graph.add_component("lib", exporting(adder, None))?;
+        graph.connect(app, adder, lib, adder)?;
+        assert_eq!(graph.resolve()?.unchecked.len(), 1);
+        let graph = graph.with_strict_typing(true);
+        assert!(graph.resolve().is_err());
+        assert_eq!(graph.mismatches()[0].reason, TypeMismatch::UndeclaredType);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_incompatible_types_resources_and_cycles() -> Result<()> {
+        let name = "docs:store/store@0.1.0";
+        let mut graph = CompositionGraph::new();
+        let get = function(vec![], vec![FormatValType::U32]);
+        let app = graph.add_component("app", importing(name, instance_type(vec![("get", get)])))?;
+        let lib = graph.add_component(
+            "lib",
+            exporting(
+                name,
+                Some(instance_type(vec![("get", function(vec![], vec![FormatValType::String]))])),
+            ),
+        )?;
+        graph.connect(app, name, lib, name)?;
+        assert!(graph.resolve().is_err());
+        assert_eq!(graph.mismatches()[0].reason, TypeMismatch::Value);
+
+        // Two distinct exported resources cannot both satisfy one imported
+        // resource
+        let mut provider = exporting(
+            name,
+            Some(instance_type(vec![
+                ("make-a", function(vec![], vec![FormatValType::Own(0)])),
+                ("make-b", function(vec![], vec![FormatValType::Own(1)])),
+            ])),
+        );
+        provider.types = vec![resource(), resource()];
+        let mut consumer = importing(
+            name,
+            instance_type(vec![
+                ("make-a", function(vec![], vec![FormatValType::Own(0)])),
+                ("make-b", function(vec![], vec![FormatValType::Own(0)])),
+            ]),
+        );
+        consumer.types = vec![resource()];
+
+        let mut graph = CompositionGraph::new();
+        let app = graph.add_component("app", consumer)?;
+        let lib = graph.add_component("lib", provider)?;
+        graph.connect(app, name, lib, name)?;
+        assert_eq!(graph.mismatches()[0].reason, TypeMismatch::Resource);
+
+        // Components importing from each other cannot be ordered
+        let mut first = importing("b", instance_type(vec![]));
+        first.exports.push(exporting("a", None).exports.remove(0));
+        let mut second = importing("a", instance_type(vec![]));
+        second.exports.push(exporting("b", None).exports.remove(0));
+        let mut graph = CompositionGraph::new();
+        graph.add_component("first", first)?;
+        graph.add_component("second", second)?;
+        assert_eq!(graph.connect_by_name()?, 2);
+        assert!(graph.instantiation_order().is_err());
+        Ok(())
+    }
+}
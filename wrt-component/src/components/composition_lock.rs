@@ -0,0 +1,480 @@
+//! Lockfile of a composition
+//!
+//! A [`CompositionLock`] records the exact inputs of a composition: the
+//! SHA-256 digest and size of every component binary, the digest of every
+//! WIT package the components were built against, and the wires between
+//! the components. Checking a lockfile in next to a deployment makes the
+//! composition reproducible; [`CompositionLock::verify`] reports every
+//! input that differs from the locked one.
+//!
+//! The lockfile is a TOML document:
+//!
+//! ```toml
+//! # Generated by cargo-wrt compose; do not edit.
+//! version = 1
+//!
+//! [[component]]
+//! name = "adder"
+//! size = 1042
+//! digest = "sha256:5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef"
+//!
+//! [[package]]
+//! name = "docs:adder@0.1.0"
+//! digest = "sha256:13d4a6a5ab2a1c0e5bf5d3fe32f6ed1c8d3f67e0b9d8b9a1b1d8c5bda2f1c0a3"
+//!
+//! [[wire]]
+//! importer = "calculator"
+//! import = "docs:adder/add@0.1.0"
+//! exporter = "adder"
+//! export = "docs:adder/add@0.1.0"
+//! ```
+
+use std::{
+    fmt,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use wrt_error::{Error, Result};
+use wrt_foundation::sha256::sha256;
+
+use super::composition::CompositionGraph;
+
+/// Version of the lockfile format
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// Conventional file name of a composition lockfile
+pub const LOCKFILE_NAME: &str = "wrt-compose.lock";
+
+/// Prefix of digests in the lockfile
+const DIGEST_PREFIX: &str = "sha256:";
+
+/// Component binary of a composition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedComponent {
+    /// Name of the component in the composition
+    pub name:   String,
+    /// Size of the binary in bytes
+    pub size:   u64,
+    /// SHA-256 digest of the binary
+    pub digest: [u8; 32],
+}
+
+/// WIT package the composed components were built against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    /// Package name, e.g. `wasi:http@0.2.0`
+    pub name:   String,
+    /// SHA-256 digest of the package sources
+    pub digest: [u8; 32],
+}
+
+/// Import of one component satisfied by an export of another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedWire {
+    /// Component declaring the import
+    pub importer: String,
+    /// Import name
+    pub import:   String,
+    /// Component providing the export
+    pub exporter: String,
+    /// Export name
+    pub export:   String,
+}
+
+/// Input of a composition that differs from the lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockDifference {
+    /// A locked component or package is missing
+    Missing {
+        /// `component` or `package`
+        kind: &'static str,
+        /// Its name
+        name: String,
+    },
+    /// A component or package is not in the lockfile
+    Unlocked {
+        /// `component` or `package`
+        kind: &'static str,
+        /// Its name
+        name: String,
+    },
+    /// A component or package has a different digest
+    Digest {
+        /// `component` or `package`
+        kind: &'static str,
+        /// Its name
+        name: String,
+    },
+    /// A locked wire is missing
+    MissingWire(LockedWire),
+    /// A wire is not in the lockfile
+    UnlockedWire(LockedWire),
+}
+
+impl fmt::Display for LockDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { kind, name } => write!(f, "locked {kind} {name} is missing"),
+            Self::Unlocked { kind, name } => write!(f, "{kind} {name} is not locked"),
+            Self::Digest { kind, name } => write!(f, "{kind} {name} differs from the locked digest"),
+            Self::MissingWire(wire) => write!(f, "locked wire {wire} is missing"),
+            Self::UnlockedWire(wire) => write!(f, "wire {wire} is not locked"),
+        }
+    }
+}
+
+impl fmt::Display for LockedWire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} <- {}.{}", self.importer, self.import, self.exporter, self.export)
+    }
+}
+
+/// Exact inputs of a composition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompositionLock {
+    /// Component binaries in the order they were added
+    pub components: Vec<LockedComponent>,
+    /// WIT packages sorted by name
+    pub packages:   Vec<LockedPackage>,
+    /// Wires between the components
+    pub wires:      Vec<LockedWire>,
+}
+
+impl CompositionLock {
+    /// Create an empty lock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock the binary of component `name`
+    pub fn lock_component(&mut self, name: &str, binary: &[u8]) {
+        self.components.retain(|component| component.name != name);
+        self.components.push(LockedComponent {
+            name:   name.to_string(),
+            size:   binary.len() as u64,
+            digest: sha256(binary),
+        });
+    }
+
+    /// Lock the sources of WIT package `name`
+    pub fn lock_package(&mut self, name: &str, sources: &[u8]) {
+        self.packages.retain(|package| package.name != name);
+        self.packages.push(LockedPackage {
+            name:   name.to_string(),
+            digest: sha256(sources),
+        });
+        self.packages.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Lock the wires of a composition graph
+    pub fn lock_wires(&mut self, graph: &CompositionGraph) {
+        let name = |node| graph.name(node).unwrap_or_default().to_string();
+        self.wires = graph
+            .wires()
+            .iter()
+            .map(|wire| LockedWire {
+                importer: name(wire.importer),
+                import:   wire.import.clone(),
+                exporter: name(wire.exporter),
+                export:   wire.export.clone(),
+            })
+            .collect();
+    }
+
+    /// Differences of `actual` inputs from this lock
+    pub fn verify(&self, actual: &CompositionLock) -> Vec<LockDifference> {
+        let mut differences = Vec::new();
+        let components = |lock: &CompositionLock| {
+            lock.components.iter().map(|c| (c.name.clone(), c.digest)).collect::<Vec<_>>()
+        };
+        let packages = |lock: &CompositionLock| {
+            lock.packages.iter().map(|p| (p.name.clone(), p.digest)).collect::<Vec<_>>()
+        };
+        compare("component", &components(self), &components(actual), &mut differences);
+        compare("package", &packages(self), &packages(actual), &mut differences);
+
+        for wire in &self.wires {
+            if !actual.wires.contains(wire) {
+                differences.push(LockDifference::MissingWire(wire.clone()));
+            }
+        }
+        for wire in &actual.wires {
+            if !self.wires.contains(wire) {
+                differences.push(LockDifference::UnlockedWire(wire.clone()));
+            }
+        }
+        differences
+    }
+
+    /// Encode the lock as a lockfile
+    pub fn to_lockfile(&self) -> String {
+        let mut text = String::from("# Generated by cargo-wrt compose; do not edit.\n");
+        text.push_str(&format!("version = {LOCKFILE_VERSION}\n"));
+        for component in &self.components {
+            text.push_str("\n[[component]]\n");
+            push_string(&mut text, "name", &component.name);
+            text.push_str(&format!("size = {}\n", component.size));
+            push_string(&mut text, "digest", &encode_digest(&component.digest));
+        }
+        for package in &self.packages {
+            text.push_str("\n[[package]]\n");
+            push_string(&mut text, "name", &package.name);
+            push_string(&mut text, "digest", &encode_digest(&package.digest));
+        }
+        for wire in &self.wires {
+            text.push_str("\n[[wire]]\n");
+            push_string(&mut text, "importer", &wire.importer);
+            push_string(&mut text, "import", &wire.import);
+            push_string(&mut text, "exporter", &wire.exporter);
+            push_string(&mut text, "export", &wire.export);
+        }
+        text
+    }
+
+    /// Decode a lockfile
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lockfile is malformed, misses a field or was
+    /// written by a newer format version.
+    pub fn from_lockfile(text: &str) -> Result<Self> {
+        let mut lock = Self::new();
+        let mut version = None;
+        let mut table: Option<(&str, Vec<(&str, Value)>)> = None;
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("[[").and_then(|rest| rest.strip_suffix("]]")) {
+                if let Some((kind, fields)) = table.take() {
+                    lock.add_entry(kind, &fields)?;
+                }
+                table = Some((name.trim(), Vec::new()));
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| Error::parse_error("Expected key = value in lockfile"))?;
+            let key = key.trim();
+            let value = parse_value(value.trim())?;
+            match table.as_mut() {
+                Some((_, fields)) => fields.push((key, value)),
+                None if key == "version" => version = Some(value.integer()?),
+                None => return Err(Error::parse_error("Unknown top-level key in lockfile")),
+            }
+        }
+        if let Some((kind, fields)) = table.take() {
+            lock.add_entry(kind, &fields)?;
+        }
+
+        match version {
+            Some(version) if version <= u64::from(LOCKFILE_VERSION) => Ok(lock),
+            Some(_) => Err(Error::parse_error("Lockfile was written by a newer version")),
+            None => Err(Error::parse_error("Lockfile version missing")),
+        }
+    }
+
+    fn add_entry(&mut self, kind: &str, fields: &[(&str, Value)]) -> Result<()> {
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| Error::parse_error("Lockfile entry misses a field"))
+        };
+        let string = |key: &str| field(key).and_then(Value::string).map(str::to_string);
+        match kind {
+            "component" => self.components.push(LockedComponent {
+                name:   string("name")?,
+                size:   field("size")?.integer()?,
+                digest: decode_digest(field("digest")?.string()?)?,
+            }),
+            "package" => self.packages.push(LockedPackage {
+                name:   string("name")?,
+                digest: decode_digest(field("digest")?.string()?)?,
+            }),
+            "wire" => self.wires.push(LockedWire {
+                importer: string("importer")?,
+                import:   string("import")?,
+                exporter: string("exporter")?,
+                export:   string("export")?,
+            }),
+            _ => return Err(Error::parse_error("Unknown table in lockfile")),
+        }
+        Ok(())
+    }
+}
+
+/// Compare named digests, reporting differences in the order of `locked`
+fn compare(
+    kind: &'static str,
+    locked: &[(String, [u8; 32])],
+    actual: &[(String, [u8; 32])],
+    differences: &mut Vec<LockDifference>,
+) {
+    for (name, digest) in locked {
+        match actual.iter().find(|(actual, _)| actual == name) {
+            None => differences.push(LockDifference::Missing {
+                kind,
+                name: name.clone(),
+            }),
+            Some((_, actual)) if actual != digest => differences.push(LockDifference::Digest {
+                kind,
+                name: name.clone(),
+            }),
+            Some(_) => {},
+        }
+    }
+    for (name, _) in actual {
+        if !locked.iter().any(|(locked, _)| locked == name) {
+            differences.push(LockDifference::Unlocked {
+                kind,
+                name: name.clone(),
+            });
+        }
+    }
+}
+
+/// Value of a lockfile key
+#[derive(Debug)]
+enum Value {
+    String(String),
+    Integer(u64),
+}
+
+impl Value {
+    fn string(&self) -> Result<&str> {
+        match self {
+            Self::String(value) => Ok(value),
+            Self::Integer(_) => Err(Error::parse_error("Expected a string in lockfile")),
+        }
+    }
+
+    fn integer(&self) -> Result<u64> {
+        match self {
+            Self::Integer(value) => Ok(*value),
+            Self::String(_) => Err(Error::parse_error("Expected an integer in lockfile")),
+        }
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value> {
+    let Some(quoted) = text.strip_prefix('"') else {
+        return text
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| Error::parse_error("Invalid integer in lockfile"));
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    loop {
+        match chars.next() {
+            Some('"') => break,
+            Some('\\') => match chars.next() {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                _ => return Err(Error::parse_error("Invalid escape in lockfile string")),
+            },
+            Some(c) => value.push(c),
+            None => return Err(Error::parse_error("Unterminated string in lockfile")),
+        }
+    }
+    if !chars.as_str().trim().is_empty() {
+        return Err(Error::parse_error("Unexpected text after lockfile string"));
+    }
+    Ok(Value::String(value))
+}
+
+fn push_string(text: &mut String, key: &str, value: &str) {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    text.push_str(&format!("{key} = \"{escaped}\"\n"));
+}
+
+fn encode_digest(digest: &[u8; 32]) -> String {
+    let mut text = String::from(DIGEST_PREFIX);
+    for byte in digest {
+        text.push_str(&format!("{byte:02x}"));
+    }
+    text
+}
+
+fn decode_digest(text: &str) -> Result<[u8; 32]> {
+    let hex = text
+        .strip_prefix(DIGEST_PREFIX)
+        .filter(|hex| hex.len() == 64 && hex.is_ascii())
+        .ok_or_else(|| Error::parse_error("Expected a sha256 digest in lockfile"))?;
+    let mut digest = [0u8; 32];
+    for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = core::str::from_utf8(pair).unwrap_or_default();
+        *byte = u8::from_str_radix(pair, 16)
+            .map_err(|_| Error::parse_error("Invalid digest in lockfile"))?;
+    }
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock() -> CompositionLock {
+        let mut lock = CompositionLock::new();
+        lock.lock_component("adder", b"\0asm adder");
+        lock.lock_component("calculator", b"\0asm calculator");
+        lock.lock_package("wasi:cli@0.2.0", b"package wasi:cli@0.2.0;");
+        lock.lock_package("docs:adder@0.1.0", b"package docs:adder@0.1.0;");
+        lock.wires.push(LockedWire {
+            importer: "calculator".to_string(),
+            import:   "docs:adder/add@0.1.0".to_string(),
+            exporter: "adder".to_string(),
+            export:   "docs:adder/add@0.1.0".to_string(),
+        });
+        lock
+    }
+
+    #[test]
+    fn test_lockfile_round_trip() -> Result<()> {
+        let lock = lock();
+        assert_eq!(lock.packages[0].name, "docs:adder@0.1.0");
+        let text = lock.to_lockfile();
+        assert!(text.contains("[[wire]]\nimporter = \"calculator\""));
+        assert_eq!(CompositionLock::from_lockfile(&text)?, lock);
+
+        assert!(CompositionLock::from_lockfile("version = 2\n").is_err());
+        assert!(CompositionLock::from_lockfile("[[component]]\nname = \"a\"\n").is_err());
+        let truncated = text.replace("digest = \"sha256:", "digest = \"sha256:0");
+        assert!(CompositionLock::from_lockfile(&truncated).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_reports_differences() {
+        let locked = lock();
+        assert!(locked.verify(&locked.clone()).is_empty());
+
+        let mut actual = locked.clone();
+        actual.lock_component("adder", b"\0asm adder v2");
+        actual.lock_package("wasi:io@0.2.0", b"package wasi:io@0.2.0;");
+        actual.wires.clear();
+        let differences = locked.verify(&actual);
+        assert_eq!(differences, [
+            LockDifference::Digest {
+                kind: "component",
+                name: "adder".to_string(),
+            },
+            LockDifference::Unlocked {
+                kind: "package",
+                name: "wasi:io@0.2.0".to_string(),
+            },
+            LockDifference::MissingWire(locked.wires[0].clone()),
+        ]);
+        assert_eq!(
+            differences[2].to_string(),
+            "locked wire calculator.docs:adder/add@0.1.0 <- adder.docs:adder/add@0.1.0 is missing"
+        );
+    }
+}
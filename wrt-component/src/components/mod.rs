@@ -3,6 +3,8 @@
 //! This module handles component instantiation, communication, linking,
 //! and registry management for the WebAssembly Component Model.
 
+#[cfg(feature = "std")]
+pub mod call_graph;
 pub mod component;
 pub mod component_communication;
 pub mod component_instantiation;
@@ -10,6 +12,10 @@
 pub mod component_no_std;
 pub mod component_registry; // Consolidated: contains both std and no_std implementations
 pub mod component_resolver;
+#[cfg(feature = "std")]
+pub mod composition;
+#[cfg(feature = "std")]
+pub mod composition_lock;
 
 // Re-export based on feature flags to avoid ambiguous imports
 #[cfg(feature = "std")]
@@ -31,6 +37,19 @@
     MemoryConfig, MemoryHandle, ResolvedImport, create_component_export, create_component_import,
     create_function_signature,
 };
+#[cfg(feature = "std")]
+pub use call_graph::{BindingKind, BindingProvider, CallBinding, CallGraph, UnresolvedImport};
+#[cfg(all(feature = "std", feature = "wrt-execution"))]
+pub use composition::ComposedInstance;
+#[cfg(feature = "std")]
+pub use composition::{
+    CompositionGraph, CompositionPlan, LinkMismatch, NodeId, OpenImport, TypeMismatch, Wire,
+};
+#[cfg(feature = "std")]
+pub use composition_lock::{
+    CompositionLock, LOCKFILE_NAME, LOCKFILE_VERSION, LockDifference, LockedComponent,
+    LockedPackage, LockedWire,
+};
 pub use component_linker::{
     CircularDependencyMode, ComponentDefinition, ComponentId, ComponentLinker, ComponentMetadata,
     GraphEdge, GraphNode, LinkGraph, LinkerConfig, LinkingStats,
@@ -182,14 +182,7 @@ pub fn get_generative_type(
         instance_id: ComponentInstanceId,
     ) -> Option<GenerativeResourceType> {
         let instance_types = self.instance_types.get(&instance_id)?;
-        for i in 0..instance_types.len() {
-            if let Ok(t) = instance_types.get(i) {
-                if t.unique_type_id == type_id {
-                    return Some(t);
-                }
-            }
-        }
-        None
+        instance_types.iter().find(|t| t.unique_type_id == type_id)
     }
 
     pub fn add_type_bound(
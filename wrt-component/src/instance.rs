@@ -179,14 +179,7 @@ pub fn get_export(&self, name: &str) -> Option<Export> {
         /// Gets a mutable export by name
         /// Note: Due to BoundedVec's serialization architecture, this returns an owned value
         pub fn get_export_mut(&mut self, name: &str) -> Option<Export> {
-            for i in 0..self.exports.len() {
-                if let Ok(export) = self.exports.get(i) {
-                    if export.name == name {
-                        return Some(export);
-                    }
-                }
-            }
-            None
+            self.exports.iter().find(|export| export.name == name)
         }
 
         /// Creates a builder for this instance value
@@ -287,14 +280,7 @@ pub fn get_instance(&self, name: &str) -> Option<InstanceValue> {
 
         /// Gets a mutable instance by name
         pub fn get_instance_mut(&mut self, name: &str) -> Option<InstanceValue> {
-            for i in 0..self.instances.len() {
-                if let Ok(instance) = self.instances.get(i) {
-                    if instance.name.as_str().ok() == Some(name) {
-                        return Some(instance);
-                    }
-                }
-            }
-            None
+            self.instances.iter().find(|instance| instance.name.as_str().ok() == Some(name))
         }
 
         /// Returns the number of instances in the collection
@@ -5,7 +5,8 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
-// Core imports for std environments
+// Shared host-tier prelude
+pub use wrt_prelude::host::*;
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -21,33 +22,17 @@
     vec::Vec,
 };
 pub use core::{
-    any::Any,
     array,
-    cmp::{Eq, Ord, PartialEq, PartialOrd},
-    convert::{From, Into, TryFrom, TryInto},
-    fmt,
-    fmt::{Debug, Display, Write as FmtWrite},
-    iter,
-    marker::PhantomData,
-    mem,
-    ops::{Deref, DerefMut},
-    result, slice, str,
+    convert::{From, Into},
+    fmt::Write as FmtWrite,
+    iter, result,
     time::Duration,
 };
-// Re-export from std when the std feature is enabled
 #[cfg(feature = "std")]
-pub use std::{
-    boxed::Box,
-    collections::{HashMap, HashSet},
-    fmt as std_fmt, format, io,
-    string::{String, ToString},
-    sync::Arc,
-    vec,
-    vec::Vec,
-};
+pub use std::{fmt as std_fmt, io};
 
-// Always use wrt_sync for consistent Mutex/RwLock behavior across std/no_std
-#[cfg(feature = "std")]
+// Replaces the tier's locks: always use wrt_sync for consistent Mutex/RwLock
+// behavior across std/no_std
 pub use wrt_sync::{Mutex, RwLock};
 
 #[cfg(feature = "decoder")]
@@ -68,8 +53,6 @@
 // Placeholder for ResourceHandle - seems to be defined in multiple places
 pub type ResourceHandle = u32;
 // Note: sections moved to decoder_no_alloc or not available
-// Re-export from wrt-error
-pub use wrt_error::{Error, ErrorCategory, Result, codes, kinds};
 
 // Re-export from wrt-format
 pub use wrt_format::component::ValType as FormatValType;
@@ -80,19 +63,9 @@
 pub use wrt_foundation::component_builder::{
     ComponentTypeBuilder, ExportBuilder, ImportBuilder, NamespaceBuilder,
 };
+// Re-export component_value for no_std; the tier provides it with std
 #[cfg(not(feature = "std"))]
 pub use wrt_foundation::component_value::{ComponentValue, ValType};
-// Re-export component_value for both std and no_std
-#[cfg(feature = "std")]
-pub use wrt_foundation::MemoryProvider;
-#[cfg(feature = "std")]
-pub use wrt_foundation::component_value::{ComponentValue, ValType};
-// Binary std/no_std choice - remove conflicting type aliases
-#[cfg(not(feature = "std"))]
-pub use wrt_foundation::{
-    MemoryProvider,
-    bounded_collections::{BoundedMap, BoundedSet},
-};
 
 // Unified type aliases for std/no_std compatibility
 #[cfg(not(feature = "std"))]
@@ -105,23 +78,10 @@
 pub use wrt_foundation::{
     // Common types
     ExternType,
-    bounded::{BoundedStack, BoundedString, BoundedVec, MAX_WASM_NAME_LENGTH},
-    // Budget management
-    budget_aware_provider::CrateId,
-    // Builtin types
-    builtin::BuiltinType,
+    bounded::{BoundedStack, MAX_WASM_NAME_LENGTH},
     component::ComponentType,
     // Resource types
     resource::{ResourceOperation, ResourceType},
-    safe_managed_alloc,
-    // Memory providers
-    safe_memory::NoStdProvider,
-    // SafeMemory types
-    safe_memory::{SafeMemoryHandler, SafeSlice, SafeStack},
-    types::{BlockType, FuncType, GlobalType, MemoryType, TableType, ValueType},
-    values::Value,
-    // Verification types
-    verification::VerificationLevel,
 };
 // Re-export from wrt-host
 pub use wrt_host::{
@@ -141,10 +101,6 @@
 //     LinkInterceptorStrategy,
 //     Modification,
 // };
-// Import synchronization primitives for no_std
-#[cfg(not(feature = "std"))]
-pub use wrt_sync::{Mutex, RwLock};
-
 // Include debug logging macro (crate-internal only)
 // pub use crate::debug_println;
 // Re-export Instant for no_std environments
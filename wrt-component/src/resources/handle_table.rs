@@ -0,0 +1,746 @@
+//! Canonical resource handle tables
+//!
+//! A [`HandleTable`] implements the lifecycle of Component Model resources
+//! across the instances of a composition. Every instance has its own table
+//! of handles; a handle is either an `own`, which drops the resource when
+//! it is dropped, or a `borrow`, which is lent for the duration of one call
+//! and must be dropped before that call returns.
+//!
+//! - [`HandleTable::resource_new`], [`HandleTable::resource_rep`] and
+//!   [`HandleTable::resource_drop`] implement the `resource.new`,
+//!   `resource.rep` and `resource.drop` built-ins. Dropping an own runs the
+//!   drop handler of its type and fails while the resource is lent out.
+//! - [`HandleTable::transfer`] moves an own to another instance, as when an
+//!   `own<T>` is passed across a component boundary.
+//! - [`HandleTable::lend`] creates a borrow in another instance within a
+//!   call scope opened by [`HandleTable::begin_call`];
+//!   [`HandleTable::end_call`] fails if a borrow of the scope is still live.
+//! - [`HandleTable::teardown`] drops the handles left in an instance and
+//!   runs their drop handlers.
+//!
+//! With leak tracking enabled each own records the call site that created
+//! it, and [`HandleTable::teardown`] reports the owns left at teardown as
+//! [`LeakedResource`]s. Resource operations are offered to an attached
+//! [`LinkInterceptor`] first; a strategy error vetoes the operation and a
+//! strategy may override the representation returned by `resource.rep`.
+//!
+//! Trusted host code can read and mint representations of owns through
+//! [`HandleTable::host_resource_rep`] and [`HandleTable::host_resource_mint`]
+//! while it holds a capability from [`HandleTable::grant_rep_access`]; see
+//! [`rep_access`](super::rep_access).
+
+use std::{
+    boxed::Box,
+    collections::BTreeMap,
+    vec::Vec,
+};
+
+use wrt_error::{Error, ErrorCategory, Result, codes};
+use wrt_foundation::resource::{
+    ResourceCanonicalOperation, ResourceDrop, ResourceNew, ResourceRep,
+};
+use wrt_intercept::LinkInterceptor;
+
+use super::rep_access::{
+    RepAccessCapability, RepAccessControl, RepAccessOp, RepAccessScope, RepAuditRecord,
+    RepAuditSink,
+};
+use crate::components::InstanceId;
+
+/// Maximum number of live handles in the table of one instance
+pub const MAX_HANDLES_PER_INSTANCE: usize = 1024;
+
+/// Index of a resource type in a [`HandleTable`]
+pub type ResourceTypeId = u32;
+
+/// Identifier of the code location that created a resource, e.g. the
+/// function index and instruction offset of the `resource.new` packed by
+/// the caller
+pub type CallSiteId = u64;
+
+/// Call within which borrows are lent
+pub type CallScope = u64;
+
+/// Called with the representation of a resource when its last own is
+/// dropped
+pub type ResourceDropHandler = Box<dyn FnMut(u32) -> Result<()> + Send>;
+
+/// Kind of a handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    /// Owning handle
+    Own,
+    /// Handle lent by `lender` for the duration of `scope`
+    Borrow {
+        /// Instance holding the own the borrow was created from
+        lender: InstanceId,
+        /// Handle of that own in the lender's table
+        handle: u32,
+        /// Call the borrow is valid in
+        scope:  CallScope,
+    },
+}
+
+/// Own left in an instance when it was torn down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeakedResource {
+    /// Instance the handle was left in
+    pub instance: InstanceId,
+    /// Handle in that instance
+    pub handle:   u32,
+    /// Resource type
+    pub type_id:  ResourceTypeId,
+    /// Representation of the resource
+    pub rep:      u32,
+    /// Call site that created the resource
+    pub site:     Option<CallSiteId>,
+}
+
+/// Entry of an instance's handle table
+#[derive(Debug, Clone, Copy)]
+struct HandleEntry {
+    type_id: ResourceTypeId,
+    rep:     u32,
+    kind:    HandleKind,
+    /// Number of live borrows lent from this handle
+    lent:    u32,
+    site:    Option<CallSiteId>,
+}
+
+/// Handles of one instance
+#[derive(Debug, Default)]
+struct InstanceHandles {
+    entries: BTreeMap<u32, HandleEntry>,
+    /// Handles freed for reuse
+    free:    Vec<u32>,
+}
+
+impl InstanceHandles {
+    /// Handle the next insertion will use; 0 is never a valid handle
+    fn next_handle(&self) -> u32 {
+        self.free.last().copied().unwrap_or(self.entries.len() as u32 + 1)
+    }
+
+    fn insert(&mut self, entry: HandleEntry) -> Result<u32> {
+        if self.entries.len() >= MAX_HANDLES_PER_INSTANCE {
+            return Err(Error::resource_exhausted("Handle table of the instance is full"));
+        }
+        let handle = self.next_handle();
+        self.free.pop();
+        self.entries.insert(handle, entry);
+        Ok(handle)
+    }
+
+    fn remove(&mut self, handle: u32) -> Option<HandleEntry> {
+        let entry = self.entries.remove(&handle)?;
+        self.free.push(handle);
+        Some(entry)
+    }
+}
+
+/// Resource type with its drop handler
+struct ResourceTypeEntry {
+    /// Instance implementing the type
+    owner:   InstanceId,
+    on_drop: Option<ResourceDropHandler>,
+}
+
+/// Handle tables of the instances of a composition
+#[derive(Default)]
+pub struct HandleTable {
+    types:       Vec<ResourceTypeEntry>,
+    instances:   BTreeMap<InstanceId, InstanceHandles>,
+    /// Live borrows per open call scope
+    scopes:      BTreeMap<CallScope, u32>,
+    next_scope:  CallScope,
+    track_leaks: bool,
+    interceptor: Option<LinkInterceptor>,
+    rep_access:  RepAccessControl,
+}
+
+impl core::fmt::Debug for HandleTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HandleTable")
+            .field("type_count", &self.types.len())
+            .field("instances", &self.instances)
+            .field("scopes", &self.scopes)
+            .field("track_leaks", &self.track_leaks)
+            .field("intercepted", &self.interceptor.is_some())
+            .field("rep_access", &self.rep_access)
+            .finish()
+    }
+}
+
+impl HandleTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the creation site of every own and report the owns left at
+    /// teardown
+    pub fn set_leak_tracking(&mut self, enabled: bool) {
+        self.track_leaks = enabled;
+    }
+
+    /// Offer every resource operation to the strategies of `interceptor`
+    pub fn set_interceptor(&mut self, interceptor: LinkInterceptor) {
+        self.interceptor = Some(interceptor);
+    }
+
+    /// Define a resource type implemented by `owner`
+    pub fn define_type(
+        &mut self,
+        owner: InstanceId,
+        on_drop: Option<ResourceDropHandler>,
+    ) -> ResourceTypeId {
+        self.types.push(ResourceTypeEntry { owner, on_drop });
+        self.types.len() as ResourceTypeId - 1
+    }
+
+    /// Instance implementing `type_id`
+    pub fn type_owner(&self, type_id: ResourceTypeId) -> Option<InstanceId> {
+        self.types.get(type_id as usize).map(|ty| ty.owner)
+    }
+
+    /// `resource.new`: create a resource of `type_id` with representation
+    /// `rep` and return an own of it in `instance`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is unknown, the instance's table is full
+    /// or an interceptor vetoes the operation.
+    pub fn resource_new(
+        &mut self,
+        instance: InstanceId,
+        type_id: ResourceTypeId,
+        rep: u32,
+    ) -> Result<u32> {
+        self.resource_new_at(instance, type_id, rep, None)
+    }
+
+    /// `resource.new` recording the call site creating the resource, which
+    /// leak reports refer to while leak tracking is enabled
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is unknown, the instance's table is full
+    /// or an interceptor vetoes the operation.
+    pub fn resource_new_at(
+        &mut self,
+        instance: InstanceId,
+        type_id: ResourceTypeId,
+        rep: u32,
+        site: Option<CallSiteId>,
+    ) -> Result<u32> {
+        if type_id as usize >= self.types.len() {
+            return Err(Error::resource_not_found("Unknown resource type"));
+        }
+        let handles = self.instances.entry(instance).or_default();
+        let operation = ResourceCanonicalOperation::New(ResourceNew { type_idx: type_id });
+        intercept(self.interceptor.as_ref(), handles.next_handle(), &operation)?;
+        handles.insert(HandleEntry {
+            type_id,
+            rep,
+            kind: HandleKind::Own,
+            lent: 0,
+            site: site.filter(|_| self.track_leaks),
+        })
+    }
+
+    /// `resource.rep`: representation of the resource `handle` of
+    /// `instance` refers to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid or an interceptor vetoes
+    /// the operation.
+    pub fn resource_rep(&self, instance: InstanceId, handle: u32) -> Result<u32> {
+        let entry = self.entry(instance, handle)?;
+        let operation = ResourceCanonicalOperation::Rep(ResourceRep {
+            type_idx: entry.type_id,
+        });
+        match intercept(self.interceptor.as_ref(), handle, &operation)? {
+            Some(bytes) => bytes
+                .get(..4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u32::from_le_bytes)
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_ERROR,
+                        "Interceptor returned an invalid resource representation",
+                    )
+                }),
+            None => Ok(entry.rep),
+        }
+    }
+
+    /// Kind of `handle` in `instance`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid.
+    pub fn kind(&self, instance: InstanceId, handle: u32) -> Result<HandleKind> {
+        Ok(self.entry(instance, handle)?.kind)
+    }
+
+    /// Number of live handles in `instance`
+    pub fn handle_count(&self, instance: InstanceId) -> usize {
+        self.instances.get(&instance).map_or(0, |handles| handles.entries.len())
+    }
+
+    /// `resource.drop`: drop `handle` of `instance`
+    ///
+    /// Dropping an own runs the drop handler of its type; dropping a borrow
+    /// ends the loan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid, an own is still lent out,
+    /// an interceptor vetoes the operation or the drop handler fails.
+    pub fn resource_drop(&mut self, instance: InstanceId, handle: u32) -> Result<()> {
+        let entry = *self.entry(instance, handle)?;
+        if entry.lent > 0 {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_ACCESS_ERROR,
+                "Resource dropped while it is borrowed",
+            ));
+        }
+        let operation = ResourceCanonicalOperation::Drop(ResourceDrop {
+            type_idx: entry.type_id,
+        });
+        intercept(self.interceptor.as_ref(), handle, &operation)?;
+
+        self.remove(instance, handle);
+        match entry.kind {
+            HandleKind::Own => self.run_drop_handler(entry.type_id, entry.rep),
+            HandleKind::Borrow { .. } => Ok(()),
+        }
+    }
+
+    /// Move the own `handle` of `from` into the table of `to` and return
+    /// its new handle
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid, is a borrow, is lent out
+    /// or the table of `to` is full.
+    pub fn transfer(&mut self, from: InstanceId, handle: u32, to: InstanceId) -> Result<u32> {
+        let entry = *self.entry(from, handle)?;
+        if entry.kind != HandleKind::Own {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_ACCESS_ERROR,
+                "Only an own handle can be transferred",
+            ));
+        }
+        if entry.lent > 0 {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_ACCESS_ERROR,
+                "Resource transferred while it is borrowed",
+            ));
+        }
+        let new_handle = self.instances.entry(to).or_default().insert(entry)?;
+        self.remove(from, handle);
+        Ok(new_handle)
+    }
+
+    /// Open a call scope for lending borrows
+    pub fn begin_call(&mut self) -> CallScope {
+        self.next_scope += 1;
+        self.scopes.insert(self.next_scope, 0);
+        self.next_scope
+    }
+
+    /// Lend `handle` of `from` to `to` for the call `scope` and return the
+    /// borrow handle in `to`
+    ///
+    /// Lending a borrow lends the own it was created from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid, the scope is not open or
+    /// the table of `to` is full.
+    pub fn lend(
+        &mut self,
+        from: InstanceId,
+        handle: u32,
+        to: InstanceId,
+        scope: CallScope,
+    ) -> Result<u32> {
+        let entry = *self.entry(from, handle)?;
+        if !self.scopes.contains_key(&scope) {
+            return Err(Error::validation_error("Call scope is not open"));
+        }
+        let (lender, lender_handle) = match entry.kind {
+            HandleKind::Own => (from, handle),
+            HandleKind::Borrow { lender, handle, .. } => (lender, handle),
+        };
+        let borrow = self.instances.entry(to).or_default().insert(HandleEntry {
+            kind: HandleKind::Borrow {
+                lender,
+                handle: lender_handle,
+                scope,
+            },
+            lent: 0,
+            site: None,
+            ..entry
+        })?;
+        self.adjust_loan(lender, lender_handle, scope, true);
+        Ok(borrow)
+    }
+
+    /// Close the call `scope`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a borrow lent within the scope has not been
+    /// dropped; such borrows are revoked.
+    pub fn end_call(&mut self, scope: CallScope) -> Result<()> {
+        let live = self.scopes.get(&scope).copied().unwrap_or(0);
+        if live > 0 {
+            let outstanding: Vec<(InstanceId, u32)> = self
+                .instances
+                .iter()
+                .flat_map(|(&instance, handles)| {
+                    handles.entries.iter().filter_map(move |(&handle, entry)| {
+                        matches!(entry.kind, HandleKind::Borrow { scope: s, .. } if s == scope)
+                            .then_some((instance, handle))
+                    })
+                })
+                .collect();
+            for (instance, handle) in outstanding {
+                self.remove(instance, handle);
+            }
+        }
+        self.scopes.remove(&scope);
+        if live > 0 {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_ACCESS_ERROR,
+                "Borrowed handle not dropped before the call returned",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drop every handle left in `instance` and run the drop handlers of
+    /// its owns
+    ///
+    /// Borrows lent from the instance's owns are revoked. Returns the owns
+    /// that were left when leak tracking is enabled, and no leaks otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of a drop handler; the remaining handles are
+    /// dropped regardless.
+    pub fn teardown(&mut self, instance: InstanceId) -> Result<Vec<LeakedResource>> {
+        let Some(handles) = self.instances.remove(&instance) else {
+            return Ok(Vec::new());
+        };
+
+        // Revoke borrows of the instance's owns held elsewhere
+        for other in self.instances.values_mut() {
+            other.entries.retain(|_, entry| {
+                !matches!(entry.kind, HandleKind::Borrow { lender, .. } if lender == instance)
+            });
+        }
+
+        let mut leaks = Vec::new();
+        let mut result = Ok(());
+        for (handle, entry) in handles.entries {
+            match entry.kind {
+                HandleKind::Own => {
+                    if self.track_leaks {
+                        leaks.push(LeakedResource {
+                            instance,
+                            handle,
+                            type_id: entry.type_id,
+                            rep: entry.rep,
+                            site: entry.site,
+                        });
+                    }
+                    let dropped = self.run_drop_handler(entry.type_id, entry.rep);
+                    if result.is_ok() {
+                        result = dropped;
+                    }
+                },
+                HandleKind::Borrow {
+                    lender,
+                    handle,
+                    scope,
+                } => self.adjust_loan(lender, handle, scope, false),
+            }
+        }
+        result.map(|()| leaks)
+    }
+
+    /// Grant `holder` access to the representations of the resource types
+    /// and operations of `scope`
+    ///
+    /// `holder` names the host subsystem in audit records.
+    pub fn grant_rep_access(&mut self, holder: &str, scope: RepAccessScope) -> RepAccessCapability {
+        self.rep_access.grant(holder, scope)
+    }
+
+    /// Revoke `capability`, returning whether it was granted by this table
+    pub fn revoke_rep_access(&mut self, capability: RepAccessCapability) -> bool {
+        self.rep_access.revoke(capability)
+    }
+
+    /// Pass every audit record of representation access to `sink` as it is
+    /// made
+    pub fn set_rep_audit_sink(&mut self, sink: RepAuditSink) {
+        self.rep_access.set_sink(sink);
+    }
+
+    /// Most recent audit records of representation access, oldest first
+    pub fn rep_audit_log(&self) -> impl Iterator<Item = &RepAuditRecord> {
+        self.rep_access.log()
+    }
+
+    /// Representation of the own `handle` of `instance`, for trusted host
+    /// code holding `capability`
+    ///
+    /// Unlike [`Self::resource_rep`] this returns the stored representation
+    /// and is not offered to the interceptor. The attempt is audited whether
+    /// or not it succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `codes::ACCESS_DENIED` if the capability is not granted or
+    /// does not cover reading the handle's type, and an error if the handle
+    /// is invalid or a borrow.
+    pub fn host_resource_rep(
+        &mut self,
+        capability: &RepAccessCapability,
+        instance: InstanceId,
+        handle: u32,
+    ) -> Result<u32> {
+        let entry = self.entry(instance, handle).copied();
+        let target = match entry {
+            Ok(entry) if entry.kind != HandleKind::Own => Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_ACCESS_ERROR,
+                "Host representation access requires an own handle",
+            )),
+            Ok(_) => Ok(()),
+            Err(error) => Err(error),
+        };
+        let type_id = entry.as_ref().ok().map(|entry| entry.type_id);
+        self.rep_access.authorize(
+            capability,
+            RepAccessOp::Rep,
+            instance,
+            type_id,
+            Some(handle),
+            target,
+        )?;
+        entry.map(|entry| entry.rep)
+    }
+
+    /// Create a resource of `type_id` for the representation `rep` and
+    /// return an own of it in `instance`, for trusted host code holding
+    /// `capability`
+    ///
+    /// The attempt is audited whether or not it succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `codes::ACCESS_DENIED` if the capability is not granted or
+    /// does not cover minting `type_id`, and the errors of
+    /// [`Self::resource_new`].
+    pub fn host_resource_mint(
+        &mut self,
+        capability: &RepAccessCapability,
+        instance: InstanceId,
+        type_id: ResourceTypeId,
+        rep: u32,
+    ) -> Result<u32> {
+        let target = if (type_id as usize) < self.types.len() {
+            Ok(())
+        } else {
+            Err(Error::resource_not_found("Unknown resource type"))
+        };
+        self.rep_access.authorize(
+            capability,
+            RepAccessOp::Mint,
+            instance,
+            Some(type_id),
+            None,
+            target,
+        )?;
+        let minted = self.resource_new(instance, type_id, rep);
+        self.rep_access.complete_mint(minted.as_ref().ok().copied());
+        minted
+    }
+
+    fn entry(&self, instance: InstanceId, handle: u32) -> Result<&HandleEntry> {
+        self.instances
+            .get(&instance)
+            .and_then(|handles| handles.entries.get(&handle))
+            .ok_or_else(|| Error::resource_invalid_handle("Invalid resource handle"))
+    }
+
+    /// Remove a handle, ending its loan if it is a borrow
+    fn remove(&mut self, instance: InstanceId, handle: u32) {
+        let entry = self.instances.get_mut(&instance).and_then(|handles| handles.remove(handle));
+        if let Some(HandleEntry {
+            kind:
+                HandleKind::Borrow {
+                    lender,
+                    handle,
+                    scope,
+                },
+            ..
+        }) = entry
+        {
+            self.adjust_loan(lender, handle, scope, false);
+        }
+    }
+
+    /// Count a borrow of the own `handle` of `lender` in `scope` in or out
+    fn adjust_loan(&mut self, lender: InstanceId, handle: u32, scope: CallScope, lend: bool) {
+        let own = self.instances.get_mut(&lender).and_then(|ours| ours.entries.get_mut(&handle));
+        if let Some(own) = own {
+            own.lent = if lend { own.lent + 1 } else { own.lent.saturating_sub(1) };
+        }
+        if let Some(live) = self.scopes.get_mut(&scope) {
+            *live = if lend { *live + 1 } else { live.saturating_sub(1) };
+        }
+    }
+
+    fn run_drop_handler(&mut self, type_id: ResourceTypeId, rep: u32) -> Result<()> {
+        match self.types.get_mut(type_id as usize).and_then(|ty| ty.on_drop.as_mut()) {
+            Some(on_drop) => on_drop(rep),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Offer a resource operation to the strategies of `interceptor`
+fn intercept(
+    interceptor: Option<&LinkInterceptor>,
+    handle: u32,
+    operation: &ResourceCanonicalOperation,
+) -> Result<Option<Vec<u8>>> {
+    match interceptor {
+        Some(interceptor) => interceptor.intercept_resource_operation(handle, operation),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use wrt_intercept::LinkInterceptorStrategy;
+
+    use super::*;
+
+    /// Strategy rejecting drops of type 1
+    struct PinType1;
+
+    impl LinkInterceptorStrategy for PinType1 {
+        fn before_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            args: &[wrt_foundation::values::Value],
+        ) -> Result<Vec<wrt_foundation::values::Value>> {
+            Ok(args.to_vec())
+        }
+
+        fn after_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[wrt_foundation::values::Value],
+            result: Result<Vec<wrt_foundation::values::Value>>,
+        ) -> Result<Vec<wrt_foundation::values::Value>> {
+            result
+        }
+
+        fn intercept_resource_operation(
+            &self,
+            _handle: u32,
+            operation: &ResourceCanonicalOperation,
+        ) -> Result<Option<Vec<u8>>> {
+            match operation {
+                ResourceCanonicalOperation::Drop(ResourceDrop { type_idx: 1 }) => {
+                    Err(Error::validation_error("Type 1 is pinned"))
+                },
+                _ => Ok(None),
+            }
+        }
+
+        fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+            Arc::new(PinType1)
+        }
+    }
+
+    #[test]
+    fn test_own_borrow_transfer_and_teardown() -> Result<()> {
+        let (producer, consumer) = (1, 2);
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&dropped);
+
+        let mut table = HandleTable::new();
+        table.set_leak_tracking(true);
+        let file = table.define_type(
+            producer,
+            Some(Box::new(move |rep| {
+                log.lock().unwrap().push(rep);
+                Ok(())
+            })),
+        );
+        let pinned = table.define_type(producer, None);
+        let mut interceptor = LinkInterceptor::new("resources");
+        interceptor.add_strategy(Arc::new(PinType1));
+        table.set_interceptor(interceptor);
+
+        // Borrows must be dropped before the call returns
+        let own = table.resource_new_at(producer, file, 100, Some(7))?;
+        let scope = table.begin_call();
+        let borrow = table.lend(producer, own, consumer, scope)?;
+        assert_eq!(table.resource_rep(consumer, borrow)?, 100);
+        assert!(table.resource_drop(producer, own).is_err());
+        assert!(table.transfer(producer, own, consumer).is_err());
+        table.resource_drop(consumer, borrow)?;
+        table.end_call(scope)?;
+
+        let scope = table.begin_call();
+        table.lend(producer, own, consumer, scope)?;
+        assert!(table.end_call(scope).is_err());
+        assert_eq!(table.handle_count(consumer), 0);
+
+        // Ownership moves across instances
+        let moved = table.transfer(producer, own, consumer)?;
+        assert_eq!(table.handle_count(producer), 0);
+        assert_eq!(table.kind(consumer, moved)?, HandleKind::Own);
+        table.resource_drop(consumer, moved)?;
+        assert!(table.resource_rep(consumer, moved).is_err());
+
+        let held = table.resource_new(consumer, pinned, 5)?;
+        assert!(table.resource_drop(consumer, held).is_err());
+
+        // Teardown drops and reports what is left
+        table.resource_new_at(consumer, file, 200, Some(9))?;
+        let leaks = table.teardown(consumer)?;
+        assert_eq!(leaks.len(), 2);
+        assert_eq!(leaks[1], LeakedResource {
+            instance: consumer,
+            handle:   2,
+            type_id:  file,
+            rep:      200,
+            site:     Some(9),
+        });
+        assert_eq!(*dropped.lock().unwrap(), vec![100, 200]);
+        Ok(())
+    }
+}
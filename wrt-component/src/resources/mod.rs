@@ -15,8 +15,12 @@
 pub mod buffer_pool;
 pub mod dynamic_quota_manager;
 #[cfg(feature = "std")]
+pub mod handle_table;
+#[cfg(feature = "std")]
 pub mod memory_access;
 pub mod memory_strategy;
+#[cfg(feature = "std")]
+pub mod rep_access;
 pub mod resource_arena; // Consolidated: contains both std and no_std implementations
 pub mod resource_builder;
 pub mod resource_interceptor;
@@ -45,8 +49,18 @@
     DynamicQuotaManager, QuotaNode, QuotaNodeType, QuotaPolicy, QuotaRequest, QuotaResponse,
     QuotaStatus, QuotaStrategy, QuotaWatcher, ResourceType as QuotaResourceType,
 };
+// Export canonical handle tables
+#[cfg(feature = "std")]
+pub use handle_table::{
+    CallScope, CallSiteId, HandleKind, HandleTable, LeakedResource, ResourceDropHandler,
+    ResourceTypeId,
+};
 #[cfg(feature = "std")]
 pub use memory_access::MemoryAccessMode;
+#[cfg(feature = "std")]
+pub use rep_access::{
+    RepAccessCapability, RepAccessOp, RepAccessScope, RepAuditRecord, RepAuditSink,
+};
 // Common re-exports for both std and no_std
 // pub use memory_strategy::MemoryStrategy as MemoryStrategyTrait;
 // ResourceArena handles std/no_std internally
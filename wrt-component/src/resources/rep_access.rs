@@ -0,0 +1,302 @@
+//! Capability-gated host access to resource representations
+//!
+//! Guests only ever see handles, and the representation behind an own is
+//! private to the instance implementing its type. Legacy handle-based host
+//! subsystems sometimes need to cross that line: read the representation
+//! behind an own a guest passed them, or hand a guest an own for a
+//! representation they already manage.
+//! [`HandleTable::host_resource_rep`](super::HandleTable::host_resource_rep)
+//! and
+//! [`HandleTable::host_resource_mint`](super::HandleTable::host_resource_mint)
+//! allow this for host code holding a [`RepAccessCapability`], which only
+//! the embedder can grant through
+//! [`HandleTable::grant_rep_access`](super::HandleTable::grant_rep_access).
+//!
+//! A capability is limited to the resource types and operations of its
+//! [`RepAccessScope`] and can be revoked. Every use of a capability, allowed
+//! or denied, is recorded as a [`RepAuditRecord`] in the table's audit log
+//! and passed to the audit sink, if one is set.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{Error, ErrorCategory, Result, codes};
+
+use super::handle_table::ResourceTypeId;
+use crate::components::InstanceId;
+
+/// Number of audit records kept in the table; older ones are dropped
+pub const MAX_AUDIT_RECORDS: usize = 256;
+
+/// Source of capability identifiers, unique across all handle tables
+static NEXT_CAPABILITY: AtomicU64 = AtomicU64::new(1);
+
+/// Operation performed through a [`RepAccessCapability`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepAccessOp {
+    /// Read the representation behind an own
+    Rep,
+    /// Create an own for a representation
+    Mint,
+}
+
+/// Resource types and operations a capability grants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepAccessScope {
+    types: Vec<ResourceTypeId>,
+    rep:   bool,
+    mint:  bool,
+}
+
+impl RepAccessScope {
+    /// Scope reading representations of owns of `types`
+    pub fn read(types: &[ResourceTypeId]) -> Self {
+        Self {
+            types: types.to_vec(),
+            rep:   true,
+            mint:  false,
+        }
+    }
+
+    /// Also allow minting owns of the scope's types
+    #[must_use]
+    pub fn with_mint(mut self) -> Self {
+        self.mint = true;
+        self
+    }
+
+    /// Whether the scope allows `operation` on resources of `type_id`
+    pub fn allows(&self, operation: RepAccessOp, type_id: ResourceTypeId) -> bool {
+        let operation_allowed = match operation {
+            RepAccessOp::Rep => self.rep,
+            RepAccessOp::Mint => self.mint,
+        };
+        operation_allowed && self.types.contains(&type_id)
+    }
+}
+
+/// Right of trusted host code to read and mint resource representations
+///
+/// Capabilities are only created by
+/// [`HandleTable::grant_rep_access`](super::HandleTable::grant_rep_access)
+/// and cannot be cloned, so the embedder controls which host code holds
+/// one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RepAccessCapability {
+    id: u64,
+}
+
+impl RepAccessCapability {
+    /// Identifier audit records refer to
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// Use of a [`RepAccessCapability`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepAuditRecord {
+    /// Capability used
+    pub capability: u64,
+    /// Holder the capability was granted to, empty if it is not granted by
+    /// this table or was revoked
+    pub holder:     String,
+    /// Operation attempted
+    pub operation:  RepAccessOp,
+    /// Instance whose table was accessed
+    pub instance:   InstanceId,
+    /// Resource type, if the handle was valid
+    pub type_id:    Option<ResourceTypeId>,
+    /// Handle read, or minted on success
+    pub handle:     Option<u32>,
+    /// Whether the operation was allowed
+    pub allowed:    bool,
+}
+
+/// Callback receiving every audit record as it is made
+pub type RepAuditSink = Box<dyn FnMut(&RepAuditRecord) + Send>;
+
+/// Grant of a capability
+#[derive(Debug)]
+struct Grant {
+    holder: String,
+    scope:  RepAccessScope,
+}
+
+/// Granted capabilities and audit log of a handle table
+#[derive(Default)]
+pub(crate) struct RepAccessControl {
+    grants: BTreeMap<u64, Grant>,
+    log:    VecDeque<RepAuditRecord>,
+    sink:   Option<RepAuditSink>,
+}
+
+impl core::fmt::Debug for RepAccessControl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RepAccessControl")
+            .field("grants", &self.grants)
+            .field("log_len", &self.log.len())
+            .field("has_sink", &self.sink.is_some())
+            .finish()
+    }
+}
+
+impl RepAccessControl {
+    pub(crate) fn grant(&mut self, holder: &str, scope: RepAccessScope) -> RepAccessCapability {
+        let id = NEXT_CAPABILITY.fetch_add(1, Ordering::Relaxed);
+        self.grants.insert(
+            id,
+            Grant {
+                holder: holder.into(),
+                scope,
+            },
+        );
+        RepAccessCapability { id }
+    }
+
+    pub(crate) fn revoke(&mut self, capability: RepAccessCapability) -> bool {
+        self.grants.remove(&capability.id).is_some()
+    }
+
+    pub(crate) fn set_sink(&mut self, sink: RepAuditSink) {
+        self.sink = Some(sink);
+    }
+
+    pub(crate) fn log(&self) -> impl Iterator<Item = &RepAuditRecord> {
+        self.log.iter()
+    }
+
+    /// Check `capability` for `operation` and record the attempt
+    ///
+    /// `target` is the outcome of validating the handle or type the
+    /// operation applies to; a failed target is recorded as denied and its
+    /// error returned.
+    pub(crate) fn authorize(
+        &mut self,
+        capability: &RepAccessCapability,
+        operation: RepAccessOp,
+        instance: InstanceId,
+        type_id: Option<ResourceTypeId>,
+        handle: Option<u32>,
+        target: Result<()>,
+    ) -> Result<()> {
+        let grant = self.grants.get(&capability.id);
+        let result = match (grant, target) {
+            (None, _) => Err(Error::new(
+                ErrorCategory::Security,
+                codes::ACCESS_DENIED,
+                "Resource representation capability not granted or revoked",
+            )),
+            (Some(_), Err(error)) => Err(error),
+            (Some(grant), Ok(())) => match type_id {
+                Some(type_id) if grant.scope.allows(operation, type_id) => Ok(()),
+                _ => Err(Error::new(
+                    ErrorCategory::Security,
+                    codes::ACCESS_DENIED,
+                    "Resource representation access outside the capability scope",
+                )),
+            },
+        };
+
+        self.record(RepAuditRecord {
+            capability: capability.id,
+            holder: grant.map(|grant| grant.holder.clone()).unwrap_or_default(),
+            operation,
+            instance,
+            type_id,
+            handle,
+            allowed: result.is_ok(),
+        });
+        result
+    }
+
+    /// Complete the audit record of an authorized mint with the handle it
+    /// created, or mark it denied if creating the resource failed
+    pub(crate) fn complete_mint(&mut self, minted: Option<u32>) {
+        if let Some(record) = self.log.back_mut() {
+            record.handle = minted;
+            record.allowed = minted.is_some();
+            if let Some(sink) = self.sink.as_mut() {
+                sink(record);
+            }
+        }
+    }
+
+    fn record(&mut self, record: RepAuditRecord) {
+        // Successful mints reach the sink once their handle is known
+        let deferred = record.allowed && record.operation == RepAccessOp::Mint;
+        if let Some(sink) = self.sink.as_mut().filter(|_| !deferred) {
+            sink(&record);
+        }
+        if self.log.len() == MAX_AUDIT_RECORDS {
+            self.log.pop_front();
+        }
+        self.log.push_back(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::resources::HandleTable;
+
+    #[test]
+    fn test_rep_access_is_scoped_revocable_and_audited() -> Result<()> {
+        let (host, guest) = (0, 1);
+        let mut table = HandleTable::new();
+        let socket = table.define_type(host, None);
+        let timer = table.define_type(host, None);
+        let audited = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&audited);
+        table.set_rep_audit_sink(Box::new(move |record: &RepAuditRecord| {
+            sink.lock().unwrap().push(record.clone());
+        }));
+
+        let net = table.grant_rep_access("net", RepAccessScope::read(&[socket]).with_mint());
+        let own = table.host_resource_mint(&net, guest, socket, 42)?;
+        assert_eq!(table.host_resource_rep(&net, guest, own)?, 42);
+
+        // Types and operations outside the scope are denied
+        let other = table.resource_new(guest, timer, 7)?;
+        let error = table.host_resource_rep(&net, guest, other).unwrap_err();
+        assert_eq!(error.code, codes::ACCESS_DENIED);
+        let reader = table.grant_rep_access("reader", RepAccessScope::read(&[socket]));
+        assert!(table.host_resource_mint(&reader, guest, socket, 1).is_err());
+
+        // Borrows do not expose their representation
+        let scope = table.begin_call();
+        let borrow = table.lend(guest, own, host, scope)?;
+        assert!(table.host_resource_rep(&net, host, borrow).is_err());
+        table.resource_drop(host, borrow)?;
+        table.end_call(scope)?;
+
+        let net_id = net.id();
+        assert!(table.revoke_rep_access(net));
+        let revoked = RepAccessCapability { id: net_id };
+        assert!(table.host_resource_rep(&revoked, guest, own).is_err());
+
+        let log: Vec<_> = table.rep_audit_log().cloned().collect();
+        assert_eq!(log, *audited.lock().unwrap());
+        let outcomes: Vec<_> =
+            log.iter().map(|record| (record.operation, record.allowed)).collect();
+        assert_eq!(outcomes, [
+            (RepAccessOp::Mint, true),
+            (RepAccessOp::Rep, true),
+            (RepAccessOp::Rep, false),
+            (RepAccessOp::Mint, false),
+            (RepAccessOp::Rep, false),
+            (RepAccessOp::Rep, false),
+        ]);
+        assert_eq!(log[0].handle, Some(own));
+        assert_eq!(log[0].holder, "net");
+        assert_eq!(log[5].holder, "");
+        Ok(())
+    }
+}
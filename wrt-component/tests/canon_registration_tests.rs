@@ -0,0 +1,37 @@
+//! Registration of lifted and lowered functions during instantiation
+
+#![cfg(all(feature = "std", feature = "decoder", feature = "wrt-execution", feature = "wasi"))]
+
+use std::sync::Arc;
+
+use wrt_component::components::component_instantiation::ComponentInstance;
+use wrt_host::CallbackRegistry;
+
+fn instantiate(wat: &str) -> wrt_error::Result<ComponentInstance> {
+    let bytes = wat::parse_str(wat).unwrap();
+    let mut parsed = wrt_decoder::component::decode_component(&bytes).unwrap();
+    ComponentInstance::from_parsed(0, &mut parsed, Some(Arc::new(CallbackRegistry::new())))
+}
+
+#[test]
+fn test_lift_of_a_function_no_core_instance_exports_fails() {
+    // `get-stdout` re-lifts a lowered import, which is not an export of any
+    // core instance the engine instantiated
+    let result = instantiate(
+        r#"
+        (component
+          (import "wasi:cli/stdout@0.2.0" (instance $stdout
+            (export "get-stdout" (func (result u32)))))
+          (core module $libc (memory (export "memory") 1))
+          (core instance $libc (instantiate $libc))
+          (core module $m (func (export "_start")))
+          (core instance $i (instantiate $m))
+          (core func $get (canon lower (func $stdout "get-stdout")))
+          (func (export "run") (canon lift (core func $i "_start")))
+          (func (export "get-stdout") (canon lift (core func $get)))
+        )
+        "#,
+    );
+    let error = result.err().expect("instantiation should fail");
+    assert_eq!(error.message, "Lifted function is not exported by a core instance");
+}
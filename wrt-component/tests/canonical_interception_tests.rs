@@ -0,0 +1,213 @@
+//! Canonical lift/lower interception through real component calls
+//!
+//! The component below lowers two WASI imports and calls both from its
+//! `run` export, so every lowered call crosses the canonical ABI once in
+//! each direction.
+
+#![cfg(all(feature = "std", feature = "decoder", feature = "wrt-execution", feature = "wasi"))]
+
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use wrt_component::components::component_instantiation::ComponentInstance;
+use wrt_error::{
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::values::Value;
+use wrt_host::CallbackRegistry;
+use wrt_intercept::{
+    strategies::{
+        CallOutcome,
+        LoggingConfig,
+        LoggingStrategy,
+        RecordingStrategy,
+        ReplayStrategy,
+        Trace,
+        TraceEvent,
+    },
+    LinkInterceptor,
+};
+
+/// Component whose `run` export gets the stdout handle and checks how many
+/// bytes it accepts
+const COMPONENT: &str = r#"
+(component
+  (import "wasi:io/streams@0.2.0" (instance $streams
+    (export "output-stream" (type $os (sub resource)))
+    (export "[method]output-stream.check-write"
+      (func (param "self" (borrow $os)) (result u64)))))
+  (alias export $streams "output-stream" (type $os))
+  (import "wasi:cli/stdout@0.2.0" (instance $stdout
+    (export "output-stream" (type (eq $os)))
+    (export "get-stdout" (func (result u32)))))
+  (core module $libc (memory (export "memory") 1))
+  (core instance $libc (instantiate $libc))
+  (core module $m
+    (import "wasi:cli/stdout@0.2.0" "get-stdout" (func $get (result i32)))
+    (import "wasi:io/streams@0.2.0" "[method]output-stream.check-write"
+      (func $check (param i32) (result i64)))
+    (memory (export "memory") 1)
+    (func (export "_start") (drop (call $check (call $get)))))
+  (core func $get (canon lower (func $stdout "get-stdout")))
+  (core func $check (canon lower (func $streams "[method]output-stream.check-write")))
+  (core instance $stdout_imports (export "get-stdout" (func $get)))
+  (core instance $streams_imports (export "[method]output-stream.check-write" (func $check)))
+  (core instance $i (instantiate $m
+    (with "wasi:cli/stdout@0.2.0" (instance $stdout_imports))
+    (with "wasi:io/streams@0.2.0" (instance $streams_imports))))
+  (func (export "run") (canon lift (core func $i "_start")))
+)
+"#;
+
+/// Instantiate the component and call `run` with `interceptor` on the host
+/// registry
+fn try_run_component(interceptor: LinkInterceptor) -> Result<()> {
+    let bytes = wat::parse_str(COMPONENT).unwrap();
+    let mut parsed = wrt_decoder::component::decode_component(&bytes).unwrap();
+    let registry = Arc::new(CallbackRegistry::new().with_interceptor(Arc::new(interceptor)));
+    let mut instance =
+        ComponentInstance::from_parsed(0, &mut parsed, Some(registry.clone())).unwrap();
+    let results = instance.call_function("run", &[], Some(&registry))?;
+    assert!(results.is_empty());
+    Ok(())
+}
+
+fn run_component(interceptor: LinkInterceptor) {
+    try_run_component(interceptor).unwrap();
+}
+
+/// Run the component with `strategy` as the only strategy
+fn run_with(strategy: Arc<dyn wrt_intercept::LinkInterceptorStrategy>) -> Result<()> {
+    let mut interceptor = LinkInterceptor::new("guest");
+    interceptor.add_strategy(strategy);
+    try_run_component(interceptor)
+}
+
+#[test]
+fn test_logging_strategy_traces_lift_and_lower() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let lines = lines.clone();
+        Arc::new(move |line: &str| lines.lock().unwrap().push(line.to_string()))
+    };
+    let config = LoggingConfig {
+        log_canonical: true,
+        ..LoggingConfig::default()
+    };
+    let mut interceptor = LinkInterceptor::new("guest");
+    interceptor.add_strategy(Arc::new(LoggingStrategy::new(sink).with_config(config)));
+
+    run_component(interceptor);
+
+    let lines = lines.lock().unwrap();
+    let canonical: Vec<&str> = lines
+        .iter()
+        .map(String::as_str)
+        .filter(|line| line.starts_with("LIFT: ") || line.starts_with("LOWER: "))
+        .collect();
+    assert_eq!(canonical.len(), 4, "unexpected trace: {lines:#?}");
+
+    // get-stdout takes no parameters and returns the stream handle
+    let get = "guest->wasi:cli/stdout@0.2.0::get-stdout";
+    assert_eq!(canonical[0], format!("LIFT: {get} addr=0x00000000 bytes="));
+    let handle = canonical[1]
+        .strip_prefix(&format!("LOWER: {get} addr=0x00000000 bytes="))
+        .unwrap_or_else(|| panic!("unexpected lower: {}", canonical[1]));
+    assert_eq!(handle.len(), 8);
+
+    // check-write lifts that handle and lowers a u64
+    let check = "guest->wasi:io/streams@0.2.0::[method]output-stream.check-write";
+    assert_eq!(canonical[2], format!("LIFT: {check} addr=0x00000000 bytes={handle}"));
+    let available = canonical[3]
+        .strip_prefix(&format!("LOWER: {check} addr=0x00000000 bytes="))
+        .unwrap_or_else(|| panic!("unexpected lower: {}", canonical[3]));
+    assert_eq!(available.len(), 16);
+}
+
+#[test]
+fn test_canonical_hooks_are_skipped_without_log_canonical() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let lines = lines.clone();
+        Arc::new(move |line: &str| lines.lock().unwrap().push(line.to_string()))
+    };
+    let mut interceptor = LinkInterceptor::new("guest");
+    interceptor.add_strategy(Arc::new(LoggingStrategy::new(sink)));
+
+    run_component(interceptor);
+
+    let lines = lines.lock().unwrap();
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|line| !line.starts_with("LIFT: ") && !line.starts_with("LOWER: ")));
+}
+
+#[test]
+fn test_record_and_replay_component_call() {
+    let recorder = Arc::new(RecordingStrategy::new());
+    run_with(recorder.clone()).unwrap();
+    let trace = recorder.take_trace().unwrap();
+
+    // Both imports are recorded as top-level calls, each with one lift and
+    // one lower nested inside it
+    let calls: Vec<(u64, &str)> = trace
+        .events()
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Call {
+                seq,
+                parent: None,
+                function,
+                ..
+            } => Some((*seq, function.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        calls.iter().map(|(_, function)| *function).collect::<Vec<_>>(),
+        ["get-stdout", "[method]output-stream.check-write"]
+    );
+    for (seq, _) in &calls {
+        let nested = |lift: bool| {
+            trace.events().iter().filter(move |event| {
+                matches!(
+                    (lift, event),
+                    (true, TraceEvent::Lift { parent: Some(p), .. })
+                        | (false, TraceEvent::Lower { parent: Some(p), .. }) if p == seq
+                )
+            })
+        };
+        assert_eq!(nested(true).count(), 1);
+        assert_eq!(nested(false).count(), 1);
+    }
+    let handle = trace.events().iter().find_map(|event| match event {
+        TraceEvent::Return {
+            seq,
+            outcome: CallOutcome::Ok(values),
+        } if *seq == calls[0].0 => Some(values.clone()),
+        _ => None,
+    });
+    assert!(matches!(handle.as_deref(), Some([Value::I32(_)])));
+
+    // The text form replays without the WASI dispatcher being called
+    let replay = Arc::new(ReplayStrategy::from_text(&trace.to_text().unwrap()).unwrap());
+    run_with(replay.clone()).unwrap();
+    assert!(replay.is_complete().unwrap());
+
+    // A replayed result reaches the guest: a different stdout handle makes
+    // the next call diverge from the trace
+    let mut tampered = Trace::new();
+    for event in trace.events() {
+        tampered.push(match event {
+            TraceEvent::Return { seq, .. } if *seq == calls[0].0 => TraceEvent::Return {
+                seq:     *seq,
+                outcome: CallOutcome::Ok(vec![Value::I32(0x7fff)]),
+            },
+            event => event.clone(),
+        });
+    }
+    let error = run_with(Arc::new(ReplayStrategy::new(&tampered).unwrap())).unwrap_err();
+    assert_eq!(error.category, ErrorCategory::Verification);
+}
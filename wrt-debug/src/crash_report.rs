@@ -0,0 +1,563 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Offline crash dump reader
+//!
+//! Reads the crash dumps a runtime writes when a guest traps or the host
+//! panics and maps their guest frames back to source lines:
+//!
+//! - [`CrashReport`] decodes a dump together with its `wrt-panic` panic
+//!   information block;
+//! - [`CodeMap`] walks the code section of the module the dump was taken
+//!   from and gives the byte offset of each instruction, which is what
+//!   DWARF line tables are keyed by;
+//! - [`DwarfLines`] combines both with the module's `.debug_line` section
+//!   into a [`LineTable`], so [`CrashReport::symbolicate`] can resolve
+//!   frames without the runtime that crashed.
+//!
+//! ```no_run
+//! use wrt_debug::crash_report::{CrashReport, DwarfLines};
+//!
+//! # fn main() -> wrt_error::Result<()> {
+//! let module = std::fs::read("app.wasm").unwrap();
+//! let dump = std::fs::read("app.crash").unwrap();
+//! let report = CrashReport::read(&dump)?;
+//! let lines = DwarfLines::new(&module)?;
+//! for frame in report.symbolicate(&lines) {
+//!     println!("{frame}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#![cfg(feature = "crash-dump")]
+
+use std::{cell::RefCell, fmt, string::String, vec::Vec};
+
+use wrt_error::{Error, Result};
+pub use wrt_format::crash_dump::{CrashCause, CrashDump, DumpFrame, DumpValue, MemoryRegion};
+use wrt_format::binary::read_leb128_u32;
+pub use wrt_panic::WrtPanicInfo;
+
+use crate::{DwarfDebugInfo, LineInfo, LineTable};
+
+/// Byte offsets of the instructions of a module's functions
+#[derive(Debug, Clone, Default)]
+pub struct CodeMap {
+    /// Number of imported functions, which precede the defined ones
+    imported:       u32,
+    /// Instruction offsets relative to the code section contents, per
+    /// defined function
+    functions:      Vec<Vec<u32>>,
+    /// Name, module offset and size of each `.debug_*` custom section
+    debug_sections: Vec<(String, u32, u32)>,
+}
+
+impl CodeMap {
+    /// Map the functions of the core module `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `module` is not a core module or holds an
+    /// instruction the map does not know
+    pub fn from_module(module: &[u8]) -> Result<Self> {
+        if module.len() < 8 || &module[..4] != b"\0asm" {
+            return Err(Error::parse_error("Not a WebAssembly module"));
+        }
+        let mut map = Self::default();
+        let mut pos = 8;
+        while pos < module.len() {
+            let id = module[pos];
+            let (size, read) = read_leb128_u32(module, pos + 1)?;
+            let start = pos + 1 + read;
+            let end = start
+                .checked_add(size as usize)
+                .filter(|&end| end <= module.len())
+                .ok_or_else(|| Error::parse_error("Section extends beyond module"))?;
+            let section = &module[..end];
+            match id {
+                0 => {
+                    let (name_len, read) = read_leb128_u32(section, start)?;
+                    let name_start = start + read;
+                    let data = name_start + name_len as usize;
+                    let name = section
+                        .get(name_start..data)
+                        .and_then(|name| core::str::from_utf8(name).ok())
+                        .ok_or_else(|| Error::parse_error("Invalid custom section name"))?;
+                    if name.starts_with(".debug_") {
+                        map.debug_sections.push((name.into(), data as u32, (end - data) as u32));
+                    }
+                },
+                2 => map.imported = count_function_imports(section, start)?,
+                10 => map.functions = instruction_offsets(section, start)?,
+                _ => {},
+            }
+            pos = end;
+        }
+        Ok(map)
+    }
+
+    /// Offset of instruction `pc` of function `func_idx` relative to the
+    /// code section contents, as DWARF addresses code
+    pub fn code_offset(&self, func_idx: u32, pc: u32) -> Option<u32> {
+        let body = self.functions.get(func_idx.checked_sub(self.imported)? as usize)?;
+        body.get(pc as usize).copied()
+    }
+
+    /// Number of instructions of function `func_idx`
+    pub fn instruction_count(&self, func_idx: u32) -> Option<usize> {
+        let body = self.functions.get(func_idx.checked_sub(self.imported)? as usize)?;
+        Some(body.len())
+    }
+
+    /// DWARF sections of the module as name, module offset and size
+    pub fn debug_sections(&self) -> &[(String, u32, u32)] {
+        &self.debug_sections
+    }
+}
+
+/// Source lines of a module's instructions from its DWARF line table
+pub struct DwarfLines<'a> {
+    info: RefCell<DwarfDebugInfo<'a>>,
+    code: CodeMap,
+}
+
+impl<'a> DwarfLines<'a> {
+    /// Read the code section and DWARF sections of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module cannot be mapped
+    pub fn new(module: &'a [u8]) -> Result<Self> {
+        let code = CodeMap::from_module(module)?;
+        let mut info = DwarfDebugInfo::new(module)?;
+        for (name, offset, size) in code.debug_sections() {
+            info.add_section(name, *offset, *size);
+        }
+        Ok(Self { info: RefCell::new(info), code })
+    }
+
+    /// Instruction offsets of the module
+    pub fn code_map(&self) -> &CodeMap {
+        &self.code
+    }
+
+    /// Whether the module carries DWARF line information
+    pub fn has_debug_info(&self) -> bool {
+        self.info.borrow().has_debug_info()
+    }
+}
+
+impl LineTable for DwarfLines<'_> {
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo> {
+        let offset = self.code.code_offset(function, pc)?;
+        self.info.borrow_mut().find_line_info(offset).ok().flatten()
+    }
+}
+
+/// Guest frame of a crash dump with its source line
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolicatedFrame {
+    /// Position on the stack, 0 for the frame that crashed
+    pub depth:    usize,
+    /// Function index
+    pub func_idx: u32,
+    /// Instruction index, if the dump has it
+    pub pc:       Option<u32>,
+    /// Source line of the instruction
+    pub line:     Option<LineInfo>,
+}
+
+impl fmt::Display for SymbolicatedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} func[{}]", self.depth, self.func_idx)?;
+        if let Some(pc) = self.pc {
+            write!(f, " pc {pc}")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, " at file {}:{}:{}", line.file_index, line.line, line.column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoded crash dump
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    /// The dump as written by the runtime
+    pub dump:  CrashDump,
+    /// Panic information block, if the dump holds a valid one
+    pub panic: Option<WrtPanicInfo>,
+}
+
+impl CrashReport {
+    /// Decode a crash dump
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a crash dump this version can read
+    pub fn read(bytes: &[u8]) -> Result<Self> {
+        let dump = CrashDump::from_bytes(bytes)?;
+        let panic = dump
+            .panic_info
+            .as_deref()
+            .and_then(WrtPanicInfo::from_bytes)
+            .filter(WrtPanicInfo::is_valid);
+        Ok(Self { dump, panic })
+    }
+
+    /// Guest frames with their source lines, innermost first
+    pub fn symbolicate<T: LineTable + ?Sized>(&self, lines: &T) -> Vec<SymbolicatedFrame> {
+        self.dump
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(depth, frame)| SymbolicatedFrame {
+                depth,
+                func_idx: frame.func_idx,
+                pc: frame.pc,
+                line: frame.pc.and_then(|pc| lines.line_at(frame.func_idx, pc)),
+            })
+            .collect()
+    }
+
+    /// Write a readable report with frames resolved through `lines`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails
+    pub fn write_report<T: LineTable + ?Sized>(
+        &self,
+        lines: &T,
+        out: &mut impl fmt::Write,
+    ) -> fmt::Result {
+        writeln!(out, "crash: {}", self.dump.cause)?;
+        writeln!(out, "module: {:08x}", self.dump.module_fingerprint)?;
+        if let Some(panic) = &self.panic {
+            let (error_code, location_hash) = (panic.error_code, panic.location_hash);
+            writeln!(
+                out,
+                "panic: error {error_code:08x} at location {location_hash:08x}, ASIL level {}",
+                panic.asil_level
+            )?;
+            if let Some(payload) = panic.payload() {
+                match payload.error_category() {
+                    Some(category) => write!(out, "error: {category:?}")?,
+                    None => write!(out, "error: category {}", payload.category)?,
+                }
+                writeln!(out, " code {} detail {}", payload.code, payload.detail)?;
+            }
+        }
+        for (frame, symbolicated) in self.dump.frames.iter().zip(self.symbolicate(lines)) {
+            writeln!(out, "{symbolicated}")?;
+            for (index, value) in frame.locals.iter().enumerate() {
+                writeln!(out, "    local {index} = {value}")?;
+            }
+        }
+        for memory in &self.dump.memories {
+            writeln!(
+                out,
+                "memory {}: {} pages, {} bytes in {} used ranges, checksum {:08x}",
+                memory.index,
+                memory.pages,
+                memory.used_bytes(),
+                memory.used.len(),
+                memory.checksum
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of function imports in the import section at `pos`
+fn count_function_imports(section: &[u8], pos: usize) -> Result<u32> {
+    let mut reader = Reader { bytes: section, pos };
+    let mut functions = 0;
+    for _ in 0..reader.u32()? {
+        for _ in 0..2 {
+            let len = reader.u32()? as usize;
+            reader.skip(len)?;
+        }
+        match reader.byte()? {
+            0 => {
+                reader.leb()?;
+                functions += 1;
+            },
+            1 => {
+                reader.value_type()?;
+                reader.limits()?;
+            },
+            2 => reader.limits()?,
+            3 => {
+                reader.value_type()?;
+                reader.byte()?;
+            },
+            4 => {
+                reader.byte()?;
+                reader.leb()?;
+            },
+            _ => return Err(Error::parse_error("Invalid import kind")),
+        }
+    }
+    Ok(functions)
+}
+
+/// Instruction offsets of each body of the code section at `start`
+fn instruction_offsets(section: &[u8], start: usize) -> Result<Vec<Vec<u32>>> {
+    let mut reader = Reader { bytes: section, pos: start };
+    let count = reader.u32()?;
+    let mut functions = Vec::new();
+    for _ in 0..count {
+        let size = reader.u32()? as usize;
+        let end = reader.pos + size;
+        if end > section.len() {
+            return Err(Error::parse_error("Function body extends beyond code section"));
+        }
+        for _ in 0..reader.u32()? {
+            reader.leb()?;
+            reader.value_type()?;
+        }
+        let mut offsets = Vec::new();
+        while reader.pos < end {
+            offsets.push((reader.pos - start) as u32);
+            reader.instruction()?;
+        }
+        functions.push(offsets);
+    }
+    Ok(functions)
+}
+
+/// Cursor skipping over encoded module items
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos:   usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| Error::parse_error("Unexpected end of section"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<()> {
+        if self.pos + count > self.bytes.len() {
+            return Err(Error::parse_error("Unexpected end of section"));
+        }
+        self.pos += count;
+        Ok(())
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let (value, read) = read_leb128_u32(self.bytes, self.pos)?;
+        self.pos += read;
+        Ok(value)
+    }
+
+    /// Skip a LEB128 number of any width and signedness
+    fn leb(&mut self) -> Result<()> {
+        while self.byte()? & 0x80 != 0 {}
+        Ok(())
+    }
+
+    /// Skip a value type, a reference type or a block type
+    fn value_type(&mut self) -> Result<()> {
+        // Single-byte types and type indices are LEB128 encoded alike
+        let first = self.bytes.get(self.pos).copied();
+        self.leb()?;
+        if matches!(first, Some(0x63 | 0x64)) {
+            self.leb()?;
+        }
+        Ok(())
+    }
+
+    fn limits(&mut self) -> Result<()> {
+        let flags = self.byte()?;
+        self.leb()?;
+        if flags & 0x01 != 0 {
+            self.leb()?;
+        }
+        if flags & 0x08 != 0 {
+            self.leb()?;
+        }
+        Ok(())
+    }
+
+    fn memarg(&mut self) -> Result<()> {
+        let align = self.u32()?;
+        if align & 0x40 != 0 {
+            self.leb()?;
+        }
+        self.leb()
+    }
+
+    /// Skip one instruction with its immediates
+    fn instruction(&mut self) -> Result<()> {
+        match self.byte()? {
+            0x00 | 0x01 | 0x05 | 0x0A | 0x0B | 0x0F | 0x19 | 0x1A | 0x1B | 0x45..=0xC4 | 0xD1
+            | 0xD3 | 0xD4 => {},
+            0x02 | 0x03 | 0x04 | 0x06 => self.value_type()?,
+            0x07 | 0x08 | 0x09 | 0x0C | 0x0D | 0x10 | 0x12 | 0x14 | 0x15 | 0x18 | 0x20..=0x26
+            | 0x3F | 0x40 | 0x41 | 0x42 | 0xD0 | 0xD2 | 0xD5 | 0xD6 => self.leb()?,
+            0x11 | 0x13 => {
+                self.leb()?;
+                self.leb()?;
+            },
+            0x0E => {
+                for _ in 0..=self.u32()? {
+                    self.leb()?;
+                }
+            },
+            0x1C => {
+                for _ in 0..self.u32()? {
+                    self.value_type()?;
+                }
+            },
+            0x1F => {
+                self.value_type()?;
+                for _ in 0..self.u32()? {
+                    if self.byte()? < 2 {
+                        self.leb()?;
+                    }
+                    self.leb()?;
+                }
+            },
+            0x28..=0x3E => self.memarg()?,
+            0x43 => self.skip(4)?,
+            0x44 => self.skip(8)?,
+            0xFC => match self.u32()? {
+                0..=7 => {},
+                9 | 11 | 13 | 15..=17 => self.leb()?,
+                8 | 10 | 12 | 14 => {
+                    self.leb()?;
+                    self.leb()?;
+                },
+                _ => return Err(Error::parse_error("Unknown 0xFC instruction")),
+            },
+            0xFD => match self.u32()? {
+                0..=11 | 92 | 93 => self.memarg()?,
+                12 | 13 => self.skip(16)?,
+                21..=34 => self.skip(1)?,
+                84..=91 => {
+                    self.memarg()?;
+                    self.skip(1)?;
+                },
+                _ => {},
+            },
+            0xFE => match self.u32()? {
+                3 => self.skip(1)?,
+                _ => self.memarg()?,
+            },
+            _ => return Err(Error::parse_error("Unknown instruction")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineRange;
+
+    /// Module importing one function and defining two
+    fn module() -> Vec<u8> {
+        let mut module = b"\0asm\x01\0\0\0".to_vec();
+        // Import "env" "f" as function of type 0
+        module.extend_from_slice(&[2, 9, 1, 3, b'e', b'n', b'v', 1, b'f', 0, 0]);
+        // Custom section ".debug_line" with two bytes of data
+        module.extend_from_slice(&[0, 14, 11]);
+        module.extend_from_slice(b".debug_line");
+        module.extend_from_slice(&[0xAA, 0xBB]);
+        let bodies: [&[u8]; 2] = [
+            // no locals; i32.const 300; drop; end
+            &[0, 0x41, 0xAC, 0x02, 0x1A, 0x0B],
+            // one i32 local; local.get 0; i32.load offset 4; br_table [0] 0; end
+            &[1, 1, 0x7F, 0x20, 0, 0x28, 2, 4, 0x0E, 1, 0, 0, 0x0B],
+        ];
+        let mut code = vec![2];
+        for body in bodies {
+            code.push(body.len() as u8);
+            code.extend_from_slice(body);
+        }
+        module.push(10);
+        module.push(code.len() as u8);
+        module.extend_from_slice(&code);
+        module
+    }
+
+    #[test]
+    fn test_code_map_offsets() -> Result<()> {
+        let map = CodeMap::from_module(&module())?;
+        // Bodies start after the count and size bytes
+        assert_eq!(map.code_offset(1, 0), Some(3));
+        assert_eq!(map.code_offset(1, 1), Some(6));
+        assert_eq!(map.code_offset(1, 2), Some(7));
+        assert_eq!(map.instruction_count(1), Some(3));
+        assert_eq!(map.code_offset(2, 0), Some(12));
+        assert_eq!(map.code_offset(2, 1), Some(14));
+        assert_eq!(map.code_offset(2, 2), Some(17));
+        assert_eq!(map.code_offset(2, 3), Some(21));
+        assert_eq!(map.code_offset(2, 4), None);
+        assert_eq!(map.code_offset(0, 0), None);
+        assert_eq!(map.debug_sections(), &[(".debug_line".into(), 33, 2)]);
+        assert!(CodeMap::from_module(b"\0asm\x01\0\0\0\x0a\x04\x01\x02\0\xFF").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_symbolicates_frames() -> Result<()> {
+        let payload = wrt_panic::PanicPayload::new(
+            wrt_error::ErrorCategory::Memory as u16,
+            wrt_error::codes::MEMORY_ACCESS_ERROR,
+        )
+        .with_detail(4096);
+        let panic = wrt_panic::record_panic_with_payload(
+            "guest buffer overflow",
+            "src/host.rs",
+            17,
+            Some(payload),
+        );
+        let dump = CrashDump {
+            frames: vec![
+                DumpFrame { func_idx: 2, pc: Some(1), ..DumpFrame::default() },
+                DumpFrame {
+                    func_idx:      1,
+                    pc:            Some(0),
+                    locals:        vec![DumpValue::I32(7)],
+                    operand_stack: Vec::new(),
+                },
+            ],
+            panic_info: Some(panic.to_bytes().to_vec()),
+            ..CrashDump::new(CrashCause::HostPanic { message: "overflow".into() }, 0xABCD)
+        };
+        let report = CrashReport::read(&dump.to_bytes("wrt-runtime", "0.2.0")?)?;
+        assert_eq!(report.panic, Some(panic));
+
+        let line = |line| LineInfo {
+            file_index: 1,
+            line,
+            column: 3,
+            is_stmt: true,
+            end_sequence: false,
+        };
+        let lines = [
+            LineRange { function: 2, start: 0, end: 2, line: line(40) },
+            LineRange { function: 1, start: 0, end: 1, line: line(12) },
+        ];
+        let frames = report.symbolicate(&lines[..]);
+        assert_eq!(frames[0].line.map(|line| line.line), Some(40));
+        assert_eq!(frames[1].to_string(), "#1 func[1] pc 0 at file 1:12:3");
+
+        let mut text = String::new();
+        report.write_report(&lines[..], &mut text).unwrap();
+        assert!(text.starts_with("crash: host panic: overflow\n"));
+        assert!(text.contains("#0 func[2] pc 1 at file 1:40:3\n"));
+        assert!(text.contains("    local 0 = i32:7\n"));
+        assert!(text.contains("error: Memory code 1007 detail 4096\n"));
+        Ok(())
+    }
+}
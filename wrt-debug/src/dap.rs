@@ -0,0 +1,761 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Debug Adapter Protocol server
+//!
+//! Lets an editor such as VS Code debug guest code running on a WRT engine.
+//! [`session`] creates two halves sharing one debug session:
+//!
+//! - [`DapDebugger`] is attached to the engine as its
+//!   [`RuntimeDebugger`](crate::runtime_traits::RuntimeDebugger). It checks
+//!   breakpoints, drives source-level steps through a
+//!   [`SourceStepper`](crate::SourceStepper) and, when execution stops,
+//!   blocks the engine thread until the client resumes it;
+//! - [`DapServer`] speaks the protocol with the client over stdio or TCP on
+//!   another thread: breakpoints, threads, stack traces, scopes, variables,
+//!   continue, step and pause requests.
+//!
+//! Source locations come from a [`SourceMap`], built from the module's
+//! line table, which maps instruction offsets of each function to source
+//! lines and back.
+//!
+//! ```no_run
+//! # fn attach(_: Box<dyn wrt_debug::runtime_traits::RuntimeDebugger>) {}
+//! use wrt_debug::dap::{session, SourceMap};
+//!
+//! let mut map = SourceMap::new();
+//! let file = map.add_file("src/lib.rs");
+//! map.add_line(0, 0..4, file, 12);
+//! let (server, debugger) = session(map);
+//! attach(Box::new(debugger));
+//! std::thread::spawn(move || server.serve_tcp("127.0.0.1:4711"));
+//! ```
+
+#![cfg(feature = "dap")]
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, ToSocketAddrs},
+    ops::Range,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    runtime_traits::{Breakpoint, BreakpointId, DebugAction, RuntimeDebugger, RuntimeState},
+    LineInfo, LineRange, LineTable, SourceStep, SourceStepper,
+};
+
+/// Thread reported to the client; an engine executes one thread
+const THREAD_ID: i64 = 1;
+
+/// Variables reference of the innermost frame's locals
+const LOCALS_REFERENCE: i64 = 1;
+
+/// Variables reference of the innermost frame's operand stack
+const STACK_REFERENCE: i64 = 2;
+
+/// Values read from locals or the operand stack of a stopped frame at most
+const MAX_VALUES: u32 = 1024;
+
+/// Source lines of a module's functions
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files:     Vec<String>,
+    ranges:    Vec<LineRange>,
+    functions: HashMap<u32, String>,
+}
+
+impl SourceMap {
+    /// Create an empty source map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source file, returning its index
+    pub fn add_file(&mut self, path: impl Into<String>) -> u16 {
+        self.files.push(path.into());
+        (self.files.len() - 1) as u16
+    }
+
+    /// Map instruction offsets of `function` to a line of `file`
+    pub fn add_line(&mut self, function: u32, offsets: Range<u32>, file: u16, line: u32) {
+        self.add_range(LineRange {
+            function,
+            start: offsets.start,
+            end: offsets.end,
+            line: LineInfo {
+                file_index: file,
+                line,
+                column: 0,
+                is_stmt: true,
+                end_sequence: false,
+            },
+        });
+    }
+
+    /// Add a line range as decoded from a line table
+    pub fn add_range(&mut self, range: LineRange) {
+        self.ranges.push(range);
+    }
+
+    /// Name `function` in stack traces
+    pub fn set_function_name(&mut self, function: u32, name: impl Into<String>) {
+        self.functions.insert(function, name.into());
+    }
+
+    /// Path of source file `index`
+    pub fn file(&self, index: u16) -> Option<&str> {
+        self.files.get(index as usize).map(String::as_str)
+    }
+
+    /// Code location of a source line: the first statement on `line` of
+    /// `path`, or on the next line with code
+    ///
+    /// Returns the function, instruction offset and line found.
+    pub fn location_of(&self, path: &str, line: u32) -> Option<(u32, u32, u32)> {
+        self.ranges
+            .iter()
+            .filter(|range| range.line.is_stmt && range.line.line >= line)
+            .filter(|range| self.file(range.line.file_index).is_some_and(|file| same_source(file, path)))
+            .min_by_key(|range| (range.line.line, range.function, range.start))
+            .map(|range| (range.function, range.start, range.line.line))
+    }
+
+    fn function_name(&self, function: u32) -> String {
+        self.functions.get(&function).cloned().unwrap_or_else(|| format!("func[{function}]"))
+    }
+}
+
+impl LineTable for SourceMap {
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo> {
+        self.ranges.line_at(function, pc)
+    }
+}
+
+/// Whether two paths name the same source file, one possibly relative to
+/// a directory of the other
+fn same_source(a: &str, b: &str) -> bool {
+    let a = a.replace('\\', "/");
+    let b = b.replace('\\', "/");
+    let (long, short) = if a.len() >= b.len() { (&a, &b) } else { (&b, &a) };
+    long == short
+        || (long.ends_with(short.as_str()) && long[..long.len() - short.len()].ends_with('/'))
+}
+
+/// Frame of the guest call stack
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    function: u32,
+    pc:       u32,
+}
+
+/// State captured when execution stopped
+#[derive(Debug)]
+struct Stopped {
+    frames: Vec<Frame>,
+    locals: Vec<u64>,
+    stack:  Vec<u64>,
+}
+
+/// How the client resumes execution
+#[derive(Debug, Clone, Copy)]
+enum Resume {
+    Continue,
+    Step(SourceStep),
+}
+
+/// Breakpoint set by the client on a source line
+#[derive(Debug)]
+struct SourceBreakpoint {
+    path:       String,
+    breakpoint: Breakpoint,
+}
+
+/// Session state shared by the server and the debugger
+#[derive(Default)]
+struct Shared {
+    connected:       bool,
+    output:          Option<Box<dyn Write + Send>>,
+    seq:             i64,
+    breakpoints:     Vec<SourceBreakpoint>,
+    next_breakpoint: u32,
+    pause:           bool,
+    stopped:         Option<Stopped>,
+    resume:          Option<Resume>,
+}
+
+impl Shared {
+    fn send(&mut self, mut message: Value) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let Some(output) = self.output.as_mut() else {
+            return;
+        };
+        let body = message.to_string();
+        // A client that went away is noticed by the server's reader
+        let _ = write!(output, "Content-Length: {}\r\n\r\n{body}", body.len());
+        let _ = output.flush();
+    }
+
+    fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+}
+
+struct Session {
+    map:     Arc<SourceMap>,
+    shared:  Mutex<Shared>,
+    resumed: Condvar,
+}
+
+impl Session {
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Create a debug session over the source lines of a module
+pub fn session(map: SourceMap) -> (DapServer, DapDebugger) {
+    let map = Arc::new(map);
+    let session = Arc::new(Session {
+        map: map.clone(),
+        shared: Mutex::new(Shared::default()),
+        resumed: Condvar::new(),
+    });
+    let debugger = DapDebugger {
+        session: session.clone(),
+        stepper: SourceStepper::new(map),
+        frames:  Vec::new(),
+    };
+    (DapServer { session }, debugger)
+}
+
+/// Protocol half of a debug session
+pub struct DapServer {
+    session: Arc<Session>,
+}
+
+impl DapServer {
+    /// Serve one client over stdin and stdout
+    pub fn serve_stdio(&self) -> io::Result<()> {
+        self.serve(io::stdin().lock(), io::stdout())
+    }
+
+    /// Serve the first client connecting to `addr`
+    pub fn serve_tcp(&self, addr: impl ToSocketAddrs) -> io::Result<()> {
+        let (stream, _) = TcpListener::bind(addr)?.accept()?;
+        self.serve(BufReader::new(stream.try_clone()?), stream)
+    }
+
+    /// Serve one client until it disconnects
+    pub fn serve(&self, mut reader: impl BufRead, writer: impl Write + Send + 'static) -> io::Result<()> {
+        {
+            let mut shared = self.session.lock();
+            shared.connected = true;
+            shared.output = Some(Box::new(writer));
+        }
+        let result = loop {
+            match read_message(&mut reader) {
+                Ok(Some(message)) => {
+                    if !self.handle(&message) {
+                        break Ok(());
+                    }
+                },
+                Ok(None) => break Ok(()),
+                Err(error) => break Err(error),
+            }
+        };
+        self.disconnect();
+        result
+    }
+
+    /// Handle one message, returning whether the session goes on
+    fn handle(&self, message: &Value) -> bool {
+        let command = message["command"].as_str().unwrap_or_default();
+        let arguments = &message["arguments"];
+        let result = match command {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsHitConditionalBreakpoints": false,
+                "supportsConditionalBreakpoints": false,
+            })),
+            "launch" | "attach" => {
+                if arguments["stopOnEntry"].as_bool() == Some(true) {
+                    self.session.lock().pause = true;
+                }
+                Ok(json!({}))
+            },
+            "setBreakpoints" => Ok(self.set_breakpoints(arguments)),
+            "configurationDone" | "disconnect" => Ok(json!({})),
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+            "stackTrace" => self.stack_trace(),
+            "scopes" => self.scopes(arguments),
+            "variables" => self.variables(arguments),
+            "continue" => self.resume(Resume::Continue).map(|()| json!({ "allThreadsContinued": true })),
+            "next" => self.resume(Resume::Step(SourceStep::Over)).map(|()| json!({})),
+            "stepIn" => self.resume(Resume::Step(SourceStep::Into)).map(|()| json!({})),
+            "stepOut" => self.resume(Resume::Step(SourceStep::Out)).map(|()| json!({})),
+            "pause" => {
+                self.session.lock().pause = true;
+                Ok(json!({}))
+            },
+            _ => Err(format!("Unsupported request '{command}'")),
+        };
+
+        let mut response = json!({
+            "type": "response",
+            "request_seq": message["seq"],
+            "command": command,
+            "success": result.is_ok(),
+        });
+        match result {
+            Ok(body) => response["body"] = body,
+            Err(error) => response["message"] = json!(error),
+        }
+        let mut shared = self.session.lock();
+        shared.send(response);
+        if command == "initialize" {
+            shared.event("initialized", json!({}));
+        }
+        command != "disconnect"
+    }
+
+    fn set_breakpoints(&self, arguments: &Value) -> Value {
+        let path = arguments["source"]["path"].as_str().unwrap_or_default();
+        let lines = arguments["breakpoints"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let map = &self.session.map;
+
+        let mut shared = self.session.lock();
+        shared.breakpoints.retain(|bp| bp.path != path);
+        let mut verified = Vec::with_capacity(lines.len());
+        for requested in lines {
+            let line = requested["line"].as_u64().unwrap_or_default() as u32;
+            shared.next_breakpoint += 1;
+            let id = shared.next_breakpoint;
+            match map.location_of(path, line) {
+                Some((function, pc, line)) => {
+                    let mut breakpoint = Breakpoint::new(BreakpointId(id), pc);
+                    breakpoint.func_idx = Some(function);
+                    shared.breakpoints.push(SourceBreakpoint {
+                        path: path.to_string(),
+                        breakpoint,
+                    });
+                    verified.push(json!({ "id": id, "verified": true, "line": line }));
+                },
+                None => verified.push(json!({
+                    "id": id,
+                    "verified": false,
+                    "message": "No code at this line",
+                })),
+            }
+        }
+        json!({ "breakpoints": verified })
+    }
+
+    fn stack_trace(&self) -> Result<Value, String> {
+        let shared = self.session.lock();
+        let stopped = shared.stopped.as_ref().ok_or("Execution is not stopped")?;
+        let map = &self.session.map;
+        let frames: Vec<Value> = stopped
+            .frames
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, frame)| {
+                let mut value = json!({
+                    "id": id,
+                    "name": map.function_name(frame.function),
+                    "line": 0,
+                    "column": 0,
+                    "instructionPointerReference": format!("{}:{}", frame.function, frame.pc),
+                });
+                if let Some(line) = map.line_at(frame.function, frame.pc) {
+                    value["line"] = json!(line.line);
+                    value["column"] = json!(line.column.max(1));
+                    if let Some(path) = map.file(line.file_index) {
+                        value["source"] = json!({ "path": path });
+                    }
+                }
+                value
+            })
+            .collect();
+        Ok(json!({ "stackFrames": frames, "totalFrames": frames.len() }))
+    }
+
+    fn scopes(&self, arguments: &Value) -> Result<Value, String> {
+        let shared = self.session.lock();
+        let stopped = shared.stopped.as_ref().ok_or("Execution is not stopped")?;
+        // Locals and operand stack are captured for the innermost frame
+        let innermost = stopped.frames.len().saturating_sub(1) as u64;
+        if arguments["frameId"].as_u64() != Some(innermost) {
+            return Ok(json!({ "scopes": [] }));
+        }
+        Ok(json!({ "scopes": [
+            { "name": "Locals", "variablesReference": LOCALS_REFERENCE, "expensive": false },
+            { "name": "Operand Stack", "variablesReference": STACK_REFERENCE, "expensive": false },
+        ] }))
+    }
+
+    fn variables(&self, arguments: &Value) -> Result<Value, String> {
+        let shared = self.session.lock();
+        let stopped = shared.stopped.as_ref().ok_or("Execution is not stopped")?;
+        let (prefix, values) = match arguments["variablesReference"].as_i64() {
+            Some(LOCALS_REFERENCE) => ("local", &stopped.locals),
+            Some(STACK_REFERENCE) => ("stack", &stopped.stack),
+            _ => return Err("Unknown variables reference".to_string()),
+        };
+        let variables: Vec<Value> = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                json!({
+                    "name": format!("{prefix}{index}"),
+                    "value": format!("{value} ({value:#x})"),
+                    "variablesReference": 0,
+                })
+            })
+            .collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn resume(&self, resume: Resume) -> Result<(), String> {
+        let mut shared = self.session.lock();
+        if shared.stopped.is_none() {
+            return Err("Execution is not stopped".to_string());
+        }
+        shared.resume = Some(resume);
+        self.session.resumed.notify_all();
+        Ok(())
+    }
+
+    /// Let the engine run freely once the client is gone
+    fn disconnect(&self) {
+        let mut shared = self.session.lock();
+        shared.connected = false;
+        shared.output = None;
+        shared.breakpoints.clear();
+        shared.pause = false;
+        shared.resume = Some(Resume::Continue);
+        self.session.resumed.notify_all();
+    }
+}
+
+/// Read one `Content-Length` framed message
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(io::Error::other)
+}
+
+/// Engine half of a debug session
+pub struct DapDebugger {
+    session: Arc<Session>,
+    stepper: SourceStepper<Arc<SourceMap>>,
+    frames:  Vec<Frame>,
+}
+
+impl DapDebugger {
+    /// Follow calls and returns seen as changes of the current function,
+    /// like [`SourceStepper`] does
+    fn track_frame(&mut self, pc: u32, state: &dyn RuntimeState) {
+        let Some(function) = state.current_function() else {
+            return;
+        };
+        match self.frames.last() {
+            Some(top) if top.function == function => {},
+            Some(_) if pc != 0 => {
+                self.frames.pop();
+                if self.frames.last().is_none_or(|top| top.function != function) {
+                    self.frames.push(Frame { function, pc });
+                }
+            },
+            _ => self.frames.push(Frame { function, pc }),
+        }
+        if let Some(top) = self.frames.last_mut() {
+            top.pc = pc;
+        }
+    }
+
+    /// Report a stop to the client and wait until it resumes execution
+    fn stop(&mut self, reason: &str, description: Option<String>, state: &dyn RuntimeState) {
+        let session = self.session.clone();
+        let mut shared = session.lock();
+        if !shared.connected {
+            return;
+        }
+        let locals = (0..MAX_VALUES).map_while(|index| state.read_local(index)).collect();
+        let stack = (0..MAX_VALUES).map_while(|offset| state.read_stack(offset)).collect();
+        shared.stopped = Some(Stopped {
+            frames: self.frames.clone(),
+            locals,
+            stack,
+        });
+        shared.resume = None;
+        shared.pause = false;
+        let mut body = json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true });
+        if let Some(description) = description {
+            body["description"] = json!(description);
+        }
+        shared.event("stopped", body);
+
+        while shared.resume.is_none() {
+            shared = session.resumed.wait(shared).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        let resume = shared.resume.take();
+        shared.stopped = None;
+        drop(shared);
+
+        match resume {
+            Some(Resume::Step(step)) => {
+                self.stepper.step(step, state);
+            },
+            _ => self.stepper.cancel(),
+        }
+    }
+}
+
+impl RuntimeDebugger for DapDebugger {
+    fn on_breakpoint(&mut self, _bp: &Breakpoint, state: &dyn RuntimeState) -> DebugAction {
+        self.track_frame(state.pc(), state);
+        self.stepper.breakpoint_hit(state);
+        self.stop("breakpoint", None, state);
+        DebugAction::Continue
+    }
+
+    fn on_instruction(&mut self, pc: u32, state: &dyn RuntimeState) -> DebugAction {
+        self.track_frame(pc, state);
+        let stepped = self.stepper.instruction(pc, state) == DebugAction::Break;
+
+        let function = state.current_function();
+        let reason = {
+            let mut shared = self.session.lock();
+            let hit = shared.breakpoints.iter_mut().find(|bp| {
+                let bp = &bp.breakpoint;
+                bp.enabled && bp.address == pc && (bp.func_idx.is_none() || bp.func_idx == function)
+            });
+            if let Some(hit) = hit {
+                hit.breakpoint.hit_count += 1;
+                Some("breakpoint")
+            } else if stepped {
+                Some("step")
+            } else if shared.pause {
+                Some("pause")
+            } else {
+                None
+            }
+        };
+
+        if let Some(reason) = reason {
+            if reason == "breakpoint" {
+                self.stepper.breakpoint_hit(state);
+            }
+            self.stop(reason, None, state);
+        }
+        // Stops block here; the engine never needs to unwind for them
+        DebugAction::Continue
+    }
+
+    fn on_function_entry(&mut self, func_idx: u32, _state: &dyn RuntimeState) {
+        self.stepper.function_entry(func_idx);
+        self.frames.push(Frame {
+            function: func_idx,
+            pc:       0,
+        });
+    }
+
+    fn on_function_exit(&mut self, _func_idx: u32, _state: &dyn RuntimeState) {
+        self.stepper.function_exit();
+        self.frames.pop();
+    }
+
+    fn on_trap(&mut self, trap_code: u32, state: &dyn RuntimeState) {
+        self.stop("exception", Some(format!("Trap {trap_code}")), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, thread, time::Duration};
+
+    use super::*;
+
+    struct State {
+        function: u32,
+        pc:       u32,
+    }
+
+    impl RuntimeState for State {
+        fn pc(&self) -> u32 {
+            self.pc
+        }
+
+        fn sp(&self) -> u32 {
+            1
+        }
+
+        fn fp(&self) -> Option<u32> {
+            None
+        }
+
+        fn read_local(&self, index: u32) -> Option<u64> {
+            (index < 2).then_some(u64::from(index) + 40)
+        }
+
+        fn read_stack(&self, offset: u32) -> Option<u64> {
+            (offset == 0).then_some(7)
+        }
+
+        fn current_function(&self) -> Option<u32> {
+            Some(self.function)
+        }
+    }
+
+    /// Output shared with the test
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Output {
+        fn messages(&self) -> Vec<Value> {
+            let bytes = self.0.lock().unwrap().clone();
+            let mut reader = Cursor::new(bytes);
+            std::iter::from_fn(|| read_message(&mut reader).unwrap()).collect()
+        }
+    }
+
+    fn frame(message: Value) -> Vec<u8> {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{body}", body.len()).into_bytes()
+    }
+
+    fn source_map() -> SourceMap {
+        let mut map = SourceMap::new();
+        let file = map.add_file("/work/src/main.rs");
+        map.add_line(0, 0..2, file, 3);
+        map.add_line(0, 2..4, file, 5);
+        map.add_line(1, 0..3, file, 10);
+        map.set_function_name(0, "main");
+        map
+    }
+
+    fn request(server: &DapServer, seq: i64, command: &str, arguments: Value) {
+        server.handle(&json!({ "seq": seq, "type": "request", "command": command, "arguments": arguments }));
+    }
+
+    #[test]
+    fn test_source_map_locations() {
+        let map = source_map();
+        assert_eq!(map.location_of("src/main.rs", 5), Some((0, 2, 5)));
+        assert_eq!(map.location_of("/work/src/main.rs", 4), Some((0, 2, 5)));
+        assert_eq!(map.location_of("rc/main.rs", 5), None);
+        assert_eq!(map.location_of("src/main.rs", 11), None);
+        assert!(same_source("C:\\work\\src\\main.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn test_messages_over_stream() {
+        let (server, _debugger) = session(source_map());
+        let mut input = frame(json!({ "seq": 1, "type": "request", "command": "initialize" }));
+        input.extend(frame(json!({ "seq": 2, "type": "request", "command": "evaluate" })));
+        input.extend(frame(json!({ "seq": 3, "type": "request", "command": "disconnect" })));
+        let output = Output::default();
+        server.serve(Cursor::new(input), output.clone()).unwrap();
+
+        let messages = output.messages();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0]["command"], "initialize");
+        assert_eq!(messages[0]["body"]["supportsConfigurationDoneRequest"], true);
+        assert_eq!(messages[1]["event"], "initialized");
+        assert_eq!(messages[2]["success"], false);
+        assert_eq!(messages[3]["request_seq"], 3);
+    }
+
+    #[test]
+    fn test_breakpoint_stop_inspect_and_step() {
+        let (server, mut debugger) = session(source_map());
+        let output = Output::default();
+        {
+            let mut shared = server.session.lock();
+            shared.connected = true;
+            shared.output = Some(Box::new(output.clone()));
+        }
+        request(&server, 1, "setBreakpoints", json!({
+            "source": { "path": "src/main.rs" },
+            "breakpoints": [{ "line": 4 }, { "line": 20 }],
+        }));
+        let response = output.messages().pop().unwrap();
+        assert_eq!(response["body"]["breakpoints"][0]["line"], 5);
+        assert_eq!(response["body"]["breakpoints"][1]["verified"], false);
+
+        // Function 0 runs line 3, then line 5 which calls function 1
+        let engine = thread::spawn(move || {
+            for (function, pc) in [(0, 0), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (0, 4)] {
+                debugger.on_instruction(pc, &State { function, pc });
+            }
+        });
+        let wait_stopped = |count: usize| {
+            while output.messages().iter().filter(|message| message["event"] == "stopped").count() < count {
+                thread::sleep(Duration::from_millis(1));
+            }
+        };
+
+        wait_stopped(1);
+        request(&server, 2, "stackTrace", json!({}));
+        let trace = output.messages().pop().unwrap();
+        assert_eq!(trace["body"]["stackFrames"][0]["name"], "main");
+        assert_eq!(trace["body"]["stackFrames"][0]["line"], 5);
+        assert_eq!(trace["body"]["stackFrames"][0]["source"]["path"], "/work/src/main.rs");
+        request(&server, 3, "variables", json!({ "variablesReference": LOCALS_REFERENCE }));
+        let variables = output.messages().pop().unwrap();
+        assert_eq!(variables["body"]["variables"][1]["value"], "41 (0x29)");
+
+        request(&server, 4, "stepIn", json!({}));
+        wait_stopped(2);
+        request(&server, 5, "stackTrace", json!({}));
+        let trace = output.messages().pop().unwrap();
+        assert_eq!(trace["body"]["totalFrames"], 2);
+        assert_eq!(trace["body"]["stackFrames"][0]["name"], "func[1]");
+        assert_eq!(trace["body"]["stackFrames"][0]["line"], 10);
+
+        request(&server, 6, "continue", json!({}));
+        engine.join().unwrap();
+        let stops: Vec<Value> = output
+            .messages()
+            .into_iter()
+            .filter(|message| message["event"] == "stopped")
+            .map(|message| message["body"]["reason"].clone())
+            .collect();
+        assert_eq!(stops, [json!("breakpoint"), json!("step")]);
+    }
+}
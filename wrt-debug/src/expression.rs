@@ -0,0 +1,687 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! DWARF expression and location list evaluation
+//!
+//! Variables are described by DWARF location expressions: small stack
+//! machine programs computing where the variable lives. [`evaluate`] runs
+//! such a program against an [`ExpressionContext`] providing the frame base,
+//! WebAssembly locals, globals and operand stack, and linear memory reads.
+//! The result is a [`Location`] the value can then be read from.
+//!
+//! Variables whose location changes over their lifetime use location lists
+//! instead of a single expression. [`find_in_debug_loc`] (DWARF 4) and
+//! [`find_in_debug_loclists`] (DWARF 5) pick the expression valid at a
+//! program counter.
+//!
+//! Addresses are 4 bytes, as in wasm32. Register operations, composite
+//! locations (`DW_OP_piece`) and entries indexing `.debug_addr` are not
+//! supported and fail with an unsupported feature error.
+
+use wrt_error::{Error, Result};
+
+use crate::cursor::DwarfCursor;
+
+/// Maximum number of entries on the expression stack
+pub const MAX_EXPRESSION_STACK: usize = 64;
+
+/// Maximum number of operations executed by one evaluation, bounding loops
+/// built from `DW_OP_bra` and `DW_OP_skip`
+pub const MAX_EXPRESSION_STEPS: usize = 1024;
+
+/// Address size of wasm32 in bytes
+const ADDRESS_SIZE: u8 = 4;
+
+/// DWARF expression operations
+mod op {
+    pub const ADDR: u8 = 0x03;
+    pub const DEREF: u8 = 0x06;
+    pub const CONST1U: u8 = 0x08;
+    pub const CONST1S: u8 = 0x09;
+    pub const CONST2U: u8 = 0x0a;
+    pub const CONST2S: u8 = 0x0b;
+    pub const CONST4U: u8 = 0x0c;
+    pub const CONST4S: u8 = 0x0d;
+    pub const CONST8U: u8 = 0x0e;
+    pub const CONST8S: u8 = 0x0f;
+    pub const CONSTU: u8 = 0x10;
+    pub const CONSTS: u8 = 0x11;
+    pub const DUP: u8 = 0x12;
+    pub const DROP: u8 = 0x13;
+    pub const OVER: u8 = 0x14;
+    pub const PICK: u8 = 0x15;
+    pub const SWAP: u8 = 0x16;
+    pub const ROT: u8 = 0x17;
+    pub const ABS: u8 = 0x19;
+    pub const AND: u8 = 0x1a;
+    pub const DIV: u8 = 0x1b;
+    pub const MINUS: u8 = 0x1c;
+    pub const MOD: u8 = 0x1d;
+    pub const MUL: u8 = 0x1e;
+    pub const NEG: u8 = 0x1f;
+    pub const NOT: u8 = 0x20;
+    pub const OR: u8 = 0x21;
+    pub const PLUS: u8 = 0x22;
+    pub const PLUS_UCONST: u8 = 0x23;
+    pub const SHL: u8 = 0x24;
+    pub const SHR: u8 = 0x25;
+    pub const SHRA: u8 = 0x26;
+    pub const XOR: u8 = 0x27;
+    pub const BRA: u8 = 0x28;
+    pub const EQ: u8 = 0x29;
+    pub const GE: u8 = 0x2a;
+    pub const GT: u8 = 0x2b;
+    pub const LE: u8 = 0x2c;
+    pub const LT: u8 = 0x2d;
+    pub const NE: u8 = 0x2e;
+    pub const SKIP: u8 = 0x2f;
+    pub const LIT0: u8 = 0x30;
+    pub const LIT31: u8 = 0x4f;
+    pub const FBREG: u8 = 0x91;
+    pub const DEREF_SIZE: u8 = 0x94;
+    pub const NOP: u8 = 0x96;
+    pub const IMPLICIT_VALUE: u8 = 0x9e;
+    pub const STACK_VALUE: u8 = 0x9f;
+    pub const WASM_LOCATION: u8 = 0xed;
+}
+
+/// Access to the state of the suspended frame an expression is evaluated in
+pub trait ExpressionContext {
+    /// Value of the frame base (`DW_AT_frame_base`) of the current function
+    fn frame_base(&self) -> Option<u64>;
+
+    /// Value of a WebAssembly local of the current frame
+    fn local(&self, index: u32) -> Option<u64>;
+
+    /// Value of a WebAssembly global
+    fn global(&self, index: u32) -> Option<u64>;
+
+    /// Value on the operand stack, 0 being the top
+    fn operand(&self, depth: u32) -> Option<u64>;
+
+    /// Little-endian value of `size` bytes (at most 8) of linear memory
+    fn read_memory(&self, address: u32, size: u8) -> Option<u64>;
+}
+
+/// Where the value of a variable is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location<'a> {
+    /// In linear memory at an address
+    Memory(u32),
+    /// In a WebAssembly local
+    Local(u32),
+    /// In a WebAssembly global
+    Global(u32),
+    /// On the operand stack, 0 being the top
+    Operand(u32),
+    /// Nowhere; the value is computed by the expression
+    Value(u64),
+    /// Nowhere; the value is given by the expression
+    Implicit(&'a [u8]),
+    /// The variable was optimized out
+    OptimizedOut,
+}
+
+impl Location<'_> {
+    /// Read `size` bytes (at most 8) of the variable's value
+    ///
+    /// Returns `None` if the value is not available in the current frame.
+    pub fn read(&self, context: &dyn ExpressionContext, size: u8) -> Option<u64> {
+        let value = match *self {
+            Self::Memory(address) => return context.read_memory(address, size.min(8)),
+            Self::Local(index) => context.local(index)?,
+            Self::Global(index) => context.global(index)?,
+            Self::Operand(depth) => context.operand(depth)?,
+            Self::Value(value) => value,
+            Self::Implicit(bytes) => {
+                let mut value = [0u8; 8];
+                let len = bytes.len().min(8);
+                value[..len].copy_from_slice(&bytes[..len]);
+                u64::from_le_bytes(value)
+            },
+            Self::OptimizedOut => return None,
+        };
+        Some(truncate(value, size))
+    }
+}
+
+/// Keep the low `size` bytes of `value`
+fn truncate(value: u64, size: u8) -> u64 {
+    match size {
+        0 => 0,
+        1..=7 => value & ((1u64 << (u32::from(size) * 8)) - 1),
+        _ => value,
+    }
+}
+
+/// Sign extend the low `size` bytes of `value`
+fn sign_extend(value: u64, size: u8) -> u64 {
+    let shift = 64 - u32::from(size.clamp(1, 8)) * 8;
+    (((value << shift) as i64) >> shift) as u64
+}
+
+/// Fixed-capacity expression stack
+struct Stack {
+    entries: [u64; MAX_EXPRESSION_STACK],
+    len: usize,
+}
+
+impl Stack {
+    fn push(&mut self, value: u64) -> Result<()> {
+        let slot = self
+            .entries
+            .get_mut(self.len)
+            .ok_or(Error::runtime_stack_overflow("DWARF expression stack overflow"))?;
+        *slot = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<u64> {
+        self.len = self
+            .len
+            .checked_sub(1)
+            .ok_or(Error::runtime_stack_underflow("DWARF expression stack underflow"))?;
+        Ok(self.entries[self.len])
+    }
+
+    /// Entry `depth` below the top
+    fn peek(&self, depth: usize) -> Result<u64> {
+        self.len
+            .checked_sub(depth + 1)
+            .map(|index| self.entries[index])
+            .ok_or(Error::runtime_stack_underflow("DWARF expression stack underflow"))
+    }
+}
+
+/// Evaluate a DWARF location expression
+///
+/// An empty expression means the variable was optimized out. An expression
+/// consisting of a single `DW_OP_WASM_location` names the local, global or
+/// operand stack slot holding the value; otherwise the top of the stack is
+/// a memory address, or the value itself after `DW_OP_stack_value`.
+///
+/// # Errors
+///
+/// Returns an error if the expression is malformed, uses an unsupported
+/// operation, or reads state the context does not provide.
+pub fn evaluate<'a>(expression: &'a [u8], context: &dyn ExpressionContext) -> Result<Location<'a>> {
+    if expression.is_empty() {
+        return Ok(Location::OptimizedOut);
+    }
+
+    let mut cursor = DwarfCursor::new(expression);
+    let mut stack = Stack { entries: [0; MAX_EXPRESSION_STACK], len: 0 };
+    // WebAssembly location named by the last operation, if it was a
+    // DW_OP_WASM_location
+    let mut wasm_location = None;
+    let mut steps = 0;
+
+    while !cursor.is_at_end() {
+        steps += 1;
+        if steps > MAX_EXPRESSION_STEPS {
+            return Err(Error::runtime_execution_error("DWARF expression does not terminate"));
+        }
+        wasm_location = None;
+
+        let opcode = cursor.read_u8()?;
+        match opcode {
+            op::ADDR => stack.push(u64::from(cursor.read_u32()?))?,
+            op::DEREF => {
+                let address = address(stack.pop()?)?;
+                stack.push(read_memory(context, address, ADDRESS_SIZE)?)?;
+            },
+            op::DEREF_SIZE => {
+                let size = cursor.read_u8()?;
+                if size == 0 || size > 8 {
+                    return Err(Error::parse_error("Invalid DW_OP_deref_size size"));
+                }
+                let address = address(stack.pop()?)?;
+                stack.push(read_memory(context, address, size)?)?;
+            },
+            op::CONST1U => stack.push(u64::from(cursor.read_u8()?))?,
+            op::CONST1S => stack.push(sign_extend(u64::from(cursor.read_u8()?), 1))?,
+            op::CONST2U => stack.push(u64::from(cursor.read_u16()?))?,
+            op::CONST2S => stack.push(sign_extend(u64::from(cursor.read_u16()?), 2))?,
+            op::CONST4U => stack.push(u64::from(cursor.read_u32()?))?,
+            op::CONST4S => stack.push(sign_extend(u64::from(cursor.read_u32()?), 4))?,
+            op::CONST8U | op::CONST8S => stack.push(cursor.read_u64()?)?,
+            op::CONSTU => stack.push(cursor.read_uleb128()?)?,
+            op::CONSTS => stack.push(cursor.read_sleb128()? as u64)?,
+            op::LIT0..=op::LIT31 => stack.push(u64::from(opcode - op::LIT0))?,
+            op::DUP => stack.push(stack.peek(0)?)?,
+            op::DROP => {
+                stack.pop()?;
+            },
+            op::OVER => stack.push(stack.peek(1)?)?,
+            op::PICK => {
+                let depth = cursor.read_u8()?;
+                stack.push(stack.peek(usize::from(depth))?)?;
+            },
+            op::SWAP => {
+                let (top, second) = (stack.pop()?, stack.pop()?);
+                stack.push(top)?;
+                stack.push(second)?;
+            },
+            op::ROT => {
+                let (top, second, third) = (stack.pop()?, stack.pop()?, stack.pop()?);
+                stack.push(top)?;
+                stack.push(third)?;
+                stack.push(second)?;
+            },
+            op::ABS => {
+                let value = stack.pop()? as i64;
+                stack.push(value.unsigned_abs())?;
+            },
+            op::NEG => {
+                let value = stack.pop()? as i64;
+                stack.push(value.wrapping_neg() as u64)?;
+            },
+            op::NOT => {
+                let value = stack.pop()?;
+                stack.push(!value)?;
+            },
+            op::PLUS_UCONST => {
+                let addend = cursor.read_uleb128()?;
+                let value = stack.pop()?;
+                stack.push(value.wrapping_add(addend))?;
+            },
+            op::AND
+            | op::DIV
+            | op::MINUS
+            | op::MOD
+            | op::MUL
+            | op::OR
+            | op::PLUS
+            | op::SHL
+            | op::SHR
+            | op::SHRA
+            | op::XOR
+            | op::EQ
+            | op::GE
+            | op::GT
+            | op::LE
+            | op::LT
+            | op::NE => {
+                let (rhs, lhs) = (stack.pop()?, stack.pop()?);
+                stack.push(binary(opcode, lhs, rhs)?)?;
+            },
+            op::SKIP => {
+                let offset = cursor.read_u16()? as i16;
+                branch(&mut cursor, expression, offset)?;
+            },
+            op::BRA => {
+                let offset = cursor.read_u16()? as i16;
+                if stack.pop()? != 0 {
+                    branch(&mut cursor, expression, offset)?;
+                }
+            },
+            op::FBREG => {
+                let offset = cursor.read_sleb128()?;
+                let base = context
+                    .frame_base()
+                    .ok_or(Error::runtime_invalid_state("Frame base not available"))?;
+                stack.push(base.wrapping_add(offset as u64))?;
+            },
+            op::NOP => {},
+            op::WASM_LOCATION => {
+                let location = match cursor.read_u8()? {
+                    0 => Location::Local(cursor.read_uleb128_u32()?),
+                    1 => Location::Global(cursor.read_uleb128_u32()?),
+                    2 => Location::Operand(cursor.read_uleb128_u32()?),
+                    3 => Location::Global(cursor.read_u32()?),
+                    _ => return Err(Error::parse_error("Invalid DW_OP_WASM_location kind")),
+                };
+                let value = location
+                    .read(context, 8)
+                    .ok_or(Error::runtime_invalid_state("WebAssembly location not available"))?;
+                stack.push(value)?;
+                wasm_location = Some(location);
+            },
+            op::IMPLICIT_VALUE => {
+                let len = usize::try_from(cursor.read_uleb128()?)
+                    .map_err(|_| Error::parse_error("DW_OP_implicit_value too long"))?;
+                let bytes = cursor.read_bytes(len)?;
+                return finish(&cursor, Location::Implicit(bytes));
+            },
+            op::STACK_VALUE => return finish(&cursor, Location::Value(stack.pop()?)),
+            _ => {
+                return Err(Error::validation_unsupported_feature(
+                    "Unsupported DWARF expression operation",
+                ));
+            },
+        }
+    }
+
+    match wasm_location {
+        Some(location) => Ok(location),
+        None => Ok(Location::Memory(address(stack.pop()?)?)),
+    }
+}
+
+/// Evaluate the frame base expression of a function (`DW_AT_frame_base`)
+///
+/// Compilers for WebAssembly name the local or global holding the stack
+/// pointer; its value is the frame base `DW_OP_fbreg` is relative to.
+///
+/// # Errors
+///
+/// Returns an error if the expression cannot be evaluated or the frame base
+/// is not available.
+pub fn evaluate_frame_base(expression: &[u8], context: &dyn ExpressionContext) -> Result<u64> {
+    let location = evaluate(expression, context)?;
+    match location {
+        Location::Memory(address) => Ok(u64::from(address)),
+        _ => location
+            .read(context, 8)
+            .ok_or(Error::runtime_invalid_state("Frame base not available")),
+    }
+}
+
+/// Operations ending a location description must be the last ones
+fn finish<'a>(cursor: &DwarfCursor<'a>, location: Location<'a>) -> Result<Location<'a>> {
+    if cursor.is_at_end() {
+        Ok(location)
+    } else {
+        Err(Error::validation_unsupported_feature("Composite DWARF locations not supported"))
+    }
+}
+
+fn address(value: u64) -> Result<u32> {
+    u32::try_from(value).map_err(|_| Error::runtime_out_of_bounds("DWARF address exceeds wasm32"))
+}
+
+fn read_memory(context: &dyn ExpressionContext, address: u32, size: u8) -> Result<u64> {
+    context
+        .read_memory(address, size)
+        .ok_or(Error::runtime_memory_access_error("DWARF expression reads unavailable memory"))
+}
+
+/// Move the cursor by `offset` bytes from its position
+fn branch<'a>(cursor: &mut DwarfCursor<'a>, expression: &'a [u8], offset: i16) -> Result<()> {
+    let target = cursor
+        .position()
+        .checked_add_signed(isize::from(offset))
+        .filter(|&target| target <= expression.len())
+        .ok_or(Error::parse_error("DWARF expression branch out of bounds"))?;
+    *cursor = DwarfCursor::new(expression);
+    cursor.skip(target)
+}
+
+fn binary(opcode: u8, lhs: u64, rhs: u64) -> Result<u64> {
+    let (signed_lhs, signed_rhs) = (lhs as i64, rhs as i64);
+    Ok(match opcode {
+        op::AND => lhs & rhs,
+        op::OR => lhs | rhs,
+        op::XOR => lhs ^ rhs,
+        op::PLUS => lhs.wrapping_add(rhs),
+        op::MINUS => lhs.wrapping_sub(rhs),
+        op::MUL => lhs.wrapping_mul(rhs),
+        op::DIV => {
+            if rhs == 0 {
+                return Err(Error::runtime_division_by_zero("DWARF expression divides by zero"));
+            }
+            signed_lhs.wrapping_div(signed_rhs) as u64
+        },
+        op::MOD => {
+            if rhs == 0 {
+                return Err(Error::runtime_division_by_zero("DWARF expression divides by zero"));
+            }
+            lhs % rhs
+        },
+        op::SHL => lhs.checked_shl(rhs.min(64) as u32).unwrap_or(0),
+        op::SHR => lhs.checked_shr(rhs.min(64) as u32).unwrap_or(0),
+        op::SHRA => (signed_lhs >> rhs.min(63)) as u64,
+        op::EQ => u64::from(signed_lhs == signed_rhs),
+        op::NE => u64::from(signed_lhs != signed_rhs),
+        op::GE => u64::from(signed_lhs >= signed_rhs),
+        op::GT => u64::from(signed_lhs > signed_rhs),
+        op::LE => u64::from(signed_lhs <= signed_rhs),
+        op::LT => u64::from(signed_lhs < signed_rhs),
+        _ => unreachable!("not a binary DWARF operation"),
+    })
+}
+
+/// Find the expression valid at `pc` in a DWARF 4 `.debug_loc` list
+///
+/// `offset` is the value of the variable's `DW_AT_location` and
+/// `base_address` the low PC of its compilation unit. Returns `None` if no
+/// entry covers `pc`.
+///
+/// # Errors
+///
+/// Returns an error if the list is malformed.
+pub fn find_in_debug_loc(
+    section: &[u8],
+    offset: usize,
+    pc: u32,
+    base_address: u32,
+) -> Result<Option<&[u8]>> {
+    let mut cursor = DwarfCursor::new(section);
+    cursor.skip(offset)?;
+    let mut base = base_address;
+    loop {
+        let (begin, end) = (cursor.read_u32()?, cursor.read_u32()?);
+        if begin == 0 && end == 0 {
+            return Ok(None);
+        }
+        if begin == u32::MAX {
+            base = end;
+            continue;
+        }
+        let len = cursor.read_u16()?;
+        let expression = cursor.read_bytes(usize::from(len))?;
+        if covers(base.wrapping_add(begin), base.wrapping_add(end), pc) {
+            return Ok(Some(expression));
+        }
+    }
+}
+
+/// Find the expression valid at `pc` in a DWARF 5 `.debug_loclists` list
+///
+/// `offset` is the offset of the list in the section and `base_address` the
+/// low PC of its compilation unit. A `DW_LLE_default_location` entry is used
+/// if no other entry covers `pc`.
+///
+/// # Errors
+///
+/// Returns an error if the list is malformed or uses entries indexing
+/// `.debug_addr`.
+pub fn find_in_debug_loclists(
+    section: &[u8],
+    offset: usize,
+    pc: u32,
+    base_address: u32,
+) -> Result<Option<&[u8]>> {
+    const END_OF_LIST: u8 = 0x00;
+    const OFFSET_PAIR: u8 = 0x04;
+    const DEFAULT_LOCATION: u8 = 0x05;
+    const BASE_ADDRESS: u8 = 0x06;
+    const START_END: u8 = 0x07;
+    const START_LENGTH: u8 = 0x08;
+
+    let mut cursor = DwarfCursor::new(section);
+    cursor.skip(offset)?;
+    let mut base = base_address;
+    let mut default = None;
+    loop {
+        let range = match cursor.read_u8()? {
+            END_OF_LIST => return Ok(default),
+            BASE_ADDRESS => {
+                base = cursor.read_u32()?;
+                continue;
+            },
+            OFFSET_PAIR => {
+                let begin = uleb_address(&mut cursor)?;
+                let end = uleb_address(&mut cursor)?;
+                Some((base.wrapping_add(begin), base.wrapping_add(end)))
+            },
+            START_END => Some((cursor.read_u32()?, cursor.read_u32()?)),
+            START_LENGTH => {
+                let start = cursor.read_u32()?;
+                Some((start, start.wrapping_add(uleb_address(&mut cursor)?)))
+            },
+            DEFAULT_LOCATION => None,
+            _ => {
+                return Err(Error::validation_unsupported_feature(
+                    "Unsupported DWARF location list entry",
+                ));
+            },
+        };
+        let len = usize::try_from(cursor.read_uleb128()?)
+            .map_err(|_| Error::parse_error("Location expression too long"))?;
+        let expression = cursor.read_bytes(len)?;
+        match range {
+            Some((begin, end)) if covers(begin, end, pc) => return Ok(Some(expression)),
+            Some(_) => {},
+            None => default = Some(expression),
+        }
+    }
+}
+
+fn uleb_address(cursor: &mut DwarfCursor<'_>) -> Result<u32> {
+    u32::try_from(cursor.read_uleb128()?)
+        .map_err(|_| Error::parse_error("Location list address exceeds wasm32"))
+}
+
+fn covers(begin: u32, end: u32, pc: u32) -> bool {
+    begin <= pc && pc < end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Frame {
+        locals: [u64; 4],
+        memory: [u8; 64],
+    }
+
+    impl ExpressionContext for Frame {
+        fn frame_base(&self) -> Option<u64> {
+            self.local(0)
+        }
+
+        fn local(&self, index: u32) -> Option<u64> {
+            self.locals.get(index as usize).copied()
+        }
+
+        fn global(&self, index: u32) -> Option<u64> {
+            (index == 0).then_some(48)
+        }
+
+        fn operand(&self, _depth: u32) -> Option<u64> {
+            None
+        }
+
+        fn read_memory(&self, address: u32, size: u8) -> Option<u64> {
+            let start = address as usize;
+            let bytes = self.memory.get(start..start + usize::from(size))?;
+            let mut value = [0u8; 8];
+            value[..bytes.len()].copy_from_slice(bytes);
+            Some(u64::from_le_bytes(value))
+        }
+    }
+
+    fn frame() -> Frame {
+        let mut memory = [0u8; 64];
+        memory[16..20].copy_from_slice(&0xdead_beef_u32.to_le_bytes());
+        memory[24..28].copy_from_slice(&16u32.to_le_bytes());
+        Frame { locals: [8, 42, 0, 0], memory }
+    }
+
+    #[test]
+    fn test_locations_of_wasm_variables() -> Result<()> {
+        let frame = frame();
+
+        // Value in local 1
+        let location = evaluate(&[op::WASM_LOCATION, 0, 1, op::STACK_VALUE], &frame)?;
+        assert_eq!(location, Location::Value(42));
+        assert_eq!(evaluate(&[op::WASM_LOCATION, 0, 1], &frame)?, Location::Local(1));
+
+        // Frame base is the stack pointer in local 0, the variable at +8
+        let base = evaluate_frame_base(&[op::WASM_LOCATION, 0, 0], &frame)?;
+        assert_eq!(base, 8);
+        let location = evaluate(&[op::FBREG, 8], &frame)?;
+        assert_eq!(location, Location::Memory(16));
+        assert_eq!(location.read(&frame, 4), Some(0xdead_beef));
+
+        // Pointer in memory at global 0 - 24, dereferenced
+        let expression = [op::WASM_LOCATION, 3, 0, 0, 0, 0, op::LIT0 + 24, op::MINUS, op::DEREF];
+        assert_eq!(evaluate(&expression, &frame)?, Location::Memory(16));
+
+        assert_eq!(evaluate(&[], &frame)?, Location::OptimizedOut);
+        let location = evaluate(&[op::IMPLICIT_VALUE, 2, 0x34, 0x12], &frame)?;
+        assert_eq!(location.read(&frame, 2), Some(0x1234));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic_and_branches() -> Result<()> {
+        let frame = frame();
+        // 7 - 10 < 0 ? 1 : 2, as a stack value
+        let expression = [
+            op::LIT0 + 7,
+            op::LIT0 + 10,
+            op::MINUS,
+            op::LIT0,
+            op::LT,
+            op::BRA,
+            4,
+            0,
+            op::LIT0 + 2,
+            op::SKIP,
+            1,
+            0,
+            op::LIT0 + 1,
+            op::STACK_VALUE,
+        ];
+        assert_eq!(evaluate(&expression, &frame)?, Location::Value(1));
+
+        let expression = [op::CONST1S, 0xfe, op::ABS, op::LIT0 + 3, op::MUL, op::STACK_VALUE];
+        assert_eq!(evaluate(&expression, &frame)?, Location::Value(6));
+
+        // Infinite loop, unsupported register operation, stack underflow
+        assert!(evaluate(&[op::SKIP, 0xfd, 0xff], &frame).is_err());
+        assert!(evaluate(&[0x50], &frame).is_err());
+        assert!(evaluate(&[op::PLUS], &frame).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_lists() -> Result<()> {
+        let first = [op::WASM_LOCATION, 0, 1, op::STACK_VALUE];
+        let second = [op::FBREG, 8];
+
+        // DWARF 4: [0x10, 0x20) and, after a base address of 0x100, [0x120, 0x130)
+        let mut loc = [0u8; 4].to_vec();
+        for value in [0x10u32, 0x20] {
+            loc.extend_from_slice(&value.to_le_bytes());
+        }
+        loc.extend_from_slice(&(first.len() as u16).to_le_bytes());
+        loc.extend_from_slice(&first);
+        for value in [u32::MAX, 0x100, 0x20, 0x30] {
+            loc.extend_from_slice(&value.to_le_bytes());
+        }
+        loc.extend_from_slice(&(second.len() as u16).to_le_bytes());
+        loc.extend_from_slice(&second);
+        loc.extend_from_slice(&[0; 8]);
+
+        assert_eq!(find_in_debug_loc(&loc, 4, 0x18, 0)?, Some(&first[..]));
+        assert_eq!(find_in_debug_loc(&loc, 4, 0x125, 0)?, Some(&second[..]));
+        assert_eq!(find_in_debug_loc(&loc, 4, 0x20, 0)?, None);
+
+        // DWARF 5: offset pair from the CU base, then a default location
+        let mut loclists = [0x04, 0x10, 0x20, first.len() as u8].to_vec();
+        loclists.extend_from_slice(&first);
+        loclists.extend_from_slice(&[0x05, second.len() as u8]);
+        loclists.extend_from_slice(&second);
+        loclists.push(0x00);
+
+        assert_eq!(find_in_debug_loclists(&loclists, 0, 0x1010, 0x1000)?, Some(&first[..]));
+        assert_eq!(find_in_debug_loclists(&loclists, 0, 0x2000, 0x1000)?, Some(&second[..]));
+        assert!(find_in_debug_loclists(&[0x02, 0, 0], 0, 0, 0).is_err());
+        Ok(())
+    }
+}
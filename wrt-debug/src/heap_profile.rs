@@ -0,0 +1,519 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Guest heap profiling
+//!
+//! [`HeapTracker`] follows the guest's allocator through the per-instruction
+//! callbacks of a runtime. Calls into the functions named by [`HeapHooks`]
+//! are recognised when execution enters them at offset 0:
+//!
+//! - `malloc(size)` and `realloc(ptr, size)` are completed when execution
+//!   returns to the instruction after the call, where the returned address
+//!   is on top of the operand stack;
+//! - `free(ptr)` releases the allocation on entry;
+//! - the canonical ABI `cabi_realloc(old_ptr, old_size, align, new_size)`
+//!   is treated as `realloc`.
+//!
+//! Live allocations are kept in a bounded table together with the call site
+//! that made them. Allocations beyond the bound are counted but not kept.
+//! At a configurable interval the tracker records a [`HeapSample`], which
+//! gives a time series of heap usage over executed instructions for leak
+//! hunting in long-running components.
+
+#![cfg(feature = "heap-profiling")]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    string::String,
+    vec::Vec,
+};
+
+use crate::runtime_traits::{Breakpoint, DebugAction, RuntimeDebugger, RuntimeState};
+
+/// Default number of live allocations kept
+pub const DEFAULT_MAX_LIVE_ALLOCATIONS: usize = 4096;
+
+/// Default number of samples kept
+pub const DEFAULT_MAX_SAMPLES: usize = 1024;
+
+/// Names of the guest allocator functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeapSymbols {
+    /// `malloc(size) -> ptr`
+    pub malloc:            String,
+    /// `free(ptr)`
+    pub free:              String,
+    /// `realloc(ptr, size) -> ptr`
+    pub realloc:           String,
+    /// `cabi_realloc(old_ptr, old_size, align, new_size) -> ptr`
+    pub canonical_realloc: String,
+}
+
+impl Default for HeapSymbols {
+    fn default() -> Self {
+        Self {
+            malloc:            "malloc".into(),
+            free:              "free".into(),
+            realloc:           "realloc".into(),
+            canonical_realloc: "cabi_realloc".into(),
+        }
+    }
+}
+
+/// Function indices of the guest allocator functions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapHooks {
+    /// Index of `malloc`
+    pub malloc:            Option<u32>,
+    /// Index of `free`
+    pub free:              Option<u32>,
+    /// Index of `realloc`
+    pub realloc:           Option<u32>,
+    /// Index of the canonical ABI realloc
+    pub canonical_realloc: Option<u32>,
+}
+
+impl HeapHooks {
+    /// Resolve `symbols` to function indices with `lookup`, e.g. from the
+    /// module's exports or name section
+    pub fn resolve(symbols: &HeapSymbols, lookup: impl Fn(&str) -> Option<u32>) -> Self {
+        Self {
+            malloc:            lookup(&symbols.malloc),
+            free:              lookup(&symbols.free),
+            realloc:           lookup(&symbols.realloc),
+            canonical_realloc: lookup(&symbols.canonical_realloc),
+        }
+    }
+
+    /// Whether no allocator function is hooked
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Instruction that called an allocator function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallSite {
+    /// Calling function
+    pub function: u32,
+    /// Offset of the call instruction
+    pub pc:       u32,
+}
+
+/// Allocation the guest has not freed yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveAllocation {
+    /// Guest address
+    pub address:      u32,
+    /// Size in bytes
+    pub size:         u32,
+    /// Call that made the allocation
+    pub call_site:    Option<CallSite>,
+    /// Instruction count when the allocation was made
+    pub allocated_at: u64,
+}
+
+/// Heap usage at one point of the execution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapSample {
+    /// Instructions executed
+    pub instruction:       u64,
+    /// Allocations made so far
+    pub total_allocations: u64,
+    /// Bytes allocated so far
+    pub total_bytes:       u64,
+    /// Allocations currently live
+    pub live_allocations:  u64,
+    /// Bytes currently live
+    pub live_bytes:        u64,
+    /// Largest live allocation
+    pub largest:           u32,
+    /// Allocations not kept because the table was full
+    pub untracked:         u64,
+}
+
+/// Live allocations made from one call site
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallSiteUsage {
+    /// Call site, `None` for allocations whose caller was not seen
+    pub call_site:   Option<CallSite>,
+    /// Live allocations
+    pub allocations: u32,
+    /// Live bytes
+    pub bytes:       u64,
+}
+
+/// Allocator call waiting for its result
+#[derive(Debug, Clone, Copy)]
+struct PendingCall {
+    /// Address being reallocated, 0 for fresh allocations
+    old:       u32,
+    size:      u32,
+    call_site: Option<CallSite>,
+}
+
+/// Tracks the allocations of a guest heap
+#[derive(Debug)]
+pub struct HeapTracker {
+    hooks:             HeapHooks,
+    max_live:          usize,
+    sample_interval:   u64,
+    max_samples:       usize,
+    live:              HashMap<u32, LiveAllocation>,
+    pending:           Vec<PendingCall>,
+    last:              Option<CallSite>,
+    clock:             u64,
+    total_allocations: u64,
+    total_bytes:       u64,
+    live_bytes:        u64,
+    untracked:         u64,
+    failed:            u64,
+    unknown_frees:     u64,
+    samples:           VecDeque<HeapSample>,
+}
+
+impl HeapTracker {
+    /// Create a tracker for the allocator functions `hooks`
+    pub fn new(hooks: HeapHooks) -> Self {
+        Self {
+            hooks,
+            max_live: DEFAULT_MAX_LIVE_ALLOCATIONS,
+            sample_interval: 0,
+            max_samples: DEFAULT_MAX_SAMPLES,
+            live: HashMap::new(),
+            pending: Vec::new(),
+            last: None,
+            clock: 0,
+            total_allocations: 0,
+            total_bytes: 0,
+            live_bytes: 0,
+            untracked: 0,
+            failed: 0,
+            unknown_frees: 0,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Keep at most `max_live` live allocations
+    pub fn with_max_live(mut self, max_live: usize) -> Self {
+        self.max_live = max_live;
+        self
+    }
+
+    /// Record a sample every `interval` instructions, keeping the last
+    /// `max_samples`; an interval of 0 disables sampling
+    pub fn with_sampling(mut self, interval: u64, max_samples: usize) -> Self {
+        self.sample_interval = interval;
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// Allocator functions being tracked
+    pub fn hooks(&self) -> &HeapHooks {
+        &self.hooks
+    }
+
+    /// Instructions observed
+    pub fn instructions(&self) -> u64 {
+        self.clock
+    }
+
+    /// Live allocation containing `address`
+    pub fn find(&self, address: u32) -> Option<&LiveAllocation> {
+        self.live.get(&address).or_else(|| {
+            self.live.values().find(|alloc| {
+                address >= alloc.address && address < alloc.address.saturating_add(alloc.size)
+            })
+        })
+    }
+
+    /// Live allocations ordered by address
+    pub fn live_allocations(&self) -> Vec<LiveAllocation> {
+        let mut live: Vec<_> = self.live.values().copied().collect();
+        live.sort_by_key(|alloc| alloc.address);
+        live
+    }
+
+    /// Live allocations made at least `min_age` instructions ago, oldest
+    /// first
+    pub fn leak_candidates(&self, min_age: u64) -> Vec<LiveAllocation> {
+        let mut old: Vec<_> = self
+            .live
+            .values()
+            .filter(|alloc| self.clock - alloc.allocated_at >= min_age)
+            .copied()
+            .collect();
+        old.sort_by_key(|alloc| (alloc.allocated_at, alloc.address));
+        old
+    }
+
+    /// Live allocations grouped by call site, most bytes first
+    pub fn usage_by_call_site(&self) -> Vec<CallSiteUsage> {
+        let mut sites: HashMap<Option<CallSite>, CallSiteUsage> = HashMap::new();
+        for alloc in self.live.values() {
+            let usage = sites.entry(alloc.call_site).or_insert(CallSiteUsage {
+                call_site:   alloc.call_site,
+                allocations: 0,
+                bytes:       0,
+            });
+            usage.allocations += 1;
+            usage.bytes += u64::from(alloc.size);
+        }
+        let mut sites: Vec<_> = sites.into_values().collect();
+        sites.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.call_site.cmp(&b.call_site)));
+        sites
+    }
+
+    /// Current heap usage
+    pub fn usage(&self) -> HeapSample {
+        HeapSample {
+            instruction:       self.clock,
+            total_allocations: self.total_allocations,
+            total_bytes:       self.total_bytes,
+            live_allocations:  self.live.len() as u64,
+            live_bytes:        self.live_bytes,
+            largest:           self.live.values().map(|alloc| alloc.size).max().unwrap_or(0),
+            untracked:         self.untracked,
+        }
+    }
+
+    /// Recorded samples, oldest first
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = &HeapSample> {
+        self.samples.iter()
+    }
+
+    /// Record a sample of the current usage
+    pub fn sample(&mut self) {
+        if self.max_samples == 0 {
+            return;
+        }
+        if self.samples.len() == self.max_samples {
+            self.samples.pop_front();
+        }
+        let usage = self.usage();
+        self.samples.push_back(usage);
+    }
+
+    /// Allocator calls that returned a null address
+    pub fn failed_allocations(&self) -> u64 {
+        self.failed
+    }
+
+    /// Frees of addresses that were not live in the table
+    pub fn unknown_frees(&self) -> u64 {
+        self.unknown_frees
+    }
+
+    /// Observe the instruction at `pc`
+    pub fn instruction(&mut self, pc: u32, state: &dyn RuntimeState) {
+        self.clock += 1;
+        if let Some(function) = state.current_function() {
+            let entered = pc == 0 && self.last.is_none_or(|last| last.function != function);
+            if entered {
+                self.enter(function, state);
+            } else if let Some(call) = self.pending.last().copied() {
+                let returned = call
+                    .call_site
+                    .is_some_and(|site| site.function == function && site.pc + 1 == pc);
+                if returned {
+                    self.pending.pop();
+                    let address = state.read_stack(0).unwrap_or(0) as u32;
+                    self.complete(call, address);
+                }
+            }
+            self.last = Some(CallSite { function, pc });
+        }
+        if self.sample_interval > 0 && self.clock % self.sample_interval == 0 {
+            self.sample();
+        }
+    }
+
+    /// Record an allocator call entered at function `function`
+    fn enter(&mut self, function: u32, state: &dyn RuntimeState) {
+        let hooks = self.hooks;
+        let local = |index| state.read_local(index).unwrap_or(0) as u32;
+        let call_site = self.last;
+        let call = if Some(function) == hooks.malloc {
+            PendingCall { old: 0, size: local(0), call_site }
+        } else if Some(function) == hooks.realloc {
+            PendingCall { old: local(0), size: local(1), call_site }
+        } else if Some(function) == hooks.canonical_realloc {
+            PendingCall { old: local(0), size: local(3), call_site }
+        } else {
+            if Some(function) == hooks.free {
+                let address = local(0);
+                if address != 0 {
+                    self.release(address);
+                }
+            }
+            return;
+        };
+        self.pending.push(call);
+    }
+
+    /// Complete `call`, which returned `address`
+    fn complete(&mut self, call: PendingCall, address: u32) {
+        if address == 0 {
+            self.failed += 1;
+            return;
+        }
+        if call.old != 0 {
+            self.release(call.old);
+        }
+        self.total_allocations += 1;
+        self.total_bytes += u64::from(call.size);
+        if let Some(previous) = self.live.remove(&address) {
+            // The free of the previous allocation was missed
+            self.live_bytes -= u64::from(previous.size);
+        } else if self.live.len() >= self.max_live {
+            self.untracked += 1;
+            return;
+        }
+        self.live_bytes += u64::from(call.size);
+        self.live.insert(address, LiveAllocation {
+            address,
+            size: call.size,
+            call_site: call.call_site,
+            allocated_at: self.clock,
+        });
+    }
+
+    fn release(&mut self, address: u32) {
+        match self.live.remove(&address) {
+            Some(alloc) => self.live_bytes -= u64::from(alloc.size),
+            None => self.unknown_frees += 1,
+        }
+    }
+}
+
+impl RuntimeDebugger for HeapTracker {
+    fn on_breakpoint(&mut self, _bp: &Breakpoint, _state: &dyn RuntimeState) -> DebugAction {
+        DebugAction::Continue
+    }
+
+    fn on_instruction(&mut self, pc: u32, state: &dyn RuntimeState) -> DebugAction {
+        self.instruction(pc, state);
+        DebugAction::Continue
+    }
+
+    fn on_function_entry(&mut self, _func_idx: u32, _state: &dyn RuntimeState) {}
+
+    fn on_function_exit(&mut self, _func_idx: u32, _state: &dyn RuntimeState) {}
+
+    fn on_trap(&mut self, _trap_code: u32, _state: &dyn RuntimeState) {
+        // A trapped call never returns its result
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct State {
+        function: u32,
+        locals:   Vec<u64>,
+        top:      u64,
+    }
+
+    impl RuntimeState for State {
+        fn pc(&self) -> u32 {
+            0
+        }
+
+        fn sp(&self) -> u32 {
+            0
+        }
+
+        fn fp(&self) -> Option<u32> {
+            None
+        }
+
+        fn read_local(&self, index: u32) -> Option<u64> {
+            self.locals.get(index as usize).copied()
+        }
+
+        fn read_stack(&self, offset: u32) -> Option<u64> {
+            (offset == 0).then_some(self.top)
+        }
+
+        fn current_function(&self) -> Option<u32> {
+            Some(self.function)
+        }
+    }
+
+    const MALLOC: u32 = 1;
+    const FREE: u32 = 2;
+    const REALLOC: u32 = 3;
+
+    fn tracker() -> HeapTracker {
+        let hooks = HeapHooks::resolve(&HeapSymbols::default(), |name| match name {
+            "malloc" => Some(MALLOC),
+            "free" => Some(FREE),
+            "cabi_realloc" => Some(REALLOC),
+            _ => None,
+        });
+        assert_eq!(hooks.realloc, None);
+        HeapTracker::new(hooks)
+    }
+
+    fn step(tracker: &mut HeapTracker, function: u32, pc: u32, locals: &[u64], top: u64) {
+        let state = State { function, locals: locals.to_vec(), top };
+        tracker.instruction(pc, &state);
+    }
+
+    /// Call `function` with `args` from function 0 at `pc`, returning `result`
+    fn call(tracker: &mut HeapTracker, pc: u32, function: u32, args: &[u64], result: u64) {
+        step(tracker, 0, pc, &[], 0);
+        step(tracker, function, 0, args, 0);
+        step(tracker, function, 1, args, 0);
+        step(tracker, 0, pc + 1, &[], result);
+    }
+
+    #[test]
+    fn test_tracks_allocations_by_call_site() {
+        let mut tracker = tracker().with_sampling(4, 2);
+        call(&mut tracker, 10, MALLOC, &[32], 0x1000);
+        call(&mut tracker, 20, MALLOC, &[64], 0x2000);
+        call(&mut tracker, 10, MALLOC, &[16], 0x3000);
+        call(&mut tracker, 30, FREE, &[0x2000], 0);
+        // Grow the first allocation through the canonical realloc
+        call(&mut tracker, 40, REALLOC, &[0x1000, 32, 8, 128], 0x4000);
+        call(&mut tracker, 50, MALLOC, &[8], 0);
+
+        let live = tracker.live_allocations();
+        assert_eq!(
+            live.iter().map(|alloc| (alloc.address, alloc.size)).collect::<Vec<_>>(),
+            [(0x3000, 16), (0x4000, 128)]
+        );
+        assert_eq!(tracker.find(0x4010).map(|alloc| alloc.address), Some(0x4000));
+        assert_eq!(tracker.failed_allocations(), 1);
+        assert_eq!(tracker.unknown_frees(), 0);
+
+        let usage = tracker.usage();
+        assert_eq!((usage.total_allocations, usage.total_bytes), (4, 240));
+        assert_eq!((usage.live_allocations, usage.live_bytes, usage.largest), (2, 144, 128));
+
+        let sites = tracker.usage_by_call_site();
+        assert_eq!(sites[0].call_site, Some(CallSite { function: 0, pc: 40 }));
+        assert_eq!(sites[1].bytes, 16);
+        assert_eq!(tracker.leak_candidates(tracker.instructions()).len(), 0);
+        assert_eq!(tracker.leak_candidates(10).len(), 1);
+
+        // 24 instructions sampled every 4, keeping the last 2
+        let samples: Vec<_> = tracker.samples().map(|sample| sample.instruction).collect();
+        assert_eq!(samples, [20, 24]);
+    }
+
+    #[test]
+    fn test_table_is_bounded() {
+        let mut tracker = tracker().with_max_live(1);
+        call(&mut tracker, 10, MALLOC, &[4], 0x100);
+        call(&mut tracker, 10, MALLOC, &[4], 0x200);
+        call(&mut tracker, 30, FREE, &[0x200], 0);
+        assert_eq!(tracker.usage().untracked, 1);
+        assert_eq!(tracker.usage().live_allocations, 1);
+        assert_eq!(tracker.unknown_frees(), 1);
+    }
+}
@@ -30,6 +30,7 @@
 #[cfg(feature = "abbrev")]
 pub use abbrev::{Abbreviation, AbbreviationTable, AttributeForm, AttributeSpec};
 pub use cursor::DwarfCursor;
+pub use expression::{ExpressionContext, Location};
 pub use file_table::{FileEntry, FilePath, FileTable};
 // Platform debug exports
 #[cfg(feature = "debug-info")]
@@ -52,6 +53,10 @@
 pub mod realtime_monitor;
 
 // Re-export realtime monitoring types
+#[cfg(feature = "heap-profiling")]
+pub use heap_profile::{
+    CallSite, CallSiteUsage, HeapHooks, HeapSample, HeapSymbols, HeapTracker, LiveAllocation,
+};
 #[cfg(feature = "memory-profiling")]
 pub use memory_profiling::{
     AccessPatternSummary, AccessRecord, AccessType, AllocationRecord, AllocationType, LeakInfo,
@@ -75,6 +80,8 @@
 pub use runtime_step::{StepController, StepMode, SteppingDebugger};
 #[cfg(feature = "runtime-variables")]
 pub use runtime_vars::{ValueDisplay, VariableDefinition, VariableInspector, VariableScope};
+#[cfg(all(feature = "line-info", feature = "runtime-traits"))]
+pub use source_step::{LineRange, LineTable, SourceStep, SourceStepper};
 #[cfg(feature = "line-info")]
 pub use stack_trace::{StackFrame, StackTrace, StackTraceBuilder};
 pub use strings::{DebugString, StringTable};
@@ -105,6 +112,8 @@
 mod abbrev;
 mod cursor;
 mod error;
+/// DWARF expression and location list evaluation
+pub mod expression;
 mod file_table;
 #[cfg(feature = "debug-info")]
 mod info;
@@ -112,12 +121,20 @@
 mod line_info;
 mod parameter;
 pub mod platform_debug;
+#[cfg(all(feature = "line-info", feature = "runtime-traits"))]
+mod source_step;
 #[cfg(feature = "line-info")]
 mod stack_trace;
 mod strings;
 mod types;
 
 // Runtime debug modules
+#[cfg(feature = "crash-dump")]
+pub mod crash_report;
+#[cfg(feature = "dap")]
+pub mod dap;
+#[cfg(feature = "heap-profiling")]
+mod heap_profile;
 #[cfg(feature = "memory-profiling")]
 mod memory_profiling;
 #[cfg(feature = "runtime-inspection")]
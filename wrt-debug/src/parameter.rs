@@ -236,9 +236,9 @@ pub fn add_parameter(&mut self, param: Parameter<'a>) -> Result<(), ()> {
         self.parameters.push(param).map_err(|_| ())
     }
 
-    /// Get all parameters
-    pub fn parameters(&self) -> &[Parameter<'a>] {
-        self.parameters.as_slice().unwrap_or(&[])
+    /// Iterate over all parameters in declaration order
+    pub fn parameters(&self) -> impl DoubleEndedIterator<Item = Parameter<'a>> + '_ {
+        self.parameters.iter()
     }
 
     /// Get parameter count
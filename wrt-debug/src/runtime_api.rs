@@ -127,7 +127,7 @@ pub enum DwarfLocation {
     Memory(u32),
     /// On stack at offset from frame pointer
     FrameOffset(i32),
-    /// Complex expression (not yet supported)
+    /// DWARF location expression, see [`crate::expression::evaluate`]
     Expression(BoundedVec<u8, 64, crate::bounded_debug_infra::DebugProvider>),
 }
 
@@ -8,6 +8,8 @@
 };
 
 use crate::bounded_debug_infra;
+#[cfg(feature = "heap-profiling")]
+use crate::heap_profile::{HeapSample, HeapTracker};
 /// Runtime memory inspection implementation
 /// Provides safe memory access and heap analysis capabilities
 use crate::runtime_api::{DebugMemory, RuntimeState};
@@ -66,6 +68,9 @@ pub struct MemoryInspector<'a> {
         BoundedVec<HeapAllocation, MAX_DWARF_FILE_TABLE, crate::bounded_debug_infra::DebugProvider>,
     /// Reference to debug memory interface
     memory: Option<&'a dyn DebugMemory>,
+    /// Guest allocator tracking, when enabled
+    #[cfg(feature = "heap-profiling")]
+    heap: Option<HeapTracker>,
 }
 
 impl<'a> MemoryInspector<'a> {
@@ -80,6 +85,8 @@ pub fn new() -> Result<Self, Error> {
             allocations: BoundedVec::new(allocations_provider)
                 .map_err(|_| Error::allocation_failed("Failed to create allocations vector"))?,
             memory: None,
+            #[cfg(feature = "heap-profiling")]
+            heap: None,
         })
     }
 
@@ -150,8 +157,52 @@ pub fn read_cstring(&self, addr: u32, max_len: usize) -> Option<CStringView<'_>>
         })
     }
 
+    /// Track the guest's allocations with `tracker`
+    ///
+    /// The tracker has to observe execution, so it must be driven from the
+    /// runtime's debugger callbacks through [`Self::heap_tracker_mut`].
+    /// While tracking, [`Self::heap_stats`] reports the tracked heap instead
+    /// of the registered allocations.
+    #[cfg(feature = "heap-profiling")]
+    pub fn enable_allocation_tracking(&mut self, tracker: HeapTracker) {
+        self.heap = Some(tracker);
+    }
+
+    /// Stop tracking allocations, returning the tracker
+    #[cfg(feature = "heap-profiling")]
+    pub fn disable_allocation_tracking(&mut self) -> Option<HeapTracker> {
+        self.heap.take()
+    }
+
+    /// Allocation tracker, if tracking is enabled
+    #[cfg(feature = "heap-profiling")]
+    pub fn heap_tracker(&self) -> Option<&HeapTracker> {
+        self.heap.as_ref()
+    }
+
+    /// Mutable allocation tracker, if tracking is enabled
+    #[cfg(feature = "heap-profiling")]
+    pub fn heap_tracker_mut(&mut self) -> Option<&mut HeapTracker> {
+        self.heap.as_mut()
+    }
+
+    /// Heap statistics of the tracker's samples, oldest first
+    #[cfg(feature = "heap-profiling")]
+    pub fn heap_stats_series(&self) -> std::vec::Vec<HeapStats> {
+        self.heap
+            .iter()
+            .flat_map(|tracker| tracker.samples())
+            .map(HeapStats::from_sample)
+            .collect()
+    }
+
     /// Get heap statistics
     pub fn heap_stats(&self) -> HeapStats {
+        #[cfg(feature = "heap-profiling")]
+        if let Some(tracker) = &self.heap {
+            return HeapStats::from_sample(&tracker.usage());
+        }
+
         let mut stats = HeapStats {
             total_allocations: 0,
             active_allocations: 0,
@@ -269,6 +320,30 @@ pub struct HeapStats {
     pub fragmentation: f32,
 }
 
+#[cfg(feature = "heap-profiling")]
+impl HeapStats {
+    /// Statistics of a heap tracker sample
+    ///
+    /// Totals count every allocation made, so the fragmentation ratio is
+    /// the share of allocated bytes that has been freed again.
+    pub fn from_sample(sample: &HeapSample) -> Self {
+        let saturate = |value: u64| u32::try_from(value).unwrap_or(u32::MAX);
+        let freed = sample.total_bytes.saturating_sub(sample.live_bytes);
+        Self {
+            total_allocations: saturate(sample.total_allocations),
+            active_allocations: saturate(sample.live_allocations),
+            total_bytes: saturate(sample.total_bytes),
+            allocated_bytes: saturate(sample.live_bytes),
+            largest_allocation: sample.largest,
+            fragmentation: if sample.total_bytes > 0 {
+                freed as f32 / sample.total_bytes as f32
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
 /// Stack usage analysis
 #[derive(Debug, Clone)]
 pub struct StackAnalysis {
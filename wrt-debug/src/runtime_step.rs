@@ -52,8 +52,10 @@ pub struct StepController {
     target_file: Option<u16>,
     /// Call stack for step-over/out
     call_stack: BoundedStack<StepFrame, 64, crate::bounded_debug_infra::DebugProvider>,
-    /// Depth for step-over
-    step_over_depth: u32,
+    /// Current call depth
+    depth: u32,
+    /// Call depth the step started at
+    step_depth: u32,
     /// Previous PC for detecting loops
     previous_pc: u32,
     /// Previous line for line stepping
@@ -71,7 +73,8 @@ pub fn new() -> Self {
                 safe_managed_alloc!(32768, CrateId::Debug).unwrap().provider().clone(),
             )
             .unwrap(),
-            step_over_depth: 0,
+            depth: 0,
+            step_depth: 0,
             previous_pc: 0,
             previous_line: None,
         }
@@ -80,21 +83,15 @@ pub fn new() -> Self {
     /// Start stepping with given mode
     pub fn start_step(&mut self, mode: StepMode, current_line: Option<&LineInfo>) {
         self.mode = mode;
+        self.step_depth = self.depth;
 
-        match mode {
-            StepMode::Line | StepMode::Over | StepMode::Into => {
-                // Remember current line
-                if let Some(line) = current_line {
-                    self.target_file = Some(line.file_index);
-                    self.target_line = Some(line.line);
-                    self.previous_line = Some(line.line);
-                }
-            },
-            StepMode::Out => {
-                // Step out needs at least one frame
-                self.step_over_depth = 1;
-            },
-            _ => {},
+        if let StepMode::Line | StepMode::Over | StepMode::Into = mode {
+            // Remember current line
+            if let Some(line) = current_line {
+                self.target_file = Some(line.file_index);
+                self.target_line = Some(line.line);
+                self.previous_line = Some(line.line);
+            }
         }
     }
 
@@ -126,23 +123,13 @@ pub fn should_break(
 
             StepMode::Into => self.check_step_into(current_line),
 
-            StepMode::Out => self.check_step_out(),
+            StepMode::Out => self.check_step_out(current_line),
         }
     }
 
     /// Handle function entry
     pub fn on_function_entry(&mut self, func_idx: u32, return_pc: u32) {
-        match self.mode {
-            StepMode::Over => {
-                // Track call depth
-                self.step_over_depth += 1;
-            },
-            StepMode::Out => {
-                // Track call depth
-                self.step_over_depth += 1;
-            },
-            _ => {},
-        }
+        self.depth += 1;
 
         // Push frame
         let frame = StepFrame {
@@ -157,20 +144,7 @@ pub fn on_function_entry(&mut self, func_idx: u32, return_pc: u32) {
     pub fn on_function_exit(&mut self) {
         // Pop frame
         self.call_stack.pop();
-
-        match self.mode {
-            StepMode::Over => {
-                self.step_over_depth = self.step_over_depth.saturating_sub(1);
-            },
-            StepMode::Out => {
-                self.step_over_depth = self.step_over_depth.saturating_sub(1);
-                if self.step_over_depth == 0 {
-                    // We've stepped out
-                    self.mode = StepMode::None;
-                }
-            },
-            _ => {},
-        }
+        self.depth = self.depth.saturating_sub(1);
     }
 
     /// Reset stepping state
@@ -178,7 +152,8 @@ pub fn reset(&mut self) {
         self.mode = StepMode::None;
         self.target_line = None;
         self.target_file = None;
-        self.step_over_depth = 0;
+        self.depth = 0;
+        self.step_depth = 0;
         self.call_stack.clear();
     }
 
@@ -204,22 +179,31 @@ fn check_line_step(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
         DebugAction::Continue
     }
 
+    /// Get current call depth
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Check whether `line` is a statement of a source line other than the
+    /// one the step started on
+    fn is_new_line(&self, line: &LineInfo) -> bool {
+        line.is_stmt
+            && (self.target_line != Some(line.line) || self.target_file != Some(line.file_index))
+    }
+
     /// Check for step-over completion
     fn check_step_over(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
-        // Only break if we're at the original call depth
-        if self.step_over_depth > 0 {
+        // Calls made by the stepped line run to completion
+        if self.depth > self.step_depth {
             return DebugAction::Continue;
         }
 
-        // Check if we've moved to a different line
+        // Break on a new line, or on any line of the caller once returned
         if let Some(line) = current_line {
-            if let Some(target_line) = self.target_line {
-                if line.line != target_line || line.file_index != self.target_file.unwrap_or(0) {
-                    // Different line at original depth - stop
-                    self.mode = StepMode::None;
-                    self.previous_line = Some(line.line);
-                    return DebugAction::Break;
-                }
+            if self.depth < self.step_depth || self.is_new_line(line) {
+                self.mode = StepMode::None;
+                self.previous_line = Some(line.line);
+                return DebugAction::Break;
             }
         }
 
@@ -228,16 +212,10 @@ fn check_step_over(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
 
     /// Check for step-into completion
     fn check_step_into(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
-        // Step into breaks on any new line
+        // Step into breaks on any new line, including the first line of a
+        // called function and the caller's line after a return
         if let Some(line) = current_line {
-            if let Some(prev_line) = self.previous_line {
-                if line.line != prev_line {
-                    self.mode = StepMode::None;
-                    self.previous_line = Some(line.line);
-                    return DebugAction::Break;
-                }
-            } else {
-                // First line
+            if self.depth != self.step_depth || self.is_new_line(line) {
                 self.mode = StepMode::None;
                 self.previous_line = Some(line.line);
                 return DebugAction::Break;
@@ -248,13 +226,17 @@ fn check_step_into(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
     }
 
     /// Check for step-out completion
-    fn check_step_out(&self) -> DebugAction {
-        // Step-out is handled in on_function_exit
-        if self.step_over_depth == 0 {
-            DebugAction::Break
-        } else {
-            DebugAction::Continue
+    fn check_step_out(&mut self, current_line: Option<&LineInfo>) -> DebugAction {
+        // Break on the first line of the caller once the function returned
+        if self.depth < self.step_depth {
+            if let Some(line) = current_line {
+                self.mode = StepMode::None;
+                self.previous_line = Some(line.line);
+                return DebugAction::Break;
+            }
         }
+
+        DebugAction::Continue
     }
 }
 
@@ -457,4 +439,40 @@ fn test_step_over() {
         let action = controller.should_break(0x1100, &state, Some(&line2));
         assert_eq!(action, DebugAction::Break);
     }
+
+    #[test]
+    fn test_step_out() {
+        let mut controller = StepController::new();
+        let state = MockState { pc: 0x2000 };
+
+        let callee_line = LineInfo {
+            file_index: 1,
+            line: 20,
+            column: 0,
+            is_stmt: true,
+            end_sequence: false,
+        };
+        let caller_line = LineInfo {
+            line: 10,
+            ..callee_line
+        };
+
+        // Stopped inside a called function
+        controller.on_function_entry(1, 0x1100);
+        controller.start_step(StepMode::Out, Some(&callee_line));
+
+        // Nested call and remaining lines of the function - continue
+        controller.on_function_entry(2, 0x2010);
+        let action = controller.should_break(0x3000, &state, Some(&callee_line));
+        assert_eq!(action, DebugAction::Continue);
+        controller.on_function_exit();
+        let action = controller.should_break(0x2010, &state, Some(&callee_line));
+        assert_eq!(action, DebugAction::Continue);
+
+        // Returned to the caller - break, even mid-line
+        controller.on_function_exit();
+        let action = controller.should_break(0x1100, &state, Some(&caller_line));
+        assert_eq!(action, DebugAction::Break);
+        assert_eq!(controller.depth(), 0);
+    }
 }
@@ -11,6 +11,7 @@
 /// Runtime variable inspection implementation
 /// Provides the ability to read variable values from runtime state
 use crate::{
+    expression::{self, ExpressionContext, Location},
     parameter::{BasicType, Parameter},
     runtime_api::{DebugMemory, DwarfLocation, LiveVariable, RuntimeState, VariableValue},
     strings::DebugString,
@@ -132,9 +133,19 @@ pub fn read_variable(
                     None
                 }
             },
-            DwarfLocation::Expression(_) => {
-                // Complex DWARF expressions not yet supported
-                None
+            DwarfLocation::Expression(bytes) => {
+                let context = FrameContext { state, memory };
+                let location = expression::evaluate(bytes.as_slice().ok()?, &context).ok()?;
+                let size = size_for_type(&var.var_type);
+                location.read(&context, size).map(|value| VariableValue {
+                    bytes: value.to_le_bytes(),
+                    size: size.min(8),
+                    var_type: var.var_type.clone(),
+                    address: match location {
+                        Location::Memory(addr) => Some(addr),
+                        _ => None,
+                    },
+                })
             },
         }
     }
@@ -173,6 +184,39 @@ pub fn format_value(value: &VariableValue) -> ValueDisplay {
     }
 }
 
+/// Expression context over the runtime state of the inspected frame
+struct FrameContext<'s> {
+    state: &'s dyn RuntimeState,
+    memory: &'s dyn DebugMemory,
+}
+
+impl ExpressionContext for FrameContext<'_> {
+    fn frame_base(&self) -> Option<u64> {
+        self.state.fp().map(u64::from)
+    }
+
+    fn local(&self, index: u32) -> Option<u64> {
+        self.state.read_local(index)
+    }
+
+    fn global(&self, _index: u32) -> Option<u64> {
+        // The runtime state does not expose globals
+        None
+    }
+
+    fn operand(&self, depth: u32) -> Option<u64> {
+        self.state.read_stack(depth)
+    }
+
+    fn read_memory(&self, address: u32, size: u8) -> Option<u64> {
+        let data = self.memory.read_bytes(address, usize::from(size))?;
+        let mut bytes = [0u8; 8];
+        let len = data.len().min(8);
+        bytes[..len].copy_from_slice(&data[..len]);
+        Some(u64::from_le_bytes(bytes))
+    }
+}
+
 /// Helper to get size in bytes for a basic type
 fn size_for_type(ty: &BasicType) -> u8 {
     match ty {
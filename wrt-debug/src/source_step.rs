@@ -0,0 +1,387 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Source-level stepping
+//!
+//! [`SourceStepper`] turns the per-instruction callbacks of a runtime into
+//! step-into, step-over and step-out at source line granularity. It combines
+//! a [`LineTable`] mapping instruction offsets to source lines with call frame
+//! detection:
+//!
+//! - runtimes reporting calls and returns through
+//!   [`RuntimeDebugger::on_function_entry`] and
+//!   [`RuntimeDebugger::on_function_exit`] have their frames tracked exactly;
+//! - otherwise a change of [`RuntimeState::current_function`] is taken as a
+//!   call when execution starts at offset 0 of the new function and as a
+//!   return when it does not. Direct recursion is only visible through the
+//!   entry and exit callbacks.
+//!
+//! A step ends on the first statement of a different source line in the
+//! frame the step applies to, or when a breakpoint is hit first.
+
+#![cfg(all(feature = "line-info", feature = "runtime-traits"))]
+
+use crate::{
+    runtime_traits::{Breakpoint, DebugAction, RuntimeDebugger, RuntimeState},
+    LineInfo,
+};
+
+/// Source line lookup for instruction offsets
+pub trait LineTable {
+    /// Line of the instruction at `pc` within function `function`
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo>;
+}
+
+impl<T: LineTable + ?Sized> LineTable for &T {
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo> {
+        (**self).line_at(function, pc)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: LineTable + ?Sized> LineTable for std::sync::Arc<T> {
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo> {
+        (**self).line_at(function, pc)
+    }
+}
+
+/// Instruction offsets `start..end` of a function mapped to one source line
+#[derive(Debug, Clone, Copy)]
+pub struct LineRange {
+    /// Function index
+    pub function: u32,
+    /// First instruction offset
+    pub start:    u32,
+    /// Instruction offset after the last one
+    pub end:      u32,
+    /// Source line of the range
+    pub line:     LineInfo,
+}
+
+impl LineTable for [LineRange] {
+    fn line_at(&self, function: u32, pc: u32) -> Option<LineInfo> {
+        self.iter()
+            .find(|range| range.function == function && (range.start..range.end).contains(&pc))
+            .map(|range| range.line)
+    }
+}
+
+/// Source-level step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceStep {
+    /// Stop on the next source line, entering called functions
+    Into,
+    /// Stop on the next source line of the current function or its caller
+    Over,
+    /// Stop in the caller once the current function returns
+    Out,
+}
+
+/// Step in progress
+#[derive(Debug, Clone, Copy)]
+struct PendingStep {
+    step:     SourceStep,
+    depth:    u32,
+    function: Option<u32>,
+    line:     Option<(u16, u32)>,
+}
+
+/// Drives source-level steps from runtime debugger callbacks
+///
+/// The stepper can be attached to a runtime as its [`RuntimeDebugger`]
+/// directly, or be called from another debugger's callbacks. After a
+/// breakpoint hit, [`SourceStepper::step`] starts a step; the runtime then
+/// resumes and the stepper answers [`DebugAction::Break`] once the step is
+/// complete.
+#[derive(Debug)]
+pub struct SourceStepper<T> {
+    lines:    T,
+    pending:  Option<PendingStep>,
+    depth:    u32,
+    function: Option<u32>,
+    location: Option<(u32, u32)>,
+    started:  bool,
+}
+
+impl<T: LineTable> SourceStepper<T> {
+    /// Create a stepper over a line table
+    pub fn new(lines: T) -> Self {
+        Self {
+            lines,
+            pending: None,
+            depth: 0,
+            function: None,
+            location: None,
+            started: false,
+        }
+    }
+
+    /// Line table used for stepping
+    pub fn line_table(&self) -> &T {
+        &self.lines
+    }
+
+    /// Call depth relative to the first observed frame
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Step in progress, if any
+    pub fn pending_step(&self) -> Option<SourceStep> {
+        self.pending.map(|pending| pending.step)
+    }
+
+    /// Source line execution is stopped at
+    pub fn current_line(&self) -> Option<LineInfo> {
+        let (function, pc) = self.location?;
+        self.lines.line_at(function, pc)
+    }
+
+    /// Start a step from the location of `state`
+    ///
+    /// Returns the action the runtime should resume with.
+    pub fn step(&mut self, step: SourceStep, state: &dyn RuntimeState) -> DebugAction {
+        self.observe(state.pc(), state);
+        let line = self.current_line().map(|line| (line.file_index, line.line));
+        self.pending = Some(PendingStep {
+            step,
+            depth: self.depth,
+            function: self.function,
+            line,
+        });
+        match step {
+            SourceStep::Into => DebugAction::StepLine,
+            SourceStep::Over => DebugAction::StepOver,
+            SourceStep::Out => DebugAction::StepOut,
+        }
+    }
+
+    /// Abandon the step in progress
+    pub fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    /// Forget all frames, e.g. before a new invocation
+    pub fn reset(&mut self) {
+        self.pending = None;
+        self.depth = 0;
+        self.function = None;
+        self.location = None;
+        self.started = false;
+    }
+
+    /// Record a breakpoint hit, which ends any step in progress
+    pub fn breakpoint_hit(&mut self, state: &dyn RuntimeState) -> DebugAction {
+        self.observe(state.pc(), state);
+        self.pending = None;
+        DebugAction::Break
+    }
+
+    /// Check whether the step in progress completes at instruction `pc`
+    pub fn instruction(&mut self, pc: u32, state: &dyn RuntimeState) -> DebugAction {
+        self.observe(pc, state);
+        let Some(pending) = self.pending else {
+            return DebugAction::Continue;
+        };
+        let Some(line) = self.current_line() else {
+            return DebugAction::Continue;
+        };
+
+        let returned = self.depth < pending.depth;
+        let done = match pending.step {
+            SourceStep::Out => returned,
+            SourceStep::Over if self.depth > pending.depth => false,
+            SourceStep::Into | SourceStep::Over => {
+                line.is_stmt
+                    && (returned
+                        || self.depth != pending.depth
+                        || self.function != pending.function
+                        || Some((line.file_index, line.line)) != pending.line)
+            },
+        };
+        if done {
+            self.pending = None;
+            DebugAction::Break
+        } else {
+            DebugAction::Continue
+        }
+    }
+
+    /// Record a call into `func_idx`
+    pub fn function_entry(&mut self, func_idx: u32) {
+        if self.started {
+            self.depth += 1;
+        }
+        self.started = true;
+        self.function = Some(func_idx);
+        self.location = None;
+    }
+
+    /// Record a return from the current function
+    pub fn function_exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        // The caller is picked up again by the next instruction
+        self.function = None;
+        self.location = None;
+    }
+
+    /// Track the frame of the instruction at `pc`
+    fn observe(&mut self, pc: u32, state: &dyn RuntimeState) {
+        let Some(function) = state.current_function() else {
+            return;
+        };
+        match self.function {
+            Some(previous) if previous != function => {
+                if pc == 0 {
+                    self.depth += 1;
+                } else {
+                    self.depth = self.depth.saturating_sub(1);
+                }
+            },
+            _ => {},
+        }
+        self.started = true;
+        self.function = Some(function);
+        self.location = Some((function, pc));
+    }
+}
+
+impl<T: LineTable + Send + Sync> RuntimeDebugger for SourceStepper<T> {
+    fn on_breakpoint(&mut self, _bp: &Breakpoint, state: &dyn RuntimeState) -> DebugAction {
+        self.breakpoint_hit(state)
+    }
+
+    fn on_instruction(&mut self, pc: u32, state: &dyn RuntimeState) -> DebugAction {
+        self.instruction(pc, state)
+    }
+
+    fn on_function_entry(&mut self, func_idx: u32, _state: &dyn RuntimeState) {
+        self.function_entry(func_idx);
+    }
+
+    fn on_function_exit(&mut self, _func_idx: u32, _state: &dyn RuntimeState) {
+        self.function_exit();
+    }
+
+    fn on_trap(&mut self, _trap_code: u32, _state: &dyn RuntimeState) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct State {
+        function: u32,
+        pc:       u32,
+    }
+
+    impl RuntimeState for State {
+        fn pc(&self) -> u32 {
+            self.pc
+        }
+
+        fn sp(&self) -> u32 {
+            0
+        }
+
+        fn fp(&self) -> Option<u32> {
+            None
+        }
+
+        fn read_local(&self, _: u32) -> Option<u64> {
+            None
+        }
+
+        fn read_stack(&self, _: u32) -> Option<u64> {
+            None
+        }
+
+        fn current_function(&self) -> Option<u32> {
+            Some(self.function)
+        }
+    }
+
+    // Function 0: line 10 calls function 1 (lines 20-21), then line 11
+    const LINES: [LineRange; 5] = [
+        range(0, 0, 3, 10),
+        range(0, 3, 5, 11),
+        range(1, 0, 2, 20),
+        range(1, 2, 4, 21),
+        range(0, 5, 6, 12),
+    ];
+
+    const fn range(function: u32, start: u32, end: u32, line: u32) -> LineRange {
+        LineRange {
+            function,
+            start,
+            end,
+            line: LineInfo {
+                file_index: 1,
+                line,
+                column: 0,
+                is_stmt: true,
+                end_sequence: false,
+            },
+        }
+    }
+
+    /// Run the trace until the stepper breaks, returning the break location
+    fn run(
+        stepper: &mut SourceStepper<&[LineRange]>,
+        trace: &[(u32, u32)],
+    ) -> Option<(u32, u32)> {
+        trace.iter().copied().find(|&(function, pc)| {
+            stepper.instruction(pc, &State { function, pc }) == DebugAction::Break
+        })
+    }
+
+    // Line 10 at 0..3 with the call at 2, function 1 returns to offset 3
+    const CALL: [(u32, u32); 7] = [(0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (1, 3), (0, 3)];
+
+    #[test]
+    fn test_step_into_and_over() {
+        let mut into = SourceStepper::new(&LINES[..]);
+        into.instruction(0, &State { function: 0, pc: 0 });
+        assert_eq!(into.step(SourceStep::Into, &State { function: 0, pc: 0 }), DebugAction::StepLine);
+        assert_eq!(run(&mut into, &CALL), Some((1, 0)));
+        assert_eq!(into.depth(), 1);
+        assert_eq!(into.current_line().map(|line| line.line), Some(20));
+
+        let mut over = SourceStepper::new(&LINES[..]);
+        over.instruction(0, &State { function: 0, pc: 0 });
+        over.step(SourceStep::Over, &State { function: 0, pc: 0 });
+        assert_eq!(run(&mut over, &CALL), Some((0, 3)));
+        assert_eq!(over.depth(), 0);
+        assert_eq!(over.current_line().map(|line| line.line), Some(11));
+        assert_eq!(over.pending_step(), None);
+    }
+
+    #[test]
+    fn test_step_out_with_frame_callbacks() {
+        let mut stepper = SourceStepper::new(&LINES[..]);
+        stepper.instruction(2, &State { function: 0, pc: 2 });
+        stepper.function_entry(1);
+        stepper.instruction(0, &State { function: 1, pc: 0 });
+        assert_eq!(stepper.depth(), 1);
+
+        stepper.step(SourceStep::Out, &State { function: 1, pc: 0 });
+        assert_eq!(run(&mut stepper, &[(1, 1), (1, 2), (1, 3)]), None);
+        stepper.function_exit();
+        assert_eq!(run(&mut stepper, &[(0, 3)]), Some((0, 3)));
+        assert_eq!(stepper.depth(), 0);
+    }
+
+    #[test]
+    fn test_breakpoint_ends_step() {
+        let mut stepper = SourceStepper::new(&LINES[..]);
+        stepper.step(SourceStep::Over, &State { function: 0, pc: 0 });
+        let bp = Breakpoint::new(crate::runtime_traits::BreakpointId(1), 1);
+        let action = stepper.on_breakpoint(&bp, &State { function: 1, pc: 1 });
+        assert_eq!(action, DebugAction::Break);
+        assert_eq!(stepper.pending_step(), None);
+        assert_eq!(stepper.instruction(5, &State { function: 0, pc: 5 }), DebugAction::Continue);
+    }
+}
@@ -0,0 +1,57 @@
+//! Report the name footprint reduction from string interning
+//!
+//! Decodes each core module or component given on the command line and
+//! compares the footprint of its import and export names stored as owned
+//! strings with the footprint when interned into one pool per core module.
+//!
+//! Run with: cargo run --example name_footprint --features std -- app.wasm
+
+#[cfg(feature = "std")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use wrt_decoder::{
+        component::decode_component,
+        decoder::decode_module,
+        utils::{BinaryType, detect_binary_type},
+    };
+    use wrt_format::StringPoolStats;
+
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        return Err("usage: name_footprint <file.wasm>...".into());
+    }
+
+    println!(
+        "{:<40} {:>8} {:>8} {:>10} {:>10} {:>8}",
+        "binary", "names", "unique", "owned", "pooled", "saved"
+    );
+    for path in &paths {
+        let bytes = std::fs::read(path)?;
+        let error = |e: wrt_decoder::Error| format!("{}: {}", path, e);
+        let stats = match detect_binary_type(&bytes).map_err(error)? {
+            BinaryType::CoreModule => decode_module(&bytes).map_err(error)?.name_footprint(),
+            BinaryType::Component => {
+                let mut stats = StringPoolStats::default();
+                for module in &decode_component(&bytes).map_err(error)?.modules {
+                    stats += module.name_footprint();
+                }
+                stats
+            },
+        };
+        println!(
+            "{:<40} {:>8} {:>8} {:>10} {:>10} {:>7.1}%",
+            path,
+            stats.strings,
+            stats.unique,
+            stats.owned_footprint(),
+            stats.pooled_footprint(),
+            stats.reduction_percent()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+fn main() {
+    println!("This example requires the 'std' feature to be enabled.");
+    println!("Run with: cargo run --example name_footprint --features std -- app.wasm");
+}
@@ -0,0 +1,318 @@
+//! Per-ASIL caps on module complexity
+//!
+//! A module deployed at a higher ASIL has to stay small enough to be
+//! reviewed, tested and bounded in time and memory. [`ComplexityLimits`]
+//! gives default caps for each ASIL level, which the
+//! [`StreamingWasmValidator`](crate::StreamingWasmValidator) enforces while
+//! it parses a module. The call depth cap cannot be checked in a single pass
+//! and is checked against the static call graph analysis of the runtime.
+//!
+//! Projects can override the defaults in the `[complexity]` table of their
+//! `wrt.toml`:
+//!
+//! ```toml
+//! [complexity]
+//! asil = "ASIL-C"
+//! max_functions = 2048
+//! ```
+//!
+//! Overriding a default is allowed but has to be justified, so every
+//! override is listed in the compliance note of the verification report.
+
+use wrt_error::{Error, Result};
+use wrt_foundation::safety_system::AsilLevel;
+
+#[cfg(feature = "std")]
+use serde::Deserialize;
+
+/// Caps on the complexity of a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityLimits {
+    /// Maximum number of functions defined by the module
+    pub max_functions: u32,
+    /// Maximum size of a single function body in bytes
+    pub max_body_size: u32,
+    /// Maximum number of locals declared by a single function
+    pub max_locals: u32,
+    /// Maximum depth of the static call graph, in frames
+    pub max_call_depth: u32,
+    /// Maximum size of a linear memory in 64 KiB pages
+    pub max_memory_pages: u32,
+}
+
+impl ComplexityLimits {
+    /// Default caps for modules deployed at `asil`
+    pub const fn for_asil(asil: AsilLevel) -> Self {
+        match asil {
+            AsilLevel::QM => Self {
+                max_functions: 10_000,
+                max_body_size: 256 * 1024,
+                max_locals: 50_000,
+                max_call_depth: 1024,
+                max_memory_pages: 65_536,
+            },
+            AsilLevel::AsilA => Self {
+                max_functions: 4096,
+                max_body_size: 64 * 1024,
+                max_locals: 1000,
+                max_call_depth: 512,
+                max_memory_pages: 16_384,
+            },
+            AsilLevel::AsilB => Self {
+                max_functions: 2048,
+                max_body_size: 32 * 1024,
+                max_locals: 512,
+                max_call_depth: 256,
+                max_memory_pages: 4096,
+            },
+            AsilLevel::AsilC => Self {
+                max_functions: 1024,
+                max_body_size: 16 * 1024,
+                max_locals: 256,
+                max_call_depth: 128,
+                max_memory_pages: 1024,
+            },
+            AsilLevel::AsilD => Self {
+                max_functions: 256,
+                max_body_size: 8 * 1024,
+                max_locals: 128,
+                max_call_depth: 64,
+                max_memory_pages: 256,
+            },
+        }
+    }
+
+    /// Check the number of functions defined by a module
+    pub fn check_functions(&self, count: u32) -> Result<()> {
+        check(count, self.max_functions, "Function count exceeds complexity limit")
+    }
+
+    /// Check the size of a function body in bytes
+    pub fn check_body_size(&self, size: u32) -> Result<()> {
+        check(size, self.max_body_size, "Function body exceeds complexity limit")
+    }
+
+    /// Check the number of locals declared by a function
+    pub fn check_locals(&self, count: u32) -> Result<()> {
+        check(count, self.max_locals, "Function locals exceed complexity limit")
+    }
+
+    /// Check the depth of the static call graph
+    pub fn check_call_depth(&self, depth: u32) -> Result<()> {
+        check(depth, self.max_call_depth, "Call depth exceeds complexity limit")
+    }
+
+    /// Check the size of a linear memory in pages
+    pub fn check_memory_pages(&self, pages: u32) -> Result<()> {
+        check(pages, self.max_memory_pages, "Memory size exceeds complexity limit")
+    }
+}
+
+impl Default for ComplexityLimits {
+    fn default() -> Self {
+        Self::for_asil(AsilLevel::default())
+    }
+}
+
+fn check(value: u32, limit: u32, message: &'static str) -> Result<()> {
+    if value > limit {
+        return Err(Error::resource_exhausted(message));
+    }
+    Ok(())
+}
+
+/// A single cap that differs from the ASIL default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitOverride {
+    /// Name of the cap as written in `wrt.toml`
+    pub name: &'static str,
+    /// Default cap for the ASIL level
+    pub default: u32,
+    /// Configured cap
+    pub value: u32,
+}
+
+impl LimitOverride {
+    /// Whether the override allows more than the default
+    pub fn is_relaxed(&self) -> bool {
+        self.value > self.default
+    }
+}
+
+/// Complexity caps in effect for an ASIL level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityPolicy {
+    /// ASIL level the policy applies to
+    pub asil: AsilLevel,
+    /// Caps in effect, defaults with any overrides applied
+    pub limits: ComplexityLimits,
+}
+
+impl ComplexityPolicy {
+    /// Policy with the default caps of `asil`
+    pub const fn new(asil: AsilLevel) -> Self {
+        Self { asil, limits: ComplexityLimits::for_asil(asil) }
+    }
+
+    /// Caps that differ from the defaults of the ASIL level
+    pub fn overrides(&self) -> impl Iterator<Item = LimitOverride> {
+        let defaults = ComplexityLimits::for_asil(self.asil);
+        let limits = self.limits;
+        [
+            ("max_functions", defaults.max_functions, limits.max_functions),
+            ("max_body_size", defaults.max_body_size, limits.max_body_size),
+            ("max_locals", defaults.max_locals, limits.max_locals),
+            ("max_call_depth", defaults.max_call_depth, limits.max_call_depth),
+            ("max_memory_pages", defaults.max_memory_pages, limits.max_memory_pages),
+        ]
+        .into_iter()
+        .filter(|&(_, default, value)| default != value)
+        .map(|(name, default, value)| LimitOverride { name, default, value })
+    }
+
+    /// Whether any cap differs from the defaults of the ASIL level
+    pub fn is_overridden(&self) -> bool {
+        self.overrides().next().is_some()
+    }
+}
+
+impl Default for ComplexityPolicy {
+    fn default() -> Self {
+        Self::new(AsilLevel::default())
+    }
+}
+
+/// `[complexity]` table of `wrt.toml`
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TomlComplexityPolicy {
+    /// ASIL level, e.g. "ASIL-B" or "QM"
+    pub asil: Option<String>,
+    pub max_functions: Option<u32>,
+    pub max_body_size: Option<u32>,
+    pub max_locals: Option<u32>,
+    pub max_call_depth: Option<u32>,
+    pub max_memory_pages: Option<u32>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Deserialize)]
+struct WrtToml {
+    #[serde(default)]
+    complexity: TomlComplexityPolicy,
+}
+
+#[cfg(feature = "std")]
+impl ComplexityPolicy {
+    /// Load the policy from the `[complexity]` table of a `wrt.toml` file
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|_| Error::runtime_execution_error("Failed to read TOML file"))?;
+        Self::from_toml_str(&contents)
+    }
+
+    /// Parse the policy from the `[complexity]` table of a `wrt.toml`
+    ///
+    /// Other tables are ignored; a missing table yields the QM defaults.
+    pub fn from_toml_str(toml_str: &str) -> Result<Self> {
+        let config: WrtToml =
+            toml::from_str(toml_str).map_err(|_| Error::parse_error("Invalid TOML format"))?;
+        config.complexity.to_policy()
+    }
+
+    /// Compliance note listing the overridden caps, if any
+    pub fn compliance_note(&self) -> Option<String> {
+        if !self.is_overridden() {
+            return None;
+        }
+        let mut note = format!(
+            "Module complexity caps for {} differ from the defaults and require a justification:\n",
+            self.asil
+        );
+        for limit in self.overrides() {
+            let direction = if limit.is_relaxed() { "relaxed" } else { "tightened" };
+            note.push_str(&format!(
+                "- `{}`: {} (default {}, {})\n",
+                limit.name, limit.value, limit.default, direction
+            ));
+        }
+        Some(note)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TomlComplexityPolicy {
+    /// Apply the configured overrides to the defaults of the ASIL level
+    pub fn to_policy(&self) -> Result<ComplexityPolicy> {
+        let asil = match self.asil.as_deref() {
+            None => AsilLevel::default(),
+            Some(name) => parse_asil(name)?,
+        };
+        let mut policy = ComplexityPolicy::new(asil);
+        let limits = &mut policy.limits;
+        limits.max_functions = self.max_functions.unwrap_or(limits.max_functions);
+        limits.max_body_size = self.max_body_size.unwrap_or(limits.max_body_size);
+        limits.max_locals = self.max_locals.unwrap_or(limits.max_locals);
+        limits.max_call_depth = self.max_call_depth.unwrap_or(limits.max_call_depth);
+        limits.max_memory_pages = self.max_memory_pages.unwrap_or(limits.max_memory_pages);
+        Ok(policy)
+    }
+}
+
+#[cfg(feature = "std")]
+fn parse_asil(name: &str) -> Result<AsilLevel> {
+    let name = name.trim().to_ascii_uppercase();
+    match name.strip_prefix("ASIL-").or_else(|| name.strip_prefix("ASIL")).unwrap_or(&name) {
+        "QM" => Ok(AsilLevel::QM),
+        "A" => Ok(AsilLevel::AsilA),
+        "B" => Ok(AsilLevel::AsilB),
+        "C" => Ok(AsilLevel::AsilC),
+        "D" => Ok(AsilLevel::AsilD),
+        _ => Err(Error::parse_error("Unknown ASIL level")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_tighten_with_asil() {
+        let qm = ComplexityLimits::for_asil(AsilLevel::QM);
+        let d = ComplexityLimits::for_asil(AsilLevel::AsilD);
+        assert!(d.max_functions < qm.max_functions);
+        assert!(d.check_locals(d.max_locals).is_ok());
+        assert!(d.check_locals(d.max_locals + 1).is_err());
+        assert!(!ComplexityPolicy::new(AsilLevel::AsilD).is_overridden());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_toml_overrides() {
+        let policy = ComplexityPolicy::from_toml_str(
+            r#"
+[execution]
+max_call_depth = 100
+
+[complexity]
+asil = "ASIL-C"
+max_functions = 2048
+max_locals = 64
+"#,
+        )
+        .unwrap();
+        assert_eq!(policy.asil, AsilLevel::AsilC);
+        assert_eq!(policy.limits.max_functions, 2048);
+        assert_eq!(policy.limits.max_body_size, 16 * 1024);
+
+        let overrides: Vec<_> = policy.overrides().collect();
+        assert_eq!(overrides.len(), 2);
+        assert!(overrides[0].is_relaxed() && !overrides[1].is_relaxed());
+        let note = policy.compliance_note().unwrap();
+        assert!(note.contains("ASIL-C") && note.contains("`max_functions`: 2048 (default 1024"));
+
+        assert_eq!(ComplexityPolicy::from_toml_str("").unwrap(), ComplexityPolicy::default());
+        assert!(ComplexityPolicy::default().compliance_note().is_none());
+        assert!(ComplexityPolicy::from_toml_str("[complexity]\nasil = \"E\"").is_err());
+    }
+}
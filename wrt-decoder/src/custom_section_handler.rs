@@ -196,6 +196,173 @@ fn default() -> Self {
     }
 }
 
+/// Maximum number of custom section parsers one registry holds
+pub const MAX_CUSTOM_SECTION_PARSERS: usize = 32;
+
+/// Typed parser for the custom sections of one name
+///
+/// Registered with [`CustomSectionParsers`] and invoked by the streaming
+/// decoder as each section of that name is reached, so embedders get their
+/// metadata decoded in the same pass instead of re-scanning the binary.
+pub trait CustomSectionParser: Send + Sync {
+    /// Decoded form of a section
+    type Artifact: core::any::Any + Send + Sync;
+
+    /// Decode the payload of a section, without its name
+    ///
+    /// # Errors
+    ///
+    /// An error fails decoding of the whole module.
+    fn parse(&self, data: &[u8]) -> Result<Self::Artifact>;
+}
+
+impl<A, F> CustomSectionParser for F
+where
+    A: core::any::Any + Send + Sync,
+    F: Fn(&[u8]) -> Result<A> + Send + Sync,
+{
+    type Artifact = A;
+
+    fn parse(&self, data: &[u8]) -> Result<A> {
+        self(data)
+    }
+}
+
+/// Type-erased parse function of a registered parser
+type ErasedParser =
+    Box<dyn Fn(&[u8]) -> Result<Box<dyn core::any::Any + Send + Sync>> + Send + Sync>;
+
+/// Parsers for named custom sections, consulted while decoding
+#[derive(Default)]
+pub struct CustomSectionParsers {
+    /// Section name and parser, at most one per name
+    parsers: Vec<(String, ErasedParser)>,
+}
+
+impl CustomSectionParsers {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse custom sections named `name` with `parser`, replacing a parser
+    /// registered for the name before
+    ///
+    /// # Errors
+    ///
+    /// Returns a capacity error if [`MAX_CUSTOM_SECTION_PARSERS`] names
+    /// already have a parser.
+    pub fn register<P>(&mut self, name: &str, parser: P) -> Result<()>
+    where
+        P: CustomSectionParser + 'static,
+    {
+        let erased: ErasedParser = Box::new(move |data| {
+            parser.parse(data).map(|artifact| Box::new(artifact) as Box<dyn core::any::Any + Send + Sync>)
+        });
+        if let Some(slot) = self.parsers.iter_mut().find(|(registered, _)| registered == name) {
+            slot.1 = erased;
+            return Ok(());
+        }
+        if self.parsers.len() >= MAX_CUSTOM_SECTION_PARSERS {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Too many custom section parsers",
+            ));
+        }
+        self.parsers.push((name.to_string(), erased));
+        Ok(())
+    }
+
+    /// Whether custom sections named `name` have a parser
+    pub fn contains(&self, name: &str) -> bool {
+        self.parsers.iter().any(|(registered, _)| registered == name)
+    }
+
+    /// Number of registered parsers
+    pub fn len(&self) -> usize {
+        self.parsers.len()
+    }
+
+    /// Whether no parser is registered
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    /// Decode a custom section with the parser of its name, returning
+    /// `None` if it has none
+    pub(crate) fn parse(
+        &self,
+        name: &str,
+        data: &[u8],
+    ) -> Option<Result<Box<dyn core::any::Any + Send + Sync>>> {
+        self.parsers
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, parser)| parser(data))
+    }
+}
+
+impl core::fmt::Debug for CustomSectionParsers {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.parsers.iter().map(|(name, _)| name)).finish()
+    }
+}
+
+/// Artifacts of the custom sections decoded by registered parsers
+///
+/// A name may occur in several sections; its artifacts are kept in binary
+/// order.
+#[derive(Default)]
+pub struct CustomSectionArtifacts {
+    /// Section name and artifact, in binary order
+    artifacts: Vec<(String, Box<dyn core::any::Any + Send + Sync>)>,
+}
+
+impl CustomSectionArtifacts {
+    /// Record the artifact of a section
+    pub(crate) fn push(&mut self, name: String, artifact: Box<dyn core::any::Any + Send + Sync>) {
+        self.artifacts.push((name, artifact));
+    }
+
+    /// Artifact of the first section named `name`, if it decoded to a `T`
+    pub fn get<T: core::any::Any>(&self, name: &str) -> Option<&T> {
+        self.artifacts
+            .iter()
+            .filter(|(section, _)| section == name)
+            .find_map(|(_, artifact)| artifact.downcast_ref::<T>())
+    }
+
+    /// Artifacts of all sections named `name` that decoded to a `T`
+    pub fn all<'s, T: core::any::Any>(&'s self, name: &'s str) -> impl Iterator<Item = &'s T> + 's {
+        self.artifacts
+            .iter()
+            .filter(move |(section, _)| section == name)
+            .filter_map(|(_, artifact)| artifact.downcast_ref::<T>())
+    }
+
+    /// Names of the decoded sections, in binary order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.artifacts.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Number of decoded sections
+    pub fn len(&self) -> usize {
+        self.artifacts.len()
+    }
+
+    /// Whether no section was decoded
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+}
+
+impl core::fmt::Debug for CustomSectionArtifacts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
 /// Parse a WebAssembly name section
 fn parse_name_section(_data: &[u8]) -> Result<CustomSection> {
     // Simplified name section parsing - normally this would be more complex
@@ -284,6 +451,40 @@ fn test_custom_section_handler() {
         assert!(names.contains(&"unknown".to_string()));
     }
 
+    #[test]
+    fn test_registered_parsers_decode_while_streaming() {
+        #[derive(Debug, PartialEq)]
+        struct BuildId(Vec<u8>);
+
+        fn custom_section(name: &str, payload: &[u8]) -> Vec<u8> {
+            let mut section = vec![0, (1 + name.len() + payload.len()) as u8, name.len() as u8];
+            section.extend_from_slice(name.as_bytes());
+            section.extend_from_slice(payload);
+            section
+        }
+
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        wasm.extend(custom_section("acme.build-id", &[0xAB, 0xCD]));
+        wasm.extend(custom_section("other", &[1]));
+        wasm.extend(custom_section("acme.build-id", &[0xEF]));
+
+        let mut parsers = CustomSectionParsers::new();
+        parsers
+            .register("acme.build-id", |data: &[u8]| Ok(BuildId(data.to_vec())))
+            .unwrap();
+        let (_, artifacts) =
+            crate::decoder::decode_module_with_custom_sections(&wasm, &parsers).unwrap();
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts.get::<BuildId>("acme.build-id"), Some(&BuildId(vec![0xAB, 0xCD])));
+        assert_eq!(artifacts.all::<BuildId>("acme.build-id").count(), 2);
+        assert!(artifacts.get::<BuildId>("other").is_none());
+
+        parsers
+            .register("other", |_: &[u8]| -> Result<()> { Err(Error::parse_error("Bad metadata")) })
+            .unwrap();
+        assert!(crate::decoder::decode_module_with_custom_sections(&wasm, &parsers).is_err());
+    }
+
     #[test]
     fn test_extract_custom_section() {
         // Create test custom section data: name length + name + data
@@ -36,6 +36,32 @@ pub fn decode_module(binary: &[u8]) -> Result<WrtModule> {
     crate::streaming_decoder::decode_module_streaming(binary)
 }
 
+/// Decode a WebAssembly module from binary format, failing with a
+/// resource exhaustion error if the binary exceeds `budget`
+///
+/// Use this instead of [`decode_module`] for binaries from untrusted
+/// sources, typically with
+/// [`ParseBudget::untrusted`](crate::parse_budget::ParseBudget::untrusted).
+#[cfg(feature = "std")]
+pub fn decode_module_with_budget(
+    binary: &[u8],
+    budget: crate::parse_budget::ParseBudget,
+) -> Result<WrtModule> {
+    crate::streaming_decoder::decode_module_streaming_with_budget(binary, budget)
+}
+
+/// Decode a WebAssembly module from binary format, decoding the custom
+/// sections that have a parser in `parsers` in the same pass
+///
+/// The artifacts of the decoded sections are returned alongside the module.
+#[cfg(feature = "std")]
+pub fn decode_module_with_custom_sections(
+    binary: &[u8],
+    parsers: &crate::custom_section_handler::CustomSectionParsers,
+) -> Result<(WrtModule, crate::custom_section_handler::CustomSectionArtifacts)> {
+    crate::streaming_decoder::decode_module_streaming_with_custom_sections(binary, parsers)
+}
+
 /// Decode a WebAssembly module from binary format (no_std version)
 #[cfg(not(feature = "std"))]
 pub fn decode_module(binary: &[u8]) -> Result<WrtModule<DecoderProvider>> {
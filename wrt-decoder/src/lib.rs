@@ -55,12 +55,15 @@
 
 // Module exports
 // Core memory optimization modules (always available)
+pub mod complexity_policy;
 pub mod decoder;
 pub mod format_detection_tests;
 pub mod lazy_detection;
 pub mod memory_optimized;
 pub mod optimized_string;
+pub mod parse_budget;
 pub mod prelude;
+pub mod proposals;
 pub mod shared_cache;
 pub mod streaming_decoder;
 pub mod streaming_validation;
@@ -110,11 +113,13 @@
 // Component functionality (std only)
 #[cfg(feature = "std")]
 pub use component::decode_no_alloc;
+pub use complexity_policy::{ComplexityLimits, ComplexityPolicy, LimitOverride};
 pub use decoder_no_alloc::{
     MAX_MODULE_SIZE, SectionId, SectionInfo, ValidatorType, WasmModuleHeader,
     create_memory_provider, decode_module_header, extract_section_info, validate_module_no_alloc,
     verify_wasm_header,
 };
+pub use parse_budget::ParseBudget;
 // Lazy detection exports
 pub use lazy_detection::{
     ComponentDetection, DetectionConfig, LazyDetector, create_fast_detector,
@@ -0,0 +1,375 @@
+//! Resource-exhaustion budgets for parsing untrusted binaries
+//!
+//! The global `MAX_*` limits bound what the runtime can represent, not what
+//! it is willing to spend on one binary. A module that stays below all of
+//! them can still be built to maximize work: a 4MB code section of tiny
+//! functions with thousands of locals each, or one section repeated until
+//! the validator's cost grows quadratically in the input size.
+//!
+//! A [`ParseBudget`] adds configurable byte and count budgets per section
+//! and per item, checked by the streaming decoder before the corresponding
+//! bytes are parsed. Exceeding a budget fails with an error in the
+//! [`ErrorCategory::ResourceExhaustion`](wrt_error::ErrorCategory) category,
+//! so callers can tell a hostile binary from a malformed one.
+//!
+//! The default budget is unlimited, leaving only the global limits;
+//! [`ParseBudget::untrusted`] is a starting point for binaries from
+//! untrusted sources.
+
+use wrt_error::{Error, Result, codes};
+
+/// Number of known section IDs (custom section 0 through tag section 13)
+pub const SECTION_COUNT: usize = 14;
+
+/// Section ID of the custom section, also used for unknown section IDs
+const CUSTOM_SECTION_ID: u8 = 0;
+
+/// Byte and count budgets for parsing one module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBudget {
+    /// Maximum size of the whole binary
+    max_module_bytes: usize,
+    /// Maximum total size of all sections of each ID
+    max_section_bytes: [usize; SECTION_COUNT],
+    /// Maximum item count declared by a section of each ID
+    max_section_items: [u32; SECTION_COUNT],
+    /// Maximum size of one function body
+    max_function_body_bytes: usize,
+    /// Maximum number of locals of one function
+    max_function_locals: usize,
+    /// Maximum number of locals of all functions together
+    max_total_locals: usize,
+    /// Maximum size of one data segment
+    max_data_segment_bytes: usize,
+    /// Maximum number of items of one element segment
+    max_element_items: u32,
+}
+
+impl Default for ParseBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl ParseBudget {
+    /// No budgets beyond the global limits
+    pub const fn unlimited() -> Self {
+        Self {
+            max_module_bytes: usize::MAX,
+            max_section_bytes: [usize::MAX; SECTION_COUNT],
+            max_section_items: [u32::MAX; SECTION_COUNT],
+            max_function_body_bytes: usize::MAX,
+            max_function_locals: usize::MAX,
+            max_total_locals: usize::MAX,
+            max_data_segment_bytes: usize::MAX,
+            max_element_items: u32::MAX,
+        }
+    }
+
+    /// Budgets for binaries from untrusted sources
+    ///
+    /// Generous for real programs, but keeps the decoding and validation
+    /// work of any accepted binary roughly linear in its size.
+    pub const fn untrusted() -> Self {
+        const KIB: usize = 1024;
+        Self {
+            max_module_bytes: 4 * KIB * KIB,
+            max_section_bytes: [
+                64 * KIB,        // custom
+                256 * KIB,       // type
+                64 * KIB,        // import
+                64 * KIB,        // function
+                4 * KIB,         // table
+                KIB,             // memory
+                64 * KIB,        // global
+                64 * KIB,        // export
+                16,              // start
+                256 * KIB,       // element
+                4 * KIB * KIB,   // code
+                2 * KIB * KIB,   // data
+                16,              // data count
+                4 * KIB,         // tag
+            ],
+            max_section_items: [
+                u32::MAX, // custom sections declare no count
+                4096,     // types
+                2048,     // imports
+                16384,    // functions
+                64,       // tables
+                16,       // memories
+                4096,     // globals
+                4096,     // exports
+                u32::MAX, // start
+                1024,     // element segments
+                16384,    // function bodies
+                1024,     // data segments
+                u32::MAX, // data count
+                256,      // tags
+            ],
+            max_function_body_bytes: 256 * KIB,
+            max_function_locals: 2048,
+            max_total_locals: 256 * KIB,
+            max_data_segment_bytes: KIB * KIB,
+            max_element_items: 16384,
+        }
+    }
+
+    /// Set the maximum size of the whole binary
+    #[must_use]
+    pub const fn with_module_bytes(mut self, bytes: usize) -> Self {
+        self.max_module_bytes = bytes;
+        self
+    }
+
+    /// Set the maximum total size of all sections with ID `section_id`
+    #[must_use]
+    pub const fn with_section_bytes(mut self, section_id: u8, bytes: usize) -> Self {
+        self.max_section_bytes[section_index(section_id)] = bytes;
+        self
+    }
+
+    /// Set the maximum item count of sections with ID `section_id`
+    #[must_use]
+    pub const fn with_section_items(mut self, section_id: u8, items: u32) -> Self {
+        self.max_section_items[section_index(section_id)] = items;
+        self
+    }
+
+    /// Set the maximum size of one function body
+    #[must_use]
+    pub const fn with_function_body_bytes(mut self, bytes: usize) -> Self {
+        self.max_function_body_bytes = bytes;
+        self
+    }
+
+    /// Set the maximum number of locals of one function
+    #[must_use]
+    pub const fn with_function_locals(mut self, locals: usize) -> Self {
+        self.max_function_locals = locals;
+        self
+    }
+
+    /// Set the maximum number of locals of all functions together
+    #[must_use]
+    pub const fn with_total_locals(mut self, locals: usize) -> Self {
+        self.max_total_locals = locals;
+        self
+    }
+
+    /// Set the maximum size of one data segment
+    #[must_use]
+    pub const fn with_data_segment_bytes(mut self, bytes: usize) -> Self {
+        self.max_data_segment_bytes = bytes;
+        self
+    }
+
+    /// Set the maximum number of items of one element segment
+    #[must_use]
+    pub const fn with_element_items(mut self, items: u32) -> Self {
+        self.max_element_items = items;
+        self
+    }
+
+    /// Maximum size of the whole binary
+    pub const fn module_bytes(&self) -> usize {
+        self.max_module_bytes
+    }
+
+    /// Maximum total size of all sections with ID `section_id`
+    pub const fn section_bytes(&self, section_id: u8) -> usize {
+        self.max_section_bytes[section_index(section_id)]
+    }
+
+    /// Maximum item count of sections with ID `section_id`
+    pub const fn section_items(&self, section_id: u8) -> u32 {
+        self.max_section_items[section_index(section_id)]
+    }
+}
+
+/// Budget index of a section ID; unknown IDs are parsed as custom sections
+const fn section_index(section_id: u8) -> usize {
+    if (section_id as usize) < SECTION_COUNT {
+        section_id as usize
+    } else {
+        CUSTOM_SECTION_ID as usize
+    }
+}
+
+/// Whether a section starts with the count of its items
+const fn has_item_count(section_id: u8) -> bool {
+    matches!(section_id, 1..=7 | 9..=11 | 13)
+}
+
+/// Budget spent while parsing one module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BudgetTracker {
+    /// Budgets being enforced
+    budget: ParseBudget,
+    /// Bytes of the sections of each ID parsed so far
+    section_bytes: [usize; SECTION_COUNT],
+    /// Locals of all functions parsed so far
+    total_locals: usize,
+}
+
+impl BudgetTracker {
+    /// Start tracking against `budget`
+    pub(crate) const fn new(budget: ParseBudget) -> Self {
+        Self {
+            budget,
+            section_bytes: [0; SECTION_COUNT],
+            total_locals: 0,
+        }
+    }
+
+    /// Budgets being enforced
+    pub(crate) const fn budget(&self) -> &ParseBudget {
+        &self.budget
+    }
+
+    /// Check the size of a binary before its header is parsed
+    pub(crate) fn check_module(&self, bytes: usize) -> Result<()> {
+        if bytes > self.budget.max_module_bytes {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_MODULE_BUDGET_EXCEEDED,
+                "Binary exceeds module byte budget",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Account for a section of `data.len()` bytes and check its item count
+    pub(crate) fn enter_section(&mut self, section_id: u8, data: &[u8]) -> Result<()> {
+        let index = section_index(section_id);
+        let spent = self.section_bytes[index].saturating_add(data.len());
+        if spent > self.budget.max_section_bytes[index] {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_SECTION_BUDGET_EXCEEDED,
+                "Section exceeds its byte budget",
+            ));
+        }
+        self.section_bytes[index] = spent;
+
+        if has_item_count(section_id) {
+            if let Ok((count, _)) = wrt_format::binary::read_leb128_u32(data, 0) {
+                if count > self.budget.max_section_items[index] {
+                    return Err(Error::parse_budget_exceeded(
+                        codes::PARSE_COUNT_BUDGET_EXCEEDED,
+                        "Section declares more items than its count budget",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the size of a function body
+    pub(crate) fn check_function_body(&self, bytes: usize) -> Result<()> {
+        if bytes > self.budget.max_function_body_bytes {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_ITEM_BUDGET_EXCEEDED,
+                "Function body exceeds its byte budget",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Account for `added` locals, bringing a function to `function_locals`
+    pub(crate) fn add_locals(&mut self, function_locals: usize, added: usize) -> Result<()> {
+        if function_locals > self.budget.max_function_locals {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_ITEM_BUDGET_EXCEEDED,
+                "Function exceeds its local count budget",
+            ));
+        }
+        let total = self.total_locals.saturating_add(added);
+        if total > self.budget.max_total_locals {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_COUNT_BUDGET_EXCEEDED,
+                "Module exceeds its total local count budget",
+            ));
+        }
+        self.total_locals = total;
+        Ok(())
+    }
+
+    /// Check the size of a data segment
+    pub(crate) fn check_data_segment(&self, bytes: usize) -> Result<()> {
+        if bytes > self.budget.max_data_segment_bytes {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_ITEM_BUDGET_EXCEEDED,
+                "Data segment exceeds its byte budget",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the item count of an element segment
+    pub(crate) fn check_element_items(&self, items: u32) -> Result<()> {
+        if items > self.budget.max_element_items {
+            return Err(Error::parse_budget_exceeded(
+                codes::PARSE_ITEM_BUDGET_EXCEEDED,
+                "Element segment exceeds its item budget",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use wrt_error::ErrorCategory;
+
+    use super::*;
+    use crate::decoder::decode_module_with_budget;
+
+    /// Module with `types` copies of `() -> ()` and one function declaring
+    /// 3000 i32 locals
+    fn module_with_locals(types: u8) -> Vec<u8> {
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        binary.extend_from_slice(&[0x01, 1 + 3 * types, types]);
+        for _ in 0..types {
+            binary.extend_from_slice(&[0x60, 0x00, 0x00]);
+        }
+        binary.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        // One local group of 3000 (0xb8 0x17) i32 locals
+        binary.extend_from_slice(&[0x0a, 0x07, 0x01, 0x05, 0x01, 0xb8, 0x17, 0x7f, 0x0b]);
+        binary
+    }
+
+    #[test]
+    fn test_budgets_fail_with_resource_exhaustion() {
+        let binary = module_with_locals(1);
+        assert!(decode_module_with_budget(&binary, ParseBudget::unlimited()).is_ok());
+
+        let err = decode_module_with_budget(&binary, ParseBudget::untrusted()).unwrap_err();
+        assert_eq!(err.category, ErrorCategory::ResourceExhaustion);
+        assert_eq!(err.code, codes::PARSE_ITEM_BUDGET_EXCEEDED);
+
+        let budget = ParseBudget::unlimited().with_total_locals(100);
+        let err = decode_module_with_budget(&binary, budget).unwrap_err();
+        assert_eq!(err.code, codes::PARSE_COUNT_BUDGET_EXCEEDED);
+
+        let budget = ParseBudget::unlimited().with_section_bytes(10, 6);
+        let err = decode_module_with_budget(&binary, budget).unwrap_err();
+        assert_eq!(err.code, codes::PARSE_SECTION_BUDGET_EXCEEDED);
+
+        let budget = ParseBudget::unlimited().with_module_bytes(binary.len() - 1);
+        let err = decode_module_with_budget(&binary, budget).unwrap_err();
+        assert_eq!(err.code, codes::PARSE_MODULE_BUDGET_EXCEEDED);
+        assert!(err.is_resource_exhaustion_error());
+    }
+
+    #[test]
+    fn test_section_item_budget() {
+        let binary = module_with_locals(3);
+        let budget = ParseBudget::unlimited().with_section_items(1, 3);
+        assert!(decode_module_with_budget(&binary, budget).is_ok());
+
+        let budget = budget.with_section_items(1, 2);
+        let err = decode_module_with_budget(&binary, budget).unwrap_err();
+        assert_eq!(err.code, codes::PARSE_COUNT_BUDGET_EXCEEDED);
+        assert_eq!(budget.section_items(1), 2);
+        // Unknown section IDs share the custom section budget
+        assert_eq!(budget.with_section_bytes(42, 7).section_bytes(0), 7);
+    }
+}
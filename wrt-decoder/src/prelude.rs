@@ -9,49 +9,22 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
-// Don't duplicate format import since it's already in the use block above
-#[cfg(not(feature = "std"))]
-pub use core::result::Result as StdResult;
-pub use core::{
-    any::Any,
-    cmp::{Eq, Ord, PartialEq, PartialOrd},
-    convert::{From, Into, TryFrom, TryInto},
-    fmt,
-    fmt::{Debug, Display},
-    marker::PhantomData,
-    mem,
-    ops::{Deref, DerefMut},
-    slice, str,
-};
-// Re-export from alloc when the std feature is enabled
+// Shared runtime-tier prelude
+pub use wrt_prelude::runtime::*;
+
 #[cfg(feature = "std")]
 pub use alloc::{
     borrow::Cow,
-    boxed::Box,
     collections::{BTreeMap, BTreeSet},
-    format,
     rc::Rc,
-    string::{String, ToString},
-    sync::Arc,
-    vec,
-    vec::Vec,
 };
-#[cfg(feature = "std")]
 pub use core::result::Result as StdResult;
 #[cfg(feature = "std")]
 pub use std::{
-    collections::{HashMap, HashSet},
     io,
     io::{Read, Write},
-    sync::{Mutex, RwLock},
 };
 
-// Import synchronization primitives for no_std
-//#[cfg(not(feature = "std"))]
-// pub use wrt_sync::{Mutex, RwLock};
-
-// Re-export from wrt-error
-pub use wrt_error::{Error, ErrorCategory, Result, codes, kinds};
 // Re-export format module for compatibility
 pub use wrt_format as wrt_format_module;
 // Re-export from wrt-format
@@ -91,15 +64,11 @@
     // SafeMemory types
     safe_memory::{SafeMemoryHandler, SafeSlice, SafeStack},
     // Legacy types for compatibility
-    types::{BlockType, RefType, ValueType},
-    values::Value,
+    types::RefType,
 };
 // Use our unified memory management system
 #[cfg(not(feature = "std"))]
-pub use wrt_foundation::{
-    BoundedString, BoundedVec,
-    unified_types_simple::{DefaultTypes, EmbeddedTypes},
-};
+pub use wrt_foundation::unified_types_simple::{DefaultTypes, EmbeddedTypes};
 // Re-export clean types only when allocation is available
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use wrt_foundation::{
@@ -0,0 +1,854 @@
+//! Detection of post-MVP proposals used by a module
+//!
+//! Engine profiles restrict which WebAssembly proposals a module may use.
+//! [`check_proposals`] walks the raw binary before it is decoded and reports
+//! every construct that belongs to a proposal outside the allowed
+//! [`ProposalSet`]: value types and type definitions in the type section,
+//! shared and 64-bit memories, and prefixed or proposal-specific
+//! instructions in constant expressions and function bodies. Each
+//! [`ProposalUse`] names the section, function, byte offset and instruction,
+//! so a module is rejected with a precise diagnostic before it is
+//! instantiated instead of failing when the instruction is first executed.
+//!
+//! The walk only recognises proposal constructs; it does not validate the
+//! module. Bodies containing opcodes it does not know are skipped from that
+//! opcode on, leaving the error to the decoder.
+
+use core::fmt;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::binary::{
+    read_leb128_i32,
+    read_leb128_i64,
+    read_leb128_u32,
+    read_leb128_u64,
+};
+
+/// Post-MVP proposal an engine profile can allow or reject
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Proposal {
+    /// Fixed-width 128-bit SIMD
+    Simd,
+    /// Shared memories and atomic instructions
+    Threads,
+    /// `return_call` and its variants
+    TailCall,
+    /// Garbage collection, including typed function references
+    Gc,
+    /// Memories with 64-bit addresses
+    Memory64,
+}
+
+impl Proposal {
+    /// All proposals known to the checker
+    pub const ALL: [Proposal; 5] =
+        [Self::Simd, Self::Threads, Self::TailCall, Self::Gc, Self::Memory64];
+
+    /// Name of the proposal as used by toolchains and the spec repository
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Simd => "simd",
+            Self::Threads => "threads",
+            Self::TailCall => "tail-call",
+            Self::Gc => "gc",
+            Self::Memory64 => "memory64",
+        }
+    }
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Error returned for a module using this proposal while it is disabled
+    pub const fn disabled_error(self) -> Error {
+        Error::validation_unsupported_feature(match self {
+            Self::Simd => "Module uses the simd proposal, which the engine profile disables",
+            Self::Threads => "Module uses the threads proposal, which the engine profile disables",
+            Self::TailCall => {
+                "Module uses the tail-call proposal, which the engine profile disables"
+            },
+            Self::Gc => "Module uses the gc proposal, which the engine profile disables",
+            Self::Memory64 => {
+                "Module uses the memory64 proposal, which the engine profile disables"
+            },
+        })
+    }
+}
+
+impl fmt::Display for Proposal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Set of proposals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProposalSet(u8);
+
+impl ProposalSet {
+    /// No proposals: MVP and the finished proposals merged into it
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    /// All proposals known to the checker
+    pub const fn all() -> Self {
+        let mut set = Self::none();
+        let mut i = 0;
+        while i < Proposal::ALL.len() {
+            set = set.with(Proposal::ALL[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// This set with `proposal` added
+    pub const fn with(self, proposal: Proposal) -> Self {
+        Self(self.0 | proposal.bit())
+    }
+
+    /// This set with `proposal` removed
+    pub const fn without(self, proposal: Proposal) -> Self {
+        Self(self.0 & !proposal.bit())
+    }
+
+    /// Whether `proposal` is in the set
+    pub const fn contains(self, proposal: Proposal) -> bool {
+        self.0 & proposal.bit() != 0
+    }
+
+    /// Whether the set is empty
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Proposals in the set
+    pub fn iter(self) -> impl Iterator<Item = Proposal> {
+        Proposal::ALL.into_iter().filter(move |&proposal| self.contains(proposal))
+    }
+}
+
+impl fmt::Display for ProposalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none");
+        }
+        for (i, proposal) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(proposal.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// One construct of a module belonging to a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalUse {
+    /// Proposal the construct belongs to
+    pub proposal:  Proposal,
+    /// ID of the section containing the construct
+    pub section:   u8,
+    /// Index of the function whose body contains the construct, counting
+    /// imported functions
+    pub function:  Option<u32>,
+    /// Offset of the construct in the binary
+    pub offset:    usize,
+    /// Instruction or construct, e.g. `return_call` or `v128 value type`
+    pub construct: &'static str,
+    /// Prefix byte and opcode of an instruction; the prefix is the opcode
+    /// itself for unprefixed instructions
+    pub opcode:    Option<(u8, u32)>,
+}
+
+impl ProposalUse {
+    /// Name of the section containing the construct
+    pub const fn section_name(&self) -> &'static str {
+        section_name(self.section)
+    }
+}
+
+impl fmt::Display for ProposalUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.function {
+            Some(function) => write!(f, "function {function}")?,
+            None => write!(f, "{} section", self.section_name())?,
+        }
+        write!(f, " at offset {:#x}: {}", self.offset, self.construct)?;
+        match self.opcode {
+            Some((prefix, opcode)) if u32::from(prefix) == opcode => {
+                write!(f, " ({prefix:#04x})")?
+            },
+            Some((prefix, opcode)) => write!(f, " ({prefix:#04x} {opcode})")?,
+            None => {},
+        }
+        write!(f, " requires the {} proposal", self.proposal)
+    }
+}
+
+/// Maximum number of disabled uses a [`ProposalReport`] records
+pub const MAX_REPORTED_USES: usize = 8;
+
+/// Uses of disabled proposals found in a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProposalReport {
+    /// Proposals the module was checked against
+    allowed:  ProposalSet,
+    /// Disabled proposals the module uses
+    disabled: ProposalSet,
+    /// First recorded uses of disabled proposals
+    uses:     [Option<ProposalUse>; MAX_REPORTED_USES],
+    /// Number of uses of disabled proposals, including unrecorded ones
+    total:    usize,
+}
+
+impl ProposalReport {
+    const fn new(allowed: ProposalSet) -> Self {
+        Self {
+            allowed,
+            disabled: ProposalSet::none(),
+            uses: [None; MAX_REPORTED_USES],
+            total: 0,
+        }
+    }
+
+    fn record(&mut self, usage: ProposalUse) {
+        if self.allowed.contains(usage.proposal) {
+            return;
+        }
+        self.disabled = self.disabled.with(usage.proposal);
+        if let Some(slot) = self.uses.get_mut(self.total) {
+            *slot = Some(usage);
+        }
+        self.total += 1;
+    }
+
+    /// Whether the module uses only allowed proposals
+    pub const fn is_ok(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Proposals the module was checked against
+    pub const fn allowed(&self) -> ProposalSet {
+        self.allowed
+    }
+
+    /// Disabled proposals the module uses
+    pub const fn disabled(&self) -> ProposalSet {
+        self.disabled
+    }
+
+    /// First [`MAX_REPORTED_USES`] uses of disabled proposals, in binary
+    /// order
+    pub fn uses(&self) -> impl Iterator<Item = &ProposalUse> {
+        self.uses.iter().flatten()
+    }
+
+    /// Number of uses of disabled proposals, including those not recorded
+    pub const fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Fail with the error of the first disabled proposal used
+    pub fn into_result(self) -> Result<()> {
+        match self.uses().next() {
+            Some(usage) => Err(usage.proposal.disabled_error()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ProposalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "module uses no disabled proposals (allowed: {})", self.allowed);
+        }
+        write!(
+            f,
+            "module uses disabled proposals: {} (allowed: {})",
+            self.disabled, self.allowed
+        )?;
+        for usage in self.uses() {
+            write!(f, "\n  {usage}")?;
+        }
+        let unrecorded = self.total.saturating_sub(MAX_REPORTED_USES);
+        if unrecorded > 0 {
+            write!(f, "\n  ... and {unrecorded} more")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check which proposals outside `allowed` the module `binary` uses
+///
+/// Fails only if the binary is too malformed to walk; uses of disabled
+/// proposals are returned in the report.
+pub fn check_proposals(binary: &[u8], allowed: ProposalSet) -> Result<ProposalReport> {
+    let mut scanner = Scanner {
+        bytes:          binary,
+        pos:            8,
+        report:         ProposalReport::new(allowed),
+        section:        0,
+        function:       None,
+        imported_funcs: 0,
+    };
+    if binary.len() < 8 || binary[..4] != [0x00, 0x61, 0x73, 0x6d] {
+        return Err(Error::parse_error("Invalid WebAssembly magic number"));
+    }
+    scanner.scan_module()?;
+    Ok(scanner.report)
+}
+
+/// Proposals the module `binary` uses
+pub fn used_proposals(binary: &[u8]) -> Result<ProposalSet> {
+    Ok(check_proposals(binary, ProposalSet::none())?.disabled())
+}
+
+/// Name of a section ID
+const fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+/// Limits flag of shared memories
+const LIMITS_SHARED: u8 = 0x02;
+/// Limits flag of 64-bit memories and tables
+const LIMITS_64: u8 = 0x04;
+
+/// Walks a binary, recording proposal constructs
+struct Scanner<'a> {
+    bytes:          &'a [u8],
+    pos:            usize,
+    report:         ProposalReport,
+    /// Section being walked
+    section:        u8,
+    /// Function whose body is being walked
+    function:       Option<u32>,
+    /// Number of imported functions, which precede defined ones
+    imported_funcs: u32,
+}
+
+impl Scanner<'_> {
+    fn record(&mut self, proposal: Proposal, offset: usize, construct: &'static str) {
+        self.record_op(proposal, offset, construct, None);
+    }
+
+    fn record_op(
+        &mut self,
+        proposal: Proposal,
+        offset: usize,
+        construct: &'static str,
+        opcode: Option<(u8, u32)>,
+    ) {
+        self.report.record(ProposalUse {
+            proposal,
+            section: self.section,
+            function: self.function,
+            offset,
+            construct,
+            opcode,
+        });
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| Error::parse_error("Unexpected end of module"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let (value, len) = read_leb128_u32(self.bytes, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let (value, len) = read_leb128_u64(self.bytes, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    /// Signed LEB128 up to 64 bits, also used for s33 block and heap types
+    fn s64(&mut self) -> Result<i64> {
+        let (value, len) = read_leb128_i64(self.bytes, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        if self.bytes.len() - self.pos < len {
+            return Err(Error::parse_error("Unexpected end of module"));
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<()> {
+        let len = self.u32()?;
+        self.skip(len as usize)
+    }
+
+    fn scan_module(&mut self) -> Result<()> {
+        while self.pos < self.bytes.len() {
+            let id = self.byte()?;
+            let size = self.u32()? as usize;
+            let start = self.pos;
+            let end = start
+                .checked_add(size)
+                .filter(|&end| end <= self.bytes.len())
+                .ok_or_else(|| Error::parse_error("Section size exceeds module size"))?;
+            self.section = id;
+            match id {
+                1 => self.scan_types()?,
+                2 => self.scan_imports()?,
+                4 => self.scan_tables()?,
+                5 => self.scan_memories()?,
+                6 => self.scan_globals()?,
+                10 => self.scan_code(end)?,
+                _ => {},
+            }
+            self.function = None;
+            self.pos = end;
+        }
+        Ok(())
+    }
+
+    fn scan_types(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        for _ in 0..count {
+            let offset = self.pos;
+            if self.byte()? != 0x60 {
+                // Recursion groups, subtypes, structs and arrays all come
+                // from GC; their encoding is not walked further
+                self.record(Proposal::Gc, offset, "gc type definition");
+                return Ok(());
+            }
+            for _ in 0..2 {
+                let len = self.u32()?;
+                for _ in 0..len {
+                    self.value_type()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_imports(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        for _ in 0..count {
+            self.name()?;
+            self.name()?;
+            match self.byte()? {
+                0x00 => {
+                    self.u32()?;
+                    self.imported_funcs += 1;
+                },
+                0x01 => self.table_type()?,
+                0x02 => self.memory_type()?,
+                0x03 => {
+                    self.value_type()?;
+                    self.byte()?;
+                },
+                0x04 => {
+                    self.byte()?;
+                    self.u32()?;
+                },
+                _ => return Err(Error::parse_error("Invalid import kind")),
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_tables(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        for _ in 0..count {
+            let offset = self.pos;
+            if self.bytes.get(self.pos) == Some(&0x40) {
+                // Table with an initializer expression
+                self.record(Proposal::Gc, offset, "table initializer");
+                self.skip(2)?;
+                self.table_type()?;
+                self.const_expr()?;
+            } else {
+                self.table_type()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_memories(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        for _ in 0..count {
+            self.memory_type()?;
+        }
+        Ok(())
+    }
+
+    fn scan_globals(&mut self) -> Result<()> {
+        let count = self.u32()?;
+        for _ in 0..count {
+            self.value_type()?;
+            self.byte()?;
+            self.const_expr()?;
+        }
+        Ok(())
+    }
+
+    fn scan_code(&mut self, end: usize) -> Result<()> {
+        let count = self.u32()?;
+        for index in 0..count {
+            let size = self.u32()? as usize;
+            let body_end = self
+                .pos
+                .checked_add(size)
+                .filter(|&body_end| body_end <= end)
+                .ok_or_else(|| Error::parse_error("Function body exceeds code section"))?;
+            self.function = Some(self.imported_funcs.saturating_add(index));
+            let groups = self.u32()?;
+            for _ in 0..groups {
+                self.u32()?;
+                self.value_type()?;
+            }
+            while self.pos < body_end {
+                if !self.instruction()? {
+                    break;
+                }
+            }
+            self.pos = body_end;
+        }
+        Ok(())
+    }
+
+    /// Walk a constant expression up to and including its `end`
+    fn const_expr(&mut self) -> Result<()> {
+        loop {
+            if self.bytes.get(self.pos) == Some(&0x0B) {
+                self.pos += 1;
+                return Ok(());
+            }
+            if !self.instruction()? {
+                return Err(Error::parse_error("Unknown opcode in constant expression"));
+            }
+        }
+    }
+
+    fn value_type(&mut self) -> Result<()> {
+        let offset = self.pos;
+        match self.byte()? {
+            0x7F | 0x7E | 0x7D | 0x7C | 0x70 | 0x6F => {},
+            0x7B => self.record(Proposal::Simd, offset, "v128 value type"),
+            0x63 | 0x64 => {
+                self.s64()?;
+                self.record(Proposal::Gc, offset, "typed reference type");
+            },
+            _ => self.record(Proposal::Gc, offset, "gc reference type"),
+        }
+        Ok(())
+    }
+
+    fn heap_type(&mut self) -> Result<()> {
+        let offset = self.pos;
+        let heap_type = self.s64()?;
+        // funcref and externref; everything else is a GC heap type
+        if heap_type != -0x10 && heap_type != -0x11 {
+            self.record(Proposal::Gc, offset, "gc heap type");
+        }
+        Ok(())
+    }
+
+    fn block_type(&mut self) -> Result<()> {
+        match self.bytes.get(self.pos) {
+            Some(0x40) => {
+                self.pos += 1;
+                Ok(())
+            },
+            Some(0x41..=0x7F) => self.value_type(),
+            _ => self.s64().map(drop),
+        }
+    }
+
+    fn limits(&mut self) -> Result<u8> {
+        let flags = self.byte()?;
+        self.u64()?;
+        if flags & 0x01 != 0 {
+            self.u64()?;
+        }
+        Ok(flags)
+    }
+
+    fn table_type(&mut self) -> Result<()> {
+        self.value_type()?;
+        self.limits().map(drop)
+    }
+
+    fn memory_type(&mut self) -> Result<()> {
+        let offset = self.pos;
+        let flags = self.limits()?;
+        if flags & LIMITS_SHARED != 0 {
+            self.record(Proposal::Threads, offset, "shared memory");
+        }
+        if flags & LIMITS_64 != 0 {
+            self.record(Proposal::Memory64, offset, "64-bit memory");
+        }
+        Ok(())
+    }
+
+    fn mem_arg(&mut self) -> Result<()> {
+        let align = self.u32()?;
+        if align & 0x40 != 0 {
+            self.u32()?;
+        }
+        self.u64().map(drop)
+    }
+
+    /// Walk one instruction, returning false on an opcode the walk does not
+    /// know
+    fn instruction(&mut self) -> Result<bool> {
+        let offset = self.pos;
+        let opcode = self.byte()?;
+        let op = Some((opcode, u32::from(opcode)));
+        match opcode {
+            0x00 | 0x01 | 0x05 | 0x0B | 0x0F | 0x1A | 0x1B | 0xD1 => {},
+            0x45..=0xC4 => {},
+            0x02..=0x04 => self.block_type()?,
+            0x0C | 0x0D | 0x10 | 0x20..=0x26 | 0x3F | 0x40 | 0xD2 => {
+                self.u32()?;
+            },
+            0x0E => {
+                let targets = self.u32()?;
+                for _ in 0..=targets {
+                    self.u32()?;
+                }
+            },
+            0x11 => {
+                self.u32()?;
+                self.u32()?;
+            },
+            0x12 => {
+                self.record_op(Proposal::TailCall, offset, "return_call", op);
+                self.u32()?;
+            },
+            0x13 => {
+                self.record_op(Proposal::TailCall, offset, "return_call_indirect", op);
+                self.u32()?;
+                self.u32()?;
+            },
+            0x14 => {
+                self.record_op(Proposal::Gc, offset, "call_ref", op);
+                self.u32()?;
+            },
+            0x15 => {
+                self.record_op(Proposal::TailCall, offset, "return_call_ref", op);
+                self.record_op(Proposal::Gc, offset, "return_call_ref", op);
+                self.u32()?;
+            },
+            0x1C => {
+                let count = self.u32()?;
+                for _ in 0..count {
+                    self.value_type()?;
+                }
+            },
+            0x28..=0x3E => self.mem_arg()?,
+            0x41 => {
+                let (_, len) = read_leb128_i32(self.bytes, self.pos)?;
+                self.pos += len;
+            },
+            0x42 => {
+                self.s64()?;
+            },
+            0x43 => self.skip(4)?,
+            0x44 => self.skip(8)?,
+            0xD0 => self.heap_type()?,
+            0xD3 => self.record_op(Proposal::Gc, offset, "ref.eq", op),
+            0xD4 => self.record_op(Proposal::Gc, offset, "ref.as_non_null", op),
+            0xD5 | 0xD6 => {
+                let name = if opcode == 0xD5 { "br_on_null" } else { "br_on_non_null" };
+                self.record_op(Proposal::Gc, offset, name, op);
+                self.u32()?;
+            },
+            0xFC => self.misc_instruction()?,
+            0xFD => self.simd_instruction(offset)?,
+            0xFE => self.atomic_instruction(offset)?,
+            0xFB => return self.gc_instruction(offset),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn misc_instruction(&mut self) -> Result<()> {
+        match self.u32()? {
+            0..=7 => {},
+            8 | 10 | 12 | 14 => {
+                self.u32()?;
+                self.u32()?;
+            },
+            _ => {
+                self.u32()?;
+            },
+        }
+        Ok(())
+    }
+
+    fn simd_instruction(&mut self, offset: usize) -> Result<()> {
+        let opcode = self.u32()?;
+        let construct = match opcode {
+            0..=11 | 92 | 93 => {
+                self.mem_arg()?;
+                if opcode == 11 { "v128.store" } else { "v128 load" }
+            },
+            12 => {
+                self.skip(16)?;
+                "v128.const"
+            },
+            13 => {
+                self.skip(16)?;
+                "i8x16.shuffle"
+            },
+            21..=34 => {
+                self.skip(1)?;
+                "v128 lane access"
+            },
+            84..=91 => {
+                self.mem_arg()?;
+                self.skip(1)?;
+                "v128 lane load or store"
+            },
+            _ => "simd instruction",
+        };
+        self.record_op(Proposal::Simd, offset, construct, Some((0xFD, opcode)));
+        Ok(())
+    }
+
+    fn atomic_instruction(&mut self, offset: usize) -> Result<()> {
+        let opcode = self.u32()?;
+        let construct = match opcode {
+            0x00 => "memory.atomic.notify",
+            0x01 | 0x02 => "memory.atomic.wait",
+            0x03 => "atomic.fence",
+            _ => "atomic memory access",
+        };
+        if opcode == 0x03 {
+            self.skip(1)?;
+        } else {
+            self.mem_arg()?;
+        }
+        self.record_op(Proposal::Threads, offset, construct, Some((0xFE, opcode)));
+        Ok(())
+    }
+
+    fn gc_instruction(&mut self, offset: usize) -> Result<bool> {
+        let opcode = self.u32()?;
+        self.record_op(Proposal::Gc, offset, "gc instruction", Some((0xFB, opcode)));
+        match opcode {
+            // type index
+            0 | 1 | 6 | 7 | 11..=14 | 16 => {
+                self.u32()?;
+            },
+            // type index and field, length, data or element index
+            2..=5 | 8..=10 | 17..=19 => {
+                self.u32()?;
+                self.u32()?;
+            },
+            15 | 26..=30 => {},
+            20..=23 => self.heap_type()?,
+            24 | 25 => {
+                self.byte()?;
+                self.u32()?;
+                self.heap_type()?;
+                self.heap_type()?;
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn wasm(source: &str) -> alloc::vec::Vec<u8> {
+        wat::parse_str(source).unwrap()
+    }
+
+    #[test]
+    fn mvp_module_uses_no_proposals() {
+        let binary = wasm(
+            r#"(module
+                 (memory 1)
+                 (func (export "add") (param i32 i32) (result i32)
+                   (i32.add (local.get 0) (local.get 1))))"#,
+        );
+        assert!(used_proposals(&binary).unwrap().is_empty());
+        assert!(check_proposals(&binary, ProposalSet::none()).unwrap().is_ok());
+    }
+
+    #[test]
+    fn reports_disabled_instructions_with_location() {
+        let binary = wasm(
+            r#"(module
+                 (import "env" "f" (func $f))
+                 (func $g (param v128) (result v128)
+                   (i32x4.add (local.get 0) (v128.const i32x4 1 2 3 4)))
+                 (func $h (result i32)
+                   (return_call $k))
+                 (func $k (result i32) (i32.const 0)))"#,
+        );
+        assert_eq!(
+            used_proposals(&binary).unwrap(),
+            ProposalSet::none().with(Proposal::Simd).with(Proposal::TailCall)
+        );
+
+        let allowed = ProposalSet::all().without(Proposal::TailCall);
+        let report = check_proposals(&binary, allowed).unwrap();
+        assert_eq!(report.disabled(), ProposalSet::none().with(Proposal::TailCall));
+        let uses: alloc::vec::Vec<_> = report.uses().collect();
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].function, Some(2));
+        assert_eq!(uses[0].construct, "return_call");
+        assert_eq!(binary[uses[0].offset], 0x12);
+        assert!(report.into_result().is_err());
+
+        let message = alloc::format!("{}", uses[0]);
+        assert!(message.starts_with("function 2 at offset"));
+        assert!(message.ends_with("return_call (0x12) requires the tail-call proposal"));
+    }
+
+    #[test]
+    fn reports_disabled_types_and_memories() {
+        let binary = wasm(
+            r#"(module
+                 (type (struct (field i32)))
+                 (memory 1 1 shared))"#,
+        );
+        let report = check_proposals(&binary, ProposalSet::none()).unwrap();
+        assert_eq!(
+            report.disabled(),
+            ProposalSet::none().with(Proposal::Gc).with(Proposal::Threads)
+        );
+        let sections: alloc::vec::Vec<_> = report.uses().map(ProposalUse::section_name).collect();
+        assert_eq!(sections, ["type", "memory"]);
+    }
+}
@@ -372,6 +372,7 @@ pub fn parse_import_section(
                             max: mem_limits.max.map(|v| v as u32),
                         },
                         shared: mem_limits.shared,
+                        memory64: mem_limits.memory64,
                     };
                     WrtImportDesc::Memory(memory_type)
                 },
@@ -460,6 +461,7 @@ pub fn parse_memory_section(bytes: &[u8]) -> Result<BoundedMemoryVec<WrtMemoryTy
             let memory_type = WrtMemoryType {
                 limits: wrt_limits,
                 shared: limits.shared,
+                memory64: limits.memory64,
             };
 
             memories
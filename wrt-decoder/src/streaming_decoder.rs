@@ -23,9 +23,14 @@
 use wrt_foundation::{bounded::BoundedVec, safe_memory::NoStdProvider, types::TagType};
 
 use crate::{
+    parse_budget::{BudgetTracker, ParseBudget},
     prelude::*,
     streaming_validator::{ComprehensivePlatformLimits, StreamingWasmValidator},
 };
+#[cfg(feature = "std")]
+use crate::custom_section_handler::{
+    CustomSectionArtifacts, CustomSectionParsers, extract_custom_section,
+};
 
 /// Skip an LEB128-encoded unsigned integer and return the number of bytes consumed
 fn skip_leb128_u32(data: &[u8], offset: usize) -> usize {
@@ -78,6 +83,47 @@ fn skip_leb128_i64(data: &[u8], offset: usize) -> usize {
     bytes
 }
 
+/// Read the u64 limits of a memory64 memory type at `offset`, with `flags`
+/// the limits flag byte. Returns the minimum, maximum and bytes consumed.
+///
+/// Core limits count pages in u32: a maximum beyond that is clamped, since
+/// no memory can grow that far, while a minimum beyond it is rejected.
+#[cfg(feature = "memory64")]
+fn read_memory64_limits(data: &[u8], offset: usize, flags: u8) -> Result<(u32, Option<u32>, usize)> {
+    use wrt_format::{binary::read_leb128_u64, types::MEMORY64_MAX_PAGES};
+
+    let (min, mut consumed) = read_leb128_u64(data, offset)?;
+    let max = if flags & 0x01 != 0 {
+        let (max, bytes_read) = read_leb128_u64(data, offset + consumed)?;
+        consumed += bytes_read;
+        Some(max)
+    } else {
+        None
+    };
+
+    if min > MEMORY64_MAX_PAGES || max.is_some_and(|max| max > MEMORY64_MAX_PAGES) {
+        return Err(Error::validation_error(
+            "memory size must be at most 2^48 pages",
+        ));
+    }
+    let min = u32::try_from(min)
+        .map_err(|_| Error::validation_error("memory size exceeds the runtime page limit"))?;
+    let max = max.map(|max| u32::try_from(max).unwrap_or(u32::MAX));
+    Ok((min, max, consumed))
+}
+
+/// Memory64 memory types are rejected when the proposal is disabled
+#[cfg(not(feature = "memory64"))]
+fn read_memory64_limits(
+    _data: &[u8],
+    _offset: usize,
+    _flags: u8,
+) -> Result<(u32, Option<u32>, usize)> {
+    Err(Error::validation_error(
+        "memory64 memories require the memory64 feature",
+    ))
+}
+
 /// Find the end of an expression by properly parsing instructions.
 /// Returns the position AFTER the end opcode (0x0B).
 ///
@@ -159,6 +205,14 @@ pub struct StreamingDecoder<'a> {
     data_count_value: Option<u32>,
     /// Count from data section
     data_section_count: Option<u32>,
+    /// Byte and count budgets and what has been spent of them
+    budget: BudgetTracker,
+    /// Parsers for named custom sections
+    #[cfg(feature = "std")]
+    custom_parsers: Option<&'a CustomSectionParsers>,
+    /// Artifacts of the custom sections decoded so far
+    #[cfg(feature = "std")]
+    custom_artifacts: CustomSectionArtifacts,
     /// The module being built (std version)
     #[cfg(feature = "std")]
     module: WrtModule,
@@ -183,6 +237,9 @@ pub fn new(binary: &'a [u8]) -> Result<Self> {
             code_count: None,
             data_count_value: None,
             data_section_count: None,
+            budget: BudgetTracker::new(ParseBudget::unlimited()),
+            custom_parsers: None,
+            custom_artifacts: CustomSectionArtifacts::default(),
             module,
         })
     }
@@ -206,12 +263,41 @@ pub fn new(binary: &'a [u8]) -> Result<Self> {
             code_count: None,
             data_count_value: None,
             data_section_count: None,
+            budget: BudgetTracker::new(ParseBudget::unlimited()),
             module,
         })
     }
 
+    /// Create a streaming decoder enforcing `budget`
+    pub fn with_budget(binary: &'a [u8], budget: ParseBudget) -> Result<Self> {
+        let mut decoder = Self::new(binary)?;
+        decoder.budget = BudgetTracker::new(budget);
+        Ok(decoder)
+    }
+
+    /// Budgets enforced while decoding
+    pub fn budget(&self) -> &ParseBudget {
+        self.budget.budget()
+    }
+
+    /// Decode custom sections with the parsers registered for their names
+    /// as they are reached
+    #[cfg(feature = "std")]
+    pub fn with_custom_sections(mut self, parsers: &'a CustomSectionParsers) -> Self {
+        self.custom_parsers = Some(parsers);
+        self
+    }
+
+    /// Take the artifacts of the custom sections decoded so far
+    #[cfg(feature = "std")]
+    pub fn take_custom_sections(&mut self) -> CustomSectionArtifacts {
+        core::mem::take(&mut self.custom_artifacts)
+    }
+
     /// Decode the module header
     pub fn decode_header(&mut self) -> Result<()> {
+        self.budget.check_module(self.binary.len())?;
+
         // Validate magic number and version
         if self.binary.len() < 8 {
             return Err(Error::parse_error(
@@ -254,6 +340,7 @@ pub fn process_next_section(&mut self) -> Result<bool> {
 
         // Process section data without loading it all into memory
         let section_data = &self.binary[self.offset..section_end];
+        self.budget.enter_section(section_id, section_data)?;
         self.process_section(section_id, section_data)?;
 
         self.offset = section_end;
@@ -862,8 +949,6 @@ fn process_import_section(&mut self, data: &[u8]) -> Result<usize> {
                 },
                 0x02 => {
                     // Memory import - need to parse limits
-                    use wrt_format::binary::read_leb128_u64;
-
                     if offset >= data.len() {
                         return Err(Error::parse_error("Unexpected end of memory import"));
                     }
@@ -878,32 +963,9 @@ fn process_import_section(&mut self, data: &[u8]) -> Result<usize> {
 
                     // Parse limits - memory64 uses u64, regular memory uses u32
                     let (min, max) = if is_memory64 {
-                        let (min64, bytes_read) = read_leb128_u64(data, offset)?;
+                        let (min, max, bytes_read) = read_memory64_limits(data, offset, flags)?;
                         offset += bytes_read;
-
-                        let max64 = if flags & 0x01 != 0 {
-                            let (max_val, bytes_read) = read_leb128_u64(data, offset)?;
-                            offset += bytes_read;
-                            Some(max_val)
-                        } else {
-                            None
-                        };
-
-                        // Validate memory64 limits
-                        if min64 > MAX_MEMORY_PAGES as u64 {
-                            return Err(Error::validation_error(
-                                "memory size must be at most 65536 pages (4 GiB)",
-                            ));
-                        }
-                        if let Some(max64) = max64 {
-                            if max64 > MAX_MEMORY_PAGES as u64 {
-                                return Err(Error::validation_error(
-                                    "memory size must be at most 65536 pages (4 GiB)",
-                                ));
-                            }
-                        }
-
-                        (min64 as u32, max64.map(|v| v as u32))
+                        (min, max)
                     } else {
                         let (min, bytes_read) = read_leb128_u32(data, offset)?;
                         offset += bytes_read;
@@ -946,6 +1008,7 @@ fn process_import_section(&mut self, data: &[u8]) -> Result<usize> {
                         let memory_type = MemoryType {
                             limits,
                             shared: flags & 0x02 != 0, // bit 1 = shared
+                            memory64: is_memory64,
                         };
 
                         let import = Import {
@@ -1279,7 +1342,7 @@ fn process_table_section(&mut self, data: &[u8]) -> Result<usize> {
 
     /// Process memory section
     fn process_memory_section(&mut self, data: &[u8]) -> Result<usize> {
-        use wrt_format::{read_leb128_u32, read_leb128_u64};
+        use wrt_format::read_leb128_u32;
 
         let mut offset = 0;
         let (count, bytes_read) = read_leb128_u32(data, offset)?;
@@ -1315,33 +1378,9 @@ fn process_memory_section(&mut self, data: &[u8]) -> Result<usize> {
 
             // Parse limits - memory64 uses u64, regular memory uses u32
             let (min, max) = if is_memory64 {
-                let (min64, bytes_read) = read_leb128_u64(data, offset)?;
+                let (min, max, bytes_read) = read_memory64_limits(data, offset, flags)?;
                 offset += bytes_read;
-
-                let max64 = if flags & 0x01 != 0 {
-                    let (max_val, bytes_read) = read_leb128_u64(data, offset)?;
-                    offset += bytes_read;
-                    Some(max_val)
-                } else {
-                    None
-                };
-
-                // Validate memory64 limits (still have a limit, though higher)
-                // For non-memory64 tests, values > 65536 pages should fail
-                if min64 > MAX_MEMORY_PAGES as u64 {
-                    return Err(Error::validation_error(
-                        "memory size must be at most 65536 pages (4 GiB)",
-                    ));
-                }
-                if let Some(max64) = max64 {
-                    if max64 > MAX_MEMORY_PAGES as u64 {
-                        return Err(Error::validation_error(
-                            "memory size must be at most 65536 pages (4 GiB)",
-                        ));
-                    }
-                }
-
-                (min64 as u32, max64.map(|v| v as u32))
+                (min, max)
             } else {
                 let (min, bytes_read) = read_leb128_u32(data, offset)?;
                 offset += bytes_read;
@@ -1363,23 +1402,26 @@ fn process_memory_section(&mut self, data: &[u8]) -> Result<usize> {
                 return Err(Error::validation_error("shared memory must have maximum"));
             }
 
-            if min > MAX_MEMORY_PAGES {
-                return Err(Error::validation_error(
-                    "memory size must be at most 65536 pages (4 GiB)",
-                ));
-            }
-            if let Some(max_val) = max {
-                if max_val > MAX_MEMORY_PAGES {
+            if !is_memory64 {
+                if min > MAX_MEMORY_PAGES {
                     return Err(Error::validation_error(
                         "memory size must be at most 65536 pages (4 GiB)",
                     ));
                 }
+                if let Some(max_val) = max {
+                    if max_val > MAX_MEMORY_PAGES {
+                        return Err(Error::validation_error(
+                            "memory size must be at most 65536 pages (4 GiB)",
+                        ));
+                    }
+                }
             }
 
             // Create memory type
             let memory_type = wrt_foundation::types::MemoryType {
                 limits: wrt_foundation::types::Limits { min, max },
                 shared,
+                memory64: is_memory64,
             };
 
             // Add to module
@@ -1865,6 +1907,7 @@ fn process_element_section(&mut self, data: &[u8]) -> Result<usize> {
                     "Element segment exceeds maximum item count for platform",
                 ));
             }
+            self.budget.check_element_items(item_count)?;
 
             #[cfg(feature = "tracing")]
             trace!(
@@ -1992,6 +2035,7 @@ fn process_code_section(&mut self, data: &[u8]) -> Result<usize> {
                     "Function body exceeds maximum code size for platform",
                 ));
             }
+            self.budget.check_function_body(body_size as usize)?;
 
             let body_start = offset;
             let body_end = offset + body_size as usize;
@@ -2047,6 +2091,7 @@ fn process_code_section(&mut self, data: &[u8]) -> Result<usize> {
                             "Function exceeds maximum local count for platform",
                         ));
                     }
+                    self.budget.add_locals(new_total, count as usize)?;
 
                     #[cfg(feature = "allocation-tracing")]
                     trace_alloc!(
@@ -2164,6 +2209,7 @@ fn process_data_section(&mut self, data: &[u8]) -> Result<usize> {
                     // Parse data byte count and data
                     let (data_len, bytes_read) = read_leb128_u32(data, offset)?;
                     offset += bytes_read;
+                    self.budget.check_data_segment(data_len as usize)?;
 
                     if offset + data_len as usize > data.len() {
                         return Err(Error::parse_error("Data segment data exceeds bounds"));
@@ -2195,6 +2241,7 @@ fn process_data_section(&mut self, data: &[u8]) -> Result<usize> {
                     // Parse data byte count and data
                     let (data_len, bytes_read) = read_leb128_u32(data, offset)?;
                     offset += bytes_read;
+                    self.budget.check_data_segment(data_len as usize)?;
 
                     if offset + data_len as usize > data.len() {
                         return Err(Error::parse_error("Data segment data exceeds bounds"));
@@ -2253,6 +2300,7 @@ fn process_data_section(&mut self, data: &[u8]) -> Result<usize> {
                     // Parse data byte count and data
                     let (data_len, bytes_read) = read_leb128_u32(data, offset)?;
                     offset += bytes_read;
+                    self.budget.check_data_segment(data_len as usize)?;
 
                     if offset + data_len as usize > data.len() {
                         return Err(Error::parse_error("Data segment data exceeds bounds"));
@@ -2369,7 +2417,14 @@ fn process_data_count_section(&mut self, data: &[u8]) -> Result<usize> {
     /// Process custom section
     /// Returns the number of bytes consumed (entire section for custom sections).
     fn process_custom_section(&mut self, data: &[u8]) -> Result<usize> {
-        // Custom sections are skipped - consume all bytes
+        // Custom sections without a registered parser are skipped
+        #[cfg(feature = "std")]
+        if let Some(parsers) = self.custom_parsers.filter(|parsers| !parsers.is_empty()) {
+            let (name, payload) = extract_custom_section(data)?;
+            if let Some(artifact) = parsers.parse(&name, payload) {
+                self.custom_artifacts.push(name, artifact?);
+            }
+        }
         Ok(data.len())
     }
 
@@ -2467,6 +2522,41 @@ pub fn decode_module_streaming(binary: &[u8]) -> Result<WrtModule> {
     // Scope drops here, memory available for reuse
 }
 
+/// Decode a WebAssembly module using streaming processing, enforcing
+/// `budget` (std version)
+#[cfg(feature = "std")]
+pub fn decode_module_streaming_with_budget(
+    binary: &[u8],
+    budget: ParseBudget,
+) -> Result<WrtModule> {
+    let _scope = wrt_foundation::capabilities::MemoryFactory::enter_module_scope(
+        wrt_foundation::budget_aware_provider::CrateId::Decoder,
+    )?;
+
+    let mut decoder = StreamingDecoder::with_budget(binary, budget)?;
+    decoder.decode_header()?;
+    while decoder.process_next_section()? {}
+    decoder.finish()
+}
+
+/// Decode a WebAssembly module using streaming processing, decoding custom
+/// sections with `parsers` along the way (std version)
+#[cfg(feature = "std")]
+pub fn decode_module_streaming_with_custom_sections(
+    binary: &[u8],
+    parsers: &CustomSectionParsers,
+) -> Result<(WrtModule, CustomSectionArtifacts)> {
+    let _scope = wrt_foundation::capabilities::MemoryFactory::enter_module_scope(
+        wrt_foundation::budget_aware_provider::CrateId::Decoder,
+    )?;
+
+    let mut decoder = StreamingDecoder::new(binary)?.with_custom_sections(parsers);
+    decoder.decode_header()?;
+    while decoder.process_next_section()? {}
+    let artifacts = decoder.take_custom_sections();
+    Ok((decoder.finish()?, artifacts))
+}
+
 /// Decode a WebAssembly module using streaming processing (no_std version)
 #[cfg(not(feature = "std"))]
 pub fn decode_module_streaming(binary: &[u8]) -> Result<WrtModule<NoStdProvider<8192>>> {
@@ -2483,3 +2573,23 @@ pub fn decode_module_streaming(binary: &[u8]) -> Result<WrtModule<NoStdProvider<
     // Return the completed module
     decoder.finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 64-bit memory only decodes when the memory64 feature is enabled
+    #[test]
+    fn test_memory64_memory_requires_feature() {
+        let wasm = wat::parse_str("(module (memory i64 1 4))").unwrap();
+        let result = decode_module_streaming(&wasm);
+
+        #[cfg(feature = "memory64")]
+        assert!(result.is_ok());
+        #[cfg(not(feature = "memory64"))]
+        assert_eq!(
+            result.err().map(|error| error.message()),
+            Some("memory64 memories require the memory64 feature")
+        );
+    }
+}
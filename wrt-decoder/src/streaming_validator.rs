@@ -11,6 +11,8 @@
     verification::Checksum,
 };
 
+use crate::complexity_policy::ComplexityLimits;
+
 #[cfg(feature = "std")]
 extern crate std;
 
@@ -291,6 +293,8 @@ pub struct StreamingWasmValidator {
     requirements: WasmRequirements,
     /// Validation state
     state: ValidationState,
+    /// Complexity caps of the deployment's ASIL level, if any
+    complexity_limits: Option<ComplexityLimits>,
 }
 
 /// Validation state tracking
@@ -313,9 +317,20 @@ pub fn new(platform_limits: ComprehensivePlatformLimits) -> Self {
             platform_limits,
             requirements: WasmRequirements::default(),
             state: ValidationState::Header,
+            complexity_limits: None,
         }
     }
 
+    /// Also enforce per-ASIL complexity caps
+    ///
+    /// Function count, function body size, locals and memory size are
+    /// checked while the module is parsed. The call depth cap needs the
+    /// call graph and is left to the runtime's static analysis.
+    pub fn with_complexity_limits(mut self, limits: ComplexityLimits) -> Self {
+        self.complexity_limits = Some(limits);
+        self
+    }
+
     /// Validate WebAssembly module in single pass with immediate limit checking
     pub fn validate_single_pass(&mut self, wasm_bytes: &[u8]) -> Result<WasmConfiguration, Error> {
         // Reset state
@@ -477,7 +492,10 @@ fn parse_code_section(&self, section_data: &[u8]) -> Result<Section, Error> {
             }));
         }
 
-        let (function_count, _) = self.read_leb128_u32(section_data)?;
+        let (function_count, size_bytes) = self.read_leb128_u32(section_data)?;
+        if let Some(limits) = &self.complexity_limits {
+            self.check_function_bodies(limits, function_count, &section_data[size_bytes..])?;
+        }
 
         // Estimate stack usage based on function count
         // This is a simplified heuristic - real implementation would analyze function
@@ -490,6 +508,45 @@ fn parse_code_section(&self, section_data: &[u8]) -> Result<Section, Error> {
         }))
     }
 
+    /// Check the size and locals of every function body against `limits`
+    fn check_function_bodies(
+        &self,
+        limits: &ComplexityLimits,
+        function_count: u32,
+        bodies: &[u8],
+    ) -> Result<(), Error> {
+        limits.check_functions(function_count)?;
+
+        let mut offset = 0;
+        for _ in 0..function_count {
+            let (body_size, size_bytes) = self.read_leb128_u32(&bodies[offset..])?;
+            offset += size_bytes;
+            limits.check_body_size(body_size)?;
+            let body = bodies
+                .get(offset..offset + body_size as usize)
+                .ok_or_else(|| Error::parse_error("Function body extends beyond code section"))?;
+            offset += body_size as usize;
+
+            let (decl_count, mut pos) = self.read_leb128_u32(body)?;
+            let mut locals = 0u32;
+            for _ in 0..decl_count {
+                let (count, count_bytes) = self.read_leb128_u32(&body[pos..])?;
+                pos += count_bytes;
+                locals = locals.saturating_add(count);
+                limits.check_locals(locals)?;
+
+                // Value type, with a heap type for typed references
+                let value_type =
+                    *body.get(pos).ok_or_else(|| Error::parse_error("Truncated local type"))?;
+                pos += 1;
+                if matches!(value_type, 0x63 | 0x64) {
+                    pos += self.read_leb128_u32(&body[pos..])?.1;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Validate individual section against platform limits
     fn validate_section(&mut self, section: &Section) -> Result<(), Error> {
         match section {
@@ -499,6 +556,11 @@ fn validate_section(&mut self, section: &Section) -> Result<(), Error> {
                 if required > self.platform_limits.max_wasm_linear_memory {
                     return Err(Error::resource_exhausted("Memory limit exceeded"));
                 }
+                if let Some(limits) = &self.complexity_limits {
+                    // Memories without a maximum are bounded by the platform
+                    // limits when they grow
+                    limits.check_memory_pages(mem.maximum.unwrap_or(mem.initial))?;
+                }
 
                 self.requirements.required_memory = required;
             },
@@ -706,4 +768,34 @@ fn test_requirements_validation() {
         let section = Section::Memory(large_memory);
         assert!(validator.validate_section(&section).is_err());
     }
+
+    #[test]
+    fn test_complexity_limits() {
+        use wrt_foundation::safety_system::AsilLevel;
+
+        // One function declaring 200 i32 locals, one memory of up to 512 pages
+        let module = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x05, 0x05, 0x01, 0x01, 0x01, 0x80, 0x04, // memory section
+            0x0A, 0x07, 0x01, 0x05, 0x01, 0xC8, 0x01, 0x7F, 0x0B, // code section
+        ];
+        let validator = |asil| {
+            StreamingWasmValidator::new(ComprehensivePlatformLimits::default())
+                .with_complexity_limits(ComplexityLimits::for_asil(asil))
+        };
+
+        assert!(validator(AsilLevel::QM).validate_single_pass(&module).is_ok());
+        assert!(validator(AsilLevel::AsilD).validate_single_pass(&module).is_err());
+
+        let mut limits = ComplexityLimits::for_asil(AsilLevel::AsilD);
+        limits.max_locals = 200;
+        let mut relaxed = StreamingWasmValidator::new(ComprehensivePlatformLimits::default())
+            .with_complexity_limits(limits);
+        assert!(relaxed.validate_single_pass(&module).is_err());
+        limits.max_memory_pages = 512;
+        relaxed = relaxed.with_complexity_limits(limits);
+        assert!(relaxed.validate_single_pass(&module).is_ok());
+    }
 }
@@ -0,0 +1,157 @@
+//! Tests of the bounded-memory component stream parser against components
+//! produced by `wat`
+
+use wrt_error::Result;
+use wrt_format::streaming::{
+    AliasTarget,
+    Canon,
+    CanonEncoding,
+    ComponentEvent,
+    ComponentSectionKind,
+    ComponentSort,
+    ComponentStreamParser,
+    ComponentTypeKind,
+    CoreInstanceExpr,
+    InstanceExpr,
+    SectionItems,
+};
+
+const COMPONENT: &str = r#"
+(component
+  (type $greet (func (param "name" string) (result string)))
+  (type (record (field "x" u32) (field "y" (list u8))))
+  (type (variant (case "a") (case "b" s64)))
+  (type (instance (export "f" (func (param "a" u32)))))
+  (core module $m
+    (memory (export "mem") 1)
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32) i32.const 0)
+    (func (export "greet") (param i32 i32) (result i32) i32.const 0))
+  (core instance $i (instantiate $m))
+  (alias core export $i "mem" (core memory $mem))
+  (alias core export $i "realloc" (core func $realloc))
+  (alias core export $i "greet" (core func $core_greet))
+  (func $f (type $greet)
+    (canon lift (core func $core_greet) (memory $mem) (realloc $realloc) string-encoding=utf16))
+  (component $inner
+    (import "g" (func $g))
+    (export "h" (func $g)))
+  (instance $n (instantiate $inner (with "g" (func $f))))
+  (export "greet" (func $f))
+)
+"#;
+
+/// Events of a streamed component with their section contents copied out
+#[derive(Debug, Default)]
+struct Streamed {
+    starts:   Vec<usize>,
+    ends:     Vec<usize>,
+    module:   Vec<u8>,
+    sections: Vec<(usize, ComponentSectionKind, Vec<u8>)>,
+}
+
+fn stream(bytes: &[u8], chunk_size: usize) -> Result<Streamed> {
+    let mut parser = ComponentStreamParser::<512>::new();
+    let mut streamed = Streamed::default();
+    for chunk in bytes.chunks(chunk_size) {
+        let mut input = chunk;
+        loop {
+            let (consumed, event) = parser.feed(input)?;
+            input = &input[consumed..];
+            match event {
+                ComponentEvent::NeedMoreData => break,
+                ComponentEvent::ComponentStart { depth, .. } => streamed.starts.push(depth),
+                ComponentEvent::ComponentEnd { depth } => streamed.ends.push(depth),
+                ComponentEvent::CoreModule { data, .. } => streamed.module.extend_from_slice(data),
+                ComponentEvent::Section { depth, kind, data } => {
+                    streamed.sections.push((depth, kind, data.to_vec()))
+                },
+                ComponentEvent::SkippedCustom { .. } => {},
+            }
+        }
+    }
+    parser.finish()?;
+    Ok(streamed)
+}
+
+fn section(streamed: &Streamed, depth: usize, kind: ComponentSectionKind) -> &[u8] {
+    &streamed
+        .sections
+        .iter()
+        .find(|(d, k, _)| *d == depth && *k == kind)
+        .unwrap_or_else(|| panic!("no {kind:?} section at depth {depth}"))
+        .2
+}
+
+#[test]
+fn test_stream_wat_component_in_any_chunk_size() -> Result<()> {
+    let bytes = wat::parse_str(COMPONENT).expect("valid component");
+    let whole = stream(&bytes, bytes.len())?;
+
+    assert_eq!(whole.starts, [0, 1]);
+    assert_eq!(whole.ends, [1]);
+    assert!(whole.module.starts_with(&[0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]));
+
+    for chunk_size in [1, 2, 3, 7, 64] {
+        let chunked = stream(&bytes, chunk_size)?;
+        assert_eq!(chunked.module, whole.module);
+        assert_eq!(chunked.sections, whole.sections);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_read_items_of_wat_component() -> Result<()> {
+    let bytes = wat::parse_str(COMPONENT).expect("valid component");
+    let streamed = stream(&bytes, 5)?;
+
+    let types = SectionItems::types(section(&streamed, 0, ComponentSectionKind::Type))?
+        .map(|ty| ty.map(|ty| ty.kind))
+        .collect::<Result<Vec<_>>>()?;
+    // The inline list type is hoisted into its own definition
+    assert_eq!(
+        types,
+        [
+            ComponentTypeKind::Func,
+            ComponentTypeKind::Defined,
+            ComponentTypeKind::Defined,
+            ComponentTypeKind::Defined,
+            ComponentTypeKind::Instance,
+        ]
+    );
+
+    let mut core_instances =
+        SectionItems::core_instances(section(&streamed, 0, ComponentSectionKind::CoreInstance))?;
+    assert!(matches!(
+        core_instances.next(),
+        Some(Ok(CoreInstanceExpr::Instantiate { module: 0, .. }))
+    ));
+
+    let aliases = SectionItems::aliases(section(&streamed, 0, ComponentSectionKind::Alias))?
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(aliases.len(), 3);
+    assert_eq!(aliases[0].sort, ComponentSort::CoreMemory);
+    assert_eq!(aliases[2].target, AliasTarget::CoreExport { instance: 0, name: "greet" });
+
+    let canons = SectionItems::canons(section(&streamed, 0, ComponentSectionKind::Canon))?
+        .collect::<Result<Vec<_>>>()?;
+    match canons[..] {
+        [Canon::Lift { core_func: 1, type_index: 0, options }] => {
+            assert_eq!(options.memory, Some(0));
+            assert_eq!(options.realloc, Some(0));
+            assert_eq!(options.encoding, CanonEncoding::Utf16);
+        },
+        _ => panic!("unexpected canonical functions {canons:?}"),
+    }
+
+    let instance = SectionItems::instances(section(&streamed, 0, ComponentSectionKind::Instance))?
+        .next()
+        .unwrap()?;
+    match instance {
+        InstanceExpr::Instantiate { component: 0, args } => {
+            let args: Vec<_> = args.map(|arg| (arg.name, arg.sort, arg.index)).collect();
+            assert_eq!(args, [("g", ComponentSort::Func, 0)]);
+        },
+        other => panic!("unexpected instance {other:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Round trips of decoded modules through the `wrt-format` encoder
+
+use wrt_decoder::decoder::decode_module;
+use wrt_format::encode::{
+    encode_module,
+    ModuleEncoder,
+};
+
+/// Written without names, so the binary has no `name` section, which the
+/// decoder does not keep
+const MODULE: &str = r#"
+(module
+  (import "env" "log" (func (param i32)))
+  (memory (export "memory") 1 2)
+  (table 2 funcref)
+  (global (mut i32) (i32.const 11))
+  (elem (i32.const 0) 1 0)
+  (data (i32.const 16) "hello")
+  (func (export "run") (param i32) (result i32)
+    (local i64 i64 f32)
+    local.get 0
+    global.get 0
+    i32.add
+    call 0
+    i32.const 0
+    i32.load offset=16)
+)
+"#;
+
+#[test]
+fn test_decoded_module_encodes_to_original_binary() {
+    let binary = wat::parse_str(MODULE).unwrap();
+    let module = decode_module(&binary).unwrap();
+    let encoded = encode_module(&module).unwrap();
+    assert_eq!(encoded, binary);
+
+    let again = encode_module(&decode_module(&encoded).unwrap()).unwrap();
+    assert_eq!(again, encoded);
+}
+
+#[test]
+fn test_custom_sections_are_injected_and_stripped() {
+    let binary = wat::parse_str(MODULE).unwrap();
+    let module = decode_module(&binary).unwrap();
+    let tagged = ModuleEncoder::new(&module)
+        .with_custom_section("build-id", vec![0xAB; 4])
+        .encode()
+        .unwrap();
+    assert_eq!(tagged.len(), binary.len() + 15);
+    assert!(decode_module(&tagged).is_ok());
+
+    let decoded = decode_module(&tagged).unwrap();
+    let stripped = ModuleEncoder::new(&decoded).strip_custom_sections().encode().unwrap();
+    assert_eq!(stripped, binary);
+}
@@ -0,0 +1,100 @@
+//! Round trips between the `wrt-format` text format and binary modules
+
+use wrt_decoder::decoder::decode_module;
+use wrt_format::{
+    encode::encode_module,
+    text::{
+        assemble_module,
+        parse_module,
+        print_module,
+        write_binary_module,
+    },
+};
+use wrt_foundation::collections::StaticVec;
+
+const MODULE: &str = r#"
+(module $demo
+  (type $binop (func (param i32 i32) (result i32)))
+  (import "env" "log" (func $log (param i32)))
+  (import "env" "base" (global $base i32))
+  (memory $mem (export "memory") 1 2)
+  (table $table 3 funcref)
+  (global $counter (mut i32) (i32.const 11))
+  (global $pi f64 (f64.const 3.141592653589793))
+  (global $half f32 (f32.const 0x1p-1))
+  (elem (i32.const 0) $add $run)
+  (elem $lazy func $add)
+  (elem declare func $run)
+  (data (i32.const 16) "hello\00\ff\u{263a}")
+  (data $bulk "passive")
+  (func $add (type $binop) (param $a i32) (param $b i32) (result i32)
+    (i32.add (local.get $a) (local.get $b)))
+  (func $run (export "run") (param $n i32) (result i32)
+    (local $acc i32) (local i64 f32)
+    (block $done
+      (loop $again
+        (br_if $done (i32.eqz (local.get $n)))
+        (local.set $acc
+          (call $add (local.get $acc) (local.get $n)))
+        (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+        (br $again)))
+    (if (result i32) (i32.gt_s (local.get $acc) (i32.const 100))
+      (then (i32.const 100))
+      (else (local.get $acc)))
+    global.get $base
+    i32.add
+    i32.const 0
+    i32.load8_u offset=16 align=1
+    drop
+    (call $log (i32.const -1))
+    (global.set $counter (i32.const 0x7fff_ffff))
+    (memory.init $bulk (i32.const 0) (i32.const 0) (i32.const 4))
+    (data.drop $bulk)
+    (table.init $lazy (i32.const 2) (i32.const 0) (i32.const 1))
+    (drop (i32.trunc_sat_f64_s (f64.const -inf)))
+    (drop (i64.extend32_s (i64.const -9223372036854775808)))
+    (drop (ref.is_null (ref.func $run)))
+    (drop (f32.const nan:0x200000))
+    (call_indirect (type $binop) (i32.const 1) (i32.const 2) (i32.const 0))
+    (br_table 0 0 (i32.const 0))
+  )
+  (start $main)
+  (func $main)
+)
+"#;
+
+#[test]
+fn test_parsed_text_encodes_like_reference_assembler() {
+    let parsed = parse_module(MODULE).unwrap();
+    let reference = decode_module(&wat::parse_str(MODULE).unwrap()).unwrap();
+    assert_eq!(encode_module(&parsed).unwrap(), encode_module(&reference).unwrap());
+}
+
+#[test]
+fn test_printed_module_parses_back() {
+    let module = decode_module(&wat::parse_str(MODULE).unwrap()).unwrap();
+    let binary = encode_module(&module).unwrap();
+    let text = print_module(&module).unwrap();
+
+    let reparsed = parse_module(&text).unwrap();
+    assert_eq!(encode_module(&reparsed).unwrap(), binary);
+    assert_eq!(print_module(&reparsed).unwrap(), text);
+    // The reference assembler agrees with the printed text
+    assert_eq!(decode_module(&wat::parse_str(&text).unwrap()).unwrap().types, module.types);
+}
+
+#[test]
+fn test_bounded_assembler_matches_encoder() {
+    let mut binary = StaticVec::<u8, 4096>::new();
+    assemble_module(MODULE, &mut binary).unwrap();
+    let encoded = encode_module(&parse_module(MODULE).unwrap()).unwrap();
+    assert_eq!(binary.as_slice(), &encoded[..]);
+
+    let mut text = String::new();
+    write_binary_module(&mut text, binary.as_slice()).unwrap();
+    assert_eq!(text, print_module(&parse_module(MODULE).unwrap()).unwrap());
+
+    let mut small = StaticVec::<u8, 64>::new();
+    assert!(assemble_module(MODULE, &mut small).is_err());
+    assert!(small.is_empty());
+}
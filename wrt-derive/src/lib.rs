@@ -0,0 +1,429 @@
+// WRT - wrt-derive
+// Module: Derive macros for wrt-foundation traits
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Derive macros for `Checksummable`, `ToBytes` and `FromBytes`.
+//!
+//! The macros are re-exported by `wrt-foundation`; use them from there:
+//!
+//! ```ignore
+//! use wrt_foundation::{Checksummable, FromBytes, ToBytes};
+//!
+//! #[derive(Checksummable, ToBytes, FromBytes)]
+//! enum Edit {
+//!     None,
+//!     #[wrt(tag = 4)]
+//!     Remove { offset: u32, length: u32 },
+//! }
+//! ```
+//!
+//! # Encoding
+//!
+//! - Structs encode their fields in declaration order.
+//! - Enums encode a one byte tag followed by the variant's fields in
+//!   declaration order. Tags follow the rules of Rust discriminants: the first
+//!   variant is `0` and every other variant is the previous tag plus one,
+//!   unless set explicitly with `#[wrt(tag = N)]`. Pin tags explicitly on any
+//!   enum whose encoding is persisted, so reordering variants cannot change
+//!   it.
+//!
+//! The checksum covers exactly the encoded bytes in the same order, so the
+//! three derived impls always agree with each other.
+
+use proc_macro::TokenStream;
+use proc_macro2::{
+    Span,
+    TokenStream as TokenStream2,
+};
+use quote::{
+    format_ident,
+    quote,
+};
+use syn::{
+    parse_macro_input,
+    parse_quote,
+    spanned::Spanned,
+    Data,
+    DeriveInput,
+    Fields,
+    Ident,
+    LitInt,
+    LitStr,
+    Type,
+};
+
+/// Derive `Checksummable`, feeding the enum tag and all fields to the
+/// checksum in encoding order
+#[proc_macro_derive(Checksummable, attributes(wrt))]
+pub fn derive_checksummable(input: TokenStream) -> TokenStream {
+    expand(input, expand_checksummable)
+}
+
+/// Derive `ToBytes`, writing the enum tag followed by all fields
+#[proc_macro_derive(ToBytes, attributes(wrt))]
+pub fn derive_to_bytes(input: TokenStream) -> TokenStream {
+    expand(input, expand_to_bytes)
+}
+
+/// Derive `FromBytes`, reading the layout written by the `ToBytes` derive
+#[proc_macro_derive(FromBytes, attributes(wrt))]
+pub fn derive_from_bytes(input: TokenStream) -> TokenStream {
+    expand(input, expand_from_bytes)
+}
+
+fn expand(input: TokenStream, f: fn(&Item) -> TokenStream2) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Item::from_input(&input) {
+        Ok(item) => f(&item).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Fields of a struct or variant with the bindings used to access them
+struct FieldSet {
+    kind:     FieldKind,
+    names:    Vec<Option<Ident>>,
+    types:    Vec<Type>,
+    bindings: Vec<Ident>,
+}
+
+enum FieldKind {
+    Named,
+    Unnamed,
+    Unit,
+}
+
+impl FieldSet {
+    fn new(fields: &Fields) -> syn::Result<Self> {
+        reject_wrt_attrs(fields.iter().flat_map(|field| &field.attrs))?;
+        let kind = match fields {
+            Fields::Named(_) => FieldKind::Named,
+            Fields::Unnamed(_) => FieldKind::Unnamed,
+            Fields::Unit => FieldKind::Unit,
+        };
+        Ok(Self {
+            kind,
+            names: fields.iter().map(|field| field.ident.clone()).collect(),
+            types: fields.iter().map(|field| field.ty.clone()).collect(),
+            bindings: (0..fields.len()).map(|i| format_ident!("__wrt_field_{}", i)).collect(),
+        })
+    }
+
+    /// Shape the given per-field tokens like the field list, e.g.
+    /// `{ a: x, b: y }` or `(x, y)`
+    fn shape(&self, values: &[TokenStream2]) -> TokenStream2 {
+        match self.kind {
+            FieldKind::Named => {
+                let names = self.names.iter().flatten();
+                quote! { { #(#names: #values),* } }
+            },
+            FieldKind::Unnamed => quote! { ( #(#values),* ) },
+            FieldKind::Unit => quote! {},
+        }
+    }
+
+    /// Pattern binding every field to its binding identifier
+    fn pattern(&self) -> TokenStream2 {
+        let bindings: Vec<_> = self.bindings.iter().map(|binding| quote! { #binding }).collect();
+        self.shape(&bindings)
+    }
+
+    /// `Option<usize>` expression for the fixed encoded size of the fields
+    fn fixed_size(&self) -> TokenStream2 {
+        let types = &self.types;
+        quote! {
+            ::wrt_foundation::derive_support::fixed_size_sum(&[
+                #(<#types as ::wrt_foundation::traits::ToBytes>::FIXED_SERIALIZED_SIZE),*
+            ])
+        }
+    }
+}
+
+struct Variant {
+    ident:  Ident,
+    tag:    u8,
+    fields: FieldSet,
+}
+
+enum Body {
+    Struct(FieldSet),
+    Enum(Vec<Variant>),
+}
+
+struct Item<'a> {
+    input: &'a DeriveInput,
+    body:  Body,
+}
+
+impl<'a> Item<'a> {
+    fn from_input(input: &'a DeriveInput) -> syn::Result<Self> {
+        reject_wrt_attrs(&input.attrs)?;
+        let body = match &input.data {
+            Data::Struct(data) => Body::Struct(FieldSet::new(&data.fields)?),
+            Data::Enum(data) => {
+                let mut variants: Vec<Variant> = Vec::with_capacity(data.variants.len());
+                let mut next_tag = Some(0u8);
+                for variant in &data.variants {
+                    let tag = match explicit_tag(&variant.attrs)? {
+                        Some(tag) => tag,
+                        None => next_tag.ok_or_else(|| {
+                            syn::Error::new(variant.span(), "enum tag overflows u8; set #[wrt(tag = N)]")
+                        })?,
+                    };
+                    if let Some(other) = variants.iter().find(|other| other.tag == tag) {
+                        return Err(syn::Error::new(
+                            variant.span(),
+                            format!("tag {} is already used by variant `{}`", tag, other.ident),
+                        ));
+                    }
+                    next_tag = tag.checked_add(1);
+                    variants.push(Variant {
+                        ident: variant.ident.clone(),
+                        tag,
+                        fields: FieldSet::new(&variant.fields)?,
+                    });
+                }
+                Body::Enum(variants)
+            },
+            Data::Union(data) => {
+                return Err(syn::Error::new(
+                    data.union_token.span,
+                    "wrt derives are not supported for unions",
+                ));
+            },
+        };
+        Ok(Self { input, body })
+    }
+
+    /// `impl` header tokens with `bound` added to every type parameter
+    fn impl_header(&self, bound: TokenStream2) -> (TokenStream2, TokenStream2, TokenStream2) {
+        let mut generics = self.input.generics.clone();
+        for param in generics.type_params_mut() {
+            param.bounds.push(parse_quote!(#bound));
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        (quote! { #impl_generics }, quote! { #ty_generics }, quote! { #where_clause })
+    }
+}
+
+/// Parse `#[wrt(tag = N)]` from a variant's attributes
+fn explicit_tag(attrs: &[syn::Attribute]) -> syn::Result<Option<u8>> {
+    let mut tag = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("wrt")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                let lit: LitInt = meta.value()?.parse()?;
+                tag = Some(lit.base10_parse::<u8>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported wrt attribute; expected `tag = N`"))
+            }
+        })?;
+    }
+    Ok(tag)
+}
+
+/// `#[wrt(...)]` is only meaningful on enum variants
+fn reject_wrt_attrs<'a>(attrs: impl IntoIterator<Item = &'a syn::Attribute>) -> syn::Result<()> {
+    match attrs.into_iter().find(|attr| attr.path().is_ident("wrt")) {
+        Some(attr) => Err(syn::Error::new(attr.span(), "#[wrt(...)] is only supported on enum variants")),
+        None => Ok(()),
+    }
+}
+
+/// `match` over `self`; an enum without variants has no value to borrow
+fn match_variants(variants: &[Variant], arms: TokenStream2) -> TokenStream2 {
+    if variants.is_empty() {
+        quote! { match *self {} }
+    } else {
+        quote! { match self { #arms } }
+    }
+}
+
+fn expand_checksummable(item: &Item) -> TokenStream2 {
+    let name = &item.input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        item.impl_header(quote! { ::wrt_foundation::traits::Checksummable });
+
+    let update = |fields: &FieldSet| {
+        let bindings = &fields.bindings;
+        quote! {
+            #(::wrt_foundation::traits::Checksummable::update_checksum(#bindings, checksum);)*
+        }
+    };
+    let body = match &item.body {
+        Body::Struct(fields) => {
+            let pattern = fields.pattern();
+            let update = update(fields);
+            quote! {
+                let Self #pattern = self;
+                #update
+            }
+        },
+        Body::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let tag = variant.tag;
+                let pattern = variant.fields.pattern();
+                let update = update(&variant.fields);
+                quote! {
+                    Self::#ident #pattern => {
+                        checksum.update(#tag);
+                        #update
+                    }
+                }
+            });
+            match_variants(variants, quote! { #(#arms)* })
+        },
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::wrt_foundation::traits::Checksummable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn update_checksum(&self, checksum: &mut ::wrt_foundation::verification::Checksum) {
+                #body
+            }
+        }
+    }
+}
+
+fn expand_to_bytes(item: &Item) -> TokenStream2 {
+    let name = &item.input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        item.impl_header(quote! { ::wrt_foundation::traits::ToBytes });
+
+    let size = |fields: &FieldSet| {
+        let bindings = &fields.bindings;
+        quote! { 0usize #(+ ::wrt_foundation::traits::ToBytes::serialized_size(#bindings))* }
+    };
+    let write = |fields: &FieldSet| {
+        let bindings = &fields.bindings;
+        quote! {
+            #(::wrt_foundation::traits::ToBytes::to_bytes_with_provider(#bindings, writer, provider)?;)*
+        }
+    };
+
+    let (fixed_size, serialized_size, to_bytes) = match &item.body {
+        Body::Struct(fields) => {
+            let pattern = fields.pattern();
+            let size = size(fields);
+            let write = write(fields);
+            (
+                fields.fixed_size(),
+                quote! {
+                    let Self #pattern = self;
+                    #size
+                },
+                quote! {
+                    let Self #pattern = self;
+                    #write
+                },
+            )
+        },
+        Body::Enum(variants) => {
+            let fixed_sizes = variants.iter().map(|variant| variant.fields.fixed_size());
+            let size_arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let pattern = variant.fields.pattern();
+                let size = size(&variant.fields);
+                quote! { Self::#ident #pattern => 1 + #size, }
+            });
+            let write_arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let tag = variant.tag;
+                let pattern = variant.fields.pattern();
+                let write = write(&variant.fields);
+                quote! {
+                    Self::#ident #pattern => {
+                        writer.write_u8(#tag)?;
+                        #write
+                    }
+                }
+            });
+            (
+                quote! {
+                    ::wrt_foundation::derive_support::fixed_variant_size(&[#(#fixed_sizes),*])
+                },
+                match_variants(variants, quote! { #(#size_arms)* }),
+                match_variants(variants, quote! { #(#write_arms)* }),
+            )
+        },
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::wrt_foundation::traits::ToBytes for #name #ty_generics #where_clause {
+            const FIXED_SERIALIZED_SIZE: ::core::option::Option<usize> = #fixed_size;
+
+            fn serialized_size(&self) -> usize {
+                #serialized_size
+            }
+
+            #[allow(unused_variables)]
+            fn to_bytes_with_provider<'__wrt_stream, __WrtProvider: ::wrt_foundation::MemoryProvider>(
+                &self,
+                writer: &mut ::wrt_foundation::traits::WriteStream<'__wrt_stream>,
+                provider: &__WrtProvider,
+            ) -> ::wrt_foundation::derive_support::Result<()> {
+                #to_bytes
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+fn expand_from_bytes(item: &Item) -> TokenStream2 {
+    let name = &item.input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        item.impl_header(quote! { ::wrt_foundation::traits::FromBytes });
+
+    let construct = |path: TokenStream2, fields: &FieldSet| {
+        let reads: Vec<_> = fields
+            .types
+            .iter()
+            .map(|ty| {
+                quote! {
+                    <#ty as ::wrt_foundation::traits::FromBytes>::from_bytes_with_provider(reader, provider)?
+                }
+            })
+            .collect();
+        let shape = fields.shape(&reads);
+        quote! { ::core::result::Result::Ok(#path #shape) }
+    };
+
+    let body = match &item.body {
+        Body::Struct(fields) => construct(quote! { Self }, fields),
+        Body::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let tag = variant.tag;
+                let construct = construct(quote! { Self::#ident }, &variant.fields);
+                quote! { #tag => #construct, }
+            });
+            let message = LitStr::new(&format!("Invalid {} tag", name), Span::call_site());
+            quote! {
+                match reader.read_u8()? {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(::wrt_foundation::derive_support::invalid_tag(#message)),
+                }
+            }
+        },
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::wrt_foundation::traits::FromBytes for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn from_bytes_with_provider<'__wrt_stream, __WrtProvider: ::wrt_foundation::MemoryProvider>(
+                reader: &mut ::wrt_foundation::traits::ReadStream<'__wrt_stream>,
+                provider: &__WrtProvider,
+            ) -> ::wrt_foundation::derive_support::Result<Self> {
+                #body
+            }
+        }
+    }
+}
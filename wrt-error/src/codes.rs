@@ -555,6 +555,12 @@
 pub const SYSTEM_RESOURCE_LIMIT_ERROR: u16 = 8802;
 /// System unsupported feature error
 pub const SYSTEM_UNSUPPORTED_FEATURE_ERROR: u16 = 8803;
+/// Runtime plugin could not be loaded
+pub const PLUGIN_LOAD_FAILED: u16 = 8804;
+/// Runtime plugin was built against an incompatible plugin ABI
+pub const PLUGIN_ABI_MISMATCH: u16 = 8805;
+/// Runtime plugin reported a failure from a callback
+pub const PLUGIN_CALL_FAILED: u16 = 8806;
 
 // Security errors (8900-8999)
 /// Control Flow Integrity violation
@@ -577,6 +583,9 @@
 /// Mutex error
 pub const MUTEX_ERROR: u16 = 7010;
 
+/// Execution suspended at an epoch deadline, to be resumed by the host
+pub const EXECUTION_YIELDED: u16 = 7011;
+
 /// Function not found error
 pub const FUNCTION_NOT_FOUND: u16 = 2010;
 
@@ -730,6 +739,17 @@
 /// Async resource cleanup failed
 pub const ASYNC_RESOURCE_CLEANUP_FAILED: u16 = 27008;
 
+// Parse budget error codes (33000-33999)
+/// Section larger than its byte budget
+pub const PARSE_SECTION_BUDGET_EXCEEDED: u16 = 33000;
+/// Section declares more items than its count budget
+pub const PARSE_COUNT_BUDGET_EXCEEDED: u16 = 33001;
+/// Single item (function body, data segment, name) larger than its byte
+/// budget
+pub const PARSE_ITEM_BUDGET_EXCEEDED: u16 = 33002;
+/// Total size of all sections larger than the module byte budget
+pub const PARSE_MODULE_BUDGET_EXCEEDED: u16 = 33003;
+
 /// Codes representing WebAssembly runtime trap conditions.
 /// These are used when an operation cannot complete normally due to a runtime
 /// error defined by the WebAssembly specification.
@@ -763,6 +783,12 @@ pub enum TrapCode {
     // Add more specific trap codes as needed based on Wasm spec.
     /// A generic trap for conditions not covered by more specific codes.
     GenericTrap = 12,
+    /// Call depth limit reached.
+    CallStackExhausted = 13,
+    /// Atomic access to an address not aligned to its size.
+    UnalignedAtomic = 14,
+    /// `memory.atomic.wait` on a memory that is not shared.
+    ExpectedSharedMemory = 15,
 }
 
 impl TrapCode {
@@ -784,6 +810,9 @@ pub const fn message(&self) -> &'static str {
             Self::UninitializedElement => "uninitialized element",
             Self::TableOutOfBounds => "out of bounds table access",
             Self::GenericTrap => "a WebAssembly trap occurred",
+            Self::CallStackExhausted => "call stack exhausted",
+            Self::UnalignedAtomic => "unaligned atomic",
+            Self::ExpectedSharedMemory => "expected shared memory",
         }
     }
 }
@@ -80,6 +80,58 @@ pub enum ErrorCategory {
     InvalidInput = 31,
     /// Async operation errors
     Async = 32,
+    /// Input exceeded a configured work or size budget (hostile binaries)
+    ResourceExhaustion = 33,
+}
+
+impl ErrorCategory {
+    /// Every category, in declaration order
+    pub const ALL: [Self; 30] = [
+        Self::Core,
+        Self::Component,
+        Self::Resource,
+        Self::Memory,
+        Self::Validation,
+        Self::Type,
+        Self::Runtime,
+        Self::System,
+        Self::Io,
+        Self::Unknown,
+        Self::Parse,
+        Self::Concurrency,
+        Self::Capacity,
+        Self::RuntimeTrap,
+        Self::Initialization,
+        Self::NotSupported,
+        Self::Safety,
+        Self::Security,
+        Self::Parameter,
+        Self::Verification,
+        Self::ComponentRuntime,
+        Self::PlatformRuntime,
+        Self::FoundationRuntime,
+        Self::AsyncRuntime,
+        Self::Platform,
+        Self::InvalidState,
+        Self::NotImplemented,
+        Self::InvalidInput,
+        Self::Async,
+        Self::ResourceExhaustion,
+    ];
+
+    /// Category with the discriminant `value`, as stored in records that
+    /// outlive the process such as panic information blocks
+    #[must_use]
+    pub const fn from_u16(value: u16) -> Option<Self> {
+        let mut index = 0;
+        while index < Self::ALL.len() {
+            if Self::ALL[index] as u16 == value {
+                return Some(Self::ALL[index]);
+            }
+            index += 1;
+        }
+        None
+    }
 }
 
 /// Base trait for all error types - `no_std` version
@@ -321,6 +373,12 @@ pub fn is_async_runtime_error(&self) -> bool {
         self.category == ErrorCategory::AsyncRuntime
     }
 
+    /// Check if this is a resource exhaustion error
+    #[must_use]
+    pub fn is_resource_exhaustion_error(&self) -> bool {
+        self.category == ErrorCategory::ResourceExhaustion
+    }
+
     /// Get the ASIL level of this error (ASIL-B and above)
     #[cfg(any(feature = "asil-b", feature = "asil-c", feature = "asil-d"))]
     #[must_use]
@@ -376,6 +434,7 @@ pub const fn validate_integrity(&self) -> bool {
             ErrorCategory::AsyncRuntime => self.code >= 27000 && self.code < 28000,
             ErrorCategory::InvalidInput => self.code >= 31000 && self.code < 32000,
             ErrorCategory::Async => self.code >= 32000 && self.code < 33000,
+            ErrorCategory::ResourceExhaustion => self.code >= 33000 && self.code < 34000,
             _ => self.code >= 9000 && self.code <= 9999,
         };
 
@@ -973,6 +1032,12 @@ pub const fn parse_error(message: &'static str) -> Self {
         Self::new(ErrorCategory::Parse, codes::PARSE_ERROR, message)
     }
 
+    /// Create a parse budget exceeded error
+    #[must_use]
+    pub const fn parse_budget_exceeded(code: u16, message: &'static str) -> Self {
+        Self::new(ErrorCategory::ResourceExhaustion, code, message)
+    }
+
     /// Create an invalid type error
     #[must_use]
     pub const fn invalid_type_error(message: &'static str) -> Self {
@@ -1611,6 +1676,12 @@ pub const fn trap_divide_by_zero(message: &'static str) -> Self {
         Self::new(ErrorCategory::RuntimeTrap, codes::DIVISION_BY_ZERO, message)
     }
 
+    /// Create a WebAssembly trap error coded with `trap`
+    #[must_use]
+    pub const fn wasm_trap(trap: codes::TrapCode, message: &'static str) -> Self {
+        Self::new(ErrorCategory::RuntimeTrap, trap as u16, message)
+    }
+
     /// Create a platform memory error error
     #[must_use]
     pub const fn platform_memory_error(message: &'static str) -> Self {
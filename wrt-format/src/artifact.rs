@@ -0,0 +1,314 @@
+//! Common header of serialized WRT artifacts.
+//!
+//! Snapshots, validation caches, telemetry records, traces and crash dumps
+//! written by different crates all start with the same [`ArtifactHeader`],
+//! so a tool can tell what a file is, which crate and build wrote it and
+//! whether it can read it, without knowing the artifact's own encoding. The
+//! header records
+//!
+//! - the [`ARTIFACT_MAGIC`] bytes and the layout version of the header,
+//! - the [`ArtifactKind`] and the schema version of its payload encoding,
+//! - the name and version of the producing crate,
+//! - the target the producing build ran on, and
+//! - a checksum over the header and payload.
+//!
+//! [`read_artifact`] is the single reader for all kinds: it checks the
+//! magic, header layout, lengths and checksum, and hands back the header
+//! and payload. Callers then check the kind and schema version they
+//! support with [`ArtifactHeader::expect`].
+
+use std::{
+    env::consts::{
+        ARCH,
+        OS,
+    },
+    fmt,
+    format,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::verification::Checksum;
+
+/// Magic bytes at the start of every artifact
+pub const ARTIFACT_MAGIC: &[u8; 4] = b"WRTA";
+
+/// Layout version of the header itself
+pub const ARTIFACT_HEADER_VERSION: u8 = 1;
+
+/// Smallest possible encoded header
+const MIN_HEADER_SIZE: usize = ARTIFACT_MAGIC.len() + 1 + 1 + 4 + 3 + 8 + 4;
+
+/// What an artifact holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    /// Instance state snapshot
+    Snapshot,
+    /// Cache of validation results
+    ValidationCache,
+    /// Telemetry records
+    Telemetry,
+    /// Recorded call trace
+    Trace,
+    /// Crash dump of a guest trap or host panic
+    CrashDump,
+}
+
+impl ArtifactKind {
+    /// Encoded tag of the kind
+    pub fn tag(self) -> u8 {
+        match self {
+            Self::Snapshot => 1,
+            Self::ValidationCache => 2,
+            Self::Telemetry => 3,
+            Self::Trace => 4,
+            Self::CrashDump => 5,
+        }
+    }
+
+    /// Kind with encoded tag `tag`
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        Some(match tag {
+            1 => Self::Snapshot,
+            2 => Self::ValidationCache,
+            3 => Self::Telemetry,
+            4 => Self::Trace,
+            5 => Self::CrashDump,
+            _ => return None,
+        })
+    }
+
+    /// Human-readable name of the kind
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Snapshot => "snapshot",
+            Self::ValidationCache => "validation cache",
+            Self::Telemetry => "telemetry",
+            Self::Trace => "trace",
+            Self::CrashDump => "crash dump",
+        }
+    }
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Header identifying a serialized artifact
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactHeader {
+    /// What the artifact holds
+    pub kind:             ArtifactKind,
+    /// Version of the payload encoding of `kind`
+    pub schema_version:   u32,
+    /// Crate that wrote the artifact
+    pub producer:         String,
+    /// Version of the crate that wrote the artifact
+    pub producer_version: String,
+    /// Architecture and operating system the artifact was written on, e.g.
+    /// `x86_64-linux`
+    pub target:           String,
+}
+
+impl ArtifactHeader {
+    /// Header of a `kind` artifact written by `producer` in version
+    /// `producer_version` on the current target
+    ///
+    /// Producers pass their own `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`.
+    pub fn new(
+        kind: ArtifactKind,
+        schema_version: u32,
+        producer: &str,
+        producer_version: &str,
+    ) -> Self {
+        Self {
+            kind,
+            schema_version,
+            producer: producer.into(),
+            producer_version: producer_version.into(),
+            target: current_target(),
+        }
+    }
+
+    /// Whether the artifact was written on the current target
+    pub fn is_current_target(&self) -> bool {
+        self.target == current_target()
+    }
+
+    /// Check that the artifact is a `kind` artifact in `schema_version`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kind or the schema version differ
+    pub fn expect(&self, kind: ArtifactKind, schema_version: u32) -> Result<()> {
+        if self.kind != kind {
+            return Err(Error::validation_parse_error("Artifact is of another kind"));
+        }
+        if self.schema_version != schema_version {
+            return Err(Error::validation_parse_error("Unsupported artifact schema version"));
+        }
+        Ok(())
+    }
+
+    /// Encode the header followed by `payload`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the producer, its version or the target are
+    /// longer than 255 bytes
+    pub fn write(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(MIN_HEADER_SIZE + 64 + payload.len());
+        out.extend_from_slice(ARTIFACT_MAGIC);
+        out.push(ARTIFACT_HEADER_VERSION);
+        out.push(self.kind.tag());
+        out.extend_from_slice(&self.schema_version.to_le_bytes());
+        for field in [&self.producer, &self.producer_version, &self.target] {
+            let len = u8::try_from(field.len())
+                .map_err(|_| Error::validation_error("Artifact header field too long"))?;
+            out.push(len);
+            out.extend_from_slice(field.as_bytes());
+        }
+        out.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+
+        let mut checksum = Checksum::compute(&out);
+        for &byte in payload {
+            checksum.update(byte);
+        }
+        out.extend_from_slice(&checksum.value().to_le_bytes());
+        out.extend_from_slice(payload);
+        Ok(out)
+    }
+}
+
+impl fmt::Display for ArtifactHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} v{} written by {} {} on {}",
+            self.kind, self.schema_version, self.producer, self.producer_version, self.target
+        )
+    }
+}
+
+/// Read the header of an artifact and return it with the payload
+///
+/// # Errors
+///
+/// Returns an error if `bytes` is not an artifact, uses an unknown header
+/// layout or kind, is truncated, or fails its checksum
+pub fn read_artifact(bytes: &[u8]) -> Result<(ArtifactHeader, &[u8])> {
+    if bytes.len() < MIN_HEADER_SIZE || !bytes.starts_with(ARTIFACT_MAGIC) {
+        return Err(Error::validation_parse_error("Not a WRT artifact"));
+    }
+    let mut reader = HeaderReader { bytes, pos: ARTIFACT_MAGIC.len() };
+    if reader.u8()? != ARTIFACT_HEADER_VERSION {
+        return Err(Error::validation_parse_error("Unsupported artifact header version"));
+    }
+    let kind = ArtifactKind::from_tag(reader.u8()?)
+        .ok_or_else(|| Error::validation_parse_error("Unknown artifact kind"))?;
+    let schema_version = u32::from_le_bytes(reader.array()?);
+    let producer = reader.string()?;
+    let producer_version = reader.string()?;
+    let target = reader.string()?;
+    let payload_len = usize::try_from(u64::from_le_bytes(reader.array()?))
+        .map_err(|_| Error::validation_parse_error("Artifact payload too large"))?;
+    let header_end = reader.pos;
+    let expected = u32::from_le_bytes(reader.array()?);
+
+    let payload = &bytes[reader.pos..];
+    if payload.len() != payload_len {
+        return Err(Error::validation_parse_error("Artifact payload truncated"));
+    }
+    let mut checksum = Checksum::compute(&bytes[..header_end]);
+    for &byte in payload {
+        checksum.update(byte);
+    }
+    if checksum.value() != expected {
+        return Err(Error::validation_parse_error("Artifact checksum mismatch"));
+    }
+
+    let header = ArtifactHeader { kind, schema_version, producer, producer_version, target };
+    Ok((header, payload))
+}
+
+/// Architecture and operating system of the current build, e.g.
+/// `x86_64-linux`
+pub fn current_target() -> String {
+    format!("{ARCH}-{OS}")
+}
+
+/// Cursor over the fields of an encoded header
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    pos:   usize,
+}
+
+impl HeaderReader<'_> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let field = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| Error::validation_parse_error("Artifact header truncated"))?;
+        self.pos += N;
+        let mut array = [0u8; N];
+        array.copy_from_slice(field);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = usize::from(self.u8()?);
+        let field = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| Error::validation_parse_error("Artifact header truncated"))?;
+        self.pos += len;
+        String::from_utf8(field.to_vec())
+            .map_err(|_| Error::validation_parse_error("Invalid artifact header string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_round_trip() -> Result<()> {
+        let header = ArtifactHeader::new(ArtifactKind::Trace, 3, "wrt-intercept", "0.2.0");
+        let bytes = header.write(b"payload")?;
+
+        let (read, payload) = read_artifact(&bytes)?;
+        assert_eq!(read, header);
+        assert_eq!(payload, b"payload");
+        assert!(read.is_current_target());
+        read.expect(ArtifactKind::Trace, 3)?;
+        assert!(read.expect(ArtifactKind::Snapshot, 3).is_err());
+        assert!(read.expect(ArtifactKind::Trace, 4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_damaged_artifacts_are_rejected() -> Result<()> {
+        let header = ArtifactHeader::new(ArtifactKind::Snapshot, 1, "wrt-runtime", "0.2.0");
+        let bytes = header.write(&[7; 16])?;
+
+        // Every single-byte change is caught by a check or the checksum
+        for pos in 0..bytes.len() {
+            let mut damaged = bytes.clone();
+            damaged[pos] ^= 0x10;
+            assert!(read_artifact(&damaged).is_err(), "byte {pos}");
+        }
+        assert!(read_artifact(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_artifact(b"\0asm\x01\0\0\0").is_err());
+        Ok(())
+    }
+}
@@ -13,8 +13,6 @@
 #[cfg(feature = "std")]
 use wrt_error::{Error, ErrorCategory, Result, codes};
 // Conditional imports for different environments
-#[cfg(all(feature = "std", feature = "safety-critical"))]
-use wrt_foundation::allocator::{CrateId, WrtVec};
 #[cfg(not(feature = "std"))]
 use wrt_foundation::bounded::{BoundedString, BoundedVec};
 // wrt_error is imported above unconditionally
@@ -873,7 +871,8 @@ pub mod with_alloc {
 
     /// Generate a WebAssembly binary from a module
     ///
-    /// This is a placeholder that will be implemented fully in Phase 1.
+    /// Returns the binary the module was decoded from if there is one and
+    /// encodes the module otherwise.
     #[cfg(feature = "std")]
     pub fn generate_binary(module: &Module) -> Result<Vec<u8>> {
         // If we have the original binary and haven't modified the module,
@@ -882,49 +881,7 @@ pub fn generate_binary(module: &Module) -> Result<Vec<u8>> {
             return Ok(binary.clone());
         }
 
-        // Create a minimal valid module with bounded allocation
-        #[cfg(feature = "safety-critical")]
-        let mut binary: WrtVec<u8, { CrateId::Format as u8 }, { 4 * 1024 * 1024 }> = WrtVec::new();
-
-        #[cfg(not(feature = "safety-critical"))]
-        let mut binary = Vec::with_capacity(8);
-
-        // Magic bytes with capacity checking
-        #[cfg(feature = "safety-critical")]
-        {
-            for &byte in &WASM_MAGIC {
-                binary.push(byte).map_err(|_| {
-                    Error::runtime_execution_error(
-                        "Binary generation capacity exceeded (magic bytes)",
-                    )
-                })?;
-            }
-        }
-        #[cfg(not(feature = "safety-critical"))]
-        binary.extend_from_slice(&WASM_MAGIC);
-
-        // Version with capacity checking
-        #[cfg(feature = "safety-critical")]
-        {
-            for &byte in &WASM_VERSION {
-                binary.push(byte).map_err(|_| {
-                    Error::runtime_execution_error("Binary generation capacity exceeded (version)")
-                })?;
-            }
-        }
-        #[cfg(not(feature = "safety-critical"))]
-        binary.extend_from_slice(&WASM_VERSION);
-
-        // Generate sections (placeholder)
-        // This will be implemented in Phase 1
-
-        // Convert to Vec<u8> for return
-        #[cfg(feature = "safety-critical")]
-        let result = binary.to_vec();
-        #[cfg(not(feature = "safety-critical"))]
-        let result = binary;
-
-        Ok(result)
+        crate::encode::encode_module(module)
     }
 
     /// Read a LEB128 unsigned integer from a byte array
@@ -1145,17 +1102,9 @@ pub fn write_leb128_i32(value: i32) -> Vec<u8> {
             let mut byte = (value & 0x7f) as u8;
             value >>= 7;
 
-            // If the original value is negative, we need to sign extend
+            // Done once the rest is the sign extension of the byte's sign bit
             let is_sign_bit_set = (byte & 0x40) != 0;
-            let sign_extended_value = if value == 0 && !is_sign_bit_set {
-                0
-            } else if value == -1 && is_sign_bit_set {
-                -1
-            } else {
-                value
-            };
-
-            more = sign_extended_value != 0 && sign_extended_value != -1;
+            more = !((value == 0 && !is_sign_bit_set) || (value == -1 && is_sign_bit_set));
 
             if more {
                 byte |= 0x80;
@@ -1180,17 +1129,9 @@ pub fn write_leb128_i64(value: i64) -> Vec<u8> {
             let mut byte = (value & 0x7f) as u8;
             value >>= 7;
 
-            // If the original value is negative, we need to sign extend
+            // Done once the rest is the sign extension of the byte's sign bit
             let is_sign_bit_set = (byte & 0x40) != 0;
-            let sign_extended_value = if value == 0 && !is_sign_bit_set {
-                0
-            } else if value == -1 && is_sign_bit_set {
-                -1
-            } else {
-                value
-            };
-
-            more = sign_extended_value != 0 && sign_extended_value != -1;
+            more = !((value == 0 && !is_sign_bit_set) || (value == -1 && is_sign_bit_set));
 
             if more {
                 byte |= 0x80;
@@ -1844,33 +1785,7 @@ pub fn generate_component_binary(component: &crate::component::Component) -> Res
             return Ok(binary.clone());
         }
 
-        // Create a minimal valid component
-        let mut binary = Vec::with_capacity(8);
-
-        // Magic bytes
-        binary.extend_from_slice(&COMPONENT_MAGIC);
-
-        // Version and layer
-        binary.extend_from_slice(&COMPONENT_VERSION);
-
-        // Generate sections
-        // This is a placeholder - full implementation will be added in the future
-
-        // In a complete implementation, we would encode all sections:
-        // - Core module sections
-        // - Core instance sections
-        // - Core type sections
-        // - Component sections
-        // - Instance sections
-        // - Alias sections
-        // - Type sections
-        // - Canon sections
-        // - Start sections
-        // - Import sections
-        // - Export sections
-        // - Value sections
-
-        Ok(binary)
+        crate::encode::encode_component(component)
     }
 
     /// Binary format utilities for WebAssembly
@@ -1945,8 +1860,9 @@ pub fn read_name(bytes: &[u8], pos: usize) -> Result<(&[u8], usize)> {
         Ok((name_slice, pos + len_size + name_len as usize))
     }
 
-    // STUB for parsing limits - to be fully implemented in wrt-format
-    // Should parse wrt_format::types::Limits
+    /// Parse limits: a flags byte (bit 0 max present, bit 1 shared, bit 2
+    /// memory64) followed by the minimum and optional maximum. Memory64
+    /// limits are encoded as u64, all others as u32.
     pub fn parse_limits(
         bytes: &[u8],
         offset: usize,
@@ -1956,28 +1872,34 @@ pub fn parse_limits(
             return Err(Error::parse_error("Unexpected end of limits"));
         }
         let flags = bytes[offset];
+        let memory64 = (flags & 0x04) != 0;
+        let read_bound = |pos: usize| -> wrt_error::Result<(u64, usize)> {
+            if memory64 {
+                read_leb128_u64(bytes, pos)
+            } else {
+                read_leb128_u32(bytes, pos).map(|(val, bytes_read)| (u64::from(val), bytes_read))
+            }
+        };
         let mut current_offset = offset + 1;
 
-        let (min, new_offset) = read_leb128_u32(bytes, current_offset)?;
-        current_offset = new_offset;
+        let (min, bytes_read) = read_bound(current_offset)?;
+        current_offset += bytes_read;
 
         let max = if (flags & 0x01) != 0 {
-            let (val, new_offset) = read_leb128_u32(bytes, current_offset)?;
-            current_offset = new_offset;
+            let (val, bytes_read) = read_bound(current_offset)?;
+            current_offset += bytes_read;
             Some(val)
         } else {
             None
         };
-        // Ignoring shared and memory64 flags for now as they are not in
-        // wrt_format::types::Limits directly
 
         Ok((
             crate::types::Limits {
-                min: min.into(),
-                max: max.map(Into::into),
-                shared: false,
-                memory64: false,
-            }, // Assuming default shared/memory64
+                min,
+                max,
+                shared: (flags & 0x02) != 0,
+                memory64,
+            },
             current_offset,
         ))
     }
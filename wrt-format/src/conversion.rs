@@ -45,14 +45,29 @@ pub fn block_type_to_format_block_type(block_type: &BlockType) -> FormatBlockTyp
 
 /// Convert from format-specific Limits to wrt_foundation::Limits
 ///
-/// Validates and converts format limits to core limits.
+/// Validates and converts format limits to core limits. Core limits count
+/// pages in u32: a memory64 maximum beyond that is clamped, since no memory
+/// can grow that far, while a minimum beyond it is rejected.
 pub fn format_limits_to_wrt_limits(
     limits: &crate::types::Limits,
 ) -> Result<wrt_foundation::types::Limits> {
     if limits.memory64 {
-        return Err(Error::runtime_execution_error(
-            "memory64 limits are not supported by the current runtime type system (u32 limits).",
-        ));
+        let max_pages = crate::types::MEMORY64_MAX_PAGES;
+        if limits.min > max_pages || limits.max.is_some_and(|max| max > max_pages) {
+            return Err(crate::error::validation_error(
+                "memory64 limits exceed 2^48 pages.",
+            ));
+        }
+        let min = limits.min.try_into().map_err(|_| {
+            crate::error::validation_error("memory64 minimum exceeds the runtime page limit.")
+        })?;
+        let max = limits.max.map(|max| u32::try_from(max).unwrap_or(u32::MAX));
+        if max.is_some_and(|max| max < min) {
+            return Err(crate::error::validation_error(
+                "Maximum limit cannot be less than minimum limit.",
+            ));
+        }
+        return Ok(wrt_foundation::types::Limits { min, max });
     }
 
     let min_u32 = limits.min.try_into().map_err(|_| {
@@ -333,6 +348,19 @@ fn test_limits_conversion() {
 
         assert_eq!(wrt_limits_both_2.min, 10);
         assert_eq!(wrt_limits_both_2.max, Some(20));
+
+        // Memory64 maxima beyond u32 pages are clamped, beyond 2^48 rejected
+        let mem64_limits = |min, max| Limits {
+            min,
+            max,
+            shared: false,
+            memory64: true,
+        };
+        let clamped = format_limits_to_wrt_limits(&mem64_limits(1, Some(1 << 40))).unwrap();
+        assert_eq!(clamped.min, 1);
+        assert_eq!(clamped.max, Some(u32::MAX));
+        assert!(format_limits_to_wrt_limits(&mem64_limits(1, Some((1 << 48) + 1))).is_err());
+        assert!(format_limits_to_wrt_limits(&mem64_limits(1 << 40, None)).is_err());
     }
 
     #[test]
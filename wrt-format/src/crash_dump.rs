@@ -0,0 +1,552 @@
+//! Crash dump format.
+//!
+//! A [`CrashDump`] is written when a guest traps or the host panics, so the
+//! failure can be analysed offline. It records
+//!
+//! - the [`CrashCause`]: the trap's error code and message, or the panic
+//!   message,
+//! - the fingerprint of the module that was executing,
+//! - the guest call stack as [`DumpFrame`]s, innermost first, with their
+//!   locals and operand stacks where the runtime kept them,
+//! - a [`MemoryRegion`] summary of each linear memory, optionally with
+//!   windows of captured bytes, and
+//! - the raw panic information block of `wrt-panic`, if a panic was
+//!   recorded.
+//!
+//! The dump is an [`ArtifactKind::CrashDump`] artifact; its payload is a
+//! little-endian encoding of the fields above in the order listed.
+
+use std::{
+    fmt,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::verification::Checksum;
+
+use crate::artifact::{
+    read_artifact,
+    ArtifactHeader,
+    ArtifactKind,
+};
+
+/// Version of the crash dump payload encoding
+pub const CRASH_DUMP_SCHEMA_VERSION: u32 = 1;
+
+/// Most used ranges kept in a memory summary
+pub const MAX_MEMORY_RANGES: usize = 64;
+
+/// Zero bytes between two used ranges that still merge them
+const RANGE_MERGE_GAP: usize = 16;
+
+/// What ended the execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrashCause {
+    /// The guest trapped with an error
+    Trap {
+        /// Error code of the trap
+        code:    u16,
+        /// Error message of the trap
+        message: String,
+    },
+    /// The host panicked
+    HostPanic {
+        /// Panic message
+        message: String,
+    },
+}
+
+impl fmt::Display for CrashCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trap { code, message } => write!(f, "trap {code}: {message}"),
+            Self::HostPanic { message } => write!(f, "host panic: {message}"),
+        }
+    }
+}
+
+/// Value of a local or operand in a dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpValue {
+    /// 32-bit integer
+    I32(u32),
+    /// 64-bit integer
+    I64(u64),
+    /// 32-bit float as its bits
+    F32(u32),
+    /// 64-bit float as its bits
+    F64(u64),
+    /// 128-bit vector
+    V128([u8; 16]),
+    /// Reference, `None` if null
+    Ref(Option<u32>),
+    /// Value the dump cannot represent
+    Opaque,
+}
+
+impl fmt::Display for DumpValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I32(v) => write!(f, "i32:{}", *v as i32),
+            Self::I64(v) => write!(f, "i64:{}", *v as i64),
+            Self::F32(v) => write!(f, "f32:{}", f32::from_bits(*v)),
+            Self::F64(v) => write!(f, "f64:{}", f64::from_bits(*v)),
+            Self::V128(bytes) => {
+                f.write_str("v128:0x")?;
+                bytes.iter().rev().try_for_each(|byte| write!(f, "{byte:02x}"))
+            },
+            Self::Ref(Some(index)) => write!(f, "ref:{index}"),
+            Self::Ref(None) => f.write_str("ref:null"),
+            Self::Opaque => f.write_str("?"),
+        }
+    }
+}
+
+/// Guest frame on the stack at the time of the crash
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DumpFrame {
+    /// Function index within its module
+    pub func_idx:      u32,
+    /// Instruction index within the function, if known
+    pub pc:            Option<u32>,
+    /// Locals, parameters first; empty if the runtime did not keep them
+    pub locals:        Vec<DumpValue>,
+    /// Operand stack, bottom first
+    pub operand_stack: Vec<DumpValue>,
+}
+
+/// Summary of a linear memory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// Memory index
+    pub index:    u32,
+    /// Size in pages
+    pub pages:    u32,
+    /// Size in bytes
+    pub size:     u64,
+    /// Checksum of the contents
+    pub checksum: u32,
+    /// Byte ranges `start..end` holding non-zero data; small gaps are
+    /// merged and the last range covers the rest once
+    /// [`MAX_MEMORY_RANGES`] is reached
+    pub used:     Vec<(u64, u64)>,
+    /// Captured bytes by start offset
+    pub windows:  Vec<(u64, Vec<u8>)>,
+}
+
+impl MemoryRegion {
+    /// Summarize memory `index` of `pages` pages with contents `data`
+    pub fn summarize(index: u32, pages: u32, data: &[u8]) -> Self {
+        let mut used: Vec<(u64, u64)> = Vec::new();
+        let mut pos = 0;
+        while let Some(offset) = data[pos..].iter().position(|&byte| byte != 0) {
+            let start = pos + offset;
+            let len = data[start..].iter().position(|&byte| byte == 0).unwrap_or(data.len() - start);
+            let end = start + len;
+            let full = used.len() == MAX_MEMORY_RANGES;
+            match used.last_mut() {
+                Some(last) if full || start - last.1 as usize <= RANGE_MERGE_GAP => {
+                    last.1 = end as u64;
+                },
+                _ => used.push((start as u64, end as u64)),
+            }
+            pos = end;
+        }
+        Self {
+            index,
+            pages,
+            size: data.len() as u64,
+            checksum: Checksum::compute(data).value(),
+            used,
+            windows: Vec::new(),
+        }
+    }
+
+    /// Capture `len` bytes of `data` from `start`, clamped to its end
+    pub fn capture(&mut self, data: &[u8], start: u64, len: usize) {
+        let Ok(begin) = usize::try_from(start) else {
+            return;
+        };
+        if let Some(bytes) = data.get(begin..begin.saturating_add(len).min(data.len())) {
+            if !bytes.is_empty() {
+                self.windows.push((start, bytes.to_vec()));
+            }
+        }
+    }
+
+    /// Number of non-zero bytes within the used ranges' bounds
+    pub fn used_bytes(&self) -> u64 {
+        self.used.iter().map(|(start, end)| end - start).sum()
+    }
+}
+
+/// Persistent record of a guest trap or host panic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashDump {
+    /// What ended the execution
+    pub cause:              CrashCause,
+    /// Fingerprint of the executing module
+    pub module_fingerprint: u32,
+    /// Guest frames, innermost first
+    pub frames:             Vec<DumpFrame>,
+    /// Summaries of the linear memories
+    pub memories:           Vec<MemoryRegion>,
+    /// Encoded `wrt-panic` panic information block
+    pub panic_info:         Option<Vec<u8>>,
+}
+
+impl CrashDump {
+    /// Dump with `cause` and no frames, memories or panic information
+    pub fn new(cause: CrashCause, module_fingerprint: u32) -> Self {
+        Self {
+            cause,
+            module_fingerprint,
+            frames: Vec::new(),
+            memories: Vec::new(),
+            panic_info: None,
+        }
+    }
+
+    /// Innermost frame, where the crash happened
+    pub fn crash_frame(&self) -> Option<&DumpFrame> {
+        self.frames.first()
+    }
+
+    /// Encode the dump as an artifact written by `producer` in version
+    /// `producer_version`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact header cannot be written
+    pub fn to_bytes(&self, producer: &str, producer_version: &str) -> Result<Vec<u8>> {
+        let mut out = DumpWriter::default();
+        match &self.cause {
+            CrashCause::Trap { code, message } => {
+                out.u8(0);
+                out.u32(u32::from(*code));
+                out.bytes(message.as_bytes());
+            },
+            CrashCause::HostPanic { message } => {
+                out.u8(1);
+                out.bytes(message.as_bytes());
+            },
+        }
+        out.u32(self.module_fingerprint);
+
+        out.len(self.frames.len());
+        for frame in &self.frames {
+            out.u32(frame.func_idx);
+            match frame.pc {
+                Some(pc) => {
+                    out.u8(1);
+                    out.u32(pc);
+                },
+                None => out.u8(0),
+            }
+            out.values(&frame.locals);
+            out.values(&frame.operand_stack);
+        }
+
+        out.len(self.memories.len());
+        for memory in &self.memories {
+            out.u32(memory.index);
+            out.u32(memory.pages);
+            out.u64(memory.size);
+            out.u32(memory.checksum);
+            out.len(memory.used.len());
+            for &(start, end) in &memory.used {
+                out.u64(start);
+                out.u64(end);
+            }
+            out.len(memory.windows.len());
+            for (start, bytes) in &memory.windows {
+                out.u64(*start);
+                out.bytes(bytes);
+            }
+        }
+
+        match &self.panic_info {
+            Some(block) => {
+                out.u8(1);
+                out.bytes(block);
+            },
+            None => out.u8(0),
+        }
+
+        ArtifactHeader::new(
+            ArtifactKind::CrashDump,
+            CRASH_DUMP_SCHEMA_VERSION,
+            producer,
+            producer_version,
+        )
+        .write(&out.data)
+    }
+
+    /// Decode a dump written by [`CrashDump::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a crash dump artifact in a
+    /// supported schema version or is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (header, payload) = read_artifact(bytes)?;
+        header.expect(ArtifactKind::CrashDump, CRASH_DUMP_SCHEMA_VERSION)?;
+        let mut input = DumpReader { data: payload, pos: 0 };
+
+        let cause = match input.u8()? {
+            0 => {
+                let code = u16::try_from(input.u32()?)
+                    .map_err(|_| Error::validation_parse_error("Invalid crash dump trap code"))?;
+                CrashCause::Trap { code, message: input.string()? }
+            },
+            1 => CrashCause::HostPanic { message: input.string()? },
+            _ => return Err(Error::validation_parse_error("Invalid crash dump cause")),
+        };
+        let module_fingerprint = input.u32()?;
+
+        let mut frames = Vec::new();
+        for _ in 0..input.len()? {
+            let func_idx = input.u32()?;
+            let pc = match input.u8()? {
+                0 => None,
+                _ => Some(input.u32()?),
+            };
+            frames.push(DumpFrame {
+                func_idx,
+                pc,
+                locals: input.values()?,
+                operand_stack: input.values()?,
+            });
+        }
+
+        let mut memories = Vec::new();
+        for _ in 0..input.len()? {
+            let mut memory = MemoryRegion {
+                index: input.u32()?,
+                pages: input.u32()?,
+                size: input.u64()?,
+                checksum: input.u32()?,
+                ..MemoryRegion::default()
+            };
+            for _ in 0..input.len()? {
+                memory.used.push((input.u64()?, input.u64()?));
+            }
+            for _ in 0..input.len()? {
+                let start = input.u64()?;
+                memory.windows.push((start, input.bytes()?.to_vec()));
+            }
+            memories.push(memory);
+        }
+
+        let panic_info = match input.u8()? {
+            0 => None,
+            _ => Some(input.bytes()?.to_vec()),
+        };
+        if input.pos != payload.len() {
+            return Err(Error::validation_parse_error("Trailing bytes in crash dump"));
+        }
+
+        Ok(Self {
+            cause,
+            module_fingerprint,
+            frames,
+            memories,
+            panic_info,
+        })
+    }
+}
+
+/// Little-endian encoder for the dump payload
+#[derive(Default)]
+struct DumpWriter {
+    data: Vec<u8>,
+}
+
+impl DumpWriter {
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u64(len as u64);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.data.extend_from_slice(bytes);
+    }
+
+    fn values(&mut self, values: &[DumpValue]) {
+        self.len(values.len());
+        for value in values {
+            match *value {
+                DumpValue::I32(v) => {
+                    self.u8(0);
+                    self.u32(v);
+                },
+                DumpValue::I64(v) => {
+                    self.u8(1);
+                    self.u64(v);
+                },
+                DumpValue::F32(v) => {
+                    self.u8(2);
+                    self.u32(v);
+                },
+                DumpValue::F64(v) => {
+                    self.u8(3);
+                    self.u64(v);
+                },
+                DumpValue::V128(v) => {
+                    self.u8(4);
+                    self.data.extend_from_slice(&v);
+                },
+                DumpValue::Ref(None) => self.u8(5),
+                DumpValue::Ref(Some(index)) => {
+                    self.u8(6);
+                    self.u32(index);
+                },
+                DumpValue::Opaque => self.u8(7),
+            }
+        }
+    }
+}
+
+/// Little-endian decoder for the dump payload
+struct DumpReader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> DumpReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(count)
+            .and_then(|end| self.data.get(self.pos..end))
+            .ok_or_else(|| Error::validation_parse_error("Crash dump truncated"))?;
+        self.pos += count;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let len = usize::try_from(self.u64()?)
+            .map_err(|_| Error::validation_parse_error("Crash dump length too large"))?;
+        // Every element takes at least one byte, which bounds allocations
+        if len > self.data.len() - self.pos {
+            return Err(Error::validation_parse_error("Crash dump truncated"));
+        }
+        Ok(len)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec())
+            .map_err(|_| Error::validation_parse_error("Invalid crash dump string"))
+    }
+
+    fn values(&mut self) -> Result<Vec<DumpValue>> {
+        let len = self.len()?;
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(match self.u8()? {
+                0 => DumpValue::I32(self.u32()?),
+                1 => DumpValue::I64(self.u64()?),
+                2 => DumpValue::F32(self.u32()?),
+                3 => DumpValue::F64(self.u64()?),
+                4 => DumpValue::V128(self.array()?),
+                5 => DumpValue::Ref(None),
+                6 => DumpValue::Ref(Some(self.u32()?)),
+                7 => DumpValue::Opaque,
+                _ => return Err(Error::validation_parse_error("Invalid crash dump value")),
+            });
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_dump_round_trip() -> Result<()> {
+        let mut data = vec![0u8; 65536];
+        data[16..20].copy_from_slice(&[1, 2, 3, 4]);
+        data[30] = 9;
+        data[4096] = 5;
+        let mut memory = MemoryRegion::summarize(0, 1, &data);
+        memory.capture(&data, 16, 8);
+        assert_eq!(memory.used, vec![(16, 31), (4096, 4097)]);
+        assert_eq!(memory.used_bytes(), 16);
+        assert_eq!(memory.windows, vec![(16, vec![1, 2, 3, 4, 0, 0, 0, 0])]);
+
+        let dump = CrashDump {
+            frames: vec![
+                DumpFrame { func_idx: 3, pc: Some(7), ..DumpFrame::default() },
+                DumpFrame {
+                    func_idx:      1,
+                    pc:            Some(12),
+                    locals:        vec![DumpValue::I32(5), DumpValue::F64(1.5f64.to_bits())],
+                    operand_stack: vec![DumpValue::Ref(None), DumpValue::V128([7; 16])],
+                },
+            ],
+            memories: vec![memory],
+            panic_info: Some(vec![0xef, 0xbe, 0xad, 0xde]),
+            ..CrashDump::new(
+                CrashCause::Trap { code: 8001, message: "out of bounds memory access".into() },
+                0x1234_5678,
+            )
+        };
+        let bytes = dump.to_bytes("wrt-runtime", "0.2.0")?;
+        assert_eq!(CrashDump::from_bytes(&bytes)?, dump);
+        assert_eq!(dump.crash_frame().map(|frame| frame.func_idx), Some(3));
+
+        assert!(CrashDump::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let other = ArtifactHeader::new(ArtifactKind::Trace, 1, "wrt-runtime", "0.2.0").write(&[])?;
+        assert!(CrashDump::from_bytes(&other).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_summary_caps_ranges() {
+        let data: Vec<u8> = (0..8192).map(|i| u8::from(i % 64 == 0)).collect();
+        let memory = MemoryRegion::summarize(1, 1, &data);
+        assert_eq!(memory.used.len(), MAX_MEMORY_RANGES);
+        assert_eq!(memory.used.last(), Some(&(63 * 64, 8128 + 1)));
+        assert_eq!(DumpValue::I32(-1i32 as u32).to_string(), "i32:-1");
+    }
+}
@@ -0,0 +1,1544 @@
+//! WebAssembly binary encoder.
+//!
+//! [`ModuleEncoder`] and [`ComponentEncoder`] serialize the in-memory
+//! [`Module`] and [`Component`] structures back to binaries that follow the
+//! core specification and the Component Model binary format. Both can drop
+//! and add custom sections on the way, so a pipeline can decode a binary,
+//! strip or inject metadata and write it out again without an external
+//! tool.
+//!
+//! The encoders always work from the structures; the original `binary` a
+//! structure was decoded from is ignored.
+
+use std::{
+    string::{
+        String,
+        ToString,
+    },
+    vec,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    CleanCoreFuncType,
+    Limits,
+};
+
+use crate::{
+    binary::{
+        with_alloc::{
+            write_leb128_i64,
+            write_leb128_u32,
+            write_leb128_u64,
+            write_string,
+        },
+        CODE_SECTION_ID,
+        COMPONENT_ALIAS_SECTION_ID,
+        COMPONENT_CANON_SECTION_ID,
+        COMPONENT_COMPONENT_SECTION_ID,
+        COMPONENT_CORE_INSTANCE_SECTION_ID,
+        COMPONENT_CORE_MODULE_SECTION_ID,
+        COMPONENT_CORE_SORT_FUNC,
+        COMPONENT_CORE_SORT_GLOBAL,
+        COMPONENT_CORE_SORT_INSTANCE,
+        COMPONENT_CORE_SORT_MEMORY,
+        COMPONENT_CORE_SORT_MODULE,
+        COMPONENT_CORE_SORT_TABLE,
+        COMPONENT_CORE_SORT_TYPE,
+        COMPONENT_CORE_TYPE_SECTION_ID,
+        COMPONENT_EXPORT_SECTION_ID,
+        COMPONENT_IMPORT_SECTION_ID,
+        COMPONENT_INSTANCE_SECTION_ID,
+        COMPONENT_MAGIC,
+        COMPONENT_SORT_COMPONENT,
+        COMPONENT_SORT_CORE,
+        COMPONENT_SORT_FUNC,
+        COMPONENT_SORT_INSTANCE,
+        COMPONENT_SORT_TYPE,
+        COMPONENT_SORT_VALUE,
+        COMPONENT_START_SECTION_ID,
+        COMPONENT_TYPE_SECTION_ID,
+        COMPONENT_VALUE_SECTION_ID,
+        COMPONENT_VERSION,
+        CUSTOM_SECTION_ID,
+        DATA_COUNT_SECTION_ID,
+        DATA_SECTION_ID,
+        ELEMENT_SECTION_ID,
+        EXPORT_SECTION_ID,
+        FUNCTION_SECTION_ID,
+        GLOBAL_SECTION_ID,
+        IMPORT_SECTION_ID,
+        MEMORY_SECTION_ID,
+        START_SECTION_ID,
+        TABLE_SECTION_ID,
+        TAG_SECTION_ID,
+        TYPE_SECTION_ID,
+        WASM_MAGIC,
+        WASM_VERSION,
+    },
+    component::{
+        Alias,
+        AliasTarget,
+        Canon,
+        CanonOperation,
+        Component,
+        ComponentType,
+        ComponentTypeDefinition,
+        CoreExternType,
+        CoreInstance,
+        CoreInstanceExpr,
+        CoreSort,
+        CoreType,
+        CoreTypeDefinition,
+        ExternType,
+        FormatResourceOperation,
+        FormatValType,
+        Instance,
+        InstanceExpr,
+        ResourceRepresentation,
+        Sort,
+        StringEncoding,
+    },
+    module::{
+        ExportKind,
+        ImportDesc,
+        Memory,
+        Module,
+        Table,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureElementInit,
+        PureElementMode,
+    },
+    section::CustomSection,
+    types::{
+        FormatGlobalType,
+        RefType,
+        ValueType,
+    },
+};
+
+/// Expression `end` opcode
+const END: u8 = 0x0B;
+
+// Component Model type encodings, as in the Component Model `Binary.md`
+const PRIM_BOOL: u8 = 0x7F;
+const PRIM_S8: u8 = 0x7E;
+const PRIM_U8: u8 = 0x7D;
+const PRIM_S16: u8 = 0x7C;
+const PRIM_U16: u8 = 0x7B;
+const PRIM_S32: u8 = 0x7A;
+const PRIM_U32: u8 = 0x79;
+const PRIM_S64: u8 = 0x78;
+const PRIM_U64: u8 = 0x77;
+const PRIM_F32: u8 = 0x76;
+const PRIM_F64: u8 = 0x75;
+const PRIM_CHAR: u8 = 0x74;
+const PRIM_STRING: u8 = 0x73;
+const PRIM_ERROR_CONTEXT: u8 = 0x64;
+const DEF_RECORD: u8 = 0x72;
+const DEF_VARIANT: u8 = 0x71;
+const DEF_LIST: u8 = 0x70;
+const DEF_TUPLE: u8 = 0x6F;
+const DEF_FLAGS: u8 = 0x6E;
+const DEF_ENUM: u8 = 0x6D;
+const DEF_OPTION: u8 = 0x6B;
+const DEF_RESULT: u8 = 0x6A;
+const DEF_OWN: u8 = 0x69;
+const DEF_BORROW: u8 = 0x68;
+const DEF_FIXED_LIST: u8 = 0x67;
+const DEF_FUNC: u8 = 0x40;
+const DEF_COMPONENT: u8 = 0x41;
+const DEF_INSTANCE: u8 = 0x42;
+const DEF_RESOURCE: u8 = 0x3F;
+const CORE_DEF_FUNC: u8 = 0x60;
+const CORE_DEF_MODULE: u8 = 0x50;
+
+// `externdesc` tags
+const EXTERN_MODULE: u8 = 0x00;
+const EXTERN_FUNC: u8 = 0x01;
+const EXTERN_VALUE: u8 = 0x02;
+const EXTERN_TYPE: u8 = 0x03;
+const EXTERN_COMPONENT: u8 = 0x04;
+const EXTERN_INSTANCE: u8 = 0x05;
+
+/// Custom sections to drop and add while encoding
+#[derive(Debug, Clone, Default)]
+struct CustomSectionEdits {
+    /// Drop every existing custom section
+    strip_all: bool,
+    /// Names of existing custom sections to drop
+    strip:     Vec<String>,
+    /// Sections appended after the existing ones
+    add:       Vec<CustomSection>,
+}
+
+impl CustomSectionEdits {
+    fn keeps(&self, name: &str) -> bool {
+        !self.strip_all
+            && !self.strip.iter().any(|stripped| stripped == name)
+            && !self.add.iter().any(|added| added.name == name)
+    }
+
+    fn write(&self, out: &mut Vec<u8>, existing: &[CustomSection]) {
+        let kept = existing.iter().filter(|section| self.keeps(&section.name));
+        for section in kept.chain(&self.add) {
+            let mut body = write_string(&section.name);
+            body.extend_from_slice(&section.data);
+            write_section(out, CUSTOM_SECTION_ID, &body);
+        }
+    }
+}
+
+/// Serializes a [`Module`] to a core WebAssembly binary
+///
+/// As produced by the decoder, `functions` starts with one entry per
+/// imported function; only the entries after those are encoded as code.
+/// Expressions (global initializers, segment offsets and element
+/// expressions) and function bodies are kept as raw bytes including their
+/// final `end`.
+#[derive(Debug, Clone)]
+pub struct ModuleEncoder<'a> {
+    module: &'a Module,
+    custom: CustomSectionEdits,
+}
+
+impl<'a> ModuleEncoder<'a> {
+    /// Create an encoder for `module`
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            custom: CustomSectionEdits::default(),
+        }
+    }
+
+    /// Drop all custom sections of the module
+    pub fn strip_custom_sections(mut self) -> Self {
+        self.custom.strip_all = true;
+        self
+    }
+
+    /// Drop the custom sections named `name`
+    pub fn strip_custom_section(mut self, name: &str) -> Self {
+        self.custom.strip.push(name.to_string());
+        self
+    }
+
+    /// Add a custom section, replacing existing sections of the same name
+    pub fn with_custom_section(mut self, name: &str, data: Vec<u8>) -> Self {
+        self.custom.add.push(CustomSection::new(name.to_string(), data));
+        self
+    }
+
+    /// Encode the module
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `functions` has fewer entries than the module
+    /// imports functions.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let module = self.module;
+        let mut out = Vec::new();
+        out.extend_from_slice(&WASM_MAGIC);
+        out.extend_from_slice(&WASM_VERSION);
+
+        let imported_functions =
+            module
+                .imports
+                .iter()
+                .filter(|import| matches!(import.desc, ImportDesc::Function(_)))
+                .count();
+        let defined = module.functions.get(imported_functions..).ok_or_else(|| {
+            Error::validation_error("Module has fewer functions than function imports")
+        })?;
+
+        write_vec_section(&mut out, TYPE_SECTION_ID, &module.types, write_core_func_type);
+        write_vec_section(&mut out, IMPORT_SECTION_ID, &module.imports, |out, import| {
+            out.extend_from_slice(&write_string(&import.module));
+            out.extend_from_slice(&write_string(&import.name));
+            match &import.desc {
+                ImportDesc::Function(type_idx) => {
+                    out.push(0x00);
+                    write_u32(out, *type_idx);
+                },
+                ImportDesc::Table(table) => {
+                    out.push(0x01);
+                    write_table_type(out, table);
+                },
+                ImportDesc::Memory(memory) => {
+                    out.push(0x02);
+                    write_memory_type(out, memory);
+                },
+                ImportDesc::Global(global) => {
+                    out.push(0x03);
+                    write_global_type(out, global);
+                },
+                ImportDesc::Tag(type_idx) => {
+                    out.extend_from_slice(&[0x04, 0x00]);
+                    write_u32(out, *type_idx);
+                },
+            }
+        });
+        write_vec_section(&mut out, FUNCTION_SECTION_ID, defined, |out, function| {
+            write_u32(out, function.type_idx);
+        });
+        write_vec_section(&mut out, TABLE_SECTION_ID, &module.tables, write_table_type);
+        write_vec_section(&mut out, MEMORY_SECTION_ID, &module.memories, write_memory_type);
+        write_vec_section(&mut out, TAG_SECTION_ID, &module.tags, |out, tag| {
+            out.push(tag.attribute);
+            write_u32(out, tag.type_idx);
+        });
+        write_vec_section(&mut out, GLOBAL_SECTION_ID, &module.globals, |out, global| {
+            write_global_type(out, &global.global_type);
+            out.extend_from_slice(&global.init);
+        });
+        write_vec_section(&mut out, EXPORT_SECTION_ID, &module.exports, |out, export| {
+            out.extend_from_slice(&write_string(&export.name));
+            out.push(match export.kind {
+                ExportKind::Function => 0x00,
+                ExportKind::Table => 0x01,
+                ExportKind::Memory => 0x02,
+                ExportKind::Global => 0x03,
+                ExportKind::Tag => 0x04,
+            });
+            write_u32(out, export.index);
+        });
+        if let Some(start) = module.start {
+            let mut body = Vec::new();
+            write_u32(&mut body, start);
+            write_section(&mut out, START_SECTION_ID, &body);
+        }
+        write_vec_section(&mut out, ELEMENT_SECTION_ID, &module.elements, |out, segment| {
+            write_element_segment(out, segment)
+        });
+        // Only `memory.init` and `data.drop` need the data count, and they
+        // are only useful with passive segments
+        if module.data.iter().any(|segment| segment.mode == PureDataMode::Passive) {
+            let mut body = Vec::new();
+            write_u32(&mut body, module.data.len() as u32);
+            write_section(&mut out, DATA_COUNT_SECTION_ID, &body);
+        }
+        write_vec_section(&mut out, CODE_SECTION_ID, defined, |out, function| {
+            let mut body = Vec::new();
+            let mut groups: Vec<(u32, ValueType)> = Vec::new();
+            for local in &function.locals {
+                match groups.last_mut() {
+                    Some((count, ty)) if ty == local => *count += 1,
+                    _ => groups.push((1, *local)),
+                }
+            }
+            write_u32(&mut body, groups.len() as u32);
+            for (count, ty) in groups {
+                write_u32(&mut body, count);
+                write_value_type(&mut body, ty);
+            }
+            body.extend_from_slice(&function.code);
+            if function.code.is_empty() {
+                body.push(END);
+            }
+            write_u32(out, body.len() as u32);
+            out.extend_from_slice(&body);
+        });
+        write_vec_section(&mut out, DATA_SECTION_ID, &module.data, |out, segment| {
+            match segment.mode {
+                PureDataMode::Active { memory_index: 0, .. } => {
+                    out.push(0x00);
+                    out.extend_from_slice(&segment.offset_expr_bytes);
+                },
+                PureDataMode::Passive => out.push(0x01),
+                PureDataMode::Active { memory_index, .. } => {
+                    out.push(0x02);
+                    write_u32(out, memory_index);
+                    out.extend_from_slice(&segment.offset_expr_bytes);
+                },
+            }
+            write_u32(out, segment.data_bytes.len() as u32);
+            out.extend_from_slice(&segment.data_bytes);
+        });
+
+        self.custom.write(&mut out, &module.custom_sections);
+        Ok(out)
+    }
+}
+
+/// Serializes a [`Module`] to a core WebAssembly binary
+pub fn encode_module(module: &Module) -> Result<Vec<u8>> {
+    ModuleEncoder::new(module).encode()
+}
+
+/// Serializes a [`Component`] to a Component Model binary
+///
+/// [`Component`] keeps its definitions grouped by kind rather than in the
+/// order the binary declared them, so the encoder writes one section per
+/// kind in a fixed order: core types, types, imports, core modules, core
+/// instances, aliases, canonical functions, nested components, instances,
+/// values, start and exports. Indices in the structure are taken to refer
+/// to index spaces filled in that order.
+///
+/// Component Model value types may only nest other types by index. Nested
+/// compound types such as a `list<list<u8>>` built in memory are therefore
+/// given a type definition of their own in front of the type that uses
+/// them, and type indices are renumbered to match. Inline function and
+/// instance types of imports and exports are appended to the type section.
+#[derive(Debug, Clone)]
+pub struct ComponentEncoder<'a> {
+    component: &'a Component,
+    custom:    CustomSectionEdits,
+}
+
+impl<'a> ComponentEncoder<'a> {
+    /// Create an encoder for `component`
+    pub fn new(component: &'a Component) -> Self {
+        Self {
+            component,
+            custom: CustomSectionEdits::default(),
+        }
+    }
+
+    /// Drop the `component-name` section generated from the component name
+    pub fn strip_custom_sections(mut self) -> Self {
+        self.custom.strip_all = true;
+        self
+    }
+
+    /// Drop the custom sections named `name`
+    pub fn strip_custom_section(mut self, name: &str) -> Self {
+        self.custom.strip.push(name.to_string());
+        self
+    }
+
+    /// Add a custom section, replacing existing sections of the same name
+    pub fn with_custom_section(mut self, name: &str, data: Vec<u8>) -> Self {
+        self.custom.add.push(CustomSection::new(name.to_string(), data));
+        self
+    }
+
+    /// Encode the component
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for structures the binary format cannot express,
+    /// such as a `void` value type outside a `result`, function types with
+    /// several unnamed results, or canonical options recorded as functions.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let component = self.component;
+        let mut out = Vec::new();
+        out.extend_from_slice(&COMPONENT_MAGIC);
+        out.extend_from_slice(&COMPONENT_VERSION);
+
+        let mut core_types = Vec::new();
+        for core_type in &component.core_types {
+            core_types.push(encode_core_type(core_type)?);
+        }
+        write_entries_section(&mut out, COMPONENT_CORE_TYPE_SECTION_ID, &core_types);
+
+        // Types come first so that hoisted definitions keep the other index
+        // spaces untouched
+        let mut types = TypeScope::top_level();
+        for ty in &component.types {
+            types.declare(ty)?;
+        }
+        let mut import_descs = Vec::new();
+        for import in &component.imports {
+            let mut desc = Vec::new();
+            types.extern_desc(&mut desc, &import.ty)?;
+            import_descs.push(desc);
+        }
+        let mut export_descs = Vec::new();
+        for export in &component.exports {
+            let mut desc = Vec::new();
+            if let Some(ty) = &export.ty {
+                desc.push(0x01);
+                types.extern_desc(&mut desc, ty)?;
+            } else {
+                desc.push(0x00);
+            }
+            export_descs.push(desc);
+        }
+        write_entries_section(&mut out, COMPONENT_TYPE_SECTION_ID, &types.entries);
+        types.written = types.entries.len();
+
+        let imports = component.imports.iter().zip(import_descs).map(|(import, desc)| {
+            let name = if import.name.namespace.is_empty() {
+                import.name.name.clone()
+            } else {
+                format!("{}:{}", import.name.namespace, import.name.name)
+            };
+            let mut entry = extern_name(&name);
+            entry.extend_from_slice(&desc);
+            entry
+        });
+        write_entries_section(&mut out, COMPONENT_IMPORT_SECTION_ID, &imports.collect::<Vec<_>>());
+
+        for module in &component.modules {
+            let binary = ModuleEncoder::new(module).encode()?;
+            write_section(&mut out, COMPONENT_CORE_MODULE_SECTION_ID, &binary);
+        }
+
+        let core_instances: Vec<_> =
+            component.core_instances.iter().map(encode_core_instance).collect();
+        write_entries_section(&mut out, COMPONENT_CORE_INSTANCE_SECTION_ID, &core_instances);
+
+        let aliases: Vec<_> = component.aliases.iter().map(encode_alias).collect();
+        write_entries_section(&mut out, COMPONENT_ALIAS_SECTION_ID, &aliases);
+
+        let mut canons = Vec::new();
+        for canon in &component.canonicals {
+            canons.push(encode_canon(canon, &types)?);
+        }
+        write_entries_section(&mut out, COMPONENT_CANON_SECTION_ID, &canons);
+
+        for nested in &component.components {
+            let binary = ComponentEncoder::new(nested).encode()?;
+            write_section(&mut out, COMPONENT_COMPONENT_SECTION_ID, &binary);
+        }
+
+        let instances: Vec<_> =
+            component.instances.iter().map(|instance| encode_instance(instance, &types)).collect();
+        write_entries_section(&mut out, COMPONENT_INSTANCE_SECTION_ID, &instances);
+
+        let mut values = Vec::new();
+        for value in &component.values {
+            let mut entry = Vec::new();
+            types.val_type(&mut entry, &value.ty)?;
+            write_u32(&mut entry, value.data.len() as u32);
+            entry.extend_from_slice(&value.data);
+            values.push(entry);
+        }
+        if !values.is_empty() {
+            // Value types are encoded after the type section was written, so
+            // any definitions they needed would be lost
+            if types.entries.len() != types.written {
+                return Err(Error::validation_error(
+                    "Component value types must be primitive or refer to a type by index",
+                ));
+            }
+            write_entries_section(&mut out, COMPONENT_VALUE_SECTION_ID, &values);
+        }
+
+        if let Some(start) = &component.start {
+            let mut body = Vec::new();
+            write_u32(&mut body, start.func_idx);
+            write_u32(&mut body, start.args.len() as u32);
+            for arg in &start.args {
+                write_u32(&mut body, *arg);
+            }
+            write_u32(&mut body, start.results);
+            write_section(&mut out, COMPONENT_START_SECTION_ID, &body);
+        }
+
+        let exports = component.exports.iter().zip(export_descs).map(|(export, desc)| {
+            let mut entry = extern_name(&export.name.name);
+            write_sort_idx(&mut entry, &export.sort, export.idx, &types);
+            entry.extend_from_slice(&desc);
+            entry
+        });
+        write_entries_section(&mut out, COMPONENT_EXPORT_SECTION_ID, &exports.collect::<Vec<_>>());
+
+        let mut generated = Vec::new();
+        if let Some(name) = &component.name {
+            // `component-name` subsection 0 holds the component's own name
+            let subsection = write_string(name);
+            let mut data = vec![0x00];
+            write_u32(&mut data, subsection.len() as u32);
+            data.extend_from_slice(&subsection);
+            generated.push(CustomSection::new("component-name".to_string(), data));
+        }
+        self.custom.write(&mut out, &generated);
+        Ok(out)
+    }
+}
+
+/// Serializes a [`Component`] to a Component Model binary
+pub fn encode_component(component: &Component) -> Result<Vec<u8>> {
+    ComponentEncoder::new(component).encode()
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&write_leb128_u32(value));
+}
+
+fn write_section(out: &mut Vec<u8>, id: u8, body: &[u8]) {
+    out.push(id);
+    write_u32(out, body.len() as u32);
+    out.extend_from_slice(body);
+}
+
+/// Write a section holding a vector of `items`, unless there are none
+fn write_vec_section<T>(
+    out: &mut Vec<u8>,
+    id: u8,
+    items: &[T],
+    mut write_item: impl FnMut(&mut Vec<u8>, &T),
+) {
+    if items.is_empty() {
+        return;
+    }
+    let mut body = Vec::new();
+    write_u32(&mut body, items.len() as u32);
+    for item in items {
+        write_item(&mut body, item);
+    }
+    write_section(out, id, &body);
+}
+
+/// Write a section holding a vector of already encoded entries
+fn write_entries_section(out: &mut Vec<u8>, id: u8, entries: &[Vec<u8>]) {
+    write_vec_section(out, id, entries, |out, entry| out.extend_from_slice(entry));
+}
+
+fn write_value_type(out: &mut Vec<u8>, ty: ValueType) {
+    match ty {
+        // (ref null? $t) is followed by its heap type as an s33 index
+        ValueType::TypedFuncRef(type_idx, nullable) => {
+            out.push(if nullable { 0x63 } else { 0x64 });
+            out.extend_from_slice(&write_leb128_i64(i64::from(type_idx)));
+        },
+        _ => out.push(ty.to_binary()),
+    }
+}
+
+fn write_ref_type(out: &mut Vec<u8>, ty: RefType) {
+    write_value_type(out, ty.to_value_type());
+}
+
+fn write_core_func_type(out: &mut Vec<u8>, ty: &CleanCoreFuncType) {
+    write_func_signature(out, &ty.params, &ty.results);
+}
+
+fn write_func_signature(out: &mut Vec<u8>, params: &[ValueType], results: &[ValueType]) {
+    out.push(CORE_DEF_FUNC);
+    for types in [params, results] {
+        write_u32(out, types.len() as u32);
+        for ty in types {
+            write_value_type(out, *ty);
+        }
+    }
+}
+
+fn write_limits(out: &mut Vec<u8>, flags: u8, min: u64, max: Option<u64>, wide: bool) {
+    out.push(flags | u8::from(max.is_some()));
+    for value in Some(min).into_iter().chain(max) {
+        if wide {
+            out.extend_from_slice(&write_leb128_u64(value));
+        } else {
+            write_u32(out, value as u32);
+        }
+    }
+}
+
+fn write_table_type(out: &mut Vec<u8>, table: &Table) {
+    write_ref_type(out, table.element_type);
+    write_table_limits(out, &table.limits);
+}
+
+fn write_table_limits(out: &mut Vec<u8>, limits: &Limits) {
+    write_limits(out, 0, u64::from(limits.min), limits.max.map(u64::from), false);
+}
+
+fn write_memory_type(out: &mut Vec<u8>, memory: &Memory) {
+    let flags = if memory.shared { 0x02 } else { 0 } | if memory.memory64 { 0x04 } else { 0 };
+    write_limits(
+        out,
+        flags,
+        u64::from(memory.limits.min),
+        memory.limits.max.map(u64::from),
+        memory.memory64,
+    );
+}
+
+fn write_global_type(out: &mut Vec<u8>, global: &FormatGlobalType) {
+    write_value_type(out, global.value_type);
+    out.push(u8::from(global.mutable));
+}
+
+fn write_element_segment(
+    out: &mut Vec<u8>,
+    segment: &crate::pure_format_types::PureElementSegment,
+) {
+    let expressions = matches!(segment.init_data, PureElementInit::ExpressionBytes(_));
+    let funcref = segment.element_type == RefType::Funcref;
+    // Bit 0: passive or declarative, bit 1: explicit table or declarative,
+    // bit 2: expressions instead of function indices
+    let (mut flags, table) = match segment.mode {
+        PureElementMode::Active { table_index: 0, .. } if funcref => (0x00, None),
+        PureElementMode::Active { table_index, .. } => (0x02, Some(table_index)),
+        PureElementMode::Passive => (0x01, None),
+        PureElementMode::Declared => (0x03, None),
+    };
+    if expressions {
+        flags |= 0x04;
+    }
+    out.push(flags);
+    if let Some(table) = table {
+        write_u32(out, table);
+    }
+    if matches!(segment.mode, PureElementMode::Active { .. }) {
+        out.extend_from_slice(&segment.offset_expr_bytes);
+    }
+    if flags & 0x03 != 0 {
+        if expressions {
+            write_ref_type(out, segment.element_type);
+        } else {
+            // elemkind funcref
+            out.push(0x00);
+        }
+    }
+    match &segment.init_data {
+        PureElementInit::FunctionIndices(indices) => {
+            write_u32(out, indices.len() as u32);
+            for index in indices {
+                write_u32(out, *index);
+            }
+        },
+        PureElementInit::ExpressionBytes(exprs) => {
+            write_u32(out, exprs.len() as u32);
+            for expr in exprs {
+                out.extend_from_slice(expr);
+            }
+        },
+    }
+}
+
+/// `importname'` / `exportname'` holding a plain name
+fn extern_name(name: &str) -> Vec<u8> {
+    let mut out = vec![0x00];
+    out.extend_from_slice(&write_string(name));
+    out
+}
+
+fn core_sort_byte(sort: CoreSort) -> u8 {
+    match sort {
+        CoreSort::Function => COMPONENT_CORE_SORT_FUNC,
+        CoreSort::Table => COMPONENT_CORE_SORT_TABLE,
+        CoreSort::Memory => COMPONENT_CORE_SORT_MEMORY,
+        CoreSort::Global => COMPONENT_CORE_SORT_GLOBAL,
+        CoreSort::Type => COMPONENT_CORE_SORT_TYPE,
+        CoreSort::Module => COMPONENT_CORE_SORT_MODULE,
+        CoreSort::Instance => COMPONENT_CORE_SORT_INSTANCE,
+    }
+}
+
+fn write_sort(out: &mut Vec<u8>, sort: &Sort) {
+    match sort {
+        Sort::Core(core) => out.extend_from_slice(&[COMPONENT_SORT_CORE, core_sort_byte(*core)]),
+        Sort::Function => out.push(COMPONENT_SORT_FUNC),
+        Sort::Value => out.push(COMPONENT_SORT_VALUE),
+        Sort::Type => out.push(COMPONENT_SORT_TYPE),
+        Sort::Component => out.push(COMPONENT_SORT_COMPONENT),
+        Sort::Instance => out.push(COMPONENT_SORT_INSTANCE),
+    }
+}
+
+fn write_sort_idx(out: &mut Vec<u8>, sort: &Sort, idx: u32, types: &TypeScope) {
+    write_sort(out, sort);
+    let idx = if matches!(sort, Sort::Type) { types.index(idx) } else { idx };
+    write_u32(out, idx);
+}
+
+fn write_core_extern(out: &mut Vec<u8>, ty: &CoreExternType, type_idx: Option<u32>) {
+    match ty {
+        CoreExternType::Function { .. } => {
+            out.push(0x00);
+            write_u32(out, type_idx.unwrap_or(0));
+        },
+        CoreExternType::Table {
+            element_type,
+            min,
+            max,
+        } => {
+            out.push(0x01);
+            write_value_type(out, *element_type);
+            write_limits(out, 0, u64::from(*min), max.map(u64::from), false);
+        },
+        CoreExternType::Memory { min, max, shared } => {
+            out.push(0x02);
+            let flags = if *shared { 0x02 } else { 0 };
+            write_limits(out, flags, u64::from(*min), max.map(u64::from), false);
+        },
+        CoreExternType::Global {
+            value_type,
+            mutable,
+        } => {
+            out.push(0x03);
+            write_value_type(out, *value_type);
+            out.push(u8::from(*mutable));
+        },
+    }
+}
+
+fn encode_core_type(core_type: &CoreType) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match &core_type.definition {
+        CoreTypeDefinition::Function { params, results } => {
+            write_func_signature(&mut out, params, results);
+        },
+        CoreTypeDefinition::Module { imports, exports } => {
+            // Function imports and exports refer to a type declared inside
+            // the module type
+            let mut decls = Vec::new();
+            let mut local_types = 0u32;
+            let mut declare = |decls: &mut Vec<Vec<u8>>, ty: &CoreExternType| {
+                if let CoreExternType::Function { params, results } = ty {
+                    let mut decl = vec![0x01];
+                    write_func_signature(&mut decl, params, results);
+                    decls.push(decl);
+                    local_types += 1;
+                    Some(local_types - 1)
+                } else {
+                    None
+                }
+            };
+            for (module, name, ty) in imports {
+                let type_idx = declare(&mut decls, ty);
+                let mut decl = vec![0x00];
+                decl.extend_from_slice(&write_string(module));
+                decl.extend_from_slice(&write_string(name));
+                write_core_extern(&mut decl, ty, type_idx);
+                decls.push(decl);
+            }
+            for (name, ty) in exports {
+                let type_idx = declare(&mut decls, ty);
+                let mut decl = vec![0x03];
+                decl.extend_from_slice(&write_string(name));
+                write_core_extern(&mut decl, ty, type_idx);
+                decls.push(decl);
+            }
+            out.push(CORE_DEF_MODULE);
+            write_u32(&mut out, decls.len() as u32);
+            for decl in decls {
+                out.extend_from_slice(&decl);
+            }
+        },
+    }
+    Ok(out)
+}
+
+fn encode_core_instance(instance: &CoreInstance) -> Vec<u8> {
+    let mut out = Vec::new();
+    match &instance.instance_expr {
+        CoreInstanceExpr::ModuleReference {
+            module_idx,
+            arg_refs,
+        } => {
+            out.push(0x00);
+            write_u32(&mut out, *module_idx);
+            write_u32(&mut out, arg_refs.len() as u32);
+            for arg in arg_refs {
+                out.extend_from_slice(&write_string(&arg.name));
+                out.push(arg.kind);
+                write_u32(&mut out, arg.idx);
+            }
+        },
+        CoreInstanceExpr::InlineExports(exports) => {
+            out.push(0x01);
+            write_u32(&mut out, exports.len() as u32);
+            for export in exports {
+                out.extend_from_slice(&write_string(&export.name));
+                out.push(core_sort_byte(export.sort));
+                write_u32(&mut out, export.idx);
+            }
+        },
+    }
+    out
+}
+
+fn encode_instance(instance: &Instance, types: &TypeScope) -> Vec<u8> {
+    let mut out = Vec::new();
+    match &instance.instance_expr {
+        InstanceExpr::ComponentReference {
+            component_idx,
+            arg_refs,
+        } => {
+            out.push(0x00);
+            write_u32(&mut out, *component_idx);
+            write_u32(&mut out, arg_refs.len() as u32);
+            for arg in arg_refs {
+                out.extend_from_slice(&write_string(&arg.name));
+                write_sort_idx(&mut out, &arg.sort, arg.idx, types);
+            }
+        },
+        InstanceExpr::InlineExports(exports) => {
+            out.push(0x01);
+            write_u32(&mut out, exports.len() as u32);
+            for export in exports {
+                out.extend_from_slice(&extern_name(&export.name));
+                write_sort_idx(&mut out, &export.sort, export.idx, types);
+            }
+        },
+    }
+    out
+}
+
+fn encode_alias(alias: &Alias) -> Vec<u8> {
+    let mut out = Vec::new();
+    match &alias.target {
+        AliasTarget::InstanceExport {
+            instance_idx,
+            name,
+            kind,
+        } => {
+            write_sort(&mut out, kind);
+            out.push(0x00);
+            write_u32(&mut out, *instance_idx);
+            out.extend_from_slice(&write_string(name));
+        },
+        AliasTarget::CoreInstanceExport {
+            instance_idx,
+            name,
+            kind,
+        } => {
+            write_sort(&mut out, &Sort::Core(*kind));
+            out.push(0x01);
+            write_u32(&mut out, *instance_idx);
+            out.extend_from_slice(&write_string(name));
+        },
+        AliasTarget::Outer { count, kind, idx } => {
+            // Outer indices refer to an enclosing component's index spaces
+            write_sort(&mut out, kind);
+            out.push(0x02);
+            write_u32(&mut out, *count);
+            write_u32(&mut out, *idx);
+        },
+    }
+    out
+}
+
+/// Write a vector of canonical options
+fn write_canon_options(
+    out: &mut Vec<u8>,
+    encoding: &Option<StringEncoding>,
+    memory: Option<u32>,
+    realloc: Option<u32>,
+    post_return: Option<u32>,
+    is_async: bool,
+) {
+    let mut options = Vec::new();
+    let mut count = 0u32;
+    if let Some(encoding) = encoding {
+        options.push(match encoding {
+            // ASCII is a subset of UTF-8, which has no option of its own
+            StringEncoding::UTF8 | StringEncoding::ASCII => 0x00,
+            StringEncoding::UTF16 => 0x01,
+            StringEncoding::Latin1 => 0x02,
+        });
+        count += 1;
+    }
+    for (tag, index) in [(0x03, memory), (0x04, realloc), (0x05, post_return)] {
+        if let Some(index) = index {
+            options.push(tag);
+            write_u32(&mut options, index);
+            count += 1;
+        }
+    }
+    if is_async {
+        options.push(0x06);
+        count += 1;
+    }
+    write_u32(out, count);
+    out.extend_from_slice(&options);
+}
+
+fn encode_canon(canon: &Canon, types: &TypeScope) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match &canon.operation {
+        CanonOperation::Lift {
+            func_idx,
+            type_idx,
+            options,
+        } => {
+            out.extend_from_slice(&[0x00, 0x00]);
+            write_u32(&mut out, *func_idx);
+            write_canon_options(
+                &mut out,
+                &options.string_encoding,
+                options.memory_idx,
+                options.realloc_func_idx,
+                options.post_return_func_idx,
+                options.is_async,
+            );
+            write_u32(&mut out, types.index(*type_idx));
+        },
+        CanonOperation::Async {
+            func_idx,
+            type_idx,
+            options,
+        } => {
+            out.extend_from_slice(&[0x00, 0x00]);
+            write_u32(&mut out, *func_idx);
+            write_canon_options(
+                &mut out,
+                &options.string_encoding,
+                Some(options.memory_idx),
+                options.realloc_func_idx,
+                None,
+                true,
+            );
+            write_u32(&mut out, types.index(*type_idx));
+        },
+        CanonOperation::Lower { func_idx, options } => {
+            out.extend_from_slice(&[0x01, 0x00]);
+            write_u32(&mut out, *func_idx);
+            write_canon_options(
+                &mut out,
+                &options.string_encoding,
+                options.memory_idx,
+                options.realloc_func_idx,
+                None,
+                options.is_async,
+            );
+        },
+        CanonOperation::Resource(operation) => {
+            let (tag, type_idx) = match operation {
+                FormatResourceOperation::New(new) => (0x02, new.type_idx),
+                FormatResourceOperation::Drop(drop) => (0x03, drop.type_idx),
+                FormatResourceOperation::Rep(rep) => (0x04, rep.type_idx),
+            };
+            out.push(tag);
+            write_u32(&mut out, types.index(type_idx));
+        },
+        CanonOperation::Realloc { .. }
+        | CanonOperation::PostReturn { .. }
+        | CanonOperation::MemoryCopy { .. } => {
+            return Err(Error::validation_error(
+                "Canonical option has no binary encoding as a definition",
+            ));
+        },
+    }
+    Ok(out)
+}
+
+/// A type index space being filled while encoding
+///
+/// At the top level the entries make up the type section and the declared
+/// types are renumbered around hoisted definitions. Inside an instance or
+/// component type the entries are declarators and type definitions are
+/// wrapped as such.
+#[derive(Debug, Default)]
+struct TypeScope {
+    /// Type section entries or declarators, in order
+    entries:  Vec<Vec<u8>>,
+    /// `externdesc` tag each type index is referred to with, if any
+    kinds:    Vec<Option<u8>>,
+    /// Index each declared type ended up at
+    declared: Vec<u32>,
+    /// Entries already written to the binary
+    written:  usize,
+    /// Whether this is the scope of an instance or component type
+    local:    bool,
+}
+
+impl TypeScope {
+    fn top_level() -> Self {
+        Self::default()
+    }
+
+    fn local() -> Self {
+        Self {
+            local: true,
+            ..Self::default()
+        }
+    }
+
+    /// Final index of type `idx` of the structure
+    fn index(&self, idx: u32) -> u32 {
+        if self.local {
+            return idx;
+        }
+        match self.declared.get(idx as usize) {
+            Some(index) => *index,
+            // Types defined after the type section, by imports or aliases
+            None => idx - self.declared.len() as u32 + self.kinds.len() as u32,
+        }
+    }
+
+    /// Add a type definition, returning its index
+    fn define(&mut self, def: Vec<u8>, kind: Option<u8>) -> u32 {
+        if self.local {
+            let mut decl = vec![0x01];
+            decl.extend_from_slice(&def);
+            self.entries.push(decl);
+        } else {
+            self.entries.push(def);
+        }
+        self.kinds.push(kind);
+        (self.kinds.len() - 1) as u32
+    }
+
+    /// Define a type of the component's type section
+    fn declare(&mut self, ty: &ComponentType) -> Result<()> {
+        let (def, kind) = match &ty.definition {
+            ComponentTypeDefinition::Function { params, results } => {
+                (self.func_type(params, results)?, Some(EXTERN_FUNC))
+            },
+            ComponentTypeDefinition::Instance { exports } => {
+                (instance_type(exports)?, Some(EXTERN_INSTANCE))
+            },
+            ComponentTypeDefinition::Component { imports, exports } => {
+                (component_type(imports, exports)?, Some(EXTERN_COMPONENT))
+            },
+            ComponentTypeDefinition::Value(ty) => (self.def_val_type(ty)?, None),
+            ComponentTypeDefinition::Resource { representation, .. } => {
+                let rep = match representation {
+                    ResourceRepresentation::Handle64 => 0x7E,
+                    _ => 0x7F,
+                };
+                // No destructor
+                (vec![DEF_RESOURCE, rep, 0x00], None)
+            },
+        };
+        let index = self.define(def, kind);
+        self.declared.push(index);
+        Ok(())
+    }
+
+    /// Write a `valtype`, defining compound types first
+    fn val_type(&mut self, out: &mut Vec<u8>, ty: &FormatValType) -> Result<()> {
+        if let Some(byte) = primitive(ty) {
+            out.push(byte);
+            return Ok(());
+        }
+        let index = match ty {
+            FormatValType::Ref(idx) => self.index(*idx),
+            _ => {
+                let def = self.def_val_type(ty)?;
+                self.define(def, None)
+            },
+        };
+        write_u32(out, index);
+        Ok(())
+    }
+
+    /// Encode a `defvaltype`
+    fn def_val_type(&mut self, ty: &FormatValType) -> Result<Vec<u8>> {
+        if let Some(byte) = primitive(ty) {
+            return Ok(vec![byte]);
+        }
+        let mut out = Vec::new();
+        match ty {
+            FormatValType::Record(fields) => {
+                out.push(DEF_RECORD);
+                write_u32(&mut out, fields.len() as u32);
+                for (name, field) in fields {
+                    out.extend_from_slice(&write_string(name));
+                    self.val_type(&mut out, field)?;
+                }
+            },
+            FormatValType::Variant(cases) => {
+                out.push(DEF_VARIANT);
+                write_u32(&mut out, cases.len() as u32);
+                for (name, case) in cases {
+                    out.extend_from_slice(&write_string(name));
+                    match case {
+                        Some(case) => {
+                            out.push(0x01);
+                            self.val_type(&mut out, case)?;
+                        },
+                        None => out.push(0x00),
+                    }
+                    // No `refines`
+                    out.push(0x00);
+                }
+            },
+            FormatValType::List(element) => {
+                out.push(DEF_LIST);
+                self.val_type(&mut out, element)?;
+            },
+            FormatValType::FixedList(element, length) => {
+                out.push(DEF_FIXED_LIST);
+                self.val_type(&mut out, element)?;
+                write_u32(&mut out, *length);
+            },
+            FormatValType::Tuple(elements) => {
+                out.push(DEF_TUPLE);
+                write_u32(&mut out, elements.len() as u32);
+                for element in elements {
+                    self.val_type(&mut out, element)?;
+                }
+            },
+            FormatValType::Flags(names) | FormatValType::Enum(names) => {
+                out.push(if matches!(ty, FormatValType::Flags(_)) { DEF_FLAGS } else { DEF_ENUM });
+                write_u32(&mut out, names.len() as u32);
+                for name in names {
+                    out.extend_from_slice(&write_string(name));
+                }
+            },
+            FormatValType::Option(inner) => {
+                out.push(DEF_OPTION);
+                self.val_type(&mut out, inner)?;
+            },
+            // The decoder records a result without an ok type as `void`
+            FormatValType::Result(ok) => {
+                out.push(DEF_RESULT);
+                if matches!(**ok, FormatValType::Void) {
+                    out.push(0x00);
+                } else {
+                    out.push(0x01);
+                    self.val_type(&mut out, ok)?;
+                }
+                out.push(0x00);
+            },
+            FormatValType::Own(idx) => {
+                out.push(DEF_OWN);
+                write_u32(&mut out, self.index(*idx));
+            },
+            FormatValType::Borrow(idx) => {
+                out.push(DEF_BORROW);
+                write_u32(&mut out, self.index(*idx));
+            },
+            FormatValType::Ref(_) => {
+                return Err(Error::validation_error(
+                    "A type definition cannot be a reference to another type",
+                ));
+            },
+            _ => {
+                return Err(Error::validation_error(
+                    "Void is only encodable as the ok type of a result",
+                ));
+            },
+        }
+        Ok(out)
+    }
+
+    fn func_type(
+        &mut self,
+        params: &[(String, FormatValType)],
+        results: &[FormatValType],
+    ) -> Result<Vec<u8>> {
+        let mut out = vec![DEF_FUNC];
+        write_u32(&mut out, params.len() as u32);
+        for (name, ty) in params {
+            out.extend_from_slice(&write_string(name));
+            self.val_type(&mut out, ty)?;
+        }
+        match results {
+            [] => out.extend_from_slice(&[0x01, 0x00]),
+            [result] => {
+                out.push(0x00);
+                self.val_type(&mut out, result)?;
+            },
+            _ => {
+                return Err(Error::validation_error(
+                    "Function types may have at most one unnamed result",
+                ))
+            },
+        }
+        Ok(out)
+    }
+
+    /// Write the `externdesc` of an import or export
+    fn extern_desc(&mut self, out: &mut Vec<u8>, ty: &ExternType) -> Result<()> {
+        match ty {
+            ExternType::Module { type_idx } => {
+                out.extend_from_slice(&[EXTERN_MODULE, COMPONENT_CORE_SORT_MODULE]);
+                write_u32(out, *type_idx);
+            },
+            ExternType::Function { params, results } => {
+                let def = self.func_type(params, results)?;
+                let index = self.define(def, Some(EXTERN_FUNC));
+                out.push(EXTERN_FUNC);
+                write_u32(out, index);
+            },
+            ExternType::Value(ty) => {
+                out.extend_from_slice(&[EXTERN_VALUE, 0x01]);
+                self.val_type(out, ty)?;
+            },
+            // The decoder records references to function and instance types
+            // as plain type references, so the tag follows the referenced type
+            ExternType::Type(idx) => {
+                let index = self.index(*idx);
+                match self.kinds.get(index as usize).copied().flatten() {
+                    Some(kind) => out.push(kind),
+                    None => {
+                        out.extend_from_slice(&[EXTERN_TYPE, 0x00]);
+                        // A type export declares a new local type
+                        if self.local {
+                            self.kinds.push(None);
+                        }
+                    },
+                }
+                write_u32(out, index);
+            },
+            ExternType::Instance { exports } => {
+                let def = instance_type(exports)?;
+                let index = self.define(def, Some(EXTERN_INSTANCE));
+                out.push(EXTERN_INSTANCE);
+                write_u32(out, index);
+            },
+            ExternType::Component { type_idx } => {
+                out.push(EXTERN_COMPONENT);
+                write_u32(out, self.index(*type_idx));
+            },
+        }
+        Ok(())
+    }
+}
+
+fn primitive(ty: &FormatValType) -> Option<u8> {
+    Some(match ty {
+        FormatValType::Bool => PRIM_BOOL,
+        FormatValType::S8 => PRIM_S8,
+        FormatValType::U8 => PRIM_U8,
+        FormatValType::S16 => PRIM_S16,
+        FormatValType::U16 => PRIM_U16,
+        FormatValType::S32 => PRIM_S32,
+        FormatValType::U32 => PRIM_U32,
+        FormatValType::S64 => PRIM_S64,
+        FormatValType::U64 => PRIM_U64,
+        FormatValType::F32 => PRIM_F32,
+        FormatValType::F64 => PRIM_F64,
+        FormatValType::Char => PRIM_CHAR,
+        FormatValType::String => PRIM_STRING,
+        FormatValType::ErrorContext => PRIM_ERROR_CONTEXT,
+        _ => return None,
+    })
+}
+
+/// Encode an instance type from its exports
+fn instance_type(exports: &[(String, ExternType)]) -> Result<Vec<u8>> {
+    let mut scope = TypeScope::local();
+    for (name, ty) in exports {
+        scope.extern_decl(0x04, name, ty)?;
+    }
+    Ok(scope.into_declarators(DEF_INSTANCE))
+}
+
+/// Encode a component type from its imports and exports
+fn component_type(
+    imports: &[(String, String, ExternType)],
+    exports: &[(String, ExternType)],
+) -> Result<Vec<u8>> {
+    let mut scope = TypeScope::local();
+    for (namespace, name, ty) in imports {
+        let name = if namespace.is_empty() {
+            name.clone()
+        } else {
+            format!("{}:{}", namespace, name)
+        };
+        scope.extern_decl(0x03, &name, ty)?;
+    }
+    for (name, ty) in exports {
+        scope.extern_decl(0x04, name, ty)?;
+    }
+    Ok(scope.into_declarators(DEF_COMPONENT))
+}
+
+impl TypeScope {
+    /// Add an import (`0x03`) or export (`0x04`) declarator
+    fn extern_decl(&mut self, tag: u8, name: &str, ty: &ExternType) -> Result<()> {
+        let mut decl = vec![tag];
+        decl.extend_from_slice(&extern_name(name));
+        self.extern_desc(&mut decl, ty)?;
+        self.entries.push(decl);
+        Ok(())
+    }
+
+    fn into_declarators(self, tag: u8) -> Vec<u8> {
+        let mut out = vec![tag];
+        write_u32(&mut out, self.entries.len() as u32);
+        for entry in self.entries {
+            out.extend_from_slice(&entry);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::{
+        MemoryType,
+        TableType,
+    };
+
+    use super::*;
+    use crate::{
+        component::{
+            Export,
+            ExportName,
+            Import,
+            ImportName,
+            LiftOptions,
+        },
+        module::{
+            Export as ModuleExport,
+            Function,
+            Global,
+            Import as ModuleImport,
+        },
+        pure_format_types::{
+            PureDataSegment,
+            PureElementSegment,
+        },
+    };
+
+    fn sample_module() -> Module {
+        let mut module = Module::new();
+        module.types.push(CleanCoreFuncType {
+            params:  vec![ValueType::I32],
+            results: vec![ValueType::I32],
+        });
+        module.imports.push(ModuleImport {
+            module: "env".to_string(),
+            name:   "log".to_string(),
+            desc:   ImportDesc::Function(0),
+        });
+        // Placeholder of the imported function, then one defined function
+        module.functions.push(Function {
+            type_idx: 0,
+            locals:   Vec::new(),
+            code:     Vec::new(),
+        });
+        module.functions.push(Function {
+            type_idx: 0,
+            locals:   vec![ValueType::I64, ValueType::I64, ValueType::I32],
+            code:     vec![0x20, 0x00, END],
+        });
+        module.tables.push(TableType {
+            element_type: RefType::Funcref,
+            limits:       Limits::new(1, None),
+        });
+        module.memories.push(MemoryType::new(Limits::new(1, Some(2)), false));
+        module.globals.push(Global {
+            global_type: FormatGlobalType {
+                value_type: ValueType::I32,
+                mutable:    true,
+            },
+            init:        vec![0x41, 0x2A, END],
+        });
+        module.exports.push(ModuleExport {
+            name:  "run".to_string(),
+            kind:  ExportKind::Function,
+            index: 1,
+        });
+        module.elements.push(PureElementSegment {
+            mode:              PureElementMode::Active {
+                table_index:     0,
+                offset_expr_len: 3,
+            },
+            element_type:      RefType::Funcref,
+            offset_expr_bytes: vec![0x41, 0x00, END],
+            init_data:         PureElementInit::FunctionIndices(vec![1]),
+        });
+        module.data.push(PureDataSegment {
+            mode:              PureDataMode::Passive,
+            offset_expr_bytes: Vec::new(),
+            data_bytes:        b"hi".to_vec(),
+        });
+        module.custom_sections.push(CustomSection::new("producers".to_string(), vec![0]));
+        module
+    }
+
+    #[test]
+    fn test_module_encodes_all_sections() {
+        let binary = encode_module(&sample_module()).unwrap();
+        #[rustfmt::skip]
+        let expected: &[u8] = &[
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+            // type: (i32) -> i32
+            0x01, 0x06, 0x01, 0x60, 0x01, 0x7F, 0x01, 0x7F,
+            // import env.log func 0
+            0x02, 0x0B, 0x01, 0x03, b'e', b'n', b'v', 0x03, b'l', b'o', b'g', 0x00, 0x00,
+            // function: one defined function of type 0
+            0x03, 0x02, 0x01, 0x00,
+            // table funcref 1
+            0x04, 0x04, 0x01, 0x70, 0x00, 0x01,
+            // memory 1 2
+            0x05, 0x04, 0x01, 0x01, 0x01, 0x02,
+            // global mut i32 = 42
+            0x06, 0x06, 0x01, 0x7F, 0x01, 0x41, 0x2A, 0x0B,
+            // export "run" func 1
+            0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n', 0x00, 0x01,
+            // element: active table 0 at 0, func 1
+            0x09, 0x07, 0x01, 0x00, 0x41, 0x00, 0x0B, 0x01, 0x01,
+            // data count
+            0x0C, 0x01, 0x01,
+            // code: locals 2 x i64, 1 x i32; local.get 0
+            0x0A, 0x0A, 0x01, 0x08, 0x02, 0x02, 0x7E, 0x01, 0x7F, 0x20, 0x00, 0x0B,
+            // data: passive "hi"
+            0x0B, 0x05, 0x01, 0x01, 0x02, b'h', b'i',
+            // custom "producers"
+            0x00, 0x0B, 0x09, b'p', b'r', b'o', b'd', b'u', b'c', b'e', b'r', b's', 0x00,
+        ];
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn test_module_custom_sections_can_be_replaced() {
+        let module = sample_module();
+        let stripped = ModuleEncoder::new(&module).strip_custom_sections().encode().unwrap();
+        let full = encode_module(&module).unwrap();
+        assert_eq!(stripped.len(), full.len() - 13);
+
+        let injected = ModuleEncoder::new(&module)
+            .with_custom_section("producers", vec![1, 2])
+            .with_custom_section("build-id", vec![3])
+            .encode()
+            .unwrap();
+        assert!(injected.ends_with(&[
+            0x00, 0x0C, 0x09, b'p', b'r', b'o', b'd', b'u', b'c', b'e', b'r', b's', 1, 2, 0x00,
+            0x0A, 0x08, b'b', b'u', b'i', b'l', b'd', b'-', b'i', b'd', 3,
+        ]));
+        assert_eq!(injected.len(), full.len() + 13);
+
+        let mut broken = module.clone();
+        broken.functions.clear();
+        assert!(encode_module(&broken).is_err());
+    }
+
+    #[test]
+    fn test_component_hoists_nested_types() {
+        let mut component = Component::new();
+        component.types.push(ComponentType {
+            definition: ComponentTypeDefinition::Function {
+                params:  vec![(
+                    "lines".to_string(),
+                    FormatValType::List(Box::new(FormatValType::String)),
+                )],
+                results: vec![FormatValType::U32],
+            },
+        });
+        component.imports.push(Import {
+            name: ImportName::new(String::new(), "count".to_string()),
+            ty:   ExternType::Type(0),
+        });
+        component.canonicals.push(Canon {
+            operation: CanonOperation::Lift {
+                func_idx: 0,
+                type_idx: 0,
+                options:  LiftOptions {
+                    memory_idx:           Some(0),
+                    string_encoding:      Some(StringEncoding::UTF8),
+                    realloc_func_idx:     None,
+                    post_return_func_idx: None,
+                    is_async:             false,
+                },
+            },
+        });
+        component.exports.push(Export {
+            name: ExportName::new("count".to_string()),
+            sort: Sort::Function,
+            idx:  1,
+            ty:   None,
+        });
+
+        let binary = encode_component(&component).unwrap();
+        #[rustfmt::skip]
+        let expected: &[u8] = &[
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x01, 0x00,
+            // types: 0 = list<string>, 1 = func(lines: 0) -> u32
+            0x07, 0x0E, 0x02, 0x70, 0x73, 0x40, 0x01, 0x05, b'l', b'i', b'n', b'e', b's', 0x00, 0x00, 0x79,
+            // import "count" (func 1)
+            0x0A, 0x0A, 0x01, 0x00, 0x05, b'c', b'o', b'u', b'n', b't', 0x01, 0x01,
+            // canon lift core func 0, utf8, memory 0, type 1
+            0x08, 0x09, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x00, 0x01,
+            // export "count" func 1
+            0x0B, 0x0B, 0x01, 0x00, 0x05, b'c', b'o', b'u', b'n', b't', 0x01, 0x01, 0x00,
+        ];
+        assert_eq!(binary, expected);
+    }
+}
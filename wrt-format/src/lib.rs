@@ -199,11 +199,17 @@ macro_rules! format {
     };
 }
 
+/// Common header of serialized artifacts
+#[cfg(feature = "std")]
+pub mod artifact;
 /// Abstract Syntax Tree types for WIT parsing (simplified version)
 #[cfg(feature = "std")]
 pub mod ast_simple;
 #[cfg(feature = "std")]
 pub use ast_simple as ast;
+/// Crash dumps of guest traps and host panics
+#[cfg(feature = "std")]
+pub mod crash_dump;
 /// WebAssembly binary format parsing and access
 pub mod binary;
 /// Bounded infrastructure for static memory allocation
@@ -222,6 +228,9 @@ macro_rules! format {
 pub mod compression;
 /// Conversion utilities for type system standardization
 pub mod conversion;
+/// Binary encoder for modules and components
+#[cfg(feature = "std")]
+pub mod encode;
 /// Error utilities for working with wrt-error types
 pub mod error;
 /// Incremental parser for efficient WIT re-parsing
@@ -246,8 +255,16 @@ macro_rules! format {
 /// Safe memory operations
 pub mod safe_memory;
 pub mod section;
+/// Ed25519 signing and signature verification of binaries
+#[cfg(feature = "signing")]
+pub mod signing;
 /// Streaming parser for no_std environments
 pub mod streaming;
+/// Interned name strings for decoded modules
+#[cfg(feature = "std")]
+pub mod string_pool;
+/// WebAssembly text format parsing and printing
+pub mod text;
 /// Type storage system for Component Model
 #[cfg(feature = "std")]
 pub mod type_store;
@@ -265,8 +282,12 @@ macro_rules! format {
 // pub mod wit_parser_types;
 // #[cfg(feature = "std")]
 // pub mod wit_parser_traits;
-// #[cfg(feature = "std")]
-// pub mod wit_parser;
+/// WIT documents: types, functions, worlds and `use` statements
+#[cfg(feature = "std")]
+pub mod wit_parser;
+/// WIT package resolution over several files and `deps/`
+#[cfg(feature = "std")]
+pub mod wit_resolve;
 // Bounded WIT parser for no_std environments
 #[cfg(feature = "wit-parsing")]
 pub mod wit_parser_bounded;
@@ -363,6 +384,8 @@ macro_rules! format {
 };
 pub use safe_memory::safe_slice;
 pub use section::{CustomSection, Section};
+#[cfg(feature = "std")]
+pub use string_pool::{PooledStr, StringPool, StringPoolStats};
 // Use the conversion module versions for consistency
 pub use types::{FormatBlockType, Limits, MemoryIndexType};
 pub use validation::Validatable;
@@ -1753,6 +1753,20 @@ pub fn new() -> Self {
         }
     }
 
+    /// Measure the footprint of the module's import and export names when
+    /// interned into a [`StringPool`](crate::string_pool::StringPool)
+    pub fn name_footprint(&self) -> crate::string_pool::StringPoolStats {
+        let mut pool = crate::string_pool::StringPool::new();
+        for import in &self.imports {
+            pool.intern(&import.module);
+            pool.intern(&import.name);
+        }
+        for export in &self.exports {
+            pool.intern(&export.name);
+        }
+        pool.stats()
+    }
+
     /// Convert a WebAssembly binary to a Module.
     ///
     /// This is a convenience method that wraps Binary::from_bytes +
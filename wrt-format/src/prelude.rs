@@ -5,51 +5,19 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
-// Core imports for both std and no_std environments
-// Import synchronization primitives for no_std
-// For a no_std implementation, we should use appropriate no_std compatible sync
-// primitives since wrt-sync is not implemented yet, we use spin-based locks as
-// a placeholder
+// Shared runtime-tier prelude
+pub use wrt_prelude::runtime::*;
+
+// Interior mutability for no_std builds
 #[cfg(not(feature = "std"))]
 pub use core::cell::{
     Cell,
     RefCell,
 };
-pub use core::{
-    any::Any,
-    cmp::{
-        Eq,
-        Ord,
-        PartialEq,
-        PartialOrd,
-    },
-    convert::{
-        TryFrom,
-        TryInto,
-    },
-    fmt,
-    fmt::{
-        Debug,
-        Display,
-    },
-    marker::PhantomData,
-    mem,
-    ops::{
-        Deref,
-        DerefMut,
-    },
-    slice,
-    str,
-};
 
-// Re-export from wrt-error
+// Error conversion traits beyond the tier's error types
 pub use wrt_error::{
-    codes,
-    kinds,
-    Error,
-    ErrorCategory,
     FromError,
-    Result,
     ToErrorCategory,
 };
 // Binary std/no_std choice
@@ -63,34 +31,18 @@
 // Re-export additional clean types when allocation is available
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use wrt_foundation::CleanExternType;
-// Conditional imports for safety features
-#[cfg(feature = "safety")]
-pub use wrt_foundation::MemoryProvider;
 // No-std memory provider
 #[cfg(all(feature = "safety", not(feature = "std")))]
 pub use wrt_foundation::NoStdProvider as NoStdMemoryProvider;
 // Re-export clean types from wrt-foundation
 pub use wrt_foundation::{
     // Legacy types for compatibility
-    types::{
-        BlockType,
-        RefType,
-        ValueType,
-    },
-    values::Value,
-    // Verification types
-    verification::VerificationLevel,
+    types::RefType,
     BoundedStack,
     // SafeMemory types
     SafeMemoryHandler,
     SafeSlice,
 };
-#[cfg(not(any(feature = "std")))]
-pub use wrt_foundation::{
-    BoundedMap,
-    BoundedString,
-    BoundedVec,
-};
 // Clean types without provider parameters - only when allocation is available
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use wrt_foundation::{
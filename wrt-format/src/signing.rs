@@ -0,0 +1,438 @@
+//! Ed25519 signing of modules and components.
+//!
+//! A signed binary ends with a [`SIGNATURE_SECTION_NAME`] custom section
+//! holding a SHA-256 digest of every section before it and one or more
+//! Ed25519 signatures. Each signature covers a manifest digest computed
+//! over the binary's preamble and the section digests, so any change to a
+//! signed section, a section inserted or dropped, or a section appended
+//! after the signature fails verification. The section payload is
+//!
+//! ```text
+//! format version       u8 (1)
+//! section count        LEB128 u32
+//! section digests      count × 32 bytes, SHA-256 of id, size and contents
+//! signature count      LEB128 u32
+//! signatures           count × (32-byte public key, 64-byte signature)
+//! ```
+//!
+//! [`verify`] checks a binary against a set of [`TrustAnchor`]s without
+//! allocating. With `std`, [`ModuleSigner`] adds signatures to a binary;
+//! signing an already signed binary adds a further signature.
+
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use ed25519_dalek::{
+    Signature,
+    VerifyingKey,
+};
+#[cfg(feature = "std")]
+use ed25519_dalek::{
+    Signer,
+    SigningKey,
+};
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::sha256::{
+    digests_equal,
+    Sha256,
+};
+
+#[cfg(feature = "std")]
+use crate::binary::with_alloc::write_leb128_u32;
+use crate::binary::{
+    read_leb128_u32,
+    read_string,
+    CUSTOM_SECTION_ID,
+    WASM_MAGIC,
+};
+
+/// Name of the custom section holding the signatures
+pub const SIGNATURE_SECTION_NAME: &str = "wrt-signature";
+
+/// Version of the signature section format
+pub const SIGNATURE_FORMAT_VERSION: u8 = 1;
+
+/// Domain separator of the manifest digest
+const MANIFEST_DOMAIN: &[u8] = b"wrt-signature-v1\0";
+
+/// Size of the module or component preamble
+const PREAMBLE_SIZE: usize = 8;
+
+/// Size of a public key
+const KEY_SIZE: usize = 32;
+
+/// Size of a signature
+const SIGNATURE_SIZE: usize = 64;
+
+/// Public key whose signatures are trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustAnchor {
+    key: VerifyingKey,
+}
+
+impl TrustAnchor {
+    /// Trust anchor of an Ed25519 public key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid public key
+    pub fn from_bytes(bytes: &[u8; KEY_SIZE]) -> Result<Self> {
+        VerifyingKey::from_bytes(bytes)
+            .map(|key| Self { key })
+            .map_err(|_| Error::validation_error("Invalid Ed25519 public key"))
+    }
+
+    /// Encoded public key
+    pub fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        self.key.to_bytes()
+    }
+}
+
+/// Outcome of a successful [`verify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedSignature {
+    /// Index of the trust anchor that signed the binary
+    pub anchor:   usize,
+    /// Number of sections the signature covers
+    pub sections: u32,
+}
+
+/// Verify that `binary` is signed by one of `trust_anchors`
+///
+/// # Errors
+///
+/// Returns a security error if the binary is not signed, was modified
+/// after signing, or carries no valid signature by a trust anchor, and a
+/// parse error if the binary or its signature section is malformed
+pub fn verify(binary: &[u8], trust_anchors: &[TrustAnchor]) -> Result<VerifiedSignature> {
+    let signed = SignedBinary::parse(binary)?
+        .ok_or_else(|| Error::security_access_denied("Module is not signed"))?;
+    signed.check_digests()?;
+    let manifest = signed.manifest();
+
+    let mut signatures = signed.signatures()?;
+    while let Some((key, signature)) = signatures.next()? {
+        let Some(anchor) = trust_anchors.iter().position(|anchor| anchor.to_bytes() == key) else {
+            continue;
+        };
+        trust_anchors[anchor]
+            .key
+            .verify_strict(&manifest, &Signature::from_bytes(&signature))
+            .map_err(|_| {
+                Error::new(
+                    ErrorCategory::Security,
+                    codes::VERIFICATION_FAILED,
+                    "Invalid module signature",
+                )
+            })?;
+        return Ok(VerifiedSignature { anchor, sections: signed.section_count });
+    }
+    Err(Error::security_access_denied("Module is not signed by a trusted key"))
+}
+
+/// Whether `binary` carries a signature section
+///
+/// # Errors
+///
+/// Returns an error if the binary or its signature section is malformed
+pub fn is_signed(binary: &[u8]) -> Result<bool> {
+    Ok(SignedBinary::parse(binary)?.is_some())
+}
+
+/// Signed sections and signature section of a binary
+struct SignedBinary<'a> {
+    /// Preamble and the sections covered by the signatures
+    covered:       &'a [u8],
+    /// Number of covered sections
+    section_count: u32,
+    /// Section digests recorded when signing
+    digests:       &'a [u8],
+    /// Signature count and signatures
+    signatures:    &'a [u8],
+}
+
+impl<'a> SignedBinary<'a> {
+    /// Split `binary` at its signature section, or `None` if it has none
+    fn parse(binary: &'a [u8]) -> Result<Option<Self>> {
+        if binary.len() < PREAMBLE_SIZE || binary[..4] != WASM_MAGIC {
+            return Err(Error::validation_parse_error("Not a WebAssembly binary"));
+        }
+        let mut pos = PREAMBLE_SIZE;
+        let mut section_count = 0u32;
+        while pos < binary.len() {
+            let start = pos;
+            let (contents, end) = section_at(binary, pos)?;
+            if binary[start] == CUSTOM_SECTION_ID {
+                let (name, name_len) = read_string(contents, 0)?;
+                if name == SIGNATURE_SECTION_NAME.as_bytes() {
+                    if end != binary.len() {
+                        return Err(modified());
+                    }
+                    return Self::read_payload(&binary[..start], section_count, &contents[name_len..])
+                        .map(Some);
+                }
+            }
+            section_count += 1;
+            pos = end;
+        }
+        Ok(None)
+    }
+
+    fn read_payload(covered: &'a [u8], sections: u32, payload: &'a [u8]) -> Result<Self> {
+        if payload.first() != Some(&SIGNATURE_FORMAT_VERSION) {
+            return Err(Error::validation_parse_error("Unsupported signature format version"));
+        }
+        let (section_count, len) = read_leb128_u32(payload, 1)?;
+        if section_count != sections {
+            return Err(modified());
+        }
+        let start = 1 + len;
+        let end = start
+            .checked_add(section_count as usize * 32)
+            .filter(|&end| end <= payload.len())
+            .ok_or_else(|| Error::validation_parse_error("Signature section truncated"))?;
+        Ok(Self {
+            covered,
+            section_count,
+            digests: &payload[start..end],
+            signatures: &payload[end..],
+        })
+    }
+
+    /// Check the recorded digests against the covered sections
+    fn check_digests(&self) -> Result<()> {
+        let mut pos = PREAMBLE_SIZE;
+        for expected in self.digests.chunks_exact(32) {
+            let (_, end) = section_at(self.covered, pos)?;
+            let digest = sha256_of(&self.covered[pos..end]);
+            if !digests_equal(&digest, expected.try_into().unwrap_or(&[0; 32])) {
+                return Err(modified());
+            }
+            pos = end;
+        }
+        Ok(())
+    }
+
+    /// Digest the signatures are computed over
+    fn manifest(&self) -> [u8; 32] {
+        manifest_digest(&self.covered[..PREAMBLE_SIZE], self.section_count, self.digests)
+    }
+
+    fn signatures(&self) -> Result<Signatures<'a>> {
+        let (count, len) = read_leb128_u32(self.signatures, 0)?;
+        if self.signatures.len() - len != count as usize * (KEY_SIZE + SIGNATURE_SIZE) {
+            return Err(Error::validation_parse_error("Malformed signature list"));
+        }
+        Ok(Signatures { entries: &self.signatures[len..] })
+    }
+}
+
+/// Public keys and signatures of a signature section
+struct Signatures<'a> {
+    entries: &'a [u8],
+}
+
+impl Signatures<'_> {
+    fn next(&mut self) -> Result<Option<([u8; KEY_SIZE], [u8; SIGNATURE_SIZE])>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        let (entry, rest) = self.entries.split_at(KEY_SIZE + SIGNATURE_SIZE);
+        self.entries = rest;
+        let mut key = [0; KEY_SIZE];
+        let mut signature = [0; SIGNATURE_SIZE];
+        key.copy_from_slice(&entry[..KEY_SIZE]);
+        signature.copy_from_slice(&entry[KEY_SIZE..]);
+        Ok(Some((key, signature)))
+    }
+}
+
+/// Contents and end offset of the section starting at `pos`
+fn section_at(binary: &[u8], pos: usize) -> Result<(&[u8], usize)> {
+    let (size, len) = read_leb128_u32(binary, pos + 1)?;
+    let start = pos + 1 + len;
+    let end = start
+        .checked_add(size as usize)
+        .filter(|&end| end <= binary.len())
+        .ok_or_else(|| Error::validation_parse_error("Section extends past the end of the binary"))?;
+    Ok((&binary[start..end], end))
+}
+
+fn sha256_of(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Digest over the preamble and section digests that is signed
+fn manifest_digest(preamble: &[u8], section_count: u32, digests: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(MANIFEST_DOMAIN);
+    hasher.update(preamble);
+    hasher.update(&section_count.to_le_bytes());
+    hasher.update(digests);
+    hasher.finalize()
+}
+
+fn modified() -> Error {
+    Error::new(ErrorCategory::Security, codes::INTEGRITY_VIOLATION, "Signed module was modified")
+}
+
+/// Ed25519 key signing modules and components
+#[cfg(feature = "std")]
+pub struct ModuleSigner {
+    key: SigningKey,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for ModuleSigner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ModuleSigner").field("public_key", &self.trust_anchor()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl ModuleSigner {
+    /// Signer with the Ed25519 secret key `seed`
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self { key: SigningKey::from_bytes(seed) }
+    }
+
+    /// Trust anchor verifying this signer's signatures
+    pub fn trust_anchor(&self) -> TrustAnchor {
+        TrustAnchor { key: self.key.verifying_key() }
+    }
+
+    /// Sign `binary`, returning the signed binary
+    ///
+    /// An unsigned binary gets a signature section covering all its
+    /// sections. A signed binary keeps its section and gets this signer's
+    /// signature added, replacing an earlier one by the same key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary is malformed or was modified after it
+    /// was signed
+    pub fn sign(&self, binary: &[u8]) -> Result<Vec<u8>> {
+        let (covered, section_count, digests, mut entries) = match SignedBinary::parse(binary)? {
+            Some(signed) => {
+                signed.check_digests()?;
+                let mut entries = Vec::new();
+                let mut signatures = signed.signatures()?;
+                while let Some((key, signature)) = signatures.next()? {
+                    if key != self.trust_anchor().to_bytes() {
+                        entries.push((key, signature));
+                    }
+                }
+                (signed.covered, signed.section_count, signed.digests.to_vec(), entries)
+            },
+            None => {
+                let mut digests = Vec::new();
+                let mut section_count = 0u32;
+                let mut pos = PREAMBLE_SIZE;
+                while pos < binary.len() {
+                    let (_, end) = section_at(binary, pos)?;
+                    digests.extend_from_slice(&sha256_of(&binary[pos..end]));
+                    section_count += 1;
+                    pos = end;
+                }
+                (binary, section_count, digests, Vec::new())
+            },
+        };
+
+        let manifest = manifest_digest(&covered[..PREAMBLE_SIZE], section_count, &digests);
+        entries.push((self.trust_anchor().to_bytes(), self.key.sign(&manifest).to_bytes()));
+
+        let mut payload = write_leb128_u32(SIGNATURE_SECTION_NAME.len() as u32);
+        payload.extend_from_slice(SIGNATURE_SECTION_NAME.as_bytes());
+        payload.push(SIGNATURE_FORMAT_VERSION);
+        payload.extend(write_leb128_u32(section_count));
+        payload.extend_from_slice(&digests);
+        payload.extend(write_leb128_u32(entries.len() as u32));
+        for (key, signature) in &entries {
+            payload.extend_from_slice(key);
+            payload.extend_from_slice(signature);
+        }
+
+        let mut signed = covered.to_vec();
+        signed.push(CUSTOM_SECTION_ID);
+        signed.extend(write_leb128_u32(payload.len() as u32));
+        signed.extend(payload);
+        Ok(signed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    /// Module with a type, function and code section
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // preamble
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type () -> ()
+        0x03, 0x02, 0x01, 0x00, // function of type 0
+        0x0A, 0x04, 0x01, 0x02, 0x00, 0x0B, // empty body
+    ];
+
+    #[test]
+    fn test_signed_module_verifies() -> Result<()> {
+        let signer = ModuleSigner::from_seed(&[7; 32]);
+        let other = ModuleSigner::from_seed(&[9; 32]);
+        let signed = signer.sign(MODULE)?;
+
+        assert!(signed.starts_with(MODULE));
+        assert!(is_signed(&signed)?);
+        assert!(!is_signed(MODULE)?);
+        let verified = verify(&signed, &[other.trust_anchor(), signer.trust_anchor()])?;
+        assert_eq!(verified, VerifiedSignature { anchor: 1, sections: 3 });
+
+        // Only the trusted keys count
+        let error = verify(&signed, &[other.trust_anchor()]).unwrap_err();
+        assert_eq!(error.code, codes::ACCESS_DENIED);
+        let error = verify(MODULE, &[signer.trust_anchor()]).unwrap_err();
+        assert_eq!(error.code, codes::ACCESS_DENIED);
+
+        // A second signer adds a signature, signing again replaces it
+        let cosigned = other.sign(&signer.sign(&signed)?)?;
+        assert_eq!(verify(&cosigned, &[other.trust_anchor()])?.sections, 3);
+        assert_eq!(verify(&cosigned, &[signer.trust_anchor()])?.sections, 3);
+        assert_eq!(cosigned.len(), signed.len() + KEY_SIZE + SIGNATURE_SIZE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_module_is_rejected() -> Result<()> {
+        let signer = ModuleSigner::from_seed(&[7; 32]);
+        let anchors = [signer.trust_anchor()];
+        let signed = signer.sign(MODULE)?;
+
+        // Every change to the signed part or the signature is caught
+        for pos in 0..signed.len() {
+            let mut tampered = signed.clone();
+            tampered[pos] ^= 0x01;
+            assert!(verify(&tampered, &anchors).is_err(), "byte {pos}");
+        }
+
+        // Appending a section after the signature
+        let mut appended = signed.clone();
+        appended.extend_from_slice(&[0x00, 0x02, 0x01, b'x']);
+        let error = verify(&appended, &anchors).unwrap_err();
+        assert_eq!(error.code, codes::INTEGRITY_VIOLATION);
+
+        // Dropping a signed section
+        let mut dropped = MODULE[..14].to_vec();
+        dropped.extend_from_slice(&signed[MODULE.len()..]);
+        assert!(verify(&dropped, &anchors).is_err());
+
+        // A modified module cannot be signed on top of the old signature
+        let mut modified = signed.clone();
+        modified[13] = 0x01;
+        assert!(signer.sign(&modified).is_err());
+        Ok(())
+    }
+}
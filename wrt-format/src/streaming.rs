@@ -32,14 +32,42 @@
     NoStdProvider,
 };
 
-#[cfg(feature = "std")]
-use crate::binary::WASM_MAGIC;
 #[cfg(not(any(feature = "std")))]
+use crate::binary::WASM_VERSION;
 use crate::binary::{
     read_leb128_u32,
     read_string,
+    COMPONENT_ALIAS_SECTION_ID,
+    COMPONENT_CANON_SECTION_ID,
+    COMPONENT_COMPONENT_SECTION_ID,
+    COMPONENT_CORE_INSTANCE_SECTION_ID,
+    COMPONENT_CORE_MODULE_SECTION_ID,
+    COMPONENT_CORE_SORT_FUNC,
+    COMPONENT_CORE_SORT_GLOBAL,
+    COMPONENT_CORE_SORT_INSTANCE,
+    COMPONENT_CORE_SORT_MEMORY,
+    COMPONENT_CORE_SORT_MODULE,
+    COMPONENT_CORE_SORT_TABLE,
+    COMPONENT_CORE_SORT_TYPE,
+    COMPONENT_CORE_TYPE_SECTION_ID,
+    COMPONENT_CUSTOM_SECTION_ID,
+    COMPONENT_EXPORT_SECTION_ID,
+    COMPONENT_IMPORT_SECTION_ID,
+    COMPONENT_INSTANCE_SECTION_ID,
+    COMPONENT_LAYER,
+    COMPONENT_MAGIC,
+    COMPONENT_SORT_COMPONENT,
+    COMPONENT_SORT_CORE,
+    COMPONENT_SORT_FUNC,
+    COMPONENT_SORT_INSTANCE,
+    COMPONENT_SORT_TYPE,
+    COMPONENT_SORT_VALUE,
+    COMPONENT_START_SECTION_ID,
+    COMPONENT_TYPE_SECTION_ID,
+    COMPONENT_VALUE_SECTION_ID,
+    CORE_INSTANCE_INLINE_EXPORTS_TAG,
+    CORE_INSTANCE_INSTANTIATE_TAG,
     WASM_MAGIC,
-    WASM_VERSION,
 };
 #[cfg(not(any(feature = "std")))]
 use crate::{
@@ -515,6 +543,1217 @@ pub fn remaining(&self) -> usize {
     }
 }
 
+//==========================================================================
+// Component model streaming
+//==========================================================================
+
+/// Maximum nesting depth of components embedded in components
+pub const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// Size of the component preamble: magic, version and layer
+const COMPONENT_HEADER_SIZE: usize = 8;
+
+/// Longest section header: the id and a five-byte LEB128 size
+const MAX_SECTION_HEADER_SIZE: usize = 6;
+
+/// Section of a component binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentSectionKind {
+    /// Custom section
+    Custom,
+    /// Embedded core module
+    CoreModule,
+    /// Core instance definitions
+    CoreInstance,
+    /// Core type definitions
+    CoreType,
+    /// Embedded component
+    Component,
+    /// Component instance definitions
+    Instance,
+    /// Alias definitions
+    Alias,
+    /// Component type definitions
+    Type,
+    /// Canonical function definitions
+    Canon,
+    /// Start function
+    Start,
+    /// Imports
+    Import,
+    /// Exports
+    Export,
+    /// Value definitions
+    Value,
+}
+
+impl ComponentSectionKind {
+    /// Kind of the section with id `id`
+    pub fn from_id(id: u8) -> Option<Self> {
+        Some(match id {
+            COMPONENT_CUSTOM_SECTION_ID => Self::Custom,
+            COMPONENT_CORE_MODULE_SECTION_ID => Self::CoreModule,
+            COMPONENT_CORE_INSTANCE_SECTION_ID => Self::CoreInstance,
+            COMPONENT_CORE_TYPE_SECTION_ID => Self::CoreType,
+            COMPONENT_COMPONENT_SECTION_ID => Self::Component,
+            COMPONENT_INSTANCE_SECTION_ID => Self::Instance,
+            COMPONENT_ALIAS_SECTION_ID => Self::Alias,
+            COMPONENT_TYPE_SECTION_ID => Self::Type,
+            COMPONENT_CANON_SECTION_ID => Self::Canon,
+            COMPONENT_START_SECTION_ID => Self::Start,
+            COMPONENT_IMPORT_SECTION_ID => Self::Import,
+            COMPONENT_EXPORT_SECTION_ID => Self::Export,
+            COMPONENT_VALUE_SECTION_ID => Self::Value,
+            _ => return None,
+        })
+    }
+}
+
+/// Event reported by a [`ComponentStreamParser`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentEvent<'a> {
+    /// All input was consumed; feed the next chunk
+    NeedMoreData,
+    /// Preamble of a component read, at depth 0 for the outermost one
+    ComponentStart {
+        /// Nesting depth of the component
+        depth:   usize,
+        /// Version from the preamble
+        version: u16,
+    },
+    /// Section read in full into the section buffer
+    Section {
+        /// Nesting depth of the component holding the section
+        depth: usize,
+        /// Kind of the section
+        kind:  ComponentSectionKind,
+        /// Contents of the section
+        data:  &'a [u8],
+    },
+    /// Part of an embedded core module, passed on without buffering
+    CoreModule {
+        /// Nesting depth of the component holding the module
+        depth:  usize,
+        /// Offset of `data` within the module binary
+        offset: u32,
+        /// Next bytes of the module binary
+        data:   &'a [u8],
+        /// Whether `data` ends the module
+        last:   bool,
+    },
+    /// Custom section skipped because it does not fit the section buffer
+    SkippedCustom {
+        /// Nesting depth of the component holding the section
+        depth: usize,
+        /// Size of the section in bytes
+        size:  u32,
+    },
+    /// End of an embedded component
+    ComponentEnd {
+        /// Nesting depth of the component that ended
+        depth: usize,
+    },
+}
+
+/// What a [`ComponentStreamParser`] reads next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ComponentState {
+    /// Component preamble
+    Header,
+    /// Section id and size
+    SectionHeader,
+    /// Section contents collected in the buffer
+    Buffering {
+        kind:      ComponentSectionKind,
+        remaining: u32,
+    },
+    /// Embedded core module passed on
+    CoreModule { offset: u32, remaining: u32 },
+    /// Oversized custom section
+    Skipping { remaining: u32 },
+}
+
+/// Streaming parser of component binaries in bounded memory
+///
+/// Takes a component in chunks of any size and reports its structure as
+/// [`ComponentEvent`]s. Sections are collected in a buffer of `N` bytes, so
+/// each section other than an embedded core module must fit in it, and
+/// nothing is allocated. Embedded core modules are passed on in chunks for
+/// the caller to parse or store, and embedded components are followed up
+/// to [`MAX_COMPONENT_DEPTH`] levels deep. The items of buffered sections
+/// are read with [`SectionItems`].
+#[derive(Debug, Clone)]
+pub struct ComponentStreamParser<const N: usize> {
+    /// What is read next
+    state:           ComponentState,
+    /// Section contents
+    buffer:          [u8; N],
+    /// Bytes of the current section in `buffer`
+    buffered:        usize,
+    /// Partial preamble or section header
+    pending:         [u8; COMPONENT_HEADER_SIZE],
+    /// Bytes in `pending`
+    pending_len:     usize,
+    /// Offsets at which the enclosing embedded components end
+    ends:            [usize; MAX_COMPONENT_DEPTH],
+    /// Nesting depth of the current component
+    depth:           usize,
+    /// Bytes consumed so far
+    bytes_processed: usize,
+}
+
+impl<const N: usize> Default for ComponentStreamParser<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> ComponentStreamParser<N> {
+    /// Create a parser expecting the preamble of a component
+    pub const fn new() -> Self {
+        Self {
+            state:           ComponentState::Header,
+            buffer:          [0; N],
+            buffered:        0,
+            pending:         [0; COMPONENT_HEADER_SIZE],
+            pending_len:     0,
+            ends:            [0; MAX_COMPONENT_DEPTH],
+            depth:           0,
+            bytes_processed: 0,
+        }
+    }
+
+    /// Number of bytes consumed so far
+    pub fn bytes_processed(&self) -> usize {
+        self.bytes_processed
+    }
+
+    /// Nesting depth of the component being read
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Consume `input` up to the next event
+    ///
+    /// Returns the number of bytes consumed with the event. Call again with
+    /// the rest of the input until [`ComponentEvent::NeedMoreData`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed binaries, unknown sections, sections
+    /// larger than the buffer and components nested too deeply.
+    pub fn feed<'a>(
+        &'a mut self,
+        input: &'a [u8],
+    ) -> core::result::Result<(usize, ComponentEvent<'a>), Error> {
+        let mut consumed = 0;
+        loop {
+            if self.state == ComponentState::SectionHeader
+                && self.pending_len == 0
+                && self.depth > 0
+                && self.bytes_processed == self.ends[self.depth - 1]
+            {
+                self.depth -= 1;
+                return Ok((consumed, ComponentEvent::ComponentEnd { depth: self.depth + 1 }));
+            }
+            let rest = &input[consumed..];
+            if rest.is_empty() {
+                return Ok((consumed, ComponentEvent::NeedMoreData));
+            }
+
+            match self.state {
+                ComponentState::Header => {
+                    let take = core::cmp::min(COMPONENT_HEADER_SIZE - self.pending_len, rest.len());
+                    self.pending[self.pending_len..self.pending_len + take]
+                        .copy_from_slice(&rest[..take]);
+                    self.pending_len += take;
+                    self.consume(&mut consumed, take);
+                    if self.pending_len < COMPONENT_HEADER_SIZE {
+                        continue;
+                    }
+                    self.pending_len = 0;
+                    if self.pending[..4] != COMPONENT_MAGIC {
+                        return Err(Error::validation_parse_error(
+                            "Invalid component magic bytes",
+                        ));
+                    }
+                    if self.pending[6..8] != COMPONENT_LAYER {
+                        return Err(Error::validation_parse_error("Binary is not a component"));
+                    }
+                    self.state = ComponentState::SectionHeader;
+                    let version = u16::from_le_bytes([self.pending[4], self.pending[5]]);
+                    return Ok((consumed, ComponentEvent::ComponentStart {
+                        depth: self.depth,
+                        version,
+                    }));
+                },
+                ComponentState::SectionHeader => {
+                    let byte = rest[0];
+                    self.consume(&mut consumed, 1);
+                    self.pending[self.pending_len] = byte;
+                    self.pending_len += 1;
+                    if self.pending_len == 1 || byte & 0x80 != 0 {
+                        if self.pending_len == MAX_SECTION_HEADER_SIZE {
+                            return Err(Error::validation_parse_error("Section size too long"));
+                        }
+                        continue;
+                    }
+                    let (size, _) = read_leb128_u32(&self.pending[..self.pending_len], 1)?;
+                    self.pending_len = 0;
+                    if let Some(event) = self.begin_section(self.pending[0], size)? {
+                        return Ok((consumed, event));
+                    }
+                },
+                ComponentState::Buffering { kind, remaining } => {
+                    let take = core::cmp::min(remaining as usize, rest.len());
+                    self.buffer[self.buffered..self.buffered + take].copy_from_slice(&rest[..take]);
+                    self.buffered += take;
+                    self.consume(&mut consumed, take);
+                    let remaining = remaining - take as u32;
+                    if remaining > 0 {
+                        self.state = ComponentState::Buffering { kind, remaining };
+                        continue;
+                    }
+                    self.state = ComponentState::SectionHeader;
+                    let len = core::mem::take(&mut self.buffered);
+                    return Ok((consumed, ComponentEvent::Section {
+                        depth: self.depth,
+                        kind,
+                        data: &self.buffer[..len],
+                    }));
+                },
+                ComponentState::CoreModule { offset, remaining } => {
+                    let take = core::cmp::min(remaining as usize, rest.len());
+                    let start = consumed;
+                    self.consume(&mut consumed, take);
+                    let remaining = remaining - take as u32;
+                    self.state = if remaining == 0 {
+                        ComponentState::SectionHeader
+                    } else {
+                        ComponentState::CoreModule {
+                            offset: offset + take as u32,
+                            remaining,
+                        }
+                    };
+                    return Ok((consumed, ComponentEvent::CoreModule {
+                        depth: self.depth,
+                        offset,
+                        data: &input[start..consumed],
+                        last: remaining == 0,
+                    }));
+                },
+                ComponentState::Skipping { remaining } => {
+                    let take = core::cmp::min(remaining as usize, rest.len());
+                    self.consume(&mut consumed, take);
+                    let remaining = remaining - take as u32;
+                    self.state = if remaining == 0 {
+                        ComponentState::SectionHeader
+                    } else {
+                        ComponentState::Skipping { remaining }
+                    };
+                },
+            }
+        }
+    }
+
+    /// Check that the input ended after a complete component
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component or a section is incomplete
+    pub fn finish(&self) -> core::result::Result<(), Error> {
+        if self.state != ComponentState::SectionHeader || self.pending_len != 0 || self.depth != 0
+        {
+            return Err(Error::validation_parse_error("Component binary truncated"));
+        }
+        Ok(())
+    }
+
+    fn consume(&mut self, consumed: &mut usize, bytes: usize) {
+        *consumed += bytes;
+        self.bytes_processed += bytes;
+    }
+
+    /// Start reading a section of `size` bytes whose header was just read
+    fn begin_section(
+        &mut self,
+        id: u8,
+        size: u32,
+    ) -> core::result::Result<Option<ComponentEvent<'static>>, Error> {
+        let kind = ComponentSectionKind::from_id(id)
+            .ok_or_else(|| Error::validation_parse_error("Unknown component section"))?;
+        let end = self.bytes_processed + size as usize;
+        if self.depth > 0 && end > self.ends[self.depth - 1] {
+            return Err(Error::validation_parse_error(
+                "Section exceeds the enclosing component",
+            ));
+        }
+
+        match kind {
+            ComponentSectionKind::Component => {
+                if self.depth == MAX_COMPONENT_DEPTH {
+                    return Err(Error::validation_parse_error("Components nested too deeply"));
+                }
+                if (size as usize) < COMPONENT_HEADER_SIZE {
+                    return Err(Error::validation_parse_error("Embedded component truncated"));
+                }
+                self.ends[self.depth] = end;
+                self.depth += 1;
+                self.state = ComponentState::Header;
+                Ok(None)
+            },
+            ComponentSectionKind::CoreModule => {
+                if size == 0 {
+                    return Err(Error::validation_parse_error("Embedded core module is empty"));
+                }
+                self.state = ComponentState::CoreModule {
+                    offset:    0,
+                    remaining: size,
+                };
+                Ok(None)
+            },
+            ComponentSectionKind::Custom if size as usize > N => {
+                self.state = ComponentState::Skipping { remaining: size };
+                Ok(Some(ComponentEvent::SkippedCustom {
+                    depth: self.depth,
+                    size,
+                }))
+            },
+            _ if size as usize > N => Err(Error::validation_parse_error(
+                "Component section exceeds the parser buffer",
+            )),
+            _ if size == 0 => Ok(Some(ComponentEvent::Section {
+                depth: self.depth,
+                kind,
+                data: &[],
+            })),
+            _ => {
+                self.state = ComponentState::Buffering {
+                    kind,
+                    remaining: size,
+                };
+                Ok(None)
+            },
+        }
+    }
+}
+
+/// Sort of an item referred to by index in a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentSort {
+    /// Core function
+    CoreFunc,
+    /// Core table
+    CoreTable,
+    /// Core memory
+    CoreMemory,
+    /// Core global
+    CoreGlobal,
+    /// Core type
+    CoreType,
+    /// Core module
+    CoreModule,
+    /// Core instance
+    CoreInstance,
+    /// Component function
+    Func,
+    /// Value
+    Value,
+    /// Component type
+    Type,
+    /// Component
+    Component,
+    /// Component instance
+    Instance,
+}
+
+/// Reference to an item by name, such as an instantiation argument or an
+/// inline export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamedItem<'a> {
+    /// Name of the argument or export
+    pub name:  &'a str,
+    /// Sort of the item
+    pub sort:  ComponentSort,
+    /// Index of the item in its sort
+    pub index: u32,
+}
+
+/// How the items of a [`NamedItems`] list are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NamedItemForm {
+    /// Core instantiation argument: name and core instance index
+    CoreArg,
+    /// Core inline export: name, core sort and index
+    CoreExport,
+    /// Instantiation argument: name, sort and index
+    Arg,
+    /// Inline export: export name, sort and index
+    Export,
+}
+
+/// Arguments or inline exports of an instance definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedItems<'a> {
+    cursor:    ItemCursor<'a>,
+    remaining: u32,
+    form:      NamedItemForm,
+}
+
+impl<'a> NamedItems<'a> {
+    /// Read and check a list of items, keeping it to be iterated later
+    fn read(
+        cursor: &mut ItemCursor<'a>,
+        form: NamedItemForm,
+    ) -> core::result::Result<Self, Error> {
+        let count = cursor.u32()?;
+        let start = cursor.pos;
+        for _ in 0..count {
+            cursor.named_item(form)?;
+        }
+        Ok(Self {
+            cursor: ItemCursor::new(&cursor.data[start..cursor.pos]),
+            remaining: count,
+            form,
+        })
+    }
+
+    /// Number of items not yet iterated
+    pub fn len(&self) -> usize {
+        self.remaining as usize
+    }
+
+    /// Whether all items were iterated
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+impl<'a> Iterator for NamedItems<'a> {
+    type Item = NamedItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // Checked when the list was read
+        self.cursor.named_item(self.form).ok()
+    }
+}
+
+/// Definition in a core instance section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreInstanceExpr<'a> {
+    /// Instantiation of a core module
+    Instantiate {
+        /// Core module index
+        module: u32,
+        /// Instances passed as imports, by module name
+        args:   NamedItems<'a>,
+    },
+    /// Instance made of core items
+    Exports(NamedItems<'a>),
+}
+
+/// Definition in an instance section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceExpr<'a> {
+    /// Instantiation of a component
+    Instantiate {
+        /// Component index
+        component: u32,
+        /// Items passed as imports, by import name
+        args:      NamedItems<'a>,
+    },
+    /// Instance made of items
+    Exports(NamedItems<'a>),
+}
+
+/// Item an alias refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasTarget<'a> {
+    /// Export of a component instance
+    Export {
+        /// Instance index
+        instance: u32,
+        /// Export name
+        name:     &'a str,
+    },
+    /// Export of a core instance
+    CoreExport {
+        /// Core instance index
+        instance: u32,
+        /// Export name
+        name:     &'a str,
+    },
+    /// Item of an enclosing component
+    Outer {
+        /// Number of components out
+        count: u32,
+        /// Index of the item there
+        index: u32,
+    },
+}
+
+/// Definition in an alias section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alias<'a> {
+    /// Sort of the aliased item
+    pub sort:   ComponentSort,
+    /// Aliased item
+    pub target: AliasTarget<'a>,
+}
+
+/// String encoding of a canonical function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CanonEncoding {
+    /// UTF-8
+    #[default]
+    Utf8,
+    /// UTF-16
+    Utf16,
+    /// Latin-1 or UTF-16
+    CompactUtf16,
+}
+
+/// Options of a lifted or lowered function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CanonOptions {
+    /// String encoding
+    pub encoding:    CanonEncoding,
+    /// Core memory strings and lists are passed in
+    pub memory:      Option<u32>,
+    /// Core function allocating in `memory`
+    pub realloc:     Option<u32>,
+    /// Core function called after a lifted function returned
+    pub post_return: Option<u32>,
+    /// Whether the function is asynchronous
+    pub is_async:    bool,
+    /// Core function called back by an asynchronous function
+    pub callback:    Option<u32>,
+}
+
+/// Definition in a canonical function section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Canon {
+    /// Component function lifted from a core function
+    Lift {
+        /// Core function index
+        core_func:  u32,
+        /// Component function type index
+        type_index: u32,
+        /// Options
+        options:    CanonOptions,
+    },
+    /// Core function lowered from a component function
+    Lower {
+        /// Component function index
+        func:    u32,
+        /// Options
+        options: CanonOptions,
+    },
+    /// `resource.new` of a resource type
+    ResourceNew(u32),
+    /// `resource.drop` of a resource type
+    ResourceDrop(u32),
+    /// `resource.rep` of a resource type
+    ResourceRep(u32),
+}
+
+/// Kind of a component type definition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentTypeKind {
+    /// Value type such as a record, variant or list
+    Defined,
+    /// Function type
+    Func,
+    /// Component type
+    Component,
+    /// Instance type
+    Instance,
+    /// Resource type
+    Resource,
+}
+
+/// Definition in a component type section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTypeDef<'a> {
+    /// Kind of the type
+    pub kind:     ComponentTypeKind,
+    /// Binary encoding of the type, for callers that decode it further
+    pub encoding: &'a [u8],
+}
+
+/// Items of a buffered component section, read without allocating
+///
+/// Created for the section kinds it understands by [`Self::core_instances`],
+/// [`Self::instances`], [`Self::aliases`], [`Self::canons`] and
+/// [`Self::types`]. Iteration stops at the first malformed item.
+#[derive(Debug, Clone)]
+pub struct SectionItems<'a, T> {
+    cursor:    ItemCursor<'a>,
+    remaining: u32,
+    read:      fn(&mut ItemCursor<'a>) -> core::result::Result<T, Error>,
+}
+
+impl<'a> SectionItems<'a, CoreInstanceExpr<'a>> {
+    /// Items of a core instance section
+    pub fn core_instances(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        Self::new(data, ItemCursor::core_instance)
+    }
+}
+
+impl<'a> SectionItems<'a, InstanceExpr<'a>> {
+    /// Items of an instance section
+    pub fn instances(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        Self::new(data, ItemCursor::instance)
+    }
+}
+
+impl<'a> SectionItems<'a, Alias<'a>> {
+    /// Items of an alias section
+    pub fn aliases(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        Self::new(data, ItemCursor::alias)
+    }
+}
+
+impl<'a> SectionItems<'a, Canon> {
+    /// Items of a canonical function section
+    pub fn canons(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        Self::new(data, ItemCursor::canon)
+    }
+}
+
+impl<'a> SectionItems<'a, ComponentTypeDef<'a>> {
+    /// Items of a component type section
+    pub fn types(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        Self::new(data, ItemCursor::type_def)
+    }
+}
+
+impl<'a, T> SectionItems<'a, T> {
+    fn new(
+        data: &'a [u8],
+        read: fn(&mut ItemCursor<'a>) -> core::result::Result<T, Error>,
+    ) -> core::result::Result<Self, Error> {
+        let mut cursor = ItemCursor::new(data);
+        let remaining = cursor.u32()?;
+        Ok(Self {
+            cursor,
+            remaining,
+            read,
+        })
+    }
+
+    /// Number of items not yet read
+    pub fn len(&self) -> usize {
+        self.remaining as usize
+    }
+
+    /// Whether all items were read
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+impl<T> Iterator for SectionItems<'_, T> {
+    type Item = core::result::Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            if self.cursor.pos < self.cursor.data.len() {
+                self.cursor.pos = self.cursor.data.len();
+                return Some(Err(Error::validation_parse_error(
+                    "Trailing bytes after section items",
+                )));
+            }
+            return None;
+        }
+        let item = (self.read)(&mut self.cursor);
+        self.remaining = if item.is_ok() { self.remaining - 1 } else { 0 };
+        if item.is_err() {
+            self.cursor.pos = self.cursor.data.len();
+        }
+        Some(item)
+    }
+}
+
+/// Primitive value types, by their encoding
+const PRIMITIVE_VALTYPES: core::ops::RangeInclusive<u8> = 0x73..=0x7F;
+
+/// Encoding of the `error-context` value type
+const ERROR_CONTEXT_VALTYPE: u8 = 0x64;
+
+/// Cursor over the contents of a buffered section
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ItemCursor<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> ItemCursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn u8(&mut self) -> core::result::Result<u8, Error> {
+        let byte = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| Error::validation_parse_error("Unexpected end of section"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn peek(&self) -> core::result::Result<u8, Error> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| Error::validation_parse_error("Unexpected end of section"))
+    }
+
+    fn expect(&mut self, byte: u8) -> core::result::Result<(), Error> {
+        if self.u8()? != byte {
+            return Err(Error::validation_parse_error("Malformed component item"));
+        }
+        Ok(())
+    }
+
+    fn u32(&mut self) -> core::result::Result<u32, Error> {
+        let (value, consumed) = read_leb128_u32(self.data, self.pos)?;
+        self.pos += consumed;
+        Ok(value)
+    }
+
+    /// Skip a LEB128 integer of up to 64 bits
+    fn skip_leb(&mut self) -> core::result::Result<(), Error> {
+        for _ in 0..10 {
+            if self.u8()? & 0x80 == 0 {
+                return Ok(());
+            }
+        }
+        Err(Error::validation_parse_error("Integer representation too long"))
+    }
+
+    fn name(&mut self) -> core::result::Result<&'a str, Error> {
+        let (bytes, consumed) = read_string(self.data, self.pos)?;
+        self.pos += consumed;
+        core::str::from_utf8(bytes)
+            .map_err(|_| Error::validation_parse_error("Invalid UTF-8 string"))
+    }
+
+    /// Read an import or export name with its kind prefix
+    fn extern_name(&mut self) -> core::result::Result<&'a str, Error> {
+        match self.u8()? {
+            0x00 | 0x01 => self.name(),
+            _ => Err(Error::validation_parse_error("Malformed extern name")),
+        }
+    }
+
+    /// Read `0x00` or `0x01` followed by a value read by `read`
+    fn optional<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> core::result::Result<T, Error>,
+    ) -> core::result::Result<Option<T>, Error> {
+        match self.u8()? {
+            0x00 => Ok(None),
+            0x01 => read(self).map(Some),
+            _ => Err(Error::validation_parse_error("Malformed optional item")),
+        }
+    }
+
+    fn core_sort(&mut self) -> core::result::Result<ComponentSort, Error> {
+        Ok(match self.u8()? {
+            COMPONENT_CORE_SORT_FUNC => ComponentSort::CoreFunc,
+            COMPONENT_CORE_SORT_TABLE => ComponentSort::CoreTable,
+            COMPONENT_CORE_SORT_MEMORY => ComponentSort::CoreMemory,
+            COMPONENT_CORE_SORT_GLOBAL => ComponentSort::CoreGlobal,
+            COMPONENT_CORE_SORT_TYPE => ComponentSort::CoreType,
+            COMPONENT_CORE_SORT_MODULE => ComponentSort::CoreModule,
+            COMPONENT_CORE_SORT_INSTANCE => ComponentSort::CoreInstance,
+            _ => return Err(Error::validation_parse_error("Unknown core sort")),
+        })
+    }
+
+    fn sort(&mut self) -> core::result::Result<ComponentSort, Error> {
+        Ok(match self.u8()? {
+            COMPONENT_SORT_CORE => return self.core_sort(),
+            COMPONENT_SORT_FUNC => ComponentSort::Func,
+            COMPONENT_SORT_VALUE => ComponentSort::Value,
+            COMPONENT_SORT_TYPE => ComponentSort::Type,
+            COMPONENT_SORT_COMPONENT => ComponentSort::Component,
+            COMPONENT_SORT_INSTANCE => ComponentSort::Instance,
+            _ => return Err(Error::validation_parse_error("Unknown sort")),
+        })
+    }
+
+    fn named_item(&mut self, form: NamedItemForm) -> core::result::Result<NamedItem<'a>, Error> {
+        let name = match form {
+            NamedItemForm::Export => self.extern_name()?,
+            _ => self.name()?,
+        };
+        let sort = match form {
+            NamedItemForm::CoreArg => {
+                self.expect(COMPONENT_CORE_SORT_INSTANCE)?;
+                ComponentSort::CoreInstance
+            },
+            NamedItemForm::CoreExport => self.core_sort()?,
+            NamedItemForm::Arg | NamedItemForm::Export => self.sort()?,
+        };
+        Ok(NamedItem {
+            name,
+            sort,
+            index: self.u32()?,
+        })
+    }
+
+    fn core_instance(&mut self) -> core::result::Result<CoreInstanceExpr<'a>, Error> {
+        match self.u8()? {
+            CORE_INSTANCE_INSTANTIATE_TAG => Ok(CoreInstanceExpr::Instantiate {
+                module: self.u32()?,
+                args:   NamedItems::read(self, NamedItemForm::CoreArg)?,
+            }),
+            CORE_INSTANCE_INLINE_EXPORTS_TAG => Ok(CoreInstanceExpr::Exports(NamedItems::read(
+                self,
+                NamedItemForm::CoreExport,
+            )?)),
+            _ => Err(Error::validation_parse_error("Unknown core instance definition")),
+        }
+    }
+
+    fn instance(&mut self) -> core::result::Result<InstanceExpr<'a>, Error> {
+        match self.u8()? {
+            0x00 => Ok(InstanceExpr::Instantiate {
+                component: self.u32()?,
+                args:      NamedItems::read(self, NamedItemForm::Arg)?,
+            }),
+            0x01 => Ok(InstanceExpr::Exports(NamedItems::read(self, NamedItemForm::Export)?)),
+            _ => Err(Error::validation_parse_error("Unknown instance definition")),
+        }
+    }
+
+    fn alias(&mut self) -> core::result::Result<Alias<'a>, Error> {
+        let sort = self.sort()?;
+        let target = match self.u8()? {
+            0x00 => AliasTarget::Export {
+                instance: self.u32()?,
+                name:     self.name()?,
+            },
+            0x01 => AliasTarget::CoreExport {
+                instance: self.u32()?,
+                name:     self.name()?,
+            },
+            0x02 => AliasTarget::Outer {
+                count: self.u32()?,
+                index: self.u32()?,
+            },
+            _ => return Err(Error::validation_parse_error("Unknown alias target")),
+        };
+        Ok(Alias { sort, target })
+    }
+
+    fn canon_options(&mut self) -> core::result::Result<CanonOptions, Error> {
+        let mut options = CanonOptions::default();
+        for _ in 0..self.u32()? {
+            match self.u8()? {
+                0x00 => options.encoding = CanonEncoding::Utf8,
+                0x01 => options.encoding = CanonEncoding::Utf16,
+                0x02 => options.encoding = CanonEncoding::CompactUtf16,
+                0x03 => options.memory = Some(self.u32()?),
+                0x04 => options.realloc = Some(self.u32()?),
+                0x05 => options.post_return = Some(self.u32()?),
+                0x06 => options.is_async = true,
+                0x07 => options.callback = Some(self.u32()?),
+                _ => return Err(Error::validation_parse_error("Unknown canonical option")),
+            }
+        }
+        Ok(options)
+    }
+
+    fn canon(&mut self) -> core::result::Result<Canon, Error> {
+        match self.u8()? {
+            0x00 => {
+                self.expect(0x00)?;
+                let core_func = self.u32()?;
+                let options = self.canon_options()?;
+                Ok(Canon::Lift {
+                    core_func,
+                    type_index: self.u32()?,
+                    options,
+                })
+            },
+            0x01 => {
+                self.expect(0x00)?;
+                Ok(Canon::Lower {
+                    func:    self.u32()?,
+                    options: self.canon_options()?,
+                })
+            },
+            0x02 => Ok(Canon::ResourceNew(self.u32()?)),
+            0x03 => Ok(Canon::ResourceDrop(self.u32()?)),
+            0x04 => Ok(Canon::ResourceRep(self.u32()?)),
+            _ => Err(Error::validation_parse_error("Unsupported canonical function")),
+        }
+    }
+
+    fn type_def(&mut self) -> core::result::Result<ComponentTypeDef<'a>, Error> {
+        let start = self.pos;
+        let kind = self.def_type()?;
+        Ok(ComponentTypeDef {
+            kind,
+            encoding: &self.data[start..self.pos],
+        })
+    }
+
+    fn def_type(&mut self) -> core::result::Result<ComponentTypeKind, Error> {
+        Ok(match self.u8()? {
+            0x40 | 0x43 => {
+                self.skip_func_type()?;
+                ComponentTypeKind::Func
+            },
+            0x41 => {
+                for _ in 0..self.u32()? {
+                    match self.u8()? {
+                        0x03 => {
+                            self.extern_name()?;
+                            self.skip_extern_desc()?;
+                        },
+                        tag => self.skip_instance_decl(tag)?,
+                    }
+                }
+                ComponentTypeKind::Component
+            },
+            0x42 => {
+                for _ in 0..self.u32()? {
+                    let tag = self.u8()?;
+                    self.skip_instance_decl(tag)?;
+                }
+                ComponentTypeKind::Instance
+            },
+            0x3F => {
+                self.expect(0x7F)?;
+                self.optional(Self::u32)?;
+                ComponentTypeKind::Resource
+            },
+            0x3E => {
+                self.expect(0x7F)?;
+                self.optional(Self::u32)?;
+                self.optional(Self::u32)?;
+                ComponentTypeKind::Resource
+            },
+            tag => {
+                self.skip_def_val_type(tag)?;
+                ComponentTypeKind::Defined
+            },
+        })
+    }
+
+    fn skip_val_type(&mut self) -> core::result::Result<(), Error> {
+        let byte = self.peek()?;
+        // Primitive types are negative single-byte s33 values
+        if byte & 0xC0 == 0x40 {
+            self.pos += 1;
+            return match byte {
+                ERROR_CONTEXT_VALTYPE => Ok(()),
+                _ if PRIMITIVE_VALTYPES.contains(&byte) => Ok(()),
+                _ => Err(Error::validation_parse_error("Unknown primitive value type")),
+            };
+        }
+        self.u32().map(|_| ())
+    }
+
+    fn skip_def_val_type(&mut self, tag: u8) -> core::result::Result<(), Error> {
+        match tag {
+            ERROR_CONTEXT_VALTYPE => {},
+            _ if PRIMITIVE_VALTYPES.contains(&tag) => {},
+            // record
+            0x72 => {
+                for _ in 0..self.u32()? {
+                    self.name()?;
+                    self.skip_val_type()?;
+                }
+            },
+            // variant
+            0x71 => {
+                for _ in 0..self.u32()? {
+                    self.name()?;
+                    self.optional(Self::skip_val_type)?;
+                    self.optional(Self::u32)?;
+                }
+            },
+            // list, option
+            0x70 | 0x6B => self.skip_val_type()?,
+            // tuple
+            0x6F => {
+                for _ in 0..self.u32()? {
+                    self.skip_val_type()?;
+                }
+            },
+            // flags, enum
+            0x6E | 0x6D => {
+                for _ in 0..self.u32()? {
+                    self.name()?;
+                }
+            },
+            // result
+            0x6A => {
+                self.optional(Self::skip_val_type)?;
+                self.optional(Self::skip_val_type)?;
+            },
+            // own, borrow
+            0x69 | 0x68 => {
+                self.u32()?;
+            },
+            // fixed-size list
+            0x67 => {
+                self.skip_val_type()?;
+                self.u32()?;
+            },
+            // stream, future
+            0x66 | 0x65 => {
+                self.optional(Self::skip_val_type)?;
+            },
+            _ => return Err(Error::validation_parse_error("Unknown component type")),
+        }
+        Ok(())
+    }
+
+    fn skip_func_type(&mut self) -> core::result::Result<(), Error> {
+        for _ in 0..self.u32()? {
+            self.name()?;
+            self.skip_val_type()?;
+        }
+        match self.u8()? {
+            0x00 => self.skip_val_type(),
+            0x01 => {
+                for _ in 0..self.u32()? {
+                    self.name()?;
+                    self.skip_val_type()?;
+                }
+                Ok(())
+            },
+            _ => Err(Error::validation_parse_error("Malformed function results")),
+        }
+    }
+
+    fn skip_instance_decl(&mut self, tag: u8) -> core::result::Result<(), Error> {
+        match tag {
+            0x00 => self.skip_core_type(),
+            0x01 => self.def_type().map(|_| ()),
+            0x02 => self.alias().map(|_| ()),
+            0x04 => {
+                self.extern_name()?;
+                self.skip_extern_desc()
+            },
+            _ => Err(Error::validation_parse_error("Unknown type declaration")),
+        }
+    }
+
+    fn skip_extern_desc(&mut self) -> core::result::Result<(), Error> {
+        match self.u8()? {
+            0x00 => {
+                self.expect(COMPONENT_CORE_SORT_MODULE)?;
+                self.u32()?;
+            },
+            0x01 | 0x04 | 0x05 => {
+                self.u32()?;
+            },
+            0x02 => match self.u8()? {
+                0x00 => {
+                    self.u32()?;
+                },
+                0x01 => self.skip_val_type()?,
+                _ => return Err(Error::validation_parse_error("Malformed value bound")),
+            },
+            0x03 => match self.u8()? {
+                0x00 => {
+                    self.u32()?;
+                },
+                0x01 => {},
+                _ => return Err(Error::validation_parse_error("Malformed type bound")),
+            },
+            _ => return Err(Error::validation_parse_error("Unknown extern descriptor")),
+        }
+        Ok(())
+    }
+
+    fn skip_core_type(&mut self) -> core::result::Result<(), Error> {
+        match self.u8()? {
+            0x60 => {
+                for _ in 0..2 {
+                    for _ in 0..self.u32()? {
+                        self.skip_core_val_type()?;
+                    }
+                }
+                Ok(())
+            },
+            0x50 => {
+                for _ in 0..self.u32()? {
+                    match self.u8()? {
+                        0x00 => {
+                            self.name()?;
+                            self.name()?;
+                            self.skip_core_import_desc()?;
+                        },
+                        0x01 => self.skip_core_type()?,
+                        0x02 => {
+                            self.core_sort()?;
+                            self.expect(0x01)?;
+                            self.u32()?;
+                            self.u32()?;
+                        },
+                        0x03 => {
+                            self.name()?;
+                            self.skip_core_import_desc()?;
+                        },
+                        _ => return Err(Error::validation_parse_error("Unknown module declaration")),
+                    }
+                }
+                Ok(())
+            },
+            _ => Err(Error::validation_parse_error("Unsupported core type")),
+        }
+    }
+
+    fn skip_core_val_type(&mut self) -> core::result::Result<(), Error> {
+        match self.u8()? {
+            // Nullable and non-nullable references carry a heap type
+            0x63 | 0x64 => self.skip_leb(),
+            _ => Ok(()),
+        }
+    }
+
+    fn skip_limits(&mut self) -> core::result::Result<(), Error> {
+        let flags = self.u8()?;
+        self.skip_leb()?;
+        if flags & 0x01 != 0 {
+            self.skip_leb()?;
+        }
+        Ok(())
+    }
+
+    fn skip_core_import_desc(&mut self) -> core::result::Result<(), Error> {
+        match self.u8()? {
+            0x00 => self.u32().map(|_| ()),
+            0x01 => {
+                self.skip_core_val_type()?;
+                self.skip_limits()
+            },
+            0x02 => self.skip_limits(),
+            0x03 => {
+                self.skip_core_val_type()?;
+                self.u8().map(|_| ())
+            },
+            0x04 => {
+                self.expect(0x00)?;
+                self.u32().map(|_| ())
+            },
+            _ => Err(Error::validation_parse_error("Unknown core import kind")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use wrt_foundation::NoStdProvider;
@@ -561,4 +1800,136 @@ fn test_section_parser_creation() {
         let parser = SectionParser::new(provider);
         assert!(parser.is_ok());
     }
+
+    /// Component with an embedded core module and component, and one
+    /// section of each kind the item readers understand
+    fn sample_component() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[0x00, 0x61, 0x73, 0x6D, 0x0D, 0x00, 0x01, 0x00]);
+        // Core module
+        bytes.extend_from_slice(&[0x01, 0x08, 0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]);
+        // Core instance of module 0 without arguments
+        bytes.extend_from_slice(&[0x02, 0x04, 0x01, 0x00, 0x00, 0x00]);
+        // Embedded component with an outer alias
+        bytes.extend_from_slice(&[0x04, 0x0F, 0x00, 0x61, 0x73, 0x6D, 0x0D, 0x00, 0x01, 0x00]);
+        bytes.extend_from_slice(&[0x06, 0x05, 0x01, 0x01, 0x02, 0x01, 0x00]);
+        // Type `func(x: u32) -> u32`
+        bytes.extend_from_slice(&[0x07, 0x08, 0x01, 0x40, 0x01, 0x01, 0x78, 0x79, 0x00, 0x79]);
+        // Lift of core function 0 with memory 0 and UTF-8
+        bytes.extend_from_slice(&[0x08, 0x09, 0x01, 0x00, 0x00, 0x00, 0x02, 0x03, 0x00, 0x00, 0x00]);
+        // Custom section larger than the buffer
+        bytes.extend_from_slice(&[0x00, 0x20, 0x04]);
+        bytes.extend_from_slice(b"name");
+        bytes.extend_from_slice(&[0; 0x1B]);
+        bytes
+    }
+
+    #[test]
+    fn test_component_stream_byte_by_byte() -> core::result::Result<(), Error> {
+        let bytes = sample_component();
+        let mut parser = ComponentStreamParser::<16>::new();
+        let mut module = Vec::new();
+        let mut sections = Vec::new();
+        let mut depths = Vec::new();
+        let mut skipped = 0;
+
+        for byte in bytes.chunks(1) {
+            let mut input = byte;
+            loop {
+                let (consumed, event) = parser.feed(input)?;
+                input = &input[consumed..];
+                match event {
+                    ComponentEvent::NeedMoreData => break,
+                    ComponentEvent::ComponentStart { depth, version } => {
+                        assert_eq!(version, 0x0D);
+                        depths.push(depth);
+                    },
+                    ComponentEvent::ComponentEnd { depth } => depths.push(depth + 100),
+                    ComponentEvent::CoreModule { offset, data, .. } => {
+                        assert_eq!(offset as usize, module.len());
+                        module.extend_from_slice(data);
+                    },
+                    ComponentEvent::Section { depth, kind, data } => {
+                        sections.push((depth, kind, data.to_vec()));
+                    },
+                    ComponentEvent::SkippedCustom { size, .. } => skipped = size,
+                }
+            }
+        }
+        parser.finish()?;
+
+        assert_eq!(parser.bytes_processed(), bytes.len());
+        assert_eq!(depths, [0, 1, 101]);
+        assert_eq!(module, [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(skipped, 0x20);
+        let kinds: Vec<_> = sections.iter().map(|(depth, kind, _)| (*depth, *kind)).collect();
+        assert_eq!(kinds, [
+            (0, ComponentSectionKind::CoreInstance),
+            (1, ComponentSectionKind::Alias),
+            (0, ComponentSectionKind::Type),
+            (0, ComponentSectionKind::Canon),
+        ]);
+
+        let mut instances = SectionItems::core_instances(&sections[0].2)?;
+        match instances.next() {
+            Some(Ok(CoreInstanceExpr::Instantiate { module: 0, args })) => assert!(args.is_empty()),
+            other => panic!("unexpected core instance {other:?}"),
+        }
+        assert!(instances.next().is_none());
+
+        let alias = SectionItems::aliases(&sections[1].2)?.next().unwrap()?;
+        assert_eq!(alias, Alias {
+            sort:   ComponentSort::Func,
+            target: AliasTarget::Outer { count: 1, index: 0 },
+        });
+
+        let ty = SectionItems::types(&sections[2].2)?.next().unwrap()?;
+        assert_eq!(ty.kind, ComponentTypeKind::Func);
+        assert_eq!(ty.encoding, &sections[2].2[1..]);
+
+        let canon = SectionItems::canons(&sections[3].2)?.next().unwrap()?;
+        assert_eq!(canon, Canon::Lift {
+            core_func:  0,
+            type_index: 0,
+            options:    CanonOptions { memory: Some(0), ..CanonOptions::default() },
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_component_stream_rejects_malformed_input() {
+        let bytes = sample_component();
+
+        // Sections must fit the buffer
+        let mut parser = ComponentStreamParser::<4>::new();
+        let mut input = &bytes[..];
+        let result = loop {
+            match parser.feed(input) {
+                Ok((_, ComponentEvent::NeedMoreData)) => break Ok(()),
+                Ok((consumed, _)) => input = &input[consumed..],
+                Err(error) => break Err(error),
+            }
+        };
+        assert!(result.is_err());
+
+        // A core module is not a component
+        let mut parser = ComponentStreamParser::<16>::new();
+        assert!(parser.feed(&[0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]).is_err());
+
+        // Truncated input is caught at the end
+        let mut parser = ComponentStreamParser::<16>::new();
+        let mut input = &bytes[..20];
+        while let Ok((consumed, event)) = parser.feed(input) {
+            input = &input[consumed..];
+            if event == ComponentEvent::NeedMoreData {
+                break;
+            }
+        }
+        assert!(parser.finish().is_err());
+
+        // Items must match the section contents
+        assert!(SectionItems::canons(&[0x01, 0x09]).unwrap().next().unwrap().is_err());
+        let mut trailing = SectionItems::aliases(&[0x00, 0x01]).unwrap();
+        assert!(trailing.next().unwrap().is_err());
+    }
 }
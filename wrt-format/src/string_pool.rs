@@ -0,0 +1,302 @@
+//! Interned name strings for decoded modules.
+//!
+//! Import module names repeat for every import of a module (a WASI program
+//! imports dozens of functions from `wasi_snapshot_preview1`), and the same
+//! names reappear in every structure that refers to an import or export.
+//! A [`StringPool`] keeps one shared copy of each distinct name per module
+//! and hands out cheap [`PooledStr`] handles to it.
+//!
+//! The pool is bounded in the number and total size of the strings it
+//! keeps, so a malicious binary cannot grow it without limit. Names beyond
+//! the bound are still returned, but as unshared copies.
+//!
+//! [`StringPoolStats`] records how names were interned and estimates the
+//! heap footprint of the names with and without the pool.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    mem::size_of,
+    ops::{
+        AddAssign,
+        Deref,
+    },
+};
+use std::{
+    collections::BTreeSet,
+    string::String,
+    sync::Arc,
+};
+
+use crate::{
+    MAX_MODULE_EXPORTS,
+    MAX_MODULE_IMPORTS,
+};
+
+/// Default maximum number of distinct strings a pool keeps
+pub const MAX_POOLED_STRINGS: usize = MAX_MODULE_IMPORTS + MAX_MODULE_EXPORTS;
+
+/// Default maximum total size, in bytes, of the strings a pool keeps
+pub const MAX_POOLED_BYTES: usize = 64 * 1024;
+
+/// Reference counts stored in front of every shared string
+const ARC_HEADER_SIZE: usize = 2 * size_of::<usize>();
+
+/// Shared handle to an interned string
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PooledStr(Arc<str>);
+
+impl PooledStr {
+    /// The string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for PooledStr {
+    fn default() -> Self {
+        Self(Arc::from(""))
+    }
+}
+
+impl From<&str> for PooledStr {
+    fn from(s: &str) -> Self {
+        Self(Arc::from(s))
+    }
+}
+
+impl Deref for PooledStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for PooledStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PooledStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for PooledStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PooledStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for PooledStr {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl fmt::Debug for PooledStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for PooledStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+/// How a pool's strings were interned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StringPoolStats {
+    /// Strings interned, counting repeats
+    pub strings:      usize,
+    /// Total length of the strings interned, counting repeats
+    pub string_bytes: usize,
+    /// Separately allocated strings, shared or not
+    pub unique:       usize,
+    /// Total length of the separately allocated strings
+    pub unique_bytes: usize,
+    /// Strings not shared because the pool was full
+    pub overflowed:   usize,
+}
+
+impl StringPoolStats {
+    /// Estimated heap and handle footprint of the strings as owned `String`s
+    pub fn owned_footprint(&self) -> usize {
+        self.strings * size_of::<String>() + self.string_bytes
+    }
+
+    /// Estimated heap and handle footprint of the strings as pooled handles,
+    /// including the pool itself
+    pub fn pooled_footprint(&self) -> usize {
+        let pooled = self.unique - self.overflowed;
+        self.strings * size_of::<PooledStr>()
+            + self.unique_bytes
+            + self.unique * ARC_HEADER_SIZE
+            + pooled * size_of::<Arc<str>>()
+    }
+
+    /// Bytes saved by pooling
+    pub fn saved_bytes(&self) -> usize {
+        self.owned_footprint().saturating_sub(self.pooled_footprint())
+    }
+
+    /// Footprint reduction by pooling, in percent
+    pub fn reduction_percent(&self) -> f64 {
+        let owned = self.owned_footprint();
+        if owned == 0 {
+            0.0
+        } else {
+            self.saved_bytes() as f64 * 100.0 / owned as f64
+        }
+    }
+}
+
+impl AddAssign for StringPoolStats {
+    /// Combine the statistics of two pools, such as those of the core
+    /// modules of one component
+    fn add_assign(&mut self, other: Self) {
+        self.strings += other.strings;
+        self.string_bytes += other.string_bytes;
+        self.unique += other.unique;
+        self.unique_bytes += other.unique_bytes;
+        self.overflowed += other.overflowed;
+    }
+}
+
+/// Bounded per-module pool of interned strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringPool {
+    /// Distinct strings kept by the pool
+    strings:     BTreeSet<Arc<str>>,
+    /// Total length of the kept strings
+    bytes:       usize,
+    /// Maximum number of kept strings
+    max_strings: usize,
+    /// Maximum total length of the kept strings
+    max_bytes:   usize,
+    /// Interning statistics
+    stats:       StringPoolStats,
+}
+
+impl Default for StringPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringPool {
+    /// Create a pool with the default bounds
+    pub fn new() -> Self {
+        Self::with_limits(MAX_POOLED_STRINGS, MAX_POOLED_BYTES)
+    }
+
+    /// Create a pool keeping at most `max_strings` strings of at most
+    /// `max_bytes` bytes in total
+    pub fn with_limits(max_strings: usize, max_bytes: usize) -> Self {
+        Self {
+            strings: BTreeSet::new(),
+            bytes: 0,
+            max_strings,
+            max_bytes,
+            stats: StringPoolStats::default(),
+        }
+    }
+
+    /// Return the shared copy of `s`, adding it to the pool if needed
+    ///
+    /// If the pool is full, `s` is returned as an unshared copy.
+    pub fn intern(&mut self, s: &str) -> PooledStr {
+        self.stats.strings += 1;
+        self.stats.string_bytes += s.len();
+        if let Some(shared) = self.strings.get(s) {
+            return PooledStr(shared.clone());
+        }
+
+        let shared: Arc<str> = Arc::from(s);
+        self.stats.unique += 1;
+        self.stats.unique_bytes += s.len();
+        if self.strings.len() < self.max_strings && self.bytes + s.len() <= self.max_bytes {
+            self.bytes += s.len();
+            self.strings.insert(shared.clone());
+        } else {
+            self.stats.overflowed += 1;
+        }
+        PooledStr(shared)
+    }
+
+    /// Number of distinct strings kept
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether the pool keeps no strings
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Total length of the kept strings
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Interning statistics
+    pub fn stats(&self) -> StringPoolStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_repeated_names() {
+        let mut pool = StringPool::new();
+        let a = pool.intern("wasi_snapshot_preview1");
+        let b = pool.intern("wasi_snapshot_preview1");
+        let c = pool.intern("fd_write");
+
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, "wasi_snapshot_preview1");
+        assert_eq!(c.as_str(), "fd_write");
+        assert_eq!(pool.len(), 2);
+
+        let stats = pool.stats();
+        assert_eq!((stats.strings, stats.unique, stats.overflowed), (3, 2, 0));
+        assert_eq!(stats.string_bytes, 2 * 22 + 8);
+    }
+
+    #[test]
+    fn test_pool_bounds_and_footprint() {
+        let mut pool = StringPool::with_limits(1, 64);
+        let a = pool.intern("env");
+        let b = pool.intern("memory");
+        let c = pool.intern("memory");
+
+        assert_eq!(b, c);
+        assert!(!Arc::ptr_eq(&b.0, &c.0));
+        assert_eq!(a, "env");
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.stats().overflowed, 2);
+
+        let mut pool = StringPool::new();
+        for _ in 0..100 {
+            pool.intern("wasi_snapshot_preview1");
+        }
+        let stats = pool.stats();
+        assert!(stats.pooled_footprint() < stats.owned_footprint());
+        assert!(stats.reduction_percent() > 50.0);
+        assert_eq!(StringPoolStats::default().reduction_percent(), 0.0);
+    }
+}
@@ -0,0 +1,3555 @@
+//! WebAssembly text format (WAT).
+//!
+//! [`assemble_module`] turns a module written in the text format into its
+//! binary and [`write_binary_module`] prints a binary back as text, so
+//! diagnostics and test tooling need neither the `wat` nor the
+//! `wasmprinter` crate. Both only need `core`: the parser works in bounded
+//! scratch storage and the printer writes into any
+//! [`fmt::Write`](core::fmt::Write), so `no_std` builds can round-trip a
+//! module through fixed-size buffers. With `std`, `parse_module` reads the
+//! text into the in-memory `Module` and `print_module` prints one.
+//!
+//! The parser accepts the flat and the folded instruction syntax, symbolic
+//! `$names` for all index spaces and labels, inline imports and exports,
+//! and the table `elem` and memory `data` abbreviations. Instructions cover
+//! the core specification with sign extension, saturating conversions,
+//! bulk memory, reference types and tail calls; SIMD, exceptions and GC
+//! instructions are rejected. The printer names nothing, since binaries
+//! keep no names here, and annotates definitions with their index.
+//!
+//! The [`Lexer`], the number parsers and [`write_instructions`] tokenize
+//! text and disassemble code on their own.
+
+use core::{
+    fmt,
+    ops::{
+        Deref,
+        DerefMut,
+        Range,
+    },
+};
+#[cfg(feature = "std")]
+use std::{
+    collections::BTreeMap,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+#[cfg(not(feature = "std"))]
+use wrt_foundation::collections::StaticMap;
+#[cfg(feature = "std")]
+use wrt_foundation::CleanCoreFuncType;
+use wrt_foundation::{
+    collections::StaticVec,
+    Limits,
+};
+
+use crate::{
+    binary::{
+        read_leb128_i32,
+        read_leb128_i64,
+        read_leb128_u32,
+        CODE_SECTION_ID,
+        CUSTOM_SECTION_ID,
+        DATA_COUNT_SECTION_ID,
+        DATA_SECTION_ID,
+        ELEMENT_SECTION_ID,
+        EXPORT_SECTION_ID,
+        FUNCTION_SECTION_ID,
+        GLOBAL_SECTION_ID,
+        IMPORT_SECTION_ID,
+        MEMORY_SECTION_ID,
+        START_SECTION_ID,
+        TABLE_SECTION_ID,
+        TAG_SECTION_ID,
+        TYPE_SECTION_ID,
+        WASM_MAGIC,
+        WASM_VERSION,
+    },
+    module::{
+        ExportKind,
+        Memory,
+        Table,
+    },
+    types::{
+        FormatGlobalType,
+        RefType,
+        ValueType,
+    },
+};
+#[cfg(feature = "std")]
+use crate::{
+    encode::encode_module,
+    module::{
+        Export,
+        Function,
+        Global,
+        Import,
+        ImportDesc,
+        Module,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureDataSegment,
+        PureElementInit,
+        PureElementMode,
+        PureElementSegment,
+    },
+};
+
+/// Expression `end` opcode
+const END: u8 = 0x0B;
+
+/// Prefix of the saturating conversion, bulk memory and table instructions
+const MISC_PREFIX: u8 = 0xFC;
+
+/// Longest decimal float literal, without underscores, the lexer parses
+const MAX_FLOAT_DIGITS: usize = 256;
+
+/// A token of the text format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// Keyword such as `module`, `i32.add` or `offset=8`
+    Keyword(&'a str),
+    /// Symbolic identifier without its `$`
+    Id(&'a str),
+    /// Integer literal as written
+    Integer(&'a str),
+    /// Float literal as written
+    Float(&'a str),
+    /// String literal between its quotes, with escapes not yet decoded
+    String(&'a str),
+}
+
+/// Tokenizer of the text format
+///
+/// Skips white space and comments and borrows every token from the text,
+/// so it never allocates.
+#[derive(Debug, Clone)]
+pub struct Lexer<'a> {
+    text:  &'a str,
+    pos:   usize,
+    start: usize,
+}
+
+impl<'a> Lexer<'a> {
+    /// Create a lexer over `text`
+    pub fn new(text: &'a str) -> Self {
+        Self { text, pos: 0, start: 0 }
+    }
+
+    /// Byte offset of the last token returned
+    pub fn offset(&self) -> usize {
+        self.start
+    }
+
+    /// Next token, or `None` at the end of the text
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unterminated strings and comments and for
+    /// characters that start no token
+    pub fn next_token(&mut self) -> Result<Option<Token<'a>>> {
+        self.skip_trivia()?;
+        let bytes = self.text.as_bytes();
+        self.start = self.pos;
+        let Some(&first) = bytes.get(self.pos) else {
+            return Ok(None);
+        };
+        match first {
+            b'(' => {
+                self.pos += 1;
+                Ok(Some(Token::LParen))
+            },
+            b')' => {
+                self.pos += 1;
+                Ok(Some(Token::RParen))
+            },
+            b'"' => {
+                let mut end = self.pos + 1;
+                loop {
+                    match bytes.get(end) {
+                        None => return Err(Error::parse_error("Unterminated string literal")),
+                        Some(b'"') => break,
+                        Some(b'\\') => end += 2,
+                        Some(_) => end += 1,
+                    }
+                }
+                let content = &self.text[self.pos + 1..end];
+                self.pos = end + 1;
+                Ok(Some(Token::String(content)))
+            },
+            _ => {
+                let end = bytes[self.pos..]
+                    .iter()
+                    .position(|&byte| !is_idchar(byte))
+                    .map_or(bytes.len(), |len| self.pos + len);
+                if end == self.pos {
+                    return Err(Error::parse_error("Unexpected character in text"));
+                }
+                let atom = &self.text[self.pos..end];
+                self.pos = end;
+                classify(atom).map(Some)
+            },
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<()> {
+        let bytes = self.text.as_bytes();
+        loop {
+            match bytes.get(self.pos..self.pos + 2) {
+                Some(b";;") => {
+                    self.pos = bytes[self.pos..]
+                        .iter()
+                        .position(|&byte| byte == b'\n')
+                        .map_or(bytes.len(), |len| self.pos + len);
+                },
+                Some(b"(;") => {
+                    let mut depth = 0usize;
+                    loop {
+                        match bytes.get(self.pos..self.pos + 2) {
+                            None => return Err(Error::parse_error("Unterminated block comment")),
+                            Some(b"(;") => {
+                                depth += 1;
+                                self.pos += 2;
+                            },
+                            Some(b";)") => {
+                                depth -= 1;
+                                self.pos += 2;
+                                if depth == 0 {
+                                    break;
+                                }
+                            },
+                            Some(_) => self.pos += 1,
+                        }
+                    }
+                },
+                _ => match bytes.get(self.pos) {
+                    Some(byte) if byte.is_ascii_whitespace() => self.pos += 1,
+                    _ => return Ok(()),
+                },
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
+
+fn is_idchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-./:<=>?@\\^_`|~".contains(&byte)
+}
+
+fn classify(atom: &str) -> Result<Token<'_>> {
+    if let Some(id) = atom.strip_prefix('$') {
+        if id.is_empty() {
+            return Err(Error::parse_error("Empty identifier"));
+        }
+        return Ok(Token::Id(id));
+    }
+    if atom.as_bytes()[0].is_ascii_lowercase()
+        && !atom.starts_with("inf")
+        && !atom.starts_with("nan")
+    {
+        return Ok(Token::Keyword(atom));
+    }
+    let unsigned = atom.strip_prefix(['+', '-']).unwrap_or(atom);
+    if unsigned == "inf" || unsigned == "nan" || unsigned.starts_with("nan:0x") {
+        return Ok(Token::Float(atom));
+    }
+    if !unsigned.as_bytes().first().is_some_and(u8::is_ascii_digit) {
+        return Err(Error::parse_error("Unknown token"));
+    }
+    let float = match unsigned.strip_prefix("0x") {
+        Some(hex) => hex.contains(['.', 'p', 'P']),
+        None => unsigned.contains(['.', 'e', 'E']),
+    };
+    Ok(if float { Token::Float(atom) } else { Token::Integer(atom) })
+}
+
+/// Split an optional sign off a number
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+/// Parse the digits of an unsigned integer, with `0x` and `_` separators
+fn parse_magnitude(text: &str) -> Option<u64> {
+    let (digits, radix) = match text.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (text, 10),
+    };
+    if digits.is_empty()
+        || digits.starts_with('_')
+        || digits.ends_with('_')
+        || digits.contains("__")
+    {
+        return None;
+    }
+    let mut value = 0u64;
+    for ch in digits.chars().filter(|&ch| ch != '_') {
+        let digit = u64::from(ch.to_digit(radix)?);
+        value = value.checked_mul(u64::from(radix))?.checked_add(digit)?;
+    }
+    Some(value)
+}
+
+/// Parse an unsigned 32-bit integer, such as an index or offset
+pub fn parse_u32(text: &str) -> Option<u32> {
+    if text.starts_with(['+', '-']) {
+        return None;
+    }
+    u32::try_from(parse_magnitude(text)?).ok()
+}
+
+/// Parse an `i32` literal, written signed or unsigned
+pub fn parse_i32(text: &str) -> Option<i32> {
+    let (negative, digits) = split_sign(text);
+    let magnitude = parse_magnitude(digits)?;
+    if negative {
+        (magnitude <= 1 << 31).then(|| (magnitude as u32).wrapping_neg() as i32)
+    } else {
+        u32::try_from(magnitude).ok().map(|value| value as i32)
+    }
+}
+
+/// Parse an `i64` literal, written signed or unsigned
+pub fn parse_i64(text: &str) -> Option<i64> {
+    let (negative, digits) = split_sign(text);
+    let magnitude = parse_magnitude(digits)?;
+    if negative {
+        (magnitude <= 1 << 63).then(|| magnitude.wrapping_neg() as i64)
+    } else {
+        Some(magnitude as i64)
+    }
+}
+
+/// Parse an `f32` literal into its bits
+pub fn parse_f32(text: &str) -> Option<u32> {
+    parse_float(text, 23, 8).map(|bits| bits as u32)
+}
+
+/// Parse an `f64` literal into its bits
+pub fn parse_f64(text: &str) -> Option<u64> {
+    parse_float(text, 52, 11)
+}
+
+/// Parse a float literal into the bits of a float with `mant_bits`
+/// fraction and `exp_bits` exponent bits
+fn parse_float(text: &str, mant_bits: u32, exp_bits: u32) -> Option<u64> {
+    let (negative, body) = split_sign(text);
+    let exp_mask = (1u64 << exp_bits) - 1;
+    let frac_mask = (1u64 << mant_bits) - 1;
+    let magnitude = if body == "inf" {
+        exp_mask << mant_bits
+    } else if body == "nan" {
+        (exp_mask << mant_bits) | (1 << (mant_bits - 1))
+    } else if let Some(payload) = body.strip_prefix("nan:") {
+        let payload = parse_magnitude(payload).filter(|&p| p != 0 && p <= frac_mask)?;
+        (exp_mask << mant_bits) | payload
+    } else if let Some(hex) = body.strip_prefix("0x") {
+        parse_hex_float(hex, mant_bits, exp_bits)?
+    } else {
+        parse_decimal_float(body, mant_bits)?
+    };
+    Some(magnitude | (u64::from(negative) << (mant_bits + exp_bits)))
+}
+
+fn parse_decimal_float(body: &str, mant_bits: u32) -> Option<u64> {
+    if !body.as_bytes().first()?.is_ascii_digit() || body.contains("__") {
+        return None;
+    }
+    let mut buffer = [0u8; MAX_FLOAT_DIGITS];
+    let mut len = 0;
+    for &byte in body.as_bytes().iter().filter(|&&byte| byte != b'_') {
+        *buffer.get_mut(len)? = byte;
+        len += 1;
+    }
+    let digits = core::str::from_utf8(&buffer[..len]).ok()?;
+    if mant_bits == 23 {
+        let value: f32 = digits.parse().ok()?;
+        value.is_finite().then(|| u64::from(value.to_bits()))
+    } else {
+        let value: f64 = digits.parse().ok()?;
+        value.is_finite().then(|| value.to_bits())
+    }
+}
+
+/// Parse a hexadecimal float, rounding to nearest even
+fn parse_hex_float(hex: &str, mant_bits: u32, exp_bits: u32) -> Option<u64> {
+    let (digits, exponent) = match hex.find(['p', 'P']) {
+        Some(at) => (&hex[..at], Some(&hex[at + 1..])),
+        None => (hex, None),
+    };
+    let mut mantissa = 0u64;
+    let mut scale = 0i64;
+    let mut sticky = false;
+    let mut fraction = false;
+    let mut any_digit = false;
+    for ch in digits.chars() {
+        match ch {
+            '_' => continue,
+            '.' if !fraction => fraction = true,
+            _ => {
+                let digit = u64::from(ch.to_digit(16)?);
+                any_digit = true;
+                if mantissa < 1 << 59 {
+                    mantissa = mantissa * 16 + digit;
+                    if fraction {
+                        scale -= 4;
+                    }
+                } else {
+                    sticky |= digit != 0;
+                    if !fraction {
+                        scale += 4;
+                    }
+                }
+            },
+        }
+    }
+    if !any_digit {
+        return None;
+    }
+    if let Some(exponent) = exponent {
+        let (negative, digits) = split_sign(exponent);
+        let value = parse_magnitude(digits)?.min(1 << 20) as i64;
+        scale += if negative { -value } else { value };
+    }
+    if mantissa == 0 {
+        return Some(0);
+    }
+
+    let bias = (1i64 << (exp_bits - 1)) - 1;
+    let min_exp = 1 - bias;
+    let top = i64::from(63 - mantissa.leading_zeros());
+    let mut exp = top + scale;
+    let shift = top - i64::from(mant_bits) + (min_exp - exp).max(0);
+    let mut mant = if shift <= 0 {
+        mantissa << (-shift)
+    } else {
+        let (kept, round, rest) = if shift > 64 {
+            (0, false, true)
+        } else if shift == 64 {
+            (0, mantissa >> 63 == 1, mantissa << 1 != 0)
+        } else {
+            let half = 1u64 << (shift - 1);
+            (mantissa >> shift, mantissa & half != 0, mantissa & (half - 1) != 0)
+        };
+        if round && (rest || sticky || kept & 1 == 1) {
+            kept + 1
+        } else {
+            kept
+        }
+    };
+    let frac_mask = (1u64 << mant_bits) - 1;
+    if exp < min_exp {
+        let biased = u64::from(mant > frac_mask);
+        return Some((biased << mant_bits) | (mant & frac_mask));
+    }
+    if mant >> (mant_bits + 1) != 0 {
+        mant >>= 1;
+        exp += 1;
+    }
+    let biased = exp + bias;
+    if biased >= (1 << exp_bits) - 1 {
+        return None;
+    }
+    Some(((biased as u64) << mant_bits) | (mant & frac_mask))
+}
+
+/// Decode the escapes of a string literal, passing each byte to `push`
+///
+/// # Errors
+///
+/// Returns an error for malformed escapes, or the first error of `push`
+pub fn unescape(raw: &str, mut push: impl FnMut(u8) -> Result<()>) -> Result<()> {
+    let bytes = raw.as_bytes();
+    let mut pos = 0;
+    while let Some(&byte) = bytes.get(pos) {
+        pos += 1;
+        if byte != b'\\' {
+            push(byte)?;
+            continue;
+        }
+        let escape =
+            *bytes.get(pos).ok_or_else(|| Error::parse_error("Malformed string escape"))?;
+        pos += 1;
+        match escape {
+            b't' => push(b'\t')?,
+            b'n' => push(b'\n')?,
+            b'r' => push(b'\r')?,
+            b'"' | b'\'' | b'\\' => push(escape)?,
+            b'u' => {
+                let close = raw[pos..]
+                    .find('}')
+                    .filter(|_| bytes.get(pos) == Some(&b'{'))
+                    .ok_or_else(|| Error::parse_error("Malformed unicode escape"))?;
+                let ch = u32::from_str_radix(&raw[pos + 1..pos + close], 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| Error::parse_error("Malformed unicode escape"))?;
+                let mut encoded = [0u8; 4];
+                for &byte in ch.encode_utf8(&mut encoded).as_bytes() {
+                    push(byte)?;
+                }
+                pos += close + 1;
+            },
+            high => {
+                let low = bytes.get(pos).copied().unwrap_or(0);
+                let (Some(high), Some(low)) =
+                    (char::from(high).to_digit(16), char::from(low).to_digit(16))
+                else {
+                    return Err(Error::parse_error("Malformed string escape"));
+                };
+                push((high * 16 + low) as u8)?;
+                pos += 1;
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Immediate operands of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Imm {
+    None,
+    Block,
+    Label,
+    LabelTable,
+    Func,
+    CallIndirect,
+    Local,
+    Global,
+    Table,
+    /// Memory argument with the natural alignment as a power of two
+    Memarg(u32),
+    Memory,
+    I32,
+    I64,
+    F32,
+    F64,
+    RefNull,
+    Select,
+    Data,
+    Elem,
+    MemoryInit,
+    MemoryCopy,
+    TableInit,
+    TableCopy,
+}
+
+/// Text name, encoding and immediates of an instruction
+#[derive(Debug, Clone, Copy)]
+struct Instr {
+    name:   &'static str,
+    /// Opcode, or the sub-opcode after [`MISC_PREFIX`]
+    code:   u8,
+    prefix: bool,
+    imm:    Imm,
+}
+
+const fn op(name: &'static str, code: u8, imm: Imm) -> Instr {
+    Instr { name, code, prefix: false, imm }
+}
+
+const fn misc(name: &'static str, code: u8, imm: Imm) -> Instr {
+    Instr { name, code, prefix: true, imm }
+}
+
+/// Instructions with immediates, or outside the numeric opcode range
+const INSTRUCTIONS: &[Instr] = &[
+    op("unreachable", 0x00, Imm::None),
+    op("nop", 0x01, Imm::None),
+    op("block", 0x02, Imm::Block),
+    op("loop", 0x03, Imm::Block),
+    op("if", 0x04, Imm::Block),
+    op("else", 0x05, Imm::None),
+    op("end", 0x0B, Imm::None),
+    op("br", 0x0C, Imm::Label),
+    op("br_if", 0x0D, Imm::Label),
+    op("br_table", 0x0E, Imm::LabelTable),
+    op("return", 0x0F, Imm::None),
+    op("call", 0x10, Imm::Func),
+    op("call_indirect", 0x11, Imm::CallIndirect),
+    op("return_call", 0x12, Imm::Func),
+    op("return_call_indirect", 0x13, Imm::CallIndirect),
+    op("drop", 0x1A, Imm::None),
+    op("select", 0x1B, Imm::Select),
+    op("local.get", 0x20, Imm::Local),
+    op("local.set", 0x21, Imm::Local),
+    op("local.tee", 0x22, Imm::Local),
+    op("global.get", 0x23, Imm::Global),
+    op("global.set", 0x24, Imm::Global),
+    op("table.get", 0x25, Imm::Table),
+    op("table.set", 0x26, Imm::Table),
+    op("i32.load", 0x28, Imm::Memarg(2)),
+    op("i64.load", 0x29, Imm::Memarg(3)),
+    op("f32.load", 0x2A, Imm::Memarg(2)),
+    op("f64.load", 0x2B, Imm::Memarg(3)),
+    op("i32.load8_s", 0x2C, Imm::Memarg(0)),
+    op("i32.load8_u", 0x2D, Imm::Memarg(0)),
+    op("i32.load16_s", 0x2E, Imm::Memarg(1)),
+    op("i32.load16_u", 0x2F, Imm::Memarg(1)),
+    op("i64.load8_s", 0x30, Imm::Memarg(0)),
+    op("i64.load8_u", 0x31, Imm::Memarg(0)),
+    op("i64.load16_s", 0x32, Imm::Memarg(1)),
+    op("i64.load16_u", 0x33, Imm::Memarg(1)),
+    op("i64.load32_s", 0x34, Imm::Memarg(2)),
+    op("i64.load32_u", 0x35, Imm::Memarg(2)),
+    op("i32.store", 0x36, Imm::Memarg(2)),
+    op("i64.store", 0x37, Imm::Memarg(3)),
+    op("f32.store", 0x38, Imm::Memarg(2)),
+    op("f64.store", 0x39, Imm::Memarg(3)),
+    op("i32.store8", 0x3A, Imm::Memarg(0)),
+    op("i32.store16", 0x3B, Imm::Memarg(1)),
+    op("i64.store8", 0x3C, Imm::Memarg(0)),
+    op("i64.store16", 0x3D, Imm::Memarg(1)),
+    op("i64.store32", 0x3E, Imm::Memarg(2)),
+    op("memory.size", 0x3F, Imm::Memory),
+    op("memory.grow", 0x40, Imm::Memory),
+    op("i32.const", 0x41, Imm::I32),
+    op("i64.const", 0x42, Imm::I64),
+    op("f32.const", 0x43, Imm::F32),
+    op("f64.const", 0x44, Imm::F64),
+    op("ref.null", 0xD0, Imm::RefNull),
+    op("ref.is_null", 0xD1, Imm::None),
+    op("ref.func", 0xD2, Imm::Func),
+    misc("i32.trunc_sat_f32_s", 0x00, Imm::None),
+    misc("i32.trunc_sat_f32_u", 0x01, Imm::None),
+    misc("i32.trunc_sat_f64_s", 0x02, Imm::None),
+    misc("i32.trunc_sat_f64_u", 0x03, Imm::None),
+    misc("i64.trunc_sat_f32_s", 0x04, Imm::None),
+    misc("i64.trunc_sat_f32_u", 0x05, Imm::None),
+    misc("i64.trunc_sat_f64_s", 0x06, Imm::None),
+    misc("i64.trunc_sat_f64_u", 0x07, Imm::None),
+    misc("memory.init", 0x08, Imm::MemoryInit),
+    misc("data.drop", 0x09, Imm::Data),
+    misc("memory.copy", 0x0A, Imm::MemoryCopy),
+    misc("memory.fill", 0x0B, Imm::Memory),
+    misc("table.init", 0x0C, Imm::TableInit),
+    misc("elem.drop", 0x0D, Imm::Elem),
+    misc("table.copy", 0x0E, Imm::TableCopy),
+    misc("table.grow", 0x0F, Imm::Table),
+    misc("table.size", 0x10, Imm::Table),
+    misc("table.fill", 0x11, Imm::Table),
+];
+
+/// First opcode of the numeric instructions without immediates
+const NUMERIC_BASE: u8 = 0x45;
+
+/// Numeric instructions without immediates, from [`NUMERIC_BASE`] on
+const NUMERIC: [&str; 128] = [
+    "i32.eqz",
+    "i32.eq",
+    "i32.ne",
+    "i32.lt_s",
+    "i32.lt_u",
+    "i32.gt_s",
+    "i32.gt_u",
+    "i32.le_s",
+    "i32.le_u",
+    "i32.ge_s",
+    "i32.ge_u",
+    "i64.eqz",
+    "i64.eq",
+    "i64.ne",
+    "i64.lt_s",
+    "i64.lt_u",
+    "i64.gt_s",
+    "i64.gt_u",
+    "i64.le_s",
+    "i64.le_u",
+    "i64.ge_s",
+    "i64.ge_u",
+    "f32.eq",
+    "f32.ne",
+    "f32.lt",
+    "f32.gt",
+    "f32.le",
+    "f32.ge",
+    "f64.eq",
+    "f64.ne",
+    "f64.lt",
+    "f64.gt",
+    "f64.le",
+    "f64.ge",
+    "i32.clz",
+    "i32.ctz",
+    "i32.popcnt",
+    "i32.add",
+    "i32.sub",
+    "i32.mul",
+    "i32.div_s",
+    "i32.div_u",
+    "i32.rem_s",
+    "i32.rem_u",
+    "i32.and",
+    "i32.or",
+    "i32.xor",
+    "i32.shl",
+    "i32.shr_s",
+    "i32.shr_u",
+    "i32.rotl",
+    "i32.rotr",
+    "i64.clz",
+    "i64.ctz",
+    "i64.popcnt",
+    "i64.add",
+    "i64.sub",
+    "i64.mul",
+    "i64.div_s",
+    "i64.div_u",
+    "i64.rem_s",
+    "i64.rem_u",
+    "i64.and",
+    "i64.or",
+    "i64.xor",
+    "i64.shl",
+    "i64.shr_s",
+    "i64.shr_u",
+    "i64.rotl",
+    "i64.rotr",
+    "f32.abs",
+    "f32.neg",
+    "f32.ceil",
+    "f32.floor",
+    "f32.trunc",
+    "f32.nearest",
+    "f32.sqrt",
+    "f32.add",
+    "f32.sub",
+    "f32.mul",
+    "f32.div",
+    "f32.min",
+    "f32.max",
+    "f32.copysign",
+    "f64.abs",
+    "f64.neg",
+    "f64.ceil",
+    "f64.floor",
+    "f64.trunc",
+    "f64.nearest",
+    "f64.sqrt",
+    "f64.add",
+    "f64.sub",
+    "f64.mul",
+    "f64.div",
+    "f64.min",
+    "f64.max",
+    "f64.copysign",
+    "i32.wrap_i64",
+    "i32.trunc_f32_s",
+    "i32.trunc_f32_u",
+    "i32.trunc_f64_s",
+    "i32.trunc_f64_u",
+    "i64.extend_i32_s",
+    "i64.extend_i32_u",
+    "i64.trunc_f32_s",
+    "i64.trunc_f32_u",
+    "i64.trunc_f64_s",
+    "i64.trunc_f64_u",
+    "f32.convert_i32_s",
+    "f32.convert_i32_u",
+    "f32.convert_i64_s",
+    "f32.convert_i64_u",
+    "f32.demote_f64",
+    "f64.convert_i32_s",
+    "f64.convert_i32_u",
+    "f64.convert_i64_s",
+    "f64.convert_i64_u",
+    "f64.promote_f32",
+    "i32.reinterpret_f32",
+    "i64.reinterpret_f64",
+    "f32.reinterpret_i32",
+    "f64.reinterpret_i64",
+    "i32.extend8_s",
+    "i32.extend16_s",
+    "i64.extend8_s",
+    "i64.extend16_s",
+    "i64.extend32_s",
+];
+
+fn instr_by_name(name: &str) -> Option<Instr> {
+    if let Some(at) = NUMERIC.iter().position(|&numeric| numeric == name) {
+        return Some(op(NUMERIC[at], NUMERIC_BASE + at as u8, Imm::None));
+    }
+    INSTRUCTIONS.iter().find(|instr| instr.name == name).copied()
+}
+
+fn instr_by_code(prefix: bool, code: u8) -> Option<Instr> {
+    if !prefix {
+        if let Some(&name) =
+            code.checked_sub(NUMERIC_BASE).and_then(|at| NUMERIC.get(usize::from(at)))
+        {
+            return Some(op(name, code, Imm::None));
+        }
+    }
+    INSTRUCTIONS
+        .iter()
+        .find(|instr| instr.prefix == prefix && instr.code == code)
+        .copied()
+}
+
+/// Text name of a value type encoding
+fn value_type_name(byte: u8) -> Option<&'static str> {
+    Some(match byte {
+        0x7F => "i32",
+        0x7E => "i64",
+        0x7D => "f32",
+        0x7C => "f64",
+        0x7B => "v128",
+        0x70 => "funcref",
+        0x6F => "externref",
+        _ => return None,
+    })
+}
+
+/// Cursor over encoded instructions and module sections
+struct CodeReader<'a> {
+    code: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> CodeReader<'a> {
+    /// Bytes after the cursor
+    fn rest(&self) -> &'a [u8] {
+        self.code.get(self.pos..).unwrap_or_default()
+    }
+
+    fn slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self.rest().get(..len).ok_or_else(|| Error::parse_error("Binary truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Length-prefixed bytes, such as a name
+    fn vec_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.slice(len)
+    }
+
+    /// Parameter and result types of the function type at the cursor
+    fn signature(&mut self) -> Result<(&'a [u8], &'a [u8])> {
+        if self.u8()? != 0x60 {
+            return Err(Error::validation_unsupported_feature("Only function types are supported"));
+        }
+        Ok((self.vec_bytes()?, self.vec_bytes()?))
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let byte = *self
+            .code
+            .get(self.pos)
+            .ok_or_else(|| Error::parse_error("Instruction truncated"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let (value, len) = read_leb128_u32(self.code, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        let (value, len) = read_leb128_i32(self.code, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        let (value, len) = read_leb128_i64(self.code, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .code
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| Error::parse_error("Instruction truncated"))?;
+        self.pos += N;
+        let mut array = [0u8; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+}
+
+fn write_error(_: fmt::Error) -> Error {
+    Error::serialization_error("Failed to write text")
+}
+
+/// Write the instructions of an expression, one per line indented by
+/// `indent` spaces, and return the number of bytes it takes up
+///
+/// The expression ends with the `end` that closes it, which is not
+/// printed, as in a function body or a constant expression.
+///
+/// # Errors
+///
+/// Returns an error for truncated code, instructions the text format
+/// support does not cover, or if `out` fails
+pub fn write_instructions<W: fmt::Write>(out: &mut W, code: &[u8], indent: usize) -> Result<usize> {
+    write_expression(out, code, Some(indent))
+}
+
+/// Write the instructions of an expression on a single line, separated by
+/// spaces, and return the number of bytes it takes up
+///
+/// # Errors
+///
+/// As for [`write_instructions`]
+pub fn write_inline_instructions<W: fmt::Write>(out: &mut W, code: &[u8]) -> Result<usize> {
+    write_expression(out, code, None)
+}
+
+fn write_expression<W: fmt::Write>(
+    out: &mut W,
+    code: &[u8],
+    indent: Option<usize>,
+) -> Result<usize> {
+    let mut reader = CodeReader { code, pos: 0 };
+    let mut depth = 0usize;
+    let mut first = true;
+    loop {
+        let opcode = reader.u8()?;
+        let instr = if opcode == MISC_PREFIX {
+            let sub = reader.u32()?;
+            u8::try_from(sub).ok().and_then(|sub| instr_by_code(true, sub))
+        } else {
+            instr_by_code(false, opcode)
+        }
+        .ok_or_else(|| {
+            Error::validation_unsupported_feature("Instruction not supported in text")
+        })?;
+
+        if instr.code == END && !instr.prefix {
+            if depth == 0 {
+                return Ok(reader.pos);
+            }
+            depth -= 1;
+        }
+        let level = if instr.name == "else" { depth.saturating_sub(1) } else { depth };
+        match indent {
+            Some(indent) => {
+                write!(out, "{:width$}", "", width = indent + 2 * level).map_err(write_error)?;
+            },
+            None if !first => out.write_char(' ').map_err(write_error)?,
+            None => {},
+        }
+        first = false;
+        out.write_str(instr.name).map_err(write_error)?;
+        write_immediates(out, &mut reader, instr.imm)?;
+        if indent.is_some() {
+            out.write_char('\n').map_err(write_error)?;
+        }
+        if instr.imm == Imm::Block {
+            depth += 1;
+        }
+    }
+}
+
+fn write_immediates<W: fmt::Write>(
+    out: &mut W,
+    reader: &mut CodeReader<'_>,
+    imm: Imm,
+) -> Result<()> {
+    match imm {
+        Imm::None => {},
+        Imm::Block => match reader.code.get(reader.pos) {
+            Some(0x40) => reader.pos += 1,
+            Some(&byte) if value_type_name(byte).is_some() => {
+                reader.pos += 1;
+                write!(out, " (result {})", value_type_name(byte).unwrap_or_default())
+                    .map_err(write_error)?;
+            },
+            _ => {
+                let index = reader.i64()?;
+                let index =
+                    u32::try_from(index).map_err(|_| Error::parse_error("Invalid block type"))?;
+                write!(out, " (type {index})").map_err(write_error)?;
+            },
+        },
+        Imm::Label | Imm::Func | Imm::Local | Imm::Global | Imm::Table | Imm::Data | Imm::Elem => {
+            write!(out, " {}", reader.u32()?).map_err(write_error)?
+        },
+        Imm::LabelTable => {
+            let count = reader.u32()?;
+            for _ in 0..=count {
+                write!(out, " {}", reader.u32()?).map_err(write_error)?;
+            }
+        },
+        Imm::CallIndirect => {
+            let type_idx = reader.u32()?;
+            let table = reader.u32()?;
+            if table != 0 {
+                write!(out, " {table}").map_err(write_error)?;
+            }
+            write!(out, " (type {type_idx})").map_err(write_error)?;
+        },
+        Imm::Memarg(natural) => {
+            let align = reader.u32()?;
+            let offset = reader.u32()?;
+            if offset != 0 {
+                write!(out, " offset={offset}").map_err(write_error)?;
+            }
+            if align != natural {
+                let bytes = 1u64
+                    .checked_shl(align)
+                    .ok_or_else(|| Error::parse_error("Invalid alignment"))?;
+                write!(out, " align={bytes}").map_err(write_error)?;
+            }
+        },
+        Imm::Memory => {
+            let memory = reader.u32()?;
+            if memory != 0 {
+                write!(out, " {memory}").map_err(write_error)?;
+            }
+        },
+        Imm::I32 => write!(out, " {}", reader.i32()?).map_err(write_error)?,
+        Imm::I64 => write!(out, " {}", reader.i64()?).map_err(write_error)?,
+        Imm::F32 => {
+            let bits = u32::from_le_bytes(reader.bytes()?);
+            out.write_char(' ').map_err(write_error)?;
+            write_f32(out, bits).map_err(write_error)?;
+        },
+        Imm::F64 => {
+            let bits = u64::from_le_bytes(reader.bytes()?);
+            out.write_char(' ').map_err(write_error)?;
+            write_f64(out, bits).map_err(write_error)?;
+        },
+        Imm::RefNull => match reader.u8()? {
+            0x70 => out.write_str(" func").map_err(write_error)?,
+            0x6F => out.write_str(" extern").map_err(write_error)?,
+            _ => {
+                return Err(Error::validation_unsupported_feature(
+                    "Heap type not supported in text",
+                ))
+            },
+        },
+        Imm::Select => {},
+        Imm::MemoryInit => {
+            let data = reader.u32()?;
+            let memory = reader.u32()?;
+            if memory != 0 {
+                write!(out, " {memory}").map_err(write_error)?;
+            }
+            write!(out, " {data}").map_err(write_error)?;
+        },
+        Imm::MemoryCopy => {
+            let (dst, src) = (reader.u32()?, reader.u32()?);
+            if dst != 0 || src != 0 {
+                write!(out, " {dst} {src}").map_err(write_error)?;
+            }
+        },
+        Imm::TableInit => {
+            let elem = reader.u32()?;
+            let table = reader.u32()?;
+            write!(out, " {table} {elem}").map_err(write_error)?;
+        },
+        Imm::TableCopy => {
+            let (dst, src) = (reader.u32()?, reader.u32()?);
+            write!(out, " {dst} {src}").map_err(write_error)?;
+        },
+    }
+    Ok(())
+}
+
+/// Write the bits of an `f32` as a literal that parses back to them
+fn write_f32<W: fmt::Write>(out: &mut W, bits: u32) -> fmt::Result {
+    let value = f32::from_bits(bits);
+    if value.is_nan() {
+        let payload = bits & 0x007F_FFFF;
+        return write_nan(out, bits >> 31 == 1, u64::from(payload), 1 << 22);
+    }
+    write_finite(out, value.is_sign_negative(), value.is_infinite(), format_args!("{value:e}"))
+}
+
+/// Write the bits of an `f64` as a literal that parses back to them
+fn write_f64<W: fmt::Write>(out: &mut W, bits: u64) -> fmt::Result {
+    let value = f64::from_bits(bits);
+    if value.is_nan() {
+        let payload = bits & 0x000F_FFFF_FFFF_FFFF;
+        return write_nan(out, bits >> 63 == 1, payload, 1 << 51);
+    }
+    write_finite(out, value.is_sign_negative(), value.is_infinite(), format_args!("{value:e}"))
+}
+
+fn write_nan<W: fmt::Write>(
+    out: &mut W,
+    negative: bool,
+    payload: u64,
+    canonical: u64,
+) -> fmt::Result {
+    if negative {
+        out.write_char('-')?;
+    }
+    if payload == canonical {
+        out.write_str("nan")
+    } else {
+        write!(out, "nan:0x{payload:x}")
+    }
+}
+
+fn write_finite<W: fmt::Write>(
+    out: &mut W,
+    negative: bool,
+    infinite: bool,
+    value: fmt::Arguments<'_>,
+) -> fmt::Result {
+    if infinite {
+        out.write_str(if negative { "-inf" } else { "inf" })
+    } else {
+        out.write_fmt(value)
+    }
+}
+
+/// Write `bytes` as a string literal
+fn write_string<W: fmt::Write>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    out.write_char('"')?;
+    for &byte in bytes {
+        match byte {
+            b'"' | b'\\' => write!(out, "\\{}", char::from(byte))?,
+            0x20..=0x7E => out.write_char(char::from(byte))?,
+            _ => write!(out, "\\{byte:02x}")?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Print a module in the text format
+///
+/// The module is encoded and printed with [`write_binary_module`].
+///
+/// # Errors
+///
+/// Returns an error if the module uses instructions or definitions the
+/// text format support does not cover, such as exception tags
+#[cfg(feature = "std")]
+pub fn print_module(module: &Module) -> Result<String> {
+    let mut out = String::new();
+    write_module(&mut out, module)?;
+    Ok(out)
+}
+
+/// Write a module in the text format to `out`
+///
+/// # Errors
+///
+/// As for [`print_module`], or if `out` fails
+#[cfg(feature = "std")]
+pub fn write_module<W: fmt::Write>(out: &mut W, module: &Module) -> Result<()> {
+    write_binary_module(out, &encode_module(module)?)
+}
+
+/// Number of section ids a module binary may use
+const SECTIONS: usize = TAG_SECTION_ID as usize + 1;
+
+/// Write a module binary in the text format to `out`
+///
+/// Custom sections are skipped. Only `core` is needed, so `no_std` builds
+/// can print a module into a bounded buffer.
+///
+/// # Errors
+///
+/// Returns an error for a malformed binary, instructions or definitions the
+/// text format support does not cover, such as exception tags, or if `out`
+/// fails
+pub fn write_binary_module<W: fmt::Write>(out: &mut W, wasm: &[u8]) -> Result<()> {
+    if wasm.get(..4) != Some(&WASM_MAGIC[..]) || wasm.get(4..8) != Some(&WASM_VERSION[..]) {
+        return Err(Error::parse_error("Not a WebAssembly module"));
+    }
+    let mut sections: [&[u8]; SECTIONS] = [&[]; SECTIONS];
+    let mut reader = CodeReader { code: wasm, pos: 8 };
+    while reader.pos < wasm.len() {
+        let id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let body = reader.slice(size)?;
+        match sections.get_mut(usize::from(id)) {
+            Some(_) if id == CUSTOM_SECTION_ID => {},
+            Some(section) => *section = body,
+            None => return Err(Error::parse_error("Unknown section id")),
+        }
+    }
+    if !sections[usize::from(TAG_SECTION_ID)].is_empty() {
+        return Err(Error::validation_unsupported_feature("Exception tags not supported in text"));
+    }
+    let section = |id: u8| sections[usize::from(id)];
+
+    writeln!(out, "(module").map_err(write_error)?;
+    let (mut types, count) = entries(section(TYPE_SECTION_ID))?;
+    let signatures = types.rest();
+    for index in 0..count {
+        let (params, results) = types.signature()?;
+        write!(out, "  (type (;{index};) (func").map_err(write_error)?;
+        write_signature(out, params, results)?;
+        writeln!(out, "))").map_err(write_error)?;
+    }
+
+    let mut counts = [0u32; 4];
+    let (mut imports, count) = entries(section(IMPORT_SECTION_ID))?;
+    for _ in 0..count {
+        write!(out, "  (import ").map_err(write_error)?;
+        write_string(out, imports.vec_bytes()?).map_err(write_error)?;
+        out.write_char(' ').map_err(write_error)?;
+        write_string(out, imports.vec_bytes()?).map_err(write_error)?;
+        let kind = imports.u8()?;
+        let keyword = match kind {
+            0x00 => "func",
+            0x01 => "table",
+            0x02 => "memory",
+            0x03 => "global",
+            0x04 => {
+                return Err(Error::validation_unsupported_feature(
+                    "Exception tags not supported in text",
+                ))
+            },
+            _ => return Err(Error::parse_error("Unknown import kind")),
+        };
+        let index = &mut counts[usize::from(kind)];
+        write!(out, " ({keyword} (;{index};) ").map_err(write_error)?;
+        *index += 1;
+        match kind {
+            0x00 => write!(out, "(type {})", imports.u32()?).map_err(write_error)?,
+            0x01 => write_table_type(out, &mut imports)?,
+            0x02 => write_memory_type(out, &mut imports)?,
+            _ => write_global_type(out, &mut imports)?,
+        }
+        writeln!(out, "))").map_err(write_error)?;
+    }
+
+    let (mut functions, count) = entries(section(FUNCTION_SECTION_ID))?;
+    let (mut bodies, bodies_count) = entries(section(CODE_SECTION_ID))?;
+    if bodies_count != count {
+        return Err(Error::validation_error("Function and code section sizes differ"));
+    }
+    for offset in 0..count {
+        let type_idx = functions.u32()?;
+        write!(out, "  (func (;{};) (type {type_idx})", counts[0] + offset).map_err(write_error)?;
+        if let Some((params, results)) = signature_of(signatures, type_idx)? {
+            write_signature(out, params, results)?;
+        }
+        writeln!(out).map_err(write_error)?;
+        let size = bodies.u32()? as usize;
+        let mut body = CodeReader { code: bodies.slice(size)?, pos: 0 };
+        let mut locals = false;
+        for _ in 0..body.u32()? {
+            let count = body.u32()?;
+            let ty = value_type_text(body.u8()?)?;
+            for _ in 0..count {
+                if !locals {
+                    write!(out, "    (local").map_err(write_error)?;
+                    locals = true;
+                }
+                write!(out, " {ty}").map_err(write_error)?;
+            }
+        }
+        if locals {
+            writeln!(out, ")").map_err(write_error)?;
+        }
+        if !body.rest().is_empty() {
+            write_instructions(out, body.rest(), 4)?;
+        }
+        writeln!(out, "  )").map_err(write_error)?;
+    }
+
+    let (mut tables, count) = entries(section(TABLE_SECTION_ID))?;
+    for offset in 0..count {
+        write!(out, "  (table (;{};) ", counts[1] + offset).map_err(write_error)?;
+        write_table_type(out, &mut tables)?;
+        writeln!(out, ")").map_err(write_error)?;
+    }
+    let (mut memories, count) = entries(section(MEMORY_SECTION_ID))?;
+    for offset in 0..count {
+        write!(out, "  (memory (;{};) ", counts[2] + offset).map_err(write_error)?;
+        write_memory_type(out, &mut memories)?;
+        writeln!(out, ")").map_err(write_error)?;
+    }
+    let (mut globals, count) = entries(section(GLOBAL_SECTION_ID))?;
+    for offset in 0..count {
+        write!(out, "  (global (;{};) ", counts[3] + offset).map_err(write_error)?;
+        write_global_type(out, &mut globals)?;
+        out.write_char(' ').map_err(write_error)?;
+        write_const_expr(out, &mut globals)?;
+        writeln!(out, ")").map_err(write_error)?;
+    }
+
+    let (mut exports, count) = entries(section(EXPORT_SECTION_ID))?;
+    for _ in 0..count {
+        write!(out, "  (export ").map_err(write_error)?;
+        write_string(out, exports.vec_bytes()?).map_err(write_error)?;
+        let kind = match exports.u8()? {
+            0x00 => "func",
+            0x01 => "table",
+            0x02 => "memory",
+            0x03 => "global",
+            0x04 => {
+                return Err(Error::validation_unsupported_feature(
+                    "Exception tags not supported in text",
+                ))
+            },
+            _ => return Err(Error::parse_error("Unknown export kind")),
+        };
+        writeln!(out, " ({kind} {}))", exports.u32()?).map_err(write_error)?;
+    }
+    let start = section(START_SECTION_ID);
+    if !start.is_empty() {
+        let start = CodeReader { code: start, pos: 0 }.u32()?;
+        writeln!(out, "  (start {start})").map_err(write_error)?;
+    }
+
+    let (mut elements, count) = entries(section(ELEMENT_SECTION_ID))?;
+    for index in 0..count {
+        write!(out, "  (elem (;{index};)").map_err(write_error)?;
+        // Bit 0: passive or declarative, bit 1: explicit table or
+        // declarative, bit 2: expressions instead of function indices
+        let flags = elements.u32()?;
+        if flags > 0x07 {
+            return Err(Error::parse_error("Unknown element segment kind"));
+        }
+        match flags & 0x03 {
+            0x01 => {},
+            0x03 => write!(out, " declare").map_err(write_error)?,
+            _ => {
+                let table = if flags & 0x02 != 0 { elements.u32()? } else { 0 };
+                if table != 0 {
+                    write!(out, " (table {table})").map_err(write_error)?;
+                }
+                write!(out, " (offset ").map_err(write_error)?;
+                write_const_expr(out, &mut elements)?;
+                out.write_char(')').map_err(write_error)?;
+            },
+        }
+        let kind = if flags & 0x03 != 0 { Some(elements.u8()?) } else { None };
+        let items = elements.u32()?;
+        if flags & 0x04 == 0 {
+            // elemkind funcref
+            if kind.is_some_and(|kind| kind != 0x00) {
+                return Err(Error::validation_unsupported_feature(
+                    "Element kind not supported in text",
+                ));
+            }
+            write!(out, " func").map_err(write_error)?;
+            for _ in 0..items {
+                write!(out, " {}", elements.u32()?).map_err(write_error)?;
+            }
+        } else {
+            let ty = match kind.unwrap_or(0x70) {
+                0x70 => "funcref",
+                0x6F => "externref",
+                _ => {
+                    return Err(Error::validation_unsupported_feature(
+                        "Value type not supported in text",
+                    ))
+                },
+            };
+            write!(out, " {ty}").map_err(write_error)?;
+            for _ in 0..items {
+                write!(out, " (item ").map_err(write_error)?;
+                write_const_expr(out, &mut elements)?;
+                out.write_char(')').map_err(write_error)?;
+            }
+        }
+        writeln!(out, ")").map_err(write_error)?;
+    }
+
+    let (mut data, count) = entries(section(DATA_SECTION_ID))?;
+    for index in 0..count {
+        write!(out, "  (data (;{index};)").map_err(write_error)?;
+        match data.u32()? {
+            0x01 => {},
+            flags @ (0x00 | 0x02) => {
+                let memory = if flags == 0x02 { data.u32()? } else { 0 };
+                if memory != 0 {
+                    write!(out, " (memory {memory})").map_err(write_error)?;
+                }
+                write!(out, " (offset ").map_err(write_error)?;
+                write_const_expr(out, &mut data)?;
+                out.write_char(')').map_err(write_error)?;
+            },
+            _ => return Err(Error::parse_error("Unknown data segment kind")),
+        }
+        out.write_char(' ').map_err(write_error)?;
+        write_string(out, data.vec_bytes()?).map_err(write_error)?;
+        writeln!(out, ")").map_err(write_error)?;
+    }
+    writeln!(out, ")").map_err(write_error)
+}
+
+/// Reader over the entries of a vector section, and their number
+fn entries(body: &[u8]) -> Result<(CodeReader<'_>, u32)> {
+    let mut reader = CodeReader { code: body, pos: 0 };
+    let count = if body.is_empty() { 0 } else { reader.u32()? };
+    Ok((reader, count))
+}
+
+/// Parameter and result types of function type `index` in the encoded
+/// entries of a type section
+fn signature_of(types: &[u8], index: u32) -> Result<Option<(&[u8], &[u8])>> {
+    let mut reader = CodeReader { code: types, pos: 0 };
+    let mut current = 0;
+    while !reader.rest().is_empty() {
+        let signature = reader.signature()?;
+        if current == index {
+            return Ok(Some(signature));
+        }
+        current += 1;
+    }
+    Ok(None)
+}
+
+/// Write the constant expression at the reader and move past it
+fn write_const_expr<W: fmt::Write>(out: &mut W, reader: &mut CodeReader<'_>) -> Result<()> {
+    reader.pos += write_inline_instructions(out, reader.rest())?;
+    Ok(())
+}
+
+fn value_type_text(byte: u8) -> Result<&'static str> {
+    value_type_name(byte)
+        .ok_or_else(|| Error::validation_unsupported_feature("Value type not supported in text"))
+}
+
+fn write_signature<W: fmt::Write>(out: &mut W, params: &[u8], results: &[u8]) -> Result<()> {
+    for (keyword, types) in [("param", params), ("result", results)] {
+        if types.is_empty() {
+            continue;
+        }
+        write!(out, " ({keyword}").map_err(write_error)?;
+        for &ty in types {
+            write!(out, " {}", value_type_text(ty)?).map_err(write_error)?;
+        }
+        out.write_char(')').map_err(write_error)?;
+    }
+    Ok(())
+}
+
+/// Write limits whose flags only say whether there is a maximum
+fn write_limits<W: fmt::Write>(
+    out: &mut W,
+    reader: &mut CodeReader<'_>,
+    unsupported: &'static str,
+) -> Result<()> {
+    let flags = reader.u8()?;
+    if flags > 0x01 {
+        return Err(Error::validation_unsupported_feature(unsupported));
+    }
+    write!(out, "{}", reader.u32()?).map_err(write_error)?;
+    if flags == 0x01 {
+        write!(out, " {}", reader.u32()?).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn write_table_type<W: fmt::Write>(out: &mut W, reader: &mut CodeReader<'_>) -> Result<()> {
+    let ty = match reader.u8()? {
+        0x70 => " funcref",
+        0x6F => " externref",
+        _ => return Err(Error::validation_unsupported_feature("Table type not supported in text")),
+    };
+    write_limits(out, reader, "Table type not supported in text")?;
+    out.write_str(ty).map_err(write_error)
+}
+
+fn write_memory_type<W: fmt::Write>(out: &mut W, reader: &mut CodeReader<'_>) -> Result<()> {
+    write_limits(out, reader, "Memory type not supported in text")
+}
+
+fn write_global_type<W: fmt::Write>(out: &mut W, reader: &mut CodeReader<'_>) -> Result<()> {
+    let ty = value_type_text(reader.u8()?)?;
+    match reader.u8()? {
+        0x00 => out.write_str(ty).map_err(write_error),
+        0x01 => write!(out, "(mut {ty})").map_err(write_error),
+        _ => Err(Error::parse_error("Invalid global mutability")),
+    }
+}
+
+/// Parse a module in the text format
+///
+/// The text may be a `(module ...)` form or just the module fields.
+///
+/// # Errors
+///
+/// Returns an error for malformed text, unknown names or instructions, and
+/// features the text format support does not cover
+#[cfg(feature = "std")]
+pub fn parse_module(text: &str) -> Result<Module> {
+    let mut builder = ModuleBuilder::default();
+    parse_fields(text, &mut builder)?;
+    let mut module = builder.module;
+    module.functions = builder.imported_functions;
+    module.functions.append(&mut builder.defined_functions);
+    Ok(module)
+}
+
+/// Assemble a module in the text format and append its binary to `out`
+///
+/// The binary is the one the encoder writes for the module `parse_module`
+/// reads from the text, but nothing is allocated: the text is parsed once
+/// per section into bounded scratch storage. Without `std` that storage
+/// holds up to 128 names per index space, 64 locals, 32 nested blocks,
+/// 4 KiB of code per function and 4 KiB of data per segment, and text
+/// beyond that fails to assemble.
+///
+/// # Errors
+///
+/// As for parsing, or if the text or its binary exceed the capacities. `out`
+/// is left as it was.
+pub fn assemble_module<const N: usize>(text: &str, out: &mut StaticVec<u8, N>) -> Result<()> {
+    let start = out.len();
+    let mut assembler = Assembler {
+        out,
+        section: TYPE_SECTION_ID,
+        count: 0,
+        types: 0..0,
+        explicit_types: 0,
+        data: 0,
+        passive_data: false,
+    };
+    let result = assembler.assemble(text);
+    if result.is_err() {
+        while out.len() > start {
+            out.pop();
+        }
+    }
+    result
+}
+
+/// Number of index spaces names are resolved in
+const SPACES: usize = 7;
+
+/// Names the parser keeps in each index space without `std`
+const MAX_NAMES: usize = 128;
+
+/// Fields of a module the parser keeps track of without `std`
+const MAX_FIELDS: usize = 512;
+
+/// Parameters, results or locals of a function without `std`
+const MAX_LOCALS: usize = 64;
+
+/// Blocks nested in a function without `std`
+const MAX_LABELS: usize = 32;
+
+/// Bytes of code in a function or expression without `std`
+const MAX_CODE_BYTES: usize = 4096;
+
+/// Bytes of an import or export name without `std`
+const MAX_NAME_BYTES: usize = 256;
+
+/// Bytes of a data segment without `std`
+const MAX_DATA_BYTES: usize = 4096;
+
+/// Initializers of an element segment without `std`
+const MAX_ELEMENTS: usize = 256;
+
+/// Scratch storage of the parser
+///
+/// A `Vec` with `std`; without it a [`StaticVec`] of at most `N` items, so
+/// parsing never allocates.
+#[derive(Debug)]
+struct Buf<T, const N: usize> {
+    #[cfg(feature = "std")]
+    items: Vec<T>,
+    #[cfg(not(feature = "std"))]
+    items: StaticVec<T, N>,
+}
+
+impl<T, const N: usize> Default for Buf<T, N> {
+    fn default() -> Self {
+        Self { items: Default::default() }
+    }
+}
+
+impl<T, const N: usize> Buf<T, N> {
+    fn push(&mut self, item: T) -> Result<()> {
+        #[cfg(feature = "std")]
+        self.items.push(item);
+        #[cfg(not(feature = "std"))]
+        self.items.push(item).map_err(|_| capacity_error())?;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+}
+
+impl<T, const N: usize> Deref for Buf<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.items.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for Buf<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.items.as_mut_slice()
+    }
+}
+
+/// Bytes of code or of a segment
+type Code = Buf<u8, MAX_CODE_BYTES>;
+
+/// Value types of parameters, results or locals
+type Types = Buf<ValueType, MAX_LOCALS>;
+
+/// Names of parameters
+type ParamNames<'a> = Buf<Option<&'a str>, MAX_LOCALS>;
+
+/// Names in one index space, or of locals
+///
+/// A `BTreeMap` with `std`; without it a [`StaticMap`] of at most `N`
+/// names.
+#[derive(Debug, Default)]
+struct NameMap<'a, const N: usize> {
+    #[cfg(feature = "std")]
+    names: BTreeMap<&'a str, u32>,
+    #[cfg(not(feature = "std"))]
+    names: StaticMap<&'a str, u32, N>,
+}
+
+impl<'a, const N: usize> NameMap<'a, N> {
+    /// Give `name` to `index`, returning whether the name was new
+    fn insert(&mut self, name: &'a str, index: u32) -> Result<bool> {
+        #[cfg(feature = "std")]
+        let previous = self.names.insert(name, index);
+        #[cfg(not(feature = "std"))]
+        let previous = self.names.insert(name, index).map_err(|_| capacity_error())?;
+        Ok(previous.is_none())
+    }
+
+    fn get(&self, name: &'a str) -> Option<u32> {
+        #[cfg(feature = "std")]
+        let index = self.names.get(name);
+        #[cfg(not(feature = "std"))]
+        let index = self.names.get(&name);
+        index.copied()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn capacity_error() -> Error {
+    Error::foundation_bounded_capacity_exceeded("Text exceeds the parser's bounded storage")
+}
+
+/// Byte buffer encodings are appended to
+trait ByteOut {
+    fn extend_bytes(&mut self, bytes: &[u8]) -> Result<()>;
+
+    fn push_u32(&mut self, value: u32) -> Result<()> {
+        let (bytes, len) = leb128(i64::from(value), false);
+        self.extend_bytes(&bytes[..len])
+    }
+
+    fn push_i32(&mut self, value: i32) -> Result<()> {
+        self.push_i64(i64::from(value))
+    }
+
+    fn push_i64(&mut self, value: i64) -> Result<()> {
+        let (bytes, len) = leb128(value, true);
+        self.extend_bytes(&bytes[..len])
+    }
+
+    /// Length-prefixed bytes, such as a name
+    fn push_vec(&mut self, bytes: &[u8]) -> Result<()> {
+        self.push_u32(bytes.len() as u32)?;
+        self.extend_bytes(bytes)
+    }
+}
+
+impl<const N: usize> ByteOut for Buf<u8, N> {
+    fn extend_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        bytes.iter().try_for_each(|&byte| self.push(byte))
+    }
+}
+
+impl<const N: usize> ByteOut for StaticVec<u8, N> {
+    fn extend_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        bytes.iter().try_for_each(|&byte| self.push(byte))
+    }
+}
+
+/// LEB128 encoding of `value`, signed or unsigned, and its length
+fn leb128(mut value: i64, signed: bool) -> ([u8; 10], usize) {
+    let mut bytes = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        let done = if signed {
+            (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0)
+        } else {
+            value == 0
+        };
+        bytes[len] = if done { byte } else { byte | 0x80 };
+        len += 1;
+        if done {
+            return (bytes, len);
+        }
+    }
+}
+
+/// Offset expression `i32.const 0`
+const ZERO_OFFSET: [u8; 3] = [0x41, 0x00, END];
+
+/// Placement of an element or data segment
+#[derive(Debug, Clone, Copy)]
+enum Segment<'b> {
+    Active { index: u32, offset: &'b [u8] },
+    Passive,
+    Declared,
+}
+
+/// Initializers of an element segment, encoded one after another
+#[derive(Debug, Default)]
+struct Elements {
+    /// Function indices as LEB128, or constant expressions
+    bytes:       Code,
+    /// End of each initializer in `bytes`
+    ends:        Buf<usize, MAX_ELEMENTS>,
+    expressions: bool,
+}
+
+impl Elements {
+    #[cfg(feature = "std")]
+    fn items(&self) -> impl Iterator<Item = &[u8]> {
+        let starts = core::iter::once(0).chain(self.ends.iter().copied());
+        starts.zip(self.ends.iter()).map(|(start, &end)| &self.bytes[start..end])
+    }
+}
+
+/// What an import brings in
+#[derive(Debug)]
+enum Desc {
+    Function(u32),
+    Table(Table),
+    Memory(Memory),
+    Global(FormatGlobalType),
+}
+
+/// Receiver of the definitions the parser finds, in text order
+///
+/// Names are valid UTF-8.
+trait ModuleSink {
+    /// Index of a function type, added unless `reuse` finds an equal one
+    fn func_type(
+        &mut self,
+        params: &[ValueType],
+        results: &[ValueType],
+        reuse: bool,
+    ) -> Result<u32>;
+    /// Number of parameters of function type `index`, if there is one
+    fn type_params(&self, index: u32) -> Result<Option<usize>>;
+    fn import(&mut self, module: &[u8], name: &[u8], desc: Desc) -> Result<()>;
+    fn function(&mut self, type_idx: u32, locals: &[ValueType], code: &[u8]) -> Result<()>;
+    fn table(&mut self, table: Table) -> Result<()>;
+    fn memory(&mut self, memory: Memory) -> Result<()>;
+    fn global(&mut self, global_type: FormatGlobalType, init: &[u8]) -> Result<()>;
+    fn export(&mut self, name: &[u8], kind: ExportKind, index: u32) -> Result<()>;
+    fn start(&mut self, index: u32) -> Result<()>;
+    fn element(
+        &mut self,
+        segment: Segment<'_>,
+        element_type: RefType,
+        elements: &Elements,
+    ) -> Result<()>;
+    fn data(&mut self, segment: Segment<'_>, data: &[u8]) -> Result<()>;
+}
+
+/// Sink building a [`Module`]
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct ModuleBuilder {
+    module:             Module,
+    imported_functions: Vec<Function>,
+    defined_functions:  Vec<Function>,
+}
+
+#[cfg(feature = "std")]
+fn name_string(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).into_owned()
+}
+
+#[cfg(feature = "std")]
+impl ModuleSink for ModuleBuilder {
+    fn func_type(
+        &mut self,
+        params: &[ValueType],
+        results: &[ValueType],
+        reuse: bool,
+    ) -> Result<u32> {
+        let ty = CleanCoreFuncType { params: params.to_vec(), results: results.to_vec() };
+        let types = &mut self.module.types;
+        match types.iter().position(|existing| *existing == ty).filter(|_| reuse) {
+            Some(index) => Ok(index as u32),
+            None => {
+                types.push(ty);
+                Ok(types.len() as u32 - 1)
+            },
+        }
+    }
+
+    fn type_params(&self, index: u32) -> Result<Option<usize>> {
+        Ok(self.module.types.get(index as usize).map(|ty| ty.params.len()))
+    }
+
+    fn import(&mut self, module: &[u8], name: &[u8], desc: Desc) -> Result<()> {
+        let desc = match desc {
+            Desc::Function(type_idx) => {
+                self.imported_functions.push(Function {
+                    type_idx,
+                    locals: Vec::new(),
+                    code: Vec::new(),
+                });
+                ImportDesc::Function(type_idx)
+            },
+            Desc::Table(table) => ImportDesc::Table(table),
+            Desc::Memory(memory) => ImportDesc::Memory(memory),
+            Desc::Global(global) => ImportDesc::Global(global),
+        };
+        self.module.imports.push(Import {
+            module: name_string(module),
+            name: name_string(name),
+            desc,
+        });
+        Ok(())
+    }
+
+    fn function(&mut self, type_idx: u32, locals: &[ValueType], code: &[u8]) -> Result<()> {
+        self.defined_functions.push(Function {
+            type_idx,
+            locals: locals.to_vec(),
+            code: code.to_vec(),
+        });
+        Ok(())
+    }
+
+    fn table(&mut self, table: Table) -> Result<()> {
+        self.module.tables.push(table);
+        Ok(())
+    }
+
+    fn memory(&mut self, memory: Memory) -> Result<()> {
+        self.module.memories.push(memory);
+        Ok(())
+    }
+
+    fn global(&mut self, global_type: FormatGlobalType, init: &[u8]) -> Result<()> {
+        self.module.globals.push(Global { global_type, init: init.to_vec() });
+        Ok(())
+    }
+
+    fn export(&mut self, name: &[u8], kind: ExportKind, index: u32) -> Result<()> {
+        self.module.exports.push(Export { name: name_string(name), kind, index });
+        Ok(())
+    }
+
+    fn start(&mut self, index: u32) -> Result<()> {
+        self.module.start = Some(index);
+        Ok(())
+    }
+
+    fn element(
+        &mut self,
+        segment: Segment<'_>,
+        element_type: RefType,
+        elements: &Elements,
+    ) -> Result<()> {
+        let (mode, offset) = match segment {
+            Segment::Active { index, offset } => (
+                PureElementMode::Active {
+                    table_index:     index,
+                    offset_expr_len: offset.len() as u32,
+                },
+                offset.to_vec(),
+            ),
+            Segment::Passive => (PureElementMode::Passive, Vec::new()),
+            Segment::Declared => (PureElementMode::Declared, Vec::new()),
+        };
+        let init_data = if elements.expressions {
+            PureElementInit::ExpressionBytes(elements.items().map(<[u8]>::to_vec).collect())
+        } else {
+            let indices = elements.items().map(|item| Ok(read_leb128_u32(item, 0)?.0));
+            PureElementInit::FunctionIndices(indices.collect::<Result<_>>()?)
+        };
+        self.module.elements.push(PureElementSegment {
+            mode,
+            element_type,
+            offset_expr_bytes: offset,
+            init_data,
+        });
+        Ok(())
+    }
+
+    fn data(&mut self, segment: Segment<'_>, data: &[u8]) -> Result<()> {
+        let (mode, offset) = match segment {
+            Segment::Active { index, offset } => (
+                PureDataMode::Active {
+                    memory_index:    index,
+                    offset_expr_len: offset.len() as u32,
+                },
+                offset.to_vec(),
+            ),
+            Segment::Passive | Segment::Declared => (PureDataMode::Passive, Vec::new()),
+        };
+        self.module.data.push(PureDataSegment {
+            mode,
+            offset_expr_bytes: offset,
+            data_bytes: data.to_vec(),
+        });
+        Ok(())
+    }
+}
+
+/// Sink writing one section of the binary per pass over the text
+struct Assembler<'o, const N: usize> {
+    out:            &'o mut StaticVec<u8, N>,
+    /// Id of the section this pass writes
+    section:        u8,
+    /// Entries written to the section
+    count:          u32,
+    /// Encoded entries of the type section in `out`
+    types:          Range<usize>,
+    /// Explicit types requested in this pass
+    explicit_types: u32,
+    /// Data segments of the module
+    data:           u32,
+    passive_data:   bool,
+}
+
+impl<const N: usize> Assembler<'_, N> {
+    fn assemble(&mut self, text: &str) -> Result<()> {
+        self.out.extend_bytes(&WASM_MAGIC)?;
+        self.out.extend_bytes(&WASM_VERSION)?;
+        for id in [
+            TYPE_SECTION_ID,
+            IMPORT_SECTION_ID,
+            FUNCTION_SECTION_ID,
+            TABLE_SECTION_ID,
+            MEMORY_SECTION_ID,
+            GLOBAL_SECTION_ID,
+            EXPORT_SECTION_ID,
+            START_SECTION_ID,
+            ELEMENT_SECTION_ID,
+            DATA_COUNT_SECTION_ID,
+            CODE_SECTION_ID,
+            DATA_SECTION_ID,
+        ] {
+            let start = self.out.len();
+            self.section = id;
+            self.count = 0;
+            self.explicit_types = 0;
+            if id == TYPE_SECTION_ID {
+                self.types = start..start;
+            }
+            if id != DATA_COUNT_SECTION_ID {
+                parse_fields(text, self)?;
+            } else if self.passive_data {
+                // Only `memory.init` and `data.drop` need the data count,
+                // and they are only useful with passive segments
+                self.out.push_u32(self.data)?;
+                self.count = 1;
+            }
+            if self.count == 0 {
+                continue;
+            }
+            // The start and data count sections hold no vector
+            if id != START_SECTION_ID && id != DATA_COUNT_SECTION_ID {
+                insert_leb(self.out, start, self.count)?;
+            }
+            insert_leb(self.out, start, (self.out.len() - start) as u32)?;
+            self.out.push(id)?;
+            self.out.as_mut_slice()[start..].rotate_right(1);
+            if id == TYPE_SECTION_ID {
+                let len = self.types.len();
+                self.types = self.out.len() - len..self.out.len();
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this pass writes section `id`, counting the entry if so
+    fn entry(&mut self, id: u8) -> bool {
+        let writes = self.section == id;
+        if writes {
+            self.count += 1;
+        }
+        writes
+    }
+
+    fn write_limits(&mut self, flags: u8, limits: Limits) -> Result<()> {
+        self.out.push(flags | u8::from(limits.max.is_some()))?;
+        self.out.push_u32(limits.min)?;
+        if let Some(max) = limits.max {
+            self.out.push_u32(max)?;
+        }
+        Ok(())
+    }
+
+    fn write_table_type(&mut self, table: Table) -> Result<()> {
+        self.out.push(table.element_type.to_value_type().to_binary())?;
+        self.write_limits(0x00, table.limits)
+    }
+
+    fn write_memory_type(&mut self, memory: Memory) -> Result<()> {
+        let flags = if memory.shared { 0x02 } else { 0 } | if memory.memory64 { 0x04 } else { 0 };
+        self.write_limits(flags, memory.limits)
+    }
+
+    fn write_global_type(&mut self, global: FormatGlobalType) -> Result<()> {
+        self.out.push(global.value_type.to_binary())?;
+        self.out.push(u8::from(global.mutable))
+    }
+}
+
+/// Insert the LEB128 encoding of `value` at `at`
+fn insert_leb<const N: usize>(out: &mut StaticVec<u8, N>, at: usize, value: u32) -> Result<()> {
+    let (bytes, len) = leb128(i64::from(value), false);
+    out.extend_bytes(&bytes[..len])?;
+    out.as_mut_slice()[at..].rotate_right(len);
+    Ok(())
+}
+
+/// Index of the function type with these types in the encoded entries of
+/// a type section
+fn find_signature(
+    types: &[u8],
+    params: &[ValueType],
+    results: &[ValueType],
+) -> Result<Option<u32>> {
+    let encoded = |types: &[ValueType], bytes: &[u8]| {
+        types.iter().map(|ty| ty.to_binary()).eq(bytes.iter().copied())
+    };
+    let mut reader = CodeReader { code: types, pos: 0 };
+    let mut index = 0;
+    while !reader.rest().is_empty() {
+        let (entry_params, entry_results) = reader.signature()?;
+        if encoded(params, entry_params) && encoded(results, entry_results) {
+            return Ok(Some(index));
+        }
+        index += 1;
+    }
+    Ok(None)
+}
+
+impl<const N: usize> ModuleSink for Assembler<'_, N> {
+    fn func_type(
+        &mut self,
+        params: &[ValueType],
+        results: &[ValueType],
+        reuse: bool,
+    ) -> Result<u32> {
+        // Every type was written in the first pass, in the order of the
+        // requests of each pass
+        if self.section != TYPE_SECTION_ID && !reuse {
+            self.explicit_types += 1;
+            return Ok(self.explicit_types - 1);
+        }
+        if reuse {
+            if let Some(index) = find_signature(&self.out[self.types.clone()], params, results)? {
+                return Ok(index);
+            }
+        }
+        if self.section != TYPE_SECTION_ID {
+            return Err(Error::validation_error("Function type missing from the type section"));
+        }
+        self.out.push(0x60)?;
+        for types in [params, results] {
+            self.out.push_u32(types.len() as u32)?;
+            for ty in types {
+                self.out.push(ty.to_binary())?;
+            }
+        }
+        self.types.end = self.out.len();
+        self.count += 1;
+        Ok(self.count - 1)
+    }
+
+    fn type_params(&self, index: u32) -> Result<Option<usize>> {
+        let signature = signature_of(&self.out[self.types.clone()], index)?;
+        Ok(signature.map(|(params, _)| params.len()))
+    }
+
+    fn import(&mut self, module: &[u8], name: &[u8], desc: Desc) -> Result<()> {
+        if !self.entry(IMPORT_SECTION_ID) {
+            return Ok(());
+        }
+        self.out.push_vec(module)?;
+        self.out.push_vec(name)?;
+        match desc {
+            Desc::Function(type_idx) => {
+                self.out.push(0x00)?;
+                self.out.push_u32(type_idx)
+            },
+            Desc::Table(table) => {
+                self.out.push(0x01)?;
+                self.write_table_type(table)
+            },
+            Desc::Memory(memory) => {
+                self.out.push(0x02)?;
+                self.write_memory_type(memory)
+            },
+            Desc::Global(global) => {
+                self.out.push(0x03)?;
+                self.write_global_type(global)
+            },
+        }
+    }
+
+    fn function(&mut self, type_idx: u32, locals: &[ValueType], code: &[u8]) -> Result<()> {
+        if self.entry(FUNCTION_SECTION_ID) {
+            return self.out.push_u32(type_idx);
+        }
+        if !self.entry(CODE_SECTION_ID) {
+            return Ok(());
+        }
+        let start = self.out.len();
+        let groups = locals.chunk_by(|a, b| a == b);
+        self.out.push_u32(groups.clone().count() as u32)?;
+        for group in groups {
+            self.out.push_u32(group.len() as u32)?;
+            self.out.push(group[0].to_binary())?;
+        }
+        self.out.extend_bytes(code)?;
+        if code.is_empty() {
+            self.out.push(END)?;
+        }
+        insert_leb(self.out, start, (self.out.len() - start) as u32)
+    }
+
+    fn table(&mut self, table: Table) -> Result<()> {
+        if !self.entry(TABLE_SECTION_ID) {
+            return Ok(());
+        }
+        self.write_table_type(table)
+    }
+
+    fn memory(&mut self, memory: Memory) -> Result<()> {
+        if !self.entry(MEMORY_SECTION_ID) {
+            return Ok(());
+        }
+        self.write_memory_type(memory)
+    }
+
+    fn global(&mut self, global_type: FormatGlobalType, init: &[u8]) -> Result<()> {
+        if !self.entry(GLOBAL_SECTION_ID) {
+            return Ok(());
+        }
+        self.write_global_type(global_type)?;
+        self.out.extend_bytes(init)
+    }
+
+    fn export(&mut self, name: &[u8], kind: ExportKind, index: u32) -> Result<()> {
+        if !self.entry(EXPORT_SECTION_ID) {
+            return Ok(());
+        }
+        self.out.push_vec(name)?;
+        self.out.push(match kind {
+            ExportKind::Function => 0x00,
+            ExportKind::Table => 0x01,
+            ExportKind::Memory => 0x02,
+            ExportKind::Global => 0x03,
+            ExportKind::Tag => 0x04,
+        })?;
+        self.out.push_u32(index)
+    }
+
+    fn start(&mut self, index: u32) -> Result<()> {
+        if !self.entry(START_SECTION_ID) {
+            return Ok(());
+        }
+        self.out.push_u32(index)
+    }
+
+    fn element(
+        &mut self,
+        segment: Segment<'_>,
+        element_type: RefType,
+        elements: &Elements,
+    ) -> Result<()> {
+        if !self.entry(ELEMENT_SECTION_ID) {
+            return Ok(());
+        }
+        let funcref = element_type == RefType::Funcref;
+        // Bit 0: passive or declarative, bit 1: explicit table or
+        // declarative, bit 2: expressions instead of function indices
+        let (mut flags, table, offset) = match segment {
+            Segment::Active { index: 0, offset } if funcref => (0x00, None, offset),
+            Segment::Active { index, offset } => (0x02, Some(index), offset),
+            Segment::Passive => (0x01, None, &[][..]),
+            Segment::Declared => (0x03, None, &[][..]),
+        };
+        if elements.expressions {
+            flags |= 0x04;
+        }
+        self.out.push(flags)?;
+        if let Some(table) = table {
+            self.out.push_u32(table)?;
+        }
+        self.out.extend_bytes(offset)?;
+        if flags & 0x03 != 0 {
+            if elements.expressions {
+                self.out.push(element_type.to_value_type().to_binary())?;
+            } else {
+                // elemkind funcref
+                self.out.push(0x00)?;
+            }
+        }
+        self.out.push_u32(elements.ends.len() as u32)?;
+        self.out.extend_bytes(&elements.bytes)
+    }
+
+    fn data(&mut self, segment: Segment<'_>, data: &[u8]) -> Result<()> {
+        if self.section == TYPE_SECTION_ID {
+            self.data += 1;
+            self.passive_data |= !matches!(segment, Segment::Active { .. });
+        }
+        if !self.entry(DATA_SECTION_ID) {
+            return Ok(());
+        }
+        match segment {
+            Segment::Active { index: 0, offset } => {
+                self.out.push(0x00)?;
+                self.out.extend_bytes(offset)?;
+            },
+            Segment::Active { index, offset } => {
+                self.out.push(0x02)?;
+                self.out.push_u32(index)?;
+                self.out.extend_bytes(offset)?;
+            },
+            Segment::Passive | Segment::Declared => self.out.push(0x01)?,
+        }
+        self.out.push_vec(data)
+    }
+}
+
+/// Parse the module in `text`, handing its definitions to `sink`
+fn parse_fields<S: ModuleSink>(text: &str, sink: &mut S) -> Result<()> {
+    let mut parser =
+        ModuleParser { text, pos: 0, names: Names::default(), sink, defined: [0; SPACES] };
+    let (start, end) = parser.module_bounds()?;
+    let fields = parser.fields(start, end)?;
+    parser.collect_names(&fields)?;
+
+    // Explicit types keep their indices; types used inline are appended
+    for &field in fields.iter() {
+        parser.pos = field;
+        if parser.peek_group() == Some("type") {
+            parser.type_field()?;
+        }
+    }
+    for &field in fields.iter() {
+        parser.pos = field;
+        parser.field()?;
+    }
+    Ok(())
+}
+
+/// Index spaces of a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Space {
+    Type,
+    Func,
+    Table,
+    Memory,
+    Global,
+    Elem,
+    Data,
+}
+
+impl Space {
+    fn of_keyword(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "func" => Self::Func,
+            "table" => Self::Table,
+            "memory" => Self::Memory,
+            "global" => Self::Global,
+            _ => return None,
+        })
+    }
+}
+
+/// Names declared in each index space
+#[derive(Debug, Default)]
+struct Names<'a> {
+    spaces:  [NameMap<'a, MAX_NAMES>; SPACES],
+    /// Number of imports in each space
+    imports: [u32; SPACES],
+}
+
+/// What a module field declares
+#[derive(Debug, Clone, Copy)]
+struct Declaration<'a> {
+    space:  Space,
+    name:   Option<&'a str>,
+    import: bool,
+    /// Space of an unnamed segment the field declares inline
+    inline: Option<Space>,
+}
+
+/// Locals and labels visible to the instructions being parsed
+#[derive(Debug, Default)]
+struct Scope<'a> {
+    locals: NameMap<'a, MAX_LOCALS>,
+    /// Labels of the enclosing blocks, innermost last
+    labels: Buf<Option<&'a str>, MAX_LABELS>,
+}
+
+/// Parser of the fields of a module, lexing the text again wherever the
+/// cursor moves so that it keeps no tokens
+struct ModuleParser<'a, 's, S> {
+    text:    &'a str,
+    /// Byte offset of the cursor in `text`
+    pos:     usize,
+    names:   Names<'a>,
+    sink:    &'s mut S,
+    /// Definitions parsed so far in each space
+    defined: [u32; SPACES],
+}
+
+impl<'a, S: ModuleSink> ModuleParser<'a, '_, S> {
+    fn lexer(&self) -> Lexer<'a> {
+        Lexer { text: self.text, pos: self.pos, start: self.pos }
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.lexer().next_token().ok().flatten()
+    }
+
+    /// Token after the one at the cursor
+    fn peek_second(&self) -> Option<Token<'a>> {
+        let mut lexer = self.lexer();
+        lexer.next_token().ok()?;
+        lexer.next_token().ok().flatten()
+    }
+
+    /// Offset of the token at the cursor, or of the end of the text
+    fn offset(&self) -> usize {
+        let mut lexer = self.lexer();
+        let _ = lexer.next_token();
+        lexer.offset()
+    }
+
+    fn next(&mut self) -> Result<Token<'a>> {
+        let mut lexer = self.lexer();
+        let token = lexer
+            .next_token()?
+            .ok_or_else(|| Error::parse_error("Unexpected end of text"))?;
+        self.pos = lexer.pos;
+        Ok(token)
+    }
+
+    /// Move past the token at the cursor
+    fn advance(&mut self) {
+        let _ = self.next();
+    }
+
+    fn lparen(&mut self) -> Result<()> {
+        match self.next()? {
+            Token::LParen => Ok(()),
+            _ => Err(Error::parse_error("Expected `(`")),
+        }
+    }
+
+    fn rparen(&mut self) -> Result<()> {
+        match self.next()? {
+            Token::RParen => Ok(()),
+            _ => Err(Error::parse_error("Expected `)`")),
+        }
+    }
+
+    fn keyword(&mut self) -> Result<&'a str> {
+        match self.next()? {
+            Token::Keyword(keyword) => Ok(keyword),
+            _ => Err(Error::parse_error("Expected a keyword")),
+        }
+    }
+
+    fn peek_keyword(&self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Keyword(keyword)) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword() == Some(keyword);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    /// Keyword of the parenthesized group at the cursor
+    fn peek_group(&self) -> Option<&'a str> {
+        match (self.peek(), self.peek_second()) {
+            (Some(Token::LParen), Some(Token::Keyword(keyword))) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// Enter the group at the cursor if its keyword is `keyword`
+    fn eat_group(&mut self, keyword: &str) -> bool {
+        let found = self.peek_group() == Some(keyword);
+        if found {
+            self.advance();
+            self.advance();
+        }
+        found
+    }
+
+    fn id(&mut self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                self.advance();
+                Some(id)
+            },
+            _ => None,
+        }
+    }
+
+    /// Append the bytes of the string at the cursor to `out`
+    fn string<const N: usize>(&mut self, out: &mut Buf<u8, N>) -> Result<()> {
+        match self.next()? {
+            Token::String(raw) => unescape(raw, |byte| out.push(byte)),
+            _ => Err(Error::parse_error("Expected a string")),
+        }
+    }
+
+    fn name(&mut self) -> Result<Buf<u8, MAX_NAME_BYTES>> {
+        let mut name = Buf::default();
+        self.string(&mut name)?;
+        core::str::from_utf8(&name).map_err(|_| Error::parse_error("Name is not UTF-8"))?;
+        Ok(name)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        match self.next()? {
+            Token::Integer(text) => {
+                parse_u32(text).ok_or_else(|| Error::parse_error("Invalid unsigned integer"))
+            },
+            _ => Err(Error::parse_error("Expected an integer")),
+        }
+    }
+
+    /// Skip the group at the cursor
+    fn skip_group(&mut self) -> Result<()> {
+        self.lparen()?;
+        let mut depth = 1usize;
+        while depth > 0 {
+            match self.next()? {
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the cursor is at an index
+    fn at_index(&self) -> bool {
+        matches!(self.peek(), Some(Token::Integer(_) | Token::Id(_)))
+    }
+
+    fn index(&mut self, space: Space) -> Result<u32> {
+        match self.next()? {
+            Token::Integer(text) => {
+                parse_u32(text).ok_or_else(|| Error::parse_error("Invalid index"))
+            },
+            Token::Id(id) => self.names.spaces[space as usize]
+                .get(id)
+                .ok_or_else(|| Error::parse_error("Unknown name")),
+            _ => Err(Error::parse_error("Expected an index")),
+        }
+    }
+
+    /// Byte range of the module fields
+    fn module_bounds(&mut self) -> Result<(usize, usize)> {
+        // Lex the whole text first, so lexical errors surface wherever
+        // they are
+        let mut lexer = Lexer::new(self.text);
+        let mut last = None;
+        while let Some(token) = lexer.next_token()? {
+            last = Some((lexer.offset(), token));
+        }
+        if self.peek_group() != Some("module") {
+            return Ok((0, self.text.len()));
+        }
+        self.advance();
+        self.advance();
+        self.id();
+        match last {
+            Some((end, Token::RParen)) => Ok((self.pos, end)),
+            _ => Err(Error::parse_error("Expected `)` after module")),
+        }
+    }
+
+    /// Start positions of the fields between `start` and `end`
+    fn fields(&mut self, start: usize, end: usize) -> Result<Buf<usize, MAX_FIELDS>> {
+        let mut fields = Buf::default();
+        self.pos = start;
+        while self.offset() < end {
+            fields.push(self.pos)?;
+            self.skip_group()?;
+        }
+        if self.offset() != end {
+            return Err(Error::parse_error("Unbalanced parentheses"));
+        }
+        Ok(fields)
+    }
+
+    /// Assign indices to the names of all definitions
+    fn collect_names(&mut self, fields: &[usize]) -> Result<()> {
+        // Imports come first in every index space
+        for &field in fields {
+            let Some(declaration) = self.declaration(field)? else {
+                continue;
+            };
+            if declaration.import {
+                let index = self.names.imports[declaration.space as usize];
+                self.names.imports[declaration.space as usize] += 1;
+                self.declare(declaration.space, declaration.name, index)?;
+            }
+        }
+        let mut next = self.names.imports;
+        for &field in fields {
+            let Some(declaration) = self.declaration(field)? else {
+                continue;
+            };
+            if !declaration.import {
+                let index = next[declaration.space as usize];
+                next[declaration.space as usize] += 1;
+                self.declare(declaration.space, declaration.name, index)?;
+                if let Some(inline) = declaration.inline {
+                    next[inline as usize] += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn declare(&mut self, space: Space, name: Option<&'a str>, index: u32) -> Result<()> {
+        match name {
+            Some(name) if !self.names.spaces[space as usize].insert(name, index)? => {
+                Err(Error::parse_error("Duplicate name"))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// What the field at `field` declares, if anything
+    fn declaration(&mut self, field: usize) -> Result<Option<Declaration<'a>>> {
+        self.pos = field;
+        self.advance();
+        let keyword = self.keyword()?;
+        let space = match keyword {
+            "type" => Space::Type,
+            "elem" => Space::Elem,
+            "data" => Space::Data,
+            "import" => {
+                self.name()?;
+                self.name()?;
+                self.lparen()?;
+                let space = Space::of_keyword(self.keyword()?)
+                    .ok_or_else(|| Error::parse_error("Unknown import kind"))?;
+                let name = self.id();
+                return Ok(Some(Declaration { space, name, import: true, inline: None }));
+            },
+            _ => match Space::of_keyword(keyword) {
+                Some(space) => space,
+                None => return Ok(None),
+            },
+        };
+        let name = self.id();
+        let mut declaration = Declaration { space, name, import: false, inline: None };
+        if matches!(space, Space::Type | Space::Elem | Space::Data) {
+            return Ok(Some(declaration));
+        }
+        while self.peek_group() == Some("export") {
+            self.skip_group()?;
+        }
+        declaration.import = self.peek_group() == Some("import");
+        if declaration.import {
+            return Ok(Some(declaration));
+        }
+        // Inline segments of tables and memories
+        if space == Space::Table {
+            if !self.eat_keyword("funcref") {
+                self.eat_keyword("externref");
+            }
+            if self.peek_group() == Some("elem") {
+                declaration.inline = Some(Space::Elem);
+            }
+        } else if space == Space::Memory && self.peek_group() == Some("data") {
+            declaration.inline = Some(Space::Data);
+        }
+        Ok(Some(declaration))
+    }
+
+    /// Index the next definition in `space` gets
+    fn next_definition(&mut self, space: Space) -> u32 {
+        let index = self.names.imports[space as usize] + self.defined[space as usize];
+        self.defined[space as usize] += 1;
+        index
+    }
+
+    fn type_field(&mut self) -> Result<()> {
+        self.advance();
+        self.advance();
+        self.id();
+        self.lparen()?;
+        if self.keyword()? != "func" {
+            return Err(Error::validation_unsupported_feature("Only function types are supported"));
+        }
+        self.id();
+        let (params, _) = self.params()?;
+        let results = self.results()?;
+        self.rparen()?;
+        self.rparen()?;
+        self.sink.func_type(&params, &results, false)?;
+        Ok(())
+    }
+
+    fn field(&mut self) -> Result<()> {
+        self.lparen()?;
+        match self.keyword()? {
+            "type" => return self.skip_rest(),
+            "import" => {
+                let module = self.name()?;
+                let name = self.name()?;
+                self.lparen()?;
+                let keyword = self.keyword()?;
+                self.id();
+                let desc = self.import_desc(keyword)?;
+                self.rparen()?;
+                self.sink.import(&module, &name, desc)?;
+            },
+            "func" => self.func_field()?,
+            "table" => self.table_field()?,
+            "memory" => self.memory_field()?,
+            "global" => self.global_field()?,
+            "export" => {
+                let name = self.name()?;
+                self.lparen()?;
+                let keyword = self.keyword()?;
+                let space = Space::of_keyword(keyword)
+                    .ok_or_else(|| Error::parse_error("Unknown export kind"))?;
+                let index = self.index(space)?;
+                self.rparen()?;
+                self.export(&name, space, index)?;
+            },
+            "start" => {
+                let index = self.index(Space::Func)?;
+                self.sink.start(index)?;
+            },
+            "elem" => self.elem_field()?,
+            "data" => self.data_field()?,
+            _ => return Err(Error::parse_error("Unknown module field")),
+        }
+        self.rparen()
+    }
+
+    /// Skip to the end of the field the cursor is in
+    fn skip_rest(&mut self) -> Result<()> {
+        while self.peek() != Some(Token::RParen) {
+            match self.peek() {
+                Some(Token::LParen) => self.skip_group()?,
+                _ => {
+                    self.next()?;
+                },
+            }
+        }
+        self.rparen()
+    }
+
+    fn export(&mut self, name: &[u8], space: Space, index: u32) -> Result<()> {
+        let kind = match space {
+            Space::Table => ExportKind::Table,
+            Space::Memory => ExportKind::Memory,
+            Space::Global => ExportKind::Global,
+            _ => ExportKind::Function,
+        };
+        self.sink.export(name, kind, index)
+    }
+
+    /// Parse inline exports of a definition about to get `index`
+    fn inline_exports(&mut self, space: Space, index: u32) -> Result<()> {
+        while self.eat_group("export") {
+            let name = self.name()?;
+            self.rparen()?;
+            self.export(&name, space, index)?;
+        }
+        Ok(())
+    }
+
+    /// Parse an inline import, returning whether there was one
+    fn inline_import(&mut self, keyword: &str) -> Result<bool> {
+        if !self.eat_group("import") {
+            return Ok(false);
+        }
+        let module = self.name()?;
+        let name = self.name()?;
+        self.rparen()?;
+        let desc = self.import_desc(keyword)?;
+        self.sink.import(&module, &name, desc)?;
+        Ok(true)
+    }
+
+    fn import_desc(&mut self, keyword: &str) -> Result<Desc> {
+        Ok(match keyword {
+            "func" => Desc::Function(self.type_use()?.0),
+            "table" => Desc::Table(self.table_type()?),
+            "memory" => Desc::Memory(self.memory_type()?),
+            "global" => Desc::Global(self.global_type()?),
+            _ => return Err(Error::parse_error("Unknown import kind")),
+        })
+    }
+
+    fn func_field(&mut self) -> Result<()> {
+        self.id();
+        let index = if self.peek_import_after_exports() {
+            self.names.imports[Space::Func as usize]
+        } else {
+            self.next_definition(Space::Func)
+        };
+        self.inline_exports(Space::Func, index)?;
+        if self.inline_import("func")? {
+            return Ok(());
+        }
+        let (type_idx, param_names) = self.type_use()?;
+        let mut scope = Scope::default();
+        let mut locals = Types::default();
+        for (local, name) in param_names.iter().enumerate() {
+            if let Some(name) = name {
+                scope.locals.insert(name, local as u32)?;
+            }
+        }
+        let params = param_names.len() as u32;
+        while self.eat_group("local") {
+            if let Some(name) = self.id() {
+                scope.locals.insert(name, params + locals.len() as u32)?;
+                locals.push(self.value_type()?)?;
+            } else {
+                while self.peek() != Some(Token::RParen) {
+                    locals.push(self.value_type()?)?;
+                }
+            }
+            self.rparen()?;
+        }
+        let mut code = Code::default();
+        self.instructions(&mut scope, &mut code)?;
+        code.push(END)?;
+        self.sink.function(type_idx, &locals, &code)
+    }
+
+    /// Whether an inline import follows the inline exports at the cursor,
+    /// which makes the definition an import
+    fn peek_import_after_exports(&self) -> bool {
+        let mut lexer = self.lexer();
+        loop {
+            match (lexer.next_token(), lexer.next_token()) {
+                (Ok(Some(Token::LParen)), Ok(Some(Token::Keyword("export")))) => {
+                    let mut depth = 1usize;
+                    while depth > 0 {
+                        match lexer.next_token() {
+                            Ok(Some(Token::LParen)) => depth += 1,
+                            Ok(Some(Token::RParen)) => depth -= 1,
+                            Ok(Some(_)) => {},
+                            _ => return false,
+                        }
+                    }
+                },
+                (Ok(Some(Token::LParen)), Ok(Some(Token::Keyword("import")))) => return true,
+                _ => return false,
+            }
+        }
+    }
+
+    fn table_field(&mut self) -> Result<()> {
+        self.id();
+        if self.peek_import_after_exports() {
+            let index = self.names.imports[Space::Table as usize];
+            self.inline_exports(Space::Table, index)?;
+            self.inline_import("table")?;
+            return Ok(());
+        }
+        let index = self.next_definition(Space::Table);
+        self.inline_exports(Space::Table, index)?;
+        let element_type = match self.peek_keyword() {
+            Some("funcref") => Some(RefType::Funcref),
+            Some("externref") => Some(RefType::Externref),
+            _ => None,
+        };
+        let Some(element_type) = element_type.filter(|_| self.peek_second() == Some(Token::LParen))
+        else {
+            let table = self.table_type()?;
+            return self.sink.table(table);
+        };
+
+        // (table funcref (elem ...)) sizes the table to its elements
+        self.advance();
+        if !self.eat_group("elem") {
+            return Err(Error::parse_error("Expected inline elements"));
+        }
+        let elements = self.element_list(false)?;
+        self.rparen()?;
+        let count = elements.ends.len() as u32;
+        self.sink
+            .table(Table { element_type, limits: Limits { min: count, max: Some(count) } })?;
+        self.defined[Space::Elem as usize] += 1;
+        let segment = Segment::Active { index, offset: &ZERO_OFFSET };
+        self.sink.element(segment, element_type, &elements)
+    }
+
+    fn memory_field(&mut self) -> Result<()> {
+        self.id();
+        if self.peek_import_after_exports() {
+            let index = self.names.imports[Space::Memory as usize];
+            self.inline_exports(Space::Memory, index)?;
+            self.inline_import("memory")?;
+            return Ok(());
+        }
+        let index = self.next_definition(Space::Memory);
+        self.inline_exports(Space::Memory, index)?;
+        if !self.eat_group("data") {
+            let memory = self.memory_type()?;
+            return self.sink.memory(memory);
+        }
+
+        // (memory (data ...)) sizes the memory to its data
+        let mut data = Buf::<u8, MAX_DATA_BYTES>::default();
+        while self.peek() != Some(Token::RParen) {
+            self.string(&mut data)?;
+        }
+        self.rparen()?;
+        let pages = data.len().div_ceil(65536) as u32;
+        self.sink.memory(Memory {
+            limits:   Limits { min: pages, max: Some(pages) },
+            shared:   false,
+            memory64: false,
+        })?;
+        self.defined[Space::Data as usize] += 1;
+        self.sink.data(Segment::Active { index, offset: &ZERO_OFFSET }, &data)
+    }
+
+    fn global_field(&mut self) -> Result<()> {
+        self.id();
+        if self.peek_import_after_exports() {
+            let index = self.names.imports[Space::Global as usize];
+            self.inline_exports(Space::Global, index)?;
+            self.inline_import("global")?;
+            return Ok(());
+        }
+        let index = self.next_definition(Space::Global);
+        self.inline_exports(Space::Global, index)?;
+        let global_type = self.global_type()?;
+        let init = self.const_expr()?;
+        self.sink.global(global_type, &init)
+    }
+
+    fn elem_field(&mut self) -> Result<()> {
+        self.id();
+        self.defined[Space::Elem as usize] += 1;
+        let mut table = None;
+        if self.eat_group("table") {
+            table = Some(self.index(Space::Table)?);
+            self.rparen()?;
+        }
+        let declared = self.eat_keyword("declare");
+        let offset = if !declared && (table.is_some() || self.peek_offset()) {
+            Some(self.offset_expr()?)
+        } else {
+            None
+        };
+        let element_type = match self.peek_keyword() {
+            Some("externref") => RefType::Externref,
+            _ => RefType::Funcref,
+        };
+        // Active segments of table 0 may list bare function indices
+        let bare = offset.is_some() && table.is_none();
+        let elements = self.element_list(bare)?;
+        let segment = match &offset {
+            Some(offset) => Segment::Active { index: table.unwrap_or(0), offset },
+            None if declared => Segment::Declared,
+            None => Segment::Passive,
+        };
+        self.sink.element(segment, element_type, &elements)
+    }
+
+    /// Parse `func idx*`, `reftype expr*` or, if `bare`, just `idx*`
+    fn element_list(&mut self, bare: bool) -> Result<Elements> {
+        let mut elements = Elements::default();
+        if self.eat_keyword("func")
+            || (bare && self.at_index())
+            || self.peek() == Some(Token::RParen)
+        {
+            while self.at_index() {
+                let index = self.index(Space::Func)?;
+                elements.bytes.push_u32(index)?;
+                elements.ends.push(elements.bytes.len())?;
+            }
+            return Ok(elements);
+        }
+        if !(self.eat_keyword("funcref") || self.eat_keyword("externref")) {
+            return Err(Error::parse_error("Expected element list"));
+        }
+        elements.expressions = true;
+        while self.peek() == Some(Token::LParen) {
+            if self.eat_group("item") {
+                self.instructions(&mut Scope::default(), &mut elements.bytes)?;
+                self.rparen()?;
+            } else {
+                self.folded_instruction(&mut Scope::default(), &mut elements.bytes)?;
+            }
+            elements.bytes.push(END)?;
+            elements.ends.push(elements.bytes.len())?;
+        }
+        Ok(elements)
+    }
+
+    fn data_field(&mut self) -> Result<()> {
+        self.id();
+        self.defined[Space::Data as usize] += 1;
+        let mut memory = None;
+        if self.eat_group("memory") {
+            memory = Some(self.index(Space::Memory)?);
+            self.rparen()?;
+        }
+        let offset = if memory.is_some() || self.peek_offset() {
+            Some(self.offset_expr()?)
+        } else {
+            None
+        };
+        let mut data = Buf::<u8, MAX_DATA_BYTES>::default();
+        while self.peek() != Some(Token::RParen) {
+            self.string(&mut data)?;
+        }
+        let segment = match &offset {
+            Some(offset) => Segment::Active { index: memory.unwrap_or(0), offset },
+            None => Segment::Passive,
+        };
+        self.sink.data(segment, &data)
+    }
+
+    /// Whether the cursor is at the offset of an active segment
+    fn peek_offset(&self) -> bool {
+        self.peek_group()
+            .is_some_and(|keyword| keyword == "offset" || instr_by_name(keyword).is_some())
+    }
+
+    /// Parse `(offset instr*)` or a single folded instruction
+    fn offset_expr(&mut self) -> Result<Code> {
+        let mut expr = Code::default();
+        if self.eat_group("offset") {
+            self.instructions(&mut Scope::default(), &mut expr)?;
+            self.rparen()?;
+        } else {
+            self.folded_instruction(&mut Scope::default(), &mut expr)?;
+        }
+        expr.push(END)?;
+        Ok(expr)
+    }
+
+    fn const_expr(&mut self) -> Result<Code> {
+        let mut expr = Code::default();
+        self.instructions(&mut Scope::default(), &mut expr)?;
+        expr.push(END)?;
+        Ok(expr)
+    }
+
+    fn value_type(&mut self) -> Result<ValueType> {
+        Ok(match self.keyword()? {
+            "i32" => ValueType::I32,
+            "i64" => ValueType::I64,
+            "f32" => ValueType::F32,
+            "f64" => ValueType::F64,
+            "v128" => ValueType::V128,
+            "funcref" => ValueType::FuncRef,
+            "externref" => ValueType::ExternRef,
+            _ => return Err(Error::parse_error("Unknown value type")),
+        })
+    }
+
+    fn ref_type(&mut self) -> Result<RefType> {
+        match self.keyword()? {
+            "funcref" => Ok(RefType::Funcref),
+            "externref" => Ok(RefType::Externref),
+            _ => Err(Error::parse_error("Expected a reference type")),
+        }
+    }
+
+    fn limits(&mut self) -> Result<Limits> {
+        let min = self.u32()?;
+        let max = if matches!(self.peek(), Some(Token::Integer(_))) {
+            Some(self.u32()?)
+        } else {
+            None
+        };
+        Ok(Limits { min, max })
+    }
+
+    fn table_type(&mut self) -> Result<Table> {
+        let limits = self.limits()?;
+        let element_type = self.ref_type()?;
+        Ok(Table { element_type, limits })
+    }
+
+    fn memory_type(&mut self) -> Result<Memory> {
+        if self.peek_keyword().is_some() {
+            return Err(Error::validation_unsupported_feature("Memory type not supported in text"));
+        }
+        Ok(Memory { limits: self.limits()?, shared: false, memory64: false })
+    }
+
+    fn global_type(&mut self) -> Result<FormatGlobalType> {
+        if self.eat_group("mut") {
+            let value_type = self.value_type()?;
+            self.rparen()?;
+            return Ok(FormatGlobalType { value_type, mutable: true });
+        }
+        Ok(FormatGlobalType { value_type: self.value_type()?, mutable: false })
+    }
+
+    /// Parse `(param ...)*`, returning the types and their names
+    fn params(&mut self) -> Result<(Types, ParamNames<'a>)> {
+        let mut types = Types::default();
+        let mut names = ParamNames::default();
+        while self.eat_group("param") {
+            if let Some(name) = self.id() {
+                types.push(self.value_type()?)?;
+                names.push(Some(name))?;
+            } else {
+                while self.peek() != Some(Token::RParen) {
+                    types.push(self.value_type()?)?;
+                    names.push(None)?;
+                }
+            }
+            self.rparen()?;
+        }
+        Ok((types, names))
+    }
+
+    fn results(&mut self) -> Result<Types> {
+        let mut types = Types::default();
+        while self.eat_group("result") {
+            while self.peek() != Some(Token::RParen) {
+                types.push(self.value_type()?)?;
+            }
+            self.rparen()?;
+        }
+        Ok(types)
+    }
+
+    /// Parse a type use, returning the type index and the parameter names
+    fn type_use(&mut self) -> Result<(u32, ParamNames<'a>)> {
+        let explicit = if self.eat_group("type") {
+            let index = self.index(Space::Type)?;
+            self.rparen()?;
+            Some(index)
+        } else {
+            None
+        };
+        let (params, mut names) = self.params()?;
+        let results = self.results()?;
+        let index = match explicit {
+            Some(index) => {
+                let params = self
+                    .sink
+                    .type_params(index)?
+                    .ok_or_else(|| Error::parse_error("Unknown type"))?;
+                if names.is_empty() {
+                    for _ in 0..params {
+                        names.push(None)?;
+                    }
+                }
+                index
+            },
+            None => self.sink.func_type(&params, &results, true)?,
+        };
+        Ok((index, names))
+    }
+
+    /// Parse instructions up to the next `)`, `end`, `else` or `then`
+    fn instructions(&mut self, scope: &mut Scope<'a>, out: &mut Code) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(Token::LParen) => {
+                    if !self.peek_group().is_some_and(|keyword| instr_by_name(keyword).is_some()) {
+                        return Ok(());
+                    }
+                    self.folded_instruction(scope, out)?;
+                },
+                Some(Token::Keyword("end" | "else")) | Some(Token::RParen) | None => return Ok(()),
+                Some(Token::Keyword(_)) => self.plain_instruction(scope, out)?,
+                Some(_) => return Err(Error::parse_error("Expected an instruction")),
+            }
+        }
+    }
+
+    /// Parse an instruction in flat syntax, with blocks up to their `end`
+    fn plain_instruction(&mut self, scope: &mut Scope<'a>, out: &mut Code) -> Result<()> {
+        let name = self.keyword()?;
+        let instr = instr_by_name(name).ok_or_else(|| Error::parse_error("Unknown instruction"))?;
+        if instr.imm != Imm::Block {
+            return self.instruction_with_immediates(instr, scope, out);
+        }
+        let label = self.id();
+        out.push(instr.code)?;
+        self.block_type(out)?;
+        scope.labels.push(label)?;
+        self.instructions(scope, out)?;
+        if instr.name == "if" && self.eat_keyword("else") {
+            self.id();
+            out.push(0x05)?;
+            self.instructions(scope, out)?;
+        }
+        if !self.eat_keyword("end") {
+            return Err(Error::parse_error("Expected `end`"));
+        }
+        self.id();
+        scope.labels.pop();
+        out.push(END)
+    }
+
+    /// Parse an instruction in folded syntax
+    fn folded_instruction(&mut self, scope: &mut Scope<'a>, out: &mut Code) -> Result<()> {
+        self.lparen()?;
+        let name = self.keyword()?;
+        let instr = instr_by_name(name).ok_or_else(|| Error::parse_error("Unknown instruction"))?;
+        let start = out.len();
+        if instr.imm != Imm::Block {
+            self.instruction_with_immediates(instr, scope, out)?;
+            let encoded = out.len() - start;
+            // Operands come first
+            while self.peek() == Some(Token::LParen) {
+                self.folded_instruction(scope, out)?;
+            }
+            out[start..].rotate_left(encoded);
+            return self.rparen();
+        }
+
+        let label = self.id();
+        out.push(instr.code)?;
+        self.block_type(out)?;
+        let header = out.len() - start;
+        if instr.name == "if" {
+            // Conditions precede the `then` arm
+            while self.peek() == Some(Token::LParen) && self.peek_group() != Some("then") {
+                self.folded_instruction(scope, out)?;
+            }
+        }
+        out[start..].rotate_left(header);
+        scope.labels.push(label)?;
+        if instr.name == "if" {
+            if !self.eat_group("then") {
+                return Err(Error::parse_error("Expected `then`"));
+            }
+            self.instructions(scope, out)?;
+            self.rparen()?;
+            if self.eat_group("else") {
+                out.push(0x05)?;
+                self.instructions(scope, out)?;
+                self.rparen()?;
+            }
+        } else {
+            self.instructions(scope, out)?;
+        }
+        scope.labels.pop();
+        out.push(END)?;
+        self.rparen()
+    }
+
+    fn block_type(&mut self, out: &mut Code) -> Result<()> {
+        if self.peek_group() == Some("type") {
+            let (index, _) = self.type_use()?;
+            return out.push_i64(i64::from(index));
+        }
+        let (params, _) = self.params()?;
+        let results = self.results()?;
+        match (params.is_empty(), &results[..]) {
+            (true, []) => out.push(0x40),
+            (true, [single]) => out.push(single.to_binary()),
+            _ => {
+                let index = self.sink.func_type(&params, &results, true)?;
+                out.push_i64(i64::from(index))
+            },
+        }
+    }
+
+    fn label(&mut self, scope: &Scope<'a>) -> Result<u32> {
+        match self.next()? {
+            Token::Integer(text) => {
+                parse_u32(text).ok_or_else(|| Error::parse_error("Invalid label"))
+            },
+            Token::Id(id) => scope
+                .labels
+                .iter()
+                .rev()
+                .position(|label| *label == Some(id))
+                .map(|depth| depth as u32)
+                .ok_or_else(|| Error::parse_error("Unknown label")),
+            _ => Err(Error::parse_error("Expected a label")),
+        }
+    }
+
+    fn local(&mut self, scope: &Scope<'a>) -> Result<u32> {
+        match self.next()? {
+            Token::Integer(text) => {
+                parse_u32(text).ok_or_else(|| Error::parse_error("Invalid local index"))
+            },
+            Token::Id(id) => {
+                scope.locals.get(id).ok_or_else(|| Error::parse_error("Unknown local"))
+            },
+            _ => Err(Error::parse_error("Expected a local")),
+        }
+    }
+
+    fn optional_index(&mut self, space: Space) -> Result<u32> {
+        if self.at_index() {
+            self.index(space)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn instruction_with_immediates(
+        &mut self,
+        instr: Instr,
+        scope: &mut Scope<'a>,
+        out: &mut Code,
+    ) -> Result<()> {
+        if instr.prefix {
+            out.push(MISC_PREFIX)?;
+            out.push_u32(u32::from(instr.code))?;
+        } else if instr.imm != Imm::Select {
+            out.push(instr.code)?;
+        }
+        match instr.imm {
+            Imm::None | Imm::Block => {},
+            Imm::Label => out.push_u32(self.label(scope)?)?,
+            Imm::LabelTable => {
+                let mut labels = Buf::<u32, MAX_LABELS>::default();
+                while self.at_index() {
+                    labels.push(self.label(scope)?)?;
+                }
+                let default =
+                    labels.pop().ok_or_else(|| Error::parse_error("br_table needs a label"))?;
+                out.push_u32(labels.len() as u32)?;
+                for &label in labels.iter() {
+                    out.push_u32(label)?;
+                }
+                out.push_u32(default)?;
+            },
+            Imm::Func => out.push_u32(self.index(Space::Func)?)?,
+            Imm::CallIndirect => {
+                let table = self.optional_index(Space::Table)?;
+                let (type_idx, _) = self.type_use()?;
+                out.push_u32(type_idx)?;
+                out.push_u32(table)?;
+            },
+            Imm::Local => out.push_u32(self.local(scope)?)?,
+            Imm::Global => out.push_u32(self.index(Space::Global)?)?,
+            Imm::Table => out.push_u32(self.optional_index(Space::Table)?)?,
+            Imm::Memarg(natural) => {
+                let mut offset = 0;
+                let mut align = natural;
+                while let Some(keyword) = self.peek_keyword() {
+                    if let Some(value) = keyword.strip_prefix("offset=") {
+                        offset = parse_u32(value)
+                            .ok_or_else(|| Error::parse_error("Invalid memory offset"))?;
+                    } else if let Some(value) = keyword.strip_prefix("align=") {
+                        let bytes = parse_u32(value)
+                            .filter(|bytes| bytes.is_power_of_two())
+                            .ok_or_else(|| Error::parse_error("Invalid alignment"))?;
+                        align = bytes.trailing_zeros();
+                    } else {
+                        break;
+                    }
+                    self.advance();
+                }
+                out.push_u32(align)?;
+                out.push_u32(offset)?;
+            },
+            Imm::Memory => out.push_u32(self.optional_index(Space::Memory)?)?,
+            Imm::I32 => {
+                let value = match self.next()? {
+                    Token::Integer(text) => parse_i32(text),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::parse_error("Invalid i32 literal"))?;
+                out.push_i32(value)?;
+            },
+            Imm::I64 => {
+                let value = match self.next()? {
+                    Token::Integer(text) => parse_i64(text),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::parse_error("Invalid i64 literal"))?;
+                out.push_i64(value)?;
+            },
+            Imm::F32 => {
+                let bits = match self.next()? {
+                    Token::Integer(text) | Token::Float(text) => parse_f32(text),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::parse_error("Invalid f32 literal"))?;
+                out.extend_bytes(&bits.to_le_bytes())?;
+            },
+            Imm::F64 => {
+                let bits = match self.next()? {
+                    Token::Integer(text) | Token::Float(text) => parse_f64(text),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::parse_error("Invalid f64 literal"))?;
+                out.extend_bytes(&bits.to_le_bytes())?;
+            },
+            Imm::RefNull => match self.keyword()? {
+                "func" => out.push(0x70)?,
+                "extern" => out.push(0x6F)?,
+                _ => return Err(Error::parse_error("Unknown heap type")),
+            },
+            Imm::Select => {
+                let results = self.results()?;
+                if results.is_empty() {
+                    out.push(0x1B)?;
+                } else {
+                    out.push(0x1C)?;
+                    out.push_u32(results.len() as u32)?;
+                    for ty in results.iter() {
+                        out.push(ty.to_binary())?;
+                    }
+                }
+            },
+            Imm::Data => out.push_u32(self.index(Space::Data)?)?,
+            Imm::Elem => out.push_u32(self.index(Space::Elem)?)?,
+            Imm::MemoryInit => {
+                let start = self.pos;
+                let first = self.index(Space::Data);
+                let (memory, data) = if self.at_index() {
+                    self.pos = start;
+                    let memory = self.index(Space::Memory)?;
+                    (memory, self.index(Space::Data)?)
+                } else {
+                    (0, first?)
+                };
+                out.push_u32(data)?;
+                out.push_u32(memory)?;
+            },
+            Imm::MemoryCopy => {
+                let dst = self.optional_index(Space::Memory)?;
+                let src = self.optional_index(Space::Memory)?;
+                out.push_u32(dst)?;
+                out.push_u32(src)?;
+            },
+            Imm::TableInit => {
+                let start = self.pos;
+                let first = self.index(Space::Elem);
+                let (table, elem) = if self.at_index() {
+                    self.pos = start;
+                    let table = self.index(Space::Table)?;
+                    (table, self.index(Space::Elem)?)
+                } else {
+                    (0, first?)
+                };
+                out.push_u32(elem)?;
+                out.push_u32(table)?;
+            },
+            Imm::TableCopy => {
+                let dst = self.optional_index(Space::Table)?;
+                let src = self.optional_index(Space::Table)?;
+                out.push_u32(dst)?;
+                out.push_u32(src)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexer_skips_comments() -> Result<()> {
+        let mut lexer =
+            Lexer::new("(func $f ;; line\n (; outer (; inner ;) ;) i32.const -0x10 \"a\\\"b\")");
+        let mut tokens = [None; 8];
+        for slot in &mut tokens {
+            *slot = lexer.next_token()?;
+        }
+        assert_eq!(
+            tokens,
+            [
+                Some(Token::LParen),
+                Some(Token::Keyword("func")),
+                Some(Token::Id("f")),
+                Some(Token::Keyword("i32.const")),
+                Some(Token::Integer("-0x10")),
+                Some(Token::String("a\\\"b")),
+                Some(Token::RParen),
+                None,
+            ]
+        );
+        assert!(Lexer::new("(; open").next_token().is_err());
+        assert!(Lexer::new("\"open").next_token().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_literals() {
+        assert_eq!(parse_u32("1_000"), Some(1000));
+        assert_eq!(parse_u32("0xffff_ffff"), Some(u32::MAX));
+        assert_eq!(parse_u32("-1"), None);
+        assert_eq!(parse_u32("1__0"), None);
+        assert_eq!(parse_i32("0xffffffff"), Some(-1));
+        assert_eq!(parse_i32("-2147483648"), Some(i32::MIN));
+        assert_eq!(parse_i32("-2147483649"), None);
+        assert_eq!(parse_i64("-0x8000000000000000"), Some(i64::MIN));
+        assert_eq!(parse_i64("18446744073709551615"), Some(-1));
+    }
+
+    #[test]
+    fn test_float_literals() {
+        assert_eq!(parse_f32("0x1p-1"), Some(0.5f32.to_bits()));
+        assert_eq!(parse_f32("0x1.fffffep127"), Some(f32::MAX.to_bits()));
+        assert_eq!(parse_f32("0x1p-149"), Some(1));
+        assert_eq!(parse_f32("0x1p128"), None);
+        assert_eq!(parse_f32("-inf"), Some(f32::NEG_INFINITY.to_bits()));
+        assert_eq!(parse_f32("nan:0x200000"), Some(0x7FA0_0000));
+        assert_eq!(parse_f32("1e39"), None);
+        assert_eq!(parse_f64("1_000.5e-1"), Some(100.05f64.to_bits()));
+        // Halfway cases round to even
+        assert_eq!(parse_f64("0x1.00000000000008p0"), Some(1.0f64.to_bits()));
+        assert_eq!(parse_f64("0x1.00000000000018p0"), Some(1.0f64.to_bits() + 2));
+        assert_eq!(parse_f64("-0x0p0"), Some((-0.0f64).to_bits()));
+    }
+
+    #[test]
+    fn test_unescape() -> Result<()> {
+        let mut out = [0u8; 8];
+        let mut len = 0;
+        unescape("a\\n\\41\\u{e9}", |byte| {
+            out[len] = byte;
+            len += 1;
+            Ok(())
+        })?;
+        assert_eq!(&out[..len], b"a\nA\xc3\xa9");
+        assert!(unescape("\\q", |_| Ok(())).is_err());
+        Ok(())
+    }
+
+    /// Fixed-size output, as a `no_std` caller would use
+    struct Buffer<const N: usize> {
+        bytes: [u8; N],
+        len:   usize,
+    }
+
+    impl<const N: usize> fmt::Write for Buffer<N> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_instructions_into_bounded_buffer() -> Result<()> {
+        // block (result i32) i32.const -1 br_if 0 end f64.const 0.5 end
+        let code =
+            [0x02, 0x7F, 0x41, 0x7F, 0x0D, 0x00, 0x0B, 0x44, 0, 0, 0, 0, 0, 0, 0xE0, 0x3F, 0x0B];
+        let mut buffer = Buffer { bytes: [0; 128], len: 0 };
+        assert_eq!(write_instructions(&mut buffer, &code, 2)?, code.len());
+        let text = core::str::from_utf8(&buffer.bytes[..buffer.len]).unwrap_or_default();
+        assert_eq!(
+            text,
+            "  block (result i32)\n    i32.const -1\n    br_if 0\n  end\n  f64.const 5e-1\n"
+        );
+
+        buffer.len = 0;
+        assert_eq!(write_inline_instructions(&mut buffer, &[0x41, 0x10, 0x0B])?, 3);
+        assert_eq!(&buffer.bytes[..buffer.len], b"i32.const 16");
+        assert!(write_inline_instructions(&mut buffer, &[0xFD, 0x0C, 0x0B]).is_err());
+
+        let mut small = Buffer { bytes: [0; 128], len: 120 };
+        assert!(write_instructions(&mut small, &code, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounded_text_round_trip() -> Result<()> {
+        let text = r#"(module
+          (import "env" "tick" (func $tick (param i32)))
+          (table funcref (elem func $count))
+          (memory (export "mem") (data "hi\00"))
+          (global $total (mut i64) (i64.const 0))
+          (elem $later func $count)
+          (data $bytes "\01\02")
+          (func $count (export "count") (param $n i32) (result i32) (local $i i32)
+            (block $out
+              (loop $next
+                (br_if $out (i32.ge_u (local.get $i) (local.get $n)))
+                (call $tick (local.get $i))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (memory.init $bytes (i32.const 8) (i32.const 0) (i32.const 2))
+            (if (result i32) (local.get $n) (then (local.get $i)) (else (i32.const -1))))
+          (start 0))"#;
+        let mut binary = StaticVec::<u8, 512>::new();
+        assemble_module(text, &mut binary)?;
+        assert_eq!(binary.as_slice().get(..4), Some(&WASM_MAGIC[..]));
+
+        let mut printed = Buffer { bytes: [0; 2048], len: 0 };
+        write_binary_module(&mut printed, binary.as_slice())?;
+        let printed = core::str::from_utf8(&printed.bytes[..printed.len]).unwrap_or_default();
+        assert!(printed.contains(r#"(data (;1;) "\01\02")"#));
+
+        let mut reassembled = StaticVec::<u8, 512>::new();
+        assemble_module(printed, &mut reassembled)?;
+        assert_eq!(reassembled.as_slice(), binary.as_slice());
+        let mut reprinted = Buffer { bytes: [0; 2048], len: 0 };
+        write_binary_module(&mut reprinted, reassembled.as_slice())?;
+        assert_eq!(&reprinted.bytes[..reprinted.len], printed.as_bytes());
+
+        let mut small = StaticVec::<u8, 32>::new();
+        assert!(assemble_module(text, &mut small).is_err());
+        assert!(small.is_empty());
+        assert!(
+            write_binary_module(&mut Buffer { bytes: [0; 64], len: 0 }, binary.as_slice()).is_err()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_names_resolve_and_unknown_names_fail() -> Result<()> {
+        let module = parse_module(
+            "(func $f (param $x i32) (result i32) local.get $x) (export \"f\" (func $f))",
+        )?;
+        assert_eq!(module.types.len(), 1);
+        assert_eq!(module.functions[0].code, [0x20, 0x00, END]);
+        assert_eq!(module.exports[0].index, 0);
+        assert!(parse_module("(module (func call $missing))").is_err());
+        assert!(parse_module("(module (func i32.bogus))").is_err());
+        Ok(())
+    }
+}
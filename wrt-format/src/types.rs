@@ -30,6 +30,39 @@ pub enum MemoryIndexType {
     I64,
 }
 
+/// Maximum number of pages of a standard memory (4GiB)
+pub const MEMORY32_MAX_PAGES: u64 = 1 << 16;
+
+/// Maximum number of pages of a memory64 memory
+pub const MEMORY64_MAX_PAGES: u64 = 1 << 48;
+
+impl MemoryIndexType {
+    /// Index type of a memory type
+    pub fn of(memory: &wrt_foundation::types::MemoryType) -> Self {
+        if memory.memory64 {
+            Self::I64
+        } else {
+            Self::I32
+        }
+    }
+
+    /// Value type of addresses, sizes and page counts of the memory
+    pub fn address_type(self) -> ValueType {
+        match self {
+            Self::I32 => ValueType::I32,
+            Self::I64 => ValueType::I64,
+        }
+    }
+
+    /// Maximum number of pages the memory type may declare
+    pub fn max_pages(self) -> u64 {
+        match self {
+            Self::I32 => MEMORY32_MAX_PAGES,
+            Self::I64 => MEMORY64_MAX_PAGES,
+        }
+    }
+}
+
 /// WebAssembly limits
 ///
 /// Limits represent the minimum and optional maximum sizes for
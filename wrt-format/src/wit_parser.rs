@@ -1,602 +1,958 @@
-//! WIT (WebAssembly Interface Types) parser with simplified types
+//! WIT documents
+//!
+//! [`WitDocument::parse`] reads the package declaration, interfaces, worlds,
+//! type definitions, function signatures and `use` statements of one WIT
+//! file. Types keep references to other named types as [`WitType::Named`];
+//! [`WitDocument::resolve_type`] follows them, and [`WitDocument::layout`]
+//! and [`WitDocument::flat_count`] give the canonical ABI shape of a type.
+//! Packages spread over several files and their dependencies are resolved
+//! against each other by [`crate::wit_resolve`].
+
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    fmt,
+    string::String,
+    vec::Vec,
+};
 
-#[cfg(feature = "std")]
-use std::collections::BTreeMap;
-#[cfg(feature = "std")]
-use std::boxed::Box;
+/// Error from parsing or resolving WIT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitError {
+    message: String,
+}
 
-#[cfg(all(not(feature = "std")))]
-use std::{boxed::Box, collections::BTreeMap, vec::Vec};
+impl WitError {
+    /// Create an error with the given message
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
 
-use core::fmt;
+    /// Message describing the error
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
 
-use wrt_foundation::{
-    BoundedVec, BoundedString,
-    bounded::MAX_GENERATIVE_TYPES,
-    NoStdProvider,
-};
+impl fmt::Display for WitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
-use wrt_error::Error;
+impl std::error::Error for WitError {}
 
-// Include type definitions
-#[path = "wit_parser_types.rs"]
-mod wit_parser_types;
-#[path = "wit_parser_traits.rs"]
-mod wit_parser_traits;
+impl From<WitError> for wrt_error::Error {
+    fn from(_: WitError) -> Self {
+        wrt_error::Error::parse_error("Invalid WIT document")
+    }
+}
 
-// Re-export all types and traits
-pub use wit_parser_types::*;
-pub use wit_parser_traits::*;
+/// Result of parsing or resolving WIT
+pub type WitResult<T> = core::result::Result<T, WitError>;
+
+/// Maximum nesting depth when resolving named WIT types
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A WIT type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    S8,
+    S16,
+    S32,
+    S64,
+    F32,
+    F64,
+    Char,
+    String,
+    List(Box<WitType>),
+    Option(Box<WitType>),
+    Result {
+        ok:  Option<Box<WitType>>,
+        err: Option<Box<WitType>>,
+    },
+    Tuple(Vec<WitType>),
+    Record(Vec<(String, WitType)>),
+    Enum(Vec<String>),
+    Flags(Vec<String>),
+    Variant(Vec<(String, Option<WitType>)>),
+    /// `own<T>` / `borrow<T>` resource handle
+    Handle(String),
+    /// Reference to a type defined elsewhere in the document
+    Named(String),
+}
 
-// Type aliases and type definitions are now in wit_parser_types.rs
+impl fmt::Display for WitType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitType::Bool => write!(f, "bool"),
+            WitType::U8 => write!(f, "u8"),
+            WitType::U16 => write!(f, "u16"),
+            WitType::U32 => write!(f, "u32"),
+            WitType::U64 => write!(f, "u64"),
+            WitType::S8 => write!(f, "s8"),
+            WitType::S16 => write!(f, "s16"),
+            WitType::S32 => write!(f, "s32"),
+            WitType::S64 => write!(f, "s64"),
+            WitType::F32 => write!(f, "f32"),
+            WitType::F64 => write!(f, "f64"),
+            WitType::Char => write!(f, "char"),
+            WitType::String => write!(f, "string"),
+            WitType::List(inner) => write!(f, "list<{}>", inner),
+            WitType::Option(inner) => write!(f, "option<{}>", inner),
+            WitType::Result { ok, err } => {
+                let ok = ok.as_ref().map_or_else(|| "_".to_string(), |t| t.to_string());
+                match err {
+                    Some(err) => write!(f, "result<{}, {}>", ok, err),
+                    None => write!(f, "result<{}>", ok),
+                }
+            },
+            WitType::Tuple(items) => {
+                let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+                write!(f, "tuple<{}>", items.join(", "))
+            },
+            WitType::Record(_) => write!(f, "record"),
+            WitType::Enum(_) => write!(f, "enum"),
+            WitType::Flags(_) => write!(f, "flags"),
+            WitType::Variant(_) => write!(f, "variant"),
+            WitType::Handle(name) => write!(f, "own<{}>", name),
+            WitType::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
 
-// WIT parser implementation starts here
+/// A WIT function signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitFunction {
+    /// Function name
+    pub name:      String,
+    /// Interface the function was declared in, if any
+    pub interface: Option<String>,
+    /// Named parameters
+    pub params:    Vec<(String, WitType)>,
+    /// Results; a single unnamed result is called `result`
+    pub results:   Vec<(String, WitType)>,
+}
 
-/// A parser for WIT (WebAssembly Interface Types) source code
-#[derive(Debug, Clone)]
-#[allow(dead_code)] // Fields are part of future parser state implementation
-pub struct WitParser {
-    current_position: usize,
-    type_definitions: BTreeMap<WitBoundedString, WitType>,
-    provider: NoStdProvider<1024>,
+/// An import or export of a WIT world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitWorldItem {
+    /// Interface declared in the document or inline in the world
+    Interface(String),
+    /// Function declared directly in the world
+    Function(String),
+    /// Interface of another package, such as `wasi:cli/stdout@0.2.0`
+    External(String),
 }
 
-// WitParseError is defined in wit_parser_types.rs
-
-impl From<WitParseError> for Error {
-    fn from(err: WitParseError) -> Self {
-        match err {
-            WitParseError::UnexpectedEnd => Error::parse_error("Unexpected end of WIT input"),
-            WitParseError::InvalidSyntax(_) => Error::parse_error("Invalid WIT syntax"),
-            WitParseError::UnknownType(_) => Error::parse_error("Unknown WIT type"),
-            WitParseError::TooManyItems => Error::parse_error("Too many WIT items"),
-            WitParseError::InvalidIdentifier(_) => Error::parse_error("Invalid WIT identifier"),
-            WitParseError::DuplicateDefinition(_) => Error::parse_error("Duplicate WIT definition"),
-        }
-    }
+/// Imports and exports of a WIT world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitWorld {
+    /// World name
+    pub name:     String,
+    /// Imported interfaces and functions
+    pub imports:  Vec<WitWorldItem>,
+    /// Exported interfaces and functions
+    pub exports:  Vec<WitWorldItem>,
+    /// Worlds whose imports and exports this world includes, as written
+    pub includes: Vec<String>,
 }
 
-impl WitParser {
-    /// Create a new WIT parser
-    pub fn new() -> Self {
-        Self {
-            current_position: 0,
-            type_definitions: BTreeMap::new(),
-            provider: NoStdProvider::default(),
+/// A `use` statement
+///
+/// `use types.{handle, error as io-error};` inside an interface or world
+/// brings types of another interface into scope; a top-level
+/// `use wasi:io/streams@0.2.0;` only names an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitUse {
+    /// Interface or world the statement appears in; `None` at top level
+    pub scope: Option<String>,
+    /// Interface the names come from, as written, such as `types` or
+    /// `wasi:io/streams@0.2.0`
+    pub from:  String,
+    /// Used names and the local names they are known under
+    pub names: Vec<(String, String)>,
+}
+
+/// Type and function definitions extracted from a WIT document
+#[derive(Debug, Clone, Default)]
+pub struct WitDocument {
+    package:    Option<String>,
+    interfaces: Vec<String>,
+    types:      HashMap<String, WitType>,
+    functions:  Vec<WitFunction>,
+    worlds:     Vec<WitWorld>,
+    uses:       Vec<WitUse>,
+}
+
+impl WitDocument {
+    /// Parse the type definitions and function signatures of a WIT document
+    pub fn parse(source: &str) -> WitResult<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = WitParser {
+            tokens,
+            pos: 0,
+            doc: WitDocument::default(),
+        };
+        parser.parse_document()?;
+        Ok(parser.doc)
+    }
+
+    /// All function signatures in declaration order
+    pub fn functions(&self) -> &[WitFunction] {
+        &self.functions
+    }
+
+    /// Package the document declares, such as `example:plugin@1.0.0`
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    /// All worlds in declaration order
+    pub fn worlds(&self) -> &[WitWorld] {
+        &self.worlds
+    }
+
+    /// Top-level interfaces in declaration order
+    pub fn interfaces(&self) -> &[String] {
+        &self.interfaces
+    }
+
+    /// All `use` statements in declaration order
+    pub fn uses(&self) -> &[WitUse] {
+        &self.uses
+    }
+
+    /// Definition of a named type
+    pub fn type_def(&self, name: &str) -> Option<&WitType> {
+        self.types.get(name)
+    }
+
+    /// Define a type under `name` unless one of that name exists
+    pub(crate) fn define_type(&mut self, name: &str, ty: WitType) {
+        self.types.entry(name.to_string()).or_insert(ty);
+    }
+
+    /// Merge another file of the same package into this document
+    pub(crate) fn merge(&mut self, other: WitDocument) {
+        if self.package.is_none() {
+            self.package = other.package;
+        }
+        self.interfaces.extend(other.interfaces);
+        for (name, ty) in other.types {
+            self.types.entry(name).or_insert(ty);
         }
+        self.functions.extend(other.functions);
+        self.worlds.extend(other.worlds);
+        self.uses.extend(other.uses);
     }
 
-    /// Parse a WIT world definition from source code
-    pub fn parse_world(&mut self, source: &str) -> Result<WitWorld<P>, WitParseError<P>> {
-        let mut world = WitWorld {
-            name: BoundedString::default(),
-            imports: BoundedVec::default(),
-            exports: BoundedVec::default(),
-            types: BoundedVec::default(),
-        };
+    /// Name an interface of this document is imported under
+    ///
+    /// Top-level interfaces are qualified with the document's package, so
+    /// `logger` in package `example:logging@0.1.0` becomes
+    /// `example:logging/logger@0.1.0`. Interfaces declared inline in a world
+    /// keep their plain name.
+    pub fn qualified_interface(&self, interface: &str) -> String {
+        if !self.interfaces.iter().any(|name| name == interface) {
+            return interface.to_string();
+        }
+        match self.package.as_deref() {
+            Some(package) => match package.split_once('@') {
+                Some((name, version)) => format!("{}/{}@{}", name, interface, version),
+                None => format!("{}/{}", package, interface),
+            },
+            None => interface.to_string(),
+        }
+    }
 
-        #[cfg(feature = "std")]
-        {
-            let lines: Vec<&str> = source.lines().collect();
-            let mut i = 0;
-
-            while i < lines.len() {
-                let line = lines[i].trim();
-                
-                if line.is_empty() || line.starts_with("//") {
-                    i += 1;
-                    continue;
-                }
+    /// Number of core values a type flattens to in the canonical ABI
+    pub fn flat_count(&self, ty: &WitType) -> WitResult<usize> {
+        self.flat_count_at(ty, 0)
+    }
 
-                if line.starts_with("world ") {
-                    let name = self.extract_identifier(line, "world ")?;
-                    world.name = name;
-                } else if line.starts_with("import ") {
-                    let import = self.parse_import(line)?;
-                    world.imports.push(import)
-                        .map_err(|_| WitParseError::TooManyItems)?;
-                } else if line.starts_with("export ") {
-                    let export = self.parse_export(line)?;
-                    world.exports.push(export)
-                        .map_err(|_| WitParseError::TooManyItems)?;
-                } else if line.starts_with("type ") {
-                    let type_def = self.parse_type_def(line)?;
-                    world.types.push(type_def)
-                        .map_err(|_| WitParseError::TooManyItems)?;
-                }
+    fn flat_count_at(&self, ty: &WitType, depth: usize) -> WitResult<usize> {
+        let ty = self.resolve(ty, depth)?;
+        Ok(match ty {
+            WitType::String | WitType::List(_) => 2,
+            WitType::Tuple(items) => self.fields_flat_count(items.iter(), depth)?,
+            WitType::Record(fields) => {
+                self.fields_flat_count(fields.iter().map(|(_, t)| t), depth)?
+            },
+            WitType::Flags(flags) => flags.len().div_ceil(32),
+            WitType::Option(inner) => 1 + self.flat_count_at(inner, depth + 1)?,
+            WitType::Result { ok, err } => {
+                1 + self.payload_flat_count(&[ok.as_deref(), err.as_deref()], depth)?
+            },
+            WitType::Variant(cases) => {
+                let payloads: Vec<Option<&WitType>> =
+                    cases.iter().map(|(_, payload)| payload.as_ref()).collect();
+                1 + self.payload_flat_count(&payloads, depth)?
+            },
+            WitType::Named(_) => unreachable!("resolve() never returns a named type"),
+            _ => 1,
+        })
+    }
 
-                i += 1;
-            }
+    fn fields_flat_count<'a>(
+        &self,
+        fields: impl Iterator<Item = &'a WitType>,
+        depth: usize,
+    ) -> WitResult<usize> {
+        let mut count = 0;
+        for field in fields {
+            count += self.flat_count_at(field, depth + 1)?;
         }
+        Ok(count)
+    }
 
-        Ok(world)
+    /// Cases of a variant share their flattened payload slots
+    fn payload_flat_count(&self, cases: &[Option<&WitType>], depth: usize) -> WitResult<usize> {
+        let mut count = 0;
+        for payload in cases.iter().flatten() {
+            count = count.max(self.flat_count_at(payload, depth + 1)?);
+        }
+        Ok(count)
     }
 
-    /// Parse a WIT interface definition from source code
-    pub fn parse_interface(&mut self, source: &str) -> Result<WitInterface<P>, WitParseError<P>> {
-        let mut interface = WitInterface {
-            name: BoundedString::default(),
-            functions: BoundedVec::default(),
-            types: BoundedVec::default(),
+    /// Look up a function by `interface#name` or by its bare name
+    ///
+    /// A bare name must identify exactly one function in the document.
+    pub fn function(&self, name: &str) -> WitResult<&WitFunction> {
+        let matches: Vec<&WitFunction> = match name.split_once('#') {
+            Some((interface, function)) => self
+                .functions
+                .iter()
+                .filter(|f| f.name == function && f.interface.as_deref() == Some(interface))
+                .collect(),
+            None => self.functions.iter().filter(|f| f.name == name).collect(),
         };
 
-        #[cfg(feature = "std")]
-        {
-            let lines: Vec<&str> = source.lines().collect();
-            let mut i = 0;
-
-            while i < lines.len() {
-                let line = lines[i].trim();
-                
-                if line.is_empty() || line.starts_with("//") {
-                    i += 1;
-                    continue;
-                }
+        match matches.as_slice() {
+            [function] => Ok(function),
+            [] => Err(WitError::new(format!(
+                "No WIT signature for function '{}'",
+                name
+            ))),
+            _ => Err(WitError::new(format!(
+                "Function name '{}' is ambiguous; qualify it as 'interface#{}'",
+                name, name
+            ))),
+        }
+    }
 
-                if line.starts_with("interface ") {
-                    let name = self.extract_identifier(line, "interface ")?;
-                    interface.name = name;
-                } else if line.contains(":") && (line.contains("func") || line.contains("->")) {
-                    let function = self.parse_function(line)?;
-                    interface.functions.push(function)
-                        .map_err(|_| WitParseError::TooManyItems)?;
-                } else if line.starts_with("type ") {
-                    let type_def = self.parse_type_def(line)?;
-                    interface.types.push(type_def)
-                        .map_err(|_| WitParseError::TooManyItems)?;
-                }
+    /// Follow named type references to a structural type
+    ///
+    /// # Errors
+    ///
+    /// Fails if a referenced type is undefined or references nest too
+    /// deeply, as recursive types do.
+    pub fn resolve_type<'a>(&'a self, ty: &'a WitType) -> WitResult<&'a WitType> {
+        self.resolve(ty, 0)
+    }
 
-                i += 1;
-            }
+    fn resolve<'a>(&'a self, ty: &'a WitType, depth: usize) -> WitResult<&'a WitType> {
+        match ty {
+            WitType::Named(name) => {
+                if depth >= MAX_TYPE_DEPTH {
+                    return Err(WitError::new(format!(
+                        "Type '{}' is nested too deeply or recursive",
+                        name
+                    )));
+                }
+                let target = self
+                    .types
+                    .get(name)
+                    .ok_or_else(|| WitError::new(format!("Undefined WIT type '{}'", name)))?;
+                self.resolve(target, depth + 1)
+            },
+            other => Ok(other),
         }
+    }
 
-        Ok(interface)
+    /// Canonical ABI `(size, alignment)` of a type in linear memory
+    pub fn layout(&self, ty: &WitType) -> WitResult<(usize, usize)> {
+        self.layout_at(ty, 0)
     }
 
-    fn parse_import(&mut self, line: &str) -> Result<WitImport, WitParseError> {
-        #[cfg(feature = "std")]
-        {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid import syntax", self.provider.clone()).unwrap()
-                ));
-            }
+    fn layout_at(&self, ty: &WitType, depth: usize) -> WitResult<(usize, usize)> {
+        let ty = self.resolve(ty, depth)?;
+        Ok(match ty {
+            WitType::Bool | WitType::U8 | WitType::S8 => (1, 1),
+            WitType::U16 | WitType::S16 => (2, 2),
+            WitType::U32 | WitType::S32 | WitType::F32 | WitType::Char | WitType::Handle(_) => {
+                (4, 4)
+            },
+            WitType::U64 | WitType::S64 | WitType::F64 => (8, 8),
+            WitType::String | WitType::List(_) => (8, 4),
+            WitType::Tuple(items) => self.fields_layout(items.iter(), depth)?,
+            WitType::Record(fields) => self.fields_layout(fields.iter().map(|(_, t)| t), depth)?,
+            WitType::Enum(cases) => {
+                let size = discriminant_size(cases.len());
+                (size, size)
+            },
+            WitType::Flags(flags) => match flags.len() {
+                0 => (0, 1),
+                1..=8 => (1, 1),
+                9..=16 => (2, 2),
+                n => (4 * n.div_ceil(32), 4),
+            },
+            WitType::Option(inner) => self.variant_layout(&[None, Some(inner.as_ref())], depth)?,
+            WitType::Result { ok, err } => {
+                self.variant_layout(&[ok.as_deref(), err.as_deref()], depth)?
+            },
+            WitType::Variant(cases) => {
+                let payloads: Vec<Option<&WitType>> =
+                    cases.iter().map(|(_, payload)| payload.as_ref()).collect();
+                self.variant_layout(&payloads, depth)?
+            },
+            WitType::Named(_) => unreachable!("resolve() never returns a named type"),
+        })
+    }
+
+    fn fields_layout<'a>(
+        &self,
+        fields: impl Iterator<Item = &'a WitType>,
+        depth: usize,
+    ) -> WitResult<(usize, usize)> {
+        let mut size = 0;
+        let mut align = 1;
+        for field in fields {
+            let (field_size, field_align) = self.layout_at(field, depth + 1)?;
+            size = align_to(size, field_align) + field_size;
+            align = align.max(field_align);
+        }
+        Ok((align_to(size, align), align))
+    }
 
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
+    /// Layout of a variant: `(size, alignment)`; the payload starts at
+    /// `align_to(discriminant_size, max_case_alignment)`
+    fn variant_layout(
+        &self,
+        cases: &[Option<&WitType>],
+        depth: usize,
+    ) -> WitResult<(usize, usize)> {
+        let (payload_size, payload_align) = self.payload_layout(cases, depth)?;
+        let disc = discriminant_size(cases.len());
+        let align = disc.max(payload_align);
+        let size = align_to(align_to(disc, payload_align) + payload_size, align);
+        Ok((size, align))
+    }
 
-            let item_type = parts[2];
-            let item = match item_type {
-                "func" => {
-                    let func = self.parse_function(line)?;
-                    WitItem::Function(func)
-                }
-                _ => {
-                    return Err(WitParseError::InvalidSyntax(
-                        BoundedString::try_from_str("Unsupported import type", self.provider.clone()).unwrap()
-                    ));
-                }
-            };
+    /// `(size, alignment)` of the payload area of a variant with the given
+    /// case payloads; the payload starts at the discriminant rounded up to
+    /// the alignment
+    pub fn payload_layout_of(&self, cases: &[Option<&WitType>]) -> WitResult<(usize, usize)> {
+        self.payload_layout(cases, 0)
+    }
 
-            Ok(WitImport { name, item })
+    fn payload_layout(
+        &self,
+        cases: &[Option<&WitType>],
+        depth: usize,
+    ) -> WitResult<(usize, usize)> {
+        let mut size = 0;
+        let mut align = 1;
+        for payload in cases.iter().flatten() {
+            let (case_size, case_align) = self.layout_at(payload, depth + 1)?;
+            size = size.max(case_size);
+            align = align.max(case_align);
         }
-        
-        #[cfg(not(any(feature = "std", )))]
-        {
-            // Parse import statement using no_std compatible approach
-            let mut parts = BoundedVec::new(self.provider.clone()).map_err(|_| WitParseError::InvalidSyntax(
-                BoundedString::try_from_str("Failed to create parts vector", self.provider.clone()).unwrap()
-            ))?;
-            
-            // Split whitespace manually for no_std
-            let mut start = 0;
-            let bytes = line.as_bytes();
-            
-            for (i, &byte) in bytes.iter().enumerate() {
-                if byte.is_ascii_whitespace() {
-                    if start < i {
-                        if let Ok(part) = core::str::from_utf8(&bytes[start..i]) {
-                            if parts.push(part).is_err() {
-                                return Err(WitParseError::InvalidSyntax(
-                                    BoundedString::try_from_str("Too many import parts", self.provider.clone()).unwrap()
-                                ));
-                            }
-                        }
+        Ok((size, align))
+    }
+}
+
+/// Size in bytes of the discriminant for a variant with `cases` cases
+pub fn discriminant_size(cases: usize) -> usize {
+    match cases {
+        0..=256 => 1,
+        257..=65536 => 2,
+        _ => 4,
+    }
+}
+
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Arrow,
+}
+
+fn tokenize(source: &str) -> WitResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {},
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
                     }
-                    start = i + 1;
                 }
-            }
-            
-            // Add the last part
-            if start < bytes.len() {
-                if let Ok(part) = core::str::from_utf8(&bytes[start..]) {
-                    let _ = parts.push(part);
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => prev = c,
+                        None => {
+                            return Err(WitError::new("Unterminated block comment in WIT"));
+                        },
+                    }
                 }
-            }
-            
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid import syntax", self.provider.clone()).unwrap()
-                ));
-            }
-
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
-
-            let item_type = parts[2];
-            let item = match item_type {
-                "func" => {
-                    let func = self.parse_function(line)?;
-                    WitItem::Function(func)
+            },
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            },
+            c if c.is_alphanumeric() || c == '%' || c == '_' => {
+                let mut ident = String::new();
+                if c != '%' {
+                    ident.push(c);
                 }
-                _ => {
-                    return Err(WitParseError::InvalidSyntax(
-                        BoundedString::try_from_str("Unsupported import type", self.provider.clone()).unwrap()
-                    ));
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '-' || next == '_' {
+                        ident.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
                 }
-            };
-
-            Ok(WitImport { name, item })
+                tokens.push(Token::Ident(ident));
+            },
+            c => tokens.push(Token::Punct(c)),
         }
     }
 
-    fn parse_export(&mut self, line: &str) -> Result<WitExport, WitParseError> {
-        #[cfg(feature = "std")]
-        {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid export syntax", self.provider.clone()).unwrap()
-                ));
-            }
+    Ok(tokens)
+}
 
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
+struct WitParser {
+    tokens: Vec<Token>,
+    pos:    usize,
+    doc:    WitDocument,
+}
 
-            let item_type = parts[2];
-            let item = match item_type {
-                "func" => {
-                    let func = self.parse_function(line)?;
-                    WitItem::Function(func)
-                }
-                _ => {
-                    return Err(WitParseError::InvalidSyntax(
-                        BoundedString::try_from_str("Unsupported export type", self.provider.clone()).unwrap()
-                    ));
-                }
-            };
+impl WitParser {
+    fn error(&self, message: &str) -> WitError {
+        WitError::new(format!(
+            "WIT parse error at token {}: {}",
+            self.pos, message
+        ))
+    }
 
-            Ok(WitExport { name, item })
-        }
-        
-        #[cfg(not(any(feature = "std", )))]
-        {
-            // Parse export statement using no_std compatible approach
-            let mut parts = BoundedVec::new(self.provider.clone()).map_err(|_| WitParseError::InvalidSyntax(
-                BoundedString::try_from_str("Failed to create parts vector", self.provider.clone()).unwrap()
-            ))?;
-            
-            // Split whitespace manually for no_std
-            let mut start = 0;
-            let bytes = line.as_bytes);
-            
-            for (i, &byte) in bytes.iter().enumerate() {
-                if byte.is_ascii_whitespace() {
-                    if start < i {
-                        if let Ok(part) = core::str::from_utf8(&bytes[start..i]) {
-                            if parts.push(part).is_err() {
-                                return Err(WitParseError::InvalidSyntax(
-                                    BoundedString::try_from_str("Too many export parts", self.provider.clone()).unwrap()
-                                ;
-                            }
-                        }
-                    }
-                    start = i + 1;
-                }
-            }
-            
-            // Add the last part
-            if start < bytes.len() {
-                if let Ok(part) = core::str::from_utf8(&bytes[start..]) {
-                    let _ = parts.push(part);
-                }
-            }
-            
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid export syntax", self.provider.clone()).unwrap()
-                ;
-            }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
 
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
 
-            let item_type = parts[2];
-            let item = match item_type {
-                "func" => {
-                    let func = self.parse_function(line)?;
-                    WitItem::Function(func)
-                }
-                _ => {
-                    return Err(WitParseError::InvalidSyntax(
-                        BoundedString::try_from_str("Unsupported export type", self.provider.clone()).unwrap()
-                    ;
-                }
-            };
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
 
-            Ok(WitExport { name, item })
+    fn expect_punct(&mut self, c: char) -> WitResult<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
         }
     }
 
-    fn parse_function(&mut self, line: &str) -> Result<WitFunction, WitParseError> {
-        let mut function = WitFunction {
-            name: BoundedString::try_from_str("", self.provider.clone()).unwrap_or_default(),
-            params: BoundedVec::new(self.provider.clone()).unwrap_or_default(),
-            results: BoundedVec::new(self.provider.clone()).unwrap_or_default(),
-            is_async: line.contains("async"),
-        };
+    fn ident(&mut self) -> WitResult<String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            _ => Err(self.error("expected identifier")),
+        }
+    }
 
-        #[cfg(feature = "std")]
-        if let Some(colon_pos) = line.find(':') {
-            let name_part = &line[..colon_pos].trim);
-            let parts: Vec<&str> = name_part.split_whitespace().collect();
-            
-            if let Some(name) = parts.last() {
-                function.name = BoundedString::try_from_str(name, self.provider.clone())
-                    .map_err(|_| WitParseError::InvalidIdentifier(
-                        BoundedString::try_from_str(name, self.provider.clone()).unwrap_or_default()
-                    ))?;
+    fn skip_statement(&mut self) -> WitResult<()> {
+        while let Some(token) = self.next() {
+            if token == Token::Punct(';') {
+                return Ok(());
             }
         }
-
-        Ok(function)
+        Err(self.error("expected ';'"))
     }
 
-    fn parse_type_def(&mut self, line: &str) -> Result<WitTypeDef, WitParseError> {
-        #[cfg(feature = "std")]
-        {
-            let parts: Vec<&str> = line.splitn(3, ' ').collect();
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid type definition", self.provider.clone()).unwrap()
-                ;
+    /// Text of the tokens up to the next `;`, which is consumed
+    fn statement_text(&mut self) -> WitResult<String> {
+        let mut text = String::new();
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct(';') => return Ok(text),
+                Token::Punct(c) => text.push(c),
+                Token::Ident(ident) => text.push_str(&ident),
+                Token::Arrow => text.push_str("->"),
             }
-
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
-
-            let type_str = parts[2];
-            let is_resource = type_str.starts_with("resource";
-            
-            let ty = self.parse_type(type_str)?;
-
-            Ok(WitTypeDef {
-                name: name.clone(),
-                ty: ty.clone(),
-                is_resource,
-            })
         }
-        
-        #[cfg(not(any(feature = "std", )))]
-        {
-            // Parse type definition using no_std compatible approach
-            let mut parts = BoundedVec::new(self.provider.clone()).map_err(|_| WitParseError::InvalidSyntax(
-                BoundedString::try_from_str("Failed to create parts vector", self.provider.clone()).unwrap()
-            ))?;
-            
-            // Split by space for type definition (e.g., "type name value")
-            let mut start = 0;
-            let bytes = line.as_bytes);
-            let mut part_count = 0;
-            
-            for (i, &byte) in bytes.iter().enumerate() {
-                if byte == b' ' && part_count < 2 {
-                    if start < i {
-                        if let Ok(part) = core::str::from_utf8(&bytes[start..i]) {
-                            if parts.push(part).is_err() {
-                                return Err(WitParseError::InvalidSyntax(
-                                    BoundedString::try_from_str("Too many type def parts", self.provider.clone()).unwrap()
-                                ;
-                            }
-                            part_count += 1;
-                        }
-                    }
-                    start = i + 1;
-                }
-            }
-            
-            // Add the remaining part (the type definition)
-            if start < bytes.len() {
-                if let Ok(part) = core::str::from_utf8(&bytes[start..]) {
-                    let _ = parts.push(part);
-                }
-            }
-            
-            if parts.len() < 3 {
-                return Err(WitParseError::InvalidSyntax(
-                    BoundedString::try_from_str("Invalid type definition", self.provider.clone()).unwrap()
-                ;
+        Err(self.error("expected ';'"))
+    }
+
+    fn skip_block(&mut self) -> WitResult<()> {
+        self.expect_punct('{')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::Punct('{')) => depth += 1,
+                Some(Token::Punct('}')) => depth -= 1,
+                Some(_) => {},
+                None => return Err(self.error("unterminated block")),
             }
+        }
+        Ok(())
+    }
 
-            let name = BoundedString::try_from_str(parts[1], self.provider.clone())
-                .map_err(|_| WitParseError::InvalidIdentifier(
-                    BoundedString::try_from_str(parts[1], self.provider.clone()).unwrap_or_default()
-                ))?;
-
-            let type_str = parts[2];
-            let is_resource = type_str.len() >= 8 && &type_str[..8] == "resource"; // Simplified starts_with for no_std
-            
-            let ty = self.parse_type(type_str)?;
-
-            Ok(WitTypeDef {
-                name: name.clone(),
-                ty: ty.clone(),
-                is_resource,
-            })
+    fn parse_document(&mut self) -> WitResult<()> {
+        while let Some(token) = self.next() {
+            match token {
+                Token::Ident(kw) if kw == "package" => {
+                    self.doc.package = Some(self.statement_text()?);
+                },
+                Token::Ident(kw) if kw == "use" => self.parse_use(None)?,
+                Token::Ident(kw) if kw == "interface" => {
+                    let name = self.ident()?;
+                    self.parse_items(Some(&name))?;
+                    self.doc.interfaces.push(name);
+                },
+                Token::Ident(kw) if kw == "world" => {
+                    let name = self.ident()?;
+                    self.parse_world(name)?;
+                },
+                _ => return Err(self.error("expected 'package', 'interface' or 'world'")),
+            }
         }
+        Ok(())
     }
 
-    fn parse_type(&mut self, type_str: &str) -> Result<WitType, WitParseError> {
-        let type_str = type_str.trim);
-        
-        match type_str {
-            "bool" => Ok(WitType::Bool),
-            "u8" => Ok(WitType::U8),
-            "u16" => Ok(WitType::U16),
-            "u32" => Ok(WitType::U32),
-            "u64" => Ok(WitType::U64),
-            "s8" => Ok(WitType::S8),
-            "s16" => Ok(WitType::S16),
-            "s32" => Ok(WitType::S32),
-            "s64" => Ok(WitType::S64),
-            "f32" => Ok(WitType::F32),
-            "f64" => Ok(WitType::F64),
-            "char" => Ok(WitType::Char),
-            "string" => Ok(WitType::String),
-            _ => {
-                #[cfg(feature = "std")]
-                {
-                    if type_str.starts_with("list<") && type_str.ends_with(">") {
-                        let inner = &type_str[5..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::List(Box::new(inner_type)))
-                    } else if type_str.starts_with("option<") && type_str.ends_with(">") {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Option(Box::new(inner_type)))
-                    } else if type_str.starts_with("stream<") && type_str.ends_with(">") {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Stream(Box::new(inner_type)))
-                    } else if type_str.starts_with("future<") && type_str.ends_with(">") {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Future(Box::new(inner_type)))
-                    } else {
-                        let name = BoundedString::try_from_str(type_str, self.provider.clone())
-                            .map_err(|_| WitParseError::InvalidIdentifier(
-                                BoundedString::try_from_str(type_str, self.provider.clone()).unwrap_or_default()
-                            ))?;
-                        Ok(WitType::Named(name))
-                    }
-                }
-                
-                #[cfg(not(any(feature = "std", )))]
-                {
-                    // Handle complex types in no_std environments
-                    let bytes = type_str.as_bytes);
-                    
-                    // Check for list<T>
-                    if bytes.len() > 6 && &bytes[..5] == b"list<" && bytes[bytes.len()-1] == b'>' {
-                        let inner = &type_str[5..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::List(Box::new(inner_type)))
-                    }
-                    // Check for option<T>
-                    else if bytes.len() > 8 && &bytes[..7] == b"option<" && bytes[bytes.len()-1] == b'>' {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Option(Box::new(inner_type)))
-                    }
-                    // Check for stream<T>
-                    else if bytes.len() > 8 && &bytes[..7] == b"stream<" && bytes[bytes.len()-1] == b'>' {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Stream(Box::new(inner_type)))
-                    }
-                    // Check for future<T>
-                    else if bytes.len() > 8 && &bytes[..7] == b"future<" && bytes[bytes.len()-1] == b'>' {
-                        let inner = &type_str[7..type_str.len()-1];
-                        let inner_type = self.parse_type(inner)?;
-                        Ok(WitType::Future(Box::new(inner_type)))
+    fn parse_world(&mut self, name: String) -> WitResult<()> {
+        let mut world = WitWorld {
+            name,
+            imports: Vec::new(),
+            exports: Vec::new(),
+            includes: Vec::new(),
+        };
+
+        self.expect_punct('{')?;
+        while !self.eat_punct('}') {
+            match self.peek() {
+                Some(Token::Ident(kw)) if kw == "import" || kw == "export" => {
+                    let items =
+                        if kw == "import" { &mut world.imports } else { &mut world.exports };
+                    self.pos += 1;
+                    let name = self.ident()?;
+                    if !self.eat_punct(':') {
+                        // `import logger;` reference to an interface of this document
+                        self.skip_statement()?;
+                        items.push(WitWorldItem::Interface(name));
+                        continue;
                     }
-                    // Handle named types
-                    else {
-                        let name = BoundedString::try_from_str(type_str, self.provider.clone())
-                            .map_err(|_| WitParseError::InvalidIdentifier(
-                                BoundedString::try_from_str(type_str, self.provider.clone()).unwrap_or_default()
-                            ))?;
-                        Ok(WitType::Named(name))
+                    match self.peek() {
+                        Some(Token::Ident(kw)) if kw == "interface" => {
+                            self.pos += 1;
+                            self.parse_items(Some(&name))?;
+                            items.push(WitWorldItem::Interface(name));
+                        },
+                        Some(Token::Ident(kw)) if kw == "func" || kw == "async" => {
+                            self.parse_function(name.clone(), None)?;
+                            items.push(WitWorldItem::Function(name));
+                        },
+                        // `import wasi:cli/stdout;` style interface reference
+                        _ => {
+                            let path = self.statement_text()?;
+                            items.push(WitWorldItem::External(format!("{}:{}", name, path)));
+                        },
                     }
-                }
+                },
+                Some(Token::Ident(kw)) if kw == "include" => {
+                    self.pos += 1;
+                    world.includes.push(self.statement_text()?);
+                },
+                Some(Token::Ident(kw)) if kw == "use" => {
+                    self.pos += 1;
+                    self.parse_use(Some(&world.name))?;
+                },
+                Some(_) => self.parse_item(None)?,
+                None => return Err(self.error("unterminated world")),
+            }
+        }
+        self.doc.worlds.push(world);
+        Ok(())
+    }
+
+    fn parse_items(&mut self, interface: Option<&str>) -> WitResult<()> {
+        self.expect_punct('{')?;
+        while !self.eat_punct('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unterminated interface"));
             }
+            self.parse_item(interface)?;
         }
+        Ok(())
     }
 
-    fn extract_identifier(&self, line: &str, prefix: &str) -> Result<WitBoundedString, WitParseError> {
-        let remaining = line.strip_prefix(prefix)
-            .ok_or_else(|| WitParseError::InvalidSyntax(
-                BoundedString::try_from_str("Missing prefix", self.provider.clone()).unwrap()
-            ))?;
-        
-        let identifier = remaining.split_whitespace().next()
-            .ok_or_else(|| WitParseError::InvalidSyntax(
-                BoundedString::try_from_str("Missing identifier", self.provider.clone()).unwrap()
-            ))?;
-
-        BoundedString::try_from_str(identifier, self.provider.clone())
-            .map_err(|_| WitParseError::InvalidIdentifier(
-                BoundedString::try_from_str(identifier, self.provider.clone()).unwrap_or_default()
-            ))
-    }
-
-    #[cfg(feature = "std")]
-    /// Convert a WIT type to a WebAssembly value type
-    pub fn convert_to_valtype(&self, wit_type: &WitType) -> Result<crate::types::ValueType, Error> {
-        match wit_type {
-            WitType::Bool | WitType::U8 | WitType::U16 | WitType::U32 | WitType::U64 |
-            WitType::S8 | WitType::S16 | WitType::S32 | WitType::S64 => {
-                Ok(crate::types::ValueType::I32) // WIT integers map to i32 in core wasm
+    fn parse_item(&mut self, interface: Option<&str>) -> WitResult<()> {
+        let keyword = self.ident()?;
+        match keyword.as_str() {
+            "use" => self.parse_use(interface),
+            "type" => {
+                let name = self.ident()?;
+                self.expect_punct('=')?;
+                let ty = self.parse_type()?;
+                self.expect_punct(';')?;
+                self.doc.types.insert(name, ty);
+                Ok(())
             },
-            WitType::F32 => Ok(crate::types::ValueType::F32),
-            WitType::F64 => Ok(crate::types::ValueType::F64),
-            WitType::Char | WitType::String => {
-                Ok(crate::types::ValueType::I32) // Strings/chars are represented as pointers
+            "record" => {
+                let name = self.ident()?;
+                let fields = self.parse_braced(|p| {
+                    let field = p.ident()?;
+                    p.expect_punct(':')?;
+                    Ok((field, p.parse_type()?))
+                })?;
+                self.doc.types.insert(name, WitType::Record(fields));
+                Ok(())
             },
-            WitType::List(_) | WitType::Option(_) => {
-                // Complex types are represented as references in core wasm
-                Ok(crate::types::ValueType::I32)
+            "enum" => {
+                let name = self.ident()?;
+                let cases = self.parse_braced(WitParser::ident)?;
+                self.doc.types.insert(name, WitType::Enum(cases));
+                Ok(())
+            },
+            "flags" => {
+                let name = self.ident()?;
+                let flags = self.parse_braced(WitParser::ident)?;
+                self.doc.types.insert(name, WitType::Flags(flags));
+                Ok(())
+            },
+            "variant" => {
+                let name = self.ident()?;
+                let cases = self.parse_braced(|p| {
+                    let case = p.ident()?;
+                    let payload = if p.eat_punct('(') {
+                        let ty = p.parse_type()?;
+                        p.expect_punct(')')?;
+                        Some(ty)
+                    } else {
+                        None
+                    };
+                    Ok((case, payload))
+                })?;
+                self.doc.types.insert(name, WitType::Variant(cases));
+                Ok(())
+            },
+            "resource" => {
+                let name = self.ident()?;
+                if !self.eat_punct(';') {
+                    self.skip_block()?;
+                }
+                self.doc.types.insert(name.clone(), WitType::Handle(name));
+                Ok(())
+            },
+            _ => {
+                self.expect_punct(':')?;
+                self.parse_function(keyword, interface)
             },
-            _ => Err(Error::parse_error("Unsupported WIT type conversion")),
         }
     }
-}
 
-impl Default for WitParser {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    /// Parse a `use` statement after the keyword
+    fn parse_use(&mut self, scope: Option<&str>) -> WitResult<()> {
+        let mut from = String::new();
+        loop {
+            match self.peek() {
+                Some(Token::Punct('.'))
+                    if self.tokens.get(self.pos + 1) == Some(&Token::Punct('{')) =>
+                {
+                    self.pos += 1;
+                    break;
+                },
+                Some(Token::Punct(';')) => break,
+                Some(Token::Ident(kw)) if kw == "as" => break,
+                Some(Token::Punct(c)) => from.push(*c),
+                Some(Token::Ident(ident)) => from.push_str(ident),
+                Some(Token::Arrow) | None => return Err(self.error("malformed 'use'")),
+            }
+            self.pos += 1;
+        }
 
-impl fmt::Display for WitParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            WitParseError::UnexpectedEnd => write!(f, "Unexpected end of input"),
-            WitParseError::InvalidSyntax(msg) => {
-                write!(f, "Invalid syntax: {}", msg.as_str().unwrap_or("<invalid>"))
+        let names = if self.peek() == Some(&Token::Punct('{')) {
+            self.parse_braced(|p| {
+                let name = p.ident()?;
+                let local = if matches!(p.peek(), Some(Token::Ident(kw)) if kw == "as") {
+                    p.pos += 1;
+                    p.ident()?
+                } else {
+                    name.clone()
+                };
+                Ok((name, local))
+            })?
+        } else {
+            // `use pkg:name/iface as alias;` only names the interface
+            if matches!(self.peek(), Some(Token::Ident(kw)) if kw == "as") {
+                self.pos += 2;
             }
-            WitParseError::UnknownType(name) => {
-                write!(f, "Unknown type: {}", name.as_str().unwrap_or("<invalid>"))
+            Vec::new()
+        };
+        self.expect_punct(';')?;
+
+        self.doc.uses.push(WitUse {
+            scope: scope.map(str::to_string),
+            from,
+            names,
+        });
+        Ok(())
+    }
+
+    fn parse_braced<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> WitResult<T>,
+    ) -> WitResult<Vec<T>> {
+        self.expect_punct('{')?;
+        let mut items = Vec::new();
+        while !self.eat_punct('}') {
+            items.push(item(self)?);
+            if !self.eat_punct(',') {
+                self.expect_punct('}')?;
+                break;
             }
-            WitParseError::TooManyItems => write!(f, "Too many items"),
-            WitParseError::InvalidIdentifier(name) => {
-                write!(f, "Invalid identifier: {}", name.as_str().unwrap_or("<invalid>"))
+        }
+        Ok(items)
+    }
+
+    fn parse_function(&mut self, name: String, interface: Option<&str>) -> WitResult<()> {
+        if matches!(self.peek(), Some(Token::Ident(kw)) if kw == "async") {
+            self.pos += 1;
+        }
+        match self.ident()?.as_str() {
+            "func" => {},
+            _ => return Err(self.error("expected 'func'")),
+        }
+
+        let params = self.parse_named_list()?;
+        let results = if self.peek() == Some(&Token::Arrow) {
+            self.pos += 1;
+            if self.peek() == Some(&Token::Punct('(')) {
+                self.parse_named_list()?
+            } else {
+                vec![("result".to_string(), self.parse_type()?)]
             }
-            WitParseError::DuplicateDefinition(name) => {
-                write!(f, "Duplicate definition: {}", name.as_str().unwrap_or("<invalid>"))
+        } else {
+            Vec::new()
+        };
+        self.expect_punct(';')?;
+
+        self.doc.functions.push(WitFunction {
+            name,
+            interface: interface.map(str::to_string),
+            params,
+            results,
+        });
+        Ok(())
+    }
+
+    fn parse_named_list(&mut self) -> WitResult<Vec<(String, WitType)>> {
+        self.expect_punct('(')?;
+        let mut items = Vec::new();
+        while !self.eat_punct(')') {
+            let name = self.ident()?;
+            self.expect_punct(':')?;
+            items.push((name, self.parse_type()?));
+            if !self.eat_punct(',') {
+                self.expect_punct(')')?;
+                break;
             }
         }
+        Ok(items)
+    }
+
+    fn parse_type(&mut self) -> WitResult<WitType> {
+        let name = self.ident()?;
+        Ok(match name.as_str() {
+            "bool" => WitType::Bool,
+            "u8" => WitType::U8,
+            "u16" => WitType::U16,
+            "u32" => WitType::U32,
+            "u64" => WitType::U64,
+            "s8" => WitType::S8,
+            "s16" => WitType::S16,
+            "s32" => WitType::S32,
+            "s64" => WitType::S64,
+            "f32" | "float32" => WitType::F32,
+            "f64" | "float64" => WitType::F64,
+            "char" => WitType::Char,
+            "string" => WitType::String,
+            "list" => WitType::List(Box::new(self.parse_single_param()?)),
+            "option" => WitType::Option(Box::new(self.parse_single_param()?)),
+            "own" | "borrow" => {
+                self.expect_punct('<')?;
+                let resource = self.ident()?;
+                self.expect_punct('>')?;
+                WitType::Handle(resource)
+            },
+            "tuple" => {
+                self.expect_punct('<')?;
+                let mut items = Vec::new();
+                while !self.eat_punct('>') {
+                    items.push(self.parse_type()?);
+                    if !self.eat_punct(',') {
+                        self.expect_punct('>')?;
+                        break;
+                    }
+                }
+                WitType::Tuple(items)
+            },
+            "result" => {
+                if !self.eat_punct('<') {
+                    return Ok(WitType::Result {
+                        ok:  None,
+                        err: None,
+                    });
+                }
+                let ok = if matches!(self.peek(), Some(Token::Ident(n)) if n == "_") {
+                    self.pos += 1;
+                    None
+                } else {
+                    Some(Box::new(self.parse_type()?))
+                };
+                let err =
+                    if self.eat_punct(',') { Some(Box::new(self.parse_type()?)) } else { None };
+                self.expect_punct('>')?;
+                WitType::Result { ok, err }
+            },
+            _ => WitType::Named(name),
+        })
+    }
+
+    fn parse_single_param(&mut self) -> WitResult<WitType> {
+        self.expect_punct('<')?;
+        let ty = self.parse_type()?;
+        self.expect_punct('>')?;
+        Ok(ty)
     }
 }
 
@@ -604,95 +960,92 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_wit_parser_creation() {
-        let parser = WitParser::new();
-        assert_eq!(parser.current_position, 0);
-        assert_eq!(parser.type_definitions.len(), 0);
-    }
+    const WIT: &str = r#"
+        package example:logging@0.1.0;
 
-    #[test]
-    fn test_parse_basic_types() {
-        let mut parser = WitParser::new();
-        
-        assert_eq!(parser.parse_type("bool").unwrap(), WitType::Bool;
-        assert_eq!(parser.parse_type("u32").unwrap(), WitType::U32;
-        assert_eq!(parser.parse_type("string").unwrap(), WitType::String;
-        assert_eq!(parser.parse_type("f64").unwrap(), WitType::F64;
-    }
+        interface logger {
+            enum level { trace, debug, info, warn, error }
 
-    #[cfg(feature = "std")]
-    #[test]
-    fn test_parse_compound_types() {
-        let mut parser = WitParser::new();
-        
-        let list_type = parser.parse_type("list<u32>").unwrap();
-        match list_type {
-            WitType::List(inner) => assert_eq!(*inner, WitType::U32),
-            _ => panic!("Expected list type"),
-        }
+            record entry {
+                level: level,
+                code: u16,
+                message: string,
+            }
 
-        let option_type = parser.parse_type("option<string>").unwrap();
-        match option_type {
-            WitType::Option(inner) => assert_eq!(*inner, WitType::String),
-            _ => panic!("Expected option type"),
-        }
-    }
+            flags mode { read, write, exec }
 
-    #[cfg(feature = "std")]
-    #[test]
-    fn test_parse_async_types() {
-        let mut parser = WitParser::new();
-        
-        let stream_type = parser.parse_type("stream<u8>").unwrap();
-        match stream_type {
-            WitType::Stream(inner) => assert_eq!(*inner, WitType::U8),
-            _ => panic!("Expected stream type"),
+            /// Log a message
+            log: func(level: level, message: string);
+            submit: func(entry: entry, retry: option<u32>) -> result<u64, level>;
+            set-mode: func(mode: mode);
         }
 
-        let future_type = parser.parse_type("future<string>").unwrap();
-        match future_type {
-            WitType::Future(inner) => assert_eq!(*inner, WitType::String),
-            _ => panic!("Expected future type"),
+        world host {
+            import logger;
+            export run: func() -> s32;
         }
+    "#;
+
+    #[test]
+    fn test_parse_wit_signatures() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        assert_eq!(doc.functions().len(), 4);
+
+        let submit = doc.function("logger#submit").unwrap();
+        assert_eq!(submit.params.len(), 2);
+        assert_eq!(submit.results[0].1.to_string(), "result<u64, level>");
+        assert!(doc.function("run").is_ok());
+        assert!(doc.function("missing").is_err());
     }
 
-    #[cfg(feature = "std")]
     #[test]
-    fn test_parse_simple_world() {
-        let mut parser = WitParser::new();
-        let source = r#"
-            world test-world {
-                import test-func: func()
-                export result-func: func() -> u32
-            }
-        "#;
+    fn test_parse_wit_worlds() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        assert_eq!(doc.package(), Some("example:logging@0.1.0"));
+        assert_eq!(doc.qualified_interface("logger"), "example:logging/logger@0.1.0");
+
+        let world = &doc.worlds()[0];
+        assert_eq!(world.name, "host");
+        assert_eq!(world.imports, vec![WitWorldItem::Interface("logger".to_string())]);
+        assert_eq!(world.exports, vec![WitWorldItem::Function("run".to_string())]);
+
+        let doc = WitDocument::parse(
+            "world cli { import wasi:cli/stdout@0.2.0; import host: interface { f: func(); } }",
+        )
+        .unwrap();
+        assert_eq!(doc.qualified_interface("host"), "host");
+        assert_eq!(
+            doc.worlds()[0].imports,
+            vec![
+                WitWorldItem::External("wasi:cli/stdout@0.2.0".to_string()),
+                WitWorldItem::Interface("host".to_string()),
+            ]
+        );
+    }
 
-        let world = parser.parse_world(source;
-        assert!(world.is_ok());
-        
-        let world = world.unwrap();
-        assert_eq!(world.name.as_str().unwrap(), "test-world";
-        
-        // Import BoundedCapacity trait for len() method
-        use wrt_foundation::traits::BoundedCapacity;
-        assert_eq!(world.imports.len(), 1);
-        assert_eq!(world.exports.len(), 1);
+    #[test]
+    fn test_flat_count() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let submit = doc.function("logger#submit").unwrap();
+        // entry: level + code + (ptr, len); option<u32>: discriminant + payload
+        assert_eq!(doc.flat_count(&submit.params[0].1).unwrap(), 4);
+        assert_eq!(doc.flat_count(&submit.params[1].1).unwrap(), 2);
+        assert_eq!(doc.flat_count(&submit.results[0].1).unwrap(), 2);
+        assert_eq!(doc.flat_count(&WitType::Named("mode".to_string())).unwrap(), 1);
     }
 
-    #[cfg(feature = "std")]
     #[test]
-    fn test_convert_to_valtype() {
-        let parser = WitParser::new();
-        
-        assert_eq!(parser.convert_to_valtype(&WitType::Bool).unwrap(), crate::types::ValueType::I32;
-        assert_eq!(parser.convert_to_valtype(&WitType::U32).unwrap(), crate::types::ValueType::I32;
-        assert_eq!(parser.convert_to_valtype(&WitType::String).unwrap(), crate::types::ValueType::I32;
-        
-        let list_wit = WitType::List(Box::new(WitType::U32;
-        let list_val = parser.convert_to_valtype(&list_wit).unwrap();
-        assert_eq!(list_val, crate::types::ValueType::I32); // Lists are represented as pointers
+    fn test_canonical_layout() {
+        let doc = WitDocument::parse(WIT).unwrap();
+        let entry = WitType::Named("entry".to_string());
+        // level(u8) @0, code(u16) @2, message(ptr,len) @4 -> 12 bytes, align 4
+        assert_eq!(doc.layout(&entry).unwrap(), (12, 4));
+        let option = WitType::Option(Box::new(WitType::U32));
+        assert_eq!(doc.layout(&option).unwrap(), (8, 4));
+        let result = WitType::Result {
+            ok: Some(Box::new(WitType::U64)),
+            err: Some(Box::new(WitType::Named("level".to_string()))),
+        };
+        assert_eq!(doc.layout(&result).unwrap(), (16, 8));
     }
 }
-
-// Trait implementations are already included at the top of the file
\ No newline at end of file
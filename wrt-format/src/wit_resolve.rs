@@ -0,0 +1,652 @@
+//! WIT package resolution
+//!
+//! [`WitDocument`] parses one file. A component's WIT usually spans a
+//! package split over several files plus the packages it depends on, kept
+//! in `deps/` next to it. [`WitPackageSet`] merges the files of each
+//! package, builds the dependency graph between packages from their `use`
+//! statements, external imports and exports and `include`s, rejects
+//! dependency cycles and copies the types named by `use` statements into the
+//! using package. A world of the set then resolves into a [`ResolvedWorld`]:
+//! its imports and exports flattened over `include`s, qualified with their
+//! package, and extended by the interfaces they use types from, which is
+//! what the component linker binds against.
+
+use std::{
+    collections::{
+        BTreeSet,
+        HashSet,
+    },
+    fs,
+    path::Path,
+    string::String,
+    vec::Vec,
+};
+
+use crate::wit_parser::{
+    WitDocument,
+    WitError,
+    WitFunction,
+    WitResult,
+    WitWorldItem,
+};
+
+/// Maximum nesting of world `include`s
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Package and interface an interface reference points to
+///
+/// `wasi:io/streams@0.2.0` refers to `streams` of package `wasi:io@0.2.0`;
+/// a plain `streams` refers to an interface of the referring package.
+fn split_reference(reference: &str) -> (Option<String>, String) {
+    let Some((package, item)) = reference.split_once('/') else {
+        return (None, reference.to_string());
+    };
+    match item.split_once('@') {
+        Some((item, version)) => (Some(format!("{}@{}", package, version)), item.to_string()),
+        None => (Some(package.to_string()), item.to_string()),
+    }
+}
+
+/// Package name without its version
+fn unversioned(package: &str) -> &str {
+    package.split_once('@').map_or(package, |(name, _)| name)
+}
+
+/// Dependencies between the packages of a [`WitPackageSet`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WitDependencyGraph {
+    /// Packages in the order they were added
+    pub packages: Vec<String>,
+    /// `(dependent, dependency)` pairs of indices into `packages`
+    pub edges:    BTreeSet<(usize, usize)>,
+}
+
+impl WitDependencyGraph {
+    /// Packages `package` depends on directly
+    pub fn dependencies(&self, package: &str) -> Vec<&str> {
+        let Some(index) = self.packages.iter().position(|p| p == package) else {
+            return Vec::new();
+        };
+        self.edges
+            .iter()
+            .filter(|(from, _)| *from == index)
+            .map(|(_, to)| self.packages[*to].as_str())
+            .collect()
+    }
+
+    /// Packages ordered so every package comes after its dependencies
+    ///
+    /// # Errors
+    ///
+    /// Fails with the packages of a cycle if the packages depend on each
+    /// other in a cycle.
+    pub fn topological_order(&self) -> WitResult<Vec<String>> {
+        // 0 = unvisited, 1 = on the current path, 2 = done
+        let mut state = vec![0u8; self.packages.len()];
+        let mut path = Vec::new();
+        let mut order = Vec::new();
+        for start in 0..self.packages.len() {
+            self.visit(start, &mut state, &mut path, &mut order)?;
+        }
+        Ok(order.into_iter().map(|index| self.packages[index].clone()).collect())
+    }
+
+    fn visit(
+        &self,
+        index: usize,
+        state: &mut [u8],
+        path: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> WitResult<()> {
+        match state[index] {
+            2 => return Ok(()),
+            1 => {
+                let start = path.iter().position(|&i| i == index).unwrap_or(0);
+                let cycle: Vec<&str> = path[start..]
+                    .iter()
+                    .chain(Some(&index))
+                    .map(|&i| self.packages[i].as_str())
+                    .collect();
+                return Err(WitError::new(format!(
+                    "WIT package dependency cycle: {}",
+                    cycle.join(" -> ")
+                )));
+            },
+            _ => {},
+        }
+        state[index] = 1;
+        path.push(index);
+        for &(_, dependency) in self.edges.iter().filter(|(from, _)| *from == index) {
+            self.visit(dependency, state, path, order)?;
+        }
+        path.pop();
+        state[index] = 2;
+        order.push(index);
+        Ok(())
+    }
+}
+
+/// Whether a resolved world item is an interface or a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ResolvedItemKind {
+    /// Interface, qualified with its package unless declared inline
+    Interface,
+    /// Function declared directly in the world
+    Function,
+}
+
+/// An import or export of a resolved world
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedItem {
+    /// Name the linker binds the item under, such as
+    /// `wasi:cli/stdout@0.2.0`
+    pub name:      String,
+    /// Interface or function
+    pub kind:      ResolvedItemKind,
+    /// Names of the functions the item provides
+    pub functions: Vec<String>,
+}
+
+/// A world with its includes and type dependencies flattened
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedWorld {
+    /// Package declaring the world
+    pub package: String,
+    /// World name
+    pub name:    String,
+    /// Imports, including interfaces used by imports and exports
+    pub imports: Vec<ResolvedItem>,
+    /// Exports
+    pub exports: Vec<ResolvedItem>,
+}
+
+impl ResolvedWorld {
+    /// Import of the given name
+    pub fn import(&self, name: &str) -> Option<&ResolvedItem> {
+        self.imports.iter().find(|item| item.name == name)
+    }
+
+    /// Export of the given name
+    pub fn export(&self, name: &str) -> Option<&ResolvedItem> {
+        self.exports.iter().find(|item| item.name == name)
+    }
+}
+
+/// WIT packages resolved against each other
+#[derive(Debug, Clone, Default)]
+pub struct WitPackageSet {
+    /// One merged document per package, in the order packages were added
+    packages: Vec<WitDocument>,
+}
+
+impl WitPackageSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a WIT file, merging it into the package it declares
+    ///
+    /// Returns the package name.
+    pub fn add_source(&mut self, source: &str) -> WitResult<String> {
+        let document = WitDocument::parse(source)?;
+        let package = document
+            .package()
+            .ok_or_else(|| WitError::new("WIT document declares no package"))?
+            .to_string();
+        match self.packages.iter_mut().find(|doc| doc.package() == Some(package.as_str())) {
+            Some(existing) => existing.merge(document),
+            None => self.packages.push(document),
+        }
+        Ok(package)
+    }
+
+    /// Add the `.wit` files of a directory and the packages in its `deps/`
+    /// directory, which holds one directory or `.wit` file per package
+    ///
+    /// Returns the packages declared by the files of `dir` itself.
+    pub fn add_dir(&mut self, dir: &Path) -> WitResult<Vec<String>> {
+        let mut packages = Vec::new();
+        for path in wit_files(dir)? {
+            let source = fs::read_to_string(&path)
+                .map_err(|e| WitError::new(format!("Failed to read {}: {}", path.display(), e)))?;
+            let package = self
+                .add_source(&source)
+                .map_err(|e| WitError::new(format!("{}: {}", path.display(), e)))?;
+            if !packages.contains(&package) {
+                packages.push(package);
+            }
+        }
+
+        let deps = dir.join("deps");
+        if deps.is_dir() {
+            for entry in sorted_entries(&deps)? {
+                if entry.is_dir() {
+                    self.add_dir(&entry)?;
+                } else if entry.extension().is_some_and(|ext| ext == "wit") {
+                    let source = fs::read_to_string(&entry).map_err(|e| {
+                        WitError::new(format!("Failed to read {}: {}", entry.display(), e))
+                    })?;
+                    self.add_source(&source)?;
+                }
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Merged document of a package
+    pub fn package(&self, package: &str) -> Option<&WitDocument> {
+        self.find(package).map(|index| &self.packages[index])
+    }
+
+    /// Names of all packages in the order they were added
+    pub fn package_names(&self) -> Vec<&str> {
+        self.packages.iter().filter_map(WitDocument::package).collect()
+    }
+
+    /// Index of the package a reference names
+    ///
+    /// An unversioned reference matches the only package of that name.
+    fn find(&self, reference: &str) -> Option<usize> {
+        if let Some(index) = self.packages.iter().position(|doc| doc.package() == Some(reference)) {
+            return Some(index);
+        }
+        if reference.contains('@') {
+            return None;
+        }
+        let mut matches = self
+            .packages
+            .iter()
+            .enumerate()
+            .filter(|(_, doc)| doc.package().map(unversioned) == Some(reference));
+        match (matches.next(), matches.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    fn find_required(&self, reference: &str, from: usize) -> WitResult<usize> {
+        self.find(reference).ok_or_else(|| {
+            WitError::new(format!(
+                "Package {} depends on unknown package {}",
+                self.name(from),
+                reference
+            ))
+        })
+    }
+
+    fn name(&self, index: usize) -> &str {
+        self.packages[index].package().unwrap_or("")
+    }
+
+    /// Build the dependency graph between the packages
+    ///
+    /// # Errors
+    ///
+    /// Fails if a package refers to a package that is not in the set.
+    pub fn dependency_graph(&self) -> WitResult<WitDependencyGraph> {
+        let mut graph = WitDependencyGraph {
+            packages: self.package_names().into_iter().map(str::to_string).collect(),
+            edges:    BTreeSet::new(),
+        };
+        for (index, doc) in self.packages.iter().enumerate() {
+            let uses = doc.uses().iter().map(|u| split_reference(&u.from).0);
+            let items = doc.worlds().iter().flat_map(|world| {
+                let external =
+                    world.imports.iter().chain(&world.exports).filter_map(|item| match item {
+                        WitWorldItem::External(reference) => split_reference(reference).0,
+                        _ => None,
+                    });
+                let includes =
+                    world.includes.iter().filter_map(|include| split_reference(include).0);
+                external.chain(includes).collect::<Vec<_>>()
+            });
+            for reference in uses.flatten().chain(items) {
+                let dependency = self.find_required(&reference, index)?;
+                if dependency != index {
+                    graph.edges.insert((index, dependency));
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Check the references between packages and bring the types named by
+    /// `use` statements into the using package
+    ///
+    /// # Errors
+    ///
+    /// Fails on dependency cycles, unknown packages or interfaces, and used
+    /// types their interface does not define.
+    pub fn resolve(&mut self) -> WitResult<()> {
+        let order = self.dependency_graph()?.topological_order()?;
+        for package in order {
+            let Some(index) = self.find(&package) else {
+                continue;
+            };
+            for used in self.packages[index].uses().to_vec() {
+                let (target_package, interface) = split_reference(&used.from);
+                let target = match &target_package {
+                    Some(reference) => self.find_required(reference, index)?,
+                    None => index,
+                };
+                let target_doc = &self.packages[target];
+                if !target_doc.interfaces().contains(&interface) {
+                    return Err(WitError::new(format!(
+                        "Package {} uses unknown interface {}",
+                        package, used.from
+                    )));
+                }
+                let mut types = Vec::new();
+                for (name, local) in &used.names {
+                    let ty = target_doc.type_def(name).cloned().ok_or_else(|| {
+                        WitError::new(format!(
+                            "Interface {} does not define type {}",
+                            used.from, name
+                        ))
+                    })?;
+                    if target != index || name != local {
+                        types.push((local.clone(), ty));
+                    }
+                }
+                for (local, ty) in types {
+                    self.packages[index].define_type(&local, ty);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Flatten a world of `package` for linking
+    ///
+    /// # Errors
+    ///
+    /// Fails if the package or world is unknown, an item refers to an
+    /// unknown interface or `include`s nest too deep or form a cycle.
+    pub fn resolve_world(&self, package: &str, world: &str) -> WitResult<ResolvedWorld> {
+        let index = self
+            .find(package)
+            .ok_or_else(|| WitError::new(format!("Unknown package {}", package)))?;
+        let mut resolved = ResolvedWorld {
+            package: self.name(index).to_string(),
+            name:    world.to_string(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+        };
+        let mut including = Vec::new();
+        self.flatten_world(index, world, &mut resolved, &mut including)?;
+
+        // Interfaces whose types imports and exports use are imported too
+        let mut pending: Vec<(usize, String)> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for item in resolved.imports.iter().chain(&resolved.exports) {
+            seen.insert(item.name.clone());
+            if item.kind == ResolvedItemKind::Interface {
+                if let Some(origin) = self.interface_origin(index, &item.name) {
+                    pending.push(origin);
+                }
+            }
+        }
+        while let Some((owner, interface)) = pending.pop() {
+            let doc = &self.packages[owner];
+            for used in doc.uses().iter().filter(|u| u.scope.as_deref() == Some(interface.as_str()))
+            {
+                let (target_package, target_interface) = split_reference(&used.from);
+                let target = match &target_package {
+                    Some(reference) => self.find_required(reference, owner)?,
+                    None => owner,
+                };
+                let item = self.interface_item(target, &target_interface)?;
+                if seen.insert(item.name.clone()) {
+                    resolved.imports.push(item);
+                    pending.push((target, target_interface));
+                }
+            }
+        }
+        Ok(resolved)
+    }
+
+    fn flatten_world(
+        &self,
+        index: usize,
+        world: &str,
+        resolved: &mut ResolvedWorld,
+        including: &mut Vec<String>,
+    ) -> WitResult<()> {
+        let key = format!("{}/{}", self.name(index), world);
+        if including.contains(&key) || including.len() >= MAX_INCLUDE_DEPTH {
+            return Err(WitError::new(format!("World {} includes itself", key)));
+        }
+        let doc = &self.packages[index];
+        let definition = doc.worlds().iter().find(|w| w.name == world).ok_or_else(|| {
+            WitError::new(format!(
+                "Package {} has no world {}",
+                self.name(index),
+                world
+            ))
+        })?;
+
+        including.push(key);
+        for include in &definition.includes {
+            let (package, included) = split_reference(include);
+            let target = match &package {
+                Some(reference) => self.find_required(reference, index)?,
+                None => index,
+            };
+            self.flatten_world(target, &included, resolved, including)?;
+        }
+        including.pop();
+
+        for (items, out) in [
+            (&definition.imports, &mut resolved.imports),
+            (&definition.exports, &mut resolved.exports),
+        ] {
+            for item in items {
+                let item = self.world_item(index, item)?;
+                if !out.iter().any(|existing| existing.name == item.name) {
+                    out.push(item);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn world_item(&self, index: usize, item: &WitWorldItem) -> WitResult<ResolvedItem> {
+        match item {
+            WitWorldItem::Interface(name) => self.interface_item(index, name),
+            WitWorldItem::Function(name) => Ok(ResolvedItem {
+                name:      name.clone(),
+                kind:      ResolvedItemKind::Function,
+                functions: vec![name.clone()],
+            }),
+            WitWorldItem::External(reference) => {
+                let (package, interface) = split_reference(reference);
+                let target = self.find_required(package.as_deref().unwrap_or(""), index)?;
+                self.interface_item(target, &interface)
+            },
+        }
+    }
+
+    /// Item for an interface of a package, qualified if it is a top-level
+    /// interface
+    fn interface_item(&self, index: usize, interface: &str) -> WitResult<ResolvedItem> {
+        let doc = &self.packages[index];
+        let functions: Vec<String> = doc
+            .functions()
+            .iter()
+            .filter(|f| f.interface.as_deref() == Some(interface))
+            .map(|f: &WitFunction| f.name.clone())
+            .collect();
+        if !doc.interfaces().iter().any(|name| name == interface) && functions.is_empty() {
+            return Err(WitError::new(format!(
+                "Package {} has no interface {}",
+                self.name(index),
+                interface
+            )));
+        }
+        Ok(ResolvedItem {
+            name: doc.qualified_interface(interface),
+            kind: ResolvedItemKind::Interface,
+            functions,
+        })
+    }
+
+    /// Package and interface name a resolved interface item came from
+    fn interface_origin(&self, world_package: usize, name: &str) -> Option<(usize, String)> {
+        let (package, interface) = split_reference(name);
+        match package {
+            Some(reference) => self.find(&reference).map(|index| (index, interface)),
+            None => Some((world_package, interface)),
+        }
+    }
+}
+
+/// `.wit` files directly in `dir`, sorted by path
+fn wit_files(dir: &Path) -> WitResult<Vec<std::path::PathBuf>> {
+    Ok(sorted_entries(dir)?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "wit"))
+        .collect())
+}
+
+fn sorted_entries(dir: &Path) -> WitResult<Vec<std::path::PathBuf>> {
+    let mut entries = fs::read_dir(dir)
+        .map_err(|e| WitError::new(format!("Failed to read {}: {}", dir.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wit_parser::WitType;
+
+    const IO: &str = r#"
+        package wasi:io@0.2.0;
+
+        interface error {
+            resource error;
+        }
+
+        interface streams {
+            use error.{error as stream-error};
+            resource output-stream;
+            write: func(stream: borrow<output-stream>, bytes: list<u8>) -> result<_, stream-error>;
+        }
+    "#;
+
+    const APP_TYPES: &str = r#"
+        package example:app@1.0.0;
+
+        interface log {
+            use wasi:io/streams@0.2.0.{output-stream};
+            record entry { level: u8, sink: own<output-stream> }
+            log: func(entry: entry);
+        }
+    "#;
+
+    const APP_WORLDS: &str = r#"
+        package example:app@1.0.0;
+
+        world base {
+            import wasi:io/streams@0.2.0;
+        }
+
+        world app {
+            include base;
+            import log;
+            export run: func() -> u32;
+        }
+    "#;
+
+    fn package_set() -> WitPackageSet {
+        let mut set = WitPackageSet::new();
+        set.add_source(APP_TYPES).unwrap();
+        set.add_source(APP_WORLDS).unwrap();
+        set.add_source(IO).unwrap();
+        set
+    }
+
+    #[test]
+    fn test_packages_resolve_in_dependency_order() {
+        let mut set = package_set();
+        assert_eq!(
+            set.package_names(),
+            vec!["example:app@1.0.0", "wasi:io@0.2.0"]
+        );
+
+        let graph = set.dependency_graph().unwrap();
+        assert_eq!(
+            graph.dependencies("example:app@1.0.0"),
+            vec!["wasi:io@0.2.0"]
+        );
+        assert_eq!(
+            graph.topological_order().unwrap(),
+            vec!["wasi:io@0.2.0", "example:app@1.0.0"]
+        );
+
+        set.resolve().unwrap();
+        let app = set.package("example:app").unwrap();
+        assert_eq!(
+            app.type_def("output-stream"),
+            Some(&WitType::Handle("output-stream".to_string()))
+        );
+        let io = set.package("wasi:io@0.2.0").unwrap();
+        assert_eq!(
+            io.type_def("stream-error"),
+            Some(&WitType::Handle("error".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_world_flattens_includes_and_used_interfaces() {
+        let mut set = package_set();
+        set.resolve().unwrap();
+        let world = set.resolve_world("example:app@1.0.0", "app").unwrap();
+
+        let imports: Vec<&str> = world.imports.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(
+            imports,
+            vec![
+                "wasi:io/streams@0.2.0",
+                "example:app/log@1.0.0",
+                "wasi:io/error@0.2.0",
+            ]
+        );
+        assert_eq!(
+            world.import("example:app/log@1.0.0").unwrap().functions,
+            vec!["log"]
+        );
+        assert_eq!(
+            world.export("run").unwrap().kind,
+            ResolvedItemKind::Function
+        );
+    }
+
+    #[test]
+    fn test_cycles_and_unknown_references_are_rejected() {
+        let mut set = WitPackageSet::new();
+        set.add_source("package a:a; interface x { use b:b/y.{t}; }").unwrap();
+        set.add_source("package b:b; interface y { use a:a/x.{u}; type t = u32; }")
+            .unwrap();
+        let error = set.resolve().unwrap_err().to_string();
+        assert!(error.contains("cycle: a:a -> b:b -> a:a"), "{}", error);
+
+        let mut set = WitPackageSet::new();
+        set.add_source("package a:a; interface x { use c:c/y.{t}; }").unwrap();
+        assert!(set.dependency_graph().is_err());
+
+        let mut set = WitPackageSet::new();
+        set.add_source("package a:a; interface x { use y.{t}; } interface y { }")
+            .unwrap();
+        assert!(set.resolve().is_err());
+        assert!(set.add_source("interface z { }").is_err());
+    }
+}
@@ -0,0 +1,769 @@
+// WRT - wrt-foundation
+// Module: Scoped arena allocator
+// SW-REQ-ID: REQ_MEMORY_001
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+#![allow(unsafe_code)] // Hands out disjoint blocks of a region owned by a scope
+
+//! Scoped arena allocation with deterministic reset.
+//!
+//! [`with_scope`] reserves a block of the crate's budget from its
+//! [`VerifiedAllocator`](crate::verified_allocator::VerifiedAllocator) and
+//! passes an [`Arena`] over it to a closure. Providers created from the arena
+//! are carved off the block by bumping an offset, and all of them are freed
+//! at once when the closure returns by resetting the allocator to its
+//! checkpoint. Nothing is released individually, so a request handler that
+//! runs in a scope leaves no fragmentation behind.
+//!
+//! ```rust,no_run
+//! use wrt_foundation::{
+//!     allocator::{arena, CrateId},
+//!     safe_memory::Provider,
+//! };
+//!
+//! let checksum = arena::with_scope(CrateId::Runtime, 4096, |arena| {
+//!     let mut request = arena.provider(1024)?;
+//!     request.write_data(0, b"payload")?;
+//!
+//!     // Scratch space released as soon as the nested scope ends
+//!     arena.with_scope(512, |scratch| scratch.provider(256).map(|p| p.capacity()))??;
+//!     Ok::<_, wrt_error::Error>(request.size())
+//! })??;
+//! # Ok::<(), wrt_error::Error>(())
+//! ```
+//!
+//! Scopes nest at most [`MAX_SCOPE_DEPTH`] deep. The depth is part of the
+//! arena's type, so nesting deeper does not compile:
+//!
+//! ```compile_fail
+//! use wrt_foundation::allocator::{arena, CrateId};
+//!
+//! arena::with_scope(CrateId::Runtime, 4096, |a1| {
+//!     a1.with_scope(1024, |a2| {
+//!         a2.with_scope(512, |a3| {
+//!             a3.with_scope(256, |a4| a4.with_scope(64, |_a5| ()))
+//!         })
+//!     })
+//! });
+//! ```
+//!
+//! A top-level scope holds its crate's allocator exclusively: until it
+//! returns, other allocations and scopes of that crate fail instead of
+//! landing in memory the reset would hand out again.
+
+use core::{
+    alloc::Layout,
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::{
+    budget_aware_provider::CrateId,
+    safe_memory::{
+        Allocator,
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+    },
+    telemetry::{
+        self,
+        event_codes,
+        Category,
+        Severity,
+    },
+    verification::VerificationLevel,
+    verified_allocator::global_allocators::{
+        get_crate_allocator,
+        CRATE_ALLOCATORS,
+    },
+};
+
+/// Maximum nesting depth of arena scopes, including the top-level scope
+pub const MAX_SCOPE_DEPTH: usize = 4;
+
+/// Alignment of scope blocks
+const SCOPE_ALIGN: usize = 16;
+
+/// Alignment of provider blocks within a scope
+const PROVIDER_ALIGN: usize = 8;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Type-level nesting depth of an [`Arena`]
+pub trait ScopeDepth: sealed::Sealed {
+    /// Depth of the scope, starting at 1 for a top-level scope
+    const DEPTH: usize;
+}
+
+/// Depth of scope that may contain a nested scope
+///
+/// Not implemented for the depth [`MAX_SCOPE_DEPTH`], which is what turns
+/// excessive nesting into a compile error.
+pub trait NestedScope: ScopeDepth {
+    /// Depth of a scope nested in this one
+    type Next: ScopeDepth;
+}
+
+macro_rules! scope_depths {
+    ($($depth:ident = $n:literal),+ $(,)?) => {
+        $(
+            #[doc = concat!("Depth ", stringify!($n), " of an arena scope")]
+            #[derive(Debug)]
+            pub enum $depth {}
+
+            impl sealed::Sealed for $depth {}
+
+            impl ScopeDepth for $depth {
+                const DEPTH: usize = $n;
+            }
+        )+
+    };
+}
+
+scope_depths!(Depth1 = 1, Depth2 = 2, Depth3 = 3, Depth4 = 4);
+
+impl NestedScope for Depth1 {
+    type Next = Depth2;
+}
+
+impl NestedScope for Depth2 {
+    type Next = Depth3;
+}
+
+impl NestedScope for Depth3 {
+    type Next = Depth4;
+}
+
+const _: () = assert!(Depth4::DEPTH == MAX_SCOPE_DEPTH);
+
+/// Counters of scopes entered since startup
+static SCOPES_ENTERED: AtomicUsize = AtomicUsize::new(0);
+/// Counter of providers created from arenas
+static PROVIDERS_CREATED: AtomicUsize = AtomicUsize::new(0);
+/// Counter of provider or scope requests an arena could not satisfy
+static EXHAUSTED: AtomicUsize = AtomicUsize::new(0);
+/// Largest high-water mark of any top-level scope
+static HIGH_WATER_MARK: AtomicUsize = AtomicUsize::new(0);
+
+/// Arena usage since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArenaStatistics {
+    /// Scopes entered, top-level and nested
+    pub scopes_entered:    usize,
+    /// Providers created from arenas
+    pub providers_created: usize,
+    /// Requests rejected because a scope's budget was used up
+    pub exhausted:         usize,
+    /// Most bytes used by a single top-level scope
+    pub high_water_mark:   usize,
+}
+
+/// Snapshot of arena usage since startup
+pub fn arena_statistics() -> ArenaStatistics {
+    ArenaStatistics {
+        scopes_entered:    SCOPES_ENTERED.load(Ordering::Relaxed),
+        providers_created: PROVIDERS_CREATED.load(Ordering::Relaxed),
+        exhausted:         EXHAUSTED.load(Ordering::Relaxed),
+        high_water_mark:   HIGH_WATER_MARK.load(Ordering::Relaxed),
+    }
+}
+
+/// Run `f` with an arena of `budget` bytes charged to `crate_id`
+///
+/// Every provider created from the arena is freed in O(1) when `f` returns.
+/// The high-water mark of the scope is reported as a
+/// [`MEM_ARENA_RESET`](event_codes::MEM_ARENA_RESET) telemetry event.
+///
+/// # Errors
+///
+/// Returns an error if the budget is zero, the crate has no allocator, the
+/// allocator has active scopes or its heap cannot hold `budget` more bytes.
+pub fn with_scope<R>(
+    crate_id: CrateId,
+    budget: usize,
+    f: impl FnOnce(&Arena<Depth1>) -> R,
+) -> Result<R> {
+    if crate_id as usize >= CRATE_ALLOCATORS.len() {
+        return Err(Error::validation_invalid_parameter(
+            "Crate has no verified allocator for arena scopes",
+        ));
+    }
+    let layout = Layout::from_size_align(budget, SCOPE_ALIGN)
+        .map_err(|_| Error::validation_invalid_parameter("Arena budget too large"))?;
+    // The block stays valid until the guard resets the allocator, and nothing
+    // else can allocate from the crate heap meanwhile
+    let (guard, base) = get_crate_allocator(crate_id)
+        .enter_exclusive_scope(crate_id, layout)
+        .inspect_err(|_| {
+            EXHAUSTED.fetch_add(1, Ordering::Relaxed);
+        })?;
+
+    let arena = Arena::new(crate_id, base, budget);
+    let result = f(&arena);
+
+    let high_water = arena.high_water_mark();
+    HIGH_WATER_MARK.fetch_max(high_water, Ordering::Relaxed);
+    telemetry::record_event(
+        Severity::Info,
+        Category::Memory,
+        event_codes::MEM_ARENA_RESET,
+        high_water as u64,
+        crate_id as u64,
+    );
+
+    // Borrows of the arena ended with `f`, so no provider refers to the block
+    // when the guard resets the allocator
+    guard.exit()?;
+    Ok(result)
+}
+
+/// Block of memory shared by an arena and the providers created from it
+struct Region {
+    /// Crate the block is charged to
+    crate_id:   CrateId,
+    /// Start of the block
+    base:       NonNull<u8>,
+    /// Size of the block in bytes
+    capacity:   usize,
+    /// Bytes handed out so far
+    offset:     AtomicUsize,
+    /// Largest value `offset` has reached
+    high_water: AtomicUsize,
+    /// Providers created from the block
+    providers:  AtomicUsize,
+}
+
+// SAFETY: The region only hands out disjoint parts of the block, reserved by
+// an atomic bump of `offset`, and never accesses their contents itself.
+unsafe impl Send for Region {}
+
+// SAFETY: See the Send impl above.
+unsafe impl Sync for Region {}
+
+impl Region {
+    /// Reserve `size` bytes aligned to `align`, returning their offset
+    fn bump(&self, size: usize, align: usize) -> Result<usize> {
+        let mut current = self.offset.load(Ordering::Relaxed);
+        loop {
+            let start = (current + align - 1) & !(align - 1);
+            let end = match start.checked_add(size) {
+                Some(end) if end <= self.capacity => end,
+                _ => {
+                    EXHAUSTED.fetch_add(1, Ordering::Relaxed);
+                    telemetry::record_event(
+                        Severity::Warning,
+                        Category::Memory,
+                        event_codes::MEM_BUDGET_VIOLATION,
+                        size as u64,
+                        self.crate_id as u64,
+                    );
+                    return Err(Error::memory_error("Arena scope budget exhausted"));
+                },
+            };
+            match self.offset.compare_exchange_weak(
+                current,
+                end,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    self.high_water.fetch_max(end, Ordering::Relaxed);
+                    return Ok(start);
+                },
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Hand `[start, end)` back if nothing was reserved after it
+    fn release_last(&self, start: usize, end: usize) {
+        let _ = self.offset.compare_exchange(end, start, Ordering::AcqRel, Ordering::Relaxed);
+    }
+
+    fn ptr(&self, offset: usize) -> NonNull<u8> {
+        // SAFETY: Callers only pass offsets returned by `bump`, which lie
+        // within the block.
+        unsafe { NonNull::new_unchecked(self.base.as_ptr().add(offset)) }
+    }
+
+    fn provider(&self, capacity: usize) -> Result<ArenaProvider<'_>> {
+        let start = self.bump(capacity, PROVIDER_ALIGN)?;
+        let data = self.ptr(start);
+        // SAFETY: [start, start + capacity) was just reserved for this
+        // provider alone. Zeroing it keeps data of earlier scopes from leaking.
+        unsafe { core::ptr::write_bytes(data.as_ptr(), 0, capacity) };
+        self.providers.fetch_add(1, Ordering::Relaxed);
+        PROVIDERS_CREATED.fetch_add(1, Ordering::Relaxed);
+
+        Ok(ArenaProvider {
+            region: Some(self),
+            data,
+            capacity,
+            used: 0,
+            access_count: AtomicUsize::new(0),
+            max_access_size: AtomicUsize::new(0),
+            verification_level: VerificationLevel::default(),
+            exhausted: false,
+        })
+    }
+}
+
+/// Bump arena of a scope entered with [`with_scope`]
+///
+/// `D` is the nesting depth of the scope, see [`NestedScope`].
+pub struct Arena<D: ScopeDepth> {
+    region: Region,
+    _depth: PhantomData<D>,
+}
+
+impl<D: ScopeDepth> Arena<D> {
+    fn new(crate_id: CrateId, base: NonNull<u8>, capacity: usize) -> Self {
+        SCOPES_ENTERED.fetch_add(1, Ordering::Relaxed);
+        Self {
+            region: Region {
+                crate_id,
+                base,
+                capacity,
+                offset: AtomicUsize::new(0),
+                high_water: AtomicUsize::new(0),
+                providers: AtomicUsize::new(0),
+            },
+            _depth: PhantomData,
+        }
+    }
+
+    /// Create a zeroed provider of `capacity` bytes, freed on scope exit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remaining budget of the scope is too small.
+    pub fn provider(&self, capacity: usize) -> Result<ArenaProvider<'_>> {
+        self.region.provider(capacity)
+    }
+
+    /// Run `f` with a nested arena of `budget` bytes taken from this one
+    ///
+    /// The nested block is returned to this arena on exit unless this arena
+    /// created providers while the nested scope was active.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remaining budget of this scope is too small.
+    pub fn with_scope<R>(&self, budget: usize, f: impl FnOnce(&Arena<D::Next>) -> R) -> Result<R>
+    where
+        D: NestedScope,
+    {
+        let start = self.region.bump(budget, SCOPE_ALIGN)?;
+        let nested = Arena::new(self.region.crate_id, self.region.ptr(start), budget);
+        let result = f(&nested);
+        self.region.release_last(start, start + budget);
+        Ok(result)
+    }
+
+    /// Nesting depth of the scope, starting at 1
+    pub const fn depth(&self) -> usize {
+        D::DEPTH
+    }
+
+    /// Crate the scope is charged to
+    pub fn crate_id(&self) -> CrateId {
+        self.region.crate_id
+    }
+
+    /// Budget of the scope in bytes
+    pub fn capacity(&self) -> usize {
+        self.region.capacity
+    }
+
+    /// Bytes currently handed out, including alignment padding
+    pub fn used(&self) -> usize {
+        self.region.offset.load(Ordering::Relaxed)
+    }
+
+    /// Bytes still available to providers and nested scopes
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.used()
+    }
+
+    /// Most bytes handed out at any point during the scope
+    pub fn high_water_mark(&self) -> usize {
+        self.region.high_water.load(Ordering::Relaxed)
+    }
+
+    /// Number of providers created from the scope
+    pub fn provider_count(&self) -> usize {
+        self.region.providers.load(Ordering::Relaxed)
+    }
+}
+
+impl<D: ScopeDepth> fmt::Debug for Arena<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("crate_id", &self.crate_id())
+            .field("depth", &D::DEPTH)
+            .field("capacity", &self.capacity())
+            .field("used", &self.used())
+            .field("high_water_mark", &self.high_water_mark())
+            .finish()
+    }
+}
+
+/// Allocator of [`ArenaProvider`], which does not hand out raw memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArenaAllocator;
+
+impl Allocator for ArenaAllocator {
+    fn allocate(&self, _layout: Layout) -> Result<*mut u8> {
+        // Raw pointers could outlive the scope the memory belongs to
+        Err(Error::memory_error(
+            "ArenaProvider does not support raw allocation",
+        ))
+    }
+
+    fn deallocate(&self, _ptr: *mut u8, _layout: Layout) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Memory provider carved from an [`Arena`]
+///
+/// The memory is released together with the scope, not when the provider is
+/// dropped. A provider created through `Default` has no capacity.
+///
+/// A clone takes a new block from the arena. [`ArenaProvider::try_clone`]
+/// reports an exhausted budget directly; a provider returned by `clone` in
+/// that case fails every access with that error instead.
+pub struct ArenaProvider<'a> {
+    /// Region the data was taken from, used to clone the provider
+    region:             Option<&'a Region>,
+    /// Start of the data
+    data:               NonNull<u8>,
+    /// Usable bytes
+    capacity:           usize,
+    /// Highest byte offset written so far
+    used:               usize,
+    /// Counter for access operations
+    access_count:       AtomicUsize,
+    /// Largest single access
+    max_access_size:    AtomicUsize,
+    /// Verification level for runtime checks
+    verification_level: VerificationLevel,
+    /// Set on a clone the arena had no budget for
+    exhausted:          bool,
+}
+
+// SAFETY: The data block is reserved for this provider alone and outlives it,
+// as the provider borrows the arena. Mutable access goes through &mut self.
+unsafe impl Send for ArenaProvider<'_> {}
+
+// SAFETY: See the Send impl above. The counters are atomics.
+unsafe impl Sync for ArenaProvider<'_> {}
+
+impl ArenaProvider<'_> {
+    /// Copy the provider into a new block of the same arena
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arena's remaining budget cannot hold the copy.
+    pub fn try_clone(&self) -> Result<Self> {
+        if self.exhausted {
+            return Err(Self::exhausted_error());
+        }
+        let mut clone = match self.region {
+            Some(region) => region.provider(self.capacity)?,
+            None => Self::default(),
+        };
+        clone.data_mut()[..self.used].copy_from_slice(&self.data()[..self.used]);
+        clone.used = self.used;
+        clone.verification_level = self.verification_level;
+        Ok(clone)
+    }
+
+    fn exhausted_error() -> Error {
+        Error::memory_error("Arena scope budget exhausted by ArenaProvider clone")
+    }
+
+    fn data(&self) -> &[u8] {
+        // SAFETY: [data, data + capacity) is initialized memory reserved for
+        // this provider, or a dangling pointer with zero capacity.
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr(), self.capacity) }
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        // SAFETY: As in `data`; &mut self guarantees exclusive access.
+        unsafe { core::slice::from_raw_parts_mut(self.data.as_ptr(), self.capacity) }
+    }
+
+    fn track_access(&self, len: usize) {
+        self.access_count.fetch_add(1, Ordering::Relaxed);
+        self.max_access_size.fetch_max(len, Ordering::Relaxed);
+    }
+}
+
+impl Default for ArenaProvider<'_> {
+    fn default() -> Self {
+        Self {
+            region:             None,
+            data:               NonNull::dangling(),
+            capacity:           0,
+            used:               0,
+            access_count:       AtomicUsize::new(0),
+            max_access_size:    AtomicUsize::new(0),
+            verification_level: VerificationLevel::default(),
+            exhausted:          false,
+        }
+    }
+}
+
+impl Clone for ArenaProvider<'_> {
+    fn clone(&self) -> Self {
+        self.try_clone().unwrap_or_else(|_| Self {
+            verification_level: self.verification_level,
+            exhausted: true,
+            ..Self::default()
+        })
+    }
+}
+
+impl PartialEq for ArenaProvider<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.capacity == other.capacity
+            && self.exhausted == other.exhausted
+            && self.verification_level == other.verification_level
+            && self.data()[..self.used] == other.data()[..other.used]
+    }
+}
+
+impl Eq for ArenaProvider<'_> {}
+
+impl fmt::Debug for ArenaProvider<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaProvider")
+            .field("capacity", &self.capacity)
+            .field("used", &self.used)
+            .field("access_count", &self.access_count.load(Ordering::Relaxed))
+            .field("verification_level", &self.verification_level)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
+
+impl Provider for ArenaProvider<'_> {
+    type Allocator = ArenaAllocator;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        Slice::with_verification_level(&self.data()[offset..offset + len], self.verification_level)
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.verify_access(offset, data.len())?;
+        self.track_access(data.len());
+        self.data_mut()[offset..offset + data.len()].copy_from_slice(data);
+        self.used = core::cmp::max(self.used, offset + data.len());
+        Ok(())
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        if self.exhausted {
+            return Err(Self::exhausted_error());
+        }
+        if offset.checked_add(len).map_or(true, |end| end > self.capacity) {
+            return Err(Error::memory_out_of_bounds("Access out of bounds"));
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.used
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        if self.exhausted {
+            return Err(Self::exhausted_error());
+        }
+        if self.used > self.capacity {
+            return Err(Error::validation_error("Corrupted state: used > capacity"));
+        }
+        Ok(())
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        self.verification_level = level;
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        self.verification_level
+    }
+
+    fn memory_stats(&self) -> Stats {
+        Stats {
+            total_size:      self.capacity,
+            access_count:    self.access_count.load(Ordering::Relaxed),
+            unique_regions:  0, // Not tracked by this provider
+            max_access_size: self.max_access_size.load(Ordering::Relaxed),
+        }
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        self.used = core::cmp::max(self.used, offset + len);
+        let level = self.verification_level;
+        SliceMut::with_verification_level(&mut self.data_mut()[offset..offset + len], level)
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        self.verify_access(src_offset, len)?;
+        self.verify_access(dst_offset, len)?;
+        self.track_access(len);
+        self.data_mut().copy_within(src_offset..src_offset + len, dst_offset);
+        self.used = core::cmp::max(self.used, dst_offset + len);
+        Ok(())
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        if self.exhausted {
+            return Err(Self::exhausted_error());
+        }
+        if byte_offset > self.capacity {
+            return Err(Error::memory_error("Offset exceeds capacity"));
+        }
+        self.used = core::cmp::max(self.used, byte_offset);
+        Ok(())
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        &ArenaAllocator
+    }
+
+    fn acquire_memory(&self, layout: Layout) -> Result<*mut u8> {
+        ArenaAllocator.allocate(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: Layout) -> Result<()> {
+        ArenaAllocator.deallocate(ptr, layout)
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.try_clone()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounded::BoundedVec;
+
+    // Top-level scopes share the crate heap with the other allocators, so a
+    // single test writes to arena memory.
+    #[test]
+    fn test_arena_scopes() {
+        let before = arena_statistics();
+
+        let total = with_scope(CrateId::VerificationTool, 4096, |arena| {
+            assert_eq!(arena.depth(), 1);
+            let mut request = arena.provider(100).unwrap();
+            request.write_data(0, b"request").unwrap();
+            assert_eq!(arena.used(), 100);
+
+            let copy = request.try_clone().unwrap();
+            assert_eq!(copy, request);
+            assert_eq!(arena.provider_count(), 2);
+
+            // Nothing else may allocate from the crate heap during the scope
+            let allocator = get_crate_allocator(CrateId::VerificationTool);
+            assert!(allocator.allocate(8).is_err());
+            assert!(allocator.enter_scope(CrateId::VerificationTool, 8).is_err());
+            assert!(with_scope(CrateId::VerificationTool, 64, |_| ()).is_err());
+
+            let mut values = BoundedVec::<u32, 8, _>::new(arena.provider(64).unwrap()).unwrap();
+            values.push(7).unwrap();
+            assert_eq!(values.get(0).unwrap(), 7);
+
+            let used = arena.used();
+            let nested = arena
+                .with_scope(1024, |scratch| {
+                    assert_eq!(scratch.depth(), 2);
+                    let scratch_provider = scratch.provider(1000).unwrap();
+                    assert_eq!(
+                        scratch_provider.borrow_slice(0, 4).unwrap().as_ref(),
+                        &[0; 4]
+                    );
+                    assert!(scratch.provider(100).is_err());
+                    scratch.high_water_mark()
+                })
+                .unwrap();
+            assert_eq!(nested, 1000);
+            // The nested block is handed back in O(1)
+            assert_eq!(arena.used(), used);
+            assert!(arena.high_water_mark() >= used + 1024);
+
+            assert!(arena.provider(arena.remaining() + 1).is_err());
+
+            // A clone the budget cannot hold fails instead of panicking
+            let big = arena.provider(arena.remaining() / 2 + 8).unwrap();
+            assert!(big.try_clone().is_err());
+            let failed = big.clone();
+            assert_ne!(failed, big);
+            assert!(failed.borrow_slice(0, 1).is_err());
+            assert!(failed.verify_integrity().is_err());
+            assert!(failed.new_handler().is_err());
+            arena.high_water_mark()
+        })
+        .unwrap();
+
+        let after = arena_statistics();
+        assert!(after.scopes_entered >= before.scopes_entered + 2);
+        assert!(after.providers_created >= before.providers_created + 4);
+        assert!(after.exhausted >= before.exhausted + 5);
+        assert!(after.high_water_mark >= total);
+
+        // The reset makes the full budget available again
+        with_scope(CrateId::VerificationTool, 4096, |arena| {
+            assert_eq!(arena.used(), 0);
+            assert_eq!(
+                arena.provider(100).unwrap().borrow_slice(0, 7).unwrap().as_ref(),
+                &[0; 7]
+            );
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_arena_scope_errors() {
+        assert!(with_scope(CrateId::TestRegistry, 0, |_| ()).is_err());
+        assert!(with_scope(CrateId::Wasi, 64, |_| ()).is_err());
+
+        let provider = ArenaProvider::default();
+        assert_eq!(provider.capacity(), 0);
+        assert_eq!(provider.clone(), provider);
+        assert_eq!(provider.try_clone().unwrap(), provider);
+        assert!(provider.acquire_memory(Layout::new::<u64>()).is_err());
+    }
+}
@@ -29,12 +29,22 @@
 //! map.insert("key".to_string(), data)?;
 //! ```
 
+pub mod arena;
+
 #[cfg(feature = "wrt-allocator")]
 pub mod collections;
 
 #[cfg(feature = "wrt-allocator")]
 pub mod phantom_budgets;
 
+pub use arena::{
+    arena_statistics,
+    with_scope,
+    Arena,
+    ArenaProvider,
+    ArenaStatistics,
+    MAX_SCOPE_DEPTH,
+};
 #[cfg(all(not(feature = "wrt-allocator"), feature = "std"))]
 pub use std::collections::HashMap as WrtHashMap;
 // Re-export for convenience when not using the allocator feature
@@ -0,0 +1,375 @@
+//! Runtime assertions with ASIL-dependent behavior
+//!
+//! [`wrt_assert!`](crate::wrt_assert) and
+//! [`wrt_debug_assert!`](crate::wrt_debug_assert) replace `assert!`,
+//! `debug_assert!` and `panic!` for checks of internal invariants. What a
+//! failed check does is not decided at the call site but by the
+//! [`AssertionPolicy`] of the ASIL profile the runtime is built for:
+//!
+//! | Profile | `wrt_assert!` | `wrt_debug_assert!` |
+//! |---------|---------------|---------------------|
+//! | QM      | log           | ignore              |
+//! | ASIL-A  | log           | ignore              |
+//! | ASIL-B  | degrade       | log                 |
+//! | ASIL-C  | safe state    | degrade             |
+//! | ASIL-D  | safe state    | safe state          |
+//!
+//! Every failed check is recorded in the global
+//! [`SafetyMonitor`](crate::safety_monitor::SafetyMonitor) and as a safety
+//! telemetry event. Ignored checks are not evaluated at all.
+//!
+//! Both macros evaluate to a [`Result`], so call sites propagate a degraded
+//! check with `?`:
+//!
+//! ```
+//! use wrt_error::Result;
+//! use wrt_foundation::wrt_debug_assert;
+//!
+//! fn read(data: &[u8], offset: usize) -> Result<u8> {
+//!     wrt_debug_assert!(offset < data.len(), "offset checked by the caller")?;
+//!     Ok(data.get(offset).copied().unwrap_or_default())
+//! }
+//! ```
+//!
+//! # Safety Requirements
+//! - SW-REQ-ID: ASIL-A-MON-003
+//! - Failed assertions are handled consistently per ASIL profile
+
+use core::sync::atomic::{
+    AtomicU8,
+    Ordering,
+};
+
+use wrt_error::{
+    codes,
+    ErrorCategory,
+};
+use wrt_sync::WrtMutex;
+
+use crate::{
+    safety_monitor::with_safety_monitor,
+    safety_system::AsilLevel,
+    telemetry::{
+        self,
+        event_codes,
+        Category,
+        Severity,
+    },
+    Error,
+    Result,
+};
+
+/// What happens when an assertion fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum AssertionAction {
+    /// The condition is not evaluated
+    Ignore    = 0,
+    /// The failure is recorded and execution continues
+    Log       = 1,
+    /// The failure is recorded and the assertion returns a safety error
+    Degrade   = 2,
+    /// The failure is recorded and the safe state handler is entered
+    SafeState = 3,
+}
+
+impl AssertionAction {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Ignore,
+            1 => Self::Log,
+            2 => Self::Degrade,
+            _ => Self::SafeState,
+        }
+    }
+
+    const fn severity(self) -> Severity {
+        match self {
+            Self::Ignore | Self::Log => Severity::Warning,
+            Self::Degrade => Severity::Error,
+            Self::SafeState => Severity::Critical,
+        }
+    }
+}
+
+/// Kind of assertion a check was written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssertionKind {
+    /// [`wrt_assert!`](crate::wrt_assert): an invariant execution depends on
+    Assert,
+    /// [`wrt_debug_assert!`](crate::wrt_debug_assert): a consistency check
+    /// of a condition already guaranteed elsewhere
+    DebugAssert,
+}
+
+/// Actions taken for failed assertions of each kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AssertionPolicy {
+    /// Action for [`wrt_assert!`](crate::wrt_assert)
+    pub assert:       AssertionAction,
+    /// Action for [`wrt_debug_assert!`](crate::wrt_debug_assert)
+    pub debug_assert: AssertionAction,
+}
+
+impl AssertionPolicy {
+    /// Policy of an ASIL profile
+    pub const fn for_asil(level: AsilLevel) -> Self {
+        use AssertionAction::*;
+        let (assert, debug_assert) = match level {
+            AsilLevel::QM | AsilLevel::AsilA => (Log, Ignore),
+            AsilLevel::AsilB => (Degrade, Log),
+            AsilLevel::AsilC => (SafeState, Degrade),
+            AsilLevel::AsilD => (SafeState, SafeState),
+        };
+        Self {
+            assert,
+            debug_assert,
+        }
+    }
+
+    /// Action for failed assertions of `kind`
+    pub const fn action(&self, kind: AssertionKind) -> AssertionAction {
+        match kind {
+            AssertionKind::Assert => self.assert,
+            AssertionKind::DebugAssert => self.debug_assert,
+        }
+    }
+}
+
+impl Default for AssertionPolicy {
+    fn default() -> Self {
+        Self::for_asil(build_asil_level())
+    }
+}
+
+/// ASIL profile the crate is built for
+pub const fn build_asil_level() -> AsilLevel {
+    if cfg!(feature = "asil-d") {
+        AsilLevel::AsilD
+    } else if cfg!(feature = "asil-c") {
+        AsilLevel::AsilC
+    } else if cfg!(feature = "asil-b") {
+        AsilLevel::AsilB
+    } else if cfg!(feature = "asil-a") {
+        AsilLevel::AsilA
+    } else {
+        AsilLevel::QM
+    }
+}
+
+/// Failed assertion passed to the safe state handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssertionFailure {
+    /// Kind of the failed assertion
+    pub kind:    AssertionKind,
+    /// Action taken for it
+    pub action:  AssertionAction,
+    /// Message given at the call site
+    pub message: &'static str,
+    /// Source file of the call site
+    pub file:    &'static str,
+    /// Source line of the call site
+    pub line:    u32,
+}
+
+/// Function bringing the system into its safe state; it must not return
+pub type SafeStateHandler = fn(&AssertionFailure) -> !;
+
+/// Marker for "use the policy of the build profile"
+const UNSET: u8 = u8::MAX;
+
+static ASSERT_ACTION: AtomicU8 = AtomicU8::new(UNSET);
+static DEBUG_ASSERT_ACTION: AtomicU8 = AtomicU8::new(UNSET);
+static SAFE_STATE_HANDLER: WrtMutex<Option<SafeStateHandler>> = WrtMutex::new(None);
+
+/// Replace the policy of the build profile, e.g. with
+/// [`AssertionPolicy::for_asil`] of the deployment's profile
+pub fn set_assertion_policy(policy: AssertionPolicy) {
+    ASSERT_ACTION.store(policy.assert as u8, Ordering::Release);
+    DEBUG_ASSERT_ACTION.store(policy.debug_assert as u8, Ordering::Release);
+}
+
+/// Policy failed assertions are handled with
+pub fn assertion_policy() -> AssertionPolicy {
+    let default = AssertionPolicy::default();
+    let load = |action: &AtomicU8, default| match action.load(Ordering::Acquire) {
+        UNSET => default,
+        action => AssertionAction::from_u8(action),
+    };
+    AssertionPolicy {
+        assert:       load(&ASSERT_ACTION, default.assert),
+        debug_assert: load(&DEBUG_ASSERT_ACTION, default.debug_assert),
+    }
+}
+
+/// Install the handler entered by assertions failing with
+/// [`AssertionAction::SafeState`]
+///
+/// Without a handler the runtime panics when `std` is available and halts
+/// in a spin loop otherwise.
+pub fn set_safe_state_handler(handler: SafeStateHandler) {
+    *SAFE_STATE_HANDLER.lock() = Some(handler);
+}
+
+/// Check an assertion; called by the assertion macros
+///
+/// # Errors
+///
+/// Returns a safety error if `condition` does not hold and the policy
+/// degrades failed assertions of `kind`.
+#[inline]
+pub fn check(
+    kind: AssertionKind,
+    condition: impl FnOnce() -> bool,
+    message: &'static str,
+    file: &'static str,
+    line: u32,
+) -> Result<()> {
+    let action = assertion_policy().action(kind);
+    if action == AssertionAction::Ignore || condition() {
+        return Ok(());
+    }
+    failed(&AssertionFailure {
+        kind,
+        action,
+        message,
+        file,
+        line,
+    })
+}
+
+#[cold]
+fn failed(failure: &AssertionFailure) -> Result<()> {
+    with_safety_monitor(|monitor| monitor.record_assertion_failure(failure));
+    telemetry::record_event(
+        failure.action.severity(),
+        Category::Safety,
+        event_codes::SAFETY_ASSERTION_FAILED,
+        u64::from(failure.line),
+        failure.action as u64,
+    );
+
+    match failure.action {
+        AssertionAction::Ignore | AssertionAction::Log => Ok(()),
+        AssertionAction::Degrade => {
+            Err(Error::new(ErrorCategory::Safety, codes::SAFETY_VIOLATION, failure.message))
+        },
+        AssertionAction::SafeState => {
+            let handler = *SAFE_STATE_HANDLER.lock();
+            match handler {
+                Some(handler) => handler(failure),
+                None => enter_default_safe_state(failure),
+            }
+        },
+    }
+}
+
+fn enter_default_safe_state(failure: &AssertionFailure) -> ! {
+    #[cfg(feature = "std")]
+    panic!(
+        "Assertion failed at {}:{}: {}; entering safe state",
+        failure.file, failure.line, failure.message
+    );
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = failure;
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Assert an invariant, handling a failure as the ASIL profile's
+/// [`AssertionPolicy`] says
+///
+/// Evaluates to a [`Result`](crate::Result) that is an error only if the
+/// policy degrades failed assertions.
+#[macro_export]
+macro_rules! wrt_assert {
+    ($condition:expr, $message:expr $(,)?) => {
+        $crate::assertions::check(
+            $crate::assertions::AssertionKind::Assert,
+            || $condition,
+            $message,
+            file!(),
+            line!(),
+        )
+    };
+}
+
+/// Check a condition already guaranteed elsewhere, handling a failure as
+/// the ASIL profile's [`AssertionPolicy`] says
+///
+/// Evaluates to a [`Result`](crate::Result) that is an error only if the
+/// policy degrades failed debug assertions.
+#[macro_export]
+macro_rules! wrt_debug_assert {
+    ($condition:expr, $message:expr $(,)?) => {
+        $crate::assertions::check(
+            $crate::assertions::AssertionKind::DebugAssert,
+            || $condition,
+            $message,
+            file!(),
+            line!(),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_per_asil_profile() {
+        use AssertionAction::*;
+        let policy = |level| {
+            let policy = AssertionPolicy::for_asil(level);
+            (policy.assert, policy.debug_assert)
+        };
+        assert_eq!(policy(AsilLevel::QM), (Log, Ignore));
+        assert_eq!(policy(AsilLevel::AsilB), (Degrade, Log));
+        assert_eq!(policy(AsilLevel::AsilC), (SafeState, Degrade));
+        assert_eq!(policy(AsilLevel::AsilD), (SafeState, SafeState));
+    }
+
+    fn expect_safe_state(_failure: &AssertionFailure) -> ! {
+        panic!("safe state");
+    }
+
+    // One test, as the policy is global
+    #[test]
+    fn test_failed_assertions_follow_policy() {
+        let failures = || with_safety_monitor(|monitor| monitor.get_safety_report().assertion_failures);
+        set_safe_state_handler(expect_safe_state);
+
+        set_assertion_policy(AssertionPolicy {
+            assert:       AssertionAction::Degrade,
+            debug_assert: AssertionAction::Ignore,
+        });
+        let before = failures();
+        assert!(crate::wrt_assert!(1 + 1 == 2, "arithmetic").is_ok());
+        let error = crate::wrt_assert!(1 + 1 == 3, "arithmetic").unwrap_err();
+        assert_eq!(error.category, ErrorCategory::Safety);
+        assert_eq!(error.message, "arithmetic");
+        let evaluated = core::cell::Cell::new(false);
+        assert!(crate::wrt_debug_assert!({ evaluated.set(true); false }, "ignored").is_ok());
+        assert!(!evaluated.get());
+        assert_eq!(failures(), before + 1);
+
+        set_assertion_policy(AssertionPolicy {
+            assert:       AssertionAction::SafeState,
+            debug_assert: AssertionAction::Log,
+        });
+        assert!(crate::wrt_debug_assert!(false, "logged").is_ok());
+        assert_eq!(failures(), before + 2);
+        #[cfg(feature = "std")]
+        {
+            let safe_state = std::panic::catch_unwind(|| crate::wrt_assert!(false, "fatal"));
+            assert!(safe_state.is_err());
+            assert_eq!(failures(), before + 3);
+        }
+
+        set_assertion_policy(AssertionPolicy::default());
+    }
+}
@@ -1,7 +1,11 @@
 //! Simple async executor support for no_std environments
 //!
-//! This is a simplified version that avoids unsafe code and complex
-//! initialization.
+//! [`AsyncRuntime`] polls a single future once and avoids complex
+//! initialization. [`BoundedExecutor`] runs several tasks cooperatively
+//! without allocating: tasks live in a fixed number of slots, borrow their
+//! pinned futures from the caller, and are scheduled by priority and then by
+//! earliest deadline. Every poll is charged fuel, so a task exceeding its
+//! fuel budget or missing its deadline is stopped deterministically.
 
 use core::{
     future::Future,
@@ -12,6 +16,14 @@
     },
 };
 
+use crate::{
+    operations::{
+        global_fuel_consumed,
+        Type as OperationType,
+    },
+    verification::VerificationLevel,
+};
+
 /// Simple executor error type
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExecutorError {
@@ -19,6 +31,12 @@ pub enum ExecutorError {
     TaskPanicked,
     OutOfResources,
     NotSupported,
+    /// The task did not complete before its deadline
+    DeadlineMissed,
+    /// The task consumed its whole fuel budget
+    FuelExhausted,
+    /// The task ID does not name a task of this executor
+    InvalidTask,
     Custom(&'static str),
 }
 
@@ -96,10 +114,535 @@ fn create_noop_waker() -> core::task::Waker {
     }
 }
 
+/// Scheduling priority of a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TaskPriority {
+    /// Background work
+    Low,
+    /// Regular work
+    #[default]
+    Normal,
+    /// Latency-sensitive work
+    High,
+    /// Work that must run before anything else
+    Critical,
+}
+
+/// What happens to a task that is still pending at its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeadlinePolicy {
+    /// Stop the task and fail it with [`ExecutorError::DeadlineMissed`]
+    #[default]
+    Cancel,
+    /// Record the miss and keep running the task
+    Report,
+}
+
+/// Options a task is spawned with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaskOptions {
+    /// Scheduling priority
+    pub priority:    TaskPriority,
+    /// Deadline in ticks relative to the spawn tick
+    pub deadline:    Option<u64>,
+    /// Fuel the task may consume, overriding the executor default
+    pub fuel_budget: Option<u64>,
+}
+
+impl TaskOptions {
+    /// Options with normal priority, no deadline and the default fuel budget
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the scheduling priority
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the deadline in ticks relative to the spawn tick
+    pub fn with_deadline(mut self, ticks: u64) -> Self {
+        self.deadline = Some(ticks);
+        self
+    }
+
+    /// Set the fuel the task may consume
+    pub fn with_fuel_budget(mut self, fuel: u64) -> Self {
+        self.fuel_budget = Some(fuel);
+        self
+    }
+}
+
+/// Configuration of a [`BoundedExecutor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorConfig {
+    /// Tasks polled per tick; the rest wait for the next tick
+    pub max_polls_per_tick:  usize,
+    /// Fuel budget of tasks spawned without one
+    pub default_fuel_budget: Option<u64>,
+    /// Handling of tasks pending at their deadline
+    pub deadline_policy:     DeadlinePolicy,
+    /// Verification level the fixed per-poll fuel cost is computed for
+    pub verification_level:  VerificationLevel,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            max_polls_per_tick:  usize::MAX,
+            default_fuel_budget: None,
+            deadline_policy:     DeadlinePolicy::Cancel,
+            verification_level:  VerificationLevel::Standard,
+        }
+    }
+}
+
+/// Handle of a spawned task
+///
+/// The generation makes handles of finished tasks invalid once their slot is
+/// reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId {
+    slot:       usize,
+    generation: u32,
+}
+
+/// Instrumentation of one task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TaskStats {
+    /// Times the task was polled
+    pub polls:           u32,
+    /// Fuel charged to the task
+    pub fuel_consumed:   u64,
+    /// Tick the task was spawned at
+    pub spawned_at:      u64,
+    /// Tick the task completed or failed at
+    pub finished_at:     Option<u64>,
+    /// Whether the task was pending at its deadline
+    pub deadline_missed: bool,
+}
+
+/// Instrumentation of the whole executor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExecutorStats {
+    /// Tasks spawned
+    pub spawned:          u64,
+    /// Tasks that completed
+    pub completed:        u64,
+    /// Tasks that failed, including deadline and fuel failures
+    pub failed:           u64,
+    /// Tasks cancelled by the host
+    pub cancelled:        u64,
+    /// Tasks pending at their deadline
+    pub deadline_misses:  u64,
+    /// Tasks stopped because they consumed their fuel budget
+    pub fuel_exhaustions: u64,
+    /// Polls of all tasks
+    pub polls:            u64,
+    /// Polls postponed to a later tick by the per-tick poll limit
+    pub deferred_polls:   u64,
+    /// Ticks run
+    pub ticks:            u64,
+    /// Largest number of tasks occupying slots at once
+    pub peak_tasks:       usize,
+}
+
+/// Progress of a task
+enum TaskState<T> {
+    Pending,
+    Done(Result<T, ExecutorError>),
+}
+
+/// Task occupying an executor slot
+struct TaskSlot<'a, T> {
+    future:      Pin<&'a mut dyn Future<Output = T>>,
+    generation:  u32,
+    sequence:    u64,
+    priority:    TaskPriority,
+    deadline:    Option<u64>,
+    fuel_budget: Option<u64>,
+    state:       TaskState<T>,
+    stats:       TaskStats,
+}
+
+impl<T> TaskSlot<'_, T> {
+    /// Scheduling order: higher priority first, then earliest deadline, then
+    /// spawn order
+    fn schedule_key(&self) -> (core::cmp::Reverse<TaskPriority>, u64, u64) {
+        (core::cmp::Reverse(self.priority), self.deadline.unwrap_or(u64::MAX), self.sequence)
+    }
+}
+
+/// Cooperative executor with `N` fixed task slots
+///
+/// Each [`run_tick`](Self::run_tick) polls the pending tasks in scheduling
+/// order, up to [`ExecutorConfig::max_polls_per_tick`]. Tasks are polled on
+/// every tick rather than on wake-up, which keeps scheduling independent of
+/// wakers and fully deterministic.
+///
+/// A poll is charged a fixed fuel cost for a future operation plus the fuel
+/// recorded in the global operation counter while the task ran, so
+/// concurrent recording on other threads is charged to the polled task.
+pub struct BoundedExecutor<'a, T, const N: usize> {
+    slots:           [Option<TaskSlot<'a, T>>; N],
+    config:          ExecutorConfig,
+    now:             u64,
+    next_generation: u32,
+    next_sequence:   u64,
+    stats:           ExecutorStats,
+}
+
+impl<T, const N: usize> Default for BoundedExecutor<'_, T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> BoundedExecutor<'a, T, N> {
+    /// Create an executor with the default configuration
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(ExecutorConfig::default())
+    }
+
+    /// Create an executor with the given configuration
+    #[must_use]
+    pub fn with_config(config: ExecutorConfig) -> Self {
+        Self {
+            slots: [const { None }; N],
+            config,
+            now: 0,
+            next_generation: 0,
+            next_sequence: 0,
+            stats: ExecutorStats::default(),
+        }
+    }
+
+    /// Spawn a task running `future`
+    ///
+    /// The future is borrowed for the lifetime of the executor, so it can be
+    /// pinned on the caller's stack with [`core::pin::pin!`].
+    pub fn spawn<F>(
+        &mut self,
+        future: Pin<&'a mut F>,
+        options: TaskOptions,
+    ) -> Result<TaskId, ExecutorError>
+    where
+        F: Future<Output = T> + 'a,
+    {
+        let slot = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(ExecutorError::OutOfResources)?;
+
+        let generation = self.next_generation;
+        self.next_generation = self.next_generation.wrapping_add(1);
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        self.slots[slot] = Some(TaskSlot {
+            future,
+            generation,
+            sequence,
+            priority: options.priority,
+            deadline: options.deadline.map(|ticks| self.now.saturating_add(ticks)),
+            fuel_budget: options.fuel_budget.or(self.config.default_fuel_budget),
+            state: TaskState::Pending,
+            stats: TaskStats {
+                spawned_at: self.now,
+                ..TaskStats::default()
+            },
+        });
+
+        self.stats.spawned += 1;
+        self.stats.peak_tasks = self.stats.peak_tasks.max(self.task_count());
+        Ok(TaskId { slot, generation })
+    }
+
+    /// Poll the pending tasks once in scheduling order and advance the tick
+    ///
+    /// Returns the number of tasks polled.
+    pub fn run_tick(&mut self) -> usize {
+        self.expire_deadlines();
+
+        let mut polled = 0;
+        let mut previous: Option<(core::cmp::Reverse<TaskPriority>, u64, u64)> = None;
+        loop {
+            // Selecting the next task by key instead of sorting keeps the
+            // executor free of scratch storage
+            let next = self
+                .slots
+                .iter()
+                .enumerate()
+                .filter_map(|(index, slot)| slot.as_ref().map(|task| (index, task)))
+                .filter(|(_, task)| matches!(task.state, TaskState::Pending))
+                .map(|(index, task)| (task.schedule_key(), index))
+                .filter(|(key, _)| previous.is_none_or(|previous| *key > previous))
+                .min();
+            let Some((key, index)) = next else {
+                break;
+            };
+            previous = Some(key);
+
+            if polled == self.config.max_polls_per_tick {
+                self.stats.deferred_polls += 1;
+                continue;
+            }
+            self.poll_task(index);
+            polled += 1;
+        }
+
+        self.now += 1;
+        self.stats.ticks += 1;
+        polled
+    }
+
+    /// Run ticks until no task is pending or `max_ticks` ticks have run
+    ///
+    /// Returns the number of ticks run.
+    pub fn run_until_idle(&mut self, max_ticks: u64) -> u64 {
+        let mut ticks = 0;
+        while ticks < max_ticks && self.pending_count() > 0 {
+            self.run_tick();
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// Take the result of a finished task, freeing its slot
+    ///
+    /// Returns `None` while the task is pending.
+    pub fn take_result(&mut self, id: TaskId) -> Option<Result<T, ExecutorError>> {
+        let slot = self.slots.get_mut(id.slot)?;
+        match slot {
+            Some(task) if task.generation == id.generation => {
+                if matches!(task.state, TaskState::Pending) {
+                    return None;
+                }
+            },
+            _ => return Some(Err(ExecutorError::InvalidTask)),
+        }
+        match slot.take().map(|task| task.state) {
+            Some(TaskState::Done(result)) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Cancel a task, freeing its slot
+    pub fn cancel(&mut self, id: TaskId) -> Result<(), ExecutorError> {
+        let slot = self.slots.get_mut(id.slot).ok_or(ExecutorError::InvalidTask)?;
+        if slot.as_ref().is_none_or(|task| task.generation != id.generation) {
+            return Err(ExecutorError::InvalidTask);
+        }
+        if slot.take().is_some_and(|task| matches!(task.state, TaskState::Pending)) {
+            self.stats.cancelled += 1;
+        }
+        Ok(())
+    }
+
+    /// Whether the task has completed or failed
+    pub fn is_finished(&self, id: TaskId) -> bool {
+        self.task(id).is_some_and(|task| !matches!(task.state, TaskState::Pending))
+    }
+
+    /// Instrumentation of a task that still occupies its slot
+    pub fn task_stats(&self, id: TaskId) -> Option<TaskStats> {
+        self.task(id).map(|task| task.stats)
+    }
+
+    /// Instrumentation of the executor
+    pub fn stats(&self) -> ExecutorStats {
+        self.stats
+    }
+
+    /// Current tick
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Tasks occupying slots, finished or not
+    pub fn task_count(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Tasks that are still pending
+    pub fn pending_count(&self) -> usize {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|task| matches!(task.state, TaskState::Pending))
+            .count()
+    }
+
+    /// Number of task slots
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    fn task(&self, id: TaskId) -> Option<&TaskSlot<'a, T>> {
+        self.slots
+            .get(id.slot)?
+            .as_ref()
+            .filter(|task| task.generation == id.generation)
+    }
+
+    /// Apply the deadline policy to tasks pending past their deadline
+    fn expire_deadlines(&mut self) {
+        let now = self.now;
+        for task in self.slots.iter_mut().flatten() {
+            if !matches!(task.state, TaskState::Pending)
+                || task.stats.deadline_missed
+                || task.deadline.is_none_or(|deadline| now <= deadline)
+            {
+                continue;
+            }
+
+            task.stats.deadline_missed = true;
+            self.stats.deadline_misses += 1;
+            if self.config.deadline_policy == DeadlinePolicy::Cancel {
+                task.state = TaskState::Done(Err(ExecutorError::DeadlineMissed));
+                task.stats.finished_at = Some(now);
+                self.stats.failed += 1;
+            }
+        }
+    }
+
+    /// Poll the task in `index` and charge the fuel it consumed
+    fn poll_task(&mut self, index: usize) {
+        let poll_cost = OperationType::fuel_cost_for_operation(
+            OperationType::FutureOperation,
+            self.config.verification_level,
+        )
+        .unwrap_or(0);
+        let Some(task) = self.slots[index].as_mut() else {
+            return;
+        };
+
+        let waker = create_noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let fuel_before = global_fuel_consumed();
+        let poll = task.future.as_mut().poll(&mut cx);
+        let fuel = global_fuel_consumed().saturating_sub(fuel_before).saturating_add(poll_cost);
+
+        task.stats.polls += 1;
+        task.stats.fuel_consumed = task.stats.fuel_consumed.saturating_add(fuel);
+        self.stats.polls += 1;
+
+        match poll {
+            Poll::Ready(output) => {
+                task.state = TaskState::Done(Ok(output));
+                task.stats.finished_at = Some(self.now);
+                self.stats.completed += 1;
+            },
+            Poll::Pending => {
+                if task.fuel_budget.is_some_and(|budget| task.stats.fuel_consumed >= budget) {
+                    task.state = TaskState::Done(Err(ExecutorError::FuelExhausted));
+                    task.stats.finished_at = Some(self.now);
+                    self.stats.fuel_exhaustions += 1;
+                    self.stats.failed += 1;
+                }
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use core::pin::pin;
+
     use super::*;
 
+    /// Future that is pending a fixed number of polls before returning its
+    /// value
+    struct YieldTimes {
+        remaining: u32,
+        value:     u32,
+    }
+
+    impl Future for YieldTimes {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+            if self.remaining == 0 {
+                Poll::Ready(self.value)
+            } else {
+                self.remaining -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    fn yield_times(remaining: u32, value: u32) -> YieldTimes {
+        YieldTimes { remaining, value }
+    }
+
+    #[test]
+    fn test_bounded_executor_priority_and_slots() {
+        let mut low = pin!(yield_times(0, 1));
+        let mut high = pin!(yield_times(0, 2));
+        let mut extra = pin!(yield_times(0, 3));
+        let mut rejected = pin!(yield_times(0, 4));
+        let mut executor = BoundedExecutor::<u32, 2>::with_config(ExecutorConfig {
+            max_polls_per_tick: 1,
+            ..ExecutorConfig::default()
+        });
+
+        let low_id = executor
+            .spawn(low.as_mut(), TaskOptions::new().with_priority(TaskPriority::Low))
+            .unwrap();
+        let high_id = executor
+            .spawn(high.as_mut(), TaskOptions::new().with_priority(TaskPriority::High))
+            .unwrap();
+        assert_eq!(
+            executor.spawn(rejected.as_mut(), TaskOptions::new()),
+            Err(ExecutorError::OutOfResources)
+        );
+
+        assert_eq!(executor.run_tick(), 1);
+        assert!(executor.is_finished(high_id));
+        assert!(!executor.is_finished(low_id));
+        assert_eq!(executor.stats().deferred_polls, 1);
+
+        assert_eq!(executor.take_result(high_id), Some(Ok(2)));
+        assert_eq!(executor.take_result(high_id), Some(Err(ExecutorError::InvalidTask)));
+        assert!(executor.spawn(extra.as_mut(), TaskOptions::new()).is_ok());
+
+        executor.run_until_idle(10);
+        assert_eq!(executor.take_result(low_id), Some(Ok(1)));
+        assert_eq!(executor.stats().completed, 3);
+        assert_eq!(executor.stats().peak_tasks, 2);
+    }
+
+    #[test]
+    fn test_bounded_executor_deadline_and_fuel() {
+        let mut late = pin!(yield_times(5, 1));
+        let mut hungry = pin!(yield_times(5, 2));
+        let mut reported = pin!(yield_times(3, 3));
+        let mut executor = BoundedExecutor::<u32, 4>::new();
+
+        let late_id = executor.spawn(late.as_mut(), TaskOptions::new().with_deadline(2)).unwrap();
+        let hungry_id =
+            executor.spawn(hungry.as_mut(), TaskOptions::new().with_fuel_budget(1)).unwrap();
+        executor.run_until_idle(10);
+
+        assert_eq!(executor.take_result(late_id), Some(Err(ExecutorError::DeadlineMissed)));
+        assert_eq!(executor.task_stats(hungry_id).map(|stats| stats.polls), Some(1));
+        assert_eq!(executor.take_result(hungry_id), Some(Err(ExecutorError::FuelExhausted)));
+
+        let mut executor = BoundedExecutor::<u32, 1>::with_config(ExecutorConfig {
+            deadline_policy: DeadlinePolicy::Report,
+            ..ExecutorConfig::default()
+        });
+        let id = executor.spawn(reported.as_mut(), TaskOptions::new().with_deadline(1)).unwrap();
+        executor.run_until_idle(10);
+        assert!(executor.task_stats(id).is_some_and(|stats| stats.deadline_missed));
+        assert_eq!(executor.take_result(id), Some(Ok(3)));
+        assert_eq!(executor.stats().deadline_misses, 1);
+    }
+
     #[test]
     fn test_simple_async() {
         extern crate alloc;
@@ -201,10 +201,14 @@
         ReadStream,
         ToBytes,
         WriteStream,
+        ZeroCopy,
     },
     verification::{
         Checksum,
         VerificationLevel,
+        VerificationSchedule,
+        VerificationScheduler,
+        VerificationTrigger,
     },
 };
 
@@ -1046,8 +1050,13 @@ fn get_item_size_impl<T>() -> usize
 
     // NOTE: If actual serialization size differs significantly from this estimate,
     // the BoundedVec might have capacity/indexing issues. This is a trade-off
-    // to prevent immediate crash.
-    12
+    // to prevent immediate crash. Fixed-size types that opt in are stored
+    // contiguously at their exact size, and fixed-size types that do not fit
+    // the estimate get their exact size rather than failing on every push.
+    match T::FIXED_SERIALIZED_SIZE {
+        Some(size) if T::STORE_PACKED || size > 12 => size,
+        _ => 12,
+    }
 }
 
 /// A bounded vector with a fixed maximum capacity and verification.
@@ -1060,13 +1069,14 @@ pub struct BoundedVec<T, const N_ELEMENTS: usize, P: MemoryProvider>
     T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
     P: MemoryProvider + Clone + Default + PartialEq + Eq,
 {
-    provider:             P, // Changed from handler: SafeMemoryHandler<P>
-    length:               usize,
-    item_serialized_size: usize, /* From T::default().serialized_size(), assumes fixed for all T
-                                  * in this Vec */
-    checksum:             Checksum,
-    verification_level:   VerificationLevel,
-    _phantom:             PhantomData<T>,
+    provider:              P, // Changed from handler: SafeMemoryHandler<P>
+    length:                usize,
+    item_serialized_size:  usize, /* From T::default().serialized_size(), assumes fixed for all T
+                                   * in this Vec */
+    checksum:              Checksum,
+    verification_level:    VerificationLevel,
+    verification_schedule: Option<VerificationScheduler>,
+    _phantom:              PhantomData<T>,
 }
 
 impl<T, const N_ELEMENTS: usize, P> Default for BoundedVec<T, N_ELEMENTS, P>
@@ -1078,12 +1088,13 @@ fn default() -> Self {
         let item_s_size = Self::get_item_size();
 
         Self {
-            provider:             P::default(), // Requires P: Default
-            length:               0,
-            item_serialized_size: item_s_size,
-            checksum:             Checksum::default(), // Checksum is Default
-            verification_level:   VerificationLevel::default(), // VerificationLevel is Default
-            _phantom:             PhantomData,
+            provider:              P::default(), // Requires P: Default
+            length:                0,
+            item_serialized_size:  item_s_size,
+            checksum:              Checksum::default(), // Checksum is Default
+            verification_level:    VerificationLevel::default(), // VerificationLevel is Default
+            verification_schedule: None,
+            _phantom:              PhantomData,
         }
     }
 }
@@ -1113,12 +1124,13 @@ pub fn new(provider_arg: P) -> Result<Self> {
         }
 
         Ok(Self {
-            provider:             provider_arg, // Store the provider directly
-            length:               0,
-            item_serialized_size: item_s_size,
-            checksum:             Checksum::default(),
-            verification_level:   VerificationLevel::default(),
-            _phantom:             PhantomData,
+            provider:              provider_arg, // Store the provider directly
+            length:                0,
+            item_serialized_size:  item_s_size,
+            checksum:              Checksum::default(),
+            verification_level:    VerificationLevel::default(),
+            verification_schedule: None,
+            _phantom:              PhantomData,
         })
     }
 
@@ -1161,6 +1173,7 @@ pub fn with_verification_level(
             item_serialized_size: item_size,
             checksum: Checksum::new(),
             verification_level,
+            verification_schedule: None,
             _phantom: PhantomData,
         })
     }
@@ -1307,6 +1320,7 @@ pub fn get(&self, index: usize) -> Result<T> {
         if index >= self.length {
             return Err(crate::Error::index_out_of_bounds("Index out of bounds"));
         }
+        self.verify_scheduled(VerificationTrigger::Access)?;
 
         // ASIL-A: Fault detection for bounds checking
         #[cfg(feature = "fault-detection")]
@@ -1386,6 +1400,71 @@ fn recalculate_checksum(&mut self) {
         );
     }
 
+    /// Opts the vector into scheduled checksum verification.
+    ///
+    /// With a schedule, `get` verifies the whole vector whenever the schedule
+    /// asks for it, and [`Self::verify_on_idle`] and
+    /// [`Self::verify_before_transition`] give it the chance to verify at
+    /// quiet points and component boundaries. Checksums are only maintained
+    /// from `VerificationLevel::Full` upwards; at lower levels the schedule
+    /// never finds anything to verify. `None` opts out again.
+    pub fn set_verification_schedule(&mut self, schedule: Option<VerificationSchedule>) {
+        self.verification_schedule = schedule.map(VerificationScheduler::new);
+    }
+
+    /// Returns the verification schedule the vector opted into, if any.
+    pub fn verification_schedule(&self) -> Option<VerificationSchedule> {
+        self.verification_schedule.as_ref().map(VerificationScheduler::schedule)
+    }
+
+    /// Verifies the checksum if the schedule asks for it on `trigger`.
+    ///
+    /// Outcomes are recorded in
+    /// [`VERIFICATION_MONITOR`](crate::monitoring::VERIFICATION_MONITOR).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checksum does not match the stored elements.
+    pub fn verify_scheduled(&self, trigger: VerificationTrigger) -> Result<()> {
+        use crate::monitoring::VERIFICATION_MONITOR;
+
+        let Some(scheduler) = &self.verification_schedule else {
+            return Ok(());
+        };
+        if self.verification_level < VerificationLevel::Full
+            || !scheduler.should_verify(self.verification_level, trigger)
+        {
+            VERIFICATION_MONITOR.record_skipped();
+            return Ok(());
+        }
+        let passed = self.verify_checksum();
+        VERIFICATION_MONITOR.record_verification(trigger, passed);
+        if passed {
+            Ok(())
+        } else {
+            Err(crate::Error::memory_corruption_detected("BoundedVec checksum mismatch"))
+        }
+    }
+
+    /// Gives a scheduled vector the chance to verify during an idle period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checksum does not match the stored elements.
+    pub fn verify_on_idle(&self) -> Result<()> {
+        self.verify_scheduled(VerificationTrigger::Idle)
+    }
+
+    /// Verifies a scheduled vector before it is exported to another
+    /// component.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checksum does not match the stored elements.
+    pub fn verify_before_transition(&self) -> Result<()> {
+        self.verify_scheduled(VerificationTrigger::Transition)
+    }
+
     /// Verifies the integrity of the vector using its checksum.
     fn verify_checksum(&self) -> bool {
         if self.verification_level == VerificationLevel::Off {
@@ -1445,6 +1524,7 @@ pub fn iter(&self) -> BoundedVecIterator<'_, T, N_ELEMENTS, P> {
         BoundedVecIterator {
             vec:           self,
             current_index: 0,
+            end_index:     self.length,
         }
     }
 
@@ -2970,21 +3050,6 @@ pub fn extend_from_slice(&mut self, other: &[T]) -> core::result::Result<(), Bou
         Ok(())
     }
 
-    /// Returns a slice view of the vector's contents
-    ///
-    /// Note: This method is not supported in no_std mode due to memory layout
-    /// constraints. Use individual element access methods instead.
-    pub fn as_slice(&self) -> Result<&[T]> {
-        // This operation is not supported in no_std mode because we can't
-        // safely return a reference to our internal storage structure.
-        // The memory layout of BoundedVec is not compatible with slice representation.
-        Err(crate::Error::new(
-            crate::ErrorCategory::Runtime,
-            crate::codes::UNIMPLEMENTED,
-            "Operation not supported",
-        ))
-    }
-
     /// Get a mutable reference to an element at the given index
     ///
     /// This is not efficiently implementable with the current architecture
@@ -3094,6 +3159,85 @@ pub fn new_from_slice(provider: P, slice: &[T]) -> core::result::Result<Self, Bo
     }
 }
 
+impl<T, const N_ELEMENTS: usize, P> BoundedVec<T, N_ELEMENTS, P>
+where
+    T: ZeroCopy + Checksummable + ToBytes + FromBytes + Default + PartialEq + Eq,
+    P: MemoryProvider + Clone + Default + PartialEq + Eq,
+{
+    /// Returns a zero-copy slice view of the vector's contents
+    ///
+    /// Only element types that opt into packed storage through
+    /// [`ToBytes::STORE_PACKED`] are laid out as a slice; for all others this
+    /// fails at runtime, as it did before zero-copy views existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` is not stored packed, or if the backing memory
+    /// fails its integrity check or is not suitably aligned for `T`.
+    pub fn as_slice(&self) -> Result<&[T]> {
+        if self.length == 0 {
+            return Ok(&[]);
+        }
+        self.check_packed()?;
+        let bytes = self.provider.borrow_slice(0, self.length * self.item_serialized_size)?.data()?;
+        // SAFETY: `T: ZeroCopy` guarantees that the serialized bytes are a valid
+        // in-memory `T`; `align_to` only yields correctly aligned elements.
+        #[allow(unsafe_code)]
+        let (prefix, items, suffix) = unsafe { bytes.align_to::<T>() };
+        if !prefix.is_empty() || !suffix.is_empty() {
+            return Err(crate::Error::memory_error("BoundedVec storage is misaligned for slice view"));
+        }
+        Ok(items)
+    }
+
+    /// Modifies the vector's contents in place through a mutable slice view
+    ///
+    /// The checksum is recalculated once `f` returns, so in-place edits keep
+    /// the vector verifiable without per-element `set` calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing memory is not accessible or not
+    /// suitably aligned for `T`.
+    pub fn modify_slice<R, F>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut [T]) -> R,
+    {
+        record_global_operation(OperationType::CollectionWrite, self.verification_level);
+        if self.length == 0 {
+            return Ok(f(&mut []));
+        }
+        self.check_packed()?;
+        let len = self.length * self.item_serialized_size;
+        let result = {
+            let mut slice = self.provider.get_slice_mut(0, len)?;
+            let bytes = slice.data_mut()?;
+            // SAFETY: `T: ZeroCopy` accepts every bit pattern and has no
+            // padding, so any write through `&mut [T]` leaves valid bytes.
+            #[allow(unsafe_code)]
+            let (prefix, items, suffix) = unsafe { bytes.align_to_mut::<T>() };
+            if !prefix.is_empty() || !suffix.is_empty() {
+                return Err(crate::Error::memory_error(
+                    "BoundedVec storage is misaligned for slice view",
+                ));
+            }
+            f(items)
+        };
+        self.recalculate_checksum();
+        Ok(result)
+    }
+
+    /// Ensures elements are stored back to back at their in-memory size
+    fn check_packed(&self) -> Result<()> {
+        if !T::STORE_PACKED || self.item_serialized_size != core::mem::size_of::<T>() {
+            return Err(crate::Error::memory_error(
+                "BoundedVec elements are not stored contiguously",
+            ));
+        }
+        Ok(())
+    }
+}
+
 pub struct BoundedVecIterator<'a, T, const N_ELEMENTS: usize, P>
 where
     T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
@@ -3101,6 +3245,7 @@ pub struct BoundedVecIterator<'a, T, const N_ELEMENTS: usize, P>
 {
     vec:           &'a BoundedVec<T, N_ELEMENTS, P>,
     current_index: usize,
+    end_index:     usize,
 }
 
 impl<'a, T, const N_ELEMENTS: usize, P> Iterator for BoundedVecIterator<'a, T, N_ELEMENTS, P>
@@ -3113,7 +3258,7 @@ impl<'a, T, const N_ELEMENTS: usize, P> Iterator for BoundedVecIterator<'a, T, N
     // Iterator returns T, not Result<T> or Option<T> directly from next()
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current_index < self.vec.len() {
+        if self.current_index < self.end_index {
             // self.vec.get() returns Result<T>.
             // The iterator should yield T if successful, or None if error or end.
             // For simplicity, if get() fails, this iterator will stop.
@@ -3127,7 +3272,7 @@ fn next(&mut self) -> Option<Self::Item> {
                 Err(_) => {
                     // Optionally log the error or handle it.
                     // For now, stop iteration on error.
-                    self.current_index = self.vec.len(); // Ensure it stops
+                    self.current_index = self.end_index; // Ensure it stops
                     None
                 },
             }
@@ -3135,6 +3280,55 @@ fn next(&mut self) -> Option<Self::Item> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end_index - self.current_index;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.current_index = self.current_index.saturating_add(n).min(self.end_index);
+        self.next()
+    }
+}
+
+impl<T, const N_ELEMENTS: usize, P> DoubleEndedIterator
+    for BoundedVecIterator<'_, T, N_ELEMENTS, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Clone + Default + PartialEq + Eq,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current_index < self.end_index {
+            match self.vec.get(self.end_index - 1) {
+                Ok(item) => {
+                    self.end_index -= 1;
+                    Some(item)
+                },
+                Err(_) => {
+                    self.end_index = self.current_index;
+                    None
+                },
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, const N_ELEMENTS: usize, P> ExactSizeIterator for BoundedVecIterator<'_, T, N_ELEMENTS, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Clone + Default + PartialEq + Eq,
+{
+}
+
+impl<T, const N_ELEMENTS: usize, P> core::iter::FusedIterator
+    for BoundedVecIterator<'_, T, N_ELEMENTS, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Clone + Default + PartialEq + Eq,
+{
 }
 
 /// Mutable iterator over BoundedVec elements
@@ -3317,6 +3511,11 @@ fn next(&mut self) -> Option<Self::Item> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len() - self.current_index;
+        (remaining, Some(remaining))
+    }
 }
 
 // Implement IntoIterator for BoundedVec (owned version)
@@ -4028,12 +4227,64 @@ pub fn ends_with(&self, suffix: &str) -> core::result::Result<bool, BoundedError
         Ok(s.ends_with(suffix))
     }
 
-    // TODO(#118): Re-implement substring() using StaticVec
-    // /// Returns a substring of this string.
-    // pub fn substring(&self, start: usize, end: usize) -> core::result::Result<Self, BoundedError> {
-    //     // Will be reimplemented using StaticVec
-    //     Err(BoundedError::runtime_execution_error("Not yet implemented"))
-    // }
+    /// Returns the substring between the byte offsets `start` and `end`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SliceError` if the range is out of bounds or either offset
+    /// does not lie on a UTF-8 character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wrt_foundation::bounded::BoundedString;
+    /// let s = BoundedString::<16>::from_str_truncate("grüße").unwrap();
+    /// assert_eq!(s.substring(0, 4).unwrap().as_str().unwrap(), "grü");
+    /// assert!(s.substring(0, 3).is_err()); // Inside 'ü'
+    /// ```
+    pub fn substring(&self, start: usize, end: usize) -> core::result::Result<Self, BoundedError> {
+        let s = self.as_str()?;
+        let sub = s.get(start..end).ok_or_else(|| {
+            BoundedError::new(
+                BoundedErrorKind::SliceError,
+                "Substring range out of bounds or not on a char boundary",
+            )
+        })?;
+        Self::from_str_truncate(sub)
+    }
+
+    /// Shortens the string to at most `max_len` bytes.
+    ///
+    /// If `max_len` falls inside a multi-byte character, that character is
+    /// removed as well, so the string stays valid UTF-8. Has no effect if the
+    /// string is already shorter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wrt_foundation::bounded::BoundedString;
+    /// let mut s = BoundedString::<16>::from_str_truncate("grüße").unwrap();
+    /// s.truncate(4);
+    /// assert_eq!(s.as_str().unwrap(), "grü");
+    /// s.truncate(3); // Inside 'ü'
+    /// assert_eq!(s.as_str().unwrap(), "gr");
+    /// ```
+    pub fn truncate(&mut self, max_len: usize) {
+        let bytes = self.bytes.as_slice();
+        let mut new_len = core::cmp::min(max_len, bytes.len());
+        // Continuation bytes have the form 0b10xx_xxxx
+        while new_len > 0 && new_len < bytes.len() && (bytes[new_len] & 0xC0) == 0x80 {
+            new_len -= 1;
+        }
+        while self.bytes.len() > new_len {
+            self.bytes.pop();
+        }
+    }
+
+    /// Returns the number of characters in the string.
+    pub fn char_count(&self) -> core::result::Result<usize, BoundedError> {
+        Ok(self.as_str()?.chars().count())
+    }
 
     /// Appends a character to the end of the string.
     ///
@@ -4063,6 +4314,91 @@ pub fn push_char(&mut self, c: char) -> core::result::Result<(), BoundedError> {
     //     Err(BoundedError::runtime_execution_error("Not yet implemented"))
     // }
 
+    /// Checks if the string equals `other`, ignoring ASCII case.
+    ///
+    /// Suited to WIT identifiers and other ASCII names; non-ASCII
+    /// characters must match exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wrt_foundation::bounded::BoundedString;
+    /// let s = BoundedString::<16>::from_str_truncate("Wasi-Logging").unwrap();
+    /// assert!(s.eq_ignore_ascii_case("wasi-logging"));
+    /// ```
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.bytes.as_slice().eq_ignore_ascii_case(other.as_bytes())
+    }
+
+    /// Checks if the string equals `other`, ignoring case.
+    ///
+    /// Compares the lowercase mappings of both strings character by
+    /// character, without allocating.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Utf8Error` if the string is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wrt_foundation::bounded::BoundedString;
+    /// let s = BoundedString::<16>::from_str_truncate("GRÜSSE").unwrap();
+    /// assert!(s.eq_ignore_case("grüsse").unwrap());
+    /// ```
+    pub fn eq_ignore_case(&self, other: &str) -> core::result::Result<bool, BoundedError> {
+        let lower = |c: char| c.to_lowercase();
+        Ok(self.as_str()?.chars().flat_map(lower).eq(other.chars().flat_map(lower)))
+    }
+
+    /// Compares the string with `other`, ignoring ASCII case.
+    pub fn cmp_ignore_ascii_case(&self, other: &str) -> core::cmp::Ordering {
+        let lower = |b: &u8| b.to_ascii_lowercase();
+        self.bytes.as_slice().iter().map(lower).cmp(other.as_bytes().iter().map(lower))
+    }
+
+    /// Appends formatted text, e.g. from `format_args!`.
+    ///
+    /// With [`OverflowPolicy::Truncate`], text that does not fit is dropped
+    /// at a character boundary and `Ok(true)` is returned. With
+    /// [`OverflowPolicy::Error`], the string is left unchanged on overflow.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CapacityExceeded` if the text does not fit and the policy is
+    /// [`OverflowPolicy::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wrt_foundation::bounded::{BoundedString, OverflowPolicy};
+    /// let mut s = BoundedString::<12>::default();
+    /// let truncated = s
+    ///     .write_fmt_with_policy(format_args!("trap at {:#x}", 0x1234), OverflowPolicy::Truncate)
+    ///     .unwrap();
+    /// assert!(truncated);
+    /// assert_eq!(s.as_str().unwrap(), "trap at 0x12");
+    /// ```
+    pub fn write_fmt_with_policy(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+        policy: OverflowPolicy,
+    ) -> core::result::Result<bool, BoundedError> {
+        let original_len = self.len();
+        let mut writer = BoundedStringWriter {
+            string: self,
+            policy,
+            truncated: false,
+        };
+        let written = core::fmt::Write::write_fmt(&mut writer, args);
+        let truncated = writer.truncated;
+        if written.is_err() {
+            self.truncate(original_len);
+            return Err(BoundedError::capacity_exceeded());
+        }
+        Ok(truncated)
+    }
+
     /// Converts all characters in the string to lowercase.
     ///
     /// This returns a new `BoundedString` instance.
@@ -4146,6 +4482,57 @@ pub fn contains(&self, substring: &str) -> core::result::Result<bool, BoundedErr
     }
 }
 
+/// What a [`BoundedString`] does with formatted text that exceeds its
+/// capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Keep what fits, cut at a character boundary
+    Truncate,
+    /// Fail the write and leave the string unchanged
+    #[default]
+    Error,
+}
+
+/// `core::fmt::Write` adapter applying an [`OverflowPolicy`]
+struct BoundedStringWriter<'a, const N_BYTES: usize> {
+    string:    &'a mut BoundedString<N_BYTES>,
+    policy:    OverflowPolicy,
+    truncated: bool,
+}
+
+impl<const N_BYTES: usize> core::fmt::Write for BoundedStringWriter<'_, N_BYTES> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.string.len() + s.len() > N_BYTES {
+            if self.policy == OverflowPolicy::Error {
+                return Err(core::fmt::Error);
+            }
+            self.truncated = true;
+        }
+        self.string.push_str(s).map_err(|_| core::fmt::Error)
+    }
+}
+
+/// Writes with [`OverflowPolicy::Error`]; a failed `write!` may leave the
+/// text written before the overflow. Use
+/// [`BoundedString::write_fmt_with_policy`] to roll it back or truncate.
+impl<const N_BYTES: usize> core::fmt::Write for BoundedString<N_BYTES> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.len() + s.len() > N_BYTES {
+            return Err(core::fmt::Error);
+        }
+        self.push_str(s).map_err(|_| core::fmt::Error)
+    }
+}
+
+impl<const N_BYTES: usize> core::fmt::Display for BoundedString<N_BYTES> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.as_str() {
+            Ok(s) => f.write_str(s),
+            Err(_) => f.write_str("<invalid UTF-8>"),
+        }
+    }
+}
+
 // Add as_bytes_slice to BoundedVec
 impl<
         T: Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq + core::fmt::Debug, /* Added Debug */
@@ -4372,6 +4759,140 @@ pub fn split(&self, delimiter: char) -> core::result::Result<Vec<Self>, BoundedE
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        budget_aware_provider::CrateId,
+        safe_managed_alloc,
+        safe_memory::NoStdProvider,
+    };
+
+    #[test]
+    fn test_bounded_vec_iterator_adaptors() {
+        let provider = safe_managed_alloc!(1024, CrateId::Foundation).unwrap();
+        let mut vec = BoundedVec::<u32, 8, NoStdProvider<1024>>::new(provider).unwrap();
+        for i in 1..=5 {
+            vec.push(i * 10).unwrap();
+        }
+
+        let mut iter = vec.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.next_back(), Some(50));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.nth(1), Some(30));
+        assert_eq!(iter.next_back(), Some(40));
+        assert_eq!(iter.next(), None);
+
+        assert!(vec.iter().rev().eq([50, 40, 30, 20, 10]));
+        assert_eq!(vec.iter().rposition(|v| v == 20), Some(1));
+    }
+
+    #[test]
+    fn test_bounded_vec_zero_copy_slice() {
+        let provider = safe_managed_alloc!(1024, CrateId::Foundation).unwrap();
+        let mut vec = BoundedVec::<u8, 16, NoStdProvider<1024>>::new(provider).unwrap();
+        assert_eq!(vec.as_slice().unwrap(), &[] as &[u8]);
+
+        vec.extend_from_slice(b"wrt").unwrap();
+        assert_eq!(vec.as_slice().unwrap(), b"wrt");
+
+        let sum = vec
+            .modify_slice(|bytes| {
+                bytes.make_ascii_uppercase();
+                bytes.iter().map(|&b| u32::from(b)).sum::<u32>()
+            })
+            .unwrap();
+        assert_eq!(sum, u32::from(b'W') + u32::from(b'R') + u32::from(b'T'));
+        assert_eq!(vec.as_slice().unwrap(), b"WRT");
+        assert_eq!(vec.get(1).unwrap(), b'R');
+        assert!(vec.verify_checksum());
+    }
+
+    #[test]
+    fn test_bounded_vec_scheduled_verification() {
+        use crate::{
+            monitoring::VERIFICATION_MONITOR,
+            verification::VerificationSchedule,
+        };
+
+        let provider = safe_managed_alloc!(1024, CrateId::Foundation).unwrap();
+        let mut vec = BoundedVec::<u32, 8, NoStdProvider<1024>>::with_verification_level(
+            provider,
+            VerificationLevel::Full,
+        )
+        .unwrap();
+        for value in [1, 2, 3] {
+            vec.push(value).unwrap();
+        }
+        vec.set_verification_schedule(Some(VerificationSchedule::Periodic(2)));
+        assert_eq!(vec.verification_schedule(), Some(VerificationSchedule::Periodic(2)));
+
+        let before = VERIFICATION_MONITOR.get_statistics();
+        vec.provider.write_data(0, &[0xFF; 4]).unwrap();
+        // The first access falls between two periodic checks
+        assert_eq!(vec.get(1).unwrap(), 2);
+        assert!(vec.get(1).is_err());
+        assert!(vec.verify_on_idle().is_err());
+
+        vec.set_verification_schedule(Some(VerificationSchedule::OnTransition));
+        assert_eq!(vec.get(2).unwrap(), 3);
+        assert!(vec.verify_on_idle().is_ok());
+        assert!(vec.verify_before_transition().is_err());
+
+        let after = VERIFICATION_MONITOR.get_statistics();
+        assert!(after.access_verifications > before.access_verifications);
+        assert!(after.transition_verifications > before.transition_verifications);
+        assert!(after.verification_failures >= before.verification_failures + 3);
+        assert!(after.skipped_verifications >= before.skipped_verifications + 3);
+
+        vec.set_verification_schedule(None);
+        assert!(vec.verify_before_transition().is_ok());
+    }
+
+    #[test]
+    fn test_bounded_string_utf8_operations() {
+        use core::fmt::Write;
+
+        let mut s = BoundedString::<16>::from_str_truncate("Grüße").unwrap();
+        assert_eq!(s.char_count().unwrap(), 5);
+        assert_eq!(s.substring(2, 4).unwrap().as_str().unwrap(), "ü");
+        assert!(s.substring(3, 4).is_err());
+        assert!(s.substring(0, 17).is_err());
+
+        assert!(s.eq_ignore_case("GRÜSSE").is_ok_and(|eq| !eq));
+        assert!(s.eq_ignore_case("grÜße").unwrap());
+        assert!(s.eq_ignore_ascii_case("gRüßE"));
+        assert!(!s.eq_ignore_ascii_case("GRÜßE"));
+        assert_eq!(s.cmp_ignore_ascii_case("gRüßE"), core::cmp::Ordering::Equal);
+        assert_eq!(s.cmp_ignore_ascii_case("gS"), core::cmp::Ordering::Less);
+
+        s.truncate(3);
+        assert_eq!(s.as_str().unwrap(), "Gr");
+
+        let mut message = BoundedString::<8>::default();
+        write!(message, "E{}", 42).unwrap();
+        // A plain `write!` keeps the pieces written before the overflow
+        let value = 1_000_000;
+        assert!(write!(message, ": {}", value).is_err());
+        assert_eq!(message.as_str().unwrap(), "E42: ");
+        message.truncate(3);
+        assert_eq!(
+            message.write_fmt_with_policy(format_args!("{}", "-ü-ü"), OverflowPolicy::Error),
+            Err(BoundedError::capacity_exceeded())
+        );
+        assert_eq!(message.as_str().unwrap(), "E42");
+        assert!(message
+            .write_fmt_with_policy(format_args!(": {}", "üüü"), OverflowPolicy::Truncate)
+            .unwrap());
+        assert_eq!(message.as_str().unwrap(), "E42: ü");
+        let mut displayed = BoundedString::<8>::default();
+        write!(displayed, "{}", message).unwrap();
+        assert_eq!(displayed.as_str().unwrap(), "E42: ü");
+    }
+}
+
 /// Kani verification proofs for BoundedVec and BoundedString operations
 #[cfg(kani)]
 mod kani_proofs {
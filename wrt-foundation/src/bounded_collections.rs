@@ -424,22 +424,14 @@ pub fn with_verification_level(
     /// If the key doesn't exist and the map is full, returns an error.
     pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, BoundedError> {
         // Check if the key already exists
-        for i in 0..self.entries.len() {
-            if let Ok(entry) = self.entries.get(i) {
-                if entry.0 == key {
-                    // Update existing entry
-                    let old_value = entry.1.clone();
-                    let new_entry = (key, value);
-
-                    // Replace the entry in our vector
-                    // Note: This is inefficient as we're removing and adding, but it's simple
-                    // A real implementation would directly edit the entry in place
-                    self.entries.remove(i)?;
-                    self.entries.push(new_entry)?;
-
-                    return Ok(Some(old_value));
-                }
-            }
+        if let Some(i) = self.entries.iter().position(|entry| entry.0 == key) {
+            // Replace the entry in our vector
+            // Note: This is inefficient as we're removing and adding, but it's simple
+            // A real implementation would directly edit the entry in place
+            let (_, old_value) = self.entries.remove(i)?;
+            self.entries.push((key, value))?;
+
+            return Ok(Some(old_value));
         }
 
         // Key doesn't exist, insert new entry
@@ -456,45 +448,22 @@ pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, BoundedError> {
     ///
     /// Returns `None` if the key doesn't exist.
     pub fn get(&self, key: &K) -> Result<Option<V>, BoundedError> {
-        for i in 0..self.entries.len() {
-            if let Ok(entry) = self.entries.get(i) {
-                if &entry.0 == key {
-                    return Ok(Some(entry.1.clone()));
-                }
-            }
-        }
-
-        Ok(None)
+        Ok(self.entries.iter().find(|entry| &entry.0 == key).map(|entry| entry.1))
     }
 
     /// Removes a key from the map, returning the value associated with the key.
     ///
     /// Returns `None` if the key doesn't exist.
     pub fn remove(&mut self, key: &K) -> Result<Option<V>, BoundedError> {
-        for i in 0..self.entries.len() {
-            if let Ok(entry) = self.entries.get(i) {
-                if &entry.0 == key {
-                    let removed_entry = entry.clone();
-                    self.entries.remove(i)?;
-                    return Ok(Some(removed_entry.1));
-                }
-            }
+        match self.entries.iter().position(|entry| &entry.0 == key) {
+            Some(i) => Ok(Some(self.entries.remove(i)?.1)),
+            None => Ok(None),
         }
-
-        Ok(None)
     }
 
     /// Checks if the map contains the given key.
     pub fn contains_key(&self, key: &K) -> Result<bool, BoundedError> {
-        for i in 0..self.entries.len() {
-            if let Ok(entry) = self.entries.get(i) {
-                if &entry.0 == key {
-                    return Ok(true);
-                }
-            }
-        }
-
-        Ok(false)
+        Ok(self.entries.iter().any(|entry| &entry.0 == key))
     }
 
     /// Returns the number of key-value pairs in the map.
@@ -707,15 +676,7 @@ pub fn insert(&mut self, value: T) -> Result<bool, BoundedError> {
 
     /// Checks if the set contains the given element.
     pub fn contains(&self, value: &T) -> Result<bool, BoundedError> {
-        for i in 0..self.elements.len() {
-            if let Ok(element) = self.elements.get(i) {
-                if &element == value {
-                    return Ok(true);
-                }
-            }
-        }
-
-        Ok(false)
+        Ok(self.elements.iter().any(|element| &element == value))
     }
 
     /// Removes an element from the set.
@@ -723,16 +684,13 @@ pub fn contains(&self, value: &T) -> Result<bool, BoundedError> {
     /// Returns `true` if the element was present and removed, `false` if it was
     /// not present.
     pub fn remove(&mut self, value: &T) -> Result<bool, BoundedError> {
-        for i in 0..self.elements.len() {
-            if let Ok(element) = self.elements.get(i) {
-                if &element == value {
-                    self.elements.remove(i)?;
-                    return Ok(true);
-                }
-            }
+        match self.elements.iter().position(|element| &element == value) {
+            Some(i) => {
+                self.elements.remove(i)?;
+                Ok(true)
+            },
+            None => Ok(false),
         }
-
-        Ok(false)
     }
 
     /// Returns the number of elements in the set.
@@ -3397,11 +3355,9 @@ fn clone(&self) -> Self {
         new_map.verification_level = self.verification_level;
 
         // Clone all entries - don't silently drop errors
-        for i in 0..self.entries.len() {
-            if let Ok((k, v)) = self.entries.get(i) {
-                // Insert should succeed since we cloned the provider with same capacity
-                drop(new_map.insert(k, v));
-            }
+        for (k, v) in self.entries.iter() {
+            // Insert should succeed since we cloned the provider with same capacity
+            drop(new_map.insert(k, v));
         }
 
         new_map
@@ -3419,15 +3375,7 @@ fn eq(&self, other: &Self) -> bool {
             return false;
         }
 
-        for i in 0..self.entries.len() {
-            if let (Ok((k1, v1)), Ok((k2, v2))) = (self.entries.get(i), other.entries.get(i)) {
-                if k1 != k2 || v1 != v2 {
-                    return false;
-                }
-            }
-        }
-
-        true
+        self.entries.iter().zip(other.entries.iter()).all(|(a, b)| a == b)
     }
 }
 
@@ -3448,11 +3396,9 @@ impl<K, V, const N_ELEMENTS: usize, P: MemoryProvider> Checksummable
 {
     fn update_checksum(&self, checksum: &mut Checksum) {
         checksum.update_slice(&(self.len() as u32).to_le_bytes());
-        for i in 0..self.entries.len() {
-            if let Ok((k, v)) = self.entries.get(i) {
-                k.update_checksum(checksum);
-                v.update_checksum(checksum);
-            }
+        for (k, v) in self.entries.iter() {
+            k.update_checksum(checksum);
+            v.update_checksum(checksum);
         }
     }
 }
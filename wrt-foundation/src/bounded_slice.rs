@@ -265,15 +265,13 @@ fn test_bounded_slice_creation() {
         vec.push(2).unwrap();
         vec.push(3).unwrap();
 
-        // as_slice() is not supported with the current BoundedVec architecture
-        // (elements are serialized, not stored contiguously)
-        assert!(vec.as_slice().is_err());
-
-        // Use individual element access instead
-        assert_eq!(vec.len(), 3);
-        assert_eq!(vec.get(0).ok(), Some(1));
-        assert_eq!(vec.get(1).ok(), Some(2));
-        assert_eq!(vec.get(2).ok(), Some(3));
+        // Multi-byte elements are serialized rather than stored contiguously,
+        // so the view reads them through the vector instead of `&[i32]`
+        let slice = vec.as_slice();
+        assert_eq!(slice.len(), 3);
+        assert_eq!(slice.get(0), Some(1));
+        assert_eq!(slice.get(1), Some(2));
+        assert_eq!(slice.get(2), Some(3));
     }
 
     #[test]
@@ -300,8 +298,8 @@ fn test_bounded_slice_iterator() {
             vec.push(i).unwrap();
         }
 
-        let slice = vec.as_slice().unwrap();
-        let collected: Vec<i32> = slice.iter().copied().collect();
+        let slice = vec.as_slice();
+        let collected: Vec<i32> = slice.iter().collect();
         assert_eq!(collected, vec![0, 1, 2, 3, 4]);
     }
 }
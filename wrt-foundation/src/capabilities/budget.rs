@@ -0,0 +1,567 @@
+//! Runtime rebalancing of memory budgets
+//!
+//! The per-crate budgets in [`CRATE_BUDGETS`] are fixed at compile time. A
+//! [`BudgetLedger`] starts from them and lets a supervisor move unused
+//! budget between pools at runtime while the sum of all limits never
+//! exceeds the verified total.
+//!
+//! Pools form a two-level hierarchy: every crate has a pool, and a
+//! component pool is carved out of the unused budget of its crate. Budget
+//! moves between two crates, between a crate and one of its components, or
+//! between two components of the same crate; anything else has to go
+//! through the crates involved.
+//!
+//! The limit and the usage of a pool share one atomic word, so an
+//! allocation can never slip past a limit that is being lowered at the same
+//! time. Moves are serialized and each one is appended to an audit trail
+//! with a strictly increasing sequence number.
+//!
+//! A ledger can be frozen, after which no budget moves anymore. With the
+//! `asil-d` feature ledgers are frozen from the start.
+//!
+//! SW-REQ-ID: REQ_MEM_002 - Budget enforcement
+
+use core::sync::atomic::{
+    AtomicBool,
+    AtomicU64,
+    Ordering,
+};
+
+use wrt_error::helpers::memory_limit_exceeded_error;
+use wrt_sync::WrtMutex;
+
+use crate::{
+    budget_aware_provider::CrateId,
+    budget_verification::{
+        calculate_total_budget,
+        CRATE_BUDGETS,
+        CRATE_COUNT,
+    },
+    memory_coordinator::CrateIdentifier,
+    Error,
+    Result,
+};
+
+/// Maximum number of pools in a ledger, crates included
+pub const MAX_BUDGET_POOLS: usize = 64;
+
+/// Number of most recent audit records a ledger keeps
+pub const BUDGET_AUDIT_CAPACITY: usize = 32;
+
+// The compile-time budgets must fit the packed accounting words
+const _: () = assert!(calculate_total_budget() <= u32::MAX as usize);
+
+/// Every crate, in index order
+const CRATES: [CrateId; CRATE_COUNT] = [
+    CrateId::Foundation,
+    CrateId::Decoder,
+    CrateId::Runtime,
+    CrateId::Component,
+    CrateId::Host,
+    CrateId::Debug,
+    CrateId::Platform,
+    CrateId::Instructions,
+    CrateId::Format,
+    CrateId::Intercept,
+    CrateId::Sync,
+    CrateId::Math,
+    CrateId::Logging,
+    CrateId::Panic,
+    CrateId::TestRegistry,
+    CrateId::VerificationTool,
+    CrateId::Unknown,
+    CrateId::Wasi,
+    CrateId::WasiComponents,
+];
+
+/// Owner of a budget pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetOwner {
+    /// Budget of a crate
+    Crate(CrateId),
+    /// Budget of a component, carved out of its crate's budget
+    Component {
+        /// Crate the component's budget comes from
+        parent: CrateId,
+        /// Identifier of the component, unique within its crate
+        id:     u32,
+    },
+}
+
+impl BudgetOwner {
+    /// Crate at the top of the owner's branch of the hierarchy
+    pub fn crate_id(&self) -> CrateId {
+        match self {
+            BudgetOwner::Crate(crate_id) => *crate_id,
+            BudgetOwner::Component { parent, .. } => *parent,
+        }
+    }
+
+    /// Whether budget may move directly between `self` and `other`
+    fn is_adjacent(&self, other: &BudgetOwner) -> bool {
+        match (self, other) {
+            (BudgetOwner::Crate(_), BudgetOwner::Crate(_)) => true,
+            _ => self.crate_id() == other.crate_id(),
+        }
+    }
+}
+
+/// Handle of a pool in a [`BudgetLedger`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BudgetPoolId(u8);
+
+/// Change recorded in the audit trail of a [`BudgetLedger`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetEvent {
+    /// Budget moved from one pool to another
+    Transfer {
+        /// Pool that gave up budget
+        from:       BudgetOwner,
+        /// Pool that received budget
+        to:         BudgetOwner,
+        /// Bytes moved
+        amount:     usize,
+        /// Limit of `from` after the move
+        from_limit: usize,
+        /// Limit of `to` after the move
+        to_limit:   usize,
+    },
+    /// The ledger was frozen
+    Freeze,
+}
+
+/// Entry of the audit trail of a [`BudgetLedger`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetAuditRecord {
+    /// Position in the trail, starting at 1 and increasing by one per record
+    pub sequence: u64,
+    /// What changed
+    pub event:    BudgetEvent,
+}
+
+/// Pools and audit trail, changed only under the ledger's lock
+#[derive(Debug)]
+struct LedgerState {
+    owners:        [Option<BudgetOwner>; MAX_BUDGET_POOLS],
+    trail:         [Option<BudgetAuditRecord>; BUDGET_AUDIT_CAPACITY],
+    next_sequence: u64,
+}
+
+impl LedgerState {
+    fn find(&self, owner: &BudgetOwner) -> Option<usize> {
+        self.owners.iter().position(|slot| slot.as_ref() == Some(owner))
+    }
+
+    fn append(&mut self, event: BudgetEvent) -> BudgetAuditRecord {
+        let record = BudgetAuditRecord {
+            sequence: self.next_sequence,
+            event,
+        };
+        self.trail[(record.sequence % BUDGET_AUDIT_CAPACITY as u64) as usize] = Some(record);
+        self.next_sequence += 1;
+        record
+    }
+}
+
+/// Pack a pool's limit and usage into one word
+const fn pack(limit: u32, used: u32) -> u64 {
+    (limit as u64) << 32 | used as u64
+}
+
+/// Limit and usage of a pool from its packed word
+const fn unpack(word: u64) -> (u32, u32) {
+    ((word >> 32) as u32, word as u32)
+}
+
+/// Memory budgets that can be moved between crates and components at runtime
+///
+/// See the [module documentation](self) for the hierarchy and the
+/// guarantees.
+#[derive(Debug)]
+pub struct BudgetLedger {
+    /// Limit and usage of every pool, packed as `limit << 32 | used`
+    pools:  [AtomicU64; MAX_BUDGET_POOLS],
+    state:  WrtMutex<LedgerState>,
+    total:  usize,
+    frozen: AtomicBool,
+}
+
+impl BudgetLedger {
+    /// Create a ledger from per-crate budgets indexed like [`CRATE_BUDGETS`]
+    ///
+    /// The verified total is the sum of the budgets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the budgets add up to more than `u32::MAX` bytes.
+    pub fn new(budgets: &[usize; CRATE_COUNT]) -> Result<Self> {
+        let mut total = 0usize;
+        for budget in budgets {
+            total = total.checked_add(*budget).filter(|total| *total <= u32::MAX as usize).ok_or(
+                memory_limit_exceeded_error("Memory budgets exceed the ledger's range"),
+            )?;
+        }
+        Ok(Self::with_total(budgets, total))
+    }
+
+    /// Create a ledger from the compile-time [`CRATE_BUDGETS`]
+    pub fn from_crate_budgets() -> Self {
+        Self::with_total(&CRATE_BUDGETS, calculate_total_budget())
+    }
+
+    fn with_total(budgets: &[usize; CRATE_COUNT], total: usize) -> Self {
+        let pools = core::array::from_fn(|index| {
+            AtomicU64::new(pack(budgets.get(index).map_or(0, |budget| *budget as u32), 0))
+        });
+        let mut owners = [None; MAX_BUDGET_POOLS];
+        for (slot, crate_id) in owners.iter_mut().zip(CRATES) {
+            *slot = Some(BudgetOwner::Crate(crate_id));
+        }
+
+        Self {
+            pools,
+            state: WrtMutex::new(LedgerState {
+                owners,
+                trail: [None; BUDGET_AUDIT_CAPACITY],
+                next_sequence: 1,
+            }),
+            total,
+            frozen: AtomicBool::new(cfg!(feature = "asil-d")),
+        }
+    }
+
+    /// Sum of all limits, which no rebalancing can exceed
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Pool of a crate
+    pub fn crate_pool(&self, crate_id: CrateId) -> BudgetPoolId {
+        BudgetPoolId(crate_id.as_index() as u8)
+    }
+
+    /// Pool of an owner, if it has one
+    pub fn pool(&self, owner: &BudgetOwner) -> Option<BudgetPoolId> {
+        match owner {
+            BudgetOwner::Crate(crate_id) => Some(self.crate_pool(*crate_id)),
+            BudgetOwner::Component { .. } => {
+                self.state.lock().find(owner).map(|index| BudgetPoolId(index as u8))
+            },
+        }
+    }
+
+    /// Create the pool of a component with `budget` bytes from its crate
+    ///
+    /// The budget is moved like by [`rebalance`](Self::rebalance) and the
+    /// move is recorded in the audit trail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger is frozen, the component already has
+    /// a pool, all pools are in use, or the crate has less unused budget.
+    pub fn add_component(&self, parent: CrateId, id: u32, budget: usize) -> Result<BudgetPoolId> {
+        let owner = BudgetOwner::Component { parent, id };
+        let mut state = self.state.lock();
+        self.ensure_unfrozen()?;
+        if state.find(&owner).is_some() {
+            return Err(Error::runtime_invalid_parameter(
+                "Component already has a budget pool",
+            ));
+        }
+        let index = state
+            .owners
+            .iter()
+            .position(Option::is_none)
+            .ok_or(memory_limit_exceeded_error("Maximum number of budget pools exceeded"))?;
+
+        self.pools[index].store(0, Ordering::Release);
+        state.owners[index] = Some(owner);
+        if let Err(error) =
+            self.transfer(&mut state, BudgetOwner::Crate(parent), index, owner, budget)
+        {
+            state.owners[index] = None;
+            return Err(error);
+        }
+        Ok(BudgetPoolId(index as u8))
+    }
+
+    /// Current limit of a pool in bytes
+    pub fn limit(&self, pool: BudgetPoolId) -> usize {
+        unpack(self.word(pool).load(Ordering::Acquire)).0 as usize
+    }
+
+    /// Bytes currently consumed from a pool
+    pub fn used(&self, pool: BudgetPoolId) -> usize {
+        unpack(self.word(pool).load(Ordering::Acquire)).1 as usize
+    }
+
+    /// Bytes of a pool that are neither used nor moved elsewhere
+    pub fn available(&self, pool: BudgetPoolId) -> usize {
+        let (limit, used) = unpack(self.word(pool).load(Ordering::Acquire));
+        (limit - used) as usize
+    }
+
+    /// Consume `size` bytes of a pool's budget
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pool has less than `size` bytes available.
+    pub fn try_consume(&self, pool: BudgetPoolId, size: usize) -> Result<()> {
+        self.update(pool, |limit, used| {
+            let used = used.checked_add(u32::try_from(size).ok()?)?;
+            (used <= limit).then_some((limit, used))
+        })
+        .map(|_| ())
+        .map_err(|_| memory_limit_exceeded_error("Memory budget of pool exceeded"))
+    }
+
+    /// Return `size` bytes previously consumed from a pool
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pool has less than `size` bytes in use.
+    pub fn release(&self, pool: BudgetPoolId, size: usize) -> Result<()> {
+        self.update(pool, |limit, used| {
+            Some((limit, used.checked_sub(u32::try_from(size).ok()?)?))
+        })
+        .map(|_| ())
+        .map_err(|_| Error::invalid_state_error("Released more budget than was consumed"))
+    }
+
+    /// Move `amount` bytes of unused budget from one pool to another
+    ///
+    /// Budget moves between two crates, between a crate and one of its
+    /// components, or between two components of the same crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger is frozen, either owner has no pool,
+    /// the owners are not adjacent in the hierarchy, or `from` has less than
+    /// `amount` bytes available.
+    pub fn rebalance(
+        &self,
+        from: BudgetOwner,
+        to: BudgetOwner,
+        amount: usize,
+    ) -> Result<BudgetAuditRecord> {
+        let mut state = self.state.lock();
+        self.ensure_unfrozen()?;
+        if from == to || !from.is_adjacent(&to) {
+            return Err(Error::runtime_invalid_parameter(
+                "Budget can only move between adjacent pools",
+            ));
+        }
+        let to_index = state
+            .find(&to)
+            .ok_or(Error::no_capability("No budget pool for the receiving owner"))?;
+        self.transfer(&mut state, from, to_index, to, amount)
+    }
+
+    /// Stop all further budget moves
+    ///
+    /// Freezing cannot be undone. The first call is recorded in the audit
+    /// trail.
+    pub fn freeze(&self) -> Option<BudgetAuditRecord> {
+        let mut state = self.state.lock();
+        if self.frozen.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        Some(state.append(BudgetEvent::Freeze))
+    }
+
+    /// Whether budget moves are rejected
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::Acquire)
+    }
+
+    /// Check that the limits add up to the verified total and no pool uses
+    /// more than its limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounting is inconsistent.
+    pub fn verify(&self) -> Result<()> {
+        let state = self.state.lock();
+        let mut total = 0usize;
+        for (word, owner) in self.pools.iter().zip(state.owners.iter()) {
+            let (limit, used) = unpack(word.load(Ordering::Acquire));
+            if owner.is_none() && limit != 0 {
+                return Err(Error::invalid_state_error("Budget held by an unused pool"));
+            }
+            if used > limit {
+                return Err(Error::invalid_state_error("Budget pool uses more than its limit"));
+            }
+            total += limit as usize;
+        }
+        if total != self.total {
+            return Err(Error::invalid_state_error(
+                "Budget limits do not add up to the verified total",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sequence number of the most recent audit record, or 0 if none
+    pub fn last_sequence(&self) -> u64 {
+        self.state.lock().next_sequence - 1
+    }
+
+    /// Visit the retained audit records with a sequence number above
+    /// `after`, oldest first
+    ///
+    /// Only the last [`BUDGET_AUDIT_CAPACITY`] records are retained.
+    pub fn audit_records(&self, after: u64, mut visit: impl FnMut(&BudgetAuditRecord)) {
+        let state = self.state.lock();
+        let oldest = state.next_sequence.saturating_sub(BUDGET_AUDIT_CAPACITY as u64).max(1);
+        for sequence in (after + 1).max(oldest)..state.next_sequence {
+            let slot = &state.trail[(sequence % BUDGET_AUDIT_CAPACITY as u64) as usize];
+            if let Some(record) = slot {
+                visit(record);
+            }
+        }
+    }
+
+    fn word(&self, pool: BudgetPoolId) -> &AtomicU64 {
+        &self.pools[usize::from(pool.0)]
+    }
+
+    fn ensure_unfrozen(&self) -> Result<()> {
+        if self.is_frozen() {
+            return Err(Error::capability_violation("Memory budgets are frozen"));
+        }
+        Ok(())
+    }
+
+    /// Apply `change` to a pool's limit and usage atomically
+    fn update(
+        &self,
+        pool: BudgetPoolId,
+        change: impl Fn(u32, u32) -> Option<(u32, u32)>,
+    ) -> core::result::Result<u64, u64> {
+        self.word(pool).fetch_update(Ordering::AcqRel, Ordering::Acquire, |word| {
+            let (limit, used) = unpack(word);
+            change(limit, used).map(|(limit, used)| pack(limit, used))
+        })
+    }
+
+    /// Move budget while holding the lock, so moves are serialized and
+    /// recorded in order
+    ///
+    /// The limit of `from` is lowered before the limit of `to` is raised:
+    /// between the two steps the limits add up to less than the total,
+    /// never more.
+    fn transfer(
+        &self,
+        state: &mut LedgerState,
+        from: BudgetOwner,
+        to_index: usize,
+        to: BudgetOwner,
+        amount: usize,
+    ) -> Result<BudgetAuditRecord> {
+        let from_index = state
+            .find(&from)
+            .ok_or(Error::no_capability("No budget pool for the giving owner"))?;
+        let amount32 = u32::try_from(amount)
+            .map_err(|_| memory_limit_exceeded_error("Budget move exceeds the ledger's range"))?;
+
+        let from_word = self
+            .update(BudgetPoolId(from_index as u8), |limit, used| {
+                (limit - used >= amount32).then(|| (limit - amount32, used))
+            })
+            .map_err(|_| memory_limit_exceeded_error("Not enough unused budget to move"))?;
+        let to_word = self.pools[to_index].fetch_add(u64::from(amount32) << 32, Ordering::AcqRel);
+
+        Ok(state.append(BudgetEvent::Transfer {
+            from,
+            to,
+            amount,
+            from_limit: (unpack(from_word).0 - amount32) as usize,
+            to_limit: (unpack(to_word).0 + amount32) as usize,
+        }))
+    }
+}
+
+impl Default for BudgetLedger {
+    fn default() -> Self {
+        Self::from_crate_budgets()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger() -> BudgetLedger {
+        let mut budgets = [0; CRATE_COUNT];
+        budgets[CrateId::Runtime.as_index()] = 1000;
+        budgets[CrateId::Component.as_index()] = 500;
+        let ledger = BudgetLedger::new(&budgets).unwrap();
+        // asil-d builds start frozen; these tests exercise the moves
+        ledger.frozen.store(false, Ordering::Release);
+        ledger
+    }
+
+    #[test]
+    fn test_rebalance_moves_unused_budget_within_total() {
+        let ledger = ledger();
+        let runtime = ledger.crate_pool(CrateId::Runtime);
+        let component = ledger.crate_pool(CrateId::Component);
+
+        ledger.try_consume(runtime, 700).unwrap();
+        let (from, to) = (
+            BudgetOwner::Crate(CrateId::Runtime),
+            BudgetOwner::Crate(CrateId::Component),
+        );
+        assert!(ledger.rebalance(from, to, 400).is_err());
+        let record = ledger.rebalance(from, to, 300).unwrap();
+        assert_eq!(record.sequence, 1);
+        assert_eq!(ledger.limit(runtime), 700);
+        assert_eq!(ledger.limit(component), 800);
+        assert!(ledger.try_consume(runtime, 1).is_err());
+        ledger.release(runtime, 700).unwrap();
+        assert!(ledger.release(runtime, 1).is_err());
+        assert_eq!(ledger.total(), 1500);
+        ledger.verify().unwrap();
+    }
+
+    #[test]
+    fn test_component_pools_and_audit_trail() {
+        let ledger = ledger();
+        let first = ledger.add_component(CrateId::Runtime, 1, 200).unwrap();
+        ledger.add_component(CrateId::Runtime, 2, 100).unwrap();
+        assert!(ledger.add_component(CrateId::Runtime, 1, 10).is_err());
+        assert!(ledger.add_component(CrateId::Component, 3, 600).is_err());
+        assert_eq!(ledger.limit(first), 200);
+        assert_eq!(ledger.limit(ledger.crate_pool(CrateId::Runtime)), 700);
+
+        let one = BudgetOwner::Component {
+            parent: CrateId::Runtime,
+            id:     1,
+        };
+        let two = BudgetOwner::Component {
+            parent: CrateId::Runtime,
+            id:     2,
+        };
+        assert_eq!(ledger.pool(&one), Some(first));
+        ledger.rebalance(one, two, 50).unwrap();
+        assert!(ledger.rebalance(one, BudgetOwner::Crate(CrateId::Component), 50).is_err());
+
+        assert!(ledger.freeze().is_some());
+        assert!(ledger.freeze().is_none());
+        assert!(ledger.rebalance(two, one, 10).is_err());
+        ledger.verify().unwrap();
+
+        let mut sequences = [0u64; 4];
+        let mut count = 0;
+        ledger.audit_records(0, |record| {
+            sequences[count] = record.sequence;
+            count += 1;
+        });
+        assert_eq!(sequences, [1, 2, 3, 4]);
+        assert_eq!(ledger.last_sequence(), 4);
+
+        let mut last = None;
+        ledger.audit_records(3, |record| last = Some(record.event));
+        assert_eq!(last, Some(BudgetEvent::Freeze));
+    }
+}
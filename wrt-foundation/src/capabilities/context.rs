@@ -18,6 +18,11 @@
 use wrt_error::helpers::memory_limit_exceeded_error;
 
 use super::{
+    budget::{
+        BudgetAuditRecord,
+        BudgetLedger,
+        BudgetOwner,
+    },
     dynamic::DynamicMemoryCapability,
     static_alloc::StaticMemoryCapability,
     verified::VerifiedMemoryCapability,
@@ -54,6 +59,9 @@ pub struct MemoryCapabilityContext {
 
     /// Whether runtime verification is enabled
     runtime_verification: bool,
+
+    /// Memory budgets of crates and components, rebalanced at runtime
+    budgets: BudgetLedger,
 }
 
 /// Type alias for complex capability storage type
@@ -122,6 +130,7 @@ pub fn new(default_verification_level: VerificationLevel, runtime_verification:
             capabilities: core::array::from_fn(|_| (None, None)),
             default_verification_level,
             runtime_verification,
+            budgets: BudgetLedger::from_crate_budgets(),
         }
     }
 
@@ -130,6 +139,24 @@ pub fn default_verification_level(&self) -> VerificationLevel {
         self.default_verification_level
     }
 
+    /// Get the memory budget ledger of this context
+    pub fn budgets(&self) -> &BudgetLedger {
+        &self.budgets
+    }
+
+    /// Move unused memory budget between crates or components
+    ///
+    /// The budget ledger keeps the sum of all budgets at the verified total
+    /// and records the move in its audit trail.
+    pub fn rebalance_budget(
+        &self,
+        from: BudgetOwner,
+        to: BudgetOwner,
+        amount: usize,
+    ) -> Result<BudgetAuditRecord> {
+        self.budgets.rebalance(from, to, amount)
+    }
+
     /// Register a dynamic memory capability for a crate
     pub fn register_dynamic_capability(
         &mut self,
@@ -269,6 +296,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 &self.default_verification_level,
             )
             .field("runtime_verification", &self.runtime_verification)
+            .field("budgets_frozen", &self.budgets.is_frozen())
             .finish()
     }
 }
@@ -350,6 +378,7 @@ unsafe impl<const N: usize> Sync for CapabilityGuardedProvider<N> {}
 pub struct CapabilityContextBuilder {
     verification_level:   VerificationLevel,
     runtime_verification: bool,
+    freeze_budgets:       bool,
 }
 
 impl CapabilityContextBuilder {
@@ -358,6 +387,7 @@ pub fn new() -> Self {
         Self {
             verification_level:   VerificationLevel::Standard,
             runtime_verification: false,
+            freeze_budgets:       false,
         }
     }
 
@@ -373,9 +403,22 @@ pub fn with_runtime_verification(mut self, enabled: bool) -> Self {
         self
     }
 
+    /// Freeze the memory budgets so they cannot be rebalanced
+    ///
+    /// Budgets are always frozen with the `asil-d` feature.
+    pub fn with_frozen_budgets(mut self, frozen: bool) -> Self {
+        self.freeze_budgets = frozen;
+        self
+    }
+
     /// Build the capability context
     pub fn build(self) -> MemoryCapabilityContext {
-        MemoryCapabilityContext::new(self.verification_level, self.runtime_verification)
+        let context =
+            MemoryCapabilityContext::new(self.verification_level, self.runtime_verification);
+        if self.freeze_budgets {
+            context.budgets.freeze();
+        }
+        context
     }
 }
 
@@ -44,6 +44,7 @@
 };
 
 pub mod atomic;
+pub mod budget;
 pub mod context;
 pub mod dynamic;
 pub mod factory;
@@ -58,6 +59,13 @@
 pub mod verified;
 
 // Re-export key types for convenience
+pub use budget::{
+    BudgetAuditRecord,
+    BudgetEvent,
+    BudgetLedger,
+    BudgetOwner,
+    BudgetPoolId,
+};
 pub use context::{
     AnyMemoryCapability,
     MemoryCapabilityContext,
@@ -31,9 +31,11 @@ pub struct CoreFuncType {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct CoreMemoryType {
         /// Memory limits
-        pub limits: crate::types::Limits,
+        pub limits:   crate::types::Limits,
         /// Whether the memory is shared
-        pub shared: bool,
+        pub shared:   bool,
+        /// Whether the memory is indexed with i64 addresses (memory64)
+        pub memory64: bool,
     }
 
     /// Clean core WebAssembly table type without provider parameters
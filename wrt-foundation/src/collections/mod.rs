@@ -61,7 +61,9 @@
 mod static_vec;
 mod static_queue;
 mod static_map;
+mod mpmc_queue;
 
 pub use static_vec::StaticVec;
 pub use static_queue::StaticQueue;
 pub use static_map::StaticMap;
+pub use mpmc_queue::MpmcQueue;
@@ -0,0 +1,439 @@
+// WRT - wrt-foundation
+// Module: MpmcQueue - Lock-free multi-producer multi-consumer queue
+// SW-REQ-ID: REQ_RESOURCE_001, REQ_MEM_SAFETY_001, REQ_TEMPORAL_001
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+// Allow unsafe code for the slot hand-over between threads (documented and
+// verified via KANI)
+#![allow(unsafe_code)]
+
+//! Lock-free bounded FIFO queue shared between cores.
+//!
+//! `MpmcQueue<T, N>` lets any number of producers and consumers exchange
+//! elements through `&self`, e.g. to dispatch host callbacks from the core
+//! that received them to whichever core is free to run them. It never
+//! blocks and never allocates.
+//!
+//! # Characteristics
+//!
+//! - **Zero allocation**: All memory is inline, one slot per element
+//! - **Lock-free**: A stalled thread never blocks the others; a failed
+//!   compare-and-swap is retried with the updated position
+//! - **Const construction**: Usable in `static` items
+//! - **ASIL-D compliant**: No `alloc`, no locks, no priority inversion
+//!
+//! # Algorithm
+//!
+//! Each slot carries a sequence number telling which lap of the ring it is
+//! ready for (D. Vyukov's bounded MPMC queue). A producer claims position
+//! `pos` by advancing `tail` once the slot's sequence equals `pos`, writes
+//! the element and publishes it by setting the sequence to `pos + 1`. A
+//! consumer claims it by advancing `head` once the sequence equals
+//! `pos + 1`, reads the element and frees the slot for the next lap by
+//! setting the sequence to `pos + N`. Sequence numbers are stored relative
+//! to the slot index so that every slot starts at zero and the queue can be
+//! built in a `const` context.
+
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+};
+
+use wrt_error::Result;
+
+use crate::platform_atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+/// A slot of the ring
+struct Slot<T> {
+    /// Lap sequence, minus the slot index
+    sequence: AtomicUsize,
+    /// Element, initialized while the sequence marks it as published
+    value:    UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    const fn new() -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            value:    UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// A lock-free multi-producer multi-consumer queue with compile-time
+/// capacity and inline storage.
+///
+/// # Requirements
+///
+/// - REQ_RESOURCE_001: Static allocation only
+/// - REQ_MEM_SAFETY_001: Bounds validation
+/// - REQ_TEMPORAL_001: Bounded operations without locks
+///
+/// # Invariants
+///
+/// 1. `N` is a power of two, so positions wrap consistently with `usize`
+/// 2. `head <= tail <= head + N` (modulo wrap-around)
+/// 3. A slot is accessed by at most one thread between claiming its
+///    position and updating its sequence
+///
+/// # Examples
+///
+/// ```
+/// use wrt_foundation::collections::MpmcQueue;
+///
+/// static CALLBACKS: MpmcQueue<u32, 8> = MpmcQueue::new();
+///
+/// CALLBACKS.push(7)?;
+/// CALLBACKS.push(9)?;
+/// assert_eq!(CALLBACKS.pop(), Some(7));
+/// assert_eq!(CALLBACKS.len(), 1);
+/// # Ok::<(), wrt_error::Error>(())
+/// ```
+pub struct MpmcQueue<T, const N: usize> {
+    /// Ring of slots
+    slots: [Slot<T>; N],
+    /// Next position to pop
+    head:  AtomicUsize,
+    /// Next position to push
+    tail:  AtomicUsize,
+}
+
+// SAFETY: Elements are moved between threads, never shared; the sequence
+// protocol gives each slot a single owner at a time.
+unsafe impl<T: Send, const N: usize> Send for MpmcQueue<T, N> {}
+// SAFETY: See above; `&MpmcQueue` only hands out owned elements.
+unsafe impl<T: Send, const N: usize> Sync for MpmcQueue<T, N> {}
+
+impl<T, const N: usize> Default for MpmcQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> MpmcQueue<T, N> {
+    const CAPACITY_IS_POWER_OF_TWO: () =
+        assert!(N.is_power_of_two(), "MpmcQueue capacity must be a power of two");
+
+    /// Creates a new empty queue.
+    ///
+    /// Fails to compile if `N` is not a power of two.
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_IS_POWER_OF_TWO;
+        Self {
+            slots: [const { Slot::new() }; N],
+            head:  AtomicUsize::new(0),
+            tail:  AtomicUsize::new(0),
+        }
+    }
+
+    /// Sequence of the slot for `pos`, in absolute lap numbering
+    fn sequence(&self, pos: usize) -> (&Slot<T>, usize) {
+        let index = pos & (N - 1);
+        let slot = &self.slots[index];
+        (slot, slot.sequence.load(Ordering::Acquire).wrapping_add(index))
+    }
+
+    /// Appends an element, handing it back if the queue is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(value)` if all `N` slots are occupied.
+    pub fn try_push(&self, value: T) -> core::result::Result<(), T> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let (slot, sequence) = self.sequence(pos);
+            let lag = sequence.wrapping_sub(pos) as isize;
+            if lag == 0 {
+                match self.tail.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: The successful CAS made this thread the
+                        // only one owning the slot until the sequence below
+                        // is published.
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.sequence.store(
+                            pos.wrapping_add(1).wrapping_sub(pos & (N - 1)),
+                            Ordering::Release,
+                        );
+                        return Ok(());
+                    },
+                    Err(current) => pos = current,
+                }
+            } else if lag < 0 {
+                // The slot still holds the element of the previous lap
+                return Err(value);
+            } else {
+                pos = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Appends an element.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(CapacityExceeded)` if the queue is full; the element is
+    /// dropped. Use [`Self::try_push`] to get it back.
+    pub fn push(&self, value: T) -> Result<()> {
+        self.try_push(value).map_err(|_| {
+            wrt_error::Error::foundation_bounded_capacity_exceeded("MpmcQueue capacity exceeded")
+        })
+    }
+
+    /// Removes the oldest element, `None` if the queue is empty.
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let (slot, sequence) = self.sequence(pos);
+            let lag = sequence.wrapping_sub(pos.wrapping_add(1)) as isize;
+            if lag == 0 {
+                match self.head.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: The sequence showed the element as
+                        // published, and the successful CAS made this thread
+                        // the only one reading it.
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.sequence.store(
+                            pos.wrapping_add(N).wrapping_sub(pos & (N - 1)),
+                            Ordering::Release,
+                        );
+                        return Some(value);
+                    },
+                    Err(current) => pos = current,
+                }
+            } else if lag < 0 {
+                // No element has been published for this position yet
+                return None;
+            } else {
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of elements in the queue.
+    ///
+    /// Only a snapshot while other threads push or pop concurrently.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(N)
+    }
+
+    /// Returns `true` if the queue held no elements when checked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the queue.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Drop for MpmcQueue<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for MpmcQueue<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MpmcQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+// ============================================================================
+// KANI Formal Verification
+// ============================================================================
+
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    #[kani::unwind(6)]
+    fn verify_fifo_order_with_wraparound() {
+        let queue: MpmcQueue<u8, 4> = MpmcQueue::new();
+        let first: u8 = kani::any();
+        let second: u8 = kani::any();
+
+        // Move the positions past the end of the ring
+        for _ in 0..3 {
+            queue.try_push(0).unwrap();
+            assert!(queue.pop() == Some(0));
+        }
+        queue.try_push(first).unwrap();
+        queue.try_push(second).unwrap();
+        assert!(queue.pop() == Some(first));
+        assert!(queue.pop() == Some(second));
+        assert!(queue.pop().is_none());
+    }
+
+    #[kani::proof]
+    #[kani::unwind(6)]
+    fn verify_capacity_enforcement() {
+        let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+        let value: u32 = kani::any();
+
+        for i in 0..4 {
+            assert!(queue.try_push(i).is_ok());
+        }
+        assert!(queue.try_push(value) == Err(value));
+        assert!(queue.len() == 4);
+
+        assert!(queue.pop() == Some(0));
+        assert!(queue.try_push(value).is_ok());
+        assert!(queue.len() == 4);
+    }
+
+    #[kani::proof]
+    #[kani::unwind(10)]
+    fn verify_interleaved_producer_and_consumer() {
+        let queue: MpmcQueue<u8, 2> = MpmcQueue::new();
+        let mut produced: u8 = 0;
+        let mut consumed: u8 = 0;
+
+        // Any schedule of producer and consumer steps, wrapping the ring
+        for _ in 0..8 {
+            if kani::any() {
+                match queue.try_push(produced) {
+                    Ok(()) => produced += 1,
+                    Err(rejected) => {
+                        assert!(rejected == produced);
+                        assert!(produced - consumed == 2);
+                    },
+                }
+            } else {
+                match queue.pop() {
+                    Some(value) => {
+                        assert!(value == consumed);
+                        consumed += 1;
+                    },
+                    None => assert!(produced == consumed),
+                }
+            }
+            assert!(queue.len() == usize::from(produced - consumed));
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(6)]
+    fn verify_drop_cleanup() {
+        let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+        queue.try_push(1).unwrap();
+        queue.try_push(2).unwrap();
+
+        drop(queue); // KANI verifies no leaks
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_and_capacity() {
+        let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+        assert!(queue.is_empty());
+        for value in 0..4 {
+            queue.push(value).unwrap();
+        }
+        assert_eq!(queue.try_push(4), Err(4));
+        assert!(queue.push(4).is_err());
+
+        for round in 0..10 {
+            assert_eq!(queue.pop(), Some(round));
+            queue.push(round + 4).unwrap();
+        }
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.capacity(), 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concurrent_producers_and_consumers() {
+        use std::{
+            sync::atomic::AtomicU64,
+            thread,
+        };
+
+        const PER_PRODUCER: u64 = 10_000;
+        static QUEUE: MpmcQueue<u64, 64> = MpmcQueue::new();
+        static SUM: AtomicU64 = AtomicU64::new(0);
+        static RECEIVED: AtomicU64 = AtomicU64::new(0);
+
+        thread::scope(|scope| {
+            for producer in 0..4u64 {
+                scope.spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        let mut value = producer * PER_PRODUCER + i;
+                        while let Err(rejected) = QUEUE.try_push(value) {
+                            value = rejected;
+                            thread::yield_now();
+                        }
+                    }
+                });
+            }
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    while RECEIVED.load(Ordering::Relaxed) < 4 * PER_PRODUCER {
+                        match QUEUE.pop() {
+                            Some(value) => {
+                                SUM.fetch_add(value, Ordering::Relaxed);
+                                RECEIVED.fetch_add(1, Ordering::Relaxed);
+                            },
+                            None => thread::yield_now(),
+                        }
+                    }
+                });
+            }
+        });
+
+        let count = 4 * PER_PRODUCER;
+        assert_eq!(RECEIVED.load(Ordering::Relaxed), count);
+        assert_eq!(SUM.load(Ordering::Relaxed), count * (count - 1) / 2);
+        assert!(QUEUE.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_drop_releases_elements() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        {
+            let queue: MpmcQueue<Rc<()>, 2> = MpmcQueue::new();
+            queue.push(Rc::clone(&tracker)).unwrap();
+            queue.push(Rc::clone(&tracker)).unwrap();
+            assert_eq!(Rc::strong_count(&tracker), 3);
+        }
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+}
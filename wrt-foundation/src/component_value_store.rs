@@ -401,12 +401,10 @@ pub fn intern_type(&mut self, ty: ValType<P>) -> Result<ValTypeRef> {
         #[cfg(feature = "std")]
         {
             // Search through the type_to_ref_map to find existing type
-            for i in 0..self.type_to_ref_map.len() {
-                if let Ok((stored_type, type_ref)) = self.type_to_ref_map.get(i) {
-                    if stored_type == ty {
-                        return Ok(type_ref);
-                    }
-                }
+            if let Some((_, type_ref)) =
+                self.type_to_ref_map.iter().find(|(stored_type, _)| *stored_type == ty)
+            {
+                return Ok(type_ref);
             }
         }
 
@@ -0,0 +1,761 @@
+// WRT - wrt-foundation
+// Module: Copy-on-write memory provider
+// SW-REQ-ID: REQ_MEMORY_001
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+#![allow(unsafe_code)] // Private chunks live in a mapping filled in through a shared reference
+
+//! Copy-on-write memory provider for instances spawned from one snapshot
+//!
+//! A [`CowSnapshot`] holds the initial memory image of a module once. Every
+//! [`CowProvider`] created from it reads clean chunks straight from the
+//! shared image, and bytes past the end of the image as zero. A chunk is
+//! copied into the instance's private pages only when it is first written,
+//! or when a borrowed slice spans both clean and private chunks and has to
+//! be contiguous. [`Provider::read_into`] assembles such ranges chunk by
+//! chunk instead, so plain reads never copy.
+//!
+//! The private pages come from a `PageAllocator` mapping of the full
+//! capacity. Hosted allocators map anonymous memory, which the operating
+//! system only commits once a page is touched, so spawning a hundred
+//! instances of a module with 64 MiB of memory costs the shared image plus
+//! the chunks each instance dirties.
+//!
+//! `Default` and `Clone` cannot report a failed mapping, so they never
+//! panic: `Default` yields an empty, unmapped provider, and a clone whose
+//! mapping fails is unmapped and rejects every access with an error.
+
+use alloc::{
+    boxed::Box,
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicU8,
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_platform::memory::{
+    PageAllocator,
+    WASM_PAGE_SIZE,
+};
+
+use crate::{
+    safe_memory::{
+        Allocator,
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+    },
+    verification::VerificationLevel,
+};
+
+/// Granularity of the copy-on-write tracking, matching the usual OS page
+pub const COW_CHUNK_SIZE: usize = 4096;
+
+/// Chunk still served from the snapshot, zero past its end
+const CLEAN: u8 = 0;
+/// Chunk being copied into the private pages
+const COPYING: u8 = 1;
+/// Chunk served from the private pages
+const PRIVATE: u8 = 2;
+
+/// Contents of a clean chunk past the end of the snapshot
+static ZEROES: [u8; COW_CHUNK_SIZE] = [0; COW_CHUNK_SIZE];
+
+/// Immutable memory image shared by all instances spawned from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CowSnapshot {
+    data: Arc<[u8]>,
+}
+
+impl CowSnapshot {
+    /// Create a snapshot from the initial memory contents
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        Self { data: data.into() }
+    }
+
+    /// Size of the image in bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the image is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Number of providers currently sharing this image
+    pub fn instances(&self) -> usize {
+        Arc::strong_count(&self.data) - 1
+    }
+
+    /// Create a provider of `capacity` bytes starting out with this image,
+    /// with private pages mapped by `allocator`
+    ///
+    /// Bytes past the end of the image start out as zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `capacity` is smaller than the image or the
+    /// allocator cannot map it.
+    pub fn instance<A: PageAllocator>(
+        &self,
+        allocator: A,
+        capacity: usize,
+    ) -> Result<CowProvider<A>> {
+        CowProvider::new(
+            allocator,
+            self.clone(),
+            capacity,
+            VerificationLevel::default(),
+        )
+    }
+}
+
+/// Memory provider sharing unmodified chunks with a [`CowSnapshot`]
+///
+/// The `Provider` impl needs `A: Default` so that clones can map private
+/// pages of their own.
+pub struct CowProvider<A: PageAllocator> {
+    /// Allocator owning the private pages
+    allocator:          A,
+    /// Shared initial image
+    snapshot:           Arc<[u8]>,
+    /// Start of the private pages, `None` if unmapped
+    private:            Option<NonNull<u8>>,
+    /// Bytes committed by the allocator, excluding any guard region
+    mapped:             usize,
+    /// Usable bytes
+    capacity:           usize,
+    /// `CLEAN`, `COPYING` or `PRIVATE` per chunk
+    chunks:             Box<[AtomicU8]>,
+    /// Chunks copied into the private pages so far
+    copied:             AtomicUsize,
+    /// Highest byte offset in use
+    used:               usize,
+    /// Counter for access operations
+    access_count:       AtomicUsize,
+    /// Largest single access
+    max_access_size:    AtomicUsize,
+    /// Verification level for runtime checks
+    verification_level: VerificationLevel,
+}
+
+// SAFETY: The private pages are owned by the provider. Through &self, bytes
+// of a chunk are only written while it is COPYING, which exactly one thread
+// can claim, and the private pages are only read for PRIVATE chunks, which
+// are never written through &self again. The allocator is Send + Sync.
+unsafe impl<A: PageAllocator> Send for CowProvider<A> {}
+
+// SAFETY: See the Send impl above.
+unsafe impl<A: PageAllocator> Sync for CowProvider<A> {}
+
+impl<A: PageAllocator> CowProvider<A> {
+    /// Create a provider of `capacity` bytes on top of `snapshot`, with
+    /// private pages mapped by `allocator`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `capacity` is smaller than the snapshot or the
+    /// allocator cannot map it.
+    pub fn new(
+        mut allocator: A,
+        snapshot: CowSnapshot,
+        capacity: usize,
+        verification_level: VerificationLevel,
+    ) -> Result<Self> {
+        let snapshot = snapshot.data;
+        if capacity < snapshot.len() {
+            return Err(Error::memory_error(
+                "Copy-on-write capacity is smaller than the snapshot",
+            ));
+        }
+        let pages = u32::try_from(capacity.div_ceil(WASM_PAGE_SIZE).max(1))
+            .map_err(|_| Error::memory_error("Copy-on-write capacity exceeds page count limit"))?;
+        let (base, mapped) = allocator.allocate(pages, Some(pages))?;
+        if mapped < capacity {
+            // SAFETY: base and the reservation size come straight from the
+            // allocation above and nothing refers to the region yet.
+            let _ = unsafe { allocator.deallocate(base, mapped + allocator.guard_size()) };
+            return Err(Error::memory_error(
+                "Allocator committed less memory than requested",
+            ));
+        }
+
+        let mut provider = Self::unmapped(allocator, snapshot, capacity, verification_level);
+        provider.private = Some(base);
+        provider.mapped = mapped;
+        provider.used = provider.snapshot.len();
+        Ok(provider)
+    }
+
+    /// A provider without private pages; accesses fail unless `capacity` is 0
+    fn unmapped(
+        allocator: A,
+        snapshot: Arc<[u8]>,
+        capacity: usize,
+        verification_level: VerificationLevel,
+    ) -> Self {
+        Self {
+            allocator,
+            snapshot,
+            private: None,
+            mapped: 0,
+            capacity,
+            chunks: (0..capacity.div_ceil(COW_CHUNK_SIZE))
+                .map(|_| AtomicU8::new(CLEAN))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            copied: AtomicUsize::new(0),
+            used: 0,
+            access_count: AtomicUsize::new(0),
+            max_access_size: AtomicUsize::new(0),
+            verification_level,
+        }
+    }
+
+    /// Whether the provider owns private pages
+    pub fn is_mapped(&self) -> bool {
+        self.private.is_some()
+    }
+
+    /// Number of chunks copied into the private pages
+    pub fn copied_chunks(&self) -> usize {
+        self.copied.load(Ordering::Relaxed)
+    }
+
+    /// Bytes held in private pages
+    pub fn private_bytes(&self) -> usize {
+        (0..self.chunks.len())
+            .filter(|&chunk| self.is_private(chunk))
+            .map(|chunk| self.chunk_range(chunk).len())
+            .sum()
+    }
+
+    /// Bytes of the snapshot this provider still shares
+    pub fn shared_bytes(&self) -> usize {
+        (0..self.chunks.len())
+            .filter(|&chunk| !self.is_private(chunk))
+            .map(|chunk| self.snapshot_part(chunk).len())
+            .sum()
+    }
+
+    /// Private bytes in `offset..offset + len`
+    ///
+    /// # Safety
+    ///
+    /// The provider must be mapped, the range must lie within the capacity
+    /// and no other reference to it may be live.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn private_mut(&self, offset: usize, len: usize) -> &mut [u8] {
+        let base = self.private.unwrap_or(NonNull::dangling());
+        // SAFETY: Guaranteed by the caller.
+        unsafe { core::slice::from_raw_parts_mut(base.as_ptr().add(offset), len) }
+    }
+
+    /// Private bytes in `offset..offset + len`
+    ///
+    /// # Safety
+    ///
+    /// The provider must be mapped, the range must lie within the capacity
+    /// and only cover PRIVATE chunks.
+    unsafe fn private(&self, offset: usize, len: usize) -> &[u8] {
+        let base = self.private.unwrap_or(NonNull::dangling());
+        // SAFETY: Guaranteed by the caller; PRIVATE chunks are only written
+        // through &mut self.
+        unsafe { core::slice::from_raw_parts(base.as_ptr().add(offset), len) }
+    }
+
+    fn is_private(&self, chunk: usize) -> bool {
+        self.chunks[chunk].load(Ordering::Acquire) == PRIVATE
+    }
+
+    fn chunk_range(&self, chunk: usize) -> core::ops::Range<usize> {
+        chunk * COW_CHUNK_SIZE..((chunk + 1) * COW_CHUNK_SIZE).min(self.capacity)
+    }
+
+    fn chunks_of(offset: usize, len: usize) -> core::ops::Range<usize> {
+        offset / COW_CHUNK_SIZE..(offset + len).div_ceil(COW_CHUNK_SIZE)
+    }
+
+    /// Part of the snapshot backing `chunk`; the rest of a clean chunk is zero
+    fn snapshot_part(&self, chunk: usize) -> &[u8] {
+        let range = self.chunk_range(chunk);
+        let end = range.end.min(self.snapshot.len());
+        &self.snapshot[range.start.min(end)..end]
+    }
+
+    /// Copy the contents of `chunk` at `offset..offset + buffer.len()`
+    /// (relative to the chunk) into `buffer`
+    fn read_chunk(&self, chunk: usize, offset: usize, buffer: &mut [u8]) {
+        let start = chunk * COW_CHUNK_SIZE + offset;
+        if self.is_private(chunk) {
+            // SAFETY: The chunk is PRIVATE and within the capacity.
+            buffer.copy_from_slice(unsafe { self.private(start, buffer.len()) });
+            return;
+        }
+        // A COPYING chunk still holds its clean contents
+        let image = self.snapshot_part(chunk).get(offset..).unwrap_or(&[]);
+        let split = image.len().min(buffer.len());
+        buffer[..split].copy_from_slice(&image[..split]);
+        buffer[split..].fill(0);
+    }
+
+    /// Copy the clean chunks covering `offset..offset + len` into the
+    /// private pages
+    fn make_private(&self, offset: usize, len: usize) {
+        for chunk in Self::chunks_of(offset, len) {
+            let state = &self.chunks[chunk];
+            loop {
+                match state.compare_exchange(CLEAN, COPYING, Ordering::Acquire, Ordering::Acquire) {
+                    Ok(_) => {
+                        let range = self.chunk_range(chunk);
+                        let image = self.snapshot_part(chunk);
+                        // SAFETY: This thread claimed the chunk, and callers
+                        // only reach here on a mapped provider after
+                        // `verify_access`.
+                        let target = unsafe { self.private_mut(range.start, range.len()) };
+                        target[..image.len()].copy_from_slice(image);
+                        target[image.len()..].fill(0);
+                        self.copied.fetch_add(1, Ordering::Relaxed);
+                        state.store(PRIVATE, Ordering::Release);
+                        break;
+                    },
+                    Err(PRIVATE) => break,
+                    Err(_) => core::hint::spin_loop(),
+                }
+            }
+        }
+    }
+
+    /// Contiguous view of `offset..offset + len`, which must be in bounds
+    ///
+    /// Uniform ranges are served without copying. Only a range mixing clean
+    /// and private chunks has its clean chunks made private.
+    fn view(&self, offset: usize, len: usize) -> &[u8] {
+        if len == 0 {
+            return &[];
+        }
+        let end = offset + len;
+        let chunks = Self::chunks_of(offset, len);
+        if !chunks.clone().any(|chunk| self.is_private(chunk)) {
+            if end <= self.snapshot.len() {
+                return &self.snapshot[offset..end];
+            }
+            if offset >= self.snapshot.len() && len <= ZEROES.len() {
+                return &ZEROES[..len];
+            }
+        }
+        self.make_private(offset, len);
+        // SAFETY: All chunks of the range are now PRIVATE.
+        unsafe { self.private(offset, len) }
+    }
+
+    /// Mutable view of `offset..offset + len`, which must be in bounds
+    fn view_mut(&mut self, offset: usize, len: usize) -> &mut [u8] {
+        self.make_private(offset, len);
+        // SAFETY: &mut self excludes every other borrow of the pages.
+        unsafe { self.private_mut(offset, len) }
+    }
+
+    fn track_access(&self, len: usize) {
+        self.access_count.fetch_add(1, Ordering::Relaxed);
+        self.max_access_size.fetch_max(len, Ordering::Relaxed);
+    }
+}
+
+impl<A: PageAllocator> Drop for CowProvider<A> {
+    fn drop(&mut self) {
+        if let Some(base) = self.private {
+            let reserved = self.mapped + self.allocator.guard_size();
+            // SAFETY: The region was allocated by this allocator with exactly
+            // this reservation, and no borrows outlive the provider.
+            let _ = unsafe { self.allocator.deallocate(base, reserved) };
+        }
+    }
+}
+
+impl<A: PageAllocator + Default> Default for CowProvider<A> {
+    /// An empty, unmapped provider; spawn instances with
+    /// [`CowSnapshot::instance`]
+    fn default() -> Self {
+        Self::unmapped(A::default(), Arc::from([]), 0, VerificationLevel::default())
+    }
+}
+
+impl<A: PageAllocator + Default> Clone for CowProvider<A> {
+    /// Maps private pages from a default allocator and copies the private
+    /// chunks; clean chunks stay shared with the snapshot. If that mapping
+    /// fails the clone is unmapped and every access to it returns an error.
+    fn clone(&self) -> Self {
+        let unmapped = || {
+            Self::unmapped(
+                A::default(),
+                self.snapshot.clone(),
+                self.capacity,
+                self.verification_level,
+            )
+        };
+        if !self.is_mapped() {
+            return unmapped();
+        }
+        let snapshot = CowSnapshot {
+            data: self.snapshot.clone(),
+        };
+        let Ok(mut clone) = Self::new(
+            A::default(),
+            snapshot,
+            self.capacity,
+            self.verification_level,
+        ) else {
+            return unmapped();
+        };
+        for chunk in (0..self.chunks.len()).filter(|&chunk| self.is_private(chunk)) {
+            let range = self.chunk_range(chunk);
+            // SAFETY: Both providers are mapped with this capacity, the
+            // chunk is PRIVATE here, and nothing borrows the fresh clone.
+            unsafe { clone.private_mut(range.start, range.len()) }
+                .copy_from_slice(unsafe { self.private(range.start, range.len()) });
+            clone.chunks[chunk].store(PRIVATE, Ordering::Release);
+        }
+        clone.copied.store(self.copied_chunks(), Ordering::Relaxed);
+        clone.used = self.used;
+        clone
+    }
+}
+
+impl<A: PageAllocator> PartialEq for CowProvider<A> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut ours = [0u8; COW_CHUNK_SIZE];
+        let mut theirs = [0u8; COW_CHUNK_SIZE];
+        self.capacity == other.capacity
+            && self.used == other.used
+            && self.is_mapped() == other.is_mapped()
+            && self.verification_level == other.verification_level
+            && (0..self.chunks.len()).all(|chunk| {
+                let len = self.chunk_range(chunk).len();
+                self.read_chunk(chunk, 0, &mut ours[..len]);
+                other.read_chunk(chunk, 0, &mut theirs[..len]);
+                ours[..len] == theirs[..len]
+            })
+    }
+}
+
+impl<A: PageAllocator> Eq for CowProvider<A> {}
+
+impl<A: PageAllocator> fmt::Debug for CowProvider<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CowProvider")
+            .field("capacity", &self.capacity)
+            .field("mapped", &self.is_mapped())
+            .field("used", &self.used)
+            .field("snapshot_len", &self.snapshot.len())
+            .field("copied_chunks", &self.copied_chunks())
+            .field("verification_level", &self.verification_level)
+            .finish()
+    }
+}
+
+impl<A: PageAllocator + Default + 'static> Provider for CowProvider<A> {
+    type Allocator = Self;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        Slice::with_verification_level(self.view(offset, len), self.verification_level)
+    }
+
+    fn read_into(&self, offset: usize, buffer: &mut [u8]) -> Result<()> {
+        self.verify_access(offset, buffer.len())?;
+        self.track_access(buffer.len());
+        let mut position = offset;
+        for part in buffer.chunks_mut(COW_CHUNK_SIZE) {
+            // Split so that no piece crosses a chunk boundary
+            let (head, tail) =
+                part.split_at_mut(part.len().min(COW_CHUNK_SIZE - position % COW_CHUNK_SIZE));
+            for piece in [head, tail] {
+                if !piece.is_empty() {
+                    let chunk = position / COW_CHUNK_SIZE;
+                    self.read_chunk(chunk, position % COW_CHUNK_SIZE, piece);
+                    position += piece.len();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.verify_access(offset, data.len())?;
+        self.track_access(data.len());
+        self.view_mut(offset, data.len()).copy_from_slice(data);
+        self.used = core::cmp::max(self.used, offset + data.len());
+        Ok(())
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        if self.private.is_none() && self.capacity != 0 {
+            return Err(Error::memory_error("CowProvider has no private pages"));
+        }
+        if offset.checked_add(len).map_or(true, |end| end > self.capacity) {
+            return Err(Error::memory_out_of_bounds("Access out of bounds"));
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.used
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        if self.private.is_none() && self.capacity != 0 {
+            return Err(Error::memory_error("CowProvider has no private pages"));
+        }
+        if self.used > self.capacity
+            || self.snapshot.len() > self.capacity
+            || (self.private.is_some() && self.capacity > self.mapped)
+        {
+            return Err(Error::validation_error("Corrupted state: used > capacity"));
+        }
+        Ok(())
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        self.verification_level = level;
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        self.verification_level
+    }
+
+    fn memory_stats(&self) -> Stats {
+        Stats {
+            total_size:      self.capacity,
+            access_count:    self.access_count.load(Ordering::Relaxed),
+            unique_regions:  self.copied_chunks(),
+            max_access_size: self.max_access_size.load(Ordering::Relaxed),
+        }
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        self.used = core::cmp::max(self.used, offset + len);
+        let level = self.verification_level;
+        SliceMut::with_verification_level(self.view_mut(offset, len), level)
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        self.verify_access(src_offset, len)?;
+        self.verify_access(dst_offset, len)?;
+        self.track_access(len);
+        self.make_private(src_offset, len);
+        self.make_private(dst_offset, len);
+        // SAFETY: Both ranges are in bounds and PRIVATE, and &mut self
+        // excludes other borrows; `copy` handles the overlap.
+        unsafe {
+            let base = self.private_mut(0, self.capacity).as_mut_ptr();
+            core::ptr::copy(base.add(src_offset), base.add(dst_offset), len);
+        }
+        self.used = core::cmp::max(self.used, dst_offset + len);
+        Ok(())
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        if self.private.is_none() && byte_offset != 0 {
+            return Err(Error::memory_error("CowProvider has no private pages"));
+        }
+        if byte_offset > self.capacity {
+            return Err(Error::memory_error("Offset exceeds capacity"));
+        }
+        self.used = core::cmp::max(self.used, byte_offset);
+        Ok(())
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        self
+    }
+
+    fn acquire_memory(&self, layout: core::alloc::Layout) -> wrt_error::Result<*mut u8> {
+        self.allocate(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: core::alloc::Layout) -> wrt_error::Result<()> {
+        self.deallocate(ptr, layout)
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.clone()))
+    }
+}
+
+impl<A: PageAllocator> Allocator for CowProvider<A> {
+    fn allocate(&self, _layout: core::alloc::Layout) -> wrt_error::Result<*mut u8> {
+        Err(Error::memory_error(
+            "CowProvider does not support raw allocation",
+        ))
+    }
+
+    fn deallocate(&self, _ptr: *mut u8, _layout: core::alloc::Layout) -> wrt_error::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{
+        alloc,
+        dealloc,
+        Layout,
+    };
+
+    use super::*;
+
+    /// Heap-backed allocator handing out pages filled with garbage, so the
+    /// tests catch any reliance on zeroed mappings
+    #[derive(Debug, Default)]
+    struct DirtyPages {
+        reserved: usize,
+    }
+
+    impl PageAllocator for DirtyPages {
+        fn allocate(
+            &mut self,
+            initial_pages: u32,
+            _maximum_pages: Option<u32>,
+        ) -> Result<(NonNull<u8>, usize)> {
+            self.reserved = initial_pages as usize * WASM_PAGE_SIZE;
+            let layout = Layout::from_size_align(self.reserved, WASM_PAGE_SIZE).unwrap();
+            let ptr = NonNull::new(unsafe { alloc(layout) })
+                .ok_or_else(|| Error::memory_error("Out of memory"))?;
+            unsafe { ptr.as_ptr().write_bytes(0xCD, self.reserved) };
+            Ok((ptr, self.reserved))
+        }
+
+        fn grow(&mut self, _current_pages: u32, _additional_pages: u32) -> Result<()> {
+            Err(Error::memory_error("Fixed size"))
+        }
+
+        unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
+            assert_eq!(size, self.reserved);
+            unsafe {
+                dealloc(
+                    ptr.as_ptr(),
+                    Layout::from_size_align(size, WASM_PAGE_SIZE).unwrap(),
+                )
+            };
+            Ok(())
+        }
+    }
+
+    fn image() -> CowSnapshot {
+        CowSnapshot::new((0..3 * COW_CHUNK_SIZE + 10).map(|i| i as u8).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_instances_share_clean_chunks() {
+        let snapshot = image();
+        let mut first = snapshot.instance(DirtyPages::default(), 8 * COW_CHUNK_SIZE).unwrap();
+        let second = snapshot.instance(DirtyPages::default(), 8 * COW_CHUNK_SIZE).unwrap();
+        assert_eq!(snapshot.instances(), 2);
+        assert_eq!(first.size(), snapshot.len());
+        assert_eq!(first.shared_bytes(), snapshot.len());
+        assert_eq!(first.private_bytes(), 0);
+
+        first.write_data(COW_CHUNK_SIZE + 1, &[0xAA, 0xBB]).unwrap();
+        assert_eq!(first.copied_chunks(), 1);
+        assert_eq!(first.private_bytes(), COW_CHUNK_SIZE);
+        assert_eq!(second.copied_chunks(), 0);
+        assert_eq!(
+            first.borrow_slice(COW_CHUNK_SIZE, 4).unwrap().as_ref(),
+            &[0, 0xAA, 0xBB, 3]
+        );
+        assert_eq!(
+            second.borrow_slice(COW_CHUNK_SIZE, 4).unwrap().as_ref(),
+            &[0, 1, 2, 3]
+        );
+        assert_ne!(first, second);
+
+        // Past the image everything reads as zero, without copying
+        let tail = 3 * COW_CHUNK_SIZE;
+        assert_eq!(second.borrow_slice(tail + 16, 4).unwrap().as_ref(), &[0; 4]);
+        assert_eq!(second.copied_chunks(), 0);
+        assert_eq!(
+            first.borrow_slice(tail + 8, 4).unwrap().as_ref(),
+            &[8, 9, 0, 0]
+        );
+        first.write_data(7 * COW_CHUNK_SIZE, &[1]).unwrap();
+        assert_eq!(
+            first.borrow_slice(7 * COW_CHUNK_SIZE, 2).unwrap().as_ref(),
+            &[1, 0]
+        );
+        assert!(first.borrow_slice(8 * COW_CHUNK_SIZE - 1, 2).is_err());
+    }
+
+    #[test]
+    fn test_mixed_ranges_and_clones() {
+        let snapshot = image();
+        let mut memory = snapshot.instance(DirtyPages::default(), 4 * COW_CHUNK_SIZE).unwrap();
+        memory.write_data(COW_CHUNK_SIZE, &[7]).unwrap();
+
+        // Reading into a buffer across a clean and a private chunk copies nothing
+        let end = 2 * COW_CHUNK_SIZE - 2;
+        let expected = [(end % 256) as u8, (end + 1) as u8, 0, 1];
+        let mut buffer = [0; 4];
+        memory.read_into(end, &mut buffer).unwrap();
+        assert_eq!(buffer, expected);
+        let mut whole = vec![0; 4 * COW_CHUNK_SIZE];
+        memory.read_into(0, &mut whole).unwrap();
+        assert_eq!(whole[COW_CHUNK_SIZE], 7);
+        assert_eq!(whole[3 * COW_CHUNK_SIZE + 9], 9);
+        assert!(whole[3 * COW_CHUNK_SIZE + 10..].iter().all(|&byte| byte == 0));
+        assert_eq!(memory.copied_chunks(), 1);
+
+        // Only a contiguous borrow of such a range copies the clean chunk
+        assert_eq!(memory.borrow_slice(end, 4).unwrap().as_ref(), &expected);
+        assert_eq!(memory.copied_chunks(), 2);
+
+        memory.copy_within(0, 3 * COW_CHUNK_SIZE, 16).unwrap();
+        assert_eq!(
+            memory.borrow_slice(3 * COW_CHUNK_SIZE + 15, 1).unwrap().as_ref(),
+            &[15]
+        );
+
+        let clone = memory.clone();
+        assert!(clone.is_mapped());
+        assert_eq!(clone, memory);
+        assert_eq!(clone.shared_bytes(), memory.shared_bytes());
+        assert!(snapshot.instance(DirtyPages::default(), COW_CHUNK_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_default_is_unmapped() {
+        let mut provider = CowProvider::<DirtyPages>::default();
+        assert!(!provider.is_mapped());
+        assert_eq!(provider.capacity(), 0);
+        assert!(provider.borrow_slice(0, 0).unwrap().as_ref().is_empty());
+        assert!(provider.write_data(0, &[1]).is_err());
+        assert_eq!(provider.clone(), provider);
+    }
+}
@@ -0,0 +1,177 @@
+// WRT - wrt-foundation
+// Module: Derive macro support
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Items referenced by code generated by the `wrt-derive` macros.
+//!
+//! Not part of the public API; the macros only need these to be reachable
+//! without the deriving crate depending on `wrt-error` itself.
+
+pub use wrt_error::Result;
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+};
+
+/// Fixed encoded size of a sequence of fields, if every field has one
+#[must_use]
+pub const fn fixed_size_sum(sizes: &[Option<usize>]) -> Option<usize> {
+    let mut total = 0usize;
+    let mut i = 0;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) => total += size,
+            None => return None,
+        }
+        i += 1;
+    }
+    Some(total)
+}
+
+/// Fixed encoded size of an enum: its tag plus the size shared by all
+/// variants, if every variant has the same fixed size
+#[must_use]
+pub const fn fixed_variant_size(sizes: &[Option<usize>]) -> Option<usize> {
+    let Some(Some(first)) = sizes.first().copied() else {
+        return None;
+    };
+    let mut i = 1;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) if size == first => {},
+            _ => return None,
+        }
+        i += 1;
+    }
+    Some(1 + first)
+}
+
+/// Error for an enum tag that matches no variant
+#[must_use]
+pub const fn invalid_tag(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Parse, codes::DESERIALIZATION_ERROR, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bounded::BoundedVec,
+        budget_aware_provider::CrateId,
+        safe_managed_alloc,
+        safe_memory::{
+            NoStdProvider,
+            Slice,
+            SliceMut,
+        },
+        traits::{
+            ReadStream,
+            WriteStream,
+        },
+        verification::Checksum,
+        Checksummable,
+        FromBytes,
+        ToBytes,
+    };
+
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+    struct Point {
+        x: u32,
+        y: i16,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+    struct Pair<T>(T, bool);
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
+    enum Edit {
+        #[default]
+        None,
+        Move(Point),
+        #[wrt(tag = 7)]
+        Remove {
+            offset: u64,
+            length: u32,
+        },
+        Clear,
+    }
+
+    fn round_trip<T: ToBytes + FromBytes>(value: &T) -> ([u8; 32], usize, T) {
+        let provider = safe_managed_alloc!(64, CrateId::Foundation).unwrap();
+        let mut buffer = [0u8; 32];
+        let written = {
+            let mut writer = WriteStream::new(SliceMut::new(&mut buffer).unwrap());
+            value.to_bytes_with_provider(&mut writer, &provider).unwrap();
+            writer.position()
+        };
+        let mut reader = ReadStream::new(Slice::new(&buffer[..written]).unwrap());
+        let decoded = T::from_bytes_with_provider(&mut reader, &provider).unwrap();
+        assert_eq!(reader.position(), written);
+        (buffer, written, decoded)
+    }
+
+    fn checksum_of<T: Checksummable>(value: &T) -> Checksum {
+        let mut checksum = Checksum::new();
+        value.update_checksum(&mut checksum);
+        checksum
+    }
+
+    #[test]
+    fn test_struct_encodes_fields_in_order() {
+        let point = Point { x: 0x0403_0201, y: -2 };
+        let (buffer, written, decoded) = round_trip(&point);
+        assert_eq!(&buffer[..written], &[1, 2, 3, 4, 0xfe, 0xff]);
+        assert_eq!(decoded, point);
+        assert_eq!(point.serialized_size(), written);
+        assert_eq!(Point::FIXED_SERIALIZED_SIZE, Some(6));
+        assert_eq!(checksum_of(&point), Checksum::compute(&buffer[..written]));
+
+        let pair = Pair(point, true);
+        assert_eq!(round_trip(&pair).2, pair);
+        assert_eq!(Pair::<Point>::FIXED_SERIALIZED_SIZE, Some(7));
+    }
+
+    #[test]
+    fn test_enum_tags_are_stable() {
+        let cases = [
+            (Edit::None, 0u8),
+            (Edit::Move(Point { x: 1, y: 2 }), 1),
+            (Edit::Remove { offset: 5, length: 6 }, 7),
+            (Edit::Clear, 8),
+        ];
+        for (edit, tag) in cases {
+            let (buffer, written, decoded) = round_trip(&edit);
+            assert_eq!(buffer[0], tag);
+            assert_eq!(decoded, edit);
+            assert_eq!(edit.serialized_size(), written);
+            assert_eq!(checksum_of(&edit), Checksum::compute(&buffer[..written]));
+        }
+        // Variants differ in size, so there is no fixed stride
+        assert_eq!(Edit::FIXED_SERIALIZED_SIZE, None);
+
+        let provider = safe_managed_alloc!(64, CrateId::Foundation).unwrap();
+        let mut reader = ReadStream::new(Slice::new(&[3]).unwrap());
+        assert!(Edit::from_bytes_with_provider(&mut reader, &provider).is_err());
+    }
+
+    #[test]
+    fn test_derived_types_in_bounded_vec() {
+        let provider = safe_managed_alloc!(1024, CrateId::Foundation).unwrap();
+        let mut vec = BoundedVec::<Point, 4, NoStdProvider<1024>>::new(provider).unwrap();
+        vec.push(Point { x: 1, y: -1 }).unwrap();
+        vec.push(Point { x: 2, y: -2 }).unwrap();
+        assert!(vec.iter().eq([Point { x: 1, y: -1 }, Point { x: 2, y: -2 }]));
+    }
+
+    #[test]
+    fn test_fixed_size_helpers() {
+        assert_eq!(super::fixed_size_sum(&[]), Some(0));
+        assert_eq!(super::fixed_size_sum(&[Some(2), None]), None);
+        assert_eq!(super::fixed_variant_size(&[Some(4), Some(4)]), Some(5));
+        assert_eq!(super::fixed_variant_size(&[Some(4), Some(0)]), None);
+        assert_eq!(super::fixed_variant_size(&[]), None);
+    }
+}
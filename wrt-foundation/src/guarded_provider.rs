@@ -0,0 +1,579 @@
+// WRT - wrt-foundation
+// Module: Guard-page backed memory provider
+// SW-REQ-ID: REQ_MEMORY_001 REQ_PLATFORM_001
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+#![allow(unsafe_code)] // Owns and accesses a region mapped by a PageAllocator
+
+//! Memory provider whose backing store is mapped by a `PageAllocator` with a
+//! guard region directly after the usable bytes.
+//!
+//! [`GuardedProvider`] implements [`Provider`], so it can back a
+//! `BoundedVec` or a `SafeMemoryHandler` like any other provider. The data is
+//! placed at the end of the mapping, flush against the allocator's guard
+//! region, so an overrun that slips past the bounds checks faults on the
+//! first byte instead of silently corrupting the neighbouring allocation.
+//!
+//! `Default` and `Clone` cannot report a failed mapping, so they never panic
+//! either: `Default` yields an empty, unmapped provider, and a clone whose
+//! mapping fails is unmapped and rejects every access with an error.
+
+use core::{
+    fmt,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_platform::memory::{
+    PageAllocator,
+    WASM_PAGE_SIZE,
+};
+
+use crate::{
+    safe_memory::{
+        Allocator,
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+    },
+    verification::VerificationLevel,
+};
+
+/// A memory provider backed by a guard-page protected mapping.
+///
+/// The allocator must report a non-zero [`PageAllocator::guard_size`];
+/// construction fails otherwise. Only the end of the region is guarded, so
+/// the bounds checks of the provider remain the defence against underruns.
+///
+/// The `Provider` impl needs `A: Default` so that clones can map a region of
+/// their own.
+pub struct GuardedProvider<A: PageAllocator> {
+    /// Allocator owning the mapping
+    allocator:          A,
+    /// Start of the mapping returned by the allocator, `None` if unmapped
+    base:               Option<NonNull<u8>>,
+    /// Bytes committed by the allocator, excluding the guard region
+    mapped:             usize,
+    /// Usable bytes, ending at the guard region
+    capacity:           usize,
+    /// Highest byte offset written so far
+    used:               usize,
+    /// Counter for access operations
+    access_count:       AtomicUsize,
+    /// Largest single access
+    max_access_size:    AtomicUsize,
+    /// Verification level for runtime checks
+    verification_level: VerificationLevel,
+}
+
+// SAFETY: The mapping is exclusively owned by the provider and released on
+// drop. Shared access only hands out immutable slices, mutable access goes
+// through &mut self, and the allocator itself is Send + Sync.
+unsafe impl<A: PageAllocator> Send for GuardedProvider<A> {}
+
+// SAFETY: See the Send impl above. The counters are atomics.
+unsafe impl<A: PageAllocator> Sync for GuardedProvider<A> {}
+
+impl<A: PageAllocator> GuardedProvider<A> {
+    /// Create a provider of `capacity` bytes mapped by `allocator`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocator has no guard region or cannot map
+    /// the requested capacity.
+    pub fn new(allocator: A, capacity: usize) -> Result<Self> {
+        Self::with_verification_level(allocator, capacity, VerificationLevel::default())
+    }
+
+    /// Create a provider of `capacity` bytes mapped by `allocator`, checked at
+    /// `verification_level`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`GuardedProvider::new`].
+    pub fn with_verification_level(
+        mut allocator: A,
+        capacity: usize,
+        verification_level: VerificationLevel,
+    ) -> Result<Self> {
+        if allocator.guard_size() == 0 {
+            return Err(Error::memory_error(
+                "GuardedProvider requires an allocator with guard pages",
+            ));
+        }
+        let pages = u32::try_from(capacity.div_ceil(WASM_PAGE_SIZE).max(1))
+            .map_err(|_| Error::memory_error("Guarded capacity exceeds page count limit"))?;
+        let (base, mapped) = allocator.allocate(pages, Some(pages))?;
+        if mapped < capacity {
+            // SAFETY: base and the reservation size come straight from the
+            // allocation above and nothing refers to the region yet.
+            let _ = unsafe { allocator.deallocate(base, mapped + allocator.guard_size()) };
+            return Err(Error::memory_error(
+                "Allocator committed less memory than requested",
+            ));
+        }
+
+        Ok(Self {
+            allocator,
+            base: Some(base),
+            mapped,
+            capacity,
+            used: 0,
+            access_count: AtomicUsize::new(0),
+            max_access_size: AtomicUsize::new(0),
+            verification_level,
+        })
+    }
+
+    /// Size in bytes of the guard region following the data
+    pub fn guard_size(&self) -> usize {
+        self.allocator.guard_size()
+    }
+
+    /// Whether the provider owns a mapping
+    pub fn is_mapped(&self) -> bool {
+        self.base.is_some()
+    }
+
+    /// A provider without a mapping; accesses fail unless `capacity` is 0
+    fn unmapped(allocator: A, capacity: usize, verification_level: VerificationLevel) -> Self {
+        Self {
+            allocator,
+            base: None,
+            mapped: 0,
+            capacity,
+            used: 0,
+            access_count: AtomicUsize::new(0),
+            max_access_size: AtomicUsize::new(0),
+            verification_level,
+        }
+    }
+
+    fn data(&self) -> &[u8] {
+        match self.base {
+            // SAFETY: [base, base + mapped) is committed, readable memory owned
+            // by this provider, and the usable bytes are its last `capacity`
+            // bytes.
+            Some(base) => unsafe {
+                core::slice::from_raw_parts(
+                    base.as_ptr().add(self.mapped - self.capacity),
+                    self.capacity,
+                )
+            },
+            None => &[],
+        }
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        match self.base {
+            // SAFETY: As in `data`; &mut self guarantees exclusive access.
+            Some(base) => unsafe {
+                core::slice::from_raw_parts_mut(
+                    base.as_ptr().add(self.mapped - self.capacity),
+                    self.capacity,
+                )
+            },
+            None => &mut [],
+        }
+    }
+
+    fn track_access(&self, len: usize) {
+        self.access_count.fetch_add(1, Ordering::Relaxed);
+        self.max_access_size.fetch_max(len, Ordering::Relaxed);
+    }
+}
+
+impl<A: PageAllocator> Drop for GuardedProvider<A> {
+    fn drop(&mut self) {
+        if let Some(base) = self.base {
+            let reserved = self.mapped + self.allocator.guard_size();
+            // SAFETY: The region was allocated by this allocator with exactly
+            // this reservation, and no slices can outlive the provider.
+            let _ = unsafe { self.allocator.deallocate(base, reserved) };
+        }
+    }
+}
+
+impl<A: PageAllocator + Default> Default for GuardedProvider<A> {
+    /// An empty, unmapped provider; map memory with [`GuardedProvider::new`]
+    fn default() -> Self {
+        Self::unmapped(A::default(), 0, VerificationLevel::default())
+    }
+}
+
+impl<A: PageAllocator + Default> Clone for GuardedProvider<A> {
+    /// Maps a fresh region from a default allocator and copies the used bytes.
+    /// If that mapping fails the clone is unmapped and every access to it
+    /// returns an error.
+    fn clone(&self) -> Self {
+        if self.base.is_none() {
+            return Self::unmapped(A::default(), self.capacity, self.verification_level);
+        }
+        match Self::with_verification_level(A::default(), self.capacity, self.verification_level)
+        {
+            Ok(mut clone) => {
+                clone.data_mut()[..self.used].copy_from_slice(&self.data()[..self.used]);
+                clone.used = self.used;
+                clone
+            },
+            Err(_) => Self::unmapped(A::default(), self.capacity, self.verification_level),
+        }
+    }
+}
+
+impl<A: PageAllocator> PartialEq for GuardedProvider<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.capacity == other.capacity
+            && self.is_mapped() == other.is_mapped()
+            && self.verification_level == other.verification_level
+            && self.data()[..self.used] == other.data()[..other.used]
+    }
+}
+
+impl<A: PageAllocator> Eq for GuardedProvider<A> {}
+
+impl<A: PageAllocator> fmt::Debug for GuardedProvider<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuardedProvider")
+            .field("capacity", &self.capacity)
+            .field("mapped", &self.is_mapped())
+            .field("used", &self.used)
+            .field("guard_size", &self.guard_size())
+            .field("access_count", &self.access_count.load(Ordering::Relaxed))
+            .field("verification_level", &self.verification_level)
+            .finish()
+    }
+}
+
+impl<A: PageAllocator + Default + 'static> Provider for GuardedProvider<A> {
+    type Allocator = Self;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        Slice::with_verification_level(&self.data()[offset..offset + len], self.verification_level)
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.verify_access(offset, data.len())?;
+        self.track_access(data.len());
+        self.data_mut()[offset..offset + data.len()].copy_from_slice(data);
+        self.used = core::cmp::max(self.used, offset + data.len());
+        Ok(())
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        if self.base.is_none() && self.capacity != 0 {
+            return Err(Error::memory_error("GuardedProvider has no mapping"));
+        }
+        if offset.checked_add(len).map_or(true, |end| end > self.capacity) {
+            return Err(Error::memory_out_of_bounds("Access out of bounds"));
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.used
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        if self.base.is_none() && self.capacity != 0 {
+            return Err(Error::memory_error("GuardedProvider has no mapping"));
+        }
+        if self.used > self.capacity || (self.base.is_some() && self.capacity > self.mapped) {
+            return Err(Error::validation_error("Corrupted state: used > capacity"));
+        }
+        Ok(())
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        self.verification_level = level;
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        self.verification_level
+    }
+
+    fn memory_stats(&self) -> Stats {
+        Stats {
+            total_size:      self.capacity,
+            access_count:    self.access_count.load(Ordering::Relaxed),
+            unique_regions:  0, // Not tracked by this provider
+            max_access_size: self.max_access_size.load(Ordering::Relaxed),
+        }
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        self.verify_access(offset, len)?;
+        self.track_access(len);
+        self.used = core::cmp::max(self.used, offset + len);
+        let level = self.verification_level;
+        SliceMut::with_verification_level(&mut self.data_mut()[offset..offset + len], level)
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        self.verify_access(src_offset, len)?;
+        self.verify_access(dst_offset, len)?;
+        self.track_access(len);
+        self.data_mut().copy_within(src_offset..src_offset + len, dst_offset);
+        self.used = core::cmp::max(self.used, dst_offset + len);
+        Ok(())
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        if self.base.is_none() && byte_offset != 0 {
+            return Err(Error::memory_error("GuardedProvider has no mapping"));
+        }
+        if byte_offset > self.capacity {
+            return Err(Error::memory_error("Offset exceeds capacity"));
+        }
+        self.used = core::cmp::max(self.used, byte_offset);
+        Ok(())
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        self
+    }
+
+    fn acquire_memory(&self, layout: core::alloc::Layout) -> wrt_error::Result<*mut u8> {
+        self.allocate(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: core::alloc::Layout) -> wrt_error::Result<()> {
+        self.deallocate(ptr, layout)
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.clone()))
+    }
+}
+
+impl<A: PageAllocator> Allocator for GuardedProvider<A> {
+    fn allocate(&self, _layout: core::alloc::Layout) -> wrt_error::Result<*mut u8> {
+        // Handing out raw pointers would bypass the bounds checks the guard
+        // region is meant to back up
+        Err(Error::memory_error(
+            "GuardedProvider does not support raw allocation",
+        ))
+    }
+
+    fn deallocate(&self, _ptr: *mut u8, _layout: core::alloc::Layout) -> wrt_error::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{
+        alloc_zeroed,
+        dealloc,
+        Layout,
+    };
+
+    use super::*;
+    use crate::bounded::BoundedVec;
+
+    /// Heap-backed allocator that reserves, but cannot protect, a guard page
+    #[derive(Debug, Default)]
+    struct HeapPages {
+        reserved: usize,
+    }
+
+    impl PageAllocator for HeapPages {
+        fn allocate(
+            &mut self,
+            initial_pages: u32,
+            _maximum_pages: Option<u32>,
+        ) -> Result<(NonNull<u8>, usize)> {
+            let committed = initial_pages as usize * WASM_PAGE_SIZE;
+            self.reserved = committed + WASM_PAGE_SIZE;
+            let layout = Layout::from_size_align(self.reserved, WASM_PAGE_SIZE).unwrap();
+            let ptr = NonNull::new(unsafe { alloc_zeroed(layout) })
+                .ok_or_else(|| Error::memory_error("Out of memory"))?;
+            Ok((ptr, committed))
+        }
+
+        fn grow(&mut self, _current_pages: u32, _additional_pages: u32) -> Result<()> {
+            Err(Error::memory_error("Fixed size"))
+        }
+
+        unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
+            assert_eq!(size, self.reserved);
+            unsafe { dealloc(ptr.as_ptr(), Layout::from_size_align(size, WASM_PAGE_SIZE).unwrap()) };
+            Ok(())
+        }
+
+        fn guard_size(&self) -> usize {
+            WASM_PAGE_SIZE
+        }
+    }
+
+    /// Allocator without a guard region
+    #[derive(Debug, Default)]
+    struct Unguarded(HeapPages);
+
+    impl PageAllocator for Unguarded {
+        fn allocate(
+            &mut self,
+            initial_pages: u32,
+            maximum_pages: Option<u32>,
+        ) -> Result<(NonNull<u8>, usize)> {
+            self.0.allocate(initial_pages, maximum_pages)
+        }
+
+        fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
+            self.0.grow(current_pages, additional_pages)
+        }
+
+        unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
+            unsafe { self.0.deallocate(ptr, size) }
+        }
+    }
+
+    #[test]
+    fn test_data_ends_at_guard() {
+        let mut provider = GuardedProvider::new(HeapPages::default(), 100).unwrap();
+        assert_eq!(provider.capacity(), 100);
+        assert_eq!(provider.guard_size(), WASM_PAGE_SIZE);
+
+        provider.write_data(96, &[1, 2, 3, 4]).unwrap();
+        let end = provider.borrow_slice(96, 4).unwrap().as_ref().as_ptr_range().end;
+        assert_eq!(end as usize, provider.base.unwrap().as_ptr() as usize + WASM_PAGE_SIZE);
+        assert!(provider.write_data(97, &[0; 4]).is_err());
+
+        let clone = provider.clone();
+        assert_eq!(clone, provider);
+        assert_ne!(clone.base, provider.base);
+
+        assert!(GuardedProvider::new(Unguarded::default(), 100).is_err());
+    }
+
+    #[test]
+    fn test_default_and_failed_clone_are_unmapped() {
+        let empty = GuardedProvider::<HeapPages>::default();
+        assert!(!empty.is_mapped());
+        assert_eq!(empty.capacity(), 0);
+        assert!(empty.verify_integrity().is_ok());
+        assert!(empty.borrow_slice(0, 1).is_err());
+
+        // Clones map through Unguarded::default(), which has no guard region
+        let mut unmapped =
+            GuardedProvider::unmapped(Unguarded::default(), 64, VerificationLevel::default());
+        assert!(unmapped.write_data(0, &[1]).is_err());
+        assert!(unmapped.ensure_used_up_to(8).is_err());
+        assert!(unmapped.verify_integrity().is_err());
+        let clone = unmapped.clone();
+        assert!(!clone.is_mapped());
+        assert!(clone.borrow_slice(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_backs_bounded_vec() {
+        // u32 is not stored packed, so each element takes the 12-byte default slot
+        let provider = GuardedProvider::new(HeapPages::default(), 16 * 12).unwrap();
+        let mut vec = BoundedVec::<u32, 16, _>::new(provider).unwrap();
+        for i in 0..16 {
+            vec.push(i * 3).unwrap();
+        }
+        assert!(vec.push(99).is_err());
+        assert_eq!(vec.get(5).unwrap(), 15);
+
+        let handler =
+            GuardedProvider::new(HeapPages::default(), 32).unwrap().new_handler().unwrap();
+        assert_eq!(handler.provider().capacity(), 32);
+    }
+
+    /// `LinuxAllocator` with guard pages, default-constructible so that the
+    /// provider can be cloned
+    #[cfg(target_os = "linux")]
+    #[derive(Debug)]
+    struct GuardedLinux(wrt_platform::LinuxAllocator);
+
+    #[cfg(target_os = "linux")]
+    impl Default for GuardedLinux {
+        fn default() -> Self {
+            Self(wrt_platform::LinuxAllocator::new(None, true))
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    impl PageAllocator for GuardedLinux {
+        fn allocate(
+            &mut self,
+            initial_pages: u32,
+            maximum_pages: Option<u32>,
+        ) -> Result<(NonNull<u8>, usize)> {
+            self.0.allocate(initial_pages, maximum_pages)
+        }
+
+        fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
+            self.0.grow(current_pages, additional_pages)
+        }
+
+        unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
+            unsafe { self.0.deallocate(ptr, size) }
+        }
+
+        fn guard_size(&self) -> usize {
+            self.0.guard_size()
+        }
+    }
+
+    /// Reading the byte after the data faults on the `LinuxAllocator` guard
+    /// page. The read happens in a re-run of this test in a child process,
+    /// which must die with SIGSEGV.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linux_overrun_faults() {
+        use std::{
+            os::unix::process::ExitStatusExt,
+            process::{
+                Command,
+                Stdio,
+            },
+        };
+
+        const CHILD_ENV: &str = "WRT_GUARDED_PROVIDER_FAULT_CHILD";
+        const SIGSEGV: i32 = 11;
+
+        let mut provider = GuardedProvider::new(GuardedLinux::default(), 100).unwrap();
+        assert_eq!(provider.guard_size(), WASM_PAGE_SIZE);
+        provider.write_data(96, &[1, 2, 3, 4]).unwrap();
+        let end = provider.borrow_slice(96, 4).unwrap().as_ref().as_ptr_range().end;
+
+        if std::env::var_os(CHILD_ENV).is_some() {
+            // SAFETY: Not safe at all; the read targets the guard page and
+            // must kill this child process before returning.
+            let _ = unsafe { core::ptr::read_volatile(end) };
+            std::process::exit(0);
+        }
+
+        let status = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "guarded_provider::tests::test_linux_overrun_faults"])
+            .env(CHILD_ENV, "1")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert_eq!(status.signal(), Some(SIGSEGV));
+    }
+}
@@ -21,7 +21,7 @@
 #![allow(clippy::return_self_not_must_use)]
 #![allow(clippy::must_use_candidate)]
 #![allow(clippy::doc_markdown)]
-#![allow(hidden_glob_reexports)]
+#![deny(hidden_glob_reexports)]
 // Allow clippy warnings that would require substantial refactoring
 #![allow(clippy::needless_continue)]
 #![allow(clippy::if_not_else)]
@@ -90,14 +90,9 @@ macro_rules! has_feature {
 #[cfg(any(feature = "std", feature = "alloc"))]
 extern crate alloc;
 
-// External crates
-use wrt_error::{
-    codes,
-    kinds,
-    Error,
-    ErrorCategory,
-    Result,
-};
+// Lets the wrt-derive macros, which expand to `::wrt_foundation` paths, be
+// used inside this crate
+extern crate self as wrt_foundation;
 
 // WRT - wrt-foundation
 // SW-REQ-ID: REQ_MEM_SAFETY_001
@@ -224,16 +219,23 @@ macro_rules! safe_managed_alloc {
 pub mod safe_memory;
 /// WebAssembly section definitions
 pub mod sections;
+/// SHA-256 and HMAC-SHA256 digests
+pub mod sha256;
 /// Shared memory support for multi-threading
 pub mod shared_memory;
 /// Common traits for type conversions
 pub mod traits;
+/// Support code for the wrt-derive macros
+#[doc(hidden)]
+pub mod derive_support;
 /// Core WebAssembly types
 pub mod types;
 /// Validation utilities
 pub mod validation;
 /// WebAssembly value representations
 pub mod values;
+/// Conversion between crate-local value types and `Value`
+pub mod value_interop;
 /// Verification and integrity checking
 pub mod verification;
 /// Verified memory allocator with GlobalAlloc and scope support
@@ -315,11 +317,19 @@ macro_rules! safe_managed_alloc {
 pub mod fault_detection;
 // Runtime safety monitoring for production deployments (ASIL-A)
 pub mod safety_monitor;
+// Runtime assertions with ASIL-dependent behavior
+pub mod assertions;
 // Production telemetry and logging infrastructure (ASIL-A)
 pub mod telemetry;
+// Named counters and gauges shared by the runtime and guests
+#[cfg(feature = "std")]
+pub mod metrics;
 // Heap-based memory provider to avoid stack overflow
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod heap_provider;
+// Copy-on-write provider sharing a memory snapshot between instances
+#[cfg(all(any(feature = "std", feature = "alloc"), feature = "platform-memory"))]
+pub mod cow_provider;
 
 // Binary std/no_std choice
 #[cfg(feature = "std")]
@@ -341,6 +351,9 @@ macro_rules! safe_managed_alloc {
 #[cfg(feature = "platform-memory")]
 /// Linear memory implementation using PageAllocator.
 pub mod linear_memory;
+#[cfg(all(feature = "std", feature = "platform-memory"))]
+/// Memory provider backed by a guard-page protected mapping.
+pub mod guarded_provider;
 // Memory system modules removed - now using clean architecture
 // #[cfg(feature = "platform-memory")]
 // /// Memory builder patterns for platform-backed memory types
@@ -365,6 +378,7 @@ macro_rules! safe_managed_alloc {
     BoundedString,
     BoundedVec,
     CapacityError,
+    OverflowPolicy,
     WasmName,
 };
 // Alloc-dependent re-exports
@@ -568,10 +582,20 @@ macro_rules! safe_managed_alloc {
 };
 pub use traits::{
     BoundedCapacity,
+    Checksummable,
     Checksummed,
+    FromBytes,
     FromFormat,
+    ToBytes,
     ToFormat,
     Validatable,
+    ZeroCopy,
+};
+// Derive macros sharing their trait's name, as with `serde`
+pub use wrt_derive::{
+    Checksummable,
+    FromBytes,
+    ToBytes,
 };
 #[cfg(feature = "std")]
 pub use traits::{HostImportHandler, MemoryAccessor, SliceMemory};
@@ -628,6 +652,7 @@ macro_rules! safe_managed_alloc {
     SafetyCriticalTypes,
     UnifiedTypes,
 };
+pub use value_interop::ValueInterop;
 pub use values::Value;
 pub use verification::{
     Checksum,
@@ -677,7 +702,7 @@ macro_rules! safe_managed_alloc {
 /// Bridge between Component Model async and Rust async
 pub mod async_bridge;
 #[cfg(feature = "async-api")]
-/// Simple async executor support
+/// Simple and bounded async executor support
 pub mod async_executor_simple;
 
 // Component Model async re-exports
@@ -695,7 +720,15 @@ macro_rules! safe_managed_alloc {
     is_using_fallback,
     with_async,
     AsyncRuntime,
+    BoundedExecutor,
+    DeadlinePolicy,
+    ExecutorConfig,
     ExecutorError,
+    ExecutorStats,
+    TaskId,
+    TaskOptions,
+    TaskPriority,
+    TaskStats,
 };
 #[cfg(feature = "component-model-async")]
 pub use async_types::{
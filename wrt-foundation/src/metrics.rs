@@ -0,0 +1,371 @@
+//! Named counters and gauges
+//!
+//! The runtime and the guest components it runs publish named metrics into
+//! one global registry, so that application and runtime metrics leave
+//! through the same path: [`snapshot`] returns all of them, and every update
+//! is also recorded as a [`Category::Metric`] telemetry event.
+//!
+//! Metrics are grouped by namespace. The runtime publishes under
+//! [`RUNTIME_NAMESPACE`], each guest component under its own name. A
+//! namespace given a [`MetricQuota`] is bounded in the number of metrics and
+//! the length of their names, so a guest cannot exhaust host memory through
+//! its metrics; updates beyond the quota are rejected and counted.
+
+use std::{
+    collections::BTreeMap,
+    string::{
+        String,
+        ToString,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+    vec::Vec,
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::telemetry::{
+    self,
+    event_codes,
+    Category,
+    Severity,
+};
+
+/// Namespace of the metrics published by the runtime itself
+pub const RUNTIME_NAMESPACE: &str = "wrt";
+
+/// Default maximum number of metrics in a namespace with a quota
+pub const DEFAULT_MAX_METRICS: usize = 64;
+
+/// Default maximum length in bytes of a metric name in a namespace with a
+/// quota
+pub const DEFAULT_MAX_NAME_LEN: usize = 128;
+
+/// Bounds on the metrics of one namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricQuota {
+    /// Maximum number of distinct metrics
+    pub max_metrics:  usize,
+    /// Maximum length of a metric name in bytes
+    pub max_name_len: usize,
+}
+
+impl MetricQuota {
+    /// Create a quota
+    pub const fn new(max_metrics: usize, max_name_len: usize) -> Self {
+        Self {
+            max_metrics,
+            max_name_len,
+        }
+    }
+}
+
+impl Default for MetricQuota {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_METRICS, DEFAULT_MAX_NAME_LEN)
+    }
+}
+
+/// Current value of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricValue {
+    /// Monotonic count, saturating at `u64::MAX`
+    Counter(u64),
+    /// Last value set
+    Gauge(i64),
+}
+
+/// Metric with its namespace, as exported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricSample {
+    /// Namespace, the component name or [`RUNTIME_NAMESPACE`]
+    pub namespace: String,
+    /// Metric name
+    pub name:      String,
+    /// Current value
+    pub value:     MetricValue,
+}
+
+/// Metrics of one namespace
+#[derive(Debug, Default)]
+struct MetricNamespace {
+    quota:    Option<MetricQuota>,
+    metrics:  BTreeMap<String, MetricValue>,
+    rejected: u64,
+}
+
+/// Registry of named metrics grouped by namespace
+#[derive(Debug, Default)]
+pub struct MetricRegistry {
+    namespaces: BTreeMap<String, MetricNamespace>,
+}
+
+impl MetricRegistry {
+    /// Create an empty registry
+    pub const fn new() -> Self {
+        Self {
+            namespaces: BTreeMap::new(),
+        }
+    }
+
+    /// Bound the metrics of `namespace`
+    ///
+    /// Metrics already published are kept even if they exceed the quota.
+    pub fn set_quota(&mut self, namespace: &str, quota: MetricQuota) {
+        self.namespace_mut(namespace).quota = Some(quota);
+    }
+
+    /// Add `delta` to a counter, creating it at zero, and return its total
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metric is a gauge or the update exceeds the
+    /// quota of the namespace; the update is counted as rejected.
+    pub fn counter_add(&mut self, namespace: &str, name: &str, delta: u64) -> Result<u64> {
+        self.update(namespace, name, MetricValue::Counter(0), |value| match value {
+            MetricValue::Counter(count) => {
+                *count = count.saturating_add(delta);
+                Ok(*count)
+            },
+            MetricValue::Gauge(_) => Err(Error::validation_error("Metric is a gauge")),
+        })
+    }
+
+    /// Set a gauge, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metric is a counter or the update exceeds the
+    /// quota of the namespace; the update is counted as rejected.
+    pub fn gauge_set(&mut self, namespace: &str, name: &str, value: i64) -> Result<()> {
+        self.update(namespace, name, MetricValue::Gauge(0), |current| match current {
+            MetricValue::Gauge(gauge) => {
+                *gauge = value;
+                Ok(())
+            },
+            MetricValue::Counter(_) => Err(Error::validation_error("Metric is a counter")),
+        })
+    }
+
+    /// Current value of a metric
+    pub fn get(&self, namespace: &str, name: &str) -> Option<MetricValue> {
+        self.namespaces.get(namespace)?.metrics.get(name).copied()
+    }
+
+    /// Number of updates of `namespace` rejected so far
+    pub fn rejected(&self, namespace: &str) -> u64 {
+        self.namespaces.get(namespace).map_or(0, |ns| ns.rejected)
+    }
+
+    /// Drop the metrics and quota of `namespace`, e.g. when its component
+    /// is torn down
+    pub fn remove_namespace(&mut self, namespace: &str) {
+        self.namespaces.remove(namespace);
+    }
+
+    /// All metrics, ordered by namespace and name
+    pub fn snapshot(&self) -> Vec<MetricSample> {
+        self.namespaces
+            .iter()
+            .flat_map(|(namespace, ns)| {
+                ns.metrics.iter().map(move |(name, value)| MetricSample {
+                    namespace: namespace.clone(),
+                    name:      name.clone(),
+                    value:     *value,
+                })
+            })
+            .collect()
+    }
+
+    fn namespace_mut(&mut self, namespace: &str) -> &mut MetricNamespace {
+        if !self.namespaces.contains_key(namespace) {
+            self.namespaces.insert(namespace.to_string(), MetricNamespace::default());
+        }
+        // Inserted above if missing
+        self.namespaces.get_mut(namespace).expect("namespace present")
+    }
+
+    /// Apply `update` to a metric, creating it as `initial` if needed, and
+    /// record the outcome in telemetry
+    fn update<T>(
+        &mut self,
+        namespace: &str,
+        name: &str,
+        initial: MetricValue,
+        update: impl FnOnce(&mut MetricValue) -> Result<T>,
+    ) -> Result<T> {
+        let id = metric_id(namespace, name);
+        let ns = self.namespace_mut(namespace);
+        let result = match ns.metrics.get_mut(name) {
+            Some(value) => update(value).map(|out| (out, *value)),
+            None => match ns.quota {
+                Some(quota) if name.len() > quota.max_name_len => Err(Error::new(
+                    ErrorCategory::Resource,
+                    codes::RESOURCE_LIMIT_EXCEEDED,
+                    "Metric name exceeds the quota of its namespace",
+                )),
+                Some(quota) if ns.metrics.len() >= quota.max_metrics => Err(Error::new(
+                    ErrorCategory::Resource,
+                    codes::RESOURCE_LIMIT_EXCEEDED,
+                    "Metric count exceeds the quota of its namespace",
+                )),
+                _ => {
+                    let mut value = initial;
+                    update(&mut value).map(|out| {
+                        ns.metrics.insert(name.to_string(), value);
+                        (out, value)
+                    })
+                },
+            },
+        };
+
+        match result {
+            Ok((_, MetricValue::Counter(count))) => telemetry::record_event(
+                Severity::Info,
+                Category::Metric,
+                event_codes::METRIC_COUNTER,
+                id,
+                count,
+            ),
+            Ok((_, MetricValue::Gauge(gauge))) => telemetry::record_event(
+                Severity::Info,
+                Category::Metric,
+                event_codes::METRIC_GAUGE,
+                id,
+                gauge as u64,
+            ),
+            Err(_) => {
+                ns.rejected += 1;
+                telemetry::record_event(
+                    Severity::Warning,
+                    Category::Metric,
+                    event_codes::METRIC_REJECTED,
+                    id,
+                    ns.rejected,
+                );
+            },
+        }
+        result.map(|(out, _)| out)
+    }
+}
+
+/// Stable identifier of a metric carried by its telemetry events (FNV-1a of
+/// `namespace/name`)
+pub fn metric_id(namespace: &str, name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in namespace.bytes().chain(core::iter::once(b'/')).chain(name.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Global registry shared by the runtime and guests
+static METRICS: Mutex<MetricRegistry> = Mutex::new(MetricRegistry::new());
+
+fn registry() -> Result<MutexGuard<'static, MetricRegistry>> {
+    METRICS.lock().map_err(|_| {
+        Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Metric registry lock poisoned")
+    })
+}
+
+/// Bound the metrics of `namespace` in the global registry
+///
+/// # Errors
+///
+/// Returns an error if the registry lock is poisoned.
+pub fn set_quota(namespace: &str, quota: MetricQuota) -> Result<()> {
+    registry()?.set_quota(namespace, quota);
+    Ok(())
+}
+
+/// Add `delta` to a counter of the global registry and return its total
+///
+/// # Errors
+///
+/// Returns an error if the registry lock is poisoned, the metric is a gauge
+/// or the update exceeds the quota of the namespace.
+pub fn counter_add(namespace: &str, name: &str, delta: u64) -> Result<u64> {
+    registry()?.counter_add(namespace, name, delta)
+}
+
+/// Set a gauge of the global registry
+///
+/// # Errors
+///
+/// Returns an error if the registry lock is poisoned, the metric is a
+/// counter or the update exceeds the quota of the namespace.
+pub fn gauge_set(namespace: &str, name: &str, value: i64) -> Result<()> {
+    registry()?.gauge_set(namespace, name, value)
+}
+
+/// Current value of a metric of the global registry
+pub fn get(namespace: &str, name: &str) -> Option<MetricValue> {
+    registry().ok()?.get(namespace, name)
+}
+
+/// Number of rejected updates of `namespace` in the global registry
+pub fn rejected(namespace: &str) -> u64 {
+    registry().map_or(0, |registry| registry.rejected(namespace))
+}
+
+/// Drop the metrics and quota of `namespace` from the global registry
+///
+/// # Errors
+///
+/// Returns an error if the registry lock is poisoned.
+pub fn remove_namespace(namespace: &str) -> Result<()> {
+    registry()?.remove_namespace(namespace);
+    Ok(())
+}
+
+/// All metrics of the global registry, ordered by namespace and name
+pub fn snapshot() -> Vec<MetricSample> {
+    registry().map(|registry| registry.snapshot()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_enforces_quota_per_namespace() {
+        let mut registry = MetricRegistry::new();
+        registry.set_quota("app", MetricQuota::new(2, 8));
+
+        assert_eq!(registry.counter_add("app", "requests", 2).unwrap(), 2);
+        assert_eq!(registry.counter_add("app", "requests", 3).unwrap(), 5);
+        registry.gauge_set("app", "queue", -4).unwrap();
+        assert!(registry.gauge_set("app", "requests", 1).is_err());
+        assert!(registry.counter_add("app", "errors", 1).is_err());
+        assert!(registry.counter_add("other", "much-too-long-name", 1).is_ok());
+        registry.set_quota("other", MetricQuota::new(8, 4));
+        assert!(registry.counter_add("other", "long-name", 1).is_err());
+        assert_eq!(registry.rejected("app"), 2);
+        assert_eq!(registry.rejected("other"), 1);
+
+        // The runtime namespace has no quota unless given one
+        for i in 0..DEFAULT_MAX_METRICS + 1 {
+            registry.gauge_set(RUNTIME_NAMESPACE, &std::format!("gauge-{i}"), 1).unwrap();
+        }
+
+        let samples = registry.snapshot();
+        assert_eq!(samples[0], MetricSample {
+            namespace: "app".to_string(),
+            name:      "queue".to_string(),
+            value:     MetricValue::Gauge(-4),
+        });
+        assert_eq!(samples[1].value, MetricValue::Counter(5));
+        assert_eq!(samples.len(), 3 + DEFAULT_MAX_METRICS + 1);
+
+        registry.remove_namespace("app");
+        assert_eq!(registry.get("app", "requests"), None);
+    }
+}
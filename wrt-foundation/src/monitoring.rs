@@ -14,6 +14,7 @@
 use crate::{
     budget_aware_provider::CrateId,
     memory_coordinator::CrateIdentifier,
+    verification::VerificationTrigger,
 };
 
 /// Global monitoring statistics
@@ -211,6 +212,97 @@ pub struct CrateStatistics {
     pub peak_usage:    usize,
 }
 
+/// Checksum verification statistics of scheduled collections
+pub struct VerificationMonitor {
+    /// Verifications triggered by element accesses
+    pub access_verifications:     AtomicU64,
+    /// Verifications triggered by idle periods
+    pub idle_verifications:       AtomicU64,
+    /// Verifications triggered by component transitions
+    pub transition_verifications: AtomicU64,
+    /// Triggers the schedule decided not to verify
+    pub skipped_verifications:    AtomicU64,
+    /// Verifications that found a checksum mismatch
+    pub verification_failures:    AtomicU64,
+}
+
+impl Default for VerificationMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerificationMonitor {
+    /// Create a new verification monitor
+    pub const fn new() -> Self {
+        Self {
+            access_verifications:     AtomicU64::new(0),
+            idle_verifications:       AtomicU64::new(0),
+            transition_verifications: AtomicU64::new(0),
+            skipped_verifications:    AtomicU64::new(0),
+            verification_failures:    AtomicU64::new(0),
+        }
+    }
+
+    /// Record a verification caused by `trigger`
+    pub fn record_verification(&self, trigger: VerificationTrigger, passed: bool) {
+        let counter = match trigger {
+            VerificationTrigger::Access => &self.access_verifications,
+            VerificationTrigger::Idle => &self.idle_verifications,
+            VerificationTrigger::Transition => &self.transition_verifications,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if !passed {
+            self.verification_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a trigger that the schedule skipped
+    pub fn record_skipped(&self) {
+        self.skipped_verifications.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get current statistics snapshot
+    pub fn get_statistics(&self) -> VerificationStatistics {
+        VerificationStatistics {
+            access_verifications:     self.access_verifications.load(Ordering::Relaxed),
+            idle_verifications:       self.idle_verifications.load(Ordering::Relaxed),
+            transition_verifications: self.transition_verifications.load(Ordering::Relaxed),
+            skipped_verifications:    self.skipped_verifications.load(Ordering::Relaxed),
+            verification_failures:    self.verification_failures.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reset all counters (useful for testing)
+    pub fn reset(&self) {
+        self.access_verifications.store(0, Ordering::Relaxed);
+        self.idle_verifications.store(0, Ordering::Relaxed);
+        self.transition_verifications.store(0, Ordering::Relaxed);
+        self.skipped_verifications.store(0, Ordering::Relaxed);
+        self.verification_failures.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of verification statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VerificationStatistics {
+    pub access_verifications:     u64,
+    pub idle_verifications:       u64,
+    pub transition_verifications: u64,
+    pub skipped_verifications:    u64,
+    pub verification_failures:    u64,
+}
+
+impl VerificationStatistics {
+    /// Total number of checksum verifications performed
+    pub fn total_verifications(&self) -> u64 {
+        self.access_verifications + self.idle_verifications + self.transition_verifications
+    }
+}
+
+/// Global verification monitor instance
+pub static VERIFICATION_MONITOR: VerificationMonitor = VerificationMonitor::new();
+
 /// Debug tracking for development
 #[cfg(debug_assertions)]
 pub fn debug_track_allocation(crate_id: CrateId, size: usize, purpose: &str) {
@@ -309,4 +401,9 @@ pub fn current_usage_kb() -> f64 {
     pub fn peak_usage_kb() -> f64 {
         global_stats().peak_usage as f64 / 1024.0
     }
+
+    /// Get global checksum verification statistics
+    pub fn verification_stats() -> VerificationStatistics {
+        VERIFICATION_MONITOR.get_statistics()
+    }
 }
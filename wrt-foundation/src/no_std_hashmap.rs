@@ -29,13 +29,10 @@
 
 use crate::{
     bounded::BoundedVec,
-    traits::{
-        Checksummable,
-        FromBytes,
-        ToBytes,
-    },
-    verification::Checksum,
+    Checksummable,
+    FromBytes,
     MemoryProvider,
+    ToBytes,
 };
 
 /// A simple fixed-capacity hash map implementation for no_std environments.
@@ -61,7 +58,7 @@ pub struct SimpleHashMap<
 pub type BoundedHashMap<K, V, const N: usize, P> = SimpleHashMap<K, V, N, P>;
 
 /// A key-value pair entry in the hash map.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Checksummable, ToBytes, FromBytes)]
 struct Entry<K, V>
 where
     K: Clone + PartialEq + Eq,
@@ -86,51 +83,6 @@ fn default() -> Self {
     }
 }
 
-impl<K, V> Checksummable for Entry<K, V>
-where
-    K: Clone + PartialEq + Eq + Checksummable,
-    V: Clone + PartialEq + Eq + Checksummable,
-{
-    fn update_checksum(&self, checksum: &mut Checksum) {
-        self.key.update_checksum(checksum);
-        self.value.update_checksum(checksum);
-        self.hash.update_checksum(checksum);
-    }
-}
-
-impl<K, V> ToBytes for Entry<K, V>
-where
-    K: Clone + PartialEq + Eq + ToBytes,
-    V: Clone + PartialEq + Eq + ToBytes,
-{
-    fn to_bytes_with_provider<'a, PStream: MemoryProvider>(
-        &self,
-        writer: &mut crate::traits::WriteStream<'a>,
-        provider: &PStream,
-    ) -> wrt_error::Result<()> {
-        self.key.to_bytes_with_provider(writer, provider)?;
-        self.value.to_bytes_with_provider(writer, provider)?;
-        self.hash.to_bytes_with_provider(writer, provider)?;
-        Ok(())
-    }
-}
-
-impl<K, V> FromBytes for Entry<K, V>
-where
-    K: Clone + PartialEq + Eq + FromBytes,
-    V: Clone + PartialEq + Eq + FromBytes,
-{
-    fn from_bytes_with_provider<'a, PStream: MemoryProvider>(
-        reader: &mut crate::traits::ReadStream<'a>,
-        provider: &PStream,
-    ) -> wrt_error::Result<Self> {
-        let key = K::from_bytes_with_provider(reader, provider)?;
-        let value = V::from_bytes_with_provider(reader, provider)?;
-        let hash = u64::from_bytes_with_provider(reader, provider)?;
-        Ok(Self { key, value, hash })
-    }
-}
-
 impl<K, V, const N: usize, P: MemoryProvider + Default + Clone + fmt::Debug + PartialEq + Eq>
     SimpleHashMap<K, V, N, P>
 where
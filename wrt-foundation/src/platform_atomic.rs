@@ -9,6 +9,14 @@
     AtomicU32,
     AtomicU64,
     AtomicU8,
+};
+/// Word-sized atomics for lock-free structures that own their storage
+///
+/// Collections such as [`MpmcQueue`](crate::collections::MpmcQueue) take
+/// their atomics from here rather than from `core`, so a target without
+/// native compare-and-swap has a single place to substitute an emulation.
+pub use core::sync::atomic::{
+    AtomicUsize,
     Ordering,
 };
 
@@ -15,28 +15,17 @@
 
 // Binary std/no_std choice
 
-use core::{
-    fmt,
-    sync::atomic::{
-        AtomicUsize,
-        Ordering,
-    },
+use core::sync::atomic::{
+    AtomicUsize,
+    Ordering,
 };
 
-use crate::{
-    codes,
-    operations::{
-        record_global_operation,
-        Type as OperationType,
-    },
-    verification::{
-        Checksum,
-        VerificationLevel,
-    },
-    Error,
-    ErrorCategory,
+use crate::operations::{
+    record_global_operation,
+    Type as OperationType,
 };
-// Result is imported through the prelude
+// `fmt`, the error types, `Checksum` and `VerificationLevel` are imported
+// through the prelude
 
 /// Binary std/no_std choice
 pub const DEFAULT_MEMORY_PROVIDER_CAPACITY: usize = 4096;
@@ -55,13 +44,10 @@
 // REMOVE: use std::sync::Mutex;
 // REMOVE: #[cfg(feature = "std")]
 // REMOVE: use std::vec::Vec; // Explicitly import Vec for StdProvider
+// The prelude's `Mutex` is the wrt-sync lock; the std provider's access log
+// uses the std one
 #[cfg(feature = "std")]
-use std::collections::HashSet;
-#[cfg(feature = "std")]
-use std::sync::Mutex; /* Note: std::sync::Mutex might be an issue if a no_std mutex is
-                       * needed elsewhere. */
-#[cfg(feature = "std")]
-use std::vec::Vec;
+use std::sync::Mutex as StdMutex;
 
 #[cfg(feature = "std")]
 pub use crate::prelude::ToString;
@@ -551,6 +537,20 @@ pub trait Provider: Send + Sync + fmt::Debug + Clone + Default + PartialEq + Eq
     /// that the memory region is valid for reads of `len` bytes at `offset`.
     fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>>;
 
+    /// Copies `buffer.len()` bytes starting at `offset` into `buffer`.
+    ///
+    /// The default borrows a slice of the range. Providers that do not keep
+    /// their bytes contiguous override this to copy without first making
+    /// the range contiguous.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds or fails verification.
+    fn read_into(&self, offset: usize, buffer: &mut [u8]) -> Result<()> {
+        buffer.copy_from_slice(self.borrow_slice(offset, buffer.len())?.data()?);
+        Ok(())
+    }
+
     /// Writes data to the memory at a given offset.
     ///
     /// # Safety
@@ -674,7 +674,7 @@ pub struct StdProvider {
     /// The underlying data buffer
     data:               Vec<u8>,
     /// Track memory accesses for safety monitoring
-    access_log:         Mutex<Vec<(usize, usize)>>,
+    access_log:         StdMutex<Vec<(usize, usize)>>,
     /// Counter for access operations
     access_count:       AtomicUsize,
     /// Maximum size of any access seen
@@ -682,7 +682,7 @@ pub struct StdProvider {
     /// Number of unique regions accessed
     unique_regions:     AtomicUsize,
     /// Regions hash (for uniqueness tracking)
-    regions_hash:       Mutex<HashSet<usize>>,
+    regions_hash:       StdMutex<HashSet<usize>>,
     /// Verification level for memory operations
     verification_level: VerificationLevel,
 }
@@ -692,11 +692,11 @@ impl Clone for StdProvider {
     fn clone(&self) -> Self {
         Self {
             data:               self.data.clone(),
-            access_log:         Mutex::new(Vec::new()), // Create new empty access log
+            access_log:         StdMutex::new(Vec::new()), // Create new empty access log
             access_count:       AtomicUsize::new(0),
             max_access_size:    AtomicUsize::new(0),
             unique_regions:     AtomicUsize::new(0),
-            regions_hash:       Mutex::new(HashSet::new()),
+            regions_hash:       StdMutex::new(HashSet::new()),
             verification_level: self.verification_level,
         }
     }
@@ -736,11 +736,11 @@ impl Default for StdProvider {
     fn default() -> Self {
         Self {
             data:               Vec::new(),
-            access_log:         Mutex::new(Vec::new()),
+            access_log:         StdMutex::new(Vec::new()),
             access_count:       AtomicUsize::new(0),
             max_access_size:    AtomicUsize::new(0),
             unique_regions:     AtomicUsize::new(0),
-            regions_hash:       Mutex::new(HashSet::new()),
+            regions_hash:       StdMutex::new(HashSet::new()),
             verification_level: VerificationLevel::default(),
         }
     }
@@ -752,11 +752,11 @@ impl StdProvider {
     pub fn new(data: Vec<u8>) -> Self {
         Self {
             data,
-            access_log: Mutex::new(Vec::new()),
+            access_log: StdMutex::new(Vec::new()),
             access_count: AtomicUsize::new(0),
             max_access_size: AtomicUsize::new(0),
             unique_regions: AtomicUsize::new(0),
-            regions_hash: Mutex::new(HashSet::new()),
+            regions_hash: StdMutex::new(HashSet::new()),
             verification_level: VerificationLevel::default(),
         }
     }
@@ -765,11 +765,11 @@ pub fn new(data: Vec<u8>) -> Self {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             data:               Vec::with_capacity(capacity),
-            access_log:         Mutex::new(Vec::new()),
+            access_log:         StdMutex::new(Vec::new()),
             access_count:       AtomicUsize::new(0),
             max_access_size:    AtomicUsize::new(0),
             unique_regions:     AtomicUsize::new(0),
-            regions_hash:       Mutex::new(HashSet::new()),
+            regions_hash:       StdMutex::new(HashSet::new()),
             verification_level: VerificationLevel::default(),
         }
     }
@@ -890,14 +890,10 @@ fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
         self.verify_access(offset, len)?;
         self.track_access(offset, len);
         // Ensure that the slice we create does not exceed the actual data length.
-        debug_assert!(
+        crate::wrt_debug_assert!(
             offset.checked_add(len).map_or(false, |end| end <= self.data.len()),
-            "StdProvider::borrow_slice: offset+len must be <= self.data.len(). Offset: {}, Len: \
-             {}, DataLen: {}",
-            offset,
-            len,
-            self.data.len()
-        );
+            "StdProvider::borrow_slice: offset+len must be <= self.data.len()"
+        )?;
         Slice::with_verification_level(&self.data[offset..offset + len], self.verification_level)
     }
 
@@ -909,14 +905,10 @@ fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
     fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
         self.verify_access(offset, len)?;
         self.track_access(offset, len); // Tracking access before mutation
-        debug_assert!(
+        crate::wrt_debug_assert!(
             offset.checked_add(len).map_or(false, |end| end <= self.data.len()),
-            "StdProvider::get_slice_mut: offset+len must be <= self.data.len() after \
-             verify_access. Offset: {}, Len: {}, DataLen: {}",
-            offset,
-            len,
-            self.data.len()
-        );
+            "StdProvider::get_slice_mut: offset+len must be <= self.data.len() after verify_access"
+        )?;
         // Create a SliceMut with the provider's verification level
         SliceMut::with_verification_level(
             &mut self.data[offset..offset + len],
@@ -933,16 +925,12 @@ fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
     fn write_data(&mut self, offset: usize, data_to_write: &[u8]) -> Result<()> {
         self.verify_access(offset, data_to_write.len())?;
         self.track_access(offset, data_to_write.len());
-        debug_assert!(
+        crate::wrt_debug_assert!(
             offset
                 .checked_add(data_to_write.len())
                 .map_or(false, |end| end <= self.data.len()),
-            "StdProvider::write_data: offset+len must be <= self.data.len() after verify_access. \
-             Offset: {}, Len: {}, DataLen: {}",
-            offset,
-            data_to_write.len(),
-            self.data.len()
-        );
+            "StdProvider::write_data: offset+len must be <= self.data.len() after verify_access"
+        )?;
 
         // Safety: verify_access ensures offset + data_to_write.len() is within
         // self.data.capacity(). And also ensures offset + data_to_write.len()
@@ -1023,22 +1011,14 @@ fn memory_stats(&self) -> Stats {
     fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
         self.verify_access(src_offset, len)?;
         self.verify_access(dst_offset, len)?; // Ensure dst is also valid
-        debug_assert!(
+        crate::wrt_debug_assert!(
             src_offset.checked_add(len).map_or(false, |end| end <= self.data.len()),
-            "StdProvider::copy_within (src): src_offset+len must be <= self.data.len(). \
-             SrcOffset: {}, Len: {}, DataLen: {}",
-            src_offset,
-            len,
-            self.data.len()
-        );
-        debug_assert!(
+            "StdProvider::copy_within (src): src_offset+len must be <= self.data.len()"
+        )?;
+        crate::wrt_debug_assert!(
             dst_offset.checked_add(len).map_or(false, |end| end <= self.data.len()),
-            "StdProvider::copy_within (dst): dst_offset+len must be <= self.data.len(). \
-             DstOffset: {}, Len: {}, DataLen: {}",
-            dst_offset,
-            len,
-            self.data.len()
-        );
+            "StdProvider::copy_within (dst): dst_offset+len must be <= self.data.len()"
+        )?;
 
         // Safety: verify_access for both src and dst ranges has been called.
         // This ensures that src_offset + len and dst_offset + len are within bounds.
@@ -1468,19 +1448,14 @@ impl<const N: usize> Provider for NoStdProvider<N> {
 
     fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
         self.verify_access(offset, len)?;
-        debug_assert!(
+        crate::wrt_debug_assert!(
             offset.checked_add(len).map_or(false, |end| end <= self.used),
-            "NoStdProvider::borrow_slice: offset+len must be <= self.used. Offset: {}, Len: {}, \
-             Used: {}",
-            offset,
-            len,
-            self.used
-        );
-        debug_assert!(
+            "NoStdProvider::borrow_slice: offset+len must be <= self.used"
+        )?;
+        crate::wrt_debug_assert!(
             offset.checked_add(len).map_or(false, |end| end <= N),
-            "NoStdProvider::borrow_slice: offset+len must be <= N (capacity). Offset: {offset}, \
-             Len: {len}, Capacity: {N}"
-        );
+            "NoStdProvider::borrow_slice: offset+len must be <= N (capacity)"
+        )?;
         Slice::with_verification_level(&self.data[offset..offset + len], self.verification_level)
     }
 
@@ -1793,6 +1768,11 @@ pub fn get_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
         self.provider.borrow_slice(offset, len)
     }
 
+    /// Copy bytes starting at `offset` into `buffer`
+    pub fn read_into(&self, offset: usize, buffer: &mut [u8]) -> Result<()> {
+        self.provider.read_into(offset, buffer)
+    }
+
     /// Get a mutable slice from the memory handler
     pub fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
         self.provider.get_slice_mut(offset, len)
@@ -1822,8 +1802,9 @@ pub fn to_vec(&self) -> Result<std::vec::Vec<u8>> {
             return Ok(std::vec::Vec::new());
         }
 
-        let slice = self.provider.borrow_slice(0, size)?;
-        Ok(slice.as_ref().to_vec())
+        let mut data = std::vec![0; size];
+        self.provider.read_into(0, &mut data)?;
+        Ok(data)
     }
 
     /// Converts the memory handler to a BoundedVec of bytes (no_std version).
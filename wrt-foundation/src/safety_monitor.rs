@@ -14,6 +14,10 @@
 // Note: no_std is configured at the crate level
 
 use crate::{
+    assertions::{
+        AssertionAction,
+        AssertionFailure,
+    },
     capabilities::CapabilityMask,
     CrateId,
 };
@@ -77,6 +81,10 @@ struct ErrorMonitor {
     recent_error_count: u32,
     /// Operations counter for rate calculation
     operation_count:    u64,
+    /// Failed runtime assertions
+    assertion_failures: u64,
+    /// Most recent failed runtime assertion
+    last_assertion:     Option<AssertionFailure>,
 }
 
 /// Monitors performance degradation
@@ -113,6 +121,8 @@ pub struct SafetyReport {
     pub peak_memory_bytes:     usize,
     /// Recent error rate (per 1000 operations)
     pub error_rate_per_1000:   u32,
+    /// Failed runtime assertion count
+    pub assertion_failures:    u64,
 }
 
 /// Safety violation types for reporting
@@ -160,6 +170,8 @@ pub const fn new() -> Self {
                 fatal_errors:       0,
                 recent_error_count: 0,
                 operation_count:    0,
+                assertion_failures: 0,
+                last_assertion:     None,
             },
             performance_monitor: PerformanceMonitor {
                 slow_allocations:       0,
@@ -255,6 +267,25 @@ pub fn record_fatal_error(&mut self) {
         self.increment_operations();
     }
 
+    /// Record failed runtime assertion
+    pub fn record_assertion_failure(&mut self, failure: &AssertionFailure) {
+        self.error_monitor.assertion_failures += 1;
+        self.error_monitor.last_assertion = Some(*failure);
+        let level = match failure.action {
+            AssertionAction::SafeState => 0, // Critical severity
+            AssertionAction::Degrade => 1,   // High severity
+            AssertionAction::Ignore | AssertionAction::Log => 2, // Medium severity
+        };
+        self.error_monitor.errors_by_level[level] += 1;
+        self.update_error_rate();
+        self.increment_operations();
+    }
+
+    /// Most recent failed runtime assertion
+    pub fn last_assertion_failure(&self) -> Option<AssertionFailure> {
+        self.error_monitor.last_assertion
+    }
+
     /// Get safety report
     pub fn get_safety_report(&self) -> SafetyReport {
         SafetyReport {
@@ -267,6 +298,7 @@ pub fn get_safety_report(&self) -> SafetyReport {
             current_memory_bytes:  self.allocation_monitor.current_allocated,
             peak_memory_bytes:     self.allocation_monitor.peak_allocated,
             error_rate_per_1000:   self.error_monitor.recent_error_count,
+            assertion_failures:    self.error_monitor.assertion_failures,
         }
     }
 
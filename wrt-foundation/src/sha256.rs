@@ -1,7 +1,8 @@
-//! SHA-256 implementation for model hashing
+//! SHA-256 and HMAC-SHA256 digests
 //!
 //! This module provides a no_std compatible SHA-256 implementation
-//! following FIPS 180-4 specification for secure model hashing.
+//! following the FIPS 180-4 specification, used for model and module
+//! hashing, and HMAC-SHA256 (RFC 2104) for keyed authentication tags.
 //!
 //! # Safety
 //!
@@ -11,8 +12,6 @@
 //! - Deterministic output
 //! - No unsafe code
 
-#![cfg_attr(not(feature = "std"), no_std)]
-
 /// SHA-256 constants (first 32 bits of fractional parts of cube roots of first
 /// 64 primes)
 const K: [u32; 64] = [
@@ -45,6 +44,12 @@ pub struct Sha256 {
     bit_len:    u64,
 }
 
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sha256 {
     /// Create a new SHA-256 context
     pub const fn new() -> Self {
@@ -207,6 +212,38 @@ fn pad(&mut self) {
     hasher.finalize()
 }
 
+/// Compute the HMAC-SHA256 tag of `data` under `key`
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block_key = [0u8; 64];
+    if key.len() > 64 {
+        block_key[..32].copy_from_slice(&sha256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner_pad = [0x36u8; 64];
+    let mut outer_pad = [0x5cu8; 64];
+    for ((inner, outer), key) in inner_pad.iter_mut().zip(outer_pad.iter_mut()).zip(block_key) {
+        *inner ^= key;
+        *outer ^= key;
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&inner_pad);
+    inner.update(data);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(&outer_pad);
+    outer.update(&inner_hash);
+    outer.finalize()
+}
+
+/// Compare two digests in time independent of where they differ
+pub fn digests_equal(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,4 +308,17 @@ fn test_incremental_hashing() {
 
         assert_eq!(hash1, hash2);
     }
+
+    #[test]
+    fn test_hmac_rfc4231() {
+        // RFC 4231 test case 2
+        let tag = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let expected = [
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43,
+        ];
+        assert!(digests_equal(&tag, &expected));
+        assert!(!digests_equal(&tag, &sha256(b"Jefe")));
+    }
 }
@@ -51,6 +51,10 @@ pub enum Category {
     Safety,
     /// System lifecycle
     Lifecycle,
+    /// Intercepted cross-component calls
+    Interception,
+    /// Named counters and gauges published by the runtime or guests
+    Metric,
 }
 
 /// Telemetry event
@@ -106,13 +110,29 @@ pub mod event_codes {
     pub const SAFETY_DOUBLE_FREE: u32 = 0x5001;
     /// Safety health degraded
     pub const SAFETY_HEALTH_DEGRADED: u32 = 0x5002;
+    /// Runtime assertion failed
+    pub const SAFETY_ASSERTION_FAILED: u32 = 0x5003;
     /// Memory deallocation
     pub const MEMORY_DEALLOCATION: u32 = 0x1004;
+    /// Arena scope reset (context1: high-water mark, context2: crate)
+    pub const MEM_ARENA_RESET: u32 = 0x1005;
 
     /// System initialized
     pub const LIFECYCLE_INIT: u32 = 0x6000;
     /// System shutdown
     pub const LIFECYCLE_SHUTDOWN: u32 = 0x6001;
+
+    /// Intercepted call completed
+    pub const INTERCEPT_CALL: u32 = 0x7000;
+    /// Intercepted call failed
+    pub const INTERCEPT_CALL_FAILED: u32 = 0x7001;
+
+    /// Counter incremented
+    pub const METRIC_COUNTER: u32 = 0x8000;
+    /// Gauge set
+    pub const METRIC_GAUGE: u32 = 0x8001;
+    /// Metric update rejected by the quota of its namespace
+    pub const METRIC_REJECTED: u32 = 0x8002;
 }
 
 /// Simple ring buffer for telemetry events
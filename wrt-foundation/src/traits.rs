@@ -85,6 +85,29 @@ pub trait LittleEndian: Sized {
     fn write_le_bytes<W: BytesWriter>(&self, writer: &mut W) -> wrt_error::Result<()>;
 }
 
+/// Marker for types whose serialized form is their in-memory representation
+///
+/// Collections storing such types serialized can hand out `&[T]` views of
+/// their backing memory instead of deserializing element by element. The
+/// views also need the type to be stored packed (see
+/// [`ToBytes::STORE_PACKED`]), which only single-byte types are.
+///
+/// # Safety
+///
+/// Implementors must have no padding, accept every bit pattern, and serialize
+/// through [`ToBytes`] to exactly `size_of::<Self>()` bytes identical to their
+/// in-memory representation on the target.
+#[allow(unsafe_code)] // Marker contract is checked by implementors
+pub unsafe trait ZeroCopy: Copy {}
+
+// SAFETY: single-byte integers have no padding, no invalid bit patterns and
+// serialize as themselves.
+#[allow(unsafe_code)]
+unsafe impl ZeroCopy for u8 {}
+// SAFETY: see `u8`.
+#[allow(unsafe_code)]
+unsafe impl ZeroCopy for i8 {}
+
 /// Trait for types that can be converted to WRT Value representation
 pub trait ToWrtValue {
     /// Converts self to the target WRT Value type.
@@ -145,6 +168,30 @@ fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
     }
 }
 
+// Implement Checksummable for Vec<u8> - covers the bytes like &[u8]
+#[cfg(feature = "std")]
+impl Checksummable for alloc::vec::Vec<u8> {
+    fn update_checksum(&self, checksum: &mut crate::verification::Checksum) {
+        checksum.update_slice(self);
+    }
+}
+
+// Implement ToBytes for Vec<u8> - same layout as &[u8]
+#[cfg(feature = "std")]
+impl ToBytes for alloc::vec::Vec<u8> {
+    fn serialized_size(&self) -> usize {
+        self.as_slice().serialized_size()
+    }
+
+    fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
+        &self,
+        writer: &mut WriteStream<'a>,
+        provider: &PStream,
+    ) -> wrt_error::Result<()> {
+        self.as_slice().to_bytes_with_provider(writer, provider)
+    }
+}
+
 // Implement FromBytes for Vec<u8> - dynamic byte vectors
 #[cfg(feature = "std")]
 impl FromBytes for alloc::vec::Vec<u8> {
@@ -296,6 +343,16 @@ fn max_serialized_size() -> usize {
 
 /// Trait for types that can be serialized to bytes.
 pub trait ToBytes: Sized {
+    /// Serialized size shared by every value of this type, if it is fixed
+    const FIXED_SERIALIZED_SIZE: Option<usize> = None;
+
+    /// Whether bounded collections store values back to back, using
+    /// [`Self::FIXED_SERIALIZED_SIZE`] as their element stride
+    ///
+    /// Types that do not opt in keep the default stride, which leaves room
+    /// for any element up to 12 bytes.
+    const STORE_PACKED: bool = false;
+
     /// Returns the size in bytes required to serialize this type.
     /// This should be a constant for fixed-size types.
     /// Default implementation returns 0 - types should override this.
@@ -401,6 +458,11 @@ macro_rules! impl_bytes_for_primitive {
     ($($T:ty => $read_method:ident, $write_method:ident);*) => {
         $(
             impl ToBytes for $T {
+                const FIXED_SERIALIZED_SIZE: Option<usize> = Some(core::mem::size_of::<$T>());
+                // Single bytes need no alignment, so a packed buffer can always
+                // be viewed as a slice of them
+                const STORE_PACKED: bool = core::mem::size_of::<$T>() == 1;
+
                 fn serialized_size(&self) -> usize {
                     core::mem::size_of::<$T>()
                 }
@@ -447,6 +509,8 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
 
 // Corrected ToBytes for bool
 impl ToBytes for bool {
+    const FIXED_SERIALIZED_SIZE: Option<usize> = Some(1);
+
     fn serialized_size(&self) -> usize {
         1
     }
@@ -3819,20 +3819,40 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
 pub struct MemoryType {
-    pub limits: Limits,
-    pub shared: bool,
+    pub limits:   Limits,
+    pub shared:   bool,
+    /// Whether the memory is indexed with i64 addresses (memory64 proposal)
+    pub memory64: bool,
 }
 
 impl MemoryType {
     pub const fn new(limits: Limits, shared: bool) -> Self {
-        Self { limits, shared }
+        Self {
+            limits,
+            shared,
+            memory64: false,
+        }
+    }
+
+    /// Create the type of a memory indexed with i64 addresses
+    pub const fn new_memory64(limits: Limits, shared: bool) -> Self {
+        Self {
+            limits,
+            shared,
+            memory64: true,
+        }
+    }
+
+    /// Flags byte: bit 0 shared, bit 1 memory64
+    const fn flags(&self) -> u8 {
+        (self.shared as u8) | ((self.memory64 as u8) << 1)
     }
 }
 
 impl Checksummable for MemoryType {
     fn update_checksum(&self, checksum: &mut Checksum) {
         self.limits.update_checksum(checksum);
-        checksum.update(self.shared as u8);
+        checksum.update(self.flags());
     }
 }
 
@@ -3843,7 +3863,7 @@ fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         provider: &PStream,
     ) -> wrt_error::Result<()> {
         self.limits.to_bytes_with_provider(writer, provider)?;
-        writer.write_u8(self.shared as u8)?;
+        writer.write_u8(self.flags())?;
         Ok(())
     }
     // Default to_bytes method will be used if #cfg(feature = "default-provider") is
@@ -3856,17 +3876,17 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         provider: &PStream,
     ) -> wrt_error::Result<Self> {
         let limits = Limits::from_bytes_with_provider(reader, provider)?;
-        let shared_byte = reader.read_u8()?;
-        let shared = match shared_byte {
-            0 => false,
-            1 => true,
-            _ => {
-                return Err(Error::runtime_execution_error(
-                    "Invalid memory shared flag value",
-                ));
-            },
-        };
-        Ok(MemoryType { limits, shared })
+        let flags = reader.read_u8()?;
+        if flags > 0b11 {
+            return Err(Error::runtime_execution_error(
+                "Invalid memory flags value",
+            ));
+        }
+        Ok(MemoryType {
+            limits,
+            shared: flags & 0b01 != 0,
+            memory64: flags & 0b10 != 0,
+        })
     }
     // Default from_bytes method will be used if #cfg(feature = ")
     // is active
@@ -0,0 +1,127 @@
+// WRT - wrt-foundation
+// Module: Value Interoperability
+// SW-REQ-ID: REQ_018
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Conversion between crate-local value types and [`Value`]
+//!
+//! Several crates carry their own value enum (`wrt-wasi`'s `value_compat`,
+//! `wrt-component`'s canonical ABI `ComponentValue`). [`Value`] is the
+//! shared representation between them: each local type implements
+//! [`ValueInterop`] once, and host functions convert through [`Value`]
+//! instead of keeping per-crate adapters.
+//!
+//! # Round-trip guarantee
+//!
+//! For every implementation and every `x`,
+//! `T::try_from_value(x.into_value())` returns a value equal to `x`,
+//! bit-for-bit for floats. The other direction is partial: a [`Value`] with
+//! no counterpart in `T` is rejected with a
+//! [`ErrorCategory::Type`] error rather than approximated.
+//!
+//! Core numeric values are accepted as their component model counterparts
+//! (`I32` as `S32`, `I64` as `S64`, see [`normalize_core_value`]) so host
+//! functions can take arguments straight from core WebAssembly.
+
+// `Value` always carries component model types, so alloc is always needed
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::values::Value;
+
+/// Lossless conversion to and from the shared [`Value`]
+pub trait ValueInterop: Sized {
+    /// Convert into the shared representation
+    ///
+    /// This never fails: every local value has a [`Value`] counterpart.
+    fn into_value(self) -> Value;
+
+    /// Convert from the shared representation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` has no counterpart in `Self`.
+    fn try_from_value(value: Value) -> Result<Self>;
+}
+
+impl ValueInterop for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+
+    fn try_from_value(value: Value) -> Result<Self> {
+        Ok(value)
+    }
+}
+
+/// Convert a sequence of local values into shared values
+pub fn values_into<T: ValueInterop>(values: impl IntoIterator<Item = T>) -> Vec<Value> {
+    values.into_iter().map(ValueInterop::into_value).collect()
+}
+
+/// Convert a sequence of shared values into local values
+///
+/// # Errors
+///
+/// Returns an error if any value has no counterpart in `T`.
+pub fn values_from<T: ValueInterop>(values: impl IntoIterator<Item = Value>) -> Result<Vec<T>> {
+    values.into_iter().map(T::try_from_value).collect()
+}
+
+/// Map core numeric values onto their component model counterparts
+///
+/// `I32` becomes `S32` and `I64` becomes `S64`; every other value is
+/// returned unchanged. `F32` and `F64` are shared by both models.
+#[must_use]
+pub fn normalize_core_value(value: Value) -> Value {
+    match value {
+        Value::I32(v) => Value::S32(v),
+        Value::I64(v) => Value::S64(v),
+        other => other,
+    }
+}
+
+/// Error for a [`Value`] without a counterpart in the target representation
+#[must_use]
+pub fn unrepresentable_value() -> Error {
+    Error::new(
+        ErrorCategory::Type,
+        codes::TYPE_MISMATCH,
+        "Value has no counterpart in the target representation",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_identity_round_trip() {
+        let values = vec![Value::I32(1), Value::S64(-2), Value::Void];
+        let shared = values_into(values);
+        let back: Vec<Value> = values_from(shared).unwrap();
+        assert_eq!(back.len(), 3);
+        assert!(matches!(back[1], Value::S64(-2)));
+    }
+
+    #[test]
+    fn test_normalize_core_value() {
+        assert!(matches!(normalize_core_value(Value::I32(-1)), Value::S32(-1)));
+        assert!(matches!(normalize_core_value(Value::I64(7)), Value::S64(7)));
+        assert!(matches!(normalize_core_value(Value::U8(3)), Value::U8(3)));
+    }
+}
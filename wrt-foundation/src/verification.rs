@@ -147,6 +147,112 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
     }
 }
 
+/// When a collection re-checks its checksum
+///
+/// Checking on every access is what [`VerificationLevel::Full`] does by
+/// default; the other schedules trade detection latency for throughput.
+/// Every schedule verifies before data is handed to another component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum VerificationSchedule {
+    /// Verify on every access
+    #[default]
+    EveryAccess,
+    /// Verify on every N-th access; a period of 0 is treated as 1
+    Periodic(u32),
+    /// Verify only when the owner reports an idle period
+    OnIdle,
+    /// Verify only before the data crosses a component boundary
+    OnTransition,
+}
+
+/// Event that gives a collection the chance to verify its checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerificationTrigger {
+    /// An element was read
+    Access,
+    /// The owner is idle
+    Idle,
+    /// The data is about to be exported to another component
+    Transition,
+}
+
+/// Decides which triggers lead to a checksum verification
+///
+/// The access counter uses interior mutability so that read-only accesses
+/// can advance a periodic schedule.
+pub struct VerificationScheduler {
+    schedule:   VerificationSchedule,
+    operations: AtomicU32,
+}
+
+impl VerificationScheduler {
+    /// Create a scheduler following `schedule`
+    #[must_use]
+    pub const fn new(schedule: VerificationSchedule) -> Self {
+        Self {
+            schedule,
+            operations: AtomicU32::new(0),
+        }
+    }
+
+    /// The schedule this scheduler follows
+    #[must_use]
+    pub fn schedule(&self) -> VerificationSchedule {
+        self.schedule
+    }
+
+    /// Check whether `trigger` should lead to a verification at `level`
+    ///
+    /// Access triggers advance the period counter of a periodic schedule.
+    pub fn should_verify(&self, level: VerificationLevel, trigger: VerificationTrigger) -> bool {
+        if level == VerificationLevel::Off {
+            return false;
+        }
+        match trigger {
+            VerificationTrigger::Transition => true,
+            VerificationTrigger::Idle => self.schedule != VerificationSchedule::OnTransition,
+            VerificationTrigger::Access => match self.schedule {
+                VerificationSchedule::EveryAccess => {
+                    level.should_verify(crate::traits::importance::READ)
+                },
+                VerificationSchedule::Periodic(period) => {
+                    let count = self.operations.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+                    count % period.max(1) == 0
+                },
+                VerificationSchedule::OnIdle | VerificationSchedule::OnTransition => false,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for VerificationScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerificationScheduler")
+            .field("schedule", &self.schedule)
+            .field("operations", &self.operations.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Clone for VerificationScheduler {
+    fn clone(&self) -> Self {
+        Self {
+            schedule:   self.schedule,
+            operations: AtomicU32::new(self.operations.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Schedulers compare equal if they follow the same schedule; the position
+/// within a period is not part of the compared state.
+impl PartialEq for VerificationScheduler {
+    fn eq(&self, other: &Self) -> bool {
+        self.schedule == other.schedule
+    }
+}
+
+impl Eq for VerificationScheduler {}
+
 /// A simple checksum implementation for data integrity verification
 ///
 /// This is a simple Adler32-like checksum that doesn't rely on external
@@ -321,4 +427,27 @@ fn test_hasher() {
         // Verify against known good value
         assert_eq!(hash, 0xafd0_71e5);
     }
+
+    #[test]
+    fn test_verification_schedules() {
+        use VerificationTrigger::{
+            Access,
+            Idle,
+            Transition,
+        };
+        let level = VerificationLevel::Full;
+
+        let periodic = VerificationScheduler::new(VerificationSchedule::Periodic(3));
+        let accesses: [bool; 6] = core::array::from_fn(|_| periodic.should_verify(level, Access));
+        assert_eq!(accesses, [false, false, true, false, false, true]);
+
+        let on_idle = VerificationScheduler::new(VerificationSchedule::OnIdle);
+        assert!(!on_idle.should_verify(level, Access));
+        assert!(on_idle.should_verify(level, Idle));
+
+        let on_transition = VerificationScheduler::new(VerificationSchedule::OnTransition);
+        assert!(!on_transition.should_verify(level, Idle));
+        assert!(on_transition.should_verify(level, Transition));
+        assert!(!on_transition.should_verify(VerificationLevel::Off, Transition));
+    }
 }
@@ -13,7 +13,7 @@
 use core::{
     alloc::{GlobalAlloc, Layout},
     cell::UnsafeCell,
-    ptr,
+    ptr::{self, NonNull},
     sync::atomic::{AtomicUsize, AtomicBool, Ordering},
 };
 
@@ -60,6 +60,8 @@ fn get(&self) -> *mut T {
 /// Scope information for hierarchical memory management
 #[derive(Debug, Clone, Copy)]
 pub struct ScopeInfo {
+    /// Identifier of the scope, unique per allocator
+    pub id: usize,
     /// Memory offset when scope was created (checkpoint)
     pub checkpoint: usize,
     /// Crate that owns this scope
@@ -68,16 +70,26 @@ pub struct ScopeInfo {
     pub budget: usize,
     /// Bytes allocated in this scope
     pub allocated: usize,
+    /// For an exclusive scope, the end of its block. Its memory is only
+    /// reset if nothing was allocated after the block.
+    pub block_end: Option<usize>,
+    /// Set when the scope's guard was dropped while a later scope was still
+    /// active. Its memory is not reclaimed; the entry is discarded once it is
+    /// back on top of the stack.
+    pub abandoned: bool,
 }
 
 impl ScopeInfo {
     /// Create a new scope
-    pub const fn new(checkpoint: usize, crate_id: CrateId, budget: usize) -> Self {
+    pub const fn new(id: usize, checkpoint: usize, crate_id: CrateId, budget: usize) -> Self {
         Self {
+            id,
             checkpoint,
             crate_id,
             budget,
             allocated: 0,
+            block_end: None,
+            abandoned: false,
         }
     }
 }
@@ -92,6 +104,10 @@ pub struct VerifiedAllocator {
     enabled: AtomicBool,
     /// Scope stack for hierarchical memory management (fixed size for const init)
     scopes: WrtMutex<StaticVec<ScopeInfo, MAX_SCOPES>>,
+    /// Identifier of the next scope
+    next_scope_id: AtomicUsize,
+    /// Set while an exclusive scope owns the allocator
+    exclusive: AtomicBool,
     /// Invariant checker
     #[cfg(debug_assertions)]
     invariant_checker: InvariantChecker,
@@ -117,6 +133,8 @@ pub const fn new(budget: usize) -> Self {
             allocated: AtomicUsize::new(0),
             enabled: AtomicBool::new(true),
             scopes: WrtMutex::new(StaticVec::new()),
+            next_scope_id: AtomicUsize::new(1),
+            exclusive: AtomicBool::new(false),
             #[cfg(debug_assertions)]
             invariant_checker: InvariantChecker {
                 check_frequency: 100,
@@ -141,6 +159,12 @@ pub fn allocate(&self, size: usize) -> Result<VerifiedAllocation<'_>> {
             return Err(Error::validation_invalid_parameter("Cannot allocate zero bytes"));
         }
 
+        if self.exclusive.load(Ordering::Acquire) {
+            return Err(Error::runtime_execution_error(
+                "Allocator is held by an exclusive scope",
+            ));
+        }
+
         // Atomic allocation with overflow checking
         let mut current = self.allocated.load(Ordering::Acquire);
         loop {
@@ -185,11 +209,9 @@ fn deallocate(&self, size: usize) {
         let result = self.allocated.fetch_sub(size, Ordering::AcqRel);
 
         // Check for underflow in debug mode
-        debug_assert!(
-            result >= size,
-            "Deallocation underflow: allocated={}, deallocating={}",
-            result, size
-        );
+        // Deallocation has no error path; a degraded failure is still recorded
+        // by the safety monitor
+        let _ = crate::wrt_debug_assert!(result >= size, "Deallocation underflow");
 
         #[cfg(debug_assertions)]
         self.check_invariants();
@@ -200,8 +222,14 @@ fn deallocate(&self, size: usize) {
     fn check_invariants(&self) {
         let count = self.invariant_checker.check_counter.fetch_add(1, Ordering::Relaxed);
         if count % self.invariant_checker.check_frequency == 0 {
-            debug_assert!(self.allocated.load(Ordering::Acquire) <= self.total_budget);
-            debug_assert!(self.enabled.load(Ordering::Acquire) || self.allocated.load(Ordering::Acquire) == 0);
+            let _ = crate::wrt_debug_assert!(
+                self.allocated.load(Ordering::Acquire) <= self.total_budget,
+                "Allocation exceeds budget"
+            );
+            let _ = crate::wrt_debug_assert!(
+                self.enabled.load(Ordering::Acquire) || self.allocated.load(Ordering::Acquire) == 0,
+                "Disabled allocator holds allocations"
+            );
         }
     }
     
@@ -217,7 +245,12 @@ pub fn disable(&self) {
     /// Enter a new memory scope with budget limit
     ///
     /// Creates a checkpoint at the current allocation position. All allocations
-    /// until `exit_scope()` will be tracked against this scope's budget.
+    /// until the scope exits will be tracked against this scope's budget.
+    ///
+    /// Scopes are shared by every user of the allocator and must exit in LIFO
+    /// order. A scope whose guard drops while a later scope is still active
+    /// is abandoned: its memory stays allocated instead of being reset
+    /// underneath the later scope.
     ///
     /// # Arguments
     /// * `crate_id` - The crate entering the scope
@@ -225,45 +258,138 @@ pub fn disable(&self) {
     ///
     /// # Returns
     /// * `Ok(ScopeGuard)` - RAII guard that exits scope on drop
-    /// * `Err` - If scope stack is full or parameters invalid
+    /// * `Err` - If scope stack is full, an exclusive scope owns the
+    ///   allocator or parameters are invalid
     pub fn enter_scope(&self, crate_id: CrateId, budget: usize) -> Result<ScopeGuard<'_>> {
         if budget == 0 {
             return Err(Error::validation_invalid_parameter("Scope budget cannot be zero"));
         }
 
-        let checkpoint = self.allocated.load(Ordering::Acquire);
-        let scope = ScopeInfo::new(checkpoint, crate_id, budget);
-
         let mut scopes = self.scopes.lock();
-        scopes.push(scope).map_err(|_| {
+        if self.exclusive.load(Ordering::Acquire) {
+            return Err(Error::runtime_execution_error(
+                "Allocator is held by an exclusive scope",
+            ));
+        }
+        let id = self.next_scope_id.fetch_add(1, Ordering::Relaxed);
+        let checkpoint = self.allocated.load(Ordering::Acquire);
+        scopes.push(ScopeInfo::new(id, checkpoint, crate_id, budget)).map_err(|_| {
             Error::runtime_execution_error("Scope stack overflow: too many nested scopes")
         })?;
         drop(scopes); // Release lock
 
         Ok(ScopeGuard {
             allocator: self,
+            id,
             entered: true,
         })
     }
 
-    /// Exit the current scope and reset memory to checkpoint
+    /// Enter a scope that owns the allocator and reserve `layout` for it
+    ///
+    /// Until the returned guard exits, every other allocation and scope of
+    /// this allocator fails, so the exit frees exactly the returned block.
+    /// The allocator must not have active scopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is empty, the allocator is disabled,
+    /// has active scopes or cannot hold the block.
+    pub fn enter_exclusive_scope(
+        &self,
+        crate_id: CrateId,
+        layout: Layout,
+    ) -> Result<(ScopeGuard<'_>, NonNull<u8>)> {
+        if layout.size() == 0 {
+            return Err(Error::validation_invalid_parameter("Scope budget cannot be zero"));
+        }
+        if !self.enabled.load(Ordering::Acquire) {
+            return Err(Error::runtime_error("Allocator is disabled"));
+        }
+
+        let mut scopes = self.scopes.lock();
+        if !scopes.is_empty() || self.exclusive.load(Ordering::Acquire) {
+            return Err(Error::runtime_execution_error(
+                "Exclusive scope requires an allocator without active scopes",
+            ));
+        }
+        self.exclusive.store(true, Ordering::Release);
+
+        // Allocations that were already past their exclusivity check may
+        // still land after the checkpoint; the exit then leaves the block
+        // allocated instead of resetting underneath them.
+        let Some((checkpoint, start)) = self.bump(layout) else {
+            self.exclusive.store(false, Ordering::Release);
+            return Err(Error::memory_error("Allocator cannot hold exclusive scope"));
+        };
+        let id = self.next_scope_id.fetch_add(1, Ordering::Relaxed);
+        let mut scope = ScopeInfo::new(id, checkpoint, crate_id, layout.size());
+        scope.allocated = layout.size();
+        scope.block_end = Some(start + layout.size());
+        if scopes.push(scope).is_err() {
+            self.exclusive.store(false, Ordering::Release);
+            return Err(Error::runtime_execution_error(
+                "Scope stack overflow: too many nested scopes",
+            ));
+        }
+        drop(scopes);
+
+        // SAFETY: `bump` reserved [start, start + size) within the heap buffer.
+        #[allow(unsafe_code)]
+        let block = unsafe { NonNull::new_unchecked(self.heap_start().add(start)) };
+        Ok((ScopeGuard { allocator: self, id, entered: true }, block))
+    }
+
+    /// Exit scope `id` and reset memory to its checkpoint
     ///
     /// This resets the bump allocator pointer to where it was when the
     /// scope was entered, effectively "freeing" all allocations made
-    /// within the scope.
+    /// within the scope. Only the scope on top of the stack can exit; any
+    /// other scope is marked abandoned and keeps its memory.
     ///
-    /// # Safety
-    /// After calling this, all pointers allocated within the scope become invalid.
-    /// The ScopeGuard ensures this is called automatically on drop.
-    pub fn exit_scope(&self) {
+    /// After this returns `Ok`, all pointers allocated within the scope
+    /// become invalid. The ScopeGuard ensures this is called automatically
+    /// on drop.
+    fn exit_scope(&self, id: usize) -> Result<()> {
         let mut scopes = self.scopes.lock();
-        if let Some(scope) = scopes.pop() {
-            // Reset allocator to checkpoint
-            self.allocated.store(scope.checkpoint, Ordering::Release);
+        match scopes.last() {
+            Some(scope) if scope.id == id => {},
+            _ => {
+                return match scopes.iter_mut().find(|scope| scope.id == id) {
+                    Some(scope) => {
+                        scope.abandoned = true;
+                        Err(Error::runtime_execution_error(
+                            "Scope exited out of order: a later scope is still active",
+                        ))
+                    },
+                    None => Err(Error::runtime_execution_error("Scope is not active")),
+                };
+            },
+        }
 
-            #[cfg(debug_assertions)]
-            self.check_invariants();
+        if let Some(scope) = scopes.pop() {
+            match scope.block_end {
+                Some(end) => {
+                    let _ = self.allocated.compare_exchange(
+                        end,
+                        scope.checkpoint,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                    );
+                    self.exclusive.store(false, Ordering::Release);
+                },
+                // Reset allocator to checkpoint
+                None => self.allocated.store(scope.checkpoint, Ordering::Release),
+            }
         }
+        // Scopes abandoned above the new top keep their memory
+        while scopes.last().is_some_and(|scope| scope.abandoned) {
+            scopes.pop();
+        }
+
+        #[cfg(debug_assertions)]
+        self.check_invariants();
+        Ok(())
     }
 
     /// Check if allocation fits within current scope budget
@@ -286,6 +412,41 @@ fn check_scope_budget(&self, size: usize) -> Result<()> {
         Ok(())
     }
 
+    /// Reserve `layout` by bumping the allocation offset
+    ///
+    /// Returns the previous offset and the aligned start of the block, or
+    /// `None` if the heap cannot hold it.
+    fn bump(&self, layout: Layout) -> Option<(usize, usize)> {
+        // Atomic bump allocation with alignment
+        let mut current = self.allocated.load(Ordering::Acquire);
+        loop {
+            let aligned = align_up(current, layout.align());
+            let new_offset = aligned.checked_add(layout.size())?;
+
+            // Check against total budget (heap size)
+            if new_offset > TOTAL_HEAP_SIZE {
+                return None; // Out of memory
+            }
+
+            // Try atomic update
+            match self.allocated.compare_exchange_weak(
+                current,
+                new_offset,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    #[cfg(debug_assertions)]
+                    self.check_invariants();
+
+                    return Some((current, aligned));
+                }
+                // Retry with updated value
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
     /// Get the start of the heap buffer
     #[inline]
     fn heap_start(&self) -> *mut u8 {
@@ -343,6 +504,7 @@ fn drop(&mut self) {
 /// All pointers allocated within the scope become invalid when the guard drops.
 pub struct ScopeGuard<'a> {
     allocator: &'a VerifiedAllocator,
+    id: usize,
     entered: bool,
 }
 
@@ -351,18 +513,23 @@ impl<'a> ScopeGuard<'a> {
     ///
     /// This consumes the guard, preventing the Drop implementation from
     /// running again.
-    pub fn exit(mut self) {
-        if self.entered {
-            self.allocator.exit_scope();
-            self.entered = false;
-        }
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a scope entered after this one is still active.
+    /// The scope is then abandoned and its memory is not reclaimed.
+    pub fn exit(mut self) -> Result<()> {
+        self.entered = false;
+        self.allocator.exit_scope(self.id)
     }
 }
 
 impl<'a> Drop for ScopeGuard<'a> {
     fn drop(&mut self) {
         if self.entered {
-            self.allocator.exit_scope();
+            // An out-of-order exit abandons the scope; there is no caller to
+            // report it to
+            let _ = self.allocator.exit_scope(self.id);
         }
     }
 }
@@ -402,47 +569,20 @@ unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
             return ptr::null_mut();
         }
 
+        // An exclusive scope owns all memory allocated after its checkpoint
+        if self.exclusive.load(Ordering::Acquire) {
+            return ptr::null_mut();
+        }
+
         // Check scope budget if we're in a scope
         if self.check_scope_budget(size).is_err() {
             return ptr::null_mut();
         }
 
-        // Atomic bump allocation with alignment
-        loop {
-            let current = self.allocated.load(Ordering::Acquire);
-            let aligned = align_up(current, align);
-            let new_offset = match aligned.checked_add(size) {
-                Some(offset) => offset,
-                None => return ptr::null_mut(), // Overflow
-            };
-
-            // Check against total budget (heap size)
-            if new_offset > TOTAL_HEAP_SIZE {
-                return ptr::null_mut(); // Out of memory
-            }
-
-            // Try atomic update
-            match self.allocated.compare_exchange_weak(
-                current,
-                new_offset,
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            ) {
-                Ok(_) => {
-                    // Success - return pointer
-                    // SAFETY: Edition 2024 requires explicit unsafe blocks in unsafe functions
-                    let ptr = unsafe { self.heap_start().add(aligned) };
-
-                    #[cfg(debug_assertions)]
-                    self.check_invariants();
-
-                    return ptr;
-                }
-                Err(_) => {
-                    // Retry with updated value
-                    continue;
-                }
-            }
+        match self.bump(layout) {
+            // SAFETY: Edition 2024 requires explicit unsafe blocks in unsafe functions
+            Some((_, aligned)) => unsafe { self.heap_start().add(aligned) },
+            None => ptr::null_mut(),
         }
     }
 
@@ -591,7 +731,7 @@ fn test_scope_basic() {
         assert_eq!(allocator.current_offset(), 512);
 
         // Exit scope - memory resets
-        scope.exit();
+        scope.exit().unwrap();
         assert_eq!(allocator.current_offset(), checkpoint);
     }
 
@@ -639,15 +779,56 @@ fn test_nested_scopes() {
             assert!(offset_after_inner > offset_after_outer);
 
             // Inner scope exits
-            scope2.exit();
+            scope2.exit().unwrap();
             assert_eq!(allocator.current_offset(), offset_after_outer);
         }
 
         // Outer scope exits
-        scope1.exit();
+        scope1.exit().unwrap();
         assert_eq!(allocator.current_offset(), checkpoint1);
     }
 
+    #[test]
+    fn test_out_of_order_exit() {
+        let allocator = VerifiedAllocator::new(TOTAL_HEAP_SIZE);
+
+        let checkpoint = allocator.current_offset();
+        let scope1 = allocator.enter_scope(CrateId::Runtime, 2048).unwrap();
+        unsafe { allocator.alloc(Layout::from_size_align(512, 8).unwrap()) };
+        let scope2 = allocator.enter_scope(CrateId::Component, 1024).unwrap();
+        unsafe { allocator.alloc(Layout::from_size_align(256, 8).unwrap()) };
+        let offset = allocator.current_offset();
+
+        // The outer scope is abandoned instead of resetting under the inner one
+        assert!(scope1.exit().is_err());
+        assert_eq!(allocator.current_offset(), offset);
+
+        // The inner scope resets to its own checkpoint and drops the abandoned one
+        scope2.exit().unwrap();
+        assert_eq!(allocator.current_offset(), checkpoint + 512);
+        assert!(allocator.scopes.lock().is_empty());
+    }
+
+    #[test]
+    fn test_exclusive_scope() {
+        let allocator = VerifiedAllocator::new(TOTAL_HEAP_SIZE);
+        let layout = Layout::from_size_align(1024, 16).unwrap();
+
+        let scope = allocator.enter_scope(CrateId::Runtime, 64).unwrap();
+        assert!(allocator.enter_exclusive_scope(CrateId::Runtime, layout).is_err());
+        scope.exit().unwrap();
+
+        let checkpoint = allocator.current_offset();
+        let (scope, _block) = allocator.enter_exclusive_scope(CrateId::Runtime, layout).unwrap();
+        assert!(allocator.allocate(8).is_err());
+        assert!(allocator.enter_scope(CrateId::Runtime, 64).is_err());
+        assert!(unsafe { allocator.alloc(Layout::new::<u64>()) }.is_null());
+
+        scope.exit().unwrap();
+        assert_eq!(allocator.current_offset(), checkpoint);
+        assert!(allocator.allocate(8).is_ok());
+    }
+
     #[test]
     fn test_align_up() {
         assert_eq!(align_up(0, 8), 0);
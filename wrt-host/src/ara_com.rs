@@ -0,0 +1,484 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Bridge between component interfaces and AUTOSAR Adaptive `ara::com`.
+//!
+//! Each WIT interface of a world maps to one `ara::com` service interface
+//! and each of its functions to one service method:
+//!
+//! - An interface the component **exports** is offered as a service. A
+//!   [`ServiceSkeleton`] receives method requests from the middleware and
+//!   calls the matching component export through an [`ExportInvoker`].
+//! - An interface the component **imports** is consumed from a service. A
+//!   [`ServiceProxy`] registers a host function for every import that sends
+//!   the call to the service as a method request.
+//!
+//! The middleware itself, e.g. a vendor `ara::com` stack reached over FFI or
+//! a SOME/IP binding, is supplied by the integrator as an [`AraComBinding`].
+//! Arguments and results are passed as the core values of the canonical ABI,
+//! so the binding serializes them without knowing the WIT types. Events and
+//! fields have no WIT counterpart and are not mapped.
+//!
+//! Skeletons and proxies are generated from WIT by `cargo-wrt ara-com`,
+//! which assigns service and method IDs and computes the number of core
+//! values of each method.
+
+use crate::{
+    callback::CallbackRegistry,
+    prelude::{
+        codes,
+        Arc,
+        Error,
+        ErrorCategory,
+        HostFunctionHandler,
+        Result,
+        String,
+        ToString,
+        Value,
+        Vec,
+    },
+};
+
+/// Method of a service interface, backed by a component function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceMethod {
+    /// Name of the component function
+    pub function:  String,
+    /// Method ID within the service
+    pub method_id: u16,
+    /// Number of core values the method is called with
+    pub params:    usize,
+    /// Number of core values the method returns
+    pub results:   usize,
+}
+
+/// `ara::com` service interface mapped from a WIT interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInterface {
+    /// Short name of the service interface
+    name:          String,
+    /// Service ID
+    service_id:    u16,
+    /// Major interface version; proxies only bind to the same major version
+    major_version: u8,
+    /// Minor interface version
+    minor_version: u32,
+    /// Methods of the service
+    methods:       Vec<ServiceMethod>,
+}
+
+impl ServiceInterface {
+    /// Create a service interface without methods
+    #[must_use]
+    pub fn new(name: &str, service_id: u16, major_version: u8, minor_version: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            service_id,
+            major_version,
+            minor_version,
+            methods: Vec::new(),
+        }
+    }
+
+    /// Add a method backed by `function`, called with `params` core values
+    /// and returning `results` core values
+    #[must_use]
+    pub fn method(mut self, function: &str, method_id: u16, params: usize, results: usize) -> Self {
+        self.methods.push(ServiceMethod {
+            function: function.to_string(),
+            method_id,
+            params,
+            results,
+        });
+        self
+    }
+
+    /// Short name of the service interface
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Service ID
+    #[must_use]
+    pub fn service_id(&self) -> u16 {
+        self.service_id
+    }
+
+    /// Major and minor interface version
+    #[must_use]
+    pub fn version(&self) -> (u8, u32) {
+        (self.major_version, self.minor_version)
+    }
+
+    /// Methods of the service
+    #[must_use]
+    pub fn methods(&self) -> &[ServiceMethod] {
+        &self.methods
+    }
+
+    /// Method with ID `method_id`
+    #[must_use]
+    pub fn find_method(&self, method_id: u16) -> Option<&ServiceMethod> {
+        self.methods.iter().find(|method| method.method_id == method_id)
+    }
+
+    /// Check that method IDs are unique
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if two methods share an ID
+    pub fn validate(&self) -> Result<()> {
+        for (index, method) in self.methods.iter().enumerate() {
+            if self.methods[..index].iter().any(|other| other.method_id == method.method_id) {
+                return Err(Error::new(
+                    ErrorCategory::Validation,
+                    codes::VALIDATION_ERROR,
+                    "Duplicate method ID in ara::com service interface",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Communication middleware the bridge offers and consumes services through
+pub trait AraComBinding: Send + Sync {
+    /// Offer the service instance of `skeleton`
+    ///
+    /// The binding calls [`ServiceSkeleton::handle`] for every method request
+    /// it receives until the offer is stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the middleware rejects the offer
+    fn offer_service(&self, skeleton: &ServiceSkeleton) -> Result<()>;
+
+    /// Stop offering `instance_id` of the service with ID `service_id`
+    fn stop_offer_service(&self, service_id: u16, instance_id: u16);
+
+    /// Send a method request to `instance_id` of `service` and wait for the
+    /// response
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no matching service instance is available or the
+    /// method returns an application error
+    fn call_method(
+        &self,
+        service: &ServiceInterface,
+        instance_id: u16,
+        method: &ServiceMethod,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>>;
+}
+
+/// Calls functions exported by a component instance
+pub trait ExportInvoker {
+    /// Call `function` exported from `module` with `args`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the export is missing or traps
+    fn invoke(&mut self, module: &str, function: &str, args: Vec<Value>) -> Result<Vec<Value>>;
+}
+
+/// Server side of a service, backed by the exports of a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceSkeleton {
+    /// Offered service interface
+    interface:     ServiceInterface,
+    /// Offered service instance
+    instance_id:   u16,
+    /// Module the component exports the methods from
+    export_module: String,
+}
+
+impl ServiceSkeleton {
+    /// Create a skeleton offering `instance_id` of `interface` with the
+    /// functions the component exports from `export_module`
+    #[must_use]
+    pub fn new(interface: ServiceInterface, instance_id: u16, export_module: &str) -> Self {
+        Self {
+            interface,
+            instance_id,
+            export_module: export_module.to_string(),
+        }
+    }
+
+    /// Offered service interface
+    #[must_use]
+    pub fn interface(&self) -> &ServiceInterface {
+        &self.interface
+    }
+
+    /// Offered service instance
+    #[must_use]
+    pub fn instance_id(&self) -> u16 {
+        self.instance_id
+    }
+
+    /// Module the component exports the methods from
+    #[must_use]
+    pub fn export_module(&self) -> &str {
+        &self.export_module
+    }
+
+    /// Offer the service through `binding`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interface is invalid or the middleware
+    /// rejects the offer
+    pub fn offer(&self, binding: &dyn AraComBinding) -> Result<()> {
+        self.interface.validate()?;
+        binding.offer_service(self)
+    }
+
+    /// Stop offering the service through `binding`
+    pub fn stop_offer(&self, binding: &dyn AraComBinding) {
+        binding.stop_offer_service(self.interface.service_id, self.instance_id);
+    }
+
+    /// Handle a method request by calling the component export
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the method is unknown, the request or response
+    /// has the wrong number of values, or the export fails
+    pub fn handle(
+        &self,
+        method_id: u16,
+        args: Vec<Value>,
+        invoker: &mut dyn ExportInvoker,
+    ) -> Result<Vec<Value>> {
+        let method = self.interface.find_method(method_id).ok_or_else(|| {
+            Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_NOT_FOUND,
+                "Unknown method ID for ara::com service",
+            )
+        })?;
+        if args.len() != method.params {
+            return Err(arity_mismatch(
+                "Service method called with the wrong arguments",
+            ));
+        }
+        let results = invoker.invoke(&self.export_module, &method.function, args)?;
+        if results.len() != method.results {
+            return Err(arity_mismatch(
+                "Component export returned the wrong results",
+            ));
+        }
+        Ok(results)
+    }
+}
+
+/// Client side of a service, backing the imports of a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceProxy {
+    /// Consumed service interface
+    interface:     ServiceInterface,
+    /// Consumed service instance
+    instance_id:   u16,
+    /// Module the component imports the methods from
+    import_module: String,
+}
+
+impl ServiceProxy {
+    /// Create a proxy binding the functions a component imports from
+    /// `import_module` to `instance_id` of `interface`
+    #[must_use]
+    pub fn new(interface: ServiceInterface, instance_id: u16, import_module: &str) -> Self {
+        Self {
+            interface,
+            instance_id,
+            import_module: import_module.to_string(),
+        }
+    }
+
+    /// Consumed service interface
+    #[must_use]
+    pub fn interface(&self) -> &ServiceInterface {
+        &self.interface
+    }
+
+    /// Consumed service instance
+    #[must_use]
+    pub fn instance_id(&self) -> u16 {
+        self.instance_id
+    }
+
+    /// Module the component imports the methods from
+    #[must_use]
+    pub fn import_module(&self) -> &str {
+        &self.import_module
+    }
+
+    /// Register a host function for every method that sends the call to the
+    /// service through `binding`
+    ///
+    /// Calls with the wrong number of values, in either direction, fail with
+    /// a type mismatch. Returns the number of registered handlers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interface is invalid, in which case no
+    /// handlers are registered
+    pub fn install(
+        &self,
+        registry: &mut CallbackRegistry,
+        binding: Arc<dyn AraComBinding>,
+    ) -> Result<usize> {
+        self.interface.validate()?;
+        let interface = Arc::new(self.interface.clone());
+        for method in &self.interface.methods {
+            let interface = Arc::clone(&interface);
+            let binding = Arc::clone(&binding);
+            let method = method.clone();
+            let instance_id = self.instance_id;
+            let function = method.function.clone();
+            let handler = HostFunctionHandler::new_with_args(move |_, args| {
+                if args.len() != method.params {
+                    return Err(arity_mismatch(
+                        "Service import called with the wrong arguments",
+                    ));
+                }
+                let results = binding.call_method(&interface, instance_id, &method, args)?;
+                if results.len() != method.results {
+                    return Err(arity_mismatch("Service method returned the wrong results"));
+                }
+                Ok(results)
+            });
+            registry.register_host_function(&self.import_module, &function, handler);
+        }
+        Ok(self.interface.methods.len())
+    }
+}
+
+/// Error for a call whose value count does not match the service interface
+fn arity_mismatch(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Type, codes::TYPE_MISMATCH, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::prelude::vec;
+
+    /// Loopback middleware dispatching requests to the offered skeletons
+    #[derive(Default)]
+    struct Loopback {
+        offered: Mutex<Vec<ServiceSkeleton>>,
+    }
+
+    /// Component exporting `add`
+    struct Calculator;
+
+    impl ExportInvoker for Calculator {
+        fn invoke(&mut self, _: &str, function: &str, args: Vec<Value>) -> Result<Vec<Value>> {
+            match (function, args.as_slice()) {
+                ("add", [Value::I32(a), Value::I32(b)]) => Ok(vec![Value::I32(a + b)]),
+                _ => Ok(vec![]),
+            }
+        }
+    }
+
+    impl AraComBinding for Loopback {
+        fn offer_service(&self, skeleton: &ServiceSkeleton) -> Result<()> {
+            self.offered.lock().unwrap().push(skeleton.clone());
+            Ok(())
+        }
+
+        fn stop_offer_service(&self, service_id: u16, instance_id: u16) {
+            self.offered.lock().unwrap().retain(|skeleton| {
+                (skeleton.interface().service_id(), skeleton.instance_id())
+                    != (service_id, instance_id)
+            });
+        }
+
+        fn call_method(
+            &self,
+            service: &ServiceInterface,
+            instance_id: u16,
+            method: &ServiceMethod,
+            args: Vec<Value>,
+        ) -> Result<Vec<Value>> {
+            let offered = self.offered.lock().unwrap();
+            let skeleton = offered
+                .iter()
+                .find(|skeleton| {
+                    skeleton.interface().service_id() == service.service_id()
+                        && skeleton.interface().version().0 == service.version().0
+                        && skeleton.instance_id() == instance_id
+                })
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_NOT_FOUND,
+                        "No instance",
+                    )
+                })?;
+            skeleton.handle(method.method_id, args, &mut Calculator)
+        }
+    }
+
+    fn calculator() -> ServiceInterface {
+        ServiceInterface::new("math", 0x1000, 1, 0)
+            .method("add", 0x0001, 2, 1)
+            .method("reset", 0x0002, 0, 0)
+    }
+
+    #[test]
+    fn test_skeleton_handles_method_requests() {
+        let skeleton = ServiceSkeleton::new(calculator(), 1, "example:calc/math@1.0.0");
+        let results = skeleton
+            .handle(0x0001, vec![Value::I32(2), Value::I32(3)], &mut Calculator)
+            .unwrap();
+        assert!(matches!(results.as_slice(), [Value::I32(5)]));
+
+        let err = skeleton.handle(0x0003, vec![], &mut Calculator).unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_NOT_FOUND);
+        let err = skeleton.handle(0x0001, vec![Value::I32(2)], &mut Calculator).unwrap_err();
+        assert_eq!(err.code, codes::TYPE_MISMATCH);
+
+        let invalid = calculator().method("sub", 0x0001, 2, 1);
+        assert!(ServiceSkeleton::new(invalid, 1, "math").offer(&Loopback::default()).is_err());
+    }
+
+    #[test]
+    fn test_proxy_forwards_imports_to_offered_service() {
+        let binding = Arc::new(Loopback::default());
+        let skeleton = ServiceSkeleton::new(calculator(), 1, "example:calc/math@1.0.0");
+        skeleton.offer(binding.as_ref()).unwrap();
+
+        let mut registry = CallbackRegistry::new();
+        let proxy = ServiceProxy::new(calculator(), 1, "example:calc/math@1.0.0");
+        assert_eq!(proxy.install(&mut registry, binding.clone()).unwrap(), 2);
+
+        let mut engine = ();
+        let results = registry
+            .call_host_function(
+                &mut engine,
+                "example:calc/math@1.0.0",
+                "add",
+                vec![Value::I32(4), Value::I32(5)],
+            )
+            .unwrap();
+        assert!(matches!(results.as_slice(), [Value::I32(9)]));
+
+        skeleton.stop_offer(binding.as_ref());
+        let err = registry
+            .call_host_function(
+                &mut engine,
+                "example:calc/math@1.0.0",
+                "add",
+                vec![Value::I32(4), Value::I32(5)],
+            )
+            .unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_NOT_FOUND);
+    }
+}
@@ -684,6 +684,11 @@ pub fn create_safety_check_function() -> SimpleBoundedHostFunction {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(not(feature = "std"))]
+    extern crate alloc;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
     use super::*;
 
     #[test]
@@ -405,7 +405,7 @@ pub fn build_builtin_host(&self) -> BuiltinHost {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use wrt_foundation::values::Value;
 
@@ -478,7 +478,6 @@ fn test_manually_implemented_builtin() {
         assert!(result.is_ok());
     }
 
-    #[cfg(feature = "std")]
     #[test]
     fn test_link_interceptor() {
         // Creating a simple mock interceptor for testing
@@ -532,7 +531,6 @@ fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
         assert!(registry.get_interceptor().is_some());
     }
 
-    #[cfg(feature = "std")]
     #[test]
     fn test_builtin_host_creation() {
         let builder = HostBuilder::new()
@@ -552,7 +550,6 @@ fn test_builtin_host_creation() {
         assert_eq!(result.unwrap(), vec![Value::I32(42)]);
     }
 
-    #[cfg(feature = "std")]
     #[test]
     fn test_fallback_registration() {
         let builder = HostBuilder::new()
@@ -570,7 +567,6 @@ fn test_fallback_registration() {
         assert_eq!(result.unwrap(), vec![Value::I32(99)]);
     }
 
-    #[cfg(feature = "std")]
     #[test]
     fn test_builder_with_interceptor() {
         #[cfg(feature = "std")]
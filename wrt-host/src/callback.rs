@@ -7,6 +7,12 @@
 //! This module provides a registry for callbacks that can be invoked from
 //! WebAssembly components, including host functions and interceptors.
 
+#[cfg(feature = "std")]
+use std::sync::MutexGuard;
+
+#[cfg(feature = "std")]
+use wrt_foundation::CleanExternType;
+
 // Use the prelude for consistent imports
 #[cfg(feature = "std")]
 use crate::prelude::LinkInterceptor;
@@ -28,10 +34,27 @@
     Value,
 };
 #[cfg(feature = "std")]
+use crate::contract::{
+    ContractAction,
+    ContractReport,
+    HostContract,
+};
+#[cfg(feature = "std")]
+use crate::externref::HostObjectTable;
+#[cfg(feature = "std")]
+use crate::instance_context::InstanceContexts;
+#[cfg(feature = "std")]
+use crate::memo::{
+    MemoCache,
+    MemoScope,
+    MemoStats,
+};
+#[cfg(feature = "std")]
 use crate::prelude::{
     fmt,
     Arc,
     BuiltinHost,
+    Mutex,
 };
 
 // Type aliases for no_std compatibility
@@ -239,6 +262,26 @@ pub struct CallbackRegistry {
     /// Optional interceptor for monitoring and modifying function calls
     #[cfg(feature = "std")]
     interceptor: Option<Arc<LinkInterceptor>>,
+
+    /// Pure host functions (module::function -> memoization scope)
+    #[cfg(feature = "std")]
+    pure_functions: HashMap<String, MemoScope>,
+
+    /// Memoized results of pure host functions, shared between clones
+    #[cfg(feature = "std")]
+    memo: Arc<Mutex<MemoCache>>,
+
+    /// Host objects referenced by `externref` values, shared between clones
+    #[cfg(feature = "std")]
+    host_objects: Arc<HostObjectTable>,
+
+    /// Typed host state of each instance, shared between clones
+    #[cfg(feature = "std")]
+    instance_contexts: Arc<InstanceContexts>,
+
+    /// Contract violations of checked host functions, shared between clones
+    #[cfg(feature = "std")]
+    contracts: Arc<ContractReport>,
 }
 
 #[cfg(feature = "std")]
@@ -273,9 +316,14 @@ impl CallbackRegistry {
     #[cfg(feature = "std")]
     pub fn new() -> Self {
         Self {
-            callbacks:      HashMap::with_capacity(0),
-            interceptor:    None,
-            host_functions: HashMap::with_capacity(0),
+            callbacks:         HashMap::with_capacity(0),
+            interceptor:       None,
+            host_functions:    HashMap::with_capacity(0),
+            pure_functions:    HashMap::with_capacity(0),
+            memo:              Arc::new(Mutex::new(MemoCache::new())),
+            host_objects:      Arc::new(HostObjectTable::new()),
+            instance_contexts: Arc::new(InstanceContexts::new()),
+            contracts:         Arc::new(ContractReport::default()),
         }
     }
 
@@ -370,10 +418,153 @@ pub fn register_host_function(
         let module_name = module_name.to_string();
         let function_name = function_name.to_string();
 
+        // Results of a replaced handler must not be reused
+        if self.pure_functions.contains_key(&function_key(&module_name, &function_name)) {
+            self.memo().clear_function(&function_key(&module_name, &function_name));
+        }
+
         let module_functions = self.host_functions.entry(module_name).or_default();
         module_functions.insert(function_name, handler);
     }
 
+    /// Register a pure host function whose results are memoized
+    ///
+    /// A pure function must return the same results for the same arguments
+    /// and have no side effects, e.g. a static configuration query.
+    #[cfg(feature = "std")]
+    pub fn register_pure_host_function(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        handler: HostFunctionHandler,
+        scope: MemoScope,
+    ) {
+        self.register_host_function(module_name, function_name, handler);
+        self.mark_pure(module_name, function_name, scope);
+    }
+
+    /// Register a host function whose results are checked against its
+    /// declared type in debug builds
+    ///
+    /// Violations panic or are recorded in [`Self::contract_report`],
+    /// depending on [`Self::with_contract_action`]. In release builds the
+    /// handler is registered unchecked.
+    ///
+    /// # Errors
+    ///
+    /// Returns a type error if `ty` is not a function type
+    #[cfg(feature = "std")]
+    pub fn register_checked_host_function(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        handler: HostFunctionHandler,
+        ty: &CleanExternType,
+    ) -> Result<()> {
+        let contract = HostContract::new(module_name, function_name, ty)?;
+        let handler = contract.wrap(handler, self.contracts.clone());
+        self.register_host_function(module_name, function_name, handler);
+        Ok(())
+    }
+
+    /// Set what checked host functions registered from now on do when they
+    /// break their contract, dropping the violations recorded so far
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn with_contract_action(mut self, action: ContractAction) -> Self {
+        self.contracts = Arc::new(ContractReport::new(action));
+        self
+    }
+
+    /// Contract violations recorded by checked host functions
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn contract_report(&self) -> &Arc<ContractReport> {
+        &self.contracts
+    }
+
+    /// Mark a host function as pure so its results are memoized for `scope`
+    #[cfg(feature = "std")]
+    pub fn mark_pure(&mut self, module_name: &str, function_name: &str, scope: MemoScope) {
+        self.pure_functions.insert(function_key(module_name, function_name), scope);
+    }
+
+    /// Memoization scope of a host function, if it is marked pure
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn pure_scope(&self, module_name: &str, function_name: &str) -> Option<MemoScope> {
+        self.pure_functions.get(&function_key(module_name, function_name)).copied()
+    }
+
+    /// Bound the number of memoized results, dropping those memoized so far
+    #[cfg(feature = "std")]
+    pub fn with_memo_capacity(mut self, capacity: usize) -> Self {
+        self.memo = Arc::new(Mutex::new(MemoCache::with_capacity(capacity)));
+        self
+    }
+
+    /// Drop results memoized for the current top-level call
+    ///
+    /// Engines call this when a top-level call into the guest returns.
+    #[cfg(feature = "std")]
+    pub fn end_memo_call(&self) {
+        self.memo().end_call();
+    }
+
+    /// Drop results memoized for an instance that was discarded
+    #[cfg(feature = "std")]
+    pub fn clear_memo_instance(&self, instance: usize) {
+        self.memo().clear_instance(instance);
+    }
+
+    /// Hit and miss counts of the memoization cache
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn memo_stats(&self) -> MemoStats {
+        self.memo().stats()
+    }
+
+    /// Host objects referenced by `externref` values
+    ///
+    /// Objects stored while a host function runs on behalf of an instance
+    /// are owned by that instance. Clone the table into host functions that
+    /// store or look up objects.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn host_objects(&self) -> &Arc<HostObjectTable> {
+        &self.host_objects
+    }
+
+    /// Drop the host objects owned by an instance that was discarded
+    #[cfg(feature = "std")]
+    pub fn release_host_objects(&self, instance: usize) -> usize {
+        self.host_objects.release_instance(instance)
+    }
+
+    /// Typed host state of each instance
+    ///
+    /// Host functions clone this into their closure and reach the state of
+    /// the calling instance with [`InstanceContexts::with_current`].
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn instance_contexts(&self) -> &Arc<InstanceContexts> {
+        &self.instance_contexts
+    }
+
+    /// Drop the host state of an instance that was discarded
+    #[cfg(feature = "std")]
+    pub fn release_instance_context(&self, instance: usize) -> usize {
+        self.instance_contexts.release_instance(instance)
+    }
+
+    /// Lock the memoization cache
+    ///
+    /// A poisoned lock is recovered: entries are only ever inserted whole.
+    #[cfg(feature = "std")]
+    fn memo(&self) -> MutexGuard<'_, MemoCache> {
+        self.memo.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
     /// Register a host function (`no_std` version)
     #[cfg(not(feature = "std"))]
     pub fn register_host_function(
@@ -397,6 +588,17 @@ pub fn has_host_function(&self, module_name: &str, function_name: &str) -> bool
             .is_some()
     }
 
+    /// Get the handler registered for a host function
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn get_host_function(
+        &self,
+        module_name: &str,
+        function_name: &str,
+    ) -> Option<&HostFunctionHandler> {
+        self.host_functions.get(module_name).and_then(|funcs| funcs.get(function_name))
+    }
+
     /// Check if a host function is registered (`no_std` version)
     #[must_use]
     #[cfg(not(feature = "std"))]
@@ -417,28 +619,108 @@ pub fn call_host_function(
         module_name: &str,
         function_name: &str,
         args: ValueVec,
+    ) -> Result<ValueVec> {
+        self.call_host_function_scoped(engine, None, module_name, function_name, args)
+    }
+
+    /// Call a host function on behalf of an instance
+    ///
+    /// Behaves like [`Self::call_host_function`], but results of pure
+    /// functions memoized with [`MemoScope::Instance`] are only reused for
+    /// calls from the same instance, and host objects the function stores are
+    /// owned by the instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host function is not found or fails during
+    /// execution
+    #[cfg(feature = "std")]
+    pub fn call_host_function_for_instance(
+        &self,
+        engine: &mut dyn Any,
+        instance: usize,
+        module_name: &str,
+        function_name: &str,
+        args: ValueVec,
+    ) -> Result<ValueVec> {
+        self.host_objects.enter_caller(instance);
+        self.instance_contexts.enter_caller(instance);
+        let results =
+            self.call_host_function_scoped(engine, Some(instance), module_name, function_name, args);
+        self.instance_contexts.exit_caller();
+        self.host_objects.exit_caller();
+        results
+    }
+
+    /// Call a host function through the interceptor, if any
+    fn call_host_function_scoped(
+        &self,
+        engine: &mut dyn Any,
+        instance: Option<usize>,
+        module_name: &str,
+        function_name: &str,
+        args: ValueVec,
     ) -> Result<ValueVec> {
         // If we have an interceptor, use it to intercept the call
+        // The key is built once per call in a reused buffer, and the
+        // arguments are moved through the interceptor rather than copied
         #[cfg(feature = "std")]
-        {
+        return with_function_key(module_name, function_name, |key| {
             if let Some(interceptor) = self.get_interceptor() {
-                return interceptor.intercept_call(
-                    "host",
-                    &function_key(module_name, function_name),
-                    &args,
-                    |modified_args| {
-                        self.call_host_function_internal(
-                            engine,
-                            module_name,
-                            function_name,
-                            modified_args,
-                        )
-                    },
-                );
+                return interceptor.intercept_call_owned("host", key, args, |modified_args| {
+                    self.call_host_function_memoized(
+                        engine,
+                        instance,
+                        key,
+                        module_name,
+                        function_name,
+                        modified_args,
+                    )
+                });
             }
+
+            self.call_host_function_memoized(
+                engine,
+                instance,
+                key,
+                module_name,
+                function_name,
+                args,
+            )
+        });
+
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = instance;
+            self.call_host_function_internal(engine, module_name, function_name, args)
         }
+    }
 
-        self.call_host_function_internal(engine, module_name, function_name, args)
+    /// Call a host function, reusing memoized results of pure functions
+    #[cfg(feature = "std")]
+    fn call_host_function_memoized(
+        &self,
+        engine: &mut dyn Any,
+        instance: Option<usize>,
+        key: &str,
+        module_name: &str,
+        function_name: &str,
+        args: ValueVec,
+    ) -> Result<ValueVec> {
+        let Some(scope) = self.pure_functions.get(key).copied() else {
+            return self.call_host_function_internal(engine, module_name, function_name, args);
+        };
+
+        if let Some(results) = self.memo().lookup(key, instance, &args) {
+            return Ok(results);
+        }
+
+        // The lock is not held across the call, so a host function may call
+        // back into the registry
+        let results =
+            self.call_host_function_internal(engine, module_name, function_name, args.clone())?;
+        self.memo().insert(key, instance, scope, args, results.clone());
+        Ok(results)
     }
 
     /// Internal implementation of call_host_function without interception
@@ -619,6 +901,15 @@ fn clone(&self) -> Self {
                     );
                 }
             }
+
+            // Share the memoization cache so results are reused across clones
+            new_registry.pure_functions = self.pure_functions.clone();
+            new_registry.memo = Arc::clone(&self.memo);
+
+            // Share host objects so references stay valid across clones
+            new_registry.host_objects = Arc::clone(&self.host_objects);
+            new_registry.instance_contexts = Arc::clone(&self.instance_contexts);
+            new_registry.contracts = Arc::clone(&self.contracts);
         }
 
         #[cfg(not(feature = "std"))]
@@ -633,13 +924,8 @@ fn clone(&self) -> Self {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    #[cfg(not(feature = "std"))]
-    extern crate alloc;
-    #[cfg(not(feature = "std"))]
-    use alloc::vec;
-
     use wrt_foundation::{
         builtin::BuiltinType,
         values::Value,
@@ -678,6 +964,108 @@ fn test_callback_registry() {
         assert!(err.is_err());
     }
 
+    #[test]
+    fn test_pure_host_function_memoization() {
+        use std::sync::atomic::{
+            AtomicU32,
+            Ordering,
+        };
+
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        let mut registry = CallbackRegistry::new();
+        let handler = HostFunctionHandler::new(|_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![Value::I32(7)])
+        });
+        registry.register_pure_host_function("env", "config", handler, MemoScope::Call);
+        let shared = registry.clone();
+
+        let mut engine = ();
+        for _ in 0..3 {
+            let result = shared.call_host_function_for_instance(
+                &mut engine,
+                0,
+                "env",
+                "config",
+                vec![Value::I32(1)],
+            );
+            assert!(matches!(result.unwrap().as_slice(), [Value::I32(7)]));
+        }
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(registry.memo_stats().hits, 2);
+
+        // Different arguments and a new call both reach the host again
+        registry
+            .call_host_function_for_instance(&mut engine, 0, "env", "config", vec![Value::I32(2)])
+            .unwrap();
+        registry.end_memo_call();
+        registry
+            .call_host_function_for_instance(&mut engine, 0, "env", "config", vec![Value::I32(1)])
+            .unwrap();
+        assert_eq!(CALLS.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_function_key_buffer_is_reused() {
+        let outer = with_function_key("env", "outer", |key| {
+            assert_eq!(key, function_key("env", "outer"));
+            // A nested call builds its key without clobbering the outer one
+            with_function_key("wasi", "inner", |inner| assert_eq!(inner, "wasi::inner"));
+            key.to_string()
+        });
+        assert_eq!(outer, "env::outer");
+
+        let first = with_function_key("a", "b", |key| key.as_ptr());
+        let second = with_function_key("c", "d", |key| key.as_ptr());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_host_objects_owned_by_calling_instance() {
+        let mut registry = CallbackRegistry::new();
+        let objects = Arc::clone(registry.host_objects());
+        let handler = HostFunctionHandler::new(move |_| {
+            Ok(vec![objects.insert_value(String::from("handle"))?])
+        });
+        registry.register_host_function("env", "open", handler);
+        let shared = registry.clone();
+
+        let mut engine = ();
+        let results =
+            shared.call_host_function_for_instance(&mut engine, 3, "env", "open", vec![]).unwrap();
+        let object = registry.host_objects().downcast_value::<String>(&results[0]).unwrap();
+        assert_eq!(object.unwrap().as_str(), "handle");
+
+        assert_eq!(registry.release_host_objects(2), 0);
+        assert_eq!(shared.release_host_objects(3), 1);
+        assert!(registry.host_objects().is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_checked_host_function_reports_violations() {
+        use wrt_foundation::{
+            CleanFuncType,
+            CleanValType,
+        };
+
+        let mut registry = CallbackRegistry::new().with_contract_action(ContractAction::Report);
+        let ty = CleanExternType::Function(CleanFuncType {
+            params:  vec![],
+            results: vec![CleanValType::U16],
+        });
+        let handler = HostFunctionHandler::new(|_| Ok(vec![Value::I32(70_000)]));
+        registry.register_checked_host_function("env", "port", handler, &ty).unwrap();
+
+        let mut engine = ();
+        let results = registry.clone().call_host_function(&mut engine, "env", "port", vec![]);
+        assert_eq!(results.unwrap(), [Value::I32(70_000)]);
+        let violations = registry.contract_report().take_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].function, "env::port");
+    }
+
     #[test]
     fn test_callback_registry_callback() {
         let mut registry = CallbackRegistry::new();
@@ -701,7 +1089,6 @@ fn test_callback_registry_callback() {
         assert_eq!(*callback.unwrap(), 24);
     }
 
-    #[cfg(feature = "std")]
     #[test]
     fn test_call_builtin_function() {
         // Create a registry with a host function for resource.create
@@ -775,6 +1162,33 @@ pub fn function_key(module_name: &str, function_name: &str) -> String {
     return alloc::format!("{}::{}", module_name, function_name);
 }
 
+#[cfg(feature = "std")]
+thread_local! {
+    /// Buffer reused to build function keys on the call path
+    static KEY_BUFFER: core::cell::Cell<String> = const { core::cell::Cell::new(String::new()) };
+}
+
+/// Run `f` with the [`function_key`] of a function, built in a reused
+/// thread-local buffer
+///
+/// The buffer is taken for the duration of `f`, so a host function calling
+/// back into the registry builds its key in a buffer of its own.
+#[cfg(feature = "std")]
+fn with_function_key<R>(
+    module_name: &str,
+    function_name: &str,
+    f: impl FnOnce(&str) -> R,
+) -> R {
+    let mut key = KEY_BUFFER.with(core::cell::Cell::take);
+    key.clear();
+    key.push_str(module_name);
+    key.push_str("::");
+    key.push_str(function_name);
+    let result = f(&key);
+    KEY_BUFFER.with(|buffer| buffer.set(key));
+    result
+}
+
 /// Generate a unique function key from module and function names (`no_std`
 /// version)
 ///
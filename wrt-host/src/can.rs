@@ -0,0 +1,623 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! CAN bus host capability (`wrt:can`).
+//!
+//! A [`CanHub`] owns one platform [`CanBus`] driver, such as
+//! `wrt_platform::SocketCan` on Linux or a board support package's
+//! controller driver on an MCU, and shares it between components. Each
+//! component gets a [`CanCapability`] whose [`CanPolicy`] fixes the
+//! identifiers it may send and listen to and caps its transmit rate.
+//!
+//! The host functions are registered in [`CAN_MODULE`] and take lifted
+//! component values:
+//!
+//! ```text
+//! send: func(id: u32, extended: bool, data: list<u8>)
+//! receive: func() -> option<frame>  // record frame { id, extended, remote, data }
+//! add-filter: func(id: u32, mask: u32, extended: bool) -> u32
+//! remove-filter: func(handle: u32)
+//! ```
+//!
+//! Received frames are dispatched to every component with a matching filter.
+//! Each component has a bounded receive queue; when it is full the oldest
+//! frame is dropped and counted as an overrun. Whether frames one component
+//! sends reach the others depends on the driver: [`StubCanBus`] loops every
+//! frame back, while a SocketCAN socket does not receive its own frames.
+//!
+//! [`StubCanBus`]: wrt_platform::can::StubCanBus
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        Mutex,
+        MutexGuard,
+        Weak,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use wrt_platform::can::{
+    CanBus,
+    CanFilter,
+    CanFrame,
+};
+
+use crate::{
+    callback::CallbackRegistry,
+    prelude::{
+        codes,
+        vec,
+        Arc,
+        Error,
+        ErrorCategory,
+        HostFunctionHandler,
+        Result,
+        ToString,
+        Value,
+        Vec,
+    },
+};
+
+/// Module the CAN host functions are registered in
+pub const CAN_MODULE: &str = "wrt:can/bus";
+
+/// Transmit rate cap: at most `frames` per `period`, with bursts of up to
+/// `frames` after an idle period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransmitRate {
+    /// Frames allowed per period
+    pub frames: u32,
+    /// Length of the period
+    pub period: Duration,
+}
+
+/// Identifiers and limits a component is granted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanPolicy {
+    /// Identifiers the component may send
+    transmit:    Vec<CanFilter>,
+    /// Identifiers the component may register receive filters for
+    receive:     Vec<CanFilter>,
+    /// Transmit rate cap, if any
+    rate:        Option<TransmitRate>,
+    /// Receive filters the component may hold at once
+    max_filters: usize,
+    /// Frames buffered for the component before the oldest are dropped
+    queue_depth: usize,
+}
+
+impl Default for CanPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CanPolicy {
+    /// Policy granting no identifiers, no rate cap, 8 filters and a 64
+    /// frame receive queue
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            transmit:    Vec::new(),
+            receive:     Vec::new(),
+            rate:        None,
+            max_filters: 8,
+            queue_depth: 64,
+        }
+    }
+
+    /// Allow sending the identifiers accepted by `filter`
+    #[must_use]
+    pub fn allow_transmit(mut self, filter: CanFilter) -> Self {
+        self.transmit.push(filter);
+        self
+    }
+
+    /// Allow receive filters covered by `filter`
+    #[must_use]
+    pub fn allow_receive(mut self, filter: CanFilter) -> Self {
+        self.receive.push(filter);
+        self
+    }
+
+    /// Cap transmissions at `frames` per `period`
+    #[must_use]
+    pub fn with_transmit_rate(mut self, frames: u32, period: Duration) -> Self {
+        self.rate = Some(TransmitRate { frames, period });
+        self
+    }
+
+    /// Allow up to `max_filters` receive filters at once
+    #[must_use]
+    pub fn with_max_filters(mut self, max_filters: usize) -> Self {
+        self.max_filters = max_filters;
+        self
+    }
+
+    /// Buffer up to `queue_depth` received frames
+    #[must_use]
+    pub fn with_queue_depth(mut self, queue_depth: usize) -> Self {
+        self.queue_depth = queue_depth;
+        self
+    }
+
+    /// Whether the component may send `id`
+    #[must_use]
+    pub fn may_transmit(&self, id: u32, extended: bool) -> bool {
+        self.transmit.iter().any(|allowed| allowed.matches_id(id, extended))
+    }
+
+    /// Whether the component may register `filter`
+    #[must_use]
+    pub fn may_receive(&self, filter: &CanFilter) -> bool {
+        self.receive.iter().any(|allowed| allowed.covers(filter))
+    }
+}
+
+/// Token bucket enforcing a [`TransmitRate`]
+#[derive(Debug)]
+struct RateLimiter {
+    /// Granted rate
+    rate:        TransmitRate,
+    /// Accumulated transmit time, capped at one period
+    credit:      Duration,
+    /// When `credit` was last topped up
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: TransmitRate) -> Self {
+        Self {
+            rate,
+            credit: rate.period,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Credit one frame costs
+    fn cost(&self) -> Duration {
+        self.rate.period.checked_div(self.rate.frames).unwrap_or(Duration::MAX)
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        self.credit = (self.credit + (now - self.last_refill)).min(self.rate.period);
+        self.last_refill = now;
+        match self.credit.checked_sub(self.cost()) {
+            Some(credit) => {
+                self.credit = credit;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn refund(&mut self) {
+        self.credit = (self.credit + self.cost()).min(self.rate.period);
+    }
+}
+
+/// Per-component state on a hub
+#[derive(Debug)]
+struct Endpoint {
+    /// Granted identifiers and limits
+    policy:      Arc<CanPolicy>,
+    /// Registered receive filters by handle
+    filters:     Vec<(u32, CanFilter)>,
+    /// Handle of the next filter
+    next_handle: u32,
+    /// Frames received for the component
+    queue:       VecDeque<CanFrame>,
+    /// Frames dropped because the queue was full
+    overruns:    u64,
+    /// Transmit rate cap
+    limiter:     Option<RateLimiter>,
+}
+
+impl Endpoint {
+    fn deliver(&mut self, frame: &CanFrame) {
+        if !self.filters.iter().any(|(_, filter)| filter.matches(frame)) {
+            return;
+        }
+        if self.queue.len() >= self.policy.queue_depth {
+            self.queue.pop_front();
+            self.overruns += 1;
+        }
+        if self.policy.queue_depth > 0 {
+            self.queue.push_back(*frame);
+        }
+    }
+}
+
+/// Driver and attached components of a hub
+struct HubState {
+    /// Platform CAN driver
+    driver:    Box<dyn CanBus>,
+    /// Components attached to the bus; dropped ones are pruned lazily
+    endpoints: Vec<Weak<Mutex<Endpoint>>>,
+}
+
+impl HubState {
+    /// Move every pending frame from the driver into the matching queues
+    fn pump(&mut self) -> Result<()> {
+        while let Some(frame) = self.driver.receive()? {
+            self.endpoints.retain(|endpoint| endpoint.strong_count() > 0);
+            for endpoint in self.endpoints.iter().filter_map(Weak::upgrade) {
+                lock(&endpoint).deliver(&frame);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One CAN bus shared by the components attached to it
+#[derive(Clone)]
+pub struct CanHub {
+    /// Driver and attached components
+    state: Arc<Mutex<HubState>>,
+}
+
+impl CanHub {
+    /// Share `driver` between components
+    pub fn new(driver: impl CanBus + 'static) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(HubState {
+                driver:    Box::new(driver),
+                endpoints: Vec::new(),
+            })),
+        }
+    }
+
+    /// Number of components attached to the bus
+    pub fn attached(&self) -> usize {
+        lock(&self.state).endpoints.iter().filter(|e| e.strong_count() > 0).count()
+    }
+}
+
+impl core::fmt::Debug for CanHub {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CanHub")
+            .field("attached", &self.attached())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Host capability granting a component scoped use of a CAN bus
+#[derive(Debug)]
+pub struct CanCapability {
+    /// Bus the component is attached to
+    hub:      CanHub,
+    /// Filters, receive queue and rate limiter of the component
+    endpoint: Arc<Mutex<Endpoint>>,
+}
+
+impl CanCapability {
+    /// Attach a component to `hub` within `policy`
+    pub fn new(hub: &CanHub, policy: CanPolicy) -> Self {
+        let endpoint = Arc::new(Mutex::new(Endpoint {
+            limiter:     policy.rate.map(RateLimiter::new),
+            policy:      Arc::new(policy),
+            filters:     Vec::new(),
+            next_handle: 1,
+            queue:       VecDeque::new(),
+            overruns:    0,
+        }));
+        lock(&hub.state).endpoints.push(Arc::downgrade(&endpoint));
+        Self {
+            hub: hub.clone(),
+            endpoint,
+        }
+    }
+
+    /// Granted identifiers and limits
+    pub fn policy(&self) -> Arc<CanPolicy> {
+        Arc::clone(&lock(&self.endpoint).policy)
+    }
+
+    /// Receive filters the component has registered
+    pub fn filters(&self) -> Vec<CanFilter> {
+        lock(&self.endpoint).filters.iter().map(|(_, filter)| *filter).collect()
+    }
+
+    /// Frames dropped because the component did not drain its queue
+    pub fn overruns(&self) -> u64 {
+        lock(&self.endpoint).overruns
+    }
+
+    /// Register the host functions with `registry`, returning their number
+    pub fn install(&self, registry: &mut CallbackRegistry) -> usize {
+        let (hub, endpoint) = (self.hub.clone(), Arc::clone(&self.endpoint));
+        registry.register_host_function(
+            CAN_MODULE,
+            "send",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let (id, extended, data) = match args.as_slice() {
+                    [Value::U32(id), Value::Bool(extended), Value::List(data)] => {
+                        (*id, *extended, data)
+                    },
+                    _ => return Err(type_mismatch("CAN send called with the wrong arguments")),
+                };
+                let data = data
+                    .iter()
+                    .map(|byte| match byte {
+                        Value::U8(byte) => Ok(*byte),
+                        _ => Err(type_mismatch("CAN data must be a list of u8")),
+                    })
+                    .collect::<Result<Vec<u8>>>()?;
+                let frame = CanFrame::new(id, extended, &data)?;
+
+                {
+                    let mut endpoint = lock(&endpoint);
+                    if !endpoint.policy.may_transmit(id, extended) {
+                        return Err(access_denied("CAN identifier not granted for sending"));
+                    }
+                    if let Some(limiter) = endpoint.limiter.as_mut() {
+                        if !limiter.try_acquire() {
+                            return Err(Error::new(
+                                ErrorCategory::Resource,
+                                codes::RESOURCE_LIMIT_EXCEEDED,
+                                "CAN transmit rate limit exceeded",
+                            ));
+                        }
+                    }
+                }
+
+                // The endpoint lock is not held across the driver call; the
+                // hub lock is always taken first when both are needed.
+                let sent = lock(&hub.state).driver.transmit(&frame);
+                if sent.is_err() {
+                    if let Some(limiter) = lock(&endpoint).limiter.as_mut() {
+                        limiter.refund();
+                    }
+                }
+                sent?;
+                Ok(Vec::new())
+            }),
+        );
+
+        let (hub, endpoint) = (self.hub.clone(), Arc::clone(&self.endpoint));
+        registry.register_host_function(
+            CAN_MODULE,
+            "receive",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                expect_args(&args, 0)?;
+                lock(&hub.state).pump()?;
+                let frame = lock(&endpoint).queue.pop_front().map(|frame| {
+                    Box::new(Value::Record(vec![
+                        ("id".to_string(), Value::U32(frame.id())),
+                        ("extended".to_string(), Value::Bool(frame.is_extended())),
+                        ("remote".to_string(), Value::Bool(frame.is_remote())),
+                        (
+                            "data".to_string(),
+                            Value::List(frame.data().iter().copied().map(Value::U8).collect()),
+                        ),
+                    ]))
+                });
+                Ok(vec![Value::Option(frame)])
+            }),
+        );
+
+        let endpoint = Arc::clone(&self.endpoint);
+        registry.register_host_function(
+            CAN_MODULE,
+            "add-filter",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let filter = match args.as_slice() {
+                    [Value::U32(id), Value::U32(mask), Value::Bool(extended)] => {
+                        CanFilter::new(*id, *mask, *extended)?
+                    },
+                    _ => {
+                        return Err(type_mismatch(
+                            "CAN add-filter called with the wrong arguments",
+                        ));
+                    },
+                };
+                let mut endpoint = lock(&endpoint);
+                if !endpoint.policy.may_receive(&filter) {
+                    return Err(access_denied("CAN filter not granted for receiving"));
+                }
+                if endpoint.filters.len() >= endpoint.policy.max_filters {
+                    return Err(Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_LIMIT_EXCEEDED,
+                        "CAN filter limit reached",
+                    ));
+                }
+                let handle = endpoint.next_handle;
+                endpoint.next_handle = endpoint.next_handle.wrapping_add(1).max(1);
+                endpoint.filters.push((handle, filter));
+                Ok(vec![Value::U32(handle)])
+            }),
+        );
+
+        let endpoint = Arc::clone(&self.endpoint);
+        registry.register_host_function(
+            CAN_MODULE,
+            "remove-filter",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let [Value::U32(handle)] = args.as_slice() else {
+                    return Err(type_mismatch(
+                        "CAN remove-filter called with the wrong arguments",
+                    ));
+                };
+                let mut endpoint = lock(&endpoint);
+                let index =
+                    endpoint.filters.iter().position(|(h, _)| h == handle).ok_or_else(|| {
+                        Error::new(
+                            ErrorCategory::Resource,
+                            codes::RESOURCE_NOT_FOUND,
+                            "Unknown CAN filter handle",
+                        )
+                    })?;
+                endpoint.filters.remove(index);
+                Ok(Vec::new())
+            }),
+        );
+
+        4
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn expect_args(args: &[Value], count: usize) -> Result<()> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(type_mismatch(
+            "CAN function called with the wrong arguments",
+        ))
+    }
+}
+
+fn type_mismatch(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Type, codes::TYPE_MISMATCH, message)
+}
+
+fn access_denied(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Security, codes::ACCESS_DENIED, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_platform::can::StubCanBus;
+
+    use super::*;
+
+    fn call(
+        registry: &mut CallbackRegistry,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        registry.call_host_function(&mut (), CAN_MODULE, function, args)
+    }
+
+    fn send_args(id: u32, data: &[u8]) -> Vec<Value> {
+        vec![
+            Value::U32(id),
+            Value::Bool(false),
+            Value::List(data.iter().copied().map(Value::U8).collect()),
+        ]
+    }
+
+    fn received_id(result: &[Value]) -> Option<u32> {
+        match result {
+            [Value::Option(Some(record))] => match record.as_ref() {
+                Value::Record(fields) => match fields.first() {
+                    Some((_, Value::U32(id))) => Some(*id),
+                    _ => panic!("frame record without an id"),
+                },
+                _ => panic!("frame is not a record"),
+            },
+            [Value::Option(None)] => None,
+            _ => panic!("receive returned {result:?}"),
+        }
+    }
+
+    fn filter(id: u32, mask: u32) -> CanFilter {
+        CanFilter::new(id, mask, false).unwrap()
+    }
+
+    #[test]
+    fn test_send_respects_granted_identifiers() {
+        let hub = CanHub::new(StubCanBus::<16>::new());
+        let capability =
+            CanCapability::new(&hub, CanPolicy::new().allow_transmit(filter(0x100, 0x700)));
+        let mut registry = CallbackRegistry::new();
+        assert_eq!(capability.install(&mut registry), 4);
+
+        assert!(call(&mut registry, "send", send_args(0x123, &[1, 2])).is_ok());
+        let denied = call(&mut registry, "send", send_args(0x200, &[1])).unwrap_err();
+        assert_eq!(denied.code, codes::ACCESS_DENIED);
+        assert!(call(&mut registry, "send", send_args(0x123, &[0; 9])).is_err());
+        assert!(call(&mut registry, "send", vec![Value::U32(0x123)]).is_err());
+    }
+
+    #[test]
+    fn test_frames_are_dispatched_by_component_filters() {
+        let hub = CanHub::new(StubCanBus::<16>::new());
+        let sender =
+            CanCapability::new(&hub, CanPolicy::new().allow_transmit(CanFilter::any(false)));
+        let engine = CanCapability::new(
+            &hub,
+            CanPolicy::new().allow_receive(filter(0x100, 0x700)).with_queue_depth(2),
+        );
+        let body = CanCapability::new(&hub, CanPolicy::new().allow_receive(filter(0x200, 0x700)));
+        assert_eq!(hub.attached(), 3);
+
+        let mut sender_fns = CallbackRegistry::new();
+        let mut engine_fns = CallbackRegistry::new();
+        let mut body_fns = CallbackRegistry::new();
+        sender.install(&mut sender_fns);
+        engine.install(&mut engine_fns);
+        body.install(&mut body_fns);
+
+        // Filters must stay within the granted range
+        let args = vec![Value::U32(0), Value::U32(0), Value::Bool(false)];
+        assert!(call(&mut engine_fns, "add-filter", args).is_err());
+        let args = vec![Value::U32(0x120), Value::U32(0x7F0), Value::Bool(false)];
+        let handle = call(&mut engine_fns, "add-filter", args).unwrap();
+        let args = vec![Value::U32(0x200), Value::U32(0x700), Value::Bool(false)];
+        call(&mut body_fns, "add-filter", args).unwrap();
+
+        for id in [0x121, 0x122, 0x123, 0x130, 0x2AA] {
+            call(&mut sender_fns, "send", send_args(id, &[0xFF])).unwrap();
+        }
+
+        // The engine queue holds two frames, so the oldest match overran
+        assert_eq!(
+            received_id(&call(&mut engine_fns, "receive", vec![]).unwrap()),
+            Some(0x122)
+        );
+        assert_eq!(
+            received_id(&call(&mut engine_fns, "receive", vec![]).unwrap()),
+            Some(0x123)
+        );
+        assert_eq!(
+            received_id(&call(&mut engine_fns, "receive", vec![]).unwrap()),
+            None
+        );
+        assert_eq!(engine.overruns(), 1);
+        assert_eq!(
+            received_id(&call(&mut body_fns, "receive", vec![]).unwrap()),
+            Some(0x2AA)
+        );
+
+        call(&mut engine_fns, "remove-filter", handle).unwrap();
+        assert!(engine.filters().is_empty());
+        call(&mut sender_fns, "send", send_args(0x121, &[])).unwrap();
+        assert_eq!(
+            received_id(&call(&mut engine_fns, "receive", vec![]).unwrap()),
+            None
+        );
+
+        drop((engine, engine_fns));
+        assert_eq!(hub.attached(), 2);
+    }
+
+    #[test]
+    fn test_transmit_rate_is_capped() {
+        let hub = CanHub::new(StubCanBus::<16>::new());
+        let policy = CanPolicy::new()
+            .allow_transmit(CanFilter::any(false))
+            .with_transmit_rate(2, Duration::from_millis(50));
+        let capability = CanCapability::new(&hub, policy);
+        let mut registry = CallbackRegistry::new();
+        capability.install(&mut registry);
+
+        call(&mut registry, "send", send_args(0x10, &[])).unwrap();
+        call(&mut registry, "send", send_args(0x10, &[])).unwrap();
+        let limited = call(&mut registry, "send", send_args(0x10, &[])).unwrap_err();
+        assert_eq!(limited.code, codes::RESOURCE_LIMIT_EXCEEDED);
+
+        std::thread::sleep(Duration::from_millis(30));
+        call(&mut registry, "send", send_args(0x10, &[])).unwrap();
+    }
+}
@@ -0,0 +1,503 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Contract checking of host function results in debug builds.
+//!
+//! A host function that returns a value its declared type does not allow,
+//! such as `300` for a `u8` or an enum case that does not exist, only
+//! surfaces later as a confusing guest trap. A [`HostContract`] holds the
+//! declared [`ExternType`] of a host function and checks every result
+//! against it: integer ranges, chars, enum and variant cases, flag labels,
+//! record fields, tuple arities and list lengths.
+//!
+//! Checked handlers only check in debug builds. In release builds
+//! [`HostContract::wrap`] returns the handler unchanged, so contracts cost
+//! nothing in production. Violations either panic at the host function or
+//! are recorded in a [`ContractReport`] while the call goes on, depending on
+//! its [`ContractAction`].
+
+use core::fmt;
+use std::sync::MutexGuard;
+
+use wrt_foundation::clean_types::{
+    ExternType,
+    FuncType,
+    ValType,
+};
+
+use crate::prelude::{
+    codes,
+    format,
+    Arc,
+    Error,
+    ErrorCategory,
+    HostFunctionHandler,
+    Mutex,
+    Result,
+    String,
+    ToString,
+    Value,
+    Vec,
+};
+
+/// Default number of violations a [`ContractReport`] keeps
+pub const MAX_REPORTED_VIOLATIONS: usize = 256;
+
+/// What happens when a host function breaks its contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContractAction {
+    /// Panic in the host function, pointing at the bug
+    #[default]
+    Panic,
+    /// Record the violation and return the results unchanged
+    Report,
+}
+
+/// A result that does not match the declared type of its host function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractViolation {
+    /// `module::function` key of the host function
+    pub function: String,
+    /// Location of the value, e.g. `result 0.items[2]`
+    pub path:     String,
+    /// What is wrong with the value
+    pub message:  String,
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "host function {} returned {}: {}", self.function, self.path, self.message)
+    }
+}
+
+/// Violations recorded by checked host functions
+#[derive(Debug)]
+pub struct ContractReport {
+    /// What checked host functions do on a violation
+    action:     ContractAction,
+    /// Recorded violations, oldest first
+    violations: Mutex<Vec<ContractViolation>>,
+    /// Maximum number of recorded violations
+    capacity:   usize,
+}
+
+impl ContractReport {
+    /// Create a report for `action` keeping up to
+    /// [`MAX_REPORTED_VIOLATIONS`] violations
+    #[must_use]
+    pub fn new(action: ContractAction) -> Self {
+        Self { action, violations: Mutex::new(Vec::new()), capacity: MAX_REPORTED_VIOLATIONS }
+    }
+
+    /// What checked host functions do on a violation
+    #[must_use]
+    pub fn action(&self) -> ContractAction {
+        self.action
+    }
+
+    /// Record `violations`, or panic if the action is
+    /// [`ContractAction::Panic`]
+    ///
+    /// Violations beyond the capacity of the report are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the first violation if the action is
+    /// [`ContractAction::Panic`].
+    pub fn record(&self, violations: Vec<ContractViolation>) {
+        let Some(first) = violations.first() else {
+            return;
+        };
+        if self.action == ContractAction::Panic {
+            panic!("{first}");
+        }
+        let mut recorded = self.lock();
+        let room = self.capacity.saturating_sub(recorded.len());
+        recorded.extend(violations.into_iter().take(room));
+    }
+
+    /// Violations recorded so far
+    #[must_use]
+    pub fn violations(&self) -> Vec<ContractViolation> {
+        self.lock().clone()
+    }
+
+    /// Remove and return the violations recorded so far
+    pub fn take_violations(&self) -> Vec<ContractViolation> {
+        core::mem::take(&mut *self.lock())
+    }
+
+    /// Lock the recorded violations, recovering a poisoned lock
+    fn lock(&self) -> MutexGuard<'_, Vec<ContractViolation>> {
+        self.violations.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for ContractReport {
+    fn default() -> Self {
+        Self::new(ContractAction::default())
+    }
+}
+
+/// Declared type of a host function its results are checked against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostContract {
+    /// `module::function` key of the host function
+    function:     String,
+    /// Declared type
+    ty:           FuncType,
+    /// Longest list a result may contain
+    max_list_len: usize,
+}
+
+impl HostContract {
+    /// Create the contract of `module::function` declared as `ty`
+    ///
+    /// # Errors
+    ///
+    /// Returns a type error if `ty` is not a function type
+    pub fn new(module: &str, function: &str, ty: &ExternType) -> Result<Self> {
+        let ExternType::Function(ty) = ty else {
+            return Err(Error::new(
+                ErrorCategory::Type,
+                codes::TYPE_MISMATCH,
+                "Host function contract requires a function type",
+            ));
+        };
+        Ok(Self {
+            function:     format!("{module}::{function}"),
+            ty:           ty.clone(),
+            max_list_len: u32::MAX as usize,
+        })
+    }
+
+    /// Reject lists longer than `max_list_len` elements
+    ///
+    /// By default lists may be as long as the canonical ABI allows.
+    #[must_use]
+    pub fn with_max_list_len(mut self, max_list_len: usize) -> Self {
+        self.max_list_len = max_list_len;
+        self
+    }
+
+    /// `module::function` key of the host function
+    #[must_use]
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+
+    /// Check `results` against the declared result types
+    #[must_use]
+    pub fn check(&self, results: &[Value]) -> Vec<ContractViolation> {
+        let mut violations = Vec::new();
+        if results.len() != self.ty.results.len() {
+            violations.push(self.violation(
+                "results",
+                format!("{} values, expected {}", results.len(), self.ty.results.len()),
+            ));
+            return violations;
+        }
+        for (index, (value, ty)) in results.iter().zip(&self.ty.results).enumerate() {
+            self.check_value(value, ty, &format!("result {index}"), &mut violations);
+        }
+        violations
+    }
+
+    /// Wrap `handler` so its results are checked in debug builds
+    ///
+    /// Errors returned by the handler are passed through unchecked. In
+    /// release builds the handler is returned unchanged.
+    #[must_use]
+    pub fn wrap(
+        self,
+        handler: HostFunctionHandler,
+        report: Arc<ContractReport>,
+    ) -> HostFunctionHandler {
+        if !cfg!(debug_assertions) {
+            return handler;
+        }
+        let contract = Arc::new(self);
+        HostFunctionHandler::new_with_args(move |target, args| {
+            let results = handler.call(target, args)?;
+            report.record(contract.check(&results));
+            Ok(results)
+        })
+    }
+
+    /// Check one value, appending violations found at `path` and below
+    fn check_value(
+        &self,
+        value: &Value,
+        ty: &ValType,
+        path: &str,
+        violations: &mut Vec<ContractViolation>,
+    ) {
+        match (ty, value) {
+            (ValType::Bool, Value::Bool(_))
+            | (ValType::S8, Value::S8(_))
+            | (ValType::U8, Value::U8(_))
+            | (ValType::S16, Value::S16(_))
+            | (ValType::U16, Value::U16(_))
+            | (ValType::S32, Value::S32(_) | Value::I32(_))
+            | (ValType::U32, Value::U32(_) | Value::I32(_))
+            | (ValType::S64, Value::S64(_) | Value::I64(_))
+            | (ValType::U64, Value::U64(_) | Value::I64(_))
+            | (ValType::F32, Value::F32(_))
+            | (ValType::F64, Value::F64(_))
+            | (ValType::Char, Value::Char(_))
+            | (ValType::String, Value::String(_))
+            | (ValType::Own(_), Value::Own(_) | Value::I32(_))
+            | (ValType::Borrow(_), Value::Borrow(_) | Value::I32(_))
+            | (ValType::Stream(_), Value::Stream(_))
+            | (ValType::Future(_), Value::Future(_)) => {},
+
+            // Core values lowered from smaller types must fit them
+            (ValType::Bool, Value::I32(raw)) => {
+                self.check_range(*raw, 0, 1, "bool", path, violations);
+            },
+            (ValType::S8, Value::I32(raw)) => {
+                self.check_range(*raw, i8::MIN.into(), i8::MAX.into(), "s8", path, violations);
+            },
+            (ValType::U8, Value::I32(raw)) => {
+                self.check_range(*raw, 0, u8::MAX.into(), "u8", path, violations);
+            },
+            (ValType::S16, Value::I32(raw)) => {
+                self.check_range(*raw, i16::MIN.into(), i16::MAX.into(), "s16", path, violations);
+            },
+            (ValType::U16, Value::I32(raw)) => {
+                self.check_range(*raw, 0, u16::MAX.into(), "u16", path, violations);
+            },
+            (ValType::Char, Value::I32(raw)) => {
+                if char::from_u32(*raw as u32).is_none() {
+                    violations.push(
+                        self.violation(path, format!("{raw:#x} is not a Unicode scalar value")),
+                    );
+                }
+            },
+
+            (ValType::List(element), Value::List(items)) => {
+                if items.len() > self.max_list_len {
+                    violations.push(self.violation(
+                        path,
+                        format!("list of {} elements, at most {}", items.len(), self.max_list_len),
+                    ));
+                }
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(item, element, &format!("{path}[{index}]"), violations);
+                }
+            },
+            (ValType::Record(record), Value::Record(fields)) => {
+                if fields.len() != record.fields.len() {
+                    violations.push(self.violation(
+                        path,
+                        format!(
+                            "record of {} fields, expected {}",
+                            fields.len(),
+                            record.fields.len()
+                        ),
+                    ));
+                    return;
+                }
+                for ((name, field), declared) in fields.iter().zip(&record.fields) {
+                    if *name != declared.name {
+                        violations.push(self.violation(
+                            path,
+                            format!("field `{name}` where `{}` is declared", declared.name),
+                        ));
+                        continue;
+                    }
+                    self.check_value(field, &declared.ty, &format!("{path}.{name}"), violations);
+                }
+            },
+            (ValType::Tuple(tuple), Value::Tuple(items)) => {
+                if items.len() != tuple.types.len() {
+                    violations.push(self.violation(
+                        path,
+                        format!("tuple of {} values, expected {}", items.len(), tuple.types.len()),
+                    ));
+                    return;
+                }
+                for (index, (item, ty)) in items.iter().zip(&tuple.types).enumerate() {
+                    self.check_value(item, ty, &format!("{path}.{index}"), violations);
+                }
+            },
+            (ValType::Variant(variant), Value::Variant(name, payload)) => {
+                let Some(case) = variant.cases.iter().find(|case| case.name == *name) else {
+                    violations.push(self.violation(path, format!("unknown variant case `{name}`")));
+                    return;
+                };
+                self.check_payload(
+                    payload.as_deref(),
+                    case.ty.as_ref(),
+                    &format!("{path}.{name}"),
+                    violations,
+                );
+            },
+            (ValType::Enum(cases), Value::Enum(name)) => {
+                if !cases.cases.contains(name) {
+                    violations.push(self.violation(path, format!("unknown enum case `{name}`")));
+                }
+            },
+            (ValType::Enum(cases), Value::I32(raw)) => {
+                if usize::try_from(*raw).map_or(true, |index| index >= cases.cases.len()) {
+                    violations.push(self.violation(
+                        path,
+                        format!("enum discriminant {raw}, expected below {}", cases.cases.len()),
+                    ));
+                }
+            },
+            (ValType::Option(inner), Value::Option(payload)) => {
+                if let Some(payload) = payload {
+                    self.check_value(payload, inner, &format!("{path}.some"), violations);
+                }
+            },
+            (ValType::Result(result), Value::Result(outcome)) => match outcome {
+                Ok(payload) => {
+                    self.check_payload(
+                        Some(payload),
+                        result.ok.as_deref(),
+                        &format!("{path}.ok"),
+                        violations,
+                    );
+                },
+                Err(payload) => {
+                    self.check_payload(
+                        Some(payload),
+                        result.err.as_deref(),
+                        &format!("{path}.err"),
+                        violations,
+                    );
+                },
+            },
+            (ValType::Flags(flags), Value::Flags(set)) => {
+                for (index, label) in set.iter().enumerate() {
+                    if !flags.labels.contains(label) {
+                        violations.push(self.violation(path, format!("unknown flag `{label}`")));
+                    } else if set[..index].contains(label) {
+                        violations.push(self.violation(path, format!("flag `{label}` set twice")));
+                    }
+                }
+            },
+
+            _ => violations.push(self.violation(path, format!("{value:?} is not a {ty:?}"))),
+        }
+    }
+
+    /// Check the payload of a variant case or result against its declared
+    /// type, where [`Value::Void`] stands for no payload
+    fn check_payload(
+        &self,
+        payload: Option<&Value>,
+        ty: Option<&ValType>,
+        path: &str,
+        violations: &mut Vec<ContractViolation>,
+    ) {
+        match (payload.filter(|payload| !matches!(payload, Value::Void)), ty) {
+            (None, None) => {},
+            (Some(payload), Some(ty)) => self.check_value(payload, ty, path, violations),
+            (None, Some(ty)) => {
+                violations.push(self.violation(path, format!("missing payload of type {ty:?}")));
+            },
+            (Some(payload), None) => {
+                violations.push(self.violation(path, format!("unexpected payload {payload:?}")));
+            },
+        }
+    }
+
+    /// Append a violation if `raw` lies outside `min..=max`
+    fn check_range(
+        &self,
+        raw: i32,
+        min: i32,
+        max: i32,
+        name: &str,
+        path: &str,
+        violations: &mut Vec<ContractViolation>,
+    ) {
+        if !(min..=max).contains(&raw) {
+            violations.push(self.violation(path, format!("{raw} is out of range for {name}")));
+        }
+    }
+
+    fn violation(&self, path: &str, message: String) -> ContractViolation {
+        ContractViolation { function: self.function.clone(), path: path.to_string(), message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use wrt_foundation::clean_types::{
+        Enum,
+        Field,
+        Record,
+    };
+
+    use super::*;
+    use crate::prelude::{
+        vec,
+        Box,
+    };
+
+    fn contract(results: Vec<ValType>) -> HostContract {
+        let ty = ExternType::Function(FuncType { params: Vec::new(), results });
+        HostContract::new("env", "get", &ty).unwrap()
+    }
+
+    #[test]
+    fn test_results_are_checked_against_declared_types() {
+        let status = ValType::Enum(Enum { cases: vec!["ok".to_string(), "busy".to_string()] });
+        let entry = ValType::Record(Record {
+            fields: vec![Field { name: "level".to_string(), ty: ValType::U8 }],
+        });
+        let contract = contract(vec![ValType::U8, status, ValType::List(Box::new(entry))])
+            .with_max_list_len(2);
+
+        let good = [
+            Value::I32(255),
+            Value::Enum("busy".to_string()),
+            Value::List(vec![Value::Record(vec![("level".to_string(), Value::U8(3))])]),
+        ];
+        assert!(contract.check(&good).is_empty());
+
+        let bad = [
+            Value::I32(300),
+            Value::I32(2),
+            Value::List(vec![
+                Value::Record(vec![("level".to_string(), Value::I32(-1))]),
+                Value::Record(vec![("level".to_string(), Value::U8(0))]),
+                Value::Record(vec![("level".to_string(), Value::U8(0))]),
+            ]),
+        ];
+        let paths: Vec<_> = contract.check(&bad).into_iter().map(|v| v.path).collect();
+        assert_eq!(paths, ["result 0", "result 1", "result 2", "result 2[0].level"]);
+
+        assert_eq!(contract.check(&[]).len(), 1);
+        assert!(HostContract::new("env", "get", &ExternType::Instance(Default::default())).is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_wrapped_handler_reports_or_panics() {
+        let handler = HostFunctionHandler::new(|_| Ok(vec![Value::I32(256)]));
+        let report = Arc::new(ContractReport::new(ContractAction::Report));
+        let checked = contract(vec![ValType::U8]).wrap(handler.clone(), report.clone());
+
+        let results = checked.call(&mut (), vec![]).unwrap();
+        assert_eq!(results, [Value::I32(256)]);
+        let violations = report.take_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "host function env::get returned result 0: 256 is out of range for u8"
+        );
+
+        let panicking =
+            contract(vec![ValType::U8]).wrap(handler, Arc::new(ContractReport::default()));
+        let outcome =
+            std::panic::catch_unwind(AssertUnwindSafe(|| panicking.call(&mut (), vec![])));
+        assert!(outcome.is_err());
+    }
+}
@@ -0,0 +1,808 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! DDS publish/subscribe host capability, usable as a ROS 2 message bridge.
+//!
+//! A [`DdsCapability`] grants a component access to a fixed set of topics.
+//! For every granted topic it registers host functions in [`DDS_MODULE`]:
+//!
+//! ```text
+//! publish-<topic>: func(sample: <record>)
+//! subscribe-<topic>: func() -> u32
+//! take-<topic>: func(reader: u32) -> option<<record>>
+//! unsubscribe: func(reader: u32)
+//! ```
+//!
+//! Topic types are WIT records of scalar fields, described by a
+//! [`TopicType`]. Samples cross the component boundary as the flattened
+//! fields of the record and reach the [`DdsBackend`] as a `Value::Record`
+//! with the WIT field names, so a backend can map them to the IDL type of
+//! the DDS topic. ROS 2 nodes use the topic names returned by
+//! [`ros2_topic`].
+//!
+//! The backend, e.g. a vendor DDS stack reached over FFI, is supplied by the
+//! integrator. [`LoopbackBackend`] delivers samples in memory and is meant
+//! for tests and single-process deployments. Readers and published samples
+//! are limited by a [`DdsQuota`].
+
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::{
+    callback::CallbackRegistry,
+    prelude::{
+        codes,
+        format,
+        Arc,
+        Error,
+        ErrorCategory,
+        HostFunctionHandler,
+        Result,
+        String,
+        ToString,
+        Value,
+        Vec,
+    },
+};
+
+/// Module the DDS host functions are registered in
+pub const DDS_MODULE: &str = "wrt:dds/topics";
+
+/// DDS topic name ROS 2 uses for the topic `name`
+///
+/// ROS 2 prefixes topics with `rt`, so `/chatter` and `chatter` both map to
+/// `rt/chatter`.
+#[must_use]
+pub fn ros2_topic(name: &str) -> String {
+    format!("rt/{}", name.trim_start_matches('/'))
+}
+
+/// WIT type of a topic record field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// `bool`
+    Bool,
+    /// `s8`
+    S8,
+    /// `u8`
+    U8,
+    /// `s16`
+    S16,
+    /// `u16`
+    U16,
+    /// `s32`
+    S32,
+    /// `u32`
+    U32,
+    /// `s64`
+    S64,
+    /// `u64`
+    U64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// `char`
+    Char,
+}
+
+impl FieldType {
+    /// Lift the core value a field is passed as to its component value
+    fn lift(self, value: &Value) -> Result<Value> {
+        Ok(match (self, value) {
+            (Self::Bool, Value::I32(v)) => Value::Bool(*v != 0),
+            (Self::S8, Value::I32(v)) => Value::S8(*v as i8),
+            (Self::U8, Value::I32(v)) => Value::U8(*v as u8),
+            (Self::S16, Value::I32(v)) => Value::S16(*v as i16),
+            (Self::U16, Value::I32(v)) => Value::U16(*v as u16),
+            (Self::S32, Value::I32(v)) => Value::S32(*v),
+            (Self::U32, Value::I32(v)) => Value::U32(*v as u32),
+            (Self::S64, Value::I64(v)) => Value::S64(*v),
+            (Self::U64, Value::I64(v)) => Value::U64(*v as u64),
+            (Self::F32, Value::F32(v)) => Value::F32(v.clone()),
+            (Self::F64, Value::F64(v)) => Value::F64(v.clone()),
+            (Self::Char, Value::I32(v)) => Value::Char(
+                char::from_u32(*v as u32)
+                    .ok_or_else(|| type_mismatch("Invalid char in DDS sample"))?,
+            ),
+            _ => return Err(type_mismatch("DDS sample field has the wrong type")),
+        })
+    }
+
+    /// Lower a component value to the core value the field is passed as
+    fn lower(self, value: &Value) -> Result<Value> {
+        Ok(match (self, value) {
+            (Self::Bool, Value::Bool(v)) => Value::I32(i32::from(*v)),
+            (Self::S8, Value::S8(v)) => Value::I32(i32::from(*v)),
+            (Self::U8, Value::U8(v)) => Value::I32(i32::from(*v)),
+            (Self::S16, Value::S16(v)) => Value::I32(i32::from(*v)),
+            (Self::U16, Value::U16(v)) => Value::I32(i32::from(*v)),
+            (Self::S32, Value::S32(v)) => Value::I32(*v),
+            (Self::U32, Value::U32(v)) => Value::I32(*v as i32),
+            (Self::S64, Value::S64(v)) => Value::I64(*v),
+            (Self::U64, Value::U64(v)) => Value::I64(*v as i64),
+            (Self::F32, Value::F32(v)) => Value::F32(v.clone()),
+            (Self::F64, Value::F64(v)) => Value::F64(v.clone()),
+            (Self::Char, Value::Char(v)) => Value::I32(*v as i32),
+            _ => return Err(type_mismatch("DDS sample field has the wrong type")),
+        })
+    }
+
+    /// Core value of the field when no sample is returned
+    fn zero(self) -> Value {
+        match self {
+            Self::S64 | Self::U64 => Value::I64(0),
+            Self::F32 => Value::F32(Default::default()),
+            Self::F64 => Value::F64(Default::default()),
+            _ => Value::I32(0),
+        }
+    }
+}
+
+/// Record type of the samples of a topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicType {
+    /// Name of the WIT record
+    name:   String,
+    /// Fields of the record, in declaration order
+    fields: Vec<(String, FieldType)>,
+}
+
+impl TopicType {
+    /// Create a record type without fields
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name:   name.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a field
+    #[must_use]
+    pub fn field(mut self, name: &str, ty: FieldType) -> Self {
+        self.fields.push((name.to_string(), ty));
+        self
+    }
+
+    /// Name of the WIT record
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Fields of the record, in declaration order
+    #[must_use]
+    pub fn fields(&self) -> &[(String, FieldType)] {
+        &self.fields
+    }
+
+    /// Build a sample record from the core values of its fields
+    ///
+    /// # Errors
+    ///
+    /// Returns a type mismatch if the values do not match the fields
+    pub fn lift(&self, values: &[Value]) -> Result<Value> {
+        if values.len() != self.fields.len() {
+            return Err(type_mismatch("DDS sample has the wrong number of fields"));
+        }
+        let fields = self
+            .fields
+            .iter()
+            .zip(values)
+            .map(|((name, ty), value)| Ok((name.clone(), ty.lift(value)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Value::Record(fields))
+    }
+
+    /// Flatten a sample record to the core values of its fields
+    ///
+    /// # Errors
+    ///
+    /// Returns a type mismatch if the sample is not a record of this type
+    pub fn lower(&self, sample: &Value) -> Result<Vec<Value>> {
+        let Value::Record(values) = sample else {
+            return Err(type_mismatch("DDS sample is not a record"));
+        };
+        if values.len() != self.fields.len() {
+            return Err(type_mismatch("DDS sample has the wrong number of fields"));
+        }
+        self.fields
+            .iter()
+            .zip(values)
+            .map(|((name, ty), (field, value))| {
+                if name != field {
+                    return Err(type_mismatch("DDS sample field has the wrong name"));
+                }
+                ty.lower(value)
+            })
+            .collect()
+    }
+}
+
+/// DDS implementation samples are published and received through
+pub trait DdsBackend: Send + Sync {
+    /// Register `topic` with record type `ty` in `domain_id`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic exists with a different type
+    fn register_topic(&self, domain_id: u32, topic: &str, ty: &TopicType) -> Result<()>;
+
+    /// Publish `sample`, a `Value::Record`, to `topic`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic is not registered or the write fails
+    fn publish(&self, domain_id: u32, topic: &str, sample: Value) -> Result<()>;
+
+    /// Create a reader for `topic`, returning its ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic is not registered
+    fn create_reader(&self, domain_id: u32, topic: &str) -> Result<u32>;
+
+    /// Take the oldest sample received by `reader`, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reader does not exist
+    fn take(&self, reader: u32) -> Result<Option<Value>>;
+
+    /// Delete `reader`
+    fn delete_reader(&self, reader: u32);
+}
+
+/// Reader of the loopback backend
+#[derive(Debug)]
+struct LoopbackReader {
+    /// Domain and name of the topic read
+    topic:   (u32, String),
+    /// Received samples, oldest first
+    samples: VecDeque<Value>,
+}
+
+/// State of the loopback backend
+#[derive(Debug, Default)]
+struct LoopbackState {
+    /// Registered topics by domain and name
+    topics:      HashMap<(u32, String), TopicType>,
+    /// Readers by ID
+    readers:     HashMap<u32, LoopbackReader>,
+    /// ID of the next reader
+    next_reader: u32,
+}
+
+/// In-memory backend delivering samples to the readers of the same process
+///
+/// Readers keep the last `history_depth` samples, like the `KEEP_LAST`
+/// history QoS.
+#[derive(Debug)]
+pub struct LoopbackBackend {
+    /// Samples kept per reader
+    history_depth: usize,
+    /// Topics and readers
+    state:         Mutex<LoopbackState>,
+}
+
+impl LoopbackBackend {
+    /// Create a backend whose readers keep `history_depth` samples
+    #[must_use]
+    pub fn new(history_depth: usize) -> Self {
+        Self {
+            history_depth: history_depth.max(1),
+            state:         Mutex::new(LoopbackState::default()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, LoopbackState> {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for LoopbackBackend {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl DdsBackend for LoopbackBackend {
+    fn register_topic(&self, domain_id: u32, topic: &str, ty: &TopicType) -> Result<()> {
+        let mut state = self.state();
+        match state.topics.get(&(domain_id, topic.to_string())) {
+            Some(existing) if existing != ty => {
+                Err(type_mismatch("DDS topic registered with a different type"))
+            },
+            Some(_) => Ok(()),
+            None => {
+                state.topics.insert((domain_id, topic.to_string()), ty.clone());
+                Ok(())
+            },
+        }
+    }
+
+    fn publish(&self, domain_id: u32, topic: &str, sample: Value) -> Result<()> {
+        let key = (domain_id, topic.to_string());
+        let mut state = self.state();
+        let ty = state.topics.get(&key).ok_or_else(topic_not_found)?;
+        ty.lower(&sample)?;
+        for reader in state.readers.values_mut().filter(|reader| reader.topic == key) {
+            if reader.samples.len() == self.history_depth {
+                reader.samples.pop_front();
+            }
+            reader.samples.push_back(sample.clone());
+        }
+        Ok(())
+    }
+
+    fn create_reader(&self, domain_id: u32, topic: &str) -> Result<u32> {
+        let key = (domain_id, topic.to_string());
+        let mut state = self.state();
+        if !state.topics.contains_key(&key) {
+            return Err(topic_not_found());
+        }
+        let id = state.next_reader;
+        state.next_reader = state.next_reader.wrapping_add(1);
+        state.readers.insert(
+            id,
+            LoopbackReader {
+                topic:   key,
+                samples: VecDeque::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    fn take(&self, reader: u32) -> Result<Option<Value>> {
+        let mut state = self.state();
+        let reader = state.readers.get_mut(&reader).ok_or_else(invalid_reader)?;
+        Ok(reader.samples.pop_front())
+    }
+
+    fn delete_reader(&self, reader: u32) {
+        self.state().readers.remove(&reader);
+    }
+}
+
+/// Limits on the DDS usage of a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DdsQuota {
+    /// Readers the component may hold at once
+    pub max_readers:           usize,
+    /// Samples the component may publish in total
+    pub max_published_samples: u64,
+}
+
+impl Default for DdsQuota {
+    fn default() -> Self {
+        Self {
+            max_readers:           16,
+            max_published_samples: u64::MAX,
+        }
+    }
+}
+
+/// Operations a component may perform on a topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicAccess {
+    /// Publish samples only
+    Publish,
+    /// Subscribe to samples only
+    Subscribe,
+    /// Publish and subscribe
+    PublishSubscribe,
+}
+
+impl TopicAccess {
+    fn publish(self) -> bool {
+        matches!(self, Self::Publish | Self::PublishSubscribe)
+    }
+
+    fn subscribe(self) -> bool {
+        matches!(self, Self::Subscribe | Self::PublishSubscribe)
+    }
+}
+
+/// Topic a component is granted access to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicGrant {
+    /// Name of the topic in the host function names
+    pub name:      String,
+    /// Name of the topic in DDS
+    pub dds_topic: String,
+    /// Record type of the samples
+    pub ty:        TopicType,
+    /// Granted operations
+    pub access:    TopicAccess,
+}
+
+/// Usage of a capability, shared by its host functions
+#[derive(Debug, Default)]
+struct DdsUsage {
+    /// Samples published so far
+    published:   u64,
+    /// Open readers by handle: granted topic index and backend reader ID
+    readers:     HashMap<u32, (usize, u32)>,
+    /// Handle of the next reader
+    next_handle: u32,
+}
+
+/// Host capability granting a component publish/subscribe access to topics
+pub struct DdsCapability {
+    /// DDS implementation
+    backend:   Arc<dyn DdsBackend>,
+    /// DDS domain of all topics
+    domain_id: u32,
+    /// Granted topics
+    topics:    Vec<TopicGrant>,
+    /// Limits on the usage
+    quota:     DdsQuota,
+    /// Usage so far
+    usage:     Arc<Mutex<DdsUsage>>,
+}
+
+impl DdsCapability {
+    /// Create a capability without topics in `domain_id` of `backend`
+    #[must_use]
+    pub fn new(backend: Arc<dyn DdsBackend>, domain_id: u32) -> Self {
+        Self {
+            backend,
+            domain_id,
+            topics: Vec::new(),
+            quota: DdsQuota::default(),
+            usage: Arc::new(Mutex::new(DdsUsage::default())),
+        }
+    }
+
+    /// Grant `access` to the DDS topic `dds_topic`, exposed to the component
+    /// as `name`
+    #[must_use]
+    pub fn topic(
+        mut self,
+        name: &str,
+        dds_topic: &str,
+        ty: TopicType,
+        access: TopicAccess,
+    ) -> Self {
+        self.topics.push(TopicGrant {
+            name: name.to_string(),
+            dds_topic: dds_topic.to_string(),
+            ty,
+            access,
+        });
+        self
+    }
+
+    /// Limit the usage to `quota`
+    #[must_use]
+    pub fn with_quota(mut self, quota: DdsQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Granted topics
+    #[must_use]
+    pub fn topics(&self) -> &[TopicGrant] {
+        &self.topics
+    }
+
+    /// Samples published so far
+    #[must_use]
+    pub fn published_samples(&self) -> u64 {
+        lock_usage(&self.usage).published
+    }
+
+    /// Readers currently open
+    #[must_use]
+    pub fn open_readers(&self) -> usize {
+        lock_usage(&self.usage).readers.len()
+    }
+
+    /// Register the topics with the backend and the host functions of the
+    /// granted operations with `registry`
+    ///
+    /// Returns the number of registered host functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if two grants share a name or the backend rejects a
+    /// topic, in which case no host functions are registered
+    pub fn install(&self, registry: &mut CallbackRegistry) -> Result<usize> {
+        for (index, grant) in self.topics.iter().enumerate() {
+            if self.topics[..index].iter().any(|other| other.name == grant.name) {
+                return Err(Error::new(
+                    ErrorCategory::Validation,
+                    codes::VALIDATION_ERROR,
+                    "Duplicate topic name in DDS capability",
+                ));
+            }
+        }
+        for grant in &self.topics {
+            self.backend.register_topic(self.domain_id, &grant.dds_topic, &grant.ty)?;
+        }
+
+        let mut registered = 0;
+        for (index, grant) in self.topics.iter().enumerate() {
+            if grant.access.publish() {
+                registry.register_host_function(
+                    DDS_MODULE,
+                    &format!("publish-{}", grant.name),
+                    self.publish_handler(grant),
+                );
+                registered += 1;
+            }
+            if grant.access.subscribe() {
+                registry.register_host_function(
+                    DDS_MODULE,
+                    &format!("subscribe-{}", grant.name),
+                    self.subscribe_handler(index, grant),
+                );
+                registry.register_host_function(
+                    DDS_MODULE,
+                    &format!("take-{}", grant.name),
+                    self.take_handler(index, grant),
+                );
+                registered += 2;
+            }
+        }
+        if self.topics.iter().any(|grant| grant.access.subscribe()) {
+            registry.register_host_function(DDS_MODULE, "unsubscribe", self.unsubscribe_handler());
+            registered += 1;
+        }
+        Ok(registered)
+    }
+
+    fn publish_handler(&self, grant: &TopicGrant) -> HostFunctionHandler {
+        let backend = Arc::clone(&self.backend);
+        let usage = Arc::clone(&self.usage);
+        let domain_id = self.domain_id;
+        let max_published = self.quota.max_published_samples;
+        let topic = grant.dds_topic.clone();
+        let ty = grant.ty.clone();
+        HostFunctionHandler::new_with_args(move |_, args| {
+            let sample = ty.lift(&args)?;
+            let mut usage = lock_usage(&usage);
+            if usage.published >= max_published {
+                return Err(quota_exceeded("DDS publish quota exhausted"));
+            }
+            backend.publish(domain_id, &topic, sample)?;
+            usage.published += 1;
+            Ok(Vec::new())
+        })
+    }
+
+    fn subscribe_handler(&self, index: usize, grant: &TopicGrant) -> HostFunctionHandler {
+        let backend = Arc::clone(&self.backend);
+        let usage = Arc::clone(&self.usage);
+        let domain_id = self.domain_id;
+        let max_readers = self.quota.max_readers;
+        let topic = grant.dds_topic.clone();
+        HostFunctionHandler::new_with_args(move |_, args| {
+            if !args.is_empty() {
+                return Err(type_mismatch("DDS subscribe takes no arguments"));
+            }
+            let mut usage = lock_usage(&usage);
+            if usage.readers.len() >= max_readers {
+                return Err(quota_exceeded("DDS reader quota exhausted"));
+            }
+            let reader = backend.create_reader(domain_id, &topic)?;
+            let handle = usage.next_handle;
+            usage.next_handle = usage.next_handle.wrapping_add(1);
+            usage.readers.insert(handle, (index, reader));
+            Ok(vec![Value::I32(handle as i32)])
+        })
+    }
+
+    fn take_handler(&self, index: usize, grant: &TopicGrant) -> HostFunctionHandler {
+        let backend = Arc::clone(&self.backend);
+        let usage = Arc::clone(&self.usage);
+        let ty = grant.ty.clone();
+        HostFunctionHandler::new_with_args(move |_, args| {
+            let reader = match (reader_handle(&args), lock_usage(&usage)) {
+                (Some(handle), usage) => match usage.readers.get(&handle) {
+                    Some(&(topic, reader)) if topic == index => reader,
+                    _ => return Err(invalid_reader()),
+                },
+                (None, _) => return Err(type_mismatch("DDS take expects a reader handle")),
+            };
+            let mut results = Vec::with_capacity(ty.fields().len() + 1);
+            match backend.take(reader)? {
+                Some(sample) => {
+                    results.push(Value::I32(1));
+                    results.extend(ty.lower(&sample)?);
+                },
+                None => {
+                    results.push(Value::I32(0));
+                    results.extend(ty.fields().iter().map(|(_, field)| field.zero()));
+                },
+            }
+            Ok(results)
+        })
+    }
+
+    fn unsubscribe_handler(&self) -> HostFunctionHandler {
+        let backend = Arc::clone(&self.backend);
+        let usage = Arc::clone(&self.usage);
+        HostFunctionHandler::new_with_args(move |_, args| {
+            let handle = reader_handle(&args)
+                .ok_or_else(|| type_mismatch("DDS unsubscribe expects a reader handle"))?;
+            let (_, reader) =
+                lock_usage(&usage).readers.remove(&handle).ok_or_else(invalid_reader)?;
+            backend.delete_reader(reader);
+            Ok(Vec::new())
+        })
+    }
+}
+
+impl core::fmt::Debug for DdsCapability {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DdsCapability")
+            .field("domain_id", &self.domain_id)
+            .field("topics", &self.topics)
+            .field("quota", &self.quota)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reader handle passed as the only argument
+fn reader_handle(args: &[Value]) -> Option<u32> {
+    match args {
+        [Value::I32(handle)] => Some(*handle as u32),
+        _ => None,
+    }
+}
+
+fn lock_usage(usage: &Mutex<DdsUsage>) -> MutexGuard<'_, DdsUsage> {
+    usage.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn type_mismatch(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Type, codes::TYPE_MISMATCH, message)
+}
+
+fn quota_exceeded(message: &'static str) -> Error {
+    Error::new(
+        ErrorCategory::Resource,
+        codes::RESOURCE_LIMIT_EXCEEDED,
+        message,
+    )
+}
+
+fn topic_not_found() -> Error {
+    Error::new(
+        ErrorCategory::Resource,
+        codes::RESOURCE_NOT_FOUND,
+        "DDS topic not registered",
+    )
+}
+
+fn invalid_reader() -> Error {
+    Error::new(
+        ErrorCategory::Resource,
+        codes::RESOURCE_INVALID_HANDLE,
+        "Invalid DDS reader",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::FloatBits32;
+
+    use super::*;
+    use crate::prelude::vec;
+
+    fn temperature() -> TopicType {
+        TopicType::new("temperature")
+            .field("stamp", FieldType::U64)
+            .field("celsius", FieldType::F32)
+            .field("valid", FieldType::Bool)
+    }
+
+    fn sample(stamp: i64, celsius: f32) -> Vec<Value> {
+        vec![
+            Value::I64(stamp),
+            Value::F32(FloatBits32::from_float(celsius)),
+            Value::I32(1),
+        ]
+    }
+
+    fn call(
+        registry: &mut CallbackRegistry,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        registry.call_host_function(&mut (), DDS_MODULE, function, args)
+    }
+
+    #[test]
+    fn test_topic_type_round_trip() {
+        let record = temperature().lift(&sample(7, 21.5)).unwrap();
+        let Value::Record(fields) = &record else {
+            panic!("not a record")
+        };
+        assert!(matches!(&fields[0], (name, Value::U64(7)) if name == "stamp"));
+        assert!(matches!(&fields[2], (name, Value::Bool(true)) if name == "valid"));
+        assert_eq!(temperature().lower(&record).unwrap(), sample(7, 21.5));
+
+        assert!(temperature().lift(&[Value::I32(0)]).is_err());
+        assert_eq!(ros2_topic("/chatter"), "rt/chatter");
+    }
+
+    #[test]
+    fn test_publish_and_take_through_loopback() {
+        let backend = Arc::new(LoopbackBackend::new(2));
+        let capability = DdsCapability::new(backend, 0)
+            .topic(
+                "temperature",
+                &ros2_topic("/temperature"),
+                temperature(),
+                TopicAccess::PublishSubscribe,
+            )
+            .with_quota(DdsQuota {
+                max_readers:           1,
+                max_published_samples: 3,
+            });
+        let mut registry = CallbackRegistry::new();
+        assert_eq!(capability.install(&mut registry).unwrap(), 4);
+
+        let reader = call(&mut registry, "subscribe-temperature", vec![]).unwrap();
+        let err = call(&mut registry, "subscribe-temperature", vec![]).unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_LIMIT_EXCEEDED);
+
+        for stamp in 1..=3 {
+            call(&mut registry, "publish-temperature", sample(stamp, 20.0)).unwrap();
+        }
+        let err = call(&mut registry, "publish-temperature", sample(4, 20.0)).unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_LIMIT_EXCEEDED);
+        assert_eq!(capability.published_samples(), 3);
+
+        // The reader keeps the last two samples
+        let taken = call(&mut registry, "take-temperature", reader.clone()).unwrap();
+        assert_eq!(taken[0], Value::I32(1));
+        assert_eq!(taken[1..], sample(2, 20.0)[..]);
+        call(&mut registry, "take-temperature", reader.clone()).unwrap();
+        let empty = call(&mut registry, "take-temperature", reader.clone()).unwrap();
+        assert_eq!(empty[0], Value::I32(0));
+        assert_eq!(empty.len(), 4);
+
+        call(&mut registry, "unsubscribe", reader.clone()).unwrap();
+        assert_eq!(capability.open_readers(), 0);
+        let err = call(&mut registry, "take-temperature", reader).unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_INVALID_HANDLE);
+    }
+
+    #[test]
+    fn test_grants_scope_host_functions() {
+        let backend = Arc::new(LoopbackBackend::default());
+        backend.register_topic(0, "rt/cmd", &TopicType::new("twist")).unwrap();
+
+        let publisher = DdsCapability::new(backend.clone(), 0).topic(
+            "temperature",
+            "rt/temperature",
+            temperature(),
+            TopicAccess::Publish,
+        );
+        let mut registry = CallbackRegistry::new();
+        assert_eq!(publisher.install(&mut registry).unwrap(), 1);
+        assert!(call(&mut registry, "subscribe-temperature", vec![]).is_err());
+
+        let conflicting = DdsCapability::new(backend, 0).topic(
+            "cmd",
+            "rt/cmd",
+            temperature(),
+            TopicAccess::Subscribe,
+        );
+        let err = conflicting.install(&mut CallbackRegistry::new()).unwrap_err();
+        assert_eq!(err.code, codes::TYPE_MISMATCH);
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Host objects passed to guests as `externref` values.
+//!
+//! A guest cannot look inside an `externref`, so the value only needs to
+//! identify an object the host keeps. [`HostObjectTable`] stores those
+//! objects as `Arc<dyn Any>` and hands out [`ExternRef`]s naming them; when
+//! the guest passes a reference back, the host downcasts it to the type it
+//! expects. References carry a generation, so a reference to a removed
+//! object never resolves to an object later stored in the same slot.
+//!
+//! Objects may be owned by an instance. Objects stored while a host function
+//! runs on behalf of an instance are owned by that instance, and all objects
+//! an instance owns are released when it is discarded.
+
+use std::sync::{
+    Mutex,
+    MutexGuard,
+};
+
+use wrt_foundation::values::ExternRef;
+
+use crate::prelude::{
+    codes,
+    Any,
+    Arc,
+    Error,
+    ErrorCategory,
+    Result,
+    Value,
+    Vec,
+};
+
+/// Default number of objects a [`HostObjectTable`] holds
+pub const MAX_HOST_OBJECTS: usize = 4096;
+
+/// Bits of an [`ExternRef`] index naming the slot
+const SLOT_BITS: u32 = 20;
+
+/// Mask selecting the slot of an [`ExternRef`] index
+const SLOT_MASK: u32 = (1 << SLOT_BITS) - 1;
+
+/// Generations a slot goes through before reusing one
+const GENERATIONS: u32 = 1 << (32 - SLOT_BITS);
+
+/// Object shared with guests
+pub type HostObject = Arc<dyn Any + Send + Sync>;
+
+/// One slot of the table
+#[derive(Default)]
+struct Slot {
+    /// Bumped every time the slot is freed
+    generation: u32,
+    /// Stored object and the instance owning it
+    entry:      Option<(HostObject, Option<usize>)>,
+}
+
+/// Slots and bookkeeping, guarded by the table's lock
+#[derive(Default)]
+struct TableState {
+    /// Object slots
+    slots:   Vec<Slot>,
+    /// Free slots, reused most recently freed first
+    free:    Vec<u32>,
+    /// Number of stored objects
+    len:     usize,
+    /// Instances host functions currently run on behalf of, innermost last
+    callers: Vec<usize>,
+}
+
+/// Table of host objects referenced by `externref` values
+pub struct HostObjectTable {
+    /// Slots and bookkeeping
+    state:    Mutex<TableState>,
+    /// Maximum number of stored objects
+    capacity: usize,
+}
+
+impl HostObjectTable {
+    /// Create a table holding up to [`MAX_HOST_OBJECTS`] objects
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_HOST_OBJECTS)
+    }
+
+    /// Create a table holding up to `capacity` objects
+    ///
+    /// The capacity is limited to the number of slots an [`ExternRef`] can
+    /// name.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            state:    Mutex::new(TableState::default()),
+            capacity: capacity.min(SLOT_MASK as usize + 1),
+        }
+    }
+
+    /// Store an object, owned by the instance calling into the host, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full
+    pub fn insert<T: Any + Send + Sync>(&self, object: T) -> Result<ExternRef> {
+        self.insert_arc(Arc::new(object))
+    }
+
+    /// Store a shared object, owned by the instance calling into the host,
+    /// if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full
+    pub fn insert_arc(&self, object: HostObject) -> Result<ExternRef> {
+        let owner = self.state().callers.last().copied();
+        self.insert_owned(object, owner)
+    }
+
+    /// Store a shared object owned by `owner`, or by the host if `None`
+    ///
+    /// Objects owned by the host stay in the table until removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full
+    pub fn insert_owned(&self, object: HostObject, owner: Option<usize>) -> Result<ExternRef> {
+        let mut state = self.state();
+        if state.len >= self.capacity {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Host object table is full",
+            ));
+        }
+
+        let slot = match state.free.pop() {
+            Some(slot) => slot,
+            None => {
+                state.slots.push(Slot::default());
+                (state.slots.len() - 1) as u32
+            },
+        };
+        let entry = &mut state.slots[slot as usize];
+        entry.entry = Some((object, owner));
+        let generation = entry.generation;
+        state.len += 1;
+
+        Ok(ExternRef {
+            index: generation << SLOT_BITS | slot,
+        })
+    }
+
+    /// Store an object and wrap its reference in a [`Value`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full
+    pub fn insert_value<T: Any + Send + Sync>(&self, object: T) -> Result<Value> {
+        Ok(Value::ExternRef(Some(self.insert(object)?)))
+    }
+
+    /// Object a reference names, if it is still stored
+    #[must_use]
+    pub fn get(&self, reference: ExternRef) -> Option<HostObject> {
+        let state = self.state();
+        let slot = state.slots.get((reference.index & SLOT_MASK) as usize)?;
+        if slot.generation != reference.index >> SLOT_BITS {
+            return None;
+        }
+        slot.entry.as_ref().map(|(object, _)| Arc::clone(object))
+    }
+
+    /// Object a reference names, as the type the host stored
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object was removed or is not a `T`
+    pub fn downcast<T: Any + Send + Sync>(&self, reference: ExternRef) -> Result<Arc<T>> {
+        let object = self.get(reference).ok_or_else(|| {
+            Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_NOT_FOUND,
+                "Host object no longer exists",
+            )
+        })?;
+        object.downcast::<T>().map_err(|_| {
+            Error::new(
+                ErrorCategory::Type,
+                codes::TYPE_MISMATCH,
+                "Host object has a different type",
+            )
+        })
+    }
+
+    /// Object an `externref` value names, or `None` for a null reference
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an `externref`, or the object was
+    /// removed or is not a `T`
+    pub fn downcast_value<T: Any + Send + Sync>(&self, value: &Value) -> Result<Option<Arc<T>>> {
+        match value {
+            Value::ExternRef(Some(reference)) => self.downcast(*reference).map(Some),
+            Value::ExternRef(None) => Ok(None),
+            _ => Err(Error::new(
+                ErrorCategory::Type,
+                codes::TYPE_MISMATCH,
+                "Expected an externref value",
+            )),
+        }
+    }
+
+    /// Remove an object, returning it if it was still stored
+    pub fn remove(&self, reference: ExternRef) -> Option<HostObject> {
+        let mut state = self.state();
+        let slot = reference.index & SLOT_MASK;
+        let entry = state.slots.get(slot as usize)?;
+        if entry.generation != reference.index >> SLOT_BITS || entry.entry.is_none() {
+            return None;
+        }
+        Some(state.free_slot(slot))
+    }
+
+    /// Remove every object owned by an instance, returning how many there were
+    pub fn release_instance(&self, instance: usize) -> usize {
+        let mut state = self.state();
+        let owned: Vec<u32> = state
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| matches!(slot.entry, Some((_, Some(owner))) if owner == instance))
+            .map(|(slot, _)| slot as u32)
+            .collect();
+        for &slot in &owned {
+            state.free_slot(slot);
+        }
+        owned.len()
+    }
+
+    /// Whether a reference names a stored object
+    #[must_use]
+    pub fn contains(&self, reference: ExternRef) -> bool {
+        self.get(reference).is_some()
+    }
+
+    /// Number of stored objects
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state().len
+    }
+
+    /// Whether no objects are stored
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record that host functions now run on behalf of `instance`
+    ///
+    /// Objects stored with [`Self::insert`] until the matching
+    /// [`Self::exit_caller`] are owned by `instance`.
+    pub fn enter_caller(&self, instance: usize) {
+        self.state().callers.push(instance);
+    }
+
+    /// Undo the latest [`Self::enter_caller`]
+    pub fn exit_caller(&self) {
+        self.state().callers.pop();
+    }
+
+    /// Lock the table
+    ///
+    /// A panic while the lock is held cannot leave the slots inconsistent, so
+    /// a poisoned lock is recovered.
+    fn state(&self) -> MutexGuard<'_, TableState> {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl TableState {
+    /// Free an occupied slot, returning its object
+    fn free_slot(&mut self, slot: u32) -> HostObject {
+        let entry = &mut self.slots[slot as usize];
+        let (object, _) = entry.entry.take().expect("slot is occupied");
+        entry.generation = (entry.generation + 1) % GENERATIONS;
+        self.free.push(slot);
+        self.len -= 1;
+        object
+    }
+}
+
+impl Default for HostObjectTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for HostObjectTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HostObjectTable")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::String;
+
+    #[test]
+    fn test_host_object_downcast() {
+        let table = HostObjectTable::new();
+        let reference = table.insert(String::from("config")).unwrap();
+
+        assert_eq!(table.downcast::<String>(reference).unwrap().as_str(), "config");
+        let err = table.downcast::<u32>(reference).unwrap_err();
+        assert_eq!(err.code, codes::TYPE_MISMATCH);
+
+        let value = Value::ExternRef(Some(reference));
+        assert!(table.downcast_value::<String>(&value).unwrap().is_some());
+        assert!(table.downcast_value::<String>(&Value::ExternRef(None)).unwrap().is_none());
+        assert!(table.downcast_value::<String>(&Value::I32(0)).is_err());
+    }
+
+    #[test]
+    fn test_stale_reference_does_not_resolve() {
+        let table = HostObjectTable::new();
+        let first = table.insert(1u32).unwrap();
+        assert!(table.remove(first).is_some());
+        assert!(table.remove(first).is_none());
+
+        let second = table.insert(2u32).unwrap();
+        assert_ne!(first, second);
+        assert!(!table.contains(first));
+        assert_eq!(*table.downcast::<u32>(second).unwrap(), 2);
+        let err = table.downcast::<u32>(first).unwrap_err();
+        assert_eq!(err.code, codes::RESOURCE_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_instance_objects_are_released() {
+        let table = HostObjectTable::new();
+        let host = table.insert(0u8).unwrap();
+
+        table.enter_caller(1);
+        let first = table.insert(1u8).unwrap();
+        table.enter_caller(2);
+        let second = table.insert(2u8).unwrap();
+        table.exit_caller();
+        let third = table.insert(3u8).unwrap();
+        table.exit_caller();
+
+        assert_eq!(table.release_instance(1), 2);
+        assert!(!table.contains(first) && !table.contains(third));
+        assert!(table.contains(second) && table.contains(host));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn test_host_object_table_is_bounded() {
+        let table = HostObjectTable::with_capacity(1);
+        let reference = table.insert(()).unwrap();
+        let err = table.insert(()).unwrap_err();
+        assert_eq!(err.code, codes::CAPACITY_EXCEEDED);
+
+        table.remove(reference);
+        assert!(table.insert(()).is_ok());
+    }
+}
@@ -279,47 +279,17 @@ fn default() -> Self {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    #[cfg(not(feature = "std"))]
-    extern crate alloc;
-    #[cfg(not(feature = "std"))]
-    use alloc::vec;
-
-    use wrt_foundation::{
-        allocator::CrateId,
-        safe_managed_alloc,
-        safe_memory::NoStdProvider,
-    };
-
     use super::*;
 
     #[test]
     fn test_cloneable_fn() {
-        let f = CloneableFn::new(|_| {
-            #[cfg(feature = "std")]
-            return Ok(vec![Value::I32(42)]);
-
-            #[cfg(not(feature = "std"))]
-            {
-                // Use capability-aware allocation for safety-critical code
-                let provider = crate::bounded_host_infra::create_host_provider()?;
-                let mut vec = ValueVec::new(provider).unwrap();
-                vec.push(Value::I32(42)).unwrap();
-                Ok(vec)
-            }
-        });
+        let f = CloneableFn::new(|_| Ok(vec![Value::I32(42)]));
         let f2 = f.clone();
 
         let mut target = ();
-
-        #[cfg(feature = "std")]
         let empty_args = vec![];
-        #[cfg(not(feature = "std"))]
-        let empty_args = {
-            let provider = safe_managed_alloc!(8192, CrateId::Host).unwrap();
-            ValueVec::new(provider).unwrap()
-        };
 
         let result = f.call(&mut target, empty_args.clone());
         let result2 = f2.call(&mut target, empty_args);
@@ -337,31 +307,10 @@ fn test_cloneable_fn() {
 
     #[test]
     fn test_host_function_handler() {
-        let handler = HostFunctionHandler::new(|_| {
-            #[cfg(feature = "std")]
-            return Ok(vec![Value::I32(42)]);
-
-            #[cfg(not(feature = "std"))]
-            {
-                // Use capability-aware allocation for safety-critical code
-                let provider = crate::bounded_host_infra::create_host_provider()?;
-                let mut vec = ValueVec::new(provider).unwrap();
-                vec.push(Value::I32(42)).unwrap();
-                Ok(vec)
-            }
-        });
+        let handler = HostFunctionHandler::new(|_| Ok(vec![Value::I32(42)]));
 
         let mut target = ();
-
-        #[cfg(feature = "std")]
-        let empty_args = vec![];
-        #[cfg(not(feature = "std"))]
-        let empty_args = {
-            let provider = safe_managed_alloc!(8192, CrateId::Host).unwrap();
-            ValueVec::new(provider).unwrap()
-        };
-
-        let result = handler.call(&mut target, empty_args);
+        let result = handler.call(&mut target, vec![]);
 
         assert!(result.is_ok());
         let result_vec = result.unwrap();
@@ -490,7 +490,7 @@ fn clone(&self) -> Self {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use wrt_foundation::values::Value;
 
@@ -0,0 +1,325 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Per-instance host state injected into host functions.
+//!
+//! Host providers used to keep their state in global statics, which forces
+//! every instance to share it. [`InstanceContexts`] instead gives each
+//! instance a small map of values keyed by their type, similar to the data
+//! of a store: the embedder inserts the state an instance should see before
+//! calling it, and a host function looks up the value of the instance it
+//! runs on behalf of with [`InstanceContexts::with_current`].
+//!
+//! Values are locked individually while a host function uses them. A host
+//! function that calls back into a guest which reaches the same host
+//! function again must release the value first, or the nested call fails
+//! instead of deadlocking.
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    sync::{
+        Mutex,
+        MutexGuard,
+        TryLockError,
+    },
+};
+
+use crate::prelude::{
+    codes,
+    Any,
+    Arc,
+    Error,
+    ErrorCategory,
+    Result,
+    Vec,
+};
+
+/// Default number of values one instance's context holds
+pub const MAX_CONTEXT_VALUES: usize = 32;
+
+/// Value stored in a context, locked while a host function uses it
+type ContextValue = Arc<Mutex<dyn Any + Send>>;
+
+/// Values of one instance keyed by their type
+#[derive(Default)]
+pub struct InstanceContext {
+    /// Values in insertion order
+    values: Vec<(TypeId, ContextValue)>,
+}
+
+impl InstanceContext {
+    /// Number of values in the context
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the context holds no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn get(&self, type_id: TypeId) -> Option<&ContextValue> {
+        self.values.iter().find(|(id, _)| *id == type_id).map(|(_, value)| value)
+    }
+}
+
+impl core::fmt::Debug for InstanceContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InstanceContext").field("len", &self.values.len()).finish()
+    }
+}
+
+/// Contexts and bookkeeping, guarded by the lock of [`InstanceContexts`]
+#[derive(Default)]
+struct ContextsState {
+    /// Context of each instance with at least one value
+    instances: HashMap<usize, InstanceContext>,
+    /// Instances host functions currently run on behalf of, innermost last
+    callers:   Vec<usize>,
+}
+
+/// Typed host state of each instance
+pub struct InstanceContexts {
+    /// Contexts and bookkeeping
+    state:    Mutex<ContextsState>,
+    /// Maximum number of values per instance
+    capacity: usize,
+}
+
+impl InstanceContexts {
+    /// Create contexts holding up to [`MAX_CONTEXT_VALUES`] values per
+    /// instance
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_CONTEXT_VALUES)
+    }
+
+    /// Create contexts holding up to `capacity` values per instance
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(ContextsState::default()),
+            capacity,
+        }
+    }
+
+    /// Store `value` in the context of `instance`, replacing a value of the
+    /// same type
+    ///
+    /// # Errors
+    ///
+    /// Returns a capacity error if the context is full.
+    pub fn insert<T: Any + Send>(&self, instance: usize, value: T) -> Result<()> {
+        let value: ContextValue = Arc::new(Mutex::new(value));
+        let mut state = self.state();
+        let context = state.instances.entry(instance).or_default();
+        let type_id = TypeId::of::<T>();
+        if let Some(slot) = context.values.iter_mut().find(|(id, _)| *id == type_id) {
+            slot.1 = value;
+            return Ok(());
+        }
+        if context.values.len() >= self.capacity {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Instance context is full",
+            ));
+        }
+        context.values.push((type_id, value));
+        Ok(())
+    }
+
+    /// Whether the context of `instance` holds a value of type `T`
+    pub fn contains<T: Any + Send>(&self, instance: usize) -> bool {
+        self.state()
+            .instances
+            .get(&instance)
+            .is_some_and(|context| context.get(TypeId::of::<T>()).is_some())
+    }
+
+    /// Remove the value of type `T` from the context of `instance`,
+    /// returning whether there was one
+    pub fn remove<T: Any + Send>(&self, instance: usize) -> bool {
+        let mut state = self.state();
+        let Some(context) = state.instances.get_mut(&instance) else {
+            return false;
+        };
+        let before = context.values.len();
+        context.values.retain(|(id, _)| *id != TypeId::of::<T>());
+        let removed = context.values.len() != before;
+        if context.is_empty() {
+            state.instances.remove(&instance);
+        }
+        removed
+    }
+
+    /// Run `f` on the value of type `T` of `instance`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has no value of type `T`, or the
+    /// value is already in use further up the call stack.
+    pub fn with<T: Any + Send, R>(&self, instance: usize, f: impl FnOnce(&mut T) -> R) -> Result<R> {
+        let value = self
+            .state()
+            .instances
+            .get(&instance)
+            .and_then(|context| context.get(TypeId::of::<T>()))
+            .cloned()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorCategory::Resource,
+                    codes::RESOURCE_NOT_FOUND,
+                    "No value of this type in instance context",
+                )
+            })?;
+
+        // The table lock is released, so `f` may use other values
+        let mut guard = match value.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                return Err(Error::new(
+                    ErrorCategory::Resource,
+                    codes::RESOURCE_ACCESS_ERROR,
+                    "Instance context value is already in use",
+                ))
+            },
+        };
+        let value = guard.downcast_mut::<T>().ok_or_else(|| {
+            Error::new(
+                ErrorCategory::Type,
+                codes::TYPE_MISMATCH,
+                "Instance context value has an unexpected type",
+            )
+        })?;
+        Ok(f(value))
+    }
+
+    /// Run `f` on the value of type `T` of the instance the current host
+    /// call runs on behalf of
+    ///
+    /// # Errors
+    ///
+    /// Returns an error outside of a host call made for an instance, and in
+    /// the cases of [`Self::with`].
+    pub fn with_current<T: Any + Send, R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R> {
+        let instance = self
+            .current_instance()
+            .ok_or_else(|| Error::runtime_error("No instance is calling a host function"))?;
+        self.with(instance, f)
+    }
+
+    /// Instance the current host call runs on behalf of
+    pub fn current_instance(&self) -> Option<usize> {
+        self.state().callers.last().copied()
+    }
+
+    /// Record that host functions now run on behalf of `instance`
+    pub fn enter_caller(&self, instance: usize) {
+        self.state().callers.push(instance);
+    }
+
+    /// Undo the latest [`Self::enter_caller`]
+    pub fn exit_caller(&self) {
+        self.state().callers.pop();
+    }
+
+    /// Remove and return the whole context of `instance`
+    pub fn take_instance(&self, instance: usize) -> Option<InstanceContext> {
+        self.state().instances.remove(&instance)
+    }
+
+    /// Make `context` the context of `instance`, replacing its current one
+    pub fn set_instance(&self, instance: usize, context: InstanceContext) {
+        if !context.is_empty() {
+            self.state().instances.insert(instance, context);
+        }
+    }
+
+    /// Drop the context of an instance that was discarded, returning the
+    /// number of values dropped
+    pub fn release_instance(&self, instance: usize) -> usize {
+        self.take_instance(instance).map_or(0, |context| context.len())
+    }
+
+    /// Number of values in the context of `instance`
+    pub fn len(&self, instance: usize) -> usize {
+        self.state().instances.get(&instance).map_or(0, InstanceContext::len)
+    }
+
+    /// Lock the contexts
+    ///
+    /// Contexts are only ever changed whole, so a poisoned lock is
+    /// recovered.
+    fn state(&self) -> MutexGuard<'_, ContextsState> {
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for InstanceContexts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for InstanceContexts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let state = self.state();
+        f.debug_struct("InstanceContexts")
+            .field("instances", &state.instances.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(u32);
+
+    #[test]
+    fn test_values_are_per_instance_and_typed() {
+        let contexts = InstanceContexts::with_capacity(2);
+        contexts.insert(1, Counter(10)).unwrap();
+        contexts.insert(2, Counter(20)).unwrap();
+        contexts.insert(1, String::from("one")).unwrap();
+        assert!(contexts.insert(1, 5u8).is_err());
+        contexts.insert(1, Counter(11)).unwrap();
+
+        assert_eq!(contexts.with(1, |c: &mut Counter| c.0).unwrap(), 11);
+        assert_eq!(contexts.with(2, |c: &mut Counter| c.0).unwrap(), 20);
+        assert!(contexts.with(2, |s: &mut String| s.len()).is_err());
+
+        assert!(contexts.remove::<Counter>(1));
+        assert!(!contexts.contains::<Counter>(1));
+        assert_eq!(contexts.release_instance(1), 1);
+        assert_eq!(contexts.len(1), 0);
+    }
+
+    #[test]
+    fn test_current_instance_follows_callers() {
+        let contexts = InstanceContexts::new();
+        contexts.insert(3, Counter(0)).unwrap();
+        contexts.insert(4, Counter(0)).unwrap();
+        assert!(contexts.with_current(|c: &mut Counter| c.0).is_err());
+
+        contexts.enter_caller(3);
+        contexts.with_current(|c: &mut Counter| c.0 += 1).unwrap();
+        contexts.enter_caller(4);
+        contexts.with_current(|c: &mut Counter| c.0 += 5).unwrap();
+
+        // A nested use of the same value fails instead of deadlocking
+        let nested = contexts
+            .with_current(|_: &mut Counter| contexts.with_current(|c: &mut Counter| c.0))
+            .unwrap();
+        assert!(nested.is_err());
+        contexts.exit_caller();
+        contexts.exit_caller();
+
+        assert_eq!(contexts.with(3, |c: &mut Counter| c.0).unwrap(), 1);
+        assert_eq!(contexts.with(4, |c: &mut Counter| c.0).unwrap(), 5);
+    }
+}
@@ -59,11 +59,29 @@
 pub mod memory_limits;
 
 // Export modules
+#[cfg(feature = "ara-com")]
+pub mod ara_com;
 pub mod builder;
 pub mod callback;
+#[cfg(feature = "can")]
+pub mod can;
+#[cfg(feature = "std")]
+pub mod contract;
+#[cfg(feature = "dds")]
+pub mod dds;
+#[cfg(feature = "std")]
+pub mod externref;
 pub mod function;
 pub mod host;
+#[cfg(feature = "std")]
+pub mod instance_context;
+#[cfg(feature = "std")]
+pub mod memo;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
 pub mod prelude;
+#[cfg(feature = "std")]
+pub mod proxy;
 
 // Agent C deliverables - Enhanced Host Integration
 /// Bounded host integration with memory constraints
@@ -89,16 +107,82 @@
     HostIntegrationStatistics,
     SimpleBoundedHostFunction,
 };
+#[cfg(feature = "ara-com")]
+pub use ara_com::{
+    AraComBinding,
+    ExportInvoker,
+    ServiceInterface,
+    ServiceMethod,
+    ServiceProxy,
+    ServiceSkeleton,
+};
 pub use builder::HostBuilder;
 pub use callback::{
     CallbackRegistry,
     CallbackType,
 };
+#[cfg(feature = "can")]
+pub use can::{
+    CanCapability,
+    CanHub,
+    CanPolicy,
+    TransmitRate,
+};
+#[cfg(feature = "std")]
+pub use contract::{
+    ContractAction,
+    ContractReport,
+    ContractViolation,
+    HostContract,
+};
+#[cfg(feature = "dds")]
+pub use dds::{
+    DdsBackend,
+    DdsCapability,
+    DdsQuota,
+    FieldType,
+    LoopbackBackend,
+    TopicAccess,
+    TopicGrant,
+    TopicType,
+};
+#[cfg(feature = "std")]
+pub use externref::{
+    HostObject,
+    HostObjectTable,
+};
 pub use function::{
     CloneableFn,
     HostFunctionHandler,
 };
 pub use host::BuiltinHost;
+#[cfg(feature = "std")]
+pub use instance_context::{
+    InstanceContext,
+    InstanceContexts,
+};
+#[cfg(feature = "std")]
+pub use memo::{
+    MemoCache,
+    MemoScope,
+    MemoStats,
+};
+#[cfg(feature = "mqtt")]
+pub use mqtt::{
+    ConnectOptions,
+    IoTransport,
+    MqttCapability,
+    MqttClient,
+    MqttMessage,
+    MqttPolicy,
+    MqttTransport,
+    QoS,
+};
+#[cfg(feature = "std")]
+pub use proxy::{
+    ProxyRoute,
+    WorldProxy,
+};
 // Re-export prelude for convenience
 pub use prelude::*;
 
@@ -0,0 +1,253 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Memoization of pure host function results.
+//!
+//! Host functions marked pure with
+//! [`CallbackRegistry::mark_pure`](crate::CallbackRegistry::mark_pure) always
+//! return the same results for the same arguments, so their results can be
+//! reused instead of calling the host again. [`MemoCache`] holds those
+//! results, keyed by function and argument hash, for either the current
+//! top-level call or the lifetime of the calling instance. The cache is
+//! bounded and evicts its oldest entry when full.
+
+use core::hash::{
+    Hash,
+    Hasher,
+};
+use std::{
+    collections::{
+        hash_map::DefaultHasher,
+        VecDeque,
+    },
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use crate::prelude::{
+    String,
+    ToString,
+    Value,
+    Vec,
+};
+
+/// Default number of results a [`MemoCache`] holds
+pub const MAX_MEMO_ENTRIES: usize = 64;
+
+/// How long memoized results of a pure host function are reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoScope {
+    /// Until the current top-level call into the guest returns
+    Call,
+    /// Until the calling instance is discarded
+    Instance,
+}
+
+/// Hit and miss counts of a [`MemoCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// Calls answered from the cache
+    pub hits:    u64,
+    /// Calls that reached the host function
+    pub misses:  u64,
+    /// Results currently cached
+    pub entries: usize,
+}
+
+/// One memoized call
+#[derive(Debug)]
+struct MemoEntry {
+    /// `module::function` key of the host function
+    function: String,
+    /// Instance the call was made from, if known
+    instance: Option<usize>,
+    /// Scope the entry belongs to
+    scope:    MemoScope,
+    /// Hash of `args`
+    hash:     u64,
+    /// Arguments of the call
+    args:     Vec<Value>,
+    /// Results of the call
+    results:  Vec<Value>,
+}
+
+/// Bounded cache of pure host function results
+#[derive(Debug)]
+pub struct MemoCache {
+    /// Entries, oldest first
+    entries:  VecDeque<MemoEntry>,
+    /// Maximum number of entries
+    capacity: usize,
+    /// Calls answered from the cache
+    hits:     AtomicU64,
+    /// Calls that reached the host function
+    misses:   AtomicU64,
+}
+
+impl MemoCache {
+    /// Create a cache holding up to [`MAX_MEMO_ENTRIES`] results
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_MEMO_ENTRIES)
+    }
+
+    /// Create a cache holding up to `capacity` results
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries:  VecDeque::with_capacity(capacity),
+            capacity,
+            hits:     AtomicU64::new(0),
+            misses:   AtomicU64::new(0),
+        }
+    }
+
+    /// Look up the results of an earlier identical call
+    ///
+    /// Argument hashes are confirmed by comparing the arguments, so values
+    /// that do not compare equal to themselves never hit.
+    pub fn lookup(
+        &self,
+        function: &str,
+        instance: Option<usize>,
+        args: &[Value],
+    ) -> Option<Vec<Value>> {
+        let hash = hash_args(args);
+        let found = self
+            .entries
+            .iter()
+            .find(|entry| {
+                entry.hash == hash
+                    && entry.instance == instance
+                    && entry.function == function
+                    && entry.args.as_slice() == args
+            })
+            .map(|entry| entry.results.clone());
+
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Remember the results of a call, evicting the oldest entry if full
+    pub fn insert(
+        &mut self,
+        function: &str,
+        instance: Option<usize>,
+        scope: MemoScope,
+        args: Vec<Value>,
+        results: Vec<Value>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(MemoEntry {
+            function: function.to_string(),
+            instance,
+            scope,
+            hash: hash_args(&args),
+            args,
+            results,
+        });
+    }
+
+    /// Drop the results memoized for the current call
+    pub fn end_call(&mut self) {
+        self.entries.retain(|entry| entry.scope != MemoScope::Call);
+    }
+
+    /// Drop the results memoized for an instance
+    pub fn clear_instance(&mut self, instance: usize) {
+        self.entries.retain(|entry| entry.instance != Some(instance));
+    }
+
+    /// Drop the results memoized for a function, e.g. after re-registering it
+    pub fn clear_function(&mut self, function: &str) {
+        self.entries.retain(|entry| entry.function != function);
+    }
+
+    /// Drop all memoized results
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of memoized results
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no results are memoized
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Hit and miss counts
+    #[must_use]
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits:    self.hits.load(Ordering::Relaxed),
+            misses:  self.misses.load(Ordering::Relaxed),
+            entries: self.entries.len(),
+        }
+    }
+}
+
+impl Default for MemoCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hash call arguments
+fn hash_args(args: &[Value]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    args.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::vec;
+
+    #[test]
+    fn test_memo_cache_scopes() {
+        let mut cache = MemoCache::new();
+        let args = vec![Value::I32(1)];
+        cache.insert("env::config", Some(0), MemoScope::Call, args.clone(), vec![Value::I64(7)]);
+        cache.insert("env::limit", Some(0), MemoScope::Instance, vec![], vec![Value::I32(3)]);
+
+        let hit = cache.lookup("env::config", Some(0), &args).unwrap();
+        assert!(matches!(hit.as_slice(), [Value::I64(7)]));
+        assert!(cache.lookup("env::config", Some(1), &args).is_none());
+        assert!(cache.lookup("env::config", Some(0), &[Value::I32(2)]).is_none());
+
+        cache.end_call();
+        assert!(cache.lookup("env::config", Some(0), &args).is_none());
+        assert!(cache.lookup("env::limit", Some(0), &[]).is_some());
+
+        cache.clear_instance(0);
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats(), MemoStats { hits: 2, misses: 3, entries: 0 });
+    }
+
+    #[test]
+    fn test_memo_cache_is_bounded() {
+        let mut cache = MemoCache::with_capacity(2);
+        for i in 0..3 {
+            cache.insert("env::f", None, MemoScope::Instance, vec![Value::I32(i)], vec![]);
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.lookup("env::f", None, &[Value::I32(0)]).is_none());
+        assert!(cache.lookup("env::f", None, &[Value::I32(2)]).is_some());
+    }
+}
@@ -0,0 +1,1255 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! MQTT client host capability (`wrt:mqtt`).
+//!
+//! [`MqttClient`] speaks MQTT 3.1.1 over an [`MqttTransport`], which the
+//! integrator provides: [`IoTransport`] adapts any `Read + Write` stream,
+//! such as a `TcpStream` or a TLS session on top of one, and embedded
+//! targets implement the trait for their socket stack.
+//!
+//! [`MqttCapability`] exposes one client to a component. The broker,
+//! credentials and client ID stay with the host; the component can only
+//! use the topics its [`MqttPolicy`] grants. The host functions are
+//! registered in [`MQTT_MODULE`] and take lifted component values:
+//!
+//! ```text
+//! connect: func()
+//! disconnect: func()
+//! publish: func(topic: string, payload: list<u8>, qos: u8, retain: bool)
+//! subscribe: func(filter: string, qos: u8) -> u8
+//! unsubscribe: func(filter: string)
+//! poll: func() -> option<message>   // record message { topic, payload }
+//! ```
+//!
+//! QoS 2 is not supported; subscriptions requesting more than the policy
+//! allows are downgraded, publishes are rejected.
+
+use std::{
+    collections::VecDeque,
+    io::{
+        ErrorKind,
+        Read,
+        Write,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::{
+    callback::CallbackRegistry,
+    prelude::{
+        codes,
+        vec,
+        Arc,
+        Error,
+        ErrorCategory,
+        HostFunctionHandler,
+        Result,
+        String,
+        ToString,
+        Value,
+        Vec,
+    },
+};
+
+/// Module the MQTT host functions are registered in
+pub const MQTT_MODULE: &str = "wrt:mqtt/client";
+
+/// Largest remaining length an MQTT packet can encode
+const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+/// Packet types of MQTT 3.1.1
+const CONNECT: u8 = 1;
+const CONNACK: u8 = 2;
+const PUBLISH: u8 = 3;
+const PUBACK: u8 = 4;
+const SUBSCRIBE: u8 = 8;
+const SUBACK: u8 = 9;
+const UNSUBSCRIBE: u8 = 10;
+const UNSUBACK: u8 = 11;
+const PINGREQ: u8 = 12;
+const PINGRESP: u8 = 13;
+const DISCONNECT: u8 = 14;
+
+/// Byte stream to an MQTT broker
+pub trait MqttTransport: Send {
+    /// Write all of `bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Read at least one byte into `buf`, blocking until one is available
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails or is closed
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Whether a byte can be read without blocking
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails or is closed
+    fn readable(&mut self) -> Result<bool>;
+}
+
+/// Transport over a `Read + Write` stream
+///
+/// The stream should be non-blocking or have a read timeout, which
+/// [`MqttTransport::readable`] uses to tell whether data has arrived.
+#[derive(Debug)]
+pub struct IoTransport<S> {
+    /// Underlying stream
+    stream:  S,
+    /// Byte read ahead by `readable`
+    pending: Option<u8>,
+}
+
+impl<S: Read + Write + Send> IoTransport<S> {
+    /// Wrap `stream`
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            pending: None,
+        }
+    }
+
+    /// Underlying stream
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+}
+
+impl<S: Read + Write + Send> MqttTransport for IoTransport<S> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.stream
+            .write_all(bytes)
+            .and_then(|()| self.stream.flush())
+            .map_err(|_| io_error())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if let Some(byte) = self.pending.take() {
+            buf[0] = byte;
+            return Ok(1);
+        }
+        loop {
+            match self.stream.read(buf) {
+                Ok(0) => return Err(connection_closed()),
+                Ok(read) => return Ok(read),
+                Err(err) if would_block(err.kind()) => continue,
+                Err(_) => return Err(io_error()),
+            }
+        }
+    }
+
+    fn readable(&mut self) -> Result<bool> {
+        if self.pending.is_some() {
+            return Ok(true);
+        }
+        let mut byte = [0u8; 1];
+        match self.stream.read(&mut byte) {
+            Ok(0) => Err(connection_closed()),
+            Ok(_) => {
+                self.pending = Some(byte[0]);
+                Ok(true)
+            },
+            Err(err) if would_block(err.kind()) => Ok(false),
+            Err(_) => Err(io_error()),
+        }
+    }
+}
+
+fn would_block(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+    )
+}
+
+/// Delivery guarantee of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QoS {
+    /// Delivered at most once, without acknowledgement
+    AtMostOnce = 0,
+    /// Delivered at least once, acknowledged by the receiver
+    AtLeastOnce = 1,
+}
+
+impl QoS {
+    /// QoS with level `level`, if supported
+    #[must_use]
+    pub fn from_level(level: u8) -> Option<Self> {
+        match level {
+            0 => Some(Self::AtMostOnce),
+            1 => Some(Self::AtLeastOnce),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters of the connection to the broker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Client identifier
+    pub client_id:     String,
+    /// Keep-alive interval in seconds, 0 to disable
+    pub keep_alive:    u16,
+    /// Discard the session state of earlier connections
+    pub clean_session: bool,
+    /// User name for authentication
+    pub username:      Option<String>,
+    /// Password for authentication
+    pub password:      Option<Vec<u8>>,
+}
+
+impl ConnectOptions {
+    /// Options for a clean session of `client_id` with a 60 s keep-alive
+    #[must_use]
+    pub fn new(client_id: &str) -> Self {
+        Self {
+            client_id:     client_id.to_string(),
+            keep_alive:    60,
+            clean_session: true,
+            username:      None,
+            password:      None,
+        }
+    }
+
+    /// Authenticate with `username` and `password`
+    #[must_use]
+    pub fn with_credentials(mut self, username: &str, password: &[u8]) -> Self {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_vec());
+        self
+    }
+}
+
+/// Message received on a subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+    /// Topic the message was published to
+    pub topic:   String,
+    /// Payload of the message
+    pub payload: Vec<u8>,
+    /// QoS the message was delivered with
+    pub qos:     QoS,
+    /// Whether the broker retained the message
+    pub retain:  bool,
+}
+
+/// Packet received from the broker
+#[derive(Debug)]
+enum Packet {
+    ConnAck {
+        return_code: u8,
+    },
+    Publish {
+        message:   MqttMessage,
+        packet_id: Option<u16>,
+    },
+    PubAck(u16),
+    SubAck {
+        packet_id:    u16,
+        return_codes: Vec<u8>,
+    },
+    UnsubAck(u16),
+    PingResp,
+}
+
+/// MQTT 3.1.1 client
+#[derive(Debug)]
+pub struct MqttClient<T> {
+    /// Connection to the broker
+    transport:       T,
+    /// Parameters of the connection
+    options:         ConnectOptions,
+    /// Whether the broker accepted the connection
+    connected:       bool,
+    /// Identifier of the next packet that needs one
+    next_packet_id:  u16,
+    /// Messages received while waiting for an acknowledgement
+    inbox:           VecDeque<MqttMessage>,
+    /// Largest packet accepted from the broker
+    max_packet_size: usize,
+}
+
+impl<T: MqttTransport> MqttClient<T> {
+    /// Create a client connecting with `options` over `transport`
+    pub fn new(transport: T, options: ConnectOptions) -> Self {
+        Self {
+            transport,
+            options,
+            connected: false,
+            next_packet_id: 1,
+            inbox: VecDeque::new(),
+            max_packet_size: 64 * 1024,
+        }
+    }
+
+    /// Reject packets from the broker larger than `max_packet_size` bytes
+    #[must_use]
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// Whether the broker accepted the connection
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Connect to the broker
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transport fails or the broker refuses the
+    /// connection
+    pub fn connect(&mut self) -> Result<()> {
+        let mut body = Vec::new();
+        put_str(&mut body, "MQTT")?;
+        body.push(4); // Protocol level 3.1.1
+        let mut flags = 0u8;
+        if self.options.username.is_some() {
+            flags |= 0x80;
+        }
+        if self.options.password.is_some() {
+            flags |= 0x40;
+        }
+        if self.options.clean_session {
+            flags |= 0x02;
+        }
+        body.push(flags);
+        body.extend_from_slice(&self.options.keep_alive.to_be_bytes());
+        put_str(&mut body, &self.options.client_id)?;
+        if let Some(username) = &self.options.username {
+            put_str(&mut body, username)?;
+        }
+        if let Some(password) = &self.options.password {
+            put_bytes(&mut body, password)?;
+        }
+        self.send(CONNECT << 4, &body)?;
+
+        match self.read_packet()? {
+            Packet::ConnAck { return_code: 0 } => {
+                self.connected = true;
+                Ok(())
+            },
+            Packet::ConnAck { .. } => Err(Error::new(
+                ErrorCategory::Security,
+                codes::ACCESS_DENIED,
+                "MQTT broker refused the connection",
+            )),
+            _ => Err(protocol_error("Expected CONNACK from MQTT broker")),
+        }
+    }
+
+    /// Disconnect from the broker
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transport fails
+    pub fn disconnect(&mut self) -> Result<()> {
+        if !self.connected {
+            return Ok(());
+        }
+        self.connected = false;
+        self.send(DISCONNECT << 4, &[])
+    }
+
+    /// Publish `payload` to `topic`, waiting for the acknowledgement of
+    /// QoS 1 messages
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected, the topic is
+    /// invalid or the transport fails
+    pub fn publish(&mut self, topic: &str, payload: &[u8], qos: QoS, retain: bool) -> Result<()> {
+        self.ensure_connected()?;
+        validate_topic(topic)?;
+        let mut body = Vec::with_capacity(topic.len() + payload.len() + 4);
+        put_str(&mut body, topic)?;
+        let packet_id = (qos == QoS::AtLeastOnce).then(|| self.packet_id());
+        if let Some(packet_id) = packet_id {
+            body.extend_from_slice(&packet_id.to_be_bytes());
+        }
+        body.extend_from_slice(payload);
+        self.send(PUBLISH << 4 | (qos as u8) << 1 | u8::from(retain), &body)?;
+
+        if let Some(packet_id) = packet_id {
+            self.await_ack(|packet| matches!(packet, Packet::PubAck(id) if *id == packet_id))?;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to `filter`, returning the QoS granted by the broker
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected, the filter is
+    /// invalid, the broker rejects the subscription or the transport fails
+    pub fn subscribe(&mut self, filter: &str, qos: QoS) -> Result<QoS> {
+        self.ensure_connected()?;
+        validate_filter(filter)?;
+        let packet_id = self.packet_id();
+        let mut body = packet_id.to_be_bytes().to_vec();
+        put_str(&mut body, filter)?;
+        body.push(qos as u8);
+        self.send(SUBSCRIBE << 4 | 0x02, &body)?;
+
+        let return_codes = match self.await_ack(
+            |packet| matches!(packet, Packet::SubAck { packet_id: id, .. } if *id == packet_id),
+        )? {
+            Packet::SubAck { return_codes, .. } => return_codes,
+            _ => unreachable!("await_ack returns the matching packet"),
+        };
+        match return_codes.as_slice() {
+            [code] => QoS::from_level((*code).min(qos as u8)).ok_or_else(|| {
+                Error::new(
+                    ErrorCategory::Security,
+                    codes::ACCESS_DENIED,
+                    "MQTT broker rejected the subscription",
+                )
+            }),
+            _ => Err(protocol_error("Malformed SUBACK from MQTT broker")),
+        }
+    }
+
+    /// Unsubscribe from `filter`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected or the transport
+    /// fails
+    pub fn unsubscribe(&mut self, filter: &str) -> Result<()> {
+        self.ensure_connected()?;
+        let packet_id = self.packet_id();
+        let mut body = packet_id.to_be_bytes().to_vec();
+        put_str(&mut body, filter)?;
+        self.send(UNSUBSCRIBE << 4 | 0x02, &body)?;
+        self.await_ack(|packet| matches!(packet, Packet::UnsubAck(id) if *id == packet_id))?;
+        Ok(())
+    }
+
+    /// Send a keep-alive ping
+    ///
+    /// The response is consumed by a later call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected or the transport
+    /// fails
+    pub fn ping(&mut self) -> Result<()> {
+        self.ensure_connected()?;
+        self.send(PINGREQ << 4, &[])
+    }
+
+    /// Next received message, without blocking if none has arrived
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected or the transport
+    /// fails
+    pub fn poll(&mut self) -> Result<Option<MqttMessage>> {
+        self.ensure_connected()?;
+        while self.inbox.is_empty() && self.transport.readable()? {
+            let packet = self.read_packet()?;
+            self.dispatch(packet)?;
+        }
+        Ok(self.inbox.pop_front())
+    }
+
+    fn ensure_connected(&self) -> Result<()> {
+        if self.connected {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorCategory::Validation,
+                codes::INVALID_STATE,
+                "MQTT client is not connected",
+            ))
+        }
+    }
+
+    fn packet_id(&mut self) -> u16 {
+        let id = self.next_packet_id;
+        self.next_packet_id = self.next_packet_id.checked_add(1).unwrap_or(1);
+        id
+    }
+
+    /// Read packets until one satisfies `is_ack`, queueing messages
+    fn await_ack(&mut self, is_ack: impl Fn(&Packet) -> bool) -> Result<Packet> {
+        loop {
+            let packet = self.read_packet()?;
+            if is_ack(&packet) {
+                return Ok(packet);
+            }
+            self.dispatch(packet)?;
+        }
+    }
+
+    /// Handle a packet that is not an awaited acknowledgement
+    fn dispatch(&mut self, packet: Packet) -> Result<()> {
+        match packet {
+            Packet::Publish { message, packet_id } => {
+                if let Some(packet_id) = packet_id {
+                    self.send(PUBACK << 4, &packet_id.to_be_bytes())?;
+                }
+                self.inbox.push_back(message);
+                Ok(())
+            },
+            Packet::PingResp => Ok(()),
+            _ => Err(protocol_error("Unexpected packet from MQTT broker")),
+        }
+    }
+
+    fn send(&mut self, header: u8, body: &[u8]) -> Result<()> {
+        if body.len() > MAX_REMAINING_LENGTH {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_LIMIT_EXCEEDED,
+                "MQTT packet too large",
+            ));
+        }
+        let mut packet = Vec::with_capacity(body.len() + 5);
+        packet.push(header);
+        let mut remaining = body.len();
+        loop {
+            let mut byte = (remaining % 128) as u8;
+            remaining /= 128;
+            if remaining > 0 {
+                byte |= 0x80;
+            }
+            packet.push(byte);
+            if remaining == 0 {
+                break;
+            }
+        }
+        packet.extend_from_slice(body);
+        self.transport.write_all(&packet)
+    }
+
+    fn read_packet(&mut self) -> Result<Packet> {
+        let header = self.read_byte()?;
+        let mut length = 0usize;
+        let mut multiplier = 1usize;
+        loop {
+            let byte = self.read_byte()?;
+            length += usize::from(byte & 0x7f) * multiplier;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            multiplier *= 128;
+            if multiplier > 128 * 128 * 128 {
+                return Err(protocol_error(
+                    "Malformed remaining length from MQTT broker",
+                ));
+            }
+        }
+        if length > self.max_packet_size {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_LIMIT_EXCEEDED,
+                "MQTT packet exceeds the maximum packet size",
+            ));
+        }
+        let mut body = vec![0u8; length];
+        let mut filled = 0;
+        while filled < length {
+            filled += self.transport.read(&mut body[filled..])?;
+        }
+        decode_packet(header, &body)
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = [0u8; 1];
+        self.transport.read(&mut byte)?;
+        Ok(byte[0])
+    }
+}
+
+fn decode_packet(header: u8, body: &[u8]) -> Result<Packet> {
+    let packet_id = |body: &[u8]| match body {
+        [high, low, ..] => Ok(u16::from_be_bytes([*high, *low])),
+        _ => Err(protocol_error("Truncated packet from MQTT broker")),
+    };
+    match header >> 4 {
+        CONNACK => match body {
+            [_, return_code] => Ok(Packet::ConnAck {
+                return_code: *return_code,
+            }),
+            _ => Err(protocol_error("Malformed CONNACK from MQTT broker")),
+        },
+        PUBLISH => {
+            let qos = QoS::from_level((header >> 1) & 0x03)
+                .ok_or_else(|| protocol_error("Unsupported QoS from MQTT broker"))?;
+            let topic_len = usize::from(packet_id(body)?);
+            let topic = body
+                .get(2..2 + topic_len)
+                .and_then(|topic| core::str::from_utf8(topic).ok())
+                .ok_or_else(|| protocol_error("Malformed PUBLISH from MQTT broker"))?;
+            let mut rest = &body[2 + topic_len..];
+            let packet_id = match qos {
+                QoS::AtMostOnce => None,
+                QoS::AtLeastOnce => {
+                    let id = packet_id(rest)?;
+                    rest = &rest[2..];
+                    Some(id)
+                },
+            };
+            Ok(Packet::Publish {
+                message: MqttMessage {
+                    topic: topic.to_string(),
+                    payload: rest.to_vec(),
+                    qos,
+                    retain: header & 0x01 != 0,
+                },
+                packet_id,
+            })
+        },
+        PUBACK => Ok(Packet::PubAck(packet_id(body)?)),
+        SUBACK => Ok(Packet::SubAck {
+            packet_id:    packet_id(body)?,
+            return_codes: body[2..].to_vec(),
+        }),
+        UNSUBACK => Ok(Packet::UnsubAck(packet_id(body)?)),
+        PINGRESP => Ok(Packet::PingResp),
+        _ => Err(protocol_error("Unsupported packet from MQTT broker")),
+    }
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) -> Result<()> {
+    put_bytes(buf, value.as_bytes())
+}
+
+fn put_bytes(buf: &mut Vec<u8>, value: &[u8]) -> Result<()> {
+    let len = u16::try_from(value.len()).map_err(|_| {
+        Error::new(
+            ErrorCategory::Validation,
+            codes::VALIDATION_ERROR,
+            "MQTT string longer than 65535 bytes",
+        )
+    })?;
+    buf.extend_from_slice(&len.to_be_bytes());
+    buf.extend_from_slice(value);
+    Ok(())
+}
+
+/// Check that `topic` is a valid topic name to publish to
+fn validate_topic(topic: &str) -> Result<()> {
+    if topic.is_empty() || topic.contains(['+', '#', '\0']) {
+        return Err(invalid_topic("Invalid MQTT topic name"));
+    }
+    Ok(())
+}
+
+/// Check that `filter` is a valid topic filter
+fn validate_filter(filter: &str) -> Result<()> {
+    let levels: Vec<&str> = filter.split('/').collect();
+    let valid = !filter.is_empty()
+        && !filter.contains('\0')
+        && levels.iter().enumerate().all(|(index, level)| match *level {
+            "#" => index == levels.len() - 1,
+            "+" => true,
+            level => !level.contains(['+', '#']),
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid_topic("Invalid MQTT topic filter"))
+    }
+}
+
+/// Whether `topic` matches `filter`
+#[must_use]
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    // Wildcards at the first level do not match system topics
+    if topic.starts_with('$') && filter.starts_with(['+', '#']) {
+        return false;
+    }
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {},
+            (level, Some(topic_level)) if level == topic_level => {},
+            _ => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+/// Whether every topic matched by `filter` is also matched by `allowed`
+#[must_use]
+pub fn filter_covers(allowed: &str, filter: &str) -> bool {
+    let mut filter_levels = filter.split('/');
+    for level in allowed.split('/') {
+        match (level, filter_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(filter_level)) if filter_level != "#" => {},
+            (level, Some(filter_level)) if level == filter_level && level != "#" => {},
+            _ => return false,
+        }
+    }
+    filter_levels.next().is_none()
+}
+
+/// Topics and limits a component is granted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttPolicy {
+    /// Filters of the topics the component may publish to
+    publish:           Vec<String>,
+    /// Filters the component may subscribe to, or narrower ones
+    subscribe:         Vec<String>,
+    /// Highest QoS the component may use
+    max_qos:           QoS,
+    /// Largest payload the component may publish
+    max_payload:       usize,
+    /// Subscriptions the component may hold at once
+    max_subscriptions: usize,
+}
+
+impl Default for MqttPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MqttPolicy {
+    /// Policy granting no topics, QoS 0, 4 KiB payloads and 8 subscriptions
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            publish:           Vec::new(),
+            subscribe:         Vec::new(),
+            max_qos:           QoS::AtMostOnce,
+            max_payload:       4096,
+            max_subscriptions: 8,
+        }
+    }
+
+    /// Allow publishing to the topics matched by `filter`
+    #[must_use]
+    pub fn allow_publish(mut self, filter: &str) -> Self {
+        self.publish.push(filter.to_string());
+        self
+    }
+
+    /// Allow subscribing to `filter` and filters it covers
+    #[must_use]
+    pub fn allow_subscribe(mut self, filter: &str) -> Self {
+        self.subscribe.push(filter.to_string());
+        self
+    }
+
+    /// Allow QoS levels up to `max_qos`
+    #[must_use]
+    pub fn with_max_qos(mut self, max_qos: QoS) -> Self {
+        self.max_qos = max_qos;
+        self
+    }
+
+    /// Allow payloads of up to `max_payload` bytes
+    #[must_use]
+    pub fn with_max_payload(mut self, max_payload: usize) -> Self {
+        self.max_payload = max_payload;
+        self
+    }
+
+    /// Allow up to `max_subscriptions` subscriptions at once
+    #[must_use]
+    pub fn with_max_subscriptions(mut self, max_subscriptions: usize) -> Self {
+        self.max_subscriptions = max_subscriptions;
+        self
+    }
+
+    /// Whether the component may publish to `topic`
+    #[must_use]
+    pub fn may_publish(&self, topic: &str) -> bool {
+        self.publish.iter().any(|allowed| topic_matches(allowed, topic))
+    }
+
+    /// Whether the component may subscribe to `filter`
+    #[must_use]
+    pub fn may_subscribe(&self, filter: &str) -> bool {
+        self.subscribe.iter().any(|allowed| filter_covers(allowed, filter))
+    }
+}
+
+/// State shared by the host functions of a capability
+#[derive(Debug)]
+struct MqttSession<T> {
+    /// Client owned by the host
+    client:        MqttClient<T>,
+    /// Filters the component subscribed to
+    subscriptions: Vec<String>,
+}
+
+/// Host capability granting a component scoped use of an MQTT client
+pub struct MqttCapability<T> {
+    /// Client and subscriptions
+    session: Arc<Mutex<MqttSession<T>>>,
+    /// Granted topics and limits
+    policy:  Arc<MqttPolicy>,
+}
+
+impl<T: MqttTransport + 'static> MqttCapability<T> {
+    /// Grant a component use of `client` within `policy`
+    pub fn new(client: MqttClient<T>, policy: MqttPolicy) -> Self {
+        Self {
+            session: Arc::new(Mutex::new(MqttSession {
+                client,
+                subscriptions: Vec::new(),
+            })),
+            policy:  Arc::new(policy),
+        }
+    }
+
+    /// Granted topics and limits
+    pub fn policy(&self) -> &MqttPolicy {
+        &self.policy
+    }
+
+    /// Filters the component is subscribed to
+    pub fn subscriptions(&self) -> Vec<String> {
+        lock(&self.session).subscriptions.clone()
+    }
+
+    /// Register the host functions with `registry`, returning their number
+    pub fn install(&self, registry: &mut CallbackRegistry) -> usize {
+        let session = Arc::clone(&self.session);
+        registry.register_host_function(
+            MQTT_MODULE,
+            "connect",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                expect_args(&args, 0)?;
+                let mut session = lock(&session);
+                if !session.client.is_connected() {
+                    session.client.connect()?;
+                }
+                Ok(Vec::new())
+            }),
+        );
+
+        let session = Arc::clone(&self.session);
+        registry.register_host_function(
+            MQTT_MODULE,
+            "disconnect",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                expect_args(&args, 0)?;
+                let mut session = lock(&session);
+                session.subscriptions.clear();
+                session.client.disconnect()?;
+                Ok(Vec::new())
+            }),
+        );
+
+        let (session, policy) = (Arc::clone(&self.session), Arc::clone(&self.policy));
+        registry.register_host_function(
+            MQTT_MODULE,
+            "publish",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let (topic, payload, qos, retain) = match args.as_slice() {
+                    [
+                        Value::String(topic),
+                        Value::List(payload),
+                        Value::U8(qos),
+                        Value::Bool(retain),
+                    ] => (topic, payload, *qos, *retain),
+                    _ => {
+                        return Err(type_mismatch(
+                            "MQTT publish called with the wrong arguments",
+                        ));
+                    },
+                };
+                if !policy.may_publish(topic) {
+                    return Err(access_denied("MQTT topic not granted for publishing"));
+                }
+                let qos = QoS::from_level(qos)
+                    .filter(|qos| *qos <= policy.max_qos)
+                    .ok_or_else(|| access_denied("MQTT QoS exceeds the granted limit"))?;
+                if payload.len() > policy.max_payload {
+                    return Err(Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_LIMIT_EXCEEDED,
+                        "MQTT payload exceeds the granted limit",
+                    ));
+                }
+                let payload = payload
+                    .iter()
+                    .map(|byte| match byte {
+                        Value::U8(byte) => Ok(*byte),
+                        _ => Err(type_mismatch("MQTT payload must be a list of u8")),
+                    })
+                    .collect::<Result<Vec<u8>>>()?;
+                lock(&session).client.publish(topic, &payload, qos, retain)?;
+                Ok(Vec::new())
+            }),
+        );
+
+        let (session, policy) = (Arc::clone(&self.session), Arc::clone(&self.policy));
+        registry.register_host_function(
+            MQTT_MODULE,
+            "subscribe",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let (filter, qos) = match args.as_slice() {
+                    [Value::String(filter), Value::U8(qos)] => (filter, *qos),
+                    _ => {
+                        return Err(type_mismatch(
+                            "MQTT subscribe called with the wrong arguments",
+                        ));
+                    },
+                };
+                validate_filter(filter)?;
+                if !policy.may_subscribe(filter) {
+                    return Err(access_denied(
+                        "MQTT topic filter not granted for subscribing",
+                    ));
+                }
+                let mut session = lock(&session);
+                let subscribed = session.subscriptions.iter().any(|s| s == filter);
+                if !subscribed && session.subscriptions.len() >= policy.max_subscriptions {
+                    return Err(Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_LIMIT_EXCEEDED,
+                        "MQTT subscription limit reached",
+                    ));
+                }
+                // Requests above the granted QoS are downgraded
+                let qos = QoS::from_level(qos).unwrap_or(QoS::AtLeastOnce).min(policy.max_qos);
+                let granted = session.client.subscribe(filter, qos)?;
+                if !subscribed {
+                    session.subscriptions.push(filter.clone());
+                }
+                Ok(vec![Value::U8(granted as u8)])
+            }),
+        );
+
+        let session = Arc::clone(&self.session);
+        registry.register_host_function(
+            MQTT_MODULE,
+            "unsubscribe",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                let [Value::String(filter)] = args.as_slice() else {
+                    return Err(type_mismatch(
+                        "MQTT unsubscribe called with the wrong arguments",
+                    ));
+                };
+                let mut session = lock(&session);
+                let index =
+                    session.subscriptions.iter().position(|s| s == filter).ok_or_else(|| {
+                        Error::new(
+                            ErrorCategory::Resource,
+                            codes::RESOURCE_NOT_FOUND,
+                            "Not subscribed to MQTT topic filter",
+                        )
+                    })?;
+                session.client.unsubscribe(filter)?;
+                session.subscriptions.remove(index);
+                Ok(Vec::new())
+            }),
+        );
+
+        let session = Arc::clone(&self.session);
+        registry.register_host_function(
+            MQTT_MODULE,
+            "poll",
+            HostFunctionHandler::new_with_args(move |_, args| {
+                expect_args(&args, 0)?;
+                let mut session = lock(&session);
+                // Messages of filters the component left are still in flight
+                // after unsubscribing and are dropped
+                let message = loop {
+                    match session.client.poll()? {
+                        Some(message)
+                            if session
+                                .subscriptions
+                                .iter()
+                                .any(|filter| topic_matches(filter, &message.topic)) =>
+                        {
+                            break Some(message);
+                        },
+                        Some(_) => {},
+                        None => break None,
+                    }
+                };
+                let message = message.map(|message| {
+                    Box::new(Value::Record(vec![
+                        ("topic".to_string(), Value::String(message.topic)),
+                        (
+                            "payload".to_string(),
+                            Value::List(message.payload.into_iter().map(Value::U8).collect()),
+                        ),
+                    ]))
+                });
+                Ok(vec![Value::Option(message)])
+            }),
+        );
+
+        6
+    }
+}
+
+impl<T> core::fmt::Debug for MqttCapability<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MqttCapability")
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+fn lock<T>(session: &Mutex<MqttSession<T>>) -> MutexGuard<'_, MqttSession<T>> {
+    session.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn expect_args(args: &[Value], count: usize) -> Result<()> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(type_mismatch(
+            "MQTT function called with the wrong arguments",
+        ))
+    }
+}
+
+fn type_mismatch(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Type, codes::TYPE_MISMATCH, message)
+}
+
+fn access_denied(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Security, codes::ACCESS_DENIED, message)
+}
+
+fn invalid_topic(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Validation, codes::VALIDATION_ERROR, message)
+}
+
+fn protocol_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Io, codes::IO_ERROR, message)
+}
+
+fn io_error() -> Error {
+    protocol_error("MQTT transport failed")
+}
+
+fn connection_closed() -> Error {
+    protocol_error("MQTT connection closed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Transport replaying scripted broker packets and recording writes
+    #[derive(Debug, Clone, Default)]
+    struct Scripted {
+        incoming: Arc<Mutex<VecDeque<u8>>>,
+        written:  Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Scripted {
+        fn push(&self, bytes: &[u8]) {
+            self.incoming.lock().unwrap().extend(bytes);
+        }
+
+        fn take_written(&self) -> Vec<u8> {
+            core::mem::take(&mut *self.written.lock().unwrap())
+        }
+    }
+
+    impl MqttTransport for Scripted {
+        fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+            self.written.lock().unwrap().extend_from_slice(bytes);
+            Ok(())
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let mut incoming = self.incoming.lock().unwrap();
+            let count = buf.len().min(incoming.len());
+            if count == 0 {
+                return Err(connection_closed());
+            }
+            for (slot, byte) in buf.iter_mut().zip(incoming.drain(..count)) {
+                *slot = byte;
+            }
+            Ok(count)
+        }
+
+        fn readable(&mut self) -> Result<bool> {
+            Ok(!self.incoming.lock().unwrap().is_empty())
+        }
+    }
+
+    const CONNACK_OK: [u8; 4] = [0x20, 0x02, 0x00, 0x00];
+
+    fn publish_packet(topic: &str, payload: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        put_str(&mut body, topic).unwrap();
+        body.extend_from_slice(payload);
+        let mut packet = vec![PUBLISH << 4, body.len() as u8];
+        packet.extend(body);
+        packet
+    }
+
+    fn call(
+        registry: &mut CallbackRegistry,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        registry.call_host_function(&mut (), MQTT_MODULE, function, args)
+    }
+
+    fn bytes(payload: &[u8]) -> Value {
+        Value::List(payload.iter().copied().map(Value::U8).collect())
+    }
+
+    #[test]
+    fn test_topic_filters() {
+        assert!(topic_matches("sensors/+/temp", "sensors/kitchen/temp"));
+        assert!(topic_matches("sensors/#", "sensors"));
+        assert!(topic_matches("sensors/#", "sensors/kitchen/temp"));
+        assert!(!topic_matches("sensors/+", "sensors/kitchen/temp"));
+        assert!(!topic_matches("#", "$SYS/uptime"));
+
+        assert!(filter_covers("cmd/#", "cmd/+/reboot"));
+        assert!(filter_covers("cmd/+/reboot", "cmd/node1/reboot"));
+        assert!(!filter_covers("cmd/+/reboot", "cmd/#"));
+        assert!(!filter_covers("cmd/node1", "cmd/+"));
+
+        assert!(validate_filter("a/+/#").is_ok());
+        assert!(validate_filter("a/#/b").is_err());
+        assert!(validate_filter("a/b+").is_err());
+        assert!(validate_topic("a/+").is_err());
+    }
+
+    #[test]
+    fn test_client_connect_publish_and_receive() {
+        let transport = Scripted::default();
+        let mut client = MqttClient::new(transport.clone(), ConnectOptions::new("wrt"));
+        assert!(client.publish("a", b"x", QoS::AtMostOnce, false).is_err());
+
+        transport.push(&CONNACK_OK);
+        client.connect().unwrap();
+        assert_eq!(
+            transport.take_written(),
+            [0x10, 0x0f, 0, 4, b'M', b'Q', b'T', b'T', 4, 0x02, 0, 60, 0, 3, b'w', b'r', b't']
+        );
+
+        // A message arriving before the PUBACK is queued
+        transport.push(&publish_packet("cmd/stop", b"now"));
+        transport.push(&[0x40, 0x02, 0x00, 0x01]);
+        client.publish("data/1", b"42", QoS::AtLeastOnce, true).unwrap();
+        assert_eq!(
+            transport.take_written(),
+            [0x33, 0x0c, 0, 6, b'd', b'a', b't', b'a', b'/', b'1', 0, 1, b'4', b'2']
+        );
+
+        let message = client.poll().unwrap().unwrap();
+        assert_eq!(message.topic, "cmd/stop");
+        assert_eq!(message.payload, b"now");
+        assert_eq!(client.poll().unwrap(), None);
+
+        let mut refused = MqttClient::new(transport.clone(), ConnectOptions::new("wrt"));
+        transport.push(&[0x20, 0x02, 0x00, 0x05]);
+        assert_eq!(refused.connect().unwrap_err().code, codes::ACCESS_DENIED);
+    }
+
+    #[test]
+    fn test_capability_scopes_topics_and_qos() {
+        let transport = Scripted::default();
+        let client = MqttClient::new(transport.clone(), ConnectOptions::new("edge-1"));
+        let policy = MqttPolicy::new()
+            .allow_publish("telemetry/edge-1/#")
+            .allow_subscribe("cmd/edge-1/#")
+            .with_max_payload(8)
+            .with_max_subscriptions(1);
+        let capability = MqttCapability::new(client, policy);
+        let mut registry = CallbackRegistry::new();
+        assert_eq!(capability.install(&mut registry), 6);
+
+        transport.push(&CONNACK_OK);
+        call(&mut registry, "connect", vec![]).unwrap();
+
+        let publish = |topic: &str, payload: &[u8], qos: u8| {
+            vec![
+                Value::String(topic.to_string()),
+                bytes(payload),
+                Value::U8(qos),
+                Value::Bool(false),
+            ]
+        };
+        call(
+            &mut registry,
+            "publish",
+            publish("telemetry/edge-1/temp", b"21", 0),
+        )
+        .unwrap();
+        let err = call(
+            &mut registry,
+            "publish",
+            publish("telemetry/edge-2/temp", b"21", 0),
+        );
+        assert_eq!(err.unwrap_err().code, codes::ACCESS_DENIED);
+        let err = call(
+            &mut registry,
+            "publish",
+            publish("telemetry/edge-1/temp", b"21", 1),
+        );
+        assert_eq!(err.unwrap_err().code, codes::ACCESS_DENIED);
+        let err = call(
+            &mut registry,
+            "publish",
+            publish("telemetry/edge-1/temp", b"too long!", 0),
+        );
+        assert_eq!(err.unwrap_err().code, codes::RESOURCE_LIMIT_EXCEEDED);
+
+        // QoS 1 is downgraded to the granted QoS 0
+        transport.take_written();
+        transport.push(&[0x90, 0x03, 0x00, 0x01, 0x00]);
+        let granted = call(
+            &mut registry,
+            "subscribe",
+            vec![Value::String("cmd/edge-1/+".to_string()), Value::U8(1)],
+        )
+        .unwrap();
+        assert!(matches!(granted.as_slice(), [Value::U8(0)]));
+        assert_eq!(transport.take_written().last(), Some(&0));
+        let err = call(
+            &mut registry,
+            "subscribe",
+            vec![Value::String("cmd/#".to_string()), Value::U8(0)],
+        );
+        assert_eq!(err.unwrap_err().code, codes::ACCESS_DENIED);
+
+        transport.push(&publish_packet("other/topic", b"leak"));
+        transport.push(&publish_packet("cmd/edge-1/reboot", b"1"));
+        let polled = call(&mut registry, "poll", vec![]).unwrap();
+        let [Value::Option(Some(message))] = polled.as_slice() else {
+            panic!("expected a message");
+        };
+        let Value::Record(fields) = message.as_ref() else {
+            panic!("expected a record");
+        };
+        assert!(matches!(&fields[0].1, Value::String(topic) if topic == "cmd/edge-1/reboot"));
+        let polled = call(&mut registry, "poll", vec![]).unwrap();
+        assert!(matches!(polled.as_slice(), [Value::Option(None)]));
+
+        transport.push(&[0xb0, 0x02, 0x00, 0x02]);
+        call(
+            &mut registry,
+            "unsubscribe",
+            vec![Value::String("cmd/edge-1/+".to_string())],
+        )
+        .unwrap();
+        assert!(capability.subscriptions().is_empty());
+    }
+}
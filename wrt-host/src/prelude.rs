@@ -92,99 +92,12 @@ fn deref_mut(&mut self) -> &mut Self::Target {
 }
 
 // Drop and Debug are automatically derived for our simple Box implementation
-pub use core::{
-    any::Any,
-    cmp::{
-        Eq,
-        Ord,
-        PartialEq,
-        PartialOrd,
-    },
-    convert::{
-        TryFrom,
-        TryInto,
-    },
-    fmt,
-    fmt::{
-        Debug,
-        Display,
-    },
-    marker::PhantomData,
-    mem,
-    ops::{
-        Deref,
-        DerefMut,
-    },
-    slice,
-    str,
-};
-// Re-export from std when the std feature is enabled
-#[cfg(feature = "std")]
-pub use alloc::{
-    boxed::Box,
-    string::{
-        String,
-        ToString,
-    },
-    sync::Arc,
-    vec,
-    vec::Vec,
-};
-
-#[cfg(feature = "std")]
-pub use core::fmt::Write as FmtWrite;
 
-#[cfg(feature = "std")]
-pub use std::{
-    collections::{
-        HashMap,
-        HashSet,
-    },
-    format,
-    sync::{
-        Mutex,
-        RwLock,
-    },
-};
+// Shared host-tier prelude
+pub use wrt_prelude::host::*;
 
-// Re-export from wrt-error
-pub use wrt_error::{
-    codes,
-    kinds,
-    Error,
-    ErrorCategory,
-    Result,
-};
-// Binary std/no_std choice
 #[cfg(feature = "std")]
-pub use wrt_foundation::component_value::ComponentValue;
-// Re-export from wrt-foundation
-pub use wrt_foundation::{
-    // Builtin types
-    builtin::BuiltinType,
-    // Memory allocation
-    safe_managed_alloc,
-    // SafeMemory types
-    safe_memory::{
-        SafeMemoryHandler,
-        SafeSlice,
-        SafeStack,
-    },
-    // Common types
-    types::{
-        BlockType,
-        FuncType,
-        GlobalType,
-        MemoryType,
-        TableType,
-        ValueType,
-    },
-    values::Value,
-    // Verification types
-    verification::VerificationLevel,
-    // CrateId for budget allocation
-    CrateId,
-};
+pub use core::fmt::Write as FmtWrite;
 // Binary std/no_std choice
 #[cfg(feature = "std")]
 pub use wrt_intercept::{
@@ -194,12 +107,6 @@ fn deref_mut(&mut self) -> &mut Self::Target {
     LinkInterceptor,
     LinkInterceptorStrategy,
 };
-// Import synchronization primitives for no_std
-#[cfg(not(feature = "std"))]
-pub use wrt_sync::{
-    Mutex,
-    RwLock,
-};
 
 /// Memory size for host function allocations in no_std mode
 ///
@@ -0,0 +1,211 @@
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! World proxies forwarding component imports to legacy host functions.
+//!
+//! A native plugin API is usually registered as flat host functions, such as
+//! `env::log`. Once the API is described by a WIT world, components import the
+//! same functions under their interface names, such as
+//! `example:plugin/logging@1.0.0::log`. A [`WorldProxy`] registers a handler
+//! for every function the world imports that forwards the call, unchanged, to
+//! the legacy host function. Legacy modules and components then share one
+//! implementation and one calling convention while the API is migrated.
+//!
+//! Proxies are generated from WIT by `cargo-wrt proxy`, which computes the
+//! number of core values each import is lowered to.
+
+use crate::{
+    callback::CallbackRegistry,
+    prelude::{
+        codes,
+        Error,
+        ErrorCategory,
+        HostFunctionHandler,
+        Result,
+        String,
+        ToString,
+        Vec,
+    },
+};
+
+/// Forwarding of one imported function to a legacy host function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyRoute {
+    /// Module the component imports the function from
+    pub import_module:   String,
+    /// Name the component imports the function under
+    pub import_function: String,
+    /// Legacy host function implementing the import
+    pub legacy_function: String,
+    /// Number of core values the import is called with
+    pub params:          usize,
+    /// Number of core values the import returns
+    pub results:         usize,
+}
+
+/// Forwarding of the imports of a WIT world to legacy host functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldProxy {
+    /// World the imports belong to
+    world:         String,
+    /// Module the legacy host functions are registered in
+    legacy_module: String,
+    /// Forwarded imports
+    routes:        Vec<ProxyRoute>,
+}
+
+impl WorldProxy {
+    /// Create a proxy for `world` forwarding to functions in `legacy_module`
+    #[must_use]
+    pub fn new(world: &str, legacy_module: &str) -> Self {
+        Self {
+            world:         world.to_string(),
+            legacy_module: legacy_module.to_string(),
+            routes:        Vec::new(),
+        }
+    }
+
+    /// Forward an import to `legacy_function`, which is called with `params`
+    /// core values and must return `results` core values
+    #[must_use]
+    pub fn route(
+        mut self,
+        import_module: &str,
+        import_function: &str,
+        legacy_function: &str,
+        params: usize,
+        results: usize,
+    ) -> Self {
+        self.routes.push(ProxyRoute {
+            import_module: import_module.to_string(),
+            import_function: import_function.to_string(),
+            legacy_function: legacy_function.to_string(),
+            params,
+            results,
+        });
+        self
+    }
+
+    /// World the imports belong to
+    #[must_use]
+    pub fn world(&self) -> &str {
+        &self.world
+    }
+
+    /// Module the legacy host functions are registered in
+    #[must_use]
+    pub fn legacy_module(&self) -> &str {
+        &self.legacy_module
+    }
+
+    /// Forwarded imports
+    #[must_use]
+    pub fn routes(&self) -> &[ProxyRoute] {
+        &self.routes
+    }
+
+    /// Imports whose legacy host function is not registered
+    #[must_use]
+    pub fn missing<'a>(&'a self, registry: &CallbackRegistry) -> Vec<&'a ProxyRoute> {
+        self.routes
+            .iter()
+            .filter(|route| !registry.has_host_function(&self.legacy_module, &route.legacy_function))
+            .collect()
+    }
+
+    /// Register a forwarding handler for every import
+    ///
+    /// Handlers forward to the legacy handler registered when the proxy is
+    /// installed, so install the proxy after the legacy functions. Calls with
+    /// the wrong number of values, in either direction, fail with a type
+    /// mismatch. Returns the number of registered handlers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a legacy host function is not registered, in which
+    /// case no handlers are registered
+    pub fn install(&self, registry: &mut CallbackRegistry) -> Result<usize> {
+        let mut handlers = Vec::with_capacity(self.routes.len());
+        for route in &self.routes {
+            let legacy = registry
+                .get_host_function(&self.legacy_module, &route.legacy_function)
+                .cloned()
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorCategory::Resource,
+                        codes::RESOURCE_NOT_FOUND,
+                        "Legacy host function for a world import is not registered",
+                    )
+                })?;
+            let (params, results) = (route.params, route.results);
+            handlers.push(HostFunctionHandler::new_with_args(move |target, args| {
+                if args.len() != params {
+                    return Err(arity_mismatch("World import called with the wrong arguments"));
+                }
+                let values = legacy.call(target, args)?;
+                if values.len() != results {
+                    return Err(arity_mismatch("Legacy host function returned the wrong results"));
+                }
+                Ok(values)
+            }));
+        }
+
+        for (route, handler) in self.routes.iter().zip(handlers) {
+            registry.register_host_function(&route.import_module, &route.import_function, handler);
+        }
+        Ok(self.routes.len())
+    }
+}
+
+/// Error for a call whose value count does not match the world
+fn arity_mismatch(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Type, codes::TYPE_MISMATCH, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        vec,
+        Value,
+    };
+
+    #[test]
+    fn test_world_proxy_forwards_to_legacy_function() {
+        let mut registry = CallbackRegistry::new();
+        let add = HostFunctionHandler::new_with_args(|_, args| match args.as_slice() {
+            [Value::I32(a), Value::I32(b)] => Ok(vec![Value::I32(a + b)]),
+            _ => Ok(vec![]),
+        });
+        registry.register_host_function("env", "add", add);
+
+        let proxy = WorldProxy::new("calculator", "env")
+            .route("example:calc/math@1.0.0", "add", "add", 2, 1)
+            .route("example:calc/math@1.0.0", "sub", "sub", 2, 1);
+        assert_eq!(proxy.missing(&registry).len(), 1);
+        assert!(proxy.install(&mut registry).is_err());
+        assert!(!registry.has_host_function("example:calc/math@1.0.0", "add"));
+
+        let proxy = WorldProxy::new("calculator", "env")
+            .route("example:calc/math@1.0.0", "add", "add", 2, 1)
+            .route("$root", "broken-add", "add", 1, 1);
+        assert_eq!(proxy.install(&mut registry).unwrap(), 2);
+
+        let mut engine = ();
+        let results = registry
+            .call_host_function(
+                &mut engine,
+                "example:calc/math@1.0.0",
+                "add",
+                vec![Value::I32(2), Value::I32(3)],
+            )
+            .unwrap();
+        assert!(matches!(results.as_slice(), [Value::I32(5)]));
+
+        let err = registry
+            .call_host_function(&mut engine, "$root", "broken-add", vec![Value::I32(1)])
+            .unwrap_err();
+        assert_eq!(err.code, codes::TYPE_MISMATCH);
+    }
+}
@@ -624,15 +624,12 @@ fn execute(&self, context: &mut T) -> Result<()> {
                 }
             },
             Self::BrTable { table, default } => {
-                // Use from_slice for unified interface across all feature configurations
                 #[cfg(feature = "std")]
-                let slice: &[u32] = table.as_slice();
+                let br_table = BrTable::from_slice(table, *default)?;
+                // u32 is stored unpacked, so there is no slice view to copy from
                 #[cfg(not(feature = "std"))]
-                let slice: &[u32] = table
-                    .as_slice()
-                    .map_err(|_| Error::runtime_error("Failed to get table slice"))?;
+                let br_table = BrTable::new_bounded(table.clone(), *default);
 
-                let br_table = BrTable::from_slice(slice, *default)?;
                 br_table.execute(context)
             },
             Self::Return => {
@@ -9,58 +9,10 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
-// Core imports for both std and no_std environments
-pub use core::{
-    any::Any,
-    cmp::{
-        Eq,
-        Ord,
-        PartialEq,
-        PartialOrd,
-    },
-    convert::{
-        TryFrom,
-        TryInto,
-    },
-    fmt,
-    fmt::{
-        Debug,
-        Display,
-    },
-    marker::PhantomData,
-    mem,
-    ops::{
-        Deref,
-        DerefMut,
-    },
-    slice,
-    str,
-};
-// Re-export from std when the std feature is enabled
-#[cfg(feature = "std")]
-pub use std::{
-    boxed::Box,
-    collections::{
-        HashMap,
-        HashSet,
-    },
-    format,
-    string::{
-        String,
-        ToString,
-    },
-    sync::Arc,
-    vec,
-    vec::Vec,
-};
-
-// BoundedVec available for both std and no_std modes
-pub use wrt_foundation::bounded::{
-    BoundedString,
-    BoundedVec,
-};
+// Shared runtime-tier prelude
+pub use wrt_prelude::runtime::*;
 
-// Type alias for Vec in no_std mode to match wrt-runtime behavior
+// Replaces the tier's `Vec` in no_std mode to match wrt-runtime behavior
 #[cfg(not(feature = "std"))]
 pub type Vec<T> = wrt_foundation::bounded::BoundedVec<T, 256, wrt_foundation::NoStdProvider<1024>>;
 
@@ -105,20 +57,9 @@ macro_rules! vec {
     };
 }
 
-// Re-export from wrt-error
-pub use wrt_error::{
-    codes,
-    kinds,
-    Error,
-    ErrorCategory,
-    Result,
-};
 // Re-export from wrt-foundation
 pub use wrt_foundation::{
     bounded::BoundedStack,
-    // Memory allocation macros
-    safe_managed_alloc,
-    CrateId,
     // SafeMemory types
     safe_memory::{
         NoStdMemoryProvider,
@@ -126,27 +67,14 @@ macro_rules! vec {
         SafeSlice,
         SafeStack,
     },
-    // Traits
-    traits::BoundedCapacity,
-    // Common types
-    types::{
-        BlockType,
-        FuncType,
-        GlobalType,
-        MemoryType,
-        RefType,
-        TableType,
-        ValueType,
-    },
+    types::RefType,
     values::{
         FloatBits32,
         FloatBits64,
-        Value,
     },
-    // Verification types
-    verification::VerificationLevel,
 };
-// Import synchronization primitives for both std and no_std
+// Replaces the tier's `Mutex`/`RwLock`: the crate uses the wrt-sync locks in
+// both std and no_std builds
 pub use wrt_sync::{
     WrtMutex as Mutex,
     WrtMutexGuard as MutexGuard,
@@ -30,6 +30,9 @@
 /// Maximum function name length
 pub const MAX_FUNCTION_NAME_LEN: usize = 128;
 
+/// Maximum number of strategies a `no_std` `LinkInterceptor` can hold
+pub const MAX_STRATEGIES: usize = 8;
+
 /// Bounded map for function stats
 pub type BoundedStatsMap = BoundedMap<
     BoundedString<MAX_FUNCTION_NAME_LEN>,
@@ -0,0 +1,53 @@
+//! Fuel consumed by guest code
+//!
+//! A [`FuelClock`] counts the fuel the engine charges for executed guest
+//! code. The engine advances it at every fuel checkpoint, and strategies
+//! that measure in fuel, such as the rate limits of a
+//! [`FirewallStrategy`](crate::strategies::FirewallStrategy), read it, so
+//! their limits are expressed in executed work rather than wall time.
+//!
+//! The clock is shared by reference: one clock can serve several engines
+//! and strategies, and clones of a strategy keep reading the same clock.
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+/// Total fuel consumed by guest code
+#[derive(Debug, Default)]
+pub struct FuelClock {
+    consumed: AtomicU64,
+}
+
+impl FuelClock {
+    /// Create a clock at zero
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            consumed: AtomicU64::new(0),
+        }
+    }
+
+    /// Record `fuel` more units of consumed fuel, saturating at `u64::MAX`
+    pub fn advance(&self, fuel: u64) {
+        // fetch_update never fails when the closure always returns Some
+        let _ = self.consumed.fetch_update(Ordering::AcqRel, Ordering::Acquire, |now| {
+            Some(now.saturating_add(fuel))
+        });
+    }
+
+    /// Total fuel consumed so far
+    #[must_use]
+    pub fn now(&self) -> u64 {
+        self.consumed.load(Ordering::Acquire)
+    }
+}
+
+/// Fuel clock handle held by the engine and strategies
+#[cfg(feature = "std")]
+pub type FuelClockRef = std::sync::Arc<FuelClock>;
+
+/// Fuel clock handle held by the engine and strategies (`no_std` version)
+#[cfg(not(feature = "std"))]
+pub type FuelClockRef = &'static FuelClock;
@@ -89,6 +89,22 @@
 // Built-in strategy implementations
 pub mod strategies;
 
+// Function patterns for scoping strategies
+pub mod scope;
+
+// Sampling of traced calls
+pub mod sampling;
+
+// Fuel clock shared by the engine and strategies
+pub mod fuel;
+
+// Usage analytics for the imports of a component
+#[cfg(feature = "std")]
+pub mod usage;
+
+// Stable C ABI for strategies and host functions loaded from plugins
+pub mod plugin_abi;
+
 // Include verification module conditionally, but exclude during coverage builds
 #[cfg(all(not(coverage), any(doc, feature = "kani")))]
 pub mod verify;
@@ -480,6 +496,17 @@ fn after_start(&self, _component_name: &str, _result_data: Option<&[u8]>) -> Res
     }
 }
 
+/// Shared handle to a strategy held by a `LinkInterceptor`
+///
+/// `no_std` builds cannot reference-count, so strategies live in statics.
+#[cfg(feature = "std")]
+pub type StrategyRef = Arc<dyn LinkInterceptorStrategy>;
+/// Shared handle to a strategy held by a `LinkInterceptor`
+///
+/// `no_std` builds cannot reference-count, so strategies live in statics.
+#[cfg(not(feature = "std"))]
+pub type StrategyRef = &'static dyn LinkInterceptorStrategy;
+
 /// Main interceptor to manage connections between components/host
 #[derive(Clone)]
 pub struct LinkInterceptor {
@@ -489,8 +516,24 @@ pub struct LinkInterceptor {
     #[cfg(not(feature = "std"))]
     name:           &'static str,
     /// Collection of strategies to apply
+    ///
+    /// Private so that the indices recorded in `scopes` stay valid; strategies
+    /// are only ever appended.
     #[cfg(feature = "std")]
-    pub strategies: Vec<Arc<dyn LinkInterceptorStrategy>>,
+    strategies:     Vec<Arc<dyn LinkInterceptorStrategy>>,
+    /// Collection of strategies to apply
+    #[cfg(not(feature = "std"))]
+    strategies:     StaticVec<StrategyRef, MAX_STRATEGIES>,
+    /// Patterns limiting which calls scoped strategies see
+    scopes:         StaticVec<StrategyScope, MAX_SCOPED_STRATEGIES>,
+}
+
+/// Pattern restricting the strategy at `strategy` in
+/// `LinkInterceptor::strategies`
+#[derive(Debug, Clone)]
+struct StrategyScope {
+    strategy: usize,
+    pattern:  FunctionPattern,
 }
 
 impl LinkInterceptor {
@@ -513,9 +556,21 @@ pub fn new(name: &str) -> Self {
             name:                               "default",
             #[cfg(feature = "std")]
             strategies:                         Vec::new(),
+            #[cfg(not(feature = "std"))]
+            strategies:                         StaticVec::new(),
+            scopes:                             StaticVec::new(),
         }
     }
 
+    /// Strategies of this interceptor, in the order they are applied
+    #[must_use]
+    pub fn strategies(&self) -> &[StrategyRef] {
+        #[cfg(feature = "std")]
+        return &self.strategies;
+        #[cfg(not(feature = "std"))]
+        return self.strategies.as_slice();
+    }
+
     /// Adds a strategy to this interceptor
     ///
     /// Strategies are applied in the order they are added.
@@ -528,6 +583,89 @@ pub fn add_strategy(&mut self, strategy: Arc<dyn LinkInterceptorStrategy>) {
         self.strategies.push(strategy);
     }
 
+    /// Adds a strategy to this interceptor
+    ///
+    /// Strategies are applied in the order they are added.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to add
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`MAX_STRATEGIES`] strategies have already been
+    /// added.
+    #[cfg(not(feature = "std"))]
+    pub fn add_strategy(&mut self, strategy: StrategyRef) -> Result<()> {
+        self.strategies.push(strategy).map_err(|_| too_many_strategies())
+    }
+
+    /// Adds a strategy that only runs for calls matching `pattern`
+    ///
+    /// The pattern is matched against the call target, or against the
+    /// target and function when written as `target#function`; see
+    /// [`FunctionPattern`]. Scoped strategies keep their position in the
+    /// chain relative to unscoped ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Glob selecting the calls the strategy sees, e.g.
+    ///   `"wasi:filesystem/*"`
+    /// * `strategy` - The strategy to add
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is too long or
+    /// [`MAX_SCOPED_STRATEGIES`] scoped strategies have already been added.
+    /// In `no_std` builds it also fails once [`MAX_STRATEGIES`] strategies
+    /// have been added.
+    pub fn add_strategy_for(&mut self, pattern: &str, strategy: StrategyRef) -> Result<()> {
+        #[cfg(not(feature = "std"))]
+        if self.strategies.is_full() {
+            return Err(too_many_strategies());
+        }
+
+        let scope = StrategyScope {
+            strategy: self.strategies.len(),
+            pattern:  FunctionPattern::new(pattern)?,
+        };
+        self.scopes.push(scope).map_err(|_| {
+            Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Too many scoped strategies",
+            )
+        })?;
+        #[cfg(feature = "std")]
+        self.strategies.push(strategy);
+        #[cfg(not(feature = "std"))]
+        self.add_strategy(strategy)?;
+        Ok(())
+    }
+
+    /// Whether the strategy at `index` applies to a call to `function` on
+    /// `target`
+    fn strategy_applies(&self, index: usize, target: &str, function: &str) -> bool {
+        self.scopes
+            .iter()
+            .find(|scope| scope.strategy == index)
+            .is_none_or(|scope| scope.pattern.matches(target, function))
+    }
+
+    /// Strategies applying to a call to `function` on `target`, in chain
+    /// order
+    fn strategies_for<'a>(
+        &'a self,
+        target: &'a str,
+        function: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a StrategyRef> + 'a {
+        self.strategies
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| self.strategy_applies(*index, target, function))
+            .map(|(_, strategy)| strategy)
+    }
+
     /// Intercepts a function call
     ///
     /// This method applies all strategies in sequence, potentially
@@ -559,10 +697,53 @@ pub fn intercept_call<F>(
     where
         F: FnOnce(Vec<Value>) -> Result<Vec<Value>>,
     {
-        let mut modified_args = args.to_vec();
+        self.run_strategies(target, function, args, args.to_vec(), call_fn)
+    }
+
+    /// Intercepts a function call, taking ownership of the arguments
+    ///
+    /// Behaves like [`Self::intercept_call`], but hands `args` to `call_fn`
+    /// without copying them when no strategy applies to the call. Callers on
+    /// a hot path that already own their argument vector avoid one
+    /// allocation per call this way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if interception or function call fails
+    #[cfg(feature = "std")]
+    pub fn intercept_call_owned<F>(
+        &self,
+        target: &str,
+        function: &str,
+        args: Vec<Value>,
+        call_fn: F,
+    ) -> Result<Vec<Value>>
+    where
+        F: FnOnce(Vec<Value>) -> Result<Vec<Value>>,
+    {
+        if self.strategies_for(target, function).next().is_none() {
+            return call_fn(args);
+        }
+        // after_call strategies see the arguments as passed by the caller
+        let original = args.clone();
+        self.run_strategies(target, function, &original, args, call_fn)
+    }
 
+    /// Apply the strategies for a call around `call_fn`
+    #[cfg(feature = "std")]
+    fn run_strategies<F>(
+        &self,
+        target: &str,
+        function: &str,
+        args: &[Value],
+        mut modified_args: Vec<Value>,
+        call_fn: F,
+    ) -> Result<Vec<Value>>
+    where
+        F: FnOnce(Vec<Value>) -> Result<Vec<Value>>,
+    {
         // Apply before_call interceptors
-        for strategy in &self.strategies {
+        for strategy in self.strategies_for(target, function) {
             modified_args = strategy.before_call(&self.name, target, function, &modified_args)?;
 
             // Early return if strategy bypasses execution
@@ -575,13 +756,176 @@ pub fn intercept_call<F>(
         let mut result = call_fn(modified_args);
 
         // Apply after_call interceptors in reverse order
-        for strategy in self.strategies.iter().rev() {
+        for strategy in self.strategies_for(target, function).rev() {
             result = strategy.after_call(&self.name, target, function, args, result);
         }
 
         result
     }
 
+    /// Intercepts a function call
+    ///
+    /// `no_std` strategies observe calls but cannot rewrite arguments or
+    /// results; `call_fn` hands results back to the caller through its own
+    /// captures. A strategy returning an error from `before_call` (such as a
+    /// firewall denial) stops the call before `call_fn` runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Identifier of the target component or host
+    /// * `function` - Name of the function being called
+    /// * `args` - Arguments to the function
+    /// * `call_fn` - Function that performs the actual call
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if interception or function call fails
+    #[cfg(not(feature = "std"))]
+    pub fn intercept_call<F>(
+        &self,
+        target: &str,
+        function: &str,
+        args: &[Value],
+        call_fn: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&[Value]) -> Result<()>,
+    {
+        // Apply before_call interceptors
+        for strategy in self.strategies_for(target, function) {
+            strategy.before_call(self.name, target, function, args)?;
+
+            // Early return if strategy bypasses execution
+            if strategy.should_bypass() {
+                return Ok(());
+            }
+        }
+
+        // Execute the actual call
+        let mut result = call_fn(args);
+
+        // Apply after_call interceptors in reverse order
+        for strategy in self.strategies_for(target, function).rev() {
+            result = strategy.after_call(self.name, target, function, args, result);
+        }
+
+        result
+    }
+
+    /// Intercepts a canonical resource operation
+    ///
+    /// Strategies see the operation in the order they were added, scoped or
+    /// not, and the first one to handle it decides the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The resource handle
+    /// * `operation` - The operation being performed
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Vec<u8>>>` - Serialized result of the first strategy
+    ///   handling the operation, None if it should proceed normally
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of a strategy, which vetoes the operation
+    #[cfg(feature = "std")]
+    pub fn intercept_resource_operation(
+        &self,
+        handle: u32,
+        operation: &ResourceCanonicalOperation,
+    ) -> Result<Option<Vec<u8>>> {
+        for strategy in &self.strategies {
+            if let Some(result) = strategy.intercept_resource_operation(handle, operation)? {
+                return Ok(Some(result));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether a strategy intercepts canonical lifts and lowers of a call to
+    /// `function` on `target`
+    ///
+    /// Lets callers skip preparing the bytes for [`Self::intercept_lift`] and
+    /// [`Self::intercept_lower`] when no strategy would see them.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn intercepts_canonical(&self, target: &str, function: &str) -> bool {
+        self.strategies_for(target, function)
+            .any(|strategy| strategy.should_intercept_canonical())
+    }
+
+    /// Intercepts the canonical lift of the values passed to `function` on
+    /// `target`
+    ///
+    /// Strategies applying to the call that intercept canonical operations
+    /// see the lift in chain order, and the first one to handle it decides
+    /// the lifted bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Vec<u8>>>` - Serialized value of the first strategy
+    ///   handling the lift, None if it should proceed normally
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of a strategy, which vetoes the lift
+    #[cfg(feature = "std")]
+    pub fn intercept_lift(
+        &self,
+        target: &str,
+        function: &str,
+        ty: &ValType<wrt_foundation::NoStdProvider<64>>,
+        addr: u32,
+        memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        for strategy in self.strategies_for(target, function) {
+            if !strategy.should_intercept_canonical() {
+                continue;
+            }
+            if let Some(bytes) = strategy.intercept_lift(ty, addr, memory_bytes)? {
+                return Ok(Some(bytes));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Intercepts the canonical lower of the values returned by `function`
+    /// on `target`
+    ///
+    /// Strategies applying to the call that intercept canonical operations
+    /// see the lower in chain order until one handles it by writing the
+    /// value to `memory_bytes`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - True if a strategy handled the lower, false if it
+    ///   should proceed normally
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of a strategy, which vetoes the lower
+    #[cfg(feature = "std")]
+    pub fn intercept_lower(
+        &self,
+        target: &str,
+        function: &str,
+        value_type: &ValType<wrt_foundation::NoStdProvider<64>>,
+        value_data: &[u8],
+        addr: u32,
+        memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        for strategy in self.strategies_for(target, function) {
+            if strategy.should_intercept_canonical()
+                && strategy.intercept_lower(value_type, value_data, addr, memory_bytes)?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// Gets the name of this interceptor
     ///
     /// # Returns
@@ -610,6 +954,16 @@ pub fn get_strategy(&self) -> Option<&dyn LinkInterceptorStrategy> {
         self.strategies.first().map(std::convert::AsRef::as_ref)
     }
 
+    /// Gets the first strategy in this interceptor
+    ///
+    /// This is a convenience method for accessing the primary strategy.
+    /// If multiple strategies are present, only the first one is returned.
+    #[cfg(not(feature = "std"))]
+    #[must_use]
+    pub fn get_strategy(&self) -> Option<&dyn LinkInterceptorStrategy> {
+        self.strategies.first().copied()
+    }
+
     /// Processes results after interception
     ///
     /// # Arguments
@@ -640,8 +994,8 @@ pub fn post_intercept(
             modifications: Vec::new(),
         };
 
-        // Process with each strategy
-        for strategy in &self.strategies {
+        // Process with each strategy in scope
+        for strategy in self.strategies_for(component_name, func_name) {
             // Apply strategy-specific post-processing
             if let Some(modifications) =
                 strategy.process_results(component_name, func_name, args, results)?
@@ -724,6 +1078,16 @@ pub fn apply_modifications(
     }
 }
 
+/// Error for adding a strategy to a full `no_std` interceptor
+#[cfg(not(feature = "std"))]
+fn too_many_strategies() -> Error {
+    Error::new(
+        ErrorCategory::Capacity,
+        codes::CAPACITY_EXCEEDED,
+        "Too many interceptor strategies",
+    )
+}
+
 /// Result of an interception operation
 #[derive(Debug, Clone)]
 pub struct InterceptionResult {
@@ -742,13 +1106,27 @@ pub struct InterceptionResult {
 }
 
 /// Modification to apply to serialized data
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[derive(Default)]
+///
+/// The encoding is a one byte tag followed by the fields of the variant; the
+/// tags are pinned so modifications stored in bounded collections keep
+/// decoding if variants are added.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Default,
+    wrt_foundation::Checksummable,
+    wrt_foundation::ToBytes,
+    wrt_foundation::FromBytes,
+)]
 pub enum Modification {
     /// No modification (default)
     #[default]
+    #[wrt(tag = 0)]
     None,
     /// Replace data at an offset
+    #[wrt(tag = 1)]
     Replace {
         /// Byte offset where the replacement starts
         offset: usize,
@@ -764,6 +1142,7 @@ pub enum Modification {
         >,
     },
     /// Insert data at an offset
+    #[wrt(tag = 2)]
     Insert {
         /// Byte offset where to insert data
         offset: usize,
@@ -779,6 +1158,7 @@ pub enum Modification {
         >,
     },
     /// Remove data at an offset with a given length
+    #[wrt(tag = 3)]
     Remove {
         /// Byte offset where to start removing data
         offset: usize,
@@ -787,200 +1167,6 @@ pub enum Modification {
     },
 }
 
-
-// Implement required traits for BoundedVec compatibility
-impl wrt_foundation::traits::Checksummable for Modification {
-    fn update_checksum(&self, checksum: &mut wrt_foundation::verification::Checksum) {
-        match self {
-            Modification::None => {
-                checksum.update_slice(&[0u8]);
-            },
-            Modification::Replace { offset, data } => {
-                checksum.update_slice(&offset.to_le_bytes());
-                checksum.update_slice(&[1u8]); // tag for Replace variant
-                #[cfg(feature = "std")]
-                checksum.update_slice(data);
-                #[cfg(not(feature = "std"))]
-                for i in 0..data.len() {
-                    if let Ok(byte) = data.get(i) {
-                        checksum.update_slice(&[byte]);
-                    }
-                }
-            },
-            Modification::Insert { offset, data } => {
-                checksum.update_slice(&offset.to_le_bytes());
-                checksum.update_slice(&[2u8]); // tag for Insert variant
-                #[cfg(feature = "std")]
-                checksum.update_slice(data);
-                #[cfg(not(feature = "std"))]
-                for i in 0..data.len() {
-                    if let Ok(byte) = data.get(i) {
-                        checksum.update_slice(&[byte]);
-                    }
-                }
-            },
-            Modification::Remove { offset, length } => {
-                checksum.update_slice(&offset.to_le_bytes());
-                checksum.update_slice(&length.to_le_bytes());
-                checksum.update_slice(&[3u8]); // tag for Remove variant
-            },
-        }
-    }
-}
-
-impl wrt_foundation::traits::ToBytes for Modification {
-    fn serialized_size(&self) -> usize {
-        match self {
-            Modification::None => 1, // Just the tag
-            Modification::Replace { data, .. } | Modification::Insert { data, .. } => {
-                1 + 8 + 4 + data.len() // tag + offset + length + data
-            },
-            Modification::Remove { .. } => {
-                1 + 8 + 8 // tag + offset + length
-            },
-        }
-    }
-
-    fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
-        &self,
-        writer: &mut wrt_foundation::traits::WriteStream<'_>,
-        _provider: &P,
-    ) -> wrt_error::Result<()> {
-        match self {
-            Modification::None => {
-                writer.write_u8(0)?; // Tag for None
-            },
-            Modification::Replace { offset, data } => {
-                writer.write_u8(1)?; // Tag for Replace
-                writer.write_usize_le(*offset)?;
-                #[allow(clippy::cast_possible_truncation)]
-                writer.write_u32_le(data.len() as u32)?;
-                #[cfg(feature = "std")]
-                writer.write_all(data)?;
-                #[cfg(not(feature = "std"))]
-                for i in 0..data.len() {
-                    if let Ok(byte) = data.get(i) {
-                        writer.write_u8(byte)?;
-                    }
-                }
-            },
-            Modification::Insert { offset, data } => {
-                writer.write_u8(2)?; // Tag for Insert
-                writer.write_usize_le(*offset)?;
-                #[allow(clippy::cast_possible_truncation)]
-                writer.write_u32_le(data.len() as u32)?;
-                #[cfg(feature = "std")]
-                writer.write_all(data)?;
-                #[cfg(not(feature = "std"))]
-                for i in 0..data.len() {
-                    if let Ok(byte) = data.get(i) {
-                        writer.write_u8(byte)?;
-                    }
-                }
-            },
-            Modification::Remove { offset, length } => {
-                writer.write_u8(3)?; // Tag for Remove
-                writer.write_usize_le(*offset)?;
-                writer.write_usize_le(*length)?;
-            },
-        }
-        Ok(())
-    }
-}
-
-impl wrt_foundation::traits::FromBytes for Modification {
-    fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
-        reader: &mut wrt_foundation::traits::ReadStream<'_>,
-        _provider: &P,
-    ) -> wrt_error::Result<Self> {
-        use wrt_error::Error;
-
-        let tag = reader.read_u8()?;
-
-        match tag {
-            0 => Ok(Modification::None),
-            1 => {
-                // Replace variant
-                let offset = reader.read_usize_le()?;
-                let data_len = reader.read_u32_le()? as usize;
-
-                #[cfg(feature = "std")]
-                let data = {
-                    let mut vec = Vec::with_capacity(data_len);
-                    for _ in 0..data_len {
-                        vec.push(reader.read_u8()?);
-                    }
-                    vec
-                };
-                #[cfg(not(feature = "std"))]
-                let data = {
-                    use wrt_foundation::{
-                        bounded::BoundedVec,
-                        budget_aware_provider::CrateId,
-                        safe_managed_alloc,
-                    };
-                    let provider = safe_managed_alloc!(4096, CrateId::Intercept).map_err(|_| {
-                        Error::memory_error("Failed to allocate for modification data")
-                    })?;
-                    let mut bounded_data = BoundedVec::new(provider)
-                        .map_err(|_| Error::memory_error("Failed to create bounded vec"))?;
-                    for _ in 0..data_len {
-                        bounded_data
-                            .push(reader.read_u8()?)
-                            .map_err(|_| Error::memory_error("Failed to push to bounded vec"))?;
-                    }
-                    bounded_data
-                };
-
-                Ok(Modification::Replace { offset, data })
-            },
-            2 => {
-                // Insert variant
-                let offset = reader.read_usize_le()?;
-                let data_len = reader.read_u32_le()? as usize;
-
-                #[cfg(feature = "std")]
-                let data = {
-                    let mut vec = Vec::with_capacity(data_len);
-                    for _ in 0..data_len {
-                        vec.push(reader.read_u8()?);
-                    }
-                    vec
-                };
-                #[cfg(not(feature = "std"))]
-                let data = {
-                    use wrt_foundation::{
-                        bounded::BoundedVec,
-                        budget_aware_provider::CrateId,
-                        safe_managed_alloc,
-                    };
-                    let provider = safe_managed_alloc!(4096, CrateId::Intercept).map_err(|_| {
-                        Error::memory_error("Failed to allocate for modification data")
-                    })?;
-                    let mut bounded_data = BoundedVec::new(provider)
-                        .map_err(|_| Error::memory_error("Failed to create bounded vec"))?;
-                    for _ in 0..data_len {
-                        bounded_data
-                            .push(reader.read_u8()?)
-                            .map_err(|_| Error::memory_error("Failed to push to bounded vec"))?;
-                    }
-                    bounded_data
-                };
-
-                Ok(Modification::Insert { offset, data })
-            },
-            3 => {
-                // Remove variant
-                let offset = reader.read_usize_le()?;
-                let length = reader.read_usize_le()?;
-
-                Ok(Modification::Remove { offset, length })
-            },
-            _ => Err(Error::parse_error("Unknown Modification variant")),
-        }
-    }
-}
-
 #[cfg(feature = "std")]
 impl std::fmt::Debug for LinkInterceptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -994,7 +1180,10 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 #[cfg(not(feature = "std"))]
 impl core::fmt::Debug for LinkInterceptor {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("LinkInterceptor").field("name", &self.name).finish()
+        f.debug_struct("LinkInterceptor")
+            .field("name", &self.name)
+            .field("strategies_count", &self.strategies.len())
+            .finish()
     }
 }
 
@@ -1127,6 +1316,112 @@ fn test_interceptor_modify_result() {
         assert_eq!(result.unwrap(), vec![Value::I32(99)]);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_interceptor_owned_args() {
+        let interceptor = LinkInterceptor::new("test");
+        let args = vec![Value::I32(10)];
+        let ptr = args.as_ptr();
+        let result = interceptor.intercept_call_owned("target", "func", args, |args| {
+            // Without strategies the caller's vector is passed through
+            assert_eq!(args.as_ptr(), ptr);
+            Ok(args)
+        });
+        assert_eq!(result.unwrap(), vec![Value::I32(10)]);
+
+        let mut interceptor = LinkInterceptor::new("test");
+        interceptor.add_strategy(Arc::new(TestStrategy {
+            bypass:        false,
+            modify_args:   true,
+            modify_result: true,
+        }));
+        let args = vec![Value::I32(10)];
+        let result = interceptor.intercept_call_owned("target", "func", args, |args| {
+            assert_eq!(args, vec![Value::I32(42)]);
+            Ok(args)
+        });
+        assert_eq!(result.unwrap(), vec![Value::I32(99)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_canonical_hooks_follow_scope() {
+        struct Canonical;
+
+        impl LinkInterceptorStrategy for Canonical {
+            fn before_call(
+                &self,
+                _source: &str,
+                _target: &str,
+                _function: &str,
+                args: &[Value],
+            ) -> Result<Vec<Value>> {
+                Ok(args.to_vec())
+            }
+
+            fn after_call(
+                &self,
+                _source: &str,
+                _target: &str,
+                _function: &str,
+                _args: &[Value],
+                result: Result<Vec<Value>>,
+            ) -> Result<Vec<Value>> {
+                result
+            }
+
+            fn should_intercept_canonical(&self) -> bool {
+                true
+            }
+
+            fn intercept_lift(
+                &self,
+                _ty: &ValType<wrt_foundation::NoStdProvider<64>>,
+                addr: u32,
+                memory_bytes: &[u8],
+            ) -> Result<Option<Vec<u8>>> {
+                Ok(Some(memory_bytes[addr as usize..].iter().rev().copied().collect()))
+            }
+
+            fn intercept_lower(
+                &self,
+                _value_type: &ValType<wrt_foundation::NoStdProvider<64>>,
+                value_data: &[u8],
+                addr: u32,
+                memory_bytes: &mut [u8],
+            ) -> Result<bool> {
+                memory_bytes[addr as usize..][..value_data.len()].copy_from_slice(value_data);
+                Ok(true)
+            }
+
+            fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+                Arc::new(Canonical)
+            }
+        }
+
+        let mut interceptor = LinkInterceptor::new("test");
+        interceptor.add_strategy(Arc::new(TestStrategy {
+            bypass:        false,
+            modify_args:   false,
+            modify_result: false,
+        }));
+        interceptor.add_strategy_for("host#log", Arc::new(Canonical)).unwrap();
+
+        let ty = ValType::U8;
+        assert!(interceptor.intercepts_canonical("host", "log"));
+        assert!(!interceptor.intercepts_canonical("host", "read"));
+        assert_eq!(
+            interceptor.intercept_lift("host", "log", &ty, 1, &[1, 2, 3]).unwrap(),
+            Some(vec![3, 2])
+        );
+        assert_eq!(interceptor.intercept_lift("host", "read", &ty, 1, &[1, 2, 3]).unwrap(), None);
+
+        let mut memory = [0u8; 4];
+        assert!(interceptor.intercept_lower("host", "log", &ty, &[7, 8], 2, &mut memory).unwrap());
+        assert_eq!(memory, [0, 0, 7, 8]);
+        assert!(!interceptor.intercept_lower("host", "read", &ty, &[9], 0, &mut memory).unwrap());
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_interceptor_bypass() {
@@ -1146,6 +1441,58 @@ fn test_interceptor_bypass() {
         assert_eq!(result.unwrap(), vec![Value::I32(42)]);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_scoped_strategy() {
+        let strategy = Arc::new(TestStrategy {
+            bypass:        false,
+            modify_args:   false,
+            modify_result: true,
+        });
+
+        let mut interceptor = LinkInterceptor::new("test");
+        interceptor.add_strategy_for("wasi:filesystem/*", strategy).unwrap();
+
+        let result = interceptor
+            .intercept_call("wasi:filesystem/types", "read", &[], |_| Ok(vec![Value::I32(20)]));
+        assert_eq!(result.unwrap(), vec![Value::I32(99)]);
+
+        let result =
+            interceptor.intercept_call("wasi:cli/stdout", "write", &[], |_| Ok(vec![Value::I32(20)]));
+        assert_eq!(result.unwrap(), vec![Value::I32(20)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_scoped_strategy_keeps_chain_order() {
+        let modify_args = Arc::new(TestStrategy {
+            bypass:        false,
+            modify_args:   true,
+            modify_result: false,
+        });
+        let bypass = Arc::new(TestStrategy {
+            bypass:        true,
+            modify_args:   false,
+            modify_result: false,
+        });
+
+        let mut interceptor = LinkInterceptor::new("test");
+        interceptor.add_strategy_for("host#cached_*", bypass).unwrap();
+        interceptor.add_strategy(modify_args);
+
+        // The scoped bypass runs first for matching functions only
+        let result = interceptor.intercept_call("host", "cached_get", &[Value::I32(10)], |_| {
+            panic!("This should not be called");
+        });
+        assert_eq!(result.unwrap(), vec![Value::I32(10)]);
+
+        let result = interceptor.intercept_call("host", "get", &[Value::I32(10)], |args| {
+            assert_eq!(args, vec![Value::I32(42)]);
+            Ok(args)
+        });
+        assert_eq!(result.unwrap(), vec![Value::I32(42)]);
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_multiple_strategies() {
@@ -1172,6 +1519,187 @@ fn test_multiple_strategies() {
 
         assert_eq!(result.unwrap(), vec![Value::I32(99)]);
     }
+
+    /// Strategy counting the calls it sees, for `no_std` tests
+    #[cfg(not(feature = "std"))]
+    struct CountingStrategy {
+        before: core::sync::atomic::AtomicU32,
+        after:  core::sync::atomic::AtomicU32,
+        bypass: bool,
+    }
+
+    #[cfg(not(feature = "std"))]
+    impl CountingStrategy {
+        const fn new(bypass: bool) -> Self {
+            Self {
+                before: core::sync::atomic::AtomicU32::new(0),
+                after: core::sync::atomic::AtomicU32::new(0),
+                bypass,
+            }
+        }
+
+        fn counts(&self) -> (u32, u32) {
+            use core::sync::atomic::Ordering;
+            (self.before.load(Ordering::Relaxed), self.after.load(Ordering::Relaxed))
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    impl LinkInterceptorStrategy for CountingStrategy {
+        fn before_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[Value],
+        ) -> Result<()> {
+            self.before.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn after_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[Value],
+            result: Result<()>,
+        ) -> Result<()> {
+            self.after.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            result
+        }
+
+        fn should_bypass(&self) -> bool {
+            self.bypass
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn test_no_std_interceptor_runs_strategies() {
+        static COUNTER: CountingStrategy = CountingStrategy::new(false);
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy(&COUNTER).unwrap();
+
+        let mut calls = 0;
+        interceptor
+            .intercept_call("host", "add", &[Value::I32(1)], |args| {
+                assert_eq!(args, [Value::I32(1)]);
+                calls += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(calls, 1);
+        assert_eq!(COUNTER.counts(), (1, 1));
+        assert!(interceptor.get_strategy().is_some());
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn test_no_std_interceptor_bypass_and_scope() {
+        static BYPASS: CountingStrategy = CountingStrategy::new(true);
+        static SCOPED: CountingStrategy = CountingStrategy::new(false);
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy_for("wasi:sockets/*", &SCOPED).unwrap();
+        interceptor.add_strategy(&BYPASS).unwrap();
+
+        interceptor
+            .intercept_call("host", "add", &[], |_| panic!("bypassed call must not run"))
+            .unwrap();
+        assert_eq!(SCOPED.counts(), (0, 0));
+        assert_eq!(BYPASS.counts(), (1, 0));
+
+        interceptor
+            .intercept_call("wasi:sockets/tcp", "connect", &[], |_| unreachable!())
+            .unwrap();
+        assert_eq!(SCOPED.counts(), (1, 0));
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn test_no_std_interceptor_firewall_and_capacity() {
+        use crate::strategies::{
+            FirewallBuilder,
+            FirewallRule,
+        };
+
+        let firewall = FirewallBuilder::new()
+            .with_default_allow(true)
+            .with_rule(FirewallRule::DenyTarget("wasi:sockets/tcp"))
+            .build()
+            .unwrap();
+        let firewall: &'static _ = alloc::boxed::Box::leak(alloc::boxed::Box::new(firewall));
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy(firewall).unwrap();
+
+        let denied = interceptor.intercept_call("wasi:sockets/tcp", "connect", &[], |_| {
+            panic!("denied call must not run")
+        });
+        assert!(denied.is_err());
+        assert!(interceptor.intercept_call("host", "add", &[], |_| Ok(())).is_ok());
+
+        static FILLER: CountingStrategy = CountingStrategy::new(false);
+        for _ in 1..MAX_STRATEGIES {
+            interceptor.add_strategy(&FILLER).unwrap();
+        }
+        assert!(interceptor.add_strategy(&FILLER).is_err());
+        assert!(interceptor.add_strategy_for("host", &FILLER).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_modification_encoding_round_trip() {
+        use wrt_foundation::{
+            safe_managed_alloc,
+            safe_memory::{
+                Slice,
+                SliceMut,
+            },
+            traits::{
+                FromBytes,
+                ReadStream,
+                ToBytes,
+                WriteStream,
+            },
+            CrateId,
+        };
+
+        let provider = safe_managed_alloc!(64, CrateId::Intercept).unwrap();
+        let replace = Modification::Replace {
+            offset: 3,
+            data:   vec![0xAA, 0xBB],
+        };
+        let mut buffer = [0u8; 32];
+        let written = {
+            let mut writer = WriteStream::new(SliceMut::new(&mut buffer).unwrap());
+            replace.to_bytes_with_provider(&mut writer, &provider).unwrap();
+            writer.position()
+        };
+        assert_eq!(written, replace.serialized_size());
+
+        // Tag, offset, data length and data, as before the derive
+        let mut expected = vec![1u8];
+        expected.extend_from_slice(&3usize.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&[0xAA, 0xBB]);
+        assert_eq!(&buffer[..written], expected.as_slice());
+
+        let mut reader = ReadStream::new(Slice::new(&buffer[..written]).unwrap());
+        assert_eq!(Modification::from_bytes_with_provider(&mut reader, &provider).unwrap(), replace);
+
+        let remove = Modification::Remove {
+            offset: 1,
+            length: 4,
+        };
+        let written = {
+            let mut writer = WriteStream::new(SliceMut::new(&mut buffer).unwrap());
+            remove.to_bytes_with_provider(&mut writer, &provider).unwrap();
+            writer.position()
+        };
+        assert_eq!(buffer[0], 3);
+        let mut reader = ReadStream::new(Slice::new(&buffer[..written]).unwrap());
+        assert_eq!(Modification::from_bytes_with_provider(&mut reader, &provider).unwrap(), remove);
+    }
 }
 
 // Panic handler disabled to avoid conflicts with other crates
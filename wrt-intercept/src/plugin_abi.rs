@@ -0,0 +1,258 @@
+//! Stable C ABI for runtime-loaded plugins
+//!
+//! Interceptor strategies and host capability providers can be shipped as
+//! separate shared objects and registered into a running engine. A plugin
+//! exports one function under [`PLUGIN_ENTRY_SYMBOL`] with the
+//! [`PluginEntryFn`] signature, returning a [`PluginDescriptor`] that lists
+//! its interceptors and host functions. The loader lives in `wrt-runtime`
+//! behind the `plugins` feature.
+//!
+//! Only the types in this module cross the plugin boundary. They are
+//! `#[repr(C)]`, hold no Rust-specific types, and are versioned by
+//! [`PLUGIN_ABI_VERSION`]. The loader refuses descriptors whose ABI version
+//! or descriptor size differ from its own, so a plugin must be rebuilt
+//! against a matching version of this module.
+//!
+//! # Plugin contract
+//!
+//! - All pointers in the descriptor, including strings, stay valid until
+//!   the descriptor's `unload` callback has returned.
+//! - Callbacks may be called from any thread, concurrently, and must not
+//!   unwind across the boundary.
+//! - Callbacks return [`PLUGIN_OK`] on success. Any other status denies the
+//!   intercepted call or fails the host function.
+//! - Only numeric values cross the boundary. Other values are passed as
+//!   [`PLUGIN_VALUE_UNSUPPORTED`] and may not be returned.
+//!
+//! # Sandboxing
+//!
+//! A plugin runs with the full privileges of the host process: it is not
+//! isolated the way a WebAssembly module is. Load plugins only from
+//! locations writable by trusted users, pin them by checksum where the
+//! deployment allows it, and do not load plugins into engines configured
+//! for ASIL-B or above. Functionality that does not need native code is
+//! better shipped as a WebAssembly component.
+
+use core::ffi::c_void;
+
+use wrt_foundation::{
+    float_repr::{
+        FloatBits32,
+        FloatBits64,
+    },
+    values::Value,
+};
+
+/// Version of the plugin ABI described by this module
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol under which a plugin exports its [`PluginEntryFn`]
+pub const PLUGIN_ENTRY_SYMBOL: &str = "wrt_plugin_entry";
+
+/// Status returned by plugin callbacks on success
+pub const PLUGIN_OK: i32 = 0;
+
+/// Maximum number of interceptors a plugin may declare
+pub const MAX_PLUGIN_INTERCEPTORS: usize = 16;
+
+/// Maximum number of host functions a plugin may declare
+pub const MAX_PLUGIN_HOST_FUNCTIONS: usize = 256;
+
+/// Maximum number of results a plugin host function may return
+pub const MAX_PLUGIN_RESULTS: usize = 16;
+
+/// Maximum length of a name passed across the plugin boundary
+pub const MAX_PLUGIN_NAME_LENGTH: usize = 256;
+
+/// [`PluginValue::kind`] of a 32-bit integer
+pub const PLUGIN_VALUE_I32: u32 = 0;
+/// [`PluginValue::kind`] of a 64-bit integer
+pub const PLUGIN_VALUE_I64: u32 = 1;
+/// [`PluginValue::kind`] of a 32-bit float, stored as its bit pattern
+pub const PLUGIN_VALUE_F32: u32 = 2;
+/// [`PluginValue::kind`] of a 64-bit float, stored as its bit pattern
+pub const PLUGIN_VALUE_F64: u32 = 3;
+/// [`PluginValue::kind`] of a value that cannot cross the boundary
+pub const PLUGIN_VALUE_UNSUPPORTED: u32 = 0xFF;
+
+/// Entry point exported by a plugin
+///
+/// Receives the loader's [`PLUGIN_ABI_VERSION`] and returns the plugin's
+/// descriptor, or null if the plugin does not support that version.
+pub type PluginEntryFn = unsafe extern "C" fn(host_abi_version: u32) -> *const PluginDescriptor;
+
+/// Interceptor callback run before a call
+///
+/// Returning a status other than [`PLUGIN_OK`] denies the call.
+pub type PluginBeforeCallFn = unsafe extern "C" fn(
+    context: *mut c_void,
+    source: PluginStr,
+    target: PluginStr,
+    function: PluginStr,
+    args: *const PluginValue,
+    arg_count: usize,
+) -> i32;
+
+/// Interceptor callback run after a call
+///
+/// `call_status` is [`PLUGIN_OK`] if the call succeeded, in which case
+/// `results` holds its results. Returning a status other than
+/// [`PLUGIN_OK`] fails the call.
+pub type PluginAfterCallFn = unsafe extern "C" fn(
+    context: *mut c_void,
+    source: PluginStr,
+    target: PluginStr,
+    function: PluginStr,
+    results: *const PluginValue,
+    result_count: usize,
+    call_status: i32,
+) -> i32;
+
+/// Host function implemented by a plugin
+///
+/// Writes at most `result_capacity` results to `results` and their number
+/// to `result_count`.
+pub type PluginHostCallFn = unsafe extern "C" fn(
+    context: *mut c_void,
+    args: *const PluginValue,
+    arg_count: usize,
+    results: *mut PluginValue,
+    result_capacity: usize,
+    result_count: *mut usize,
+) -> i32;
+
+/// Borrowed UTF-8 string crossing the plugin boundary
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginStr {
+    /// First byte of the string
+    pub ptr: *const u8,
+    /// Length of the string in bytes
+    pub len: usize,
+}
+
+impl PluginStr {
+    /// Borrow a string for the lifetime of the program
+    pub const fn from_static(s: &'static str) -> Self {
+        Self { ptr: s.as_ptr(), len: s.len() }
+    }
+
+    /// Borrow a string for the duration of a callback
+    pub const fn borrowed(s: &str) -> Self {
+        Self { ptr: s.as_ptr(), len: s.len() }
+    }
+}
+
+/// Numeric value crossing the plugin boundary
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginValue {
+    /// One of the `PLUGIN_VALUE_*` kinds
+    pub kind: u32,
+    /// The value, zero-extended to 64 bits
+    pub bits: u64,
+}
+
+impl PluginValue {
+    /// Convert a runtime value for a plugin
+    pub fn from_value(value: &Value) -> Self {
+        match value {
+            Value::I32(v) => Self { kind: PLUGIN_VALUE_I32, bits: u64::from(*v as u32) },
+            Value::I64(v) => Self { kind: PLUGIN_VALUE_I64, bits: *v as u64 },
+            Value::F32(v) => Self { kind: PLUGIN_VALUE_F32, bits: u64::from(v.to_bits()) },
+            Value::F64(v) => Self { kind: PLUGIN_VALUE_F64, bits: v.to_bits() },
+            _ => Self { kind: PLUGIN_VALUE_UNSUPPORTED, bits: 0 },
+        }
+    }
+
+    /// Convert a value returned by a plugin, or `None` for an unknown kind
+    pub fn to_value(self) -> Option<Value> {
+        match self.kind {
+            PLUGIN_VALUE_I32 => Some(Value::I32(self.bits as u32 as i32)),
+            PLUGIN_VALUE_I64 => Some(Value::I64(self.bits as i64)),
+            PLUGIN_VALUE_F32 => Some(Value::F32(FloatBits32::from_bits(self.bits as u32))),
+            PLUGIN_VALUE_F64 => Some(Value::F64(FloatBits64::from_bits(self.bits))),
+            _ => None,
+        }
+    }
+}
+
+/// Interceptor strategy provided by a plugin
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginInterceptor {
+    /// Name of the strategy
+    pub name:        PluginStr,
+    /// Opaque state passed to the callbacks
+    pub context:     *mut c_void,
+    /// Callback run before each call, if any
+    pub before_call: Option<PluginBeforeCallFn>,
+    /// Callback run after each call, if any
+    pub after_call:  Option<PluginAfterCallFn>,
+}
+
+/// Host function provided by a plugin
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginHostFunction {
+    /// Module name the function is imported from
+    pub module:  PluginStr,
+    /// Function name
+    pub name:    PluginStr,
+    /// Opaque state passed to the function
+    pub context: *mut c_void,
+    /// The function
+    pub call:    Option<PluginHostCallFn>,
+}
+
+/// Everything a plugin provides, returned by its [`PluginEntryFn`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginDescriptor {
+    /// [`PLUGIN_ABI_VERSION`] the plugin was built against
+    pub abi_version:         u32,
+    /// `size_of::<PluginDescriptor>()` as seen by the plugin
+    pub descriptor_size:     u32,
+    /// Name of the plugin
+    pub name:                PluginStr,
+    /// Version of the plugin
+    pub version:             PluginStr,
+    /// Interceptor strategies
+    pub interceptors:        *const PluginInterceptor,
+    /// Number of interceptor strategies
+    pub interceptor_count:   usize,
+    /// Host functions
+    pub host_functions:      *const PluginHostFunction,
+    /// Number of host functions
+    pub host_function_count: usize,
+    /// Called once before the plugin is unloaded, if any
+    pub unload:              Option<unsafe extern "C" fn()>,
+}
+
+impl PluginDescriptor {
+    /// Size of the descriptor in this version of the ABI
+    pub const SIZE: u32 = core::mem::size_of::<Self>() as u32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_value_round_trip() {
+        let values = [
+            Value::I32(-7),
+            Value::I64(i64::MIN),
+            Value::F32(FloatBits32::from_bits(0x7fc0_0001)),
+            Value::F64(FloatBits64::from_bits(0x4009_21fb_5444_2d18)),
+        ];
+        for value in &values {
+            assert_eq!(PluginValue::from_value(value).to_value().as_ref(), Some(value));
+        }
+
+        assert_eq!(PluginValue::from_value(&Value::I32(-1)).bits, 0xffff_ffff);
+        let unsupported = PluginValue::from_value(&Value::Ref(3));
+        assert_eq!(unsupported.kind, PLUGIN_VALUE_UNSUPPORTED);
+        assert_eq!(unsupported.to_value(), None);
+    }
+}
@@ -5,98 +5,11 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
+// Shared host-tier prelude
+pub use wrt_prelude::host::*;
 // Binary std/no_std choice
-pub use core::{
-    any::Any,
-    cmp::{
-        Eq,
-        Ord,
-        PartialEq,
-        PartialOrd,
-    },
-    convert::{
-        TryFrom,
-        TryInto,
-    },
-    fmt,
-    fmt::{
-        Debug,
-        Display,
-    },
-    marker::PhantomData,
-    mem,
-    ops::{
-        Deref,
-        DerefMut,
-    },
-    slice,
-    str,
-};
-// Re-export from std when the std feature is enabled
-#[cfg(feature = "std")]
-pub use std::{
-    boxed::Box,
-    collections::{
-        HashMap,
-        HashSet,
-    },
-    format,
-    string::{
-        String,
-        ToString,
-    },
-    sync::{
-        Arc,
-        Mutex,
-        RwLock,
-    },
-    vec,
-    vec::Vec,
-};
-
-// Re-export from wrt-error
-pub use wrt_error::{
-    codes,
-    kinds,
-    Error,
-    ErrorCategory,
-    Result,
-};
-#[cfg(feature = "std")]
-pub use wrt_foundation::component_value::ComponentValue;
-// Re-export from wrt-foundation (for component model)
-#[cfg(feature = "std")]
-pub use wrt_foundation::component_value::ValType;
-// Binary std/no_std choice
-// BoundedVec already imported above
 #[cfg(not(feature = "std"))]
 pub use wrt_foundation::BoundedMap as BoundedHashMap;
-// Re-export from wrt-foundation
-pub use wrt_foundation::{
-    builtin::BuiltinType,
-    resource::ResourceCanonicalOperation,
-    // SafeMemory types
-    safe_memory::{
-        SafeMemoryHandler,
-        SafeSlice,
-        SafeStack,
-    },
-    // Core types
-    values::Value,
-};
-// no_std alternatives using bounded collections
-#[cfg(not(feature = "std"))]
-pub use wrt_foundation::{
-    BoundedCapacity,
-    BoundedString,
-    BoundedVec,
-};
-// Import synchronization primitives for no_std
-#[cfg(not(feature = "std"))]
-pub use wrt_sync::{
-    Mutex,
-    RwLock,
-};
 
 // Export BeforeBuiltinResult for both std and no_std
 pub use crate::builtins::BeforeBuiltinResult;
@@ -108,17 +21,45 @@
 };
 // Conditional imports
 #[cfg(feature = "std")]
-pub use crate::strategies::StatisticsStrategy;
+pub use crate::strategies::{
+    JsonLinesExporter,
+    PolicyStrategy,
+    RecordingStrategy,
+    ReplayStrategy,
+    StatisticsStrategy,
+};
 // Re-export from this crate
 pub use crate::{
+    // Strategy storage capacity in no_std builds
+    bounded_intercept::MAX_STRATEGIES,
     // Builtin interceptors
     builtins::InterceptContext,
+    // Fuel accounting
+    fuel::{
+        FuelClock,
+        FuelClockRef,
+    },
+    // Trace sampling
+    sampling::{
+        TraceSampler,
+        MAX_SAMPLING_OVERRIDES,
+    },
+    // Strategy scoping
+    scope::{
+        FunctionPattern,
+        MAX_SCOPED_STRATEGIES,
+    },
     // Strategies
     strategies::{
+        FirewallBuilder,
         FirewallConfig,
+        FirewallDenial,
         FirewallRule,
         FirewallStrategy,
         LoggingStrategy,
+        RingBufferExporter,
+        SpanExporter,
+        TracingStrategy,
     },
     InterceptionResult,
 
@@ -126,4 +67,5 @@
     LinkInterceptor,
     LinkInterceptorStrategy,
     Modification,
+    StrategyRef,
 };
@@ -0,0 +1,296 @@
+//! Sampling of traced calls
+//!
+//! A [`TraceSampler`] decides which calls are traced so that tracing can
+//! stay enabled in production at a fraction of its cost. Calls are sampled
+//! 1 in N: with a rate of 100 the first of every 100 calls is traced, a
+//! rate of 1 traces every call and a rate of 0 none.
+//!
+//! Overrides set a different rate for the calls selected by a
+//! [`FunctionPattern`], e.g. to trace every call to `wasi:filesystem/*`
+//! while sampling everything else, or for a function index of the engine.
+//! Each override counts its calls separately. The first matching override,
+//! in the order they were set, applies.
+//!
+//! The sampler is shared by reference and all settings can be changed while
+//! calls are running, either through its methods or with textual directives
+//! passed to [`TraceSampler::apply`] by a diagnostics front end:
+//!
+//! - `100` sets the default rate
+//! - `wasi:filesystem/*=1` sets the rate of calls matching a pattern
+//! - `@12=0` sets the rate of calls to function 12 of the engine
+//! - `clear` removes all overrides
+//!
+//! Directives are separated by commas or whitespace.
+
+use core::sync::atomic::{
+    AtomicBool,
+    AtomicU32,
+    AtomicU64,
+    Ordering,
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::collections::StaticVec;
+
+use crate::scope::FunctionPattern;
+
+/// Maximum number of sampling overrides a [`TraceSampler`] can hold
+pub const MAX_SAMPLING_OVERRIDES: usize = 16;
+
+/// Calls an override applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selector {
+    /// Intercepted calls matching a pattern
+    Pattern(FunctionPattern),
+    /// Engine calls to a function index
+    Function(u32),
+}
+
+#[derive(Debug, Clone)]
+struct SamplingOverride {
+    selector: Selector,
+    rate:     u32,
+    calls:    u64,
+}
+
+#[cfg(feature = "std")]
+type OverrideLock = std::sync::Mutex<StaticVec<SamplingOverride, MAX_SAMPLING_OVERRIDES>>;
+#[cfg(not(feature = "std"))]
+type OverrideLock = wrt_sync::Mutex<StaticVec<SamplingOverride, MAX_SAMPLING_OVERRIDES>>;
+
+/// 1-in-N sampling decision for traced calls
+#[derive(Debug)]
+pub struct TraceSampler {
+    rate:          AtomicU32,
+    calls:         AtomicU64,
+    sampled:       AtomicU64,
+    overrides:     OverrideLock,
+    /// Whether `overrides` is non-empty, checked without locking
+    has_overrides: AtomicBool,
+}
+
+impl Default for TraceSampler {
+    /// Trace every call
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Whether the `calls`-th call (counting from 0) is sampled at `rate`
+fn sampled_at(rate: u32, calls: u64) -> bool {
+    rate != 0 && calls % u64::from(rate) == 0
+}
+
+impl TraceSampler {
+    /// Create a sampler tracing 1 in `rate` calls
+    #[must_use]
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate:          AtomicU32::new(rate),
+            calls:         AtomicU64::new(0),
+            sampled:       AtomicU64::new(0),
+            overrides:     OverrideLock::new(StaticVec::new()),
+            has_overrides: AtomicBool::new(false),
+        }
+    }
+
+    /// Default rate
+    pub fn rate(&self) -> u32 {
+        self.rate.load(Ordering::Relaxed)
+    }
+
+    /// Trace 1 in `rate` calls not selected by an override
+    pub fn set_rate(&self, rate: u32) {
+        self.rate.store(rate, Ordering::Relaxed);
+    }
+
+    /// Trace 1 in `rate` calls matching `pattern`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is too long or
+    /// [`MAX_SAMPLING_OVERRIDES`] overrides are already set.
+    pub fn set_override(&self, pattern: &str, rate: u32) -> Result<()> {
+        self.insert(Selector::Pattern(FunctionPattern::new(pattern)?), rate)
+    }
+
+    /// Trace 1 in `rate` engine calls to function `func_idx`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`MAX_SAMPLING_OVERRIDES`] overrides are already
+    /// set.
+    pub fn set_function_rate(&self, func_idx: u32, rate: u32) -> Result<()> {
+        self.insert(Selector::Function(func_idx), rate)
+    }
+
+    /// Remove all overrides
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the override lock is poisoned.
+    pub fn clear_overrides(&self) -> Result<()> {
+        self.with_overrides(|overrides| {
+            overrides.clear();
+            self.has_overrides.store(false, Ordering::Relaxed);
+        })
+    }
+
+    /// Apply sampling directives, see the [module documentation](self)
+    ///
+    /// Directives before an invalid one are applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directive is malformed or cannot be applied.
+    pub fn apply(&self, directives: &str) -> Result<()> {
+        for directive in directives.split(|c: char| c == ',' || c.is_whitespace()) {
+            match directive.rsplit_once('=') {
+                _ if directive.is_empty() => {},
+                None if directive == "clear" => self.clear_overrides()?,
+                None => self.set_rate(parse_rate(directive)?),
+                Some((selector, rate)) => {
+                    let rate = parse_rate(rate)?;
+                    match selector.strip_prefix('@') {
+                        Some(index) => self.set_function_rate(
+                            index.parse().map_err(|_| invalid_directive())?,
+                            rate,
+                        )?,
+                        None => self.set_override(selector, rate)?,
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Decide whether an intercepted call to `function` on `target` is
+    /// traced
+    pub fn sample(&self, target: &str, function: &str) -> bool {
+        self.decide(|selector| match selector {
+            Selector::Pattern(pattern) => pattern.matches(target, function),
+            Selector::Function(_) => false,
+        })
+    }
+
+    /// Decide whether an engine call to function `func_idx` is traced
+    pub fn sample_function(&self, func_idx: u32) -> bool {
+        self.decide(|selector| *selector == Selector::Function(func_idx))
+    }
+
+    /// Number of calls decided so far
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Number of calls traced so far
+    pub fn sampled(&self) -> u64 {
+        self.sampled.load(Ordering::Relaxed)
+    }
+
+    fn decide(&self, selects: impl Fn(&Selector) -> bool) -> bool {
+        let overridden = if self.has_overrides.load(Ordering::Relaxed) {
+            // A poisoned lock falls back to the default rate
+            self.with_overrides(|overrides| {
+                overrides.iter_mut().find(|o| selects(&o.selector)).map(|o| {
+                    o.calls += 1;
+                    sampled_at(o.rate, o.calls - 1)
+                })
+            })
+            .ok()
+            .flatten()
+        } else {
+            None
+        };
+
+        let calls = self.calls.fetch_add(1, Ordering::Relaxed);
+        let sampled = overridden.unwrap_or_else(|| sampled_at(self.rate(), calls));
+        if sampled {
+            self.sampled.fetch_add(1, Ordering::Relaxed);
+        }
+        sampled
+    }
+
+    fn insert(&self, selector: Selector, rate: u32) -> Result<()> {
+        self.with_overrides(|overrides| {
+            if let Some(existing) = overrides.iter_mut().find(|o| o.selector == selector) {
+                existing.rate = rate;
+                existing.calls = 0;
+                return Ok(());
+            }
+            overrides
+                .push(SamplingOverride {
+                    selector,
+                    rate,
+                    calls: 0,
+                })
+                .map_err(|_| {
+                    Error::new(
+                        ErrorCategory::Capacity,
+                        codes::CAPACITY_EXCEEDED,
+                        "Too many sampling overrides",
+                    )
+                })?;
+            self.has_overrides.store(true, Ordering::Relaxed);
+            Ok(())
+        })?
+    }
+
+    fn with_overrides<R>(
+        &self,
+        f: impl FnOnce(&mut StaticVec<SamplingOverride, MAX_SAMPLING_OVERRIDES>) -> R,
+    ) -> Result<R> {
+        #[cfg(feature = "std")]
+        let mut overrides = self.overrides.lock().map_err(|_| {
+            Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Sampler lock poisoned")
+        })?;
+        #[cfg(not(feature = "std"))]
+        let mut overrides = self.overrides.lock();
+        Ok(f(&mut overrides))
+    }
+}
+
+fn invalid_directive() -> Error {
+    Error::new(
+        ErrorCategory::Validation,
+        codes::INVALID_ARGUMENT,
+        "Invalid sampling directive",
+    )
+}
+
+fn parse_rate(rate: &str) -> Result<u32> {
+    rate.parse().map_err(|_| invalid_directive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_rates_and_overrides() {
+        let sampler = TraceSampler::new(3);
+        let traced: u32 = (0..9).map(|_| u32::from(sampler.sample("host", "log"))).sum();
+        assert_eq!(traced, 3);
+
+        sampler.apply("0, wasi:filesystem/*=1 @7=2").unwrap();
+        assert_eq!(sampler.rate(), 0);
+        assert!(!sampler.sample("host", "log"));
+        assert!(sampler.sample("wasi:filesystem/types", "read"));
+        assert!(sampler.sample("wasi:filesystem/types", "write"));
+        assert!(sampler.sample_function(7));
+        assert!(!sampler.sample_function(7));
+        assert!(!sampler.sample_function(8));
+
+        sampler.apply("clear").unwrap();
+        assert!(!sampler.sample("wasi:filesystem/types", "read"));
+        assert!(sampler.apply("fast").is_err());
+        assert!(sampler.apply("@x=1").is_err());
+        assert_eq!(sampler.calls(), 16);
+        assert_eq!(sampler.sampled(), 6);
+    }
+}
@@ -0,0 +1,148 @@
+//! Function patterns for scoping strategies to a subset of calls
+//!
+//! A [`FunctionPattern`] selects calls by target and, optionally, function
+//! name:
+//!
+//! - `wasi:filesystem/*` matches every function of every target starting with
+//!   `wasi:filesystem/`
+//! - `wasi:filesystem/types#read*` additionally requires the function name to
+//!   start with `read`
+//!
+//! `*` matches any run of characters (including `/`) and `?` matches exactly
+//! one character. A pattern without wildcards must match exactly.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::bounded::BoundedString;
+
+use crate::bounded_intercept::MAX_FUNCTION_NAME_LEN;
+
+/// Maximum number of scoped strategies a `LinkInterceptor` can hold
+pub const MAX_SCOPED_STRATEGIES: usize = 32;
+
+/// Separator between the target and function parts of a pattern
+const FUNCTION_SEPARATOR: char = '#';
+
+/// Glob pattern selecting intercepted calls by target and function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionPattern {
+    pattern: BoundedString<MAX_FUNCTION_NAME_LEN>,
+}
+
+impl FunctionPattern {
+    /// Create a pattern
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is longer than
+    /// [`MAX_FUNCTION_NAME_LEN`] bytes.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let pattern = BoundedString::try_from_str(pattern).map_err(|_| {
+            Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Function pattern exceeds maximum length",
+            )
+        })?;
+        Ok(Self { pattern })
+    }
+
+    /// The pattern text
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Built from a `&str`, so always valid UTF-8
+        self.pattern.as_str().unwrap_or_default()
+    }
+
+    /// Whether a call to `function` on `target` is selected by this pattern
+    #[must_use]
+    pub fn matches(&self, target: &str, function: &str) -> bool {
+        match self.as_str().split_once(FUNCTION_SEPARATOR) {
+            Some((target_pattern, function_pattern)) => {
+                glob_match(target_pattern, target) && glob_match(function_pattern, function)
+            },
+            None => glob_match(self.as_str(), target),
+        }
+    }
+}
+
+/// Match `text` against a glob supporting `*` and `?`
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: &[u8] = pattern.as_bytes();
+    let text: &[u8] = text.as_bytes();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            },
+            Some(b'?') => {
+                p += 1;
+                t = next_char(text, t);
+            },
+            Some(&c) if c == text[t] => {
+                p += 1;
+                t += 1;
+            },
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = next_char(text, star_t);
+                    backtrack = Some((star_p, t));
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Index of the UTF-8 character following the one starting at `index`
+fn next_char(text: &[u8], mut index: usize) -> usize {
+    index += 1;
+    while index < text.len() && text[index] & 0xC0 == 0x80 {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("wasi:filesystem/*", "wasi:filesystem/types"));
+        assert!(glob_match("wasi:*/types", "wasi:filesystem/types"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("read-?", "read-é"));
+        assert!(glob_match("*-é", "é-é"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+        assert!(!glob_match("wasi:filesystem/*", "wasi:sockets/tcp"));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn test_function_pattern_matches() {
+        let pattern = FunctionPattern::new("wasi:filesystem/*").unwrap();
+        assert!(pattern.matches("wasi:filesystem/types", "read-via-stream"));
+        assert!(!pattern.matches("wasi:cli/stdout", "read-via-stream"));
+
+        let pattern = FunctionPattern::new("wasi:filesystem/types#read*").unwrap();
+        assert!(pattern.matches("wasi:filesystem/types", "read-via-stream"));
+        assert!(!pattern.matches("wasi:filesystem/types", "write-via-stream"));
+
+        let too_long = [b'x'; MAX_FUNCTION_NAME_LEN + 1];
+        assert!(FunctionPattern::new(core::str::from_utf8(&too_long).unwrap()).is_err());
+    }
+}
@@ -1,8 +1,25 @@
 //! Firewall strategy for intercepting component function calls
 //!
 //! This strategy enforces security rules for function calls between
-//! components and hosts. It can allow or deny calls based on various criteria.
+//! components and hosts. It can allow or deny calls based on the caller,
+//! the callee and the function name, constrain individual argument values,
+//! and limit how often a function may be called within a window of consumed
+//! fuel. Rate limits read the [`FuelClock`] the engine advances, passed to
+//! [`FirewallBuilder::with_fuel_clock`].
+//!
+//! Rejected calls never panic: every denial is described by a
+//! [`FirewallDenial`] which converts into a [`wrt_error::Error`] in the
+//! `Security` category, so callers can tell a policy violation, a rejected
+//! argument and a rate limit apart by error code.
+//!
+//! In `no_std` builds all rule storage is inline ([`StaticVec`]) and bounded
+//! by [`MAX_FIREWALL_RULES`], [`MAX_ARGUMENT_CONSTRAINTS`] and
+//! [`MAX_RATE_LIMITS`].
 
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
 #[cfg(feature = "std")]
 use std::{
     collections::HashSet,
@@ -13,11 +30,19 @@
 };
 
 use wrt_error::{
+    codes,
     Error,
+    ErrorCategory,
     Result,
 };
+#[cfg(not(feature = "std"))]
+use wrt_foundation::collections::StaticVec;
 
 use crate::{
+    fuel::{
+        FuelClock,
+        FuelClockRef,
+    },
     prelude::{
         str,
         Debug,
@@ -26,32 +51,212 @@
     LinkInterceptorStrategy,
 };
 
-/// A rule to enforce on function calls
+/// Maximum number of allow/deny rules in a `no_std` firewall
+pub const MAX_FIREWALL_RULES: usize = 32;
+
+/// Maximum number of argument constraints in a `no_std` firewall
+pub const MAX_ARGUMENT_CONSTRAINTS: usize = 32;
+
+/// Maximum number of rate limits in a `no_std` firewall
+pub const MAX_RATE_LIMITS: usize = 16;
+
+/// Name type used by firewall rules
 #[cfg(feature = "std")]
-#[derive(Debug, Clone)]
+pub type RuleName = String;
+
+/// Name type used by firewall rules (`no_std` version)
+#[cfg(not(feature = "std"))]
+pub type RuleName = &'static str;
+
+/// A rule to enforce on function calls
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FirewallRule {
     /// Allow a specific function to be called (source, target, function)
-    AllowFunction(String, String, String),
+    AllowFunction(RuleName, RuleName, RuleName),
     /// Allow all functions from a source to a target
-    AllowSource(String, String),
+    AllowSource(RuleName, RuleName),
     /// Allow all functions to a target
-    AllowTarget(String),
+    AllowTarget(RuleName),
     /// Deny a specific function (source, target, function)
-    DenyFunction(String, String, String),
+    DenyFunction(RuleName, RuleName, RuleName),
     /// Deny all functions from a source to a target
-    DenySource(String, String),
+    DenySource(RuleName, RuleName),
     /// Deny all functions to a target
-    DenyTarget(String),
+    DenyTarget(RuleName),
 }
 
-/// A rule to enforce on function calls (`no_std` version)
-#[cfg(not(feature = "std"))]
-#[derive(Debug, Clone)]
-pub enum FirewallRule {
-    /// Binary `std/no_std` choice
-    AllowAll,
-    /// Deny all calls
-    DenyAll,
+impl FirewallRule {
+    /// Returns `Some(allow)` if this rule applies to the given call
+    fn verdict(&self, source: &str, target: &str, function: &str) -> Option<bool> {
+        match self {
+            FirewallRule::AllowFunction(s, t, f) => {
+                (*s == source && *t == target && *f == function).then_some(true)
+            },
+            FirewallRule::AllowSource(s, t) => (*s == source && *t == target).then_some(true),
+            FirewallRule::AllowTarget(t) => (*t == target).then_some(true),
+            FirewallRule::DenyFunction(s, t, f) => {
+                (*s == source && *t == target && *f == function).then_some(false)
+            },
+            FirewallRule::DenySource(s, t) => (*s == source && *t == target).then_some(false),
+            FirewallRule::DenyTarget(t) => (*t == target).then_some(false),
+        }
+    }
+}
+
+/// A constraint on a single argument value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueConstraint {
+    /// Integer argument must lie within `min..=max` (signed interpretation)
+    IntRange {
+        /// Smallest accepted value
+        min: i64,
+        /// Largest accepted value
+        max: i64,
+    },
+    /// Integer argument interpreted as unsigned must not exceed the bound
+    ///
+    /// `i32`/`i64` core values are reinterpreted bitwise, which makes this
+    /// the constraint to use for pointers and lengths.
+    MaxUnsigned(u64),
+    /// Reference argument must not be null
+    NonNull,
+}
+
+impl ValueConstraint {
+    /// Check whether `value` satisfies this constraint
+    ///
+    /// Values of a kind the constraint does not apply to (for example a float
+    /// passed where an integer range is expected) are rejected.
+    #[must_use]
+    pub fn accepts(&self, value: &Value) -> bool {
+        match self {
+            ValueConstraint::IntRange { min, max } => {
+                signed_value(value).is_some_and(|v| v >= i128::from(*min) && v <= i128::from(*max))
+            },
+            ValueConstraint::MaxUnsigned(max) => unsigned_value(value).is_some_and(|v| v <= *max),
+            ValueConstraint::NonNull => match value {
+                Value::FuncRef(r) => r.is_some(),
+                Value::ExternRef(r) => r.is_some(),
+                Value::StructRef(r) => r.is_some(),
+                Value::ArrayRef(r) => r.is_some(),
+                Value::ExnRef(r) => r.is_some(),
+                Value::I31Ref(r) => r.is_some(),
+                Value::Ref(_) => true,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Signed view of an integer value, if it is one
+pub(super) fn signed_value(value: &Value) -> Option<i128> {
+    match value {
+        Value::I32(v) | Value::S32(v) => Some(i128::from(*v)),
+        Value::I64(v) | Value::S64(v) => Some(i128::from(*v)),
+        Value::S8(v) => Some(i128::from(*v)),
+        Value::U8(v) => Some(i128::from(*v)),
+        Value::S16(v) => Some(i128::from(*v)),
+        Value::U16(v) => Some(i128::from(*v)),
+        Value::U32(v) => Some(i128::from(*v)),
+        Value::U64(v) => Some(i128::from(*v)),
+        _ => None,
+    }
+}
+
+/// Unsigned view of an integer value, if it is one
+fn unsigned_value(value: &Value) -> Option<u64> {
+    match value {
+        Value::I32(v) => Some(u64::from(*v as u32)),
+        Value::I64(v) => Some(*v as u64),
+        Value::U8(v) => Some(u64::from(*v)),
+        Value::U16(v) => Some(u64::from(*v)),
+        Value::U32(v) => Some(u64::from(*v)),
+        Value::U64(v) => Some(*v),
+        Value::S8(v) => u64::try_from(*v).ok(),
+        Value::S16(v) => u64::try_from(*v).ok(),
+        Value::S32(v) => u64::try_from(*v).ok(),
+        Value::S64(v) => u64::try_from(*v).ok(),
+        _ => None,
+    }
+}
+
+/// A constraint on one argument of a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentConstraint {
+    /// Function the constraint applies to, regardless of caller
+    pub function:   RuleName,
+    /// Zero-based index of the constrained argument
+    pub index:      usize,
+    /// Constraint the argument must satisfy
+    pub constraint: ValueConstraint,
+}
+
+/// A limit on the number of calls to a function per window of consumed fuel
+///
+/// Windows are consecutive `window_fuel`-sized spans of the firewall's fuel
+/// clock, so a window boundary falls every `window_fuel` units of fuel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Function the limit applies to, regardless of caller
+    pub function:    RuleName,
+    /// Maximum number of calls accepted within one window
+    pub max_calls:   u32,
+    /// Length of a window in units of fuel
+    pub window_fuel: u64,
+}
+
+/// Reason a call was rejected by the firewall
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirewallDenial {
+    /// The allow/deny rules (or the default policy) reject the call
+    PolicyViolation,
+    /// An argument violated its constraint or was missing
+    ArgumentRejected {
+        /// Index of the offending argument
+        index: usize,
+    },
+    /// The function exceeded its rate limit in the current fuel window
+    RateLimited {
+        /// Configured maximum calls per window
+        max_calls:   u32,
+        /// Configured window length in fuel
+        window_fuel: u64,
+    },
+}
+
+impl FirewallDenial {
+    /// Error code reported for this denial
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            FirewallDenial::PolicyViolation => codes::ACCESS_DENIED,
+            FirewallDenial::ArgumentRejected { .. } => codes::INVALID_PARAMETER,
+            FirewallDenial::RateLimited { .. } => codes::OPERATION_NOT_PERMITTED,
+        }
+    }
+
+    /// Convert this denial into a `Security` category error
+    #[must_use]
+    pub const fn to_error(&self) -> Error {
+        let message = match self {
+            FirewallDenial::PolicyViolation => {
+                "Security error: Function call not allowed by firewall policy"
+            },
+            FirewallDenial::ArgumentRejected { .. } => {
+                "Security error: Function argument rejected by firewall constraint"
+            },
+            FirewallDenial::RateLimited { .. } => {
+                "Security error: Function call rate limit exceeded"
+            },
+        };
+        Error::new(ErrorCategory::Security, self.code(), message)
+    }
+}
+
+impl From<FirewallDenial> for Error {
+    fn from(denial: FirewallDenial) -> Self {
+        denial.to_error()
+    }
 }
 
 /// Configuration for the firewall strategy
@@ -59,11 +264,17 @@ pub enum FirewallRule {
 #[derive(Debug, Clone, Default)]
 pub struct FirewallConfig {
     /// Default policy (true = allow by default, false = deny by default)
-    pub default_allow:    bool,
+    pub default_allow:        bool,
     /// Rules to enforce
-    pub rules:            Vec<FirewallRule>,
-    /// Whether to check function parameters
-    pub check_parameters: bool,
+    pub rules:                Vec<FirewallRule>,
+    /// Whether to check function parameters against `argument_constraints`
+    pub check_parameters:     bool,
+    /// Constraints on argument values
+    pub argument_constraints: Vec<ArgumentConstraint>,
+    /// Per-function call rate limits
+    pub rate_limits:          Vec<RateLimit>,
+    /// Fuel clock the rate-limit windows are measured against
+    pub fuel_clock:           Option<FuelClockRef>,
 }
 
 /// Configuration for the firewall strategy (`no_std` version)
@@ -71,9 +282,159 @@ pub struct FirewallConfig {
 #[derive(Debug, Clone, Default)]
 pub struct FirewallConfig {
     /// Default policy (true = allow by default, false = deny by default)
-    pub default_allow:    bool,
-    /// Whether to check function parameters
-    pub check_parameters: bool,
+    pub default_allow:        bool,
+    /// Rules to enforce
+    pub rules:                StaticVec<FirewallRule, MAX_FIREWALL_RULES>,
+    /// Whether to check function parameters against `argument_constraints`
+    pub check_parameters:     bool,
+    /// Constraints on argument values
+    pub argument_constraints: StaticVec<ArgumentConstraint, MAX_ARGUMENT_CONSTRAINTS>,
+    /// Per-function call rate limits
+    pub rate_limits:          StaticVec<RateLimit, MAX_RATE_LIMITS>,
+    /// Fuel clock the rate-limit windows are measured against
+    pub fuel_clock:           Option<FuelClockRef>,
+}
+
+/// Builder for a [`FirewallStrategy`]
+///
+/// The builder starts from a deny-by-default policy. Capacity errors in
+/// `no_std` builds are reported by [`FirewallBuilder::build`].
+#[derive(Debug, Default)]
+pub struct FirewallBuilder {
+    config: FirewallConfig,
+    error:  Option<Error>,
+}
+
+impl FirewallBuilder {
+    /// Create a new builder with a deny-by-default policy
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the policy applied when no rule matches
+    #[must_use]
+    pub fn with_default_allow(mut self, allow: bool) -> Self {
+        self.config.default_allow = allow;
+        self
+    }
+
+    /// Add an allow/deny rule; rules are applied in insertion order
+    #[must_use]
+    pub fn with_rule(mut self, rule: FirewallRule) -> Self {
+        let pushed = self.config.rules.push(rule);
+        self.record(pushed);
+        self
+    }
+
+    /// Constrain the argument at `index` of every call to `function`
+    ///
+    /// This also enables parameter checking.
+    #[must_use]
+    pub fn with_argument_constraint(
+        mut self,
+        function: RuleName,
+        index: usize,
+        constraint: ValueConstraint,
+    ) -> Self {
+        self.config.check_parameters = true;
+        let pushed = self.config.argument_constraints.push(ArgumentConstraint {
+            function,
+            index,
+            constraint,
+        });
+        self.record(pushed);
+        self
+    }
+
+    /// Allow at most `max_calls` calls to `function` per `window_fuel` units
+    /// of consumed fuel
+    #[must_use]
+    pub fn with_rate_limit(mut self, function: RuleName, max_calls: u32, window_fuel: u64) -> Self {
+        let pushed = self.config.rate_limits.push(RateLimit {
+            function,
+            max_calls,
+            window_fuel,
+        });
+        self.record(pushed);
+        self
+    }
+
+    /// Measure rate-limit windows against `clock`
+    ///
+    /// Share the clock with the engine running the guest, which advances it
+    /// as it charges fuel.
+    #[must_use]
+    pub fn with_fuel_clock(mut self, clock: FuelClockRef) -> Self {
+        self.config.fuel_clock = Some(clock);
+        self
+    }
+
+    /// Build the strategy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rate limit has a zero-length window, rate
+    /// limits are set without a fuel clock or, in `no_std` builds, if any
+    /// rule table exceeded its capacity.
+    pub fn build(self) -> Result<FirewallStrategy> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        FirewallStrategy::new(self.config)
+    }
+
+    #[cfg(feature = "std")]
+    fn record(&mut self, _pushed: ()) {}
+
+    #[cfg(not(feature = "std"))]
+    fn record(&mut self, pushed: Result<()>) {
+        if let Err(error) = pushed {
+            self.error.get_or_insert(error);
+        }
+    }
+}
+
+/// Rate-limit bookkeeping for one [`RateLimit`]
+///
+/// The window number and the calls accepted in it share one atomic word, so
+/// starting a new window and charging a call are a single compare-and-swap
+/// and concurrent callers can never reset each other's charges.
+#[derive(Debug, Default)]
+struct RateWindow {
+    /// Window number in the upper 32 bits, calls accepted in it in the lower
+    state: AtomicU64,
+}
+
+impl RateWindow {
+    fn pack(window: u32, calls: u32) -> u64 {
+        (u64::from(window) << 32) | u64::from(calls)
+    }
+
+    fn unpack(state: u64) -> (u32, u32) {
+        ((state >> 32) as u32, state as u32)
+    }
+
+    /// Count a call in `window`, unless `max_calls` were already accepted
+    fn try_charge(&self, window: u32, max_calls: u32) -> bool {
+        self.state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                let (current, calls) = Self::unpack(state);
+                // A different window number means the recorded one is over
+                let calls = if current == window { calls } else { 0 };
+                (calls < max_calls).then(|| Self::pack(window, calls + 1))
+            })
+            .is_ok()
+    }
+
+    /// Undo a charge made by `try_charge` in `window`
+    fn refund(&self, window: u32) {
+        // Nothing to undo once a later window has started
+        let _ = self.state.fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+            let (current, calls) = Self::unpack(state);
+            (current == window && calls > 0).then(|| Self::pack(window, calls - 1))
+        });
+    }
 }
 
 /// A strategy that enforces security rules on function calls
@@ -82,31 +443,150 @@ pub struct FirewallStrategy {
     /// Configuration for this strategy
     config:            FirewallConfig,
     /// Cache of allowed function calls for performance
-    #[cfg(feature = "std")]
     allowed_functions: RwLock<HashSet<String>>,
     /// Cache of denied function calls for performance
-    #[cfg(feature = "std")]
     denied_functions:  RwLock<HashSet<String>>,
+    /// One window per entry in `config.rate_limits`, shared with clones
+    windows:           Arc<[RateWindow]>,
 }
 
 /// A strategy that enforces security rules on function calls (`no_std` version)
 #[cfg(not(feature = "std"))]
 pub struct FirewallStrategy {
     /// Configuration for this strategy
-    config: FirewallConfig,
+    config:  FirewallConfig,
+    /// One window per entry in `config.rate_limits`
+    windows: StaticVec<RateWindow, MAX_RATE_LIMITS>,
 }
 
 impl FirewallStrategy {
     /// Create a new firewall strategy with the given configuration
-    #[must_use]
-    pub fn new(config: FirewallConfig) -> Self {
-        Self {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rate limit has a zero-length window or rate
+    /// limits are set without a fuel clock.
+    pub fn new(config: FirewallConfig) -> Result<Self> {
+        if config.rate_limits.iter().any(|limit| limit.window_fuel == 0) {
+            return Err(Error::validation_invalid_argument(
+                "Firewall rate limit window must be at least one unit of fuel",
+            ));
+        }
+        if !config.rate_limits.is_empty() && config.fuel_clock.is_none() {
+            return Err(Error::validation_invalid_argument(
+                "Firewall rate limits need a fuel clock",
+            ));
+        }
+
+        #[cfg(feature = "std")]
+        let windows = config.rate_limits.iter().map(|_| RateWindow::default()).collect();
+        #[cfg(not(feature = "std"))]
+        let windows = {
+            let mut windows = StaticVec::new();
+            for _ in config.rate_limits.iter() {
+                windows.push(RateWindow::default())?;
+            }
+            windows
+        };
+
+        Ok(Self {
             config,
             #[cfg(feature = "std")]
             allowed_functions: RwLock::new(HashSet::new()),
             #[cfg(feature = "std")]
             denied_functions: RwLock::new(HashSet::new()),
+            windows,
+        })
+    }
+
+    /// Start building a firewall strategy
+    #[must_use]
+    pub fn builder() -> FirewallBuilder {
+        FirewallBuilder::new()
+    }
+
+    /// Current value of the fuel clock, zero without one
+    #[must_use]
+    pub fn fuel_clock(&self) -> u64 {
+        self.config.fuel_clock.as_ref().map_or(0, |clock| FuelClock::now(clock))
+    }
+
+    /// Check a call against all rules, constraints and rate limits
+    ///
+    /// A rejected call does not count against any rate limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`FirewallDenial`] describing why the call was rejected.
+    pub fn check_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> core::result::Result<(), FirewallDenial> {
+        if !self.is_allowed(source, target, function) {
+            return Err(FirewallDenial::PolicyViolation);
         }
+
+        if self.config.check_parameters {
+            for constraint in self.config.argument_constraints.iter() {
+                if constraint.function != function {
+                    continue;
+                }
+                let accepted =
+                    args.get(constraint.index).is_some_and(|arg| constraint.constraint.accepts(arg));
+                if !accepted {
+                    return Err(FirewallDenial::ArgumentRejected {
+                        index: constraint.index,
+                    });
+                }
+            }
+        }
+
+        self.charge_rate_limits(function)
+    }
+
+    /// Count the call against every rate limit for `function`
+    fn charge_rate_limits(&self, function: &str) -> core::result::Result<(), FirewallDenial> {
+        let now = self.fuel_clock();
+        let mut charged = 0;
+
+        for (limit, window) in self.config.rate_limits.iter().zip(self.windows.iter()) {
+            if limit.function != function {
+                continue;
+            }
+
+            if !window.try_charge(Self::window_number(now, limit), limit.max_calls) {
+                self.refund_rate_limits(function, now, charged);
+                return Err(FirewallDenial::RateLimited {
+                    max_calls:   limit.max_calls,
+                    window_fuel: limit.window_fuel,
+                });
+            }
+            charged += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Undo the first `count` charges made for `function` at fuel clock `now`
+    fn refund_rate_limits(&self, function: &str, now: u64, count: usize) {
+        self.config
+            .rate_limits
+            .iter()
+            .zip(self.windows.iter())
+            .filter(|(limit, _)| limit.function == function)
+            .take(count)
+            .for_each(|(limit, window)| window.refund(Self::window_number(now, limit)));
+    }
+
+    /// Number of the `limit` window containing fuel clock value `now`
+    ///
+    /// Truncated to 32 bits; a stale count is only mistaken for a current
+    /// one if a function sits idle for exactly a multiple of 2^32 windows.
+    fn window_number(now: u64, limit: &RateLimit) -> u32 {
+        (now / limit.window_fuel) as u32
     }
 
     /// Helper function to generate a unique key for a function call
@@ -148,49 +628,22 @@ fn is_allowed(&self, source: &str, target: &str, function: &str) -> bool {
         allowed
     }
 
+    /// Check if a function call is allowed (`no_std` version, uncached)
+    #[cfg(not(feature = "std"))]
+    fn is_allowed(&self, source: &str, target: &str, function: &str) -> bool {
+        self.apply_rules(source, target, function)
+    }
+
     /// Apply rules to determine if a function call is allowed
-    #[cfg(feature = "std")]
     fn apply_rules(&self, source: &str, target: &str, function: &str) -> bool {
-        // Start with default policy
-        let mut allowed = self.config.default_allow;
-
-        // Apply rules in order
-        for rule in &self.config.rules {
-            match rule {
-                FirewallRule::AllowFunction(s, t, f) => {
-                    if s == source && t == target && f == function {
-                        allowed = true;
-                    }
-                },
-                FirewallRule::AllowSource(s, t) => {
-                    if s == source && t == target {
-                        allowed = true;
-                    }
-                },
-                FirewallRule::AllowTarget(t) => {
-                    if t == target {
-                        allowed = true;
-                    }
-                },
-                FirewallRule::DenyFunction(s, t, f) => {
-                    if s == source && t == target && f == function {
-                        allowed = false;
-                    }
-                },
-                FirewallRule::DenySource(s, t) => {
-                    if s == source && t == target {
-                        allowed = false;
-                    }
-                },
-                FirewallRule::DenyTarget(t) => {
-                    if t == target {
-                        allowed = false;
-                    }
-                },
-            }
-        }
-
-        allowed
+        // Rules apply in order, so the last matching rule wins; without a
+        // match the default policy decides
+        self.config
+            .rules
+            .iter()
+            .rev()
+            .find_map(|rule| rule.verdict(source, target, function))
+            .unwrap_or(self.config.default_allow)
     }
 }
 
@@ -203,31 +656,7 @@ fn before_call(
         function: &str,
         args: &[Value],
     ) -> Result<Vec<Value>> {
-        #[cfg(feature = "std")]
-        {
-            // Check if the function call is allowed
-            if !self.is_allowed(source, target, function) {
-                return Err(Error::runtime_error(
-                    "Security error: Function call not allowed by firewall policy",
-                ));
-            }
-        }
-
-        // In pure no_std mode, we just use the default policy
-        #[cfg(not(feature = "std"))]
-        {
-            if !self.config.default_allow {
-                return Err(Error::runtime_error(
-                    "Security error: Function call not allowed by firewall policy",
-                ));
-            }
-        }
-
-        // Check parameters if configured
-        if self.config.check_parameters {
-            // Parameter checking logic would go here
-            // For example, you could check for malicious input patterns
-        }
+        self.check_call(source, target, function, args)?;
 
         // Return unmodified arguments
         Ok(args.to_vec())
@@ -246,12 +675,13 @@ fn after_call(
     }
 
     fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        // The clone reads the same fuel clock and charges the same rate
+        // windows, so cloning cannot hand out a fresh call budget
         Arc::new(Self {
             config: self.config.clone(),
-            #[cfg(feature = "std")]
             allowed_functions: RwLock::new(HashSet::new()),
-            #[cfg(feature = "std")]
             denied_functions: RwLock::new(HashSet::new()),
+            windows: Arc::clone(&self.windows),
         })
     }
 }
@@ -260,17 +690,12 @@ fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
 impl LinkInterceptorStrategy for FirewallStrategy {
     fn before_call(
         &self,
-        _source: &str,
-        _target: &str,
-        _function: &str,
-        _args: &[Value],
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
     ) -> Result<()> {
-        // In pure no_std mode, we just use the default policy
-        if !self.config.default_allow {
-            return Err(Error::runtime_error(
-                "Security error: Function call not allowed by firewall policy",
-            ));
-        }
+        self.check_call(source, target, function, args)?;
         Ok(())
     }
 
@@ -302,8 +727,9 @@ fn test_firewall_allow_by_default() {
                 "denied_function".to_string(),
             )],
             check_parameters: false,
+            ..FirewallConfig::default()
         };
-        let strategy = FirewallStrategy::new(config);
+        let strategy = FirewallStrategy::new(config).unwrap();
 
         // Test allowed function
         let result = strategy.before_call("source", "target", "allowed_function", &[]);
@@ -325,8 +751,9 @@ fn test_firewall_deny_by_default() {
                 "allowed_function".to_string(),
             )],
             check_parameters: false,
+            ..FirewallConfig::default()
         };
-        let strategy = FirewallStrategy::new(config);
+        let strategy = FirewallStrategy::new(config).unwrap();
 
         // Test allowed function
         let result = strategy.before_call("source", "target", "allowed_function", &[]);
@@ -347,8 +774,9 @@ fn test_firewall_allow_source() {
                 "target".to_string(),
             )],
             check_parameters: false,
+            ..FirewallConfig::default()
         };
-        let strategy = FirewallStrategy::new(config);
+        let strategy = FirewallStrategy::new(config).unwrap();
 
         // Test allowed source
         let result = strategy.before_call("source", "target", "any_function", &[]);
@@ -373,8 +801,9 @@ fn test_firewall_rule_precedence() {
                 ),
             ],
             check_parameters: false,
+            ..FirewallConfig::default()
         };
-        let strategy = FirewallStrategy::new(config);
+        let strategy = FirewallStrategy::new(config).unwrap();
 
         // Test allowed function
         let result = strategy.before_call("source", "target", "allowed_function", &[]);
@@ -384,4 +813,163 @@ fn test_firewall_rule_precedence() {
         let result = strategy.before_call("source", "target", "denied_function", &[]);
         assert!(result.is_err());
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_firewall_argument_constraints() {
+        let strategy = FirewallStrategy::builder()
+            .with_default_allow(true)
+            .with_argument_constraint(
+                "write".to_string(),
+                1,
+                ValueConstraint::MaxUnsigned(4096),
+            )
+            .build()
+            .unwrap();
+
+        let ok = strategy.check_call("a", "b", "write", &[Value::I32(1), Value::I32(4096)]);
+        assert_eq!(ok, Ok(()));
+
+        // -1 reinterpreted as an unsigned length is far above the bound
+        let denied = strategy.check_call("a", "b", "write", &[Value::I32(1), Value::I32(-1)]);
+        assert_eq!(denied, Err(FirewallDenial::ArgumentRejected { index: 1 }));
+
+        // A missing argument is rejected rather than ignored
+        let missing = strategy.check_call("a", "b", "write", &[Value::I32(1)]);
+        assert_eq!(missing, Err(FirewallDenial::ArgumentRejected { index: 1 }));
+
+        let error = strategy.before_call("a", "b", "write", &[Value::I32(1)]).unwrap_err();
+        assert_eq!(error.category(), ErrorCategory::Security);
+        assert_eq!(error.code(), codes::INVALID_PARAMETER);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_firewall_rate_limit_per_fuel_window() {
+        let clock = Arc::new(FuelClock::new());
+        let strategy = FirewallStrategy::builder()
+            .with_default_allow(true)
+            .with_rate_limit("tick".to_string(), 2, 100)
+            .with_fuel_clock(Arc::clone(&clock))
+            .build()
+            .unwrap();
+
+        assert!(strategy.check_call("a", "b", "tick", &[]).is_ok());
+        assert!(strategy.check_call("a", "b", "tick", &[]).is_ok());
+        assert_eq!(
+            strategy.check_call("a", "b", "tick", &[]),
+            Err(FirewallDenial::RateLimited {
+                max_calls:   2,
+                window_fuel: 100,
+            })
+        );
+        // Other functions are unaffected
+        assert!(strategy.check_call("a", "b", "other", &[]).is_ok());
+
+        // Still inside the window
+        clock.advance(99);
+        assert!(strategy.check_call("a", "b", "tick", &[]).is_err());
+
+        // A new window starts once enough fuel has been consumed
+        clock.advance(1);
+        assert_eq!(strategy.fuel_clock(), 100);
+        assert!(strategy.check_call("a", "b", "tick", &[]).is_ok());
+
+        let error = strategy.before_call("a", "b", "tick", &[]).and_then(|_| {
+            strategy.before_call("a", "b", "tick", &[])
+        });
+        assert_eq!(error.unwrap_err().code(), codes::OPERATION_NOT_PERMITTED);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_firewall_rate_limit_is_exact_under_contention() {
+        let clock = Arc::new(FuelClock::new());
+        let strategy = Arc::new(
+            FirewallStrategy::builder()
+                .with_default_allow(true)
+                .with_rate_limit("tick".to_string(), 64, 10)
+                .with_fuel_clock(Arc::clone(&clock))
+                .build()
+                .unwrap(),
+        );
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let strategy = Arc::clone(&strategy);
+                std::thread::spawn(move || {
+                    (0..32).filter(|_| strategy.check_call("a", "b", "tick", &[]).is_ok()).count()
+                })
+            })
+            .collect();
+        let accepted: usize = workers.into_iter().map(|worker| worker.join().unwrap()).sum();
+        assert_eq!(accepted, 64);
+
+        // Crossing into the next window resets the count exactly once
+        clock.advance(10);
+        assert!(strategy.check_call("a", "b", "tick", &[]).is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_firewall_clones_share_rate_windows_and_clock() {
+        let clock = Arc::new(FuelClock::new());
+        let strategy = FirewallStrategy::builder()
+            .with_default_allow(true)
+            .with_rate_limit("tick".to_string(), 1, 10)
+            .with_fuel_clock(Arc::clone(&clock))
+            .build()
+            .unwrap();
+        let clone = strategy.clone_strategy();
+
+        assert!(strategy.before_call("a", "b", "tick", &[]).is_ok());
+        assert!(clone.before_call("a", "b", "tick", &[]).is_err());
+
+        clock.advance(10);
+        assert!(clone.before_call("a", "b", "tick", &[]).is_ok());
+        assert!(strategy.before_call("a", "b", "tick", &[]).is_err());
+    }
+
+    #[test]
+    fn test_firewall_builder_rejects_empty_window() {
+        #[cfg(feature = "std")]
+        let clock = Arc::new(FuelClock::new());
+        #[cfg(not(feature = "std"))]
+        let clock = {
+            static CLOCK: FuelClock = FuelClock::new();
+            &CLOCK
+        };
+
+        let name: RuleName = "tick".into();
+        let result =
+            FirewallStrategy::builder().with_rate_limit(name, 1, 0).with_fuel_clock(clock).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_firewall_builder_rejects_rate_limit_without_clock() {
+        let name: RuleName = "tick".into();
+        let result = FirewallStrategy::builder().with_rate_limit(name, 1, 10).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_value_constraint_kinds() {
+        let range = ValueConstraint::IntRange { min: -5, max: 5 };
+        assert!(range.accepts(&Value::I32(-5)));
+        assert!(range.accepts(&Value::U8(5)));
+        assert!(!range.accepts(&Value::I64(6)));
+        assert!(!range.accepts(&Value::U64(u64::MAX)));
+        assert!(!range.accepts(&Value::Bool(true)));
+
+        assert!(ValueConstraint::NonNull.accepts(&Value::Ref(0)));
+        assert!(!ValueConstraint::NonNull.accepts(&Value::FuncRef(None)));
+    }
+
+    #[test]
+    fn test_firewall_denial_maps_to_security_error() {
+        let error: Error = FirewallDenial::PolicyViolation.into();
+        assert_eq!(error.category(), ErrorCategory::Security);
+        assert_eq!(error.code(), codes::ACCESS_DENIED);
+    }
 }
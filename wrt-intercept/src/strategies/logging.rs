@@ -2,6 +2,19 @@
 //!
 //! This strategy logs function calls between components and hosts.
 //! It can be configured to log arguments, results, timing, etc.
+//!
+//! With [`LoggingConfig::log_canonical`] enabled it also traces canonical ABI
+//! lift and lower operations, one line per operation:
+//!
+//! ```text
+//! LIFT: <source>-><target>::<function> addr=0x00001000 bytes=2a000000...
+//! LOWER: <source>-><target>::<function> addr=0x00002000 bytes=01000000...
+//! ```
+//!
+//! `LIFT` bytes are the guest memory starting at `addr` (the flattened
+//! parameters), `LOWER` bytes are the serialized results being written to
+//! `addr`. Both are truncated to [`LoggingConfig::max_canonical_bytes`].
+//! `cargo-wrt abi-trace` decodes these lines against WIT definitions.
 
 #[cfg(feature = "std")]
 use std::{
@@ -15,6 +28,8 @@
 use wrt_error::Result;
 
 // Import the prelude for unified access to standard types
+#[cfg(feature = "std")]
+use crate::prelude::ValType;
 use crate::prelude::{
     str,
     Debug,
@@ -59,25 +74,31 @@ pub trait LogSink: Send + Sync {
 #[derive(Debug, Clone)]
 pub struct LoggingConfig {
     /// Whether to log arguments
-    pub log_args:    bool,
+    pub log_args:            bool,
     /// Whether to log results
-    pub log_results: bool,
+    pub log_results:         bool,
     /// Whether to log timing information
-    pub log_timing:  bool,
+    pub log_timing:          bool,
     /// Maximum number of arguments to log (0 for unlimited)
-    pub max_args:    usize,
+    pub max_args:            usize,
     /// Maximum number of results to log (0 for unlimited)
-    pub max_results: usize,
+    pub max_results:         usize,
+    /// Whether to trace canonical ABI lift/lower operations
+    pub log_canonical:       bool,
+    /// Maximum number of bytes captured per lift/lower trace line
+    pub max_canonical_bytes: usize,
 }
 
 impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
-            log_args:    true,
-            log_results: true,
-            log_timing:  true,
-            max_args:    10,
-            max_results: 10,
+            log_args:            true,
+            log_results:         true,
+            log_timing:          true,
+            max_args:            10,
+            max_results:         10,
+            log_canonical:       false,
+            max_canonical_bytes: 64,
         }
     }
 }
@@ -94,6 +115,8 @@ pub struct LoggingStrategy<S: LogSink, F: ValueFormatter = DefaultValueFormatter
     /// Thread-local storage for timing information
     #[cfg(feature = "std")]
     timing:    Arc<Mutex<Option<Instant>>>,
+    /// Call currently in progress, used to label canonical trace lines
+    call:      Arc<Mutex<Option<String>>>,
 }
 
 /// A simple logging strategy for `no_std` environments
@@ -112,6 +135,7 @@ pub fn new(sink: Arc<S>) -> Self {
             formatter: DefaultValueFormatter,
             config: LoggingConfig::default(),
             timing: Arc::new(Mutex::new(None)),
+            call: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -125,6 +149,7 @@ pub fn with_formatter(sink: Arc<S>, formatter: F) -> Self {
             formatter,
             config: LoggingConfig::default(),
             timing: Arc::new(Mutex::new(None)),
+            call: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -134,6 +159,21 @@ pub fn with_config(mut self, config: LoggingConfig) -> Self {
         self.config = config;
         self
     }
+
+    /// Write a canonical ABI trace line for the call in progress
+    fn trace_canonical(&self, operation: &str, addr: u32, bytes: &[u8]) {
+        use core::fmt::Write;
+
+        let call = self.call.lock().ok().and_then(|call| call.clone());
+        let mut log_entry = format!(
+            "{operation}: {} addr={addr:#010x} bytes=",
+            call.as_deref().unwrap_or("<no call>")
+        );
+        for byte in bytes.iter().take(self.config.max_canonical_bytes) {
+            let _ = write!(log_entry, "{byte:02x}");
+        }
+        self.sink.write_log(&log_entry);
+    }
 }
 
 #[cfg(feature = "std")]
@@ -178,6 +218,13 @@ fn before_call(
         // Write the log entry
         self.sink.write_log(&log_entry);
 
+        // Remember the call so canonical trace lines can be attributed to it
+        if self.config.log_canonical {
+            if let Ok(mut call) = self.call.lock() {
+                *call = Some(format!("{source}->{target}::{function}"));
+            }
+        }
+
         // Store start time if timing is enabled
         if self.config.log_timing {
             if let Ok(mut timing) = self.timing.lock() {
@@ -249,16 +296,55 @@ fn after_call(
         // Write the log entry
         self.sink.write_log(&log_entry);
 
+        if self.config.log_canonical {
+            if let Ok(mut call) = self.call.lock() {
+                *call = None;
+            }
+        }
+
         // Return unmodified result
         result
     }
 
+    fn should_intercept_canonical(&self) -> bool {
+        self.config.log_canonical
+    }
+
+    fn intercept_lift(
+        &self,
+        _ty: &ValType<wrt_foundation::NoStdProvider<64>>,
+        addr: u32,
+        memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let bytes = memory_bytes.get(addr as usize..).ok_or_else(|| {
+            wrt_error::Error::runtime_out_of_bounds("Canonical lift address outside memory")
+        })?;
+        self.trace_canonical("LIFT", addr, bytes);
+
+        // Tracing only; the lift proceeds normally
+        Ok(None)
+    }
+
+    fn intercept_lower(
+        &self,
+        _value_type: &ValType<wrt_foundation::NoStdProvider<64>>,
+        value_data: &[u8],
+        addr: u32,
+        _memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        self.trace_canonical("LOWER", addr, value_data);
+
+        // Tracing only; the lower proceeds normally
+        Ok(false)
+    }
+
     fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
         Arc::new(Self {
             sink:      self.sink.clone(),
             formatter: self.formatter.clone(),
             config:    self.config.clone(),
             timing:    self.timing.clone(),
+            call:      self.call.clone(),
         })
     }
 }
@@ -448,11 +534,13 @@ fn test_logging_strategy_config() {
             logs: Mutex::new(Vec::new()),
         });
         let config = LoggingConfig {
-            log_args:    false,
-            log_results: true,
-            log_timing:  false,
-            max_args:    5,
-            max_results: 5,
+            log_args:            false,
+            log_results:         true,
+            log_timing:          false,
+            max_args:            5,
+            max_results:         5,
+            log_canonical:       false,
+            max_canonical_bytes: 0,
         };
         let strategy = LoggingStrategy::new(sink.clone()).with_config(config);
 
@@ -467,4 +555,29 @@ fn test_logging_strategy_config() {
         assert!(!logs[0].contains("I32(42)"));
         assert!(!logs[0].contains("I64(123)"));
     }
+
+    #[test]
+    fn test_logging_strategy_canonical_trace() {
+        let sink = Arc::new(TestSink {
+            logs: Mutex::new(Vec::new()),
+        });
+        let config = LoggingConfig {
+            log_canonical:       true,
+            max_canonical_bytes: 4,
+            ..LoggingConfig::default()
+        };
+        let strategy = LoggingStrategy::new(sink.clone()).with_config(config);
+        assert!(strategy.should_intercept_canonical());
+
+        let ty = ValType::U32;
+        let memory = [0u8, 0, 0, 0, 0x2a, 0, 0, 0, 0xff, 0xff];
+        let _ = strategy.before_call("guest", "host", "log", &[]);
+        assert_eq!(strategy.intercept_lift(&ty, 4, &memory).unwrap(), None);
+        assert!(!strategy.intercept_lower(&ty, &[1, 2], 16, &mut []).unwrap());
+        assert!(strategy.intercept_lift(&ty, 64, &memory).is_err());
+
+        let logs = sink.logs.lock().unwrap();
+        assert_eq!(logs[1], "LIFT: guest->host::log addr=0x00000004 bytes=2a000000");
+        assert_eq!(logs[2], "LOWER: guest->host::log addr=0x00000010 bytes=0102");
+    }
 }
@@ -6,14 +6,64 @@
 
 mod firewall;
 mod logging;
+#[cfg(feature = "std")]
+mod policy;
+#[cfg(feature = "std")]
+mod record;
 mod stats;
+mod tracing;
 
 pub use firewall::{
+    ArgumentConstraint,
+    FirewallBuilder,
     FirewallConfig,
+    FirewallDenial,
     FirewallRule,
     FirewallStrategy,
+    RateLimit,
+    RuleName,
+    ValueConstraint,
+    MAX_ARGUMENT_CONSTRAINTS,
+    MAX_FIREWALL_RULES,
+    MAX_RATE_LIMITS,
+};
+#[cfg(feature = "std")]
+pub use logging::LogSink;
+pub use logging::{
+    LoggingConfig,
+    LoggingStrategy,
+};
+#[cfg(feature = "std")]
+pub use policy::{
+    ArgumentPredicate,
+    ArgumentRewrite,
+    Comparison,
+    Policy,
+    PolicyAction,
+    PolicyRule,
+    PolicyStrategy,
+    PolicyVerdict,
+    Rewrite,
+    MAX_POLICY_RULES,
+    MAX_RULE_TERMS,
+    POLICY_HEADER,
+};
+#[cfg(feature = "std")]
+pub use record::{
+    CallOutcome,
+    RecordingStrategy,
+    ReplayStrategy,
+    ResourceOpKind,
+    Trace,
+    TraceEvent,
+    MAX_VALUE_DEPTH,
+    TRACE_HEADER,
+};
+#[cfg(feature = "std")]
+pub(crate) use record::{
+    push_name,
+    unescape_name,
 };
-pub use logging::LoggingStrategy;
 #[cfg(not(feature = "std"))]
 pub use stats::FunctionStats;
 #[cfg(feature = "std")]
@@ -21,3 +71,16 @@
     FunctionStats,
     StatisticsStrategy,
 };
+pub use tracing::{
+    CallSpan,
+    ExporterRef,
+    RingBufferExporter,
+    SamplerRef,
+    SpanExporter,
+    SpanLabel,
+    TracingStrategy,
+    MAX_OPEN_SPANS,
+    MAX_SPAN_LABEL_LEN,
+};
+#[cfg(feature = "std")]
+pub use tracing::JsonLinesExporter;
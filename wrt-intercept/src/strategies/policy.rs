@@ -0,0 +1,972 @@
+//! Policy strategy driven by declarative rules
+//!
+//! A [`PolicyStrategy`] enforces a [`Policy`]: an ordered list of rules that
+//! match calls by source component, target and function name and by
+//! predicates on integer arguments, and allow, deny, rewrite or log the
+//! matching calls. Policies are data rather than code. They are parsed from
+//! a compact text format or from JSON, and can be replaced on a running
+//! strategy with [`PolicyStrategy::set_policy`], so call policies change
+//! without recompiling any strategy.
+//!
+//! # Evaluation
+//!
+//! Rules are evaluated in order for every call:
+//!
+//! - `allow` and `deny` rules are final: the first one that matches decides.
+//! - `modify` rules rewrite arguments; later rules see the rewritten values.
+//! - `log` rules report the call through the `log` crate.
+//!
+//! When no `allow` or `deny` rule matches, the policy's default decides. A
+//! predicate on a missing or non-integer argument does not match.
+//!
+//! # Rule format
+//!
+//! ```text
+//! wrt-policy v1
+//! # Comments and blank lines are ignored
+//! default deny
+//! allow app wasi:filesystem/* read*
+//! deny * * write arg2>4096
+//! modify app host send arg0>=0 -> arg1=0..1500
+//! log * wasi:sockets/* *
+//! ```
+//!
+//! - A rule is `<action> <source> <target> <function> <conditions...>`.
+//!   Source, target and function are globs as in
+//!   [`FunctionPattern`](crate::scope::FunctionPattern) and cannot contain
+//!   whitespace.
+//! - A condition is `arg<N><op><int>` with `op` one of `==`, `!=`, `<`,
+//!   `<=`, `>` and `>=`. All conditions of a rule must hold.
+//! - A `modify` rule lists its rewrites after `->`: `arg<N>=<int>` replaces
+//!   an argument and `arg<N>=<min>..<max>` clamps it. The rewritten value
+//!   keeps the argument's type.
+//! - `default` may appear once. Without it the policy denies.
+//!
+//! # JSON format
+//!
+//! ```json
+//! {
+//!   "default": "deny",
+//!   "rules": [
+//!     { "action": "allow", "source": "app", "target": "wasi:filesystem/*", "function": "read*" },
+//!     { "action": "deny", "function": "write", "when": [{ "arg": 2, "op": ">", "value": 4096 }] },
+//!     { "action": "modify", "function": "send", "set": [{ "arg": 1, "min": 0, "max": 1500 }] }
+//!   ]
+//! }
+//! ```
+//!
+//! Omitted patterns match anything. Unknown keys are rejected so a
+//! misspelled condition cannot silently widen a rule.
+//!
+//! Note: This strategy requires the `std` feature.
+
+use std::{
+    fmt::Write as _,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use super::firewall::signed_value;
+use crate::{
+    prelude::Value,
+    scope::glob_match,
+    LinkInterceptorStrategy,
+};
+
+/// First line of every policy in the rule format
+pub const POLICY_HEADER: &str = "wrt-policy v1";
+
+/// Maximum number of rules in a parsed policy
+pub const MAX_POLICY_RULES: usize = 256;
+
+/// Maximum number of conditions or rewrites in a parsed rule
+pub const MAX_RULE_TERMS: usize = 16;
+
+/// Pattern matching any name
+const ANY_NAME: &str = "*";
+
+/// Separator between the conditions and the rewrites of a `modify` rule
+const REWRITE_SEPARATOR: &str = "->";
+
+/// Comparison used by an [`ArgumentPredicate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl Comparison {
+    /// Operators in the order they are tried when parsing
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+
+    /// Operator symbol in the rule format
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Self::OPERATORS.iter().find(|(s, _)| *s == symbol).map(|(_, op)| *op)
+    }
+
+    fn holds(self, lhs: i128, rhs: i128) -> bool {
+        match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+        }
+    }
+}
+
+/// Condition on one integer argument of a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgumentPredicate {
+    /// Zero-based index of the argument
+    pub index:      usize,
+    /// Comparison applied as `argument <op> value`
+    pub comparison: Comparison,
+    /// Right-hand side of the comparison
+    pub value:      i64,
+}
+
+impl ArgumentPredicate {
+    /// Whether the argument exists, is an integer and satisfies the condition
+    #[must_use]
+    pub fn matches(&self, args: &[Value]) -> bool {
+        args.get(self.index)
+            .and_then(signed_value)
+            .is_some_and(|arg| self.comparison.holds(arg, i128::from(self.value)))
+    }
+}
+
+/// New value for a rewritten argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rewrite {
+    /// Replace the argument
+    Set(i64),
+    /// Clamp the argument to `min..=max`
+    Clamp {
+        /// Smallest allowed value
+        min: i64,
+        /// Largest allowed value
+        max: i64,
+    },
+}
+
+/// Rewrite of one integer argument by a `modify` rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgumentRewrite {
+    /// Zero-based index of the argument
+    pub index:   usize,
+    /// How the argument is rewritten
+    pub rewrite: Rewrite,
+}
+
+impl ArgumentRewrite {
+    /// Rewrite the argument in place, keeping its type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the argument is missing, is not an integer, or
+    /// the new value does not fit its type.
+    pub fn apply(&self, args: &mut [Value]) -> Result<()> {
+        let arg = args.get_mut(self.index).ok_or_else(|| {
+            rewrite_error("Security error: Policy rewrite targets a missing argument")
+        })?;
+        let current = signed_value(arg).ok_or_else(|| {
+            rewrite_error("Security error: Policy rewrite targets a non-integer argument")
+        })?;
+        let value = match self.rewrite {
+            Rewrite::Set(value) => i128::from(value),
+            Rewrite::Clamp { min, max } => current.clamp(i128::from(min), i128::from(max)),
+        };
+        *arg = with_integer(arg, value).ok_or_else(|| {
+            rewrite_error("Security error: Policy rewrite does not fit the argument type")
+        })?;
+        Ok(())
+    }
+}
+
+/// `value` as an integer of the same type as `like`, if it fits
+fn with_integer(like: &Value, value: i128) -> Option<Value> {
+    Some(match like {
+        Value::I32(_) => Value::I32(i32::try_from(value).ok()?),
+        Value::I64(_) => Value::I64(i64::try_from(value).ok()?),
+        Value::S8(_) => Value::S8(i8::try_from(value).ok()?),
+        Value::U8(_) => Value::U8(u8::try_from(value).ok()?),
+        Value::S16(_) => Value::S16(i16::try_from(value).ok()?),
+        Value::U16(_) => Value::U16(u16::try_from(value).ok()?),
+        Value::S32(_) => Value::S32(i32::try_from(value).ok()?),
+        Value::U32(_) => Value::U32(u32::try_from(value).ok()?),
+        Value::S64(_) => Value::S64(i64::try_from(value).ok()?),
+        Value::U64(_) => Value::U64(u64::try_from(value).ok()?),
+        _ => return None,
+    })
+}
+
+/// What a rule does with the calls it matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyAction {
+    /// Allow the call; final
+    Allow,
+    /// Deny the call; final
+    Deny,
+    /// Rewrite arguments and continue evaluation
+    Modify(Vec<ArgumentRewrite>),
+    /// Log the call and continue evaluation
+    Log,
+}
+
+impl PolicyAction {
+    const fn keyword(&self) -> &'static str {
+        match self {
+            PolicyAction::Allow => "allow",
+            PolicyAction::Deny => "deny",
+            PolicyAction::Modify(_) => "modify",
+            PolicyAction::Log => "log",
+        }
+    }
+}
+
+/// A single rule of a [`Policy`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyRule {
+    /// Glob matching the calling component
+    pub source:     String,
+    /// Glob matching the target component or host
+    pub target:     String,
+    /// Glob matching the function name
+    pub function:   String,
+    /// Conditions that must all hold
+    pub predicates: Vec<ArgumentPredicate>,
+    /// Action taken for matching calls
+    pub action:     PolicyAction,
+}
+
+impl PolicyRule {
+    /// Create a rule matching every call
+    #[must_use]
+    pub fn new(action: PolicyAction) -> Self {
+        Self {
+            source: ANY_NAME.to_string(),
+            target: ANY_NAME.to_string(),
+            function: ANY_NAME.to_string(),
+            predicates: Vec::new(),
+            action,
+        }
+    }
+
+    /// Restrict the rule to calls from components matching `source`
+    #[must_use]
+    pub fn from_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
+
+    /// Restrict the rule to calls to targets matching `target`
+    #[must_use]
+    pub fn to_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// Restrict the rule to functions matching `function`
+    #[must_use]
+    pub fn for_function(mut self, function: &str) -> Self {
+        self.function = function.to_string();
+        self
+    }
+
+    /// Add a condition on an argument
+    #[must_use]
+    pub fn when(mut self, index: usize, comparison: Comparison, value: i64) -> Self {
+        self.predicates.push(ArgumentPredicate {
+            index,
+            comparison,
+            value,
+        });
+        self
+    }
+
+    /// Whether the rule applies to a call
+    #[must_use]
+    pub fn matches(&self, source: &str, target: &str, function: &str, args: &[Value]) -> bool {
+        glob_match(&self.source, source)
+            && glob_match(&self.target, target)
+            && glob_match(&self.function, function)
+            && self.predicates.iter().all(|predicate| predicate.matches(args))
+    }
+}
+
+/// Result of evaluating a [`Policy`] for one call
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyVerdict {
+    /// Whether the call may proceed
+    pub allowed:    bool,
+    /// Index of the `allow` or `deny` rule that decided, or `None` if the
+    /// default applied
+    pub decided_by: Option<usize>,
+    /// Arguments after all matching `modify` rules
+    pub args:       Vec<Value>,
+    /// Indices of the matching `log` rules
+    pub logged:     Vec<usize>,
+}
+
+/// Ordered set of rules deciding which calls may proceed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// Decision when no `allow` or `deny` rule matches
+    pub default_allow: bool,
+    /// Rules in evaluation order
+    pub rules:         Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// Create an empty policy with the given default
+    #[must_use]
+    pub fn new(default_allow: bool) -> Self {
+        Self {
+            default_allow,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Append a rule
+    #[must_use]
+    pub fn with_rule(mut self, rule: PolicyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Evaluate the policy for a call
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a matching `modify` rule cannot rewrite an
+    /// argument.
+    pub fn evaluate(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<PolicyVerdict> {
+        let mut args = args.to_vec();
+        let mut logged = Vec::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !rule.matches(source, target, function, &args) {
+                continue;
+            }
+            match &rule.action {
+                PolicyAction::Allow | PolicyAction::Deny => {
+                    return Ok(PolicyVerdict {
+                        allowed: rule.action == PolicyAction::Allow,
+                        decided_by: Some(index),
+                        args,
+                        logged,
+                    });
+                },
+                PolicyAction::Modify(rewrites) => {
+                    for rewrite in rewrites {
+                        rewrite.apply(&mut args)?;
+                    }
+                },
+                PolicyAction::Log => logged.push(index),
+            }
+        }
+
+        Ok(PolicyVerdict {
+            allowed: self.default_allow,
+            decided_by: None,
+            args,
+            logged,
+        })
+    }
+
+    /// Parse a policy from the rule format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is missing, a line is malformed, or
+    /// the policy exceeds [`MAX_POLICY_RULES`] or [`MAX_RULE_TERMS`].
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().map(str::trim);
+        if lines.next() != Some(POLICY_HEADER) {
+            return Err(parse_error("Missing policy header"));
+        }
+
+        let mut policy = Self::new(false);
+        let mut seen_default = false;
+        for line in lines.filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let mut tokens = line.split_whitespace();
+            let keyword = tokens.next().unwrap_or_default();
+            if keyword == "default" {
+                if seen_default {
+                    return Err(parse_error("Duplicate default in policy"));
+                }
+                seen_default = true;
+                policy.default_allow = match (tokens.next(), tokens.next()) {
+                    (Some("allow"), None) => true,
+                    (Some("deny"), None) => false,
+                    _ => return Err(parse_error("Invalid default in policy")),
+                };
+                continue;
+            }
+            policy.push_rule(parse_rule(keyword, tokens)?)?;
+        }
+        Ok(policy)
+    }
+
+    /// Serialize the policy to the rule format
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::from(POLICY_HEADER);
+        out.push('\n');
+        out.push_str(if self.default_allow { "default allow\n" } else { "default deny\n" });
+        for rule in &self.rules {
+            let _ = write!(
+                out,
+                "{} {} {} {}",
+                rule.action.keyword(),
+                rule.source,
+                rule.target,
+                rule.function
+            );
+            for predicate in &rule.predicates {
+                let _ = write!(
+                    out,
+                    " arg{}{}{}",
+                    predicate.index,
+                    predicate.comparison.symbol(),
+                    predicate.value
+                );
+            }
+            if let PolicyAction::Modify(rewrites) = &rule.action {
+                out.push(' ');
+                out.push_str(REWRITE_SEPARATOR);
+                for rewrite in rewrites {
+                    let _ = match rewrite.rewrite {
+                        Rewrite::Set(value) => write!(out, " arg{}={value}", rewrite.index),
+                        Rewrite::Clamp { min, max } => {
+                            write!(out, " arg{}={min}..{max}", rewrite.index)
+                        },
+                    };
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse a policy from JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not valid JSON, does not describe a
+    /// policy, or exceeds [`MAX_POLICY_RULES`] or [`MAX_RULE_TERMS`].
+    pub fn from_json(text: &str) -> Result<Self> {
+        let document: serde_json::Value =
+            serde_json::from_str(text).map_err(|_| parse_error("Invalid JSON in policy"))?;
+        let object = json_object(&document, &["default", "rules"])?;
+
+        let default_allow = match object.get("default").map(serde_json::Value::as_str) {
+            None | Some(Some("deny")) => false,
+            Some(Some("allow")) => true,
+            Some(_) => return Err(parse_error("Invalid default in policy")),
+        };
+
+        let mut policy = Self::new(default_allow);
+        for rule in json_array(object.get("rules"))? {
+            policy.push_rule(parse_json_rule(rule)?)?;
+        }
+        Ok(policy)
+    }
+
+    fn push_rule(&mut self, rule: PolicyRule) -> Result<()> {
+        if self.rules.len() >= MAX_POLICY_RULES {
+            return Err(capacity_error("Policy exceeds maximum number of rules"));
+        }
+        self.rules.push(rule);
+        Ok(())
+    }
+}
+
+fn parse_rule<'a>(keyword: &str, mut tokens: impl Iterator<Item = &'a str>) -> Result<PolicyRule> {
+    let mut next = || tokens.next().ok_or_else(|| parse_error("Truncated policy rule"));
+    let source = next()?.to_string();
+    let target = next()?.to_string();
+    let function = next()?.to_string();
+
+    let mut predicates = Vec::new();
+    let mut rewrites = None;
+    while let Ok(token) = next() {
+        if token == REWRITE_SEPARATOR {
+            rewrites = Some(Vec::new());
+        } else if let Some(rewrites) = rewrites.as_mut() {
+            push_term(rewrites, parse_rewrite(token)?)?;
+        } else {
+            push_term(&mut predicates, parse_predicate(token)?)?;
+        }
+    }
+
+    let action = match (keyword, rewrites) {
+        ("allow", None) => PolicyAction::Allow,
+        ("deny", None) => PolicyAction::Deny,
+        ("log", None) => PolicyAction::Log,
+        ("modify", Some(rewrites)) if !rewrites.is_empty() => PolicyAction::Modify(rewrites),
+        ("modify", _) => return Err(parse_error("Modify rule without rewrites")),
+        ("allow" | "deny" | "log", Some(_)) => {
+            return Err(parse_error("Rewrites are only allowed in modify rules"))
+        },
+        _ => return Err(parse_error("Unknown policy action")),
+    };
+
+    Ok(PolicyRule {
+        source,
+        target,
+        function,
+        predicates,
+        action,
+    })
+}
+
+/// Split `arg<N><rest>` into `N` and `rest`
+fn parse_arg_index(token: &str) -> Result<(usize, &str)> {
+    let rest = token
+        .strip_prefix("arg")
+        .ok_or_else(|| parse_error("Policy term must start with arg<N>"))?;
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let index = rest[..digits]
+        .parse()
+        .map_err(|_| parse_error("Invalid argument index in policy"))?;
+    Ok((index, &rest[digits..]))
+}
+
+fn parse_predicate(token: &str) -> Result<ArgumentPredicate> {
+    let (index, rest) = parse_arg_index(token)?;
+    let (symbol, comparison) = Comparison::OPERATORS
+        .iter()
+        .find(|(symbol, _)| rest.starts_with(symbol))
+        .ok_or_else(|| parse_error("Invalid comparison in policy"))?;
+    Ok(ArgumentPredicate {
+        index,
+        comparison: *comparison,
+        value: parse_int(&rest[symbol.len()..])?,
+    })
+}
+
+fn parse_rewrite(token: &str) -> Result<ArgumentRewrite> {
+    let (index, rest) = parse_arg_index(token)?;
+    let value = rest
+        .strip_prefix('=')
+        .ok_or_else(|| parse_error("Invalid rewrite in policy"))?;
+    let rewrite = match value.split_once("..") {
+        Some((min, max)) => clamp(parse_int(min)?, parse_int(max)?)?,
+        None => Rewrite::Set(parse_int(value)?),
+    };
+    Ok(ArgumentRewrite { index, rewrite })
+}
+
+fn clamp(min: i64, max: i64) -> Result<Rewrite> {
+    if min > max {
+        return Err(parse_error("Empty clamp range in policy"));
+    }
+    Ok(Rewrite::Clamp { min, max })
+}
+
+fn push_term<T>(terms: &mut Vec<T>, term: T) -> Result<()> {
+    if terms.len() >= MAX_RULE_TERMS {
+        return Err(capacity_error("Policy rule exceeds maximum number of terms"));
+    }
+    terms.push(term);
+    Ok(())
+}
+
+fn parse_int(field: &str) -> Result<i64> {
+    field.parse().map_err(|_| parse_error("Invalid integer in policy"))
+}
+
+// ---------------------------------------------------------------------------
+// JSON
+// ---------------------------------------------------------------------------
+
+type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// `value` as an object containing only `keys`
+fn json_object<'a>(value: &'a serde_json::Value, keys: &[&str]) -> Result<&'a JsonObject> {
+    let object = value.as_object().ok_or_else(|| parse_error("Expected JSON object in policy"))?;
+    if object.keys().any(|key| !keys.contains(&key.as_str())) {
+        return Err(parse_error("Unknown key in policy"));
+    }
+    Ok(object)
+}
+
+/// An optional array, empty if absent
+fn json_array(value: Option<&serde_json::Value>) -> Result<&[serde_json::Value]> {
+    match value {
+        None => Ok(&[]),
+        Some(value) => value
+            .as_array()
+            .map(Vec::as_slice)
+            .ok_or_else(|| parse_error("Expected JSON array in policy")),
+    }
+}
+
+fn json_pattern(object: &JsonObject, key: &str) -> Result<String> {
+    match object.get(key) {
+        None => Ok(ANY_NAME.to_string()),
+        Some(value) => value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| parse_error("Expected string pattern in policy")),
+    }
+}
+
+fn json_int(object: &JsonObject, key: &str) -> Result<i64> {
+    object
+        .get(key)
+        .and_then(serde_json::Value::as_i64)
+        .ok_or_else(|| parse_error("Expected integer in policy"))
+}
+
+fn json_index(object: &JsonObject) -> Result<usize> {
+    object
+        .get("arg")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|index| usize::try_from(index).ok())
+        .ok_or_else(|| parse_error("Invalid argument index in policy"))
+}
+
+fn parse_json_rule(value: &serde_json::Value) -> Result<PolicyRule> {
+    let object = json_object(value, &["action", "source", "target", "function", "when", "set"])?;
+
+    let mut predicates = Vec::new();
+    for predicate in json_array(object.get("when"))? {
+        let predicate = json_object(predicate, &["arg", "op", "value"])?;
+        let comparison = predicate
+            .get("op")
+            .and_then(serde_json::Value::as_str)
+            .and_then(Comparison::from_symbol)
+            .ok_or_else(|| parse_error("Invalid comparison in policy"))?;
+        push_term(&mut predicates, ArgumentPredicate {
+            index: json_index(predicate)?,
+            comparison,
+            value: json_int(predicate, "value")?,
+        })?;
+    }
+
+    let mut rewrites = Vec::new();
+    for rewrite in json_array(object.get("set"))? {
+        let rewrite = json_object(rewrite, &["arg", "value", "min", "max"])?;
+        let kind = if rewrite.contains_key("value") {
+            if rewrite.contains_key("min") || rewrite.contains_key("max") {
+                return Err(parse_error("Rewrite has both a value and a range"));
+            }
+            Rewrite::Set(json_int(rewrite, "value")?)
+        } else {
+            clamp(json_int(rewrite, "min")?, json_int(rewrite, "max")?)?
+        };
+        push_term(&mut rewrites, ArgumentRewrite {
+            index:   json_index(rewrite)?,
+            rewrite: kind,
+        })?;
+    }
+
+    let action = match object.get("action").and_then(serde_json::Value::as_str) {
+        Some("allow") if rewrites.is_empty() => PolicyAction::Allow,
+        Some("deny") if rewrites.is_empty() => PolicyAction::Deny,
+        Some("log") if rewrites.is_empty() => PolicyAction::Log,
+        Some("modify") if !rewrites.is_empty() => PolicyAction::Modify(rewrites),
+        Some("modify") => return Err(parse_error("Modify rule without rewrites")),
+        Some("allow" | "deny" | "log") => {
+            return Err(parse_error("Rewrites are only allowed in modify rules"))
+        },
+        _ => return Err(parse_error("Unknown policy action")),
+    };
+
+    Ok(PolicyRule {
+        source: json_pattern(object, "source")?,
+        target: json_pattern(object, "target")?,
+        function: json_pattern(object, "function")?,
+        predicates,
+        action,
+    })
+}
+
+fn parse_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Parse, codes::PARSE_ERROR, message)
+}
+
+fn capacity_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Capacity, codes::CAPACITY_EXCEEDED, message)
+}
+
+fn rewrite_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Security, codes::INVALID_PARAMETER, message)
+}
+
+/// Error returned for calls denied by a policy
+fn denied() -> Error {
+    Error::new(
+        ErrorCategory::Security,
+        codes::ACCESS_DENIED,
+        "Security error: Function call denied by policy",
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Strategy
+// ---------------------------------------------------------------------------
+
+/// A strategy enforcing a replaceable [`Policy`]
+///
+/// Clones created through [`LinkInterceptorStrategy::clone_strategy`] share
+/// the policy, so [`PolicyStrategy::set_policy`] takes effect for every
+/// interceptor the strategy was added to.
+#[derive(Debug)]
+pub struct PolicyStrategy {
+    policy: Arc<RwLock<Arc<Policy>>>,
+}
+
+impl PolicyStrategy {
+    /// Create a strategy enforcing `policy`
+    #[must_use]
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy: Arc::new(RwLock::new(Arc::new(policy))),
+        }
+    }
+
+    /// Create a strategy from a policy in the rule format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy cannot be parsed.
+    pub fn from_text(text: &str) -> Result<Self> {
+        Ok(Self::new(Policy::parse(text)?))
+    }
+
+    /// Create a strategy from a policy in JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy cannot be parsed.
+    pub fn from_json(text: &str) -> Result<Self> {
+        Ok(Self::new(Policy::from_json(text)?))
+    }
+
+    /// The policy currently enforced
+    #[must_use]
+    pub fn policy(&self) -> Arc<Policy> {
+        match self.policy.read() {
+            Ok(policy) => Arc::clone(&policy),
+            Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+        }
+    }
+
+    /// Replace the enforced policy
+    ///
+    /// Calls already being evaluated finish under the previous policy.
+    pub fn set_policy(&self, policy: Policy) {
+        let policy = Arc::new(policy);
+        match self.policy.write() {
+            Ok(mut current) => *current = policy,
+            Err(poisoned) => *poisoned.into_inner() = policy,
+        }
+    }
+}
+
+impl LinkInterceptorStrategy for PolicyStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        let verdict = self.policy().evaluate(source, target, function, args)?;
+
+        for rule in &verdict.logged {
+            log::info!(
+                target: "wrt_intercept::policy",
+                "rule {rule}: {source}->{target}::{function} ({} args)",
+                args.len()
+            );
+        }
+
+        if verdict.allowed {
+            Ok(verdict.args)
+        } else {
+            Err(denied())
+        }
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        _function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        result
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(Self {
+            policy: Arc::clone(&self.policy),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LinkInterceptor;
+
+    const POLICY: &str = "wrt-policy v1
+# file access for the app only
+default deny
+log * wasi:sockets/* *
+modify app host send arg0>=0 -> arg1=0..1500
+deny * * write arg2>4096
+allow app wasi:filesystem/* read*
+allow app host *
+";
+
+    #[test]
+    fn test_policy_text_round_trip() {
+        let policy = Policy::parse(POLICY).unwrap();
+        assert!(!policy.default_allow);
+        assert_eq!(policy.rules.len(), 5);
+        assert_eq!(
+            policy.rules[1].action,
+            PolicyAction::Modify(vec![ArgumentRewrite {
+                index:   1,
+                rewrite: Rewrite::Clamp { min: 0, max: 1500 },
+            }])
+        );
+        assert_eq!(policy.rules[2].predicates, vec![ArgumentPredicate {
+            index:      2,
+            comparison: Comparison::Gt,
+            value:      4096,
+        }]);
+        assert_eq!(Policy::parse(&policy.to_text()).unwrap(), policy);
+
+        assert!(Policy::parse("default deny\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\ndefault deny\ndefault allow\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\npermit * * *\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\nallow * *\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\nallow * * * arg0~1\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\nallow * * * -> arg0=1\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\nmodify * * * arg0>1\n").is_err());
+        assert!(Policy::parse("wrt-policy v1\nmodify * * * -> arg0=5..1\n").is_err());
+    }
+
+    #[test]
+    fn test_policy_evaluation() {
+        let policy = Policy::parse(POLICY).unwrap();
+
+        let verdict = policy.evaluate("app", "wasi:filesystem/types", "read-via-stream", &[]).unwrap();
+        assert!(verdict.allowed);
+        assert_eq!(verdict.decided_by, Some(3));
+
+        // The deny rule comes before the catch-all allow for the app
+        let small = [Value::I32(0), Value::I32(0), Value::I32(16)];
+        let large = [Value::I32(0), Value::I32(0), Value::I32(8192)];
+        assert!(policy.evaluate("app", "host", "write", &small).unwrap().allowed);
+        assert!(!policy.evaluate("app", "host", "write", &large).unwrap().allowed);
+
+        // Rewrites keep the argument type and apply before later rules
+        let verdict = policy.evaluate("app", "host", "send", &[Value::I32(3), Value::I64(-5)]).unwrap();
+        assert!(verdict.allowed);
+        assert_eq!(verdict.args, vec![Value::I32(3), Value::I64(0)]);
+        let verdict = policy.evaluate("app", "host", "send", &[Value::I32(3), Value::U32(9000)]).unwrap();
+        assert!(matches!(verdict.args[1], Value::U32(1500)));
+        assert!(policy.evaluate("app", "host", "send", &[Value::I32(3), Value::F32(Default::default())]).is_err());
+
+        let verdict = policy.evaluate("other", "wasi:sockets/tcp", "connect", &[]).unwrap();
+        assert!(!verdict.allowed);
+        assert_eq!(verdict.decided_by, None);
+        assert_eq!(verdict.logged, vec![0]);
+    }
+
+    #[test]
+    fn test_policy_from_json() {
+        let json = r#"{
+            "default": "allow",
+            "rules": [
+                { "action": "deny", "function": "write", "when": [{ "arg": 2, "op": ">", "value": 4096 }] },
+                { "action": "modify", "source": "app", "set": [{ "arg": 0, "value": 7 }, { "arg": 1, "min": 0, "max": 10 }] }
+            ]
+        }"#;
+        let policy = Policy::from_json(json).unwrap();
+        let expected = Policy::parse(
+            "wrt-policy v1\ndefault allow\ndeny * * write arg2>4096\nmodify app * * -> arg0=7 arg1=0..10\n",
+        )
+        .unwrap();
+        assert_eq!(policy, expected);
+
+        assert!(Policy::from_json(r#"{ "rules": [{ "action": "deny", "wen": [] }] }"#).is_err());
+        assert!(Policy::from_json(r#"{ "rules": [{ "action": "modify" }] }"#).is_err());
+        assert!(Policy::from_json(r#"{ "default": "maybe" }"#).is_err());
+        assert!(!Policy::from_json("{}").unwrap().default_allow);
+    }
+
+    #[test]
+    fn test_policy_strategy_replacement() {
+        let strategy = Arc::new(PolicyStrategy::from_text("wrt-policy v1\ndefault allow\n").unwrap());
+        let mut interceptor = LinkInterceptor::new("app");
+        interceptor.add_strategy(strategy.clone_strategy());
+
+        let call = |interceptor: &LinkInterceptor| {
+            interceptor.intercept_call("host", "write", &[Value::I32(1)], |args| Ok(args))
+        };
+        assert_eq!(call(&interceptor).unwrap(), vec![Value::I32(1)]);
+
+        strategy.set_policy(
+            Policy::new(true)
+                .with_rule(PolicyRule::new(PolicyAction::Deny).for_function("write").when(0, Comparison::Eq, 1)),
+        );
+        let error = call(&interceptor).unwrap_err();
+        assert_eq!(error.category, ErrorCategory::Security);
+        assert_eq!(error.code, codes::ACCESS_DENIED);
+    }
+}
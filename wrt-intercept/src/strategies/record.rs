@@ -0,0 +1,1472 @@
+//! Record/replay strategies for deterministic regression testing
+//!
+//! A [`RecordingStrategy`] observes every call passing through an
+//! interceptor and appends it to a [`Trace`]: call arguments, results or
+//! errors, resource operations and canonical lifts/lowers. A
+//! [`ReplayStrategy`] later feeds the recorded results back without
+//! executing the target, so a component can be exercised without its
+//! dependencies.
+//!
+//! # Trace format
+//!
+//! Traces serialize to line-oriented text starting with [`TRACE_HEADER`].
+//! The text contains no timestamps or addresses of host objects, so the
+//! same execution always produces the same trace:
+//!
+//! ```text
+//! wrt-trace v1
+//! call <seq> <parent> <source> <target> <function> <values>
+//! return <seq> ok <values>
+//! return <seq> err <category> <code>
+//! resource <parent> <handle> <new|drop|rep> <type-index>
+//! lift <parent> <addr> <type-checksum>
+//! lower <parent> <addr> <type-checksum> <bytes>
+//! ```
+//!
+//! - `<parent>` is the sequence number of the call that was in progress when
+//!   the event was recorded, or `-` for events at the top level.
+//! - Names are percent-escaped, so they never contain spaces.
+//! - `<values>` and `<bytes>` are lowercase hex, or `-` when empty. Values
+//!   use the discriminants of `Value`'s `ToBytes` encoding followed by a
+//!   little-endian payload; component model values are encoded recursively.
+//!
+//! # Replay
+//!
+//! Only top-level events are replayed. Anything recorded inside a call
+//! belongs to the dependency that handled it, which the replay bypasses.
+//! Calls must arrive in the recorded order with the recorded arguments;
+//! resource operations and canonical lifts/lowers are checked against the
+//! recording the same way. Any divergence is reported as a
+//! [`ErrorCategory::Verification`] error.
+//!
+//! Recording assumes calls are issued from a single thread: nesting is
+//! derived from the order of `before_call` and `after_call`.
+//!
+//! Note: This strategy requires the `std` feature.
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::{
+    component_value::ValType,
+    float_repr::{
+        FloatBits32,
+        FloatBits64,
+    },
+    resource::{
+        ResourceCanonicalOperation,
+        ResourceDrop,
+        ResourceNew,
+        ResourceRep,
+    },
+    traits::Checksummable,
+    values::{
+        ExternRef,
+        FuncRef,
+        V128,
+    },
+    verification::Checksum,
+    NoStdProvider,
+};
+
+use crate::{
+    prelude::{
+        fmt,
+        Value,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// First line of every serialized trace
+pub const TRACE_HEADER: &str = "wrt-trace v1";
+
+/// Maximum nesting depth of a recorded component value
+pub const MAX_VALUE_DEPTH: usize = 64;
+
+/// Marker written for an empty hex field or a missing parent
+const EMPTY_FIELD: &str = "-";
+
+/// Every error category, used to restore recorded errors by name
+const ERROR_CATEGORIES: [ErrorCategory; 29] = [
+    ErrorCategory::Core,
+    ErrorCategory::Component,
+    ErrorCategory::Resource,
+    ErrorCategory::Memory,
+    ErrorCategory::Validation,
+    ErrorCategory::Type,
+    ErrorCategory::Runtime,
+    ErrorCategory::System,
+    ErrorCategory::Io,
+    ErrorCategory::Unknown,
+    ErrorCategory::Parse,
+    ErrorCategory::Concurrency,
+    ErrorCategory::Capacity,
+    ErrorCategory::RuntimeTrap,
+    ErrorCategory::Initialization,
+    ErrorCategory::NotSupported,
+    ErrorCategory::Safety,
+    ErrorCategory::Security,
+    ErrorCategory::Parameter,
+    ErrorCategory::Verification,
+    ErrorCategory::ComponentRuntime,
+    ErrorCategory::PlatformRuntime,
+    ErrorCategory::FoundationRuntime,
+    ErrorCategory::AsyncRuntime,
+    ErrorCategory::Platform,
+    ErrorCategory::InvalidState,
+    ErrorCategory::NotImplemented,
+    ErrorCategory::InvalidInput,
+    ErrorCategory::Async,
+];
+
+/// Outcome of a recorded call
+#[derive(Debug, Clone)]
+pub enum CallOutcome {
+    /// The call returned these values
+    Ok(Vec<Value>),
+    /// The call failed with this error
+    ///
+    /// Error messages are not part of the trace; replayed errors carry a
+    /// generic message.
+    Err {
+        /// Category of the recorded error
+        category: ErrorCategory,
+        /// Code of the recorded error
+        code:     u16,
+    },
+}
+
+/// Kind of a recorded resource operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceOpKind {
+    /// `resource.new`
+    New,
+    /// `resource.drop`
+    Drop,
+    /// `resource.rep`
+    Rep,
+}
+
+impl ResourceOpKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Drop => "drop",
+            Self::Rep => "rep",
+        }
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        match text {
+            "new" => Ok(Self::New),
+            "drop" => Ok(Self::Drop),
+            "rep" => Ok(Self::Rep),
+            _ => Err(parse_error("Unknown resource operation in trace")),
+        }
+    }
+}
+
+/// One entry of a [`Trace`]
+#[derive(Debug, Clone)]
+pub enum TraceEvent {
+    /// A call entered the interceptor
+    Call {
+        /// Sequence number, unique within the trace
+        seq:      u64,
+        /// Call in progress when this call was made
+        parent:   Option<u64>,
+        /// Calling component
+        source:   String,
+        /// Target component or host
+        target:   String,
+        /// Called function
+        function: String,
+        /// Arguments as seen by the recording strategy
+        args:     Vec<Value>,
+    },
+    /// A call completed
+    Return {
+        /// Sequence number of the matching [`TraceEvent::Call`]
+        seq:     u64,
+        /// Returned values or error
+        outcome: CallOutcome,
+    },
+    /// A resource operation was intercepted
+    Resource {
+        /// Call in progress when the operation happened
+        parent:   Option<u64>,
+        /// Resource handle
+        handle:   u32,
+        /// Operation kind
+        kind:     ResourceOpKind,
+        /// Resource type index
+        type_idx: u32,
+    },
+    /// A canonical ABI lift was intercepted
+    Lift {
+        /// Call in progress when the lift happened
+        parent: Option<u64>,
+        /// Address lifted from
+        addr:   u32,
+        /// Checksum identifying the lifted type
+        ty:     u32,
+    },
+    /// A canonical ABI lower was intercepted
+    Lower {
+        /// Call in progress when the lower happened
+        parent: Option<u64>,
+        /// Address lowered to
+        addr:   u32,
+        /// Checksum identifying the lowered type
+        ty:     u32,
+        /// Serialized value being lowered
+        data:   Vec<u8>,
+    },
+}
+
+impl TraceEvent {
+    /// Call in progress when this event was recorded
+    #[must_use]
+    pub fn parent(&self) -> Option<u64> {
+        match self {
+            Self::Call { parent, .. }
+            | Self::Resource { parent, .. }
+            | Self::Lift { parent, .. }
+            | Self::Lower { parent, .. } => *parent,
+            Self::Return { .. } => None,
+        }
+    }
+
+    fn write_line(&self, out: &mut String) -> Result<()> {
+        match self {
+            Self::Call {
+                seq,
+                parent,
+                source,
+                target,
+                function,
+                args,
+            } => {
+                out.push_str("call ");
+                push_u64(out, *seq);
+                push_parent(out, *parent);
+                push_name(out, source);
+                push_name(out, target);
+                push_name(out, function);
+                out.push(' ');
+                push_hex(out, &encode_values(args)?);
+            },
+            Self::Return { seq, outcome } => {
+                out.push_str("return ");
+                push_u64(out, *seq);
+                match outcome {
+                    CallOutcome::Ok(values) => {
+                        out.push_str(" ok ");
+                        push_hex(out, &encode_values(values)?);
+                    },
+                    CallOutcome::Err { category, code } => {
+                        let _ = write!(out, " err {category:?} {code}");
+                    },
+                }
+            },
+            Self::Resource {
+                parent,
+                handle,
+                kind,
+                type_idx,
+            } => {
+                out.push_str("resource");
+                push_parent(out, *parent);
+                let _ = write!(out, " {handle} {} {type_idx}", kind.as_str());
+            },
+            Self::Lift { parent, addr, ty } => {
+                out.push_str("lift");
+                push_parent(out, *parent);
+                let _ = write!(out, " {addr} {ty:08x}");
+            },
+            Self::Lower {
+                parent,
+                addr,
+                ty,
+                data,
+            } => {
+                out.push_str("lower");
+                push_parent(out, *parent);
+                let _ = write!(out, " {addr} {ty:08x} ");
+                push_hex(out, data);
+            },
+        }
+        out.push('\n');
+        Ok(())
+    }
+
+    fn parse_line(line: &str) -> Result<Self> {
+        let mut fields = line.split(' ');
+        let kind = fields.next().ok_or_else(|| parse_error("Empty trace line"))?;
+        let mut next = || fields.next().ok_or_else(|| parse_error("Truncated trace line"));
+
+        let event = match kind {
+            "call" => Self::Call {
+                seq:      parse_number(next()?)?,
+                parent:   parse_parent(next()?)?,
+                source:   unescape_name(next()?)?,
+                target:   unescape_name(next()?)?,
+                function: unescape_name(next()?)?,
+                args:     decode_values(&parse_hex(next()?)?)?,
+            },
+            "return" => {
+                let seq = parse_number(next()?)?;
+                let outcome = match next()? {
+                    "ok" => CallOutcome::Ok(decode_values(&parse_hex(next()?)?)?),
+                    "err" => CallOutcome::Err {
+                        category: parse_category(next()?)?,
+                        code:     parse_number(next()?)?,
+                    },
+                    _ => return Err(parse_error("Unknown call outcome in trace")),
+                };
+                Self::Return { seq, outcome }
+            },
+            "resource" => Self::Resource {
+                parent:   parse_parent(next()?)?,
+                handle:   parse_number(next()?)?,
+                kind:     ResourceOpKind::parse(next()?)?,
+                type_idx: parse_number(next()?)?,
+            },
+            "lift" => Self::Lift {
+                parent: parse_parent(next()?)?,
+                addr:   parse_number(next()?)?,
+                ty:     parse_checksum(next()?)?,
+            },
+            "lower" => Self::Lower {
+                parent: parse_parent(next()?)?,
+                addr:   parse_number(next()?)?,
+                ty:     parse_checksum(next()?)?,
+                data:   parse_hex(next()?)?,
+            },
+            _ => return Err(parse_error("Unknown trace event")),
+        };
+
+        if fields.next().is_some() {
+            return Err(parse_error("Trailing fields in trace line"));
+        }
+        Ok(event)
+    }
+}
+
+/// Ordered record of intercepted activity
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Create an empty trace
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recorded events in order
+    #[must_use]
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Append an event
+    pub fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    /// Serialize the trace to its text form
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recorded value cannot be encoded, such as a GC
+    /// struct or array reference.
+    pub fn to_text(&self) -> Result<String> {
+        let mut out = String::from(TRACE_HEADER);
+        out.push('\n');
+        for event in &self.events {
+            event.write_line(&mut out)?;
+        }
+        Ok(out)
+    }
+
+    /// Parse a trace from its text form
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is missing or any line is malformed.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+        if lines.next() != Some(TRACE_HEADER) {
+            return Err(parse_error("Missing trace header"));
+        }
+
+        let mut trace = Self::new();
+        for line in lines.filter(|line| !line.is_empty()) {
+            trace.push(TraceEvent::parse_line(line)?);
+        }
+        Ok(trace)
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_text().map_err(|_| fmt::Error)?)
+    }
+}
+
+/// State shared between a [`RecordingStrategy`] and its clones
+#[derive(Debug, Default)]
+struct RecorderState {
+    trace:    Trace,
+    next_seq: u64,
+    /// Calls that have entered but not yet returned, innermost last
+    open:     Vec<(u64, String, String)>,
+}
+
+impl RecorderState {
+    fn parent(&self) -> Option<u64> {
+        self.open.last().map(|(seq, ..)| *seq)
+    }
+}
+
+/// Strategy recording every intercepted call into a [`Trace`]
+///
+/// The strategy only observes: arguments and results pass through
+/// unchanged and canonical operations proceed normally. Clones made with
+/// `clone_strategy` append to the same trace.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingStrategy {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl RecordingStrategy {
+    /// Create a strategy with an empty trace
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the trace recorded so far
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recorder lock is poisoned.
+    pub fn trace(&self) -> Result<Trace> {
+        Ok(self.lock()?.trace.clone())
+    }
+
+    /// Take the trace recorded so far, leaving an empty one behind
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recorder lock is poisoned.
+    pub fn take_trace(&self) -> Result<Trace> {
+        Ok(core::mem::take(&mut self.lock()?.trace))
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, RecorderState>> {
+        self.state.lock().map_err(|_| poisoned())
+    }
+
+    fn record_nested(&self, event: impl FnOnce(Option<u64>) -> TraceEvent) -> Result<()> {
+        let mut state = self.lock()?;
+        let event = event(state.parent());
+        state.trace.push(event);
+        Ok(())
+    }
+}
+
+impl LinkInterceptorStrategy for RecordingStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        let mut state = self.lock()?;
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        let event = TraceEvent::Call {
+            seq,
+            parent: state.parent(),
+            source: source.to_string(),
+            target: target.to_string(),
+            function: function.to_string(),
+            args: args.to_vec(),
+        };
+        state.trace.push(event);
+        state.open.push((seq, target.to_string(), function.to_string()));
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        let mut state = self.lock()?;
+        // Calls close innermost first; search so that a call skipped by a
+        // failing strategy does not misattribute the ones around it.
+        let position = state
+            .open
+            .iter()
+            .rposition(|(_, open_target, open_function)| {
+                open_target == target && open_function == function
+            })
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorCategory::InvalidState,
+                    codes::INVALID_STATE,
+                    "Recorded call returned without entering",
+                )
+            })?;
+        let (seq, ..) = state.open.remove(position);
+
+        let outcome = match &result {
+            Ok(values) => CallOutcome::Ok(values.clone()),
+            Err(error) => CallOutcome::Err {
+                category: error.category,
+                code:     error.code,
+            },
+        };
+        state.trace.push(TraceEvent::Return { seq, outcome });
+        drop(state);
+        result
+    }
+
+    fn should_intercept_canonical(&self) -> bool {
+        true
+    }
+
+    fn intercept_lift(
+        &self,
+        ty: &ValType<NoStdProvider<64>>,
+        addr: u32,
+        _memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        self.record_nested(|parent| TraceEvent::Lift {
+            parent,
+            addr,
+            ty: type_checksum(ty),
+        })?;
+        Ok(None)
+    }
+
+    fn intercept_lower(
+        &self,
+        value_type: &ValType<NoStdProvider<64>>,
+        value_data: &[u8],
+        addr: u32,
+        _memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        self.record_nested(|parent| TraceEvent::Lower {
+            parent,
+            addr,
+            ty: type_checksum(value_type),
+            data: value_data.to_vec(),
+        })?;
+        Ok(false)
+    }
+
+    fn intercept_resource_operation(
+        &self,
+        handle: u32,
+        operation: &ResourceCanonicalOperation,
+    ) -> Result<Option<Vec<u8>>> {
+        let (kind, type_idx) = resource_op(operation);
+        self.record_nested(|parent| TraceEvent::Resource {
+            parent,
+            handle,
+            kind,
+            type_idx,
+        })?;
+        Ok(None)
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(self.clone())
+    }
+}
+
+/// A top-level call waiting to be replayed
+#[derive(Debug)]
+struct ReplayCall {
+    source:   String,
+    target:   String,
+    function: String,
+    args:     Vec<u8>,
+    outcome:  Option<CallOutcome>,
+}
+
+/// Remaining top-level events of a replay
+#[derive(Debug, Default)]
+struct ReplayState {
+    calls:     VecDeque<ReplayCall>,
+    resources: VecDeque<TraceEvent>,
+    canonical: VecDeque<TraceEvent>,
+}
+
+/// Strategy answering calls from a recorded [`Trace`]
+///
+/// Calls are bypassed: the target never runs and the recorded results (or
+/// error) are returned instead. Clones made with `clone_strategy` share the
+/// replay position.
+#[derive(Debug, Clone)]
+pub struct ReplayStrategy {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayStrategy {
+    /// Create a strategy replaying `trace`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recorded value cannot be re-encoded for
+    /// comparison.
+    pub fn new(trace: &Trace) -> Result<Self> {
+        let mut state = ReplayState::default();
+        let mut call_seqs = Vec::new();
+
+        for event in trace.events() {
+            match event {
+                TraceEvent::Call {
+                    seq,
+                    parent: None,
+                    source,
+                    target,
+                    function,
+                    args,
+                } => {
+                    call_seqs.push(*seq);
+                    state.calls.push_back(ReplayCall {
+                        source:   source.clone(),
+                        target:   target.clone(),
+                        function: function.clone(),
+                        args:     encode_values(args)?,
+                        outcome:  None,
+                    });
+                },
+                TraceEvent::Return { seq, outcome } => {
+                    if let Some(index) = call_seqs.iter().position(|call_seq| call_seq == seq) {
+                        state.calls[index].outcome = Some(outcome.clone());
+                    }
+                },
+                TraceEvent::Resource { parent: None, .. } => {
+                    state.resources.push_back(event.clone());
+                },
+                TraceEvent::Lift { parent: None, .. } | TraceEvent::Lower { parent: None, .. } => {
+                    state.canonical.push_back(event.clone());
+                },
+                _ => {},
+            }
+        }
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Create a strategy replaying a trace in text form
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trace cannot be parsed.
+    pub fn from_text(text: &str) -> Result<Self> {
+        Self::new(&Trace::parse(text)?)
+    }
+
+    /// Number of top-level calls not yet replayed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replay lock is poisoned.
+    pub fn remaining_calls(&self) -> Result<usize> {
+        Ok(self.lock()?.calls.len())
+    }
+
+    /// Whether every top-level event of the trace has been replayed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replay lock is poisoned.
+    pub fn is_complete(&self) -> Result<bool> {
+        let state = self.lock()?;
+        Ok(state.calls.is_empty() && state.resources.is_empty() && state.canonical.is_empty())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, ReplayState>> {
+        self.state.lock().map_err(|_| poisoned())
+    }
+
+    fn expect_canonical(&self, matches: impl FnOnce(&TraceEvent) -> bool) -> Result<()> {
+        let mut state = self.lock()?;
+        match state.canonical.pop_front() {
+            Some(event) if matches(&event) => Ok(()),
+            _ => Err(diverged("Canonical operation diverges from trace")),
+        }
+    }
+}
+
+impl LinkInterceptorStrategy for ReplayStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        let call = self
+            .lock()?
+            .calls
+            .pop_front()
+            .ok_or_else(|| diverged("Call not present in trace"))?;
+
+        if call.source != source
+            || call.target != target
+            || call.function != function
+            || call.args != encode_values(args)?
+        {
+            return Err(diverged("Call diverges from trace"));
+        }
+
+        match call.outcome {
+            Some(CallOutcome::Ok(values)) => Ok(values),
+            Some(CallOutcome::Err { category, code }) => {
+                Err(Error::new(category, code, "Replayed call failed"))
+            },
+            None => Err(diverged("Call has no recorded result")),
+        }
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        _function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        result
+    }
+
+    fn should_bypass(&self) -> bool {
+        true
+    }
+
+    fn should_intercept_canonical(&self) -> bool {
+        true
+    }
+
+    fn intercept_lift(
+        &self,
+        ty: &ValType<NoStdProvider<64>>,
+        addr: u32,
+        _memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let ty = type_checksum(ty);
+        self.expect_canonical(|event| {
+            matches!(event, TraceEvent::Lift { addr: a, ty: t, .. } if *a == addr && *t == ty)
+        })?;
+        Ok(None)
+    }
+
+    fn intercept_lower(
+        &self,
+        value_type: &ValType<NoStdProvider<64>>,
+        value_data: &[u8],
+        addr: u32,
+        _memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        let ty = type_checksum(value_type);
+        self.expect_canonical(|event| {
+            matches!(
+                event,
+                TraceEvent::Lower { addr: a, ty: t, data, .. }
+                    if *a == addr && *t == ty && data.as_slice() == value_data
+            )
+        })?;
+        Ok(false)
+    }
+
+    fn intercept_resource_operation(
+        &self,
+        handle: u32,
+        operation: &ResourceCanonicalOperation,
+    ) -> Result<Option<Vec<u8>>> {
+        let (kind, type_idx) = resource_op(operation);
+        let mut state = self.lock()?;
+        match state.resources.pop_front() {
+            Some(TraceEvent::Resource {
+                handle: h,
+                kind: k,
+                type_idx: t,
+                ..
+            }) if h == handle && k == kind && t == type_idx => Ok(None),
+            _ => Err(diverged("Resource operation diverges from trace")),
+        }
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(self.clone())
+    }
+}
+
+fn resource_op(operation: &ResourceCanonicalOperation) -> (ResourceOpKind, u32) {
+    match operation {
+        ResourceCanonicalOperation::New(ResourceNew { type_idx }) => {
+            (ResourceOpKind::New, *type_idx)
+        },
+        ResourceCanonicalOperation::Drop(ResourceDrop { type_idx }) => {
+            (ResourceOpKind::Drop, *type_idx)
+        },
+        ResourceCanonicalOperation::Rep(ResourceRep { type_idx }) => {
+            (ResourceOpKind::Rep, *type_idx)
+        },
+    }
+}
+
+/// Checksum identifying a value type independently of its memory provider
+fn type_checksum(ty: &ValType<NoStdProvider<64>>) -> u32 {
+    let mut checksum = Checksum::new();
+    ty.update_checksum(&mut checksum);
+    checksum.value()
+}
+
+fn poisoned() -> Error {
+    Error::new(
+        ErrorCategory::Concurrency,
+        codes::POISONED_LOCK,
+        "Record/replay lock poisoned",
+    )
+}
+
+fn diverged(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Verification, codes::VERIFICATION_FAILED, message)
+}
+
+fn parse_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Parse, codes::PARSE_ERROR, message)
+}
+
+// ---------------------------------------------------------------------------
+// Text fields
+// ---------------------------------------------------------------------------
+
+fn push_u64(out: &mut String, value: u64) {
+    let _ = write!(out, "{value}");
+}
+
+fn push_parent(out: &mut String, parent: Option<u64>) {
+    match parent {
+        Some(seq) => {
+            let _ = write!(out, " {seq}");
+        },
+        None => {
+            out.push(' ');
+            out.push_str(EMPTY_FIELD);
+        },
+    }
+}
+
+/// Append ` name`, percent-escaping bytes that would break tokenization
+pub(crate) fn push_name(out: &mut String, name: &str) {
+    out.push(' ');
+    if name.is_empty() {
+        out.push('%');
+        return;
+    }
+    for byte in name.bytes() {
+        if byte.is_ascii_graphic() && byte != b'%' {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02x}");
+        }
+    }
+}
+
+pub(crate) fn unescape_name(field: &str) -> Result<String> {
+    if field == "%" {
+        return Ok(String::new());
+    }
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut input = field.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let high = input.next().ok_or_else(|| parse_error("Truncated escape in trace"))?;
+            let low = input.next().ok_or_else(|| parse_error("Truncated escape in trace"))?;
+            bytes.push(hex_digit(high)? << 4 | hex_digit(low)?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| parse_error("Invalid UTF-8 in trace name"))
+}
+
+fn push_hex(out: &mut String, bytes: &[u8]) {
+    if bytes.is_empty() {
+        out.push_str(EMPTY_FIELD);
+    }
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+}
+
+fn parse_hex(field: &str) -> Result<Vec<u8>> {
+    if field == EMPTY_FIELD {
+        return Ok(Vec::new());
+    }
+    if field.len() % 2 != 0 {
+        return Err(parse_error("Odd-length hex field in trace"));
+    }
+    field
+        .as_bytes()
+        .chunks_exact(2)
+        .map(|pair| Ok(hex_digit(pair[0])? << 4 | hex_digit(pair[1])?))
+        .collect()
+}
+
+fn hex_digit(digit: u8) -> Result<u8> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        _ => Err(parse_error("Invalid hex digit in trace")),
+    }
+}
+
+fn parse_number<T: core::str::FromStr>(field: &str) -> Result<T> {
+    field.parse().map_err(|_| parse_error("Invalid number in trace"))
+}
+
+fn parse_parent(field: &str) -> Result<Option<u64>> {
+    if field == EMPTY_FIELD {
+        Ok(None)
+    } else {
+        parse_number(field).map(Some)
+    }
+}
+
+fn parse_checksum(field: &str) -> Result<u32> {
+    u32::from_str_radix(field, 16).map_err(|_| parse_error("Invalid type checksum in trace"))
+}
+
+fn parse_category(field: &str) -> Result<ErrorCategory> {
+    ERROR_CATEGORIES
+        .iter()
+        .copied()
+        .find(|category| format!("{category:?}") == field)
+        .ok_or_else(|| parse_error("Unknown error category in trace"))
+}
+
+// ---------------------------------------------------------------------------
+// Value encoding
+// ---------------------------------------------------------------------------
+
+/// Encode a sequence of values as a `u32` count followed by each value
+fn encode_values(values: &[Value]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    if values.is_empty() {
+        return Ok(out);
+    }
+    push_len(&mut out, values.len())?;
+    for value in values {
+        encode_value(&mut out, value)?;
+    }
+    Ok(out)
+}
+
+fn decode_values(bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut input = bytes;
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+    let count = read_u32(&mut input)?;
+    let values = (0..count).map(|_| decode_value(&mut input, 0)).collect::<Result<Vec<_>>>()?;
+    if !input.is_empty() {
+        return Err(parse_error("Trailing bytes after trace values"));
+    }
+    Ok(values)
+}
+
+fn push_len(out: &mut Vec<u8>, len: usize) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorCategory::Capacity,
+            codes::CAPACITY_EXCEEDED,
+            "Value too large to record",
+        )
+    })?;
+    out.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn push_str(out: &mut Vec<u8>, text: &str) -> Result<()> {
+    push_len(out, text.len())?;
+    out.extend_from_slice(text.as_bytes());
+    Ok(())
+}
+
+fn push_optional_u32(out: &mut Vec<u8>, value: Option<u32>) {
+    out.push(u8::from(value.is_some()));
+    out.extend_from_slice(&value.unwrap_or(0).to_le_bytes());
+}
+
+fn push_optional_value(out: &mut Vec<u8>, value: Option<&Value>) -> Result<()> {
+    out.push(u8::from(value.is_some()));
+    value.map_or(Ok(()), |value| encode_value(out, value))
+}
+
+/// Encode one value, using the discriminants of `Value`'s `ToBytes`
+fn encode_value(out: &mut Vec<u8>, value: &Value) -> Result<()> {
+    match value {
+        Value::I32(v) => {
+            out.push(0);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::I64(v) => {
+            out.push(1);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::F32(v) => {
+            out.push(2);
+            out.extend_from_slice(&v.0.to_le_bytes());
+        },
+        Value::F64(v) => {
+            out.push(3);
+            out.extend_from_slice(&v.0.to_le_bytes());
+        },
+        Value::V128(v) => {
+            out.push(4);
+            out.extend_from_slice(&v.bytes);
+        },
+        Value::FuncRef(v) => {
+            out.push(5);
+            push_optional_u32(out, v.as_ref().map(|r| r.index));
+        },
+        Value::ExternRef(v) => {
+            out.push(6);
+            push_optional_u32(out, v.as_ref().map(|r| r.index));
+        },
+        Value::Ref(v) => {
+            out.push(7);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::I16x8(v) => {
+            out.push(8);
+            out.extend_from_slice(&v.bytes);
+        },
+        Value::StructRef(_) | Value::ArrayRef(_) => {
+            return Err(Error::new(
+                ErrorCategory::NotSupported,
+                codes::UNSUPPORTED_OPERATION,
+                "GC references cannot be recorded",
+            ));
+        },
+        Value::Bool(v) => {
+            out.push(11);
+            out.push(u8::from(*v));
+        },
+        Value::S8(v) => {
+            out.push(12);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::U8(v) => {
+            out.push(13);
+            out.push(*v);
+        },
+        Value::S16(v) => {
+            out.push(14);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::U16(v) => {
+            out.push(15);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::S32(v) => {
+            out.push(16);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::U32(v) => {
+            out.push(17);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::S64(v) => {
+            out.push(18);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::U64(v) => {
+            out.push(19);
+            out.extend_from_slice(&v.to_le_bytes());
+        },
+        Value::Char(v) => {
+            out.push(20);
+            out.extend_from_slice(&u32::from(*v).to_le_bytes());
+        },
+        Value::String(v) => {
+            out.push(21);
+            push_str(out, v)?;
+        },
+        Value::List(items) | Value::Tuple(items) => {
+            out.push(if matches!(value, Value::List(_)) { 22 } else { 23 });
+            push_len(out, items.len())?;
+            for item in items {
+                encode_value(out, item)?;
+            }
+        },
+        Value::Record(fields) => {
+            out.push(24);
+            push_len(out, fields.len())?;
+            for (name, field) in fields {
+                push_str(out, name)?;
+                encode_value(out, field)?;
+            }
+        },
+        Value::Variant(case, payload) => {
+            out.push(25);
+            push_str(out, case)?;
+            push_optional_value(out, payload.as_deref())?;
+        },
+        Value::Enum(case) => {
+            out.push(26);
+            push_str(out, case)?;
+        },
+        Value::Option(v) => {
+            out.push(27);
+            push_optional_value(out, v.as_deref())?;
+        },
+        Value::Result(v) => {
+            out.push(28);
+            let (tag, inner) = match v {
+                Ok(inner) => (0, inner),
+                Err(inner) => (1, inner),
+            };
+            out.push(tag);
+            encode_value(out, inner)?;
+        },
+        Value::Flags(names) => {
+            out.push(29);
+            push_len(out, names.len())?;
+            for name in names {
+                push_str(out, name)?;
+            }
+        },
+        Value::Own(h) | Value::Borrow(h) | Value::Stream(h) | Value::Future(h) => {
+            out.push(match value {
+                Value::Own(_) => 30,
+                Value::Borrow(_) => 31,
+                Value::Stream(_) => 33,
+                _ => 34,
+            });
+            out.extend_from_slice(&h.to_le_bytes());
+        },
+        Value::Void => out.push(32),
+        Value::ExnRef(v) => {
+            out.push(35);
+            push_optional_u32(out, *v);
+        },
+        Value::I31Ref(v) => {
+            out.push(36);
+            out.push(u8::from(v.is_some()));
+            out.extend_from_slice(&v.unwrap_or(0).to_le_bytes());
+        },
+    }
+    Ok(())
+}
+
+fn read_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N]> {
+    if input.len() < N {
+        return Err(parse_error("Truncated value in trace"));
+    }
+    let (head, rest) = input.split_at(N);
+    *input = rest;
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(head);
+    Ok(bytes)
+}
+
+fn read_u8(input: &mut &[u8]) -> Result<u8> {
+    Ok(read_array::<1>(input)?[0])
+}
+
+fn read_u32(input: &mut &[u8]) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_array(input)?))
+}
+
+fn read_flag(input: &mut &[u8]) -> Result<bool> {
+    match read_u8(input)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(parse_error("Invalid flag in trace value")),
+    }
+}
+
+fn read_optional_u32(input: &mut &[u8]) -> Result<Option<u32>> {
+    let present = read_flag(input)?;
+    let value = read_u32(input)?;
+    Ok(present.then_some(value))
+}
+
+fn read_string(input: &mut &[u8]) -> Result<String> {
+    let len = read_u32(input)? as usize;
+    if input.len() < len {
+        return Err(parse_error("Truncated string in trace"));
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    String::from_utf8(head.to_vec()).map_err(|_| parse_error("Invalid UTF-8 in trace value"))
+}
+
+fn read_optional_value(input: &mut &[u8], depth: usize) -> Result<Option<Box<Value>>> {
+    if read_flag(input)? {
+        Ok(Some(Box::new(decode_value(input, depth + 1)?)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn decode_value(input: &mut &[u8], depth: usize) -> Result<Value> {
+    if depth > MAX_VALUE_DEPTH {
+        return Err(parse_error("Trace value nested too deeply"));
+    }
+
+    let value = match read_u8(input)? {
+        0 => Value::I32(i32::from_le_bytes(read_array(input)?)),
+        1 => Value::I64(i64::from_le_bytes(read_array(input)?)),
+        2 => Value::F32(FloatBits32(read_u32(input)?)),
+        3 => Value::F64(FloatBits64(u64::from_le_bytes(read_array(input)?))),
+        4 => Value::V128(V128::new(read_array(input)?)),
+        5 => Value::FuncRef(read_optional_u32(input)?.map(FuncRef::from_index)),
+        6 => Value::ExternRef(read_optional_u32(input)?.map(|index| ExternRef { index })),
+        7 => Value::Ref(read_u32(input)?),
+        8 => Value::I16x8(V128::new(read_array(input)?)),
+        11 => Value::Bool(read_flag(input)?),
+        12 => Value::S8(i8::from_le_bytes(read_array(input)?)),
+        13 => Value::U8(read_u8(input)?),
+        14 => Value::S16(i16::from_le_bytes(read_array(input)?)),
+        15 => Value::U16(u16::from_le_bytes(read_array(input)?)),
+        16 => Value::S32(i32::from_le_bytes(read_array(input)?)),
+        17 => Value::U32(read_u32(input)?),
+        18 => Value::S64(i64::from_le_bytes(read_array(input)?)),
+        19 => Value::U64(u64::from_le_bytes(read_array(input)?)),
+        20 => Value::Char(
+            char::from_u32(read_u32(input)?)
+                .ok_or_else(|| parse_error("Invalid char in trace value"))?,
+        ),
+        21 => Value::String(read_string(input)?),
+        tag @ (22 | 23) => {
+            let count = read_u32(input)?;
+            let items = (0..count)
+                .map(|_| decode_value(input, depth + 1))
+                .collect::<Result<Vec<_>>>()?;
+            if tag == 22 {
+                Value::List(items)
+            } else {
+                Value::Tuple(items)
+            }
+        },
+        24 => {
+            let count = read_u32(input)?;
+            let fields = (0..count)
+                .map(|_| Ok((read_string(input)?, decode_value(input, depth + 1)?)))
+                .collect::<Result<Vec<_>>>()?;
+            Value::Record(fields)
+        },
+        25 => {
+            let case = read_string(input)?;
+            Value::Variant(case, read_optional_value(input, depth)?)
+        },
+        26 => Value::Enum(read_string(input)?),
+        27 => Value::Option(read_optional_value(input, depth)?),
+        28 => {
+            let is_err = read_flag(input)?;
+            let inner = Box::new(decode_value(input, depth + 1)?);
+            Value::Result(if is_err { Err(inner) } else { Ok(inner) })
+        },
+        29 => {
+            let count = read_u32(input)?;
+            Value::Flags((0..count).map(|_| read_string(input)).collect::<Result<Vec<_>>>()?)
+        },
+        30 => Value::Own(read_u32(input)?),
+        31 => Value::Borrow(read_u32(input)?),
+        32 => Value::Void,
+        33 => Value::Stream(read_u32(input)?),
+        34 => Value::Future(read_u32(input)?),
+        35 => Value::ExnRef(read_optional_u32(input)?),
+        36 => {
+            let present = read_flag(input)?;
+            let value = i32::from_le_bytes(read_array(input)?);
+            Value::I31Ref(present.then_some(value))
+        },
+        _ => return Err(parse_error("Unknown value tag in trace")),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LinkInterceptor;
+
+    fn sample_values() -> Vec<Value> {
+        vec![
+            Value::I32(-7),
+            Value::F64(FloatBits64::from_float(1.5)),
+            Value::FuncRef(Some(FuncRef::from_index(3))),
+            Value::ExternRef(None),
+            Value::Char('é'),
+            Value::String("hello world".to_string()),
+            Value::Record(vec![
+                ("id".to_string(), Value::U64(42)),
+                ("tags".to_string(), Value::List(vec![Value::Enum("a".to_string())])),
+            ]),
+            Value::Variant("some".to_string(), Some(Box::new(Value::Bool(true)))),
+            Value::Option(None),
+            Value::Result(Err(Box::new(Value::Own(9)))),
+            Value::Flags(vec!["read".to_string(), "write".to_string()]),
+            Value::I31Ref(Some(-1)),
+            Value::Void,
+        ]
+    }
+
+    #[test]
+    fn test_values_round_trip() {
+        let values = sample_values();
+        let encoded = encode_values(&values).unwrap();
+        let decoded = decode_values(&encoded).unwrap();
+        assert_eq!(encode_values(&decoded).unwrap(), encoded);
+        assert!(decode_values(&encoded[..encoded.len() - 1]).is_err());
+        assert!(encode_values(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trace_text_round_trip() {
+        let mut trace = Trace::new();
+        trace.push(TraceEvent::Call {
+            seq:      0,
+            parent:   None,
+            source:   "guest app".to_string(),
+            target:   "wasi:cli/stdout".to_string(),
+            function: String::new(),
+            args:     sample_values(),
+        });
+        trace.push(TraceEvent::Resource {
+            parent:   Some(0),
+            handle:   4,
+            kind:     ResourceOpKind::Drop,
+            type_idx: 2,
+        });
+        trace.push(TraceEvent::Lower {
+            parent: Some(0),
+            addr:   64,
+            ty:     0xdead_beef,
+            data:   vec![1, 2, 3],
+        });
+        trace.push(TraceEvent::Return {
+            seq:     0,
+            outcome: CallOutcome::Err {
+                category: ErrorCategory::RuntimeTrap,
+                code:     codes::EXECUTION_LIMIT_EXCEEDED,
+            },
+        });
+
+        let text = trace.to_text().unwrap();
+        assert!(text.starts_with("wrt-trace v1\ncall 0 - guest%20app wasi:cli/stdout % "));
+        assert!(text.contains("\nresource 0 4 drop 2\n"));
+        assert!(text.contains("\nlower 0 64 deadbeef 010203\n"));
+        assert!(text.ends_with("\nreturn 0 err RuntimeTrap 2003\n"));
+
+        let parsed = Trace::parse(&text).unwrap();
+        assert_eq!(parsed.events().len(), 4);
+        assert_eq!(parsed.to_text().unwrap(), text);
+
+        assert!(Trace::parse("call 0 - a b c -").is_err());
+        assert!(Trace::parse("wrt-trace v1\ncall 0 - a b c - extra").is_err());
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let recorder = Arc::new(RecordingStrategy::new());
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy(recorder.clone());
+
+        let sum = interceptor
+            .intercept_call("math", "add", &[Value::I32(2), Value::I32(3)], |_| {
+                // A nested call made by the dependency while handling `add`
+                recorder.before_call("math", "log", "write", &[])?;
+                recorder.after_call("math", "log", "write", &[], Ok(Vec::new()))?;
+                Ok(vec![Value::I32(5)])
+            })
+            .unwrap();
+        assert_eq!(sum, vec![Value::I32(5)]);
+        let failed = interceptor.intercept_call("math", "div", &[Value::I32(0)], |_| {
+            Err(Error::runtime_execution_error("division by zero"))
+        });
+        assert!(failed.is_err());
+
+        let text = recorder.trace().unwrap().to_text().unwrap();
+        assert!(text.contains("\ncall 1 0 math log write -\n"));
+        let replay = Arc::new(ReplayStrategy::from_text(&text).unwrap());
+        assert_eq!(replay.remaining_calls().unwrap(), 2);
+
+        let mut replayed = LinkInterceptor::new("guest");
+        replayed.add_strategy(replay.clone());
+        let sum = replayed
+            .intercept_call("math", "add", &[Value::I32(2), Value::I32(3)], |_| {
+                panic!("replayed calls must not execute")
+            })
+            .unwrap();
+        assert_eq!(sum, vec![Value::I32(5)]);
+
+        let error = replayed
+            .intercept_call("math", "div", &[Value::I32(0)], |_| unreachable!())
+            .unwrap_err();
+        let original = failed.unwrap_err();
+        assert_eq!(error.category, original.category);
+        assert_eq!(error.code, original.code);
+        assert!(replay.is_complete().unwrap());
+    }
+
+    #[test]
+    fn test_replay_detects_divergence() {
+        let recorder = RecordingStrategy::new();
+        recorder.before_call("guest", "host", "get", &[Value::I32(1)]).unwrap();
+        recorder.after_call("guest", "host", "get", &[], Ok(vec![Value::I32(10)])).unwrap();
+        recorder
+            .intercept_resource_operation(1, &ResourceCanonicalOperation::New(ResourceNew {
+                type_idx: 0,
+            }))
+            .unwrap();
+        let trace = recorder.take_trace().unwrap();
+        assert!(recorder.trace().unwrap().events().is_empty());
+
+        let replay = ReplayStrategy::new(&trace).unwrap();
+        assert!(replay.before_call("guest", "host", "get", &[Value::I32(2)]).is_err());
+
+        let replay = ReplayStrategy::new(&trace).unwrap();
+        assert_eq!(
+            replay.before_call("guest", "host", "get", &[Value::I32(1)]).unwrap(),
+            vec![Value::I32(10)]
+        );
+        assert!(replay.before_call("guest", "host", "get", &[Value::I32(1)]).is_err());
+        let drop_op = ResourceCanonicalOperation::Drop(ResourceDrop { type_idx: 0 });
+        assert!(replay.intercept_resource_operation(1, &drop_op).is_err());
+    }
+}
@@ -0,0 +1,775 @@
+//! Tracing strategy for exporting span events from intercepted calls
+//!
+//! Every call passing through a [`TracingStrategy`] produces one
+//! [`CallSpan`] describing the source, target, function, argument sizes,
+//! duration and fuel consumed. Spans are handed to a [`SpanExporter`] and
+//! mirrored into the `wrt-foundation` telemetry buffer.
+//!
+//! Two exporters are provided:
+//!
+//! - [`RingBufferExporter`]: a bounded buffer the host drains with
+//!   [`RingBufferExporter::pop`]; available in all builds.
+//! - [`JsonLinesExporter`] (std only): writes one OTLP/JSON span object per
+//!   line to any `std::io::Write`.
+//!
+//! Fuel is reported by the host through [`TracingStrategy::consume_fuel`],
+//! the same model used by the firewall's rate limits.
+//!
+//! A strategy given a [`TraceSampler`] with [`TracingStrategy::with_sampler`]
+//! only exports the calls the sampler selects; the other calls are passed
+//! through without timing, telemetry or allocation.
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+#[cfg(feature = "std")]
+use std::{
+    io::Write,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+#[cfg(not(feature = "std"))]
+use wrt_foundation::collections::StaticVec;
+use wrt_foundation::{
+    telemetry::{
+        self,
+        event_codes,
+        Category,
+        Severity,
+    },
+    traits::ToBytes,
+};
+#[cfg(not(feature = "std"))]
+use wrt_sync::Mutex;
+
+use crate::{
+    prelude::{
+        fmt,
+        Debug,
+        Value,
+    },
+    sampling::TraceSampler,
+    LinkInterceptorStrategy,
+};
+
+/// Maximum number of calls that may be in flight at once in a `no_std`
+/// tracing strategy
+pub const MAX_OPEN_SPANS: usize = 16;
+
+/// Maximum length of a span label in `no_std` builds; longer names are
+/// truncated
+pub const MAX_SPAN_LABEL_LEN: usize = 48;
+
+/// Label type used for span source, target and function names
+#[cfg(feature = "std")]
+pub type SpanLabel = String;
+
+/// Label type used for span source, target and function names (`no_std`
+/// version, truncated to [`MAX_SPAN_LABEL_LEN`] bytes)
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SpanLabel {
+    bytes: [u8; MAX_SPAN_LABEL_LEN],
+    len:   u8,
+}
+
+#[cfg(not(feature = "std"))]
+impl SpanLabel {
+    /// Create a label, truncating at a character boundary if needed
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        let mut len = name.len().min(MAX_SPAN_LABEL_LEN);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut bytes = [0u8; MAX_SPAN_LABEL_LEN];
+        bytes[..len].copy_from_slice(&name.as_bytes()[..len]);
+        #[allow(clippy::cast_possible_truncation)] // len <= MAX_SPAN_LABEL_LEN
+        Self { bytes, len: len as u8 }
+    }
+
+    /// The label text
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Always a prefix of valid UTF-8 cut at a character boundary
+        core::str::from_utf8(&self.bytes[..usize::from(self.len)]).unwrap_or_default()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl Debug for SpanLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "std")]
+fn span_label(name: &str) -> SpanLabel {
+    name.to_string()
+}
+
+#[cfg(not(feature = "std"))]
+fn span_label(name: &str) -> SpanLabel {
+    SpanLabel::new(name)
+}
+
+/// One completed intercepted call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSpan {
+    /// Calling component
+    pub source:        SpanLabel,
+    /// Target component or host
+    pub target:        SpanLabel,
+    /// Function that was called
+    pub function:      SpanLabel,
+    /// Number of arguments
+    pub arg_count:     u32,
+    /// Serialized size of the arguments in bytes
+    pub arg_bytes:     u32,
+    /// Clock reading when the call started, if a clock is configured
+    pub start_ns:      Option<u64>,
+    /// Call duration in nanoseconds, if a clock is configured
+    pub duration_ns:   Option<u64>,
+    /// Fuel reported through [`TracingStrategy::consume_fuel`] during the call
+    pub fuel_consumed: u64,
+    /// Whether the call succeeded
+    pub success:       bool,
+}
+
+/// Destination for completed spans
+pub trait SpanExporter: Send + Sync {
+    /// Export one completed span
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the span cannot be recorded; the error is
+    /// propagated to the intercepted call.
+    fn export(&self, span: &CallSpan) -> Result<()>;
+}
+
+/// Bounded span buffer read by the host
+///
+/// Holds the newest `N` spans; when full, the oldest span is discarded and
+/// counted in [`RingBufferExporter::dropped`].
+pub struct RingBufferExporter<const N: usize> {
+    #[cfg(feature = "std")]
+    spans:   Mutex<std::collections::VecDeque<CallSpan>>,
+    #[cfg(not(feature = "std"))]
+    spans:   Mutex<StaticVec<CallSpan, N>>,
+    dropped: AtomicU64,
+}
+
+impl<const N: usize> Default for RingBufferExporter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> RingBufferExporter<N> {
+    /// Create an empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            spans:   Mutex::new(std::collections::VecDeque::with_capacity(N)),
+            #[cfg(not(feature = "std"))]
+            spans:   Mutex::new(StaticVec::new()),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Remove and return the oldest buffered span
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer lock was poisoned by a panicking exporter.
+    pub fn pop(&self) -> Option<CallSpan> {
+        #[cfg(feature = "std")]
+        return self.spans.lock().expect("span buffer lock poisoned").pop_front();
+        #[cfg(not(feature = "std"))]
+        {
+            let mut spans = self.spans.lock();
+            if spans.is_empty() { None } else { Some(spans.remove(0)) }
+        }
+    }
+
+    /// Number of buffered spans
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer lock was poisoned by a panicking exporter.
+    pub fn len(&self) -> usize {
+        #[cfg(feature = "std")]
+        return self.spans.lock().expect("span buffer lock poisoned").len();
+        #[cfg(not(feature = "std"))]
+        return self.spans.lock().len();
+    }
+
+    /// Whether the buffer holds no spans
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of spans discarded because the buffer was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<const N: usize> SpanExporter for RingBufferExporter<N> {
+    fn export(&self, span: &CallSpan) -> Result<()> {
+        if N == 0 {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        #[cfg(feature = "std")]
+        let mut spans = self
+            .spans
+            .lock()
+            .map_err(|_| Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Span buffer lock poisoned"))?;
+        #[cfg(not(feature = "std"))]
+        let mut spans = self.spans.lock();
+
+        if spans.len() == N {
+            #[cfg(feature = "std")]
+            spans.pop_front();
+            #[cfg(not(feature = "std"))]
+            spans.remove(0);
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "std")]
+        spans.push_back(span.clone());
+        #[cfg(not(feature = "std"))]
+        spans.push(span.clone())?;
+        Ok(())
+    }
+}
+
+/// Exporter writing one OTLP/JSON span object per line
+#[cfg(feature = "std")]
+pub struct JsonLinesExporter<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write + Send> JsonLinesExporter<W> {
+    /// Create an exporter writing to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Consume the exporter and return the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer lock was poisoned.
+    pub fn into_inner(self) -> Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|_| Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Span writer lock poisoned"))
+    }
+
+    /// Render a span as a single OTLP/JSON span object
+    #[must_use]
+    pub fn format_span(span: &CallSpan) -> String {
+        use std::fmt::Write as _;
+
+        let mut line = String::from("{\"name\":");
+        push_json_string(&mut line, &format!("{}::{}", span.target, span.function));
+        line.push_str(",\"kind\":\"SPAN_KIND_CLIENT\"");
+        if let (Some(start), Some(duration)) = (span.start_ns, span.duration_ns) {
+            let _ = write!(
+                line,
+                ",\"startTimeUnixNano\":\"{start}\",\"endTimeUnixNano\":\"{}\"",
+                start.saturating_add(duration)
+            );
+        }
+        line.push_str(",\"attributes\":[");
+        for (i, (key, value)) in
+            [("wrt.source", &span.source), ("wrt.target", &span.target), ("wrt.function", &span.function)]
+                .into_iter()
+                .enumerate()
+        {
+            if i > 0 {
+                line.push(',');
+            }
+            let _ = write!(line, "{{\"key\":\"{key}\",\"value\":{{\"stringValue\":");
+            push_json_string(&mut line, value);
+            line.push_str("}}");
+        }
+        for (key, value) in [
+            ("wrt.arg_count", u64::from(span.arg_count)),
+            ("wrt.arg_bytes", u64::from(span.arg_bytes)),
+            ("wrt.fuel_consumed", span.fuel_consumed),
+        ] {
+            let _ = write!(line, ",{{\"key\":\"{key}\",\"value\":{{\"intValue\":\"{value}\"}}}}");
+        }
+        let status = if span.success { "STATUS_CODE_OK" } else { "STATUS_CODE_ERROR" };
+        let _ = write!(line, "],\"status\":{{\"code\":\"{status}\"}}}}");
+        line
+    }
+}
+
+#[cfg(feature = "std")]
+fn push_json_string(out: &mut String, value: &str) {
+    use std::fmt::Write as _;
+
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(feature = "std")]
+impl<W: Write + Send> SpanExporter for JsonLinesExporter<W> {
+    fn export(&self, span: &CallSpan) -> Result<()> {
+        let line = Self::format_span(span);
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Span writer lock poisoned"))?;
+        writeln!(writer, "{line}").map_err(|_| Error::io_error("Failed to write span"))
+    }
+}
+
+/// Exporter handle held by a [`TracingStrategy`]
+#[cfg(feature = "std")]
+pub type ExporterRef = Arc<dyn SpanExporter>;
+
+/// Exporter handle held by a [`TracingStrategy`] (`no_std` version)
+#[cfg(not(feature = "std"))]
+pub type ExporterRef = &'static dyn SpanExporter;
+
+/// Sampler handle held by a [`TracingStrategy`]
+#[cfg(feature = "std")]
+pub type SamplerRef = Arc<TraceSampler>;
+
+/// Sampler handle held by a [`TracingStrategy`] (`no_std` version)
+#[cfg(not(feature = "std"))]
+pub type SamplerRef = &'static TraceSampler;
+
+/// Call between `before_call` and `after_call`, with its span if it was
+/// sampled
+#[derive(Clone, Copy)]
+struct OpenCall<L> {
+    /// Hash of the source, target and function names
+    key:  u64,
+    span: Option<OpenSpan<L>>,
+}
+
+/// FNV-1a hash identifying a call by its names
+fn call_key(source: &str, target: &str, function: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for name in [source, target, function] {
+        for byte in name.bytes().chain(core::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Bookkeeping for a call between `before_call` and `after_call`
+#[derive(Clone, Copy)]
+struct OpenSpan<L> {
+    source:     L,
+    target:     L,
+    function:   L,
+    arg_count:  u32,
+    arg_bytes:  u32,
+    start_ns:   Option<u64>,
+    fuel_start: u64,
+}
+
+#[cfg(feature = "std")]
+fn unix_time_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX))
+}
+
+/// A strategy that emits a [`CallSpan`] for every intercepted call
+pub struct TracingStrategy {
+    exporter:   ExporterRef,
+    /// Nanosecond clock used for span timing
+    clock:      Option<fn() -> u64>,
+    /// Total fuel reported through [`TracingStrategy::consume_fuel`]
+    fuel_clock: AtomicU64,
+    /// Selects the calls exported; all calls without a sampler
+    sampler:    Option<SamplerRef>,
+    #[cfg(feature = "std")]
+    open:       Mutex<Vec<OpenCall<SpanLabel>>>,
+    #[cfg(not(feature = "std"))]
+    open:       Mutex<StaticVec<OpenCall<SpanLabel>, MAX_OPEN_SPANS>>,
+}
+
+impl TracingStrategy {
+    /// Create a tracing strategy exporting to `exporter`
+    ///
+    /// With `std`, spans are timed with the system clock in Unix
+    /// nanoseconds; `no_std` builds have no clock until
+    /// [`TracingStrategy::with_clock`] is used.
+    #[must_use]
+    pub fn new(exporter: ExporterRef) -> Self {
+        Self {
+            exporter,
+            #[cfg(feature = "std")]
+            clock: Some(unix_time_ns),
+            #[cfg(not(feature = "std"))]
+            clock: None,
+            fuel_clock: AtomicU64::new(0),
+            sampler: None,
+            #[cfg(feature = "std")]
+            open: Mutex::new(Vec::new()),
+            #[cfg(not(feature = "std"))]
+            open: Mutex::new(StaticVec::new()),
+        }
+    }
+
+    /// Use `clock` (nanoseconds) for span start times and durations
+    #[must_use]
+    pub fn with_clock(mut self, clock: fn() -> u64) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Only export the calls selected by `sampler`
+    ///
+    /// The sampler may be shared with other strategies and the engine, and
+    /// adjusted while calls are running.
+    #[must_use]
+    pub fn with_sampler(mut self, sampler: SamplerRef) -> Self {
+        self.sampler = Some(sampler);
+        self
+    }
+
+    /// The sampler selecting the exported calls, if any
+    pub fn sampler(&self) -> Option<&TraceSampler> {
+        #[cfg(feature = "std")]
+        return self.sampler.as_deref();
+        #[cfg(not(feature = "std"))]
+        return self.sampler;
+    }
+
+    /// Record fuel consumed by the guest since the previous report
+    pub fn consume_fuel(&self, fuel: u64) {
+        self.fuel_clock.fetch_add(fuel, Ordering::Relaxed);
+    }
+
+    /// Total fuel reported so far
+    pub fn fuel_clock(&self) -> u64 {
+        self.fuel_clock.load(Ordering::Relaxed)
+    }
+
+    fn open_span(&self, source: &str, target: &str, function: &str, args: &[Value]) -> Result<()> {
+        let sampled = self.sampler().is_none_or(|sampler| sampler.sample(target, function));
+        let span = sampled.then(|| self.start_span(source, target, function, args));
+        let call = OpenCall { key: call_key(source, target, function), span };
+
+        #[cfg(feature = "std")]
+        self.open
+            .lock()
+            .map_err(|_| Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Open span lock poisoned"))?
+            .push(call);
+        #[cfg(not(feature = "std"))]
+        self.open.lock().push(call).map_err(|_| {
+            Error::new(ErrorCategory::Capacity, codes::CAPACITY_EXCEEDED, "Too many calls in flight to trace")
+        })?;
+        Ok(())
+    }
+
+    fn start_span(&self, source: &str, target: &str, function: &str, args: &[Value]) -> OpenSpan<SpanLabel> {
+        let arg_bytes: usize = args.iter().map(ToBytes::serialized_size).sum();
+        OpenSpan {
+            source:     span_label(source),
+            target:     span_label(target),
+            function:   span_label(function),
+            arg_count:  u32::try_from(args.len()).unwrap_or(u32::MAX),
+            arg_bytes:  u32::try_from(arg_bytes).unwrap_or(u32::MAX),
+            start_ns:   self.clock.map(|clock| clock()),
+            fuel_start: self.fuel_clock(),
+        }
+    }
+
+    fn close_span(&self, source: &str, target: &str, function: &str, success: bool) -> Result<()> {
+        let open = {
+            #[cfg(feature = "std")]
+            let mut open = self
+                .open
+                .lock()
+                .map_err(|_| Error::new(ErrorCategory::Runtime, codes::POISONED_LOCK, "Open span lock poisoned"))?;
+            #[cfg(not(feature = "std"))]
+            let mut open = self.open.lock();
+
+            let key = call_key(source, target, function);
+            let index = open
+                .iter()
+                .rposition(|call| {
+                    call.key == key
+                        && call.span.as_ref().is_none_or(|span| {
+                            span.source.as_str() == source
+                                && span.target.as_str() == target
+                                && span.function.as_str() == function
+                        })
+                })
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorCategory::Runtime,
+                        codes::INVALID_STATE,
+                        "after_call without matching before_call",
+                    )
+                })?;
+            match open.remove(index).span {
+                Some(span) => span,
+                // Not sampled
+                None => return Ok(()),
+            }
+        };
+
+        let span = CallSpan {
+            source: open.source,
+            target: open.target,
+            function: open.function,
+            arg_count: open.arg_count,
+            arg_bytes: open.arg_bytes,
+            start_ns: open.start_ns,
+            duration_ns: open
+                .start_ns
+                .zip(self.clock)
+                .map(|(start, clock)| clock().saturating_sub(start)),
+            fuel_consumed: self.fuel_clock().saturating_sub(open.fuel_start),
+            success,
+        };
+
+        let (severity, code) = if success {
+            (Severity::Trace, event_codes::INTERCEPT_CALL)
+        } else {
+            (Severity::Error, event_codes::INTERCEPT_CALL_FAILED)
+        };
+        telemetry::record_event(
+            severity,
+            Category::Interception,
+            code,
+            span.duration_ns.unwrap_or(0),
+            span.fuel_consumed,
+        );
+
+        self.exporter.export(&span)
+    }
+}
+
+#[cfg(feature = "std")]
+impl LinkInterceptorStrategy for TracingStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        self.open_span(source, target, function, args)?;
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        self.close_span(source, target, function, result.is_ok())?;
+        result
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        let mut strategy = Self::new(Arc::clone(&self.exporter));
+        strategy.clock = self.clock;
+        strategy.sampler = self.sampler.clone();
+        Arc::new(strategy)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl LinkInterceptorStrategy for TracingStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<()> {
+        self.open_span(source, target, function, args)
+    }
+
+    fn after_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<()>,
+    ) -> Result<()> {
+        self.close_span(source, target, function, result.is_ok())?;
+        result
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::LinkInterceptor;
+
+    fn fixed_clock() -> u64 {
+        1_000
+    }
+
+    #[test]
+    fn test_tracing_strategy_exports_spans() {
+        let buffer = Arc::new(RingBufferExporter::<4>::new());
+        let strategy = Arc::new(TracingStrategy::new(buffer.clone()).with_clock(fixed_clock));
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy(strategy.clone());
+
+        let args = [Value::I32(7), Value::I64(9)];
+        interceptor
+            .intercept_call("host", "add", &args, |args| {
+                strategy.consume_fuel(25);
+                Ok(args)
+            })
+            .unwrap();
+        let failed = interceptor.intercept_call("host", "fail", &[], |_| {
+            Err(Error::runtime_execution_error("host call failed"))
+        });
+        assert!(failed.is_err());
+
+        let span = buffer.pop().unwrap();
+        assert_eq!(span.source, "guest");
+        assert_eq!(span.target, "host");
+        assert_eq!(span.function, "add");
+        assert_eq!(span.arg_count, 2);
+        assert_eq!(span.arg_bytes, 5 + 9);
+        assert_eq!(span.start_ns, Some(1_000));
+        assert_eq!(span.duration_ns, Some(0));
+        assert_eq!(span.fuel_consumed, 25);
+        assert!(span.success);
+
+        let span = buffer.pop().unwrap();
+        assert_eq!(span.function, "fail");
+        assert!(!span.success);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_sampled_tracing_exports_selected_calls() {
+        let buffer = Arc::new(RingBufferExporter::<8>::new());
+        let sampler = Arc::new(TraceSampler::new(2));
+        let strategy = TracingStrategy::new(buffer.clone()).with_sampler(sampler.clone());
+        let mut interceptor = LinkInterceptor::new("guest");
+        interceptor.add_strategy(Arc::new(strategy));
+
+        // A nested call with the same name must not close the outer span
+        interceptor
+            .intercept_call("host", "f", &[], |args| {
+                interceptor.intercept_call("host", "f", &args, Ok)?;
+                interceptor.intercept_call("host", "g", &args, Ok)?;
+                interceptor.intercept_call("host", "g", &args, Ok)
+            })
+            .unwrap();
+        assert_eq!(buffer.pop().unwrap().function, "g");
+        assert_eq!(buffer.pop().unwrap().function, "f");
+        assert!(buffer.is_empty());
+
+        sampler.apply("0 host#g=1").unwrap();
+        interceptor.intercept_call("host", "f", &[], Ok).unwrap();
+        interceptor.intercept_call("host", "g", &[], Ok).unwrap();
+        assert_eq!(buffer.pop().unwrap().function, "g");
+        assert!(buffer.is_empty());
+        assert_eq!(sampler.sampled(), 3);
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let buffer = RingBufferExporter::<2>::new();
+        for function in ["a", "b", "c"] {
+            let span = CallSpan {
+                source:        "guest".to_string(),
+                target:        "host".to_string(),
+                function:      function.to_string(),
+                arg_count:     0,
+                arg_bytes:     0,
+                start_ns:      None,
+                duration_ns:   None,
+                fuel_consumed: 0,
+                success:       true,
+            };
+            buffer.export(&span).unwrap();
+        }
+        assert_eq!(buffer.dropped(), 1);
+        assert_eq!(buffer.pop().unwrap().function, "b");
+        assert_eq!(buffer.pop().unwrap().function, "c");
+    }
+
+    #[test]
+    fn test_unmatched_after_call_is_an_error() {
+        let strategy = TracingStrategy::new(Arc::new(RingBufferExporter::<1>::new()));
+        let result = strategy.after_call("guest", "host", "f", &[], Ok(Vec::new()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_json_lines_exporter_format() {
+        let exporter = JsonLinesExporter::new(Vec::new());
+        let span = CallSpan {
+            source:        "guest".to_string(),
+            target:        "host".to_string(),
+            function:      "say \"hi\"".to_string(),
+            arg_count:     1,
+            arg_bytes:     5,
+            start_ns:      Some(10),
+            duration_ns:   Some(5),
+            fuel_consumed: 3,
+            success:       false,
+        };
+        exporter.export(&span).unwrap();
+        let output = String::from_utf8(exporter.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            output,
+            "{\"name\":\"host::say \\\"hi\\\"\",\"kind\":\"SPAN_KIND_CLIENT\",\
+             \"startTimeUnixNano\":\"10\",\"endTimeUnixNano\":\"15\",\"attributes\":[\
+             {\"key\":\"wrt.source\",\"value\":{\"stringValue\":\"guest\"}},\
+             {\"key\":\"wrt.target\",\"value\":{\"stringValue\":\"host\"}},\
+             {\"key\":\"wrt.function\",\"value\":{\"stringValue\":\"say \\\"hi\\\"\"}},\
+             {\"key\":\"wrt.arg_count\",\"value\":{\"intValue\":\"1\"}},\
+             {\"key\":\"wrt.arg_bytes\",\"value\":{\"intValue\":\"5\"}},\
+             {\"key\":\"wrt.fuel_consumed\",\"value\":{\"intValue\":\"3\"}}],\
+             \"status\":{\"code\":\"STATUS_CODE_ERROR\"}}\n"
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Usage analytics for the imports of a component
+//!
+//! An [`ImportUsage`] compares the imports a component declares with the
+//! calls it actually made in one or more recorded [`Trace`]s, e.g. from the
+//! test workloads of the component. Each import ends up in one of three
+//! groups:
+//!
+//! - used: declared and called at least once
+//! - unused: declared but never called, candidates for removal by an import
+//!   minimization pass
+//! - undeclared: called but not declared, which usually means the declared
+//!   imports and the traced build are out of sync
+//!
+//! Calls are attributed to the component by the `source` of the recorded
+//! call, and to an import by its target (the import's module or interface)
+//! and function name. [`ImportUsage::allow_list`] turns the used imports
+//! into a [`Policy`] that allows exactly the exercised calls, as a starting
+//! point for a sandbox policy.
+//!
+//! # Report format
+//!
+//! ```text
+//! wrt-import-usage v1
+//! component app
+//! used wasi:filesystem/types read 12 0
+//! unused wasi:sockets/tcp connect
+//! undeclared host log 3 1
+//! ```
+//!
+//! `used` and `undeclared` lines end with the number of calls and the
+//! number of calls that failed. Names are percent-escaped as in the trace
+//! format, so they never contain spaces.
+//!
+//! Note: This module requires the `std` feature.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fmt::{
+        self,
+        Write as _,
+    },
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::{
+    scope::glob_match,
+    strategies::{
+        push_name,
+        unescape_name,
+        CallOutcome,
+        Policy,
+        PolicyAction,
+        PolicyRule,
+        Trace,
+        TraceEvent,
+    },
+};
+
+/// First line of every usage report
+pub const USAGE_HEADER: &str = "wrt-import-usage v1";
+
+/// Calls observed for one import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportCalls {
+    /// Module or interface the import comes from
+    pub module: String,
+    /// Name of the imported function
+    pub name:   String,
+    /// Number of calls
+    pub calls:  u64,
+    /// Number of calls that returned an error
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Entry {
+    declared: bool,
+    calls:    u64,
+    errors:   u64,
+}
+
+/// Declared imports of a component and the calls observed for them
+#[derive(Debug, Clone)]
+pub struct ImportUsage {
+    component: String,
+    entries:   BTreeMap<(String, String), Entry>,
+}
+
+impl ImportUsage {
+    /// Create a report for calls made by components matching `component`
+    ///
+    /// `component` is a glob as in [`FunctionPattern`](crate::scope::FunctionPattern);
+    /// `*` attributes every recorded call to the component.
+    #[must_use]
+    pub fn new(component: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            entries:   BTreeMap::new(),
+        }
+    }
+
+    /// Add a declared import
+    pub fn declare(&mut self, module: &str, name: &str) {
+        self.entry(module, name).declared = true;
+    }
+
+    /// Add declared imports from `(module, name)` pairs
+    #[must_use]
+    pub fn with_imports<'a>(
+        mut self,
+        imports: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        for (module, name) in imports {
+            self.declare(module, name);
+        }
+        self
+    }
+
+    /// Count the calls of the component in a trace
+    ///
+    /// Traces of several workloads can be observed one after the other; the
+    /// counts accumulate.
+    pub fn observe(&mut self, trace: &Trace) {
+        let mut open: HashMap<u64, (String, String)> = HashMap::new();
+        for event in trace.events() {
+            match event {
+                TraceEvent::Call {
+                    seq,
+                    source,
+                    target,
+                    function,
+                    ..
+                } if glob_match(&self.component, source) => {
+                    self.entry(target, function).calls += 1;
+                    open.insert(*seq, (target.clone(), function.clone()));
+                },
+                TraceEvent::Return { seq, outcome } => {
+                    if let Some((target, function)) = open.remove(seq) {
+                        if matches!(outcome, CallOutcome::Err { .. }) {
+                            self.entry(&target, &function).errors += 1;
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Pattern selecting the component whose calls are counted
+    #[must_use]
+    pub fn component(&self) -> &str {
+        &self.component
+    }
+
+    /// Declared imports that were called, in name order
+    #[must_use]
+    pub fn used(&self) -> Vec<ImportCalls> {
+        self.collect(|entry| entry.declared && entry.calls > 0)
+    }
+
+    /// Declared imports that were never called, in name order
+    #[must_use]
+    pub fn unused(&self) -> Vec<ImportCalls> {
+        self.collect(|entry| entry.declared && entry.calls == 0)
+    }
+
+    /// Called functions that are not declared imports, in name order
+    #[must_use]
+    pub fn undeclared(&self) -> Vec<ImportCalls> {
+        self.collect(|entry| !entry.declared)
+    }
+
+    /// Whether a declared import was called
+    #[must_use]
+    pub fn is_used(&self, module: &str, name: &str) -> bool {
+        self.entries
+            .get(&(module.to_string(), name.to_string()))
+            .is_some_and(|entry| entry.declared && entry.calls > 0)
+    }
+
+    /// Fraction of the declared imports that were called, or `None` if the
+    /// component declares no imports
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn coverage(&self) -> Option<f64> {
+        let declared = self.entries.values().filter(|entry| entry.declared).count();
+        let used = self.entries.values().filter(|entry| entry.declared && entry.calls > 0).count();
+        (declared > 0).then(|| used as f64 / declared as f64)
+    }
+
+    /// Policy allowing the component to call exactly the used imports
+    ///
+    /// The policy denies by default. Whitespace and glob metacharacters in
+    /// import names are replaced by `?`, so such a rule also matches names
+    /// that differ only in those characters. The component pattern is kept
+    /// as a glob.
+    #[must_use]
+    pub fn allow_list(&self) -> Policy {
+        self.used().iter().fold(Policy::new(false), |policy, import| {
+            policy.with_rule(
+                PolicyRule::new(PolicyAction::Allow)
+                    .from_source(&self.component.replace(char::is_whitespace, "?"))
+                    .to_target(&rule_name(&import.module))
+                    .for_function(&rule_name(&import.name)),
+            )
+        })
+    }
+
+    /// Serialize the report to its text form
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::from(USAGE_HEADER);
+        out.push_str("\ncomponent");
+        push_name(&mut out, &self.component);
+        out.push('\n');
+        for ((module, name), entry) in &self.entries {
+            out.push_str(match (entry.declared, entry.calls > 0) {
+                (true, true) => "used",
+                (true, false) => "unused",
+                (false, _) => "undeclared",
+            });
+            push_name(&mut out, module);
+            push_name(&mut out, name);
+            if entry.calls > 0 {
+                let _ = write!(out, " {} {}", entry.calls, entry.errors);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse a report from its text form
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or the component line is missing or
+    /// any line is malformed.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().filter(|line| !line.is_empty());
+        if lines.next() != Some(USAGE_HEADER) {
+            return Err(parse_error("Missing import usage header"));
+        }
+        let component = lines
+            .next()
+            .and_then(|line| line.strip_prefix("component "))
+            .ok_or_else(|| parse_error("Missing component in import usage report"))?;
+        let mut usage = Self::new(&unescape_name(component)?);
+
+        for line in lines {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let (declared, counted) = match fields[0] {
+                "used" => (true, true),
+                "unused" => (true, false),
+                "undeclared" => (false, true),
+                _ => return Err(parse_error("Unknown import usage entry")),
+            };
+            if fields.len() != if counted { 5 } else { 3 } {
+                return Err(parse_error("Malformed import usage entry"));
+            }
+            let (calls, errors) = if counted {
+                (parse_count(fields[3])?, parse_count(fields[4])?)
+            } else {
+                (0, 0)
+            };
+            if counted && calls == 0 {
+                return Err(parse_error("Called import usage entry without calls"));
+            }
+            let entry = usage.entry(&unescape_name(fields[1])?, &unescape_name(fields[2])?);
+            *entry = Entry {
+                declared,
+                calls,
+                errors,
+            };
+        }
+        Ok(usage)
+    }
+
+    fn entry(&mut self, module: &str, name: &str) -> &mut Entry {
+        self.entries.entry((module.to_string(), name.to_string())).or_default()
+    }
+
+    fn collect(&self, filter: impl Fn(&Entry) -> bool) -> Vec<ImportCalls> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| filter(entry))
+            .map(|((module, name), entry)| ImportCalls {
+                module: module.clone(),
+                name:   name.clone(),
+                calls:  entry.calls,
+                errors: entry.errors,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ImportUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_text())
+    }
+}
+
+/// `name` as a policy pattern, with characters the rule format cannot hold
+/// literally replaced by `?`
+fn rule_name(name: &str) -> String {
+    if name.is_empty() {
+        return "?".to_string();
+    }
+    name.chars()
+        .map(|c| if c.is_whitespace() || c == '*' || c == '?' { '?' } else { c })
+        .collect()
+}
+
+fn parse_count(field: &str) -> Result<u64> {
+    field.parse().map_err(|_| parse_error("Invalid count in import usage report"))
+}
+
+fn parse_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Parse, codes::PARSE_ERROR, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Value;
+
+    fn call(seq: u64, source: &str, target: &str, function: &str) -> TraceEvent {
+        TraceEvent::Call {
+            seq,
+            parent: None,
+            source: source.to_string(),
+            target: target.to_string(),
+            function: function.to_string(),
+            args: vec![Value::I32(1)],
+        }
+    }
+
+    fn ok(seq: u64) -> TraceEvent {
+        TraceEvent::Return {
+            seq,
+            outcome: CallOutcome::Ok(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_import_usage_groups_and_allow_list() {
+        let mut trace = Trace::new();
+        trace.push(call(0, "app", "wasi:filesystem/types", "read"));
+        trace.push(ok(0));
+        trace.push(call(1, "app", "wasi:filesystem/types", "read"));
+        trace.push(TraceEvent::Return {
+            seq:     1,
+            outcome: CallOutcome::Err {
+                category: ErrorCategory::Io,
+                code:     codes::PARSE_ERROR,
+            },
+        });
+        trace.push(call(2, "app", "host", "log"));
+        trace.push(ok(2));
+        trace.push(call(3, "other", "wasi:sockets/tcp", "connect"));
+        trace.push(ok(3));
+
+        let mut usage = ImportUsage::new("app").with_imports([
+            ("wasi:filesystem/types", "read"),
+            ("wasi:filesystem/types", "write"),
+            ("wasi:sockets/tcp", "connect"),
+        ]);
+        usage.observe(&trace);
+
+        let used = usage.used();
+        assert_eq!(used.len(), 1);
+        assert_eq!((used[0].name.as_str(), used[0].calls, used[0].errors), ("read", 2, 1));
+        let unused: Vec<String> = usage.unused().into_iter().map(|import| import.name).collect();
+        assert_eq!(unused, ["write", "connect"]);
+        assert_eq!(usage.undeclared()[0].module, "host");
+        assert!(usage.is_used("wasi:filesystem/types", "read"));
+        assert_eq!(usage.coverage(), Some(1.0 / 3.0));
+
+        let policy = usage.allow_list();
+        let allowed = |target, function| {
+            policy.evaluate("app", target, function, &[]).map(|verdict| verdict.allowed)
+        };
+        assert!(allowed("wasi:filesystem/types", "read").unwrap());
+        assert!(!allowed("wasi:filesystem/types", "write").unwrap());
+        assert!(!allowed("host", "log").unwrap());
+        assert_eq!(Policy::parse(&policy.to_text()).unwrap(), policy);
+    }
+
+    #[test]
+    fn test_import_usage_text_round_trip() {
+        let mut trace = Trace::new();
+        trace.push(call(0, "my app", "env", "print line"));
+        trace.push(ok(0));
+        let mut usage =
+            ImportUsage::new("my app").with_imports([("env", "print line"), ("env", "")]);
+        usage.observe(&trace);
+        usage.observe(&trace);
+
+        let text = usage.to_text();
+        assert!(text.contains("used env print%20line 2 0\n"));
+        let parsed = ImportUsage::parse(&text).unwrap();
+        assert_eq!(parsed.to_text(), text);
+        assert_eq!(parsed.component(), "my app");
+        assert_eq!(parsed.unused()[0].name, "");
+        let rule = &usage.allow_list().rules[0];
+        assert_eq!((rule.source.as_str(), rule.function.as_str()), ("my?app", "print?line"));
+
+        assert!(ImportUsage::parse("wrt-import-usage v1\nused a b 1 0").is_err());
+        assert!(ImportUsage::parse(&text.replace(" 2 0", "")).is_err());
+    }
+}
@@ -56,19 +56,49 @@
 //! ## Memory Pattern Design
 //!
 //! Panic information is stored in a recognizable memory pattern for debugger
-//! access: ```
+//! access:
+//!
+//! ```text
 //! Magic: 0xDEADBEEF
 //! ASIL Level: u8
 //! Timestamp: u64 (if available)
 //! Error Code: u32
 //! Location Hash: u32
+//! Payload: category u16, code u16, detail u32 (if attached)
 //! Stack Trace: [u32; N] (if memory allows)
 //! Checksum: u32
 //! ```
+//!
+//! ## Structured Payloads
+//!
+//! The error code of a panic is a hash of its message, which is only useful
+//! together with the sources. Crates can attach a [`PanicPayload`] with a
+//! structured error category and code instead, either right before they
+//! panic with [`attach_payload`] or, with `std`, through encoders registered
+//! with [`register_payload_encoder`] that inspect the payload passed to
+//! `std::panic::panic_any`. The payload is stored in the panic information
+//! block and covered by its checksum. With the `error-codes` feature a
+//! `wrt_error::Error` converts into a payload and back into its category.
+//!
+//! ## Multi-core Targets
+//!
+//! On SMP targets the platform registers a [`smp::CoreDoorbell`]. The panic
+//! handlers then interrupt the other cores so they park in their safe state
+//! too, and keep a per-core record of which core panicked first.
+//!
+//! ## After the Panic
+//!
+//! A panicking system halts by default. [`recovery::PostPanicAction`]
+//! selects a watchdog or platform reset instead, and a
+//! [`recovery::CrashCounterRegion`] that survives the reset lets the next
+//! boot report how often the system recovered from a panic.
 
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod recovery;
+pub mod smp;
+
 use core::sync::atomic::{
     AtomicU32,
     AtomicU8,
@@ -119,15 +149,30 @@ fn capacity(&self) -> usize {
 /// Maximum stack trace entries based on memory budget
 pub const MAX_STACK_TRACE_ENTRIES: usize = 16;
 
+/// Size of the encoded panic information block in bytes
+pub const PANIC_INFO_SIZE: usize = 40 + MAX_STACK_TRACE_ENTRIES * 4;
+
+/// Flag set in [`WrtPanicInfo::flags`] when the block carries a payload
+pub const PANIC_FLAG_PAYLOAD: u8 = 0x01;
+
+/// Offset of the stack trace in the encoded block
+const STACK_TRACE_OFFSET: usize = 36;
+
+/// Panic information block as 32-bit words
+const PANIC_INFO_WORDS: usize = PANIC_INFO_SIZE / 4;
+
 /// Panic information structure stored in memory for debugger access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C, packed)]
 pub struct WrtPanicInfo {
     /// Magic number for debugger recognition (0xDEADBEEF)
     pub magic:             u32,
     /// ASIL level at time of panic
     pub asil_level:        u8,
+    /// `PANIC_FLAG_*` bits describing the optional fields
+    pub flags:             u8,
     /// Reserved for alignment
-    pub reserved:          [u8; 3],
+    pub reserved:          [u8; 2],
     /// Error code (hash of panic message)
     pub error_code:        u32,
     /// Location hash (file + line combination)
@@ -138,28 +183,202 @@ pub struct WrtPanicInfo {
     pub stack_trace_count: u8,
     /// Reserved for future use
     pub reserved2:         [u8; 3],
+    /// Error category of the attached payload
+    pub payload_category:  u16,
+    /// Error code of the attached payload
+    pub payload_code:      u16,
+    /// Crate-specific detail of the attached payload
+    pub payload_detail:    u32,
     /// Variable length stack trace (depends on memory budget)
     pub stack_trace:       [u32; MAX_STACK_TRACE_ENTRIES],
     /// Checksum of all above data
     pub checksum:          u32,
 }
 
+impl WrtPanicInfo {
+    /// Checksum over the identifying fields
+    pub fn compute_checksum(&self) -> u32 {
+        let timestamp = self.timestamp;
+        self.magic
+            ^ self.asil_level as u32
+            ^ (self.flags as u32) << 8
+            ^ self.error_code
+            ^ self.location_hash
+            ^ (timestamp as u32)
+            ^ ((timestamp >> 32) as u32)
+            ^ (self.payload_category as u32 | (self.payload_code as u32) << 16)
+            ^ self.payload_detail.rotate_left(7)
+    }
+
+    /// Structured payload attached to the panic, if any
+    pub fn payload(&self) -> Option<PanicPayload> {
+        (self.flags & PANIC_FLAG_PAYLOAD != 0).then_some(PanicPayload {
+            category: self.payload_category,
+            code:     self.payload_code,
+            detail:   self.payload_detail,
+        })
+    }
+
+    /// Whether the block carries the panic magic and a matching checksum
+    pub fn is_valid(&self) -> bool {
+        self.magic == PANIC_MAGIC && self.checksum == self.compute_checksum()
+    }
+
+    /// Encode the block in its in-memory layout, little-endian
+    pub fn to_bytes(&self) -> [u8; PANIC_INFO_SIZE] {
+        let mut bytes = [0u8; PANIC_INFO_SIZE];
+        let magic = self.magic;
+        let error_code = self.error_code;
+        let location_hash = self.location_hash;
+        let timestamp = self.timestamp;
+        let (category, code, detail) =
+            (self.payload_category, self.payload_code, self.payload_detail);
+        let stack_trace = self.stack_trace;
+        let checksum = self.checksum;
+        bytes[0..4].copy_from_slice(&magic.to_le_bytes());
+        bytes[4] = self.asil_level;
+        bytes[5] = self.flags;
+        bytes[6..8].copy_from_slice(&self.reserved);
+        bytes[8..12].copy_from_slice(&error_code.to_le_bytes());
+        bytes[12..16].copy_from_slice(&location_hash.to_le_bytes());
+        bytes[16..24].copy_from_slice(&timestamp.to_le_bytes());
+        bytes[24] = self.stack_trace_count;
+        bytes[25..28].copy_from_slice(&self.reserved2);
+        bytes[28..30].copy_from_slice(&category.to_le_bytes());
+        bytes[30..32].copy_from_slice(&code.to_le_bytes());
+        bytes[32..36].copy_from_slice(&detail.to_le_bytes());
+        let trace = &mut bytes[STACK_TRACE_OFFSET..PANIC_INFO_SIZE - 4];
+        for (chunk, entry) in trace.chunks_exact_mut(4).zip(stack_trace) {
+            chunk.copy_from_slice(&entry.to_le_bytes());
+        }
+        bytes[PANIC_INFO_SIZE - 4..].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a block written by [`WrtPanicInfo::to_bytes`]
+    ///
+    /// Returns `None` if `bytes` is not exactly [`PANIC_INFO_SIZE`] long.
+    /// The checksum is not checked; see [`WrtPanicInfo::is_valid`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PANIC_INFO_SIZE {
+            return None;
+        }
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        let mut stack_trace = [0u32; MAX_STACK_TRACE_ENTRIES];
+        for (i, entry) in stack_trace.iter_mut().enumerate() {
+            *entry = u32_at(STACK_TRACE_OFFSET + i * 4);
+        }
+        Some(Self {
+            magic: u32_at(0),
+            asil_level: bytes[4],
+            flags: bytes[5],
+            reserved: [bytes[6], bytes[7]],
+            error_code: u32_at(8),
+            location_hash: u32_at(12),
+            timestamp: u64::from(u32_at(16)) | (u64::from(u32_at(20)) << 32),
+            stack_trace_count: bytes[24],
+            reserved2: [bytes[25], bytes[26], bytes[27]],
+            payload_category: u16::from_le_bytes([bytes[28], bytes[29]]),
+            payload_code: u16::from_le_bytes([bytes[30], bytes[31]]),
+            payload_detail: u32_at(32),
+            stack_trace,
+            checksum: u32_at(PANIC_INFO_SIZE - 4),
+        })
+    }
+}
+
+/// Structured error information attached to a panic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PanicPayload {
+    /// Error category, the discriminant of `wrt_error::ErrorCategory`
+    pub category: u16,
+    /// Error code, usually one of `wrt_error::codes`
+    pub code:     u16,
+    /// Crate-specific detail, such as an index or a size
+    pub detail:   u32,
+}
+
+impl PanicPayload {
+    /// Create a payload from an error category and code
+    pub const fn new(category: u16, code: u16) -> Self {
+        Self {
+            category,
+            code,
+            detail: 0,
+        }
+    }
+
+    /// Attach a crate-specific detail
+    pub const fn with_detail(mut self, detail: u32) -> Self {
+        self.detail = detail;
+        self
+    }
+
+    /// Error category of the payload, if it is a known one
+    #[cfg(feature = "error-codes")]
+    pub const fn error_category(&self) -> Option<wrt_error::ErrorCategory> {
+        wrt_error::ErrorCategory::from_u16(self.category)
+    }
+}
+
+#[cfg(feature = "error-codes")]
+impl From<&wrt_error::Error> for PanicPayload {
+    fn from(error: &wrt_error::Error) -> Self {
+        Self::new(error.category as u16, error.code)
+    }
+}
+
+/// Encoder turning the payload of a `std` panic into a [`PanicPayload`]
+#[cfg(feature = "std")]
+pub type PanicPayloadEncoder = fn(&(dyn core::any::Any + Send)) -> Option<PanicPayload>;
+
 /// Panic context configuration
 pub struct PanicContext<P: MemoryProvider> {
-    safety_level:    AsilLevel,
-    memory_provider: P,
-    memory_budget:   usize,
+    safety_level:      AsilLevel,
+    memory_provider:   P,
+    memory_budget:     usize,
+    post_panic_action: recovery::PostPanicAction,
 }
 
 /// Global panic context storage
 static PANIC_ASIL_LEVEL: AtomicU8 = AtomicU8::new(AsilLevel::AsilD as u8);
 static PANIC_MEMORY_BUDGET: AtomicU32 = AtomicU32::new(DEFAULT_PANIC_MEMORY_BUDGET as u32);
 
+/// Last recorded panic information in its encoded layout, so debuggers find
+/// the magic pattern in memory and readers never see a half-written struct
+static PANIC_INFO_STORAGE: [AtomicU32; PANIC_INFO_WORDS] =
+    [const { AtomicU32::new(0) }; PANIC_INFO_WORDS];
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Payload attached with [`attach_payload`] for the next recorded panic
+    static PENDING_PAYLOAD: core::cell::Cell<Option<PanicPayload>> =
+        const { core::cell::Cell::new(None) };
+}
+
+/// Payload attached with [`attach_payload`] for the next recorded panic:
+/// category and code, detail, and whether it is set
+#[cfg(not(feature = "std"))]
+static PENDING_PAYLOAD: [AtomicU32; 3] = [const { AtomicU32::new(0) }; 3];
+
+/// Encoders registered with [`register_payload_encoder`]
+#[cfg(feature = "std")]
+static PAYLOAD_ENCODERS: std::sync::RwLock<std::vec::Vec<PanicPayloadEncoder>> =
+    std::sync::RwLock::new(std::vec::Vec::new());
+
 /// Builder for panic context configuration
 pub struct PanicContextBuilder<P: MemoryProvider> {
-    safety_level:    AsilLevel,
-    memory_provider: Option<P>,
-    memory_budget:   usize,
+    safety_level:      AsilLevel,
+    memory_provider:   Option<P>,
+    memory_budget:     usize,
+    post_panic_action: recovery::PostPanicAction,
 }
 
 impl<P: MemoryProvider> Default for PanicContextBuilder<P> {
@@ -172,9 +391,10 @@ impl<P: MemoryProvider> PanicContextBuilder<P> {
     /// Create a new panic context builder with ASIL-D defaults
     pub const fn new() -> Self {
         Self {
-            safety_level:    AsilLevel::AsilD,
-            memory_provider: None,
-            memory_budget:   DEFAULT_PANIC_MEMORY_BUDGET,
+            safety_level:      AsilLevel::AsilD,
+            memory_provider:   None,
+            memory_budget:     DEFAULT_PANIC_MEMORY_BUDGET,
+            post_panic_action: recovery::PostPanicAction::Halt,
         }
     }
 
@@ -191,6 +411,12 @@ pub fn with_memory_budget(mut self, budget: usize) -> Self {
         self
     }
 
+    /// Set the action taken once a panic has been recorded
+    pub fn with_post_panic_action(mut self, action: recovery::PostPanicAction) -> Self {
+        self.post_panic_action = action;
+        self
+    }
+
     /// Set the memory provider
     pub fn with_memory_provider(mut self, provider: P) -> Self {
         self.memory_provider = Some(provider);
@@ -206,9 +432,10 @@ pub fn build(self) -> Result<PanicContext<P>, &'static str> {
         }
 
         Ok(PanicContext {
-            safety_level:    self.safety_level,
-            memory_provider: provider,
-            memory_budget:   self.memory_budget,
+            safety_level:      self.safety_level,
+            memory_provider:   provider,
+            memory_budget:     self.memory_budget,
+            post_panic_action: self.post_panic_action,
         })
     }
 }
@@ -220,6 +447,7 @@ pub fn initialize_panic_handler<P: MemoryProvider>(
     // Store configuration in global atomics
     PANIC_ASIL_LEVEL.store(context.safety_level as u8, Ordering::SeqCst);
     PANIC_MEMORY_BUDGET.store(context.memory_budget as u32, Ordering::SeqCst);
+    recovery::set_post_panic_action(context.post_panic_action);
 
     // Validate memory provider can handle the budget
     if context.memory_provider.capacity() < context.memory_budget {
@@ -242,85 +470,193 @@ const fn hash_str(s: &str) -> u32 {
     hash
 }
 
-/// Store panic information in memory with debugger-visible pattern
-#[allow(dead_code)]
-fn store_panic_info(info: &core::panic::PanicInfo) {
-    let asil_level = PANIC_ASIL_LEVEL.load(Ordering::SeqCst);
-    let _memory_budget = PANIC_MEMORY_BUDGET.load(Ordering::SeqCst) as usize;
+/// Attach a payload to the next panic recorded on this thread
+///
+/// Call this right before panicking. Without `std` there are no threads to
+/// tell apart and the payload goes to the next panic recorded anywhere.
+pub fn attach_payload(payload: PanicPayload) {
+    #[cfg(feature = "std")]
+    PENDING_PAYLOAD.with(|pending| pending.set(Some(payload)));
 
-    // Create panic info structure
+    #[cfg(not(feature = "std"))]
+    {
+        PENDING_PAYLOAD[0].store(
+            payload.category as u32 | (payload.code as u32) << 16,
+            Ordering::SeqCst,
+        );
+        PENDING_PAYLOAD[1].store(payload.detail, Ordering::SeqCst);
+        PENDING_PAYLOAD[2].store(1, Ordering::SeqCst);
+    }
+}
+
+/// Take the payload attached with [`attach_payload`], if any
+fn take_pending_payload() -> Option<PanicPayload> {
+    #[cfg(feature = "std")]
+    return PENDING_PAYLOAD.with(core::cell::Cell::take);
+
+    #[cfg(not(feature = "std"))]
+    {
+        if PENDING_PAYLOAD[2].swap(0, Ordering::SeqCst) == 0 {
+            return None;
+        }
+        let word = PENDING_PAYLOAD[0].load(Ordering::SeqCst);
+        Some(PanicPayload {
+            category: word as u16,
+            code:     (word >> 16) as u16,
+            detail:   PENDING_PAYLOAD[1].load(Ordering::SeqCst),
+        })
+    }
+}
+
+/// Register an encoder for the payloads of `std` panics
+///
+/// [`record_panic_hook`] asks the encoders in registration order and uses
+/// the first payload one of them returns.
+#[cfg(feature = "std")]
+pub fn register_payload_encoder(encoder: PanicPayloadEncoder) {
+    PAYLOAD_ENCODERS
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(encoder);
+}
+
+/// Encode a `std` panic payload with the registered encoders
+#[cfg(feature = "std")]
+pub fn encode_payload(payload: &(dyn core::any::Any + Send)) -> Option<PanicPayload> {
+    PAYLOAD_ENCODERS
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .iter()
+        .find_map(|encoder| encoder(payload))
+}
+
+/// Encoder for panics whose payload is a `wrt_error::Error`
+///
+/// Register it with [`register_payload_encoder`] so that
+/// `std::panic::panic_any(error)` records the error's category and code.
+#[cfg(all(feature = "std", feature = "error-codes"))]
+pub fn encode_wrt_error(payload: &(dyn core::any::Any + Send)) -> Option<PanicPayload> {
+    payload.downcast_ref::<wrt_error::Error>().map(PanicPayload::from)
+}
+
+/// Record a panic with message `message` at `file`:`line`
+///
+/// Builds the panic information block for the configured ASIL level and
+/// stores it where [`last_panic_info`] and debuggers find it. Hosts call
+/// this from their own panic hook so that crash dumps can include the
+/// block. A payload attached with [`attach_payload`] is included.
+pub fn record_panic(message: &str, file: &str, line: u32) -> WrtPanicInfo {
+    record_panic_with_payload(message, file, line, take_pending_payload())
+}
+
+/// Record a panic like [`record_panic`] with an explicit payload
+pub fn record_panic_with_payload(
+    message: &str,
+    file: &str,
+    line: u32,
+    payload: Option<PanicPayload>,
+) -> WrtPanicInfo {
+    let payload_or_default = payload.unwrap_or_default();
     let mut panic_info = WrtPanicInfo {
         magic: PANIC_MAGIC,
-        asil_level,
-        reserved: [0; 3],
-        error_code: 0,
-        location_hash: 0,
+        asil_level: PANIC_ASIL_LEVEL.load(Ordering::SeqCst),
+        flags: if payload.is_some() { PANIC_FLAG_PAYLOAD } else { 0 },
+        reserved: [0; 2],
+        error_code: hash_str(message),
+        location_hash: hash_str(file).wrapping_add(line),
         timestamp: 0,
         stack_trace_count: 0,
         reserved2: [0; 3],
+        payload_category: payload_or_default.category,
+        payload_code: payload_or_default.code,
+        payload_detail: payload_or_default.detail,
         stack_trace: [0; MAX_STACK_TRACE_ENTRIES],
         checksum: 0,
     };
 
-    // Extract information from panic info
-    if let Some(location) = info.location() {
-        let file_hash = hash_str(location.file());
-        let line = location.line();
-        panic_info.location_hash = file_hash.wrapping_add(line);
+    #[cfg(feature = "std")]
+    {
+        if let Ok(duration) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            panic_info.timestamp = duration.as_secs();
+        }
+    }
+
+    panic_info.checksum = panic_info.compute_checksum();
+    store(&panic_info);
+    panic_info
+}
+
+/// Record a panic from a `std` panic hook
+///
+/// The payload is the one attached with [`attach_payload`] or, failing
+/// that, the one the registered encoders produce from the panic's payload.
+///
+/// ```no_run
+/// std::panic::set_hook(Box::new(|info| {
+///     wrt_panic::record_panic_hook(info);
+/// }));
+/// ```
+#[cfg(feature = "std")]
+pub fn record_panic_hook(info: &std::panic::PanicHookInfo<'_>) -> WrtPanicInfo {
+    let (file, line) =
+        info.location().map_or(("", 0), |location| (location.file(), location.line()));
+    let raw = info.payload();
+    let message = raw
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| raw.downcast_ref::<std::string::String>().map(std::string::String::as_str))
+        .unwrap_or_default();
+    let payload = take_pending_payload().or_else(|| encode_payload(raw));
+    record_panic_with_payload(message, file, line, payload)
+}
+
+/// Last panic information recorded in this process, if any
+pub fn last_panic_info() -> Option<WrtPanicInfo> {
+    let mut bytes = [0u8; PANIC_INFO_SIZE];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(&PANIC_INFO_STORAGE) {
+        chunk.copy_from_slice(&word.load(Ordering::SeqCst).to_le_bytes());
+    }
+    WrtPanicInfo::from_bytes(&bytes).filter(WrtPanicInfo::is_valid)
+}
+
+/// Store panic information in memory with debugger-visible pattern
+fn store(panic_info: &WrtPanicInfo) {
+    let bytes = panic_info.to_bytes();
+    for (chunk, word) in bytes.chunks_exact(4).zip(&PANIC_INFO_STORAGE) {
+        word.store(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]), Ordering::SeqCst);
     }
+}
+
+/// Store panic information of a panic handler invocation
+#[allow(dead_code)]
+fn store_panic_info(info: &core::panic::PanicInfo) -> WrtPanicInfo {
+    let (file, line) = info.location().map_or(("", 0), |location| (location.file(), location.line()));
 
     // Extract error code from panic message
     #[cfg(feature = "std")]
     {
         #[allow(clippy::incompatible_msrv)]
         let msg = info.message();
-        let msg_str = std::format!("{msg}");
-        panic_info.error_code = hash_str(&msg_str);
+        record_panic(&std::format!("{msg}"), file, line)
     }
     #[cfg(not(feature = "std"))]
     {
         // For no_std, we'll use the location information for error code
         // since message() is not reliable in no_std contexts
+        let mut panic_info = record_panic("", file, line);
         panic_info.error_code = panic_info.location_hash.wrapping_mul(0x9e3779b9);
+        panic_info.checksum = panic_info.compute_checksum();
+        store(&panic_info);
+        panic_info
     }
+}
 
-    // Add timestamp if available (std feature)
-    #[cfg(feature = "std")]
-    {
-        if let Ok(duration) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
-            panic_info.timestamp = duration.as_secs();
-        }
-    }
-
-    // Calculate checksum (simple XOR-based)
-    let mut checksum = panic_info.magic;
-    checksum ^= panic_info.asil_level as u32;
-    checksum ^= panic_info.error_code;
-    checksum ^= panic_info.location_hash;
-    checksum ^= (panic_info.timestamp as u32) ^ ((panic_info.timestamp >> 32) as u32);
-    panic_info.checksum = checksum;
-
-    // Store in a static location that debuggers can easily find
-    static mut PANIC_INFO_STORAGE: WrtPanicInfo = WrtPanicInfo {
-        magic:             0,
-        asil_level:        0,
-        reserved:          [0; 3],
-        error_code:        0,
-        location_hash:     0,
-        timestamp:         0,
-        stack_trace_count: 0,
-        reserved2:         [0; 3],
-        stack_trace:       [0; MAX_STACK_TRACE_ENTRIES],
-        checksum:          0,
-    };
-
-    // Safe to write since we're in a panic handler (single-threaded at this point)
-    // This unsafe block is justified: panic handlers are inherently single-threaded
-    // and we need static storage for debugger access
-    #[allow(unsafe_code)]
-    unsafe {
-        PANIC_INFO_STORAGE = panic_info;
-    }
+/// Park the other cores, count the panic and carry out the post-panic action
+#[allow(dead_code)]
+fn enter_safe_state(panic_info: &WrtPanicInfo) -> ! {
+    smp::signal_panic(panic_info);
+    recovery::record_crash(panic_info);
+    recovery::finish_panic()
 }
 
 /// ASIL-D compliant panic handler (≥99% Single-Point Fault Metric)
@@ -350,13 +686,11 @@ fn panic_asil_d(info: &core::panic::PanicInfo) -> ! {
     // ASIL-D compliant panic handling per ISO 26262:
 
     // 1. Store comprehensive error information for fault analysis
-    store_panic_info(info);
+    let panic_info = store_panic_info(info);
 
-    // 2. Ensure no recovery attempts - enter permanent safe state
-    // 3. Use hardware-efficient infinite loop for safe state maintenance
-    loop {
-        core::hint::spin_loop();
-    }
+    // 2. Ensure no recovery attempts - park all cores in the safe state
+    // 3. Halt or reset as configured, never resuming the failed execution
+    enter_safe_state(&panic_info)
 }
 
 /// ASIL-B compliant panic handler (≥90% Single-Point Fault Metric)
@@ -385,12 +719,10 @@ fn panic_asil_b(info: &core::panic::PanicInfo) -> ! {
     // ASIL-B compliant panic handling per ISO 26262:
 
     // 1. Store basic error information for fault analysis
-    store_panic_info(info);
+    let panic_info = store_panic_info(info);
 
-    // 2. Enter safe state - similar to ASIL-D but with reduced complexity
-    loop {
-        core::hint::spin_loop();
-    }
+    // 2. Enter safe state on all cores - similar to ASIL-D but with reduced complexity
+    enter_safe_state(&panic_info)
 }
 
 /// Development panic handler
@@ -415,12 +747,10 @@ fn panic_dev(info: &core::panic::PanicInfo) -> ! {
     // Development panic handling:
 
     // 1. Store enhanced error information for development debugging
-    store_panic_info(info);
+    let panic_info = store_panic_info(info);
 
-    // 2. Enter safe state with development-friendly behavior
-    loop {
-        core::hint::spin_loop();
-    }
+    // 2. Enter safe state on all cores with development-friendly behavior
+    enter_safe_state(&panic_info)
 }
 
 /// Release panic handler (default)
@@ -445,12 +775,10 @@ fn panic_release(info: &core::panic::PanicInfo) -> ! {
     // Release panic handling:
 
     // 1. Store minimal error information
-    store_panic_info(info);
+    let panic_info = store_panic_info(info);
 
-    // 2. Enter safe state immediately
-    loop {
-        core::hint::spin_loop();
-    }
+    // 2. Enter safe state on all cores immediately
+    enter_safe_state(&panic_info)
 }
 
 /// Get the current panic handler configuration information
@@ -616,6 +944,62 @@ fn test_panic_info_structure_size() {
         assert!(size <= DEFAULT_PANIC_MEMORY_BUDGET);
     }
 
+    #[test]
+    fn test_panic_info_round_trip() {
+        assert_eq!(core::mem::size_of::<WrtPanicInfo>(), PANIC_INFO_SIZE);
+
+        let info = record_panic("index out of bounds", "src/engine.rs", 42);
+        assert!(info.is_valid());
+        assert_eq!(last_panic_info(), Some(info));
+
+        let bytes = info.to_bytes();
+        assert_eq!(&bytes[..4], &PANIC_MAGIC.to_le_bytes());
+        assert_eq!(WrtPanicInfo::from_bytes(&bytes), Some(info));
+        assert_eq!(WrtPanicInfo::from_bytes(&bytes[1..]), None);
+
+        let mut corrupted = bytes;
+        corrupted[8] ^= 1;
+        assert!(!WrtPanicInfo::from_bytes(&corrupted).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_panic_payload_capture() {
+        let plain = record_panic_with_payload("plain", "src/lib.rs", 1, None);
+        assert_eq!(plain.payload(), None);
+
+        let payload = PanicPayload::new(7, 1005).with_detail(42);
+        attach_payload(payload);
+        let info = record_panic("budget exceeded", "src/lib.rs", 2);
+        assert!(info.is_valid());
+        assert_eq!(info.payload(), Some(payload));
+
+        let decoded = WrtPanicInfo::from_bytes(&info.to_bytes()).unwrap();
+        assert_eq!(decoded.payload(), Some(payload));
+        let mut tampered = decoded;
+        tampered.payload_code = 1006;
+        assert!(!tampered.is_valid());
+
+        // The attached payload is consumed by the panic that recorded it
+        assert_eq!(record_panic("next", "src/lib.rs", 3).payload(), None);
+    }
+
+    #[cfg(all(feature = "std", feature = "error-codes"))]
+    #[test]
+    fn test_wrt_error_payload_encoder() {
+        use wrt_error::{
+            codes,
+            Error,
+            ErrorCategory,
+        };
+
+        register_payload_encoder(encode_wrt_error);
+        let error = Error::new(ErrorCategory::Capacity, codes::MEMORY_LIMIT_EXCEEDED, "full");
+        let payload = encode_payload(&error).unwrap();
+        assert_eq!(payload.error_category(), Some(ErrorCategory::Capacity));
+        assert_eq!(payload.code, codes::MEMORY_LIMIT_EXCEEDED);
+        assert_eq!(encode_payload(&"message"), None);
+    }
+
     #[test]
     fn test_hash_function() {
         // Test the hash function produces consistent results
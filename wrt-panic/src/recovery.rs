@@ -0,0 +1,305 @@
+// WRT - wrt-panic
+// Module: Post-panic actions and persistent crash counters
+// SW-REQ-ID: REQ_PANIC_001, REQ_SAFETY_ASIL_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! What happens after a panic, and how the next boot learns about it
+//!
+//! The [`PostPanicAction`] decides whether a panicking system halts in its
+//! safe state or resets, either by letting the watchdog expire or through a
+//! platform reset hook registered with [`register_reset_hooks`].
+//!
+//! To tell a recovered system from a cold boot, the platform provides a
+//! [`CrashCounterRegion`] in memory that survives a reset, typically a
+//! `.noinit` RAM section or backup registers:
+//!
+//! ```rust
+//! use wrt_panic::recovery::{
+//!     boot_report,
+//!     register_crash_region,
+//!     CrashCounterRegion,
+//! };
+//!
+//! #[cfg_attr(target_os = "none", link_section = ".noinit")]
+//! static CRASH_REGION: CrashCounterRegion = CrashCounterRegion::new();
+//!
+//! register_crash_region(&CRASH_REGION);
+//! let report = boot_report();
+//! if report.panics > 3 {
+//!     // degrade to a limp-home configuration
+//! }
+//! ```
+
+use core::sync::atomic::{
+    AtomicPtr,
+    AtomicU32,
+    AtomicU8,
+    Ordering,
+};
+
+use crate::WrtPanicInfo;
+
+/// Marker of an initialized crash counter region ("WRCR")
+const CRASH_REGION_MAGIC: u32 = 0x5752_4352;
+
+/// Words of a crash counter region
+const CRASH_REGION_WORDS: usize = 6;
+
+/// Word indices in a crash counter region
+const MAGIC: usize = 0;
+const PANICS: usize = 1;
+const PENDING: usize = 2;
+const ERROR_CODE: usize = 3;
+const LOCATION_HASH: usize = 4;
+const CHECKSUM: usize = 5;
+
+/// Action taken once a panic has been recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum PostPanicAction {
+    /// Stay in the safe state until power-cycled
+    #[default]
+    Halt          = 0,
+    /// Stop servicing the watchdog so that it resets the system
+    WatchdogReset = 1,
+    /// Reset through the registered platform hook
+    PlatformReset = 2,
+}
+
+impl PostPanicAction {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::WatchdogReset,
+            2 => Self::PlatformReset,
+            _ => Self::Halt,
+        }
+    }
+}
+
+/// Platform hooks used by the resetting post-panic actions
+#[derive(Debug)]
+pub struct ResetHooks {
+    /// Reset the system immediately
+    pub reset:           fn() -> !,
+    /// Stop servicing the watchdog, e.g. by masking the interrupt that
+    /// feeds it
+    pub starve_watchdog: fn(),
+}
+
+/// Memory surviving a reset that counts the panics of the system
+#[derive(Debug)]
+pub struct CrashCounterRegion {
+    words: [AtomicU32; CRASH_REGION_WORDS],
+}
+
+impl Default for CrashCounterRegion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrashCounterRegion {
+    /// Create a region for a static
+    ///
+    /// The start-up code must not zero the static for the counters to
+    /// survive a reset; an uninitialized region reads as a cold boot.
+    pub const fn new() -> Self {
+        Self {
+            words: [const { AtomicU32::new(0) }; CRASH_REGION_WORDS],
+        }
+    }
+
+    fn word(&self, index: usize) -> u32 {
+        self.words[index].load(Ordering::SeqCst)
+    }
+
+    fn checksum(&self) -> u32 {
+        self.words[..CHECKSUM]
+            .iter()
+            .enumerate()
+            .fold(0, |sum, (index, word)| {
+                sum ^ word.load(Ordering::SeqCst).rotate_left(index as u32 * 5)
+            })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.word(MAGIC) == CRASH_REGION_MAGIC && self.word(CHECKSUM) == self.checksum()
+    }
+
+    fn seal(&self) {
+        self.words[MAGIC].store(CRASH_REGION_MAGIC, Ordering::SeqCst);
+        self.words[CHECKSUM].store(self.checksum(), Ordering::SeqCst);
+    }
+
+    /// Reset the region to a cold-boot state
+    pub fn clear(&self) {
+        for word in &self.words[PANICS..CHECKSUM] {
+            word.store(0, Ordering::SeqCst);
+        }
+        self.seal();
+    }
+}
+
+/// Panic history reported at boot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RecoveryReport {
+    /// Panics recorded since the counter was last cleared
+    pub panics:             u32,
+    /// Whether the previous run ended in a panic
+    pub last_boot_panicked: bool,
+    /// Error code of the most recent panic
+    pub last_error_code:    u32,
+    /// Location hash of the most recent panic
+    pub last_location_hash: u32,
+    /// Whether the region held valid data, false on a cold boot
+    pub valid:              bool,
+}
+
+static POST_PANIC_ACTION: AtomicU8 = AtomicU8::new(PostPanicAction::Halt as u8);
+
+/// Registered reset hooks, null while none are registered
+static RESET_HOOKS: AtomicPtr<ResetHooks> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Registered crash counter region, null while none is registered
+static CRASH_REGION: AtomicPtr<CrashCounterRegion> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Set the action taken after a panic has been recorded
+pub fn set_post_panic_action(action: PostPanicAction) {
+    POST_PANIC_ACTION.store(action as u8, Ordering::SeqCst);
+}
+
+/// Action taken after a panic has been recorded
+pub fn post_panic_action() -> PostPanicAction {
+    PostPanicAction::from_u8(POST_PANIC_ACTION.load(Ordering::SeqCst))
+}
+
+/// Register the platform reset hooks
+pub fn register_reset_hooks(hooks: &'static ResetHooks) {
+    RESET_HOOKS.store(core::ptr::from_ref(hooks).cast_mut(), Ordering::SeqCst);
+}
+
+/// Register the memory holding the crash counters
+pub fn register_crash_region(region: &'static CrashCounterRegion) {
+    CRASH_REGION.store(core::ptr::from_ref(region).cast_mut(), Ordering::SeqCst);
+}
+
+#[allow(unsafe_code)] // Reads back the 'static references stored by the registration functions
+fn registered<T>(slot: &AtomicPtr<T>) -> Option<&'static T> {
+    // SAFETY: The pointer is either null or was created from a `&'static T`
+    // by one of the registration functions, and is never written through.
+    unsafe { slot.load(Ordering::SeqCst).as_ref() }
+}
+
+/// Count a panic in the registered crash counter region
+///
+/// The panic handlers do this themselves; call it from a `std` panic hook
+/// to count panics of hosted builds.
+pub fn record_crash(panic_info: &WrtPanicInfo) {
+    let Some(region) = registered(&CRASH_REGION) else {
+        return;
+    };
+    if !region.is_valid() {
+        region.clear();
+    }
+    region.words[PANICS]
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |panics| Some(panics.saturating_add(1)))
+        .ok();
+    region.words[PENDING].store(1, Ordering::SeqCst);
+    region.words[ERROR_CODE].store(panic_info.error_code, Ordering::SeqCst);
+    region.words[LOCATION_HASH].store(panic_info.location_hash, Ordering::SeqCst);
+    region.seal();
+}
+
+/// Report the panic history at boot
+///
+/// Call this once during start-up after [`register_crash_region`]. It
+/// acknowledges the previous panic, so only the first call after a reset
+/// reports `last_boot_panicked`. An invalid region is treated as a cold
+/// boot and initialized.
+pub fn boot_report() -> RecoveryReport {
+    let Some(region) = registered(&CRASH_REGION) else {
+        return RecoveryReport::default();
+    };
+    if !region.is_valid() {
+        region.clear();
+        return RecoveryReport::default();
+    }
+    let report = RecoveryReport {
+        panics:             region.word(PANICS),
+        last_boot_panicked: region.word(PENDING) != 0,
+        last_error_code:    region.word(ERROR_CODE),
+        last_location_hash: region.word(LOCATION_HASH),
+        valid:              true,
+    };
+    region.words[PENDING].store(0, Ordering::SeqCst);
+    region.seal();
+    report
+}
+
+/// Reset the crash counter, e.g. once the system has run stable long enough
+pub fn clear_crash_counter() {
+    if let Some(region) = registered(&CRASH_REGION) {
+        region.clear();
+    }
+}
+
+/// Carry out the configured post-panic action
+pub fn finish_panic() -> ! {
+    let hooks = registered(&RESET_HOOKS);
+    match (post_panic_action(), hooks) {
+        (PostPanicAction::PlatformReset, Some(hooks)) => (hooks.reset)(),
+        (PostPanicAction::WatchdogReset, Some(hooks)) => (hooks.starve_watchdog)(),
+        _ => {},
+    }
+    // Halt, or wait for the starved watchdog to reset the system
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_counter_survives_reset() {
+        static REGION: CrashCounterRegion = CrashCounterRegion::new();
+        register_crash_region(&REGION);
+
+        // Cold boot: the region is not initialized yet
+        assert_eq!(boot_report(), RecoveryReport::default());
+
+        let mut info = WrtPanicInfo::from_bytes(&[0; crate::PANIC_INFO_SIZE]).unwrap();
+        info.error_code = 0xBAD;
+        record_crash(&info);
+        info.location_hash = 42;
+        record_crash(&info);
+
+        let report = boot_report();
+        assert!(report.valid && report.last_boot_panicked);
+        assert_eq!(report.panics, 2);
+        assert_eq!((report.last_error_code, report.last_location_hash), (0xBAD, 42));
+        assert!(!boot_report().last_boot_panicked);
+        assert_eq!(boot_report().panics, 2);
+
+        // A corrupted region reads as a cold boot
+        REGION.words[PANICS].store(7, Ordering::SeqCst);
+        assert!(!boot_report().valid);
+        assert_eq!(boot_report().panics, 0);
+
+        record_crash(&info);
+        clear_crash_counter();
+        assert_eq!(boot_report().panics, 0);
+    }
+
+    #[test]
+    fn test_post_panic_action() {
+        assert_eq!(post_panic_action(), PostPanicAction::Halt);
+        set_post_panic_action(PostPanicAction::WatchdogReset);
+        assert_eq!(post_panic_action(), PostPanicAction::WatchdogReset);
+        set_post_panic_action(PostPanicAction::Halt);
+    }
+}
@@ -0,0 +1,271 @@
+// WRT - wrt-panic
+// Module: Multi-core panic coordination
+// SW-REQ-ID: REQ_PANIC_001, REQ_SAFETY_ASIL_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Cross-core safe-state entry for multi-core targets
+//!
+//! A panic on one core must not leave the other cores running on state the
+//! panicking core may have corrupted. The platform registers a
+//! [`CoreDoorbell`] describing how to identify the executing core and how to
+//! interrupt the others (an inter-processor interrupt, a mailbox doorbell,
+//! ...). The first core to panic becomes the initiator: it rings every other
+//! core, whose interrupt handler calls [`park_core`], and waits a bounded
+//! time for them to acknowledge. Each core keeps its own [`CorePanicRecord`],
+//! so a debugger or the next boot can see which core failed first and which
+//! cores reached the safe state.
+//!
+//! Without a registered doorbell the executing core is treated as core 0 of
+//! a single-core system.
+
+use core::sync::atomic::{
+    AtomicPtr,
+    AtomicU32,
+    Ordering,
+};
+
+use crate::WrtPanicInfo;
+
+/// Maximum number of cores tracked by the panic coordination
+pub const MAX_CORES: usize = 8;
+
+/// Marker for "no core" in the initiator slots
+const NO_CORE: u32 = u32::MAX;
+
+/// Platform hooks for signalling the other cores of the system
+#[derive(Debug)]
+pub struct CoreDoorbell {
+    /// Number of cores taking part in the coordination, at most [`MAX_CORES`]
+    pub core_count:       usize,
+    /// Index of the executing core
+    pub current_core:     fn() -> usize,
+    /// Interrupt `core`; its handler is expected to call [`park_core`]
+    pub ring:             fn(core: usize),
+    /// Drive the outputs owned by `core` into their safe state
+    pub enter_safe_state: fn(core: usize),
+    /// Spin iterations the initiator waits for the other cores to park
+    pub ack_spins:        u32,
+}
+
+/// Coordination state of a core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CoreState {
+    /// The core has not been involved in a panic
+    Running  = 0,
+    /// The core panicked itself
+    Panicked = 1,
+    /// The core was parked on request of a panicking core
+    Parked   = 2,
+}
+
+impl CoreState {
+    const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Panicked,
+            2 => Self::Parked,
+            _ => Self::Running,
+        }
+    }
+}
+
+/// Panic record of a single core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorePanicRecord {
+    /// State the core reached
+    pub state:         CoreState,
+    /// Core whose panic caused this state
+    pub initiator:     usize,
+    /// Error code of the panic, for cores that panicked themselves
+    pub error_code:    u32,
+    /// Location hash of the panic, for cores that panicked themselves
+    pub location_hash: u32,
+}
+
+/// Registered doorbell, null while none is registered
+static DOORBELL: AtomicPtr<CoreDoorbell> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Core that panicked first
+static INITIATOR: AtomicU32 = AtomicU32::new(NO_CORE);
+
+/// Per-core records: state, initiator, error code and location hash
+static CORE_RECORDS: [[AtomicU32; 4]; MAX_CORES] =
+    [const { [const { AtomicU32::new(0) }; 4] }; MAX_CORES];
+
+/// Register the platform hooks used to reach the other cores
+///
+/// Call this once during start-up on any core, before the other cores
+/// start executing code that may panic.
+pub fn register_doorbell(doorbell: &'static CoreDoorbell) -> Result<(), &'static str> {
+    if doorbell.core_count == 0 || doorbell.core_count > MAX_CORES {
+        return Err("Core count must be between 1 and MAX_CORES");
+    }
+    DOORBELL.store(core::ptr::from_ref(doorbell).cast_mut(), Ordering::SeqCst);
+    Ok(())
+}
+
+#[allow(unsafe_code)] // Reads back the 'static reference stored by register_doorbell
+fn doorbell() -> Option<&'static CoreDoorbell> {
+    // SAFETY: The pointer is either null or was created from a
+    // `&'static CoreDoorbell` in `register_doorbell`, and is never written
+    // through.
+    unsafe { DOORBELL.load(Ordering::SeqCst).as_ref() }
+}
+
+fn current_core() -> usize {
+    doorbell().map_or(0, |doorbell| (doorbell.current_core)() % MAX_CORES)
+}
+
+/// Mark `core` as `state`, keeping a `Panicked` record if the core already
+/// panicked itself
+fn record(core: usize, state: CoreState, initiator: usize, error_code: u32, location_hash: u32) {
+    let slots = &CORE_RECORDS[core];
+    if slots[0]
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+            (current != CoreState::Panicked as u32).then_some(state as u32)
+        })
+        .is_ok()
+    {
+        slots[1].store(initiator as u32, Ordering::SeqCst);
+        slots[2].store(error_code, Ordering::SeqCst);
+        slots[3].store(location_hash, Ordering::SeqCst);
+    }
+}
+
+/// Record the panic of the executing core and bring the other cores into
+/// their safe state
+///
+/// Returns the index of the executing core. Only the first panicking core
+/// rings the others; a core panicking concurrently only records itself.
+pub fn signal_panic(panic_info: &WrtPanicInfo) -> usize {
+    let core = current_core();
+    record(core, CoreState::Panicked, core, panic_info.error_code, panic_info.location_hash);
+
+    let first = INITIATOR
+        .compare_exchange(NO_CORE, core as u32, Ordering::SeqCst, Ordering::SeqCst)
+        .is_ok();
+    if let Some(doorbell) = doorbell() {
+        if first {
+            let others = || (0..doorbell.core_count).filter(move |&other| other != core);
+            others().for_each(doorbell.ring);
+            let mut spins = doorbell.ack_spins;
+            while spins > 0 && others().any(|other| core_state(other) == CoreState::Running) {
+                core::hint::spin_loop();
+                spins -= 1;
+            }
+        }
+        (doorbell.enter_safe_state)(core);
+    }
+    core
+}
+
+/// Record the executing core as parked on behalf of the panicking core
+///
+/// Returns the index of the executing core.
+pub fn acknowledge_park() -> usize {
+    let core = current_core();
+    let initiator = panic_initiator().unwrap_or(core);
+    record(core, CoreState::Parked, initiator, 0, 0);
+    if let Some(doorbell) = doorbell() {
+        (doorbell.enter_safe_state)(core);
+    }
+    core
+}
+
+/// Park the executing core in its safe state
+///
+/// Called from the doorbell interrupt handler of a core that did not panic
+/// itself. Never returns.
+pub fn park_core() -> ! {
+    acknowledge_park();
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Core that panicked first, if any core panicked
+pub fn panic_initiator() -> Option<usize> {
+    let core = INITIATOR.load(Ordering::SeqCst);
+    (core != NO_CORE).then_some(core as usize)
+}
+
+fn core_state(core: usize) -> CoreState {
+    CoreState::from_u32(CORE_RECORDS[core][0].load(Ordering::SeqCst))
+}
+
+/// Panic record of `core`, if it panicked or was parked
+pub fn core_panic_record(core: usize) -> Option<CorePanicRecord> {
+    let slots = CORE_RECORDS.get(core)?;
+    let state = core_state(core);
+    (state != CoreState::Running).then(|| CorePanicRecord {
+        state,
+        initiator: slots[1].load(Ordering::SeqCst) as usize,
+        error_code: slots[2].load(Ordering::SeqCst),
+        location_hash: slots[3].load(Ordering::SeqCst),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    static EXECUTING: AtomicUsize = AtomicUsize::new(0);
+    static RUNG: AtomicU32 = AtomicU32::new(0);
+    static SAFE: AtomicU32 = AtomicU32::new(0);
+
+    static DOORBELL: CoreDoorbell = CoreDoorbell {
+        core_count:       3,
+        current_core:     || EXECUTING.load(Ordering::SeqCst),
+        ring:             |core| {
+            RUNG.fetch_or(1 << core, Ordering::SeqCst);
+        },
+        enter_safe_state: |core| {
+            SAFE.fetch_or(1 << core, Ordering::SeqCst);
+        },
+        ack_spins:        16,
+    };
+
+    #[test]
+    fn test_cross_core_safe_state() {
+        register_doorbell(&DOORBELL).unwrap();
+        let mut info = WrtPanicInfo::from_bytes(&[0; crate::PANIC_INFO_SIZE]).unwrap();
+        info.error_code = 0xC0DE;
+        EXECUTING.store(1, Ordering::SeqCst);
+        assert_eq!(signal_panic(&info), 1);
+        assert_eq!(panic_initiator(), Some(1));
+        assert_eq!(RUNG.load(Ordering::SeqCst), 0b101);
+
+        // A second panic on core 2 races the doorbell and keeps its own record
+        info.error_code ^= 1;
+        EXECUTING.store(2, Ordering::SeqCst);
+        signal_panic(&info);
+        acknowledge_park();
+        EXECUTING.store(0, Ordering::SeqCst);
+        acknowledge_park();
+
+        assert_eq!(RUNG.load(Ordering::SeqCst), 0b101);
+        assert_eq!(SAFE.load(Ordering::SeqCst), 0b111);
+        assert_eq!(panic_initiator(), Some(1));
+        let parked = core_panic_record(0).unwrap();
+        assert_eq!((parked.state, parked.initiator), (CoreState::Parked, 1));
+        let own = core_panic_record(2).unwrap();
+        assert_eq!((own.state, own.initiator), (CoreState::Panicked, 2));
+        assert_eq!(own.error_code, 0xC0DE ^ 1);
+        assert_eq!(core_panic_record(1).unwrap().error_code, 0xC0DE);
+        assert!(core_panic_record(MAX_CORES).is_none());
+
+        static TOO_MANY: CoreDoorbell = CoreDoorbell {
+            core_count:       MAX_CORES + 1,
+            current_core:     || 0,
+            ring:             |_| {},
+            enter_safe_state: |_| {},
+            ack_spins:        0,
+        };
+        assert!(register_doorbell(&TOO_MANY).is_err());
+    }
+}
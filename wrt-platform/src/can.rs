@@ -0,0 +1,339 @@
+// WRT - wrt-platform
+// Module: CAN Bus Abstraction
+// SW-REQ-ID: REQ_PLATFORM_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Classic CAN frames and the `CanBus` trait drivers implement.
+//!
+//! The trait is deliberately small so that an MCU board support package can
+//! implement it directly on top of its CAN peripheral. Linux hosts use
+//! `SocketCan` (see `linux_can`); `StubCanBus` is a fixed-capacity loopback
+//! for bring-up and tests that needs neither `std` nor an allocator.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+/// Maximum payload of a classic CAN frame
+pub const CAN_MAX_DLEN: usize = 8;
+
+/// Valid bits of an 11-bit standard identifier
+pub const CAN_SFF_MASK: u32 = 0x0000_07FF;
+
+/// Valid bits of a 29-bit extended identifier
+pub const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+
+/// A classic CAN 2.0 data or remote frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFrame {
+    /// Identifier, without any flag bits
+    id:       u32,
+    /// Whether `id` is a 29-bit extended identifier
+    extended: bool,
+    /// Whether this is a remote transmission request
+    remote:   bool,
+    /// Data length code (0..=8)
+    len:      u8,
+    /// Payload; bytes past `len` are zero
+    data:     [u8; CAN_MAX_DLEN],
+}
+
+impl CanFrame {
+    /// Creates a data frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `id` does not fit the identifier format
+    /// or `data` is longer than [`CAN_MAX_DLEN`].
+    pub fn new(id: u32, extended: bool, data: &[u8]) -> Result<Self> {
+        validate_id(id, extended)?;
+        if data.len() > CAN_MAX_DLEN {
+            return Err(Error::new(
+                ErrorCategory::Validation,
+                codes::VALIDATION_ERROR,
+                "CAN payload exceeds 8 bytes",
+            ));
+        }
+        let mut payload = [0; CAN_MAX_DLEN];
+        payload[..data.len()].copy_from_slice(data);
+        Ok(Self {
+            id,
+            extended,
+            remote: false,
+            len: data.len() as u8,
+            data: payload,
+        })
+    }
+
+    /// Creates a remote transmission request for `len` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `id` does not fit the identifier format
+    /// or `len` exceeds [`CAN_MAX_DLEN`].
+    pub fn remote(id: u32, extended: bool, len: u8) -> Result<Self> {
+        let mut frame = Self::new(id, extended, &[])?;
+        if usize::from(len) > CAN_MAX_DLEN {
+            return Err(Error::new(
+                ErrorCategory::Validation,
+                codes::VALIDATION_ERROR,
+                "CAN data length code exceeds 8",
+            ));
+        }
+        frame.remote = true;
+        frame.len = len;
+        Ok(frame)
+    }
+
+    /// Identifier, without any flag bits
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Whether the identifier is a 29-bit extended one
+    pub fn is_extended(&self) -> bool {
+        self.extended
+    }
+
+    /// Whether this is a remote transmission request
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    /// Data length code
+    pub fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Whether the frame carries no payload
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Payload; empty for remote frames
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            &[]
+        } else {
+            &self.data[..usize::from(self.len)]
+        }
+    }
+}
+
+fn validate_id(id: u32, extended: bool) -> Result<()> {
+    let mask = if extended { CAN_EFF_MASK } else { CAN_SFF_MASK };
+    if id & !mask != 0 {
+        return Err(Error::new(
+            ErrorCategory::Validation,
+            codes::VALIDATION_ERROR,
+            "CAN identifier out of range",
+        ));
+    }
+    Ok(())
+}
+
+/// Acceptance filter in the id/mask form used by CAN controllers
+///
+/// A frame matches when `frame.id & mask == id & mask` and its identifier
+/// format equals the filter's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanFilter {
+    /// Identifier to compare against
+    pub id:       u32,
+    /// Bits of the identifier that must match
+    pub mask:     u32,
+    /// Whether the filter applies to extended identifiers
+    pub extended: bool,
+}
+
+impl CanFilter {
+    /// Creates an id/mask filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `id` does not fit the identifier format.
+    pub fn new(id: u32, mask: u32, extended: bool) -> Result<Self> {
+        validate_id(id, extended)?;
+        let full = if extended { CAN_EFF_MASK } else { CAN_SFF_MASK };
+        Ok(Self {
+            id,
+            mask: mask & full,
+            extended,
+        })
+    }
+
+    /// Filter accepting exactly `id`
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `id` does not fit the identifier format.
+    pub fn exact(id: u32, extended: bool) -> Result<Self> {
+        Self::new(id, u32::MAX, extended)
+    }
+
+    /// Filter accepting every identifier of one format
+    pub fn any(extended: bool) -> Self {
+        Self {
+            id: 0,
+            mask: 0,
+            extended,
+        }
+    }
+
+    /// Whether `frame` passes the filter
+    pub fn matches(&self, frame: &CanFrame) -> bool {
+        self.matches_id(frame.id, frame.extended)
+    }
+
+    /// Whether an identifier passes the filter
+    pub fn matches_id(&self, id: u32, extended: bool) -> bool {
+        self.extended == extended && id & self.mask == self.id & self.mask
+    }
+
+    /// Whether every identifier accepted by `other` is accepted by `self`
+    pub fn covers(&self, other: &CanFilter) -> bool {
+        // `other` may leave bits free that `self` constrains, or pin them to
+        // a different value; either lets through identifiers `self` rejects.
+        self.extended == other.extended
+            && self.mask & !other.mask == 0
+            && other.id & self.mask == self.id & self.mask
+    }
+}
+
+/// A CAN controller or socket
+///
+/// Both operations are non-blocking so that callers can multiplex several
+/// buses or interleave bus traffic with execution.
+pub trait CanBus: Send {
+    /// Queues `frame` for transmission.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller rejects the frame, for example
+    /// because its transmit mailboxes are full.
+    fn transmit(&mut self, frame: &CanFrame) -> Result<()>;
+
+    /// Takes the next received frame, if one is pending.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller is in a fault state.
+    fn receive(&mut self) -> Result<Option<CanFrame>>;
+}
+
+/// Loopback CAN bus with room for `N` frames in flight
+///
+/// Every transmitted frame is received back in order. Stands in for the
+/// controller driver on targets that do not have one yet.
+#[derive(Debug)]
+pub struct StubCanBus<const N: usize> {
+    /// Ring of frames waiting to be received
+    frames: [Option<CanFrame>; N],
+    /// Index of the oldest frame
+    head:   usize,
+    /// Number of frames in the ring
+    count:  usize,
+}
+
+impl<const N: usize> Default for StubCanBus<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> StubCanBus<N> {
+    /// Creates an empty loopback bus
+    pub const fn new() -> Self {
+        Self {
+            frames: [None; N],
+            head:   0,
+            count:  0,
+        }
+    }
+
+    /// Number of frames waiting to be received
+    pub fn pending(&self) -> usize {
+        self.count
+    }
+}
+
+impl<const N: usize> CanBus for StubCanBus<N> {
+    fn transmit(&mut self, frame: &CanFrame) -> Result<()> {
+        if self.count == N {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_EXHAUSTED,
+                "CAN transmit queue full",
+            ));
+        }
+        self.frames[(self.head + self.count) % N] = Some(*frame);
+        self.count += 1;
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Option<CanFrame>> {
+        if self.count == 0 {
+            return Ok(None);
+        }
+        let frame = self.frames[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.count -= 1;
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_validation() {
+        let frame = CanFrame::new(0x123, false, &[1, 2, 3]).unwrap();
+        assert_eq!(frame.data(), &[1, 2, 3]);
+        assert_eq!(frame.len(), 3);
+
+        assert!(CanFrame::new(0x800, false, &[]).is_err());
+        assert!(CanFrame::new(0x800, true, &[]).is_ok());
+        assert!(CanFrame::new(0x1, false, &[0; 9]).is_err());
+
+        let request = CanFrame::remote(0x7DF, false, 8).unwrap();
+        assert!(request.is_remote());
+        assert!(request.data().is_empty());
+    }
+
+    #[test]
+    fn test_filters() {
+        let block = CanFilter::new(0x100, 0x700, false).unwrap();
+        assert!(block.matches_id(0x1AB, false));
+        assert!(!block.matches_id(0x200, false));
+        assert!(!block.matches_id(0x100, true));
+
+        assert!(block.covers(&CanFilter::exact(0x1FF, false).unwrap()));
+        assert!(block.covers(&CanFilter::new(0x180, 0x780, false).unwrap()));
+        assert!(!block.covers(&CanFilter::any(false)));
+        assert!(!block.covers(&CanFilter::exact(0x200, false).unwrap()));
+    }
+
+    #[test]
+    fn test_stub_bus_loops_back_in_order() {
+        let mut bus = StubCanBus::<2>::new();
+        let first = CanFrame::new(0x10, false, &[1]).unwrap();
+        let second = CanFrame::new(0x20, false, &[2]).unwrap();
+
+        bus.transmit(&first).unwrap();
+        bus.transmit(&second).unwrap();
+        assert!(bus.transmit(&first).is_err());
+
+        assert_eq!(bus.receive().unwrap(), Some(first));
+        bus.transmit(&first).unwrap();
+        assert_eq!(bus.receive().unwrap(), Some(second));
+        assert_eq!(bus.receive().unwrap(), Some(first));
+        assert_eq!(bus.receive().unwrap(), None);
+    }
+}
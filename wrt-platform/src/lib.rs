@@ -47,8 +47,10 @@
 // handlers Module declarations
 // pub mod bounded_platform; // Disabled due to circular dependency with
 // wrt-foundation
+pub mod can;
 pub mod comprehensive_limits;
 pub mod memory;
+pub mod partition;
 pub mod performance_validation;
 pub mod platform_abstraction;
 pub mod prelude;
@@ -56,6 +58,7 @@
 pub mod runtime_detection;
 pub mod simd;
 pub mod sync;
+pub mod tick;
 pub mod time;
 
 // Enhanced platform features
@@ -107,6 +110,14 @@
 #[cfg(all(feature = "std", target_os = "linux"))]
 pub mod linux_ipc;
 
+// Linux SocketCAN driver (requires std, direct syscalls)
+#[cfg(all(
+    feature = "std",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub mod linux_can;
+
 #[cfg(feature = "std")]
 pub mod high_availability;
 
@@ -166,6 +177,8 @@
 pub mod zephyr_memory;
 #[cfg(feature = "platform-zephyr")]
 pub mod zephyr_sync;
+#[cfg(feature = "platform-zephyr")]
+pub mod zephyr_tick;
 
 // Tock OS-specific modules
 #[cfg(feature = "platform-tock")]
@@ -281,6 +294,19 @@
     WASM_PAGE_SIZE,
 }; // WASM_PAGE_SIZE is always available
 // Export performance validation (for testing and benchmarking)
+pub use partition::{
+    register_health_monitor,
+    report_health,
+    HealthEvent,
+    HealthMonitor,
+    MemoryQueuingPort,
+    MemorySamplingPort,
+    PartitionSchedule,
+    QueuingPort,
+    RecoveryAction,
+    SamplingPort,
+    TimeWindow,
+};
 pub use performance_validation::{
     BenchmarkResult,
     CompileTimeValidator,
@@ -354,12 +380,32 @@
     SimdLevel,
     SimdProvider,
 };
+pub use can::{
+    CanBus,
+    CanFilter,
+    CanFrame,
+    StubCanBus,
+};
+#[cfg(all(
+    feature = "std",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub use linux_can::SocketCan;
+#[cfg(feature = "std")]
+pub use sync::ParkingFutex;
 pub use sync::{
     FutexLike,
     SpinFutex,
     SpinFutexBuilder,
     TimeoutResult,
 }; // FutexLike is always available
+pub use tick::{
+    SoftwareTickSource,
+    TickHandler,
+    TickMode,
+    TickSource,
+};
 // Export Tock OS specific implementations if enabled
 #[cfg(feature = "platform-tock")]
 pub use tock_memory::{
@@ -406,6 +452,8 @@
     ZephyrFutexBuilder,
     ZephyrSemaphoreFutex,
 };
+#[cfg(feature = "platform-zephyr")]
+pub use zephyr_tick::ZephyrTickSource;
 
 #[cfg(test)]
 #[allow(clippy::panic)] // Allow panics in the test module
@@ -415,6 +463,11 @@ mod tests {
         memory::MemoryProvider,
         prelude::*,
     };
+    #[cfg(all(feature = "platform-linux", target_os = "linux"))]
+    use super::{
+        LinuxAllocatorBuilder,
+        LinuxFutexBuilder,
+    };
 
     #[test]
     fn it_works() {
@@ -0,0 +1,311 @@
+#![allow(unsafe_code)]
+// Allow unsafe syscalls for socket setup
+// WRT - wrt-platform
+// Module: Linux SocketCAN Driver
+// SW-REQ-ID: REQ_PLATFORM_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! `CanBus` implementation over a Linux SocketCAN raw socket.
+//!
+//! The socket is created and bound with direct syscalls, without libc; frame
+//! I/O then goes through `std::fs::File` on the socket descriptor. The socket
+//! is non-blocking, so `receive` returns `None` instead of waiting.
+
+use std::{
+    fs::File,
+    io::{
+        ErrorKind,
+        Read,
+        Write,
+    },
+    os::fd::{
+        FromRawFd,
+        OwnedFd,
+    },
+    string::{
+        String,
+        ToString,
+    },
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::can::{
+    CanBus,
+    CanFrame,
+    CAN_EFF_MASK,
+    CAN_MAX_DLEN,
+    CAN_SFF_MASK,
+};
+
+/// Linux syscall numbers for socket setup
+#[cfg(target_arch = "x86_64")]
+mod syscalls {
+    pub const IOCTL: usize = 16;
+    pub const SOCKET: usize = 41;
+    pub const BIND: usize = 49;
+}
+
+#[cfg(target_arch = "aarch64")]
+mod syscalls {
+    pub const IOCTL: usize = 29;
+    pub const SOCKET: usize = 198;
+    pub const BIND: usize = 200;
+}
+
+/// Socket constants from the Linux UAPI headers
+const PF_CAN: usize = 29;
+const SOCK_RAW: usize = 3;
+const SOCK_NONBLOCK: usize = 0o4000;
+const SOCK_CLOEXEC: usize = 0o2000000;
+const CAN_RAW: usize = 1;
+const SIOCGIFINDEX: usize = 0x8933;
+
+/// Interface name capacity of `struct ifreq`, including the terminator
+const IFNAMSIZ: usize = 16;
+/// Size of `struct ifreq`
+const IFREQ_SIZE: usize = 40;
+/// Size of `struct sockaddr_can`
+const SOCKADDR_CAN_SIZE: usize = 24;
+/// Size of `struct can_frame` (`CAN_MTU`)
+const CAN_MTU: usize = 16;
+
+/// Flag bits of `can_frame.can_id`
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+
+/// Direct three-argument syscall
+unsafe fn syscall3(number: usize, arg0: usize, arg1: usize, arg2: usize) -> isize {
+    let result: isize;
+
+    // SAFETY: the caller guarantees the arguments are valid for `number`.
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        core::arch::asm!(
+            "syscall",
+            inlateout("rax") number as isize => result,
+            in("rdi") arg0,
+            in("rsi") arg1,
+            in("rdx") arg2,
+            lateout("rcx") _,
+            lateout("r11") _,
+            options(nostack),
+        );
+    }
+
+    // SAFETY: as above.
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!(
+            "svc #0",
+            in("x8") number,
+            inlateout("x0") arg0 as isize => result,
+            in("x1") arg1,
+            in("x2") arg2,
+            options(nostack),
+        );
+    }
+
+    result
+}
+
+/// A raw CAN socket bound to one interface, such as `can0` or `vcan0`
+#[derive(Debug)]
+pub struct SocketCan {
+    /// Socket descriptor
+    socket:    File,
+    /// Interface the socket is bound to
+    interface: String,
+}
+
+impl SocketCan {
+    /// Opens a raw CAN socket on `interface`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid interface name, the
+    /// kernel lacks CAN support, or the interface does not exist.
+    pub fn open(interface: &str) -> Result<Self> {
+        if interface.is_empty() || interface.len() >= IFNAMSIZ || interface.contains('\0') {
+            return Err(Error::new(
+                ErrorCategory::Validation,
+                codes::VALIDATION_ERROR,
+                "Invalid CAN interface name",
+            ));
+        }
+
+        // SAFETY: socket() takes no pointers.
+        let fd = unsafe {
+            syscall3(
+                syscalls::SOCKET,
+                PF_CAN,
+                SOCK_RAW | SOCK_NONBLOCK | SOCK_CLOEXEC,
+                CAN_RAW,
+            )
+        };
+        if fd < 0 {
+            return Err(system_error("SocketCAN socket() failed"));
+        }
+        // SAFETY: fd is a freshly created descriptor nobody else owns; the
+        // OwnedFd closes it on every path below.
+        let socket = File::from(unsafe { OwnedFd::from_raw_fd(fd as i32) });
+
+        let mut ifreq = [0u8; IFREQ_SIZE];
+        ifreq[..interface.len()].copy_from_slice(interface.as_bytes());
+        // SAFETY: ifreq is a writable buffer of the size the kernel expects.
+        let result = unsafe {
+            syscall3(
+                syscalls::IOCTL,
+                fd as usize,
+                SIOCGIFINDEX,
+                ifreq.as_mut_ptr() as usize,
+            )
+        };
+        if result < 0 {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_NOT_FOUND,
+                "CAN interface not found",
+            ));
+        }
+        let ifindex = i32::from_ne_bytes([ifreq[16], ifreq[17], ifreq[18], ifreq[19]]);
+
+        let mut address = [0u8; SOCKADDR_CAN_SIZE];
+        address[..2].copy_from_slice(&(PF_CAN as u16).to_ne_bytes());
+        address[4..8].copy_from_slice(&ifindex.to_ne_bytes());
+        // SAFETY: address is a readable sockaddr_can of the given length.
+        let result = unsafe {
+            syscall3(
+                syscalls::BIND,
+                fd as usize,
+                address.as_ptr() as usize,
+                SOCKADDR_CAN_SIZE,
+            )
+        };
+        if result < 0 {
+            return Err(system_error("SocketCAN bind() failed"));
+        }
+
+        Ok(Self {
+            socket,
+            interface: interface.to_string(),
+        })
+    }
+
+    /// Interface the socket is bound to
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+}
+
+impl CanBus for SocketCan {
+    fn transmit(&mut self, frame: &CanFrame) -> Result<()> {
+        match self.socket.write(&encode(frame)) {
+            Ok(CAN_MTU) => Ok(()),
+            Ok(_) => Err(system_error("Short write to SocketCAN socket")),
+            Err(error) if error.kind() == ErrorKind::WouldBlock => Err(Error::new(
+                ErrorCategory::Resource,
+                codes::RESOURCE_EXHAUSTED,
+                "CAN transmit queue full",
+            )),
+            Err(_) => Err(system_error("SocketCAN write failed")),
+        }
+    }
+
+    fn receive(&mut self) -> Result<Option<CanFrame>> {
+        let mut raw = [0u8; CAN_MTU];
+        loop {
+            match self.socket.read(&mut raw) {
+                Ok(CAN_MTU) => {
+                    if let Some(frame) = decode(&raw)? {
+                        return Ok(Some(frame));
+                    }
+                },
+                Ok(_) => return Err(system_error("Short read from SocketCAN socket")),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(error) if error.kind() == ErrorKind::Interrupted => {},
+                Err(_) => return Err(system_error("SocketCAN read failed")),
+            }
+        }
+    }
+}
+
+/// Serialises `frame` as a `struct can_frame`
+fn encode(frame: &CanFrame) -> [u8; CAN_MTU] {
+    let mut can_id = frame.id();
+    if frame.is_extended() {
+        can_id |= CAN_EFF_FLAG;
+    }
+    if frame.is_remote() {
+        can_id |= CAN_RTR_FLAG;
+    }
+
+    let mut raw = [0u8; CAN_MTU];
+    raw[..4].copy_from_slice(&can_id.to_ne_bytes());
+    raw[4] = frame.len();
+    raw[8..8 + frame.data().len()].copy_from_slice(frame.data());
+    raw
+}
+
+/// Parses a `struct can_frame`, skipping error frames
+fn decode(raw: &[u8; CAN_MTU]) -> Result<Option<CanFrame>> {
+    let can_id = u32::from_ne_bytes([raw[0], raw[1], raw[2], raw[3]]);
+    if can_id & CAN_ERR_FLAG != 0 {
+        return Ok(None);
+    }
+    let extended = can_id & CAN_EFF_FLAG != 0;
+    let id = can_id & if extended { CAN_EFF_MASK } else { CAN_SFF_MASK };
+    let len = raw[4].min(CAN_MAX_DLEN as u8);
+
+    let frame = if can_id & CAN_RTR_FLAG != 0 {
+        CanFrame::remote(id, extended, len)?
+    } else {
+        CanFrame::new(id, extended, &raw[8..8 + usize::from(len)])?
+    };
+    Ok(Some(frame))
+}
+
+fn system_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::System, codes::SYSTEM_ERROR, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_layout_round_trips() {
+        let frame = CanFrame::new(0x1ABC_DEF0, true, &[0xDE, 0xAD]).unwrap();
+        let raw = encode(&frame);
+        assert_eq!(
+            u32::from_ne_bytes([raw[0], raw[1], raw[2], raw[3]]),
+            0x9ABC_DEF0
+        );
+        assert_eq!(raw[4], 2);
+        assert_eq!(decode(&raw).unwrap(), Some(frame));
+
+        let request = CanFrame::remote(0x7DF, false, 8).unwrap();
+        assert_eq!(decode(&encode(&request)).unwrap(), Some(request));
+
+        let mut error_frame = [0u8; CAN_MTU];
+        error_frame[..4].copy_from_slice(&CAN_ERR_FLAG.to_ne_bytes());
+        assert_eq!(decode(&error_frame).unwrap(), None);
+    }
+
+    #[test]
+    fn test_open_rejects_unknown_interfaces() {
+        assert!(SocketCan::open("").is_err());
+        assert!(SocketCan::open("interface-name-too-long").is_err());
+        // Fails with either missing kernel CAN support or a missing interface
+        assert!(SocketCan::open("wrtnocan0").is_err());
+    }
+}
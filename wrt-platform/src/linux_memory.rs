@@ -112,31 +112,37 @@ unsafe fn mmap(
     ) -> *mut u8 {
         let result: isize;
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "x86_64")]
-        core::arch::asm!(
-            "syscall",
-            inout("rax") syscalls::MMAP => result,
-            in("rdi") addr,
-            in("rsi") len,
-            in("rdx") prot,
-            in("r10") flags,
-            in("r8") fd,
-            in("r9") offset,
-            out("rcx") _,
-            out("r11") _,
-        );
+        unsafe {
+            core::arch::asm!(
+                "syscall",
+                inout("rax") syscalls::MMAP => result,
+                in("rdi") addr,
+                in("rsi") len,
+                in("rdx") prot,
+                in("r10") flags,
+                in("r8") fd,
+                in("r9") offset,
+                out("rcx") _,
+                out("r11") _,
+            );
+        }
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "aarch64")]
-        core::arch::asm!(
-            "svc #0",
-            inout("x8") syscalls::MMAP => _,
-            inout("x0") addr => result,
-            in("x1") len,
-            in("x2") prot,
-            in("x3") flags,
-            in("x4") fd,
-            in("x5") offset,
-        );
+        unsafe {
+            core::arch::asm!(
+                "svc #0",
+                inout("x8") syscalls::MMAP => _,
+                inout("x0") addr => result,
+                in("x1") len,
+                in("x2") prot,
+                in("x3") flags,
+                in("x4") fd,
+                in("x5") offset,
+            );
+        }
 
         // Linux syscalls return negative errno on error
         if result < 0 && result >= -4095 {
@@ -150,23 +156,29 @@ unsafe fn mmap(
     unsafe fn munmap(addr: *mut u8, len: usize) -> i32 {
         let result: isize;
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "x86_64")]
-        core::arch::asm!(
-            "syscall",
-            inout("rax") syscalls::MUNMAP => result,
-            in("rdi") addr,
-            in("rsi") len,
-            out("rcx") _,
-            out("r11") _,
-        );
+        unsafe {
+            core::arch::asm!(
+                "syscall",
+                inout("rax") syscalls::MUNMAP => result,
+                in("rdi") addr,
+                in("rsi") len,
+                out("rcx") _,
+                out("r11") _,
+            );
+        }
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "aarch64")]
-        core::arch::asm!(
-            "svc #0",
-            inout("x8") syscalls::MUNMAP => _,
-            inout("x0") addr => result,
-            in("x1") len,
-        );
+        unsafe {
+            core::arch::asm!(
+                "svc #0",
+                inout("x8") syscalls::MUNMAP => _,
+                inout("x0") addr => result,
+                in("x1") len,
+            );
+        }
 
         result as i32
     }
@@ -175,25 +187,31 @@ unsafe fn munmap(addr: *mut u8, len: usize) -> i32 {
     unsafe fn mprotect(addr: *mut u8, len: usize, prot: usize) -> i32 {
         let result: isize;
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "x86_64")]
-        core::arch::asm!(
-            "syscall",
-            inout("rax") syscalls::MPROTECT => result,
-            in("rdi") addr,
-            in("rsi") len,
-            in("rdx") prot,
-            out("rcx") _,
-            out("r11") _,
-        );
+        unsafe {
+            core::arch::asm!(
+                "syscall",
+                inout("rax") syscalls::MPROTECT => result,
+                in("rdi") addr,
+                in("rsi") len,
+                in("rdx") prot,
+                out("rcx") _,
+                out("r11") _,
+            );
+        }
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "aarch64")]
-        core::arch::asm!(
-            "svc #0",
-            inout("x8") syscalls::MPROTECT => _,
-            inout("x0") addr => result,
-            in("x1") len,
-            in("x2") prot,
-        );
+        unsafe {
+            core::arch::asm!(
+                "svc #0",
+                inout("x8") syscalls::MPROTECT => _,
+                inout("x0") addr => result,
+                in("x1") len,
+                in("x2") prot,
+            );
+        }
 
         result as i32
     }
@@ -201,12 +219,16 @@ unsafe fn mprotect(addr: *mut u8, len: usize, prot: usize) -> i32 {
     /// Binary std/no_std choice
     unsafe fn setup_guard_pages(&self, base_ptr: *mut u8, total_size: usize) -> Result<()> {
         if !self.use_guard_pages {
-            return Ok();
+            return Ok(());
         }
 
         // Binary std/no_std choice
-        let guard_page_addr = base_ptr.add(total_size - WASM_PAGE_SIZE);
-        let result = Self::mprotect(guard_page_addr, WASM_PAGE_SIZE, PROT_NONE);
+        // SAFETY: The caller passes the start and size of a live mapping, whose
+        // last page is the guard page.
+        let result = unsafe {
+            let guard_page_addr = base_ptr.add(total_size - WASM_PAGE_SIZE);
+            Self::mprotect(guard_page_addr, WASM_PAGE_SIZE, PROT_NONE)
+        };
 
         if result != 0 {
             return Err(Error::runtime_execution_error(
@@ -218,6 +240,13 @@ unsafe fn setup_guard_pages(&self, base_ptr: *mut u8, total_size: usize) -> Resu
     }
 }
 
+impl Default for LinuxAllocator {
+    /// Same configuration as [`LinuxAllocatorBuilder::default`]
+    fn default() -> Self {
+        Self::new(None, false)
+    }
+}
+
 /// Builder for `LinuxAllocator` to provide a fluent configuration API.
 #[derive(Debug)]
 pub struct LinuxAllocatorBuilder {
@@ -339,7 +368,7 @@ fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
         };
 
         if additional_pages == 0 {
-            return Ok();
+            return Ok(());
         }
 
         let current_bytes_from_arg = Self::pages_to_bytes(current_pages)?;
@@ -389,7 +418,7 @@ unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
 
         // SAFETY: ptr was obtained from our mmap call and is valid.
         // size is the total size we had reserved.
-        let result = Self::munmap(ptr.as_ptr(), size);
+        let result = unsafe { Self::munmap(ptr.as_ptr(), size) };
         if result != 0 {
             // munmap failed, need to restore base_ptr
             self.base_ptr = Some(base_ptr);
@@ -403,4 +432,12 @@ unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
         self.current_committed_bytes = 0;
         Ok(())
     }
+
+    fn guard_size(&self) -> usize {
+        if self.use_guard_pages {
+            WASM_PAGE_SIZE
+        } else {
+            0
+        }
+    }
 }
@@ -512,4 +512,12 @@ unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
         self.current_committed_bytes = 0;
         Ok(())
     }
+
+    fn guard_size(&self) -> usize {
+        if self.use_guard_pages {
+            WASM_PAGE_SIZE
+        } else {
+            0
+        }
+    }
 }
@@ -112,31 +112,37 @@ unsafe fn futex(
     ) -> i32 {
         let result: isize;
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "x86_64")]
-        core::arch::asm!(
-            "syscall",
-            inout("rax") syscalls::FUTEX => result,
-            in("rdi") uaddr,
-            in("rsi") futex_op,
-            in("rdx") val,
-            in("r10") timeout,
-            in("r8") uaddr2,
-            in("r9") val3,
-            out("rcx") _,
-            out("r11") _,
-        );
+        unsafe {
+            core::arch::asm!(
+                "syscall",
+                inout("rax") syscalls::FUTEX => result,
+                in("rdi") uaddr,
+                in("rsi") futex_op,
+                in("rdx") val,
+                in("r10") timeout,
+                in("r8") uaddr2,
+                in("r9") val3,
+                out("rcx") _,
+                out("r11") _,
+            );
+        }
 
+        // SAFETY: Raw syscall; the caller upholds the contract of this function.
         #[cfg(target_arch = "aarch64")]
-        core::arch::asm!(
-            "svc #0",
-            inout("x8") syscalls::FUTEX => _,
-            inout("x0") uaddr => result,
-            in("x1") futex_op,
-            in("x2") val,
-            in("x3") timeout,
-            in("x4") uaddr2,
-            in("x5") val3,
-        );
+        unsafe {
+            core::arch::asm!(
+                "svc #0",
+                inout("x8") syscalls::FUTEX => _,
+                inout("x0") uaddr => result,
+                in("x1") futex_op,
+                in("x2") val,
+                in("x3") timeout,
+                in("x4") uaddr2,
+                in("x5") val3,
+            );
+        }
 
         result as i32
     }
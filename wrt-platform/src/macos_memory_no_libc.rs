@@ -151,6 +151,13 @@ unsafe fn munmap(addr: *mut u8, len: usize) -> i32 {
     }
 }
 
+impl Default for MacOsAllocator {
+    /// Same configuration as [`MacOsAllocatorBuilder::default`]
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
 /// Builder for `MacOsAllocator` to provide a fluent configuration API.
 #[derive(Debug)]
 pub struct MacOsAllocatorBuilder {
@@ -128,8 +128,15 @@ fn allocate(
     /// by other means).
     unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()>;
 
-    // Add methods for memory protection (e.g., MTE, guard pages) later if needed.
-    // fn protect(...) -> Result<(), Error>;
+    /// Size in bytes of the inaccessible region reserved directly after the
+    /// maximum size of an allocation.
+    ///
+    /// Accesses that run past the end of the memory fault immediately when
+    /// this is non-zero. The guard region is part of the reservation, so it
+    /// must be included in the `size` passed to [`Self::deallocate`].
+    fn guard_size(&self) -> usize {
+        0
+    }
 }
 /// Memory Provider trait for memory operations.
 ///
@@ -0,0 +1,528 @@
+//! Integration with partitioned RTOS environments (ARINC 653 style)
+//!
+//! In a partitioned system, e.g. an avionics module running an ARINC 653
+//! operating system or a separation hypervisor, a WRT instance lives inside
+//! a partition. It shares no memory with other partitions, is only scheduled
+//! during its time windows and reports faults to the health monitor of the
+//! platform rather than handling them itself. This module gives the runtime
+//! a platform-independent view of those services:
+//!
+//! - [`SamplingPort`] and [`QueuingPort`] are the inter-partition channels.
+//!   Platform bindings implement them on top of the native services, e.g.
+//!   `WRITE_SAMPLING_MESSAGE` or `RECEIVE_QUEUING_MESSAGE` of APEX.
+//!   [`MemorySamplingPort`] and [`MemoryQueuingPort`] connect instances
+//!   running in the same address space, on hosts and in tests.
+//! - [`PartitionSchedule`] describes the time windows of the partition
+//!   within the major frame, so execution slices can be sized to end before
+//!   the window does.
+//! - [`HealthMonitor`] receives health events; [`report_health`] forwards
+//!   them to the monitor registered by the platform binding and returns
+//!   the recovery action it decided on.
+
+use core::time::Duration;
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_sync::WrtMutex;
+
+/// Validity of a sampling message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validity {
+    /// The message is younger than the refresh period of the port
+    Valid,
+    /// The message is older than the refresh period of the port
+    Invalid,
+}
+
+/// Message read from a sampling port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampledMessage {
+    /// Length of the message copied into the buffer
+    pub len:      usize,
+    /// Whether the message is fresh enough to be used
+    pub validity: Validity,
+    /// Time since the message was written
+    pub age:      Duration,
+}
+
+/// Port holding the latest message written by its source partition
+///
+/// Reading does not consume the message; a new message overwrites the
+/// previous one.
+pub trait SamplingPort {
+    /// Largest message the port carries, in bytes
+    fn max_message_size(&self) -> usize;
+
+    /// Age after which a message is reported as [`Validity::Invalid`]
+    fn refresh_period(&self) -> Duration;
+
+    /// Replace the message of the port
+    ///
+    /// # Errors
+    ///
+    /// Fails if the message is larger than the port allows or the port is
+    /// not a source port of this partition.
+    fn write(&self, message: &[u8]) -> Result<()>;
+
+    /// Copy the current message into `buffer`, `None` if no message was
+    /// written yet
+    ///
+    /// # Errors
+    ///
+    /// Fails if `buffer` is too small for the message or the port is not a
+    /// destination port of this partition.
+    fn read(&self, buffer: &mut [u8]) -> Result<Option<SampledMessage>>;
+}
+
+/// Port passing messages in order from its source to its destination
+/// partition
+pub trait QueuingPort {
+    /// Largest message the port carries, in bytes
+    fn max_message_size(&self) -> usize;
+
+    /// Enqueue a message, waiting up to `timeout` for space
+    ///
+    /// # Errors
+    ///
+    /// Fails if the message is too large or the queue stays full.
+    fn send(&self, message: &[u8], timeout: Duration) -> Result<()>;
+
+    /// Dequeue a message into `buffer`, waiting up to `timeout` for one to
+    /// arrive; `None` if none did
+    ///
+    /// # Errors
+    ///
+    /// Fails if `buffer` is too small for the message.
+    fn receive(&self, buffer: &mut [u8], timeout: Duration) -> Result<Option<usize>>;
+
+    /// Number of messages waiting in the queue
+    fn pending(&self) -> usize;
+}
+
+fn check_message_size(len: usize, max: usize) -> Result<()> {
+    if len > max {
+        return Err(Error::buffer_overflow("Message exceeds maximum port message size"));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Sample<const MAX: usize> {
+    data:    [u8; MAX],
+    len:     usize,
+    written: Option<Duration>,
+}
+
+/// Sampling port between instances sharing an address space
+///
+/// The same port is written by the source and read by the destination.
+/// Message ages are measured with the monotonic clock passed at creation.
+#[derive(Debug)]
+pub struct MemorySamplingPort<const MAX: usize> {
+    sample:         WrtMutex<Sample<MAX>>,
+    refresh_period: Duration,
+    clock:          fn() -> Duration,
+}
+
+impl<const MAX: usize> MemorySamplingPort<MAX> {
+    /// Create an empty port
+    pub const fn new(refresh_period: Duration, clock: fn() -> Duration) -> Self {
+        Self {
+            sample: WrtMutex::new(Sample {
+                data:    [0; MAX],
+                len:     0,
+                written: None,
+            }),
+            refresh_period,
+            clock,
+        }
+    }
+}
+
+impl<const MAX: usize> SamplingPort for MemorySamplingPort<MAX> {
+    fn max_message_size(&self) -> usize {
+        MAX
+    }
+
+    fn refresh_period(&self) -> Duration {
+        self.refresh_period
+    }
+
+    fn write(&self, message: &[u8]) -> Result<()> {
+        check_message_size(message.len(), MAX)?;
+        let mut sample = self.sample.lock();
+        sample.data[..message.len()].copy_from_slice(message);
+        sample.len = message.len();
+        sample.written = Some((self.clock)());
+        Ok(())
+    }
+
+    fn read(&self, buffer: &mut [u8]) -> Result<Option<SampledMessage>> {
+        let sample = self.sample.lock();
+        let Some(written) = sample.written else {
+            return Ok(None);
+        };
+        check_message_size(sample.len, buffer.len())?;
+        buffer[..sample.len].copy_from_slice(&sample.data[..sample.len]);
+        let age = (self.clock)().saturating_sub(written);
+        let validity = if age > self.refresh_period { Validity::Invalid } else { Validity::Valid };
+        Ok(Some(SampledMessage {
+            len: sample.len,
+            validity,
+            age,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct Queue<const MAX: usize, const DEPTH: usize> {
+    messages: [[u8; MAX]; DEPTH],
+    lengths:  [usize; DEPTH],
+    head:     usize,
+    count:    usize,
+}
+
+/// Queuing port between instances sharing an address space
+///
+/// The port never blocks: a full queue fails a send and an empty queue
+/// returns `None` regardless of the timeout.
+#[derive(Debug)]
+pub struct MemoryQueuingPort<const MAX: usize, const DEPTH: usize> {
+    queue: WrtMutex<Queue<MAX, DEPTH>>,
+}
+
+impl<const MAX: usize, const DEPTH: usize> Default for MemoryQueuingPort<MAX, DEPTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const MAX: usize, const DEPTH: usize> MemoryQueuingPort<MAX, DEPTH> {
+    /// Create an empty port
+    pub const fn new() -> Self {
+        Self {
+            queue: WrtMutex::new(Queue {
+                messages: [[0; MAX]; DEPTH],
+                lengths:  [0; DEPTH],
+                head:     0,
+                count:    0,
+            }),
+        }
+    }
+}
+
+impl<const MAX: usize, const DEPTH: usize> QueuingPort for MemoryQueuingPort<MAX, DEPTH> {
+    fn max_message_size(&self) -> usize {
+        MAX
+    }
+
+    fn send(&self, message: &[u8], _timeout: Duration) -> Result<()> {
+        check_message_size(message.len(), MAX)?;
+        let mut queue = self.queue.lock();
+        if queue.count == DEPTH {
+            return Err(Error::resource_exhausted("Queuing port is full"));
+        }
+        let slot = (queue.head + queue.count) % DEPTH;
+        queue.messages[slot][..message.len()].copy_from_slice(message);
+        queue.lengths[slot] = message.len();
+        queue.count += 1;
+        Ok(())
+    }
+
+    fn receive(&self, buffer: &mut [u8], _timeout: Duration) -> Result<Option<usize>> {
+        let mut queue = self.queue.lock();
+        if queue.count == 0 {
+            return Ok(None);
+        }
+        let slot = queue.head;
+        let len = queue.lengths[slot];
+        check_message_size(len, buffer.len())?;
+        buffer[..len].copy_from_slice(&queue.messages[slot][..len]);
+        queue.head = (slot + 1) % DEPTH;
+        queue.count -= 1;
+        Ok(Some(len))
+    }
+
+    fn pending(&self) -> usize {
+        self.queue.lock().count
+    }
+}
+
+/// Time window of a partition within the major frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// Start of the window, relative to the start of the major frame
+    pub offset:   Duration,
+    /// Length of the window
+    pub duration: Duration,
+}
+
+impl TimeWindow {
+    /// End of the window, relative to the start of the major frame
+    pub fn end(&self) -> Duration {
+        self.offset.saturating_add(self.duration)
+    }
+}
+
+/// Time windows of a partition, repeated every major frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionSchedule<'a> {
+    major_frame: Duration,
+    windows:     &'a [TimeWindow],
+}
+
+impl<'a> PartitionSchedule<'a> {
+    /// Create a schedule from windows ordered by their offset
+    ///
+    /// # Errors
+    ///
+    /// Fails if the major frame is zero, or a window is empty, overlaps the
+    /// previous one or ends after the major frame.
+    pub fn new(major_frame: Duration, windows: &'a [TimeWindow]) -> Result<Self> {
+        if major_frame.is_zero() {
+            return Err(Error::configuration_error("Major frame must not be zero"));
+        }
+        let mut previous_end = Duration::ZERO;
+        for window in windows {
+            if window.duration.is_zero()
+                || window.offset < previous_end
+                || window.end() > major_frame
+            {
+                return Err(Error::configuration_error("Invalid partition time window"));
+            }
+            previous_end = window.end();
+        }
+        Ok(Self {
+            major_frame,
+            windows,
+        })
+    }
+
+    /// Length of the major frame
+    pub fn major_frame(&self) -> Duration {
+        self.major_frame
+    }
+
+    /// Time windows of the partition
+    pub fn windows(&self) -> &'a [TimeWindow] {
+        self.windows
+    }
+
+    /// Processor time the partition gets per major frame
+    pub fn budget(&self) -> Duration {
+        self.windows.iter().map(|window| window.duration).sum()
+    }
+
+    fn frame_offset(&self, now: Duration) -> Duration {
+        let frame = self.major_frame.as_nanos();
+        Duration::from_nanos((now.as_nanos() % frame) as u64)
+    }
+
+    /// Time left in the window active at monotonic time `now`, `None`
+    /// outside the partition's windows
+    ///
+    /// Time is measured from the start of the first major frame.
+    pub fn remaining_in_window(&self, now: Duration) -> Option<Duration> {
+        let offset = self.frame_offset(now);
+        self.windows
+            .iter()
+            .find(|window| window.offset <= offset && offset < window.end())
+            .map(|window| window.end() - offset)
+    }
+
+    /// Time until the next window of the partition starts, zero inside a
+    /// window
+    pub fn until_next_window(&self, now: Duration) -> Option<Duration> {
+        if self.remaining_in_window(now).is_some() {
+            return Some(Duration::ZERO);
+        }
+        let offset = self.frame_offset(now);
+        let first = self.windows.first()?;
+        let next = self.windows.iter().find(|window| window.offset > offset);
+        Some(match next {
+            Some(window) => window.offset - offset,
+            None => self.major_frame - offset + first.offset,
+        })
+    }
+}
+
+/// Fault reported to the health monitor, following the ARINC 653 error
+/// codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthEvent {
+    /// A deadline or time window was overrun
+    DeadlineMissed,
+    /// The application detected an error, with an application-defined code
+    ApplicationError(u32),
+    /// An arithmetic trap, e.g. an integer division by zero
+    NumericError,
+    /// A request to the platform was rejected
+    IllegalRequest,
+    /// A stack or call depth limit was exceeded
+    StackOverflow,
+    /// Memory outside the partition's regions was accessed
+    MemoryViolation,
+    /// A hardware fault was detected
+    HardwareFault,
+}
+
+impl HealthEvent {
+    /// Health event reporting a runtime error
+    pub fn from_error(error: &Error) -> Self {
+        match (error.category, error.code) {
+            (_, codes::DIVISION_BY_ZERO | codes::INTEGER_OVERFLOW) => Self::NumericError,
+            (_, codes::STACK_OVERFLOW) => Self::StackOverflow,
+            (ErrorCategory::Memory, _) => Self::MemoryViolation,
+            (ErrorCategory::Safety | ErrorCategory::Security, _) => Self::IllegalRequest,
+            _ => Self::ApplicationError(u32::from(error.code)),
+        }
+    }
+}
+
+/// Recovery decided by the health monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Log the event and continue
+    Ignore,
+    /// Restart the partition, keeping its persistent state
+    WarmRestart,
+    /// Restart the partition from its initial state
+    ColdRestart,
+    /// Stop the partition
+    Stop,
+}
+
+/// Health monitor of the platform
+///
+/// Platform bindings forward events to the native health monitor, e.g.
+/// `RAISE_APPLICATION_ERROR` of APEX, and translate its configured
+/// response into a [`RecoveryAction`].
+pub trait HealthMonitor: Sync {
+    /// Report `event` with a diagnostic `message`
+    fn report(&self, event: HealthEvent, message: &str) -> RecoveryAction;
+}
+
+static HEALTH_MONITOR: WrtMutex<Option<&'static dyn HealthMonitor>> = WrtMutex::new(None);
+
+/// Register the health monitor of the platform
+pub fn register_health_monitor(monitor: &'static dyn HealthMonitor) {
+    *HEALTH_MONITOR.lock() = Some(monitor);
+}
+
+/// Report a health event to the registered monitor
+///
+/// Without a registered monitor the event is ignored, as on a host without
+/// partitioning.
+pub fn report_health(event: HealthEvent, message: &str) -> RecoveryAction {
+    let monitor = *HEALTH_MONITOR.lock();
+    monitor.map_or(RecoveryAction::Ignore, |monitor| monitor.report(event, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{
+        AtomicU64,
+        Ordering,
+    };
+
+    use super::*;
+
+    static NOW_MS: AtomicU64 = AtomicU64::new(0);
+
+    fn clock() -> Duration {
+        Duration::from_millis(NOW_MS.load(Ordering::SeqCst))
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_sampling_port_validity() {
+        let port = MemorySamplingPort::<8>::new(ms(10), clock);
+        let mut buffer = [0; 8];
+        assert_eq!(port.read(&mut buffer).unwrap(), None);
+        assert!(port.write(&[0; 9]).is_err());
+
+        NOW_MS.store(100, Ordering::SeqCst);
+        port.write(b"alt").unwrap();
+        NOW_MS.store(105, Ordering::SeqCst);
+        let sample = port.read(&mut buffer).unwrap().unwrap();
+        assert_eq!((sample.len, sample.validity, sample.age), (3, Validity::Valid, ms(5)));
+        assert_eq!(&buffer[..3], b"alt");
+
+        NOW_MS.store(111, Ordering::SeqCst);
+        assert_eq!(port.read(&mut buffer).unwrap().unwrap().validity, Validity::Invalid);
+        assert!(port.read(&mut [0; 2]).is_err());
+    }
+
+    #[test]
+    fn test_queuing_port_order() {
+        let port = MemoryQueuingPort::<4, 2>::new();
+        port.send(b"a", Duration::ZERO).unwrap();
+        port.send(b"bc", Duration::ZERO).unwrap();
+        assert!(port.send(b"d", Duration::ZERO).is_err());
+        assert_eq!(port.pending(), 2);
+
+        let mut buffer = [0; 4];
+        assert_eq!(port.receive(&mut buffer, Duration::ZERO).unwrap(), Some(1));
+        assert_eq!(buffer[0], b'a');
+        port.send(b"efg", Duration::ZERO).unwrap();
+        assert_eq!(port.receive(&mut buffer, Duration::ZERO).unwrap(), Some(2));
+        assert_eq!(port.receive(&mut buffer, Duration::ZERO).unwrap(), Some(3));
+        assert_eq!(&buffer[..3], b"efg");
+        assert_eq!(port.receive(&mut buffer, Duration::ZERO).unwrap(), None);
+    }
+
+    #[test]
+    fn test_partition_schedule_windows() {
+        let windows = [
+            TimeWindow {
+                offset:   ms(0),
+                duration: ms(10),
+            },
+            TimeWindow {
+                offset:   ms(50),
+                duration: ms(20),
+            },
+        ];
+        let schedule = PartitionSchedule::new(ms(100), &windows).unwrap();
+        assert_eq!(schedule.budget(), ms(30));
+        assert_eq!(schedule.remaining_in_window(ms(204)), Some(ms(6)));
+        assert_eq!(schedule.remaining_in_window(ms(30)), None);
+        assert_eq!(schedule.until_next_window(ms(30)), Some(ms(20)));
+        assert_eq!(schedule.until_next_window(ms(80)), Some(ms(20)));
+        assert_eq!(schedule.until_next_window(ms(55)), Some(Duration::ZERO));
+
+        let overlapping = [windows[1], windows[0]];
+        assert!(PartitionSchedule::new(ms(100), &overlapping).is_err());
+        assert!(PartitionSchedule::new(ms(60), &windows).is_err());
+    }
+
+    #[test]
+    fn test_health_reports_reach_monitor() {
+        struct Monitor;
+        impl HealthMonitor for Monitor {
+            fn report(&self, event: HealthEvent, _message: &str) -> RecoveryAction {
+                match event {
+                    HealthEvent::MemoryViolation => RecoveryAction::ColdRestart,
+                    _ => RecoveryAction::Ignore,
+                }
+            }
+        }
+        static MONITOR: Monitor = Monitor;
+
+        assert_eq!(
+            report_health(HealthEvent::DeadlineMissed, "window overrun"),
+            RecoveryAction::Ignore
+        );
+        register_health_monitor(&MONITOR);
+        let event = HealthEvent::from_error(&Error::memory_out_of_bounds("out of bounds"));
+        assert_eq!(event, HealthEvent::MemoryViolation);
+        assert_eq!(report_health(event, "guest fault"), RecoveryAction::ColdRestart);
+    }
+}
@@ -415,7 +415,5 @@ fn test_posix_performance_validation() {
 
         // Check that we got some results
         assert!(count > 0);
-
-        println!("Validated {} performance tests successfully", count);
     }
 }
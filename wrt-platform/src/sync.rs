@@ -238,6 +238,81 @@ fn wake(&self, _count: u32) -> Result<()> {
     }
 }
 
+/// A portable blocking implementation of the `FutexLike` trait.
+///
+/// Parks the calling thread on a `std` condition variable instead of
+/// spinning, so it is the preferred fallback on hosted targets that have no
+/// native futex implementation enabled. The value is guarded by the same
+/// mutex the waiters sleep on, so a `set` followed by `wake` can never be
+/// lost between a waiter's value check and its sleep.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct ParkingFutex {
+    /// The futex word
+    value:   Mutex<u32>,
+    /// Waiters parked on the futex word
+    waiters: Condvar,
+}
+
+#[cfg(feature = "std")]
+impl ParkingFutex {
+    /// Creates a new `ParkingFutex` with the given initial value.
+    pub fn new(initial_value: u32) -> Self {
+        Self {
+            value:   Mutex::new(initial_value),
+            waiters: Condvar::new(),
+        }
+    }
+
+    /// Gets the current value.
+    pub fn get(&self) -> u32 {
+        *self.value.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Sets a new value.
+    pub fn set(&self, new_value: u32) {
+        *self.value.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = new_value;
+    }
+}
+
+#[cfg(feature = "std")]
+impl FutexLike for ParkingFutex {
+    fn wait(&self, expected: u32, timeout: Option<Duration>) -> Result<()> {
+        let guard = self.value.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        match timeout {
+            None => {
+                let _guard = self
+                    .waiters
+                    .wait_while(guard, |value| *value == expected)
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                Ok(())
+            },
+            Some(duration) => {
+                let (_guard, result) = self
+                    .waiters
+                    .wait_timeout_while(guard, duration, |value| *value == expected)
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                if result.timed_out() {
+                    Err(wrt_error::Error::system_error("Operation timed out"))
+                } else {
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn wake(&self, count: u32) -> Result<()> {
+        // Waiters re-check the value under the lock, so waking more threads
+        // than requested is harmless; waking fewer would not be.
+        if count == 1 {
+            self.waiters.notify_one();
+        } else if count > 1 {
+            self.waiters.notify_all();
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::panic)]
 #[allow(clippy::expect_used)]
@@ -282,4 +357,25 @@ fn test_spin_futex_builder() {
 
         assert_eq!(futex.get(), 42);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parking_futex_wakes_blocked_thread() {
+        let futex = Arc::new(ParkingFutex::new(0));
+
+        // Value matches, nobody changes it: the wait must time out
+        let result = futex.wait(0, Some(Duration::from_millis(1)));
+        assert_eq!(result.unwrap_err().category(), ErrorCategory::System);
+
+        let waiter = {
+            let futex = Arc::clone(&futex);
+            std::thread::spawn(move || futex.wait(0, Some(Duration::from_secs(10))))
+        };
+        std::thread::sleep(Duration::from_millis(10));
+        futex.set(1);
+        futex.wake(1).expect("Wake should succeed");
+
+        assert!(waiter.join().expect("waiter panicked").is_ok());
+        assert_eq!(futex.get(), 1);
+    }
 }
@@ -0,0 +1,235 @@
+//! Tick sources driving epochs and watchdogs
+//!
+//! Epoch deadlines and software watchdogs only need to be told that time
+//! has passed. On hosts a timer thread does that; on tickless RTOS
+//! configurations there is no periodic kernel tick to hook into, so the
+//! integrator binds a hardware timer instead. A [`TickSource`] abstracts
+//! over both: it is started in [`TickMode::Periodic`] or
+//! [`TickMode::OneShot`] mode and calls its [`TickHandler`] whenever a tick
+//! is due.
+//!
+//! [`SoftwareTickSource`] is the fallback for targets without a timer
+//! binding. It delivers no ticks on its own; the scheduler loop calls
+//! [`SoftwareTickSource::poll`] with the current monotonic time, and the
+//! due ticks are delivered from there.
+
+use core::{
+    sync::atomic::{
+        AtomicU32,
+        AtomicU64,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+/// Receiver of ticks
+///
+/// Hardware tick sources call [`TickHandler::on_tick`] from interrupt
+/// context, so implementations must not block or allocate.
+pub trait TickHandler: Sync {
+    /// A tick is due
+    fn on_tick(&self);
+}
+
+impl TickHandler for AtomicU64 {
+    fn on_tick(&self) {
+        self.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl TickHandler for AtomicU32 {
+    fn on_tick(&self) {
+        self.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl<T: TickHandler + ?Sized> TickHandler for &T {
+    fn on_tick(&self) {
+        (**self).on_tick();
+    }
+}
+
+/// When a tick source delivers ticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickMode {
+    /// Every period, until stopped
+    Periodic(Duration),
+    /// Once after the delay, then the source stops
+    OneShot(Duration),
+}
+
+impl TickMode {
+    /// Period or delay of the mode
+    pub const fn duration(self) -> Duration {
+        match self {
+            Self::Periodic(duration) | Self::OneShot(duration) => duration,
+        }
+    }
+}
+
+/// Source of ticks for epochs and watchdogs
+pub trait TickSource {
+    /// Start delivering ticks in `mode`, restarting a running source
+    ///
+    /// # Errors
+    ///
+    /// Fails if `mode` has a zero duration or the timer cannot be started.
+    fn start(&mut self, mode: TickMode) -> Result<()>;
+
+    /// Stop delivering ticks
+    fn stop(&mut self);
+
+    /// Whether ticks are being delivered
+    fn is_running(&self) -> bool;
+
+    /// Whether ticks come from a hardware timer rather than the scheduler
+    /// loop
+    fn is_hardware(&self) -> bool {
+        false
+    }
+}
+
+/// Default number of overdue periodic ticks delivered by one poll
+pub const DEFAULT_MAX_CATCH_UP: u32 = 16;
+
+/// Tick source driven from the scheduler loop
+///
+/// Ticks are only delivered while [`SoftwareTickSource::poll`] is called,
+/// so their accuracy is bounded by how often the loop runs. A periodic
+/// source that falls more than its catch-up limit behind delivers that many
+/// ticks and resynchronizes to the current time rather than bursting.
+#[derive(Debug)]
+pub struct SoftwareTickSource<H> {
+    /// Receiver of the ticks
+    handler:      H,
+    /// Mode of the running source
+    mode:         Option<TickMode>,
+    /// Time the next tick is due; set by the first poll after starting
+    next_due:     Option<Duration>,
+    /// Maximum number of ticks delivered by one poll
+    max_catch_up: u32,
+}
+
+impl<H: TickHandler> SoftwareTickSource<H> {
+    /// Create a stopped source delivering ticks to `handler`
+    pub const fn new(handler: H) -> Self {
+        Self {
+            handler,
+            mode: None,
+            next_due: None,
+            max_catch_up: DEFAULT_MAX_CATCH_UP,
+        }
+    }
+
+    /// Deliver at most `max` overdue periodic ticks per poll
+    pub const fn with_max_catch_up(mut self, max: u32) -> Self {
+        self.max_catch_up = if max == 0 { 1 } else { max };
+        self
+    }
+
+    /// Receiver of the ticks
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Deliver the ticks due at monotonic time `now`, returning how many
+    /// were delivered
+    ///
+    /// The first poll after [`TickSource::start`] only schedules the first
+    /// tick one period after `now`.
+    pub fn poll(&mut self, now: Duration) -> u32 {
+        let Some(mode) = self.mode else {
+            return 0;
+        };
+        let period = mode.duration();
+        let Some(mut due) = self.next_due else {
+            self.next_due = Some(now.saturating_add(period));
+            return 0;
+        };
+
+        let mut delivered = 0;
+        while due <= now && delivered < self.max_catch_up {
+            self.handler.on_tick();
+            delivered += 1;
+            if let TickMode::OneShot(_) = mode {
+                self.stop();
+                return delivered;
+            }
+            due = due.saturating_add(period);
+        }
+        if due <= now {
+            due = now.saturating_add(period);
+        }
+        self.next_due = Some(due);
+        delivered
+    }
+}
+
+impl<H: TickHandler> TickSource for SoftwareTickSource<H> {
+    fn start(&mut self, mode: TickMode) -> Result<()> {
+        if mode.duration().is_zero() {
+            return Err(Error::configuration_error("Tick period must not be zero"));
+        }
+        self.mode = Some(mode);
+        self.next_due = None;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.mode = None;
+        self.next_due = None;
+    }
+
+    fn is_running(&self) -> bool {
+        self.mode.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_periodic_ticks_follow_poll_time() {
+        let mut source = SoftwareTickSource::new(AtomicU64::new(0)).with_max_catch_up(3);
+        assert_eq!(source.poll(ms(0)), 0);
+
+        source.start(TickMode::Periodic(ms(10))).unwrap();
+        assert_eq!(source.poll(ms(5)), 0);
+        assert_eq!(source.poll(ms(14)), 0);
+        assert_eq!(source.poll(ms(15)), 1);
+        assert_eq!(source.poll(ms(36)), 2);
+
+        // Far behind: catch up by at most three ticks, then resynchronize
+        assert_eq!(source.poll(ms(200)), 3);
+        assert_eq!(source.poll(ms(205)), 0);
+        assert_eq!(source.poll(ms(210)), 1);
+        assert_eq!(source.handler().load(Ordering::Acquire), 7);
+
+        source.stop();
+        assert_eq!(source.poll(ms(1000)), 0);
+    }
+
+    #[test]
+    fn test_one_shot_stops_after_first_tick() {
+        let ticks = AtomicU32::new(0);
+        let mut source = SoftwareTickSource::new(&ticks);
+        assert!(source.start(TickMode::OneShot(Duration::ZERO)).is_err());
+
+        source.start(TickMode::OneShot(ms(20))).unwrap();
+        source.poll(ms(0));
+        assert_eq!(source.poll(ms(100)), 1);
+        assert!(!source.is_running());
+        assert_eq!(source.poll(ms(200)), 0);
+        assert_eq!(ticks.load(Ordering::Acquire), 1);
+    }
+}
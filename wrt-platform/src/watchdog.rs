@@ -40,6 +40,8 @@
     WrtRwLock,
 };
 
+use crate::tick::TickHandler;
+
 /// Watchdog configuration
 #[derive(Debug, Clone)]
 pub struct WatchdogConfig {
@@ -141,49 +143,7 @@ pub fn start(&self) -> Result<()> {
 
         let thread = std::thread::spawn(move || {
             while running.load(Ordering::Acquire) {
-                // Check all tasks
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64;
-                let tasks_snapshot = tasks.read().clone();
-
-                for task in tasks_snapshot.values() {
-                    if !task.active.load(Ordering::Acquire) {
-                        continue;
-                    }
-
-                    let last_heartbeat = *task.last_heartbeat.lock();
-                    let elapsed_ms = now.saturating_sub(last_heartbeat);
-                    let elapsed = Duration::from_millis(elapsed_ms);
-
-                    if elapsed > task.timeout {
-                        // Timeout detected
-                        eprintln!(
-                            "Watchdog: Task '{name}' (ID: {id:?}) timed out after {elapsed:?}",
-                            name = task.name,
-                            id = task.id,
-                            elapsed = elapsed
-                        );
-
-                        // Execute action
-                        match &task.action {
-                            WatchdogAction::Log => {
-                                // Already logged above
-                            },
-                            WatchdogAction::Kill => {
-                                if auto_kill {
-                                    // Platform-specific kill logic would go here
-                                    eprintln!("Watchdog: Would kill task {name}", name = task.name);
-                                }
-                            },
-                        }
-
-                        // Mark as inactive after timeout
-                        task.active.store(false, Ordering::Release);
-                    }
-                }
-
+                check_tasks(&tasks, auto_kill);
                 std::thread::sleep(check_interval);
             }
         });
@@ -192,6 +152,15 @@ pub fn start(&self) -> Result<()> {
         Ok(())
     }
 
+    /// Check all watched tasks once
+    ///
+    /// Used instead of [`SoftwareWatchdog::start`] when a
+    /// [`TickSource`](crate::tick::TickSource) drives the watchdog, e.g. on
+    /// targets without threads.
+    pub fn check_now(&self) {
+        check_tasks(&self.tasks, self.config.auto_kill);
+    }
+
     /// Stop the watchdog
     pub fn stop(&self) -> Result<()> {
         self.running.store(false, Ordering::Release);
@@ -212,10 +181,7 @@ pub fn watch_task(
     ) -> Result<WatchdogHandle<'_>> {
         let task_id = WatchedTaskId(self.next_task_id.fetch_add(1, Ordering::AcqRel));
         // Get current timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = now_ms();
 
         let task = Arc::new(WatchedTask {
             id: task_id,
@@ -254,10 +220,7 @@ pub fn heartbeat(&self, task_id: WatchedTaskId) -> Result<()> {
 
         if task.active.load(Ordering::Acquire) {
             // Update heartbeat timestamp
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64;
+            let now = now_ms();
             *task.last_heartbeat.lock() = now;
             Ok(())
         } else {
@@ -276,6 +239,62 @@ pub fn cancel_task(&self, task_id: WatchedTaskId) -> Result<()> {
     }
 }
 
+/// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Apply the timeout action of every active task that missed its heartbeat
+fn check_tasks(tasks: &WrtRwLock<BTreeMap<WatchedTaskId, Arc<WatchedTask>>>, auto_kill: bool) {
+    let now = now_ms();
+    let tasks_snapshot = tasks.read().clone();
+
+    for task in tasks_snapshot.values() {
+        if !task.active.load(Ordering::Acquire) {
+            continue;
+        }
+
+        let last_heartbeat = *task.last_heartbeat.lock();
+        let elapsed_ms = now.saturating_sub(last_heartbeat);
+        let elapsed = Duration::from_millis(elapsed_ms);
+
+        if elapsed > task.timeout {
+            // Timeout detected
+            eprintln!(
+                "Watchdog: Task '{name}' (ID: {id:?}) timed out after {elapsed:?}",
+                name = task.name,
+                id = task.id,
+                elapsed = elapsed
+            );
+
+            // Execute action
+            match &task.action {
+                WatchdogAction::Log => {
+                    // Already logged above
+                },
+                WatchdogAction::Kill => {
+                    if auto_kill {
+                        // Platform-specific kill logic would go here
+                        eprintln!("Watchdog: Would kill task {name}", name = task.name);
+                    }
+                },
+            }
+
+            // Mark as inactive after timeout
+            task.active.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl TickHandler for SoftwareWatchdog {
+    fn on_tick(&self) {
+        self.check_now();
+    }
+}
+
 /// RAII handle for watched tasks
 pub struct WatchdogHandle<'a> {
     task:     Option<Arc<WatchedTask>>,
@@ -437,4 +456,28 @@ fn test_watchdog_integration() {
 
         watchdog.stop().unwrap();
     }
+
+    #[test]
+    fn test_watchdog_driven_by_tick_source() {
+        use crate::tick::{
+            SoftwareTickSource,
+            TickMode,
+            TickSource,
+        };
+
+        let watchdog = SoftwareWatchdog::new(WatchdogConfig::default());
+        let handle = watchdog
+            .watch_task("ticked_task", Some(Duration::from_millis(20)), WatchdogAction::Log)
+            .unwrap();
+        let mut ticks = SoftwareTickSource::new(&watchdog);
+        ticks.start(TickMode::Periodic(Duration::from_millis(1))).unwrap();
+        ticks.poll(Duration::ZERO);
+
+        ticks.poll(Duration::from_millis(1));
+        assert!(handle.heartbeat().is_ok());
+
+        std::thread::sleep(Duration::from_millis(50));
+        ticks.poll(Duration::from_millis(2));
+        assert!(handle.heartbeat().is_err());
+    }
 }
@@ -0,0 +1,166 @@
+#![allow(unsafe_code)]
+// Allow unsafe FFI calls to Zephyr kernel
+// WRT - wrt-platform
+// Module: Zephyr Tick Source
+// SW-REQ-ID: REQ_PLATFORM_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Zephyr `k_timer` binding of [`TickSource`] for tickless configurations.
+//!
+//! The timer's expiry function runs in interrupt context and calls the
+//! [`TickHandler`] directly, so epochs and watchdogs advance without a
+//! kernel tick or a scheduler loop.
+
+use core::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    time::Duration,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::tick::{
+    TickHandler,
+    TickMode,
+    TickSource,
+};
+
+/// Storage for a `struct k_timer`
+///
+/// Sized for the largest `k_timer` layout of supported Zephyr
+/// configurations; the kernel only accesses it through the timer API.
+#[repr(C, align(8))]
+struct KTimer {
+    _storage: [u8; 128],
+}
+
+/// Zephyr `k_timeout_t`
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct KTimeout {
+    ticks: i64,
+}
+
+impl KTimeout {
+    /// Timeout that never expires, used as the period of one-shot timers
+    const NO_WAIT: Self = Self { ticks: 0 };
+
+    fn from_duration(duration: Duration) -> Self {
+        let us = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        // SAFETY: Pure conversion function of the Zephyr kernel
+        let ticks = unsafe { k_us_to_ticks_ceil64(us) };
+        Self {
+            ticks: i64::try_from(ticks).unwrap_or(i64::MAX).max(1),
+        }
+    }
+}
+
+type KTimerExpiry = unsafe extern "C" fn(timer: *mut KTimer);
+
+// FFI declarations for Zephyr kernel timer APIs
+unsafe extern "C" {
+    fn k_timer_init(timer: *mut KTimer, expiry: Option<KTimerExpiry>, stop: Option<KTimerExpiry>);
+    fn k_timer_start(timer: *mut KTimer, duration: KTimeout, period: KTimeout);
+    fn k_timer_stop(timer: *mut KTimer);
+    fn k_timer_user_data_set(timer: *mut KTimer, user_data: *mut c_void);
+    fn k_timer_user_data_get(timer: *const KTimer) -> *mut c_void;
+    fn k_us_to_ticks_ceil64(us: u64) -> u64;
+}
+
+/// Tick source backed by a Zephyr kernel timer
+///
+/// The timer refers back to the source while it runs, so the source must
+/// not move between [`TickSource::start`] and [`TickSource::stop`]; keep it
+/// in a `static` or another fixed location. Dropping the source stops the
+/// timer.
+pub struct ZephyrTickSource<H: TickHandler> {
+    /// Kernel timer object
+    timer:   UnsafeCell<KTimer>,
+    /// Receiver of the ticks, called from the expiry function
+    handler: H,
+    /// Whether the timer has been started and not stopped
+    running: bool,
+}
+
+// SAFETY: The kernel timer is only mutated through the Zephyr timer API,
+// which is safe to call from any thread; the handler is `Sync`.
+unsafe impl<H: TickHandler> Sync for ZephyrTickSource<H> {}
+// SAFETY: See `Sync`; a stopped timer holds no reference to the source.
+unsafe impl<H: TickHandler + Send> Send for ZephyrTickSource<H> {}
+
+impl<H: TickHandler> ZephyrTickSource<H> {
+    /// Create a stopped source delivering ticks to `handler`
+    pub const fn new(handler: H) -> Self {
+        Self {
+            timer: UnsafeCell::new(KTimer { _storage: [0; 128] }),
+            handler,
+            running: false,
+        }
+    }
+
+    /// Receiver of the ticks
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    unsafe extern "C" fn expiry(timer: *mut KTimer) {
+        // SAFETY: The user data was set to the source in `start` and the
+        // source stays in place while the timer runs.
+        let source = unsafe { &*(k_timer_user_data_get(timer) as *const Self) };
+        source.handler.on_tick();
+    }
+}
+
+impl<H: TickHandler> TickSource for ZephyrTickSource<H> {
+    fn start(&mut self, mode: TickMode) -> Result<()> {
+        if mode.duration().is_zero() {
+            return Err(Error::configuration_error("Tick period must not be zero"));
+        }
+        let duration = KTimeout::from_duration(mode.duration());
+        let period = match mode {
+            TickMode::Periodic(_) => duration,
+            TickMode::OneShot(_) => KTimeout::NO_WAIT,
+        };
+        let timer = self.timer.get();
+        // SAFETY: `timer` points to storage owned by the source, which stays
+        // in place while the timer runs.
+        unsafe {
+            if self.running {
+                k_timer_stop(timer);
+            }
+            k_timer_init(timer, Some(Self::expiry), None);
+            k_timer_user_data_set(timer, self as *mut Self as *mut c_void);
+            k_timer_start(timer, duration, period);
+        }
+        self.running = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if self.running {
+            // SAFETY: The timer was initialized by `start`
+            unsafe { k_timer_stop(self.timer.get()) };
+            self.running = false;
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn is_hardware(&self) -> bool {
+        true
+    }
+}
+
+impl<H: TickHandler> Drop for ZephyrTickSource<H> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,162 @@
+// WRT - wrt-prelude
+// Module: Shared Tiered Preludes
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Tiered preludes shared by the WRT crates.
+//!
+//! Each crate's `prelude` module starts from one tier instead of assembling
+//! its own mix of `core`, `std`, `wrt-error` and `wrt-foundation` imports:
+//!
+//! | Tier                 | Feature   | Adds                                          |
+//! |----------------------|-----------|-----------------------------------------------|
+//! | [`mod@core`]         | -         | `core`/`std` basics, sync primitives, errors   |
+//! | [`runtime`]          | `runtime` | values, types and bounded collections          |
+//! | [`host`]             | `host`    | builtins, resources and component values       |
+//!
+//! # Re-export policy
+//!
+//! - Tiers re-export every item by name. The only glob in a tier is the one
+//!   pulling in the tier below it.
+//! - A crate prelude may glob-import exactly one tier
+//!   (`pub use wrt_prelude::runtime::*;`) and must name everything else it
+//!   re-exports.
+//! - A crate prelude must not re-export the same name as the tier it uses
+//!   unless the item deliberately replaces the tier's (for example a `no_std`
+//!   `Vec` alias); write such items next to a comment saying so.
+//!
+//! `cargo-wrt validate --check-reexports` enforces the glob rules for every
+//! crate depending on `wrt-prelude` and fails for a crate prelude that does
+//! not build on a tier. The workspace denies `hidden_glob_reexports`, so a
+//! private item can no longer shadow a prelude's glob re-export.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// Items every WRT crate uses: `core` basics, `std` collections when
+/// available, synchronization primitives and error types
+pub mod core {
+    pub use ::core::{
+        any::Any,
+        cmp::{
+            Eq,
+            Ord,
+            PartialEq,
+            PartialOrd,
+        },
+        convert::{
+            TryFrom,
+            TryInto,
+        },
+        fmt,
+        fmt::{
+            Debug,
+            Display,
+        },
+        marker::PhantomData,
+        mem,
+        ops::{
+            Deref,
+            DerefMut,
+        },
+        slice,
+        str,
+    };
+    #[cfg(feature = "std")]
+    pub use std::{
+        boxed::Box,
+        collections::{
+            HashMap,
+            HashSet,
+        },
+        format,
+        string::{
+            String,
+            ToString,
+        },
+        sync::{
+            Arc,
+            Mutex,
+            RwLock,
+        },
+        vec,
+        vec::Vec,
+    };
+
+    pub use wrt_error::{
+        codes,
+        kinds,
+        Error,
+        ErrorCategory,
+        Result,
+    };
+    #[cfg(not(feature = "std"))]
+    pub use wrt_sync::{
+        Mutex,
+        RwLock,
+    };
+}
+
+/// [`mod@core`] plus the value, type and collection vocabulary of the
+/// runtime
+#[cfg(feature = "runtime")]
+pub mod runtime {
+    pub use wrt_foundation::{
+        bounded::{
+            BoundedString,
+            BoundedVec,
+        },
+        collections::StaticVec,
+        safe_managed_alloc,
+        safe_memory::NoStdProvider,
+        traits::{
+            Checksummable,
+            FromBytes,
+            ToBytes,
+        },
+        types::{
+            BlockType,
+            FuncType,
+            GlobalType,
+            MemoryType,
+            TableType,
+            ValueType,
+        },
+        values::Value,
+        verification::VerificationLevel,
+        BoundedCapacity,
+        BoundedMap,
+        BoundedSet,
+        CrateId,
+        MemoryProvider,
+    };
+
+    pub use crate::core::*;
+}
+
+/// [`runtime`] plus the types host functions and interceptors work with
+#[cfg(feature = "host")]
+pub mod host {
+    #[cfg(feature = "std")]
+    pub use wrt_foundation::component_value::{
+        ComponentValue,
+        ValType,
+    };
+    pub use wrt_foundation::{
+        builtin::BuiltinType,
+        resource::ResourceCanonicalOperation,
+        safe_memory::{
+            SafeMemoryHandler,
+            SafeSlice,
+            SafeStack,
+        },
+    };
+
+    pub use crate::runtime::*;
+}
@@ -0,0 +1,117 @@
+//! Allocator traffic of the call path
+//!
+//! Counts heap allocations made by `execute` with a counting global
+//! allocator. Operand stacks, locals and block stacks come from the
+//! engine's scratch pools, so once the pools are warm the number of
+//! allocations per `execute` must not grow with the number of calls the
+//! guest makes. The benchmark exits with an error if it does.
+//!
+//! Run with `cargo bench -p wrt-runtime --bench call_allocations`.
+
+use std::{
+    alloc::{
+        GlobalAlloc,
+        Layout,
+        System,
+    },
+    process::ExitCode,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use wrt_foundation::values::Value;
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    EnginePreset,
+    InstanceHandle,
+};
+
+/// System allocator counting every allocation
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: forwards to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: the caller upholds the `GlobalAlloc::alloc` contract.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` was allocated by `System` with `layout`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: the caller upholds the `GlobalAlloc::realloc` contract.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Guest calls made by the workload per `execute` in the large run
+const CALLS: i32 = 1000;
+
+const CALLS_WAT: &str = r#"
+(module
+  (func $inc (param i32) (result i32)
+    (i32.add (local.get 0) (i32.const 1)))
+  (func (export "call_return") (param $n i32) (result i32)
+    (local $acc i32)
+    (loop $top
+      (local.set $acc (call $inc (local.get $acc)))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $top (local.get $n)))
+    (local.get $acc))
+)
+"#;
+
+/// Allocations made by one `execute` of `call_return` with `calls` calls
+fn allocations_per_execute(
+    engine: &mut CapabilityAwareEngine,
+    instance: InstanceHandle,
+    calls: i32,
+) -> usize {
+    let args = [Value::I32(calls)];
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let results = engine.execute(instance, "call_return", &args).expect("call_return runs");
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert!(matches!(results.as_slice(), [Value::I32(acc)] if *acc == calls));
+    allocations
+}
+
+fn main() -> ExitCode {
+    let binary = wat::parse_str(CALLS_WAT).expect("call module assembles");
+    let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM).expect("engine");
+    let module = engine.load_module(&binary).expect("module loads");
+    let instance = engine.instantiate(module).expect("module instantiates");
+
+    // Warm the scratch pools
+    allocations_per_execute(&mut engine, instance, CALLS);
+
+    let single = allocations_per_execute(&mut engine, instance, 1);
+    let many = allocations_per_execute(&mut engine, instance, CALLS);
+    println!("allocations per execute: {single} with 1 call, {many} with {CALLS} calls");
+
+    let stats = engine.scratch_stats();
+    println!(
+        "scratch pools: {} reused, {} allocated, {} discarded",
+        stats.reused, stats.allocated, stats.discarded
+    );
+
+    // Allowing a little slack for per-execute bookkeeping, any per-call
+    // allocation would add at least one allocation per guest call
+    if many > single + 16 {
+        eprintln!("guest calls allocate: {} extra allocations", many - single);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
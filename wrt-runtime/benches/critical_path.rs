@@ -0,0 +1,80 @@
+//! Latency-critical interpreter paths
+//!
+//! Every benchmark in the `critical` group is gated in CI by
+//! `cargo-wrt bench --critical`, which fails when its mean time regresses
+//! beyond the tolerance against the baseline. Keep the workloads stable:
+//! changing one invalidates its baseline.
+//!
+//! - `dispatch`: tight loop of local and arithmetic instructions
+//! - `memory_load_store`: `i32.store` followed by `i32.load` in a loop
+//! - `call_return`: direct calls to a small function in a loop
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use wrt_foundation::values::Value;
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    EnginePreset,
+    InstanceHandle,
+};
+
+/// Loop iterations per benchmarked invocation
+const ITERATIONS: i32 = 1000;
+
+const CRITICAL_WAT: &str = r#"
+(module
+  (memory 1)
+  (func (export "dispatch") (param $n i32) (result i32)
+    (local $acc i32)
+    (loop $top
+      (local.set $acc (i32.xor (i32.add (local.get $acc) (local.get $n)) (i32.const 0x5a)))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $top (local.get $n)))
+    (local.get $acc))
+  (func (export "memory_load_store") (param $n i32) (result i32)
+    (local $acc i32)
+    (loop $top
+      (i32.store (i32.and (i32.shl (local.get $n) (i32.const 2)) (i32.const 0xfffc))
+        (local.get $n))
+      (local.set $acc (i32.add (local.get $acc)
+        (i32.load (i32.and (i32.shl (local.get $n) (i32.const 2)) (i32.const 0xfffc)))))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $top (local.get $n)))
+    (local.get $acc))
+  (func $inc (param i32) (result i32)
+    (i32.add (local.get 0) (i32.const 1)))
+  (func (export "call_return") (param $n i32) (result i32)
+    (local $acc i32)
+    (loop $top
+      (local.set $acc (call $inc (local.get $acc)))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $top (local.get $n)))
+    (local.get $acc))
+)
+"#;
+
+/// Engine with the critical path module instantiated
+fn instantiate() -> (CapabilityAwareEngine, InstanceHandle) {
+    let binary = wat::parse_str(CRITICAL_WAT).expect("critical path module assembles");
+    let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM).expect("engine");
+    let module = engine.load_module(&binary).expect("module loads");
+    let instance = engine.instantiate(module).expect("module instantiates");
+    (engine, instance)
+}
+
+fn bench_critical_paths(c: &mut Criterion) {
+    let (mut engine, instance) = instantiate();
+    let args = [Value::I32(ITERATIONS)];
+    let mut group = c.benchmark_group("critical");
+    for name in ["dispatch", "memory_load_store", "call_return"] {
+        group.bench_function(name, |b| {
+            b.iter(|| engine.execute(instance, black_box(name), black_box(&args)).expect(name))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_critical_paths);
+criterion_main!(benches);
@@ -0,0 +1,252 @@
+// Embedded in cargo-wrt, which writes it back with
+// `cargo-wrt fuzz --differential` when it is missing or stale.
+
+//! Differential fuzz target against the wasmi reference interpreter
+//!
+//! Each input drives wasm-smith to generate a module, which is instantiated
+//! in both WRT and wasmi. Every exported function is then called in both
+//! engines with the same arguments, and the results, trap kinds and final
+//! exported memory contents must agree.
+//!
+//! Generated modules are bounded with `ensure_termination`, so infinite loops
+//! trap with `unreachable` in both engines. NaNs are canonicalized so float
+//! results can be compared bit for bit.
+//!
+//! When `WRT_DIFFERENTIAL_DUMP` is set to a path prefix, a divergence writes
+//! the module to `<prefix>.wasm` and a description to `<prefix>.txt` before
+//! failing.
+
+#![no_main]
+
+use std::fmt::Write as _;
+
+use arbitrary::Unstructured;
+use libfuzzer_sys::fuzz_target;
+use wasmi::{core::TrapCode, ExternType, ValType};
+use wrt_foundation::{values::Value, FloatBits32, FloatBits64};
+use wrt_runtime::engine::{CapabilityEngine, EngineBuilder, TrapKind};
+
+/// Fuel each generated loop and function may consume before trapping
+const TERMINATION_FUEL: u32 = 1_000;
+/// Environment variable naming the path prefix for divergence dumps
+const DUMP_ENV: &str = "WRT_DIFFERENTIAL_DUMP";
+
+/// Result value, with floats as bit patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I32(i32),
+    I64(i64),
+    F32(u32),
+    F64(u64),
+}
+
+/// Outcome of one call in one engine
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    /// The call returned these results
+    Returned(Vec<Scalar>),
+    /// The call trapped; `None` if the error is not a recognized trap
+    Trapped(Option<TrapKind>),
+}
+
+/// Map a wasmi trap to the WRT trap kind it corresponds to
+fn reference_trap(error: &wasmi::Error) -> Option<TrapKind> {
+    Some(match error.as_trap_code()? {
+        TrapCode::UnreachableCodeReached => TrapKind::Unreachable,
+        TrapCode::MemoryOutOfBounds => TrapKind::MemoryOutOfBounds,
+        TrapCode::TableOutOfBounds => TrapKind::TableOutOfBounds,
+        TrapCode::IndirectCallToNull => TrapKind::UninitializedElement,
+        TrapCode::IntegerDivisionByZero => TrapKind::IntegerDivideByZero,
+        TrapCode::IntegerOverflow => TrapKind::IntegerOverflow,
+        TrapCode::BadConversionToInteger => TrapKind::InvalidConversionToInteger,
+        TrapCode::StackOverflow => TrapKind::CallStackExhausted,
+        TrapCode::BadSignature => TrapKind::IndirectCallTypeMismatch,
+        TrapCode::OutOfFuel => TrapKind::FuelExhausted,
+        _ => return None,
+    })
+}
+
+fn to_wrt(value: Scalar) -> Value {
+    match value {
+        Scalar::I32(v) => Value::I32(v),
+        Scalar::I64(v) => Value::I64(v),
+        Scalar::F32(bits) => Value::F32(FloatBits32(bits)),
+        Scalar::F64(bits) => Value::F64(FloatBits64(bits)),
+    }
+}
+
+fn from_wrt(value: &Value) -> Option<Scalar> {
+    Some(match value {
+        Value::I32(v) => Scalar::I32(*v),
+        Value::I64(v) => Scalar::I64(*v),
+        Value::F32(v) => Scalar::F32(v.0),
+        Value::F64(v) => Scalar::F64(v.0),
+        _ => return None,
+    })
+}
+
+fn to_wasmi(value: Scalar) -> wasmi::Val {
+    match value {
+        Scalar::I32(v) => wasmi::Val::I32(v),
+        Scalar::I64(v) => wasmi::Val::I64(v),
+        Scalar::F32(bits) => wasmi::Val::F32(wasmi::core::F32::from_bits(bits)),
+        Scalar::F64(bits) => wasmi::Val::F64(wasmi::core::F64::from_bits(bits)),
+    }
+}
+
+fn from_wasmi(value: &wasmi::Val) -> Option<Scalar> {
+    Some(match value {
+        wasmi::Val::I32(v) => Scalar::I32(*v),
+        wasmi::Val::I64(v) => Scalar::I64(*v),
+        wasmi::Val::F32(v) => Scalar::F32(v.to_bits()),
+        wasmi::Val::F64(v) => Scalar::F64(v.to_bits()),
+        _ => return None,
+    })
+}
+
+/// Draw an argument of type `ty` from the input
+fn argument(u: &mut Unstructured<'_>, ty: &ValType) -> Option<Scalar> {
+    Some(match ty {
+        ValType::I32 => Scalar::I32(u.arbitrary().ok()?),
+        ValType::I64 => Scalar::I64(u.arbitrary().ok()?),
+        ValType::F32 => Scalar::F32(u.arbitrary().ok()?),
+        ValType::F64 => Scalar::F64(u.arbitrary().ok()?),
+        _ => return None,
+    })
+}
+
+/// Module generator settings: core features both engines implement
+fn config() -> wasm_smith::Config {
+    let mut config = wasm_smith::Config::default();
+    config.max_imports = 0;
+    config.min_funcs = 1;
+    config.export_everything = true;
+    config.allow_start_export = false;
+    config.canonicalize_nans = true;
+    config.max_memories = 1;
+    config.max_tables = 1;
+    config.simd_enabled = false;
+    config.relaxed_simd_enabled = false;
+    config.threads_enabled = false;
+    config.exceptions_enabled = false;
+    config.gc_enabled = false;
+    config.memory64_enabled = false;
+    config.reference_types_enabled = false;
+    config
+}
+
+/// Record a divergence and fail
+fn diverged(wasm: &[u8], description: String) -> ! {
+    if let Ok(prefix) = std::env::var(DUMP_ENV) {
+        let _ = std::fs::write(format!("{prefix}.wasm"), wasm);
+        let _ = std::fs::write(format!("{prefix}.txt"), &description);
+    }
+    panic!("WRT diverged from wasmi: {description}");
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+    let Ok(mut module) = wasm_smith::Module::new(config(), &mut u) else {
+        return;
+    };
+    if module.ensure_termination(TERMINATION_FUEL).is_err() {
+        return;
+    }
+    let wasm = module.to_bytes();
+
+    // Reference engine
+    let engine = wasmi::Engine::default();
+    let Ok(reference_module) = wasmi::Module::new(&engine, &wasm[..]) else {
+        return;
+    };
+    let mut store = wasmi::Store::new(&engine, ());
+    let reference = wasmi::Linker::<()>::new(&engine)
+        .instantiate(&mut store, &reference_module)
+        .and_then(|pre| pre.start(&mut store));
+
+    // Engine under test
+    let mut wrt = EngineBuilder::qm().build().expect("failed to create WRT engine");
+    let instance = wrt.load_module(&wasm).and_then(|module| wrt.instantiate(module));
+
+    let (reference, instance) = match (reference, instance) {
+        (Ok(reference), Ok(instance)) => (reference, instance),
+        (Err(_), Err(_)) => return,
+        (Ok(_), Err(e)) => diverged(&wasm, format!("WRT failed to instantiate: {e}")),
+        (Err(e), Ok(_)) => diverged(&wasm, format!("wasmi failed to instantiate: {e}")),
+    };
+
+    let mut log = String::new();
+    for export in reference_module.exports() {
+        let ExternType::Func(ty) = export.ty() else {
+            continue;
+        };
+        let name = export.name();
+        let args: Option<Vec<Scalar>> = ty.params().iter().map(|ty| argument(&mut u, ty)).collect();
+        let Some(args) = args else {
+            return;
+        };
+
+        let func = reference.get_func(&store, name).expect("exported function");
+        let mut results: Vec<wasmi::Val> =
+            ty.results().iter().map(|ty| wasmi::Val::default(*ty)).collect();
+        let wasmi_args: Vec<wasmi::Val> = args.iter().copied().map(to_wasmi).collect();
+        let expected = match func.call(&mut store, &wasmi_args, &mut results) {
+            Ok(()) => match results.iter().map(from_wasmi).collect::<Option<Vec<_>>>() {
+                Some(values) => Outcome::Returned(values),
+                None => return,
+            },
+            Err(e) => Outcome::Trapped(reference_trap(&e)),
+        };
+
+        let wrt_args: Vec<Value> = args.iter().copied().map(to_wrt).collect();
+        let actual = match wrt.execute(instance, name, &wrt_args) {
+            Ok(values) => match values.iter().map(from_wrt).collect::<Option<Vec<_>>>() {
+                Some(values) => Outcome::Returned(values),
+                None => diverged(&wasm, format!("{name}{args:?}: WRT returned {values:?}")),
+            },
+            Err(e) => Outcome::Trapped(TrapKind::classify(&e)),
+        };
+
+        let _ = writeln!(log, "{name}{args:?} -> {expected:?}");
+        // Call depth limits differ between engines; once either runs out of
+        // stack the instances' states are no longer comparable
+        let exhausted = Outcome::Trapped(Some(TrapKind::CallStackExhausted));
+        if expected == exhausted || actual == exhausted {
+            return;
+        }
+        if actual != expected {
+            diverged(
+                &wasm,
+                format!("{log}{name}{args:?}: wasmi {expected:?}, WRT {actual:?}"),
+            );
+        }
+    }
+
+    for export in reference_module.exports() {
+        if !matches!(export.ty(), ExternType::Memory(_)) {
+            continue;
+        }
+        let name = export.name();
+        let expected = reference.get_memory(&store, name).expect("exported memory").data(&store);
+        let actual = wrt
+            .get_instance(instance)
+            .and_then(|instance| instance.memory_by_name(name))
+            .and_then(|memory| memory.inner().buffer());
+        match actual {
+            Ok(actual) if actual == expected => {},
+            Ok(actual) => {
+                let offset = actual.iter().zip(expected).position(|(a, b)| a != b);
+                diverged(
+                    &wasm,
+                    format!(
+                        "{log}memory {name}: WRT has {} bytes, wasmi {}, first difference at \
+                         {offset:?}",
+                        actual.len(),
+                        expected.len()
+                    ),
+                );
+            },
+            Err(e) => diverged(&wasm, format!("{log}memory {name}: WRT failed to read: {e}")),
+        }
+    }
+});
@@ -0,0 +1,459 @@
+//! Size and TTL policies, flushing and metrics for engine caches
+//!
+//! Long-lived hosts accumulate cached data: decoded modules, inline caches,
+//! memoized results. A [`BoundedCache`] keeps such data within the entry,
+//! byte and age limits of a [`CachePolicy`], evicting least recently used
+//! entries first, and counts hits, misses and evictions in
+//! [`CacheMetrics`]. Caches of different types are registered by name in a
+//! [`CacheRegistry`], through which the host flushes them, applies their
+//! policies and reads their metrics in one place.
+//!
+//! Eviction scans all entries for the least recently used one, so bounded
+//! caches are meant for at most a few thousand entries.
+
+use core::{
+    hash::Hash,
+    time::Duration,
+};
+use std::time::Instant;
+
+use crate::prelude::*;
+
+/// Limits a [`BoundedCache`] keeps to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CachePolicy {
+    /// Maximum number of entries; none for no limit
+    pub max_entries: Option<usize>,
+    /// Maximum total size of entries in bytes; none for no limit
+    pub max_bytes:   Option<usize>,
+    /// Time after insertion at which entries expire; none to keep them
+    pub ttl:         Option<Duration>,
+}
+
+impl CachePolicy {
+    /// Policy without limits
+    pub const fn unbounded() -> Self {
+        Self {
+            max_entries: None,
+            max_bytes:   None,
+            ttl:         None,
+        }
+    }
+
+    /// Policy that caches nothing
+    pub const fn disabled() -> Self {
+        Self {
+            max_entries: Some(0),
+            max_bytes:   None,
+            ttl:         None,
+        }
+    }
+
+    /// Limit the number of entries
+    #[must_use]
+    pub const fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Limit the total size of entries in bytes
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Expire entries `ttl` after they were inserted
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Whether the policy caches anything
+    pub fn is_disabled(&self) -> bool {
+        self.max_entries == Some(0) || self.max_bytes == Some(0)
+    }
+}
+
+/// Counters of a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheMetrics {
+    /// Lookups that found an entry
+    pub hits:        u64,
+    /// Lookups that found no entry or an expired one
+    pub misses:      u64,
+    /// Entries inserted
+    pub insertions:  u64,
+    /// Entries evicted to stay within the size limits
+    pub evictions:   u64,
+    /// Entries dropped because they outlived the TTL
+    pub expirations: u64,
+    /// Times the cache was flushed
+    pub flushes:     u64,
+    /// Entries currently cached
+    pub entries:     usize,
+    /// Total size of the current entries in bytes
+    pub bytes:       usize,
+}
+
+impl CacheMetrics {
+    /// Fraction of lookups that hit, zero before the first lookup
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    /// Add the counters of another cache to these
+    pub fn accumulate(&mut self, other: &Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.insertions += other.insertions;
+        self.evictions += other.evictions;
+        self.expirations += other.expirations;
+        self.flushes += other.flushes;
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Cache that can be flushed, collected and observed through a
+/// [`CacheRegistry`]
+pub trait ManagedCache {
+    /// Current counters
+    fn metrics(&self) -> CacheMetrics;
+
+    /// Drop all entries
+    fn flush(&mut self);
+
+    /// Drop expired entries and evict until the size limits hold, returning
+    /// the number of entries dropped
+    fn collect(&mut self) -> usize;
+}
+
+/// Cached value with its bookkeeping
+#[derive(Debug, Clone)]
+struct CacheEntry<V> {
+    /// Cached value
+    value:     V,
+    /// Size charged against the byte limit
+    size:      usize,
+    /// When the entry was inserted
+    inserted:  Instant,
+    /// Access tick of the last lookup or insertion
+    last_used: u64,
+}
+
+/// Map with LRU eviction within the limits of a [`CachePolicy`]
+#[derive(Debug, Clone)]
+pub struct BoundedCache<K, V> {
+    /// Limits kept to
+    policy:  CachePolicy,
+    /// Cached entries
+    entries: HashMap<K, CacheEntry<V>>,
+    /// Counter ordering accesses for LRU eviction
+    tick:    u64,
+    /// Counters
+    metrics: CacheMetrics,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> BoundedCache<K, V> {
+    /// Create an empty cache keeping to `policy`
+    pub fn new(policy: CachePolicy) -> Self {
+        Self {
+            policy,
+            entries: HashMap::new(),
+            tick: 0,
+            metrics: CacheMetrics::default(),
+        }
+    }
+
+    /// Limits kept to
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Change the limits, evicting entries that no longer fit
+    pub fn set_policy(&mut self, policy: CachePolicy) {
+        self.policy = policy;
+        self.collect();
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up an entry, counting a hit or miss
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Insert an entry charged `size` bytes, evicting others as needed
+    ///
+    /// Nothing is cached when the policy is disabled or the entry alone
+    /// exceeds the byte limit.
+    pub fn insert(&mut self, key: K, value: V, size: usize) {
+        self.insert_at(key, value, size, Instant::now());
+    }
+
+    /// Remove an entry
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.metrics.bytes -= entry.size;
+        Some(entry.value)
+    }
+
+    fn get_at(&mut self, key: &K, now: Instant) -> Option<V> {
+        let expired = match self.entries.get(key) {
+            Some(entry) => self.is_expired(entry, now),
+            None => {
+                self.metrics.misses += 1;
+                return None;
+            },
+        };
+        if expired {
+            self.remove(key);
+            self.metrics.expirations += 1;
+            self.metrics.misses += 1;
+            return None;
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = tick;
+        self.metrics.hits += 1;
+        Some(entry.value.clone())
+    }
+
+    fn insert_at(&mut self, key: K, value: V, size: usize, now: Instant) {
+        if self.policy.is_disabled() || self.policy.max_bytes.is_some_and(|max| size > max) {
+            return;
+        }
+        self.remove(&key);
+        self.tick += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                size,
+                inserted: now,
+                last_used: self.tick,
+            },
+        );
+        self.metrics.bytes += size;
+        self.metrics.insertions += 1;
+        self.collect_at(now);
+    }
+
+    fn collect_at(&mut self, now: Instant) -> usize {
+        let before = self.entries.len();
+        if let Some(ttl) = self.policy.ttl {
+            let mut expired_bytes = 0;
+            self.entries.retain(|_, entry| {
+                let keep = now.saturating_duration_since(entry.inserted) < ttl;
+                if !keep {
+                    expired_bytes += entry.size;
+                }
+                keep
+            });
+            self.metrics.bytes -= expired_bytes;
+            self.metrics.expirations += (before - self.entries.len()) as u64;
+        }
+
+        while self.over_limits() {
+            let Some(lru) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&lru);
+            self.metrics.evictions += 1;
+        }
+        before - self.entries.len()
+    }
+
+    fn is_expired(&self, entry: &CacheEntry<V>, now: Instant) -> bool {
+        self.policy.ttl.is_some_and(|ttl| now.saturating_duration_since(entry.inserted) >= ttl)
+    }
+
+    fn over_limits(&self) -> bool {
+        self.policy.max_entries.is_some_and(|max| self.entries.len() > max)
+            || self.policy.max_bytes.is_some_and(|max| self.metrics.bytes > max)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> ManagedCache for BoundedCache<K, V> {
+    fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            entries: self.entries.len(),
+            ..self.metrics
+        }
+    }
+
+    fn flush(&mut self) {
+        self.entries.clear();
+        self.metrics.bytes = 0;
+        self.metrics.flushes += 1;
+    }
+
+    fn collect(&mut self) -> usize {
+        self.collect_at(Instant::now())
+    }
+}
+
+/// Shared handle to a cache registered in a [`CacheRegistry`]
+pub type SharedCache = Arc<Mutex<dyn ManagedCache + Send>>;
+
+/// Named caches managed together
+#[derive(Default)]
+pub struct CacheRegistry {
+    /// Registered caches in registration order
+    caches: Vec<(String, SharedCache)>,
+}
+
+impl CacheRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a cache under `name`, replacing a cache of the same name
+    pub fn register(&mut self, name: &str, cache: SharedCache) {
+        self.caches.retain(|(existing, _)| existing != name);
+        self.caches.push((name.to_string(), cache));
+    }
+
+    /// Remove the cache registered under `name`
+    pub fn unregister(&mut self, name: &str) -> Option<SharedCache> {
+        let position = self.caches.iter().position(|(existing, _)| existing == name)?;
+        Some(self.caches.remove(position).1)
+    }
+
+    /// Names of the registered caches
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.caches.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Drop all entries of the cache registered under `name`
+    ///
+    /// Returns whether a cache of that name is registered.
+    pub fn flush(&self, name: &str) -> bool {
+        let Some((_, cache)) = self.caches.iter().find(|(existing, _)| existing == name) else {
+            return false;
+        };
+        if let Ok(mut cache) = cache.lock() {
+            cache.flush();
+        }
+        true
+    }
+
+    /// Drop all entries of all caches
+    pub fn flush_all(&self) {
+        for (_, cache) in &self.caches {
+            if let Ok(mut cache) = cache.lock() {
+                cache.flush();
+            }
+        }
+    }
+
+    /// Apply the policies of all caches, returning the entries dropped
+    pub fn collect_all(&self) -> usize {
+        self.caches
+            .iter()
+            .filter_map(|(_, cache)| cache.lock().ok().map(|mut cache| cache.collect()))
+            .sum()
+    }
+
+    /// Counters of each cache by name
+    pub fn metrics(&self) -> Vec<(String, CacheMetrics)> {
+        self.caches
+            .iter()
+            .filter_map(|(name, cache)| {
+                cache.lock().ok().map(|cache| (name.clone(), cache.metrics()))
+            })
+            .collect()
+    }
+
+    /// Counters summed over all caches
+    pub fn total_metrics(&self) -> CacheMetrics {
+        let mut total = CacheMetrics::default();
+        for (_, metrics) in self.metrics() {
+            total.accumulate(&metrics);
+        }
+        total
+    }
+}
+
+impl core::fmt::Debug for CacheRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction_and_ttl() {
+        let policy = CachePolicy::unbounded()
+            .with_max_entries(2)
+            .with_max_bytes(100)
+            .with_ttl(Duration::from_secs(10));
+        let mut cache = BoundedCache::new(policy);
+        let start = Instant::now();
+
+        cache.insert_at("a", 1, 10, start);
+        cache.insert_at("b", 2, 10, start);
+        assert_eq!(cache.get_at(&"a", start), Some(1));
+        cache.insert_at("c", 3, 10, start);
+        assert_eq!(cache.get_at(&"b", start), None);
+        assert_eq!(cache.len(), 2);
+
+        cache.insert_at("d", 4, 95, start);
+        assert_eq!(cache.len(), 1);
+        cache.insert_at("huge", 5, 101, start);
+        assert_eq!(cache.get_at(&"huge", start), None);
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(cache.get_at(&"d", later), None);
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.evictions, 3);
+        assert_eq!(metrics.expirations, 1);
+        assert_eq!((metrics.hits, metrics.misses), (1, 3));
+        assert_eq!((metrics.entries, metrics.bytes), (0, 0));
+    }
+
+    #[test]
+    fn test_registry_flushes_and_reports() {
+        let cache = Arc::new(Mutex::new(BoundedCache::new(CachePolicy::unbounded())));
+        let mut registry = CacheRegistry::new();
+        registry.register("memo", cache.clone());
+
+        cache.lock().unwrap().insert(1u32, "one", 8);
+        assert_eq!(cache.lock().unwrap().get(&1), Some("one"));
+        assert_eq!(registry.total_metrics().bytes, 8);
+        assert_eq!(registry.total_metrics().hit_rate(), 1.0);
+
+        assert!(registry.flush("memo"));
+        assert!(!registry.flush("missing"));
+        let metrics = &registry.metrics()[0];
+        assert_eq!(metrics.0, "memo");
+        assert_eq!((metrics.1.entries, metrics.1.flushes), (0, 1));
+    }
+}
@@ -0,0 +1,417 @@
+//! Static call depth and recursion analysis of decoded modules
+//!
+//! [`analyze_module`] builds the call graph of a module and computes, for
+//! every function, how many frames the stackless engine's frame store holds
+//! when the deepest call chain starting in that function is active. This
+//! lets a deployment size the frame store from the module instead of by
+//! trial and error.
+//!
+//! Recursion makes the depth unbounded. Functions calling each other
+//! recursively form a [`RecursionCycle`]; every function of a cycle is
+//! counted once, so depths through a cycle are lower bounds and the cycle
+//! is reported instead.
+//!
+//! `call_indirect` is resolved to every function of the same type that is
+//! placed in a table by an element segment or referenced by `ref.func`.
+//! Tail calls are counted like calls, and imported functions do not take a
+//! frame. Functions the host writes into tables are not seen.
+
+use std::{
+    collections::BTreeSet,
+    vec::Vec,
+};
+
+use wrt_decoder::ComplexityLimits;
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    module::Module,
+    pure_format_types::PureElementInit,
+};
+use wrt_foundation::types::Instruction;
+
+use crate::instruction_parser::parse_instructions;
+
+/// Functions calling each other recursively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursionCycle {
+    /// Functions of the cycle, in ascending order
+    pub functions: Vec<u32>,
+}
+
+impl RecursionCycle {
+    /// Whether the cycle is a single function calling itself
+    pub fn is_direct(&self) -> bool {
+        self.functions.len() == 1
+    }
+}
+
+/// Call depth of a single function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionDepth {
+    /// Frames on the frame store along the deepest call chain starting in
+    /// the function, including its own
+    pub depth:     u32,
+    /// Whether a call chain starting in the function enters a recursion
+    /// cycle, making `depth` a lower bound
+    pub recursive: bool,
+}
+
+/// Result of the call depth analysis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallDepthReport {
+    /// Depth of every function, `None` for imported functions
+    depths:       Vec<Option<FunctionDepth>>,
+    /// Recursion cycles, ordered by their lowest function
+    pub cycles:   Vec<RecursionCycle>,
+    /// Whether every function body could be parsed; if not, calls may be
+    /// missing and depths too low
+    pub complete: bool,
+}
+
+impl CallDepthReport {
+    /// Depth of `function`, `None` if it is imported or out of range
+    pub fn depth(&self, function: u32) -> Option<FunctionDepth> {
+        self.depths.get(function as usize).copied().flatten()
+    }
+
+    /// Function with the deepest call chain and its depth
+    ///
+    /// Of several functions with the same depth the lowest index is
+    /// returned. `None` if the module defines no functions.
+    pub fn deepest(&self) -> Option<(u32, FunctionDepth)> {
+        self.depths
+            .iter()
+            .enumerate()
+            .filter_map(|(index, depth)| depth.map(|depth| (index as u32, depth)))
+            .fold(None, |deepest, (index, depth)| match deepest {
+                Some((_, best)) if best.depth >= depth.depth => deepest,
+                _ => Some((index, depth)),
+            })
+    }
+
+    /// Deepest call chain of the module, 0 if it defines no functions
+    pub fn max_depth(&self) -> u32 {
+        self.deepest().map_or(0, |(_, depth)| depth.depth)
+    }
+
+    /// Whether the module cannot recurse, so [`Self::max_depth`] is an
+    /// upper bound of the frames it needs
+    pub fn is_bounded(&self) -> bool {
+        self.cycles.is_empty()
+    }
+
+    /// Check the call depth against the cap of a complexity policy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module recurses, so no cap can be shown to
+    /// hold, or if its deepest call chain exceeds `limits.max_call_depth`.
+    pub fn check_limits(&self, limits: &ComplexityLimits) -> Result<()> {
+        if !self.is_bounded() {
+            return Err(Error::resource_exhausted("Recursive call graph exceeds complexity limit"));
+        }
+        limits.check_call_depth(self.max_depth())
+    }
+}
+
+/// Decode a module binary and analyze its call depth
+///
+/// # Errors
+///
+/// Returns an error if the binary cannot be decoded.
+pub fn analyze_binary(binary: &[u8]) -> Result<CallDepthReport> {
+    let module = wrt_decoder::decoder::decode_module(binary)?;
+    Ok(analyze_module(&module))
+}
+
+/// Analyze the call depth of a decoded module
+pub fn analyze_module(module: &Module) -> CallDepthReport {
+    let graph = CallGraph::build(module);
+    let components = graph.strongly_connected_components();
+
+    let count = module.functions.len();
+    let mut component_of = vec![0usize; count];
+    for (component, members) in components.iter().enumerate() {
+        for &function in members {
+            component_of[function] = component;
+        }
+    }
+
+    // Components come out with callees before callers, so every callee
+    // component already has its depth when its callers are visited
+    let mut component_depth = vec![0u32; components.len()];
+    let mut component_recursive = vec![false; components.len()];
+    let mut cycles = Vec::new();
+    for (component, members) in components.iter().enumerate() {
+        let cyclic = members.len() > 1 || graph.calls[members[0]].contains(&members[0]);
+        let frames = members.iter().filter(|&&function| graph.defined[function]).count() as u32;
+        let mut deepest_callee = 0;
+        let mut recursive = cyclic;
+        for &function in members {
+            for &callee in &graph.calls[function] {
+                let callee = component_of[callee];
+                if callee != component {
+                    deepest_callee = deepest_callee.max(component_depth[callee]);
+                    recursive |= component_recursive[callee];
+                }
+            }
+        }
+        component_depth[component] = frames.saturating_add(deepest_callee);
+        component_recursive[component] = recursive;
+        if cyclic {
+            let mut functions: Vec<u32> = members.iter().map(|&f| f as u32).collect();
+            functions.sort_unstable();
+            cycles.push(RecursionCycle { functions });
+        }
+    }
+    cycles.sort_unstable_by_key(|cycle| cycle.functions[0]);
+
+    let depths = (0..count)
+        .map(|function| {
+            graph.defined[function].then(|| FunctionDepth {
+                depth:     component_depth[component_of[function]],
+                recursive: component_recursive[component_of[function]],
+            })
+        })
+        .collect();
+    CallDepthReport {
+        depths,
+        cycles,
+        complete: graph.complete,
+    }
+}
+
+/// Calls between the functions of a module
+struct CallGraph {
+    /// Callees of each function, deduplicated
+    calls:    Vec<Vec<usize>>,
+    /// Whether each function is defined by the module
+    defined:  Vec<bool>,
+    complete: bool,
+}
+
+impl CallGraph {
+    fn build(module: &Module) -> Self {
+        let count = module.functions.len();
+        let defined: Vec<bool> = module.functions.iter().map(|f| !f.code.is_empty()).collect();
+        let mut complete = true;
+
+        let mut direct = vec![BTreeSet::new(); count];
+        let mut indirect_types = vec![BTreeSet::new(); count];
+        let mut address_taken = BTreeSet::new();
+        for element in &module.elements {
+            match &element.init_data {
+                PureElementInit::FunctionIndices(indices) => address_taken.extend(indices),
+                PureElementInit::ExpressionBytes(expressions) => {
+                    for expression in expressions {
+                        match parse_instructions(expression) {
+                            Ok(instructions) => {
+                                address_taken.extend(instructions.iter().filter_map(|i| match i {
+                                    Instruction::RefFunc(function) => Some(*function),
+                                    _ => None,
+                                }));
+                            },
+                            Err(_) => complete = false,
+                        }
+                    }
+                },
+            }
+        }
+
+        for (caller, function) in module.functions.iter().enumerate() {
+            if !defined[caller] {
+                continue;
+            }
+            let Ok(instructions) = parse_instructions(&function.code) else {
+                complete = false;
+                continue;
+            };
+            for instruction in &instructions {
+                match instruction {
+                    Instruction::Call(callee) | Instruction::ReturnCall(callee)
+                        if (*callee as usize) < count =>
+                    {
+                        direct[caller].insert(*callee as usize);
+                    },
+                    Instruction::CallIndirect(type_idx, _)
+                    | Instruction::ReturnCallIndirect(type_idx, _) => {
+                        indirect_types[caller].insert(*type_idx);
+                    },
+                    Instruction::RefFunc(function) => {
+                        address_taken.insert(*function);
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        let calls = direct
+            .into_iter()
+            .zip(&indirect_types)
+            .map(|(mut callees, types)| {
+                callees.extend(
+                    address_taken
+                        .iter()
+                        .map(|&function| function as usize)
+                        .filter(|&function| {
+                            module
+                                .functions
+                                .get(function)
+                                .is_some_and(|f| types.contains(&f.type_idx))
+                        }),
+                );
+                callees.into_iter().collect()
+            })
+            .collect();
+        Self {
+            calls,
+            defined,
+            complete,
+        }
+    }
+
+    /// Strongly connected components in reverse topological order
+    ///
+    /// Iterative Tarjan, so deep call graphs cannot overflow the stack.
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let count = self.calls.len();
+        let mut index = vec![UNVISITED; count];
+        let mut lowlink = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..count {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // (function, position of the next callee to visit)
+            let mut work = vec![(root, 0)];
+            while let Some(&mut (function, ref mut next)) = work.last_mut() {
+                if *next == 0 {
+                    index[function] = next_index;
+                    lowlink[function] = next_index;
+                    next_index += 1;
+                    stack.push(function);
+                    on_stack[function] = true;
+                }
+                if let Some(&callee) = self.calls[function].get(*next) {
+                    *next += 1;
+                    if index[callee] == UNVISITED {
+                        work.push((callee, 0));
+                    } else if on_stack[callee] {
+                        lowlink[function] = lowlink[function].min(index[callee]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(caller, _)) = work.last() {
+                    lowlink[caller] = lowlink[caller].min(lowlink[function]);
+                }
+                if lowlink[function] == index[function] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == function {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_format::module::Function;
+
+    use super::*;
+
+    fn function(code: Vec<u8>) -> Function {
+        Function {
+            type_idx: 0,
+            locals: Vec::new(),
+            code,
+        }
+    }
+
+    /// Body calling each of `callees`
+    fn calls(callees: &[u8]) -> Vec<u8> {
+        let mut code: Vec<u8> = callees.iter().flat_map(|&callee| [0x10, callee]).collect();
+        code.push(0x0b);
+        code
+    }
+
+    #[test]
+    fn test_depth_of_acyclic_call_graph() {
+        let mut module = Module::new();
+        // 0 is imported; 1 -> 2 -> 3 -> 0 and 1 -> 3
+        module.functions.push(function(Vec::new()));
+        module.functions.push(function(calls(&[2, 3])));
+        module.functions.push(function(calls(&[3])));
+        module.functions.push(function(calls(&[0])));
+
+        let report = analyze_module(&module);
+        assert!(report.is_bounded());
+        assert!(report.complete);
+        assert_eq!(report.depth(0), None);
+        assert_eq!(report.depth(3).map(|d| d.depth), Some(1));
+        assert_eq!(report.deepest(), Some((1, FunctionDepth { depth: 3, recursive: false })));
+        assert_eq!(report.max_depth(), 3);
+
+        let mut limits = ComplexityLimits::default();
+        limits.max_call_depth = 3;
+        assert!(report.check_limits(&limits).is_ok());
+        limits.max_call_depth = 2;
+        assert!(report.check_limits(&limits).is_err());
+    }
+
+    #[test]
+    fn test_recursion_cycles_are_flagged() {
+        let mut module = Module::new();
+        // 0 -> 1 <-> 2 -> 3, 3 calls itself, 4 is independent
+        module.functions.push(function(calls(&[1])));
+        module.functions.push(function(calls(&[2])));
+        module.functions.push(function(calls(&[1, 3])));
+        module.functions.push(function(calls(&[3])));
+        module.functions.push(function(calls(&[])));
+
+        let report = analyze_module(&module);
+        assert!(!report.is_bounded());
+        assert_eq!(report.cycles, vec![
+            RecursionCycle { functions: vec![1, 2] },
+            RecursionCycle { functions: vec![3] },
+        ]);
+        assert!(report.cycles[1].is_direct());
+        // Each function of a cycle counts once
+        assert_eq!(report.depth(0), Some(FunctionDepth { depth: 4, recursive: true }));
+        assert_eq!(report.depth(4), Some(FunctionDepth { depth: 1, recursive: false }));
+        assert!(report.check_limits(&ComplexityLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_indirect_calls_reach_functions_in_tables() {
+        let mut module = Module::new();
+        // 0 calls through the table holding 1, 1 calls 2
+        module.functions.push(function(vec![0x41, 0x00, 0x11, 0x00, 0x00, 0x0b]));
+        module.functions.push(function(calls(&[2])));
+        module.functions.push(function(calls(&[])));
+        let mut element = wrt_format::pure_format_types::PureElementSegment::default();
+        element.init_data = PureElementInit::FunctionIndices(vec![1]);
+        module.elements.push(element);
+
+        let report = analyze_module(&module);
+        assert_eq!(report.depth(0).map(|d| d.depth), Some(3));
+    }
+}
@@ -4,6 +4,7 @@
 //! This module provides a fluent builder interface for creating WebAssembly
 //! engines with proper ASIL-level configuration and resource limits.
 
+use wrt_decoder::proposals::ProposalSet;
 use wrt_error::Result;
 use wrt_foundation::{
     capabilities::MemoryCapabilityContext,
@@ -14,10 +15,20 @@
     },
 };
 
-use crate::engine::{
-    CapabilityAwareEngine,
-    EnginePreset,
+use crate::{
+    engine::{
+        CapabilityAwareEngine,
+        EnginePreset,
+        TrapHandlers,
+    },
+    fp_mode::FpConfig,
+    module::BodyParsing,
+    store_limits::StoreLimits,
 };
+#[cfg(feature = "std")]
+use crate::cache_policy::CachePolicy;
+#[cfg(feature = "signing")]
+use crate::engine::SignaturePolicy;
 
 /// Builder for creating capability-aware WebAssembly engines
 #[derive(Debug)]
@@ -30,6 +41,24 @@ pub struct EngineBuilder {
     custom_context:  Option<MemoryCapabilityContext>,
     /// Resource limits configuration from binary
     resource_config: Option<ASILExecutionConfig>,
+    /// Runtime limits applied to executed functions
+    store_limits:    StoreLimits,
+    /// Host recovery policy applied when a call traps
+    trap_handlers:   TrapHandlers,
+    /// Floating-point mode of executed code
+    fp_config:       FpConfig,
+    /// Whether loading and instantiation are profiled
+    profile_startup: bool,
+    /// Proposals loaded modules may use, if not those of the preset
+    proposals:       Option<ProposalSet>,
+    /// When function bodies of loaded modules are parsed
+    body_parsing:    BodyParsing,
+    /// Limits of the decoded module cache
+    #[cfg(feature = "std")]
+    module_cache:    CachePolicy,
+    /// Keys loaded modules must be signed by, if any
+    #[cfg(feature = "signing")]
+    signatures:      Option<SignaturePolicy>,
 }
 
 impl EngineBuilder {
@@ -40,6 +69,16 @@ pub fn new() -> Self {
             preset:          None,
             custom_context:  None,
             resource_config: None,
+            store_limits:    StoreLimits::new(),
+            trap_handlers:   TrapHandlers::new(),
+            fp_config:       FpConfig::new(),
+            profile_startup: false,
+            proposals:       None,
+            body_parsing:    BodyParsing::Eager,
+            #[cfg(feature = "std")]
+            module_cache:    CachePolicy::disabled(),
+            #[cfg(feature = "signing")]
+            signatures:      None,
         }
     }
 
@@ -67,6 +106,58 @@ pub fn with_resource_config(mut self, config: ASILExecutionConfig) -> Self {
         self
     }
 
+    /// Set the runtime limits applied to executed functions
+    pub fn with_store_limits(mut self, limits: StoreLimits) -> Self {
+        self.store_limits = limits;
+        self
+    }
+
+    /// Set the trap handlers applied when a call traps
+    pub fn with_trap_handlers(mut self, handlers: TrapHandlers) -> Self {
+        self.trap_handlers = handlers;
+        self
+    }
+
+    /// Set the floating-point mode of executed code
+    pub fn with_fp_config(mut self, config: FpConfig) -> Self {
+        self.fp_config = config;
+        self
+    }
+
+    /// Record a startup profile of each loaded and instantiated module
+    pub fn with_startup_profiling(mut self, enabled: bool) -> Self {
+        self.profile_startup = enabled;
+        self
+    }
+
+    /// Set the proposals loaded modules may use, overriding those of the
+    /// preset
+    pub fn with_allowed_proposals(mut self, proposals: ProposalSet) -> Self {
+        self.proposals = Some(proposals);
+        self
+    }
+
+    /// Set when function bodies of loaded modules are parsed
+    pub fn with_body_parsing(mut self, parsing: BodyParsing) -> Self {
+        self.body_parsing = parsing;
+        self
+    }
+
+    /// Cache decoded modules within `policy`, so loading a binary again
+    /// skips decoding
+    #[cfg(feature = "std")]
+    pub fn with_module_cache(mut self, policy: CachePolicy) -> Self {
+        self.module_cache = policy;
+        self
+    }
+
+    /// Only load modules signed by a trust anchor of `policy`
+    #[cfg(feature = "signing")]
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signatures = Some(policy);
+        self
+    }
+
     /// Create an engine for QM (Quality Management) level
     pub fn qm() -> Self {
         Self::new().with_preset(EnginePreset::QM)
@@ -115,7 +206,35 @@ pub fn from_binary(binary: &[u8]) -> Result<Self> {
     }
 
     /// Build the engine with the configured settings
-    pub fn build(self) -> Result<CapabilityAwareEngine> {
+    pub fn build(mut self) -> Result<CapabilityAwareEngine> {
+        let store_limits = self.store_limits;
+        let trap_handlers = core::mem::take(&mut self.trap_handlers);
+        let fp_config = self.fp_config;
+        let profile_startup = self.profile_startup;
+        let proposals = self.proposals;
+        let body_parsing = self.body_parsing;
+        #[cfg(feature = "std")]
+        let module_cache = self.module_cache;
+        #[cfg(feature = "signing")]
+        let signatures = self.signatures.take();
+        let mut engine = self.build_engine()?;
+        engine.set_store_limits(store_limits);
+        engine.set_trap_handlers(trap_handlers);
+        engine.set_fp_config(fp_config);
+        engine.set_startup_profiling(profile_startup);
+        engine.set_body_parsing(body_parsing);
+        if let Some(proposals) = proposals {
+            engine.set_allowed_proposals(proposals);
+        }
+        #[cfg(feature = "std")]
+        engine.set_module_cache_policy(module_cache);
+        #[cfg(feature = "signing")]
+        engine.set_signature_policy(signatures);
+        Ok(engine)
+    }
+
+    /// Create the engine for the configured context, preset or ASIL level
+    fn build_engine(self) -> Result<CapabilityAwareEngine> {
         // Priority order: custom_context > preset > asil_level > default QM
 
         if let Some(context) = self.custom_context {
@@ -149,3 +268,516 @@ fn default() -> Self {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::values::Value;
+
+    use super::*;
+    use crate::engine::{
+        CapabilityEngine,
+        InstanceHandle,
+    };
+
+    /// Module exporting `many`, which returns the i32 constants `0..count`
+    fn many_results_module(count: u8) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: () -> (i32 * count)
+        wasm.extend_from_slice(&[0x01, count + 4, 0x01, 0x60, 0x00, count]);
+        wasm.extend(core::iter::repeat_n(0x7f, count as usize));
+        // Function and export sections
+        wasm.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        wasm.extend_from_slice(&[0x07, 0x08, 0x01, 0x04, b'm', b'a', b'n', b'y', 0x00, 0x00]);
+        // Code section: i32.const 0 .. i32.const count-1
+        let body_len = 2 * count + 2;
+        wasm.extend_from_slice(&[0x0a, body_len + 2, 0x01, body_len, 0x00]);
+        for i in 0..count {
+            wasm.extend_from_slice(&[0x41, i]);
+        }
+        wasm.push(0x0b);
+        wasm
+    }
+
+    #[test]
+    fn test_results_beyond_inline_capacity() -> Result<()> {
+        let mut engine = EngineBuilder::qm().build()?;
+        let module = engine.load_module(&many_results_module(20))?;
+        let instance = engine.instantiate(module)?;
+
+        let results = engine.execute(instance, "many", &[])?;
+        assert_eq!(results.len(), 20);
+        assert!(results.iter().zip(0..).all(|(v, i)| *v == Value::I32(i)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_limits_reject_result_arity() -> Result<()> {
+        let limits = StoreLimits::new().with_max_results(8)?;
+        let mut engine = EngineBuilder::qm().with_store_limits(limits).build()?;
+        assert_eq!(engine.store_limits().max_results(), 8);
+
+        let module = engine.load_module(&many_results_module(20))?;
+        let instance = engine.instantiate(module)?;
+        assert!(engine.execute(instance, "many", &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fp_mode_canonicalizes_and_flushes() -> Result<()> {
+        use wrt_foundation::values::FloatBits32;
+
+        use crate::fp_mode::{
+            DenormalMode,
+            CANONICAL_NAN_F32,
+        };
+
+        // Module exporting `div`, (f32, f32) -> f32
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7d,
+            0x7d, 0x01, 0x7d, 0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, b'd', b'i', b'v',
+            0x00, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x95, 0x0b,
+        ];
+        let f32_value = |value: f32| Value::F32(FloatBits32(value.to_bits()));
+
+        let mut engine = EngineBuilder::qm().with_fp_config(FpConfig::deterministic()).build()?;
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+
+        let results = engine.execute(instance, "div", &[f32_value(0.0), f32_value(0.0)])?;
+        assert_eq!(results, vec![Value::F32(FloatBits32(CANONICAL_NAN_F32))]);
+
+        let tiny = [f32_value(f32::MIN_POSITIVE), f32_value(2.0)];
+        let results = engine.execute(instance, "div", &tiny)?;
+        assert_eq!(results, vec![f32_value(f32::MIN_POSITIVE / 2.0)]);
+
+        let legacy = FpConfig::new().with_denormals(DenormalMode::FlushToZero);
+        engine.set_instance_fp_config(instance, Some(legacy))?;
+        let results = engine.execute(instance, "div", &tiny)?;
+        assert_eq!(results, vec![f32_value(0.0)]);
+
+        let report = engine.fp_mode_report(instance)?;
+        assert!(report.overridden && !report.preserves_denormals());
+        assert_eq!(report.stats.nans_canonicalized, 1);
+        assert_eq!(report.stats.values_flushed, 1);
+        assert!(engine.fp_mode_report(InstanceHandle::from_index(7)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_startup_profiling_reports_phases() -> Result<()> {
+        use crate::startup_profile::StartupPhase;
+
+        // Module with an empty start function
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            0x03, 0x02, 0x01, 0x00, 0x08, 0x01, 0x00, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b,
+        ];
+
+        let mut engine = EngineBuilder::qm().build()?;
+        let module = engine.load_module(&wasm)?;
+        engine.instantiate(module)?;
+        assert!(engine.startup_report(module).is_none());
+
+        let mut engine = EngineBuilder::qm().with_startup_profiling(true).build()?;
+        let module = engine.load_module(&wasm)?;
+        let report = engine.startup_report(module).copied().unwrap();
+        assert!(report.phase(StartupPhase::Decode).is_some());
+        assert!(report.phase(StartupPhase::Instantiate).is_none());
+
+        engine.instantiate(module)?;
+        let report = engine.startup_report(module).unwrap();
+        assert_eq!(report.phases().count(), StartupPhase::ALL.len());
+        assert!(report.total_duration().is_some_and(|total| !total.is_zero()));
+        assert!(report.to_string().contains("start"));
+        assert_eq!(engine.startup_reports().count(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn test_signature_policy_rejects_unsigned_and_tampered_modules() -> Result<()> {
+        use wrt_format::signing::ModuleSigner;
+
+        let signer = ModuleSigner::from_seed(&[3; 32]);
+        let policy = SignaturePolicy::new(vec![signer.trust_anchor()]);
+        let wasm = many_results_module(2);
+        let signed = signer.sign(&wasm)?;
+
+        // Without a policy unsigned and signed modules load
+        let mut engine = EngineBuilder::qm().build()?;
+        engine.load_module(&wasm)?;
+        engine.load_module(&signed)?;
+
+        let mut engine = EngineBuilder::qm().with_signature_policy(policy).build()?;
+        let module = engine.load_module(&signed)?;
+        let instance = engine.instantiate(module)?;
+        assert_eq!(engine.execute(instance, "many", &[])?, vec![Value::I32(0), Value::I32(1)]);
+
+        assert!(engine.load_module(&wasm).is_err());
+        let mut tampered = signed.clone();
+        // Second constant of the body
+        tampered[wasm.len() - 2] = 5;
+        assert!(engine.load_module(&tampered).is_err());
+        let untrusted = ModuleSigner::from_seed(&[4; 32]).sign(&wasm)?;
+        assert!(engine.load_module(&untrusted).is_err());
+
+        engine.set_signature_policy(None);
+        engine.load_module(&wasm)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_calls_reuse_scratch_buffers() -> Result<()> {
+        // Module exporting `add`: (i32, i32) -> i32
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f,
+            0x7f, 0x01, 0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, b'a', b'd', b'd',
+            0x00, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
+        ];
+
+        let limits = StoreLimits::new().with_scratch_capacity(16)?;
+        let mut engine = EngineBuilder::qm().with_store_limits(limits).build()?;
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+
+        // The first call fills the pools
+        engine.execute(instance, "add", &[Value::I32(1), Value::I32(2)])?;
+        let warm = engine.scratch_stats();
+
+        for i in 0..100 {
+            let results = engine.execute(instance, "add", &[Value::I32(i), Value::I32(1)])?;
+            assert_eq!(results, vec![Value::I32(i + 1)]);
+        }
+        let stats = engine.scratch_stats();
+        assert_eq!(stats.allocated, warm.allocated);
+        assert!(stats.reused >= warm.reused + 400);
+        assert_eq!(stats.discarded, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_batch_returns_per_call_results() -> Result<()> {
+        // Module exporting `add` and `div`, both (i32, i32) -> i32
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f,
+            0x7f, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x00, 0x07, 0x0d, 0x02, 0x03, b'a', b'd',
+            b'd', 0x00, 0x00, 0x03, b'd', b'i', b'v', 0x00, 0x01, 0x0a, 0x11, 0x02, 0x07, 0x00,
+            0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6d, 0x0b,
+        ];
+
+        let mut engine = EngineBuilder::qm().build()?;
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+
+        let results = engine.call_batch(
+            instance,
+            &[
+                ("add", &[Value::I32(1), Value::I32(2)]),
+                ("div", &[Value::I32(1), Value::I32(0)]),
+                ("missing", &[]),
+                ("div", &[Value::I32(6), Value::I32(3)]),
+            ],
+        )?;
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().ok(), Some(&vec![Value::I32(3)]));
+        assert!(results[1].is_err());
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_ref().ok(), Some(&vec![Value::I32(2)]));
+
+        assert!(engine.call_batch(InstanceHandle::from_index(7), &[("add", &[])]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_pure_host_import_is_memoized() -> Result<()> {
+        use std::sync::{
+            atomic::{
+                AtomicU32,
+                Ordering,
+            },
+            Arc,
+        };
+
+        use wrt_host::{
+            CallbackRegistry,
+            HostFunctionHandler,
+            MemoScope,
+        };
+
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        // Module importing `env::config: () -> i32` and exporting `run`,
+        // which returns `config() + config()`
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01,
+            0x7f, 0x02, 0x0e, 0x01, 0x03, b'e', b'n', b'v', 0x06, b'c', b'o', b'n', b'f', b'i',
+            b'g', 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n',
+            0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x10, 0x00, 0x10, 0x00, 0x6a, 0x0b,
+        ];
+
+        let mut registry = CallbackRegistry::new();
+        let handler = HostFunctionHandler::new(|_| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![Value::I32(21)])
+        });
+        registry.register_pure_host_function("env", "config", handler, MemoScope::Call);
+        let registry = Arc::new(registry);
+
+        let mut engine = EngineBuilder::qm().build()?;
+        engine.set_host_registry(registry.clone());
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+
+        assert_eq!(engine.execute(instance, "run", &[])?, vec![Value::I32(42)]);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        assert_eq!(engine.execute(instance, "run", &[])?, vec![Value::I32(42)]);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+        assert_eq!(registry.memo_stats().hits, 2);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_host_objects_round_trip_through_guest() -> Result<()> {
+        use std::sync::Arc;
+
+        use wrt_host::CallbackRegistry;
+
+        // Module exporting `id: (externref) -> externref`
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x6f,
+            0x01, 0x6f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x06, 0x01, 0x02, b'i', b'd', 0x00, 0x00,
+            0x0a, 0x06, 0x01, 0x04, 0x00, 0x20, 0x00, 0x0b,
+        ];
+
+        let registry = Arc::new(CallbackRegistry::new());
+        let mut engine = EngineBuilder::qm().build()?;
+        engine.set_host_registry(registry.clone());
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+
+        let handle = engine.host_object_ref(instance, String::from("socket"))?;
+        let results = engine.execute(instance, "id", &[handle])?;
+        let object = registry.host_objects().downcast_value::<String>(&results[0])?;
+        assert_eq!(object.as_deref().map(String::as_str), Some("socket"));
+
+        engine.drop_instance(instance)?;
+        assert!(registry.host_objects().is_empty());
+        assert!(engine.execute(instance, "id", &[Value::ExternRef(None)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_host_functions_see_calling_instance_context() -> Result<()> {
+        use std::sync::Arc;
+
+        use wrt_host::{
+            CallbackRegistry,
+            HostFunctionHandler,
+        };
+
+        /// Host state of one instance
+        struct Config {
+            value: i32,
+            reads: u32,
+        }
+
+        // Module importing `env::config: () -> i32` and exporting `run`,
+        // which returns `config() + config()`
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01,
+            0x7f, 0x02, 0x0e, 0x01, 0x03, b'e', b'n', b'v', 0x06, b'c', b'o', b'n', b'f', b'i',
+            b'g', 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n',
+            0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x10, 0x00, 0x10, 0x00, 0x6a, 0x0b,
+        ];
+
+        let mut registry = CallbackRegistry::new();
+        let contexts = Arc::clone(registry.instance_contexts());
+        let handler = HostFunctionHandler::new(move |_| {
+            let value = contexts.with_current(|config: &mut Config| {
+                config.reads += 1;
+                config.value
+            })?;
+            Ok(vec![Value::I32(value)])
+        });
+        registry.register_host_function("env", "config", handler);
+
+        let mut engine = EngineBuilder::qm().build()?;
+        engine.set_host_registry(Arc::new(registry));
+        let module = engine.load_module(&wasm)?;
+        let first = engine.instantiate(module)?;
+        let second = engine.instantiate(module)?;
+        engine.set_instance_context(first, Config { value: 1, reads: 0 })?;
+        engine.set_instance_context(second, Config { value: 10, reads: 0 })?;
+
+        assert_eq!(engine.execute(first, "run", &[])?, vec![Value::I32(2)]);
+        assert_eq!(engine.execute(second, "run", &[])?, vec![Value::I32(20)]);
+        assert_eq!(engine.with_instance_context(first, |config: &mut Config| config.reads)?, 2);
+
+        engine.drop_instance(second)?;
+        assert!(engine.with_instance_context(second, |config: &mut Config| config.reads).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_module_cache_reuses_decoded_modules() -> Result<()> {
+        use crate::cache_policy::CachePolicy;
+
+        let wasm = many_results_module(2);
+        let policy = CachePolicy::unbounded().with_max_entries(4);
+        let mut engine = EngineBuilder::qm().with_module_cache(policy).build()?;
+        let first = engine.load_module(&wasm)?;
+        let second = engine.load_module(&wasm)?;
+        assert_ne!(first, second);
+        let instance = engine.instantiate(second)?;
+        assert_eq!(engine.execute(instance, "many", &[])?.len(), 2);
+
+        let (name, metrics) = &engine.cache_metrics()[0];
+        assert_eq!(name, "modules");
+        assert_eq!((metrics.hits, metrics.misses, metrics.entries), (1, 1, 1));
+        assert_eq!(metrics.bytes, wasm.len());
+
+        engine.flush_caches();
+        engine.load_module(&wasm)?;
+        assert_eq!(engine.cache_metrics()[0].1.misses, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_rejects_disabled_proposals() -> Result<()> {
+        use crate::engine::Proposal;
+
+        let wasm = wat::parse_str(
+            r#"(module
+                 (func $f (result i32) (i32.const 7))
+                 (func (export "run") (result i32) (return_call $f)))"#,
+        )
+        .unwrap();
+
+        let mut engine = EngineBuilder::asil_d().build()?;
+        let report = engine.check_proposals(&wasm)?;
+        assert_eq!(report.disabled(), ProposalSet::none().with(Proposal::TailCall));
+        assert_eq!(report.uses().next().map(|usage| usage.function), Some(Some(1)));
+        assert!(engine.load_module(&wasm).unwrap_err().is_validation_error());
+
+        let allowed = ProposalSet::none().with(Proposal::TailCall);
+        let engine = EngineBuilder::asil_d().with_allowed_proposals(allowed).build()?;
+        assert!(engine.check_proposals(&wasm)?.is_ok());
+        #[cfg(feature = "tail-call")]
+        {
+            let mut engine = engine;
+            engine.load_module(&wasm)?;
+        }
+        assert!(EngineBuilder::qm().build()?.check_proposals(&wasm)?.is_ok());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_lazy_bodies_parse_on_first_call() -> Result<()> {
+        use crate::module::{
+            BodyParsing,
+            Module,
+        };
+
+        let wasm = many_results_module(3);
+        let decoded = wrt_decoder::decoder::decode_module(&wasm)?;
+        let module = Module::from_wrt_module_with(&decoded, BodyParsing::Lazy)?;
+        let function = module.functions.last().unwrap();
+        let lazy = function.lazy_body.clone().unwrap();
+        assert!(function.body.is_empty() && !lazy.is_parsed());
+        assert!(!function.is_import_stub());
+        assert!(!function.expr()?.is_empty());
+        assert!(lazy.is_parsed());
+
+        let mut engine = EngineBuilder::qm().with_body_parsing(BodyParsing::Lazy).build()?;
+        let instance = engine.load_module(&wasm).and_then(|module| engine.instantiate(module))?;
+        assert_eq!(engine.execute(instance, "many", &[])?.len(), 3);
+
+        let auto = BodyParsing::auto();
+        assert!(!auto.is_lazy(wasm.len()));
+        assert!(auto.is_lazy(crate::module::LAZY_PARSING_THRESHOLD + 1));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_shutdown_calls_export_within_deadline() -> Result<()> {
+        use core::time::Duration;
+
+        use super::super::{
+            ShutdownOutcome,
+            ShutdownPolicy,
+        };
+
+        let flushing = wat::parse_str(
+            r#"(module
+                (global $deadline (export "deadline") (mut i64) (i64.const 0))
+                (func (export "shutdown") (param i64) local.get 0 global.set $deadline)
+                (func (export "work") (result i32) i32.const 1))"#,
+        )
+        .unwrap();
+        let stuck = wat::parse_str(r#"(module (func (export "shutdown") (loop br 0)))"#).unwrap();
+        let plain = wat::parse_str(r#"(module (func (export "work")))"#).unwrap();
+
+        let mut engine = EngineBuilder::qm().build()?;
+        let flushing = engine.load_module(&flushing).and_then(|module| engine.instantiate(module))?;
+        let stuck = engine.load_module(&stuck).and_then(|module| engine.instantiate(module))?;
+        let plain = engine.load_module(&plain).and_then(|module| engine.instantiate(module))?;
+
+        engine.begin_drain();
+        assert!(engine.execute(flushing, "work", &[]).is_err());
+        engine.end_drain();
+        assert_eq!(engine.execute(flushing, "work", &[])?, vec![Value::I32(1)]);
+
+        let policy = ShutdownPolicy::new(Duration::from_millis(50));
+        assert_eq!(engine.shutdown_instance(stuck, policy)?, ShutdownOutcome::TimedOut);
+        assert!(engine.execute(stuck, "shutdown", &[]).is_err());
+
+        let outcomes = engine.shutdown(policy);
+        assert_eq!(outcomes, vec![
+            (plain, ShutdownOutcome::NotExported),
+            (flushing, ShutdownOutcome::Completed),
+        ]);
+        assert!(engine.is_draining());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_parallel_body_parsing_matches_eager() -> Result<()> {
+        use crate::module::{
+            BodyParsing,
+            Module,
+        };
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "log" (func $log (param i32)))
+                (func $double (param i32) (result i32) local.get 0 i32.const 2 i32.mul)
+                (func $square (param i32) (result i32) local.get 0 local.get 0 i32.mul)
+                (func (export "run") (param i32) (result i32)
+                    local.get 0 call $double call $square)
+                (func (export "noop") nop))"#,
+        )
+        .unwrap();
+        let decoded = wrt_decoder::decoder::decode_module(&wasm)?;
+        let eager = Module::from_wrt_module_with(&decoded, BodyParsing::Eager)?;
+        let parallel = Module::from_wrt_module_with(&decoded, BodyParsing::Parallel { threads: 3 })?;
+        assert_eq!(eager.functions.len(), parallel.functions.len());
+        for (eager, parallel) in eager.functions.iter().zip(&parallel.functions) {
+            assert_eq!(eager.body.instructions, parallel.body.instructions);
+            assert_eq!(eager.is_import_stub(), parallel.is_import_stub());
+        }
+
+        let parsing = BodyParsing::Parallel { threads: 4 };
+        let mut engine = EngineBuilder::qm().with_body_parsing(parsing).build()?;
+        let instance = engine.load_module(&wasm).and_then(|module| engine.instantiate(module))?;
+        assert_eq!(engine.execute(instance, "run", &[Value::I32(3)])?, vec![Value::I32(36)]);
+        assert_eq!(BodyParsing::auto().for_binary(wasm.len()), BodyParsing::Eager);
+        Ok(())
+    }
+}
@@ -18,7 +18,15 @@
 use alloc::sync::Arc;
 
 // Import decoder function
-use wrt_decoder::decoder::decode_module;
+use wrt_decoder::{
+    decoder::decode_module,
+    proposals::{
+        check_proposals,
+        Proposal,
+        ProposalReport,
+        ProposalSet,
+    },
+};
 // Import execution configuration from wrt-foundation where it belongs
 use wrt_foundation::execution::{
     extract_resource_limits_from_binary,
@@ -48,13 +56,55 @@
     HostIntegrationLimits,
 };
 
+use super::trap_handler::{
+    TrapAction,
+    TrapContext,
+    TrapHandler,
+    TrapHandlers,
+    TrapKind,
+    MAX_TRAP_RETRIES,
+};
+#[cfg(feature = "std")]
+use super::shutdown::{
+    ShutdownOutcome,
+    ShutdownPolicy,
+    SHUTDOWN_EXPORT,
+};
 use crate::{
     bounded_runtime_infra::BaseRuntimeProvider,
-    module::Module,
+    fp_mode::FpConfig,
+    module::{
+        BodyParsing,
+        MemoryWrapper,
+        Module,
+    },
     module_instance::ModuleInstance,
     prelude::*,
+    scratch::ScratchStats,
     stackless::StacklessEngine,
+    startup_profile::{
+        StartupPhase,
+        StartupProfiler,
+        StartupReport,
+    },
+    store_limits::StoreLimits,
+};
+#[cfg(feature = "std")]
+use crate::cache_policy::{
+    BoundedCache,
+    CacheMetrics,
+    CachePolicy,
+    CacheRegistry,
+    ManagedCache,
+    SharedCache,
+};
+#[cfg(feature = "std")]
+use crate::stackless::canon::{
+    CanonOptions,
+    CoreFuncRef,
 };
+#[cfg(feature = "std")]
+use wrt_foundation::cow_provider::CowSnapshot;
 
 /// Handle for a loaded module
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -163,6 +213,24 @@ pub enum EnginePreset {
     AsilD,
 }
 
+impl EnginePreset {
+    /// Proposals modules loaded by an engine with this preset may use
+    ///
+    /// GC needs a managed heap and is limited to QM; threads add
+    /// nondeterministic interleavings and end at ASIL-A together with
+    /// 64-bit memories. SIMD is allowed up to ASIL-B and tail calls, which
+    /// bound stack use, up to ASIL-C. ASIL-D accepts MVP modules only.
+    pub const fn allowed_proposals(self) -> ProposalSet {
+        match self {
+            Self::QM => ProposalSet::all(),
+            Self::AsilA => ProposalSet::all().without(Proposal::Gc),
+            Self::AsilB => ProposalSet::none().with(Proposal::Simd).with(Proposal::TailCall),
+            Self::AsilC => ProposalSet::none().with(Proposal::TailCall),
+            Self::AsilD => ProposalSet::none(),
+        }
+    }
+}
+
 /// Trait for capability-aware execution engines
 pub trait CapabilityEngine: Send + Sync {
     /// Get the capability context for this engine
@@ -202,6 +270,19 @@ fn execute(
         func: &str,
         args: &[Value],
     ) -> Result<Vec<Value>>;
+
+    /// Execute a sequence of `(function, args)` calls against one instance
+    ///
+    /// Calls run in order and each gets its own result; a failing call does
+    /// not stop the ones after it. Engines can override this to set up the
+    /// execution context once for the whole batch.
+    fn call_batch(
+        &mut self,
+        instance: InstanceHandle,
+        calls: &[(&str, &[Value])],
+    ) -> Result<Vec<Result<Vec<Value>>>> {
+        Ok(calls.iter().map(|&(func, args)| self.execute(instance, func, args)).collect())
+    }
 }
 
 /// Maximum number of modules and instances
@@ -245,10 +326,33 @@ pub struct CapabilityAwareEngine {
     context:           MemoryCapabilityContext,
     /// Engine preset used for resource limit extraction
     preset:            EnginePreset,
+    /// Proposals loaded modules may use
+    allowed_proposals: ProposalSet,
+    /// Keys loaded modules must be signed by, if any
+    #[cfg(feature = "signing")]
+    signature_policy:  Option<super::SignaturePolicy>,
     /// Loaded modules indexed by handle (using DirectMap to avoid serialization stack overflow)
     modules:           DirectMap<ModuleHandle, Arc<Module>, MAX_MODULES>,
     /// Module instances indexed by handle (using DirectMap to avoid serialization stack overflow)
     instances:         DirectMap<InstanceHandle, Arc<ModuleInstance>, MAX_INSTANCES>,
+    /// Module each instance was instantiated from, for restarts
+    instance_modules:  DirectMap<InstanceHandle, ModuleHandle, MAX_INSTANCES>,
+    /// Host recovery policy applied when a call traps
+    trap_handlers:     TrapHandlers,
+    /// Whether loading and instantiation are profiled
+    startup_profiling: bool,
+    /// Startup profile of each module loaded with profiling enabled
+    startup_reports:   DirectMap<ModuleHandle, StartupReport, MAX_MODULES>,
+    /// When function bodies of loaded modules are parsed
+    body_parsing:      BodyParsing,
+    /// Decoded modules by SHA-256 hash of their binary
+    #[cfg(feature = "std")]
+    module_cache:      BoundedCache<[u8; 32], Arc<Module>>,
+    /// Further caches flushed and observed together with the module cache
+    #[cfg(feature = "std")]
+    caches:            CacheRegistry,
+    /// Whether new calls are refused because the engine shuts down
+    draining:          bool,
     /// Next instance index
     next_instance_idx: usize,
     /// Host function registry for WASI and custom host functions
@@ -261,6 +365,9 @@ pub struct CapabilityAwareEngine {
     /// Instance handle to instance_idx mapping for cross-instance calls
     #[cfg(feature = "std")]
     handle_to_idx:     std::collections::HashMap<InstanceHandle, usize>,
+    /// Plugins loaded into the engine, kept loaded for its lifetime
+    #[cfg(feature = "plugins")]
+    plugins:           Vec<crate::plugin::LoadedPlugin>,
 }
 
 impl CapabilityAwareEngine {
@@ -309,8 +416,21 @@ pub fn with_context_and_preset(
             inner: inner_engine,
             context,
             preset,
+            allowed_proposals: preset.allowed_proposals(),
+            #[cfg(feature = "signing")]
+            signature_policy: None,
             modules,
             instances,
+            instance_modules: DirectMap::new(),
+            trap_handlers: TrapHandlers::new(),
+            startup_profiling: false,
+            startup_reports: DirectMap::new(),
+            body_parsing: BodyParsing::Eager,
+            #[cfg(feature = "std")]
+            module_cache: BoundedCache::new(CachePolicy::disabled()),
+            #[cfg(feature = "std")]
+            caches: CacheRegistry::new(),
+            draining: false,
             next_instance_idx: 0,
             host_registry,
             host_manager,
@@ -318,6 +438,8 @@ pub fn with_context_and_preset(
             import_links: std::collections::HashMap::new(),
             #[cfg(feature = "std")]
             handle_to_idx: std::collections::HashMap::new(),
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
         })
     }
 
@@ -341,6 +463,286 @@ pub fn set_host_handler(&mut self, handler: Box<dyn wrt_foundation::HostImportHa
         self.inner.set_host_handler(handler);
     }
 
+    /// Load a plugin and register its interceptor strategies and host
+    /// functions
+    ///
+    /// Plugin strategies run after any strategies already installed on the
+    /// host registry. Plugins are only accepted by the QM and ASIL-A
+    /// presets, since they run native code outside the WebAssembly sandbox.
+    ///
+    /// # Safety
+    ///
+    /// Loading runs the plugin's native code with the privileges of the
+    /// host process; see [`crate::plugin::LoadedPlugin::load`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preset does not allow plugins, the plugin
+    /// cannot be loaded or validated, or one of its host functions is
+    /// already registered.
+    #[cfg(feature = "plugins")]
+    pub unsafe fn load_plugin(&mut self, path: impl AsRef<std::path::Path>) -> Result<crate::plugin::PluginInfo> {
+        use wrt_intercept::LinkInterceptor;
+
+        if !matches!(self.preset, EnginePreset::QM | EnginePreset::AsilA) {
+            return Err(Error::not_supported_unsupported_operation(
+                "Plugins are only supported by the QM and ASIL-A presets",
+            ));
+        }
+        let Some(registry) = self.host_registry.as_ref() else {
+            return Err(Error::not_supported_unsupported_operation(
+                "Host functions not supported in this configuration",
+            ));
+        };
+
+        // SAFETY: forwarded to the caller.
+        let plugin = unsafe { crate::plugin::LoadedPlugin::load(path)? };
+        let host_functions = plugin.host_functions();
+        if host_functions.iter().any(|(module, name, _)| registry.has_host_function(module, name)) {
+            return Err(Error::new(
+                ErrorCategory::System,
+                wrt_error::codes::PLUGIN_LOAD_FAILED,
+                "Plugin host function is already registered",
+            ));
+        }
+
+        let mut interceptor = registry
+            .get_interceptor()
+            .cloned()
+            .unwrap_or_else(|| LinkInterceptor::new("plugins"));
+        for strategy in plugin.interceptor_strategies() {
+            interceptor.add_strategy(strategy);
+        }
+
+        let mut registry = self
+            .host_registry
+            .take()
+            .unwrap_or_default()
+            .with_interceptor(Arc::new(interceptor));
+        for (module, name, handler) in host_functions {
+            registry.register_host_function(&module, &name, handler);
+        }
+        self.inner.set_host_registry(std::sync::Arc::new(registry.clone()));
+        self.host_registry = Some(registry);
+
+        #[cfg(feature = "tracing")]
+        trace!(plugin = %plugin.info().name, "Loaded plugin");
+        let info = plugin.info().clone();
+        self.plugins.push(plugin);
+        Ok(info)
+    }
+
+    /// Plugins loaded into the engine
+    #[cfg(feature = "plugins")]
+    pub fn plugins(&self) -> impl Iterator<Item = &crate::plugin::PluginInfo> {
+        self.plugins.iter().map(crate::plugin::LoadedPlugin::info)
+    }
+
+    /// Set the runtime limits applied to executed functions
+    pub fn set_store_limits(&mut self, limits: StoreLimits) {
+        self.inner.set_store_limits(limits);
+    }
+
+    /// Runtime limits applied to executed functions
+    pub fn store_limits(&self) -> &StoreLimits {
+        self.inner.store_limits()
+    }
+
+    /// Counters of the reusable call buffers
+    ///
+    /// Once the pools are warm, calls reuse buffers instead of allocating, so
+    /// `allocated` stays flat under load while `reused` grows.
+    pub fn scratch_stats(&self) -> ScratchStats {
+        self.inner.scratch_stats()
+    }
+
+    /// Set the floating-point mode of instances without their own
+    pub fn set_fp_config(&mut self, config: FpConfig) {
+        self.inner.set_fp_config(config);
+    }
+
+    /// Floating-point mode of instances without their own
+    pub fn fp_config(&self) -> FpConfig {
+        self.inner.fp_config()
+    }
+
+    /// Set the floating-point mode of one instance, or inherit the engine's
+    /// again if `None`
+    ///
+    /// Lets code ported from native sources keep the numerical behavior it
+    /// was written for while other instances run with standard semantics.
+    #[cfg(feature = "std")]
+    pub fn set_instance_fp_config(
+        &mut self,
+        handle: InstanceHandle,
+        config: Option<FpConfig>,
+    ) -> Result<()> {
+        let instance_idx = *self
+            .handle_to_idx
+            .get(&handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        self.inner.set_instance_fp_config(instance_idx, config);
+        Ok(())
+    }
+
+    /// Report the floating-point mode an instance runs with
+    #[cfg(feature = "std")]
+    pub fn fp_mode_report(&self, handle: InstanceHandle) -> Result<crate::fp_mode::FpModeReport> {
+        self.handle_to_idx
+            .get(&handle)
+            .and_then(|&instance_idx| self.inner.fp_mode_report(instance_idx))
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))
+    }
+
+    /// Replace the trap handlers applied when a call traps
+    pub fn set_trap_handlers(&mut self, handlers: TrapHandlers) {
+        self.trap_handlers = handlers;
+    }
+
+    /// Register the handler for one kind of trap
+    pub fn set_trap_handler(&mut self, kind: TrapKind, handler: impl TrapHandler + 'static) {
+        self.trap_handlers.register(kind, handler);
+    }
+
+    /// Trap handlers applied when a call traps
+    pub fn trap_handlers(&self) -> &TrapHandlers {
+        &self.trap_handlers
+    }
+
+    /// Replace the proposals loaded modules may use, which default to those
+    /// of the engine preset
+    pub fn set_allowed_proposals(&mut self, proposals: ProposalSet) {
+        self.allowed_proposals = proposals;
+    }
+
+    /// Proposals loaded modules may use
+    pub fn allowed_proposals(&self) -> ProposalSet {
+        self.allowed_proposals
+    }
+
+    /// Set the keys modules loaded from now on must be signed by, or accept
+    /// unsigned modules again if `None`
+    ///
+    /// With a policy set, [`CapabilityEngine::load_module`] rejects binaries
+    /// that are unsigned, were modified after signing or are not signed by
+    /// one of the policy's trust anchors.
+    #[cfg(feature = "signing")]
+    pub fn set_signature_policy(&mut self, policy: Option<super::SignaturePolicy>) {
+        self.signature_policy = policy;
+    }
+
+    /// Keys loaded modules must be signed by, if any
+    #[cfg(feature = "signing")]
+    pub fn signature_policy(&self) -> Option<&super::SignaturePolicy> {
+        self.signature_policy.as_ref()
+    }
+
+    /// Check `binary` against the allowed proposals without loading it
+    ///
+    /// [`CapabilityEngine::load_module`] rejects a module whose report is not
+    /// ok; the report lists the offending sections and instructions.
+    pub fn check_proposals(&self, binary: &[u8]) -> Result<ProposalReport> {
+        check_proposals(binary, self.allowed_proposals)
+    }
+
+    /// Enable or disable startup profiling of modules loaded and
+    /// instantiated from now on
+    pub fn set_startup_profiling(&mut self, enabled: bool) {
+        self.startup_profiling = enabled;
+    }
+
+    /// Whether loading and instantiation are profiled
+    pub fn startup_profiling(&self) -> bool {
+        self.startup_profiling
+    }
+
+    /// Startup profile of a module loaded with profiling enabled
+    pub fn startup_report(&self, module: ModuleHandle) -> Option<&StartupReport> {
+        self.startup_reports.get(&module)
+    }
+
+    /// Startup profiles of all modules loaded with profiling enabled
+    pub fn startup_reports(&self) -> impl Iterator<Item = (&ModuleHandle, &StartupReport)> {
+        self.startup_reports.iter()
+    }
+
+    /// Set when function bodies of modules loaded from now on are parsed
+    pub fn set_body_parsing(&mut self, parsing: BodyParsing) {
+        self.body_parsing = parsing;
+    }
+
+    /// When function bodies of loaded modules are parsed
+    pub fn body_parsing(&self) -> BodyParsing {
+        self.body_parsing
+    }
+
+    /// Set the limits of the decoded module cache
+    ///
+    /// The cache is disabled by default. With it enabled, loading a binary
+    /// that was loaded before reuses the decoded module instead of decoding
+    /// it again. Entries are charged the size of their binary.
+    #[cfg(feature = "std")]
+    pub fn set_module_cache_policy(&mut self, policy: CachePolicy) {
+        self.module_cache.set_policy(policy);
+    }
+
+    /// Register a cache to be flushed and observed with the engine's caches
+    #[cfg(feature = "std")]
+    pub fn register_cache(&mut self, name: &str, cache: SharedCache) {
+        self.caches.register(name, cache);
+    }
+
+    /// Counters of the module cache, named `modules`, and of the registered
+    /// caches
+    #[cfg(feature = "std")]
+    pub fn cache_metrics(&self) -> Vec<(String, CacheMetrics)> {
+        let mut metrics = vec![("modules".to_string(), self.module_cache.metrics())];
+        metrics.extend(self.caches.metrics());
+        metrics
+    }
+
+    /// Drop all entries of the module cache and the registered caches
+    ///
+    /// Loaded modules and instances are not affected.
+    #[cfg(feature = "std")]
+    pub fn flush_caches(&mut self) {
+        self.module_cache.flush();
+        self.caches.flush_all();
+    }
+
+    /// Drop expired entries and evict until all caches are within their
+    /// limits, returning the number of entries dropped
+    #[cfg(feature = "std")]
+    pub fn collect_caches(&mut self) -> usize {
+        self.module_cache.collect() + self.caches.collect_all()
+    }
+
+    /// Remaining fuel for execution
+    pub fn remaining_fuel(&self) -> Option<u64> {
+        self.inner.remaining_fuel()
+    }
+
+    /// Add fuel for execution
+    pub fn add_fuel(&self, amount: u64) {
+        self.inner.add_fuel(amount);
+    }
+
+    /// Set the fuel for execution
+    ///
+    /// A call that runs out traps with [`TrapKind::FuelExhausted`]; a
+    /// [`TrapAction::Refuel`] handler continues it where it stopped.
+    pub fn set_fuel(&self, amount: u64) {
+        self.inner.set_fuel(amount);
+    }
+
+    /// Advance `clock` by the fuel charged for executed instructions
+    ///
+    /// Pass the same clock to strategies measuring in fuel, such as
+    /// [`wrt_intercept::FirewallBuilder::with_fuel_clock`].
+    pub fn set_fuel_clock(&mut self, clock: wrt_intercept::FuelClockRef) {
+        self.inner.set_fuel_clock(clock);
+    }
+
     /// Convert engine preset to ASIL execution mode
     fn preset_to_asil_mode(&self) -> ASILExecutionMode {
         match self.preset {
@@ -543,17 +945,55 @@ pub fn register_lowered_function(
         func_idx: usize,
         interface: String,
         function: String,
-        memory_idx: Option<u32>,
-        realloc_idx: Option<u32>,
+        options: CanonOptions,
     ) {
-        self.inner.register_lowered_function(
-            instance_id,
-            func_idx,
-            interface,
-            function,
-            memory_idx,
-            realloc_idx,
-        );
+        self.inner.register_lowered_function(instance_id, func_idx, interface, function, options);
+    }
+
+    /// Resolve a function exported by an instance for use in canonical options
+    #[cfg(feature = "std")]
+    pub fn core_func_ref(
+        &self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+    ) -> Result<CoreFuncRef> {
+        let instance = self
+            .instances
+            .get(&instance_handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let func_idx = instance
+            .module()
+            .find_function_by_name(func_name)
+            .ok_or_else(|| Error::resource_not_found("Function not found"))?;
+        Ok(CoreFuncRef {
+            instance_id: instance_handle.index(),
+            func_idx:    func_idx as usize,
+        })
+    }
+
+    /// Register the canonical options of a function exported by canon.lift
+    ///
+    /// A post-return in the options is called with the results of each call
+    /// to the function, see [`Self::run_post_return`].
+    #[cfg(feature = "std")]
+    pub fn register_lifted_function(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        options: CanonOptions,
+    ) -> Result<()> {
+        let lifted = self.core_func_ref(instance_handle, func_name)?;
+        self.inner.register_lifted_function(lifted.instance_id, lifted.func_idx, options);
+        Ok(())
+    }
+
+    /// Run the post-return of the last call to a lifted function
+    ///
+    /// Call this once the results of the call have been read. Otherwise the
+    /// post-return runs before the next call.
+    #[cfg(feature = "std")]
+    pub fn run_post_return(&mut self) -> Result<()> {
+        self.inner.run_post_return()
     }
 }
 
@@ -563,6 +1003,9 @@ fn capability_context(&self) -> &MemoryCapabilityContext {
     }
 
     fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
+        let mut profiler = StartupProfiler::new(self.startup_profiling.then(StartupReport::new));
+        profiler.phase(StartupPhase::Decode);
+
         // Verify capability for module allocation
         let operation = MemoryOperation::Allocate { size: binary.len() };
         self.context.verify_operation(CrateId::Runtime, &operation)?;
@@ -575,21 +1018,57 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
         // TODO: Apply resource limits to execution context
         // This would integrate with the fuel async executor to enforce limits
 
-        // Decode the module using wrt-decoder (Box to avoid stack overflow)
-        #[cfg(feature = "tracing")]
-        trace!(binary_size = binary.len(), "Decoding module");
-        let decoded = Box::new(decode_module(binary)?);
+        // Reject modules not signed by a trusted key before decoding
+        #[cfg(feature = "signing")]
+        if let Some(policy) = &self.signature_policy {
+            policy.check(binary)?;
+        }
+
+        // Reject proposals the profile disables before decoding
+        let proposals = self.check_proposals(binary)?;
         #[cfg(feature = "tracing")]
-        trace!(types = decoded.types.len(), functions = decoded.functions.len(), "Decode successful, converting to runtime module");
+        if !proposals.is_ok() {
+            warn!("{}", proposals);
+        }
+        proposals.into_result()?;
+
+        // Reuse the decoded module of a binary loaded before
+        #[cfg(feature = "std")]
+        let module_hash = wrt_foundation::sha256::sha256(binary);
+        #[cfg(feature = "std")]
+        let cached = self.module_cache.get(&module_hash);
+        #[cfg(not(feature = "std"))]
+        let cached: Option<Arc<Module>> = None;
 
-        // Convert to runtime module (pass by reference, returns Box<Module>)
-        let runtime_module = Module::from_wrt_module(&*decoded)?;
+        let module_arc = match cached {
+            Some(module) => {
+                profiler.end_phase();
+                module
+            },
+            None => {
+                // Decode the module using wrt-decoder (Box to avoid stack overflow)
+                #[cfg(feature = "tracing")]
+                trace!(binary_size = binary.len(), "Decoding module");
+                let decoded = Box::new(decode_module(binary)?);
+                #[cfg(feature = "tracing")]
+                trace!(types = decoded.types.len(), functions = decoded.functions.len(), "Decode successful, converting to runtime module");
+
+                // Convert to runtime module (pass by reference, returns Box<Module>)
+                profiler.phase(StartupPhase::Convert);
+                let parsing = self.body_parsing.for_binary(binary.len());
+                let module = Arc::new(*Module::from_wrt_module_with(&decoded, parsing)?);
+                profiler.end_phase();
+                #[cfg(feature = "std")]
+                self.module_cache.insert(module_hash, module.clone(), binary.len());
+                module
+            },
+        };
         #[cfg(feature = "tracing")]
         trace!("Conversion successful");
 
         #[cfg(feature = "tracing")]
         trace!(
-            exports_count = runtime_module.exports.len(),
+            exports_count = module_arc.exports.len(),
             "[LOAD_MODULE] Module loaded"
         );
 
@@ -597,10 +1076,10 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
         #[cfg(feature = "std")]
         #[cfg(feature = "tracing")]
         {
-            let elem_count = runtime_module.elements.len();
+            let elem_count = module_arc.elements.len();
             let first_elem_items = if elem_count > 0 {
                 // In std mode, elements is Vec, so get() returns Option<&T>
-                if let Some(elem) = runtime_module.elements.get(0) {
+                if let Some(elem) = module_arc.elements.get(0) {
                     elem.items.len()
                 } else {
                     0
@@ -622,15 +1101,14 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
         // Stack pointer is now initialized early during global creation in from_wrt_module()
         // No need for late initialization here anymore
 
-        // Create and store with unique handle (wrapped in Arc to avoid deep clones)
+        // Store with unique handle (wrapped in Arc to avoid deep clones)
         let handle = ModuleHandle::new();
         #[cfg(feature = "tracing")]
-        trace!("About to Arc::new(*runtime_module)");
-
-        let module_arc = Arc::new(*runtime_module);
-        #[cfg(feature = "tracing")]
         trace!("About to insert into modules map");
         self.modules.insert(handle, module_arc)?;
+        if let Some(report) = profiler.finish() {
+            self.startup_reports.insert(handle, report)?;
+        }
         #[cfg(feature = "tracing")]
         trace!("Insert completed, returning handle");
 
@@ -638,44 +1116,538 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
     }
 
     fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle> {
-        // Get the module (DirectMap returns Option<&Arc<Module>>)
-        let module_arc = self
-            .modules
-            .get(&module_handle)
-            .ok_or_else(|| Error::resource_not_found("Module not found"))?;
+        self.instantiate_module(module_handle, None)
+    }
 
-        // Verify capability for instance allocation
-        let operation = MemoryOperation::Allocate {
-            size: core::mem::size_of::<ModuleInstance>(),
-        };
-        self.context.verify_operation(CrateId::Runtime, &operation)?;
+    #[cfg(feature = "std")]
+    fn link_import(
+        &mut self,
+        module: ModuleHandle,
+        import_module: &str,
+        import_name: &str,
+        provider_instance: InstanceHandle,
+        export_name: &str,
+    ) -> Result<()> {
+        // Convert handles to instance IDs for StacklessEngine
+        // ModuleHandle and InstanceHandle are both wrappers around usize
+        let module_id = module.0 as usize;
+        let provider_id = provider_instance.0 as usize;
 
-        // Create module instance (clone the Arc, not the Module)
-        let instance = ModuleInstance::new(module_arc.clone(), self.next_instance_idx)?;
-        #[cfg(feature = "tracing")]
-        debug!("Created ModuleInstance for idx {}", self.next_instance_idx);
+        // Determine import kind from module's import declarations
+        let import_kind = self.determine_import_kind(module, import_module, import_name)?;
 
-        // Copy globals from module to instance (critical for stack pointer initialization!)
-        #[cfg(feature = "tracing")]
-        debug!("Populating globals from module...");
-        instance.populate_globals_from_module()?;
+        // Add to inner StacklessEngine's import_links
+        // Key: (instance_id, import_module, import_name)
+        // Value: (target_instance_id, export_name)
+        #[cfg(feature = "std")]
+        {
+            self.inner.add_import_link(
+                module_id,
+                import_module.to_string(),
+                import_name.to_string(),
+                provider_id,
+                export_name.to_string(),
+            );
+        }
 
-        // Copy memories from module to instance (critical for memory access!)
-        #[cfg(feature = "tracing")]
-        debug!("Populating memories from module...");
-        instance.populate_memories_from_module()?;
+        // Also store in our own links map for tracking
+        let import_key = if import_module.is_empty() {
+            import_name.to_string()
+        } else {
+            format!("{}::{}", import_module, import_name)
+        };
+
+        let link = ImportLink {
+            provider_instance,
+            export_name: export_name.to_string(),
+            import_kind,
+        };
+
+        self.import_links
+            .entry(module)
+            .or_insert_with(std::collections::HashMap::new)
+            .insert(import_key, link);
 
-        // Copy tables from module to instance (critical for call_indirect!)
         #[cfg(feature = "tracing")]
-        debug!("Populating tables from module...");
-        instance.populate_tables_from_module()?;
+        trace!(
+            import_module = import_module,
+            import_name = import_name,
+            import_kind = ?import_kind,
+            provider_instance = ?provider_instance,
+            export_name = export_name,
+            "Linked import"
+        );
 
-        // Get pending import links EARLY - we need to apply table/memory/global imports
-        // BEFORE element segment initialization
-        #[cfg(feature = "std")]
-        let pending_links = self.import_links.get(&module_handle).cloned();
+        Ok(())
+    }
 
-        // CRITICAL: Apply table/memory/global imports BEFORE element segments!
+    #[cfg(not(feature = "std"))]
+    fn link_import(
+        &mut self,
+        _module: ModuleHandle,
+        _import_module: &str,
+        _import_name: &str,
+        _provider_instance: InstanceHandle,
+        _export_name: &str,
+    ) -> Result<()> {
+        // No-std mode: import linking not yet supported
+        Err(Error::runtime_error("Import linking not supported in no_std mode"))
+    }
+
+    #[cfg(feature = "std")]
+    fn remap_import_links(
+        &mut self,
+        module: ModuleHandle,
+        instance: InstanceHandle,
+    ) -> Result<()> {
+        // Transfer import links from module_id to instance_id
+        // This is needed because link_import is called before instantiation (with module handle)
+        // but runtime lookup uses instance handle
+        let module_id = module.0 as usize;
+        let instance_id = instance.0 as usize;
+
+        #[cfg(feature = "tracing")]
+        trace!(
+            module_id = module_id,
+            instance_id = instance_id,
+            "Remapping import links from module to instance"
+        );
+
+        // Delegate to StacklessEngine's remap method
+        self.inner.remap_import_links(module_id, instance_id);
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn remap_import_links(
+        &mut self,
+        _module: ModuleHandle,
+        _instance: InstanceHandle,
+    ) -> Result<()> {
+        // No-std mode: not supported
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        self.check_not_draining()?;
+        let result = self.execute_handling_traps(instance_handle, func_name, args);
+        self.end_call();
+        result
+    }
+
+    /// Execute calls against one instance, resolving the instance and each
+    /// distinct function once for the whole batch
+    fn call_batch(
+        &mut self,
+        instance_handle: InstanceHandle,
+        calls: &[(&str, &[Value])],
+    ) -> Result<Vec<Result<Vec<Value>>>> {
+        self.check_not_draining()?;
+        let mut target = Some(self.batch_target(instance_handle)?);
+        let mut resolved: Vec<(&str, usize)> = Vec::new();
+        let mut results = Vec::with_capacity(calls.len());
+
+        for &(func_name, args) in calls {
+            let result = match target {
+                None => Err(Error::resource_not_found("Instance not found")),
+                // Host functions called by name take the regular path
+                Some(_) if Self::may_name_host_function(func_name) => {
+                    self.execute_handling_traps(instance_handle, func_name, args)
+                }
+                Some((ref instance, stackless_instance_id)) => {
+                    let func_idx = match resolved.iter().find(|(name, _)| *name == func_name) {
+                        Some(&(_, func_idx)) => Ok(func_idx),
+                        None => instance.module().validate_function_call(func_name).map(|idx| {
+                            resolved.push((func_name, idx as usize));
+                            idx as usize
+                        }),
+                    };
+                    func_idx.and_then(|func_idx| {
+                        self.handling_traps(instance_handle, func_name, |engine| {
+                            engine.inner.reset_call_depth();
+                            let mut call_args = engine.inner.take_args();
+                            call_args.extend_from_slice(args);
+                            engine.inner.execute(stackless_instance_id, func_idx, call_args)
+                        })
+                    })
+                }
+            };
+
+            // A trap handler may have terminated or restarted the instance
+            if result.is_err() && target.is_some() {
+                target = self.batch_target(instance_handle).ok();
+            }
+            self.end_call();
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+}
+
+impl CapabilityAwareEngine {
+    /// Refuse a new call while the engine drains for shutdown
+    fn check_not_draining(&self) -> Result<()> {
+        if self.draining {
+            return Err(Error::new(
+                ErrorCategory::Runtime,
+                wrt_error::codes::INVALID_STATE,
+                "Engine is shutting down",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Finish a top-level call
+    fn end_call(&self) {
+        // Results of pure host functions memoized for this call expire with it
+        #[cfg(feature = "std")]
+        if let Some(ref registry) = self.host_registry {
+            registry.end_memo_call();
+        }
+    }
+
+    /// Instance and stackless engine instance ID a batch executes against
+    fn batch_target(
+        &mut self,
+        instance_handle: InstanceHandle,
+    ) -> Result<(Arc<ModuleInstance>, usize)> {
+        let instance = self
+            .instances
+            .get(&instance_handle)
+            .map(Arc::clone)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+
+        #[cfg(feature = "std")]
+        let stackless_instance_id = self
+            .handle_to_idx
+            .get(&instance_handle)
+            .copied()
+            .ok_or_else(|| Error::resource_not_found("Instance not registered - call instantiate first"))?;
+
+        #[cfg(not(feature = "std"))]
+        let stackless_instance_id = self.inner.set_current_module(Arc::clone(&instance))?;
+
+        Ok((instance, stackless_instance_id))
+    }
+
+    /// Whether `execute_call` may dispatch `func_name` to a host function
+    fn may_name_host_function(func_name: &str) -> bool {
+        cfg!(feature = "std") && (func_name.contains("::") || func_name.starts_with("wasi:"))
+    }
+
+    /// Execute an exported function, applying the registered trap handlers
+    fn execute_handling_traps(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        self.handling_traps(instance_handle, func_name, |engine| {
+            engine.execute_call(instance_handle, func_name, args)
+        })
+    }
+
+    /// Run `call`, applying the registered trap handlers when it traps
+    ///
+    /// A call that ran out of fuel is suspended by the engine; refuelling
+    /// continues it from there, any other action discards it.
+    fn handling_traps<F>(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        call: F,
+    ) -> Result<Vec<Value>>
+    where
+        F: FnOnce(&mut Self) -> Result<Vec<Value>>,
+    {
+        let mut attempt = 0;
+        let mut outcome = call(self);
+        loop {
+            let error = match outcome {
+                Ok(results) => return Ok(results),
+                Err(error) => error,
+            };
+            let Some(kind) = TrapKind::classify(&error) else {
+                return Err(error);
+            };
+
+            let trap = TrapContext {
+                kind,
+                error: &error,
+                instance: instance_handle,
+                function: func_name,
+                attempt,
+            };
+            let action = self.trap_handlers.dispatch(&trap);
+
+            #[cfg(feature = "tracing")]
+            debug!(?kind, ?action, attempt, "[CAP_ENGINE] Applying trap handler");
+
+            if let TrapAction::Refuel(amount) = action {
+                if attempt < MAX_TRAP_RETRIES && self.inner.has_suspended_call() {
+                    self.inner.add_fuel(amount);
+                    attempt += 1;
+                    outcome = self.inner.resume();
+                    continue;
+                }
+            }
+            if kind == TrapKind::FuelExhausted {
+                self.inner.discard_suspended_call();
+            }
+
+            match action {
+                TrapAction::Propagate | TrapAction::Refuel(_) => return Err(error),
+                TrapAction::Terminate => {
+                    self.terminate_instance(instance_handle)?;
+                    return Err(error);
+                }
+                TrapAction::Restart => {
+                    self.restart_instance(instance_handle)?;
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Execute an exported function once, without applying trap handlers
+    fn execute_call(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        // Get the instance (DirectMap returns Option<&Arc<ModuleInstance>>)
+        let instance = self
+            .instances
+            .get(&instance_handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+
+        // Check if this is a directly callable host function by name
+        // This allows test code to directly invoke WASI functions
+        #[cfg(feature = "std")]
+        {
+            // Try to split func_name into module::function format
+            if func_name.contains("::") {
+                let parts: Vec<&str> = func_name.split("::").collect();
+                if parts.len() >= 2 {
+                    let module_str = parts[0];
+                    let func_str = parts[1..].join("::");
+
+                    if let Some(ref registry) = self.host_registry {
+                        if registry.has_host_function(module_str, &func_str) {
+                            #[cfg(feature = "tracing")]
+                            trace!(
+                                module = module_str,
+                                function = func_str,
+                                "Direct call to host function"
+                            );
+
+                            let mut dummy_engine = ();
+                            let results = registry.call_host_function(
+                                &mut dummy_engine as &mut dyn core::any::Any,
+                                module_str,
+                                &func_str,
+                                args.to_vec()
+                            )?;
+
+                            #[cfg(feature = "tracing")]
+                            trace!(result_count = results.len(), "Host function returned");
+                            return Ok(results);
+                        }
+                    }
+                }
+            }
+
+            // Also try wasi: prefix format like "wasi:cli/stdout@0.2.0"
+            // BUT ONLY if this function is NOT exported by the module!
+            // If the module exports it (like wasi:cli/run@0.2.3#run), we should call
+            // the module's implementation, not dispatch to host.
+            let is_module_export = instance.module().find_function_by_name(func_name).is_some();
+            if func_name.starts_with("wasi:") && !is_module_export {
+                // Extract just the function name part (last component after #)
+                if let Some(hash_pos) = func_name.rfind('#') {
+                    let module_part = &func_name[..hash_pos];
+                    let func_part = &func_name[hash_pos+1..];
+
+                    if let Some(ref registry) = self.host_registry {
+                        if registry.has_host_function(module_part, func_part) {
+                            #[cfg(feature = "tracing")]
+                            trace!(
+                                module = module_part,
+                                function = func_part,
+                                "WASI host function call"
+                            );
+
+                            let mut dummy_engine = ();
+                            let results = registry.call_host_function(
+                                &mut dummy_engine as &mut dyn core::any::Any,
+                                module_part,
+                                func_part,
+                                args.to_vec()
+                            )?;
+
+                            #[cfg(feature = "tracing")]
+                            trace!(result_count = results.len(), "Host function returned");
+                            return Ok(results);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Not a host function - execute normally
+        // Find the function by name using the new function resolution
+        let func_idx = instance.module().validate_function_call(func_name)?;
+
+        #[cfg(feature = "tracing")]
+        trace!(
+            func_name = func_name,
+            func_idx = func_idx,
+            instance_idx = instance_handle.index(),
+            "[CAP_ENGINE] Executing function"
+        );
+
+        // Get the stackless engine's instance ID from our mapping
+        // IMPORTANT: We must NOT call set_current_module here - that creates a new ID!
+        // The instance was already registered during instantiate() and import links
+        // were registered with that ID. Using a different ID breaks link lookup.
+        #[cfg(feature = "std")]
+        let stackless_instance_id = self
+            .handle_to_idx
+            .get(&instance_handle)
+            .copied()
+            .ok_or_else(|| Error::resource_not_found("Instance not registered - call instantiate first"))?;
+
+        #[cfg(not(feature = "std"))]
+        let stackless_instance_id = self.inner.set_current_module(Arc::clone(instance))?;
+
+        // Reset call depth before each top-level invocation
+        self.inner.reset_call_depth();
+
+        // Execute the function using the stackless engine's instance ID
+        let mut call_args = self.inner.take_args();
+        call_args.extend_from_slice(args);
+        let results = self.inner.execute(stackless_instance_id, func_idx as usize, call_args)?;
+
+        #[cfg(feature = "tracing")]
+        trace!(results_len = results.len(), "[CAP_ENGINE] Execution completed");
+
+        Ok(results)
+    }
+
+    /// Discard an instance so later calls on its handle fail
+    fn terminate_instance(&mut self, handle: InstanceHandle) -> Result<()> {
+        self.instances.remove(&handle)?;
+        self.instance_modules.remove(&handle)?;
+        #[cfg(feature = "std")]
+        if let Some(instance_idx) = self.handle_to_idx.remove(&handle) {
+            self.inner.remove_instance(instance_idx);
+            if let Some(ref registry) = self.host_registry {
+                registry.clear_memo_instance(instance_idx);
+                registry.release_host_objects(instance_idx);
+                registry.release_instance_context(instance_idx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace an instance with a fresh instantiation of its module
+    ///
+    /// The new instance takes over `handle`. Instances importing from the
+    /// old one stay linked to the state they were instantiated against.
+    fn restart_instance(&mut self, handle: InstanceHandle) -> Result<()> {
+        let module = self
+            .instance_modules
+            .get(&handle)
+            .copied()
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        #[cfg(feature = "std")]
+        let context = self.handle_to_idx.get(&handle).and_then(|&instance_idx| {
+            let registry = self.host_registry.as_ref()?;
+            registry.instance_contexts().take_instance(instance_idx)
+        });
+        self.terminate_instance(handle)?;
+
+        let fresh = self.instantiate(module)?;
+        let instance = self
+            .instances
+            .remove(&fresh)?
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        self.instance_modules.remove(&fresh)?;
+        self.instances.insert(handle, instance)?;
+        self.instance_modules.insert(handle, module)?;
+        #[cfg(feature = "std")]
+        if let Some(instance_idx) = self.handle_to_idx.remove(&fresh) {
+            self.handle_to_idx.insert(handle, instance_idx);
+            if let (Some(registry), Some(context)) = (self.host_registry.as_ref(), context) {
+                registry.instance_contexts().set_instance(instance_idx, context);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CapabilityAwareEngine {
+    /// Instantiate a module, optionally with memory 0 already initialized
+    ///
+    /// `spawned_memory` replaces memory 0 and the data segments targeting it.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn instantiate_module(
+        &mut self,
+        module_handle: ModuleHandle,
+        spawned_memory: Option<MemoryWrapper>,
+    ) -> Result<InstanceHandle> {
+        // Get the module (DirectMap returns Option<&Arc<Module>>)
+        let module_arc = self
+            .modules
+            .get(&module_handle)
+            .ok_or_else(|| Error::resource_not_found("Module not found"))?;
+
+        // Verify capability for instance allocation
+        let operation = MemoryOperation::Allocate {
+            size: core::mem::size_of::<ModuleInstance>(),
+        };
+        self.context.verify_operation(CrateId::Runtime, &operation)?;
+
+        let mut profiler = StartupProfiler::new(self.startup_profiling.then(|| {
+            self.startup_reports.get(&module_handle).copied().unwrap_or_default()
+        }));
+        profiler.phase(StartupPhase::Instantiate);
+
+        // Create module instance (clone the Arc, not the Module)
+        let instance = ModuleInstance::new(module_arc.clone(), self.next_instance_idx)?;
+        #[cfg(feature = "tracing")]
+        debug!("Created ModuleInstance for idx {}", self.next_instance_idx);
+
+        // Copy globals from module to instance (critical for stack pointer initialization!)
+        #[cfg(feature = "tracing")]
+        debug!("Populating globals from module...");
+        instance.populate_globals_from_module()?;
+
+        // Copy memories from module to instance (critical for memory access!)
+        #[cfg(feature = "tracing")]
+        debug!("Populating memories from module...");
+        instance.populate_memories_from_module()?;
+
+        // Copy tables from module to instance (critical for call_indirect!)
+        #[cfg(feature = "tracing")]
+        debug!("Populating tables from module...");
+        instance.populate_tables_from_module()?;
+
+        // Get pending import links EARLY - we need to apply table/memory/global imports
+        // BEFORE element segment initialization
+        profiler.phase(StartupPhase::LinkImports);
+        #[cfg(feature = "std")]
+        let pending_links = self.import_links.get(&module_handle).cloned();
+
+        // CRITICAL: Apply table/memory/global imports BEFORE element segments!
         // Tables must be available before element segments try to populate them.
         // Function imports can be applied later (resolved at call time).
         #[cfg(feature = "std")]
@@ -764,11 +1736,19 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
         }
 
         // Initialize data segments into instance memory (critical for static data!)
+        profiler.phase(StartupPhase::InitSegments);
         #[cfg(feature = "std")]
         {
             #[cfg(feature = "tracing")]
             debug!("Initializing data segments...");
-            instance.initialize_data_segments()?;
+            match spawned_memory {
+                // A memory spawned from a snapshot already holds its contents
+                Some(memory) => {
+                    instance.set_memory(0, memory)?;
+                    instance.initialize_data_segments_except(0)?;
+                },
+                None => instance.initialize_data_segments()?,
+            }
         }
 
         // Initialize element segments into tables (critical for call_indirect!)
@@ -781,6 +1761,7 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
         }
 
         // Don't clone! Cloning creates a fresh empty instance, losing all our populate work
+        profiler.phase(StartupPhase::LinkImports);
         let instance_arc = Arc::new(instance);
 
         // Register with inner engine
@@ -790,6 +1771,7 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
         // Store mapping (wrapped in Arc to avoid deep clones)
         let handle = InstanceHandle::from_index(instance_idx);
         self.instances.insert(handle, instance_arc)?;
+        self.instance_modules.insert(handle, module_handle)?;
 
         // Store handle -> instance_idx mapping for cross-instance calls
         #[cfg(feature = "std")]
@@ -844,12 +1826,14 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
         }
 
         // Run start function if present
+        profiler.end_phase();
         if let Some(start_idx) = module_arc.start {
             #[cfg(feature = "tracing")]
             trace!(
                 start_idx = start_idx,
                 "Module has start function, running automatically"
             );
+            profiler.phase(StartupPhase::Start);
             self.inner.execute(instance_idx, start_idx as usize, vec![])?;
             #[cfg(feature = "tracing")]
             trace!("Start function completed");
@@ -857,251 +1841,257 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
             #[cfg(feature = "tracing")]
             trace!("No start function in module");
         }
+        if let Some(report) = profiler.finish() {
+            self.startup_reports.insert(module_handle, report)?;
+        }
 
         Ok(handle)
     }
 
-    #[cfg(feature = "std")]
-    fn link_import(
+    /// Instantiate `module` with its memory spawned from `snapshot`
+    ///
+    /// The snapshot, typically taken with [`Self::snapshot_memory`] from an
+    /// initialized instance of the same module, stands in for the data
+    /// segments of memory 0. Instances spawned from one snapshot share its
+    /// pages until they write to them. The start function still runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module imports a memory or defines none, if
+    /// the snapshot does not fit its initial memory, or if instantiation
+    /// fails
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "macos")))]
+    pub fn instantiate_from_snapshot(
         &mut self,
-        module: ModuleHandle,
-        import_module: &str,
-        import_name: &str,
-        provider_instance: InstanceHandle,
-        export_name: &str,
-    ) -> Result<()> {
-        // Convert handles to instance IDs for StacklessEngine
-        // ModuleHandle and InstanceHandle are both wrappers around usize
-        let module_id = module.0 as usize;
-        let provider_id = provider_instance.0 as usize;
-
-        // Determine import kind from module's import declarations
-        let import_kind = self.determine_import_kind(module, import_module, import_name)?;
+        module_handle: ModuleHandle,
+        snapshot: &CowSnapshot,
+    ) -> Result<InstanceHandle> {
+        use crate::module::RuntimeImportDesc;
 
-        // Add to inner StacklessEngine's import_links
-        // Key: (instance_id, import_module, import_name)
-        // Value: (target_instance_id, export_name)
-        #[cfg(feature = "std")]
-        {
-            self.inner.add_import_link(
-                module_id,
-                import_module.to_string(),
-                import_name.to_string(),
-                provider_id,
-                export_name.to_string(),
-            );
+        let module = self
+            .modules
+            .get(&module_handle)
+            .ok_or_else(|| Error::resource_not_found("Module not found"))?;
+        if module.import_types.iter().any(|import| matches!(import, RuntimeImportDesc::Memory(_))) {
+            return Err(Error::validation_error(
+                "Cannot spawn an imported memory from a snapshot",
+            ));
         }
+        let ty = module
+            .memories
+            .first()
+            .ok_or_else(|| Error::resource_not_found("Module defines no memory to spawn"))?
+            .0
+            .ty;
+        let memory = Memory::from_snapshot(ty, snapshot)?;
+        self.instantiate_module(module_handle, Some(MemoryWrapper(Arc::from(memory))))
+    }
 
-        // Also store in our own links map for tracking
-        let import_key = if import_module.is_empty() {
-            import_name.to_string()
-        } else {
-            format!("{}::{}", import_module, import_name)
-        };
-
-        let link = ImportLink {
-            provider_instance,
-            export_name: export_name.to_string(),
-            import_kind,
-        };
-
-        self.import_links
-            .entry(module)
-            .or_insert_with(std::collections::HashMap::new)
-            .insert(import_key, link);
-
-        #[cfg(feature = "tracing")]
-        trace!(
-            import_module = import_module,
-            import_name = import_name,
-            import_kind = ?import_kind,
-            provider_instance = ?provider_instance,
-            export_name = export_name,
-            "Linked import"
-        );
+    /// Capture memory 0 of `instance` as a snapshot to spawn instances from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance does not exist or has no memory
+    #[cfg(feature = "std")]
+    pub fn snapshot_memory(&self, instance: InstanceHandle) -> Result<CowSnapshot> {
+        self.get_instance(instance)?.memory(0)?.0.snapshot()
+    }
 
-        Ok(())
+    /// Get an instance by handle (for debugging)
+    #[cfg(feature = "std")]
+    pub fn get_instance(&self, handle: InstanceHandle) -> Result<&Arc<ModuleInstance>> {
+        self.instances.get(&handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))
     }
 
-    #[cfg(not(feature = "std"))]
-    fn link_import(
-        &mut self,
-        _module: ModuleHandle,
-        _import_module: &str,
-        _import_name: &str,
-        _provider_instance: InstanceHandle,
-        _export_name: &str,
-    ) -> Result<()> {
-        // No-std mode: import linking not yet supported
-        Err(Error::runtime_error("Import linking not supported in no_std mode"))
+    /// Discard an instance and release the host objects it owns
+    ///
+    /// Later calls on `handle` fail.
+    pub fn drop_instance(&mut self, handle: InstanceHandle) -> Result<()> {
+        self.terminate_instance(handle)
     }
 
+    /// Wrap a host object in an `externref` to pass to an instance
+    ///
+    /// The object is stored in the host registry's object table and released
+    /// when the instance is terminated or restarted. Guests get the object
+    /// back to the host as an `externref`, which host functions resolve with
+    /// [`wrt_host::HostObjectTable::downcast_value`].
     #[cfg(feature = "std")]
-    fn remap_import_links(
-        &mut self,
-        module: ModuleHandle,
-        instance: InstanceHandle,
-    ) -> Result<()> {
-        // Transfer import links from module_id to instance_id
-        // This is needed because link_import is called before instantiation (with module handle)
-        // but runtime lookup uses instance handle
-        let module_id = module.0 as usize;
-        let instance_id = instance.0 as usize;
-
-        #[cfg(feature = "tracing")]
-        trace!(
-            module_id = module_id,
-            instance_id = instance_id,
-            "Remapping import links from module to instance"
-        );
+    pub fn host_object_ref<T: core::any::Any + Send + Sync>(
+        &self,
+        handle: InstanceHandle,
+        object: T,
+    ) -> Result<Value> {
+        let instance_idx = *self
+            .handle_to_idx
+            .get(&handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let registry = self
+            .host_registry
+            .as_ref()
+            .ok_or_else(|| Error::resource_not_found("No host registry to store host objects in"))?;
+        let reference =
+            registry.host_objects().insert_owned(Arc::new(object), Some(instance_idx))?;
+        Ok(Value::ExternRef(Some(reference)))
+    }
 
-        // Delegate to StacklessEngine's remap method
-        self.inner.remap_import_links(module_id, instance_id);
+    /// Stop accepting new calls, so instances can be shut down without a
+    /// guest changing their state in between
+    ///
+    /// A call suspended at the epoch deadline is discarded. Loading and
+    /// instantiating modules is still possible.
+    pub fn begin_drain(&mut self) {
+        self.draining = true;
+        #[cfg(feature = "std")]
+        self.inner.discard_suspended_call();
+    }
 
-        Ok(())
+    /// Accept new calls again after [`Self::begin_drain`]
+    pub fn end_drain(&mut self) {
+        self.draining = false;
     }
 
-    #[cfg(not(feature = "std"))]
-    fn remap_import_links(
-        &mut self,
-        _module: ModuleHandle,
-        _instance: InstanceHandle,
-    ) -> Result<()> {
-        // No-std mode: not supported
-        Ok(())
+    /// Whether new calls are refused
+    pub fn is_draining(&self) -> bool {
+        self.draining
     }
 
-    fn execute(
+    /// Give an instance the chance to persist its state, then terminate it
+    ///
+    /// Calls the instance's [`SHUTDOWN_EXPORT`] if it has a usable one and
+    /// interrupts it once `policy.deadline` has passed. The instance is
+    /// terminated in every case, so the handle is invalid afterwards. Trap
+    /// handlers are not applied to the `shutdown` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance does not exist.
+    #[cfg(feature = "std")]
+    pub fn shutdown_instance(
         &mut self,
-        instance_handle: InstanceHandle,
-        func_name: &str,
-        args: &[Value],
-    ) -> Result<Vec<Value>> {
-        // Get the instance (DirectMap returns Option<&Arc<ModuleInstance>>)
+        handle: InstanceHandle,
+        policy: ShutdownPolicy,
+    ) -> Result<ShutdownOutcome> {
         let instance = self
             .instances
-            .get(&instance_handle)
+            .get(&handle)
+            .map(Arc::clone)
             .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
 
-        // Check if this is a directly callable host function by name
-        // This allows test code to directly invoke WASI functions
-        #[cfg(feature = "std")]
-        {
-            // Try to split func_name into module::function format
-            if func_name.contains("::") {
-                let parts: Vec<&str> = func_name.split("::").collect();
-                if parts.len() >= 2 {
-                    let module_str = parts[0];
-                    let func_str = parts[1..].join("::");
-
-                    if let Some(ref registry) = self.host_registry {
-                        if registry.has_host_function(module_str, &func_str) {
-                            #[cfg(feature = "tracing")]
-                            trace!(
-                                module = module_str,
-                                function = func_str,
-                                "Direct call to host function"
-                            );
-
-                            let mut dummy_engine = ();
-                            let results = registry.call_host_function(
-                                &mut dummy_engine as &mut dyn core::any::Any,
-                                module_str,
-                                &func_str,
-                                args.to_vec()
-                            )?;
-
-                            #[cfg(feature = "tracing")]
-                            trace!(result_count = results.len(), "Host function returned");
-                            return Ok(results);
-                        }
+        let outcome = match Self::shutdown_args(instance.module(), policy) {
+            None => ShutdownOutcome::NotExported,
+            Some(args) => {
+                // Interrupt the export at the deadline with an epoch deadline
+                // on a counter only a timer thread advances
+                let mut epoch = crate::epoch::EpochDeadline::new();
+                epoch.set_ticks(1);
+                let counter = epoch.counter().clone();
+                let saved = self.inner.replace_epoch(epoch);
+                let (cancel, cancelled) = std::sync::mpsc::channel::<()>();
+                let deadline = policy.deadline;
+                let timer = std::thread::spawn(move || {
+                    if cancelled.recv_timeout(deadline)
+                        == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                    {
+                        counter.increment();
                     }
+                });
+
+                let result = self.execute_call(handle, SHUTDOWN_EXPORT, &args);
+                self.end_call();
+                drop(cancel);
+                let _ = timer.join();
+                self.inner.replace_epoch(saved);
+
+                match result {
+                    Ok(_) => ShutdownOutcome::Completed,
+                    Err(error) if error.code == wrt_error::codes::EXECUTION_TIMEOUT => ShutdownOutcome::TimedOut,
+                    Err(_) => ShutdownOutcome::Trapped,
                 }
-            }
-
-            // Also try wasi: prefix format like "wasi:cli/stdout@0.2.0"
-            // BUT ONLY if this function is NOT exported by the module!
-            // If the module exports it (like wasi:cli/run@0.2.3#run), we should call
-            // the module's implementation, not dispatch to host.
-            let is_module_export = instance.module().find_function_by_name(func_name).is_some();
-            if func_name.starts_with("wasi:") && !is_module_export {
-                // Extract just the function name part (last component after #)
-                if let Some(hash_pos) = func_name.rfind('#') {
-                    let module_part = &func_name[..hash_pos];
-                    let func_part = &func_name[hash_pos+1..];
+            },
+        };
 
-                    if let Some(ref registry) = self.host_registry {
-                        if registry.has_host_function(module_part, func_part) {
-                            #[cfg(feature = "tracing")]
-                            trace!(
-                                module = module_part,
-                                function = func_part,
-                                "WASI host function call"
-                            );
+        self.terminate_instance(handle)?;
+        Ok(outcome)
+    }
 
-                            let mut dummy_engine = ();
-                            let results = registry.call_host_function(
-                                &mut dummy_engine as &mut dyn core::any::Any,
-                                module_part,
-                                func_part,
-                                args.to_vec()
-                            )?;
+    /// Drain the engine and shut down all instances, newest first
+    ///
+    /// Instances are shut down in reverse instantiation order, so an
+    /// instance shuts down before the instances it imports from. The engine
+    /// keeps refusing calls afterwards.
+    #[cfg(feature = "std")]
+    pub fn shutdown(&mut self, policy: ShutdownPolicy) -> Vec<(InstanceHandle, ShutdownOutcome)> {
+        self.begin_drain();
+        let mut handles: Vec<(InstanceHandle, usize)> =
+            self.handle_to_idx.iter().map(|(&handle, &idx)| (handle, idx)).collect();
+        handles.sort_unstable_by_key(|&(_, idx)| core::cmp::Reverse(idx));
+
+        handles
+            .into_iter()
+            .filter_map(|(handle, _)| {
+                self.shutdown_instance(handle, policy).ok().map(|outcome| (handle, outcome))
+            })
+            .collect()
+    }
 
-                            #[cfg(feature = "tracing")]
-                            trace!(result_count = results.len(), "Host function returned");
-                            return Ok(results);
-                        }
-                    }
-                }
-            }
+    /// Arguments for the `shutdown` export of `module`, or `None` if it has
+    /// no export with a supported signature
+    #[cfg(feature = "std")]
+    fn shutdown_args(module: &Module, policy: ShutdownPolicy) -> Option<Vec<Value>> {
+        let func_idx = module.find_function_by_name(SHUTDOWN_EXPORT)?;
+        let func = module.functions.get(func_idx as usize)?;
+        let ty = module.types.get(func.type_idx as usize)?;
+        let deadline_ms = policy.deadline_ms();
+        match ty.params.as_slice() {
+            [] => Some(Vec::new()),
+            [wrt_foundation::ValueType::I32] => Some(vec![Value::I32(i32::try_from(deadline_ms).unwrap_or(i32::MAX))]),
+            [wrt_foundation::ValueType::I64] => Some(vec![Value::I64(i64::try_from(deadline_ms).unwrap_or(i64::MAX))]),
+            _ => None,
         }
+    }
 
-        // Not a host function - execute normally
-        // Find the function by name using the new function resolution
-        let func_idx = instance.module().validate_function_call(func_name)?;
-
-        #[cfg(feature = "tracing")]
-        trace!(
-            func_name = func_name,
-            func_idx = func_idx,
-            instance_idx = instance_handle.index(),
-            "[CAP_ENGINE] Executing function"
-        );
-
-        // Get the stackless engine's instance ID from our mapping
-        // IMPORTANT: We must NOT call set_current_module here - that creates a new ID!
-        // The instance was already registered during instantiate() and import links
-        // were registered with that ID. Using a different ID breaks link lookup.
-        #[cfg(feature = "std")]
-        let stackless_instance_id = self
+    /// Store `value` in the host context of an instance
+    ///
+    /// Host functions called by the instance reach the value with
+    /// [`wrt_host::InstanceContexts::with_current`] on the registry's
+    /// [`instance_contexts`](wrt_host::CallbackRegistry::instance_contexts).
+    /// A value of the same type is replaced. The context is dropped with the
+    /// instance and carried over when the instance is restarted.
+    #[cfg(feature = "std")]
+    pub fn set_instance_context<T: core::any::Any + Send>(
+        &self,
+        handle: InstanceHandle,
+        value: T,
+    ) -> Result<()> {
+        let instance_idx = *self
             .handle_to_idx
-            .get(&instance_handle)
-            .copied()
-            .ok_or_else(|| Error::resource_not_found("Instance not registered - call instantiate first"))?;
-
-        #[cfg(not(feature = "std"))]
-        let stackless_instance_id = self.inner.set_current_module(Arc::clone(instance))?;
-
-        // Reset call depth before each top-level invocation
-        self.inner.reset_call_depth();
-
-        // Execute the function using the stackless engine's instance ID
-        let results =
-            self.inner.execute(stackless_instance_id, func_idx as usize, args.to_vec())?;
-
-        #[cfg(feature = "tracing")]
-        trace!(results_len = results.len(), "[CAP_ENGINE] Execution completed");
-
-        Ok(results)
+            .get(&handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let registry = self
+            .host_registry
+            .as_ref()
+            .ok_or_else(|| Error::resource_not_found("No host registry to store host state in"))?;
+        registry.instance_contexts().insert(instance_idx, value)
     }
-}
 
-impl CapabilityAwareEngine {
-    /// Get an instance by handle (for debugging)
+    /// Run `f` on the value of type `T` in the host context of an instance
     #[cfg(feature = "std")]
-    pub fn get_instance(&self, handle: InstanceHandle) -> Result<&Arc<ModuleInstance>> {
-        self.instances.get(&handle)
-            .ok_or_else(|| Error::resource_not_found("Instance not found"))
+    pub fn with_instance_context<T: core::any::Any + Send, R>(
+        &self,
+        handle: InstanceHandle,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<R> {
+        let instance_idx = *self
+            .handle_to_idx
+            .get(&handle)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let registry = self
+            .host_registry
+            .as_ref()
+            .ok_or_else(|| Error::resource_not_found("No host registry to store host state in"))?;
+        registry.instance_contexts().with(instance_idx, f)
     }
 
     /// Find the index of an imported item (table, memory, or global) in the module
@@ -6,6 +6,10 @@
 pub mod builder;
 pub mod capability_engine;
 pub mod presets;
+pub mod shutdown;
+#[cfg(feature = "signing")]
+pub mod signature_policy;
+pub mod trap_handler;
 
 pub use builder::EngineBuilder;
 pub use capability_engine::{
@@ -22,3 +26,26 @@
     asil_d,
     qm,
 };
+pub use shutdown::{
+    ShutdownOutcome,
+    ShutdownPolicy,
+    SHUTDOWN_EXPORT,
+};
+#[cfg(feature = "signing")]
+pub use signature_policy::{
+    SignaturePolicy,
+    TrustAnchor,
+    VerifiedSignature,
+};
+pub use wrt_decoder::proposals::{
+    Proposal,
+    ProposalReport,
+    ProposalSet,
+};
+pub use trap_handler::{
+    TrapAction,
+    TrapContext,
+    TrapHandler,
+    TrapHandlers,
+    TrapKind,
+};
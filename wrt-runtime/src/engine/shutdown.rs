@@ -0,0 +1,63 @@
+//! Graceful shutdown of instances
+//!
+//! An instance may export a function named [`SHUTDOWN_EXPORT`] to flush the
+//! state it persists before it goes away. The supervisor first drains the
+//! engine, so no new calls start, then calls each instance's `shutdown`
+//! export within a [`ShutdownPolicy`] deadline and finally terminates the
+//! instance whether or not the export finished in time.
+//!
+//! The export takes either no parameters or the deadline in milliseconds as
+//! a single `i32` or `i64`, and its results are ignored.
+
+use core::time::Duration;
+
+/// Name of the export called to shut an instance down
+pub const SHUTDOWN_EXPORT: &str = "shutdown";
+
+/// Default time an instance is given to shut down
+pub const DEFAULT_SHUTDOWN_DEADLINE: Duration = Duration::from_secs(1);
+
+/// How long instances are given to shut down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownPolicy {
+    /// Wall-clock time each `shutdown` export may run
+    pub deadline: Duration,
+}
+
+impl ShutdownPolicy {
+    /// Give each instance `deadline` to shut down
+    pub const fn new(deadline: Duration) -> Self {
+        Self { deadline }
+    }
+
+    /// Deadline passed to the `shutdown` export, in milliseconds
+    pub fn deadline_ms(&self) -> u64 {
+        u64::try_from(self.deadline.as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+impl Default for ShutdownPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHUTDOWN_DEADLINE)
+    }
+}
+
+/// How an instance went away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    /// The `shutdown` export returned within the deadline
+    Completed,
+    /// The instance exports no usable `shutdown` function
+    NotExported,
+    /// The `shutdown` export was interrupted at the deadline
+    TimedOut,
+    /// The `shutdown` export trapped
+    Trapped,
+}
+
+impl ShutdownOutcome {
+    /// Whether the instance had the chance to persist its state
+    pub fn is_clean(self) -> bool {
+        matches!(self, Self::Completed | Self::NotExported)
+    }
+}
@@ -0,0 +1,42 @@
+//! Signature policy applied to loaded modules
+//!
+//! With a [`SignaturePolicy`] set, the engine only loads binaries signed by
+//! one of the policy's trust anchors, as checked by
+//! [`wrt_format::signing::verify`]. Unsigned binaries and binaries modified
+//! after signing are rejected before they are decoded.
+
+use wrt_error::Result;
+pub use wrt_format::signing::{
+    TrustAnchor,
+    VerifiedSignature,
+};
+
+use crate::prelude::*;
+
+/// Keys a loaded module must be signed by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePolicy {
+    trust_anchors: Vec<TrustAnchor>,
+}
+
+impl SignaturePolicy {
+    /// Policy accepting binaries signed by any of `trust_anchors`
+    pub fn new(trust_anchors: Vec<TrustAnchor>) -> Self {
+        Self { trust_anchors }
+    }
+
+    /// Keys whose signatures are accepted
+    pub fn trust_anchors(&self) -> &[TrustAnchor] {
+        &self.trust_anchors
+    }
+
+    /// Check that `binary` is signed by one of the trust anchors
+    ///
+    /// # Errors
+    ///
+    /// Returns a security error if the binary is unsigned, was modified
+    /// after signing, or is not signed by a trust anchor
+    pub fn check(&self, binary: &[u8]) -> Result<VerifiedSignature> {
+        wrt_format::signing::verify(binary, &self.trust_anchors)
+    }
+}
@@ -0,0 +1,539 @@
+//! Host recovery policy for guest traps
+//!
+//! Embedders register a [`TrapHandler`] per [`TrapKind`] on the engine.
+//! When a call traps, the engine classifies the error, asks the matching
+//! handler for a [`TrapAction`] and applies it before `execute` returns, so
+//! recovery policy lives in one place instead of in every caller.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+};
+
+use super::InstanceHandle;
+use crate::prelude::*;
+
+/// Maximum number of times a single call is refuelled and resumed
+pub const MAX_TRAP_RETRIES: u32 = 8;
+
+/// Kind of trap raised by guest code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapKind {
+    /// An `unreachable` instruction was executed
+    Unreachable,
+    /// Out-of-bounds linear memory access
+    MemoryOutOfBounds,
+    /// Out-of-bounds table, array, struct or function index access
+    TableOutOfBounds,
+    /// Integer division or remainder by zero
+    IntegerDivideByZero,
+    /// Integer overflow in an operation that traps on overflow
+    IntegerOverflow,
+    /// Float to integer conversion of NaN or an out-of-range value
+    InvalidConversionToInteger,
+    /// Indirect call through an entry of the wrong type
+    IndirectCallTypeMismatch,
+    /// Access to an uninitialized or null table element
+    UninitializedElement,
+    /// Call depth limit reached
+    CallStackExhausted,
+    /// Atomic access to an address not aligned to its size
+    UnalignedAtomic,
+    /// `memory.atomic.wait` on a memory that is not shared
+    ExpectedSharedMemory,
+    /// Execution ran out of fuel
+    FuelExhausted,
+    /// Execution reached its epoch deadline or another time limit
+    EpochDeadline,
+}
+
+impl TrapKind {
+    /// Number of trap kinds
+    pub const COUNT: usize = 13;
+
+    /// All trap kinds, in declaration order
+    pub const ALL: [TrapKind; Self::COUNT] = [
+        TrapKind::Unreachable,
+        TrapKind::MemoryOutOfBounds,
+        TrapKind::TableOutOfBounds,
+        TrapKind::IntegerDivideByZero,
+        TrapKind::IntegerOverflow,
+        TrapKind::InvalidConversionToInteger,
+        TrapKind::IndirectCallTypeMismatch,
+        TrapKind::UninitializedElement,
+        TrapKind::CallStackExhausted,
+        TrapKind::UnalignedAtomic,
+        TrapKind::ExpectedSharedMemory,
+        TrapKind::FuelExhausted,
+        TrapKind::EpochDeadline,
+    ];
+
+    /// Classify an execution error as a trap
+    ///
+    /// Returns `None` for errors that are not guest traps, such as a missing
+    /// export or a capability violation.
+    pub fn classify(error: &Error) -> Option<Self> {
+        match error.code {
+            codes::FUEL_EXHAUSTED | codes::ASYNC_FUEL_EXHAUSTED => Some(TrapKind::FuelExhausted),
+            codes::MEMORY_OUT_OF_BOUNDS => Some(TrapKind::MemoryOutOfBounds),
+            codes::EXECUTION_TIMEOUT => Some(TrapKind::EpochDeadline),
+            code if error.category == ErrorCategory::RuntimeTrap => Self::from_trap_code(code),
+            _ => None,
+        }
+    }
+
+    /// Map a `wrt_error::codes::TrapCode` discriminant to a trap kind
+    fn from_trap_code(code: u16) -> Option<Self> {
+        use codes::TrapCode;
+
+        let kind = match code {
+            c if c == TrapCode::Unreachable as u16 => TrapKind::Unreachable,
+            c if c == TrapCode::MemoryOutOfBounds as u16 => TrapKind::MemoryOutOfBounds,
+            c if c == TrapCode::IntegerDivideByZero as u16 => TrapKind::IntegerDivideByZero,
+            c if c == TrapCode::IntegerOverflow as u16 => TrapKind::IntegerOverflow,
+            c if c == TrapCode::InvalidConversionToInteger as u16 => {
+                TrapKind::InvalidConversionToInteger
+            },
+            c if c == TrapCode::IndirectCallSignatureMismatch as u16 => {
+                TrapKind::IndirectCallTypeMismatch
+            },
+            c if c == TrapCode::IndirectCallIndexOutOfBounds as u16
+                || c == TrapCode::TableOutOfBounds as u16 =>
+            {
+                TrapKind::TableOutOfBounds
+            },
+            c if c == TrapCode::IndirectCallNullTableEntry as u16
+                || c == TrapCode::UninitializedElement as u16 =>
+            {
+                TrapKind::UninitializedElement
+            },
+            c if c == TrapCode::CallStackExhausted as u16 => TrapKind::CallStackExhausted,
+            c if c == TrapCode::UnalignedAtomic as u16 => TrapKind::UnalignedAtomic,
+            c if c == TrapCode::ExpectedSharedMemory as u16 => TrapKind::ExpectedSharedMemory,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Position of this kind in [`Self::ALL`]
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Recovery action chosen by a trap handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapAction {
+    /// Return the trap to the caller unchanged
+    Propagate,
+    /// Discard the instance; later calls on its handle fail
+    Terminate,
+    /// Add the given fuel and continue a call that ran out of fuel where it
+    /// stopped, up to [`MAX_TRAP_RETRIES`] times; other traps propagate
+    Refuel(u64),
+    /// Replace the instance with a fresh instantiation of its module under
+    /// the same handle, then return the trap to the caller
+    Restart,
+}
+
+/// Trap details passed to a [`TrapHandler`]
+#[derive(Debug)]
+pub struct TrapContext<'a> {
+    /// Kind of trap
+    pub kind:     TrapKind,
+    /// Error raised by the engine
+    pub error:    &'a Error,
+    /// Instance the trap occurred in
+    pub instance: InstanceHandle,
+    /// Exported function that was called
+    pub function: &'a str,
+    /// Number of times this call has already been refuelled
+    pub attempt:  u32,
+}
+
+/// Host callback deciding how to recover from a trap
+pub trait TrapHandler: Send + Sync {
+    /// Choose the recovery action for `trap`
+    fn on_trap(&self, trap: &TrapContext<'_>) -> TrapAction;
+}
+
+impl<F> TrapHandler for F
+where
+    F: Fn(&TrapContext<'_>) -> TrapAction + Send + Sync,
+{
+    fn on_trap(&self, trap: &TrapContext<'_>) -> TrapAction {
+        self(trap)
+    }
+}
+
+/// Trap handlers registered per trap kind
+#[derive(Default)]
+pub struct TrapHandlers {
+    /// Handler per trap kind, indexed by [`TrapKind::index`]
+    handlers: [Option<Box<dyn TrapHandler>>; TrapKind::COUNT],
+    /// Handler for kinds without a dedicated handler
+    fallback: Option<Box<dyn TrapHandler>>,
+}
+
+impl TrapHandlers {
+    /// Create an empty registry; every trap propagates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for `kind`, replacing any previous one
+    pub fn register(&mut self, kind: TrapKind, handler: impl TrapHandler + 'static) {
+        self.handlers[kind.index()] = Some(Box::new(handler));
+    }
+
+    /// Register the handler for trap kinds without a dedicated handler
+    pub fn register_fallback(&mut self, handler: impl TrapHandler + 'static) {
+        self.fallback = Some(Box::new(handler));
+    }
+
+    /// Register `action` as the fixed response to `kind`
+    pub fn on(mut self, kind: TrapKind, action: TrapAction) -> Self {
+        self.register(kind, move |_: &TrapContext<'_>| action);
+        self
+    }
+
+    /// Remove the handler for `kind`
+    pub fn unregister(&mut self, kind: TrapKind) {
+        self.handlers[kind.index()] = None;
+    }
+
+    /// Whether no handlers are registered
+    pub fn is_empty(&self) -> bool {
+        self.fallback.is_none() && self.handlers.iter().all(Option::is_none)
+    }
+
+    /// Decide the recovery action for `trap`
+    pub fn dispatch(&self, trap: &TrapContext<'_>) -> TrapAction {
+        self.handlers[trap.kind.index()]
+            .as_deref()
+            .or(self.fallback.as_deref())
+            .map_or(TrapAction::Propagate, |handler| handler.on_trap(trap))
+    }
+}
+
+impl core::fmt::Debug for TrapHandlers {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut kinds = f.debug_list();
+        for kind in TrapKind::ALL {
+            if self.handlers[kind.index()].is_some() {
+                kinds.entry(&kind);
+            }
+        }
+        kinds.finish()?;
+        if self.fallback.is_some() {
+            f.write_str(" + fallback")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{
+        AtomicU32,
+        Ordering,
+    };
+
+    use wrt_foundation::values::Value;
+
+    use super::*;
+    use crate::engine::{
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EngineBuilder,
+    };
+
+    /// Module exporting `bump`, which increments and returns a mutable
+    /// global, and `trap`, which executes `unreachable`
+    const COUNTER_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Types: () -> i32, () -> ()
+        0x01, 0x08, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60, 0x00, 0x00,
+        // Functions
+        0x03, 0x03, 0x02, 0x00, 0x01,
+        // Global: (mut i32) = 0
+        0x06, 0x06, 0x01, 0x7f, 0x01, 0x41, 0x00, 0x0b,
+        // Exports
+        0x07, 0x0f, 0x02, 0x04, b'b', b'u', b'm', b'p', 0x00, 0x00, 0x04, b't', b'r', b'a',
+        b'p', 0x00, 0x01,
+        // Code
+        0x0a, 0x11, 0x02, 0x0b, 0x00, 0x23, 0x00, 0x41, 0x01, 0x6a, 0x24, 0x00, 0x23, 0x00,
+        0x0b, 0x03, 0x00, 0x00, 0x0b,
+    ];
+
+    fn counter_engine(handlers: TrapHandlers) -> Result<(CapabilityAwareEngine, InstanceHandle)> {
+        let mut engine = EngineBuilder::qm().with_trap_handlers(handlers).build()?;
+        let module = engine.load_module(COUNTER_MODULE)?;
+        let instance = engine.instantiate(module)?;
+        Ok((engine, instance))
+    }
+
+    fn context(error: &Error) -> TrapContext<'_> {
+        TrapContext {
+            kind: TrapKind::classify(error).unwrap(),
+            error,
+            instance: InstanceHandle::from_index(0),
+            function: "run",
+            attempt: 0,
+        }
+    }
+
+    #[test]
+    fn test_classify_engine_traps() {
+        use codes::TrapCode;
+
+        let classify = |error: Error| TrapKind::classify(&error);
+
+        assert_eq!(
+            classify(Error::wasm_trap(TrapCode::Unreachable, "unreachable")),
+            Some(TrapKind::Unreachable)
+        );
+        assert_eq!(
+            classify(Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds")),
+            Some(TrapKind::MemoryOutOfBounds)
+        );
+        assert_eq!(
+            classify(Error::wasm_trap(TrapCode::TableOutOfBounds, "array.get: index out of bounds")),
+            Some(TrapKind::TableOutOfBounds)
+        );
+        assert_eq!(
+            classify(TrapCode::CallStackExhausted.into()),
+            Some(TrapKind::CallStackExhausted)
+        );
+        assert_eq!(
+            classify(TrapCode::IntegerDivideByZero.into()),
+            Some(TrapKind::IntegerDivideByZero)
+        );
+        assert_eq!(
+            classify(Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access")),
+            Some(TrapKind::UnalignedAtomic)
+        );
+        assert_eq!(
+            classify(TrapCode::ExpectedSharedMemory.into()),
+            Some(TrapKind::ExpectedSharedMemory)
+        );
+        assert_eq!(
+            classify(Error::new(ErrorCategory::Runtime, codes::FUEL_EXHAUSTED, "Out of fuel")),
+            Some(TrapKind::FuelExhausted)
+        );
+        let timeout = Error::new(ErrorCategory::Runtime, codes::EXECUTION_TIMEOUT, "Deadline");
+        assert_eq!(classify(timeout), Some(TrapKind::EpochDeadline));
+        assert_eq!(classify(Error::resource_not_found("Instance not found")), None);
+    }
+
+    #[test]
+    fn test_classify_ignores_messages() {
+        assert_eq!(TrapKind::classify(&Error::runtime_trap("integer divide by zero")), None);
+        assert_eq!(
+            TrapKind::classify(&Error::runtime_execution_error("unreachable instruction executed")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dispatch_prefers_dedicated_handler() {
+        let mut handlers = TrapHandlers::new().on(TrapKind::FuelExhausted, TrapAction::Refuel(100));
+        let overflow = Error::from(codes::TrapCode::IntegerOverflow);
+        assert_eq!(handlers.dispatch(&context(&overflow)), TrapAction::Propagate);
+
+        handlers.register_fallback(|_: &TrapContext<'_>| TrapAction::Terminate);
+        handlers.register(TrapKind::Unreachable, |trap: &TrapContext<'_>| {
+            if trap.attempt == 0 {
+                TrapAction::Restart
+            } else {
+                TrapAction::Propagate
+            }
+        });
+
+        let fuel = Error::new(ErrorCategory::Runtime, codes::FUEL_EXHAUSTED, "Out of fuel");
+        assert_eq!(handlers.dispatch(&context(&fuel)), TrapAction::Refuel(100));
+        let unreachable = Error::from(codes::TrapCode::Unreachable);
+        assert_eq!(handlers.dispatch(&context(&unreachable)), TrapAction::Restart);
+        assert_eq!(handlers.dispatch(&context(&overflow)), TrapAction::Terminate);
+
+        handlers.unregister(TrapKind::FuelExhausted);
+        assert_eq!(handlers.dispatch(&context(&fuel)), TrapAction::Terminate);
+    }
+
+    #[test]
+    fn test_unhandled_trap_propagates() -> Result<()> {
+        let (mut engine, instance) = counter_engine(TrapHandlers::new())?;
+        assert!(engine.execute(instance, "trap", &[]).is_err());
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_terminate_discards_instance() -> Result<()> {
+        let handlers = TrapHandlers::new().on(TrapKind::Unreachable, TrapAction::Terminate);
+        let (mut engine, instance) = counter_engine(handlers)?;
+
+        assert!(engine.execute(instance, "trap", &[]).is_err());
+        assert!(engine.execute(instance, "bump", &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restart_resets_instance_state() -> Result<()> {
+        let handlers = TrapHandlers::new().on(TrapKind::Unreachable, TrapAction::Restart);
+        let (mut engine, instance) = counter_engine(handlers)?;
+
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(1)]);
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(2)]);
+        let error = engine.execute(instance, "trap", &[]).unwrap_err();
+        assert_eq!(TrapKind::classify(&error), Some(TrapKind::Unreachable));
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_call_batch_applies_trap_handlers() -> Result<()> {
+        let handlers = TrapHandlers::new().on(TrapKind::Unreachable, TrapAction::Restart);
+        let (mut engine, instance) = counter_engine(handlers)?;
+
+        let calls: [(&str, &[Value]); 4] = [("bump", &[]), ("bump", &[]), ("trap", &[]), ("bump", &[])];
+        let results = engine.call_batch(instance, &calls)?;
+        assert_eq!(results[0].as_ref().ok(), Some(&vec![Value::I32(1)]));
+        assert_eq!(results[1].as_ref().ok(), Some(&vec![Value::I32(2)]));
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_ref().ok(), Some(&vec![Value::I32(1)]));
+        Ok(())
+    }
+
+    /// Module exporting `count`, which bumps a global once and then loops
+    /// `n` times, returning the global plus the iterations
+    const LOOP_MODULE: &str = r#"
+        (module
+          (global $runs (mut i32) (i32.const 0))
+          (func (export "count") (param $n i32) (result i32)
+            (local $i i32)
+            (global.set $runs (i32.add (global.get $runs) (i32.const 1)))
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i32.add (global.get $runs) (local.get $i))))
+    "#;
+
+    fn loop_engine(handlers: TrapHandlers) -> Result<(CapabilityAwareEngine, InstanceHandle)> {
+        let wasm = wat::parse_str(LOOP_MODULE).unwrap();
+        let mut engine = EngineBuilder::qm().with_trap_handlers(handlers).build()?;
+        let module = engine.load_module(&wasm)?;
+        let instance = engine.instantiate(module)?;
+        Ok((engine, instance))
+    }
+
+    #[test]
+    fn test_refuel_resumes_suspended_call() -> Result<()> {
+        static REFUELS: AtomicU32 = AtomicU32::new(0);
+
+        let mut handlers = TrapHandlers::new();
+        handlers.register(TrapKind::FuelExhausted, |trap: &TrapContext<'_>| {
+            assert_eq!(trap.function, "count");
+            assert_eq!(trap.attempt, REFUELS.fetch_add(1, Ordering::Relaxed));
+            TrapAction::Refuel(200)
+        });
+        let (mut engine, instance) = loop_engine(handlers)?;
+        engine.set_fuel(200);
+
+        // The global is bumped once, so the call was continued, not rerun
+        assert_eq!(engine.execute(instance, "count", &[Value::I32(100)])?, vec![Value::I32(101)]);
+        assert!(REFUELS.load(Ordering::Relaxed) > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuel_retries_are_bounded() -> Result<()> {
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        let mut handlers = TrapHandlers::new();
+        handlers.register(TrapKind::FuelExhausted, |trap: &TrapContext<'_>| {
+            assert_eq!(trap.attempt, CALLS.fetch_add(1, Ordering::Relaxed));
+            TrapAction::Refuel(10)
+        });
+        let (mut engine, instance) = loop_engine(handlers)?;
+        engine.set_fuel(10);
+
+        let error = engine.execute(instance, "count", &[Value::I32(1000)]).unwrap_err();
+        assert_eq!(TrapKind::classify(&error), Some(TrapKind::FuelExhausted));
+        assert_eq!(CALLS.load(Ordering::Relaxed), MAX_TRAP_RETRIES + 1);
+
+        // The abandoned call does not block the next one
+        engine.set_fuel(u64::MAX);
+        assert_eq!(engine.execute(instance, "count", &[Value::I32(3)])?, vec![Value::I32(5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuel_is_charged_at_function_exit() -> Result<()> {
+        // `bump` has no loop, so only the exit checkpoint can catch the overrun
+        let (mut engine, instance) = counter_engine(TrapHandlers::new())?;
+        engine.set_fuel(1);
+        let error = engine.execute(instance, "bump", &[]).unwrap_err();
+        assert_eq!(TrapKind::classify(&error), Some(TrapKind::FuelExhausted));
+
+        let mut handlers = TrapHandlers::new();
+        handlers.register(TrapKind::FuelExhausted, |_: &TrapContext<'_>| TrapAction::Refuel(100));
+        let (mut engine, instance) = counter_engine(handlers)?;
+        engine.set_fuel(1);
+
+        // The call resumes past its last instruction instead of rerunning it
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(1)]);
+        assert_eq!(engine.execute(instance, "bump", &[])?, vec![Value::I32(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuel_clock_drives_firewall_rate_limits() -> Result<()> {
+        use std::sync::Arc;
+
+        use wrt_intercept::{
+            FirewallStrategy,
+            FuelClock,
+        };
+
+        let clock = Arc::new(FuelClock::new());
+        let firewall = FirewallStrategy::builder()
+            .with_default_allow(true)
+            .with_rate_limit("tick".into(), 1, 100)
+            .with_fuel_clock(Arc::clone(&clock))
+            .build()?;
+        let (mut engine, instance) = loop_engine(TrapHandlers::new())?;
+        engine.set_fuel_clock(Arc::clone(&clock));
+        engine.set_fuel(10_000);
+
+        assert!(firewall.check_call("a", "b", "tick", &[]).is_ok());
+        assert!(firewall.check_call("a", "b", "tick", &[]).is_err());
+
+        // The clock advances by exactly the fuel the engine charged
+        engine.execute(instance, "count", &[Value::I32(100)])?;
+        let charged = 10_000 - engine.remaining_fuel().unwrap_or(0);
+        assert!(charged >= 100);
+        assert_eq!(clock.now(), charged);
+        assert!(firewall.check_call("a", "b", "tick", &[]).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuel_propagates_other_traps() -> Result<()> {
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        let mut handlers = TrapHandlers::new();
+        handlers.register(TrapKind::Unreachable, |_: &TrapContext<'_>| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            TrapAction::Refuel(10)
+        });
+        let (mut engine, instance) = counter_engine(handlers)?;
+
+        assert!(engine.execute(instance, "trap", &[]).is_err());
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Epoch-based interruption of guest execution
+//!
+//! The host advances an [`EpochCounter`], typically from a timer thread or
+//! tick interrupt, and gives the engine a deadline in epochs. The engine only
+//! reads the counter at function entry and on loop back-edges, so long
+//! running guests are stopped within one loop iteration of the deadline
+//! without per-instruction fuel accounting. What happens at the deadline is
+//! set by [`EpochDeadlineAction`].
+//!
+//! The counter is a [`TickHandler`], so a platform
+//! [`TickSource`](wrt_platform::tick::TickSource) can advance it: a hardware
+//! timer on tickless RTOS targets, or the scheduler-driven
+//! [`SoftwareTickSource`](wrt_platform::tick::SoftwareTickSource) elsewhere.
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+#[cfg(feature = "std")]
+use wrt_platform::tick::TickHandler;
+
+use crate::prelude::Arc;
+
+/// Shared epoch counter advanced by the host
+///
+/// Clones refer to the same counter, so one counter can drive the deadlines
+/// of several engines.
+#[derive(Debug, Clone, Default)]
+pub struct EpochCounter {
+    /// Current epoch
+    epoch: Arc<AtomicU64>,
+}
+
+impl EpochCounter {
+    /// Create a counter at epoch zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance the counter by one epoch and return the new epoch
+    pub fn increment(&self) -> u64 {
+        self.epoch.fetch_add(1, Ordering::Release).wrapping_add(1)
+    }
+
+    /// Current epoch
+    pub fn current(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TickHandler for EpochCounter {
+    fn on_tick(&self) {
+        self.increment();
+    }
+}
+
+/// What the engine does when a guest reaches its epoch deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpochDeadlineAction {
+    /// Trap the call with `codes::EXECUTION_TIMEOUT`
+    #[default]
+    Trap,
+    /// Suspend the call so the host can resume it later, and move the
+    /// deadline `delta` epochs past the current epoch
+    Yield {
+        /// Epochs granted to the call once it is resumed
+        delta: u64,
+    },
+}
+
+/// Deadline checkpoints reached by an engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EpochStats {
+    /// Calls suspended at their deadline
+    pub yields: u64,
+    /// Calls trapped at their deadline
+    pub traps:  u64,
+}
+
+/// Epoch deadline of one engine
+#[derive(Debug, Clone, Default)]
+pub struct EpochDeadline {
+    /// Counter the deadline is measured against
+    counter:  EpochCounter,
+    /// Epoch at which guests are interrupted; none when unset
+    deadline: Option<u64>,
+    /// Action taken at the deadline
+    action:   EpochDeadlineAction,
+    /// Deadlines reached so far
+    stats:    EpochStats,
+}
+
+impl EpochDeadline {
+    /// Create a deadline state without a deadline on a fresh counter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counter the deadline is measured against
+    pub fn counter(&self) -> &EpochCounter {
+        &self.counter
+    }
+
+    /// Measure the deadline against `counter`, keeping the remaining ticks
+    pub fn set_counter(&mut self, counter: EpochCounter) {
+        let remaining = self.remaining();
+        self.counter = counter;
+        if let Some(remaining) = remaining {
+            self.set_ticks(remaining);
+        }
+    }
+
+    /// Interrupt guests `ticks` epochs after the current epoch
+    pub fn set_ticks(&mut self, ticks: u64) {
+        self.deadline = Some(self.counter.current().saturating_add(ticks));
+    }
+
+    /// Remove the deadline
+    pub fn clear(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Epoch at which guests are interrupted
+    pub fn deadline(&self) -> Option<u64> {
+        self.deadline
+    }
+
+    /// Epochs left until the deadline, zero once it has passed
+    pub fn remaining(&self) -> Option<u64> {
+        self.deadline.map(|deadline| deadline.saturating_sub(self.counter.current()))
+    }
+
+    /// Set the action taken at the deadline
+    pub fn set_action(&mut self, action: EpochDeadlineAction) {
+        self.action = action;
+    }
+
+    /// Action taken at the deadline
+    pub fn action(&self) -> EpochDeadlineAction {
+        self.action
+    }
+
+    /// Deadlines reached so far
+    pub fn stats(&self) -> EpochStats {
+        self.stats
+    }
+
+    /// Whether a checkpoint has to interrupt the guest
+    #[inline]
+    pub fn reached(&self) -> bool {
+        self.deadline.is_some_and(|deadline| self.counter.current() >= deadline)
+    }
+
+    /// Apply the deadline action at a checkpoint that reached the deadline
+    ///
+    /// Returns `Ok` when the call is to be suspended; the deadline has then
+    /// moved past the current epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `codes::EXECUTION_TIMEOUT` when the action is
+    /// [`EpochDeadlineAction::Trap`].
+    pub fn interrupt(&mut self) -> Result<()> {
+        match self.action {
+            EpochDeadlineAction::Trap => {
+                self.stats.traps += 1;
+                Err(Error::new(
+                    ErrorCategory::Runtime,
+                    codes::EXECUTION_TIMEOUT,
+                    "Epoch deadline exceeded",
+                ))
+            },
+            EpochDeadlineAction::Yield { delta } => {
+                self.stats.yields += 1;
+                self.set_ticks(delta);
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_follows_shared_counter() {
+        let counter = EpochCounter::new();
+        let mut deadline = EpochDeadline::new();
+        deadline.set_counter(counter.clone());
+        assert!(!deadline.reached());
+
+        deadline.set_ticks(2);
+        assert_eq!(deadline.deadline(), Some(2));
+        assert_eq!(counter.increment(), 1);
+        assert!(!deadline.reached());
+        counter.increment();
+        assert!(deadline.reached());
+        assert_eq!(deadline.remaining(), Some(0));
+
+        deadline.clear();
+        assert!(!deadline.reached());
+    }
+
+    #[test]
+    fn test_interrupt_traps_or_yields() {
+        let mut deadline = EpochDeadline::new();
+        deadline.set_ticks(0);
+        let error = deadline.interrupt().unwrap_err();
+        assert_eq!(error.code, codes::EXECUTION_TIMEOUT);
+
+        deadline.set_action(EpochDeadlineAction::Yield { delta: 3 });
+        assert!(deadline.interrupt().is_ok());
+        assert_eq!(deadline.remaining(), Some(3));
+        assert!(!deadline.reached());
+        assert_eq!(deadline.stats(), EpochStats { yields: 1, traps: 1 });
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_tick_source_advances_counter() {
+        use core::time::Duration;
+
+        use wrt_platform::tick::{
+            SoftwareTickSource,
+            TickMode,
+            TickSource,
+        };
+
+        let mut deadline = EpochDeadline::new();
+        let mut ticks = SoftwareTickSource::new(deadline.counter().clone());
+        deadline.set_ticks(2);
+        ticks.start(TickMode::Periodic(Duration::from_millis(1))).unwrap();
+        ticks.poll(Duration::ZERO);
+
+        ticks.poll(Duration::from_millis(1));
+        assert!(!deadline.reached());
+        ticks.poll(Duration::from_millis(2));
+        assert!(deadline.reached());
+        assert_eq!(ticks.handler().current(), 2);
+    }
+}
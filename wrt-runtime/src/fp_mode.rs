@@ -0,0 +1,239 @@
+//! Floating-point environment control
+//!
+//! WebAssembly leaves the bit pattern of NaNs produced by arithmetic to the
+//! host, so hosts differ in the NaN payloads they produce, and requires
+//! subnormal numbers to be preserved, while native code being ported may have
+//! run with flush-to-zero enabled. [`FpConfig`] selects, per engine or per
+//! instance:
+//!
+//! - NaN canonicalization, replacing every NaN an arithmetic instruction
+//!   produces by the positive canonical NaN so results are bit-identical on
+//!   every host
+//! - flush-to-zero, replacing subnormal operands and results of arithmetic
+//!   and comparison instructions by a zero of the same sign, in software, so
+//!   the behavior does not depend on the host's FPU mode
+//!
+//! [`FpModeReport`] describes the mode an instance runs in, what the host
+//! hardware does on its own, and how often each adjustment was applied.
+
+use wrt_foundation::values::{
+    FloatBits32,
+    FloatBits64,
+    Value,
+};
+
+/// Positive canonical NaN for `f32`
+pub const CANONICAL_NAN_F32: u32 = 0x7fc0_0000;
+
+/// Positive canonical NaN for `f64`
+pub const CANONICAL_NAN_F64: u64 = 0x7ff8_0000_0000_0000;
+
+/// Treatment of subnormal operands and results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DenormalMode {
+    /// Keep subnormal numbers, as WebAssembly requires
+    #[default]
+    Preserve,
+    /// Replace subnormal numbers by a zero of the same sign
+    FlushToZero,
+}
+
+/// Floating-point environment applied to executed code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FpConfig {
+    /// Replace NaN results of arithmetic by the canonical NaN
+    canonicalize_nans: bool,
+    /// Treatment of subnormal numbers
+    denormals:         DenormalMode,
+}
+
+impl FpConfig {
+    /// Standard WebAssembly semantics: NaNs as produced by the host,
+    /// subnormals preserved
+    pub const fn new() -> Self {
+        Self {
+            canonicalize_nans: false,
+            denormals:         DenormalMode::Preserve,
+        }
+    }
+
+    /// Bit-identical results on every host: NaNs canonicalized, subnormals
+    /// preserved
+    pub const fn deterministic() -> Self {
+        Self {
+            canonicalize_nans: true,
+            denormals:         DenormalMode::Preserve,
+        }
+    }
+
+    /// Enable or disable NaN canonicalization
+    #[must_use]
+    pub const fn with_nan_canonicalization(mut self, enabled: bool) -> Self {
+        self.canonicalize_nans = enabled;
+        self
+    }
+
+    /// Set the treatment of subnormal numbers
+    #[must_use]
+    pub const fn with_denormals(mut self, mode: DenormalMode) -> Self {
+        self.denormals = mode;
+        self
+    }
+
+    /// Whether NaN results of arithmetic are canonicalized
+    pub const fn canonicalize_nans(&self) -> bool {
+        self.canonicalize_nans
+    }
+
+    /// Treatment of subnormal numbers
+    pub const fn denormals(&self) -> DenormalMode {
+        self.denormals
+    }
+
+    /// Whether the configuration differs from standard semantics
+    pub const fn is_active(&self) -> bool {
+        self.canonicalize_nans || matches!(self.denormals, DenormalMode::FlushToZero)
+    }
+
+    /// Adjust an operand of an arithmetic or comparison instruction
+    pub fn adjust_operand(&self, value: &mut Value, stats: &mut FpStats) {
+        if self.denormals == DenormalMode::FlushToZero && flush_subnormal(value) {
+            stats.values_flushed += 1;
+        }
+    }
+
+    /// Adjust the result of an arithmetic instruction
+    pub fn adjust_result(&self, value: &mut Value, stats: &mut FpStats) {
+        if self.canonicalize_nans && canonicalize_nan(value) {
+            stats.nans_canonicalized += 1;
+        }
+        if self.denormals == DenormalMode::FlushToZero && flush_subnormal(value) {
+            stats.values_flushed += 1;
+        }
+    }
+}
+
+/// Adjustments applied to an instance's floating-point values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FpStats {
+    /// NaN results replaced by the canonical NaN
+    pub nans_canonicalized: u64,
+    /// Subnormal operands and results flushed to zero
+    pub values_flushed:     u64,
+}
+
+/// Floating-point mode of one instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FpModeReport {
+    /// Instance the report describes
+    pub instance_id:            usize,
+    /// Configuration the instance runs with
+    pub config:                 FpConfig,
+    /// Whether the configuration is set for this instance rather than
+    /// inherited from the engine
+    pub overridden:             bool,
+    /// Whether the host hardware flushes subnormal numbers on its own
+    pub host_flushes_denormals: bool,
+    /// Adjustments applied so far
+    pub stats:                  FpStats,
+}
+
+impl FpModeReport {
+    /// Whether results match standard WebAssembly semantics on this host
+    ///
+    /// Subnormals are only preserved if neither the configuration nor the
+    /// host hardware flushes them.
+    pub fn preserves_denormals(&self) -> bool {
+        self.config.denormals == DenormalMode::Preserve && !self.host_flushes_denormals
+    }
+}
+
+/// Whether the host FPU flushes subnormal results to zero
+///
+/// Rust code normally runs with subnormals enabled, but some embedded
+/// targets and foreign code setting the FPU control register do not.
+pub fn host_flushes_denormals() -> bool {
+    let smallest_normal = core::hint::black_box(f32::MIN_POSITIVE);
+    let half = core::hint::black_box(0.5f32);
+    smallest_normal * half == 0.0
+}
+
+/// Replace a NaN by the canonical NaN of its type, returning whether it was
+/// changed
+pub fn canonicalize_nan(value: &mut Value) -> bool {
+    match value {
+        Value::F32(bits) if f32::from_bits(bits.0).is_nan() && bits.0 != CANONICAL_NAN_F32 => {
+            *bits = FloatBits32(CANONICAL_NAN_F32);
+            true
+        },
+        Value::F64(bits) if f64::from_bits(bits.0).is_nan() && bits.0 != CANONICAL_NAN_F64 => {
+            *bits = FloatBits64(CANONICAL_NAN_F64);
+            true
+        },
+        _ => false,
+    }
+}
+
+/// Replace a subnormal number by a zero of the same sign, returning whether
+/// it was changed
+pub fn flush_subnormal(value: &mut Value) -> bool {
+    match value {
+        Value::F32(bits) if f32::from_bits(bits.0).is_subnormal() => {
+            *bits = FloatBits32(bits.0 & 0x8000_0000);
+            true
+        },
+        Value::F64(bits) if f64::from_bits(bits.0).is_subnormal() => {
+            *bits = FloatBits64(bits.0 & 0x8000_0000_0000_0000);
+            true
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nan_canonicalization() {
+        let config = FpConfig::deterministic();
+        let mut stats = FpStats::default();
+
+        let mut value = Value::F32(FloatBits32(0xffc0_0001));
+        config.adjust_result(&mut value, &mut stats);
+        assert_eq!(value, Value::F32(FloatBits32(CANONICAL_NAN_F32)));
+
+        let mut value = Value::F64(FloatBits64(0x7ff0_0000_0000_0001));
+        config.adjust_result(&mut value, &mut stats);
+        assert_eq!(value, Value::F64(FloatBits64(CANONICAL_NAN_F64)));
+
+        let mut value = Value::F32(FloatBits32(1.5f32.to_bits()));
+        config.adjust_result(&mut value, &mut stats);
+        assert_eq!(value, Value::F32(FloatBits32(1.5f32.to_bits())));
+        assert_eq!(stats.nans_canonicalized, 2);
+
+        let mut value = Value::F32(FloatBits32(0xffc0_0001));
+        FpConfig::new().adjust_result(&mut value, &mut stats);
+        assert_eq!(value, Value::F32(FloatBits32(0xffc0_0001)));
+    }
+
+    #[test]
+    fn test_flush_to_zero_keeps_sign() {
+        let config = FpConfig::new().with_denormals(DenormalMode::FlushToZero);
+        let mut stats = FpStats::default();
+
+        let mut value = Value::F32(FloatBits32((-f32::MIN_POSITIVE / 2.0).to_bits()));
+        config.adjust_operand(&mut value, &mut stats);
+        assert_eq!(value, Value::F32(FloatBits32((-0.0f32).to_bits())));
+
+        let mut value = Value::F64(FloatBits64((f64::MIN_POSITIVE / 2.0).to_bits()));
+        config.adjust_result(&mut value, &mut stats);
+        assert_eq!(value, Value::F64(FloatBits64(0)));
+
+        let mut value = Value::F64(FloatBits64(f64::MIN_POSITIVE.to_bits()));
+        config.adjust_operand(&mut value, &mut stats);
+        assert_eq!(value, Value::F64(FloatBits64(f64::MIN_POSITIVE.to_bits())));
+        assert_eq!(stats.values_flushed, 2);
+        assert!(config.is_active() && !FpConfig::new().is_active());
+    }
+}
@@ -33,9 +33,9 @@
 
 // Match WrtExpr type: Vec in std mode, BoundedVec in no_std mode
 #[cfg(feature = "std")]
-type InstructionVec = Vec<Instruction<InstructionProvider>>;
+pub(crate) type InstructionVec = Vec<Instruction<InstructionProvider>>;
 #[cfg(not(feature = "std"))]
-type InstructionVec = BoundedVec<Instruction<InstructionProvider>, 1024, InstructionProvider>;
+pub(crate) type InstructionVec = BoundedVec<Instruction<InstructionProvider>, 1024, InstructionProvider>;
 
 type TargetVec = BoundedVec<u32, 20000, InstructionProvider>;
 
@@ -275,12 +275,14 @@ fn parse_instruction_with_provider(
             consumed += table_bytes;
             Instruction::CallIndirect(type_idx, table_idx)
         },
+        #[cfg(feature = "tail-call")]
         0x12 => {
             // ReturnCall (tail-call extension): func_idx (LEB128 u32)
             let (func_idx, bytes) = read_leb128_u32(bytecode, offset + 1)?;
             consumed += bytes;
             Instruction::ReturnCall(func_idx)
         },
+        #[cfg(feature = "tail-call")]
         0x13 => {
             // ReturnCallIndirect (tail-call extension): type_idx (LEB128 u32) followed by table_idx (LEB128 u32)
             let (type_idx, type_bytes) = read_leb128_u32(bytecode, offset + 1)?;
@@ -289,6 +291,10 @@ fn parse_instruction_with_provider(
             consumed += table_bytes;
             Instruction::ReturnCallIndirect(type_idx, table_idx)
         },
+        #[cfg(not(feature = "tail-call"))]
+        0x12 | 0x13 => {
+            return Err(Error::parse_error("Tail-call instructions require the tail-call feature"));
+        },
 
         // Exception handling instructions (continued)
         0x18 => {
@@ -1265,6 +1271,115 @@ fn parse_instruction_with_provider(
             }
         }
 
+        // Threads instructions (0xFE prefix) - WebAssembly Threads Proposal
+        0xFE => {
+            let (atomic_opcode, opcode_bytes) = read_leb128_u32(bytecode, offset + 1)?;
+            consumed += opcode_bytes;
+
+            if atomic_opcode == 0x03 {
+                // atomic.fence: one reserved zero byte
+                if offset + consumed >= bytecode.len() {
+                    return Err(Error::parse_error("Unexpected end of bytecode in atomic.fence"));
+                }
+                consumed += 1;
+                return Ok((Instruction::AtomicFence, consumed));
+            }
+
+            // Every other atomic instruction takes a memarg
+            let (align, bytes1) = read_leb128_u32(bytecode, offset + consumed)?;
+            let (mem_offset, bytes2) = read_leb128_u32(bytecode, offset + consumed + bytes1)?;
+            consumed += bytes1 + bytes2;
+            let memarg = MemArg {
+                align_exponent: align,
+                offset: mem_offset,
+                memory_index: 0,
+            };
+
+            match atomic_opcode {
+                // Wait and notify
+                0x00 => Instruction::MemoryAtomicNotify { memarg },
+                0x01 => Instruction::MemoryAtomicWait32 { memarg },
+                0x02 => Instruction::MemoryAtomicWait64 { memarg },
+                // Atomic loads
+                0x10 => Instruction::I32AtomicLoad { memarg },
+                0x11 => Instruction::I64AtomicLoad { memarg },
+                0x12 => Instruction::I32AtomicLoad8U { memarg },
+                0x13 => Instruction::I32AtomicLoad16U { memarg },
+                0x14 => Instruction::I64AtomicLoad8U { memarg },
+                0x15 => Instruction::I64AtomicLoad16U { memarg },
+                0x16 => Instruction::I64AtomicLoad32U { memarg },
+                // Atomic stores
+                0x17 => Instruction::I32AtomicStore { memarg },
+                0x18 => Instruction::I64AtomicStore { memarg },
+                0x19 => Instruction::I32AtomicStore8 { memarg },
+                0x1A => Instruction::I32AtomicStore16 { memarg },
+                0x1B => Instruction::I64AtomicStore8 { memarg },
+                0x1C => Instruction::I64AtomicStore16 { memarg },
+                0x1D => Instruction::I64AtomicStore32 { memarg },
+                // Read-modify-write: add
+                0x1E => Instruction::I32AtomicRmwAdd { memarg },
+                0x1F => Instruction::I64AtomicRmwAdd { memarg },
+                0x20 => Instruction::I32AtomicRmw8AddU { memarg },
+                0x21 => Instruction::I32AtomicRmw16AddU { memarg },
+                0x22 => Instruction::I64AtomicRmw8AddU { memarg },
+                0x23 => Instruction::I64AtomicRmw16AddU { memarg },
+                0x24 => Instruction::I64AtomicRmw32AddU { memarg },
+                // Read-modify-write: sub
+                0x25 => Instruction::I32AtomicRmwSub { memarg },
+                0x26 => Instruction::I64AtomicRmwSub { memarg },
+                0x27 => Instruction::I32AtomicRmw8SubU { memarg },
+                0x28 => Instruction::I32AtomicRmw16SubU { memarg },
+                0x29 => Instruction::I64AtomicRmw8SubU { memarg },
+                0x2A => Instruction::I64AtomicRmw16SubU { memarg },
+                0x2B => Instruction::I64AtomicRmw32SubU { memarg },
+                // Read-modify-write: and
+                0x2C => Instruction::I32AtomicRmwAnd { memarg },
+                0x2D => Instruction::I64AtomicRmwAnd { memarg },
+                0x2E => Instruction::I32AtomicRmw8AndU { memarg },
+                0x2F => Instruction::I32AtomicRmw16AndU { memarg },
+                0x30 => Instruction::I64AtomicRmw8AndU { memarg },
+                0x31 => Instruction::I64AtomicRmw16AndU { memarg },
+                0x32 => Instruction::I64AtomicRmw32AndU { memarg },
+                // Read-modify-write: or
+                0x33 => Instruction::I32AtomicRmwOr { memarg },
+                0x34 => Instruction::I64AtomicRmwOr { memarg },
+                0x35 => Instruction::I32AtomicRmw8OrU { memarg },
+                0x36 => Instruction::I32AtomicRmw16OrU { memarg },
+                0x37 => Instruction::I64AtomicRmw8OrU { memarg },
+                0x38 => Instruction::I64AtomicRmw16OrU { memarg },
+                0x39 => Instruction::I64AtomicRmw32OrU { memarg },
+                // Read-modify-write: xor
+                0x3A => Instruction::I32AtomicRmwXor { memarg },
+                0x3B => Instruction::I64AtomicRmwXor { memarg },
+                0x3C => Instruction::I32AtomicRmw8XorU { memarg },
+                0x3D => Instruction::I32AtomicRmw16XorU { memarg },
+                0x3E => Instruction::I64AtomicRmw8XorU { memarg },
+                0x3F => Instruction::I64AtomicRmw16XorU { memarg },
+                0x40 => Instruction::I64AtomicRmw32XorU { memarg },
+                // Read-modify-write: xchg
+                0x41 => Instruction::I32AtomicRmwXchg { memarg },
+                0x42 => Instruction::I64AtomicRmwXchg { memarg },
+                0x43 => Instruction::I32AtomicRmw8XchgU { memarg },
+                0x44 => Instruction::I32AtomicRmw16XchgU { memarg },
+                0x45 => Instruction::I64AtomicRmw8XchgU { memarg },
+                0x46 => Instruction::I64AtomicRmw16XchgU { memarg },
+                0x47 => Instruction::I64AtomicRmw32XchgU { memarg },
+                // Read-modify-write: cmpxchg
+                0x48 => Instruction::I32AtomicRmwCmpxchg { memarg },
+                0x49 => Instruction::I64AtomicRmwCmpxchg { memarg },
+                0x4A => Instruction::I32AtomicRmw8CmpxchgU { memarg },
+                0x4B => Instruction::I32AtomicRmw16CmpxchgU { memarg },
+                0x4C => Instruction::I64AtomicRmw8CmpxchgU { memarg },
+                0x4D => Instruction::I64AtomicRmw16CmpxchgU { memarg },
+                0x4E => Instruction::I64AtomicRmw32CmpxchgU { memarg },
+                _ => {
+                    #[cfg(feature = "tracing")]
+                    wrt_foundation::tracing::warn!(atomic_opcode = format!("0xFE 0x{:02X}", atomic_opcode), offset = offset, "Unknown atomic opcode");
+                    return Err(Error::parse_error("Unknown atomic instruction opcode"));
+                }
+            }
+        }
+
         _ => {
             // Show context around the unknown opcode
             #[cfg(feature = "tracing")]
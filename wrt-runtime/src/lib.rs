@@ -47,19 +47,30 @@
 pub mod atomic_runtime;
 pub mod cfi_engine;
 pub mod core_types;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod epoch;
 pub mod execution;
 #[cfg(test)]
 mod execution_tests;
 /// Format bridge interface
 pub mod format_bridge;
+pub mod fp_mode;
 pub mod func;
 pub mod gc;
 pub mod global;
 pub mod memory;
 
+// Runtime-loaded interceptor strategy and host function plugins
+#[cfg(feature = "plugins")]
+pub mod plugin;
+
 // WebAssembly bulk memory operations runtime
 pub mod bulk_memory;
 
+// Size and TTL policies, flushing and metrics for engine caches
+#[cfg(feature = "std")]
+pub mod cache_policy;
+
 // WebAssembly 3.0 multi-memory runtime
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod multi_memory;
@@ -68,6 +79,14 @@
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod shared_memory;
 
+// Linear memory storage shared copy-on-write between instances
+#[cfg(feature = "std")]
+pub mod snapshot_memory;
+
+// Waiter queues for memory.atomic.wait / memory.atomic.notify
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod wait_table;
+
 // WebAssembly SIMD runtime
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod simd_runtime;
@@ -91,12 +110,21 @@
 pub mod module;
 pub mod module_instance;
 pub mod prelude;
+#[cfg(feature = "std")]
+pub mod reclamation;
+pub mod result_buffer;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod scratch;
 pub mod stackless;
+pub mod startup_profile;
+pub mod store_limits;
 pub mod table;
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod thread_manager;
 pub mod type_conversion;
 pub mod types;
+#[cfg(feature = "std")]
+pub mod validation_cache;
 
 // Platform-aware runtime and unified memory management
 pub mod platform_runtime;
@@ -124,6 +152,14 @@
 #[cfg(test)]
 mod instruction_parser_tests;
 
+// Static call depth and recursion analysis
+#[cfg(feature = "std")]
+pub mod call_depth;
+
+// Lint pass over decoded modules
+#[cfg(feature = "std")]
+pub mod lint;
+
 // Temporary stub modules for parallel development
 mod component_stubs;
 mod foundation_stubs;
@@ -149,6 +185,14 @@
     MemoryOrderingPolicy,
     OrderingViolationReport,
 };
+#[cfg(feature = "std")]
+pub use cache_policy::{
+    BoundedCache,
+    CacheMetrics,
+    CachePolicy,
+    CacheRegistry,
+    ManagedCache,
+};
 pub use cfi_engine::{
     CfiEngineStatistics,
     CfiExecutionEngine,
@@ -162,6 +206,12 @@
     ComponentExecutionState,
     ExecutionContext,
 };
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use epoch::{
+    EpochCounter,
+    EpochDeadlineAction,
+    EpochStats,
+};
 pub use execution::ExecutionStats;
 pub use func::Function as RuntimeFunction;
 pub use global::Global;
@@ -174,8 +224,35 @@
 };
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use memory_helpers::ArcMemoryExt;
+pub use fp_mode::{
+    DenormalMode,
+    FpConfig,
+    FpModeReport,
+};
 pub use prelude::FuncType;
+#[cfg(feature = "std")]
+pub use reclamation::{
+    HibernationStore,
+    ReclaimReport,
+    ReclaimTier,
+    Reclaimable,
+    ReclamationConfig,
+    ReclamationMetrics,
+    ReclamationOrchestrator,
+};
+pub use result_buffer::ResultBuffer;
+pub use startup_profile::{
+    PhaseProfile,
+    StartupPhase,
+    StartupReport,
+};
+pub use store_limits::StoreLimits;
 pub use table::Table;
+#[cfg(feature = "std")]
+pub use validation_cache::{
+    CacheVerification,
+    ValidationCache,
+};
 pub use wrt_foundation::platform_abstraction;
 
 /// The WebAssembly memory page size (64KiB)
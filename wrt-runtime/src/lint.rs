@@ -0,0 +1,648 @@
+//! Lint pass over decoded modules
+//!
+//! [`lint_module`] looks for constructs that are valid WebAssembly but
+//! usually point at a problem in the guest toolchain or build: imports that
+//! are never used, functions with very many locals, active data segments
+//! that overwrite each other, start functions that run host code during
+//! instantiation, NaN constants whose payload is not canonical, recursion
+//! the frame store cannot be sized for, and call chains deeper than the
+//! configured frame store. Each
+//! [`LintWarning`] carries a suggestion for fixing the guest before it is
+//! deployed.
+//!
+//! The pass reads the decoded module only; it neither validates nor
+//! instantiates it.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+    format,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::Result;
+use wrt_format::{
+    module::{
+        ExportKind,
+        ImportDesc,
+        Module,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureElementInit,
+    },
+};
+use wrt_foundation::types::Instruction;
+
+use crate::{
+    bounded_runtime_infra::RuntimeProvider,
+    call_depth,
+    instruction_parser::parse_instructions,
+};
+
+/// Default number of locals above which a function is reported
+pub const DEFAULT_MAX_LOCALS: usize = 1000;
+
+/// Kind of problem a lint reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintCode {
+    /// An imported function or global is never referenced
+    UnusedImport,
+    /// A function declares more locals than the configured maximum
+    HugeLocals,
+    /// Two active data segments write to the same bytes
+    OverlappingData,
+    /// The start function calls into the host during instantiation
+    SuspiciousStart,
+    /// A float constant is a NaN with a non-canonical payload
+    NonCanonicalNan,
+    /// Functions call each other recursively without a static bound
+    UnboundedRecursion,
+    /// A call chain needs more frames than the configured maximum
+    DeepCallChain,
+}
+
+impl LintCode {
+    /// Name used to report and filter the lint
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UnusedImport => "unused-import",
+            Self::HugeLocals => "huge-locals",
+            Self::OverlappingData => "overlapping-data",
+            Self::SuspiciousStart => "suspicious-start",
+            Self::NonCanonicalNan => "non-canonical-nan",
+            Self::UnboundedRecursion => "unbounded-recursion",
+            Self::DeepCallChain => "deep-call-chain",
+        }
+    }
+
+    /// How to address the problem
+    pub const fn help(self) -> &'static str {
+        match self {
+            Self::UnusedImport => {
+                "remove the import, e.g. by building with link-time optimization or \
+                 `wasm-opt --remove-unused-module-elements`"
+            },
+            Self::HugeLocals => {
+                "build the guest with optimizations; large local counts usually come from \
+                 unoptimized or generated code and slow down every call"
+            },
+            Self::OverlappingData => {
+                "check the linker script or memory layout; the later segment silently \
+                 overwrites the earlier one"
+            },
+            Self::SuspiciousStart => {
+                "move host-dependent initialization out of the start function, e.g. into an \
+                 exported `_initialize`, so instantiation does not depend on host state"
+            },
+            Self::NonCanonicalNan => {
+                "NaN payloads are not portable across platforms; use a canonical NaN if the \
+                 value must be deterministic"
+            },
+            Self::UnboundedRecursion => {
+                "rewrite the recursion as a loop or bound its depth explicitly; the frame store \
+                 cannot be sized for recursion whose depth depends on input"
+            },
+            Self::DeepCallChain => {
+                "raise the maximum call depth of the deployment or flatten the call chain, e.g. \
+                 by inlining small functions with `wasm-opt -O`"
+            },
+        }
+    }
+}
+
+impl fmt::Display for LintCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Problem found in a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// Kind of problem
+    pub code:     LintCode,
+    /// Function the problem is in or about, if any
+    pub function: Option<u32>,
+    /// Description of this occurrence
+    pub message:  String,
+}
+
+impl LintWarning {
+    /// How to address the problem
+    pub fn help(&self) -> &'static str {
+        self.code.help()
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+/// Configuration of the lint pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintConfig {
+    /// Number of locals above which a function is reported
+    pub max_locals:     usize,
+    /// Frames a call chain may need before it is reported, unchecked if
+    /// `None`
+    pub max_call_depth: Option<u32>,
+    /// Lints not run
+    pub disabled:       BTreeSet<LintCode>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            max_locals:     DEFAULT_MAX_LOCALS,
+            max_call_depth: None,
+            disabled:       BTreeSet::new(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Report functions with more than `max_locals` locals
+    #[must_use]
+    pub fn with_max_locals(mut self, max_locals: usize) -> Self {
+        self.max_locals = max_locals;
+        self
+    }
+
+    /// Report call chains needing more than `max_call_depth` frames
+    #[must_use]
+    pub fn with_max_call_depth(mut self, max_call_depth: u32) -> Self {
+        self.max_call_depth = Some(max_call_depth);
+        self
+    }
+
+    /// Do not run the lint `code`
+    #[must_use]
+    pub fn disable(mut self, code: LintCode) -> Self {
+        self.disabled.insert(code);
+        self
+    }
+
+    fn enabled(&self, code: LintCode) -> bool {
+        !self.disabled.contains(&code)
+    }
+}
+
+/// Decode a module binary and lint it
+///
+/// # Errors
+///
+/// Returns an error if the binary cannot be decoded.
+pub fn lint_binary(binary: &[u8], config: &LintConfig) -> Result<Vec<LintWarning>> {
+    let module = wrt_decoder::decoder::decode_module(binary)?;
+    Ok(lint_module(&module, config))
+}
+
+/// Lint a decoded module
+///
+/// Warnings are ordered by lint and then by position in the module.
+pub fn lint_module(module: &Module, config: &LintConfig) -> Vec<LintWarning> {
+    let refs = References::collect(module);
+    let mut warnings = Vec::new();
+
+    if config.enabled(LintCode::UnusedImport) {
+        unused_imports(module, &refs, &mut warnings);
+    }
+    if config.enabled(LintCode::HugeLocals) {
+        huge_locals(module, config.max_locals, &mut warnings);
+    }
+    if config.enabled(LintCode::OverlappingData) {
+        overlapping_data(module, &mut warnings);
+    }
+    if config.enabled(LintCode::SuspiciousStart) {
+        suspicious_start(module, &refs, &mut warnings);
+    }
+    if config.enabled(LintCode::NonCanonicalNan) {
+        warnings.extend(refs.nans.iter().map(|&(function, bits)| {
+            let (ty, bits) = match bits {
+                NanBits::F32(bits) => ("f32", format!("{bits:#010x}")),
+                NanBits::F64(bits) => ("f64", format!("{bits:#018x}")),
+            };
+            LintWarning {
+                code: LintCode::NonCanonicalNan,
+                function: Some(function),
+                message: format!(
+                    "function {function} has an {ty}.const NaN with non-canonical bits {bits}"
+                ),
+            }
+        }));
+    }
+    if config.enabled(LintCode::UnboundedRecursion) || config.enabled(LintCode::DeepCallChain) {
+        call_depth(module, config, &mut warnings);
+    }
+    warnings
+}
+
+/// Bits of a NaN constant
+#[derive(Debug, Clone, Copy)]
+enum NanBits {
+    F32(u32),
+    F64(u64),
+}
+
+/// Functions and globals referenced by a module, and what its function
+/// bodies contain
+#[derive(Debug, Default)]
+struct References {
+    functions:    BTreeSet<u32>,
+    globals:      BTreeSet<u32>,
+    /// Functions called by each function body
+    calls:        Vec<(u32, u32)>,
+    nans:         Vec<(u32, NanBits)>,
+    /// Whether every function body could be parsed; if not, references may
+    /// be missing
+    complete:     bool,
+}
+
+impl References {
+    fn collect(module: &Module) -> Self {
+        let mut refs = Self {
+            complete: true,
+            ..Self::default()
+        };
+
+        for (index, function) in module.functions.iter().enumerate() {
+            let index = index as u32;
+            if function.code.is_empty() {
+                // Imported function
+                continue;
+            }
+            match parse_instructions(&function.code) {
+                Ok(instructions) => {
+                    for instruction in &instructions {
+                        refs.visit(index, instruction);
+                    }
+                },
+                Err(_) => refs.complete = false,
+            }
+        }
+
+        let expressions = module
+            .globals
+            .iter()
+            .map(|global| global.init.as_slice())
+            .chain(module.data.iter().map(|data| data.offset_expr_bytes.as_slice()))
+            .chain(module.elements.iter().map(|elem| elem.offset_expr_bytes.as_slice()));
+        for expression in expressions {
+            refs.visit_expression(expression);
+        }
+        for element in &module.elements {
+            match &element.init_data {
+                PureElementInit::FunctionIndices(indices) => refs.functions.extend(indices),
+                PureElementInit::ExpressionBytes(expressions) => {
+                    for expression in expressions {
+                        refs.visit_expression(expression);
+                    }
+                },
+            }
+        }
+
+        for export in &module.exports {
+            match export.kind {
+                ExportKind::Function => {
+                    refs.functions.insert(export.index);
+                },
+                ExportKind::Global => {
+                    refs.globals.insert(export.index);
+                },
+                _ => {},
+            }
+        }
+        refs.functions.extend(module.start);
+        refs
+    }
+
+    fn visit_expression(&mut self, expression: &[u8]) {
+        if expression.is_empty() {
+            return;
+        }
+        match parse_instructions(expression) {
+            Ok(instructions) => {
+                for instruction in &instructions {
+                    match instruction {
+                        Instruction::RefFunc(function) => {
+                            self.functions.insert(*function);
+                        },
+                        Instruction::GlobalGet(global) => {
+                            self.globals.insert(*global);
+                        },
+                        _ => {},
+                    }
+                }
+            },
+            Err(_) => self.complete = false,
+        }
+    }
+
+    fn visit(&mut self, function: u32, instruction: &Instruction<RuntimeProvider>) {
+        match instruction {
+            Instruction::Call(callee) | Instruction::ReturnCall(callee) => {
+                self.functions.insert(*callee);
+                self.calls.push((function, *callee));
+            },
+            Instruction::RefFunc(callee) => {
+                self.functions.insert(*callee);
+            },
+            Instruction::GlobalGet(global) | Instruction::GlobalSet(global) => {
+                self.globals.insert(*global);
+            },
+            Instruction::F32Const(bits) if is_non_canonical_nan_f32(*bits) => {
+                self.nans.push((function, NanBits::F32(*bits)));
+            },
+            Instruction::F64Const(bits) if is_non_canonical_nan_f64(*bits) => {
+                self.nans.push((function, NanBits::F64(*bits)));
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Whether `bits` is an f32 NaN other than the canonical quiet NaN
+fn is_non_canonical_nan_f32(bits: u32) -> bool {
+    let magnitude = bits & 0x7fff_ffff;
+    magnitude > 0x7f80_0000 && magnitude != 0x7fc0_0000
+}
+
+/// Whether `bits` is an f64 NaN other than the canonical quiet NaN
+fn is_non_canonical_nan_f64(bits: u64) -> bool {
+    let magnitude = bits & 0x7fff_ffff_ffff_ffff;
+    magnitude > 0x7ff0_0000_0000_0000 && magnitude != 0x7ff8_0000_0000_0000
+}
+
+fn unused_imports(module: &Module, refs: &References, warnings: &mut Vec<LintWarning>) {
+    if !refs.complete {
+        // A body that could not be parsed may hold the missing references
+        return;
+    }
+
+    let (mut function, mut global) = (0u32, 0u32);
+    for import in &module.imports {
+        let unused = match import.desc {
+            ImportDesc::Function(_) => {
+                function += 1;
+                (!refs.functions.contains(&(function - 1))).then_some(("function", function - 1))
+            },
+            ImportDesc::Global(_) => {
+                global += 1;
+                (!refs.globals.contains(&(global - 1))).then_some(("global", global - 1))
+            },
+            _ => None,
+        };
+        if let Some((kind, index)) = unused {
+            warnings.push(LintWarning {
+                code:     LintCode::UnusedImport,
+                function: (kind == "function").then_some(index),
+                message:  format!(
+                    "imported {kind} {}::{} is never used",
+                    import.module, import.name
+                ),
+            });
+        }
+    }
+}
+
+fn huge_locals(module: &Module, max_locals: usize, warnings: &mut Vec<LintWarning>) {
+    for (index, function) in module.functions.iter().enumerate() {
+        if function.locals.len() > max_locals {
+            warnings.push(LintWarning {
+                code:     LintCode::HugeLocals,
+                function: Some(index as u32),
+                message:  format!(
+                    "function {index} declares {} locals (more than {max_locals})",
+                    function.locals.len()
+                ),
+            });
+        }
+    }
+}
+
+/// Constant offset of an active data segment
+fn constant_offset(expression: &[u8]) -> Option<u64> {
+    match parse_instructions(expression).ok()?.first()? {
+        Instruction::I32Const(offset) => Some(u64::from(*offset as u32)),
+        Instruction::I64Const(offset) => Some(*offset as u64),
+        _ => None,
+    }
+}
+
+fn overlapping_data(module: &Module, warnings: &mut Vec<LintWarning>) {
+    // (memory, start, end, segment) of the non-empty segments at constant
+    // offsets
+    let mut ranges: Vec<(u32, u64, u64, usize)> = module
+        .data
+        .iter()
+        .enumerate()
+        .filter_map(|(segment, data)| {
+            let PureDataMode::Active { memory_index, .. } = data.mode else {
+                return None;
+            };
+            let start = constant_offset(&data.offset_expr_bytes)?;
+            let end = start.checked_add(data.data_bytes.len() as u64)?;
+            (end > start).then_some((memory_index, start, end, segment))
+        })
+        .collect();
+    ranges.sort_unstable();
+
+    for (i, &(memory, start, end, segment)) in ranges.iter().enumerate() {
+        for &(other_memory, other_start, other_end, other) in &ranges[i + 1..] {
+            if other_memory != memory || other_start >= end {
+                break;
+            }
+            let (first, second) = (segment.min(other), segment.max(other));
+            warnings.push(LintWarning {
+                code:     LintCode::OverlappingData,
+                function: None,
+                message:  format!(
+                    "data segments {first} and {second} overlap in memory {memory} at bytes \
+                     {:#x}..{:#x}",
+                    other_start,
+                    end.min(other_end)
+                ),
+            });
+        }
+    }
+}
+
+fn suspicious_start(module: &Module, refs: &References, warnings: &mut Vec<LintWarning>) {
+    let Some(start) = module.start else {
+        return;
+    };
+    let imported = |function: u32| {
+        module.functions.get(function as usize).is_some_and(|f| f.code.is_empty())
+    };
+
+    let mut warn = |message: String| {
+        warnings.push(LintWarning {
+            code: LintCode::SuspiciousStart,
+            function: Some(start),
+            message,
+        });
+    };
+    if imported(start) {
+        warn(format!(
+            "start function {start} is imported and runs host code during instantiation"
+        ));
+    } else {
+        let host_calls: BTreeSet<u32> = refs
+            .calls
+            .iter()
+            .filter(|&&(caller, callee)| caller == start && imported(callee))
+            .map(|&(_, callee)| callee)
+            .collect();
+        if !host_calls.is_empty() {
+            warn(format!(
+                "start function {start} calls imported functions {host_calls:?} during \
+                 instantiation"
+            ));
+        }
+    }
+    if module
+        .exports
+        .iter()
+        .any(|export| export.kind == ExportKind::Function && export.index == start)
+    {
+        warn(format!(
+            "start function {start} is also exported and may run a second time"
+        ));
+    }
+}
+
+fn call_depth(module: &Module, config: &LintConfig, warnings: &mut Vec<LintWarning>) {
+    let report = call_depth::analyze_module(module);
+
+    if config.enabled(LintCode::UnboundedRecursion) {
+        for cycle in &report.cycles {
+            let message = if cycle.is_direct() {
+                format!("function {} calls itself recursively", cycle.functions[0])
+            } else {
+                format!("functions {:?} call each other recursively", cycle.functions)
+            };
+            warnings.push(LintWarning {
+                code: LintCode::UnboundedRecursion,
+                function: Some(cycle.functions[0]),
+                message,
+            });
+        }
+    }
+
+    let (Some(max_call_depth), Some((function, depth))) = (config.max_call_depth, report.deepest())
+    else {
+        return;
+    };
+    if config.enabled(LintCode::DeepCallChain) && depth.depth > max_call_depth {
+        warnings.push(LintWarning {
+            code:     LintCode::DeepCallChain,
+            function: Some(function),
+            message:  format!(
+                "calls from function {function} need {} frames (more than {max_call_depth})",
+                depth.depth
+            ),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_format::{
+        module::{
+            Export,
+            Function,
+            Import,
+        },
+        pure_format_types::PureDataSegment,
+    };
+
+    use super::*;
+
+    fn function(locals: usize, code: Vec<u8>) -> Function {
+        Function {
+            type_idx: 0,
+            locals: vec![wrt_foundation::types::ValueType::I32; locals],
+            code,
+        }
+    }
+
+    fn data(offset: u8, len: usize) -> PureDataSegment {
+        PureDataSegment {
+            mode:              PureDataMode::Active {
+                memory_index:    0,
+                offset_expr_len: 3,
+            },
+            offset_expr_bytes: vec![0x41, offset, 0x0b],
+            data_bytes:        vec![0; len],
+        }
+    }
+
+    #[test]
+    fn test_lints_report_actionable_warnings() {
+        let mut module = Module::new();
+        for name in ["fd_write", "proc_exit"] {
+            module.imports.push(Import {
+                module: "wasi_snapshot_preview1".into(),
+                name:   name.into(),
+                desc:   ImportDesc::Function(0),
+            });
+            module.functions.push(function(0, Vec::new()));
+        }
+        // Start function calls fd_write (0) and loads a signalling f32 NaN
+        let mut code = vec![0x10, 0x00, 0x43];
+        code.extend_from_slice(&0x7fa0_0000u32.to_le_bytes());
+        code.extend_from_slice(&[0x1a, 0x43]);
+        code.extend_from_slice(&0x7fc0_0000u32.to_le_bytes());
+        code.extend_from_slice(&[0x1a, 0x0b]);
+        module.functions.push(function(2, code));
+        module.functions.push(function(1001, vec![0x0b]));
+        module.start = Some(2);
+        module.exports.push(Export {
+            name:  "_start".into(),
+            kind:  ExportKind::Function,
+            index: 2,
+        });
+        module.data = vec![data(0, 16), data(32, 4), data(8, 4)];
+
+        let warnings = lint_module(&module, &LintConfig::default());
+        let codes: Vec<_> = warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, vec![
+            LintCode::UnusedImport,
+            LintCode::HugeLocals,
+            LintCode::OverlappingData,
+            LintCode::SuspiciousStart,
+            LintCode::SuspiciousStart,
+            LintCode::NonCanonicalNan,
+        ]);
+        assert!(warnings[0].message.contains("wasi_snapshot_preview1::proc_exit"));
+        assert_eq!(warnings[1].function, Some(3));
+        assert!(warnings[2].message.contains("segments 0 and 2"));
+        assert!(warnings[5].message.contains("0x7fa00000"));
+
+        let config = LintConfig::default().with_max_locals(2000).disable(LintCode::UnusedImport);
+        assert_eq!(lint_module(&module, &config).len(), 4);
+    }
+
+    #[test]
+    fn test_call_depth_lints() {
+        let mut module = Module::new();
+        // 0 -> 1 -> 2, 2 calls itself
+        module.functions.push(function(0, vec![0x10, 0x01, 0x0b]));
+        module.functions.push(function(0, vec![0x10, 0x02, 0x0b]));
+        module.functions.push(function(0, vec![0x10, 0x02, 0x0b]));
+
+        let warnings = lint_module(&module, &LintConfig::default());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, LintCode::UnboundedRecursion);
+        assert_eq!(warnings[0].function, Some(2));
+
+        let warnings = lint_module(&module, &LintConfig::default().with_max_call_depth(2));
+        assert_eq!(warnings[1].code, LintCode::DeepCallChain);
+        assert!(warnings[1].message.contains("need 3 frames"));
+        assert_eq!(lint_module(&module, &LintConfig::default().with_max_call_depth(3)).len(), 1);
+    }
+}
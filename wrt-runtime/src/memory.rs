@@ -138,6 +138,8 @@
     MemoryStats,
 };
 #[cfg(feature = "std")]
+use wrt_foundation::cow_provider::CowSnapshot;
+#[cfg(feature = "std")]
 use wrt_foundation::MemoryAccessor;
 // Import atomic operations trait
 use wrt_instructions::atomic_ops::AtomicOperations;
@@ -166,12 +168,15 @@
     TryFrom,
     VerificationLevel,
 };
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::wait_table::WaiterTable;
 
 // Platform-aware memory providers for memory operations
-// For std mode: Use StdProvider which uses Vec<u8> for dynamically-sized memory
+// For std mode: Use StdProvider which uses Vec<u8> for dynamically-sized memory, or
+// pages shared with a copy-on-write snapshot
 // For no_std mode: Use NoStdProvider with fixed size (limited to compile-time constant)
 #[cfg(feature = "std")]
-type LargeMemoryProvider = wrt_foundation::safe_memory::StdProvider;
+type LargeMemoryProvider = crate::snapshot_memory::LinearMemoryProvider;
 #[cfg(not(feature = "std"))]
 type LargeMemoryProvider = wrt_foundation::safe_memory::NoStdProvider<524288>; // 512KB (8 pages) for no_std
 
@@ -182,6 +187,9 @@
 pub const PAGE_SIZE: usize = 65536;
 
 /// Maximum number of memory pages allowed by WebAssembly spec
+///
+/// Memory64 memories may declare up to 2^48 pages, but linear memory is
+/// addressed with u32 offsets, so they are held to the same page limit.
 pub const MAX_PAGES: u32 = 65536;
 
 /// The maximum memory size in bytes (4GB)
@@ -192,6 +200,7 @@ fn to_core_memory_type(memory_type: &MemoryType) -> CoreMemoryType {
     CoreMemoryType {
         limits: memory_type.limits,
         shared: memory_type.shared,
+        memory64: memory_type.memory64,
     }
 }
 
@@ -356,6 +365,9 @@ pub struct Memory {
     pub metrics:            RwLock<MemoryMetrics>,
     /// Memory verification level
     pub verification_level: VerificationLevel,
+    /// Threads parked in `memory.atomic.wait` on this memory
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub waiters:            WaiterTable,
 }
 
 impl Clone for Memory {
@@ -372,7 +384,7 @@ fn clone(&self) -> Self {
         let new_data = {
             use wrt_foundation::safe_memory::StdProvider;
             // Create provider with data directly (StdProvider::new takes Vec<u8>)
-            let new_provider = StdProvider::new(current_bytes.clone());
+            let new_provider = LargeMemoryProvider::Owned(StdProvider::new(current_bytes.clone()));
             let new_handler = SafeMemoryHandler::new(new_provider);
             Box::new(std::sync::Mutex::new(new_handler))
         };
@@ -423,6 +435,9 @@ fn clone(&self) -> Self {
             debug_name:         self.debug_name.clone(),
             metrics:            cloned_metrics,
             verification_level: self.verification_level,
+            // Waiters belong to the original memory, not to the copy
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            waiters:            WaiterTable::new(),
         }
     }
 }
@@ -517,6 +532,7 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
                 max: if max == 0 { None } else { Some(max) },
             },
             shared: false,
+            memory64: false,
         };
         Self::new(to_core_memory_type(&memory_type)).map(|boxed| *boxed)
     }
@@ -548,7 +564,13 @@ pub fn new(ty: CoreMemoryType) -> Result<Box<Self>> {
                   initial_pages, maximum_pages_opt);
         }
 
-        // Wasm MVP allows up to 65536 pages (4GiB).
+        // Wasm MVP allows up to 65536 pages (4GiB); memory64 memories are
+        // held to the same runtime limit.
+        if initial_pages > MAX_PAGES {
+            return Err(Error::resource_limit_exceeded(
+                "Memory minimum exceeds the runtime page limit",
+            ));
+        }
         // Binary std/no_std choice
         // PalMemoryProvider::new will pass these pages to the PageAllocator.
 
@@ -580,7 +602,7 @@ pub fn new(ty: CoreMemoryType) -> Result<Box<Self>> {
             let mut provider = StdProvider::with_capacity(current_size_bytes);
             // Initialize the memory to zeros (WebAssembly spec requires zero-initialized memory)
             provider.add_data(&vec![0u8; current_size_bytes]);
-            provider
+            LargeMemoryProvider::Owned(provider)
         };
         #[cfg(not(feature = "std"))]
         let provider = LargeMemoryProvider::default();
@@ -600,9 +622,65 @@ pub fn new(ty: CoreMemoryType) -> Result<Box<Self>> {
             #[cfg(not(feature = "std"))]
             metrics: RwLock::new(MemoryMetrics::new(current_size_bytes)),
             verification_level,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            waiters: WaiterTable::new(),
         }))
     }
 
+    /// Creates a memory of type `ty` starting out with the contents of
+    /// `snapshot`, sharing its pages with every other memory spawned from it
+    ///
+    /// Private pages are mapped for the declared maximum, or the minimum if
+    /// the type has none, and a chunk is only copied into them when written.
+    /// Growing past that mapping turns the memory into an owned copy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot is larger than the initial memory or
+    /// the pages cannot be mapped
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "macos")))]
+    pub fn from_snapshot(ty: CoreMemoryType, snapshot: &CowSnapshot) -> Result<Box<Self>> {
+        use wrt_foundation::{
+            safe_memory::Provider,
+            types::Limits,
+        };
+
+        use crate::snapshot_memory::SnapshotAllocator;
+
+        let initial_bytes = wasm_offset_to_usize(ty.limits.min)? * PAGE_SIZE;
+        if snapshot.len() > initial_bytes {
+            return Err(Error::memory_error("Snapshot is larger than the initial memory"));
+        }
+        let mapped_pages = ty.limits.max.unwrap_or(ty.limits.min).min(MAX_PAGES);
+        let capacity = wasm_offset_to_usize(mapped_pages)? * PAGE_SIZE;
+
+        // Start from an empty owned memory and swap in the shared pages
+        let mut memory = Self::new(CoreMemoryType {
+            limits: Limits { min: 0, max: ty.limits.max },
+            ..ty
+        })?;
+        let mut provider = snapshot.instance(SnapshotAllocator::default(), capacity)?;
+        provider.ensure_used_up_to(initial_bytes)?;
+        provider.set_verification_level(memory.verification_level);
+        let handler = SafeMemoryHandler::new(LargeMemoryProvider::Snapshot(provider));
+        *memory.data = std::sync::Mutex::new(handler);
+        memory.ty = ty;
+        memory.current_pages = AtomicU32::new(ty.limits.min);
+        memory.metrics = MemoryMetrics::new(initial_bytes);
+        Ok(memory)
+    }
+
+    /// Captures the current contents as a snapshot to spawn memories from with
+    /// [`Memory::from_snapshot`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the contents cannot be read
+    #[cfg(feature = "std")]
+    pub fn snapshot(&self) -> Result<CowSnapshot> {
+        Ok(CowSnapshot::new(self.data.lock().unwrap().to_vec()?))
+    }
+
     /// Creates a new memory instance with a debug name
     ///
     /// # Arguments
@@ -654,6 +732,14 @@ pub fn size(&self) -> u32 {
         self.current_pages.load(Ordering::Relaxed)
     }
 
+    /// Whether the memory is indexed with i64 addresses (memory64)
+    ///
+    /// Addresses, sizes and page counts of such a memory are i64 values.
+    #[must_use]
+    pub fn is_memory64(&self) -> bool {
+        self.ty.memory64
+    }
+
     /// Gets the current size of the memory in bytes
     ///
     /// # Returns
@@ -984,8 +1070,7 @@ pub fn read(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
         #[cfg(feature = "std")]
         {
             let data_guard = self.data.lock().unwrap();
-            let safe_slice = data_guard.get_slice(offset_usize, size)?;
-            buffer.copy_from_slice(safe_slice.data()?);
+            data_guard.read_into(offset_usize, buffer)?;
             // DEBUG: Track reads from allocator region
             #[cfg(feature = "tracing")]
             if offset_usize >= 0x1074a0 && offset_usize <= 0x1074b0 && size >= 4 {
@@ -1002,8 +1087,7 @@ pub fn read(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
         #[cfg(not(feature = "std"))]
         {
             let data_guard = self.data.read();
-            let safe_slice = data_guard.get_slice(offset_usize, size)?;
-            buffer.copy_from_slice(safe_slice.data()?);
+            data_guard.read_into(offset_usize, buffer)?;
         }
 
         Ok(())
@@ -2443,46 +2527,40 @@ fn atomic_wait32(&mut self, addr: u32, expected: i32, timeout_ns: Option<u64>) -
         // Check alignment (atomic operations require proper alignment)
         self.check_alignment(addr, 4, 4)?;
 
-        // Read current value atomically
-        let current = self.read_i32(addr)?;
-        if current != expected {
-            return Ok(1); // Value mismatch, return immediately
-        }
-
-        // atomic.wait requires proper futex support to implement correctly.
-        // The WebAssembly threads proposal specifies that atomic.wait must block
-        // until either:
-        // 1. The memory location is notified via atomic.notify
-        // 2. The timeout expires
-        //
-        // A sleep-based fallback does NOT satisfy this because:
-        // - It doesn't wake when the value changes via atomic.notify
-        // - It always times out even if notified early
-        //
-        // Per CLAUDE.md: Return an error rather than masking with broken fallback.
-        // To use atomic.wait, integrate with wrt-platform's futex infrastructure.
-        let _ = timeout_ns; // Acknowledge parameter to avoid warning
-        Err(Error::runtime_unsupported_operation(
-            "atomic.wait32 requires platform futex support - integrate wrt-platform crate"
-        ))
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            let timeout = timeout_ns.map(core::time::Duration::from_nanos);
+            self.waiters.wait(u64::from(addr), timeout, || Ok(self.read_i32(addr)? == expected))
+        }
+
+        // Without a waiter table nothing can wake a parked thread
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        {
+            let _ = (expected, timeout_ns);
+            Err(Error::runtime_unsupported_operation(
+                "atomic.wait32 requires a waiter table (std or alloc feature)",
+            ))
+        }
     }
 
     fn atomic_wait64(&mut self, addr: u32, expected: i64, timeout_ns: Option<u64>) -> Result<i32> {
         // Check alignment (64-bit atomics require 8-byte alignment)
         self.check_alignment(addr, 8, 8)?;
 
-        // Read current value atomically
-        let current = self.read_i64(addr)?;
-        if current != expected {
-            return Ok(1); // Value mismatch, return immediately
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            let timeout = timeout_ns.map(core::time::Duration::from_nanos);
+            self.waiters.wait(u64::from(addr), timeout, || Ok(self.read_i64(addr)? == expected))
         }
 
-        // atomic.wait64 has the same requirements as atomic.wait32.
-        // See atomic_wait32 for detailed explanation.
-        let _ = timeout_ns; // Acknowledge parameter to avoid warning
-        Err(Error::runtime_unsupported_operation(
-            "atomic.wait64 requires platform futex support - integrate wrt-platform crate"
-        ))
+        // Without a waiter table nothing can wake a parked thread
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        {
+            let _ = (expected, timeout_ns);
+            Err(Error::runtime_unsupported_operation(
+                "atomic.wait64 requires a waiter table (std or alloc feature)",
+            ))
+        }
     }
 
     fn atomic_notify(&mut self, addr: u32, count: u32) -> Result<u32> {
@@ -2492,16 +2570,18 @@ fn atomic_notify(&mut self, addr: u32, count: u32) -> Result<u32> {
         // Validate address is accessible
         let _current = self.read_i32(addr)?;
 
-        // atomic.notify requires platform futex support to work correctly.
-        // Without proper futex tracking, we cannot know which threads are waiting
-        // on this address. Returning 0 (no waiters) is technically correct when
-        // no threads are actually blocked via atomic.wait (which now returns error),
-        // but we should be explicit about the limitation.
-        //
-        // When atomic.wait is properly implemented via wrt-platform futex,
-        // this function should also use the same futex infrastructure to wake waiters.
-        let _ = count; // Acknowledge parameter
-        Ok(0) // Correct: no waiters since atomic.wait returns error
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            Ok(self.waiters.notify(u64::from(addr), count))
+        }
+
+        // Without a waiter table atomic.wait always fails, so no thread can be
+        // parked on this address
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        {
+            let _ = count;
+            Ok(0)
+        }
     }
 
     fn atomic_load_i32(&self, addr: u32) -> Result<i32> {
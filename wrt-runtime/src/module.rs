@@ -28,6 +28,12 @@
     DataSegment as WrtDataSegment,
     ElementSegment as WrtElementSegment,
 };
+#[cfg(feature = "std")]
+use wrt_format::string_pool::{
+    PooledStr,
+    StringPool,
+    StringPoolStats,
+};
 // Re-export for module_builder
 pub use wrt_foundation::types::LocalEntry;
 use wrt_foundation::{
@@ -153,6 +159,7 @@ fn to_core_memory_type(memory_type: WrtMemoryType) -> CoreMemoryType {
     CoreMemoryType {
         limits: memory_type.limits,
         shared: memory_type.shared,
+        memory64: memory_type.memory64,
     }
 }
 
@@ -369,24 +376,223 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
     }
 }
 
+/// Size of a binary above which [`BodyParsing::auto`] parses bodies lazily
+pub const LAZY_PARSING_THRESHOLD: usize = 5 * 1024 * 1024;
+
+/// When function bodies are parsed into instructions
+///
+/// Eager parsing fails loading on a malformed body. Lazy parsing keeps each
+/// body as bytecode and parses it the first time the function is called,
+/// so loading a large module only costs the decoder's index scan of the
+/// code section; a malformed body then fails its first call instead.
+/// Parallel parsing is eager, but spreads the bodies over several threads;
+/// the result and the error reported for malformed bodies are the same as
+/// with eager parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyParsing {
+    /// Parse all bodies while loading
+    #[default]
+    Eager,
+    /// Parse each body on first call
+    Lazy,
+    /// Parse lazily if the binary is larger than `threshold` bytes
+    Auto {
+        /// Binary size in bytes above which bodies are parsed lazily
+        threshold: usize,
+    },
+    /// Parse all bodies while loading, on up to `threads` threads
+    ///
+    /// Only has an effect on `std` builds; elsewhere bodies are parsed
+    /// eagerly on the loading thread.
+    Parallel {
+        /// Maximum number of threads parsing bodies
+        threads: usize,
+    },
+}
+
+impl BodyParsing {
+    /// Parse lazily for binaries above [`LAZY_PARSING_THRESHOLD`]
+    pub const fn auto() -> Self {
+        Self::Auto { threshold: LAZY_PARSING_THRESHOLD }
+    }
+
+    /// Parse eagerly on as many threads as the host has cores
+    #[cfg(feature = "std")]
+    pub fn parallel() -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        Self::Parallel { threads }
+    }
+
+    /// Whether bodies of a binary of `binary_size` bytes are parsed lazily
+    pub const fn is_lazy(self, binary_size: usize) -> bool {
+        match self {
+            Self::Eager | Self::Parallel { .. } => false,
+            Self::Lazy => true,
+            Self::Auto { threshold } => binary_size > threshold,
+        }
+    }
+
+    /// Parsing used for a binary of `binary_size` bytes, with
+    /// [`BodyParsing::Auto`] resolved to eager or lazy parsing
+    pub const fn for_binary(self, binary_size: usize) -> Self {
+        match self {
+            Self::Auto { .. } if self.is_lazy(binary_size) => Self::Lazy,
+            Self::Auto { .. } => Self::Eager,
+            other => other,
+        }
+    }
+
+    /// Number of threads parsing bodies while loading
+    const fn threads(self) -> usize {
+        match self {
+            Self::Parallel { threads } if threads > 1 => threads,
+            _ => 1,
+        }
+    }
+}
+
+/// Parse the bodies of `functions` on up to `threads` scoped threads
+///
+/// Each thread takes a contiguous run of bodies of about the same total
+/// size. The result holds the instructions of each function in module
+/// order, `None` for imports; if bodies are malformed, the error of the
+/// first of them is returned, as eager parsing would.
+#[cfg(feature = "std")]
+fn parse_bodies_parallel(
+    functions: &[wrt_format::module::Function],
+    threads: usize,
+) -> Result<Vec<Option<crate::instruction_parser::InstructionVec>>> {
+    let total: usize = functions.iter().map(|func| func.code.len()).sum();
+    let share = total.div_ceil(threads.max(1)).max(1);
+
+    // Split into runs of about `share` bytes of code each
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut size = 0;
+    for (idx, func) in functions.iter().enumerate() {
+        size += func.code.len();
+        if size >= share {
+            runs.push(&functions[start..=idx]);
+            start = idx + 1;
+            size = 0;
+        }
+    }
+    if start < functions.len() {
+        runs.push(&functions[start..]);
+    }
+
+    let parsed = std::thread::scope(|scope| {
+        let workers: Vec<_> = runs
+            .into_iter()
+            .map(|run| {
+                scope.spawn(move || {
+                    run.iter()
+                        .map(|func| {
+                            if func.code.is_empty() {
+                                return Ok(None);
+                            }
+                            crate::instruction_parser::parse_instructions(&func.code).map(Some)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().map_err(|_| Error::runtime_error("Body parsing thread panicked")))
+            .collect::<Result<Vec<_>>>()
+    })?;
+    parsed.into_iter().flatten().collect()
+}
+
+/// Function body kept as bytecode until the function is first called
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct LazyBody {
+    /// Bytecode of the body, without the local declarations
+    code:   Vec<u8>,
+    /// Instructions, once parsed
+    parsed: std::sync::OnceLock<WrtExpr>,
+}
+
+#[cfg(feature = "std")]
+impl LazyBody {
+    /// Keep `code` for parsing on first use
+    pub fn new(code: Vec<u8>) -> Self {
+        Self { code, parsed: std::sync::OnceLock::new() }
+    }
+
+    /// Bytecode of the body
+    pub fn code(&self) -> &[u8] {
+        &self.code
+    }
+
+    /// Whether the body has been parsed
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.get().is_some()
+    }
+
+    /// Instructions of the body, parsing them on first use
+    ///
+    /// Parsing is repeated on every call while it fails.
+    pub fn expr(&self) -> Result<&WrtExpr> {
+        if let Some(expr) = self.parsed.get() {
+            return Ok(expr);
+        }
+        let instructions = crate::instruction_parser::parse_instructions(&self.code)?;
+        Ok(self.parsed.get_or_init(|| WrtExpr { instructions }))
+    }
+}
+
 /// Represents a WebAssembly function in the runtime
 #[derive(Debug, Clone)]
 pub struct Function {
     /// The type index of the function (referring to Module.types)
-    pub type_idx: u32,
+    pub type_idx:    u32,
     /// The parsed local variable declarations
-    pub locals:   BoundedLocalsVec,
-    /// The parsed instructions that make up the function body
-    pub body:     WrtExpr,
+    pub locals:      BoundedLocalsVec,
+    /// The parsed instructions that make up the function body, empty while
+    /// a lazy body is unparsed
+    pub body:        WrtExpr,
+    /// Bytecode of a body parsed on first call, see [`BodyParsing::Lazy`]
+    #[cfg(feature = "std")]
+    pub lazy_body:   Option<Arc<LazyBody>>,
+    /// SHA-256 of the body's bytecode in the binary the function was
+    /// decoded from, zeros for functions not decoded from a binary
+    pub code_digest: [u8; 32],
+}
+
+impl Function {
+    /// Instructions of the body, parsing a lazy body on first use
+    pub fn expr(&self) -> Result<&WrtExpr> {
+        #[cfg(feature = "std")]
+        if let Some(lazy) = &self.lazy_body {
+            return lazy.expr();
+        }
+        Ok(&self.body)
+    }
+
+    /// Whether this is the placeholder of an imported function, which has
+    /// neither locals nor a body
+    pub fn is_import_stub(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.lazy_body.is_some() {
+            return false;
+        }
+        self.body.is_empty() && self.locals.is_empty()
+    }
 }
 
 impl Default for Function {
     fn default() -> Self {
         let provider = create_runtime_provider().unwrap();
         Self {
-            type_idx: 0,
-            locals:   BoundedLocalsVec::new(provider).unwrap(),
-            body:     WrtExpr::default(),
+            type_idx:    0,
+            locals:      BoundedLocalsVec::new(provider).unwrap(),
+            body:        WrtExpr::default(),
+            #[cfg(feature = "std")]
+            lazy_body:   None,
+            code_digest: [0; 32],
         }
     }
 }
@@ -434,6 +640,9 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
             type_idx,
             locals: BoundedLocalsVec::new(provider).unwrap(),
             body: WrtExpr::default(),
+            #[cfg(feature = "std")]
+            lazy_body: None,
+            code_digest: [0; 32],
         })
     }
 }
@@ -685,9 +894,12 @@ pub struct Module {
     pub imports:         ModuleImports,
     /// Ordered list of imports for index-based lookup (module_name, field_name)
     #[cfg(feature = "std")]
-    pub import_order:    Vec<(String, String)>,
+    pub import_order:    Vec<(PooledStr, PooledStr)>,
     #[cfg(not(feature = "std"))]
     pub import_order:    wrt_foundation::bounded::BoundedVec<(BoundedImportName, BoundedImportName), 256, RuntimeProvider>,
+    /// Interned import names shared by `import_order` entries
+    #[cfg(feature = "std")]
+    pub names:           StringPool,
     /// Function definitions
     /// In std mode, use Vec since Function has variable size (contains BoundedVecs for locals/instructions)
     #[cfg(feature = "std")]
@@ -766,6 +978,12 @@ pub fn push_memory(&mut self, memory: MemoryWrapper) -> Result<()> {
         self.memories.push(memory)
     }
 
+    /// Footprint of the interned import names compared with owned strings
+    #[cfg(feature = "std")]
+    pub fn name_footprint(&self) -> StringPoolStats {
+        self.names.stats()
+    }
+
     /// Count the number of tag imports in the module
     #[cfg(feature = "std")]
     pub fn count_tag_imports(&self) -> usize {
@@ -1242,6 +1460,8 @@ pub fn new_empty() -> Result<Self> {
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone())?,
             #[cfg(feature = "std")]
             import_order: Vec::new(),
+            #[cfg(feature = "std")]
+            names: StringPool::new(),
             #[cfg(not(feature = "std"))]
             import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
             functions: Vec::new(),
@@ -1283,6 +1503,28 @@ pub fn new_empty() -> Result<Self> {
     /// This is the primary constructor after decoding.
     #[cfg(feature = "std")]
     pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Self>> {
+        Self::from_wrt_module_with(wrt_module, BodyParsing::Eager)
+    }
+
+    /// Creates a runtime Module, parsing function bodies as `parsing`
+    /// selects
+    ///
+    /// [`BodyParsing::Auto`] compares its threshold with the size of the
+    /// code of all bodies; resolve it against the binary size with
+    /// [`BodyParsing::for_binary`] first to use that instead.
+    #[cfg(feature = "std")]
+    pub fn from_wrt_module_with(
+        wrt_module: &wrt_format::module::Module,
+        parsing: BodyParsing,
+    ) -> Result<Box<Self>> {
+        let code_size = wrt_module.functions.iter().map(|func| func.code.len()).sum();
+        let lazy_bodies = parsing.is_lazy(code_size);
+        let mut parsed_bodies = if parsing.threads() > 1 && wrt_module.functions.len() > 1 {
+            parse_bodies_parallel(&wrt_module.functions, parsing.threads())?
+        } else {
+            Vec::new()
+        };
+
         // Ensure memory system is initialized before creating providers
         wrt_foundation::memory_init::MemoryInitializer::ensure_initialized()?;
 
@@ -1297,6 +1539,7 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
             types: Vec::new(),
             imports: imports_map,
             import_order: Vec::new(), // Ordered list of imports for index-based lookup
+            names: StringPool::new(),
             functions: Vec::new(),
             tables: Vec::new(), // Vec in std mode to avoid serialization issues with Arc<Table>
             memories: Vec::new(),
@@ -1414,7 +1657,8 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
             // Track import order for index-based lookup
             #[cfg(feature = "std")]
             {
-                runtime_module.import_order.push((import.module.to_string(), import.name.to_string()));
+                let order_entry = (runtime_module.names.intern(&import.module), runtime_module.names.intern(&import.name));
+                runtime_module.import_order.push(order_entry);
                 // Also store the import type for fast lookup during linking
                 let import_desc = match &import.desc {
                     FormatImportDesc::Function(type_idx) => RuntimeImportDesc::Function(*type_idx),
@@ -1456,6 +1700,7 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
             trace!(func_idx = func_idx, type_idx = func.type_idx, locals_len = func.locals.len(), code_len = func.code.len(), "Processing function");
 
             // Handle imported functions (they have no code, but still need to be in the function table)
+            let mut lazy_body = None;
             let (locals, body) = if func.code.is_empty() {
                 #[cfg(feature = "tracing")]
                 trace!(func_idx = func_idx, "Function is imported (no code) - creating stub entry");
@@ -1485,12 +1730,20 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
                     trace!(func_idx = func_idx, "Warning - Function has empty code");
                 }
 
-                let instructions = crate::instruction_parser::parse_instructions_with_provider(&func.code, shared_provider.clone())?;
+                if lazy_bodies {
+                    lazy_body = Some(Arc::new(LazyBody::new(func.code.clone())));
+                    (locals, WrtExpr::default())
+                } else {
+                    let instructions = match parsed_bodies.get_mut(func_idx).and_then(Option::take) {
+                        Some(instructions) => instructions,
+                        None => crate::instruction_parser::parse_instructions_with_provider(&func.code, shared_provider.clone())?,
+                    };
 
-                #[cfg(feature = "tracing")]
-                trace!(func_idx = func_idx, instruction_count = instructions.len(), "Parsed instructions for function");
+                    #[cfg(feature = "tracing")]
+                    trace!(func_idx = func_idx, instruction_count = instructions.len(), "Parsed instructions for function");
 
-                (locals, WrtExpr { instructions })
+                    (locals, WrtExpr { instructions })
+                }
             };
 
             #[cfg(feature = "tracing")]
@@ -1499,6 +1752,8 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
                 type_idx: func.type_idx,
                 locals,
                 body,
+                lazy_body,
+                code_digest: wrt_foundation::sha256::sha256(&func.code),
             };
             // CRITICAL DEBUG: Test provider directly before using BoundedVec
             #[cfg(feature = "tracing")]
@@ -1737,6 +1992,11 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Box<Se
                         // i32.const - parse LEB128 value
                         let (value, _) = crate::instruction_parser::read_leb128_i32(offset_bytes, 1)?;
                         value as u32
+                    } else if cfg!(feature = "memory64") && !offset_bytes.is_empty() && offset_bytes[0] == 0x42 {
+                        // i64.const offset of a memory64 segment
+                        let (value, _) = crate::instruction_parser::read_leb128_i64(offset_bytes, 1)?;
+                        u32::try_from(value as u64)
+                            .map_err(|_| Error::wasm_trap(wrt_error::codes::TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?
                     } else {
                         0
                     };
@@ -2092,7 +2352,10 @@ pub fn from_wrt_module_nostd(wrt_module: &wrt_format::module::Module) -> Result<
 
             // Track import order for index-based lookup
             #[cfg(feature = "std")]
-            runtime_module.import_order.push((import.module.to_string(), import.name.to_string()));
+            {
+                let order_entry = (runtime_module.names.intern(&import.module), runtime_module.names.intern(&import.name));
+                runtime_module.import_order.push(order_entry);
+            }
             #[cfg(not(feature = "std"))]
             {
                 let order_module = wrt_foundation::bounded::BoundedString::from_str_truncate(&import.module)?;
@@ -2114,6 +2377,9 @@ pub fn from_wrt_module_nostd(wrt_module: &wrt_format::module::Module) -> Result<
                 locals:   crate::type_conversion::convert_locals_to_bounded(&function.locals)?,
                 // Body conversion would happen here
                 body:     WrtExpr::default(),
+                #[cfg(feature = "std")]
+                lazy_body: None,
+                code_digest: [0; 32],
             })?;
         }
 
@@ -2358,6 +2624,9 @@ pub fn from_wrt_foundation_module(
                 type_idx,
                 locals: runtime_locals,
                 body: runtime_body,
+                #[cfg(feature = "std")]
+                lazy_body: None,
+                code_digest: [0; 32],
             })?;
         }
 
@@ -2906,6 +3175,9 @@ pub fn add_function_type(&mut self, type_idx: u32) -> Result<()> {
             type_idx,
             locals: wrt_foundation::bounded::BoundedVec::new(provider)?,
             body: WrtExpr::default(),
+            #[cfg(feature = "std")]
+            lazy_body: None,
+            code_digest: [0; 32],
         };
 
         self.push_function(function)?;
@@ -3069,6 +3341,9 @@ pub fn set_function_body(
             type_idx,
             locals: bounded_locals,
             body,
+            #[cfg(feature = "std")]
+            lazy_body: None,
+            code_digest: [0; 32],
         };
         if func_idx as usize == self.functions.len() {
             self.push_function(func_entry)?;
@@ -3430,6 +3705,46 @@ pub fn load_from_binary(&mut self, binary: &[u8]) -> Result<Self> {
         })
     }
 
+    /// Load a module from WebAssembly binary, skipping validation when
+    /// `cache` holds a signed artifact for it
+    ///
+    /// Modules without an artifact are validated against the platform
+    /// limits and their artifact is recorded in `cache` once they pass.
+    /// With [`CacheVerification::Strict`](crate::validation_cache::CacheVerification::Strict)
+    /// the artifact chain is re-verified on every load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary does not decode or fails validation,
+    /// or `codes::INTEGRITY_VIOLATION` if the artifact chain does not verify.
+    #[cfg(feature = "std")]
+    pub fn from_binary_with_cache(
+        binary: &[u8],
+        cache: &mut crate::validation_cache::ValidationCache,
+    ) -> Result<Box<Self>> {
+        use wrt_decoder::{
+            decoder::decode_module,
+            ComprehensivePlatformLimits,
+            StreamingWasmValidator,
+        };
+
+        let module_hash = crate::validation_cache::ValidationCache::module_hash(binary);
+        let trusted = cache.lookup(&module_hash)?;
+        if !trusted {
+            StreamingWasmValidator::new(ComprehensivePlatformLimits::default())
+                .validate_single_pass(binary)?;
+        }
+
+        let decoded = Box::new(decode_module(binary)?);
+        let mut module = Self::from_wrt_module(&decoded)?;
+        if !trusted {
+            module.validate()?;
+            cache.record(module_hash);
+        }
+        module.validated = true;
+        Ok(module)
+    }
+
     /// Create runtime Module from unified API ModuleInfo
     fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Result<Self> {
         // Create module directly using create_runtime_provider
@@ -3439,6 +3754,8 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone())?,
             #[cfg(feature = "std")]
             import_order: Vec::new(),
+            #[cfg(feature = "std")]
+            names: StringPool::new(),
             #[cfg(not(feature = "std"))]
             import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
             functions: Vec::new(),
@@ -3506,6 +3823,7 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
                     let memory_type = WrtMemoryType {
                         limits: WrtLimits { min: 0, max: None },  // Will be resolved via linking
                         shared: true,  // Component Model uses shared memory
+                        memory64: false,
                     };
                     ExternType::Memory(memory_type)
                 },
@@ -3592,7 +3910,10 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
 
             // Track import order for index-based lookup
             #[cfg(feature = "std")]
-            runtime_module.import_order.push((import.module.clone(), import.name.clone()));
+            {
+                let order_entry = (runtime_module.names.intern(&import.module), runtime_module.names.intern(&import.name));
+                runtime_module.import_order.push(order_entry);
+            }
             #[cfg(not(feature = "std"))]
             {
                 let order_module = wrt_foundation::bounded::BoundedString::from_str_truncate(&import.module)?;
@@ -3627,6 +3948,7 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
                     max: max_pages,
                 },
                 shared: false,
+                memory64: false,
             };
             runtime_module
                 .push_memory(MemoryWrapper::new(Memory::new(to_core_memory_type(
@@ -3713,7 +4035,7 @@ pub fn get_tag_import_identity(&self, tag_idx: u32) -> Option<(String, String)>
                     if tag_import_idx == tag_idx as usize {
                         // Found the tag import - get the module/name from import_order
                         if let Some((module, name)) = self.import_order.get(import_order_idx) {
-                            return Some((module.clone(), name.clone()));
+                            return Some((module.to_string(), name.to_string()));
                         }
                     }
                     tag_import_idx += 1;
@@ -3902,6 +4224,8 @@ fn from_bytes_with_provider<'a, PStream: wrt_foundation::MemoryProvider>(
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone())?,
             #[cfg(feature = "std")]
             import_order: Vec::new(),
+            #[cfg(feature = "std")]
+            names: StringPool::new(),
             #[cfg(not(feature = "std"))]
             import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
             functions: Vec::new(),
@@ -4485,13 +4809,16 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         reader.read_exact(&mut bytes)?;
 
         // FAIL LOUD AND EARLY: This violates the NO FALLBACK LOGIC rule
-        // Memories must be shared from Module 0, not created during deserialization
-        panic!(
-            "CRITICAL: MemoryWrapper::from_bytes called - this indicates a memory linking bug. \
-             Wrapper modules should inherit memory from Module 0 through shared-everything linking, \
-             not create new memories during deserialization. The hardcoded 1-page memory (min: 1, max: Some(1)) \
-             was masking a bug where wrapper modules weren't properly sharing Module 0's memory."
-        );
+        // Memories must be shared from Module 0, not created during deserialization.
+        // The hardcoded 1-page memory (min: 1, max: Some(1)) this used to build was
+        // masking a bug where wrapper modules weren't properly sharing Module 0's memory.
+        wrt_foundation::wrt_assert!(
+            false,
+            "CRITICAL: MemoryWrapper::from_bytes called - this indicates a memory linking bug"
+        )?;
+        Err(Error::runtime_error(
+            "MemoryWrapper::from_bytes called - memories must be shared from Module 0",
+        ))
     }
 }
 
@@ -4778,3 +5105,46 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
 // Ensure local `crate::module::Export` struct is defined
 // Ensure local `crate::global::Global`, `crate::table::Table`,
 // `crate::memory::Memory` are defined and their `new` methods are compatible.
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    /// Module importing `names` from `wasi_snapshot_preview1`, all of type
+    /// `() -> ()`; the import section must stay below 128 bytes
+    fn wasi_imports_module(names: &[&str]) -> Vec<u8> {
+        let mut imports = vec![names.len() as u8];
+        for name in names {
+            imports.push(22);
+            imports.extend_from_slice(b"wasi_snapshot_preview1");
+            imports.push(name.len() as u8);
+            imports.extend_from_slice(name.as_bytes());
+            imports.extend_from_slice(&[0x00, 0x00]);
+        }
+
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        binary.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        binary.push(0x02);
+        binary.push(imports.len() as u8);
+        binary.extend_from_slice(&imports);
+        binary
+    }
+
+    #[test]
+    fn test_import_names_are_interned() {
+        let binary = wasi_imports_module(&["fd_write", "fd_read", "fd_write"]);
+        let decoded = wrt_decoder::decoder::decode_module(&binary).unwrap();
+        let module = Module::from_wrt_module(&decoded).unwrap();
+
+        assert_eq!(module.import_order.len(), 3);
+        assert_eq!(module.import_order[1].1, "fd_read");
+        assert!(module.import_order.iter().all(|(m, _)| m == "wasi_snapshot_preview1"));
+        // One module name and two distinct field names
+        assert_eq!(module.names.len(), 3);
+
+        let footprint = module.name_footprint();
+        assert_eq!(footprint.strings, 6);
+        assert!(footprint.pooled_footprint() < footprint.owned_footprint());
+        assert!(decoded.name_footprint().saved_bytes() > 0);
+    }
+}
@@ -132,6 +132,7 @@ fn new() -> Self {
             types: Vec::new(),
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone()).expect("Failed to create imports"),
             import_order: Vec::new(),
+            names: wrt_format::StringPool::new(),
             functions: Vec::new(),
             tables: Vec::new(),
             memories: Vec::new(),
@@ -264,6 +265,9 @@ fn add_function_body(
             type_idx,
             locals,
             body: WrtExpr { instructions },
+            #[cfg(feature = "std")]
+            lazy_body: None,
+            code_digest: [0; 32],
         };
 
         // Add to module's functions
@@ -401,6 +405,8 @@ pub fn with_binary(_binary: Vec<u8>) -> Result<Self> {
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone())?,
             #[cfg(feature = "std")]
             import_order: Vec::new(),
+            #[cfg(feature = "std")]
+            names: wrt_format::StringPool::new(),
             #[cfg(not(feature = "std"))]
             import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
             functions: Vec::new(),
@@ -200,6 +200,34 @@ pub fn module(&self) -> &Arc<Module> {
         &self.module
     }
 
+    /// Number of memories in this instance, imported ones included
+    #[cfg(feature = "std")]
+    pub fn memory_count(&self) -> Result<usize> {
+        let memories = self
+            .memories
+            .lock()
+            .map_err(|_| Error::runtime_error("Failed to lock memories"))?;
+        Ok(memories.len())
+    }
+
+    /// Number of tables in this instance, imported ones included
+    #[cfg(feature = "std")]
+    pub fn table_count(&self) -> Result<usize> {
+        let tables =
+            self.tables.lock().map_err(|_| Error::runtime_error("Failed to lock tables"))?;
+        Ok(tables.len())
+    }
+
+    /// Number of globals in this instance, imported ones included
+    #[cfg(feature = "std")]
+    pub fn global_count(&self) -> Result<usize> {
+        let globals = self
+            .globals
+            .lock()
+            .map_err(|_| Error::runtime_error("Failed to lock globals"))?;
+        Ok(globals.len())
+    }
+
     /// Get a memory from this instance
     pub fn memory(&self, idx: u32) -> Result<MemoryWrapper> {
         #[cfg(feature = "std")]
@@ -907,6 +935,19 @@ pub fn is_data_segment_dropped(&self, segment_idx: u32) -> bool {
     /// Initialize data segments into memory
     /// This copies the static data from data segments into the appropriate memory locations
     pub fn initialize_data_segments(&self) -> Result<()> {
+        self.write_data_segments(None)
+    }
+
+    /// Initialize data segments into every memory except `memory_idx`
+    ///
+    /// Used when that memory already holds its initial contents, e.g. because
+    /// it was spawned from a snapshot.
+    pub fn initialize_data_segments_except(&self, memory_idx: u32) -> Result<()> {
+        self.write_data_segments(Some(memory_idx))
+    }
+
+    /// Write the active data segments, skipping those targeting `skip_memory`
+    fn write_data_segments(&self, skip_memory: Option<u32>) -> Result<()> {
         #[cfg(feature = "tracing")]
         use wrt_foundation::tracing::{debug, info};
         use wrt_foundation::DataMode as WrtDataMode;
@@ -933,6 +974,11 @@ pub fn initialize_data_segments(&self) -> Result<()> {
 
                 // Get the memory index (default to 0 if not specified)
                 let memory_idx = data_segment.memory_idx.unwrap_or(0);
+                if skip_memory == Some(memory_idx) {
+                    #[cfg(feature = "tracing")]
+                    debug!("Skipping data segment {} for initialized memory {}", idx, memory_idx);
+                    continue;
+                }
 
                 // Get the offset expression and evaluate it
                 let offset = if let Some(ref offset_expr) = data_segment.offset_expr {
@@ -950,6 +996,14 @@ pub fn initialize_data_segments(&self) -> Result<()> {
                                 debug!("Data segment {} has I32Const offset: {}", idx, value);
                                 *value as u32
                             }
+                            #[cfg(feature = "memory64")]
+                            wrt_foundation::types::Instruction::I64Const(value) => {
+                                // Memory64 segments are placed with an i64 offset
+                                #[cfg(feature = "tracing")]
+                                debug!("Data segment {} has I64Const offset: {}", idx, value);
+                                u32::try_from(*value as u64)
+                                    .map_err(|_| Error::wasm_trap(wrt_error::codes::TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?
+                            }
                             wrt_foundation::types::Instruction::GlobalGet(global_idx) => {
                                 // Look up the global value for the offset
                                 #[cfg(feature = "tracing")]
@@ -971,6 +1025,12 @@ pub fn initialize_data_segments(&self) -> Result<()> {
                                                     debug!("Data segment {} global offset value: {}", idx, v);
                                                     *v as u32
                                                 },
+                                                #[cfg(feature = "memory64")]
+                                                wrt_foundation::values::Value::I64(v) => {
+                                                    u32::try_from(*v as u64).map_err(|_| {
+                                                        Error::wasm_trap(wrt_error::codes::TrapCode::MemoryOutOfBounds, "out of bounds memory access")
+                                                    })?
+                                                },
                                                 _ => {
                                                     #[cfg(feature = "tracing")]
                                                     debug!("Data segment {} global has non-i32 type, using 0", idx);
@@ -1681,6 +1741,8 @@ fn from_bytes_with_provider<'a, PStream: wrt_foundation::MemoryProvider>(
             imports: wrt_foundation::bounded_collections::BoundedMap::new(provider.clone())?,
             #[cfg(feature = "std")]
             import_order: Vec::new(),
+            #[cfg(feature = "std")]
+            names: wrt_format::StringPool::new(),
             #[cfg(not(feature = "std"))]
             import_order: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
             functions: Vec::new(),
@@ -0,0 +1,443 @@
+//! Runtime-loaded interceptor strategy and host function plugins
+//!
+//! Loads shared objects implementing the C ABI in
+//! [`wrt_intercept::plugin_abi`] and adapts what they provide to the
+//! runtime: plugin interceptors become [`LinkInterceptorStrategy`]s and
+//! plugin host functions become [`HostFunctionHandler`]s. The engine
+//! registers both through `CapabilityAwareEngine::load_plugin`.
+//!
+//! Every descriptor is validated before anything from it is used: the ABI
+//! version and descriptor size must match exactly, counts must be within
+//! the ABI's bounds, and all names must be valid UTF-8.
+//!
+//! # Safety
+//!
+//! Loading a plugin runs its initialization code, and calling into it
+//! trusts it to honour the plugin contract documented in
+//! [`wrt_intercept::plugin_abi`]. This module requires unsafe code for the
+//! dynamic loading and the calls across the C ABI. The shared object stays
+//! loaded for as long as any strategy or host function created from it is
+//! alive.
+
+#![allow(unsafe_code)]
+
+use std::{
+    fmt,
+    path::{
+        Path,
+        PathBuf,
+    },
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+
+use libloading::Library;
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::values::Value;
+use wrt_host::{
+    CloneableFn,
+    HostFunctionHandler,
+};
+use wrt_intercept::{
+    plugin_abi::{
+        PluginDescriptor,
+        PluginEntryFn,
+        PluginHostFunction,
+        PluginInterceptor,
+        PluginStr,
+        PluginValue,
+        MAX_PLUGIN_HOST_FUNCTIONS,
+        MAX_PLUGIN_INTERCEPTORS,
+        MAX_PLUGIN_NAME_LENGTH,
+        MAX_PLUGIN_RESULTS,
+        PLUGIN_ABI_VERSION,
+        PLUGIN_ENTRY_SYMBOL,
+        PLUGIN_OK,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// What a loaded plugin provides
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    /// Name of the plugin
+    pub name:           String,
+    /// Version of the plugin
+    pub version:        String,
+    /// Shared object the plugin was loaded from
+    pub path:           PathBuf,
+    /// Names of the interceptor strategies
+    pub interceptors:   Vec<String>,
+    /// Module and function names of the host functions
+    pub host_functions: Vec<(String, String)>,
+}
+
+/// Shared object of a plugin, unloaded when the last user is dropped
+struct PluginLibrary {
+    /// The loaded shared object
+    library:    Library,
+    /// Descriptor returned by the plugin's entry point
+    descriptor: *const PluginDescriptor,
+}
+
+// SAFETY: the plugin contract requires descriptors to be immutable and
+// callbacks to be callable from any thread.
+unsafe impl Send for PluginLibrary {}
+// SAFETY: see `Send` above.
+unsafe impl Sync for PluginLibrary {}
+
+impl Drop for PluginLibrary {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor was validated at load time and stays valid
+        // until `unload` has returned; the library is unloaded afterwards.
+        unsafe {
+            if let Some(unload) = (*self.descriptor).unload {
+                unload();
+            }
+        }
+    }
+}
+
+/// A plugin loaded from a shared object
+pub struct LoadedPlugin {
+    /// The shared object and its descriptor
+    library: Arc<PluginLibrary>,
+    /// What the plugin provides
+    info:    PluginInfo,
+}
+
+impl fmt::Debug for LoadedPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadedPlugin").field("info", &self.info).finish()
+    }
+}
+
+impl LoadedPlugin {
+    /// Load and validate the plugin at `path`
+    ///
+    /// # Safety
+    ///
+    /// Loading runs the shared object's initialization code and entry
+    /// point with the privileges of the host process. The caller must trust
+    /// the shared object to implement the plugin contract of
+    /// [`wrt_intercept::plugin_abi`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared object cannot be loaded, does not
+    /// export the entry point, or returns a descriptor for a different ABI
+    /// version or with invalid contents.
+    pub unsafe fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        // SAFETY: the caller trusts the shared object.
+        let library = unsafe { Library::new(path) }
+            .map_err(|_| plugin_error(codes::PLUGIN_LOAD_FAILED, "Failed to load plugin shared object"))?;
+        // SAFETY: the symbol is declared by the ABI to have this type.
+        let entry: PluginEntryFn = unsafe {
+            *library
+                .get::<PluginEntryFn>(PLUGIN_ENTRY_SYMBOL.as_bytes())
+                .map_err(|_| plugin_error(codes::PLUGIN_LOAD_FAILED, "Plugin does not export an entry point"))?
+        };
+        // SAFETY: calling the entry point is part of the trusted contract.
+        let descriptor = unsafe { entry(PLUGIN_ABI_VERSION) };
+        if descriptor.is_null() {
+            return Err(plugin_error(
+                codes::PLUGIN_ABI_MISMATCH,
+                "Plugin does not support this plugin ABI version",
+            ));
+        }
+
+        // SAFETY: the descriptor is non-null and valid per the contract.
+        let info = unsafe { validate_descriptor(&*descriptor, path)? };
+        Ok(Self {
+            library: Arc::new(PluginLibrary { library, descriptor }),
+            info,
+        })
+    }
+
+    /// What the plugin provides
+    pub fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    /// The plugin's interceptor strategies, in declaration order
+    pub fn interceptor_strategies(&self) -> Vec<Arc<dyn LinkInterceptorStrategy>> {
+        (0..self.info.interceptors.len())
+            .map(|index| {
+                Arc::new(PluginStrategy { library: self.library.clone(), index }) as Arc<dyn LinkInterceptorStrategy>
+            })
+            .collect()
+    }
+
+    /// The plugin's host functions as (module, function, handler)
+    pub fn host_functions(&self) -> Vec<(String, String, HostFunctionHandler)> {
+        self.info
+            .host_functions
+            .iter()
+            .enumerate()
+            .map(|(index, (module, name))| {
+                let function = PluginFunction { library: self.library.clone(), index };
+                let handler = CloneableFn::new_with_args(move |_target, args: Vec<Value>| function.call(&args));
+                (module.clone(), name.clone(), handler)
+            })
+            .collect()
+    }
+}
+
+/// Interceptor strategy implemented by a plugin
+#[derive(Clone)]
+struct PluginStrategy {
+    /// The plugin's shared object
+    library: Arc<PluginLibrary>,
+    /// Index into the descriptor's interceptors
+    index:   usize,
+}
+
+impl PluginStrategy {
+    fn interceptor(&self) -> &PluginInterceptor {
+        // SAFETY: `index` was checked against the validated descriptor, which
+        // outlives `self.library`.
+        unsafe { &*(*self.library.descriptor).interceptors.add(self.index) }
+    }
+}
+
+impl LinkInterceptorStrategy for PluginStrategy {
+    fn before_call(&self, source: &str, target: &str, function: &str, args: &[Value]) -> Result<Vec<Value>> {
+        let interceptor = self.interceptor();
+        if let Some(before_call) = interceptor.before_call {
+            let plugin_args: Vec<PluginValue> = args.iter().map(PluginValue::from_value).collect();
+            // SAFETY: the strings and arguments outlive the call; the
+            // callback is trusted per the plugin contract.
+            let status = unsafe {
+                before_call(
+                    interceptor.context,
+                    PluginStr::borrowed(source),
+                    PluginStr::borrowed(target),
+                    PluginStr::borrowed(function),
+                    plugin_args.as_ptr(),
+                    plugin_args.len(),
+                )
+            };
+            if status != PLUGIN_OK {
+                return Err(plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin interceptor denied the call"));
+            }
+        }
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        let interceptor = self.interceptor();
+        if let Some(after_call) = interceptor.after_call {
+            let (results, call_status) = match &result {
+                Ok(values) => (values.iter().map(PluginValue::from_value).collect(), PLUGIN_OK),
+                Err(_) => (Vec::new(), -1),
+            };
+            // SAFETY: as in `before_call`.
+            let status = unsafe {
+                after_call(
+                    interceptor.context,
+                    PluginStr::borrowed(source),
+                    PluginStr::borrowed(target),
+                    PluginStr::borrowed(function),
+                    results.as_ptr(),
+                    results.len(),
+                    call_status,
+                )
+            };
+            if status != PLUGIN_OK {
+                return Err(plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin interceptor failed the call"));
+            }
+        }
+        result
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(self.clone())
+    }
+}
+
+/// Host function implemented by a plugin
+#[derive(Clone)]
+struct PluginFunction {
+    /// The plugin's shared object
+    library: Arc<PluginLibrary>,
+    /// Index into the descriptor's host functions
+    index:   usize,
+}
+
+impl PluginFunction {
+    fn call(&self, args: &[Value]) -> Result<Vec<Value>> {
+        // SAFETY: `index` was checked against the validated descriptor, which
+        // outlives `self.library`.
+        let function: &PluginHostFunction =
+            unsafe { &*(*self.library.descriptor).host_functions.add(self.index) };
+        let call = function
+            .call
+            .ok_or_else(|| plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin host function has no implementation"))?;
+
+        let plugin_args: Vec<PluginValue> = args.iter().map(PluginValue::from_value).collect();
+        let mut results = [PluginValue { kind: 0, bits: 0 }; MAX_PLUGIN_RESULTS];
+        let mut result_count = 0usize;
+        // SAFETY: the buffers outlive the call and `results` holds
+        // `MAX_PLUGIN_RESULTS` values; the function is trusted per the
+        // plugin contract.
+        let status = unsafe {
+            call(
+                function.context,
+                plugin_args.as_ptr(),
+                plugin_args.len(),
+                results.as_mut_ptr(),
+                results.len(),
+                &mut result_count,
+            )
+        };
+        if status != PLUGIN_OK {
+            return Err(plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin host function failed"));
+        }
+        if result_count > MAX_PLUGIN_RESULTS {
+            return Err(plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin host function returned too many results"));
+        }
+
+        results[..result_count]
+            .iter()
+            .map(|value| {
+                value.to_value().ok_or_else(|| {
+                    plugin_error(codes::PLUGIN_CALL_FAILED, "Plugin host function returned an unsupported value")
+                })
+            })
+            .collect()
+    }
+}
+
+/// Check a descriptor against this ABI and collect what it provides
+///
+/// # Safety
+///
+/// The descriptor's pointers must be valid per the plugin contract for the
+/// counts it declares, once those are within the ABI's bounds.
+unsafe fn validate_descriptor(descriptor: &PluginDescriptor, path: &Path) -> Result<PluginInfo> {
+    if descriptor.abi_version != PLUGIN_ABI_VERSION || descriptor.descriptor_size != PluginDescriptor::SIZE {
+        return Err(plugin_error(codes::PLUGIN_ABI_MISMATCH, "Plugin was built for a different plugin ABI"));
+    }
+    if descriptor.interceptor_count > MAX_PLUGIN_INTERCEPTORS
+        || descriptor.host_function_count > MAX_PLUGIN_HOST_FUNCTIONS
+    {
+        return Err(plugin_error(codes::PLUGIN_ABI_MISMATCH, "Plugin declares too many entries"));
+    }
+    if (descriptor.interceptor_count > 0 && descriptor.interceptors.is_null())
+        || (descriptor.host_function_count > 0 && descriptor.host_functions.is_null())
+    {
+        return Err(plugin_error(codes::PLUGIN_ABI_MISMATCH, "Plugin declares entries without a table"));
+    }
+
+    // SAFETY: pointers and counts were checked above and are valid per the
+    // contract.
+    unsafe {
+        let mut interceptors = Vec::with_capacity(descriptor.interceptor_count);
+        for index in 0..descriptor.interceptor_count {
+            let interceptor = &*descriptor.interceptors.add(index);
+            interceptors.push(plugin_string(interceptor.name)?);
+        }
+
+        let mut host_functions = Vec::with_capacity(descriptor.host_function_count);
+        for index in 0..descriptor.host_function_count {
+            let function = &*descriptor.host_functions.add(index);
+            if function.call.is_none() {
+                return Err(plugin_error(
+                    codes::PLUGIN_ABI_MISMATCH,
+                    "Plugin host function has no implementation",
+                ));
+            }
+            host_functions.push((plugin_string(function.module)?, plugin_string(function.name)?));
+        }
+
+        Ok(PluginInfo {
+            name: plugin_string(descriptor.name)?,
+            version: plugin_string(descriptor.version)?,
+            path: path.to_path_buf(),
+            interceptors,
+            host_functions,
+        })
+    }
+}
+
+/// Copy a bounded UTF-8 string out of a plugin
+///
+/// # Safety
+///
+/// `s` must point to `s.len` readable bytes unless it is null.
+unsafe fn plugin_string(s: PluginStr) -> Result<String> {
+    if s.len == 0 {
+        return Ok(String::new());
+    }
+    if s.ptr.is_null() || s.len > MAX_PLUGIN_NAME_LENGTH {
+        return Err(plugin_error(codes::PLUGIN_ABI_MISMATCH, "Plugin name is invalid"));
+    }
+    // SAFETY: non-null with `len` readable bytes per the caller.
+    let bytes = unsafe { core::slice::from_raw_parts(s.ptr, s.len) };
+    core::str::from_utf8(bytes)
+        .map(String::from)
+        .map_err(|_| plugin_error(codes::PLUGIN_ABI_MISMATCH, "Plugin name is not valid UTF-8"))
+}
+
+fn plugin_error(code: u16, message: &'static str) -> Error {
+    Error::new(ErrorCategory::System, code, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rejects_missing_library() {
+        // SAFETY: the path does not exist, so no code is run.
+        let err = unsafe { LoadedPlugin::load("/nonexistent/libwrt_plugin_missing.so") }.unwrap_err();
+        assert_eq!(err.code, codes::PLUGIN_LOAD_FAILED);
+    }
+
+    #[test]
+    fn test_validate_descriptor_checks_abi() {
+        let name = PluginStr::from_static("demo");
+        let mut descriptor = PluginDescriptor {
+            abi_version:         PLUGIN_ABI_VERSION,
+            descriptor_size:     PluginDescriptor::SIZE,
+            name,
+            version:             PluginStr::from_static("1.0.0"),
+            interceptors:        core::ptr::null(),
+            interceptor_count:   0,
+            host_functions:      core::ptr::null(),
+            host_function_count: 0,
+            unload:              None,
+        };
+        let path = Path::new("libdemo.so");
+
+        // SAFETY: the descriptor's strings are static and its tables empty.
+        let info = unsafe { validate_descriptor(&descriptor, path) }.unwrap();
+        assert_eq!(info.name, "demo");
+        assert_eq!(info.version, "1.0.0");
+
+        descriptor.abi_version = PLUGIN_ABI_VERSION + 1;
+        // SAFETY: as above.
+        let err = unsafe { validate_descriptor(&descriptor, path) }.unwrap_err();
+        assert_eq!(err.code, codes::PLUGIN_ABI_MISMATCH);
+
+        descriptor.abi_version = PLUGIN_ABI_VERSION;
+        descriptor.host_function_count = 1;
+        // SAFETY: the null table is rejected before it is read.
+        let err = unsafe { validate_descriptor(&descriptor, path) }.unwrap_err();
+        assert_eq!(err.code, codes::PLUGIN_ABI_MISMATCH);
+    }
+}
@@ -5,7 +5,8 @@
 //! consistency across all crates in the WRT project and simplify imports in
 //! individual modules.
 
-// Core imports for both std and no_std environments
+// Shared runtime-tier prelude
+pub use wrt_prelude::runtime::*;
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -24,10 +25,6 @@
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 pub type BoundedHashSet<T, P> = wrt_foundation::bounded_collections::BoundedSet<T, 128, P>;
 
-// Platform-aware string and vector types
-#[cfg(not(feature = "std"))]
-pub use wrt_foundation::bounded::BoundedString;
-
 // Helper macro to create Vec
 /// Create a new Vec for `no_std` environments
 #[cfg(not(feature = "std"))]
@@ -184,59 +181,15 @@ fn deref(&self) -> &Self::Target {
     vec,
     vec::Vec,
 };
-pub use core::{
-    any::Any,
-    cmp::{
-        Eq,
-        Ord,
-        PartialEq,
-        PartialOrd,
-    },
-    convert::{
-        TryFrom,
-        TryInto,
-    },
-    fmt,
-    fmt::{
-        Debug,
-        Display,
-    },
-    marker::PhantomData,
-    mem,
-    ops::{
-        Deref,
-        DerefMut,
-    },
-    slice,
-    str,
-    sync::atomic::{
-        AtomicUsize,
-        Ordering as AtomicOrdering,
-    },
+pub use core::sync::atomic::{
+    AtomicUsize,
+    Ordering as AtomicOrdering,
 };
-// Re-export from std when the std feature is enabled
 #[cfg(feature = "std")]
-pub use std::{
-    boxed::Box,
-    collections::{
-        HashMap,
-        HashSet,
-    },
-    format,
-    string::{
-        String,
-        ToString,
-    },
-    sync::{
-        Arc,
-        Mutex,
-        MutexGuard,
-        RwLock,
-        RwLockReadGuard,
-        RwLockWriteGuard,
-    },
-    vec,
-    vec::Vec,
+pub use std::sync::{
+    MutexGuard,
+    RwLockReadGuard,
+    RwLockWriteGuard,
 };
 
 // Provide String and ToString for pure no_std environments
@@ -280,9 +233,6 @@ fn to_string(&self) -> RuntimeString {
         RuntimeError,
         ValidationError,
     },
-    Error,
-    ErrorCategory,
-    Result,
 };
 // Re-export from wrt-format for format specifications (aliased to avoid name clashes)
 #[cfg(feature = "std")]
@@ -316,12 +266,6 @@ fn to_string(&self) -> RuntimeString {
 // Re-export from wrt-foundation for core types
 #[cfg(feature = "std")]
 pub use wrt_foundation::component::ComponentType;
-// Import required traits (these should already be implemented by wrt_foundation::Value)
-pub use wrt_foundation::traits::{
-    Checksummable,
-    FromBytes,
-    ToBytes,
-};
 // Re-export core types from wrt_foundation instead of wrt_format
 pub use wrt_foundation::types::{
     CustomSection, /* Assuming this is the intended replacement for FormatCustomSection
@@ -334,15 +278,11 @@ fn to_string(&self) -> RuntimeString {
 pub use wrt_foundation::{
     prelude::{
         BoundedStack,
-        BoundedVec,
         ResourceType,
         SafeMemoryHandler,
         SafeSlice,
-        ValueType,
-        VerificationLevel,
     },
     safe_memory::SafeStack,
-    traits::BoundedCapacity, // Add trait for len(), is_empty(), etc.
     types::{
         DataSegment,
         ElementSegment,
@@ -560,7 +500,8 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
     LinkInterceptor as InterceptorRegistry,
     LinkInterceptorStrategy as InterceptStrategy,
 };
-// Binary std/no_std choice
+// Replaces the tier's locks in no_std builds: the tier follows
+// `wrt-prelude/std`, which other crates in the graph may enable
 #[cfg(not(feature = "std"))]
 pub use wrt_sync::{
     WrtMutex as Mutex,
@@ -0,0 +1,638 @@
+//! Priority-based memory reclamation
+//!
+//! Caches, hibernated instances and pools of idle buffers all hold memory
+//! that can be given back when the host runs short. Rather than each of them
+//! watching the memory budget and reacting on its own, they register as
+//! [`Reclaimable`] sources with a [`ReclamationOrchestrator`]. When usage
+//! reported by the memory coordinator crosses the high watermark of the
+//! [`ReclamationConfig`], the orchestrator frees memory tier by tier in the
+//! order of [`ReclaimTier`] (cheapest to rebuild first) until usage is back
+//! below the low watermark. The gap between the watermarks keeps the
+//! orchestrator from reclaiming again on every small allocation.
+//!
+//! Sources implemented here are the [`CacheRegistry`], the [`ScratchPool`]
+//! of idle buffers and a [`HibernationStore`] of instance snapshots, which
+//! spills the least recently hibernated snapshots to disk, or drops them if
+//! no spill directory is configured.
+
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+use wrt_foundation::memory_coordinator::{
+    CrateIdentifier,
+    GenericMemoryCoordinator,
+};
+
+use crate::{
+    cache_policy::{
+        CacheRegistry,
+        ManagedCache,
+    },
+    prelude::*,
+    scratch::ScratchPool,
+    state::InstanceSnapshot,
+};
+
+/// Number of reclamation tiers
+pub const RECLAIM_TIERS: usize = 3;
+
+/// Kind of memory a source holds, in the order it is reclaimed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReclaimTier {
+    /// Cached data that is rebuilt on the next miss
+    Caches,
+    /// Snapshots of hibernated instances
+    Hibernated,
+    /// Buffers kept for reuse by idle pools
+    IdlePools,
+}
+
+impl ReclaimTier {
+    /// All tiers in reclamation order
+    pub const ALL: [Self; RECLAIM_TIERS] = [Self::Caches, Self::Hibernated, Self::IdlePools];
+
+    /// Position of the tier in reclamation order
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Holder of memory that can be freed under pressure
+pub trait Reclaimable {
+    /// Tier the source is reclaimed in
+    fn tier(&self) -> ReclaimTier;
+
+    /// Bytes the source could free
+    fn reclaimable_bytes(&self) -> usize;
+
+    /// Free at least `target` bytes if possible, returning the bytes freed
+    fn reclaim(&mut self, target: usize) -> usize;
+}
+
+/// Shared handle to a source registered with a [`ReclamationOrchestrator`]
+pub type SharedReclaimable = Arc<Mutex<dyn Reclaimable + Send>>;
+
+/// Watermarks of a [`ReclamationOrchestrator`], in percent of the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReclamationConfig {
+    /// Usage at which reclamation starts
+    pub high_watermark: u8,
+    /// Usage reclamation brings memory down to before it stops
+    pub low_watermark:  u8,
+}
+
+impl Default for ReclamationConfig {
+    fn default() -> Self {
+        Self { high_watermark: 90, low_watermark: 75 }
+    }
+}
+
+impl ReclamationConfig {
+    /// Start reclaiming at `high_watermark` percent of the budget and stop
+    /// at `low_watermark` percent
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless `low_watermark < high_watermark <= 100`
+    pub fn new(high_watermark: u8, low_watermark: u8) -> Result<Self> {
+        if high_watermark > 100 || low_watermark >= high_watermark {
+            return Err(Error::validation_error(
+                "Reclamation watermarks must satisfy low < high <= 100",
+            ));
+        }
+        Ok(Self { high_watermark, low_watermark })
+    }
+
+    fn bytes_at(percent: u8, budget: usize) -> usize {
+        (budget as u128 * u128::from(percent) / 100) as usize
+    }
+}
+
+/// Counters of a [`ReclamationOrchestrator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReclamationMetrics {
+    /// Times usage crossed the high watermark
+    pub activations: u64,
+    /// Reclamation passes run
+    pub runs:        u64,
+    /// Passes that could not bring usage down to the low watermark
+    pub shortfalls:  u64,
+    /// Bytes freed in each tier, indexed by [`ReclaimTier::index`]
+    pub freed:       [u64; RECLAIM_TIERS],
+}
+
+impl ReclamationMetrics {
+    /// Bytes freed in `tier`
+    pub fn freed_in(&self, tier: ReclaimTier) -> u64 {
+        self.freed[tier.index()]
+    }
+
+    /// Bytes freed in all tiers
+    pub fn total_freed(&self) -> u64 {
+        self.freed.iter().sum()
+    }
+}
+
+/// Outcome of one reclamation pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReclaimReport {
+    /// Bytes needed to reach the low watermark
+    pub requested: usize,
+    /// Bytes freed in each tier, indexed by [`ReclaimTier::index`]
+    pub freed:     [usize; RECLAIM_TIERS],
+}
+
+impl ReclaimReport {
+    /// Bytes freed in all tiers
+    pub fn total_freed(&self) -> usize {
+        self.freed.iter().sum()
+    }
+
+    /// Whether the pass freed all it was asked to
+    pub fn is_complete(&self) -> bool {
+        self.total_freed() >= self.requested
+    }
+}
+
+/// Frees memory from registered sources in tier order when usage is high
+#[derive(Default)]
+pub struct ReclamationOrchestrator {
+    /// Watermarks
+    config:     ReclamationConfig,
+    /// Registered sources in registration order
+    sources:    Vec<(String, SharedReclaimable)>,
+    /// Whether usage crossed the high watermark and has not yet been
+    /// brought below the low watermark
+    reclaiming: bool,
+    /// Counters
+    metrics:    ReclamationMetrics,
+}
+
+impl ReclamationOrchestrator {
+    /// Create an orchestrator without sources
+    pub fn new(config: ReclamationConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Watermarks in use
+    pub fn config(&self) -> ReclamationConfig {
+        self.config
+    }
+
+    /// Register a source under `name`, replacing a source of the same name
+    pub fn register(&mut self, name: &str, source: SharedReclaimable) {
+        self.sources.retain(|(existing, _)| existing != name);
+        self.sources.push((name.to_string(), source));
+    }
+
+    /// Remove the source registered under `name`
+    pub fn unregister(&mut self, name: &str) -> Option<SharedReclaimable> {
+        let position = self.sources.iter().position(|(existing, _)| existing == name)?;
+        Some(self.sources.remove(position).1)
+    }
+
+    /// Names of the registered sources
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Whether a reclamation is in progress
+    pub fn is_reclaiming(&self) -> bool {
+        self.reclaiming
+    }
+
+    /// Counters since the orchestrator was created
+    pub fn metrics(&self) -> ReclamationMetrics {
+        self.metrics
+    }
+
+    /// Bytes the sources of `tier` could free
+    pub fn reclaimable_bytes(&self, tier: ReclaimTier) -> usize {
+        self.sources_in(tier)
+            .filter_map(|source| source.lock().ok().map(|source| source.reclaimable_bytes()))
+            .sum()
+    }
+
+    /// React to `used` of `budget` bytes being allocated
+    ///
+    /// Starts reclaiming when usage reaches the high watermark and keeps
+    /// reclaiming on later calls until usage is below the low watermark.
+    /// Returns the report of the pass run, if any.
+    pub fn on_usage(&mut self, used: usize, budget: usize) -> Option<ReclaimReport> {
+        if budget == 0 {
+            return None;
+        }
+        if !self.reclaiming {
+            if used < ReclamationConfig::bytes_at(self.config.high_watermark, budget) {
+                return None;
+            }
+            self.reclaiming = true;
+            self.metrics.activations += 1;
+        }
+
+        let target =
+            used.saturating_sub(ReclamationConfig::bytes_at(self.config.low_watermark, budget));
+        if target == 0 {
+            self.reclaiming = false;
+            return None;
+        }
+        let report = self.reclaim(target);
+        if report.is_complete() {
+            self.reclaiming = false;
+        } else {
+            self.metrics.shortfalls += 1;
+        }
+        Some(report)
+    }
+
+    /// React to the usage of the whole budget of `coordinator`
+    pub fn poll<C: CrateIdentifier, const MAX_CRATES: usize>(
+        &mut self,
+        coordinator: &GenericMemoryCoordinator<C, MAX_CRATES>,
+    ) -> Option<ReclaimReport> {
+        self.on_usage(coordinator.get_total_allocation(), coordinator.get_total_budget())
+    }
+
+    /// Free `target` bytes, tier by tier, regardless of the watermarks
+    pub fn reclaim(&mut self, target: usize) -> ReclaimReport {
+        let mut report = ReclaimReport { requested: target, ..ReclaimReport::default() };
+        self.metrics.runs += 1;
+        for tier in ReclaimTier::ALL {
+            for source in self.sources_in(tier) {
+                let remaining = target.saturating_sub(report.total_freed());
+                if remaining == 0 {
+                    break;
+                }
+                if let Ok(mut source) = source.lock() {
+                    report.freed[tier.index()] += source.reclaim(remaining);
+                }
+            }
+            self.metrics.freed[tier.index()] += report.freed[tier.index()] as u64;
+        }
+        report
+    }
+
+    fn sources_in(&self, tier: ReclaimTier) -> impl Iterator<Item = &SharedReclaimable> {
+        self.sources.iter().map(|(_, source)| source).filter(move |source| {
+            source.lock().map(|source| source.tier() == tier).unwrap_or(false)
+        })
+    }
+}
+
+impl core::fmt::Debug for ReclamationOrchestrator {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReclamationOrchestrator")
+            .field("config", &self.config)
+            .field("sources", &self.names().collect::<Vec<_>>())
+            .field("reclaiming", &self.reclaiming)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+/// Caches are flushed one at a time, in registration order
+impl Reclaimable for CacheRegistry {
+    fn tier(&self) -> ReclaimTier {
+        ReclaimTier::Caches
+    }
+
+    fn reclaimable_bytes(&self) -> usize {
+        self.total_metrics().bytes
+    }
+
+    fn reclaim(&mut self, target: usize) -> usize {
+        let mut freed = 0;
+        for (name, metrics) in self.metrics() {
+            if freed >= target {
+                break;
+            }
+            if metrics.bytes > 0 && self.flush(&name) {
+                freed += metrics.bytes;
+            }
+        }
+        freed
+    }
+}
+
+/// Pooled buffers are freed until enough capacity is released
+impl<T> Reclaimable for ScratchPool<T> {
+    fn tier(&self) -> ReclaimTier {
+        ReclaimTier::IdlePools
+    }
+
+    fn reclaimable_bytes(&self) -> usize {
+        self.pooled_bytes()
+    }
+
+    fn reclaim(&mut self, target: usize) -> usize {
+        let mut freed = 0;
+        while freed < target {
+            match self.release_one() {
+                Some(bytes) => freed += bytes,
+                None => break,
+            }
+        }
+        freed
+    }
+}
+
+/// Where a hibernated snapshot is kept
+#[derive(Debug)]
+enum Hibernated {
+    /// Encoded snapshot in memory
+    Resident(Vec<u8>),
+    /// Encoded snapshot written to a file
+    Spilled(PathBuf),
+}
+
+/// Counters of a [`HibernationStore`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HibernationStats {
+    /// Instances hibernated
+    pub hibernated:     u64,
+    /// Instances woken
+    pub woken:          u64,
+    /// Snapshots moved to the spill directory
+    pub spilled:        u64,
+    /// Snapshots dropped to free memory
+    pub discarded:      u64,
+    /// Snapshots that could not be written to the spill directory
+    pub spill_failures: u64,
+    /// Bytes of the snapshots kept in memory
+    pub resident_bytes: usize,
+}
+
+/// Snapshots of hibernated instances, reclaimed least recently hibernated
+/// first
+#[derive(Debug, Default)]
+pub struct HibernationStore {
+    /// Snapshots by instance name with the tick they were hibernated at
+    instances: HashMap<String, (u64, Hibernated)>,
+    /// Directory snapshots are spilled to under pressure
+    spill_dir: Option<PathBuf>,
+    /// Counter ordering hibernations, also naming spill files
+    tick:      u64,
+    /// Counters
+    stats:     HibernationStats,
+}
+
+impl HibernationStore {
+    /// Create a store that drops snapshots under pressure
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spill snapshots to files in `dir` under pressure instead of dropping
+    /// them
+    #[must_use]
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// Number of hibernated instances, resident or spilled
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Whether no instance is hibernated
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Whether the instance `name` is hibernated
+    pub fn contains(&self, name: &str) -> bool {
+        self.instances.contains_key(name)
+    }
+
+    /// Counters since the store was created
+    pub fn stats(&self) -> HibernationStats {
+        self.stats
+    }
+
+    /// Keep the snapshot of instance `name`, replacing an earlier one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot cannot be encoded
+    pub fn hibernate(&mut self, name: &str, snapshot: &InstanceSnapshot) -> Result<()> {
+        let bytes = snapshot.to_bytes()?;
+        self.remove(name);
+        self.tick += 1;
+        self.stats.resident_bytes += bytes.len();
+        self.stats.hibernated += 1;
+        self.instances
+            .insert(name.to_string(), (self.tick, Hibernated::Resident(bytes)));
+        Ok(())
+    }
+
+    /// Take the snapshot of instance `name` to resume it
+    ///
+    /// Returns `None` if the instance is not hibernated, or its snapshot was
+    /// dropped under memory pressure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spilled snapshot cannot be read or decoded
+    pub fn wake(&mut self, name: &str) -> Result<Option<InstanceSnapshot>> {
+        let Some((_, hibernated)) = self.instances.remove(name) else {
+            return Ok(None);
+        };
+        let bytes = match hibernated {
+            Hibernated::Resident(bytes) => {
+                self.stats.resident_bytes -= bytes.len();
+                bytes
+            },
+            Hibernated::Spilled(path) => {
+                let bytes = fs::read(&path)
+                    .map_err(|_| Error::resource_error("Failed to read spilled snapshot"))?;
+                let _ = fs::remove_file(&path);
+                bytes
+            },
+        };
+        self.stats.woken += 1;
+        InstanceSnapshot::from_bytes(&bytes).map(Some)
+    }
+
+    fn remove(&mut self, name: &str) {
+        match self.instances.remove(name) {
+            Some((_, Hibernated::Resident(bytes))) => self.stats.resident_bytes -= bytes.len(),
+            Some((_, Hibernated::Spilled(path))) => {
+                let _ = fs::remove_file(path);
+            },
+            None => {},
+        }
+    }
+
+    /// Spill or drop the least recently hibernated resident snapshot,
+    /// returning the bytes freed
+    fn release_oldest(&mut self) -> Option<usize> {
+        let (name, tick) = self
+            .instances
+            .iter()
+            .filter(|(_, (_, hibernated))| matches!(hibernated, Hibernated::Resident(_)))
+            .min_by_key(|(_, (tick, _))| *tick)
+            .map(|(name, (tick, _))| (name.clone(), *tick))?;
+        let Some((_, Hibernated::Resident(bytes))) = self.instances.remove(&name) else {
+            return None;
+        };
+
+        let Some(dir) = &self.spill_dir else {
+            self.stats.resident_bytes -= bytes.len();
+            self.stats.discarded += 1;
+            return Some(bytes.len());
+        };
+        let path = dir.join(format!("hibernated-{tick}.snapshot"));
+        if fs::write(&path, &bytes).is_err() {
+            // Keep the state rather than lose it
+            self.stats.spill_failures += 1;
+            self.instances.insert(name, (tick, Hibernated::Resident(bytes)));
+            return None;
+        }
+        self.stats.resident_bytes -= bytes.len();
+        self.stats.spilled += 1;
+        self.instances.insert(name, (tick, Hibernated::Spilled(path)));
+        Some(bytes.len())
+    }
+}
+
+impl Reclaimable for HibernationStore {
+    fn tier(&self) -> ReclaimTier {
+        ReclaimTier::Hibernated
+    }
+
+    fn reclaimable_bytes(&self) -> usize {
+        self.stats.resident_bytes
+    }
+
+    fn reclaim(&mut self, target: usize) -> usize {
+        let mut freed = 0;
+        while freed < target {
+            match self.release_oldest() {
+                Some(bytes) => freed += bytes,
+                None => break,
+            }
+        }
+        freed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache_policy::{
+        BoundedCache,
+        CachePolicy,
+    };
+
+    /// Source freeing fixed-size chunks
+    struct Chunks {
+        tier:   ReclaimTier,
+        chunks: usize,
+    }
+
+    impl Reclaimable for Chunks {
+        fn tier(&self) -> ReclaimTier {
+            self.tier
+        }
+
+        fn reclaimable_bytes(&self) -> usize {
+            self.chunks * 10
+        }
+
+        fn reclaim(&mut self, target: usize) -> usize {
+            let chunks = target.div_ceil(10).min(self.chunks);
+            self.chunks -= chunks;
+            chunks * 10
+        }
+    }
+
+    fn chunks(tier: ReclaimTier, chunks: usize) -> Arc<Mutex<Chunks>> {
+        Arc::new(Mutex::new(Chunks { tier, chunks }))
+    }
+
+    #[test]
+    fn test_tiers_reclaimed_in_priority_order_with_hysteresis() -> Result<()> {
+        let mut orchestrator = ReclamationOrchestrator::new(ReclamationConfig::new(80, 50)?);
+        let pools = chunks(ReclaimTier::IdlePools, 10);
+        let caches = chunks(ReclaimTier::Caches, 2);
+        let hibernated = chunks(ReclaimTier::Hibernated, 1);
+        // Registration order does not matter, tiers do
+        orchestrator.register("pools", pools.clone());
+        orchestrator.register("caches", caches.clone());
+        orchestrator.register("hibernated", hibernated.clone());
+
+        assert_eq!(orchestrator.on_usage(790, 1000), None);
+        let report = orchestrator.on_usage(800, 1000).unwrap();
+        assert_eq!(report.requested, 300);
+        assert_eq!(report.freed, [20, 10, 100]);
+        assert!(!report.is_complete());
+        assert!(orchestrator.is_reclaiming());
+
+        // Still above the low watermark, so reclaiming continues below high
+        pools.lock().unwrap().chunks = 30;
+        let report = orchestrator.on_usage(670, 1000).unwrap();
+        assert_eq!(report.freed, [0, 0, 170]);
+        assert!(!orchestrator.is_reclaiming());
+        assert_eq!(orchestrator.on_usage(700, 1000), None);
+
+        let metrics = orchestrator.metrics();
+        assert_eq!((metrics.activations, metrics.runs, metrics.shortfalls), (1, 2, 1));
+        assert_eq!(metrics.freed_in(ReclaimTier::IdlePools), 270);
+        assert_eq!(metrics.total_freed(), 300);
+        assert!(ReclamationConfig::new(50, 50).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_registry_and_scratch_pool_sources() {
+        let cache = Arc::new(Mutex::new(BoundedCache::new(CachePolicy::unbounded())));
+        cache.lock().unwrap().insert(1u32, "one", 64);
+        let mut registry = CacheRegistry::new();
+        registry.register("memo", cache.clone());
+        assert_eq!(registry.reclaimable_bytes(), 64);
+        assert_eq!(registry.reclaim(1), 64);
+        assert!(cache.lock().unwrap().is_empty());
+
+        let mut pool: ScratchPool<u64> = ScratchPool::new(4);
+        pool.recycle(Vec::with_capacity(4));
+        pool.recycle(Vec::with_capacity(4));
+        assert_eq!(pool.reclaimable_bytes(), 64);
+        assert_eq!(pool.reclaim(1), 32);
+        assert_eq!(pool.pooled(), 1);
+    }
+
+    #[test]
+    fn test_hibernated_snapshots_spill_and_wake() -> Result<()> {
+        let snapshot = InstanceSnapshot {
+            module_fingerprint: 7,
+            fuel:               100,
+            entry_func:         None,
+            memories:           Vec::new(),
+            globals:            vec![Value::I32(3)],
+            tables:             Vec::new(),
+            frames:             Vec::new(),
+        };
+        let dir = std::env::temp_dir().join(format!("wrt-hibernate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut store = HibernationStore::new().with_spill_dir(&dir);
+        store.hibernate("old", &snapshot)?;
+        store.hibernate("new", &snapshot)?;
+        let size = store.reclaimable_bytes() / 2;
+        assert_eq!(store.reclaim(1), size);
+        assert_eq!(store.stats().spilled, 1);
+        assert_eq!(store.wake("old")?, Some(snapshot.clone()));
+        assert_eq!(store.wake("old")?, None);
+
+        let mut dropping = HibernationStore::new();
+        dropping.hibernate("only", &snapshot)?;
+        assert_eq!(dropping.reclaim(usize::MAX), size);
+        assert_eq!(dropping.stats().discarded, 1);
+        assert_eq!(dropping.wake("only")?, None);
+        fs::remove_dir_all(&dir).unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! Function result storage with spillover
+//!
+//! Most functions return a handful of values, so [`ResultBuffer`] keeps the
+//! first [`INLINE_RESULTS`] values inline and only spills the rest to a heap
+//! buffer. The total is bounded by the [`StoreLimits`] the buffer was created
+//! with. Without `std` or `alloc` there is no spill buffer and results are
+//! limited to the inline capacity.
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+use alloc::vec::Vec;
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::{
+    collections::StaticVec,
+    limits,
+    values::Value,
+};
+
+use crate::store_limits::StoreLimits;
+
+/// Number of results stored without spilling
+pub const INLINE_RESULTS: usize = 16;
+
+/// Platform ceiling for the number of results of a single call
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub const MAX_RESULTS: usize = limits::MAX_FUNCTION_RESULTS;
+
+/// Platform ceiling for the number of results of a single call
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+pub const MAX_RESULTS: usize = if limits::MAX_FUNCTION_RESULTS < INLINE_RESULTS {
+    limits::MAX_FUNCTION_RESULTS
+} else {
+    INLINE_RESULTS
+};
+
+/// Results of a function call, inline up to [`INLINE_RESULTS`] values
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultBuffer {
+    /// First results, stored inline
+    inline:      StaticVec<Value, INLINE_RESULTS>,
+    /// Results beyond the inline capacity
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    spill:       Vec<Value>,
+    /// Maximum number of results this buffer accepts
+    max_results: usize,
+}
+
+impl ResultBuffer {
+    /// Create an empty buffer bounded by `limits`
+    pub fn new(limits: &StoreLimits) -> Self {
+        Self {
+            inline:      StaticVec::new(),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            spill:       Vec::new(),
+            max_results: limits.max_results(),
+        }
+    }
+
+    /// Create an empty buffer for a function returning `arity` values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `arity` exceeds the result limit in `limits`.
+    pub fn with_arity(arity: usize, limits: &StoreLimits) -> Result<Self> {
+        limits.check_result_arity(arity)?;
+        #[allow(unused_mut)]
+        let mut buffer = Self::new(limits);
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        buffer.spill.reserve_exact(arity.saturating_sub(INLINE_RESULTS));
+        Ok(buffer)
+    }
+
+    /// Append a result
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer already holds its maximum number of
+    /// results.
+    pub fn push(&mut self, value: Value) -> Result<()> {
+        if self.len() >= self.max_results {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Function result count exceeds store limit",
+            ));
+        }
+        if self.inline.len() < INLINE_RESULTS {
+            return self.inline.push(value);
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        self.spill.push(value);
+        Ok(())
+    }
+
+    /// Number of results
+    pub fn len(&self) -> usize {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return self.inline.len() + self.spill.len();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return self.inline.len();
+    }
+
+    /// Whether the buffer holds no results
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether results were stored beyond the inline capacity
+    pub fn is_spilled(&self) -> bool {
+        self.len() > INLINE_RESULTS
+    }
+
+    /// Result at `index`
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        if index < INLINE_RESULTS {
+            return self.inline.get(index);
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return self.spill.get(index - INLINE_RESULTS);
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return None;
+    }
+
+    /// Iterate over the results in order
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        let inline = self.inline.as_slice().iter();
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return inline.chain(self.spill.iter());
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return inline;
+    }
+
+    /// Convert into a vector of results in order
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn into_vec(self) -> Vec<Value> {
+        let mut values = Vec::with_capacity(self.len());
+        self.append_to(&mut values);
+        values
+    }
+
+    /// Move the results in order to the end of `values`
+    ///
+    /// Lets the caller collect results into a reused buffer instead of a
+    /// freshly allocated one.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn append_to(self, values: &mut Vec<Value>) {
+        values.extend(self.inline);
+        values.extend(self.spill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_spill_past_inline_capacity() {
+        let limits = StoreLimits::new().with_max_results(40).unwrap();
+        let mut buffer = ResultBuffer::with_arity(40, &limits).unwrap();
+        for i in 0..40 {
+            buffer.push(Value::I32(i)).unwrap();
+        }
+
+        assert_eq!(buffer.len(), 40);
+        assert!(buffer.is_spilled());
+        assert_eq!(buffer.get(INLINE_RESULTS), Some(&Value::I32(INLINE_RESULTS as i32)));
+        assert!(buffer.iter().zip(0..).all(|(v, i)| *v == Value::I32(i)));
+
+        let values = buffer.into_vec();
+        assert_eq!(values.len(), 40);
+        assert_eq!(values[39], Value::I32(39));
+    }
+
+    #[test]
+    fn test_results_bounded_by_store_limits() {
+        let limits = StoreLimits::new().with_max_results(2).unwrap();
+        assert!(ResultBuffer::with_arity(3, &limits).is_err());
+
+        let mut buffer = ResultBuffer::with_arity(2, &limits).unwrap();
+        buffer.push(Value::I64(1)).unwrap();
+        buffer.push(Value::I64(2)).unwrap();
+        assert!(buffer.push(Value::I64(3)).is_err());
+        assert!(!buffer.is_spilled());
+    }
+}
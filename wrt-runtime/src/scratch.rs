@@ -0,0 +1,195 @@
+//! Reusable scratch buffers for function calls
+//!
+//! Every function call needs an operand stack, its locals and a block stack.
+//! Allocating them afresh on each call puts the allocator on the hot path of
+//! call-heavy code. A [`ScratchPool`] keeps the buffers of completed calls
+//! and hands them out again, so that a steady stream of calls runs without
+//! allocating once the pool is warm.
+//!
+//! Buffers are created with the capacity set by
+//! [`StoreLimits::scratch_capacity`]. The pool keeps at most
+//! [`MAX_POOLED_BUFFERS`] buffers, and a buffer that grew beyond
+//! [`MAX_SCRATCH_CAPACITY`] is freed rather than kept, so one deep call does
+//! not pin its memory for the lifetime of the engine.
+
+use crate::{
+    prelude::*,
+    store_limits::StoreLimits,
+};
+pub use crate::store_limits::MAX_SCRATCH_CAPACITY;
+
+/// Maximum number of buffers a pool keeps
+pub const MAX_POOLED_BUFFERS: usize = 64;
+
+/// Counters of a scratch pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScratchStats {
+    /// Buffers handed out from the pool
+    pub reused:    u64,
+    /// Buffers allocated because the pool was empty
+    pub allocated: u64,
+    /// Buffers freed because the pool was full or they had grown too large
+    pub discarded: u64,
+}
+
+impl ScratchStats {
+    /// Add the counters of another pool
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            reused:    self.reused + other.reused,
+            allocated: self.allocated + other.allocated,
+            discarded: self.discarded + other.discarded,
+        }
+    }
+}
+
+/// Pool of cleared buffers reused across calls
+#[derive(Debug)]
+pub struct ScratchPool<T> {
+    free:     Vec<Vec<T>>,
+    capacity: usize,
+    stats:    ScratchStats,
+}
+
+impl<T> ScratchPool<T> {
+    /// Create an empty pool handing out buffers of `capacity` elements
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            capacity,
+            stats: ScratchStats::default(),
+        }
+    }
+
+    /// Take an empty buffer, reusing a returned one if available
+    pub fn take(&mut self) -> Vec<T> {
+        match self.free.pop() {
+            Some(buffer) => {
+                self.stats.reused += 1;
+                buffer
+            },
+            None => {
+                self.stats.allocated += 1;
+                Vec::with_capacity(self.capacity)
+            },
+        }
+    }
+
+    /// Return a buffer to the pool
+    pub fn recycle(&mut self, mut buffer: Vec<T>) {
+        if self.free.len() >= MAX_POOLED_BUFFERS || buffer.capacity() > MAX_SCRATCH_CAPACITY {
+            self.stats.discarded += 1;
+            return;
+        }
+        buffer.clear();
+        self.free.push(buffer);
+    }
+
+    /// Number of buffers ready for reuse
+    pub fn pooled(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Bytes of capacity held by the buffers ready for reuse
+    pub fn pooled_bytes(&self) -> usize {
+        self.free.iter().map(|buffer| buffer.capacity() * core::mem::size_of::<T>()).sum()
+    }
+
+    /// Free one buffer ready for reuse, returning the bytes of capacity
+    /// released
+    pub fn release_one(&mut self) -> Option<usize> {
+        self.free.pop().map(|buffer| buffer.capacity() * core::mem::size_of::<T>())
+    }
+
+    /// Counters since the pool was created
+    pub fn stats(&self) -> ScratchStats {
+        self.stats
+    }
+
+    /// Free the pooled buffers and hand out buffers of `capacity` elements
+    /// from now on
+    pub fn resize(&mut self, capacity: usize) {
+        self.free = Vec::new();
+        self.capacity = capacity;
+    }
+}
+
+/// Block stack entry: (block type, start pc, block type index, entry stack
+/// height)
+pub(crate) type BlockEntry = (&'static str, usize, u32, usize);
+
+/// Scratch buffers of an execution engine
+#[derive(Debug)]
+pub struct ScratchStacks {
+    /// Operand stacks, locals and call arguments
+    pub(crate) values: ScratchPool<Value>,
+    /// Block stacks
+    pub(crate) blocks: ScratchPool<BlockEntry>,
+}
+
+impl ScratchStacks {
+    /// Create empty pools sized from `limits`
+    pub fn new(limits: &StoreLimits) -> Self {
+        Self {
+            values: ScratchPool::new(limits.scratch_capacity()),
+            blocks: ScratchPool::new(limits.scratch_capacity()),
+        }
+    }
+
+    /// Size the pools from `limits`, freeing the buffers kept so far
+    pub fn apply_limits(&mut self, limits: &StoreLimits) {
+        self.values.resize(limits.scratch_capacity());
+        self.blocks.resize(limits.scratch_capacity());
+    }
+
+    /// Take an empty value buffer
+    pub fn take_values(&mut self) -> Vec<Value> {
+        self.values.take()
+    }
+
+    /// Take a value buffer holding a copy of `values`
+    pub fn values_from(&mut self, values: &[Value]) -> Vec<Value> {
+        let mut buffer = self.values.take();
+        buffer.extend_from_slice(values);
+        buffer
+    }
+
+    /// Counters of all pools
+    pub fn stats(&self) -> ScratchStats {
+        self.values.stats().merge(self.blocks.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_reuses_returned_buffers() {
+        let mut pool: ScratchPool<u32> = ScratchPool::new(8);
+        let mut buffer = pool.take();
+        assert!(buffer.capacity() >= 8);
+        buffer.extend([1, 2, 3]);
+        pool.recycle(buffer);
+        assert_eq!(pool.pooled(), 1);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(pool.stats(), ScratchStats {
+            reused:    1,
+            allocated: 1,
+            discarded: 0,
+        });
+
+        // Oversized buffers are freed instead of kept
+        pool.recycle(Vec::with_capacity(MAX_SCRATCH_CAPACITY + 1));
+        assert_eq!(pool.pooled(), 0);
+        assert_eq!(pool.stats().discarded, 1);
+
+        for _ in 0..=MAX_POOLED_BUFFERS {
+            pool.recycle(Vec::new());
+        }
+        assert_eq!(pool.pooled(), MAX_POOLED_BUFFERS);
+    }
+}
@@ -60,14 +60,14 @@
     MemArg,
 };
 use wrt_instructions::{
-    atomic_ops::{
-        AtomicWaitNotifyOp,
-        MemoryOrdering,
-    },
+    atomic_ops::MemoryOrdering,
     memory_ops::MemoryOperations,
 };
 
-use crate::prelude::CoreMemoryType;
+use crate::{
+    prelude::CoreMemoryType,
+    wait_table::WaiterTable,
+};
 
 #[cfg(any(feature = "std", feature = "alloc"))]
 use crate::thread_manager::{
@@ -228,6 +228,14 @@ pub fn execute_atomic(&mut self, thread_id: ThreadId, atomic_op: wrt_instruction
     }
 }
 
+// SAFETY: `memory_base` is only recorded, never dereferenced; all memory
+// access goes through the `MemoryOperations` object behind the instance lock.
+// Without these impls a shared memory could not be handed to other threads.
+#[allow(unsafe_code)]
+unsafe impl Send for SafeAtomicMemoryContext {}
+#[allow(unsafe_code)]
+unsafe impl Sync for SafeAtomicMemoryContext {}
+
 /// Thread-safe shared memory instance
 pub struct SharedMemoryInstance {
     /// Memory type specification.
@@ -240,6 +248,8 @@ pub struct SharedMemoryInstance {
     atomic_context:  Arc<WrtMutex<SafeAtomicMemoryContext>>,
     /// Access statistics.
     pub stats:       Arc<WrtMutex<SharedMemoryStats>>,
+    /// Threads parked in `memory.atomic.wait`, shared by every importer.
+    waiters:         WaiterTable,
 }
 
 impl SharedMemoryInstance {
@@ -283,9 +293,16 @@ pub fn new(
                 atomic_operations: 0,
                 access_violations: 0,
             })),
+            waiters: WaiterTable::new(),
         })
     }
 
+    /// Registers a segment of this memory, enabling atomic operations on it
+    /// if the segment is atomic-capable.
+    pub fn register_segment(&self, segment: SharedMemorySegment) -> Result<usize> {
+        self.manager.lock().register_segment(segment)
+    }
+
     /// Executes an atomic operation on shared memory.
     pub fn execute_atomic_operation(
         &self,
@@ -344,55 +361,42 @@ pub fn execute_atomic_operation(
                 timeout,
                 ..
             } => {
-                let mut atomic_context = self.atomic_context.lock();
-
                 // Validate access
                 self.validate_atomic_access(thread_id, address as u64)?;
 
-                // Execute atomic wait
-                let memarg = MemArg {
-                    offset: address,
-                    align_exponent: 2,
-                    memory_index: 0,
-                };
-                let wait_op = match expected {
-                    Value::I32(_) => AtomicWaitNotifyOp::MemoryAtomicWait32 { memarg },
-                    Value::I64(_) => AtomicWaitNotifyOp::MemoryAtomicWait64 { memarg },
+                let expected_bytes: Vec<u8> = match expected {
+                    Value::I32(value) => value.to_le_bytes().to_vec(),
+                    Value::I64(value) => value.to_le_bytes().to_vec(),
                     _ => return Err(Error::type_error("Atomic wait expects i32 or i64 value")),
                 };
-                let atomic_op = wrt_instructions::atomic_ops::AtomicOp::WaitNotify(wait_op);
-
-                let result = atomic_context.execute_atomic(thread_id, atomic_op)?;
-                if result.len() == 1 {
-                    Ok(Some(Value::I32(result[0] as i32)))
-                } else {
-                    Err(Error::runtime_execution_error("Invalid atomic wait result"))
-                }
+                let address = u64::from(address);
+                let width = expected_bytes.len() as u64;
+                Self::check_alignment(address, width)?;
+
+                // The load happens under the waiter table lock, so a notify
+                // that follows a store to this address cannot be missed.
+                let result = self.waiters.wait(address, timeout, || {
+                    let current = self.memory.read().read_bytes(address, width)?;
+                    Ok(current == expected_bytes)
+                })?;
+
+                self.atomic_context.lock().stats.wait_operations += 1;
+                Ok(Some(Value::I32(result)))
             },
 
             SharedMemoryOperation::AtomicNotify { address, count, .. } => {
-                let mut atomic_context = self.atomic_context.lock();
-
                 // Validate access
                 self.validate_atomic_access(thread_id, address as u64)?;
 
-                // Execute atomic notify
-                let memarg = MemArg {
-                    offset: address,
-                    align_exponent: 2,
-                    memory_index: 0,
-                };
-                let notify_op = AtomicWaitNotifyOp::MemoryAtomicNotify { memarg };
-                let atomic_op = wrt_instructions::atomic_ops::AtomicOp::WaitNotify(notify_op);
+                let address = u64::from(address);
+                Self::check_alignment(address, 4)?;
+                // Notify traps on out-of-bounds addresses like any 4-byte access
+                self.memory.read().read_bytes(address, 4)?;
 
-                let result = atomic_context.execute_atomic(thread_id, atomic_op)?;
-                if result.len() == 1 {
-                    Ok(Some(Value::I32(result[0] as i32)))
-                } else {
-                    Err(Error::runtime_execution_error(
-                        "Invalid atomic notify result",
-                    ))
-                }
+                let woken = self.waiters.notify(address, count);
+
+                self.atomic_context.lock().stats.notify_operations += 1;
+                Ok(Some(Value::I32(woken as i32)))
             },
 
             SharedMemoryOperation::Grow { delta_pages, .. } => {
@@ -414,6 +418,16 @@ pub fn execute_atomic_operation(
         }
     }
 
+    /// Traps on wait/notify addresses that are not naturally aligned.
+    fn check_alignment(address: u64, width: u64) -> Result<()> {
+        if address % width != 0 {
+            return Err(Error::runtime_execution_error(
+                "Unaligned atomic wait/notify address",
+            ));
+        }
+        Ok(())
+    }
+
     /// Validates atomic access to shared memory.
     fn validate_atomic_access(&self, thread_id: ThreadId, address: u64) -> Result<()> {
         let manager = self.manager.lock();
@@ -620,6 +634,7 @@ pub fn create_shared_memory(
             max: memory_type.max_pages(),
         },
         shared: memory_type.is_shared(),
+        memory64: false,
     };
 
     let memory_impl = Memory::new(core_mem_type)
@@ -702,3 +717,92 @@ pub fn shared_memory_notify(
         )),
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::thread;
+
+    use wrt_foundation::{
+        capabilities::MemoryCapabilityContext,
+        shared_memory::SharedMemoryAccess,
+    };
+
+    use super::*;
+    use crate::wait_table::{
+        WAIT_NOT_EQUAL,
+        WAIT_OK,
+        WAIT_TIMED_OUT,
+    };
+
+    fn one_page_shared_memory() -> Arc<SharedMemoryInstance> {
+        let memory_type = MemoryType::Shared { min: 1, max: 1 };
+        let memory = create_shared_memory(
+            memory_type.clone(),
+            None,
+            ThreadManager::default(),
+            MemoryCapabilityContext::default(),
+        )
+        .unwrap();
+        let segment =
+            SharedMemorySegment::new(memory_type, SharedMemoryAccess::ReadWrite, 0, 65536, true)
+                .unwrap();
+        memory.register_segment(segment).unwrap();
+        memory
+    }
+
+    // Mirrors the single-threaded assertions of the threads proposal's
+    // atomic.wast
+    #[test]
+    fn wait_and_notify_without_other_threads() {
+        let memory = one_page_shared_memory();
+
+        assert_eq!(shared_memory_notify(&memory, 0, 0, 0).unwrap(), 0);
+        assert_eq!(shared_memory_notify(&memory, 0, 0, 10).unwrap(), 0);
+        assert_eq!(
+            shared_memory_wait(&memory, 0, 0, 1, Some(Duration::ZERO)).unwrap(),
+            WAIT_NOT_EQUAL
+        );
+        assert_eq!(
+            shared_memory_wait(&memory, 0, 0, 0, Some(Duration::ZERO)).unwrap(),
+            WAIT_TIMED_OUT
+        );
+
+        let wait64 = SharedMemoryOperation::AtomicWait {
+            memory_index: 0,
+            address:      8,
+            expected:     Value::I64(0),
+            timeout:      Some(Duration::from_millis(1)),
+        };
+        let result = memory.execute_atomic_operation(0, wait64).unwrap();
+        assert!(matches!(result, Some(Value::I32(WAIT_TIMED_OUT))));
+
+        // Unaligned and out-of-bounds addresses trap
+        assert!(shared_memory_wait(&memory, 0, 2, 0, Some(Duration::ZERO)).is_err());
+        assert!(shared_memory_notify(&memory, 0, 1, 1).is_err());
+        assert!(shared_memory_notify(&memory, 0, 65536, 1).is_err());
+
+        let stats = memory.get_atomic_stats().unwrap();
+        assert_eq!(stats.wait_operations, 3);
+        assert_eq!(stats.notify_operations, 2);
+    }
+
+    // Mirrors the threads proposal's wait/notify thread tests: the waiter
+    // parks on a value, the main thread stores a new one and notifies.
+    #[test]
+    fn notify_wakes_thread_parked_through_another_handle() {
+        let memory = one_page_shared_memory();
+        let waiter_memory = Arc::clone(&memory);
+
+        let waiter = thread::spawn(move || shared_memory_wait(&waiter_memory, 1, 64, 0, None));
+        while memory.waiters.waiter_count(64) == 0 {
+            thread::yield_now();
+        }
+
+        memory.memory.write().write_bytes(64, &1i32.to_le_bytes()).unwrap();
+        assert_eq!(shared_memory_notify(&memory, 0, 64, 1).unwrap(), 1);
+        assert_eq!(waiter.join().unwrap().unwrap(), WAIT_OK);
+
+        // The value changed, so a new wait on the old value returns at once
+        assert_eq!(shared_memory_wait(&memory, 0, 64, 0, None).unwrap(), WAIT_NOT_EQUAL);
+    }
+}
@@ -0,0 +1,331 @@
+//! Linear memory storage that can share a copy-on-write snapshot
+//!
+//! A [`Memory`](crate::memory::Memory) either owns its bytes or is spawned
+//! from a [`CowSnapshot`] with [`Memory::from_snapshot`]. Spawned memories
+//! map their private pages with the host's `PageAllocator` and only copy the
+//! chunks they write, so many instances of one module share its initial
+//! image. Hosts without a page allocator only get owned memories.
+//!
+//! The snapshot mapping covers the declared maximum of the memory. Growing
+//! past it converts the memory into an owned copy.
+//!
+//! [`Memory::from_snapshot`]: crate::memory::Memory::from_snapshot
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use wrt_foundation::cow_provider::CowProvider;
+use wrt_foundation::{
+    safe_memory::{
+        Allocator,
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+        StdProvider,
+    },
+    verification::VerificationLevel,
+};
+
+use crate::prelude::{
+    Error,
+    Result,
+};
+
+/// Page allocator backing snapshot memories on this host
+#[cfg(target_os = "linux")]
+pub type SnapshotAllocator = wrt_platform::linux_memory::LinuxAllocator;
+/// Page allocator backing snapshot memories on this host
+#[cfg(target_os = "macos")]
+pub type SnapshotAllocator = wrt_platform::macos_memory::MacOsAllocator;
+
+/// Storage of a linear memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearMemoryProvider {
+    /// Bytes owned by this memory
+    Owned(StdProvider),
+    /// Pages shared with a snapshot until written
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    Snapshot(CowProvider<SnapshotAllocator>),
+}
+
+impl Default for LinearMemoryProvider {
+    fn default() -> Self {
+        Self::Owned(StdProvider::default())
+    }
+}
+
+/// Forward a call to whichever provider backs the memory
+macro_rules! delegate {
+    ($self:expr, $provider:ident => $call:expr) => {
+        match $self {
+            LinearMemoryProvider::Owned($provider) => $call,
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            LinearMemoryProvider::Snapshot($provider) => $call,
+        }
+    };
+}
+
+impl LinearMemoryProvider {
+    /// Whether the memory still shares pages with a snapshot
+    pub fn is_snapshot(&self) -> bool {
+        !matches!(self, Self::Owned(_))
+    }
+
+    /// Replace a snapshot provider by an owned copy of its first `size` bytes
+    fn make_owned(&mut self, size: usize) -> Result<()> {
+        if let Self::Owned(_) = self {
+            return Ok(());
+        }
+        let mut data = vec![0; size];
+        self.read_into(0, &mut data)?;
+        let mut owned = StdProvider::new(data);
+        owned.set_verification_level(self.verification_level());
+        *self = Self::Owned(owned);
+        Ok(())
+    }
+}
+
+impl Provider for LinearMemoryProvider {
+    type Allocator = Self;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        delegate!(self, provider => provider.borrow_slice(offset, len))
+    }
+
+    fn read_into(&self, offset: usize, buffer: &mut [u8]) -> Result<()> {
+        delegate!(self, provider => provider.read_into(offset, buffer))
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        delegate!(self, provider => provider.write_data(offset, data))
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        delegate!(self, provider => provider.verify_access(offset, len))
+    }
+
+    fn size(&self) -> usize {
+        delegate!(self, provider => provider.size())
+    }
+
+    fn capacity(&self) -> usize {
+        delegate!(self, provider => provider.capacity())
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        delegate!(self, provider => provider.verify_integrity())
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        delegate!(self, provider => provider.set_verification_level(level))
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        delegate!(self, provider => provider.verification_level())
+    }
+
+    fn memory_stats(&self) -> Stats {
+        delegate!(self, provider => provider.memory_stats())
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        delegate!(self, provider => provider.get_slice_mut(offset, len))
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        delegate!(self, provider => provider.copy_within(src_offset, dst_offset, len))
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        if byte_offset > self.capacity() {
+            let size = self.size();
+            self.make_owned(size)?;
+        }
+        delegate!(self, provider => provider.ensure_used_up_to(byte_offset))
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        self
+    }
+
+    fn acquire_memory(&self, layout: core::alloc::Layout) -> Result<*mut u8> {
+        self.allocate(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: core::alloc::Layout) -> Result<()> {
+        self.deallocate(ptr, layout)
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.clone()))
+    }
+}
+
+impl Allocator for LinearMemoryProvider {
+    fn allocate(&self, _layout: core::alloc::Layout) -> Result<*mut u8> {
+        Err(Error::memory_error(
+            "Linear memory does not support raw allocation",
+        ))
+    }
+
+    fn deallocate(&self, _ptr: *mut u8, _layout: core::alloc::Layout) -> Result<()> {
+        Err(Error::memory_error(
+            "Linear memory does not support raw deallocation",
+        ))
+    }
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "macos")))]
+mod tests {
+    use wrt_foundation::{
+        types::Limits,
+        values::Value,
+    };
+
+    use super::*;
+    use crate::{
+        engine::{
+            CapabilityAwareEngine,
+            CapabilityEngine,
+            EnginePreset,
+        },
+        memory::Memory,
+        prelude::CoreMemoryType,
+    };
+
+    const WASM_PAGE: u32 = 65536;
+    const IMAGE_PAGES: u32 = 16;
+    const MAX_PAGES: u32 = 1024;
+
+    /// Module keeping a byte at address 0, initialized to 42 by its data
+    /// segment
+    const CELL_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "\2a")
+          (func (export "get") (result i32) (i32.load8_u (i32.const 0)))
+          (func (export "set") (param i32) (i32.store8 (i32.const 0) (local.get 0))))
+    "#;
+
+    fn memory_type(min: u32, max: Option<u32>) -> CoreMemoryType {
+        CoreMemoryType {
+            limits:   Limits { min, max },
+            shared:   false,
+            memory64: false,
+        }
+    }
+
+    fn image() -> Memory {
+        let mut source = *Memory::new(memory_type(IMAGE_PAGES, None)).unwrap();
+        for page in 0..IMAGE_PAGES {
+            source.write(page * WASM_PAGE, &[page as u8 + 1; 8]).unwrap();
+        }
+        source
+    }
+
+    fn copied_chunks(memory: &Memory) -> usize {
+        match memory.data.lock().unwrap().provider() {
+            LinearMemoryProvider::Snapshot(provider) => provider.copied_chunks(),
+            LinearMemoryProvider::Owned(_) => panic!("memory no longer shares its snapshot"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn resident_bytes() -> usize {
+        let statm = std::fs::read_to_string("/proc/self/statm").unwrap();
+        let pages: usize = statm.split_whitespace().nth(1).unwrap().parse().unwrap();
+        pages * 4096
+    }
+
+    #[test]
+    fn hundred_instances_share_one_image() {
+        let snapshot = image().snapshot().unwrap();
+        assert_eq!(snapshot.len(), (IMAGE_PAGES * WASM_PAGE) as usize);
+        #[cfg(target_os = "linux")]
+        let before = resident_bytes();
+
+        let end = MAX_PAGES * WASM_PAGE;
+        let mut instances = Vec::new();
+        for i in 0..100u32 {
+            let mut memory =
+                Memory::from_snapshot(memory_type(MAX_PAGES, Some(MAX_PAGES)), &snapshot).unwrap();
+            memory.write(8, &i.to_le_bytes()).unwrap();
+            memory.write(end / 2, &i.to_le_bytes()).unwrap();
+            memory.write(end - 4, &i.to_le_bytes()).unwrap();
+            instances.push(memory);
+        }
+        assert_eq!(snapshot.instances(), 100);
+
+        // Owned memories would hold 100 x 64 MiB
+        #[cfg(target_os = "linux")]
+        {
+            let grown = resident_bytes().saturating_sub(before);
+            assert!(grown < 256 << 20, "100 instances made {grown} bytes resident");
+        }
+
+        for (i, memory) in instances.iter().enumerate() {
+            let mut word = [0; 4];
+            memory.read(end / 2, &mut word).unwrap();
+            assert_eq!(u32::from_le_bytes(word), i as u32);
+            let mut head = [0; 12];
+            memory.read(0, &mut head).unwrap();
+            assert_eq!(head[..8], [1; 8]);
+            assert_eq!(head[8..], (i as u32).to_le_bytes());
+            let mut straddle = [0xFF; 16];
+            memory.read(7 * WASM_PAGE - 8, &mut straddle).unwrap();
+            assert_eq!(straddle, [[0; 8], [8; 8]].concat()[..]);
+            // Reads never copy; only the three written chunks are private
+            assert_eq!(copied_chunks(memory), 3);
+        }
+    }
+
+    #[test]
+    fn growing_past_the_mapping_makes_the_memory_owned() {
+        let snapshot = image().snapshot().unwrap();
+        let mut memory =
+            *Memory::from_snapshot(memory_type(IMAGE_PAGES, None), &snapshot).unwrap();
+        memory.write(4, &[9]).unwrap();
+        assert_eq!(copied_chunks(&memory), 1);
+
+        assert_eq!(memory.grow(1).unwrap(), IMAGE_PAGES);
+        assert!(!memory.data.lock().unwrap().provider().is_snapshot());
+        let mut bytes = [0; 6];
+        memory.read(0, &mut bytes).unwrap();
+        assert_eq!(bytes, [1, 1, 1, 1, 9, 1]);
+        memory.write(IMAGE_PAGES * WASM_PAGE, &[3]).unwrap();
+        assert_eq!(snapshot.instances(), 0);
+
+        assert!(Memory::from_snapshot(memory_type(1, None), &snapshot).is_err());
+    }
+
+    #[test]
+    fn engine_spawns_instances_from_a_snapshot() {
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM).unwrap();
+        let module = engine.load_module(&wat::parse_str(CELL_MODULE).unwrap()).unwrap();
+        let source = engine.instantiate(module).unwrap();
+        engine.execute(source, "set", &[Value::I32(7)]).unwrap();
+        let snapshot = engine.snapshot_memory(source).unwrap();
+
+        // Spawned instances start from the snapshot, not the data segment
+        let first = engine.instantiate_from_snapshot(module, &snapshot).unwrap();
+        let second = engine.instantiate_from_snapshot(module, &snapshot).unwrap();
+        assert_eq!(snapshot.instances(), 2);
+        assert_eq!(engine.execute(first, "get", &[]).unwrap(), [Value::I32(7)]);
+
+        // Writes stay private to the instance making them
+        engine.execute(first, "set", &[Value::I32(9)]).unwrap();
+        assert_eq!(engine.execute(first, "get", &[]).unwrap(), [Value::I32(9)]);
+        assert_eq!(engine.execute(second, "get", &[]).unwrap(), [Value::I32(7)]);
+        assert_eq!(engine.execute(source, "get", &[]).unwrap(), [Value::I32(7)]);
+        let memory = engine.get_instance(first).unwrap().memory(0).unwrap();
+        assert_eq!(copied_chunks(&memory.0), 1);
+
+        let importing = engine
+            .load_module(&wat::parse_str(r#"(module (import "env" "memory" (memory 1)))"#).unwrap())
+            .unwrap();
+        assert!(engine.instantiate_from_snapshot(importing, &snapshot).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Canonical options of lifted and lowered functions
+//!
+//! `canon lift` and `canon lower` carry options that say how values cross
+//! the component boundary: the memory strings and lists live in, the guest
+//! `realloc` used to allocate memory for values passed into the guest, the
+//! `post-return` function called once the results of a lifted function have
+//! been read, and the string encoding used by the guest.
+//!
+//! The options refer to core functions and memories by index in the
+//! component; [`CanonOptions`] holds them resolved to engine instances so the
+//! engine can call them directly.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use wrt_foundation::{
+    component_value::{
+        ValType,
+        ValTypeRef,
+    },
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    NoStdProvider,
+};
+
+use crate::prelude::*;
+
+/// Tag set in the length of a `latin1+utf16` string encoded as UTF-16
+pub const UTF16_TAG: u32 = 1 << 31;
+
+/// Maximum length in bytes of a string passed across the boundary
+pub const MAX_STRING_BYTE_LENGTH: u32 = (1 << 31) - 1;
+
+/// String encoding of the guest side of a lifted or lowered function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringEncoding {
+    /// UTF-8, length in bytes
+    #[default]
+    Utf8,
+    /// UTF-16 little endian, length in code units
+    Utf16,
+    /// Latin-1 when every character fits, otherwise UTF-16 with
+    /// [`UTF16_TAG`] set in the length
+    Latin1Utf16,
+}
+
+impl StringEncoding {
+    /// Alignment of strings in guest memory
+    pub fn alignment(self) -> u32 {
+        match self {
+            Self::Utf8 => 1,
+            Self::Utf16 | Self::Latin1Utf16 => 2,
+        }
+    }
+
+    /// Number of bytes taken by a string of length `tagged_len`
+    pub fn byte_len(self, tagged_len: u32) -> u32 {
+        match self {
+            Self::Utf8 => tagged_len,
+            Self::Utf16 => tagged_len.saturating_mul(2),
+            Self::Latin1Utf16 if tagged_len & UTF16_TAG != 0 => {
+                (tagged_len & !UTF16_TAG).saturating_mul(2)
+            },
+            Self::Latin1Utf16 => tagged_len,
+        }
+    }
+}
+
+impl From<&wrt_format::component::StringEncoding> for StringEncoding {
+    fn from(encoding: &wrt_format::component::StringEncoding) -> Self {
+        use wrt_format::component::StringEncoding as Format;
+        match encoding {
+            Format::UTF8 | Format::ASCII => Self::Utf8,
+            Format::UTF16 => Self::Utf16,
+            Format::Latin1 => Self::Latin1Utf16,
+        }
+    }
+}
+
+/// A core function of an engine instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoreFuncRef {
+    /// Engine instance defining the function
+    pub instance_id: usize,
+    /// Function index within the instance
+    pub func_idx:    usize,
+}
+
+/// Canonical options resolved to engine instances
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CanonOptions {
+    /// Instance whose memory holds strings and lists
+    pub memory_instance: Option<usize>,
+    /// Guest allocator for values passed into the guest
+    pub realloc:         Option<CoreFuncRef>,
+    /// Called with the core results of a lifted function once they are read
+    pub post_return:     Option<CoreFuncRef>,
+    /// Encoding of strings in guest memory
+    pub string_encoding: StringEncoding,
+}
+
+impl CanonOptions {
+    /// Use the memory of `instance_id`
+    #[must_use]
+    pub fn with_memory(mut self, instance_id: usize) -> Self {
+        self.memory_instance = Some(instance_id);
+        self
+    }
+
+    /// Allocate guest memory with `realloc`
+    #[must_use]
+    pub fn with_realloc(mut self, realloc: CoreFuncRef) -> Self {
+        self.realloc = Some(realloc);
+        self
+    }
+
+    /// Call `post_return` after the results of a lifted function are read
+    #[must_use]
+    pub fn with_post_return(mut self, post_return: CoreFuncRef) -> Self {
+        self.post_return = Some(post_return);
+        self
+    }
+
+    /// Encode strings with `encoding`
+    #[must_use]
+    pub fn with_string_encoding(mut self, encoding: StringEncoding) -> Self {
+        self.string_encoding = encoding;
+        self
+    }
+}
+
+fn string_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Runtime, codes::INVALID_ARGUMENT, message)
+}
+
+/// Encode `s` for the guest, returning the bytes and the tagged length
+///
+/// # Errors
+///
+/// Returns an error if the encoded string exceeds
+/// [`MAX_STRING_BYTE_LENGTH`].
+pub fn encode_string(s: &str, encoding: StringEncoding) -> Result<(Vec<u8>, u32)> {
+    let utf16 = |s: &str| -> (Vec<u8>, u32) {
+        let bytes: Vec<u8> = s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let units = (bytes.len() / 2) as u32;
+        (bytes, units)
+    };
+    let (bytes, tagged_len) = match encoding {
+        StringEncoding::Utf8 => (s.as_bytes().to_vec(), s.len() as u32),
+        StringEncoding::Utf16 => utf16(s),
+        StringEncoding::Latin1Utf16 if s.chars().all(|c| u32::from(c) <= 0xFF) => {
+            let bytes: Vec<u8> = s.chars().map(|c| u32::from(c) as u8).collect();
+            let len = bytes.len() as u32;
+            (bytes, len)
+        },
+        StringEncoding::Latin1Utf16 => {
+            let (bytes, units) = utf16(s);
+            (bytes, units | UTF16_TAG)
+        },
+    };
+    if bytes.len() > MAX_STRING_BYTE_LENGTH as usize {
+        return Err(string_error("String too long for the canonical ABI"));
+    }
+    Ok((bytes, tagged_len))
+}
+
+/// Decode a guest string of length `tagged_len` from its bytes
+///
+/// # Errors
+///
+/// Returns an error if `bytes` is not a valid string in `encoding`.
+pub fn decode_string(bytes: &[u8], tagged_len: u32, encoding: StringEncoding) -> Result<String> {
+    let utf16 = |bytes: &[u8]| -> Result<String> {
+        let units = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+        char::decode_utf16(units)
+            .collect::<core::result::Result<String, _>>()
+            .map_err(|_| string_error("Invalid UTF-16 string"))
+    };
+    match encoding {
+        StringEncoding::Utf8 => String::from_utf8(bytes.to_vec())
+            .map_err(|_| string_error("Invalid UTF-8 string")),
+        StringEncoding::Utf16 => utf16(bytes),
+        StringEncoding::Latin1Utf16 if tagged_len & UTF16_TAG != 0 => utf16(bytes),
+        StringEncoding::Latin1Utf16 => Ok(bytes.iter().map(|&b| char::from(b)).collect()),
+    }
+}
+
+/// Type handed to canonical lift/lower hooks for a flattened value tuple
+///
+/// The engine keeps no component type store on this path, so the tuple is
+/// described by its size: a fixed list of `byte_len` bytes.
+pub fn flat_tuple_type(byte_len: u32) -> ValType<NoStdProvider<64>> {
+    ValType::FixedList(ValTypeRef(0), byte_len)
+}
+
+/// Natural size and alignment of a flattened core value
+fn flat_layout(value: &Value) -> Result<usize> {
+    match value {
+        Value::I32(_) | Value::F32(_) => Ok(4),
+        Value::I64(_) | Value::F64(_) => Ok(8),
+        _ => Err(Error::new(
+            ErrorCategory::Runtime,
+            codes::TYPE_MISMATCH,
+            "Flattened canonical values must be numeric",
+        )),
+    }
+}
+
+/// Encode flattened core values as a tuple in canonical ABI layout
+///
+/// Each value is stored little endian at its natural alignment, which is
+/// how the tuple of the function's parameters or results is laid out in
+/// memory.
+///
+/// # Errors
+///
+/// Returns an error if a value is not an `i32`, `i64`, `f32` or `f64`.
+pub fn encode_flat(values: &[Value]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for value in values {
+        let size = flat_layout(value)?;
+        bytes.resize(bytes.len().next_multiple_of(size), 0);
+        match value {
+            Value::I32(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            Value::I64(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            Value::F32(v) => bytes.extend_from_slice(&v.to_bits().to_le_bytes()),
+            Value::F64(v) => bytes.extend_from_slice(&v.to_bits().to_le_bytes()),
+            _ => unreachable!("flat_layout accepts numeric values only"),
+        }
+    }
+    Ok(bytes)
+}
+
+/// Decode a tuple written by [`encode_flat`] into values of the same types
+/// as `like`
+///
+/// # Errors
+///
+/// Returns an error if a value of `like` is not numeric or `bytes` is too
+/// short for the tuple.
+pub fn decode_flat(like: &[Value], bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut offset = 0usize;
+    like.iter()
+        .map(|value| {
+            let size = flat_layout(value)?;
+            offset = offset.next_multiple_of(size);
+            let field = bytes.get(offset..offset + size).ok_or_else(|| {
+                Error::runtime_out_of_bounds("Flattened canonical tuple too short")
+            })?;
+            offset += size;
+            Ok(match value {
+                Value::I32(_) => Value::I32(i32::from_le_bytes(field.try_into().unwrap())),
+                Value::I64(_) => Value::I64(i64::from_le_bytes(field.try_into().unwrap())),
+                Value::F32(_) => Value::F32(FloatBits32::from_bits(u32::from_le_bytes(
+                    field.try_into().unwrap(),
+                ))),
+                _ => Value::F64(FloatBits64::from_bits(u64::from_le_bytes(
+                    field.try_into().unwrap(),
+                ))),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_encodings_round_trip() {
+        for (s, encoding, tagged_len) in [
+            ("héllo", StringEncoding::Utf8, 6),
+            ("héllo", StringEncoding::Utf16, 5),
+            ("héllo", StringEncoding::Latin1Utf16, 5),
+            ("h€llo", StringEncoding::Latin1Utf16, 5 | UTF16_TAG),
+        ] {
+            let (bytes, len) = encode_string(s, encoding).unwrap();
+            assert_eq!(len, tagged_len);
+            assert_eq!(bytes.len() as u32, encoding.byte_len(len));
+            assert_eq!(decode_string(&bytes, len, encoding).unwrap(), s);
+        }
+
+        assert!(decode_string(&[0xFF], 1, StringEncoding::Utf8).is_err());
+        assert!(decode_string(&[0x00, 0xD8], 1, StringEncoding::Utf16).is_err());
+        assert_eq!(
+            StringEncoding::from(&wrt_format::component::StringEncoding::Latin1),
+            StringEncoding::Latin1Utf16
+        );
+    }
+
+    #[test]
+    fn test_flat_tuple_round_trip() {
+        use wrt_foundation::values::V128;
+
+        let values = [
+            Value::I32(-2),
+            Value::I64(0x0102_0304_0506_0708),
+            Value::F32(FloatBits32::from_f32(1.5)),
+            Value::F64(FloatBits64::from_f64(-0.25)),
+        ];
+        let bytes = encode_flat(&values).unwrap();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[..8], &[0xFE, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+        assert_eq!(decode_flat(&values, &bytes).unwrap(), values);
+
+        assert!(decode_flat(&values, &bytes[..20]).is_err());
+        assert!(encode_flat(&[Value::V128(V128::new([0; 16]))]).is_err());
+    }
+}
@@ -23,6 +23,8 @@
     AtomicU64,
     Ordering,
 };
+#[cfg(any(feature = "std", feature = "alloc"))]
+use core::time::Duration;
 // Use std types when available, fall back to alloc, then wrt_foundation
 #[cfg(feature = "std")]
 use std::{
@@ -140,9 +142,13 @@ fn eq(&self, other: &Self) -> bool {
 #[cfg(not(any(feature = "std", feature = "alloc")))]
 impl<T: Eq> Eq for Arc<T> {}
 
-use wrt_error::Result;
+use wrt_error::{
+    codes::TrapCode,
+    Result,
+};
 use wrt_foundation::{
     traits::BoundedCapacity,
+    verification::VerificationLevel,
     values::{
         FloatBits32,
         FloatBits64,
@@ -150,7 +156,121 @@ impl<T: Eq> Eq for Arc<T> {}
     },
 };
 
-use crate::module_instance::ModuleInstance;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::epoch::{
+    EpochCounter,
+    EpochDeadline,
+    EpochDeadlineAction,
+    EpochStats,
+};
+use crate::{
+    fp_mode::FpConfig,
+    module_instance::ModuleInstance,
+    result_buffer::ResultBuffer,
+    store_limits::StoreLimits,
+};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::fp_mode::{
+    host_flushes_denormals,
+    FpModeReport,
+    FpStats,
+};
+#[cfg(feature = "std")]
+use crate::state::snapshot::{
+    module_fingerprint,
+    BlockSnapshot,
+    FrameSnapshot,
+    InstanceSnapshot,
+    MemorySnapshot,
+    BLOCK_KINDS,
+};
+#[cfg(feature = "std")]
+use wrt_format::crash_dump::{
+    CrashCause,
+    CrashDump,
+    DumpFrame,
+    DumpValue,
+    MemoryRegion,
+};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::stackless::frame_guard::{
+    fingerprint_of,
+    FrameFingerprint,
+    GuardedFrames,
+};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::scratch::{
+    ScratchStacks,
+    ScratchStats,
+};
+#[cfg(feature = "std")]
+use crate::stackless::canon::{
+    self,
+    CanonOptions,
+    CoreFuncRef,
+};
+
+/// Whether lowering a WASI value allocates guest memory
+#[cfg(all(feature = "std", feature = "wasi"))]
+fn wasi_value_needs_realloc(value: &wrt_wasi::Value) -> bool {
+    use wrt_wasi::Value as WasiValue;
+    match value {
+        WasiValue::String(_) | WasiValue::List(_) => true,
+        WasiValue::Record(fields) => {
+            fields.iter().any(|(_, value)| wasi_value_needs_realloc(value))
+        }
+        WasiValue::Tuple(values) => values.iter().any(wasi_value_needs_realloc),
+        WasiValue::Option(Some(payload)) | WasiValue::Result(Ok(payload) | Err(payload)) => {
+            wasi_value_needs_realloc(payload)
+        }
+        _ => false,
+    }
+}
+
+/// Round `offset` up to a multiple of `align`
+#[cfg(all(feature = "std", feature = "wasi"))]
+fn align_to(offset: u32, align: u32) -> u32 {
+    offset.div_ceil(align) * align
+}
+
+/// Size and alignment of consecutive WASI values laid out as the fields of a
+/// record
+#[cfg(all(feature = "std", feature = "wasi"))]
+fn wasi_fields_layout<'a>(values: impl Iterator<Item = &'a wrt_wasi::Value>) -> (u32, u32) {
+    let (mut size, mut align) = (0, 1);
+    for value in values {
+        let (field_size, field_align) = wasi_value_layout(value);
+        size = align_to(size, field_align) + field_size;
+        align = align.max(field_align);
+    }
+    (align_to(size, align), align)
+}
+
+/// Size and alignment of a WASI value in the canonical ABI
+///
+/// Option and result payloads are laid out for the case present, which
+/// matches the canonical layout when all cases share an alignment.
+#[cfg(all(feature = "std", feature = "wasi"))]
+fn wasi_value_layout(value: &wrt_wasi::Value) -> (u32, u32) {
+    use wrt_wasi::Value as WasiValue;
+
+    fn variant(payload: Option<&wrt_wasi::Value>) -> (u32, u32) {
+        let (size, align) = payload.map_or((0, 1), wasi_value_layout);
+        (align_to(align_to(1, align) + size, align), align)
+    }
+
+    match value {
+        WasiValue::Bool(_) | WasiValue::U8(_) | WasiValue::S8(_) => (1, 1),
+        WasiValue::U16(_) | WasiValue::S16(_) => (2, 2),
+        WasiValue::U32(_) | WasiValue::S32(_) | WasiValue::F32(_) => (4, 4),
+        WasiValue::U64(_) | WasiValue::S64(_) | WasiValue::F64(_) => (8, 8),
+        WasiValue::String(_) | WasiValue::List(_) => (8, 4),
+        WasiValue::Record(values) => wasi_fields_layout(values.iter().map(|(_, value)| value)),
+        WasiValue::Tuple(values) => wasi_fields_layout(values.iter()),
+        WasiValue::Option(payload) => variant(payload.as_deref()),
+        WasiValue::Result(Ok(payload) | Err(payload)) => variant(Some(payload)),
+    }
+}
 
 /// Strip the version suffix from a WASI interface name.
 /// e.g., "wasi:cli/stdout@0.2.4" -> "wasi:cli/stdout"
@@ -230,6 +350,128 @@ struct SuspendedFrame {
     instruction_count: usize,
 }
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl FrameFingerprint for SuspendedFrame {
+    fn fingerprint(&self) -> u64 {
+        fingerprint_of(&[
+            self.instance_id,
+            self.func_idx,
+            self.pc,
+            self.locals.len(),
+            self.operand_stack.len(),
+            self.block_stack.len(),
+            self.block_depth as usize,
+        ])
+    }
+}
+
+/// Call chain suspended at an epoch deadline or out of fuel until the host
+/// resumes it
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug)]
+struct SuspendedCall {
+    /// Instance and function the host called
+    entry:  (usize, usize),
+    /// Frames of the call chain, innermost last
+    frames: Vec<SuspendedFrame>,
+}
+
+/// Guest frames of the last trap, kept for crash dumps
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct TrapRecord {
+    /// Instance whose call trapped
+    instance_id: usize,
+    /// Error the call trapped with
+    cause:       CrashCause,
+    /// Frames of the instance at the trap, innermost first
+    frames:      Vec<DumpFrame>,
+}
+
+/// Convert a value into its crash dump form
+#[cfg(feature = "std")]
+fn dump_value(value: &Value) -> DumpValue {
+    match value {
+        Value::I32(v) => DumpValue::I32(*v as u32),
+        Value::I64(v) => DumpValue::I64(*v as u64),
+        Value::F32(v) => DumpValue::F32(v.0),
+        Value::F64(v) => DumpValue::F64(v.0),
+        Value::V128(v) => DumpValue::V128(v.bytes),
+        Value::FuncRef(func) => DumpValue::Ref(func.as_ref().map(|func| func.index)),
+        Value::ExternRef(extern_ref) => {
+            DumpValue::Ref(extern_ref.as_ref().map(|extern_ref| extern_ref.index))
+        },
+        Value::Ref(index) => DumpValue::Ref(Some(*index)),
+        Value::ExnRef(None) => DumpValue::Ref(None),
+        Value::I31Ref(v) => DumpValue::Ref(v.map(|v| v as u32)),
+        _ => DumpValue::Opaque,
+    }
+}
+
+/// Convert a suspended frame into its crash dump form
+#[cfg(feature = "std")]
+fn dump_frame(frame: &SuspendedFrame) -> DumpFrame {
+    DumpFrame {
+        func_idx:      frame.func_idx as u32,
+        pc:            Some(frame.pc as u32),
+        locals:        frame.locals.iter().map(dump_value).collect(),
+        operand_stack: frame.operand_stack.iter().map(dump_value).collect(),
+    }
+}
+
+/// Convert a suspended frame into its snapshot form
+#[cfg(feature = "std")]
+fn snapshot_frame(frame: &SuspendedFrame) -> FrameSnapshot {
+    FrameSnapshot {
+        func_idx:          frame.func_idx as u32,
+        pc:                frame.pc as u32,
+        locals:            frame.locals.clone(),
+        operand_stack:     frame.operand_stack.clone(),
+        blocks:            frame
+            .block_stack
+            .iter()
+            .map(|&(kind, start_pc, type_idx, stack_height)| BlockSnapshot {
+                kind: BLOCK_KINDS.iter().position(|&name| name == kind).unwrap_or(0) as u8,
+                start_pc: start_pc as u32,
+                type_idx,
+                stack_height: stack_height as u32,
+            })
+            .collect(),
+        block_depth:       frame.block_depth,
+        instruction_count: frame.instruction_count as u64,
+    }
+}
+
+/// Rebuild a suspended frame of `instance_id` from its snapshot form
+#[cfg(feature = "std")]
+fn restore_frame(instance_id: usize, frame: &FrameSnapshot) -> Result<SuspendedFrame> {
+    let block_stack = frame
+        .blocks
+        .iter()
+        .map(|block| {
+            let kind = BLOCK_KINDS.get(block.kind as usize).ok_or_else(|| {
+                wrt_error::Error::validation_parse_error("Invalid snapshot block kind")
+            })?;
+            Ok((
+                *kind,
+                block.start_pc as usize,
+                block.type_idx,
+                block.stack_height as usize,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(SuspendedFrame {
+        instance_id,
+        func_idx: frame.func_idx as usize,
+        pc: frame.pc as usize,
+        locals: frame.locals.clone(),
+        operand_stack: frame.operand_stack.clone(),
+        block_stack,
+        block_depth: frame.block_depth,
+        instruction_count: frame.instruction_count as usize,
+    })
+}
+
 /// Simple execution statistics
 #[derive(Debug, Default)]
 pub struct ExecutionStats {
@@ -245,6 +487,7 @@ enum ExecutionOutcome {
     /// Function completed normally with results
     Complete(Vec<Value>),
     /// Tail call to another function - trampoline should continue with new target
+    #[cfg(feature = "tail-call")]
     TailCall {
         /// Target function index
         func_idx: usize,
@@ -263,6 +506,10 @@ enum ExecutionOutcome {
         /// None = import redirect (no state to save, caller already on pending stack).
         return_state: Option<SuspendedFrame>,
     },
+    /// Epoch deadline reached with the yield action - park the call chain
+    Yield(SuspendedFrame),
+    /// Fuel ran out at a checkpoint - park the call chain until refuelled
+    OutOfFuel(SuspendedFrame),
 }
 
 /// Pre-allocated WASI stub memory regions
@@ -296,10 +543,8 @@ pub struct LoweredFunction {
     pub interface: String,
     /// Target function name (e.g., "[method]output-stream.blocking-write-and-flush")
     pub function: String,
-    /// Memory index for ABI operations (from canon options)
-    pub memory_idx: Option<u32>,
-    /// Realloc function index for ABI operations (from canon options)
-    pub realloc_idx: Option<u32>,
+    /// Canonical options of the canon.lower
+    pub options: CanonOptions,
 }
 
 /// Simple stackless WebAssembly execution engine
@@ -319,6 +564,10 @@ pub struct StacklessEngine {
     pub stats:             ExecutionStats,
     /// Remaining fuel for execution
     fuel:                  AtomicU64,
+    /// Instructions executed since the last fuel checkpoint
+    unmetered:             u64,
+    /// Clock advanced by the fuel charged at each checkpoint
+    fuel_clock:            Option<wrt_intercept::FuelClockRef>,
     /// Current instruction pointer
     instruction_pointer:   AtomicU64,
     /// Host function registry for calling imported functions
@@ -345,6 +594,9 @@ pub struct StacklessEngine {
     /// Optional runtime debugger for profiling and debugging
     #[cfg(all(feature = "std", feature = "debugger"))]
     debugger:              Option<Box<dyn RuntimeDebugger>>,
+    /// Selects the calls the debugger observes; all calls without a sampler
+    #[cfg(all(feature = "std", feature = "debugger"))]
+    trace_sampler:         Option<Arc<wrt_intercept::TraceSampler>>,
     /// Active exception state for exception propagation across calls
     /// Contains (instance_id, tag_idx, tag_identity, payload) when an exception is in flight
     /// tag_identity is Some((module, name)) for imported tags, None for local tags
@@ -360,6 +612,12 @@ pub struct StacklessEngine {
     /// Used for canon.lower synthesized functions that dispatch to canonical executor
     #[cfg(feature = "std")]
     lowered_functions:     HashMap<(usize, usize), LoweredFunction>,
+    /// Canonical options of canon.lift functions: (instance_id, func_idx) -> options
+    #[cfg(feature = "std")]
+    lifted_functions:      HashMap<(usize, usize), CanonOptions>,
+    /// Post-return of the last lifted call and the core results it is called with
+    #[cfg(feature = "std")]
+    pending_post_return:   Option<(CoreFuncRef, Vec<Value>)>,
     /// Instance name registry: instance_id -> registered module name
     /// Used for resolving tag identities in cross-module exception handling
     #[cfg(feature = "std")]
@@ -369,6 +627,25 @@ pub struct StacklessEngine {
     /// Kept for potential future use (e.g., stack inspection).
     #[cfg(feature = "std")]
     call_stack:            Vec<SuspendedFrame>,
+    /// Runtime limits applied to executed functions
+    store_limits:          StoreLimits,
+    /// Floating-point mode of instances without their own
+    fp_config:             FpConfig,
+    /// Floating-point modes set for individual instances
+    instance_fp_configs:   HashMap<usize, FpConfig>,
+    /// Floating-point adjustments applied per instance
+    fp_stats:              HashMap<usize, FpStats>,
+    /// Reusable operand stacks, locals and block stacks of calls
+    scratch:               ScratchStacks,
+    /// Epoch deadline interrupting long-running guests
+    epoch:                 EpochDeadline,
+    /// Call chain suspended at the epoch deadline or out of fuel
+    suspended:             Option<SuspendedCall>,
+    /// How thoroughly suspended frames are checked when they are resumed
+    frame_verification:    VerificationLevel,
+    /// Guest frames of the last trap
+    #[cfg(feature = "std")]
+    last_trap:             Option<TrapRecord>,
 }
 
 /// Simple stackless WebAssembly execution engine (no_std version)
@@ -390,6 +667,10 @@ pub struct StacklessEngine {
     fuel:                  AtomicU64,
     /// Current instruction pointer
     instruction_pointer:   AtomicU64,
+    /// Runtime limits applied to executed functions
+    store_limits:          StoreLimits,
+    /// Floating-point mode of executed code
+    fp_config:             FpConfig,
 }
 
 /// Simple RuntimeState implementation for debugger callbacks
@@ -445,34 +726,105 @@ fn current_function(&self) -> Option<u32> {
     }
 }
 
+/// Address operand of a memory instruction, treated as unsigned: an i32 for
+/// 32-bit memories and, with the memory64 feature, an i64 for memory64
+/// memories. Any other value is not an address.
+#[inline]
+fn memory_address(value: Value) -> Option<u64> {
+    match value {
+        Value::I32(addr) => Some(u64::from(addr as u32)),
+        #[cfg(feature = "memory64")]
+        Value::I64(addr) => Some(addr as u64),
+        _ => None,
+    }
+}
+
+/// Narrow a memory address or length to the u32 offsets of linear memory.
+/// Linear memory never exceeds 4GiB, so anything beyond traps.
+#[inline]
+fn linear_offset(addr: u64) -> wrt_error::Result<u32> {
+    u32::try_from(addr).map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))
+}
+
 /// Calculate effective memory address with overflow checking.
 /// Per WebAssembly spec, if base + offset overflows or exceeds u32::MAX, it traps.
 /// Returns Ok(effective_address) or Err if overflow occurs.
 #[inline]
-fn calculate_effective_address(base: i32, offset: u32, size: u32) -> wrt_error::Result<u64> {
-    // Convert base to u32 first (WebAssembly treats addresses as unsigned)
-    let base_u32 = base as u32;
-
+fn calculate_effective_address(base: u64, offset: u32, size: u32) -> wrt_error::Result<u64> {
     // Check for overflow in base + offset
-    let effective_addr = (base_u32 as u64)
+    let effective_addr = base
         .checked_add(offset as u64)
-        .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+        .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
     // Check for overflow when adding access size
     let end_addr = effective_addr
         .checked_add(size as u64)
-        .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+        .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
-    // If end_addr exceeds u32::MAX + 1 (4GB), it's out of bounds for 32-bit memory
+    // If end_addr exceeds u32::MAX + 1 (4GB), it's out of bounds for any memory,
+    // memory64 included, as linear memory never exceeds 4GB
     // But we let the actual memory bounds check handle the memory size comparison
     // Just ensure the calculation doesn't overflow
     if end_addr > u64::from(u32::MAX) + 1 {
-        return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
     }
 
     Ok(effective_addr)
 }
 
+/// Floating-point operands an instruction consumes, and whether it produces
+/// a floating-point result the FP mode applies to
+///
+/// Covers arithmetic, comparisons and precision conversions; sign operations,
+/// reinterpretations, loads and stores only move bits and are left alone.
+#[cfg(feature = "std")]
+fn fp_operation<P>(instruction: &wrt_foundation::types::Instruction<P>) -> Option<(usize, bool)>
+where
+    P: wrt_foundation::MemoryProvider + Clone + core::fmt::Debug + PartialEq + Eq + Default,
+{
+    use wrt_foundation::types::Instruction;
+
+    match instruction {
+        Instruction::F32Add
+        | Instruction::F32Sub
+        | Instruction::F32Mul
+        | Instruction::F32Div
+        | Instruction::F32Min
+        | Instruction::F32Max
+        | Instruction::F64Add
+        | Instruction::F64Sub
+        | Instruction::F64Mul
+        | Instruction::F64Div
+        | Instruction::F64Min
+        | Instruction::F64Max => Some((2, true)),
+        Instruction::F32Sqrt
+        | Instruction::F32Ceil
+        | Instruction::F32Floor
+        | Instruction::F32Trunc
+        | Instruction::F32Nearest
+        | Instruction::F64Sqrt
+        | Instruction::F64Ceil
+        | Instruction::F64Floor
+        | Instruction::F64Trunc
+        | Instruction::F64Nearest
+        | Instruction::F32DemoteF64
+        | Instruction::F64PromoteF32 => Some((1, true)),
+        Instruction::F32Eq
+        | Instruction::F32Ne
+        | Instruction::F32Lt
+        | Instruction::F32Gt
+        | Instruction::F32Le
+        | Instruction::F32Ge
+        | Instruction::F64Eq
+        | Instruction::F64Ne
+        | Instruction::F64Lt
+        | Instruction::F64Gt
+        | Instruction::F64Le
+        | Instruction::F64Ge => Some((2, false)),
+        _ => None,
+    }
+}
+
 impl StacklessEngine {
     /// Create a new stackless engine
     #[cfg(any(feature = "std", feature = "alloc"))]
@@ -485,6 +837,8 @@ pub fn new() -> Self {
             call_frames_count:   0,
             stats:               ExecutionStats::default(),
             fuel:                AtomicU64::new(u64::MAX),
+            unmetered:           0,
+            fuel_clock:          None,
             instruction_pointer: AtomicU64::new(0),
             #[cfg(feature = "std")]
             host_registry:       None,
@@ -500,6 +854,8 @@ pub fn new() -> Self {
             host_handler:        None,
             #[cfg(all(feature = "std", feature = "debugger"))]
             debugger:            None,
+            #[cfg(all(feature = "std", feature = "debugger"))]
+            trace_sampler:       None,
             #[cfg(feature = "std")]
             active_exception:    None,
             #[cfg(feature = "std")]
@@ -507,9 +863,23 @@ pub fn new() -> Self {
             #[cfg(feature = "std")]
             lowered_functions:   HashMap::new(),
             #[cfg(feature = "std")]
+            lifted_functions:    HashMap::new(),
+            #[cfg(feature = "std")]
+            pending_post_return: None,
+            #[cfg(feature = "std")]
             instance_registry:   HashMap::new(),
             #[cfg(feature = "std")]
             call_stack:          Vec::with_capacity(256),
+            store_limits:        StoreLimits::new(),
+            fp_config:           FpConfig::new(),
+            instance_fp_configs: HashMap::new(),
+            fp_stats:            HashMap::new(),
+            scratch:             ScratchStacks::new(&StoreLimits::new()),
+            epoch:               EpochDeadline::new(),
+            suspended:           None,
+            frame_verification:  VerificationLevel::default(),
+            #[cfg(feature = "std")]
+            last_trap:           None,
         }
     }
 
@@ -536,6 +906,23 @@ pub fn has_debugger(&self) -> bool {
         self.debugger.is_some()
     }
 
+    /// Only let the debugger observe the calls selected by `sampler`
+    ///
+    /// Each call to [`Self::execute`] is sampled by its function index; an
+    /// unsampled call runs without debugger callbacks, including the calls
+    /// it makes. The sampler may be shared with a tracing interceptor and
+    /// adjusted while the engine runs.
+    #[cfg(all(feature = "std", feature = "debugger"))]
+    pub fn set_trace_sampler(&mut self, sampler: Arc<wrt_intercept::TraceSampler>) {
+        self.trace_sampler = Some(sampler);
+    }
+
+    /// Let the debugger observe every call again
+    #[cfg(all(feature = "std", feature = "debugger"))]
+    pub fn clear_trace_sampler(&mut self) {
+        self.trace_sampler = None;
+    }
+
     /// Set the host import handler for resolving host function calls
     #[cfg(feature = "std")]
     pub fn set_host_handler(&mut self, handler: Box<dyn wrt_foundation::HostImportHandler>) {
@@ -574,8 +961,7 @@ pub fn register_lowered_function(
         func_idx: usize,
         interface: String,
         function: String,
-        memory_idx: Option<u32>,
-        realloc_idx: Option<u32>,
+        options: CanonOptions,
     ) {
         #[cfg(feature = "tracing")]
         trace!(
@@ -588,12 +974,138 @@ pub fn register_lowered_function(
         let lowered = LoweredFunction {
             interface,
             function,
-            memory_idx,
-            realloc_idx,
+            options,
         };
         self.lowered_functions.insert((instance_id, func_idx), lowered);
     }
 
+    /// Register the canonical options of a function exported by canon.lift
+    ///
+    /// Calls to the function through [`execute`](Self::execute) schedule the
+    /// post-return of the options, see [`run_post_return`](Self::run_post_return).
+    #[cfg(feature = "std")]
+    pub fn register_lifted_function(
+        &mut self,
+        instance_id: usize,
+        func_idx: usize,
+        options: CanonOptions,
+    ) {
+        #[cfg(feature = "tracing")]
+        trace!(
+            instance_id = instance_id,
+            func_idx = func_idx,
+            options = ?options,
+            "[CANON_LIFT] Registering lifted function"
+        );
+        self.lifted_functions.insert((instance_id, func_idx), options);
+    }
+
+    /// Canonical options registered for a lifted function
+    #[cfg(feature = "std")]
+    pub fn lifted_function_options(
+        &self,
+        instance_id: usize,
+        func_idx: usize,
+    ) -> Option<&CanonOptions> {
+        self.lifted_functions.get(&(instance_id, func_idx))
+    }
+
+    /// Whether the post-return of a lifted call has yet to run
+    #[cfg(feature = "std")]
+    pub fn has_pending_post_return(&self) -> bool {
+        self.pending_post_return.is_some()
+    }
+
+    /// Run the post-return of the last lifted call
+    ///
+    /// The results of a lifted function may point to guest memory that the
+    /// guest frees in its post-return, so the post-return only runs once the
+    /// caller has read them: when this is called, or at the latest before the
+    /// next call enters the engine.
+    #[cfg(feature = "std")]
+    pub fn run_post_return(&mut self) -> Result<()> {
+        if let Some((post_return, results)) = self.pending_post_return.take() {
+            #[cfg(feature = "tracing")]
+            trace!(
+                instance_id = post_return.instance_id,
+                func_idx = post_return.func_idx,
+                "[CANON_LIFT] Running post-return"
+            );
+            self.run_call(post_return.instance_id, post_return.func_idx, results)?;
+        }
+        Ok(())
+    }
+
+    /// Schedule the post-return of a completed call, if it has one
+    #[cfg(feature = "std")]
+    fn schedule_post_return(&mut self, instance_id: usize, func_idx: usize, results: &[Value]) {
+        let post_return = self
+            .lifted_functions
+            .get(&(instance_id, func_idx))
+            .and_then(|options| options.post_return);
+        if let Some(post_return) = post_return {
+            self.pending_post_return = Some((post_return, results.to_vec()));
+        }
+    }
+
+    /// Read a string from guest memory as described by canonical options
+    ///
+    /// `tagged_len` is the length the guest passed with the pointer, in
+    /// units of the string encoding of the options.
+    #[cfg(feature = "std")]
+    pub fn lift_string(&self, options: &CanonOptions, ptr: u32, tagged_len: u32) -> Result<String> {
+        let encoding = options.string_encoding;
+        if ptr % encoding.alignment() != 0 {
+            return Err(wrt_error::Error::runtime_trap("Misaligned string pointer"));
+        }
+        let mut bytes = vec![0u8; encoding.byte_len(tagged_len) as usize];
+        self.canon_memory(options)?.0.read(ptr, &mut bytes)?;
+        canon::decode_string(&bytes, tagged_len, encoding)
+    }
+
+    /// Copy a string into guest memory allocated with the realloc of
+    /// canonical options
+    ///
+    /// Returns the pointer and tagged length to pass to the guest.
+    #[cfg(feature = "std")]
+    pub fn lower_string(&mut self, options: &CanonOptions, s: &str) -> Result<(u32, u32)> {
+        let (bytes, tagged_len) = canon::encode_string(s, options.string_encoding)?;
+        let align = options.string_encoding.alignment();
+        let ptr = self.canon_realloc(options, align, bytes.len() as u32)?;
+        self.canon_memory(options)?.0.write_shared(ptr, &bytes)?;
+        Ok((ptr, tagged_len))
+    }
+
+    /// Memory of canonical options, defaulting to the memory of the realloc instance
+    #[cfg(feature = "std")]
+    fn canon_memory(&self, options: &CanonOptions) -> Result<crate::module::MemoryWrapper> {
+        let instance_id = options
+            .memory_instance
+            .or(options.realloc.map(|realloc| realloc.instance_id))
+            .ok_or_else(|| wrt_error::Error::runtime_error("Canonical options have no memory"))?;
+        self.instances
+            .get(&instance_id)
+            .ok_or_else(|| wrt_error::Error::runtime_error("Instance not found"))?
+            .memory(0)
+    }
+
+    /// Allocate `size` bytes aligned to `align` with the realloc of canonical options
+    #[cfg(feature = "std")]
+    fn canon_realloc(&mut self, options: &CanonOptions, align: u32, size: u32) -> Result<u32> {
+        let realloc = options
+            .realloc
+            .ok_or_else(|| wrt_error::Error::runtime_error("Canonical options have no realloc"))?;
+        let ptr = self.call_cabi_realloc(realloc.instance_id, realloc.func_idx, 0, 0, align, size)?;
+        if ptr % align != 0 {
+            return Err(wrt_error::Error::runtime_trap("realloc returned a misaligned pointer"));
+        }
+        let memory_size = self.canon_memory(options)?.0.size_in_bytes();
+        if ptr as usize + size as usize > memory_size {
+            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "realloc returned an out of bounds pointer"));
+        }
+        Ok(ptr)
+    }
+
     /// Register an instance name for cross-module exception handling
     ///
     /// This associates a human-readable module name with an instance ID.
@@ -652,6 +1164,10 @@ fn is_lowered_function(&self, instance_id: usize, func_idx: usize) -> bool {
     ///
     /// Implements canonical ABI lifting: converts core WASM values (i32 pointers/lengths)
     /// to component values (lists, records, etc.) by reading from instance memory.
+    ///
+    /// With a link interceptor on the host registry the call goes through
+    /// its strategies, and strategies intercepting canonical operations see
+    /// one lift of the flattened arguments and one lower of the results.
     #[cfg(all(feature = "std", feature = "wasi"))]
     fn execute_lowered_function(
         &mut self,
@@ -671,6 +1187,55 @@ fn execute_lowered_function(
             "[CANON_LOWER] Executing lowered function"
         );
 
+        let registry = self.host_registry.clone();
+        match registry.as_deref().and_then(wrt_host::CallbackRegistry::get_interceptor) {
+            Some(interceptor) => interceptor.intercept_call_owned(
+                &lowered.interface,
+                &lowered.function,
+                args,
+                |args| {
+                    let canonical = interceptor
+                        .intercepts_canonical(&lowered.interface, &lowered.function)
+                        .then_some(interceptor);
+                    self.call_lowered_function(instance_id, func_idx, &lowered, args, canonical)
+                },
+            ),
+            None => self.call_lowered_function(instance_id, func_idx, &lowered, args, None),
+        }
+    }
+
+    /// Lift the arguments of a lowered function, dispatch it to WASI and
+    /// lower its results
+    ///
+    /// `interceptor` sees the lift as the tuple of the flattened arguments,
+    /// without the return pointer, and the lower as the tuple of the
+    /// flattened results or the result record stored at the return pointer.
+    #[cfg(all(feature = "std", feature = "wasi"))]
+    fn call_lowered_function(
+        &mut self,
+        instance_id: usize,
+        func_idx: usize,
+        lowered: &LoweredFunction,
+        mut args: Vec<Value>,
+        interceptor: Option<&wrt_intercept::LinkInterceptor>,
+    ) -> Result<Vec<Value>> {
+        let via_memory = self.lowered_returns_via_memory(instance_id, func_idx);
+        if let Some(interceptor) = interceptor {
+            let params = if via_memory { args.len().saturating_sub(1) } else { args.len() };
+            let bytes = canon::encode_flat(&args[..params])?;
+            let ty = canon::flat_tuple_type(bytes.len() as u32);
+            if let Some(lifted) = interceptor.intercept_lift(
+                &lowered.interface,
+                &lowered.function,
+                &ty,
+                0,
+                &bytes,
+            )? {
+                let lifted = canon::decode_flat(&args[..params], &lifted)?;
+                args.splice(..params, lifted);
+            }
+        }
+
         // Lift args based on the function being called
         // Some functions need memory access to convert pointers to actual data
         let wasi_args = self.lift_lowered_function_args(
@@ -681,30 +1246,207 @@ fn execute_lowered_function(
         )?;
 
         // Dispatch to WASI using the standard dispatch interface
-        if let Some(ref mut dispatcher) = self.wasi_dispatcher {
-            let wasi_results = dispatcher.dispatch(&lowered.interface, &lowered.function, &wasi_args)?;
-
-            // Convert wrt_wasi::Value back to wrt_foundation::values::Value
-            let results: Vec<Value> = wasi_results.into_iter().map(|v| {
-                match v {
-                    wrt_wasi::Value::S32(i) => Value::I32(i),
-                    wrt_wasi::Value::U32(u) => Value::I32(u as i32),
-                    wrt_wasi::Value::S64(i) => Value::I64(i),
-                    wrt_wasi::Value::U64(u) => Value::I64(u as i64),
-                    wrt_wasi::Value::F32(f) => Value::F32(FloatBits32::from_f32(f)),
-                    wrt_wasi::Value::F64(f) => Value::F64(FloatBits64::from_f64(f)),
-                    wrt_wasi::Value::Bool(b) => Value::I32(if b { 1 } else { 0 }),
-                    wrt_wasi::Value::U8(u) => Value::I32(u as i32),
-                    wrt_wasi::Value::S8(i) => Value::I32(i as i32),
-                    wrt_wasi::Value::U16(u) => Value::I32(u as i32),
-                    wrt_wasi::Value::S16(i) => Value::I32(i as i32),
-                    _ => Value::I32(0), // Default for unsupported types
+        let wasi_results = match self.wasi_dispatcher {
+            Some(ref mut dispatcher) => {
+                dispatcher.dispatch(&lowered.interface, &lowered.function, &wasi_args)?
+            }
+            None => {
+                return Err(wrt_error::Error::runtime_error(
+                    "WASI dispatcher not available for lowered function",
+                ))
+            }
+        };
+
+        // Strings and lists are lowered into memory allocated with the guest's realloc,
+        // and results that do not fit a core result are stored through the return pointer
+        if wasi_results.iter().any(wasi_value_needs_realloc) && via_memory {
+            let retptr = match args.last() {
+                Some(Value::I32(ptr)) => *ptr as u32,
+                _ => {
+                    return Err(wrt_error::Error::runtime_error(
+                        "Missing return pointer for lowered function",
+                    ))
+                }
+            };
+            let options = self.lowered_store_options(instance_id, &lowered.options)?;
+            let memory = self.canon_memory(&options)?;
+            self.store_wasi_fields(&options, &memory, retptr, wasi_results.iter())?;
+            if let Some(interceptor) = interceptor {
+                let (size, _) = wasi_fields_layout(wasi_results.iter());
+                let mut memory_bytes = memory.0.buffer()?;
+                let stored = retptr as usize..(retptr + size) as usize;
+                let value_data = memory_bytes.get(stored.clone()).map(<[u8]>::to_vec).ok_or_else(
+                    || wrt_error::Error::runtime_out_of_bounds("Lowered results outside memory"),
+                )?;
+                // A strategy handling the lower rewrites the stored result record
+                if interceptor.intercept_lower(
+                    &lowered.interface,
+                    &lowered.function,
+                    &canon::flat_tuple_type(size),
+                    &value_data,
+                    retptr,
+                    &mut memory_bytes,
+                )? {
+                    memory.0.write_shared(retptr, &memory_bytes[stored])?;
                 }
-            }).collect();
+            }
+            return Ok(Vec::new());
+        }
 
-            Ok(results)
-        } else {
-            Err(wrt_error::Error::runtime_error("WASI dispatcher not available for lowered function"))
+        // Convert wrt_wasi::Value back to wrt_foundation::values::Value
+        let results: Vec<Value> = wasi_results.into_iter().map(|v| {
+            match v {
+                wrt_wasi::Value::S32(i) => Value::I32(i),
+                wrt_wasi::Value::U32(u) => Value::I32(u as i32),
+                wrt_wasi::Value::S64(i) => Value::I64(i),
+                wrt_wasi::Value::U64(u) => Value::I64(u as i64),
+                wrt_wasi::Value::F32(f) => Value::F32(FloatBits32::from_f32(f)),
+                wrt_wasi::Value::F64(f) => Value::F64(FloatBits64::from_f64(f)),
+                wrt_wasi::Value::Bool(b) => Value::I32(if b { 1 } else { 0 }),
+                wrt_wasi::Value::U8(u) => Value::I32(u as i32),
+                wrt_wasi::Value::S8(i) => Value::I32(i as i32),
+                wrt_wasi::Value::U16(u) => Value::I32(u as i32),
+                wrt_wasi::Value::S16(i) => Value::I32(i as i32),
+                _ => Value::I32(0), // Default for unsupported types
+            }
+        }).collect();
+
+        let Some(interceptor) = interceptor else {
+            return Ok(results);
+        };
+        let value_data = canon::encode_flat(&results)?;
+        let mut memory_bytes = value_data.clone();
+        if interceptor.intercept_lower(
+            &lowered.interface,
+            &lowered.function,
+            &canon::flat_tuple_type(value_data.len() as u32),
+            &value_data,
+            0,
+            &mut memory_bytes,
+        )? {
+            return canon::decode_flat(&results, &memory_bytes);
+        }
+        Ok(results)
+    }
+
+    /// Whether a lowered function returns its results through a return pointer
+    #[cfg(all(feature = "std", feature = "wasi"))]
+    fn lowered_returns_via_memory(&self, instance_id: usize, func_idx: usize) -> bool {
+        self.instances.get(&instance_id).is_some_and(|instance| {
+            let module = instance.module();
+            module
+                .functions
+                .get(func_idx)
+                .and_then(|func| module.types.get(func.type_idx as usize))
+                .is_some_and(|func_type| func_type.results.is_empty())
+        })
+    }
+
+    /// Canonical options used to store the results of a lowered function
+    ///
+    /// Options registered without a memory or realloc fall back to the
+    /// `cabi_realloc` export of an instance with memory.
+    #[cfg(all(feature = "std", feature = "wasi"))]
+    fn lowered_store_options(
+        &self,
+        instance_id: usize,
+        options: &CanonOptions,
+    ) -> Result<CanonOptions> {
+        if options.realloc.is_some() {
+            return Ok(options.clone());
+        }
+        let candidates = core::iter::once(instance_id)
+            .chain(options.memory_instance)
+            .chain(self.instances.keys().copied());
+        for id in candidates {
+            let Some(instance) = self.instances.get(&id) else { continue };
+            if instance.memory(0).is_err() {
+                continue;
+            }
+            if let Ok(func_idx) = self.find_export_index(&instance.module(), "cabi_realloc") {
+                return Ok(options
+                    .clone()
+                    .with_memory(id)
+                    .with_realloc(CoreFuncRef { instance_id: id, func_idx }));
+            }
+        }
+        Err(wrt_error::Error::runtime_error("Lowered function results need a realloc"))
+    }
+
+    /// Store consecutive values laid out as the fields of a record at `ptr`
+    #[cfg(all(feature = "std", feature = "wasi"))]
+    fn store_wasi_fields<'a>(
+        &mut self,
+        options: &CanonOptions,
+        memory: &crate::module::MemoryWrapper,
+        ptr: u32,
+        values: impl Iterator<Item = &'a wrt_wasi::Value>,
+    ) -> Result<()> {
+        let mut offset = 0;
+        for value in values {
+            let (size, align) = wasi_value_layout(value);
+            offset = align_to(offset, align);
+            self.store_wasi_value(options, memory, ptr + offset, value)?;
+            offset += size;
+        }
+        Ok(())
+    }
+
+    /// Store a value at `ptr` in its canonical ABI representation
+    #[cfg(all(feature = "std", feature = "wasi"))]
+    fn store_wasi_value(
+        &mut self,
+        options: &CanonOptions,
+        memory: &crate::module::MemoryWrapper,
+        ptr: u32,
+        value: &wrt_wasi::Value,
+    ) -> Result<()> {
+        use wrt_wasi::Value as WasiValue;
+
+        let write = |bytes: &[u8]| memory.0.write_shared(ptr, bytes);
+        let write_pair = |first: u32, second: u32| {
+            memory.0.write_shared(ptr, &first.to_le_bytes())?;
+            memory.0.write_shared(ptr + 4, &second.to_le_bytes())
+        };
+        match value {
+            WasiValue::Bool(v) => write(&[u8::from(*v)]),
+            WasiValue::U8(v) => write(&[*v]),
+            WasiValue::S8(v) => write(&v.to_le_bytes()),
+            WasiValue::U16(v) => write(&v.to_le_bytes()),
+            WasiValue::S16(v) => write(&v.to_le_bytes()),
+            WasiValue::U32(v) => write(&v.to_le_bytes()),
+            WasiValue::S32(v) => write(&v.to_le_bytes()),
+            WasiValue::U64(v) => write(&v.to_le_bytes()),
+            WasiValue::S64(v) => write(&v.to_le_bytes()),
+            WasiValue::F32(v) => write(&v.to_le_bytes()),
+            WasiValue::F64(v) => write(&v.to_le_bytes()),
+            WasiValue::String(s) => {
+                let (string_ptr, tagged_len) = self.lower_string(options, s)?;
+                write_pair(string_ptr, tagged_len)
+            }
+            WasiValue::List(elements) => {
+                let (size, align) = elements.first().map_or((0, 1), wasi_value_layout);
+                let list_ptr = self.canon_realloc(options, align, size * elements.len() as u32)?;
+                for (i, element) in elements.iter().enumerate() {
+                    self.store_wasi_value(options, memory, list_ptr + i as u32 * size, element)?;
+                }
+                write_pair(list_ptr, elements.len() as u32)
+            }
+            WasiValue::Record(fields) => {
+                self.store_wasi_fields(options, memory, ptr, fields.iter().map(|(_, value)| value))
+            }
+            WasiValue::Tuple(values) => self.store_wasi_fields(options, memory, ptr, values.iter()),
+            WasiValue::Option(None) => write(&[0]),
+            WasiValue::Option(Some(payload)) | WasiValue::Result(Err(payload)) => {
+                write(&[1])?;
+                let offset = align_to(1, wasi_value_layout(payload).1);
+                self.store_wasi_value(options, memory, ptr + offset, payload)
+            }
+            WasiValue::Result(Ok(payload)) => {
+                write(&[0])?;
+                let offset = align_to(1, wasi_value_layout(payload).1);
+                self.store_wasi_value(options, memory, ptr + offset, payload)
+            }
         }
     }
 
@@ -881,7 +1623,7 @@ fn call_exported_function(
             #[cfg(feature = "tracing")]
             trace!("[CROSS_CALL] call stack exhausted at depth {} (target_instance={}, export='{}')",
                      self.call_frames_count, target_instance_id, export_name);
-            return Err(wrt_error::Error::runtime_trap("call stack exhausted"));
+            return Err(wrt_error::Error::wasm_trap(TrapCode::CallStackExhausted, "call stack exhausted"));
         }
 
         // Get the target instance
@@ -973,27 +1715,29 @@ fn resolve_export_func_idx(
     /// the frame will execute the handler code.
     ///
     /// Returns true if a handler was found and applied, false otherwise.
+    ///
+    /// Fails if the frame's function body cannot be parsed.
     #[cfg(feature = "std")]
-    fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> bool {
+    fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> Result<bool> {
         use wrt_foundation::types::Instruction;
 
         // Get the active exception (including tag identity for imported tags)
         let (ex_tag_idx, ex_identity, ex_payload) = match &self.active_exception {
             Some((_inst_id, tag_idx, identity, payload)) => (*tag_idx, identity.clone(), payload.clone()),
-            None => return false,
+            None => return Ok(false),
         };
 
         // Look up the module and instructions for this frame
         let instance = match self.instances.get(&frame.instance_id) {
             Some(inst) => inst.clone(),
-            None => return false,
+            None => return Ok(false),
         };
 
         // Check if function is aliased - get the correct module
         let actual_module = if let Some(&original_instance_id) = self.aliased_functions.get(&(frame.instance_id, frame.func_idx)) {
             match self.instances.get(&original_instance_id) {
                 Some(inst) => inst.module().clone(),
-                None => return false,
+                None => return Ok(false),
             }
         } else {
             instance.module().clone()
@@ -1002,9 +1746,9 @@ fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> bo
         // Get function and instructions
         let func = match actual_module.functions.get(frame.func_idx) {
             Some(f) => f,
-            None => return false,
+            None => return Ok(false),
         };
-        let instructions = &func.body.instructions;
+        let instructions = &func.expr()?.instructions;
 
         // Search for try_table handler in frame's block_stack
         let mut found_handler = false;
@@ -1067,7 +1811,7 @@ fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> bo
         }
 
         if !found_handler {
-            return false;
+            return Ok(false);
         }
 
         // Clear active exception - it's being handled
@@ -1099,7 +1843,7 @@ fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> bo
             }
             // Set pc past end of instructions so the resumed function returns immediately
             frame.pc = instructions.len();
-            return true;
+            return Ok(true);
         };
 
         // Get entry stack height from target block
@@ -1147,7 +1891,7 @@ fn find_and_apply_exception_handler(&mut self, frame: &mut SuspendedFrame) -> bo
         }
         frame.pc = new_pc;
 
-        true
+        Ok(true)
     }
 
     /// Set the host function registry for imported function calls
@@ -1156,6 +1900,94 @@ pub fn set_host_registry(&mut self, registry: Arc<wrt_host::CallbackRegistry>) {
         self.host_registry = Some(registry);
     }
 
+    /// Set the runtime limits applied to executed functions
+    pub fn set_store_limits(&mut self, limits: StoreLimits) {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        self.scratch.apply_limits(&limits);
+        self.store_limits = limits;
+    }
+
+    /// Runtime limits applied to executed functions
+    pub fn store_limits(&self) -> &StoreLimits {
+        &self.store_limits
+    }
+
+    /// Counters of the reusable call buffers
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn scratch_stats(&self) -> ScratchStats {
+        self.scratch.stats()
+    }
+
+    /// Take an empty argument buffer from the reusable call buffers
+    ///
+    /// Passing it to [`Self::execute`] returns it to the pool once the call
+    /// has moved the arguments into its locals.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn take_args(&mut self) -> Vec<Value> {
+        self.scratch.take_values()
+    }
+
+    /// Set the floating-point mode of instances without their own
+    pub fn set_fp_config(&mut self, config: FpConfig) {
+        self.fp_config = config;
+    }
+
+    /// Floating-point mode of instances without their own
+    pub fn fp_config(&self) -> FpConfig {
+        self.fp_config
+    }
+
+    /// Set how thoroughly the guard values and fingerprints of suspended
+    /// frames are checked when they are resumed
+    ///
+    /// A mismatch traps with a `RUNTIME_FRAME_INTEGRITY_ERROR` safety error.
+    /// See [`GuardedFrames`] for what each level checks.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_frame_verification_level(&mut self, level: VerificationLevel) {
+        self.frame_verification = level;
+    }
+
+    /// Verification level of suspended frames
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn frame_verification_level(&self) -> VerificationLevel {
+        self.frame_verification
+    }
+
+    /// Set the floating-point mode of one instance, or inherit the engine's
+    /// again if `None`
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_instance_fp_config(&mut self, instance_id: usize, config: Option<FpConfig>) {
+        match config {
+            Some(config) => {
+                self.instance_fp_configs.insert(instance_id, config);
+            },
+            None => {
+                self.instance_fp_configs.remove(&instance_id);
+            },
+        }
+    }
+
+    /// Floating-point mode an instance runs with
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn instance_fp_config(&self, instance_id: usize) -> FpConfig {
+        self.instance_fp_configs.get(&instance_id).copied().unwrap_or(self.fp_config)
+    }
+
+    /// Report the floating-point mode of a loaded instance
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn fp_mode_report(&self, instance_id: usize) -> Option<FpModeReport> {
+        if !self.instances.contains_key(&instance_id) {
+            return None;
+        }
+        Some(FpModeReport {
+            instance_id,
+            config: self.instance_fp_config(instance_id),
+            overridden: self.instance_fp_configs.contains_key(&instance_id),
+            host_flushes_denormals: host_flushes_denormals(),
+            stats: self.fp_stats.get(&instance_id).copied().unwrap_or_default(),
+        })
+    }
+
     /// Add an import link for cross-instance calls
     #[cfg(feature = "std")]
     pub fn add_import_link(
@@ -1344,6 +2176,8 @@ pub fn new() -> wrt_error::Result<Self> {
                 stats: ExecutionStats::default(),
                 fuel: AtomicU64::new(u64::MAX),
                 instruction_pointer: AtomicU64::new(0),
+                store_limits: StoreLimits::new(),
+                fp_config: FpConfig::new(),
             })
         }
     }
@@ -1398,10 +2232,28 @@ pub fn reset_call_depth(&mut self) {
     /// all existing instance IDs.
     pub fn clear_instances(&mut self) {
         self.instances.clear();
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            self.instance_fp_configs.clear();
+            self.fp_stats.clear();
+        }
         // Reset instance ID counter to avoid confusion with old IDs
         self.next_instance_id.store(0, Ordering::Relaxed);
     }
 
+    /// Remove an instance by ID
+    ///
+    /// Returns the removed instance, if it was loaded. The ID is not reused.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn remove_instance(&mut self, instance_id: usize) -> Option<Arc<ModuleInstance>> {
+        if self.current_instance_id == Some(instance_id) {
+            self.current_instance_id = None;
+        }
+        self.instance_fp_configs.remove(&instance_id);
+        self.fp_stats.remove(&instance_id);
+        self.instances.remove(&instance_id)
+    }
+
     /// Get an instance by ID
     ///
     /// Returns a reference to the ModuleInstance if found.
@@ -1425,6 +2277,115 @@ pub fn execute(
         instance_id: usize,
         func_idx: usize,
         args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        if self.suspended.is_some() {
+            return Err(wrt_error::Error::runtime_execution_error(
+                "Suspended call must be resumed or discarded first",
+            ));
+        }
+
+        // The guest is not entered again before the last post-return ran
+        #[cfg(feature = "std")]
+        self.run_post_return()?;
+
+        // Unsampled calls run with the debugger detached
+        #[cfg(all(feature = "std", feature = "debugger"))]
+        let detached = match &self.trace_sampler {
+            Some(sampler) if !sampler.sample_function(func_idx as u32) => self.debugger.take(),
+            _ => None,
+        };
+
+        let results = self.run_call(instance_id, func_idx, args);
+
+        #[cfg(all(feature = "std", feature = "debugger"))]
+        if detached.is_some() {
+            self.debugger = detached;
+        }
+        let results = results?;
+        #[cfg(feature = "std")]
+        self.schedule_post_return(instance_id, func_idx, &results);
+        Ok(results)
+    }
+
+    /// Continue the call suspended at the epoch deadline or when its fuel
+    /// ran out
+    ///
+    /// Returns the results of the call once it completes, or the
+    /// `codes::EXECUTION_YIELDED` or `codes::FUEL_EXHAUSTED` error again if
+    /// it reaches the next deadline or runs out of fuel first.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn resume(&mut self) -> Result<Vec<Value>> {
+        let SuspendedCall { entry, mut frames } = self
+            .suspended
+            .take()
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("No suspended call"))?;
+        let frame = frames
+            .pop()
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("Suspended call has no frames"))?;
+        self.call_frames_count += frames.len() + 1;
+
+        let results = self.run_trampoline(
+            entry,
+            frame.instance_id,
+            frame.func_idx,
+            Vec::new(),
+            Some(frame),
+            frames,
+        )?;
+        #[cfg(feature = "std")]
+        self.schedule_post_return(entry.0, entry.1, &results);
+        Ok(results)
+    }
+
+    /// Whether a call is suspended at the epoch deadline or out of fuel
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn has_suspended_call(&self) -> bool {
+        self.suspended.is_some()
+    }
+
+    /// Drop the suspended call without completing it
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn discard_suspended_call(&mut self) {
+        self.suspended = None;
+    }
+
+    /// Run a call to completion on the trampoline
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn run_call(
+        &mut self,
+        instance_id: usize,
+        func_idx: usize,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        // Check call depth for the initial function
+        if self.call_frames_count >= MAX_CALL_DEPTH {
+            return Err(wrt_error::Error::wasm_trap(TrapCode::CallStackExhausted, "call stack exhausted"));
+        }
+        self.call_frames_count += 1;
+
+        self.run_trampoline(
+            (instance_id, func_idx),
+            instance_id,
+            func_idx,
+            args,
+            None,
+            Vec::new(),
+        )
+    }
+
+    /// Drive the trampoline from the given function and suspended callers
+    ///
+    /// `entry` is the function the host called, recorded when the call chain
+    /// is suspended at the epoch deadline or out of fuel.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn run_trampoline(
+        &mut self,
+        entry: (usize, usize),
+        instance_id: usize,
+        func_idx: usize,
+        args: Vec<Value>,
+        resume: Option<SuspendedFrame>,
+        pending: Vec<SuspendedFrame>,
     ) -> Result<Vec<Value>> {
         // Full call trampoline: handles TailCall, regular Call, and exception unwinding.
         // Instead of recursive Rust stack frames (which overflow in debug mode at ~50-100
@@ -1434,14 +2395,8 @@ pub fn execute(
         let mut current_instance_id = instance_id;
         let mut current_func_idx = func_idx;
         let mut current_args = args;
-        let mut pending_frames: Vec<SuspendedFrame> = Vec::new();
-        let mut resume_state: Option<SuspendedFrame> = None;
-
-        // Check call depth for the initial function
-        if self.call_frames_count >= MAX_CALL_DEPTH {
-            return Err(wrt_error::Error::runtime_trap("call stack exhausted"));
-        }
-        self.call_frames_count += 1;
+        let mut pending_frames = GuardedFrames::from_frames(pending, self.frame_verification);
+        let mut resume_state: Option<SuspendedFrame> = resume;
 
         loop {
             let outcome = self.execute_function_body(
@@ -1453,23 +2408,31 @@ pub fn execute(
 
             match outcome {
                 Ok(ExecutionOutcome::Complete(results)) => {
-                    if let Some(mut frame) = pending_frames.pop() {
+                    let frame = pending_frames.pop().inspect_err(|_| {
+                        let depth = pending_frames.len() + 2;
+                        self.call_frames_count = self.call_frames_count.saturating_sub(depth);
+                    })?;
+                    if let Some(mut frame) = frame {
                         // Callee completed - push results onto caller's operand stack
                         self.call_frames_count = self.call_frames_count.saturating_sub(1);
-                        for result in results {
-                            frame.operand_stack.push(result);
-                        }
+                        let mut results = results;
+                        frame.operand_stack.append(&mut results);
+                        self.scratch.values.recycle(results);
                         // Resume the caller
                         current_instance_id = frame.instance_id;
                         current_func_idx = frame.func_idx;
                         current_args = Vec::new(); // unused when resuming
                         resume_state = Some(frame);
                     } else {
-                        // Top-level return - trampoline is done
+                        // Top-level return - trampoline is done. The caller owns
+                        // the returned vector, so the pooled one is kept.
                         self.call_frames_count = self.call_frames_count.saturating_sub(1);
-                        return Ok(results);
+                        let returned = results.to_vec();
+                        self.scratch.values.recycle(results);
+                        return Ok(returned);
                     }
                 }
+                #[cfg(feature = "tail-call")]
                 Ok(ExecutionOutcome::TailCall { func_idx: next_func, args: next_args }) => {
                     // Tail call - reuse current frame slot, no stack growth
                     current_func_idx = next_func;
@@ -1490,7 +2453,7 @@ pub fn execute(
                     if self.call_frames_count >= MAX_CALL_DEPTH {
                         let depth = pending_frames.len() + 1;
                         self.call_frames_count = self.call_frames_count.saturating_sub(depth);
-                        return Err(wrt_error::Error::runtime_trap("call stack exhausted"));
+                        return Err(wrt_error::Error::wasm_trap(TrapCode::CallStackExhausted, "call stack exhausted"));
                     }
                     self.call_frames_count += 1;
                     current_instance_id = target_id;
@@ -1498,6 +2461,50 @@ pub fn execute(
                     current_args = call_args;
                     resume_state = None;
                 }
+                Ok(ExecutionOutcome::Yield(frame)) => {
+                    pending_frames.push(frame);
+                    let depth = pending_frames.len();
+                    self.call_frames_count = self.call_frames_count.saturating_sub(depth);
+                    // A trampoline nested in a host call cannot be parked
+                    // without its caller, so the deadline traps it instead
+                    if self.call_frames_count > 0 {
+                        return Err(wrt_error::Error::new(
+                            wrt_error::ErrorCategory::Runtime,
+                            wrt_error::codes::EXECUTION_TIMEOUT,
+                            "Epoch deadline exceeded in nested call",
+                        ));
+                    }
+                    self.suspended = Some(SuspendedCall {
+                        entry,
+                        frames: pending_frames.into_frames()?,
+                    });
+                    return Err(wrt_error::Error::new(
+                        wrt_error::ErrorCategory::Runtime,
+                        wrt_error::codes::EXECUTION_YIELDED,
+                        "Execution yielded at epoch deadline",
+                    ));
+                }
+                Ok(ExecutionOutcome::OutOfFuel(frame)) => {
+                    pending_frames.push(frame);
+                    let depth = pending_frames.len();
+                    self.call_frames_count = self.call_frames_count.saturating_sub(depth);
+                    if self.call_frames_count > 0 {
+                        return Err(wrt_error::Error::new(
+                            wrt_error::ErrorCategory::Runtime,
+                            wrt_error::codes::FUEL_EXHAUSTED,
+                            "Fuel exhausted in nested call",
+                        ));
+                    }
+                    self.suspended = Some(SuspendedCall {
+                        entry,
+                        frames: pending_frames.into_frames()?,
+                    });
+                    return Err(wrt_error::Error::new(
+                        wrt_error::ErrorCategory::Runtime,
+                        wrt_error::codes::FUEL_EXHAUSTED,
+                        "Execution ran out of fuel",
+                    ));
+                }
                 Err(e) => {
                     // Handle exception unwinding through pending frames
                     #[cfg(feature = "std")]
@@ -1505,8 +2512,15 @@ pub fn execute(
                         // Current function (which errored) is done
                         self.call_frames_count = self.call_frames_count.saturating_sub(1);
                         let mut found_handler = false;
-                        while let Some(mut frame) = pending_frames.pop() {
-                            if self.find_and_apply_exception_handler(&mut frame) {
+                        while let Some(mut frame) = pending_frames.pop().inspect_err(|_| {
+                            let depth = pending_frames.len() + 1;
+                            self.call_frames_count = self.call_frames_count.saturating_sub(depth);
+                        })? {
+                            let handled = self.find_and_apply_exception_handler(&mut frame).inspect_err(|_| {
+                                let depth = pending_frames.len() + 1;
+                                self.call_frames_count = self.call_frames_count.saturating_sub(depth);
+                            })?;
+                            if handled {
                                 // Handler found - resume this frame
                                 current_instance_id = frame.instance_id;
                                 current_func_idx = frame.func_idx;
@@ -1525,6 +2539,8 @@ pub fn execute(
                         return Err(e);
                     }
                     // Non-exception error - clean up all frames
+                    #[cfg(feature = "std")]
+                    self.record_trap(&e, current_instance_id, current_func_idx, &pending_frames);
                     let depth = pending_frames.len() + 1;
                     self.call_frames_count = self.call_frames_count.saturating_sub(depth);
                     return Err(e);
@@ -1533,6 +2549,40 @@ pub fn execute(
         }
     }
 
+    /// Keep the frames of `instance_id` at a trap for [`Self::crash_dump`]
+    ///
+    /// The trapping frame's instruction is the last one the engine started.
+    /// Frames of other instances the call passed through are left out.
+    #[cfg(feature = "std")]
+    fn record_trap(
+        &mut self,
+        error: &wrt_error::Error,
+        instance_id: usize,
+        func_idx: usize,
+        pending_frames: &GuardedFrames<SuspendedFrame>,
+    ) {
+        let mut frames = vec![DumpFrame {
+            func_idx: func_idx as u32,
+            pc: Some(self.instruction_pointer.load(Ordering::Relaxed) as u32),
+            ..DumpFrame::default()
+        }];
+        frames.extend(
+            pending_frames
+                .iter()
+                .rev()
+                .filter(|frame| frame.instance_id == instance_id)
+                .map(dump_frame),
+        );
+        self.last_trap = Some(TrapRecord {
+            instance_id,
+            cause: CrashCause::Trap {
+                code:    error.code,
+                message: error.message.into(),
+            },
+            frames,
+        });
+    }
+
     /// Execute a leaf function that is guaranteed not to make further calls.
     /// Used for cabi_realloc and similar canonical ABI functions that only do
     /// memory operations and return immediately. This avoids creating a nested
@@ -1549,7 +2599,7 @@ fn execute_leaf_function(
     ) -> Result<Vec<Value>> {
         // Leaf functions still count toward call depth for safety
         if self.call_frames_count >= MAX_CALL_DEPTH {
-            return Err(wrt_error::Error::runtime_trap("call stack exhausted"));
+            return Err(wrt_error::Error::wasm_trap(TrapCode::CallStackExhausted, "call stack exhausted"));
         }
         self.call_frames_count += 1;
 
@@ -1560,6 +2610,7 @@ fn execute_leaf_function(
 
         match outcome {
             Ok(ExecutionOutcome::Complete(results)) => Ok(results),
+            #[cfg(feature = "tail-call")]
             Ok(ExecutionOutcome::TailCall { .. }) => {
                 // Leaf functions must not tail call
                 Err(wrt_error::Error::runtime_error(
@@ -1572,6 +2623,19 @@ fn execute_leaf_function(
                     "leaf function attempted call (cabi_realloc contract violation)",
                 ))
             }
+            Ok(ExecutionOutcome::Yield(_)) => {
+                // Leaf functions run inside their caller and cannot be parked
+                Err(wrt_error::Error::new(
+                    wrt_error::ErrorCategory::Runtime,
+                    wrt_error::codes::EXECUTION_TIMEOUT,
+                    "Epoch deadline exceeded in leaf function",
+                ))
+            }
+            Ok(ExecutionOutcome::OutOfFuel(_)) => Err(wrt_error::Error::new(
+                wrt_error::ErrorCategory::Runtime,
+                wrt_error::codes::FUEL_EXHAUSTED,
+                "Fuel exhausted in leaf function",
+            )),
             Err(e) => Err(e),
         }
     }
@@ -1752,6 +2816,9 @@ fn execute_function_body(
                 wrt_error::Error::runtime_error("Failed to get function type")
             })?;
 
+        // Reject result arities beyond the store limit before running the body
+        self.store_limits.check_result_arity(func_type.results.len())?;
+
         // Execute the function's bytecode instructions
         #[cfg(feature = "std")]
         {
@@ -1775,7 +2842,7 @@ fn execute_function_body(
 
             debug!("Called with args.len()={}", args.len());
 
-            let instructions = &func.body.instructions;
+            let instructions = &func.expr()?.instructions;
             #[cfg(feature = "tracing")]
             trace!(
                 func_idx = func_idx,
@@ -1794,6 +2861,9 @@ fn execute_function_body(
             // so we need a separate binding for the caller's func_idx.
             let caller_func_idx = func_idx;
 
+            // Floating-point mode, applied around arithmetic and comparisons
+            let fp_config = self.instance_fp_config(instance_id);
+
             // Initialize execution state - either from resume or fresh call
             let mut operand_stack: Vec<Value>;
             let mut locals: Vec<Value>;
@@ -1811,13 +2881,14 @@ fn execute_function_body(
                 instruction_count = frame.instruction_count;
                 pc = frame.pc;
             } else {
-                // Fresh call - initialize from args and function signature
-                operand_stack = Vec::new();
-                locals = Vec::new();
+                // Fresh call - initialize from args and function signature. The
+                // stacks come from the scratch pool and the arguments become the
+                // first locals in place.
+                operand_stack = self.scratch.take_values();
                 instruction_count = 0;
                 block_depth = 0;
                 pc = 0;
-                block_stack = Vec::new();
+                block_stack = self.scratch.blocks.take();
 
                 // Initialize parameters as locals
                 // Need to match the function type signature, not just provided args
@@ -1839,16 +2910,13 @@ fn execute_function_body(
                     "[EXEC] Function parameter info"
                 );
 
-                // Add provided arguments
-                for (i, arg) in args.iter().enumerate() {
-                    if i < expected_param_count {
-                        locals.push(arg.clone());
-                    }
-                }
+                // Provided arguments, beyond the expected ones dropped
+                locals = args;
+                locals.truncate(expected_param_count);
 
                 // Pad with default values for missing parameters
-                if args.len() < expected_param_count {
-                    for i in args.len()..expected_param_count {
+                if locals.len() < expected_param_count {
+                    for i in locals.len()..expected_param_count {
                         let param_type = func_type.params.get(i)
                             .ok_or_else(|| wrt_error::Error::runtime_error(
                                 "Parameter index out of bounds - type corrupted"
@@ -1888,6 +2956,41 @@ fn execute_function_body(
                 }
                 #[cfg(feature = "tracing")]
                 trace!("Initialized {} locals total", locals.len());
+
+                // Function entry is a fuel and epoch checkpoint
+                if !self.charge_fuel() {
+                    #[cfg(all(feature = "std", feature = "debugger"))]
+                    {
+                        self.debugger = debugger_opt;
+                    }
+                    return Ok(ExecutionOutcome::OutOfFuel(SuspendedFrame {
+                        instance_id,
+                        func_idx: caller_func_idx,
+                        pc,
+                        locals,
+                        operand_stack,
+                        block_stack,
+                        block_depth,
+                        instruction_count,
+                    }));
+                }
+                if self.epoch.reached() {
+                    #[cfg(all(feature = "std", feature = "debugger"))]
+                    {
+                        self.debugger = debugger_opt;
+                    }
+                    self.epoch.interrupt()?;
+                    return Ok(ExecutionOutcome::Yield(SuspendedFrame {
+                        instance_id,
+                        func_idx: caller_func_idx,
+                        pc,
+                        locals,
+                        operand_stack,
+                        block_stack,
+                        block_depth,
+                        instruction_count,
+                    }));
+                }
             } // end of fresh-call initialization
 
             while pc < instructions.len() {
@@ -1899,6 +3002,9 @@ fn execute_function_body(
                     .map_err(|_| wrt_error::Error::runtime_error("Instruction index out of bounds"))?;
 
                 instruction_count += 1;
+                self.unmetered += 1;
+                let instruction_pc = pc;
+                self.instruction_pointer.store(pc as u64, Ordering::Relaxed);
                 #[cfg(feature = "tracing")]
                 trace!("pc={}, instruction={:?}", pc, instruction);
 
@@ -1917,6 +3023,15 @@ fn execute_function_body(
                     }
                 }
 
+                let fp_operation = if fp_config.is_active() { fp_operation(instruction) } else { None };
+                if let Some((operands, _)) = fp_operation {
+                    let stats = self.fp_stats.entry(instance_id).or_default();
+                    let first = operand_stack.len().saturating_sub(operands);
+                    for value in &mut operand_stack[first..] {
+                        fp_config.adjust_operand(value, stats);
+                    }
+                }
+
                 match *instruction {
                     Instruction::Unreachable => {
                         // Unreachable instruction - this is a WebAssembly trap
@@ -1934,7 +3049,8 @@ fn execute_function_body(
                                 "[TRAP] Unreachable instruction executed"
                             );
                         }
-                        return Err(wrt_error::Error::runtime_execution_error(
+                        return Err(wrt_error::Error::wasm_trap(
+                            TrapCode::Unreachable,
                             "WebAssembly trap: unreachable instruction executed",
                         ));
                     }
@@ -2265,7 +3381,7 @@ fn execute_function_body(
                             #[cfg(feature = "tracing")]
                             trace!("Call({}): needs {} params, stack has {} values", func_idx, param_count, operand_stack.len());
 
-                            let mut call_args = Vec::new();
+                            let mut call_args = self.scratch.take_values();
                             for _ in 0..param_count {
                                 if let Some(arg) = operand_stack.pop() {
                                     call_args.push(arg);
@@ -2338,15 +3454,15 @@ fn execute_function_body(
                                     // Tables store FuncRef values, not raw integers
                                     match func_ref {
                                         Value::FuncRef(Some(fref)) => fref.index as usize,
-                                        Value::FuncRef(None) => return Err(wrt_error::Error::runtime_trap("uninitialized element")),
+                                        Value::FuncRef(None) => return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element")),
                                         Value::I32(idx) => idx as usize, // Legacy fallback
                                         Value::I64(idx) => idx as usize, // Legacy fallback
-                                        _ => return Err(wrt_error::Error::runtime_trap("uninitialized element")),
+                                        _ => return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element")),
                                     }
                                 } else if let Ok(None) = table.0.get(table_func_idx) {
-                                    return Err(wrt_error::Error::runtime_trap("uninitialized element"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element"));
                                 } else {
-                                    return Err(wrt_error::Error::runtime_trap("undefined element"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "undefined element"));
                                 }
                             } else {
                                 // Fall back: use the element segment if tables aren't properly initialized
@@ -2425,7 +3541,7 @@ fn execute_function_body(
 
                                 // NO FALLBACK: Per CLAUDE.md, fail loud and early if element not found
                                 resolved_func_idx.ok_or_else(|| {
-                                    wrt_error::Error::runtime_trap("undefined element")
+                                    wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "undefined element")
                                 })?
                             }
                         } else {
@@ -2454,7 +3570,7 @@ fn execute_function_body(
 
                             // Pop the required number of arguments from the stack
                             let param_count = func_type.params.len();
-                            let mut call_args = Vec::new();
+                            let mut call_args = self.scratch.take_values();
                             for _ in 0..param_count {
                                 if let Some(arg) = operand_stack.pop() {
                                     call_args.push(arg);
@@ -2494,8 +3610,9 @@ fn execute_function_body(
 
                         // Validate function index
                         if func_idx >= module.functions.len() {
-                            return Err(wrt_error::Error::runtime_trap(
-                                "call_indirect: function index out of bounds"
+                            return Err(wrt_error::Error::wasm_trap(
+                                TrapCode::TableOutOfBounds,
+                                "call_indirect: function index out of bounds",
                             ));
                         }
 
@@ -2517,12 +3634,12 @@ fn execute_function_body(
                                 got_results = func_type.results.len(),
                                 "[CALL_INDIRECT] Type mismatch"
                             );
-                            return Err(wrt_error::Error::runtime_trap("indirect call type mismatch"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::IndirectCallSignatureMismatch, "indirect call type mismatch"));
                         }
 
                         // Pop the required number of arguments from the stack
                         let param_count = func_type.params.len();
-                        let mut call_args = Vec::new();
+                        let mut call_args = self.scratch.take_values();
                         for _ in 0..param_count {
                             if let Some(arg) = operand_stack.pop() {
                                 call_args.push(arg);
@@ -2534,7 +3651,7 @@ fn execute_function_body(
 
                         // Check if the function is an imported function that's linked to another instance
                         // Imported functions have empty body and locals
-                        let is_import = func.body.is_empty() && func.locals.is_empty();
+                        let is_import = func.is_import_stub();
 
                         if is_import {
                             #[cfg(feature = "tracing")]
@@ -2675,6 +3792,7 @@ fn execute_function_body(
                             });
                         }
                     }
+                    #[cfg(feature = "tail-call")]
                     Instruction::ReturnCall(func_idx) => {
                         // ReturnCall: tail call to another function
                         // Similar to Call, but the results become the current function's return value
@@ -2683,7 +3801,7 @@ fn execute_function_body(
 
                         // Get function type to determine parameter count
                         if (func_idx as usize) >= module.functions.len() {
-                            return Err(wrt_error::Error::runtime_trap("return_call: function index out of bounds"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "return_call: function index out of bounds"));
                         }
                         let func = &module.functions[func_idx as usize];
                         let func_type = module.types.get(func.type_idx as usize)
@@ -2691,7 +3809,7 @@ fn execute_function_body(
 
                         // Pop the required number of arguments from the stack
                         let param_count = func_type.params.len();
-                        let mut call_args = Vec::new();
+                        let mut call_args = self.scratch.take_values();
                         for _ in 0..param_count {
                             if let Some(arg) = operand_stack.pop() {
                                 call_args.push(arg);
@@ -2707,6 +3825,12 @@ fn execute_function_body(
                             self.debugger = debugger_opt;
                         }
 
+                        // The tail-calling frame is done; its stacks go back to the
+                        // pool and are reused by the callee's frame
+                        self.scratch.values.recycle(operand_stack);
+                        self.scratch.values.recycle(locals);
+                        self.scratch.blocks.recycle(block_stack);
+
                         // Return TailCall - the trampoline will execute the target function
                         // This avoids recursive native calls and prevents stack overflow
                         return Ok(ExecutionOutcome::TailCall {
@@ -2714,6 +3838,7 @@ fn execute_function_body(
                             args: call_args,
                         });
                     }
+                    #[cfg(feature = "tail-call")]
                     Instruction::ReturnCallIndirect(type_idx, table_idx) => {
                         // ReturnCallIndirect: tail call through indirect table reference
                         // Pop the function index from the stack
@@ -2737,15 +3862,15 @@ fn execute_function_body(
                                 if let Ok(Some(func_ref)) = table.0.get(table_func_idx) {
                                     match func_ref {
                                         Value::FuncRef(Some(fref)) => fref.index as usize,
-                                        Value::FuncRef(None) => return Err(wrt_error::Error::runtime_trap("uninitialized element")),
+                                        Value::FuncRef(None) => return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element")),
                                         Value::I32(idx) => idx as usize,
                                         Value::I64(idx) => idx as usize,
-                                        _ => return Err(wrt_error::Error::runtime_trap("uninitialized element")),
+                                        _ => return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element")),
                                     }
                                 } else if let Ok(None) = table.0.get(table_func_idx) {
-                                    return Err(wrt_error::Error::runtime_trap("uninitialized element"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "uninitialized element"));
                                 } else {
-                                    return Err(wrt_error::Error::runtime_trap("undefined element"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "undefined element"));
                                 }
                             } else {
                                 return Err(wrt_error::Error::runtime_trap("return_call_indirect: table not found"));
@@ -2756,7 +3881,7 @@ fn execute_function_body(
 
                         // Validate function index
                         if func_idx >= module.functions.len() {
-                            return Err(wrt_error::Error::runtime_trap("return_call_indirect: function index out of bounds"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "return_call_indirect: function index out of bounds"));
                         }
 
                         // Get function type and validate
@@ -2768,12 +3893,12 @@ fn execute_function_body(
                             .ok_or_else(|| wrt_error::Error::runtime_error("Invalid expected function type"))?;
 
                         if !func_types_match(expected_type, func_type) {
-                            return Err(wrt_error::Error::runtime_trap("indirect call type mismatch"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::IndirectCallSignatureMismatch, "indirect call type mismatch"));
                         }
 
                         // Pop the required number of arguments from the stack
                         let param_count = func_type.params.len();
-                        let mut call_args = Vec::new();
+                        let mut call_args = self.scratch.take_values();
                         for _ in 0..param_count {
                             if let Some(arg) = operand_stack.pop() {
                                 call_args.push(arg);
@@ -2789,6 +3914,12 @@ fn execute_function_body(
                             self.debugger = debugger_opt;
                         }
 
+                        // The tail-calling frame is done; its stacks go back to the
+                        // pool and are reused by the callee's frame
+                        self.scratch.values.recycle(operand_stack);
+                        self.scratch.values.recycle(locals);
+                        self.scratch.blocks.recycle(block_stack);
+
                         // Return TailCall - the trampoline will execute the target function
                         // This avoids recursive native calls and prevents stack overflow
                         return Ok(ExecutionOutcome::TailCall {
@@ -3071,11 +4202,11 @@ fn execute_function_body(
                     Instruction::I32DivS => {
                         if let (Some(Value::I32(b)), Some(Value::I32(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             // Check for integer overflow: INT_MIN / -1 would overflow
                             if a == i32::MIN && b == -1 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let result = a.wrapping_div(b);
                             #[cfg(feature = "tracing")]
@@ -3086,7 +4217,7 @@ fn execute_function_body(
                     Instruction::I32DivU => {
                         if let (Some(Value::I32(b)), Some(Value::I32(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             let result = (a as u32).wrapping_div(b as u32) as i32;
                             #[cfg(feature = "tracing")]
@@ -3097,7 +4228,7 @@ fn execute_function_body(
                     Instruction::I32RemS => {
                         if let (Some(Value::I32(b)), Some(Value::I32(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             // Note: INT_MIN % -1 = 0 (no overflow for remainder)
                             let result = a.wrapping_rem(b);
@@ -3109,7 +4240,7 @@ fn execute_function_body(
                     Instruction::I32RemU => {
                         if let (Some(Value::I32(b)), Some(Value::I32(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             let result = (a as u32).wrapping_rem(b as u32) as i32;
                             #[cfg(feature = "tracing")]
@@ -3149,11 +4280,11 @@ fn execute_function_body(
                     Instruction::I64DivS => {
                         if let (Some(Value::I64(b)), Some(Value::I64(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             // Check for integer overflow: INT_MIN / -1 would overflow
                             if a == i64::MIN && b == -1 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let result = a.wrapping_div(b);
                             #[cfg(feature = "tracing")]
@@ -3164,7 +4295,7 @@ fn execute_function_body(
                     Instruction::I64DivU => {
                         if let (Some(Value::I64(b)), Some(Value::I64(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             let result = (a as u64).wrapping_div(b as u64) as i64;
                             #[cfg(feature = "tracing")]
@@ -3175,7 +4306,7 @@ fn execute_function_body(
                     Instruction::I64RemS => {
                         if let (Some(Value::I64(b)), Some(Value::I64(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             // Note: INT_MIN % -1 = 0 (no overflow for remainder)
                             let result = a.wrapping_rem(b);
@@ -3187,7 +4318,7 @@ fn execute_function_body(
                     Instruction::I64RemU => {
                         if let (Some(Value::I64(b)), Some(Value::I64(a))) = (operand_stack.pop(), operand_stack.pop()) {
                             if b == 0 {
-                                return Err(wrt_error::Error::runtime_trap("integer divide by zero"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerDivideByZero, "integer divide by zero"));
                             }
                             let result = (a as u64).wrapping_rem(b as u64) as i64;
                             #[cfg(feature = "tracing")]
@@ -3265,17 +4396,18 @@ fn execute_function_body(
                         if let Some(Value::F32(bits)) = operand_stack.pop() {
                             let f = f32::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [-2147483648, 2147483647]
                             if f_trunc < -2_147_483_648.0_f32 || f_trunc >= 2_147_483_648.0_f32 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I32(f_trunc as i32));
                         }
@@ -3284,17 +4416,18 @@ fn execute_function_body(
                         if let Some(Value::F32(bits)) = operand_stack.pop() {
                             let f = f32::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [0, 4294967295]
                             if f_trunc < 0.0_f32 || f_trunc >= 4_294_967_296.0_f32 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I32(f_trunc as u32 as i32));
                         }
@@ -3303,17 +4436,18 @@ fn execute_function_body(
                         if let Some(Value::F64(bits)) = operand_stack.pop() {
                             let f = f64::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [-2147483648, 2147483647]
                             if f_trunc < -2_147_483_648.0_f64 || f_trunc >= 2_147_483_648.0_f64 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I32(f_trunc as i32));
                         }
@@ -3322,17 +4456,18 @@ fn execute_function_body(
                         if let Some(Value::F64(bits)) = operand_stack.pop() {
                             let f = f64::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [0, 4294967295]
                             if f_trunc < 0.0_f64 || f_trunc >= 4_294_967_296.0_f64 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I32(f_trunc as u32 as i32));
                         }
@@ -3341,19 +4476,20 @@ fn execute_function_body(
                         if let Some(Value::F32(bits)) = operand_stack.pop() {
                             let f = f32::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [-9223372036854775808, 9223372036854775807]
                             if f_trunc < -9_223_372_036_854_775_808.0_f32
                                 || f_trunc >= 9_223_372_036_854_775_808.0_f32
                             {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I64(f_trunc as i64));
                         }
@@ -3362,17 +4498,18 @@ fn execute_function_body(
                         if let Some(Value::F32(bits)) = operand_stack.pop() {
                             let f = f32::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [0, 18446744073709551615]
                             if f_trunc < 0.0_f32 || f_trunc >= 18_446_744_073_709_551_616.0_f32 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I64(f_trunc as u64 as i64));
                         }
@@ -3381,19 +4518,20 @@ fn execute_function_body(
                         if let Some(Value::F64(bits)) = operand_stack.pop() {
                             let f = f64::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [-9223372036854775808, 9223372036854775807]
                             if f_trunc < -9_223_372_036_854_775_808.0_f64
                                 || f_trunc >= 9_223_372_036_854_775_808.0_f64
                             {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I64(f_trunc as i64));
                         }
@@ -3402,17 +4540,18 @@ fn execute_function_body(
                         if let Some(Value::F64(bits)) = operand_stack.pop() {
                             let f = f64::from_bits(bits.0);
                             if f.is_nan() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::InvalidConversionToInteger,
                                     "invalid conversion to integer",
                                 ));
                             }
                             if f.is_infinite() {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             let f_trunc = f.trunc();
                             // Range check: must be in [0, 18446744073709551615]
                             if f_trunc < 0.0_f64 || f_trunc >= 18_446_744_073_709_551_616.0_f64 {
-                                return Err(wrt_error::Error::runtime_trap("integer overflow"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::IntegerOverflow, "integer overflow"));
                             }
                             operand_stack.push(Value::I64(f_trunc as u64 as i64));
                         }
@@ -4091,7 +5230,7 @@ fn execute_function_body(
                     // IMPORTANT: Use instance.memory() for initialized memory, not module.get_memory()
                     // The instance has data segments applied, the module is just a template
                     Instruction::I32Load(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             // Calculate effective address with overflow checking (4 bytes for i32)
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
@@ -4120,20 +5259,20 @@ fn execute_function_body(
                                                 pc = pc,
                                                 "[MEM-OOB] I32Load failed"
                                             );
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(e) => {
                                     #[cfg(feature = "tracing")]
                                     trace!("I32Load: failed to get memory at index {}: {:?}", mem_arg.memory_index, e);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Store(mem_arg) => {
-                        if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I32(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             // Calculate effective address with overflow checking (4 bytes for i32)
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
@@ -4151,18 +5290,18 @@ fn execute_function_body(
                                             trace!("I32Store: successfully wrote value {} to address {}", value, offset);
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Load8S(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!("I32Load8S: reading from address {}", offset);
@@ -4178,18 +5317,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Load8U(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4203,18 +5342,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Load16S(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4228,18 +5367,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Load16U(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4253,18 +5392,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Store8(mem_arg) => {
-                        if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I32(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
 
                             #[cfg(feature = "tracing")]
@@ -4277,18 +5416,18 @@ fn execute_function_body(
                                     match memory.write_shared(offset, &bytes) {
                                         Ok(()) => {}
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I32Store16(mem_arg) => {
-                        if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I32(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4300,18 +5439,18 @@ fn execute_function_body(
                                             trace!("I32Store16: successfully wrote value {} to address {}", value as u16, offset);
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 8)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4345,18 +5484,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Store(mem_arg) => {
-                        if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I64(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 8)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4369,14 +5508,14 @@ fn execute_function_body(
                                             trace!("I64Store: successfully wrote value {} to address {}", value, offset);
                                         }
                                         Err(_e) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_e) => {
                                     #[cfg(feature = "tracing")]
                                     trace!("I64Store: failed to get memory at index {}", mem_arg.memory_index);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
@@ -4385,7 +5524,7 @@ fn execute_function_body(
                     // I64 Partial Load Instructions (load narrower value, extend to i64)
                     // ========================================
                     Instruction::I64Load8S(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4406,18 +5545,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load8U(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4438,18 +5577,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load16S(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4470,18 +5609,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load16U(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4502,18 +5641,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load32S(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4534,18 +5673,18 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Load32U(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4566,12 +5705,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
@@ -4580,7 +5719,7 @@ fn execute_function_body(
                     // I64 Partial Store Instructions (store lower bits of i64)
                     // ========================================
                     Instruction::I64Store8(mem_arg) => {
-                        if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I64(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 1)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4601,20 +5740,20 @@ fn execute_function_body(
                                             trace!("I64Store8: successfully wrote value {} to address {}", value & 0xFF, offset);
                                         }
                                         Err(_e) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_e) => {
                                     #[cfg(feature = "tracing")]
                                     trace!("I64Store8: failed to get memory at index {}", mem_arg.memory_index);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Store16(mem_arg) => {
-                        if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I64(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 2)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4635,20 +5774,20 @@ fn execute_function_body(
                                             trace!("I64Store16: successfully wrote value {} to address {}", value & 0xFFFF, offset);
                                         }
                                         Err(_e) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_e) => {
                                     #[cfg(feature = "tracing")]
                                     trace!("I64Store16: failed to get memory at index {}", mem_arg.memory_index);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::I64Store32(mem_arg) => {
-                        if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::I64(value)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -4669,14 +5808,14 @@ fn execute_function_body(
                                             trace!("I64Store32: successfully wrote value {} to address {}", value & 0xFFFFFFFF, offset);
                                         }
                                         Err(_e) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_e) => {
                                     #[cfg(feature = "tracing")]
                                     trace!("I64Store32: failed to get memory at index {}", mem_arg.memory_index);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
@@ -4692,7 +5831,7 @@ fn execute_function_body(
                     Instruction::F32Load(mem_arg) => {
                         #[cfg(feature = "tracing")]
                         trace!("F32Load: stack before pop has {} elements", operand_stack.len());
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             #[cfg(feature = "tracing")]
                             trace!("F32Load: addr={}, offset={}, mem_idx={}", addr, offset, mem_arg.memory_index);
@@ -4712,14 +5851,14 @@ fn execute_function_body(
                                         Err(e) => {
                                             #[cfg(feature = "tracing")]
                                             error!("F32Load: memory read error: {:?}", e);
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(e) => {
                                     #[cfg(feature = "tracing")]
                                     error!("F32Load: memory access error: {:?}", e);
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         } else {
@@ -4728,24 +5867,24 @@ fn execute_function_body(
                         }
                     }
                     Instruction::F32Store(mem_arg) => {
-                        if let (Some(Value::F32(bits)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::F32(bits)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 4)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
                                     let memory = &memory_wrapper.0;
                                     let bytes = bits.0.to_le_bytes();
                                     if memory.write_shared(offset, &bytes).is_err() {
-                                        return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                     }
                                 }
                                 Err(_e) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::F64Load(mem_arg) => {
-                        if let Some(Value::I32(addr)) = operand_stack.pop() {
+                        if let Some(addr) = operand_stack.pop().and_then(memory_address) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 8)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -4757,29 +5896,29 @@ fn execute_function_body(
                                             operand_stack.push(Value::F64(FloatBits64(bits)));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
                     }
                     Instruction::F64Store(mem_arg) => {
-                        if let (Some(Value::F64(bits)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
+                        if let (Some(Value::F64(bits)), Some(addr)) = (operand_stack.pop(), operand_stack.pop().and_then(memory_address)) {
                             let offset = calculate_effective_address(addr, mem_arg.offset, 8)? as u32;
                             match instance.memory(mem_arg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
                                     let memory = &memory_wrapper.0;
                                     let bytes = bits.0.to_le_bytes();
                                     if memory.write_shared(offset, &bytes).is_err() {
-                                        return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                     }
                                 }
                                 Err(_e) => {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                             }
                         }
@@ -5665,7 +6804,11 @@ fn execute_function_body(
                                     size_in_pages = size_in_pages,
                                     "[MemorySize] Retrieved memory size"
                                 );
-                                operand_stack.push(Value::I32(size_in_pages as i32));
+                                if memory.is_memory64() {
+                                    operand_stack.push(Value::I64(i64::from(size_in_pages)));
+                                } else {
+                                    operand_stack.push(Value::I32(size_in_pages as i32));
+                                }
                             }
                             Err(e) => {
                                 #[cfg(feature = "tracing")]
@@ -5675,62 +6818,78 @@ fn execute_function_body(
                         }
                     }
                     Instruction::MemoryGrow(memory_idx) => {
-                        // Pop the number of pages to grow
-                        if let Some(Value::I32(delta)) = operand_stack.pop() {
-                            if delta < 0 {
-                                // Negative delta is invalid, return -1 (failure)
-                                #[cfg(feature = "tracing")]
-                                trace!("MemoryGrow: negative delta {}, pushing -1", delta);
-                                operand_stack.push(Value::I32(-1));
-                            } else {
-                                // Use instance memory for grow (has initialized data segments)
-                                match instance.memory(memory_idx as u32) {
-                                    Ok(memory_wrapper) => {
-                                        let memory = &memory_wrapper.0;
-                                        let current_size = memory.size();
-                                        #[cfg(feature = "tracing")]
-                                        trace!(
-                                            memory_idx = memory_idx,
-                                            delta = delta,
-                                            current_size = current_size,
-                                            "[MemoryGrow] Attempting to grow memory"
-                                        );
-                                        match memory.grow_shared(delta as u32) {
-                                            Ok(prev_pages) => {
-                                                #[cfg(feature = "tracing")]
-                                                trace!(
-                                                    memory_idx = memory_idx,
-                                                    prev_pages = prev_pages,
-                                                    new_pages = prev_pages + delta as u32,
-                                                    "[MemoryGrow] Success"
-                                                );
-                                                operand_stack.push(Value::I32(prev_pages as i32));
-                                            }
-                                            Err(e) => {
-                                                #[cfg(feature = "tracing")]
-                                                warn!(
-                                                    memory_idx = memory_idx,
-                                                    error = ?e,
-                                                    "[MemoryGrow] Failed"
-                                                );
-                                                operand_stack.push(Value::I32(-1));
-                                            }
+                        // Pop the number of pages to grow: an i32, or an i64 for memory64
+                        // memories, answered with the previous size or -1 of the same type
+                        let popped = match operand_stack.pop() {
+                            Some(Value::I32(delta)) => Some((i64::from(delta), false)),
+                            #[cfg(feature = "memory64")]
+                            Some(Value::I64(delta)) => Some((delta, true)),
+                            _ => None,
+                        };
+                        if let Some((delta, memory64)) = popped {
+                            let grow_result = |pages: i64| {
+                                if memory64 {
+                                    Value::I64(pages)
+                                } else {
+                                    Value::I32(pages as i32)
+                                }
+                            };
+                            // Negative i32 deltas are huge unsigned deltas and fail like any other
+                            // delta beyond the runtime page limit
+                            let delta = if memory64 { delta } else { i64::from(delta as i32 as u32) };
+                            match (u32::try_from(delta), instance.memory(memory_idx as u32)) {
+                                (Ok(delta), Ok(memory_wrapper)) => {
+                                    let memory = &memory_wrapper.0;
+                                    let current_size = memory.size();
+                                    #[cfg(feature = "tracing")]
+                                    trace!(
+                                        memory_idx = memory_idx,
+                                        delta = delta,
+                                        current_size = current_size,
+                                        "[MemoryGrow] Attempting to grow memory"
+                                    );
+                                    match memory.grow_shared(delta) {
+                                        Ok(prev_pages) => {
+                                            #[cfg(feature = "tracing")]
+                                            trace!(
+                                                memory_idx = memory_idx,
+                                                prev_pages = prev_pages,
+                                                new_pages = prev_pages + delta,
+                                                "[MemoryGrow] Success"
+                                            );
+                                            operand_stack.push(grow_result(i64::from(prev_pages)));
+                                        }
+                                        Err(e) => {
+                                            #[cfg(feature = "tracing")]
+                                            warn!(
+                                                memory_idx = memory_idx,
+                                                error = ?e,
+                                                "[MemoryGrow] Failed"
+                                            );
+                                            operand_stack.push(grow_result(-1));
                                         }
-                                    }
-                                    Err(e) => {
-                                        #[cfg(feature = "tracing")]
-                                        trace!("MemoryGrow: memory[{}] not found: {:?}", memory_idx, e);
-                                        operand_stack.push(Value::I32(-1));
                                     }
                                 }
+                                (Err(_), _) => {
+                                    #[cfg(feature = "tracing")]
+                                    trace!("MemoryGrow: delta {} exceeds the runtime page limit, pushing -1", delta);
+                                    operand_stack.push(grow_result(-1));
+                                }
+                                (_, Err(e)) => {
+                                    #[cfg(feature = "tracing")]
+                                    trace!("MemoryGrow: memory[{}] not found: {:?}", memory_idx, e);
+                                    operand_stack.push(grow_result(-1));
+                                }
                             }
                         }
                     }
                     Instruction::MemoryCopy(dst_mem_idx, src_mem_idx) => {
                         // Pop size, src, dest from stack (in that order per wasm spec)
-                        if let (Some(Value::I32(size)), Some(Value::I32(src)), Some(Value::I32(dest))) =
-                            (operand_stack.pop(), operand_stack.pop(), operand_stack.pop())
-                        {
+                        if let (Some(size), Some(src), Some(dest)) = (
+                            operand_stack.pop().and_then(memory_address),
+                            operand_stack.pop().and_then(memory_address),
+                            operand_stack.pop().and_then(memory_address),
+                        ) {
                             #[cfg(feature = "tracing")]
                             trace!(
                                 dest = format_args!("{:#x}", dest),
@@ -5757,14 +6916,14 @@ fn execute_function_body(
                                 let memory_wrapper = instance.memory(dst_mem_idx)?;
                                 let memory = &memory_wrapper.0;
                                 let memory_size = memory.size_in_bytes() as u32;
-                                let dest_u32 = dest as u32;
-                                let src_u32 = src as u32;
-                                let size_u32 = size as u32;
+                                let dest_u32 = linear_offset(dest)?;
+                                let src_u32 = linear_offset(src)?;
+                                let size_u32 = linear_offset(size)?;
 
                                 if size_u32 == 0 {
                                     // For size 0, check if offsets are within bounds (can be equal to size)
                                     if dest_u32 > memory_size || src_u32 > memory_size {
-                                        return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                     }
                                     // No-op for zero size copy after bounds check passes
                                     continue;
@@ -5772,12 +6931,12 @@ fn execute_function_body(
 
                                 // For size > 0, check if (offset + size) overflows or exceeds memory size
                                 let dest_end = dest_u32.checked_add(size_u32)
-                                    .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                    .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
                                 let src_end = src_u32.checked_add(size_u32)
-                                    .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                    .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
                                 if dest_end > memory_size || src_end > memory_size {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
 
                                 let size_usize = size_u32 as usize;
@@ -5822,9 +6981,11 @@ fn execute_function_body(
                     }
                     Instruction::MemoryFill(mem_idx) => {
                         // Pop size, value, dest from stack (in that order per wasm spec)
-                        if let (Some(Value::I32(size)), Some(Value::I32(value)), Some(Value::I32(dest))) =
-                            (operand_stack.pop(), operand_stack.pop(), operand_stack.pop())
-                        {
+                        if let (Some(size), Some(Value::I32(value)), Some(dest)) = (
+                            operand_stack.pop().and_then(memory_address),
+                            operand_stack.pop(),
+                            operand_stack.pop().and_then(memory_address),
+                        ) {
                             #[cfg(feature = "tracing")]
                             trace!(
                                 dest = format_args!("{:#x}", dest),
@@ -5840,13 +7001,13 @@ fn execute_function_body(
                             let memory_wrapper = instance.memory(mem_idx)?;
                             let memory = &memory_wrapper.0;
                             let memory_size = memory.size_in_bytes() as u32;
-                            let dest_u32 = dest as u32;
-                            let size_u32 = size as u32;
+                            let dest_u32 = linear_offset(dest)?;
+                            let size_u32 = linear_offset(size)?;
 
                             if size_u32 == 0 {
                                 // For size 0, check if offset is within bounds (can be equal to size)
                                 if dest_u32 > memory_size {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                                 // No-op for zero size fill after bounds check passes
                                 continue;
@@ -5854,10 +7015,10 @@ fn execute_function_body(
 
                             // For size > 0, check if (offset + size) overflows or exceeds memory size
                             let dest_end = dest_u32.checked_add(size_u32)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
                             if dest_end > memory_size {
-                                return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                             }
 
                             let size_usize = size_u32 as usize;
@@ -5887,9 +7048,11 @@ fn execute_function_body(
                     }
                     Instruction::MemoryInit(data_idx, mem_idx) => {
                         // Pop n (length), s (source offset in data), d (dest offset in memory)
-                        if let (Some(Value::I32(n)), Some(Value::I32(s)), Some(Value::I32(d))) =
-                            (operand_stack.pop(), operand_stack.pop(), operand_stack.pop())
-                        {
+                        if let (Some(Value::I32(n)), Some(Value::I32(s)), Some(d)) = (
+                            operand_stack.pop(),
+                            operand_stack.pop(),
+                            operand_stack.pop().and_then(memory_address),
+                        ) {
                             #[cfg(feature = "tracing")]
                             trace!(
                                 dest = format_args!("{:#x}", d),
@@ -5910,12 +7073,12 @@ fn execute_function_body(
 
                             // Get data segment from module (for length calculation)
                             let data_segment = module.data.get(data_idx as usize)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
                             // If dropped, treat as zero-length segment
                             let data_len = if is_dropped { 0u32 } else { data_segment.init.len() as u32 };
                             let s_u32 = s as u32;
-                            let d_u32 = d as u32;
+                            let d_u32 = linear_offset(d)?;
                             let n_u32 = n as u32;
 
                             // Per WebAssembly spec: bounds check MUST happen before checking n==0
@@ -5927,7 +7090,7 @@ fn execute_function_body(
                             if n_u32 == 0 {
                                 // For n == 0, check if offsets are within bounds (can be equal to size)
                                 if s_u32 > data_len || d_u32 > memory_size {
-                                    return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                                 }
                                 // No-op for zero size init after bounds check passes
                                 continue;
@@ -5935,15 +7098,15 @@ fn execute_function_body(
 
                             // For n > 0, check if (offset + n) overflows or exceeds bounds
                             let src_end = s_u32.checked_add(n_u32)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
                             let dest_end = d_u32.checked_add(n_u32)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap("out of bounds memory access"))?;
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"))?;
 
                             if src_end > data_len {
-                                return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                             }
                             if dest_end > memory_size {
-                                return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                             }
 
                             // Copy data from segment to memory
@@ -5975,7 +7138,7 @@ fn execute_function_body(
 
                         // Validate data segment index
                         if data_idx as usize >= module.data.len() {
-                            return Err(wrt_error::Error::runtime_trap("out of bounds memory access"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "out of bounds memory access"));
                         }
 
                         // Initialize dropped_data_segments for this instance if not already done
@@ -6305,7 +7468,8 @@ fn execute_function_body(
                                 Value::FuncRef(None) | Value::ExternRef(None) => {
                                     #[cfg(feature = "tracing")]
                                     error!("RefAsNonNull: null reference");
-                                    return Err(wrt_error::Error::runtime_trap(
+                                    return Err(wrt_error::Error::wasm_trap(
+                                        TrapCode::UninitializedElement,
                                         "null reference in ref.as_non_null",
                                     ));
                                 }
@@ -6418,7 +7582,8 @@ fn execute_function_body(
                             );
 
                             if elem_idx < 0 {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "table.get: index cannot be negative",
                                 ));
                             }
@@ -6473,7 +7638,8 @@ fn execute_function_body(
                             );
 
                             if elem_idx < 0 {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "table.set: index cannot be negative",
                                 ));
                             }
@@ -6613,7 +7779,8 @@ fn execute_function_body(
                             );
 
                             if *dest_idx < 0 || *fill_size < 0 {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "table.fill: negative dest or size",
                                 ));
                             }
@@ -6673,7 +7840,8 @@ fn execute_function_body(
                             );
 
                             if *dst_idx < 0 || *src_idx < 0 || *copy_size < 0 {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "out of bounds table access",
                                 ));
                             }
@@ -6690,16 +7858,19 @@ fn execute_function_body(
 
                                 // Bounds check BEFORE zero-length check (per WebAssembly spec)
                                 let src_end = (*src_idx as u32).checked_add(*copy_size as u32)
-                                    .ok_or_else(|| wrt_error::Error::runtime_trap(
-                                        "out of bounds table access"
+                                    .ok_or_else(|| wrt_error::Error::wasm_trap(
+                                        TrapCode::TableOutOfBounds,
+                                        "out of bounds table access",
                                     ))?;
                                 let dst_end = (*dst_idx as u32).checked_add(*copy_size as u32)
-                                    .ok_or_else(|| wrt_error::Error::runtime_trap(
-                                        "out of bounds table access"
+                                    .ok_or_else(|| wrt_error::Error::wasm_trap(
+                                        TrapCode::TableOutOfBounds,
+                                        "out of bounds table access",
                                     ))?;
                                 if src_end > src_table.size() || dst_end > dst_table.size() {
-                                    return Err(wrt_error::Error::runtime_trap(
-                                        "out of bounds table access"
+                                    return Err(wrt_error::Error::wasm_trap(
+                                        TrapCode::TableOutOfBounds,
+                                        "out of bounds table access",
                                     ));
                                 }
 
@@ -6762,7 +7933,8 @@ fn execute_function_body(
                             );
 
                             if *dst_idx < 0 || *src_idx < 0 || *init_size < 0 {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "out of bounds table access",
                                 ));
                             }
@@ -6789,11 +7961,13 @@ fn execute_function_body(
 
                             // Check bounds in element segment (must happen BEFORE zero-size check per spec)
                             let src_end = (*src_idx as usize).checked_add(*init_size as usize)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap(
-                                    "out of bounds table access"
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
+                                    "out of bounds table access",
                                 ))?;
                             if src_end > effective_elem_len {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "out of bounds table access",
                                 ));
                             }
@@ -6801,11 +7975,13 @@ fn execute_function_body(
                             // Get table and check bounds (must happen BEFORE zero-size check per spec)
                             let table = instance.table(table_idx)?;
                             let dst_end = (*dst_idx as u32).checked_add(*init_size as u32)
-                                .ok_or_else(|| wrt_error::Error::runtime_trap(
-                                    "out of bounds table access"
+                                .ok_or_else(|| wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
+                                    "out of bounds table access",
                                 ))?;
                             if dst_end > table.size() {
-                                return Err(wrt_error::Error::runtime_trap(
+                                return Err(wrt_error::Error::wasm_trap(
+                                    TrapCode::TableOutOfBounds,
                                     "out of bounds table access",
                                 ));
                             }
@@ -6820,7 +7996,8 @@ fn execute_function_body(
                                 for i in 0..*init_size as usize {
                                     let item_idx = *src_idx as usize + i;
                                     let func_idx = elem_segment.items.get(item_idx)
-                                        .map_err(|_| wrt_error::Error::runtime_trap(
+                                        .map_err(|_| wrt_error::Error::wasm_trap(
+                                            TrapCode::TableOutOfBounds,
                                             "table.init: element segment access error"
                                         ))?;
 
@@ -6899,7 +8076,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check alignment - notify requires 4-byte alignment
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -6907,9 +8084,27 @@ fn execute_function_body(
                                 count = count,
                                 "[AtomicNotify] Notify operation"
                             );
-                            // For single-threaded runtime, notify always returns 0 (no waiters)
-                            // A full implementation would use futex-like mechanisms
-                            operand_stack.push(Value::I32(0));
+                            let memory_wrapper = instance.memory(memarg.memory_index as u32)
+                                .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"))?;
+                            let memory = &memory_wrapper.0;
+                            // Notify still traps on an out-of-bounds address
+                            let mut buffer = [0u8; 4];
+                            memory.read(effective_addr, &mut buffer)
+                                .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"))?;
+                            // Nothing can wait on unshared memory, so there is never anyone to wake
+                            #[cfg(any(feature = "std", feature = "alloc"))]
+                            let woken = if memory.ty.shared {
+                                memory.waiters.notify(u64::from(effective_addr), count as u32)
+                            } else {
+                                0
+                            };
+                            // Without a waiter table no thread can be parked on any address
+                            #[cfg(not(any(feature = "std", feature = "alloc")))]
+                            let woken = {
+                                let _ = count;
+                                0u32
+                            };
+                            operand_stack.push(Value::I32(woken as i32));
                         }
                     }
 
@@ -6922,7 +8117,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check alignment - wait32 requires 4-byte alignment
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -6931,28 +8126,34 @@ fn execute_function_body(
                                 timeout = timeout,
                                 "[AtomicWait32] Wait operation"
                             );
-                            // For single-threaded runtime, return 1 (not equal) or 2 (timed out)
-                            // We'll read the current value and return immediately
-                            match instance.memory(memarg.memory_index as u32) {
-                                Ok(memory_wrapper) => {
-                                    let memory = &memory_wrapper.0;
-                                    let mut buffer = [0u8; 4];
-                                    match memory.read(effective_addr, &mut buffer) {
-                                        Ok(()) => {
-                                            let current = i32::from_le_bytes(buffer);
-                                            // Return 1 if value differs, 2 if would timeout (single-threaded)
-                                            let result = if current != expected { 1 } else { 2 };
-                                            operand_stack.push(Value::I32(result));
-                                        }
-                                        Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
-                                        }
-                                    }
-                                }
-                                Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
-                                }
+                            let memory_wrapper = instance.memory(memarg.memory_index as u32)
+                                .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"))?;
+                            let memory = &memory_wrapper.0;
+                            if !memory.ty.shared {
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::ExpectedSharedMemory, "expected shared memory"));
                             }
+                            let result = {
+                                // A negative timeout waits forever
+                                #[cfg(any(feature = "std", feature = "alloc"))]
+                                {
+                                    let timeout = u64::try_from(timeout).ok().map(Duration::from_nanos);
+                                    memory.waiters.wait(u64::from(effective_addr), timeout, || {
+                                        let mut buffer = [0u8; 4];
+                                        memory.read(effective_addr, &mut buffer)
+                                            .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"))?;
+                                        Ok(i32::from_le_bytes(buffer) == expected)
+                                    })
+                                }
+                                // Without a waiter table nothing can wake a parked thread
+                                #[cfg(not(any(feature = "std", feature = "alloc")))]
+                                {
+                                    let _ = (expected, timeout);
+                                    Err(wrt_error::Error::runtime_unsupported_operation(
+                                        "memory.atomic.wait32 requires a waiter table (std or alloc feature)",
+                                    ))
+                                }
+                            }?;
+                            operand_stack.push(Value::I32(result));
                         }
                     }
 
@@ -6965,7 +8166,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check alignment - wait64 requires 8-byte alignment
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             #[cfg(feature = "tracing")]
                             trace!(
@@ -6974,25 +8175,34 @@ fn execute_function_body(
                                 timeout = timeout,
                                 "[AtomicWait64] Wait operation"
                             );
-                            match instance.memory(memarg.memory_index as u32) {
-                                Ok(memory_wrapper) => {
-                                    let memory = &memory_wrapper.0;
-                                    let mut buffer = [0u8; 8];
-                                    match memory.read(effective_addr, &mut buffer) {
-                                        Ok(()) => {
-                                            let current = i64::from_le_bytes(buffer);
-                                            let result = if current != expected { 1 } else { 2 };
-                                            operand_stack.push(Value::I32(result));
-                                        }
-                                        Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
-                                        }
-                                    }
-                                }
-                                Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
-                                }
+                            let memory_wrapper = instance.memory(memarg.memory_index as u32)
+                                .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"))?;
+                            let memory = &memory_wrapper.0;
+                            if !memory.ty.shared {
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::ExpectedSharedMemory, "expected shared memory"));
                             }
+                            let result = {
+                                // A negative timeout waits forever
+                                #[cfg(any(feature = "std", feature = "alloc"))]
+                                {
+                                    let timeout = u64::try_from(timeout).ok().map(Duration::from_nanos);
+                                    memory.waiters.wait(u64::from(effective_addr), timeout, || {
+                                        let mut buffer = [0u8; 8];
+                                        memory.read(effective_addr, &mut buffer)
+                                            .map_err(|_| wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"))?;
+                                        Ok(i64::from_le_bytes(buffer) == expected)
+                                    })
+                                }
+                                // Without a waiter table nothing can wake a parked thread
+                                #[cfg(not(any(feature = "std", feature = "alloc")))]
+                                {
+                                    let _ = (expected, timeout);
+                                    Err(wrt_error::Error::runtime_unsupported_operation(
+                                        "memory.atomic.wait64 requires a waiter table (std or alloc feature)",
+                                    ))
+                                }
+                            }?;
+                            operand_stack.push(Value::I32(result));
                         }
                     }
 
@@ -7013,7 +8223,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check 4-byte alignment for i32
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7031,12 +8241,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7047,7 +8257,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check 8-byte alignment for i64
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7065,12 +8275,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7096,12 +8306,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7112,7 +8322,7 @@ fn execute_function_body(
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             // Check 2-byte alignment for i16
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7130,12 +8340,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7160,12 +8370,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7175,7 +8385,7 @@ fn execute_function_body(
                         if let Some(Value::I32(addr)) = operand_stack.pop() {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7193,12 +8403,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7208,7 +8418,7 @@ fn execute_function_body(
                         if let Some(Value::I32(addr)) = operand_stack.pop() {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7226,12 +8436,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I64(value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7245,7 +8455,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7261,12 +8471,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7276,7 +8486,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7292,12 +8502,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7320,12 +8530,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7335,7 +8545,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7351,12 +8561,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7379,12 +8589,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7394,7 +8604,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7410,12 +8620,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7425,7 +8635,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7441,12 +8651,12 @@ fn execute_function_body(
                                             );
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7460,7 +8670,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7484,17 +8694,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7504,7 +8714,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7526,17 +8736,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7558,17 +8768,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7578,7 +8788,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7593,17 +8803,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7625,17 +8835,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7645,7 +8855,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7660,17 +8870,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7680,7 +8890,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7695,17 +8905,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7719,7 +8929,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7734,17 +8944,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7754,7 +8964,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7769,17 +8979,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7801,17 +9011,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7821,7 +9031,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7836,17 +9046,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7868,17 +9078,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7888,7 +9098,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7903,17 +9113,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7923,7 +9133,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7938,17 +9148,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7962,7 +9172,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -7977,17 +9187,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -7997,7 +9207,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8012,17 +9222,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8044,17 +9254,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8064,7 +9274,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8079,17 +9289,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8111,17 +9321,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8131,7 +9341,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8146,17 +9356,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8166,7 +9376,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8181,17 +9391,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8205,7 +9415,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8220,17 +9430,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8240,7 +9450,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8255,17 +9465,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8287,17 +9497,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8307,7 +9517,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8322,17 +9532,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8354,17 +9564,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8374,7 +9584,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8389,17 +9599,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8409,7 +9619,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8424,17 +9634,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8448,7 +9658,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8463,17 +9673,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8483,7 +9693,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8498,17 +9708,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8530,17 +9740,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8550,7 +9760,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8565,17 +9775,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8597,17 +9807,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8617,7 +9827,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8632,17 +9842,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8652,7 +9862,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8667,17 +9877,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8691,7 +9901,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8705,17 +9915,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8725,7 +9935,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8739,17 +9949,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8770,17 +9980,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8790,7 +10000,7 @@ fn execute_function_body(
                         if let (Some(Value::I32(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8804,17 +10014,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I32(old_value as i32));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8835,17 +10045,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8855,7 +10065,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8869,17 +10079,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8889,7 +10099,7 @@ fn execute_function_body(
                         if let (Some(Value::I64(value)), Some(Value::I32(addr))) = (operand_stack.pop(), operand_stack.pop()) {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8903,17 +10113,17 @@ fn execute_function_body(
                                                     operand_stack.push(Value::I64(old_value as i64));
                                                 }
                                                 Err(_) => {
-                                                    return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                 }
                                             }
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8930,7 +10140,7 @@ fn execute_function_body(
                         {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8944,7 +10154,7 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &replacement.to_le_bytes()) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
@@ -8960,12 +10170,12 @@ fn execute_function_body(
                                             operand_stack.push(Value::I32(old_value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -8977,7 +10187,7 @@ fn execute_function_body(
                         {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 8 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -8990,19 +10200,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &replacement.to_le_bytes()) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I64(old_value));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9024,19 +10234,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &[(replacement as u8)]) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I32(old_value as i32));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9048,7 +10258,7 @@ fn execute_function_body(
                         {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -9061,19 +10271,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &(replacement as u16).to_le_bytes()) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I32(old_value as i32));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9095,19 +10305,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &[(replacement as u8)]) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I64(old_value as i64));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9119,7 +10329,7 @@ fn execute_function_body(
                         {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 2 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -9132,19 +10342,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &(replacement as u16).to_le_bytes()) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I64(old_value as i64));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9156,7 +10366,7 @@ fn execute_function_body(
                         {
                             let effective_addr = (addr as u32).wrapping_add(memarg.offset);
                             if effective_addr % 4 != 0 {
-                                return Err(wrt_error::Error::runtime_trap("unaligned atomic access"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UnalignedAtomic, "unaligned atomic access"));
                             }
                             match instance.memory(memarg.memory_index as u32) {
                                 Ok(memory_wrapper) => {
@@ -9169,19 +10379,19 @@ fn execute_function_body(
                                                 match memory.write_shared(effective_addr, &(replacement as u32).to_le_bytes()) {
                                                     Ok(()) => {}
                                                     Err(_) => {
-                                                        return Err(wrt_error::Error::runtime_trap("Memory write out of bounds"));
+                                                        return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory write out of bounds"));
                                                     }
                                                 }
                                             }
                                             operand_stack.push(Value::I64(old_value as i64));
                                         }
                                         Err(_) => {
-                                            return Err(wrt_error::Error::runtime_trap("Memory read out of bounds"));
+                                            return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory read out of bounds"));
                                         }
                                     }
                                 }
                                 Err(_) => {
-                                    return Err(wrt_error::Error::runtime_trap("Memory access error"));
+                                    return Err(wrt_error::Error::wasm_trap(TrapCode::MemoryOutOfBounds, "Memory access error"));
                                 }
                             }
                         }
@@ -9757,10 +10967,10 @@ fn execute_function_body(
                             if let Ok(field) = s.get_field(field_idx as usize) {
                                 operand_stack.push(field.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("struct.get: field index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "struct.get: field index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("struct.get: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "struct.get: null reference"));
                         }
                     }
 
@@ -9772,10 +10982,10 @@ fn execute_function_body(
                             if let Ok(field) = s.get_field(field_idx as usize) {
                                 operand_stack.push(field.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("struct.get_s: field index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "struct.get_s: field index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("struct.get_s: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "struct.get_s: null reference"));
                         }
                     }
 
@@ -9787,10 +10997,10 @@ fn execute_function_body(
                             if let Ok(field) = s.get_field(field_idx as usize) {
                                 operand_stack.push(field.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("struct.get_u: field index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "struct.get_u: field index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("struct.get_u: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "struct.get_u: null reference"));
                         }
                     }
 
@@ -9802,9 +11012,9 @@ fn execute_function_body(
                             wrt_error::Error::runtime_trap("struct.set: expected value"))?;
                         if let Some(Value::StructRef(Some(mut s))) = operand_stack.pop() {
                             s.set_field(field_idx as usize, value).map_err(|_|
-                                wrt_error::Error::runtime_trap("struct.set: field index out of bounds"))?;
+                                wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "struct.set: field index out of bounds"))?;
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("struct.set: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "struct.set: null reference"));
                         }
                     }
 
@@ -9882,10 +11092,10 @@ fn execute_function_body(
                             if let Ok(elem) = a.get(index) {
                                 operand_stack.push(elem.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("array.get: index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "array.get: index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("array.get: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "array.get: null reference"));
                         }
                     }
 
@@ -9901,10 +11111,10 @@ fn execute_function_body(
                             if let Ok(elem) = a.get(index) {
                                 operand_stack.push(elem.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("array.get_s: index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "array.get_s: index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("array.get_s: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "array.get_s: null reference"));
                         }
                     }
 
@@ -9920,10 +11130,10 @@ fn execute_function_body(
                             if let Ok(elem) = a.get(index) {
                                 operand_stack.push(elem.clone());
                             } else {
-                                return Err(wrt_error::Error::runtime_trap("array.get_u: index out of bounds"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "array.get_u: index out of bounds"));
                             }
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("array.get_u: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "array.get_u: null reference"));
                         }
                     }
 
@@ -9939,9 +11149,9 @@ fn execute_function_body(
                         };
                         if let Some(Value::ArrayRef(Some(mut a))) = operand_stack.pop() {
                             a.set(index, value).map_err(|_|
-                                wrt_error::Error::runtime_trap("array.set: index out of bounds"))?;
+                                wrt_error::Error::wasm_trap(TrapCode::TableOutOfBounds, "array.set: index out of bounds"))?;
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("array.set: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "array.set: null reference"));
                         }
                     }
 
@@ -9952,7 +11162,7 @@ fn execute_function_body(
                         if let Some(Value::ArrayRef(Some(a))) = operand_stack.pop() {
                             operand_stack.push(Value::I32(a.len() as i32));
                         } else {
-                            return Err(wrt_error::Error::runtime_trap("array.len: null reference"));
+                            return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "array.len: null reference"));
                         }
                     }
 
@@ -9978,7 +11188,7 @@ fn execute_function_body(
                                 operand_stack.push(Value::I32(n));
                             }
                             Some(Value::I31Ref(None)) => {
-                                return Err(wrt_error::Error::runtime_trap("i31.get_s: null reference"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "i31.get_s: null reference"));
                             }
                             _ => {
                                 return Err(wrt_error::Error::runtime_trap("i31.get_s: expected i31ref"));
@@ -9996,7 +11206,7 @@ fn execute_function_body(
                                 operand_stack.push(Value::I32(n & 0x7FFFFFFF));
                             }
                             Some(Value::I31Ref(None)) => {
-                                return Err(wrt_error::Error::runtime_trap("i31.get_u: null reference"));
+                                return Err(wrt_error::Error::wasm_trap(TrapCode::UninitializedElement, "i31.get_u: null reference"));
                             }
                             _ => {
                                 return Err(wrt_error::Error::runtime_trap("i31.get_u: expected i31ref"));
@@ -10039,8 +11249,68 @@ fn execute_function_body(
                     }
                 }
 
+                if let Some((_, true)) = fp_operation {
+                    if let Some(result) = operand_stack.last_mut() {
+                        fp_config.adjust_result(result, self.fp_stats.entry(instance_id).or_default());
+                    }
+                }
+
                 // Increment program counter for next iteration
                 pc += 1;
+
+                // A branch back to a loop header is a fuel and epoch checkpoint
+                if pc <= instruction_pc && !self.charge_fuel() {
+                    #[cfg(all(feature = "std", feature = "debugger"))]
+                    {
+                        self.debugger = debugger_opt;
+                    }
+                    return Ok(ExecutionOutcome::OutOfFuel(SuspendedFrame {
+                        instance_id,
+                        func_idx: caller_func_idx,
+                        pc,
+                        locals,
+                        operand_stack,
+                        block_stack,
+                        block_depth,
+                        instruction_count,
+                    }));
+                }
+                if pc <= instruction_pc && self.epoch.reached() {
+                    #[cfg(all(feature = "std", feature = "debugger"))]
+                    {
+                        self.debugger = debugger_opt;
+                    }
+                    self.epoch.interrupt()?;
+                    return Ok(ExecutionOutcome::Yield(SuspendedFrame {
+                        instance_id,
+                        func_idx: caller_func_idx,
+                        pc,
+                        locals,
+                        operand_stack,
+                        block_stack,
+                        block_depth,
+                        instruction_count,
+                    }));
+                }
+            }
+
+            // Function exit is a fuel checkpoint. A frame parked here resumes
+            // past the last instruction and returns its results right away.
+            if !self.charge_fuel() {
+                #[cfg(all(feature = "std", feature = "debugger"))]
+                {
+                    self.debugger = debugger_opt;
+                }
+                return Ok(ExecutionOutcome::OutOfFuel(SuspendedFrame {
+                    instance_id,
+                    func_idx: caller_func_idx,
+                    pc: instructions.len(),
+                    locals,
+                    operand_stack,
+                    block_stack,
+                    block_depth,
+                    instruction_count,
+                }));
             }
 
             // Return values from operand stack matching function signature
@@ -10054,20 +11324,29 @@ fn execute_function_body(
 
             trace!("Function type expects {} results", func_type.results.len());
 
-            let mut results = Vec::new();
-            for (i, result_type) in func_type.results.iter().enumerate() {
-                if let Some(value) = operand_stack.pop() {
-                    #[cfg(feature = "tracing")]
-
-                    trace!("Result {}: {:?}", i, value);
-                    results.insert(0, value);
-                } else {
-                    #[cfg(feature = "tracing")]
-
-                    trace!("Result {}: missing, using default", i);
-                    results.insert(0, Value::I32(0));
-                }
+            // Results are the top `arity` stack values; missing ones default to
+            // I32(0) and come first
+            let arity = func_type.results.len();
+            let available = operand_stack.len().min(arity);
+            let mut results = ResultBuffer::with_arity(arity, &self.store_limits)?;
+            for _ in available..arity {
+                #[cfg(feature = "tracing")]
+                trace!("Result missing, using default");
+                results.push(Value::I32(0))?;
+            }
+            for value in operand_stack.drain(operand_stack.len() - available..) {
+                #[cfg(feature = "tracing")]
+                trace!("Result: {:?}", value);
+                results.push(value)?;
             }
+            let mut values = self.scratch.take_values();
+            results.append_to(&mut values);
+            let results = values;
+
+            // The call's stacks go back to the pool for the next call
+            self.scratch.values.recycle(operand_stack);
+            self.scratch.values.recycle(locals);
+            self.scratch.blocks.recycle(block_stack);
 
             #[cfg(feature = "tracing")]
             trace!("Returning {} results", results.len());
@@ -10204,6 +11483,330 @@ pub fn remaining_fuel(&self) -> Option<u64> {
         Some(self.fuel.load(Ordering::Relaxed))
     }
 
+    /// Add fuel for execution, saturating at `u64::MAX`
+    pub fn add_fuel(&self, amount: u64) {
+        let _ = self.fuel.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |fuel| {
+            Some(fuel.saturating_add(amount))
+        });
+    }
+
+    /// Set the fuel for execution
+    ///
+    /// Each executed instruction consumes one unit. Fuel is checked at
+    /// function entry and on branches back to a loop header; a call that
+    /// runs out is suspended there until it is continued with
+    /// [`Self::resume`] after [`Self::add_fuel`].
+    pub fn set_fuel(&self, amount: u64) {
+        self.fuel.store(amount, Ordering::Relaxed);
+    }
+
+    /// Charge the instructions executed since the last checkpoint
+    ///
+    /// Returns `false`, leaving no fuel, if they cost more than was left.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn charge_fuel(&mut self) -> bool {
+        let used = core::mem::take(&mut self.unmetered);
+        let charged = self
+            .fuel
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |fuel| fuel.checked_sub(used));
+        if charged.is_err() {
+            self.fuel.store(0, Ordering::Relaxed);
+        }
+        if let Some(clock) = &self.fuel_clock {
+            // Running out charges whatever fuel was left
+            clock.advance(match charged {
+                Ok(_) => used,
+                Err(left) => left,
+            });
+        }
+        charged.is_ok()
+    }
+
+    /// Advance `clock` by the fuel charged for executed instructions
+    ///
+    /// Strategies measuring in fuel, such as firewall rate limits, read the
+    /// same clock.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_fuel_clock(&mut self, clock: wrt_intercept::FuelClockRef) {
+        self.fuel_clock = Some(clock);
+    }
+
+    /// Counter the epoch deadline is measured against
+    ///
+    /// The host keeps a clone and advances it, for example from a timer
+    /// thread, while guests run.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn epoch_counter(&self) -> EpochCounter {
+        self.epoch.counter().clone()
+    }
+
+    /// Measure the epoch deadline against a counter shared with other engines
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_epoch_counter(&mut self, counter: EpochCounter) {
+        self.epoch.set_counter(counter);
+    }
+
+    /// Advance the epoch counter by one and return the new epoch
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn increment_epoch(&self) -> u64 {
+        self.epoch.counter().increment()
+    }
+
+    /// Interrupt guests once the epoch counter advanced `ticks` times
+    ///
+    /// The counter is checked on function entry and on loop back-edges, so
+    /// a guest stops within one loop iteration or call after the deadline.
+    /// Straight-line code between checkpoints always runs to the next one.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_epoch_deadline(&mut self, ticks: u64) {
+        self.epoch.set_ticks(ticks);
+    }
+
+    /// Let guests run without an epoch deadline
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn clear_epoch_deadline(&mut self) {
+        self.epoch.clear();
+    }
+
+    /// Set whether guests trap or yield at the epoch deadline
+    ///
+    /// A yielding call makes `execute` return `codes::EXECUTION_YIELDED`;
+    /// the host continues it with [`Self::resume`].
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_epoch_deadline_action(&mut self, action: EpochDeadlineAction) {
+        self.epoch.set_action(action);
+    }
+
+    /// Replace the epoch deadline state, returning the previous one
+    ///
+    /// Used to run a call under a deadline of its own and restore the
+    /// engine's deadline afterwards.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn replace_epoch(&mut self, epoch: EpochDeadline) -> EpochDeadline {
+        core::mem::replace(&mut self.epoch, epoch)
+    }
+
+    /// Epoch deadlines reached by this engine
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn epoch_stats(&self) -> EpochStats {
+        self.epoch.stats()
+    }
+
+    /// Capture the state of an instance for checkpoint and restart
+    ///
+    /// The snapshot holds the instance's memories, globals and tables, the
+    /// fuel left in the engine and, when a call into the instance is
+    /// suspended at the epoch deadline, its frames. Take snapshots between
+    /// calls or while a call is suspended; a running call has no consistent
+    /// state to capture.
+    #[cfg(feature = "std")]
+    pub fn snapshot(&self, instance_id: usize) -> Result<InstanceSnapshot> {
+        let instance = self
+            .instances
+            .get(&instance_id)
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("Instance not found"))?;
+
+        let mut memories = Vec::new();
+        for idx in 0..instance.memory_count()? {
+            let memory = instance.memory(idx as u32)?;
+            let mut data = vec![0u8; memory.0.size_in_bytes()];
+            memory.0.read(0, &mut data)?;
+            memories.push(MemorySnapshot {
+                pages: memory.0.size(),
+                data,
+            });
+        }
+
+        let mut globals = Vec::new();
+        for idx in 0..instance.global_count()? {
+            globals.push(instance.global(idx as u32)?.get()?);
+        }
+
+        let mut tables = Vec::new();
+        for idx in 0..instance.table_count()? {
+            let table = instance.table(idx as u32)?;
+            let mut elements = Vec::with_capacity(table.size() as usize);
+            for elem_idx in 0..table.size() {
+                elements.push(table.get(elem_idx)?);
+            }
+            tables.push(elements);
+        }
+
+        let mut entry_func = None;
+        let mut frames = Vec::new();
+        if let Some(suspended) = self.suspended.as_ref().filter(|call| call.entry.0 == instance_id) {
+            if suspended.frames.iter().any(|frame| frame.instance_id != instance_id) {
+                return Err(wrt_error::Error::runtime_execution_error(
+                    "Suspended call spans several instances",
+                ));
+            }
+            entry_func = Some(suspended.entry.1 as u32);
+            frames = suspended.frames.iter().map(snapshot_frame).collect();
+        }
+
+        Ok(InstanceSnapshot {
+            module_fingerprint: module_fingerprint(instance.module()),
+            fuel: self.fuel.load(Ordering::Relaxed),
+            entry_func,
+            memories,
+            globals,
+            tables,
+            frames,
+        })
+    }
+
+    /// Crash dump of the last trap of a call into `instance_id`
+    ///
+    /// The dump holds the instance's guest frames at the trap, innermost
+    /// first, and summaries of its memories as they are now. The trapping
+    /// frame has its function and instruction only, as the engine drops its
+    /// locals when it traps; its callers have their locals and operand
+    /// stacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance does not exist or no call into it
+    /// trapped
+    #[cfg(feature = "std")]
+    pub fn crash_dump(&self, instance_id: usize) -> Result<CrashDump> {
+        let trap = self
+            .last_trap
+            .as_ref()
+            .filter(|trap| trap.instance_id == instance_id)
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("No trap recorded for instance"))?;
+        let mut dump = self.memory_dump(instance_id, trap.cause.clone())?;
+        dump.frames = trap.frames.clone();
+        Ok(dump)
+    }
+
+    /// Crash dump of a host panic with `message` while `instance_id` ran
+    ///
+    /// The dump holds summaries of the instance's memories and the panic
+    /// information block last recorded with `wrt_panic::record_panic`.
+    /// The guest frames are gone once the panic unwound the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance does not exist
+    #[cfg(feature = "std")]
+    pub fn panic_dump(&self, instance_id: usize, message: &str) -> Result<CrashDump> {
+        let mut dump = self.memory_dump(instance_id, CrashCause::HostPanic { message: message.into() })?;
+        dump.panic_info = wrt_panic::last_panic_info().map(|info| info.to_bytes().to_vec());
+        Ok(dump)
+    }
+
+    /// Crash dump with `cause` and the memory summaries of `instance_id`
+    #[cfg(feature = "std")]
+    fn memory_dump(&self, instance_id: usize, cause: CrashCause) -> Result<CrashDump> {
+        let instance = self
+            .instances
+            .get(&instance_id)
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("Instance not found"))?;
+        let mut dump = CrashDump::new(cause, module_fingerprint(instance.module()));
+        for idx in 0..instance.memory_count()? {
+            let memory = instance.memory(idx as u32)?;
+            let mut data = vec![0u8; memory.0.size_in_bytes()];
+            memory.0.read(0, &mut data)?;
+            dump.memories.push(MemoryRegion::summarize(idx as u32, memory.0.size(), &data));
+        }
+        Ok(dump)
+    }
+
+    /// Restore a snapshot onto an instance of the same module
+    ///
+    /// Memories and tables grow to the sizes in the snapshot, so the target
+    /// instance is usually freshly instantiated. The engine's fuel is set
+    /// to the fuel in the snapshot. If the snapshot holds a suspended call
+    /// it becomes this engine's suspended call, to be continued with
+    /// [`Self::resume`].
+    #[cfg(feature = "std")]
+    pub fn restore(&mut self, instance_id: usize, snapshot: &InstanceSnapshot) -> Result<()> {
+        if self.suspended.is_some() {
+            return Err(wrt_error::Error::runtime_execution_error(
+                "Suspended call must be resumed or discarded first",
+            ));
+        }
+        let instance = self
+            .instances
+            .get(&instance_id)
+            .cloned()
+            .ok_or_else(|| wrt_error::Error::runtime_execution_error("Instance not found"))?;
+        if module_fingerprint(instance.module()) != snapshot.module_fingerprint {
+            return Err(wrt_error::Error::validation_error(
+                "Snapshot was taken from a different module",
+            ));
+        }
+        if instance.memory_count()? != snapshot.memories.len()
+            || instance.global_count()? != snapshot.globals.len()
+            || instance.table_count()? != snapshot.tables.len()
+        {
+            return Err(wrt_error::Error::validation_error(
+                "Snapshot does not match the instance layout",
+            ));
+        }
+
+        for (idx, saved) in snapshot.memories.iter().enumerate() {
+            let memory = instance.memory(idx as u32)?;
+            let pages = memory.0.size();
+            if saved.pages < pages {
+                return Err(wrt_error::Error::validation_error(
+                    "Memory is larger than in the snapshot",
+                ));
+            }
+            if saved.pages > pages {
+                memory.0.grow_shared(saved.pages - pages)?;
+            }
+            memory.0.write_shared(0, &saved.data)?;
+        }
+
+        for (idx, value) in snapshot.globals.iter().enumerate() {
+            let global = instance.global(idx as u32)?;
+            let mut guard = global.inner().write().map_err(|_| {
+                wrt_error::Error::runtime_execution_error("Failed to acquire write lock on global")
+            })?;
+            guard.set_initial_value(value)?;
+        }
+
+        for (idx, elements) in snapshot.tables.iter().enumerate() {
+            let table = instance.table(idx as u32)?;
+            let size = table.size() as usize;
+            if elements.len() < size {
+                return Err(wrt_error::Error::validation_error(
+                    "Table is larger than in the snapshot",
+                ));
+            }
+            if elements.len() > size {
+                let init = match table.element_type() {
+                    wrt_foundation::types::RefType::Funcref => Value::FuncRef(None),
+                    wrt_foundation::types::RefType::Externref => Value::ExternRef(None),
+                };
+                table.grow((elements.len() - size) as u32, init)?;
+            }
+            for (elem_idx, element) in elements.iter().enumerate() {
+                table.set(elem_idx as u32, element.clone())?;
+            }
+        }
+
+        self.fuel.store(snapshot.fuel, Ordering::Relaxed);
+        if let Some(entry_func) = snapshot.entry_func {
+            let frames = snapshot
+                .frames
+                .iter()
+                .map(|frame| restore_frame(instance_id, frame))
+                .collect::<Result<Vec<_>>>()?;
+            if frames.is_empty() {
+                return Err(wrt_error::Error::validation_parse_error(
+                    "Snapshot call has no frames",
+                ));
+            }
+            self.suspended = Some(SuspendedCall {
+                entry: (instance_id, entry_func as usize),
+                frames,
+            });
+        }
+        Ok(())
+    }
+
     /// Get the current instruction pointer
     pub fn get_instruction_pointer(&self) -> Result<u32> {
         Ok(self.instruction_pointer.load(Ordering::Relaxed) as u32)
@@ -10217,11 +11820,6 @@ pub fn execute_function_step(
         params: &[Value],
         max_instructions: u32,
     ) -> Result<crate::stackless::ExecutionResult> {
-        use wrt_foundation::{
-            budget_aware_provider::CrateId,
-            safe_managed_alloc,
-        };
-
         // Validate function exists
         let module = instance.module();
         if func_idx >= module.functions.len() {
@@ -10251,9 +11849,7 @@ pub fn execute_function_step(
 
         // Simulate step execution - in real implementation would execute instructions
         // For now, return completed with default values
-        let provider = safe_managed_alloc!(1024, CrateId::Runtime)?;
-        let mut results = wrt_foundation::bounded::BoundedVec::new(provider)
-            .map_err(|_| wrt_error::Error::runtime_error("Failed to create results vector"))?;
+        let mut results = ResultBuffer::with_arity(func_type.results.len(), &self.store_limits)?;
 
         for result_type in &func_type.results {
             let default_value = match result_type {
@@ -10263,9 +11859,7 @@ pub fn execute_function_step(
                 wrt_foundation::ValueType::F64 => Value::F64(FloatBits64(0.0f64.to_bits())),
                 _ => Value::I32(0),
             };
-            results
-                .push(default_value)
-                .map_err(|_| wrt_error::Error::runtime_error("Failed to push result value"))?;
+            results.push(default_value)?;
         }
 
         // Update instruction pointer
@@ -10300,11 +11894,6 @@ pub fn continue_execution(
         &mut self,
         max_instructions: u32,
     ) -> Result<crate::stackless::ExecutionResult> {
-        use wrt_foundation::{
-            budget_aware_provider::CrateId,
-            safe_managed_alloc,
-        };
-
         // Simulate continued execution
         // In real implementation, would resume from saved state
 
@@ -10323,9 +11912,7 @@ pub fn continue_execution(
             .fetch_sub(fuel_to_consume, Ordering::Relaxed);
 
         // For now, return completed with empty results
-        let provider = safe_managed_alloc!(1024, CrateId::Runtime)?;
-        let results = wrt_foundation::bounded::BoundedVec::new(provider)
-            .map_err(|_| wrt_error::Error::runtime_error("Failed to create results vector"))?;
+        let results = ResultBuffer::new(&self.store_limits);
 
         Ok(crate::stackless::ExecutionResult::Completed(results))
     }
@@ -10339,7 +11926,7 @@ fn count_total_imports(&self, module: &crate::module::Module) -> usize {
         // Count functions that are imports (those with empty body)
         let mut import_count = 0;
         for func in &module.functions {
-            if func.body.is_empty() && func.locals.is_empty() {
+            if func.is_import_stub() {
                 import_count += 1;
             } else {
                 // Once we hit a non-import function, we're done
@@ -10381,7 +11968,7 @@ fn find_import_by_index(&self, module: &crate::module::Module, func_idx: usize)
                         #[cfg(feature = "tracing")]
                         trace!("Found function import {} at overall index {}: {}::{}",
                                func_idx, i, module_name, field_name);
-                        return Ok((module_name.clone(), field_name.clone()));
+                        return Ok((module_name.to_string(), field_name.to_string()));
                     }
                     func_import_count += 1;
                 }
@@ -10882,7 +12469,13 @@ fn call_wasi_function(
 
                     let args: Vec<wrt_foundation::Value> = vec![];
                     let mut dummy_engine: i32 = 0;
-                    match registry.call_host_function(&mut dummy_engine, module_name, field_name, args) {
+                    match registry.call_host_function_for_instance(
+                        &mut dummy_engine,
+                        instance_id,
+                        module_name,
+                        field_name,
+                        args,
+                    ) {
                         Ok(result) => {
                             #[cfg(feature = "tracing")]
                             debug!("WASI: Host function {} returned successfully", field_name);
@@ -0,0 +1,279 @@
+//! Guard values between the frames of the stackless frame store
+//!
+//! The trampoline keeps suspended caller frames in a frame store on the heap
+//! instead of on the Rust stack. [`GuardedFrames`] puts a guard value in
+//! front of every frame and records a fingerprint of the frame's identity
+//! when it is pushed. Popping a frame checks them as far as the
+//! [`VerificationLevel`] asks, so a corrupted frame store ends in a
+//! deterministic safety trap instead of resuming the wrong code:
+//!
+//! | Level       | Checked when a frame is popped                          |
+//! |-------------|---------------------------------------------------------|
+//! | `Off`       | nothing                                                 |
+//! | `Basic`     | guard of the popped frame                               |
+//! | `Sampling`  | guard and fingerprint of every 16th popped frame        |
+//! | `Standard`  | guard and fingerprint of the popped frame               |
+//! | `Full`      | as `Standard`, and the guard of the frame below it      |
+//! | `Redundant` | as `Standard`, and the guards of all remaining frames   |
+
+use alloc::vec::Vec;
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_foundation::verification::VerificationLevel;
+
+/// Pattern guard values are derived from
+const GUARD_PATTERN: u64 = 0x5752_545F_4652_4D47;
+
+/// Odd constant spreading the depth over the guard bits
+const GUARD_STRIDE: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Pops between checks at [`VerificationLevel::Sampling`]
+const SAMPLE_INTERVAL: u64 = 16;
+
+/// FNV-1a offset basis and prime used by [`fingerprint_of`]
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01B3;
+
+/// Frame whose identity must not change while it is in a [`GuardedFrames`]
+pub trait FrameFingerprint {
+    /// Fingerprint of the fields identifying the frame
+    fn fingerprint(&self) -> u64;
+}
+
+/// Fingerprint of a frame's identifying fields
+pub fn fingerprint_of(fields: &[usize]) -> u64 {
+    fields
+        .iter()
+        .fold(FNV_OFFSET, |hash, &field| (hash ^ field as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Guard value in front of the frame at `depth`
+fn guard_at(depth: usize) -> u64 {
+    GUARD_PATTERN ^ (depth as u64).wrapping_mul(GUARD_STRIDE)
+}
+
+/// Safety trap raised for a corrupted frame store
+fn frame_store_corrupted() -> Error {
+    Error::new(
+        ErrorCategory::Safety,
+        codes::RUNTIME_FRAME_INTEGRITY_ERROR,
+        "Frame store corrupted",
+    )
+}
+
+/// Frame with the guard value in front of it
+#[derive(Debug)]
+struct GuardedSlot<T> {
+    guard:       u64,
+    frame:       T,
+    fingerprint: u64,
+}
+
+/// Stack of suspended frames separated by guard values
+#[derive(Debug)]
+pub struct GuardedFrames<T> {
+    slots: Vec<GuardedSlot<T>>,
+    level: VerificationLevel,
+    pops:  u64,
+}
+
+impl<T: FrameFingerprint> GuardedFrames<T> {
+    /// Create an empty frame store checked at `level`
+    pub fn new(level: VerificationLevel) -> Self {
+        Self { slots: Vec::new(), level, pops: 0 }
+    }
+
+    /// Create a frame store holding `frames`, innermost last
+    pub fn from_frames(frames: Vec<T>, level: VerificationLevel) -> Self {
+        let mut store = Self::new(level);
+        store.slots.reserve(frames.len());
+        for frame in frames {
+            store.push(frame);
+        }
+        store
+    }
+
+    /// Verification level the store is checked at
+    pub fn level(&self) -> VerificationLevel {
+        self.level
+    }
+
+    /// Number of frames in the store
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether the store holds no frames
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Frames in the store without checking them, innermost last
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.slots.iter().map(|slot| &slot.frame)
+    }
+
+    /// Push `frame` behind a new guard value
+    pub fn push(&mut self, frame: T) {
+        let fingerprint = frame.fingerprint();
+        self.slots.push(GuardedSlot { guard: guard_at(self.slots.len()), frame, fingerprint });
+    }
+
+    /// Pop the innermost frame, checking it as the verification level asks
+    ///
+    /// # Errors
+    ///
+    /// Returns a safety trap if a checked guard value or fingerprint does not
+    /// match. The frame is removed from the store either way.
+    pub fn pop(&mut self) -> Result<Option<T>> {
+        let Some(slot) = self.slots.pop() else {
+            return Ok(None);
+        };
+        let depth = self.slots.len();
+        self.pops = self.pops.wrapping_add(1);
+
+        let (check_guard, check_fingerprint) = match self.level {
+            VerificationLevel::Off => (false, false),
+            VerificationLevel::Basic => (true, false),
+            VerificationLevel::Sampling => {
+                let sampled = self.pops % SAMPLE_INTERVAL == 0;
+                (sampled, sampled)
+            },
+            VerificationLevel::Standard | VerificationLevel::Full | VerificationLevel::Redundant => {
+                (true, true)
+            },
+        };
+        if check_guard && slot.guard != guard_at(depth) {
+            return Err(frame_store_corrupted());
+        }
+        if check_fingerprint && slot.fingerprint != slot.frame.fingerprint() {
+            return Err(frame_store_corrupted());
+        }
+        match self.level {
+            VerificationLevel::Full => {
+                if let Some(below) = self.slots.last() {
+                    if below.guard != guard_at(depth - 1) {
+                        return Err(frame_store_corrupted());
+                    }
+                }
+            },
+            VerificationLevel::Redundant => self.verify_guards()?,
+            _ => {},
+        }
+        Ok(Some(slot.frame))
+    }
+
+    /// Check the guard values and fingerprints of all frames in the store
+    ///
+    /// # Errors
+    ///
+    /// Returns a safety trap if a guard value or fingerprint does not match
+    pub fn verify_all(&self) -> Result<()> {
+        self.verify_guards()?;
+        if self.slots.iter().any(|slot| slot.fingerprint != slot.frame.fingerprint()) {
+            return Err(frame_store_corrupted());
+        }
+        Ok(())
+    }
+
+    /// Take the frames out of the store, innermost last
+    ///
+    /// Unless verification is off, all frames are checked first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a safety trap if a guard value or fingerprint does not match
+    pub fn into_frames(self) -> Result<Vec<T>> {
+        if self.level != VerificationLevel::Off {
+            self.verify_all()?;
+        }
+        Ok(self.slots.into_iter().map(|slot| slot.frame).collect())
+    }
+
+    fn verify_guards(&self) -> Result<()> {
+        if self.slots.iter().enumerate().any(|(depth, slot)| slot.guard != guard_at(depth)) {
+            return Err(frame_store_corrupted());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct TestFrame {
+        func_idx: usize,
+        pc:       usize,
+    }
+
+    impl FrameFingerprint for TestFrame {
+        fn fingerprint(&self) -> u64 {
+            fingerprint_of(&[self.func_idx, self.pc])
+        }
+    }
+
+    fn store(level: VerificationLevel) -> GuardedFrames<TestFrame> {
+        let frames = (0..4).map(|func_idx| TestFrame { func_idx, pc: func_idx * 10 }).collect();
+        GuardedFrames::from_frames(frames, level)
+    }
+
+    fn is_frame_trap(error: &Error) -> bool {
+        error.code == codes::RUNTIME_FRAME_INTEGRITY_ERROR
+            && error.category == ErrorCategory::Safety
+    }
+
+    #[test]
+    fn test_intact_frames_pop_in_order() -> Result<()> {
+        let mut frames = store(VerificationLevel::Redundant);
+        frames.verify_all()?;
+        for func_idx in (0..4).rev() {
+            assert_eq!(frames.pop()?, Some(TestFrame { func_idx, pc: func_idx * 10 }));
+        }
+        assert_eq!(frames.pop()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_guard_traps() {
+        let mut frames = store(VerificationLevel::Basic);
+        frames.slots[3].guard ^= 1;
+        assert!(is_frame_trap(&frames.pop().unwrap_err()));
+
+        // Full checks the guard of the frame below the popped one
+        let mut frames = store(VerificationLevel::Full);
+        frames.slots[2].guard = 0;
+        assert!(is_frame_trap(&frames.pop().unwrap_err()));
+
+        // Redundant checks every remaining guard
+        let mut frames = store(VerificationLevel::Redundant);
+        frames.slots[0].guard = 0;
+        assert!(is_frame_trap(&frames.pop().unwrap_err()));
+
+        let mut frames = store(VerificationLevel::Off);
+        frames.slots[3].guard = 0;
+        assert!(frames.pop().is_ok());
+    }
+
+    #[test]
+    fn test_changed_frame_traps() {
+        let mut frames = store(VerificationLevel::Standard);
+        frames.slots[3].frame.pc += 1;
+        assert!(is_frame_trap(&frames.pop().unwrap_err()));
+
+        // Basic only checks guard values
+        let mut frames = store(VerificationLevel::Basic);
+        frames.slots[3].frame.pc += 1;
+        assert!(frames.pop().is_ok());
+
+        let mut frames = store(VerificationLevel::Standard);
+        frames.slots[1].frame.func_idx = 7;
+        assert!(is_frame_trap(&frames.into_frames().unwrap_err()));
+    }
+}
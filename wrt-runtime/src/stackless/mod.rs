@@ -15,9 +15,13 @@
 type String =
     wrt_foundation::bounded::BoundedString<256>;
 
+#[cfg(feature = "std")]
+pub mod canon;
 pub mod engine;
 pub mod extensions;
 pub mod frame;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod frame_guard;
 
 #[cfg(feature = "std")]
 pub mod tail_call;
@@ -32,7 +36,7 @@
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExecutionResult {
     /// Execution completed successfully with return values.
-    Completed(wrt_foundation::bounded::BoundedVec<wrt_foundation::Value, 16, wrt_foundation::safe_memory::NoStdProvider<1024>>),
+    Completed(crate::result_buffer::ResultBuffer),
     /// Execution yielded and can be resumed.
     Yielded(YieldInfo),
     /// Execution is waiting for an external resource.
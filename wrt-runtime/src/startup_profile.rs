@@ -0,0 +1,348 @@
+//! Startup-time profiling
+//!
+//! With profiling enabled on a
+//! [`CapabilityAwareEngine`](crate::engine::CapabilityAwareEngine), loading
+//! and instantiating a module records how long each phase of the
+//! load → validate → instantiate → start pipeline took and how many
+//! allocations it made. The resulting [`StartupReport`] of each module shows
+//! where a cold start goes, and so which features are worth disabling to
+//! meet a startup budget.
+//!
+//! Durations are measured with the monotonic clock of `std` and are not
+//! available without it. Allocation counts cover the allocations made
+//! through the capability memory system, as seen by the global
+//! [safety monitor](wrt_foundation::safety_monitor); heap allocations of
+//! `std` collections are not included.
+
+use core::{
+    fmt,
+    time::Duration,
+};
+
+use wrt_foundation::safety_monitor::with_safety_monitor;
+
+/// Number of [`StartupPhase`]s
+pub const PHASE_COUNT: usize = 6;
+
+/// Phase of loading and instantiating a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StartupPhase {
+    /// Decoding and validating the binary
+    Decode,
+    /// Building the runtime module from the decoded module
+    Convert,
+    /// Creating the instance with its globals, memories and tables
+    Instantiate,
+    /// Binding imported tables, memories, globals and functions
+    LinkImports,
+    /// Copying data and element segments into memories and tables
+    InitSegments,
+    /// Running the start function
+    Start,
+}
+
+impl StartupPhase {
+    /// All phases in pipeline order
+    pub const ALL: [Self; PHASE_COUNT] = [
+        Self::Decode,
+        Self::Convert,
+        Self::Instantiate,
+        Self::LinkImports,
+        Self::InitSegments,
+        Self::Start,
+    ];
+
+    /// Lower-case name used in reports
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::Convert => "convert",
+            Self::Instantiate => "instantiate",
+            Self::LinkImports => "link-imports",
+            Self::InitSegments => "init-segments",
+            Self::Start => "start",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Measurements of one phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PhaseProfile {
+    /// Wall time spent in the phase, `None` without a clock
+    pub duration:        Option<Duration>,
+    /// Number of allocations made during the phase
+    pub allocations:     u64,
+    /// Net growth of allocated memory during the phase, in bytes
+    pub allocated_bytes: usize,
+}
+
+impl PhaseProfile {
+    /// Add the measurements of another run of the same phase
+    fn accumulate(&mut self, other: PhaseProfile) {
+        self.duration = match (self.duration, other.duration) {
+            (Some(a), Some(b)) => Some(a.saturating_add(b)),
+            _ => None,
+        };
+        self.allocations = self.allocations.saturating_add(other.allocations);
+        self.allocated_bytes = self.allocated_bytes.saturating_add(other.allocated_bytes);
+    }
+}
+
+/// Startup profile of one module
+///
+/// Loading fills the decode and convert phases, instantiating the others.
+/// Instantiating the same module again replaces the instantiation phases
+/// with those of the latest instance. A phase the module did not go through,
+/// such as the start function of a module without one, is not recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StartupReport {
+    phases: [Option<PhaseProfile>; PHASE_COUNT],
+}
+
+impl StartupReport {
+    /// Create an empty report
+    pub const fn new() -> Self {
+        Self {
+            phases: [None; PHASE_COUNT],
+        }
+    }
+
+    /// Record the measurements of `phase`, replacing earlier ones
+    pub fn record(&mut self, phase: StartupPhase, profile: PhaseProfile) {
+        self.phases[phase.index()] = Some(profile);
+    }
+
+    /// Measurements of `phase`, if recorded
+    pub fn phase(&self, phase: StartupPhase) -> Option<&PhaseProfile> {
+        self.phases[phase.index()].as_ref()
+    }
+
+    /// Recorded phases in pipeline order
+    pub fn phases(&self) -> impl Iterator<Item = (StartupPhase, &PhaseProfile)> {
+        StartupPhase::ALL
+            .into_iter()
+            .filter_map(|phase| self.phase(phase).map(|profile| (phase, profile)))
+    }
+
+    /// Total time of the recorded phases, `None` without a clock
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.phases().try_fold(Duration::ZERO, |total, (_, profile)| {
+            profile.duration.map(|duration| total.saturating_add(duration))
+        })
+    }
+
+    /// Total number of allocations of the recorded phases
+    pub fn total_allocations(&self) -> u64 {
+        self.phases().map(|(_, profile)| profile.allocations).sum()
+    }
+
+    /// Phase that took the longest, `None` if nothing was timed
+    pub fn slowest_phase(&self) -> Option<StartupPhase> {
+        self.phases()
+            .filter_map(|(phase, profile)| profile.duration.map(|duration| (phase, duration)))
+            .max_by_key(|&(_, duration)| duration)
+            .map(|(phase, _)| phase)
+    }
+}
+
+impl fmt::Display for StartupReport {
+    /// One line per recorded phase followed by the total, e.g.
+    /// `decode          1.250 ms      12 allocs      4096 B`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (phase, profile) in self.phases() {
+            write_line(f, phase.as_str(), profile.duration, profile.allocations)?;
+            writeln!(f, " {:>9} B", profile.allocated_bytes)?;
+        }
+        write_line(f, "total", self.total_duration(), self.total_allocations())?;
+        writeln!(f)
+    }
+}
+
+fn write_line(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    duration: Option<Duration>,
+    allocations: u64,
+) -> fmt::Result {
+    write!(f, "{:<14}", name)?;
+    match duration {
+        Some(duration) => {
+            let micros = duration.as_micros();
+            write!(f, " {:>6}.{:03} ms", micros / 1000, micros % 1000)?;
+        },
+        None => write!(f, " {:>13}", "-")?,
+    }
+    write!(f, " {:>7} allocs", allocations)
+}
+
+/// Allocation counters of the safety monitor
+fn allocation_counters() -> (u64, usize) {
+    with_safety_monitor(|monitor| {
+        let report = monitor.get_safety_report();
+        (report.total_allocations, report.current_memory_bytes)
+    })
+}
+
+/// Measurement of a phase in progress
+#[derive(Debug)]
+struct PhaseTimer {
+    phase:       StartupPhase,
+    #[cfg(feature = "std")]
+    started:     std::time::Instant,
+    allocations: u64,
+    bytes:       usize,
+}
+
+impl PhaseTimer {
+    fn start(phase: StartupPhase) -> Self {
+        let (allocations, bytes) = allocation_counters();
+        Self {
+            phase,
+            #[cfg(feature = "std")]
+            started: std::time::Instant::now(),
+            allocations,
+            bytes,
+        }
+    }
+
+    fn finish(self) -> PhaseProfile {
+        #[cfg(feature = "std")]
+        let duration = Some(self.started.elapsed());
+        #[cfg(not(feature = "std"))]
+        let duration = None;
+
+        let (allocations, bytes) = allocation_counters();
+        PhaseProfile {
+            duration,
+            allocations: allocations.saturating_sub(self.allocations),
+            allocated_bytes: bytes.saturating_sub(self.bytes),
+        }
+    }
+}
+
+/// Records consecutive phases into a report
+///
+/// A phase entered several times is recorded as the sum of its runs. The
+/// phases measured replace those of the same name in the report the
+/// profiler started from. A disabled profiler records nothing and never
+/// reads the clock or the safety monitor.
+#[derive(Debug)]
+pub(crate) struct StartupProfiler {
+    report:   Option<StartupReport>,
+    measured: StartupReport,
+    current:  Option<PhaseTimer>,
+}
+
+impl StartupProfiler {
+    /// Profile into `report`, or do nothing if `None`
+    pub(crate) fn new(report: Option<StartupReport>) -> Self {
+        Self {
+            report,
+            measured: StartupReport::new(),
+            current: None,
+        }
+    }
+
+    /// Start `phase`, ending the phase in progress
+    pub(crate) fn phase(&mut self, phase: StartupPhase) {
+        if self.report.is_some() {
+            self.end_phase();
+            self.current = Some(PhaseTimer::start(phase));
+        }
+    }
+
+    /// End the phase in progress, if any
+    pub(crate) fn end_phase(&mut self) {
+        if let Some(timer) = self.current.take() {
+            let phase = timer.phase;
+            let profile = timer.finish();
+            match self.measured.phases[phase.index()].as_mut() {
+                Some(total) => total.accumulate(profile),
+                None => self.measured.record(phase, profile),
+            }
+        }
+    }
+
+    /// End the phase in progress and return the updated report
+    pub(crate) fn finish(mut self) -> Option<StartupReport> {
+        self.end_phase();
+        let mut report = self.report?;
+        for (phase, profile) in self.measured.phases() {
+            report.record(phase, *profile);
+        }
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_totals_and_rendering() {
+        let mut report = StartupReport::new();
+        assert_eq!(report.total_duration(), Some(Duration::ZERO));
+        assert_eq!(report.slowest_phase(), None);
+
+        report.record(
+            StartupPhase::Decode,
+            PhaseProfile {
+                duration:        Some(Duration::from_micros(1250)),
+                allocations:     12,
+                allocated_bytes: 4096,
+            },
+        );
+        report.record(
+            StartupPhase::Start,
+            PhaseProfile {
+                duration:        Some(Duration::from_micros(300)),
+                allocations:     1,
+                allocated_bytes: 0,
+            },
+        );
+        assert_eq!(report.phases().count(), 2);
+        assert!(report.phase(StartupPhase::Convert).is_none());
+        assert_eq!(report.total_duration(), Some(Duration::from_micros(1550)));
+        assert_eq!(report.total_allocations(), 13);
+        assert_eq!(report.slowest_phase(), Some(StartupPhase::Decode));
+
+        let text = report.to_string();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("decode              1.250 ms      12 allocs      4096 B")
+        );
+        assert!(lines.next().is_some_and(|line| line.starts_with("start ")));
+        assert!(lines.next().is_some_and(|line| line.contains("1.550 ms")));
+
+        report.record(StartupPhase::Convert, PhaseProfile::default());
+        assert_eq!(report.total_duration(), None);
+    }
+
+    #[test]
+    fn test_disabled_profiler_records_nothing() {
+        let mut profiler = StartupProfiler::new(None);
+        profiler.phase(StartupPhase::Decode);
+        assert!(profiler.finish().is_none());
+
+        let mut profiler = StartupProfiler::new(Some(StartupReport::new()));
+        profiler.phase(StartupPhase::Decode);
+        profiler.phase(StartupPhase::Convert);
+        let report = profiler.finish().unwrap();
+        let phases: Vec<_> = report.phases().map(|(phase, _)| phase).collect();
+        assert_eq!(phases, vec![StartupPhase::Decode, StartupPhase::Convert]);
+
+        // Re-entered phases add up, earlier phases of the report are kept
+        let mut profiler = StartupProfiler::new(Some(report));
+        profiler.phase(StartupPhase::LinkImports);
+        profiler.phase(StartupPhase::InitSegments);
+        profiler.phase(StartupPhase::LinkImports);
+        let report = profiler.finish().unwrap();
+        assert_eq!(report.phases().count(), 4);
+        assert!(report.phase(StartupPhase::Decode).is_some());
+    }
+}
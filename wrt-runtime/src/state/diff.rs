@@ -0,0 +1,412 @@
+//! Byte-exact differences between snapshots
+//!
+//! [`diff_memory`] compares two linear memory images and reports the ranges
+//! of bytes that differ; [`InstanceSnapshot::diff`] does the same for every
+//! part of two instance snapshots. Both render as a readable report with hex
+//! dumps of the changed bytes and the bytes around them, so a failing
+//! snapshot comparison in a test shows where two executions diverged:
+//!
+//! ```text
+//! memory 0: 1 range changed
+//!   0x00000012..0x00000014 (2 bytes)
+//!     - 00000000  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+//!     + 00000000  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+//!     - 00000010  01 02 03 04 00 00 00 00 00 00 00 00 00 00 00 00
+//!     + 00000010  01 02 ff ff 00 00 00 00 00 00 00 00 00 00 00 00
+//! ```
+
+use core::fmt;
+
+use super::snapshot::{
+    FrameSnapshot,
+    InstanceSnapshot,
+    MemorySnapshot,
+};
+use crate::prelude::*;
+
+/// Bytes shown before and after a changed range by default
+pub const DEFAULT_CONTEXT: usize = 8;
+
+/// Changed bytes shown per range at most; longer ranges are elided
+pub const MAX_DUMP_BYTES: usize = 256;
+
+/// Bytes per hex dump row
+const ROW: usize = 16;
+
+/// Range of bytes that differs between two memory images
+///
+/// Bytes present in only one of the images belong to a changed range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedRange {
+    /// Offset of the first differing byte
+    pub start: usize,
+    /// Offset after the last differing byte
+    pub end:   usize,
+}
+
+impl ChangedRange {
+    /// Number of differing bytes
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Whether the range is empty
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Differences between two memory images
+#[derive(Debug, Clone)]
+pub struct MemoryDiff<'a> {
+    old:     &'a [u8],
+    new:     &'a [u8],
+    ranges:  Vec<ChangedRange>,
+    context: usize,
+}
+
+/// Compare two memory images byte by byte
+///
+/// Ranges separated by fewer unchanged bytes than twice the context are
+/// merged, so that every reported range has its context to itself.
+pub fn diff_memory<'a>(old: &'a [u8], new: &'a [u8]) -> MemoryDiff<'a> {
+    let mut diff = MemoryDiff {
+        old,
+        new,
+        ranges: Vec::new(),
+        context: DEFAULT_CONTEXT,
+    };
+    diff.collect_ranges();
+    diff
+}
+
+impl<'a> MemoryDiff<'a> {
+    /// Show `context` unchanged bytes around each range
+    pub fn with_context(mut self, context: usize) -> Self {
+        self.context = context;
+        self.collect_ranges();
+        self
+    }
+
+    /// Changed ranges in ascending order
+    pub fn ranges(&self) -> &[ChangedRange] {
+        &self.ranges
+    }
+
+    /// Whether the images are identical
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Total number of differing bytes
+    pub fn changed_bytes(&self) -> usize {
+        let common = self.old.len().min(self.new.len());
+        let differing = self.old.iter().zip(self.new).filter(|(old, new)| old != new).count();
+        differing + self.old.len().max(self.new.len()) - common
+    }
+
+    fn collect_ranges(&mut self) {
+        let len = self.old.len().max(self.new.len());
+        let differs = |offset: usize| self.old.get(offset) != self.new.get(offset);
+        let merge_gap = self.context.saturating_mul(2).max(1);
+
+        let mut ranges: Vec<ChangedRange> = Vec::new();
+        let mut offset = 0;
+        while offset < len {
+            // Compare in chunks first; memories are mostly unchanged
+            let chunk_end = (offset + 64).min(len);
+            if chunk_end <= self.old.len().min(self.new.len())
+                && self.old[offset..chunk_end] == self.new[offset..chunk_end]
+            {
+                offset = chunk_end;
+                continue;
+            }
+            if !differs(offset) {
+                offset += 1;
+                continue;
+            }
+            let start = offset;
+            while offset < len && differs(offset) {
+                offset += 1;
+            }
+            match ranges.last_mut() {
+                Some(last) if start - last.end < merge_gap => last.end = offset,
+                _ => ranges.push(ChangedRange { start, end: offset }),
+            }
+        }
+        self.ranges = ranges;
+    }
+
+    fn fmt_range(&self, f: &mut fmt::Formatter<'_>, range: &ChangedRange) -> fmt::Result {
+        writeln!(f, "  {:#010x}..{:#010x} ({} bytes)", range.start, range.end, range.len())?;
+        let end = range.end.min(range.start + MAX_DUMP_BYTES);
+        let first_row = range.start.saturating_sub(self.context) / ROW * ROW;
+        let last = end + self.context;
+        let mut row = first_row;
+        while row < last && (row < self.old.len() || row < self.new.len()) {
+            dump_row(f, '-', self.old, row)?;
+            dump_row(f, '+', self.new, row)?;
+            row += ROW;
+        }
+        if end < range.end {
+            writeln!(f, "    ... {} more changed bytes", range.end - end)?;
+        }
+        Ok(())
+    }
+}
+
+fn dump_row(f: &mut fmt::Formatter<'_>, marker: char, data: &[u8], row: usize) -> fmt::Result {
+    write!(f, "    {marker} {row:08x} ")?;
+    for offset in row..row + ROW {
+        match data.get(offset) {
+            Some(byte) => write!(f, " {byte:02x}")?,
+            None => write!(f, " --")?,
+        }
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for MemoryDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.old.len() != self.new.len() {
+            writeln!(f, "  size {} -> {} bytes", self.old.len(), self.new.len())?;
+        }
+        for range in &self.ranges {
+            self.fmt_range(f, range)?;
+        }
+        Ok(())
+    }
+}
+
+/// Difference in one part of an instance snapshot
+#[derive(Debug, Clone)]
+pub enum SnapshotChange<'a> {
+    /// Snapshots were taken from different modules
+    ModuleFingerprint(u32, u32),
+    /// Fuel left differs
+    Fuel(u64, u64),
+    /// Suspended call differs
+    EntryFunc(Option<u32>, Option<u32>),
+    /// Number of memories, globals, tables or frames differs
+    Count(&'static str, usize, usize),
+    /// Size of a memory in pages differs
+    MemoryPages(usize, u32, u32),
+    /// Contents of a memory differ
+    Memory(usize, MemoryDiff<'a>),
+    /// Value of a global differs
+    Global(usize, &'a Value, &'a Value),
+    /// Element of a table differs
+    TableElement(usize, usize, Option<&'a Value>, Option<&'a Value>),
+    /// Frame of the suspended call differs, in the named field
+    Frame(usize, &'static str),
+}
+
+impl fmt::Display for SnapshotChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ModuleFingerprint(old, new) => {
+                writeln!(f, "module fingerprint: {old:#010x} -> {new:#010x}")
+            },
+            Self::Fuel(old, new) => writeln!(f, "fuel: {old} -> {new}"),
+            Self::EntryFunc(old, new) => writeln!(f, "entry function: {old:?} -> {new:?}"),
+            Self::Count(what, old, new) => writeln!(f, "{what}: {old} -> {new}"),
+            Self::MemoryPages(index, old, new) => {
+                writeln!(f, "memory {index}: {old} -> {new} pages")
+            },
+            Self::Memory(index, diff) => {
+                let count = diff.ranges().len();
+                let plural = if count == 1 { "" } else { "s" };
+                writeln!(f, "memory {index}: {count} range{plural} changed")?;
+                write!(f, "{diff}")
+            },
+            Self::Global(index, old, new) => writeln!(f, "global {index}: {old:?} -> {new:?}"),
+            Self::TableElement(table, index, old, new) => {
+                writeln!(f, "table {table}[{index}]: {old:?} -> {new:?}")
+            },
+            Self::Frame(index, field) => writeln!(f, "frame {index}: {field} differs"),
+        }
+    }
+}
+
+/// Differences between two instance snapshots
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff<'a> {
+    /// Changes in snapshot order: header, memories, globals, tables, frames
+    pub changes: Vec<SnapshotChange<'a>>,
+}
+
+impl SnapshotDiff<'_> {
+    /// Whether the snapshots are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "snapshots are identical");
+        }
+        self.changes.iter().try_for_each(|change| write!(f, "{change}"))
+    }
+}
+
+impl InstanceSnapshot {
+    /// Compare the snapshot with a later one, e.g. of the same instance on
+    /// another engine
+    pub fn diff<'a>(&'a self, other: &'a InstanceSnapshot) -> SnapshotDiff<'a> {
+        let mut changes = Vec::new();
+        if self.module_fingerprint != other.module_fingerprint {
+            changes.push(SnapshotChange::ModuleFingerprint(
+                self.module_fingerprint,
+                other.module_fingerprint,
+            ));
+        }
+        if self.fuel != other.fuel {
+            changes.push(SnapshotChange::Fuel(self.fuel, other.fuel));
+        }
+        if self.entry_func != other.entry_func {
+            changes.push(SnapshotChange::EntryFunc(self.entry_func, other.entry_func));
+        }
+
+        count(&mut changes, "memories", self.memories.len(), other.memories.len());
+        for (index, (old, new)) in self.memories.iter().zip(&other.memories).enumerate() {
+            diff_memory_snapshot(&mut changes, index, old, new);
+        }
+
+        count(&mut changes, "globals", self.globals.len(), other.globals.len());
+        for (index, (old, new)) in self.globals.iter().zip(&other.globals).enumerate() {
+            if old != new {
+                changes.push(SnapshotChange::Global(index, old, new));
+            }
+        }
+
+        count(&mut changes, "tables", self.tables.len(), other.tables.len());
+        for (table, (old, new)) in self.tables.iter().zip(&other.tables).enumerate() {
+            for index in 0..old.len().max(new.len()) {
+                let old = old.get(index).and_then(Option::as_ref);
+                let new = new.get(index).and_then(Option::as_ref);
+                if old != new {
+                    changes.push(SnapshotChange::TableElement(table, index, old, new));
+                }
+            }
+        }
+
+        count(&mut changes, "frames", self.frames.len(), other.frames.len());
+        for (index, (old, new)) in self.frames.iter().zip(&other.frames).enumerate() {
+            if let Some(field) = frame_difference(old, new) {
+                changes.push(SnapshotChange::Frame(index, field));
+            }
+        }
+
+        SnapshotDiff { changes }
+    }
+}
+
+fn count(changes: &mut Vec<SnapshotChange<'_>>, what: &'static str, old: usize, new: usize) {
+    if old != new {
+        changes.push(SnapshotChange::Count(what, old, new));
+    }
+}
+
+fn diff_memory_snapshot<'a>(
+    changes: &mut Vec<SnapshotChange<'a>>,
+    index: usize,
+    old: &'a MemorySnapshot,
+    new: &'a MemorySnapshot,
+) {
+    if old.pages != new.pages {
+        changes.push(SnapshotChange::MemoryPages(index, old.pages, new.pages));
+    }
+    let diff = diff_memory(&old.data, &new.data);
+    if !diff.is_empty() {
+        changes.push(SnapshotChange::Memory(index, diff));
+    }
+}
+
+/// First field two frames differ in
+fn frame_difference(old: &FrameSnapshot, new: &FrameSnapshot) -> Option<&'static str> {
+    if old.func_idx != new.func_idx {
+        Some("function")
+    } else if old.pc != new.pc {
+        Some("pc")
+    } else if old.locals != new.locals {
+        Some("locals")
+    } else if old.operand_stack != new.operand_stack {
+        Some("operand stack")
+    } else if old.blocks != new.blocks || old.block_depth != new.block_depth {
+        Some("blocks")
+    } else if old.instruction_count != new.instruction_count {
+        Some("instruction count")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_ranges() {
+        let old = vec![0u8; 256];
+        let mut new = old.clone();
+        new[16] = 1;
+        new[18] = 2;
+        new[200..204].copy_from_slice(&[9; 4]);
+
+        let diff = diff_memory(&old, &new);
+        assert_eq!(diff.ranges(), &[
+            ChangedRange { start: 16, end: 19 },
+            ChangedRange {
+                start: 200,
+                end:   204,
+            },
+        ]);
+        assert_eq!(diff.changed_bytes(), 6);
+
+        let exact = diff_memory(&old, &new).with_context(0);
+        assert_eq!(exact.ranges().len(), 3);
+
+        let report = diff.to_string();
+        assert!(report.contains("0x00000010..0x00000013 (3 bytes)"));
+        assert!(report.contains("+ 00000010  01 00 02 00"));
+
+        new.extend_from_slice(&[7, 7]);
+        let grown = diff_memory(&old, &new);
+        assert_eq!(grown.ranges().last(), Some(&ChangedRange {
+            start: 256,
+            end:   258,
+        }));
+        assert!(grown.to_string().contains("size 256 -> 258 bytes"));
+        assert!(diff_memory(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let snapshot = InstanceSnapshot {
+            module_fingerprint: 1,
+            fuel:               100,
+            entry_func:         None,
+            memories:           vec![MemorySnapshot {
+                pages: 1,
+                data:  vec![0; 65536],
+            }],
+            globals:            vec![Value::I32(1)],
+            tables:             vec![vec![None]],
+            frames:             Vec::new(),
+        };
+        assert!(snapshot.diff(&snapshot.clone()).is_empty());
+
+        let mut other = snapshot.clone();
+        other.fuel = 90;
+        other.memories[0].data[1024] = 0xff;
+        other.globals[0] = Value::I32(2);
+        let diff = snapshot.diff(&other);
+        assert_eq!(diff.changes.len(), 3);
+        let report = diff.to_string();
+        assert!(report.contains("fuel: 100 -> 90"));
+        assert!(report.contains("memory 0: 1 range changed"));
+        assert!(report.contains("global 0: I32(1) -> I32(2)"));
+    }
+}
@@ -3,7 +3,11 @@
 //! This module provides utilities for managing and serializing WebAssembly
 //! runtime state including stack frames, globals, and memory.
 
+#[cfg(feature = "std")]
+pub mod diff;
 pub mod serialization;
+#[cfg(feature = "std")]
+pub mod snapshot;
 
 // Re-export functions conditionally
 #[cfg(any(feature = "std", feature = "alloc"))]
@@ -18,3 +22,19 @@
     StateSection,
     STATE_SECTION_PREFIX,
 };
+#[cfg(feature = "std")]
+pub use diff::{
+    diff_memory,
+    ChangedRange,
+    MemoryDiff,
+    SnapshotChange,
+    SnapshotDiff,
+};
+#[cfg(feature = "std")]
+pub use snapshot::{
+    module_fingerprint,
+    BlockSnapshot,
+    FrameSnapshot,
+    InstanceSnapshot,
+    MemorySnapshot,
+};
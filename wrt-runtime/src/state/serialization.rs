@@ -59,6 +59,8 @@ pub enum StateSection {
     Globals = 3,
     /// Memory section
     Memory  = 4,
+    /// Table elements section
+    Tables  = 5,
 }
 
 impl StateSection {
@@ -71,6 +73,7 @@ pub fn name(&self) -> String {
             Self::Frames => format!("{}-frames", STATE_SECTION_PREFIX),
             Self::Globals => format!("{}-globals", STATE_SECTION_PREFIX),
             Self::Memory => format!("{}-memory", STATE_SECTION_PREFIX),
+            Self::Tables => format!("{}-tables", STATE_SECTION_PREFIX),
         }
     }
 
@@ -83,6 +86,7 @@ pub fn name(&self) -> &'static str {
             Self::Frames => "wrt-state-frames",
             Self::Globals => "wrt-state-globals",
             Self::Memory => "wrt-state-memory",
+            Self::Tables => "wrt-state-tables",
         }
     }
 
@@ -94,6 +98,7 @@ pub fn from_name(name: &str) -> Option<Self> {
             "wrt-state-frames" => Some(Self::Frames),
             "wrt-state-globals" => Some(Self::Globals),
             "wrt-state-memory" => Some(Self::Memory),
+            "wrt-state-tables" => Some(Self::Tables),
             _ => None,
         }
     }
@@ -106,6 +111,7 @@ pub fn from_u32(value: u32) -> Option<Self> {
             2 => Some(Self::Frames),
             3 => Some(Self::Globals),
             4 => Some(Self::Memory),
+            5 => Some(Self::Tables),
             _ => None,
         }
     }
@@ -132,7 +138,7 @@ pub fn create_state_section(
     compression_type: CompressionType,
 ) -> Result<CustomSection> {
     // Create header
-    let mut header = Vec::with_capacity(17);
+    let mut header = Vec::with_capacity(18);
 
     // Magic bytes
     header.extend_from_slice(STATE_MAGIC);
@@ -180,7 +186,7 @@ pub fn extract_state_section(section: &CustomSection) -> Result<(StateHeader, Ve
     let data = &section.data;
 
     // Parse header
-    if data.len() < 17 {
+    if data.len() < 18 {
         return Err(Error::validation_parse_error(
             "State section header too small",
         ));
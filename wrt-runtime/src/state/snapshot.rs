@@ -0,0 +1,578 @@
+//! Versioned snapshots of instance state
+//!
+//! An [`InstanceSnapshot`] holds everything needed to continue an instance
+//! on another engine: linear memories, globals, tables, the fuel left and,
+//! when the instance was suspended at an epoch deadline, the frame stack of
+//! the suspended call with each frame's value stack. It is encoded as a
+//! sequence of `wrt-state-*` sections behind an
+//! [`ArtifactHeader`](wrt_format::artifact::ArtifactHeader), which carries
+//! the snapshot version and a checksum.
+//!
+//! Snapshots are restored onto an instance of the same module; the module
+//! fingerprint recorded in the snapshot is checked first.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    artifact::{
+        read_artifact,
+        ArtifactHeader,
+        ArtifactKind,
+    },
+    compression::CompressionType,
+    section::CustomSection,
+};
+use wrt_foundation::{
+    sha256::Sha256,
+    values::{
+        ExternRef,
+        FloatBits32,
+        FloatBits64,
+        FuncRef,
+        V128,
+    },
+};
+
+use super::serialization::{
+    create_state_section,
+    extract_state_section,
+    StateSection,
+};
+use crate::{
+    module::Module,
+    prelude::*,
+};
+
+/// Version of the snapshot encoding
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Kinds of control blocks a frame can be inside, by encoded index
+pub const BLOCK_KINDS: [&str; 5] = ["block", "loop", "if", "try", "try_table"];
+
+/// Contents of one linear memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    /// Size in pages
+    pub pages: u32,
+    /// All bytes of the memory
+    pub data:  Vec<u8>,
+}
+
+/// Control block a suspended frame is inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSnapshot {
+    /// Index of the block kind in [`BLOCK_KINDS`]
+    pub kind:         u8,
+    /// Instruction index the block starts at
+    pub start_pc:     u32,
+    /// Block type of the block
+    pub type_idx:     u32,
+    /// Value stack height when the block was entered
+    pub stack_height: u32,
+}
+
+/// One frame of a suspended call
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameSnapshot {
+    /// Function the frame executes
+    pub func_idx:          u32,
+    /// Instruction index execution continues at
+    pub pc:                u32,
+    /// Local variables, parameters first
+    pub locals:            Vec<Value>,
+    /// Value stack of the frame
+    pub operand_stack:     Vec<Value>,
+    /// Enclosing control blocks, outermost first
+    pub blocks:            Vec<BlockSnapshot>,
+    /// Nesting depth of control blocks
+    pub block_depth:       i32,
+    /// Instructions executed in the frame so far
+    pub instruction_count: u64,
+}
+
+/// State of an instance captured by `StacklessEngine::snapshot`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceSnapshot {
+    /// Fingerprint of the module the instance was created from
+    pub module_fingerprint: u32,
+    /// Fuel left in the engine
+    pub fuel:               u64,
+    /// Function the host called, when a call is suspended
+    pub entry_func:         Option<u32>,
+    /// Linear memories in index order
+    pub memories:           Vec<MemorySnapshot>,
+    /// Global values in index order
+    pub globals:            Vec<Value>,
+    /// Table elements in index order
+    pub tables:             Vec<Vec<Option<Value>>>,
+    /// Frames of the suspended call, innermost last
+    pub frames:             Vec<FrameSnapshot>,
+}
+
+impl InstanceSnapshot {
+    /// Encode the snapshot
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value cannot be represented in a snapshot, such
+    /// as a GC or component value.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut meta = SnapshotWriter::default();
+        meta.u32(self.module_fingerprint);
+        meta.u64(self.fuel);
+        match self.entry_func {
+            Some(func_idx) => {
+                meta.u8(1);
+                meta.u32(func_idx);
+            },
+            None => meta.u8(0),
+        }
+
+        let mut memory = SnapshotWriter::default();
+        memory.len(self.memories.len());
+        for snapshot in &self.memories {
+            memory.u32(snapshot.pages);
+            memory.bytes(&snapshot.data);
+        }
+
+        let mut globals = SnapshotWriter::default();
+        globals.values(&self.globals)?;
+
+        let mut tables = SnapshotWriter::default();
+        tables.len(self.tables.len());
+        for elements in &self.tables {
+            tables.len(elements.len());
+            for element in elements {
+                match element {
+                    Some(value) => {
+                        tables.u8(1);
+                        tables.value(value)?;
+                    },
+                    None => tables.u8(0),
+                }
+            }
+        }
+
+        let mut frames = SnapshotWriter::default();
+        let mut stack = SnapshotWriter::default();
+        frames.len(self.frames.len());
+        stack.len(self.frames.len());
+        for frame in &self.frames {
+            frames.u32(frame.func_idx);
+            frames.u32(frame.pc);
+            frames.u32(frame.block_depth as u32);
+            frames.u64(frame.instruction_count);
+            frames.values(&frame.locals)?;
+            frames.len(frame.blocks.len());
+            for block in &frame.blocks {
+                frames.u8(block.kind);
+                frames.u32(block.start_pc);
+                frames.u32(block.type_idx);
+                frames.u32(block.stack_height);
+            }
+            stack.values(&frame.operand_stack)?;
+        }
+
+        let sections = [
+            (StateSection::Meta, meta, CompressionType::None),
+            (StateSection::Memory, memory, CompressionType::RLE),
+            (StateSection::Globals, globals, CompressionType::None),
+            (StateSection::Tables, tables, CompressionType::RLE),
+            (StateSection::Frames, frames, CompressionType::None),
+            (StateSection::Stack, stack, CompressionType::None),
+        ];
+        let mut out = SnapshotWriter::default();
+        out.len(sections.len());
+        for (section_type, writer, compression) in sections {
+            let section = create_state_section(section_type, &writer.0, compression)?;
+            out.bytes(section.name.as_bytes());
+            out.bytes(&section.data);
+        }
+        let header = ArtifactHeader::new(
+            ArtifactKind::Snapshot,
+            SNAPSHOT_VERSION,
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+        );
+        header.write(&out.0)
+    }
+
+    /// Decode a snapshot encoded with [`Self::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is truncated or corrupted, or was
+    /// written by an unsupported snapshot version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (header, body) = read_artifact(bytes)?;
+        header.expect(ArtifactKind::Snapshot, SNAPSHOT_VERSION)?;
+
+        let mut reader = SnapshotReader::new(body);
+
+        let mut snapshot = Self {
+            module_fingerprint: 0,
+            fuel:               0,
+            entry_func:         None,
+            memories:           Vec::new(),
+            globals:            Vec::new(),
+            tables:             Vec::new(),
+            frames:             Vec::new(),
+        };
+        let mut operand_stacks = Vec::new();
+        for _ in 0..reader.len()? {
+            let name = String::from_utf8(reader.bytes()?.to_vec())
+                .map_err(|_| Error::validation_parse_error("Invalid snapshot section name"))?;
+            let section = CustomSection::new(name, reader.bytes()?.to_vec());
+            let (header, data) = extract_state_section(&section)?;
+            let mut data = SnapshotReader::new(&data);
+            match header.section_type {
+                StateSection::Meta => {
+                    snapshot.module_fingerprint = data.u32()?;
+                    snapshot.fuel = data.u64()?;
+                    snapshot.entry_func = match data.u8()? {
+                        0 => None,
+                        _ => Some(data.u32()?),
+                    };
+                },
+                StateSection::Memory => {
+                    for _ in 0..data.len()? {
+                        let pages = data.u32()?;
+                        let bytes = data.bytes()?.to_vec();
+                        snapshot.memories.push(MemorySnapshot { pages, data: bytes });
+                    }
+                },
+                StateSection::Globals => snapshot.globals = data.values()?,
+                StateSection::Tables => {
+                    for _ in 0..data.len()? {
+                        let mut elements = Vec::new();
+                        for _ in 0..data.len()? {
+                            elements.push(match data.u8()? {
+                                0 => None,
+                                _ => Some(data.value()?),
+                            });
+                        }
+                        snapshot.tables.push(elements);
+                    }
+                },
+                StateSection::Frames => {
+                    for _ in 0..data.len()? {
+                        let func_idx = data.u32()?;
+                        let pc = data.u32()?;
+                        let block_depth = data.u32()? as i32;
+                        let instruction_count = data.u64()?;
+                        let locals = data.values()?;
+                        let mut blocks = Vec::new();
+                        for _ in 0..data.len()? {
+                            let kind = data.u8()?;
+                            if usize::from(kind) >= BLOCK_KINDS.len() {
+                                return Err(Error::validation_parse_error(
+                                    "Invalid snapshot block kind",
+                                ));
+                            }
+                            blocks.push(BlockSnapshot {
+                                kind,
+                                start_pc: data.u32()?,
+                                type_idx: data.u32()?,
+                                stack_height: data.u32()?,
+                            });
+                        }
+                        snapshot.frames.push(FrameSnapshot {
+                            func_idx,
+                            pc,
+                            locals,
+                            operand_stack: Vec::new(),
+                            blocks,
+                            block_depth,
+                            instruction_count,
+                        });
+                    }
+                },
+                StateSection::Stack => {
+                    for _ in 0..data.len()? {
+                        operand_stacks.push(data.values()?);
+                    }
+                },
+            }
+        }
+
+        if operand_stacks.len() != snapshot.frames.len() {
+            return Err(Error::validation_parse_error("Snapshot frame and stack counts differ"));
+        }
+        for (frame, stack) in snapshot.frames.iter_mut().zip(operand_stacks) {
+            frame.operand_stack = stack;
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Fingerprint of a module: its shape and the bytecode of its function
+/// bodies
+///
+/// Snapshots only restore onto instances of modules with the same
+/// fingerprint. The bodies enter through the digest recorded when they were
+/// decoded, so the fingerprint is the same whether bodies are lazy or not.
+pub fn module_fingerprint(module: &Module) -> u32 {
+    let mut hasher = Sha256::new();
+    let counts = [
+        module.types.len(),
+        module.functions.len(),
+        module.memories.len(),
+        module.tables.len(),
+        module.globals.len(),
+        module.data.len(),
+        module.elements.len(),
+    ];
+    for count in counts {
+        hasher.update(&(count as u32).to_le_bytes());
+    }
+    for function in &module.functions {
+        hasher.update(&function.type_idx.to_le_bytes());
+        hasher.update(&function.code_digest);
+    }
+    let digest = hasher.finalize();
+    u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]])
+}
+
+/// Little-endian encoder for snapshot sections
+#[derive(Default)]
+struct SnapshotWriter(Vec<u8>);
+
+impl SnapshotWriter {
+    fn raw(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.raw(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.raw(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.raw(bytes);
+    }
+
+    fn values(&mut self, values: &[Value]) -> Result<()> {
+        self.len(values.len());
+        values.iter().try_for_each(|value| self.value(value))
+    }
+
+    fn value(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::I32(v) => {
+                self.u8(0);
+                self.u32(*v as u32);
+            },
+            Value::I64(v) => {
+                self.u8(1);
+                self.u64(*v as u64);
+            },
+            Value::F32(v) => {
+                self.u8(2);
+                self.u32(v.0);
+            },
+            Value::F64(v) => {
+                self.u8(3);
+                self.u64(v.0);
+            },
+            Value::V128(v) => {
+                self.u8(4);
+                self.raw(&v.bytes);
+            },
+            Value::FuncRef(None) => self.u8(5),
+            Value::FuncRef(Some(func)) => {
+                self.u8(6);
+                self.u32(func.index);
+            },
+            Value::ExternRef(None) => self.u8(7),
+            Value::ExternRef(Some(extern_ref)) => {
+                self.u8(8);
+                self.u32(extern_ref.index);
+            },
+            Value::Ref(index) => {
+                self.u8(9);
+                self.u32(*index);
+            },
+            Value::ExnRef(None) => self.u8(10),
+            Value::I31Ref(None) => self.u8(11),
+            Value::I31Ref(Some(v)) => {
+                self.u8(12);
+                self.u32(*v as u32);
+            },
+            _ => {
+                return Err(Error::runtime_execution_error(
+                    "Value cannot be stored in a snapshot",
+                ))
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Little-endian decoder for snapshot sections
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(count)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::validation_parse_error("Snapshot truncated"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn values(&mut self) -> Result<Vec<Value>> {
+        let len = self.len()?;
+        // Bound the reservation by what the remaining data can hold
+        let mut values = Vec::with_capacity(len.min(self.data.len() - self.pos));
+        for _ in 0..len {
+            values.push(self.value()?);
+        }
+        Ok(values)
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        Ok(match self.u8()? {
+            0 => Value::I32(self.u32()? as i32),
+            1 => Value::I64(self.u64()? as i64),
+            2 => Value::F32(FloatBits32(self.u32()?)),
+            3 => Value::F64(FloatBits64(self.u64()?)),
+            4 => {
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(self.take(16)?);
+                Value::V128(V128 { bytes })
+            },
+            5 => Value::FuncRef(None),
+            6 => Value::FuncRef(Some(FuncRef { index: self.u32()? })),
+            7 => Value::ExternRef(None),
+            8 => Value::ExternRef(Some(ExternRef { index: self.u32()? })),
+            9 => Value::Ref(self.u32()?),
+            10 => Value::ExnRef(None),
+            11 => Value::I31Ref(None),
+            12 => Value::I31Ref(Some(self.u32()? as i32)),
+            _ => return Err(Error::validation_parse_error("Invalid snapshot value tag")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() -> Result<()> {
+        let mut data = vec![0u8; 65536];
+        data[16..20].copy_from_slice(&[1, 2, 3, 4]);
+        let snapshot = InstanceSnapshot {
+            module_fingerprint: 0xdead_beef,
+            fuel:               1234,
+            entry_func:         Some(3),
+            memories:           vec![MemorySnapshot { pages: 1, data }],
+            globals:            vec![Value::I32(-7), Value::F64(FloatBits64(42))],
+            tables:             vec![vec![None, Some(Value::FuncRef(Some(FuncRef { index: 2 })))]],
+            frames:             vec![FrameSnapshot {
+                func_idx:          3,
+                pc:                9,
+                locals:            vec![Value::I64(5)],
+                operand_stack:     vec![Value::I32(1), Value::I32(2)],
+                blocks:            vec![BlockSnapshot {
+                    kind:         1,
+                    start_pc:     2,
+                    type_idx:     0x40,
+                    stack_height: 0,
+                }],
+                block_depth:       1,
+                instruction_count: 77,
+            }],
+        };
+
+        let bytes = snapshot.to_bytes()?;
+        // Zero pages compress away
+        assert!(bytes.len() < 4096);
+        assert_eq!(InstanceSnapshot::from_bytes(&bytes)?, snapshot);
+
+        let mut corrupted = bytes.clone();
+        corrupted[40] ^= 0xff;
+        assert!(InstanceSnapshot::from_bytes(&corrupted).is_err());
+        assert!(InstanceSnapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint_covers_function_bodies() -> Result<()> {
+        use crate::module::BodyParsing;
+
+        let fingerprint = |wat: &str, parsing: BodyParsing| -> Result<u32> {
+            let decoded = wrt_decoder::decoder::decode_module(&wat::parse_str(wat).unwrap())?;
+            Ok(module_fingerprint(&*Module::from_wrt_module_with(&decoded, parsing)?))
+        };
+        let one = r#"(module (func (export "f") (result i32) i32.const 1))"#;
+        let two = r#"(module (func (export "f") (result i32) i32.const 2))"#;
+
+        // Same shape and bytecode size, different body
+        assert_ne!(fingerprint(one, BodyParsing::Eager)?, fingerprint(two, BodyParsing::Eager)?);
+        assert_eq!(fingerprint(one, BodyParsing::Eager)?, fingerprint(one, BodyParsing::Lazy)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_values_are_rejected() {
+        let snapshot = InstanceSnapshot {
+            module_fingerprint: 0,
+            fuel:               0,
+            entry_func:         None,
+            memories:           Vec::new(),
+            globals:            vec![Value::String("text".into())],
+            tables:             Vec::new(),
+            frames:             Vec::new(),
+        };
+        assert!(snapshot.to_bytes().is_err());
+    }
+}
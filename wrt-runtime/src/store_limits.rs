@@ -0,0 +1,134 @@
+//! Per-store execution limits
+//!
+//! [`StoreLimits`] bounds what guest code running in one engine may demand
+//! at run time. Each limit defaults to, and may not exceed, the platform
+//! ceiling from `wrt_foundation::limits`.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::result_buffer::MAX_RESULTS;
+
+/// Default initial capacity of scratch buffers, in values
+pub const DEFAULT_SCRATCH_CAPACITY: usize = 64;
+
+/// Largest scratch buffer capacity, in values
+///
+/// Buffers that grew beyond this are freed rather than reused.
+pub const MAX_SCRATCH_CAPACITY: usize = 4096;
+
+/// Runtime limits applied by an engine to the functions it executes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreLimits {
+    /// Maximum number of values a single function call may return
+    max_results:      usize,
+    /// Initial capacity of the reusable call buffers, in values
+    scratch_capacity: usize,
+}
+
+impl StoreLimits {
+    /// Limits set to the platform ceilings
+    pub const fn new() -> Self {
+        Self {
+            max_results:      MAX_RESULTS,
+            scratch_capacity: DEFAULT_SCRATCH_CAPACITY,
+        }
+    }
+
+    /// Set the maximum number of values a function call may return
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_results` exceeds the platform ceiling
+    /// [`MAX_RESULTS`].
+    pub fn with_max_results(mut self, max_results: usize) -> Result<Self> {
+        if max_results > MAX_RESULTS {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Result limit exceeds the platform maximum",
+            ));
+        }
+        self.max_results = max_results;
+        Ok(self)
+    }
+
+    /// Maximum number of values a function call may return
+    pub const fn max_results(&self) -> usize {
+        self.max_results
+    }
+
+    /// Set the initial capacity of the reusable operand stack, locals and
+    /// block stack buffers
+    ///
+    /// Calls whose stacks fit in this capacity run without allocating once
+    /// the engine's scratch pools are warm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `capacity` exceeds [`MAX_SCRATCH_CAPACITY`].
+    pub fn with_scratch_capacity(mut self, capacity: usize) -> Result<Self> {
+        if capacity > MAX_SCRATCH_CAPACITY {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Scratch capacity exceeds the platform maximum",
+            ));
+        }
+        self.scratch_capacity = capacity;
+        Ok(self)
+    }
+
+    /// Initial capacity of the reusable call buffers, in values
+    pub const fn scratch_capacity(&self) -> usize {
+        self.scratch_capacity
+    }
+
+    /// Check a function's result arity against the limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `arity` exceeds [`Self::max_results`].
+    pub fn check_result_arity(&self, arity: usize) -> Result<()> {
+        if arity > self.max_results {
+            return Err(Error::new(
+                ErrorCategory::Capacity,
+                codes::CAPACITY_EXCEEDED,
+                "Function result count exceeds store limit",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for StoreLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_limits() {
+        let limits = StoreLimits::new();
+        assert_eq!(limits.max_results(), MAX_RESULTS);
+        assert!(limits.check_result_arity(MAX_RESULTS).is_ok());
+
+        let limits = limits.with_max_results(20).unwrap();
+        assert!(limits.check_result_arity(20).is_ok());
+        assert!(limits.check_result_arity(21).is_err());
+
+        assert!(StoreLimits::new().with_max_results(MAX_RESULTS + 1).is_err());
+
+        assert_eq!(StoreLimits::new().scratch_capacity(), DEFAULT_SCRATCH_CAPACITY);
+        assert!(StoreLimits::new().with_scratch_capacity(MAX_SCRATCH_CAPACITY).is_ok());
+        assert!(StoreLimits::new().with_scratch_capacity(MAX_SCRATCH_CAPACITY + 1).is_err());
+    }
+}
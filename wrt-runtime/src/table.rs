@@ -35,6 +35,8 @@
 // Import the TableOperations trait from wrt-instructions
 use wrt_instructions::table_ops::TableOperations;
 
+use wrt_error::codes::TrapCode;
+
 use crate::prelude::{
     Arc,
     BoundedCapacity,
@@ -557,9 +559,9 @@ pub fn fill_elements_shared(
 
         // Verify bounds - use checked arithmetic to prevent overflow
         let end = offset.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         if end > elements.len() {
-            return Err(Error::runtime_trap("out of bounds table access"));
+            return Err(Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"));
         }
 
         // Handle empty fill (after bounds check per spec)
@@ -600,11 +602,11 @@ pub fn copy_elements_shared(&self, dst: usize, src: usize, len: usize) -> Result
 
         // Verify bounds - use checked arithmetic to prevent overflow
         let src_end = src.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         let dst_end = dst.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         if src_end > elements.len() || dst_end > elements.len() {
-            return Err(Error::runtime_trap("out of bounds table access"));
+            return Err(Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"));
         }
 
         // Handle the case where no elements to copy (AFTER bounds check per spec)
@@ -800,11 +802,11 @@ pub fn copy_elements(&mut self, dst: usize, src: usize, len: usize) -> Result<()
 
         // Verify bounds - use checked arithmetic to prevent overflow
         let src_end = src.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         let dst_end = dst.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         if src_end > elements.len() || dst_end > elements.len() {
-            return Err(Error::runtime_trap("out of bounds table access"));
+            return Err(Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"));
         }
 
         // Handle the case where regions don't overlap or no elements to copy (AFTER bounds check per spec)
@@ -859,9 +861,9 @@ pub fn fill_elements(
 
         // Verify bounds - use checked arithmetic to prevent overflow
         let end = offset.checked_add(len)
-            .ok_or_else(|| Error::runtime_trap("out of bounds table access"))?;
+            .ok_or_else(|| Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"))?;
         if end > elements.len() {
-            return Err(Error::runtime_trap("out of bounds table access"));
+            return Err(Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"));
         }
 
         // Handle empty fill (after bounds check per spec)
@@ -921,7 +923,7 @@ pub fn init_element(&mut self, idx: usize, value: Option<WrtValue>) -> Result<()
 
         // Check bounds
         if idx >= elements.len() {
-            return Err(Error::runtime_trap("out of bounds table access"));
+            return Err(Error::wasm_trap(TrapCode::TableOutOfBounds, "out of bounds table access"));
         }
 
         // Set the element directly using BoundedVec's set method
@@ -0,0 +1,346 @@
+//! Cache of ahead-of-time validation results
+//!
+//! A [`ValidationCache`] holds one artifact per module that passed
+//! validation, keyed by the SHA-256 hash of the module binary.
+//! [`Module::from_binary_with_cache`](crate::module::Module::from_binary_with_cache)
+//! skips validation of modules with an artifact in the cache, so a module
+//! validated once on the build host or on first boot loads without being
+//! validated again.
+//!
+//! Artifacts are signed with HMAC-SHA256 under a key held by the host and
+//! form a hash chain: each artifact signs the digest of the one recorded
+//! before it, so artifacts cannot be forged, dropped or reordered without
+//! breaking every later link. Loading a persisted cache verifies the whole
+//! chain once. In [`CacheVerification::Strict`] mode, intended for ASIL
+//! deployments, every lookup re-verifies the chain up to the artifact found,
+//! so a cache corrupted in memory after loading is not trusted either.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_format::artifact::{
+    read_artifact,
+    ArtifactHeader,
+    ArtifactKind,
+};
+use wrt_foundation::sha256::{
+    digests_equal,
+    hmac_sha256,
+    sha256,
+    Sha256,
+};
+
+use crate::prelude::*;
+
+/// Version of the artifact format
+///
+/// Bumped whenever validation rules change, which invalidates all
+/// artifacts signed before.
+pub const ARTIFACT_VERSION: u32 = 1;
+
+/// Encoded size of one artifact
+const ARTIFACT_SIZE: usize = 96;
+
+/// How much of the artifact chain is checked when a module is looked up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheVerification {
+    /// Trust artifacts verified when they were recorded or loaded
+    #[default]
+    Trusted,
+    /// Re-verify the signatures and links of the chain up to the artifact on
+    /// every lookup
+    Strict,
+}
+
+/// Signed record that a module passed validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationArtifact {
+    /// SHA-256 hash of the module binary
+    pub module_hash: [u8; 32],
+    /// Digest of the previous artifact in the chain, zero for the first
+    pub previous:    [u8; 32],
+    /// HMAC-SHA256 tag over the artifact version, module hash and previous
+    /// digest
+    pub tag:         [u8; 32],
+}
+
+impl ValidationArtifact {
+    /// Bytes covered by the tag
+    fn signed_bytes(module_hash: &[u8; 32], previous: &[u8; 32]) -> [u8; 68] {
+        let mut bytes = [0u8; 68];
+        bytes[..4].copy_from_slice(&ARTIFACT_VERSION.to_le_bytes());
+        bytes[4..36].copy_from_slice(module_hash);
+        bytes[36..].copy_from_slice(previous);
+        bytes
+    }
+
+    /// Digest linking the next artifact in the chain to this one
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.module_hash);
+        hasher.update(&self.previous);
+        hasher.update(&self.tag);
+        hasher.finalize()
+    }
+}
+
+/// Lookups made in a [`ValidationCache`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ValidationCacheStats {
+    /// Modules found with a valid artifact
+    pub hits:     u64,
+    /// Modules without an artifact
+    pub misses:   u64,
+    /// Lookups that failed chain verification
+    pub rejected: u64,
+}
+
+/// Signed validation artifacts keyed by module hash
+#[derive(Debug, Clone)]
+pub struct ValidationCache {
+    /// HMAC key artifacts are signed with
+    key:          Vec<u8>,
+    /// Verification done on lookup
+    verification: CacheVerification,
+    /// Artifacts in chain order
+    artifacts:    Vec<ValidationArtifact>,
+    /// Position of each module's artifact in the chain
+    index:        HashMap<[u8; 32], usize>,
+    /// Lookups made so far
+    stats:        ValidationCacheStats,
+}
+
+impl ValidationCache {
+    /// Create an empty cache signing artifacts with `key`
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key:          key.to_vec(),
+            verification: CacheVerification::default(),
+            artifacts:    Vec::new(),
+            index:        HashMap::new(),
+            stats:        ValidationCacheStats::default(),
+        }
+    }
+
+    /// Set the verification done on lookup
+    #[must_use]
+    pub fn with_verification(mut self, verification: CacheVerification) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Verification done on lookup
+    pub fn verification(&self) -> CacheVerification {
+        self.verification
+    }
+
+    /// Artifacts in chain order
+    pub fn artifacts(&self) -> &[ValidationArtifact] {
+        &self.artifacts
+    }
+
+    /// Number of modules with an artifact
+    pub fn len(&self) -> usize {
+        self.artifacts.len()
+    }
+
+    /// Whether the cache holds no artifacts
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+
+    /// Lookups made so far
+    pub fn stats(&self) -> ValidationCacheStats {
+        self.stats
+    }
+
+    /// Hash a module binary the way the cache keys it
+    pub fn module_hash(binary: &[u8]) -> [u8; 32] {
+        sha256(binary)
+    }
+
+    /// Whether a module with this hash passed validation before
+    ///
+    /// # Errors
+    ///
+    /// Returns `codes::INTEGRITY_VIOLATION` in strict mode when the chain up
+    /// to the module's artifact does not verify.
+    pub fn lookup(&mut self, module_hash: &[u8; 32]) -> Result<bool> {
+        let Some(&position) = self.index.get(module_hash) else {
+            self.stats.misses += 1;
+            return Ok(false);
+        };
+        if self.verification == CacheVerification::Strict {
+            if let Err(error) = self.verify_chain(position) {
+                self.stats.rejected += 1;
+                return Err(error);
+            }
+        }
+        self.stats.hits += 1;
+        Ok(true)
+    }
+
+    /// Record that a module with this hash passed validation
+    ///
+    /// Returns the module's artifact, which is the existing one if the
+    /// module was recorded before.
+    pub fn record(&mut self, module_hash: [u8; 32]) -> ValidationArtifact {
+        if let Some(&position) = self.index.get(&module_hash) {
+            return self.artifacts[position];
+        }
+        let previous = self.artifacts.last().map_or([0u8; 32], ValidationArtifact::digest);
+        let artifact = ValidationArtifact {
+            module_hash,
+            previous,
+            tag: hmac_sha256(&self.key, &ValidationArtifact::signed_bytes(&module_hash, &previous)),
+        };
+        self.index.insert(module_hash, self.artifacts.len());
+        self.artifacts.push(artifact);
+        artifact
+    }
+
+    /// Verify signatures and links of the chain from the first artifact up
+    /// to and including the one at `position`
+    ///
+    /// # Errors
+    ///
+    /// Returns `codes::INTEGRITY_VIOLATION` for the first artifact whose tag
+    /// or link does not verify.
+    pub fn verify_chain(&self, position: usize) -> Result<()> {
+        let mut previous = [0u8; 32];
+        for artifact in self.artifacts.iter().take(position.saturating_add(1)) {
+            if !digests_equal(&artifact.previous, &previous) {
+                return Err(integrity_error("Validation artifact chain link broken"));
+            }
+            let expected = hmac_sha256(
+                &self.key,
+                &ValidationArtifact::signed_bytes(&artifact.module_hash, &artifact.previous),
+            );
+            if !digests_equal(&artifact.tag, &expected) {
+                return Err(integrity_error("Validation artifact signature mismatch"));
+            }
+            previous = artifact.digest();
+        }
+        Ok(())
+    }
+
+    /// Encode the cache for persistence behind an [`ArtifactHeader`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact header cannot be encoded
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(4 + self.artifacts.len() * ARTIFACT_SIZE);
+        bytes.extend_from_slice(&(self.artifacts.len() as u32).to_le_bytes());
+        for artifact in &self.artifacts {
+            bytes.extend_from_slice(&artifact.module_hash);
+            bytes.extend_from_slice(&artifact.previous);
+            bytes.extend_from_slice(&artifact.tag);
+        }
+        let header = ArtifactHeader::new(
+            ArtifactKind::ValidationCache,
+            ARTIFACT_VERSION,
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+        );
+        header.write(&bytes)
+    }
+
+    /// Load a cache persisted with [`Self::to_bytes`], verifying the whole
+    /// chain under `key`
+    ///
+    /// A cache written for another artifact version loads empty, so all
+    /// modules are validated again under the current rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is malformed, or
+    /// `codes::INTEGRITY_VIOLATION` if the chain does not verify.
+    pub fn from_bytes(key: &[u8], bytes: &[u8]) -> Result<Self> {
+        let mut cache = Self::new(key);
+        let (header, payload) = read_artifact(bytes)?;
+        if header.kind != ArtifactKind::ValidationCache || payload.len() < 4 {
+            return Err(Error::validation_parse_error("Not a validation cache"));
+        }
+        if header.schema_version != ARTIFACT_VERSION {
+            return Ok(cache);
+        }
+        let count = u32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
+        let body = &payload[4..];
+        if count.checked_mul(ARTIFACT_SIZE) != Some(body.len()) {
+            return Err(Error::validation_parse_error("Validation cache truncated"));
+        }
+
+        for chunk in body.chunks_exact(ARTIFACT_SIZE) {
+            let mut artifact = ValidationArtifact {
+                module_hash: [0u8; 32],
+                previous:    [0u8; 32],
+                tag:         [0u8; 32],
+            };
+            artifact.module_hash.copy_from_slice(&chunk[..32]);
+            artifact.previous.copy_from_slice(&chunk[32..64]);
+            artifact.tag.copy_from_slice(&chunk[64..]);
+            cache.index.insert(artifact.module_hash, cache.artifacts.len());
+            cache.artifacts.push(artifact);
+        }
+        if let Some(last) = cache.artifacts.len().checked_sub(1) {
+            cache.verify_chain(last)?;
+        }
+        Ok(cache)
+    }
+}
+
+/// Error for an artifact chain that does not verify
+fn integrity_error(message: &'static str) -> Error {
+    Error::new(ErrorCategory::Validation, codes::INTEGRITY_VIOLATION, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip_and_lookup() -> Result<()> {
+        let mut cache = ValidationCache::new(b"device key");
+        let first = ValidationCache::module_hash(b"module one");
+        let second = ValidationCache::module_hash(b"module two");
+        cache.record(first);
+        let artifact = cache.record(second);
+        assert_eq!(artifact.previous, cache.artifacts()[0].digest());
+        assert_eq!(cache.record(first), cache.artifacts()[0]);
+
+        let mut loaded = ValidationCache::from_bytes(b"device key", &cache.to_bytes()?)?
+            .with_verification(CacheVerification::Strict);
+        assert!(loaded.lookup(&second)?);
+        assert!(!loaded.lookup(&ValidationCache::module_hash(b"module three"))?);
+        assert_eq!(loaded.stats(), ValidationCacheStats { hits: 1, misses: 1, rejected: 0 });
+
+        assert!(ValidationCache::from_bytes(b"other key", &cache.to_bytes()?).is_err());
+
+        let stale = ArtifactHeader::new(ArtifactKind::ValidationCache, ARTIFACT_VERSION + 1, "", "")
+            .write(&[0; 4])?;
+        assert!(ValidationCache::from_bytes(b"device key", &stale)?.artifacts().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_lookup_detects_tampering() {
+        let mut cache =
+            ValidationCache::new(b"device key").with_verification(CacheVerification::Strict);
+        let first = ValidationCache::module_hash(b"module one");
+        let second = ValidationCache::module_hash(b"module two");
+        cache.record(first);
+        cache.record(second);
+
+        cache.artifacts[0].tag[0] ^= 1;
+        let error = cache.lookup(&second).unwrap_err();
+        assert_eq!(error.code, codes::INTEGRITY_VIOLATION);
+        assert_eq!(cache.stats().rejected, 1);
+
+        cache.verification = CacheVerification::Trusted;
+        assert!(cache.lookup(&second).unwrap());
+    }
+}
@@ -0,0 +1,373 @@
+//! Waiter table backing `memory.atomic.wait` / `memory.atomic.notify`
+//!
+//! Every shared memory owns one [`WaiterTable`]. Because the table lives with
+//! the memory rather than with an instance, all instances importing the same
+//! shared memory park on and wake the same queues.
+//!
+//! The value check and the enqueue happen under the table lock, and notify
+//! dequeues under the same lock, so a notify issued after a store can never be
+//! missed by a waiter that observed the old value. Each waiter then blocks on
+//! its own futex word:
+//! - with `std`, a [`wrt_platform::sync::ParkingFutex`] parks the thread;
+//! - without `std`, the waiter polls its flag with `spin_loop`. There is no
+//!   clock to measure a timeout against, so only waits without a timeout or
+//!   with a zero one are accepted.
+
+// Binary std/no_std choice
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::{
+        BTreeMap as HashMap,
+        VecDeque,
+    },
+    sync::Arc,
+};
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{
+    AtomicBool,
+    Ordering,
+};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::Arc,
+};
+
+use wrt_error::Result;
+#[cfg(feature = "std")]
+use wrt_platform::sync::{
+    FutexLike,
+    ParkingFutex,
+};
+use wrt_sync::WrtMutex;
+
+/// `memory.atomic.wait` result: woken by a notify
+pub const WAIT_OK: i32 = 0;
+
+/// `memory.atomic.wait` result: the loaded value did not match `expected`
+pub const WAIT_NOT_EQUAL: i32 = 1;
+
+/// `memory.atomic.wait` result: the timeout expired before a notify
+pub const WAIT_TIMED_OUT: i32 = 2;
+
+/// A single parked thread
+#[derive(Debug)]
+struct Waiter {
+    /// Futex word flipped from 0 to 1 by the notifier
+    #[cfg(feature = "std")]
+    word:     ParkingFutex,
+    /// Flag polled by the waiter when there is no scheduler to park on
+    #[cfg(not(feature = "std"))]
+    notified: AtomicBool,
+}
+
+impl Waiter {
+    fn new() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            word:                                  ParkingFutex::new(0),
+            #[cfg(not(feature = "std"))]
+            notified:                              AtomicBool::new(false),
+        }
+    }
+
+    /// Blocks until notified or until `timeout` expires; returns whether the
+    /// waiter was notified.
+    #[cfg(feature = "std")]
+    fn park(&self, timeout: Option<Duration>) -> bool {
+        // The only error is a timeout; the word is re-checked under the futex
+        // lock so spurious wake-ups never surface here.
+        self.word.wait(0, timeout).is_ok()
+    }
+
+    /// Spins until notified; [`WaiterTable::wait`] has already rejected any
+    /// timeout this could not honour
+    #[cfg(not(feature = "std"))]
+    fn park(&self, _timeout: Option<Duration>) -> bool {
+        while !self.notified.load(Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        true
+    }
+
+    #[cfg(feature = "std")]
+    fn unpark(&self) {
+        self.word.set(1);
+        // ParkingFutex::wake cannot fail
+        let _ = self.word.wake(1);
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn unpark(&self) {
+        self.notified.store(true, Ordering::Release);
+    }
+}
+
+/// FIFO queues of threads parked on addresses of one shared memory
+#[derive(Debug)]
+pub struct WaiterTable {
+    /// Waiters keyed by byte address, oldest first
+    queues: WrtMutex<HashMap<u64, VecDeque<Arc<Waiter>>>>,
+}
+
+impl WaiterTable {
+    /// Creates an empty waiter table
+    pub fn new() -> Self {
+        Self {
+            queues: WrtMutex::new(HashMap::new()),
+        }
+    }
+
+    /// Parks the calling thread on `address`.
+    ///
+    /// `matches_expected` is evaluated under the table lock and must load the
+    /// current value at `address` and compare it with the expected operand.
+    /// Returns [`WAIT_NOT_EQUAL`] without blocking if it reports a mismatch,
+    /// otherwise [`WAIT_OK`] once notified or [`WAIT_TIMED_OUT`]. A `None`
+    /// timeout waits indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any error from `matches_expected`, such as an out-of-bounds
+    /// load. Without `std` a timeout cannot be measured, so a wait with a
+    /// non-zero timeout fails.
+    pub fn wait(
+        &self,
+        address: u64,
+        timeout: Option<Duration>,
+        matches_expected: impl FnOnce() -> Result<bool>,
+    ) -> Result<i32> {
+        #[cfg(not(feature = "std"))]
+        if timeout.is_some_and(|timeout| !timeout.is_zero()) {
+            return Err(wrt_error::Error::runtime_unsupported_operation(
+                "Timed atomic wait requires a platform clock",
+            ));
+        }
+
+        let waiter = {
+            let mut queues = self.queues.lock();
+            if !matches_expected()? {
+                return Ok(WAIT_NOT_EQUAL);
+            }
+            if timeout == Some(Duration::ZERO) {
+                return Ok(WAIT_TIMED_OUT);
+            }
+            let waiter = Arc::new(Waiter::new());
+            queues.entry(address).or_default().push_back(Arc::clone(&waiter));
+            waiter
+        };
+
+        if waiter.park(timeout) {
+            return Ok(WAIT_OK);
+        }
+
+        // Timed out; unless a notifier dequeued us in the meantime, leave the
+        // queue so later notifies are not spent on this waiter.
+        let mut queues = self.queues.lock();
+        if let Some(queue) = queues.get_mut(&address) {
+            if let Some(position) = queue.iter().position(|w| Arc::ptr_eq(w, &waiter)) {
+                queue.remove(position);
+                if queue.is_empty() {
+                    queues.remove(&address);
+                }
+                return Ok(WAIT_TIMED_OUT);
+            }
+        }
+        Ok(WAIT_OK)
+    }
+
+    /// Wakes up to `count` threads parked on `address`, oldest first, and
+    /// returns how many were woken.
+    pub fn notify(&self, address: u64, count: u32) -> u32 {
+        let mut queues = self.queues.lock();
+        let Some(queue) = queues.get_mut(&address) else {
+            return 0;
+        };
+
+        let mut woken = 0;
+        while woken < count {
+            let Some(waiter) = queue.pop_front() else {
+                break;
+            };
+            waiter.unpark();
+            woken += 1;
+        }
+        if queue.is_empty() {
+            queues.remove(&address);
+        }
+        woken
+    }
+
+    /// Number of threads currently parked on `address`
+    pub fn waiter_count(&self, address: u64) -> usize {
+        self.queues.lock().get(&address).map_or(0, VecDeque::len)
+    }
+}
+
+impl Default for WaiterTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::Instant,
+    };
+
+    use super::*;
+
+    #[test]
+    fn mismatch_and_timeout_do_not_leave_waiters_behind() {
+        let table = WaiterTable::new();
+
+        assert_eq!(table.wait(0, None, || Ok(false)).unwrap(), WAIT_NOT_EQUAL);
+
+        let start = Instant::now();
+        let result = table.wait(8, Some(Duration::from_millis(20)), || Ok(true)).unwrap();
+        assert_eq!(result, WAIT_TIMED_OUT);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(table.waiter_count(8), 0);
+        assert_eq!(table.notify(8, 1), 0);
+    }
+
+    #[test]
+    fn notify_wakes_requested_number_of_waiters() {
+        let table = Arc::new(WaiterTable::new());
+
+        let waiters: Vec<_> = (0..3)
+            .map(|_| {
+                let table = Arc::clone(&table);
+                thread::spawn(move || table.wait(16, None, || Ok(true)).unwrap())
+            })
+            .collect();
+        while table.waiter_count(16) < 3 {
+            thread::yield_now();
+        }
+
+        assert_eq!(table.notify(16, 2), 2);
+        assert_eq!(table.waiter_count(16), 1);
+        assert_eq!(table.notify(16, u32::MAX), 1);
+        for waiter in waiters {
+            assert_eq!(waiter.join().unwrap(), WAIT_OK);
+        }
+    }
+
+    /// Module over one shared memory exporting `wait` (func 0) and `notify`
+    /// (func 1) on address 0
+    const WAIT_NOTIFY_MODULE: &str = r#"
+        (module
+          (memory 1 1 shared)
+          (func (export "wait") (result i32)
+            (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1)))
+          (func (export "notify") (result i32)
+            (memory.atomic.notify (i32.const 0) (i32.const 1))))
+    "#;
+
+    #[test]
+    fn engine_wait_is_woken_by_notify_from_another_thread() -> Result<()> {
+        use wrt_foundation::values::Value;
+
+        use crate::{
+            module::Module,
+            module_instance::ModuleInstance,
+            stackless::StacklessEngine,
+        };
+
+        let wasm = wat::parse_str(WAIT_NOTIFY_MODULE).unwrap();
+        let decoded = wrt_decoder::decoder::decode_module(&wasm)?;
+        let module = Arc::new(*Module::from_wrt_module(&decoded)?);
+        let instance = Arc::new(ModuleInstance::new(module, 0)?);
+        instance.populate_memories_from_module()?;
+
+        let waiter = {
+            let instance = Arc::clone(&instance);
+            thread::spawn(move || -> Result<Vec<Value>> {
+                let mut engine = StacklessEngine::new();
+                let id = engine.set_current_module(instance)?;
+                engine.execute(id, 0, Vec::new())
+            })
+        };
+        let memory = instance.memory(0)?;
+        while memory.0.waiters.waiter_count(0) == 0 {
+            thread::yield_now();
+        }
+
+        let mut engine = StacklessEngine::new();
+        let id = engine.set_current_module(instance)?;
+        assert_eq!(engine.execute(id, 1, Vec::new())?, vec![Value::I32(1)]);
+        assert_eq!(waiter.join().unwrap()?, vec![Value::I32(WAIT_OK)]);
+        Ok(())
+    }
+
+    /// Module exporting its shared memory and a `wait` (func 0) on address 0
+    const SHARED_MEMORY_WAITER: &str = r#"
+        (module
+          (memory (export "memory") 1 1 shared)
+          (func (export "wait") (result i32)
+            (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1))))
+    "#;
+
+    /// Module importing a shared memory and exporting a `notify` (func 0) on
+    /// address 0
+    const SHARED_MEMORY_NOTIFIER: &str = r#"
+        (module
+          (import "waiter" "memory" (memory 1 1 shared))
+          (func (export "notify") (result i32)
+            (memory.atomic.notify (i32.const 0) (i32.const 1))))
+    "#;
+
+    #[test]
+    fn notify_from_linked_instance_wakes_waiter_on_another_thread() -> Result<()> {
+        use wrt_foundation::values::Value;
+
+        use crate::{
+            module::Module,
+            module_instance::ModuleInstance,
+            stackless::StacklessEngine,
+        };
+
+        fn instantiate(wat: &str, instance_id: usize) -> Result<Arc<ModuleInstance>> {
+            let wasm = wat::parse_str(wat).unwrap();
+            let decoded = wrt_decoder::decoder::decode_module(&wasm)?;
+            let module = Arc::new(*Module::from_wrt_module(&decoded)?);
+            let instance = Arc::new(ModuleInstance::new(module, instance_id)?);
+            instance.populate_memories_from_module()?;
+            Ok(instance)
+        }
+
+        let waiter_instance = instantiate(SHARED_MEMORY_WAITER, 0)?;
+        let notifier_instance = instantiate(SHARED_MEMORY_NOTIFIER, 1)?;
+        // Link the import the way instantiation applies memory imports
+        let memory = waiter_instance.memory_by_name("memory")?;
+        notifier_instance.set_memory(0, memory.clone())?;
+
+        let waiter = thread::spawn(move || -> Result<Vec<Value>> {
+            let mut engine = StacklessEngine::new();
+            let id = engine.set_current_module(waiter_instance)?;
+            engine.execute(id, 0, Vec::new())
+        });
+        while memory.0.waiters.waiter_count(0) == 0 {
+            thread::yield_now();
+        }
+
+        let notifier = thread::spawn(move || -> Result<Vec<Value>> {
+            let mut engine = StacklessEngine::new();
+            let id = engine.set_current_module(notifier_instance)?;
+            engine.execute(id, 0, Vec::new())
+        });
+        assert_eq!(notifier.join().unwrap()?, vec![Value::I32(1)]);
+        assert_eq!(waiter.join().unwrap()?, vec![Value::I32(WAIT_OK)]);
+        assert_eq!(memory.0.waiters.waiter_count(0), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+//! WASI capability audit log
+//!
+//! With a [`WasiAuditConfig`] enabled, every capability check made while a
+//! WASI call is dispatched is recorded with the interface and operation
+//! called, the decision and the capability rule that produced it. Entries go
+//! into a bounded [`WasiAuditLog`]: when it is full the oldest entry is
+//! dropped and counted, so a long-running component cannot exhaust memory
+//! through the log.
+//!
+//! The log is installed globally when a
+//! [`WasiDispatcher`](crate::WasiDispatcher) or
+//! [`ComponentModelProvider`](crate::ComponentModelProvider) is created, and
+//! read back with [`audit_log`] or [`take_audit_log`] for a security review.
+//! Without `std` nothing is recorded.
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Mutex,
+    },
+};
+
+use crate::prelude::*;
+
+/// Default number of entries kept by the audit log
+pub const DEFAULT_AUDIT_CAPACITY: usize = 1024;
+
+/// Configuration of the capability audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasiAuditConfig {
+    /// Record capability checks
+    pub enabled:  bool,
+    /// Maximum number of entries kept, older entries are dropped
+    pub capacity: usize,
+}
+
+impl WasiAuditConfig {
+    /// No auditing
+    pub const fn disabled() -> Self {
+        Self {
+            enabled:  false,
+            capacity: DEFAULT_AUDIT_CAPACITY,
+        }
+    }
+
+    /// Record capability checks, keeping the latest `capacity` entries
+    pub const fn with_capacity(capacity: usize) -> Self {
+        Self {
+            enabled: true,
+            capacity,
+        }
+    }
+}
+
+impl Default for WasiAuditConfig {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// Outcome of a capability check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditDecision {
+    /// The capability was granted
+    Allowed,
+    /// The capability was refused and the call failed
+    Denied,
+}
+
+impl AuditDecision {
+    /// Lower-case name used in exports
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allowed => "allowed",
+            Self::Denied => "denied",
+        }
+    }
+}
+
+/// One recorded capability check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Position of the check since the log was installed
+    pub sequence:  u64,
+    /// Interface called, without version (e.g. `wasi:filesystem/types`)
+    pub interface: String,
+    /// Function called on the interface
+    pub operation: String,
+    /// Outcome of the check
+    pub decision:  AuditDecision,
+    /// Capability rule that decided (e.g. `filesystem.write_access`)
+    pub reason:    &'static str,
+}
+
+/// Bounded log of capability checks
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasiAuditLog {
+    entries:       VecDeque<AuditEntry>,
+    capacity:      usize,
+    next_sequence: u64,
+    dropped:       u64,
+}
+
+#[cfg(feature = "std")]
+impl WasiAuditLog {
+    /// Create an empty log keeping at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            next_sequence: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Record a check, dropping the oldest entry if the log is full
+    pub fn record(
+        &mut self,
+        interface: &str,
+        operation: &str,
+        decision: AuditDecision,
+        reason: &'static str,
+    ) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+        self.entries.push_back(AuditEntry {
+            sequence,
+            interface: interface.to_string(),
+            operation: operation.to_string(),
+            decision,
+            reason,
+        });
+    }
+
+    /// Entries kept, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter()
+    }
+
+    /// Denied checks kept, oldest first
+    pub fn denied(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter().filter(|entry| entry.decision == AuditDecision::Denied)
+    }
+
+    /// Number of entries kept
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no entry is kept
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Maximum number of entries kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries dropped because the log was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Render the log as a JSON document
+    ///
+    /// The document has a `dropped` count and an `entries` array of objects
+    /// with `sequence`, `interface`, `operation`, `decision` and `reason`.
+    pub fn to_json(&self) -> String {
+        let mut out = format!("{{\"dropped\":{},\"entries\":[", self.dropped);
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("{{\"sequence\":{},\"interface\":", entry.sequence));
+            push_json_string(&mut out, &entry.interface);
+            out.push_str(",\"operation\":");
+            push_json_string(&mut out, &entry.operation);
+            out.push_str(",\"decision\":");
+            push_json_string(&mut out, entry.decision.as_str());
+            out.push_str(",\"reason\":");
+            push_json_string(&mut out, entry.reason);
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+/// Append `value` as a JSON string literal
+#[cfg(feature = "std")]
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Whether an audit log is installed, checked before taking the lock
+#[cfg(feature = "std")]
+static AUDIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Installed audit log, `None` when auditing is disabled
+#[cfg(feature = "std")]
+static AUDIT_LOG: Mutex<Option<WasiAuditLog>> = Mutex::new(None);
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// Interface and operation of the WASI call being dispatched
+    static CURRENT_CALL: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Install the audit configuration used by the WASI operations
+///
+/// Replaces any previously installed log. A disabled configuration removes
+/// the log.
+///
+/// # Errors
+///
+/// Returns an error if the audit log lock is poisoned.
+#[cfg(feature = "std")]
+pub fn install_audit(config: &WasiAuditConfig) -> Result<()> {
+    let mut log = AUDIT_LOG
+        .lock()
+        .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire audit log lock"))?;
+    *log = config.enabled.then(|| WasiAuditLog::new(config.capacity));
+    AUDIT_ENABLED.store(config.enabled, Ordering::Release);
+    Ok(())
+}
+
+/// Copy of the installed audit log, if any
+#[cfg(feature = "std")]
+pub fn audit_log() -> Option<WasiAuditLog> {
+    AUDIT_LOG.lock().ok()?.clone()
+}
+
+/// Take the entries of the installed audit log, leaving it empty
+///
+/// The returned log carries the entries and drop count so far; the installed
+/// log keeps its capacity and continues the sequence numbers.
+#[cfg(feature = "std")]
+pub fn take_audit_log() -> Option<WasiAuditLog> {
+    let mut guard = AUDIT_LOG.lock().ok()?;
+    let log = guard.as_mut()?;
+    let mut taken = WasiAuditLog::new(log.capacity);
+    taken.next_sequence = log.next_sequence;
+    core::mem::swap(&mut taken.entries, &mut log.entries);
+    core::mem::swap(&mut taken.dropped, &mut log.dropped);
+    Some(taken)
+}
+
+/// Marks the WASI call being dispatched until dropped
+#[must_use]
+pub(crate) struct CallScope {
+    #[cfg(feature = "std")]
+    previous: Option<Option<(String, String)>>,
+}
+
+impl Drop for CallScope {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if let Some(previous) = self.previous.take() {
+            CURRENT_CALL.with(|call| *call.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Attribute the capability checks made until the scope is dropped to
+/// `interface` and `operation`
+pub(crate) fn enter_call(interface: &str, operation: &str) -> CallScope {
+    #[cfg(feature = "std")]
+    {
+        let previous = AUDIT_ENABLED.load(Ordering::Acquire).then(|| {
+            CURRENT_CALL.with(|call| {
+                call.borrow_mut().replace((interface.to_string(), operation.to_string()))
+            })
+        });
+        CallScope { previous }
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = (interface, operation);
+        CallScope {}
+    }
+}
+
+/// Record a capability check of the current call and return `allowed`
+///
+/// `reason` names the capability rule that was checked. Checks made outside
+/// a dispatched call are attributed to an empty interface and operation.
+pub(crate) fn check(allowed: bool, reason: &'static str) -> bool {
+    #[cfg(feature = "std")]
+    if AUDIT_ENABLED.load(Ordering::Acquire) {
+        let decision = if allowed { AuditDecision::Allowed } else { AuditDecision::Denied };
+        CURRENT_CALL.with(|call| {
+            let call = call.borrow();
+            let (interface, operation) =
+                call.as_ref().map_or(("", ""), |(i, o)| (i.as_str(), o.as_str()));
+            if let Ok(mut log) = AUDIT_LOG.lock() {
+                if let Some(log) = log.as_mut() {
+                    log.record(interface, operation, decision, reason);
+                }
+            }
+        });
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = reason;
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_log_is_bounded_and_exports_json() {
+        let mut log = WasiAuditLog::new(2);
+        let secure = "random.secure_random";
+        log.record("wasi:random/random", "get-random-u64", AuditDecision::Allowed, secure);
+        log.record("wasi:filesystem/types", "write", AuditDecision::Denied, "filesystem.write_access");
+        log.record("wasi:clocks/wall-clock", "now", AuditDecision::Denied, "clocks.realtime_access");
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.dropped(), 1);
+        assert_eq!(log.entries().next().map(|e| e.sequence), Some(1));
+        assert_eq!(log.denied().count(), 2);
+
+        let json = log.to_json();
+        assert!(json.starts_with("{\"dropped\":1,\"entries\":[{\"sequence\":1,"));
+        assert!(json.contains(
+            "\"interface\":\"wasi:clocks/wall-clock\",\"operation\":\"now\",\"decision\":\"denied\",\
+             \"reason\":\"clocks.realtime_access\""
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_checks_are_attributed_to_the_current_call() -> Result<()> {
+        install_audit(&WasiAuditConfig::with_capacity(16))?;
+        {
+            let _call = enter_call("wasi:filesystem/types", "write");
+            assert!(!check(false, "filesystem.write_access"));
+        }
+        assert!(check(true, "clocks.monotonic_access"));
+
+        let log = take_audit_log().unwrap();
+        let entries: Vec<_> = log.entries().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].operation, "write");
+        assert_eq!(entries[0].decision, AuditDecision::Denied);
+        assert_eq!(entries[1].interface, "");
+        assert!(audit_log().is_some_and(|log| log.is_empty()));
+
+        install_audit(&WasiAuditConfig::disabled())?;
+        assert!(!check(false, "filesystem.write_access"));
+        assert!(audit_log().is_none());
+        Ok(())
+    }
+}
@@ -15,10 +15,18 @@
 };
 
 use crate::{
+    audit::WasiAuditConfig,
+    determinism::WasiDeterminismConfig,
+    observe::WasiObserveConfig,
     prelude::*,
     WASI_CRATE_ID,
 };
 
+#[cfg(feature = "std")]
+use crate::preopens::{
+    Preopen,
+    MAX_PREOPENS,
+};
 #[cfg(feature = "wasi-sockets")]
 use crate::preview2::sockets::WasiSocketCapabilities;
 
@@ -87,6 +95,13 @@ pub struct WasiCapabilities {
     /// Socket capabilities (TCP/UDP network operations)
     #[cfg(feature = "wasi-sockets")]
     pub sockets:     WasiSocketCapabilities,
+    /// Deterministic execution (virtual clock, seeded random, ordered
+    /// filesystem); disabled in every preset
+    pub determinism: WasiDeterminismConfig,
+    /// Audit log of capability checks; disabled in every preset
+    pub audit:       WasiAuditConfig,
+    /// Metrics published by guests; disabled in every preset
+    pub observe:     WasiObserveConfig,
 }
 
 impl WasiCapabilities {
@@ -108,6 +123,9 @@ pub fn minimal() -> Result<Self> {
             nn: WasiNeuralNetworkCapabilities::minimal()?,
             #[cfg(feature = "wasi-sockets")]
             sockets: WasiSocketCapabilities::none(),
+            determinism: WasiDeterminismConfig::disabled(),
+            audit: WasiAuditConfig::disabled(),
+            observe: WasiObserveConfig::disabled(),
         })
     }
 
@@ -129,6 +147,9 @@ pub fn sandboxed() -> Result<Self> {
             nn: WasiNeuralNetworkCapabilities::sandboxed()?,
             #[cfg(feature = "wasi-sockets")]
             sockets: WasiSocketCapabilities::none(),
+            determinism: WasiDeterminismConfig::disabled(),
+            audit: WasiAuditConfig::disabled(),
+            observe: WasiObserveConfig::disabled(),
         })
     }
 
@@ -150,6 +171,9 @@ pub fn system_utility() -> Result<Self> {
             nn: WasiNeuralNetworkCapabilities::full_access()?,
             #[cfg(feature = "wasi-sockets")]
             sockets: WasiSocketCapabilities::localhost_only(),
+            determinism: WasiDeterminismConfig::disabled(),
+            audit: WasiAuditConfig::disabled(),
+            observe: WasiObserveConfig::disabled(),
         })
     }
 }
@@ -170,6 +194,9 @@ pub struct WasiFileSystemCapabilities {
         MAX_FILESYSTEM_PATHS,
         PathProvider,
     >,
+    /// Host directories mapped into the guest filesystem
+    #[cfg(feature = "std")]
+    preopens: Vec<Preopen>,
     /// Allow read operations
     pub read_access:      bool,
     /// Allow write operations
@@ -191,6 +218,7 @@ pub fn minimal() -> Result<Self> {
         {
             Ok(Self {
                 allowed_paths:    Vec::new(),
+                preopens:         Vec::new(),
                 read_access:      false,
                 write_access:     false,
                 directory_access: false,
@@ -220,6 +248,7 @@ pub fn read_only() -> Result<Self> {
         {
             Ok(Self {
                 allowed_paths:    Vec::new(),
+                preopens:         Vec::new(),
                 read_access:      true,
                 write_access:     false,
                 directory_access: true,
@@ -249,6 +278,7 @@ pub fn full_access() -> Result<Self> {
         {
             Ok(Self {
                 allowed_paths:    Vec::new(),
+                preopens:         Vec::new(),
                 read_access:      true,
                 write_access:     true,
                 directory_access: true,
@@ -308,6 +338,42 @@ pub fn add_allowed_path(&mut self, path: &str) -> Result<()> {
         }
     }
 
+    /// Map a host directory into the guest filesystem
+    ///
+    /// The preopen is resolved against the host filesystem when the
+    /// capabilities are used, so the host directory need not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The guest path exceeds the maximum length (256 characters)
+    /// - The guest path is already preopened
+    /// - The maximum number of preopens (32) has been reached
+    #[cfg(feature = "std")]
+    pub fn add_preopen(&mut self, preopen: Preopen) -> Result<()> {
+        if preopen.guest_path().len() > MAX_PATH_LENGTH {
+            return Err(Error::runtime_execution_error("Path too long"));
+        }
+        if self.preopens.iter().any(|p| p.guest_path() == preopen.guest_path()) {
+            return Err(Error::wasi_invalid_argument("Guest path is already preopened"));
+        }
+        if self.preopens.len() >= MAX_PREOPENS {
+            return Err(Error::new(
+                ErrorCategory::Resource,
+                codes::WASI_RESOURCE_LIMIT,
+                "Too many preopened directories",
+            ));
+        }
+        self.preopens.push(preopen);
+        Ok(())
+    }
+
+    /// Host directories mapped into the guest filesystem
+    #[cfg(feature = "std")]
+    pub fn preopens(&self) -> &[Preopen] {
+        &self.preopens
+    }
+
     /// Check if a path is allowed
     pub fn is_path_allowed(&self, path: &str) -> bool {
         if self.allowed_paths.is_empty() {
@@ -621,6 +687,10 @@ pub fn full_access() -> Self {
     }
 }
 
+/// Maximum number of registered neural network backends
+#[cfg(feature = "wasi-nn")]
+pub const MAX_NN_BACKENDS: usize = 8;
+
 /// Neural network capabilities (preview-agnostic)
 #[cfg(feature = "wasi-nn")]
 #[derive(Debug, Clone, PartialEq)]
@@ -635,6 +705,9 @@ pub struct WasiNeuralNetworkCapabilities {
     pub require_model_approval: bool,
     /// Verification level for NN operations
     pub verification_level:     wrt_foundation::verification::VerificationLevel,
+    /// Inference backends; for each encoding the first backend supporting
+    /// it is used
+    pub backends:               Vec<crate::nn::NnBackendRef>,
 }
 
 #[cfg(feature = "wasi-nn")]
@@ -647,6 +720,7 @@ pub fn minimal() -> Result<Self> {
             max_tensor_memory:      0,
             require_model_approval: true,
             verification_level:     wrt_foundation::verification::VerificationLevel::Standard,
+            backends:               Vec::new(),
         })
     }
 
@@ -658,6 +732,7 @@ pub fn sandboxed() -> Result<Self> {
             max_tensor_memory:      5 * 1024 * 1024,  // 5MB
             require_model_approval: false,
             verification_level:     wrt_foundation::verification::VerificationLevel::Sampling,
+            backends:               vec![crate::nn::NnBackendRef::new(crate::nn::CpuBackend::new())],
         })
     }
 
@@ -669,9 +744,27 @@ pub fn full_access() -> Result<Self> {
             max_tensor_memory:      50 * 1024 * 1024,  // 50MB
             require_model_approval: false,
             verification_level:     wrt_foundation::verification::VerificationLevel::Standard,
+            backends:               vec![crate::nn::NnBackendRef::new(crate::nn::CpuBackend::new())],
         })
     }
 
+    /// Register an inference backend
+    ///
+    /// Backends registered earlier take precedence for the encodings they
+    /// share.
+    pub fn register_backend(&mut self, backend: crate::nn::NnBackendRef) -> Result<()> {
+        if self.backends.iter().any(|b| b.backend().name() == backend.backend().name()) {
+            return Err(Error::wasi_invalid_argument(
+                "Backend with this name already registered",
+            ));
+        }
+        if self.backends.len() >= MAX_NN_BACKENDS {
+            return Err(Error::wasi_resource_exhausted("Too many neural network backends"));
+        }
+        self.backends.push(backend);
+        Ok(())
+    }
+
     /// Create capability for specific verification level
     pub fn for_verification_level(
         level: wrt_foundation::verification::VerificationLevel,
@@ -685,6 +778,7 @@ pub fn for_verification_level(
                 max_tensor_memory:      10 * 1024 * 1024, // 10MB
                 require_model_approval: true,
                 verification_level:     level,
+                backends:               Vec::new(),
             }),
             _ => Err(Error::wasi_unsupported_operation(
                 "ASIL-C/D not supported in wrtd",
@@ -725,6 +819,25 @@ fn test_filesystem_path_management() -> Result<()> {
         Ok(())
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_filesystem_preopens() -> Result<()> {
+        use crate::preopens::PreopenMode;
+
+        MemoryInitializer::ensure_initialized()?;
+        let mut fs_caps = WasiFileSystemCapabilities::read_only()?;
+
+        fs_caps.add_preopen(Preopen::new("/srv/data", "/data", PreopenMode::ReadOnly))?;
+        fs_caps.add_preopen(Preopen::new("/tmp/scratch", "/tmp", PreopenMode::ReadWrite))?;
+        assert!(fs_caps.add_preopen(Preopen::new("/var", "/data", PreopenMode::ReadOnly)).is_err());
+
+        assert_eq!(fs_caps.preopens().len(), 2);
+        assert_eq!(fs_caps.preopens()[1].guest_path(), "/tmp");
+        assert_eq!(fs_caps.preopens()[1].mode(), PreopenMode::ReadWrite);
+
+        Ok(())
+    }
+
     #[test]
     fn test_environment_var_management() -> Result<()> {
         MemoryInitializer::ensure_initialized()?;
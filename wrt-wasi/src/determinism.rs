@@ -0,0 +1,334 @@
+//! Deterministic WASI execution
+//!
+//! A [`WasiDeterminismConfig`] removes the host-dependent inputs a component
+//! can observe through WASI, so that running the same component twice with
+//! the same inputs produces identical traces. This is what lockstep redundant
+//! channels rely on to compare their results.
+//!
+//! - **Virtual clock**: every clock read returns a virtual time that starts
+//!   at a fixed point and advances by a fixed step per read. Timers become
+//!   ready by advancing the virtual time to their deadline instead of
+//!   sleeping.
+//! - **Seeded random**: `wasi:random` (secure and insecure) draws from a
+//!   SplitMix64 generator seeded by the embedder.
+//! - **Ordered filesystem**: directory listings are sorted by name instead of
+//!   returned in host order.
+//!
+//! The configuration is installed globally when a
+//! [`WasiDispatcher`](crate::WasiDispatcher) or
+//! [`ComponentModelProvider`](crate::ComponentModelProvider) is created.
+//! Installing resets the virtual clock and the random generator, so each new
+//! dispatcher or provider starts the same sequence again.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+use crate::{
+    host_provider::resource_manager::WasiClockType,
+    prelude::*,
+};
+
+/// Default virtual wall clock start: 2024-01-01T00:00:00Z in nanoseconds
+pub const DEFAULT_WALL_CLOCK_START_NS: u64 = 1_704_067_200_000_000_000;
+
+/// Default virtual time advanced per clock read (1 microsecond)
+pub const DEFAULT_CLOCK_STEP_NS: u64 = 1_000;
+
+/// Configuration of deterministic WASI execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasiDeterminismConfig {
+    /// Replace all clocks with the virtual clock
+    pub virtual_clock:      bool,
+    /// Wall clock time at virtual time zero, in nanoseconds since the epoch
+    pub wall_clock_start:   u64,
+    /// Virtual time advanced by each clock read, in nanoseconds
+    pub clock_step:         u64,
+    /// Seed of the random generator, `None` to use the platform source
+    pub random_seed:        Option<u64>,
+    /// Return directory entries sorted by name
+    pub ordered_filesystem: bool,
+}
+
+impl WasiDeterminismConfig {
+    /// Non-deterministic execution using the host clocks and random source
+    pub const fn disabled() -> Self {
+        Self {
+            virtual_clock:      false,
+            wall_clock_start:   DEFAULT_WALL_CLOCK_START_NS,
+            clock_step:         DEFAULT_CLOCK_STEP_NS,
+            random_seed:        None,
+            ordered_filesystem: false,
+        }
+    }
+
+    /// Fully deterministic execution with the given random seed
+    pub const fn reproducible(seed: u64) -> Self {
+        Self {
+            virtual_clock:      true,
+            wall_clock_start:   DEFAULT_WALL_CLOCK_START_NS,
+            clock_step:         DEFAULT_CLOCK_STEP_NS,
+            random_seed:        Some(seed),
+            ordered_filesystem: true,
+        }
+    }
+
+    /// Use the virtual clock starting at `wall_clock_start` and advancing by
+    /// `clock_step` per read
+    #[must_use]
+    pub const fn with_virtual_clock(mut self, wall_clock_start: u64, clock_step: u64) -> Self {
+        self.virtual_clock = true;
+        self.wall_clock_start = wall_clock_start;
+        self.clock_step = clock_step;
+        self
+    }
+
+    /// Seed `wasi:random` from a fixed generator
+    #[must_use]
+    pub const fn with_random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = Some(seed);
+        self
+    }
+
+    /// Sort directory listings by name
+    #[must_use]
+    pub const fn with_ordered_filesystem(mut self, ordered: bool) -> Self {
+        self.ordered_filesystem = ordered;
+        self
+    }
+
+    /// Whether any source of non-determinism is replaced
+    pub const fn is_enabled(&self) -> bool {
+        self.virtual_clock || self.random_seed.is_some() || self.ordered_filesystem
+    }
+}
+
+impl Default for WasiDeterminismConfig {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// Virtual clock and random generator of a deterministic run
+#[derive(Debug, Clone)]
+pub struct DeterminismState {
+    config:  WasiDeterminismConfig,
+    elapsed: u64,
+    rng:     Option<u64>,
+}
+
+impl DeterminismState {
+    /// Start a run at virtual time zero with a freshly seeded generator
+    pub fn new(config: WasiDeterminismConfig) -> Self {
+        Self {
+            config,
+            elapsed: 0,
+            rng:     config.random_seed,
+        }
+    }
+
+    /// The configuration this state was created from
+    pub fn config(&self) -> &WasiDeterminismConfig {
+        &self.config
+    }
+
+    /// Read a clock, advancing the virtual time by one step
+    ///
+    /// Returns `None` when the virtual clock is disabled. The monotonic and
+    /// CPU time clocks report the elapsed virtual time, the realtime clock
+    /// adds it to the configured wall clock start.
+    pub fn clock_now(&mut self, clock: WasiClockType) -> Option<u64> {
+        if !self.config.virtual_clock {
+            return None;
+        }
+        let now = match clock {
+            WasiClockType::Realtime => self.config.wall_clock_start.saturating_add(self.elapsed),
+            WasiClockType::Monotonic
+            | WasiClockType::ProcessCpuTime
+            | WasiClockType::ThreadCpuTime => self.elapsed,
+        };
+        self.elapsed = self.elapsed.saturating_add(self.config.clock_step);
+        Some(now)
+    }
+
+    /// Whether a monotonic timer deadline has passed
+    ///
+    /// A timer that has not expired moves the virtual time forward to its
+    /// deadline, standing in for the time a real poll would have blocked.
+    /// Returns `None` when the virtual clock is disabled.
+    pub fn timer_ready(&mut self, deadline_ns: u64) -> Option<bool> {
+        if !self.config.virtual_clock {
+            return None;
+        }
+        self.elapsed = self.elapsed.max(deadline_ns);
+        Some(true)
+    }
+
+    /// Fill `buffer` from the seeded generator
+    ///
+    /// Returns `false` without touching the buffer when no seed is set.
+    pub fn fill_random(&mut self, buffer: &mut [u8]) -> bool {
+        let Some(state) = self.rng.as_mut() else {
+            return false;
+        };
+        for chunk in buffer.chunks_mut(8) {
+            let bytes = splitmix64(state).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        true
+    }
+}
+
+/// Advance a SplitMix64 generator and return its next output
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// State of the installed deterministic run, `None` when disabled
+#[cfg(feature = "std")]
+static DETERMINISM: Mutex<Option<DeterminismState>> = Mutex::new(None);
+
+/// Install the determinism configuration used by the WASI operations
+///
+/// Resets the virtual clock to time zero and reseeds the random generator.
+/// A disabled configuration restores the host clocks and random source.
+///
+/// # Errors
+///
+/// Returns an error if the determinism state lock is poisoned.
+#[cfg(feature = "std")]
+pub fn install_determinism(config: &WasiDeterminismConfig) -> Result<()> {
+    let mut state = DETERMINISM
+        .lock()
+        .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire determinism lock"))?;
+    *state = config.is_enabled().then(|| DeterminismState::new(*config));
+    Ok(())
+}
+
+/// Configuration of the installed deterministic run, if any
+#[cfg(feature = "std")]
+pub fn installed_determinism() -> Option<WasiDeterminismConfig> {
+    with_state(|state| *state.config())
+}
+
+#[cfg(feature = "std")]
+fn with_state<R>(f: impl FnOnce(&mut DeterminismState) -> R) -> Option<R> {
+    DETERMINISM.lock().ok()?.as_mut().map(f)
+}
+
+/// Virtual time of `clock`, or `None` to read the host clock
+pub(crate) fn virtual_clock_now(clock: WasiClockType) -> Option<u64> {
+    #[cfg(feature = "std")]
+    {
+        with_state(|state| state.clock_now(clock)).flatten()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = clock;
+        None
+    }
+}
+
+/// Readiness of a timer on the virtual clock, or `None` to check the host
+/// clock
+#[cfg(feature = "std")]
+pub(crate) fn virtual_timer_ready(deadline_ns: u64) -> Option<bool> {
+    with_state(|state| state.timer_ready(deadline_ns)).flatten()
+}
+
+/// `len` bytes from the seeded generator, or `None` to use the platform
+/// source
+pub(crate) fn seeded_random_bytes(len: usize) -> Option<Vec<u8>> {
+    #[cfg(feature = "std")]
+    {
+        with_state(|state| {
+            let mut buffer = vec![0u8; len];
+            state.fill_random(&mut buffer).then_some(buffer)
+        })
+        .flatten()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = len;
+        None
+    }
+}
+
+/// Whether directory listings must be sorted by name
+#[cfg(feature = "std")]
+pub(crate) fn ordered_filesystem() -> bool {
+    with_state(|state| state.config().ordered_filesystem).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_clock_advances_per_read() {
+        let config = WasiDeterminismConfig::disabled().with_virtual_clock(1_000_000, 10);
+        let mut state = DeterminismState::new(config);
+
+        assert_eq!(state.clock_now(WasiClockType::Monotonic), Some(0));
+        assert_eq!(state.clock_now(WasiClockType::Realtime), Some(1_000_010));
+        assert_eq!(state.clock_now(WasiClockType::Monotonic), Some(20));
+
+        // An expired timer leaves the time alone, a pending one jumps to it
+        assert_eq!(state.timer_ready(5), Some(true));
+        assert_eq!(state.clock_now(WasiClockType::Monotonic), Some(30));
+        assert_eq!(state.timer_ready(500), Some(true));
+        assert_eq!(state.clock_now(WasiClockType::Monotonic), Some(500));
+
+        let mut host = DeterminismState::new(WasiDeterminismConfig::disabled());
+        assert_eq!(host.clock_now(WasiClockType::Monotonic), None);
+        assert_eq!(host.timer_ready(500), None);
+    }
+
+    #[test]
+    fn test_seeded_random_is_reproducible() {
+        let mut first = DeterminismState::new(WasiDeterminismConfig::reproducible(42));
+        let mut second = DeterminismState::new(WasiDeterminismConfig::reproducible(42));
+        let mut other = DeterminismState::new(WasiDeterminismConfig::reproducible(43));
+
+        let mut a = [0u8; 13];
+        let mut b = [0u8; 13];
+        let mut c = [0u8; 13];
+        assert!(first.fill_random(&mut a));
+        assert!(second.fill_random(&mut b));
+        assert!(other.fill_random(&mut c));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        // The sequence continues rather than restarting
+        assert!(first.fill_random(&mut a));
+        assert_ne!(a, b);
+
+        let mut unseeded = DeterminismState::new(WasiDeterminismConfig::disabled());
+        assert!(!unseeded.fill_random(&mut a));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_install_resets_the_run() -> Result<()> {
+        let config = WasiDeterminismConfig::reproducible(7);
+        install_determinism(&config)?;
+        let first = (virtual_clock_now(WasiClockType::Monotonic), seeded_random_bytes(8));
+        let _ = virtual_clock_now(WasiClockType::Monotonic);
+
+        install_determinism(&config)?;
+        let second = (virtual_clock_now(WasiClockType::Monotonic), seeded_random_bytes(8));
+        assert_eq!(first, second);
+        assert_eq!(installed_determinism(), Some(config));
+        assert!(ordered_filesystem());
+
+        install_determinism(&WasiDeterminismConfig::disabled())?;
+        assert_eq!(installed_determinism(), None);
+        assert_eq!(virtual_clock_now(WasiClockType::Monotonic), None);
+        Ok(())
+    }
+}
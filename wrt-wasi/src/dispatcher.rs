@@ -33,9 +33,12 @@
 use alloc::string::String;
 
 use crate::{
+    audit,
     capabilities::WasiCapabilities,
     host_provider::resource_manager::{WasiResourceManager, WasiResourceType},
+    observe,
     prelude::*,
+    stdio::{self, StdStream, StdioSink, StdioSource, StreamTarget},
     Value,
 };
 #[cfg(feature = "std")]
@@ -125,9 +128,9 @@ pub fn set_global_wasi_env(env: Vec<(String, String)>) {
 
 #[cfg(feature = "wasi-io")]
 use crate::preview2::io::{
-    wasi_stream_write,
-    wasi_stream_flush,
-    wasi_stream_check_write,
+    extract_read_length,
+    extract_stream_handle,
+    extract_write_data,
     wasi_poll_one_off,
     wasi_drop_pollable,
 };
@@ -154,7 +157,9 @@ pub fn set_global_wasi_env(env: Vec<(String, String)>) {
     wasi_udp_bind,
     wasi_udp_send,
     wasi_udp_recv,
+    wasi_socket_drop,
     wasi_resolve_addresses,
+    init_socket_table,
 };
 
 #[cfg(feature = "wasi-random")]
@@ -169,6 +174,13 @@ pub fn set_global_wasi_env(env: Vec<(String, String)>) {
 use std::collections::HashMap;
 #[cfg(feature = "std")]
 use std::path::PathBuf;
+#[cfg(feature = "std")]
+use crate::preopens::{
+    Preopen,
+    PreopenAccess,
+    PreopenMode,
+    PreopenTable,
+};
 
 /// Type of file descriptor in the WASI filesystem
 #[cfg(feature = "std")]
@@ -196,6 +208,8 @@ pub struct FileDescriptorEntry {
     pub read: bool,
     /// Whether this descriptor can be written to
     pub write: bool,
+    /// Index of the preopen this descriptor lies in, if any
+    pub preopen: Option<usize>,
 }
 
 /// WASI Dispatcher - unified entry point for all WASI function calls
@@ -219,6 +233,15 @@ pub struct WasiDispatcher {
     /// Pre-opened directories (list of (handle, path) pairs)
     #[cfg(feature = "std")]
     preopens: Vec<(u32, PathBuf)>,
+    /// Guest path mapping, modes and quotas of the pre-opened directories
+    #[cfg(feature = "std")]
+    preopen_table: PreopenTable,
+    /// Namespace of the metrics published through `wasi:observe/metrics`
+    #[cfg(feature = "std")]
+    metrics_namespace: String,
+    /// Host streams behind stream resources (resource handle -> stream handle)
+    #[cfg(feature = "std")]
+    host_streams: HashMap<u32, u32>,
 }
 
 /// Describes memory that needs to be allocated via `cabi_realloc`
@@ -253,9 +276,16 @@ pub enum DispatchResult {
 impl WasiDispatcher {
     /// Create a new WASI dispatcher with the given capabilities
     ///
+    /// The preopens declared in the filesystem capabilities are opened as
+    /// directory descriptors, the socket capabilities are installed for the
+    /// `wasi:sockets` operations, and the determinism configuration is
+    /// installed (restarting its virtual clock and random sequence).
+    ///
     /// # Errors
     ///
-    /// Returns an error if the resource manager cannot be initialized.
+    /// Returns an error if the resource manager cannot be initialized, a
+    /// preopened host directory does not exist, or the determinism
+    /// configuration cannot be installed.
     pub fn new(capabilities: WasiCapabilities) -> Result<Self> {
         #[cfg(feature = "std")]
         let mut fd_table = HashMap::new();
@@ -267,20 +297,39 @@ pub fn new(capabilities: WasiCapabilities) -> Result<Self> {
                 fd_type: FileDescriptorType::Stdin,
                 read: true,
                 write: false,
+                preopen: None,
             });
             fd_table.insert(1, FileDescriptorEntry {
                 fd_type: FileDescriptorType::Stdout,
                 read: false,
                 write: true,
+                preopen: None,
             });
             fd_table.insert(2, FileDescriptorEntry {
                 fd_type: FileDescriptorType::Stderr,
                 read: false,
                 write: true,
+                preopen: None,
             });
         }
 
-        Ok(Self {
+        #[cfg(feature = "std")]
+        let declared_preopens = capabilities.filesystem.preopens().to_vec();
+
+        #[cfg(all(feature = "wasi-sockets", feature = "std"))]
+        init_socket_table(capabilities.sockets.clone())?;
+
+        #[cfg(feature = "std")]
+        crate::determinism::install_determinism(&capabilities.determinism)?;
+
+        #[cfg(feature = "std")]
+        audit::install_audit(&capabilities.audit)?;
+
+        #[cfg(feature = "std")]
+        observe::install_namespace(observe::DEFAULT_METRICS_NAMESPACE, &capabilities.observe)?;
+
+        #[allow(unused_mut)]
+        let mut dispatcher = Self {
             capabilities,
             resource_manager: WasiResourceManager::new()?,
             args: Vec::new(),
@@ -290,7 +339,20 @@ pub fn new(capabilities: WasiCapabilities) -> Result<Self> {
             fd_table,
             #[cfg(feature = "std")]
             preopens: Vec::new(),
-        })
+            #[cfg(feature = "std")]
+            preopen_table: PreopenTable::new(),
+            #[cfg(feature = "std")]
+            metrics_namespace: observe::DEFAULT_METRICS_NAMESPACE.to_string(),
+            #[cfg(feature = "std")]
+            host_streams: HashMap::new(),
+        };
+
+        #[cfg(feature = "std")]
+        for preopen in declared_preopens {
+            dispatcher.add_preopen_with(preopen)?;
+        }
+
+        Ok(dispatcher)
     }
 
     /// Create a dispatcher with default (full) capabilities
@@ -342,33 +404,77 @@ pub fn with_args_and_env(
 
     /// Add a pre-opened directory to the WASI sandbox
     ///
+    /// The guest sees the directory under its host path, read-write if the
+    /// filesystem capabilities grant write access.
+    ///
     /// Returns the file descriptor handle for the directory
     ///
     /// # Errors
     ///
-    /// Returns an error if the directory handle cannot be created in the
-    /// resource manager.
+    /// Returns an error if the directory does not exist or its handle cannot
+    /// be created in the resource manager.
     #[cfg(feature = "std")]
     pub fn add_preopen(&mut self, path: impl Into<PathBuf>) -> Result<u32> {
         let path = path.into();
-        let path_str = path.to_string_lossy();
+        let guest_path = path.to_string_lossy().to_string();
+        let mode = if self.capabilities.filesystem.write_access {
+            PreopenMode::ReadWrite
+        } else {
+            PreopenMode::ReadOnly
+        };
+        self.add_preopen_with(Preopen::new(path, guest_path, mode))
+    }
+
+    /// Add a pre-opened directory with its own guest path, mode and quotas
+    ///
+    /// Returns the file descriptor handle for the directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory does not exist, its guest path is
+    /// invalid or already preopened, or its handle cannot be created in the
+    /// resource manager.
+    #[cfg(feature = "std")]
+    pub fn add_preopen_with(&mut self, preopen: Preopen) -> Result<u32> {
+        let index = self.preopen_table.insert(preopen, self.capabilities.filesystem.write_access)?;
+        let root = self.preopen_table.host_root(index)
+            .ok_or_else(|| Error::wasi_invalid_fd("Unknown preopened directory"))?
+            .to_path_buf();
 
         // Use resource manager to allocate handle (Preview2 style)
-        let handle = self.resource_manager.create_directory_handle(&path_str)?;
+        let handle = self.resource_manager.create_directory_handle(&root.to_string_lossy())?;
 
         // Also add to fd_table for filesystem operations
         self.fd_table.insert(handle, FileDescriptorEntry {
-            fd_type: FileDescriptorType::PreopenDirectory(path.clone()),
+            fd_type: FileDescriptorType::PreopenDirectory(root.clone()),
             read: self.capabilities.filesystem.read_access,
-            write: self.capabilities.filesystem.write_access,
+            write: self.preopen_table.is_writable(index),
+            preopen: Some(index),
         });
 
         // Add to preopens list
-        self.preopens.push((handle, path));
+        self.preopens.push((handle, root));
 
         Ok(handle)
     }
 
+    /// Guest path mapping, modes and usage of the pre-opened directories
+    #[cfg(feature = "std")]
+    pub fn preopen_table(&self) -> &PreopenTable {
+        &self.preopen_table
+    }
+
+    /// Index of the preopen behind a directory descriptor
+    #[cfg(feature = "std")]
+    fn base_preopen(&self, handle: u32) -> Result<usize> {
+        let entry = self.fd_table.get(&handle)
+            .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
+        match (&entry.fd_type, entry.preopen) {
+            (FileDescriptorType::PreopenDirectory(_), Some(index)) => Ok(index),
+            _ => Err(Error::wasi_invalid_argument("Not a directory descriptor")),
+        }
+    }
+
     /// Get the list of pre-opened directories
     #[cfg(feature = "std")]
     pub fn get_preopens(&self) -> &[(u32, PathBuf)] {
@@ -421,6 +527,148 @@ pub fn args(&self) -> &[String] {
         &self.args
     }
 
+    /// Publish the guest metrics under `namespace`, normally the component
+    /// name, with the quota of the observe capabilities
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metric registry lock is poisoned.
+    #[cfg(feature = "std")]
+    pub fn set_metrics_namespace(&mut self, namespace: impl Into<String>) -> Result<()> {
+        let namespace = namespace.into();
+        observe::install_namespace(&namespace, &self.capabilities.observe)?;
+        self.metrics_namespace = namespace;
+        Ok(())
+    }
+
+    /// Namespace of the guest metrics
+    #[cfg(feature = "std")]
+    pub fn metrics_namespace(&self) -> &str {
+        &self.metrics_namespace
+    }
+
+    /// Attach a host output stream, such as an adapter of
+    /// [`stream_adapters`](crate::stream_adapters), returning the stream
+    /// resource handle the component writes it through
+    ///
+    /// Attaching grants the component access to the stream, independent of
+    /// the stdio capabilities. The stream is closed when the component drops
+    /// the resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be registered or the resource
+    /// cannot be created.
+    pub fn attach_output_stream(&mut self, sink: impl StdioSink + 'static) -> Result<u32> {
+        let stream = stdio::register_output_stream(sink)?;
+        self.attach_stream(stream, false)
+    }
+
+    /// Attach a host input stream, returning the stream resource handle the
+    /// component reads it through
+    ///
+    /// Attaching grants the component access to the stream, independent of
+    /// the stdio capabilities. The stream is closed when the component drops
+    /// the resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be registered or the resource
+    /// cannot be created.
+    pub fn attach_input_stream(&mut self, source: impl StdioSource + 'static) -> Result<u32> {
+        let stream = stdio::register_input_stream(source)?;
+        self.attach_stream(stream, true)
+    }
+
+    /// Create the stream resource of a registered host stream
+    #[cfg(feature = "std")]
+    fn attach_stream(&mut self, stream: u32, input: bool) -> Result<u32> {
+        let created = if input {
+            self.resource_manager.create_input_stream("host")
+        } else {
+            self.resource_manager.create_output_stream("host")
+        };
+        let handle = match created {
+            Ok(handle) => handle,
+            Err(error) => {
+                stdio::close_stream(stream)?;
+                return Err(error);
+            },
+        };
+        self.host_streams.insert(handle, stream);
+        Ok(handle)
+    }
+
+    /// Stream resources are the fixed stream handles without `std`, so a
+    /// host stream is used through its own handle
+    #[cfg(not(feature = "std"))]
+    fn attach_stream(&mut self, stream: u32, _input: bool) -> Result<u32> {
+        Ok(stream)
+    }
+
+    /// Close the host stream behind a dropped stream resource
+    #[cfg(feature = "std")]
+    fn close_host_stream(&mut self, handle: u32) -> Result<()> {
+        match self.host_streams.remove(&handle) {
+            Some(stream) => stdio::close_stream(stream),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the host stream behind a dropped stream resource
+    #[cfg(not(feature = "std"))]
+    fn close_host_stream(&mut self, handle: u32) -> Result<()> {
+        match StreamTarget::from_handle(handle) {
+            StreamTarget::Host(stream) => stdio::close_stream(stream),
+            StreamTarget::Std(_) => Ok(()),
+        }
+    }
+
+    /// Stream behind a stream resource handle
+    #[cfg(feature = "std")]
+    fn stream_target(&self, handle: u32) -> Result<StreamTarget> {
+        if let Some(&stream) = self.host_streams.get(&handle) {
+            return Ok(StreamTarget::Host(stream));
+        }
+        let resource = self.resource_manager.get_resource(handle)?;
+        let name = match resource.resource_type() {
+            WasiResourceType::InputStream { name, .. }
+            | WasiResourceType::OutputStream { name, .. } => name,
+            _ => return Err(Error::wasi_invalid_fd("Handle is not a stream")),
+        };
+        let name = name.as_str().map_err(|_| Error::wasi_invalid_fd("Invalid stream name"))?;
+        StdStream::from_name(name)
+            .map(StreamTarget::Std)
+            .ok_or_else(|| Error::wasi_invalid_fd("Unknown stream"))
+    }
+
+    /// Stream behind a stream resource handle
+    ///
+    /// Stream resources carry no name without `std`, so the fixed handles
+    /// 0, 1 and 2 are the standard streams and the others host streams.
+    #[cfg(not(feature = "std"))]
+    fn stream_target(&self, handle: u32) -> Result<StreamTarget> {
+        Ok(StreamTarget::from_handle(handle))
+    }
+
+    /// Whether the capabilities allow access to `stream`
+    ///
+    /// Attached host streams are always accessible.
+    fn stream_access(&self, stream: StreamTarget) -> bool {
+        let StreamTarget::Std(stream) = stream else {
+            return true;
+        };
+        match stream {
+            StdStream::Stdin => audit::check(self.capabilities.io.stdin_access, "io.stdin_access"),
+            StdStream::Stdout => {
+                audit::check(self.capabilities.io.stdout_access, "io.stdout_access")
+            },
+            StdStream::Stderr => {
+                audit::check(self.capabilities.io.stderr_access, "io.stderr_access")
+            },
+        }
+    }
+
     /// Strip version suffix from interface name
     /// e.g., "wasi:clocks/wall-clock@0.2.4" -> "wasi:clocks/wall-clock"
     fn strip_version(interface: &str) -> &str {
@@ -451,6 +699,7 @@ pub fn dispatch(
         args: &[Value],
     ) -> Result<Vec<Value>> {
         let base_interface = Self::strip_version(interface);
+        let _call = audit::enter_call(base_interface, function);
 
         #[cfg(feature = "tracing")]
         trace!(interface = %base_interface, function = %function, arg_count = args.len(), "WASI dispatch");
@@ -569,7 +818,7 @@ pub fn dispatch(
             #[cfg(feature = "wasi-clocks")]
             ("wasi:clocks/wall-clock", "now") => {
                 // Check capability
-                if !self.capabilities.clocks.realtime_access {
+                if !audit::check(self.capabilities.clocks.realtime_access, "clocks.realtime_access") {
                     return Err(Error::wasi_permission_denied("Wall clock access denied"));
                 }
                 wasi_wall_clock_now(&mut (), args)
@@ -577,7 +826,7 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-clocks")]
             ("wasi:clocks/wall-clock", "resolution") => {
-                if !self.capabilities.clocks.realtime_access {
+                if !audit::check(self.capabilities.clocks.realtime_access, "clocks.realtime_access") {
                     return Err(Error::wasi_permission_denied("Wall clock access denied"));
                 }
                 wasi_wall_clock_resolution(&mut (), args)
@@ -585,7 +834,7 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-clocks")]
             ("wasi:clocks/monotonic-clock", "now") => {
-                if !self.capabilities.clocks.monotonic_access {
+                if !audit::check(self.capabilities.clocks.monotonic_access, "clocks.monotonic_access") {
                     return Err(Error::wasi_permission_denied("Monotonic clock access denied"));
                 }
                 wasi_monotonic_clock_now(&mut (), args)
@@ -593,7 +842,7 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-clocks")]
             ("wasi:clocks/monotonic-clock", "resolution") => {
-                if !self.capabilities.clocks.monotonic_access {
+                if !audit::check(self.capabilities.clocks.monotonic_access, "clocks.monotonic_access") {
                     return Err(Error::wasi_permission_denied("Monotonic clock access denied"));
                 }
                 wasi_monotonic_clock_resolution(&mut (), args)
@@ -605,28 +854,63 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-io")]
             ("wasi:io/streams", "[method]output-stream.blocking-write-and-flush" | "output-stream.blocking-write-and-flush") => {
-                if !self.capabilities.io.stdout_access {
+                // Args: [handle, list<u8>]
+                let stream = self.stream_target(extract_stream_handle(args)?)?;
+                if !self.stream_access(stream) {
                     return Err(Error::wasi_permission_denied("Stream write access denied"));
                 }
+                let data = extract_write_data(args, 1)?;
+                stdio::write_all(stream, &data)?;
+                stdio::flush(stream)?;
+                Ok(vec![Value::U64(data.len() as u64)])
+            }
 
-                // Args: [handle, list<u8>]
-                // The wasi_stream_write expects args to already be in the right format
-                wasi_stream_write(&mut (), args)
+            #[cfg(feature = "wasi-io")]
+            ("wasi:io/streams", "[method]output-stream.write" | "output-stream.write") => {
+                // Args: [handle, list<u8>], may write only a prefix of the data
+                let stream = self.stream_target(extract_stream_handle(args)?)?;
+                if !self.stream_access(stream) {
+                    return Err(Error::wasi_permission_denied("Stream write access denied"));
+                }
+                let data = extract_write_data(args, 1)?;
+                let written = stdio::write(stream, &data)?;
+                Ok(vec![Value::U64(written as u64)])
             }
 
             #[cfg(feature = "wasi-io")]
             ("wasi:io/streams", "[method]output-stream.blocking-flush" | "output-stream.blocking-flush") => {
-                wasi_stream_flush(&mut (), args)
+                stdio::flush(self.stream_target(extract_stream_handle(args)?)?)?;
+                Ok(vec![])
             }
 
             #[cfg(feature = "wasi-io")]
             ("wasi:io/streams", "[method]output-stream.check-write" | "output-stream.check-write") => {
-                wasi_stream_check_write(&mut (), args)
+                let available = stdio::check_write(self.stream_target(extract_stream_handle(args)?)?)?;
+                Ok(vec![Value::U64(available as u64)])
             }
 
-            // Resource drops - no-op, just consume the handle
-            ("wasi:io/streams", "[resource-drop]output-stream" | "[resource-drop]input-stream")
-            | ("wasi:io/error", "[resource-drop]error") => {
+            #[cfg(feature = "wasi-io")]
+            ("wasi:io/streams", "[method]input-stream.read" | "input-stream.read"
+                | "[method]input-stream.blocking-read" | "input-stream.blocking-read") => {
+                // Args: [handle, len]
+                let stream = self.stream_target(extract_stream_handle(args)?)?;
+                if !self.stream_access(stream) {
+                    return Err(Error::wasi_permission_denied("Stream read access denied"));
+                }
+                let len = extract_read_length(args, 1)?;
+                let data = stdio::read(stream, len.min(usize::MAX as u64) as usize)?
+                    .ok_or_else(|| Error::wasi_invalid_fd("Input stream closed"))?;
+                Ok(vec![Value::List(data.into_iter().map(Value::U8).collect())])
+            }
+
+            // Resource drops close host streams, the standard streams stay open
+            ("wasi:io/streams", "[resource-drop]output-stream" | "[resource-drop]input-stream") => {
+                #[cfg(feature = "wasi-io")]
+                self.close_host_stream(extract_stream_handle(args)?)?;
+                Ok(vec![])
+            }
+
+            ("wasi:io/error", "[resource-drop]error") => {
                 Ok(vec![])
             }
 
@@ -689,18 +973,20 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/preopens", "get-directories") => {
                 // Check filesystem capability
-                if !self.capabilities.filesystem.directory_access {
+                if !audit::check(self.capabilities.filesystem.directory_access, "filesystem.directory_access") {
                     return Err(Error::wasi_permission_denied("Filesystem access denied"));
                 }
 
-                // Return list of (descriptor, path) tuples
+                // Return list of (descriptor, guest path) tuples
                 let dirs: Vec<Value> = self.preopens
                     .iter()
-                    .map(|(handle, path)| {
-                        Value::Tuple(vec![
+                    .filter_map(|(handle, _)| {
+                        let index = self.fd_table.get(handle)?.preopen?;
+                        let preopen = self.preopen_table.preopen(index)?;
+                        Some(Value::Tuple(vec![
                             Value::U32(*handle),
-                            Value::String(path.to_string_lossy().to_string()),
-                        ])
+                            Value::String(preopen.guest_path().to_string()),
+                        ]))
                     })
                     .collect();
 
@@ -712,7 +998,9 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.open-at") => {
                 // Check filesystem capability
-                if !self.capabilities.filesystem.read_access && !self.capabilities.filesystem.write_access {
+                let filesystem = &self.capabilities.filesystem;
+                let allowed = filesystem.read_access || filesystem.write_access;
+                if !audit::check(allowed, "filesystem.read_access|write_access") {
                     return Err(Error::wasi_permission_denied("Filesystem access denied"));
                 }
 
@@ -728,50 +1016,42 @@ pub fn dispatch(
                     _ => return Err(Error::wasi_invalid_argument("Invalid path")),
                 };
 
-                // Look up the base descriptor
-                let base_entry = self.fd_table.get(&base_handle)
-                    .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
-
-                // Get the base path
-                let base_path = match &base_entry.fd_type {
-                    FileDescriptorType::PreopenDirectory(p) => p.clone(),
-                    _ => return Err(Error::wasi_invalid_argument("Not a directory descriptor")),
-                };
-
-                // Construct full path
-                let full_path = base_path.join(&path);
+                // Resolve the path inside the base descriptor's preopen,
+                // rejecting escapes via `..`, absolute paths and symlinks
+                let preopen = self.base_preopen(base_handle)?;
+                let full_path = self.preopen_table
+                    .resolve_at(preopen, &path, PreopenAccess::Read)?
+                    .host_path;
+                let writable = self.capabilities.filesystem.write_access
+                    && self.preopen_table.is_writable(preopen);
 
-                // Check path is within sandbox (no escape via ..)
-                let canonical = match full_path.canonicalize() {
-                    Ok(p) => p,
-                    Err(_) => {
-                        // File might not exist yet for write operations
-                        full_path.clone()
-                    }
-                };
-
-                // Basic safety check - path should start with base
-                if !canonical.starts_with(&base_path) && full_path.canonicalize().is_ok() {
-                    return Err(Error::wasi_permission_denied("Path escapes sandbox"));
-                }
+                // Count the file against the preopen's open file quota
+                self.preopen_table.open_file(preopen)?;
 
                 // Allocate new handle via resource manager (Preview2 semantics)
                 let path_str = full_path.to_string_lossy();
-                let new_handle = self.resource_manager.create_file_descriptor(
+                let new_handle = match self.resource_manager.create_file_descriptor(
                     &path_str,
                     self.capabilities.filesystem.read_access,
-                    self.capabilities.filesystem.write_access,
-                )?;
+                    writable,
+                ) {
+                    Ok(handle) => handle,
+                    Err(e) => {
+                        self.preopen_table.close_file(preopen);
+                        return Err(e);
+                    }
+                };
 
                 // Also add to fd_table for filesystem operations
                 self.fd_table.insert(new_handle, FileDescriptorEntry {
                     fd_type: FileDescriptorType::RegularFile(full_path.clone()),
                     read: self.capabilities.filesystem.read_access,
-                    write: self.capabilities.filesystem.write_access,
+                    write: writable,
+                    preopen: Some(preopen),
                 });
 
                 #[cfg(feature = "tracing")]
-                trace!(base_path = %base_path.display(), path = %path, handle = new_handle, "open-at completed");
+                trace!(host_path = %full_path.display(), path = %path, handle = new_handle, "open-at completed");
 
                 // Return result<descriptor, error-code> - for now just Ok(handle)
                 Ok(vec![Value::Result(Ok(Box::new(Value::U32(new_handle))))])
@@ -780,7 +1060,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.stat") => {
                 // Check filesystem capability
-                if !self.capabilities.filesystem.metadata_access {
+                if !audit::check(self.capabilities.filesystem.metadata_access, "filesystem.metadata_access") {
                     return Err(Error::wasi_permission_denied("Metadata access denied"));
                 }
 
@@ -831,7 +1111,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.read-via-stream") => {
                 // Check read capability
-                if !self.capabilities.filesystem.read_access {
+                if !audit::check(self.capabilities.filesystem.read_access, "filesystem.read_access") {
                     return Err(Error::wasi_permission_denied("Read access denied"));
                 }
 
@@ -845,7 +1125,7 @@ pub fn dispatch(
                 let entry = self.fd_table.get(&handle)
                     .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
 
-                if !entry.read {
+                if !audit::check(entry.read, "descriptor.read") {
                     return Err(Error::wasi_permission_denied("Descriptor not readable"));
                 }
 
@@ -860,7 +1140,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.write-via-stream") => {
                 // Check write capability
-                if !self.capabilities.filesystem.write_access {
+                if !audit::check(self.capabilities.filesystem.write_access, "filesystem.write_access") {
                     return Err(Error::wasi_permission_denied("Write access denied"));
                 }
 
@@ -874,7 +1154,7 @@ pub fn dispatch(
                 let entry = self.fd_table.get(&handle)
                     .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
 
-                if !entry.write {
+                if !audit::check(entry.write, "descriptor.write") {
                     return Err(Error::wasi_permission_denied("Descriptor not writable"));
                 }
 
@@ -888,7 +1168,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.readdir") => {
                 // Check directory access capability
-                if !self.capabilities.filesystem.directory_access {
+                if !audit::check(self.capabilities.filesystem.directory_access, "filesystem.directory_access") {
                     return Err(Error::wasi_permission_denied("Directory access denied"));
                 }
 
@@ -908,8 +1188,13 @@ pub fn dispatch(
 
                 match std::fs::read_dir(path) {
                     Ok(entries) => {
+                        let mut entries: Vec<_> =
+                            entries.filter_map(std::result::Result::ok).collect();
+                        if crate::determinism::ordered_filesystem() {
+                            entries.sort_by_key(std::fs::DirEntry::file_name);
+                        }
                         let dir_entries: Vec<Value> = entries
-                            .filter_map(std::result::Result::ok)
+                            .into_iter()
                             .map(|e| {
                                 let name = e.file_name().to_string_lossy().to_string();
                                 let file_type = if e.path().is_dir() { 3u8 } else { 6u8 };
@@ -931,7 +1216,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.create-directory-at") => {
                 // Check write capability
-                if !self.capabilities.filesystem.write_access {
+                if !audit::check(self.capabilities.filesystem.write_access, "filesystem.write_access") {
                     return Err(Error::wasi_permission_denied("Write access denied"));
                 }
 
@@ -946,20 +1231,11 @@ pub fn dispatch(
                     _ => return Err(Error::wasi_invalid_argument("Invalid path")),
                 };
 
-                let base_entry = self.fd_table.get(&base_handle)
-                    .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
-
-                let base_path = match &base_entry.fd_type {
-                    FileDescriptorType::PreopenDirectory(p) => p.clone(),
-                    _ => return Err(Error::wasi_invalid_argument("Not a directory descriptor")),
-                };
-
-                let full_path = base_path.join(&path);
-
-                // Sandbox check
-                if full_path.canonicalize().is_ok() && !full_path.starts_with(&base_path) {
-                    return Err(Error::wasi_permission_denied("Path escapes sandbox"));
-                }
+                // Sandbox check: the path must stay inside a writable preopen
+                let preopen = self.base_preopen(base_handle)?;
+                let full_path = self.preopen_table
+                    .resolve_at(preopen, &path, PreopenAccess::Write)?
+                    .host_path;
 
                 match std::fs::create_dir(&full_path) {
                     Ok(()) => Ok(vec![Value::Result(Ok(Box::new(Value::Tuple(vec![]))))]),
@@ -970,7 +1246,7 @@ pub fn dispatch(
             #[cfg(all(feature = "wasi-filesystem", feature = "std"))]
             ("wasi:filesystem/types", "[method]descriptor.unlink-file-at") => {
                 // Check write capability
-                if !self.capabilities.filesystem.write_access {
+                if !audit::check(self.capabilities.filesystem.write_access, "filesystem.write_access") {
                     return Err(Error::wasi_permission_denied("Write access denied"));
                 }
 
@@ -985,22 +1261,11 @@ pub fn dispatch(
                     _ => return Err(Error::wasi_invalid_argument("Invalid path")),
                 };
 
-                let base_entry = self.fd_table.get(&base_handle)
-                    .ok_or_else(|| Error::wasi_invalid_fd("Bad descriptor"))?;
-
-                let base_path = match &base_entry.fd_type {
-                    FileDescriptorType::PreopenDirectory(p) => p.clone(),
-                    _ => return Err(Error::wasi_invalid_argument("Not a directory descriptor")),
-                };
-
-                let full_path = base_path.join(&path);
-
-                // Sandbox check
-                if let Ok(canonical) = full_path.canonicalize() {
-                    if !canonical.starts_with(&base_path) {
-                        return Err(Error::wasi_permission_denied("Path escapes sandbox"));
-                    }
-                }
+                // Sandbox check: the path must stay inside a writable preopen
+                let preopen = self.base_preopen(base_handle)?;
+                let full_path = self.preopen_table
+                    .resolve_at(preopen, &path, PreopenAccess::Write)?
+                    .host_path;
 
                 match std::fs::remove_file(&full_path) {
                     Ok(()) => Ok(vec![Value::Result(Ok(Box::new(Value::Tuple(vec![]))))]),
@@ -1012,7 +1277,11 @@ pub fn dispatch(
             ("wasi:filesystem/types", "[resource-drop]descriptor") => {
                 // Remove descriptor from table
                 if let Some(Value::U32(handle)) = args.first() {
-                    self.fd_table.remove(handle);
+                    if let Some(entry) = self.fd_table.remove(handle) {
+                        if let (FileDescriptorType::RegularFile(_), Some(preopen)) = (&entry.fd_type, entry.preopen) {
+                            self.preopen_table.close_file(preopen);
+                        }
+                    }
                 }
                 Ok(vec![])
             }
@@ -1081,6 +1350,12 @@ pub fn dispatch(
                 wasi_udp_recv(&mut () as &mut dyn core::any::Any, args)
             }
 
+            #[cfg(all(feature = "wasi-sockets", feature = "std"))]
+            ("wasi:sockets/tcp", "[resource-drop]tcp-socket")
+            | ("wasi:sockets/udp", "[resource-drop]udp-socket") => {
+                wasi_socket_drop(&mut () as &mut dyn core::any::Any, args)
+            }
+
             #[cfg(all(feature = "wasi-sockets", feature = "std"))]
             ("wasi:sockets/ip-name-lookup", "resolve-addresses") => {
                 wasi_resolve_addresses(&mut () as &mut dyn core::any::Any, args)
@@ -1093,7 +1368,7 @@ pub fn dispatch(
             #[cfg(feature = "wasi-random")]
             ("wasi:random/random", "get-random-bytes") => {
                 // Check random capability
-                if !self.capabilities.random.secure_random {
+                if !audit::check(self.capabilities.random.secure_random, "random.secure_random") {
                     return Err(Error::wasi_permission_denied("Secure random access denied"));
                 }
                 wasi_get_random_bytes(&mut () as &mut dyn core::any::Any, args)
@@ -1101,7 +1376,7 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-random")]
             ("wasi:random/random", "get-random-u64") => {
-                if !self.capabilities.random.secure_random {
+                if !audit::check(self.capabilities.random.secure_random, "random.secure_random") {
                     return Err(Error::wasi_permission_denied("Secure random access denied"));
                 }
                 wasi_get_random_u64(&mut () as &mut dyn core::any::Any, args)
@@ -1109,7 +1384,7 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-random")]
             ("wasi:random/insecure", "get-insecure-random-bytes") => {
-                if !self.capabilities.random.pseudo_random {
+                if !audit::check(self.capabilities.random.pseudo_random, "random.pseudo_random") {
                     return Err(Error::wasi_permission_denied("Pseudo-random access denied"));
                 }
                 wasi_get_insecure_random_bytes(&mut () as &mut dyn core::any::Any, args)
@@ -1117,12 +1392,30 @@ pub fn dispatch(
 
             #[cfg(feature = "wasi-random")]
             ("wasi:random/insecure", "get-insecure-random-u64") => {
-                if !self.capabilities.random.pseudo_random {
+                if !audit::check(self.capabilities.random.pseudo_random, "random.pseudo_random") {
                     return Err(Error::wasi_permission_denied("Pseudo-random access denied"));
                 }
                 wasi_get_insecure_random_u64(&mut () as &mut dyn core::any::Any, args)
             }
 
+            // ================================================================
+            // wasi:observe/metrics - Guest metrics
+            // ================================================================
+
+            #[cfg(feature = "std")]
+            ("wasi:observe/metrics", "counter-add" | "gauge-set") => {
+                if !audit::check(self.capabilities.observe.enabled, "observe.enabled") {
+                    return Err(Error::wasi_permission_denied("Guest metrics denied"));
+                }
+                let (name, value) = match args {
+                    [Value::String(name), Value::U64(delta)] => (name, *delta),
+                    [Value::String(name), Value::S64(value)] => (name, *value as u64),
+                    _ => return Err(Error::wasi_invalid_argument("Expected metric name and value")),
+                };
+                observe::call(&self.metrics_namespace, function, name, value)?;
+                Ok(vec![])
+            }
+
             // ================================================================
             // Unknown function - return error
             // ================================================================
@@ -1170,6 +1463,7 @@ pub fn dispatch_core(
         use wrt_foundation::values::Value as CoreValue;
 
         let base_interface = Self::strip_version(interface);
+        let _call = audit::enter_call(base_interface, function);
 
         // DEBUG: trace WASI calls
         eprintln!("[WASI-TRACE] {}::{} args={:?}", base_interface, function, args);
@@ -1411,16 +1705,19 @@ pub fn dispatch_core(
             ("wasi:io/streams", "[method]output-stream.check-write" | "output-stream.check-write") => {
                 // check-write(self) -> result<u64, stream-error>
                 // Returns number of bytes that can be written without blocking
-                // Return large value to indicate ready for writes
                 // Args: handle, retptr
+                let available = match args.first() {
+                    Some(CoreValue::I32(h)) => stdio::check_write(self.stream_target(*h as u32)?)?,
+                    _ => return Err(Error::wasi_invalid_argument("Invalid handle type")),
+                } as u64;
                 if let Some(mem) = memory {
                     let retptr = match args.get(1) {
                         Some(CoreValue::I32(p)) => *p as u32,
-                        _ => return Ok(vec![CoreValue::I64(65536)]),
+                        _ => return Ok(vec![CoreValue::I64(available as i64)]),
                     };
                     // Write success discriminant (0) and capacity
                     mem.write_bytes(retptr, &0u32.to_le_bytes())?;
-                    mem.write_bytes(retptr + 8, &65536u64.to_le_bytes())?;
+                    mem.write_bytes(retptr + 8, &available.to_le_bytes())?;
                 }
                 Ok(vec![])
             }
@@ -1436,7 +1733,7 @@ pub fn dispatch_core(
             ("wasi:clocks/wall-clock", "now") => {
                 use wrt_platform::time::PlatformTime;
 
-                if !self.capabilities.clocks.realtime_access {
+                if !audit::check(self.capabilities.clocks.realtime_access, "clocks.realtime_access") {
                     return Err(Error::wasi_permission_denied("Wall clock access denied"));
                 }
 
@@ -1524,7 +1821,7 @@ pub fn dispatch_core(
             ("wasi:clocks/monotonic-clock", "now") => {
                 use wrt_platform::time::PlatformTime;
 
-                if !self.capabilities.clocks.monotonic_access {
+                if !audit::check(self.capabilities.clocks.monotonic_access, "clocks.monotonic_access") {
                     return Err(Error::wasi_permission_denied("Monotonic clock access denied"));
                 }
 
@@ -1538,7 +1835,7 @@ pub fn dispatch_core(
                 #[cfg(feature = "tracing")]
                 trace!(args = ?args, has_memory = memory.is_some(), "blocking-write-and-flush dispatch");
 
-                if !self.capabilities.io.stdout_access {
+                if !audit::check(self.capabilities.io.stdout_access, "io.stdout_access") {
                     return Err(Error::wasi_permission_denied("Stream write access denied"));
                 }
 
@@ -1601,40 +1898,15 @@ pub fn dispatch_core(
                 // Preview2: Look up resource by handle - NO FALLBACK
                 #[cfg(feature = "std")]
                 {
-                    use std::io::{self, Write};
-
-                    // Look up the resource in the resource manager
-                    let resource = self.resource_manager.get_resource(handle)?;
-
-                    let result = match resource.resource_type() {
-                        WasiResourceType::OutputStream { name, .. } => {
-                            let name_str = name.as_str().map_err(|_|
-                                Error::wasi_invalid_fd("Invalid stream name"))?;
-
-                            match name_str {
-                                "stdout" => {
-                                    #[cfg(feature = "tracing")]
-                                    trace!("writing to stdout (handle {})", handle);
-                                    io::stdout().write_all(&data).and_then(|_| io::stdout().flush())
-                                }
-                                "stderr" => {
-                                    #[cfg(feature = "tracing")]
-                                    trace!("writing to stderr (handle {})", handle);
-                                    io::stderr().write_all(&data).and_then(|_| io::stderr().flush())
-                                }
-                                _other => {
-                                    #[cfg(feature = "tracing")]
-                                    warn!(handle = handle, name = _other, "unknown output stream name");
-                                    return Err(Error::wasi_invalid_fd("Unknown output stream"));
-                                }
-                            }
-                        }
-                        _ => {
-                            #[cfg(feature = "tracing")]
-                            warn!(handle = handle, "handle is not an output stream");
-                            return Err(Error::wasi_invalid_fd("Handle is not an output stream"));
-                        }
-                    };
+                    let stream = self.stream_target(handle)?;
+                    if stream == StreamTarget::Std(StdStream::Stdin) {
+                        #[cfg(feature = "tracing")]
+                        warn!(handle = handle, "handle is not an output stream");
+                        return Err(Error::wasi_invalid_fd("Handle is not an output stream"));
+                    }
+                    #[cfg(feature = "tracing")]
+                    trace!("writing to {} (handle {})", stream.as_str(), handle);
+                    let result = stdio::write_all(stream, &data).and_then(|()| stdio::flush(stream));
 
                     // Write result to retptr if provided
                     // For result<_, stream-error>:
@@ -1731,24 +2003,11 @@ pub fn dispatch_core(
                     mem.read_bytes(data_ptr, &mut data)?;
 
                     // Look up resource and write
-                    let resource = self.resource_manager.get_resource(handle)?;
-                    let bytes_written = match resource.resource_type() {
-                        crate::host_provider::resource_manager::WasiResourceType::OutputStream { name, .. } => {
-                            let name_str = name.as_str().unwrap_or("unknown");
-                            match name_str {
-                                "stdout" => {
-                                    use std::io::Write;
-                                    std::io::stdout().write(&data).unwrap_or(0)
-                                }
-                                "stderr" => {
-                                    use std::io::Write;
-                                    std::io::stderr().write(&data).unwrap_or(0)
-                                }
-                                _ => data.len(), // Treat as sink
-                            }
-                        }
-                        _ => return Err(Error::wasi_invalid_fd("Handle is not an output stream")),
-                    };
+                    let stream = self.stream_target(handle)?;
+                    if stream == StreamTarget::Std(StdStream::Stdin) {
+                        return Err(Error::wasi_invalid_fd("Handle is not an output stream"));
+                    }
+                    let bytes_written = stdio::write(stream, &data)?;
 
                     // Write result<u64, stream-error> to retptr
                     if let Some(rp) = retptr {
@@ -1774,12 +2033,17 @@ pub fn dispatch_core(
 
             #[cfg(feature = "wasi-io")]
             ("wasi:io/streams", "[method]output-stream.blocking-flush" | "output-stream.blocking-flush") => {
-                #[cfg(feature = "std")]
-                {
-                    use std::io::{self, Write};
-                    // Flush operations - intentionally ignore errors
-                    drop(io::stdout().flush());
-                    drop(io::stderr().flush());
+                // Flush operations - intentionally ignore errors
+                match args.first() {
+                    Some(CoreValue::I32(h)) => {
+                        if let Ok(stream) = self.stream_target(*h as u32) {
+                            let _ = stdio::flush(stream);
+                        }
+                    },
+                    _ => {
+                        let _ = stdio::flush(StdStream::Stdout);
+                        let _ = stdio::flush(StdStream::Stderr);
+                    },
                 }
                 Ok(vec![CoreValue::I32(0)])
             }
@@ -1795,6 +2059,7 @@ pub fn dispatch_core(
                 // Remove from resource manager (Preview2 semantics)
                 // Intentionally ignore if resource was already removed
                 drop(self.resource_manager.remove_resource(handle));
+                self.close_host_stream(handle)?;
                 Ok(vec![])
             }
 
@@ -2014,7 +2279,7 @@ pub fn dispatch_core(
                 use wrt_platform::random::PlatformRandom;
 
                 // Check random capability
-                if !self.capabilities.random.secure_random {
+                if !audit::check(self.capabilities.random.secure_random, "random.secure_random") {
                     return Err(Error::wasi_permission_denied("Secure random access denied"));
                 }
 
@@ -2071,7 +2336,7 @@ pub fn dispatch_core(
             ("wasi:random/random", "get-random-u64") => {
                 use wrt_platform::random::PlatformRandom;
 
-                if !self.capabilities.random.secure_random {
+                if !audit::check(self.capabilities.random.secure_random, "random.secure_random") {
                     return Err(Error::wasi_permission_denied("Secure random access denied"));
                 }
 
@@ -2084,6 +2349,30 @@ pub fn dispatch_core(
                 Ok(vec![CoreValue::I64(val as i64)])
             }
 
+            ("wasi:observe/metrics", "counter-add" | "gauge-set") => {
+                if !audit::check(self.capabilities.observe.enabled, "observe.enabled") {
+                    return Err(Error::wasi_permission_denied("Guest metrics denied"));
+                }
+
+                // Args: (name_ptr: i32, name_len: i32, value: i64)
+                let (name_ptr, name_len, value) = match args {
+                    [CoreValue::I32(ptr), CoreValue::I32(len), CoreValue::I64(value)] => {
+                        (*ptr as u32, *len as u32, *value as u64)
+                    }
+                    _ => return Err(Error::wasi_invalid_argument("Expected metric name and value")),
+                };
+                let mem = memory.ok_or_else(||
+                    Error::wasi_capability_unavailable("Memory required for metric name"))?;
+
+                // Names beyond the quota are rejected without being read
+                let max_name_len = self.capabilities.observe.max_name_len;
+                let mut name = vec![0u8; (name_len as usize).min(max_name_len.saturating_add(1))];
+                mem.read_bytes(name_ptr, &mut name)?;
+                let name = String::from_utf8_lossy(&name);
+                observe::call(&self.metrics_namespace, function, &name, value)?;
+                Ok(vec![])
+            }
+
             _ => {
                 #[cfg(feature = "tracing")]
                 warn!(interface = %base_interface, function = %function, "unknown WASI function (core)");
@@ -2116,6 +2405,7 @@ pub fn dispatch_core_v2(
         use wrt_foundation::values::Value as CoreValue;
 
         let base_interface = Self::strip_version(interface);
+        let _call = audit::enter_call(base_interface, function);
 
         match (base_interface, function) {
             // Simple functions that complete immediately
@@ -2423,7 +2713,7 @@ fn test_get_stdout_dynamic_handle() -> Result<()> {
         let mut dispatcher = WasiDispatcher::with_defaults()?;
 
         // First call to get-stdout allocates a resource handle
-        let result1 = dispatcher.dispatch("wasi:cli/stdout@0.2.4", "get-stdout", vec![])?;
+        let result1 = dispatcher.dispatch("wasi:cli/stdout@0.2.4", "get-stdout", &[])?;
         assert_eq!(result1.len(), 1);
         let handle1 = match &result1[0] {
             Value::U32(h) => *h,
@@ -2431,7 +2721,7 @@ fn test_get_stdout_dynamic_handle() -> Result<()> {
         };
 
         // Second call should return a DIFFERENT handle (Preview2 semantics)
-        let result2 = dispatcher.dispatch("wasi:cli/stdout@0.2.4", "get-stdout", vec![])?;
+        let result2 = dispatcher.dispatch("wasi:cli/stdout@0.2.4", "get-stdout", &[])?;
         assert_eq!(result2.len(), 1);
         let handle2 = match &result2[0] {
             Value::U32(h) => *h,
@@ -2449,13 +2739,59 @@ fn test_get_stderr_dynamic_handle() -> Result<()> {
         let mut dispatcher = WasiDispatcher::with_defaults()?;
 
         // get-stderr allocates a resource handle
-        let result = dispatcher.dispatch("wasi:cli/stderr@0.2.4", "get-stderr", vec![])?;
+        let result = dispatcher.dispatch("wasi:cli/stderr@0.2.4", "get-stderr", &[])?;
         assert_eq!(result.len(), 1);
         // Handle should be a valid u32 (value doesn't matter, it's dynamic)
         assert!(matches!(result[0], Value::U32(_)));
         Ok(())
     }
 
+    #[cfg(all(feature = "std", feature = "wasi-io"))]
+    #[test]
+    fn test_attached_streams_get_their_own_handles() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        use crate::stdio::BoundedPipe;
+
+        MemoryInitializer::ensure_initialized()?;
+        let mut dispatcher = WasiDispatcher::with_defaults()?;
+        let first = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let second = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let input = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let first_handle = dispatcher.attach_output_stream(Arc::clone(&first))?;
+        let second_handle = dispatcher.attach_output_stream(Arc::clone(&second))?;
+        let input_handle = dispatcher.attach_input_stream(Arc::clone(&input))?;
+        assert_ne!(first_handle, second_handle);
+
+        let write = |dispatcher: &mut WasiDispatcher, handle: u32, data: &[u8]| {
+            let data = data.iter().copied().map(Value::U8).collect();
+            let args = [Value::U32(handle), Value::List(data)];
+            dispatcher.dispatch("wasi:io/streams", "[method]output-stream.write", &args)
+        };
+        let drop_stream = |dispatcher: &mut WasiDispatcher, resource: &str, handle: u32| {
+            dispatcher.dispatch("wasi:io/streams", resource, &[Value::U32(handle)])
+        };
+        write(&mut dispatcher, first_handle, b"one")?;
+        write(&mut dispatcher, second_handle, b"two!")?;
+        assert_eq!(first.lock().unwrap().len(), 3);
+        assert_eq!(second.lock().unwrap().len(), 4);
+
+        input.lock().unwrap().push(b"in");
+        let read = dispatcher.dispatch(
+            "wasi:io/streams",
+            "[method]input-stream.read",
+            &[Value::U32(input_handle), Value::U64(16)],
+        )?;
+        assert_eq!(read, vec![Value::List(vec![Value::U8(b'i'), Value::U8(b'n')])]);
+        assert!(write(&mut dispatcher, input_handle, b"no").is_err());
+
+        drop_stream(&mut dispatcher, "[resource-drop]output-stream", first_handle)?;
+        assert!(write(&mut dispatcher, first_handle, b"gone").is_err());
+        drop_stream(&mut dispatcher, "[resource-drop]output-stream", second_handle)?;
+        drop_stream(&mut dispatcher, "[resource-drop]input-stream", input_handle)?;
+        Ok(())
+    }
+
     #[test]
     fn test_strip_version() {
         assert_eq!(WasiDispatcher::strip_version("wasi:cli/stdout@0.2.4"), "wasi:cli/stdout");
@@ -2467,7 +2803,7 @@ fn test_strip_version() {
     fn test_wall_clock_now() -> Result<()> {
         MemoryInitializer::ensure_initialized()?;
         let mut dispatcher = WasiDispatcher::with_defaults()?;
-        let result = dispatcher.dispatch("wasi:clocks/wall-clock@0.2.4", "now", vec![])?;
+        let result = dispatcher.dispatch("wasi:clocks/wall-clock@0.2.4", "now", &[])?;
         assert_eq!(result.len(), 1);
         // Should return a Tuple with (seconds, nanoseconds)
         if let Value::Tuple(parts) = &result[0] {
@@ -20,12 +20,18 @@
 use wrt_host::HostFunctionHandler;
 
 use crate::{
+    audit::WasiAuditConfig,
     capabilities::WasiCapabilities,
+    determinism::WasiDeterminismConfig,
     host_provider::resource_manager::WasiResourceManager,
+    observe::WasiObserveConfig,
     prelude::*,
+    stdio::WasiStdio,
     wasi_safety_level,
     HostFunction,
 };
+#[cfg(feature = "std")]
+use crate::preopens::Preopen;
 #[cfg(not(feature = "std"))]
 type WasiHostString = BoundedString<256, wrt_foundation::safe_memory::NoStdProvider<1024>>;
 
@@ -37,68 +43,6 @@ fn make_string(s: &str) -> Result<WasiHostString> {
         .map_err(|_| Error::wasi_invalid_argument("Failed to create bounded string"))
 }
 
-/// Convert foundation Value types to `value_compat` Value types
-#[cfg(feature = "std")]
-#[allow(dead_code)]
-fn convert_foundation_values_to_compat(args: FoundationValueVec) -> Result<Vec<crate::Value>> {
-    let mut converted = Vec::new();
-    for arg in args {
-        match arg {
-            wrt_foundation::values::Value::I32(v) => converted.push(crate::Value::S32(v)),
-            wrt_foundation::values::Value::I64(v) => converted.push(crate::Value::S64(v)),
-            wrt_foundation::values::Value::F32(v) => converted.push(crate::Value::F32(v.value())),
-            wrt_foundation::values::Value::F64(v) => converted.push(crate::Value::F64(v.value())),
-            _ => {
-                return Err(Error::wasi_invalid_argument(
-                    "Unsupported value type for conversion",
-                ))
-            },
-        }
-    }
-    Ok(converted)
-}
-
-/// Convert `value_compat` Value types back to foundation Value types
-#[cfg(feature = "std")]
-#[allow(dead_code)]
-fn convert_compat_values_to_foundation(values: Vec<crate::Value>) -> Result<FoundationValueVec> {
-    let mut converted = Vec::new();
-    for value in values {
-        match value {
-            crate::Value::Bool(v) => {
-                converted.push(wrt_foundation::values::Value::I32(i32::from(v)));
-            },
-            crate::Value::U8(v) => converted.push(wrt_foundation::values::Value::I32(i32::from(v))),
-            crate::Value::U16(v) => converted.push(wrt_foundation::values::Value::I32(i32::from(v))),
-            crate::Value::U32(v) => converted.push(wrt_foundation::values::Value::I64(i64::from(v))),
-            crate::Value::U64(v) => converted.push(wrt_foundation::values::Value::I64(v as i64)),
-            crate::Value::S8(v) => converted.push(wrt_foundation::values::Value::I32(i32::from(v))),
-            crate::Value::S16(v) => converted.push(wrt_foundation::values::Value::I32(i32::from(v))),
-            crate::Value::S32(v) => converted.push(wrt_foundation::values::Value::I32(v)),
-            crate::Value::S64(v) => converted.push(wrt_foundation::values::Value::I64(v)),
-            crate::Value::F32(v) => converted.push(wrt_foundation::values::Value::F32(
-                wrt_foundation::values::FloatBits32::from_float(v),
-            )),
-            crate::Value::F64(v) => converted.push(wrt_foundation::values::Value::F64(
-                wrt_foundation::values::FloatBits64::from_float(v),
-            )),
-            crate::Value::List(_list) => {
-                // For lists, we'll need to convert to an appropriate foundation type
-                // This is a simplification - real implementation would need proper list support
-                return Err(Error::wasi_invalid_argument(
-                    "List values not yet supported in conversion",
-                ));
-            },
-            _ => {
-                return Err(Error::wasi_invalid_argument(
-                    "Unsupported value type for conversion",
-                ))
-            },
-        }
-    }
-    Ok(converted)
-}
-
 #[cfg(feature = "std")]
 fn make_string(s: &str) -> String {
     s.to_string()
@@ -264,12 +208,37 @@ impl ComponentModelProvider {
     /// Create a new component model provider with the given capabilities
     /// Allocates function cache using safety-aware allocation
     ///
+    /// The filesystem preopens of the capabilities are installed for the
+    /// `wasi:filesystem` operations, the socket capabilities for the
+    /// `wasi:sockets` operations, the neural network backends for the
+    /// `wasi:nn` operations, the determinism configuration for the clock,
+    /// random and filesystem operations, and the capability audit log.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the resource manager cannot be initialized.
+    /// Returns an error if the resource manager cannot be initialized, a
+    /// preopened host directory does not exist, or a neural network backend
+    /// or the determinism or audit configuration cannot be installed.
     pub fn new(capabilities: WasiCapabilities) -> Result<Self> {
         let resource_manager = WasiResourceManager::new()?;
 
+        #[cfg(all(feature = "std", feature = "wasi-filesystem"))]
+        crate::preview2::filesystem::install_preopens(&capabilities.filesystem)?;
+
+        #[cfg(all(feature = "std", feature = "wasi-sockets"))]
+        crate::preview2::sockets::init_socket_table(capabilities.sockets.clone())?;
+
+        #[cfg(all(feature = "std", feature = "wasi-nn"))]
+        if capabilities.nn.dynamic_loading {
+            crate::nn::install_capabilities(&capabilities.nn)?;
+        }
+
+        #[cfg(feature = "std")]
+        crate::determinism::install_determinism(&capabilities.determinism)?;
+
+        #[cfg(feature = "std")]
+        crate::audit::install_audit(&capabilities.audit)?;
+
         // Initialize function cache (None = not built yet)
         #[cfg(feature = "std")]
         let cached_functions = None;
@@ -664,6 +633,10 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             wasi_nn_load,
             wasi_nn_set_input,
         };
+        use wrt_foundation::value_interop::{
+            values_from,
+            values_into,
+        };
 
         let mut functions = Vec::new();
 
@@ -673,13 +646,13 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             handler:     HostFunctionHandler::new_with_args(
                 |target: &mut dyn Any, args: FoundationValueVec| {
                     // Convert wrt_foundation::Value to crate::Value
-                    let converted_args = convert_foundation_values_to_compat(args)?;
+                    let converted_args = values_from(args)?;
 
                     // Call the actual function
                     let result = wasi_nn_load(target, converted_args)?;
 
                     // Convert back from crate::Value to wrt_foundation::Value
-                    convert_compat_values_to_foundation(result)
+                    Ok(values_into(result))
                 },
             ),
             extern_type: ExternType::Function {
@@ -694,13 +667,13 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             handler:     HostFunctionHandler::new_with_args(
                 |target: &mut dyn Any, args: FoundationValueVec| {
                     // Convert wrt_foundation::Value to crate::Value
-                    let converted_args = convert_foundation_values_to_compat(args)?;
+                    let converted_args = values_from(args)?;
 
                     // Call the actual function
                     let result = wasi_nn_init_execution_context(target, converted_args)?;
 
                     // Convert back from crate::Value to wrt_foundation::Value
-                    convert_compat_values_to_foundation(result)
+                    Ok(values_into(result))
                 },
             ),
             extern_type: ExternType::Function {
@@ -715,13 +688,13 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             handler:     HostFunctionHandler::new_with_args(
                 |target: &mut dyn Any, args: FoundationValueVec| {
                     // Convert wrt_foundation::Value to crate::Value
-                    let converted_args = convert_foundation_values_to_compat(args)?;
+                    let converted_args = values_from(args)?;
 
                     // Call the actual function
                     let result = wasi_nn_set_input(target, converted_args)?;
 
                     // Convert back from crate::Value to wrt_foundation::Value
-                    convert_compat_values_to_foundation(result)
+                    Ok(values_into(result))
                 },
             ),
             extern_type: ExternType::Function {
@@ -736,13 +709,13 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             handler:     HostFunctionHandler::new_with_args(
                 |target: &mut dyn Any, args: FoundationValueVec| {
                     // Convert wrt_foundation::Value to crate::Value
-                    let converted_args = convert_foundation_values_to_compat(args)?;
+                    let converted_args = values_from(args)?;
 
                     // Call the actual function
                     let result = wasi_nn_compute(target, converted_args)?;
 
                     // Convert back from crate::Value to wrt_foundation::Value
-                    convert_compat_values_to_foundation(result)
+                    Ok(values_into(result))
                 },
             ),
             extern_type: ExternType::Function {
@@ -757,13 +730,13 @@ fn create_nn_functions(&self) -> Result<Vec<HostFunction>> {
             handler:     HostFunctionHandler::new_with_args(
                 |target: &mut dyn Any, args: FoundationValueVec| {
                     // Convert wrt_foundation::Value to crate::Value
-                    let converted_args = convert_foundation_values_to_compat(args)?;
+                    let converted_args = values_from(args)?;
 
                     // Call the actual function
                     let result = wasi_nn_get_output(target, converted_args)?;
 
                     // Convert back from crate::Value to wrt_foundation::Value
-                    convert_compat_values_to_foundation(result)
+                    Ok(values_into(result))
                 },
             ),
             extern_type: ExternType::Function {
@@ -822,6 +795,12 @@ fn capabilities(&self) -> &WasiCapabilities {
 pub struct WasiProviderBuilder {
     capabilities: Option<WasiCapabilities>,
     safety_level: Option<&'static str>,
+    #[cfg(feature = "std")]
+    preopens:     Vec<Preopen>,
+    determinism:  Option<WasiDeterminismConfig>,
+    audit:        Option<WasiAuditConfig>,
+    observe:      Option<WasiObserveConfig>,
+    stdio:        Option<WasiStdio>,
 }
 
 impl WasiProviderBuilder {
@@ -831,6 +810,12 @@ pub fn new() -> Self {
         Self {
             capabilities: None,
             safety_level: None,
+            #[cfg(feature = "std")]
+            preopens:     Vec::new(),
+            determinism:  None,
+            audit:        None,
+            observe:      None,
+            stdio:        None,
         }
     }
 
@@ -846,14 +831,66 @@ pub fn with_safety_level(mut self, level: &'static str) -> Self {
         self
     }
 
+    /// Map a host directory into the guest filesystem
+    ///
+    /// The preopen is added to the filesystem capabilities when the provider
+    /// is built.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn with_preopen(mut self, preopen: Preopen) -> Self {
+        self.preopens.push(preopen);
+        self
+    }
+
+    /// Run components deterministically
+    ///
+    /// Overrides the determinism configuration of the capabilities when the
+    /// provider is built.
+    #[must_use]
+    pub fn with_determinism(mut self, config: WasiDeterminismConfig) -> Self {
+        self.determinism = Some(config);
+        self
+    }
+
+    /// Record capability checks in an audit log
+    ///
+    /// Overrides the audit configuration of the capabilities when the
+    /// provider is built.
+    #[must_use]
+    pub fn with_audit(mut self, config: WasiAuditConfig) -> Self {
+        self.audit = Some(config);
+        self
+    }
+
+    /// Accept metrics published by guests through `wasi:observe/metrics`
+    ///
+    /// Overrides the observe configuration of the capabilities when the
+    /// provider is built.
+    #[must_use]
+    pub fn with_observe(mut self, config: WasiObserveConfig) -> Self {
+        self.observe = Some(config);
+        self
+    }
+
+    /// Route the standard streams of components through `stdio`
+    ///
+    /// The routing is installed when the provider is built.
+    #[must_use]
+    pub fn with_stdio(mut self, stdio: WasiStdio) -> Self {
+        self.stdio = Some(stdio);
+        self
+    }
+
     /// Build the WASI provider with safety-aware defaults
     ///
     /// # Errors
     ///
-    /// Returns an error if the capabilities cannot be created or the provider
+    /// Returns an error if the capabilities cannot be created, a preopen is
+    /// invalid, the stdio routing cannot be installed, or the provider
     /// initialization fails.
     pub fn build(self) -> Result<ComponentModelProvider> {
-        let capabilities = match self.capabilities {
+        #[allow(unused_mut)]
+        let mut capabilities = match self.capabilities {
             Some(caps) => caps,
             None => {
                 // Choose default capabilities based on safety level
@@ -865,6 +902,27 @@ pub fn build(self) -> Result<ComponentModelProvider> {
             },
         };
 
+        #[cfg(feature = "std")]
+        for preopen in self.preopens {
+            capabilities.filesystem.add_preopen(preopen)?;
+        }
+
+        if let Some(determinism) = self.determinism {
+            capabilities.determinism = determinism;
+        }
+
+        if let Some(audit) = self.audit {
+            capabilities.audit = audit;
+        }
+
+        if let Some(observe) = self.observe {
+            capabilities.observe = observe;
+        }
+
+        if let Some(stdio) = self.stdio {
+            crate::stdio::install_stdio(stdio)?;
+        }
+
         ComponentModelProvider::new(capabilities)
     }
 }
@@ -923,4 +981,31 @@ fn test_provider_builder() -> Result<()> {
 
         Ok(())
     }
+
+    #[cfg(all(feature = "std", feature = "wasi-filesystem"))]
+    #[test]
+    fn test_provider_builder_preopens() -> Result<()> {
+        use crate::preopens::PreopenMode;
+
+        let host_dir = std::env::temp_dir();
+        let provider = WasiProviderBuilder::new()
+            .with_capabilities(WasiCapabilities::sandboxed()?)
+            .with_preopen(Preopen::new(&host_dir, "/tmp", PreopenMode::ReadOnly))
+            .build()?;
+
+        let preopens = provider.capabilities().filesystem.preopens();
+        assert_eq!(preopens.len(), 1);
+        assert_eq!(preopens[0].guest_path(), "/tmp");
+        assert_eq!(preopens[0].host_dir(), host_dir.as_path());
+
+        let missing = WasiProviderBuilder::new()
+            .with_preopen(Preopen::new(host_dir.join("wrt-wasi-missing-preopen"), "/x", PreopenMode::ReadOnly))
+            .build();
+        assert!(missing.is_err());
+
+        // Leave later filesystem operations unconfined
+        crate::preview2::filesystem::install_preopens(&WasiCapabilities::minimal()?.filesystem)?;
+
+        Ok(())
+    }
 }
@@ -109,6 +109,13 @@ pub enum WasiResourceType {
         /// Whether to use a cryptographically secure random generator.
         secure: bool
     },
+    /// Network socket
+    Socket {
+        /// Transport protocol of the socket.
+        protocol: WasiSocketProtocol,
+        /// Address family the socket was created for.
+        family:   WasiAddressFamily,
+    },
 }
 
 /// WASI clock types
@@ -125,6 +132,28 @@ pub enum WasiClockType {
     ThreadCpuTime,
 }
 
+/// WASI socket protocols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WasiSocketProtocol {
+    /// TCP stream socket
+    #[default]
+    Tcp,
+    /// UDP datagram socket
+    Udp,
+}
+
+/// WASI IP address families
+///
+/// Discriminants match the `wasi:sockets/network.ip-address-family` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WasiAddressFamily {
+    /// IPv4
+    #[default]
+    Ipv4,
+    /// IPv6
+    Ipv6,
+}
+
 /// WASI resource manager
 ///
 /// Manages WASI resource handles using WRT's proven resource management
@@ -517,6 +546,28 @@ pub fn create_random_handle(&mut self, secure: bool) -> Result<WasiHandle> {
 
         self.create_resource(resource_type, capabilities)
     }
+
+    /// Create a socket resource
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resource table is full or allocation fails.
+    pub fn create_socket_handle(
+        &mut self,
+        protocol: WasiSocketProtocol,
+        family: WasiAddressFamily,
+    ) -> Result<WasiHandle> {
+        let resource_type = WasiResourceType::Socket { protocol, family };
+
+        let capabilities = WasiResourceCapabilities {
+            readable:        true,
+            writable:        true,
+            seekable:        false,
+            metadata_access: true,
+        };
+
+        self.create_resource(resource_type, capabilities)
+    }
 }
 
 impl WasiResource {
@@ -630,6 +681,10 @@ fn update_checksum(&self, checksum: &mut Checksum) {
                 checksum.update_slice(b"random");
                 checksum.update_slice(&[u8::from(*secure)]);
             },
+            WasiResourceType::Socket { protocol, family } => {
+                checksum.update_slice(b"socket");
+                checksum.update_slice(&[*protocol as u8, *family as u8]);
+            },
         }
     }
 }
@@ -682,6 +737,11 @@ fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
                 writer.write_u8(6)?;
                 writer.write_u8(u8::from(*secure))?;
             },
+            WasiResourceType::Socket { protocol, family } => {
+                writer.write_u8(7)?;
+                writer.write_u8(*protocol as u8)?;
+                writer.write_u8(*family as u8)?;
+            },
         }
         Ok(())
     }
@@ -739,6 +799,19 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
                 let secure = reader.read_u8()? != 0;
                 WasiResourceType::RandomHandle { secure }
             },
+            7 => {
+                let protocol = match reader.read_u8()? {
+                    0 => WasiSocketProtocol::Tcp,
+                    1 => WasiSocketProtocol::Udp,
+                    _ => return Err(Error::parse_error("Invalid socket protocol")),
+                };
+                let family = match reader.read_u8()? {
+                    0 => WasiAddressFamily::Ipv4,
+                    1 => WasiAddressFamily::Ipv6,
+                    _ => return Err(Error::parse_error("Invalid socket address family")),
+                };
+                WasiResourceType::Socket { protocol, family }
+            },
             _ => WasiResourceType::Null,
         };
 
@@ -860,4 +933,51 @@ fn test_clock_handle_creation() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn test_socket_handle_creation() -> Result<()> {
+        MemoryInitializer::ensure_initialized()?;
+        let mut manager = WasiResourceManager::new()?;
+
+        let tcp = manager.create_socket_handle(WasiSocketProtocol::Tcp, WasiAddressFamily::Ipv6)?;
+        let resource = manager.get_resource(tcp)?;
+        assert_eq!(resource.resource_type(), &WasiResourceType::Socket {
+            protocol: WasiSocketProtocol::Tcp,
+            family:   WasiAddressFamily::Ipv6,
+        });
+        assert!(resource.is_readable());
+        assert!(resource.is_writable());
+
+        manager.remove_resource(tcp)?;
+        assert!(!manager.is_valid_handle(tcp));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_socket_bytes_reject_unknown_protocol_and_family() -> Result<()> {
+        use wrt_foundation::{
+            safe_managed_alloc,
+            safe_memory::Slice,
+            CrateId,
+        };
+
+        MemoryInitializer::ensure_initialized()?;
+        let provider = safe_managed_alloc!(64, CrateId::Wasi)?;
+        // Capability bytes, socket discriminant, protocol, family
+        let parse = |protocol: u8, family: u8| {
+            let bytes = [1, 1, 0, 0, 7, protocol, family];
+            let mut reader = ReadStream::new(Slice::new(&bytes)?);
+            WasiResource::from_bytes_with_provider(&mut reader, &provider)
+        };
+
+        assert_eq!(parse(1, 1)?.resource_type(), &WasiResourceType::Socket {
+            protocol: WasiSocketProtocol::Udp,
+            family:   WasiAddressFamily::Ipv6,
+        });
+        assert!(parse(2, 0).is_err());
+        assert!(parse(0, 2).is_err());
+
+        Ok(())
+    }
 }
@@ -180,6 +180,26 @@ pub mod host_provider {
 // WASI capabilities and security model
 pub mod capabilities;
 
+// Capability audit log
+pub mod audit;
+
+// Deterministic execution (virtual clock, seeded random, ordered filesystem)
+pub mod determinism;
+
+// Host stdio streams (sinks, sources, interceptors)
+pub mod stdio;
+
+// Adapters between host I/O types and wasi:io streams
+#[cfg(feature = "std")]
+pub mod stream_adapters;
+
+// Guest metrics (wasi:observe/metrics)
+pub mod observe;
+
+// Filesystem preopens and sandbox path mapping
+#[cfg(feature = "std")]
+pub mod preopens;
+
 // Neural network support (preview-agnostic)
 #[cfg(feature = "wasi-nn")]
 pub mod nn;
@@ -203,6 +223,21 @@ pub mod host_provider {
 };
 #[cfg(feature = "preview2")]
 pub use host_provider::resource_manager::WasiResourceManager;
+pub use audit::WasiAuditConfig;
+pub use determinism::WasiDeterminismConfig;
+pub use observe::WasiObserveConfig;
+pub use stdio::{
+    BoundedPipe,
+    StdStream,
+    WasiStdio,
+};
+#[cfg(feature = "std")]
+pub use preopens::{
+    Preopen,
+    PreopenMode,
+    PreopenQuota,
+    PreopenTable,
+};
 
 /// WASI version enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
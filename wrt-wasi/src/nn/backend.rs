@@ -3,14 +3,26 @@
 //! This module defines the traits that neural network backends must implement
 //! to work with WASI-NN. It provides a capability-aware abstraction that allows
 //! different backends (Tract, ONNX Runtime, etc.) to be used interchangeably.
-
-use core::fmt::Debug;
+//!
+//! Backends registered at runtime implement the object-safe [`NnBackend`]
+//! trait, which follows the operations of `wasi:nn/inference`, and are listed
+//! in [`WasiNeuralNetworkCapabilities`](crate::WasiNeuralNetworkCapabilities).
+
+use core::{
+    any::Any,
+    fmt::{
+        self,
+        Debug,
+    },
+};
 use std::sync::{
+    Arc,
     Mutex,
     OnceLock,
 };
 
 use super::{
+    capabilities::create_nn_capability,
     GraphEncoding,
     NeuralNetworkCapability,
     Tensor,
@@ -117,6 +129,180 @@ fn estimate_flops(&self, model: &Self::Model) -> Option<u64> {
     }
 }
 
+/// Backend-specific state of an execution context created by an [`NnBackend`]
+pub type NnBackendContext = Box<dyn Any + Send + Sync>;
+
+/// Inference backend that can be plugged into WASI-NN at runtime
+///
+/// The operations follow `wasi:nn/inference`. Unlike
+/// [`NeuralNetworkBackend`] the trait is object safe: graphs are returned as
+/// [`ModelCapability`] objects and execution contexts as opaque
+/// [`NnBackendContext`] state that the backend downcasts to its own type.
+/// Capability checks, resource accounting and handle management are done by
+/// the caller, so a backend only implements inference.
+pub trait NnBackend: Send + Sync + Debug {
+    /// Backend name for diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Check if the backend can load graphs in `encoding`
+    fn supports_encoding(&self, encoding: GraphEncoding) -> bool;
+
+    /// Load a graph from its encoded form
+    fn load_graph(&self, data: &[u8], encoding: GraphEncoding) -> Result<Box<dyn ModelCapability>>;
+
+    /// Create the state of an execution context for a graph loaded by this
+    /// backend
+    fn init_execution_context(&self, graph: &dyn ModelCapability) -> Result<NnBackendContext>;
+
+    /// Set the input tensor at `index`
+    fn set_input(&self, context: &mut dyn Any, index: usize, tensor: &Tensor) -> Result<()>;
+
+    /// Run inference on the inputs set so far
+    fn compute(&self, context: &mut dyn Any) -> Result<()>;
+
+    /// Get the output tensor at `index` of the last inference
+    ///
+    /// The tensor is created under `capability` so its size is checked
+    /// against the caller's limits.
+    fn get_output(
+        &self,
+        context: &dyn Any,
+        index: usize,
+        capability: &dyn NeuralNetworkCapability,
+    ) -> Result<Tensor>;
+}
+
+/// Shared handle to an [`NnBackend`]
+///
+/// Handles compare equal when they refer to the same backend instance.
+#[derive(Debug, Clone)]
+pub struct NnBackendRef(Arc<dyn NnBackend>);
+
+impl NnBackendRef {
+    /// Wrap a backend
+    pub fn new(backend: impl NnBackend + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+
+    /// Wrap a backend that is already shared
+    pub fn from_arc(backend: Arc<dyn NnBackend>) -> Self {
+        Self(backend)
+    }
+
+    /// Get the backend
+    pub fn backend(&self) -> &dyn NnBackend {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for NnBackendRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// [`BackendProvider`] serving an [`NnBackend`] through the registry
+pub struct NnBackendProvider {
+    backend: NnBackendRef,
+}
+
+impl NnBackendProvider {
+    /// Create a provider for `backend`
+    pub fn new(backend: NnBackendRef) -> Self {
+        Self { backend }
+    }
+}
+
+impl BackendProvider for NnBackendProvider {
+    fn create_backend(
+        &self,
+        capability: &dyn NeuralNetworkCapability,
+    ) -> Result<Box<dyn DynBackend>> {
+        Ok(Box::new(NnDynBackend {
+            backend: self.backend.clone(),
+            level:   capability.verification_level(),
+        }))
+    }
+
+    fn supports_encoding(&self, encoding: GraphEncoding) -> bool {
+        self.backend.backend().supports_encoding(encoding)
+    }
+}
+
+/// Type-erased [`NnBackend`] for the registry
+#[derive(Debug)]
+struct NnDynBackend {
+    backend: NnBackendRef,
+    level:   super::VerificationLevel,
+}
+
+impl DynBackend for NnDynBackend {
+    fn load_model_dyn(
+        &self,
+        data: &[u8],
+        encoding: GraphEncoding,
+    ) -> Result<Box<dyn ModelCapability>> {
+        self.backend.backend().load_graph(data, encoding)
+    }
+
+    fn create_context_dyn(&self, model: &dyn ModelCapability) -> Result<Box<dyn ComputeCapable>> {
+        Ok(Box::new(NnComputeContext {
+            state:      self.backend.backend().init_execution_context(model)?,
+            capability: create_nn_capability(self.level)?,
+            backend:    self.backend.clone(),
+        }))
+    }
+
+    fn compute_dyn(
+        &self,
+        context: &mut dyn ComputeCapable,
+        inputs: &[Tensor],
+        model: &dyn ModelCapability,
+    ) -> Result<Vec<Tensor>> {
+        context.compute(inputs, model)
+    }
+
+    fn name(&self) -> &'static str {
+        self.backend.backend().name()
+    }
+}
+
+/// Execution context of an [`NnBackend`]
+struct NnComputeContext {
+    backend:    NnBackendRef,
+    state:      NnBackendContext,
+    capability: Box<dyn NeuralNetworkCapability>,
+}
+
+impl Debug for NnComputeContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NnComputeContext")
+            .field("backend", &self.backend.backend().name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ComputeCapable for NnComputeContext {
+    fn compute(&mut self, inputs: &[Tensor], model: &dyn ModelCapability) -> Result<Vec<Tensor>> {
+        let backend = self.backend.backend();
+        for (index, input) in inputs.iter().enumerate() {
+            backend.set_input(self.state.as_mut(), index, input)?;
+        }
+        backend.compute(self.state.as_mut())?;
+        (0..model.num_outputs())
+            .map(|index| backend.get_output(self.state.as_ref(), index, self.capability.as_ref()))
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 /// Backend registry for managing available backends
 pub struct BackendRegistry {
     backends: Vec<(GraphEncoding, Box<dyn BackendProvider>)>,
@@ -204,6 +390,27 @@ pub fn register(
         Ok(())
     }
 
+    /// Serve every encoding supported by `backend` with it
+    ///
+    /// Earlier registrations for these encodings are replaced.
+    pub fn register_nn_backend(&mut self, backend: &NnBackendRef) -> Result<()> {
+        let mut registered = false;
+        for encoding in GraphEncoding::ALL {
+            if !backend.backend().supports_encoding(encoding) {
+                continue;
+            }
+            self.backends.retain(|(existing, _)| *existing != encoding);
+            self.backends.push((encoding, Box::new(NnBackendProvider::new(backend.clone()))));
+            registered = true;
+        }
+        if !registered {
+            return Err(Error::wasi_invalid_argument(
+                "Backend does not support any graph encoding",
+            ));
+        }
+        Ok(())
+    }
+
     /// Get a backend for the given encoding and capability
     pub fn get_backend(
         &self,
@@ -263,6 +470,22 @@ pub fn initialize_backends() -> Result<()> {
         .map_err(|_| Error::wasi_capability_unavailable("Backend registry already initialized"))
 }
 
+/// Install the backends registered in WASI-NN capabilities
+///
+/// Each encoding is served by the first backend in `backends` supporting it,
+/// replacing any backend installed for it before. Creates the global
+/// registry if [`initialize_backends`] has not been called.
+pub fn install_nn_backends(backends: &[NnBackendRef]) -> Result<()> {
+    let mut registry = BACKEND_REGISTRY
+        .get_or_init(|| Mutex::new(BackendRegistry::new()))
+        .lock()
+        .map_err(|_| Error::wasi_runtime_error("Backend registry mutex poisoned"))?;
+    for backend in backends.iter().rev() {
+        registry.register_nn_backend(backend)?;
+    }
+    Ok(())
+}
+
 /// Get the global backend registry
 pub fn get_backend_registry() -> Result<std::sync::MutexGuard<'static, BackendRegistry>> {
     let mutex = BACKEND_REGISTRY
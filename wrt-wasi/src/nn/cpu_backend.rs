@@ -0,0 +1,1265 @@
+//! Reference CPU backend for WASI-NN
+//!
+//! [`CpuBackend`] is a small, dependency-free [`NnBackend`] that runs ONNX
+//! graphs on the CPU. It exists so components using `wasi:nn` run without a
+//! native inference library, and as an example for writing backends; it
+//! favours simple, predictable code over speed.
+//!
+//! Supported models:
+//!
+//! - `float` graph inputs and outputs with a declared rank; symbolic
+//!   dimensions are accepted and take the size of the input that is set
+//! - `float` initializers, and `int64` initializers used as shapes
+//! - Operators `Add`, `Sub`, `Mul`, `Div` (with broadcasting), `MatMul` (with
+//!   a 1-D or 2-D right operand), `Gemm`, `Relu`, `LeakyRelu`, `Sigmoid`,
+//!   `Tanh`, `Softmax`, `Flatten`, `Reshape` and `Identity`
+//!
+//! Anything else is rejected when the graph is loaded, not when it runs.
+
+// Inference is floating-point arithmetic
+#![allow(clippy::float_arithmetic)]
+
+use core::any::Any;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{
+            AtomicU32,
+            Ordering,
+        },
+        Arc,
+    },
+};
+
+use super::{
+    backend::{
+        NnBackend,
+        NnBackendContext,
+    },
+    GraphEncoding,
+    ModelCapability,
+    NeuralNetworkCapability,
+    Tensor,
+    TensorDimensions,
+    TensorType,
+};
+use crate::prelude::*;
+
+/// Maximum number of nodes in a graph
+const MAX_GRAPH_NODES: usize = 4096;
+
+/// Maximum number of graph inputs or outputs
+const MAX_GRAPH_VALUES: usize = 16;
+
+/// Maximum number of elements of any value computed during inference
+const MAX_VALUE_ELEMENTS: usize = 1 << 24;
+
+/// ONNX `TensorProto.DataType` of `float`
+const ONNX_FLOAT: u64 = 1;
+
+/// ONNX `TensorProto.DataType` of `int64`
+const ONNX_INT64: u64 = 7;
+
+/// Identifier of the next loaded model
+static NEXT_MODEL_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Reference CPU backend for ONNX graphs
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpuBackend;
+
+impl CpuBackend {
+    /// Create the backend
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl NnBackend for CpuBackend {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn supports_encoding(&self, encoding: GraphEncoding) -> bool {
+        encoding == GraphEncoding::ONNX
+    }
+
+    fn load_graph(&self, data: &[u8], encoding: GraphEncoding) -> Result<Box<dyn ModelCapability>> {
+        if !self.supports_encoding(encoding) {
+            return Err(Error::wasi_invalid_encoding(
+                "CPU backend only supports ONNX graphs",
+            ));
+        }
+        let graph = OnnxGraph::parse(data)?;
+        Ok(Box::new(CpuModel {
+            id:    NEXT_MODEL_ID.fetch_add(1, Ordering::Relaxed),
+            size:  data.len(),
+            hash:  super::sha256::sha256(data),
+            graph: Arc::new(graph),
+        }))
+    }
+
+    fn init_execution_context(&self, graph: &dyn ModelCapability) -> Result<NnBackendContext> {
+        let model = graph
+            .as_any()
+            .downcast_ref::<CpuModel>()
+            .ok_or_else(|| Error::wasi_invalid_argument("Graph was not loaded by the CPU backend"))?;
+        Ok(Box::new(CpuContext {
+            inputs:  vec![None; model.graph.inputs.len()],
+            outputs: Vec::new(),
+            graph:   Arc::clone(&model.graph),
+        }))
+    }
+
+    fn set_input(&self, context: &mut dyn Any, index: usize, tensor: &Tensor) -> Result<()> {
+        let context = CpuContext::downcast_mut(context)?;
+        let info = context
+            .graph
+            .inputs
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_argument("Invalid input index"))?;
+        if tensor.data_type() != TensorType::F32 {
+            return Err(Error::wasi_invalid_argument(
+                "CPU backend inputs must be f32 tensors",
+            ));
+        }
+
+        let dims: Vec<usize> = tensor.dimensions().as_slice().iter().map(|&d| d as usize).collect();
+        let shape_matches = dims.len() == info.dims.len()
+            && dims.iter().zip(&info.dims).all(|(dim, declared)| declared.is_none_or(|d| d == *dim));
+        if !shape_matches {
+            return Err(Error::wasi_invalid_argument(
+                "Input shape does not match the graph",
+            ));
+        }
+
+        let data = tensor
+            .as_bytes()
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        context.inputs[index] = Some(Array::new(dims, data)?);
+        Ok(())
+    }
+
+    fn compute(&self, context: &mut dyn Any) -> Result<()> {
+        let context = CpuContext::downcast_mut(context)?;
+        let inputs = context
+            .inputs
+            .iter()
+            .map(|input| input.as_ref().ok_or_else(|| Error::wasi_invalid_argument("Input not set")))
+            .collect::<Result<Vec<_>>>()?;
+        context.outputs = context.graph.run(&inputs)?;
+        Ok(())
+    }
+
+    fn get_output(
+        &self,
+        context: &dyn Any,
+        index: usize,
+        capability: &dyn NeuralNetworkCapability,
+    ) -> Result<Tensor> {
+        let context = context
+            .downcast_ref::<CpuContext>()
+            .ok_or_else(|| Error::wasi_invalid_argument("Context was not created by the CPU backend"))?;
+        let output = context
+            .outputs
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_argument("Output not computed"))?;
+
+        // WASI-NN tensors have at least one dimension; scalars become [1]
+        let dims = if output.dims.is_empty() { vec![1] } else { to_u32_dims(&output.dims)? };
+        let data = output.data.iter().flat_map(|value| value.to_le_bytes()).collect();
+        Tensor::from_data(TensorDimensions::new(&dims)?, TensorType::F32, data, capability)
+    }
+}
+
+/// Graph loaded by the [`CpuBackend`]
+#[derive(Debug)]
+pub struct CpuModel {
+    id:    u32,
+    size:  usize,
+    hash:  [u8; 32],
+    graph: Arc<OnnxGraph>,
+}
+
+impl CpuModel {
+    fn metadata(info: &ValueInfo) -> Result<(TensorDimensions, TensorType)> {
+        // Symbolic dimensions are reported as 1
+        let dims: Vec<usize> = info.dims.iter().map(|dim| dim.unwrap_or(1)).collect();
+        let dims = if dims.is_empty() { vec![1] } else { to_u32_dims(&dims)? };
+        Ok((TensorDimensions::new(&dims)?, TensorType::F32))
+    }
+}
+
+impl ModelCapability for CpuModel {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    fn input_metadata(&self, index: usize) -> Result<(TensorDimensions, TensorType)> {
+        let info = self
+            .graph
+            .inputs
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_argument("Invalid input index"))?;
+        Self::metadata(info)
+    }
+
+    fn output_metadata(&self, index: usize) -> Result<(TensorDimensions, TensorType)> {
+        let info = self
+            .graph
+            .outputs
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_argument("Invalid output index"))?;
+        Self::metadata(info)
+    }
+
+    fn num_inputs(&self) -> usize {
+        self.graph.inputs.len()
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.graph.outputs.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Execution context state of the [`CpuBackend`]
+#[derive(Debug)]
+struct CpuContext {
+    graph:   Arc<OnnxGraph>,
+    inputs:  Vec<Option<Array>>,
+    outputs: Vec<Array>,
+}
+
+impl CpuContext {
+    fn downcast_mut(context: &mut dyn Any) -> Result<&mut Self> {
+        context
+            .downcast_mut::<Self>()
+            .ok_or_else(|| Error::wasi_invalid_argument("Context was not created by the CPU backend"))
+    }
+}
+
+fn to_u32_dims(dims: &[usize]) -> Result<Vec<u32>> {
+    dims.iter()
+        .map(|&dim| {
+            u32::try_from(dim).map_err(|_| Error::wasi_resource_exhausted("Tensor dimension too large"))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Arrays
+// ---------------------------------------------------------------------------
+
+/// Dense row-major `f32` array
+#[derive(Debug, Clone, PartialEq)]
+struct Array {
+    dims: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl Array {
+    fn new(dims: Vec<usize>, data: Vec<f32>) -> Result<Self> {
+        if element_count(&dims)? != data.len() {
+            return Err(Error::wasi_invalid_argument(
+                "Array data does not match its shape",
+            ));
+        }
+        Ok(Self { dims, data })
+    }
+
+    fn map(&self, f: impl Fn(f32) -> f32) -> Self {
+        Self {
+            dims: self.dims.clone(),
+            data: self.data.iter().map(|&value| f(value)).collect(),
+        }
+    }
+
+    /// View as a matrix, flattening all leading dimensions into rows
+    fn rows_cols(&self) -> Result<(usize, usize)> {
+        match self.dims.split_last() {
+            Some((&cols, _)) if cols > 0 => Ok((self.data.len() / cols, cols)),
+            Some(_) => Ok((0, 0)),
+            None => Err(Error::wasi_runtime_error("Operator expects a tensor, not a scalar")),
+        }
+    }
+}
+
+/// Number of elements of an array with `dims`, bounded by
+/// [`MAX_VALUE_ELEMENTS`]
+fn element_count(dims: &[usize]) -> Result<usize> {
+    dims.iter()
+        .try_fold(1usize, |count, &dim| count.checked_mul(dim))
+        .filter(|&count| count <= MAX_VALUE_ELEMENTS)
+        .ok_or_else(|| Error::wasi_resource_exhausted("Tensor exceeds CPU backend element limit"))
+}
+
+/// Resolve a possibly negative `axis` against `rank`, allowing `rank` itself
+/// when `inclusive`
+fn resolve_axis(axis: i64, rank: usize, inclusive: bool) -> Result<usize> {
+    let rank = rank as i64;
+    let resolved = if axis < 0 { axis + rank } else { axis };
+    let limit = if inclusive { rank } else { rank - 1 };
+    if resolved < 0 || resolved > limit {
+        return Err(Error::wasi_runtime_error("Operator axis out of range"));
+    }
+    Ok(resolved as usize)
+}
+
+/// Element-wise binary operation with multidirectional broadcasting
+fn broadcast(a: &Array, b: &Array, f: impl Fn(f32, f32) -> f32) -> Result<Array> {
+    let rank = a.dims.len().max(b.dims.len());
+    let padded = |dims: &[usize]| {
+        let mut out = vec![1; rank - dims.len()];
+        out.extend_from_slice(dims);
+        out
+    };
+    let (a_dims, b_dims) = (padded(&a.dims), padded(&b.dims));
+
+    let mut dims = Vec::with_capacity(rank);
+    for (&x, &y) in a_dims.iter().zip(&b_dims) {
+        dims.push(match (x, y) {
+            _ if x == y => x,
+            (1, _) => y,
+            (_, 1) => x,
+            _ => return Err(Error::wasi_runtime_error("Operand shapes cannot be broadcast")),
+        });
+    }
+
+    // Strides of each operand in the output's index space; 0 where broadcast
+    let strides = |operand: &[usize]| {
+        let mut strides = vec![0; rank];
+        let mut stride = 1;
+        for axis in (0..rank).rev() {
+            if operand[axis] != 1 {
+                strides[axis] = stride;
+            }
+            stride *= operand[axis];
+        }
+        strides
+    };
+    let (a_strides, b_strides) = (strides(&a_dims), strides(&b_dims));
+
+    let count = element_count(&dims)?;
+    let mut data = Vec::with_capacity(count);
+    let mut index = vec![0; rank];
+    for _ in 0..count {
+        let offset = |strides: &[usize]| index.iter().zip(strides).map(|(i, s)| i * s).sum::<usize>();
+        data.push(f(a.data[offset(&a_strides)], b.data[offset(&b_strides)]));
+        for axis in (0..rank).rev() {
+            index[axis] += 1;
+            if index[axis] < dims[axis] {
+                break;
+            }
+            index[axis] = 0;
+        }
+    }
+    Array::new(dims, data)
+}
+
+/// `a[..., m, k] x b[k, n]`, or `b[k]` for a matrix-vector product
+fn matmul(a: &Array, b: &Array) -> Result<Array> {
+    let (rows, k) = a.rows_cols()?;
+    let n = match b.dims.as_slice() {
+        [bk] if *bk == k => 1,
+        [bk, n] if *bk == k => *n,
+        [_] | [_, _] => {
+            return Err(Error::wasi_runtime_error("MatMul operand shapes do not match"))
+        },
+        _ => {
+            return Err(Error::wasi_unsupported_operation(
+                "MatMul right operand must be 1-D or 2-D",
+            ))
+        },
+    };
+
+    let mut dims = a.dims[..a.dims.len() - 1].to_vec();
+    if b.dims.len() == 2 {
+        dims.push(n);
+    }
+    let mut data = vec![0.0; element_count(&[rows, n])?];
+    for row in 0..rows {
+        for inner in 0..k {
+            let lhs = a.data[row * k + inner];
+            for col in 0..n {
+                data[row * n + col] += lhs * b.data[inner * n + col];
+            }
+        }
+    }
+    Array::new(dims, data)
+}
+
+/// Transpose a 2-D array
+fn transpose(array: &Array) -> Result<Array> {
+    let [rows, cols] = array.dims[..] else {
+        return Err(Error::wasi_runtime_error("Gemm operands must be 2-D"));
+    };
+    let mut data = vec![0.0; array.data.len()];
+    for row in 0..rows {
+        for col in 0..cols {
+            data[col * rows + row] = array.data[row * cols + col];
+        }
+    }
+    Array::new(vec![cols, rows], data)
+}
+
+fn softmax(input: &Array, axis: i64) -> Result<Array> {
+    let axis = resolve_axis(axis, input.dims.len(), false)?;
+    let length = input.dims[axis];
+    let inner: usize = input.dims[axis + 1..].iter().product();
+    let mut output = input.clone();
+    if length == 0 || inner == 0 {
+        return Ok(output);
+    }
+
+    for outer in 0..input.data.len() / (length * inner) {
+        for offset in 0..inner {
+            let base = outer * length * inner + offset;
+            let lane = |i: usize| base + i * inner;
+            let max = (0..length).map(|i| input.data[lane(i)]).fold(f32::NEG_INFINITY, f32::max);
+            let mut sum = 0.0;
+            for i in 0..length {
+                let value = (input.data[lane(i)] - max).exp();
+                output.data[lane(i)] = value;
+                sum += value;
+            }
+            for i in 0..length {
+                output.data[lane(i)] /= sum;
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn reshape(input: &Array, shape: &Array) -> Result<Array> {
+    let mut dims = Vec::with_capacity(shape.data.len());
+    let mut inferred = None;
+    for (axis, &value) in shape.data.iter().enumerate() {
+        dims.push(match value as i64 {
+            -1 if inferred.is_none() => {
+                inferred = Some(axis);
+                1
+            },
+            0 => *input
+                .dims
+                .get(axis)
+                .ok_or_else(|| Error::wasi_runtime_error("Reshape copies a missing dimension"))?,
+            dim if dim > 0 => dim as usize,
+            _ => return Err(Error::wasi_runtime_error("Invalid Reshape shape")),
+        });
+    }
+
+    let known = element_count(&dims)?;
+    if let Some(axis) = inferred {
+        if known == 0 || input.data.len() % known != 0 {
+            return Err(Error::wasi_runtime_error("Reshape cannot infer a dimension"));
+        }
+        dims[axis] = input.data.len() / known;
+    }
+    Array::new(dims, input.data.clone())
+        .map_err(|_| Error::wasi_runtime_error("Reshape changes the number of elements"))
+}
+
+// ---------------------------------------------------------------------------
+// Graph
+// ---------------------------------------------------------------------------
+
+/// Supported operators and their attributes
+#[derive(Debug, Clone, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    MatMul,
+    Gemm {
+        alpha:   f32,
+        beta:    f32,
+        trans_a: bool,
+        trans_b: bool,
+    },
+    Relu,
+    LeakyRelu {
+        alpha: f32,
+    },
+    Sigmoid,
+    Tanh,
+    Softmax {
+        axis: i64,
+    },
+    Flatten {
+        axis: i64,
+    },
+    Reshape,
+    Identity,
+}
+
+impl Op {
+    fn parse(op_type: &str, attributes: &[Attribute]) -> Result<Self> {
+        let float = |name: &str, default: f32| {
+            attributes.iter().find(|a| a.name == name).and_then(|a| a.float).unwrap_or(default)
+        };
+        let int = |name: &str, default: i64| {
+            attributes.iter().find(|a| a.name == name).and_then(|a| a.int).unwrap_or(default)
+        };
+        Ok(match op_type {
+            "Add" => Op::Add,
+            "Sub" => Op::Sub,
+            "Mul" => Op::Mul,
+            "Div" => Op::Div,
+            "MatMul" => Op::MatMul,
+            "Gemm" => Op::Gemm {
+                alpha:   float("alpha", 1.0),
+                beta:    float("beta", 1.0),
+                trans_a: int("transA", 0) != 0,
+                trans_b: int("transB", 0) != 0,
+            },
+            "Relu" => Op::Relu,
+            "LeakyRelu" => Op::LeakyRelu {
+                alpha: float("alpha", 0.01),
+            },
+            "Sigmoid" => Op::Sigmoid,
+            "Tanh" => Op::Tanh,
+            "Softmax" => Op::Softmax {
+                axis: int("axis", -1),
+            },
+            "Flatten" => Op::Flatten {
+                axis: int("axis", 1),
+            },
+            "Reshape" => Op::Reshape,
+            "Identity" => Op::Identity,
+            _ => {
+                return Err(Error::wasi_unsupported_operation(
+                    "ONNX operator not supported by the CPU backend",
+                ))
+            },
+        })
+    }
+
+    /// Allowed number of inputs
+    fn arity(&self) -> (usize, usize) {
+        match self {
+            Op::Add | Op::Sub | Op::Mul | Op::Div | Op::MatMul | Op::Reshape => (2, 2),
+            Op::Gemm { .. } => (2, 3),
+            _ => (1, 1),
+        }
+    }
+
+    fn apply(&self, inputs: &[Option<&Array>]) -> Result<Array> {
+        let input = |index: usize| {
+            inputs
+                .get(index)
+                .copied()
+                .flatten()
+                .ok_or_else(|| Error::wasi_runtime_error("Missing operator input"))
+        };
+        match self {
+            Op::Add => broadcast(input(0)?, input(1)?, |a, b| a + b),
+            Op::Sub => broadcast(input(0)?, input(1)?, |a, b| a - b),
+            Op::Mul => broadcast(input(0)?, input(1)?, |a, b| a * b),
+            Op::Div => broadcast(input(0)?, input(1)?, |a, b| a / b),
+            Op::MatMul => matmul(input(0)?, input(1)?),
+            Op::Gemm {
+                alpha,
+                beta,
+                trans_a,
+                trans_b,
+            } => {
+                let a = if *trans_a { transpose(input(0)?)? } else { input(0)?.clone() };
+                let b = if *trans_b { transpose(input(1)?)? } else { input(1)?.clone() };
+                if a.dims.len() != 2 || b.dims.len() != 2 {
+                    return Err(Error::wasi_runtime_error("Gemm operands must be 2-D"));
+                }
+                let product = matmul(&a, &b)?.map(|value| value * alpha);
+                match inputs.get(2).copied().flatten() {
+                    Some(c) => broadcast(&product, c, |y, c| y + beta * c),
+                    None => Ok(product),
+                }
+            },
+            Op::Relu => Ok(input(0)?.map(|x| x.max(0.0))),
+            Op::LeakyRelu { alpha } => Ok(input(0)?.map(|x| if x < 0.0 { alpha * x } else { x })),
+            Op::Sigmoid => Ok(input(0)?.map(|x| 1.0 / (1.0 + (-x).exp()))),
+            Op::Tanh => Ok(input(0)?.map(f32::tanh)),
+            Op::Softmax { axis } => softmax(input(0)?, *axis),
+            Op::Flatten { axis } => {
+                let input = input(0)?;
+                let axis = resolve_axis(*axis, input.dims.len(), true)?;
+                let outer = input.dims[..axis].iter().product();
+                let inner = input.dims[axis..].iter().product();
+                Array::new(vec![outer, inner], input.data.clone())
+            },
+            Op::Reshape => reshape(input(0)?, input(1)?),
+            Op::Identity => Ok(input(0)?.clone()),
+        }
+    }
+}
+
+/// Graph node
+#[derive(Debug, Clone)]
+struct Node {
+    op:      Op,
+    /// Input value names; empty names mark omitted optional inputs
+    inputs:  Vec<String>,
+    output:  String,
+}
+
+/// Declared graph input or output
+#[derive(Debug, Clone)]
+struct ValueInfo {
+    name: String,
+    /// Dimensions; `None` for symbolic ones
+    dims: Vec<Option<usize>>,
+}
+
+/// Validated ONNX graph
+#[derive(Debug)]
+struct OnnxGraph {
+    inputs:       Vec<ValueInfo>,
+    outputs:      Vec<ValueInfo>,
+    initializers: HashMap<String, Array>,
+    nodes:        Vec<Node>,
+}
+
+impl OnnxGraph {
+    /// Parse and validate an ONNX `ModelProto`
+    fn parse(data: &[u8]) -> Result<Self> {
+        let mut graph = None;
+        let mut reader = ProtoReader::new(data);
+        while let Some((field, value)) = reader.next_field()? {
+            if field == 7 {
+                graph = Some(value.bytes()?);
+            }
+        }
+        let graph = graph.ok_or_else(|| Error::wasi_invalid_argument("ONNX model has no graph"))?;
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut initializers = HashMap::new();
+        let mut nodes = Vec::new();
+        let mut reader = ProtoReader::new(graph);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => {
+                    if nodes.len() >= MAX_GRAPH_NODES {
+                        return Err(Error::wasi_resource_exhausted("ONNX graph has too many nodes"));
+                    }
+                    nodes.push(parse_node(value.bytes()?)?);
+                },
+                5 => {
+                    let (name, array) = parse_initializer(value.bytes()?)?;
+                    initializers.insert(name, array);
+                },
+                11 => inputs.push(value.bytes()?),
+                12 => outputs.push(parse_value_info(value.bytes()?)?),
+                _ => {},
+            }
+        }
+
+        // Older models also list initializers as graph inputs
+        let mut graph_inputs = Vec::new();
+        for input in inputs {
+            let info = parse_value_info(input)?;
+            if !initializers.contains_key(&info.name) {
+                graph_inputs.push(info);
+            }
+        }
+
+        let graph = Self {
+            inputs: graph_inputs,
+            outputs,
+            initializers,
+            nodes,
+        };
+        graph.validate()?;
+        Ok(graph)
+    }
+
+    /// Check that every value is defined before it is used
+    fn validate(&self) -> Result<()> {
+        for values in [&self.inputs, &self.outputs] {
+            if values.is_empty() || values.len() > MAX_GRAPH_VALUES {
+                return Err(Error::wasi_invalid_argument(
+                    "ONNX graph must have between 1 and 16 inputs and outputs",
+                ));
+            }
+        }
+
+        let mut defined: Vec<&str> = self.inputs.iter().map(|info| info.name.as_str()).collect();
+        defined.extend(self.initializers.keys().map(String::as_str));
+        for node in &self.nodes {
+            let (min, max) = node.op.arity();
+            let required = node.inputs.iter().take(min);
+            if node.inputs.len() < min
+                || node.inputs.len() > max
+                || required.clone().any(String::is_empty)
+            {
+                return Err(Error::wasi_invalid_argument(
+                    "ONNX node has the wrong number of inputs",
+                ));
+            }
+            if node.inputs.iter().any(|name| !name.is_empty() && !defined.contains(&name.as_str())) {
+                return Err(Error::wasi_invalid_argument(
+                    "ONNX node uses a value before it is defined",
+                ));
+            }
+            defined.push(&node.output);
+        }
+
+        if self.outputs.iter().any(|info| !defined.contains(&info.name.as_str())) {
+            return Err(Error::wasi_invalid_argument("ONNX graph output is never defined"));
+        }
+        Ok(())
+    }
+
+    /// Run the graph on `inputs`, in declaration order
+    fn run(&self, inputs: &[&Array]) -> Result<Vec<Array>> {
+        let mut values: HashMap<&str, Array> = HashMap::new();
+        for (info, input) in self.inputs.iter().zip(inputs) {
+            values.insert(&info.name, (*input).clone());
+        }
+
+        for node in &self.nodes {
+            let operands: Vec<Option<&Array>> = node
+                .inputs
+                .iter()
+                .map(|name| values.get(name.as_str()).or_else(|| self.initializers.get(name)))
+                .collect();
+            let output = node.op.apply(&operands)?;
+            element_count(&output.dims)?;
+            values.insert(&node.output, output);
+        }
+
+        self.outputs
+            .iter()
+            .map(|info| {
+                values
+                    .get(info.name.as_str())
+                    .or_else(|| self.initializers.get(&info.name))
+                    .cloned()
+                    .ok_or_else(|| Error::wasi_runtime_error("Graph output not computed"))
+            })
+            .collect()
+    }
+}
+
+/// Node attribute, keeping only the scalar forms the operators use
+struct Attribute {
+    name:  String,
+    float: Option<f32>,
+    int:   Option<i64>,
+}
+
+fn parse_node(data: &[u8]) -> Result<Node> {
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    let mut op_type = "";
+    let mut attributes = Vec::new();
+    let mut reader = ProtoReader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => inputs.push(value.string()?.to_string()),
+            2 => outputs.push(value.string()?.to_string()),
+            4 => op_type = value.string()?,
+            5 => attributes.push(parse_attribute(value.bytes()?)?),
+            _ => {},
+        }
+    }
+
+    let output = match outputs.as_slice() {
+        [output] if !output.is_empty() => output.clone(),
+        _ => {
+            return Err(Error::wasi_unsupported_operation(
+                "ONNX nodes must have exactly one output",
+            ))
+        },
+    };
+    Ok(Node {
+        op: Op::parse(op_type, &attributes)?,
+        inputs,
+        output,
+    })
+}
+
+fn parse_attribute(data: &[u8]) -> Result<Attribute> {
+    let mut attribute = Attribute {
+        name:  String::new(),
+        float: None,
+        int:   None,
+    };
+    let mut reader = ProtoReader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, value) => attribute.name = value.string()?.to_string(),
+            (2, Field::Fixed32(bits)) => attribute.float = Some(f32::from_bits(bits)),
+            (3, Field::Varint(int)) => attribute.int = Some(int as i64),
+            _ => {},
+        }
+    }
+    Ok(attribute)
+}
+
+fn parse_value_info(data: &[u8]) -> Result<ValueInfo> {
+    let mut name = String::new();
+    let mut tensor_type = None;
+    let mut reader = ProtoReader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => name = value.string()?.to_string(),
+            2 => tensor_type = nested_field(value.bytes()?, 1)?,
+            _ => {},
+        }
+    }
+    let tensor_type =
+        tensor_type.ok_or_else(|| Error::wasi_invalid_argument("ONNX graph value is not a tensor"))?;
+
+    let mut elem_type = None;
+    let mut shape = None;
+    let mut reader = ProtoReader::new(tensor_type);
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, Field::Varint(kind)) => elem_type = Some(kind),
+            (2, value) => shape = Some(value.bytes()?),
+            _ => {},
+        }
+    }
+    if elem_type != Some(ONNX_FLOAT) {
+        return Err(Error::wasi_unsupported_operation(
+            "CPU backend graph inputs and outputs must be float",
+        ));
+    }
+    let shape = shape.ok_or_else(|| Error::wasi_invalid_argument("ONNX graph value has no shape"))?;
+
+    let mut dims = Vec::new();
+    let mut reader = ProtoReader::new(shape);
+    while let Some((field, value)) = reader.next_field()? {
+        if field == 1 {
+            dims.push(parse_dimension(value.bytes()?)?);
+        }
+    }
+    Ok(ValueInfo { name, dims })
+}
+
+fn parse_dimension(data: &[u8]) -> Result<Option<usize>> {
+    let mut reader = ProtoReader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        if let (1, Field::Varint(dim)) = (field, value) {
+            let dim = usize::try_from(dim)
+                .map_err(|_| Error::wasi_invalid_argument("ONNX dimension too large"))?;
+            return Ok(Some(dim));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_initializer(data: &[u8]) -> Result<(String, Array)> {
+    let mut dims = Vec::new();
+    let mut data_type = 0;
+    let mut name = String::new();
+    let mut floats = Vec::new();
+    let mut ints = Vec::new();
+    let mut raw = None;
+    let mut reader = ProtoReader::new(data);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => value.ints(&mut dims)?,
+            2 => data_type = value.varint()?,
+            4 => value.floats(&mut floats)?,
+            7 => value.ints(&mut ints)?,
+            8 => name = value.string()?.to_string(),
+            9 => raw = Some(value.bytes()?),
+            _ => {},
+        }
+    }
+
+    let dims = dims
+        .into_iter()
+        .map(|dim| usize::try_from(dim).map_err(|_| Error::wasi_invalid_argument("Invalid ONNX dimension")))
+        .collect::<Result<Vec<_>>>()?;
+    let data = match (data_type, raw) {
+        (ONNX_FLOAT, Some(raw)) => raw
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        (ONNX_FLOAT, None) => floats,
+        (ONNX_INT64, Some(raw)) => raw
+            .chunks_exact(8)
+            .map(|b| i64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32)
+            .collect(),
+        (ONNX_INT64, None) => ints.into_iter().map(|int| int as f32).collect(),
+        _ => {
+            return Err(Error::wasi_unsupported_operation(
+                "CPU backend initializers must be float or int64",
+            ))
+        },
+    };
+    let array = Array::new(dims, data)
+        .map_err(|_| Error::wasi_invalid_argument("ONNX initializer data does not match its shape"))?;
+    Ok((name, array))
+}
+
+/// First length-delimited `field` of a message, if any
+fn nested_field(data: &[u8], field: u64) -> Result<Option<&[u8]>> {
+    let mut reader = ProtoReader::new(data);
+    while let Some((number, value)) = reader.next_field()? {
+        if number == field {
+            return value.bytes().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+// ---------------------------------------------------------------------------
+// Protobuf wire format
+// ---------------------------------------------------------------------------
+
+fn malformed() -> Error {
+    Error::wasi_invalid_argument("Malformed ONNX model")
+}
+
+/// Field value in the protobuf wire format
+enum Field<'a> {
+    Varint(u64),
+    Fixed64,
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> Field<'a> {
+    fn varint(self) -> Result<u64> {
+        match self {
+            Field::Varint(value) => Ok(value),
+            _ => Err(malformed()),
+        }
+    }
+
+    fn bytes(self) -> Result<&'a [u8]> {
+        match self {
+            Field::Bytes(bytes) => Ok(bytes),
+            _ => Err(malformed()),
+        }
+    }
+
+    fn string(self) -> Result<&'a str> {
+        core::str::from_utf8(self.bytes()?).map_err(|_| malformed())
+    }
+
+    /// Append a repeated `int64`, packed or not
+    fn ints(self, out: &mut Vec<i64>) -> Result<()> {
+        match self {
+            Field::Varint(value) => out.push(value as i64),
+            Field::Bytes(packed) => {
+                let mut reader = ProtoReader::new(packed);
+                while !reader.data.is_empty() {
+                    out.push(reader.varint()? as i64);
+                }
+            },
+            _ => return Err(malformed()),
+        }
+        Ok(())
+    }
+
+    /// Append a repeated `float`, packed or not
+    fn floats(self, out: &mut Vec<f32>) -> Result<()> {
+        match self {
+            Field::Fixed32(bits) => out.push(f32::from_bits(bits)),
+            Field::Bytes(packed) if packed.len() % 4 == 0 => out.extend(
+                packed.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            ),
+            _ => return Err(malformed()),
+        }
+        Ok(())
+    }
+}
+
+/// Reader over the fields of a protobuf message
+struct ProtoReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.data.split_first().ok_or_else(malformed)?;
+            self.data = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(malformed())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.data.len() {
+            return Err(malformed());
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => Field::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Field::Fixed64
+            },
+            2 => {
+                let len = usize::try_from(self.varint()?).map_err(|_| malformed())?;
+                Field::Bytes(self.take(len)?)
+            },
+            5 => {
+                let bytes = self.take(4)?;
+                Field::Fixed32(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            },
+            _ => return Err(malformed()),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::capabilities::DynamicNNCapability;
+
+    fn varint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+        varint(out, field << 3 | 2);
+        varint(out, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+
+    fn varint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+        varint(out, field << 3);
+        varint(out, value);
+    }
+
+    fn value_info(name: &str, dims: &[Option<u64>]) -> Vec<u8> {
+        let mut shape = Vec::new();
+        for dim in dims {
+            let mut dimension = Vec::new();
+            match dim {
+                Some(size) => varint_field(&mut dimension, 1, *size),
+                None => bytes_field(&mut dimension, 2, b"N"),
+            }
+            bytes_field(&mut shape, 1, &dimension);
+        }
+        let mut tensor_type = Vec::new();
+        varint_field(&mut tensor_type, 1, ONNX_FLOAT);
+        bytes_field(&mut tensor_type, 2, &shape);
+        let mut type_proto = Vec::new();
+        bytes_field(&mut type_proto, 1, &tensor_type);
+        let mut info = Vec::new();
+        bytes_field(&mut info, 1, name.as_bytes());
+        bytes_field(&mut info, 2, &type_proto);
+        info
+    }
+
+    fn float_tensor(name: &str, dims: &[u64], data: &[f32]) -> Vec<u8> {
+        let mut tensor = Vec::new();
+        for dim in dims {
+            varint_field(&mut tensor, 1, *dim);
+        }
+        varint_field(&mut tensor, 2, ONNX_FLOAT);
+        let packed: Vec<u8> = data.iter().flat_map(|value| value.to_le_bytes()).collect();
+        bytes_field(&mut tensor, 4, &packed);
+        bytes_field(&mut tensor, 8, name.as_bytes());
+        tensor
+    }
+
+    fn int_tensor(name: &str, data: &[i64]) -> Vec<u8> {
+        let mut tensor = Vec::new();
+        varint_field(&mut tensor, 1, data.len() as u64);
+        varint_field(&mut tensor, 2, ONNX_INT64);
+        for value in data {
+            varint_field(&mut tensor, 7, *value as u64);
+        }
+        bytes_field(&mut tensor, 8, name.as_bytes());
+        tensor
+    }
+
+    fn node(op: &str, inputs: &[&str], output: &str, int_attrs: &[(&str, i64)]) -> Vec<u8> {
+        let mut node = Vec::new();
+        for input in inputs {
+            bytes_field(&mut node, 1, input.as_bytes());
+        }
+        bytes_field(&mut node, 2, output.as_bytes());
+        bytes_field(&mut node, 4, op.as_bytes());
+        for (name, value) in int_attrs {
+            let mut attribute = Vec::new();
+            bytes_field(&mut attribute, 1, name.as_bytes());
+            varint_field(&mut attribute, 3, *value as u64);
+            bytes_field(&mut node, 5, &attribute);
+        }
+        node
+    }
+
+    fn model(nodes: &[Vec<u8>], initializers: &[Vec<u8>], inputs: &[Vec<u8>], outputs: &[Vec<u8>]) -> Vec<u8> {
+        let mut graph = Vec::new();
+        for (field, items) in [(1, nodes), (5, initializers), (11, inputs), (12, outputs)] {
+            for item in items {
+                bytes_field(&mut graph, field, item);
+            }
+        }
+        let mut model = Vec::new();
+        varint_field(&mut model, 1, 8);
+        bytes_field(&mut model, 7, &graph);
+        model
+    }
+
+    /// `softmax(relu(x * W + B))` for `x: [N, 2]`
+    fn mlp() -> Vec<u8> {
+        model(
+            &[
+                node("Gemm", &["x", "W", "B"], "h", &[]),
+                node("Relu", &["h"], "r", &[]),
+                node("Softmax", &["r"], "y", &[("axis", 1)]),
+            ],
+            &[
+                float_tensor("W", &[2, 3], &[1.0, -1.0, 0.5, 2.0, 0.0, 0.5]),
+                float_tensor("B", &[3], &[0.0, 1.0, -1.0]),
+            ],
+            &[value_info("x", &[None, Some(2)])],
+            &[value_info("y", &[None, Some(3)])],
+        )
+    }
+
+    fn run(model: &[u8], inputs: &[(Vec<u32>, Vec<f32>)]) -> Result<Vec<Tensor>> {
+        let backend = CpuBackend::new();
+        let capability = DynamicNNCapability::new();
+        let graph = backend.load_graph(model, GraphEncoding::ONNX)?;
+        let mut context = backend.init_execution_context(graph.as_ref())?;
+        for (index, (dims, data)) in inputs.iter().enumerate() {
+            let bytes = data.iter().flat_map(|value| value.to_le_bytes()).collect();
+            let tensor =
+                Tensor::from_data(TensorDimensions::new(dims)?, TensorType::F32, bytes, &capability)?;
+            backend.set_input(context.as_mut(), index, &tensor)?;
+        }
+        backend.compute(context.as_mut())?;
+        (0..graph.num_outputs())
+            .map(|index| backend.get_output(context.as_ref(), index, &capability))
+            .collect()
+    }
+
+    fn floats(tensor: &Tensor) -> Vec<f32> {
+        tensor
+            .as_bytes()
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_cpu_backend_mlp() {
+        let outputs = run(&mlp(), &[(vec![2, 2], vec![1.0, 1.0, -1.0, 0.0])]).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].dimensions().as_slice(), &[2, 3]);
+
+        // Row 0: relu([3, 0, 0]); row 1: relu([-1, 2, -1.5])
+        let expected = |logits: [f32; 3]| {
+            let sum: f32 = logits.iter().map(|l| l.exp()).sum();
+            logits.map(|l| l.exp() / sum)
+        };
+        let values = floats(&outputs[0]);
+        for (value, expected) in values.iter().zip(expected([3.0, 0.0, 0.0]).iter().chain(&expected([0.0, 2.0, 0.0]))) {
+            assert!((value - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_cpu_backend_broadcast_and_reshape() {
+        let model = model(
+            &[
+                node("Add", &["x", "b"], "sum", &[]),
+                node("Reshape", &["sum", "shape"], "flat", &[]),
+            ],
+            &[float_tensor("b", &[3], &[10.0, 20.0, 30.0]), int_tensor("shape", &[-1])],
+            &[value_info("x", &[Some(2), Some(3)])],
+            &[value_info("flat", &[Some(6)])],
+        );
+        let outputs = run(&model, &[(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])]).unwrap();
+        assert_eq!(outputs[0].dimensions().as_slice(), &[6]);
+        assert_eq!(floats(&outputs[0]), vec![11.0, 22.0, 33.0, 14.0, 25.0, 36.0]);
+
+        // Declared static dimensions are enforced
+        assert!(run(&model, &[(vec![3, 2], vec![0.0; 6])]).is_err());
+    }
+
+    #[test]
+    fn test_cpu_backend_rejects_unsupported_graphs() {
+        let backend = CpuBackend::new();
+        let load = |model: &[u8]| backend.load_graph(model, GraphEncoding::ONNX);
+        let x = value_info("x", &[Some(1)]);
+        let y = value_info("y", &[Some(1)]);
+
+        assert!(load(&model(&[node("Conv", &["x"], "y", &[])], &[], &[x.clone()], &[y.clone()])).is_err());
+        assert!(load(&model(&[node("Relu", &["z"], "y", &[])], &[], &[x.clone()], &[y.clone()])).is_err());
+        assert!(load(&model(&[node("Add", &["x"], "y", &[])], &[], &[x.clone()], &[y.clone()])).is_err());
+        assert!(load(&model(&[], &[], &[x.clone()], &[y])).is_err());
+        assert!(load(&[0x3a, 0x10, 0x01]).is_err());
+        assert!(backend.load_graph(&mlp(), GraphEncoding::TensorFlow).is_err());
+
+        let graph = load(&mlp()).unwrap();
+        assert_eq!(graph.num_inputs(), 1);
+        let (dims, data_type) = graph.input_metadata(0).unwrap();
+        assert_eq!(dims.as_slice(), &[1, 2]);
+        assert_eq!(data_type, TensorType::F32);
+    }
+
+    #[test]
+    fn test_cpu_backend_registration() {
+        let mut capabilities = crate::WasiNeuralNetworkCapabilities::sandboxed().unwrap();
+        assert_eq!(capabilities.backends.len(), 1);
+        assert!(capabilities.register_backend(crate::nn::NnBackendRef::new(CpuBackend::new())).is_err());
+        assert!(crate::WasiNeuralNetworkCapabilities::minimal().unwrap().backends.is_empty());
+
+        let mut registry = crate::nn::backend::BackendRegistry::new();
+        registry.register_nn_backend(&capabilities.backends[0]).unwrap();
+        let capability = DynamicNNCapability::new();
+        let backend = registry.get_backend(GraphEncoding::ONNX, &capability).unwrap();
+        assert_eq!(backend.name(), "cpu");
+        assert!(registry.get_backend(GraphEncoding::PyTorch, &capability).is_err());
+
+        let model = backend.load_model_dyn(&mlp(), GraphEncoding::ONNX).unwrap();
+        let mut context = backend.create_context_dyn(model.as_ref()).unwrap();
+        let input = Tensor::from_data(
+            TensorDimensions::new(&[1, 2]).unwrap(),
+            TensorType::F32,
+            [0.0f32, 0.0].iter().flat_map(|v| v.to_le_bytes()).collect(),
+            &capability,
+        )
+        .unwrap();
+        let outputs = backend.compute_dyn(context.as_mut(), &[input], model.as_ref()).unwrap();
+        assert_eq!(outputs[0].dimensions().as_slice(), &[1, 3]);
+    }
+}
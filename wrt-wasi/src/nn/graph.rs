@@ -43,6 +43,15 @@ pub enum GraphEncoding {
 }
 
 impl GraphEncoding {
+    /// All graph encodings
+    pub const ALL: [GraphEncoding; 5] = [
+        GraphEncoding::ONNX,
+        GraphEncoding::TensorFlow,
+        GraphEncoding::PyTorch,
+        GraphEncoding::OpenVINO,
+        GraphEncoding::TractNative,
+    ];
+
     /// Convert to internal model format
     pub fn to_model_format(self) -> ModelFormat {
         match self {
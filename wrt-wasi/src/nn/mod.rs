@@ -19,15 +19,17 @@
     OnceLock,
 };
 
+use wrt_foundation::sha256;
+
 use crate::prelude::*;
 
 // Core modules
 pub mod backend;
 pub mod capabilities;
+pub mod cpu_backend;
 pub mod execution;
 pub mod graph;
 pub mod monitoring;
-mod sha256;
 pub mod tensor;
 pub mod wit_types;
 
@@ -62,11 +64,15 @@
 pub use backend::{
     get_backend_registry,
     initialize_backends,
+    install_nn_backends,
     BackendProvider,
     ComputeCapable,
     DynBackend,
     ModelCapability,
     NeuralNetworkBackend,
+    NnBackend,
+    NnBackendContext,
+    NnBackendRef,
     TensorCapability,
 };
 pub use capabilities::{
@@ -78,6 +84,7 @@
     ResourceUsageStats,
     VerificationLevel,
 };
+pub use cpu_backend::CpuBackend;
 pub use execution::{
     execute_inference,
     get_context_store,
@@ -175,6 +182,28 @@ pub fn with_nn_capability<F, R>(f: F) -> Result<R>
     f(capability.as_ref())
 }
 
+/// Set up WASI-NN from the `nn` part of the WASI capabilities
+///
+/// Installs the registered backends and, unless a capability was already
+/// initialized with [`initialize_nn`], one for the configured verification
+/// level. Does nothing if no backend is registered.
+pub fn install_capabilities(capabilities: &crate::WasiNeuralNetworkCapabilities) -> Result<()> {
+    if capabilities.backends.is_empty() {
+        return Ok(());
+    }
+
+    install_nn_backends(&capabilities.backends)?;
+    if get_nn_capability()?.is_none() {
+        let level = VerificationLevel::from(capabilities.verification_level);
+        match initialize_nn(capabilities::create_nn_capability(level)?) {
+            // Lost a race with another initializer, which is equally fine
+            Err(_) if get_nn_capability()?.is_some() => {},
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
 /// WASI-NN version information
 pub const WASI_NN_VERSION: &str = "0.2.0";
 
@@ -0,0 +1,128 @@
+//! Guest metrics (`wasi:observe/metrics`)
+//!
+//! Guests publish named counters and gauges through a small host interface:
+//!
+//! ```wit
+//! interface metrics {
+//!     counter-add: func(name: string, delta: u64);
+//!     gauge-set: func(name: string, value: s64);
+//! }
+//! ```
+//!
+//! Updates go into the global registry of [`wrt_foundation::metrics`] under
+//! the namespace of the dispatcher, normally the component name, next to the
+//! metrics of the runtime, and are recorded as telemetry events. The
+//! [`WasiObserveConfig`] quota bounds the metrics of each namespace. An
+//! update beyond the quota is dropped and counted rather than failing the
+//! call, so publishing metrics cannot change the behaviour of a component.
+//! Without `std` nothing is recorded.
+
+#[cfg(feature = "std")]
+use wrt_foundation::metrics::{
+    self,
+    MetricQuota,
+};
+
+#[cfg(feature = "std")]
+use crate::prelude::*;
+
+/// Namespace of the guest metrics of a dispatcher without a component name
+pub const DEFAULT_METRICS_NAMESPACE: &str = "component";
+
+/// Configuration of the guest metrics interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasiObserveConfig {
+    /// Accept metrics from guests
+    pub enabled:      bool,
+    /// Maximum number of metrics per component
+    pub max_metrics:  usize,
+    /// Maximum length of a metric name in bytes
+    pub max_name_len: usize,
+}
+
+impl WasiObserveConfig {
+    /// No guest metrics
+    pub const fn disabled() -> Self {
+        Self {
+            enabled:      false,
+            max_metrics:  0,
+            max_name_len: 0,
+        }
+    }
+
+    /// Accept up to `max_metrics` metrics per component, with names of at
+    /// most `max_name_len` bytes
+    pub const fn with_quota(max_metrics: usize, max_name_len: usize) -> Self {
+        Self {
+            enabled: true,
+            max_metrics,
+            max_name_len,
+        }
+    }
+}
+
+impl Default for WasiObserveConfig {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// Apply the quota of `config` to the metrics of `namespace`
+///
+/// # Errors
+///
+/// Returns an error if the metric registry lock is poisoned.
+#[cfg(feature = "std")]
+pub(crate) fn install_namespace(namespace: &str, config: &WasiObserveConfig) -> Result<()> {
+    if config.enabled {
+        metrics::set_quota(namespace, MetricQuota::new(config.max_metrics, config.max_name_len))?;
+    }
+    Ok(())
+}
+
+/// Run a `wasi:observe/metrics` function for the component publishing under
+/// `namespace`
+///
+/// `value` is the counter delta or the gauge value as its two's complement.
+///
+/// # Errors
+///
+/// Returns an error if the function is unknown.
+#[cfg(feature = "std")]
+pub(crate) fn call(namespace: &str, function: &str, name: &str, value: u64) -> Result<()> {
+    // Rejected updates are counted by the registry
+    match function {
+        "counter-add" => {
+            let _ = metrics::counter_add(namespace, name, value);
+        },
+        "gauge-set" => {
+            let _ = metrics::gauge_set(namespace, name, value as i64);
+        },
+        _ => return Err(Error::runtime_not_implemented("Unknown WASI function")),
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use wrt_foundation::metrics::MetricValue;
+
+    use super::*;
+
+    #[test]
+    fn test_updates_beyond_quota_are_dropped() -> Result<()> {
+        let namespace = "observe-test";
+        install_namespace(namespace, &WasiObserveConfig::with_quota(1, 16))?;
+
+        call(namespace, "counter-add", "requests", 2)?;
+        call(namespace, "counter-add", "requests", 3)?;
+        call(namespace, "gauge-set", "queue-depth", 7)?;
+        call(namespace, "gauge-set", "requests", -1i64 as u64)?;
+        assert!(call(namespace, "histogram-record", "latency", 1).is_err());
+
+        assert_eq!(metrics::get(namespace, "requests"), Some(MetricValue::Counter(5)));
+        assert_eq!(metrics::get(namespace, "queue-depth"), None);
+        assert_eq!(metrics::rejected(namespace), 2);
+        metrics::remove_namespace(namespace)
+    }
+}
@@ -0,0 +1,537 @@
+//! WASI filesystem preopens and sandbox path mapping
+//!
+//! A [`Preopen`] maps a host directory to the path a guest sees it under,
+//! with an access mode and optional quotas. Preopens are declared on
+//! [`WasiFileSystemCapabilities`] and turned into a [`PreopenTable`] by the
+//! code that performs filesystem operations. The table resolves guest paths
+//! to host paths and refuses any path that would leave its preopen, whether
+//! through `..` components, absolute paths or symbolic links pointing
+//! outside the host directory.
+//!
+//! Resolution canonicalizes the deepest existing ancestor of the requested
+//! path, so symbolic links are checked as they are at resolution time.
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use crate::{
+    audit,
+    capabilities::WasiFileSystemCapabilities,
+    prelude::*,
+};
+
+/// Maximum number of preopened directories
+pub const MAX_PREOPENS: usize = 32;
+
+/// Maximum length of a guest path
+pub const MAX_GUEST_PATH_LENGTH: usize = 256;
+
+/// Access mode of a preopened directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreopenMode {
+    /// Files may be read but not created, modified or removed
+    ReadOnly,
+    /// Files may be read, created, modified and removed
+    ReadWrite,
+}
+
+impl PreopenMode {
+    /// Whether the mode allows modifications
+    pub fn allows_write(self) -> bool {
+        self == Self::ReadWrite
+    }
+}
+
+/// Kind of access requested for a resolved path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreopenAccess {
+    /// Read or inspect an existing path
+    Read,
+    /// Create, modify or remove a path
+    Write,
+}
+
+/// Resource quotas of a preopened directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PreopenQuota {
+    /// Maximum number of bytes written below the directory
+    pub max_bytes_written: Option<u64>,
+    /// Maximum number of files open below the directory at once
+    pub max_open_files:    Option<u32>,
+}
+
+impl PreopenQuota {
+    /// Quota without limits
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of bytes written below the directory
+    #[must_use]
+    pub fn with_max_bytes_written(mut self, bytes: u64) -> Self {
+        self.max_bytes_written = Some(bytes);
+        self
+    }
+
+    /// Limit the number of files open below the directory at once
+    #[must_use]
+    pub fn with_max_open_files(mut self, files: u32) -> Self {
+        self.max_open_files = Some(files);
+        self
+    }
+}
+
+/// A host directory made visible to the guest under a guest path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preopen {
+    /// Directory on the host
+    host_dir:   PathBuf,
+    /// Path under which the guest sees the directory
+    guest_path: String,
+    /// Access mode
+    mode:       PreopenMode,
+    /// Resource quotas
+    quota:      PreopenQuota,
+}
+
+impl Preopen {
+    /// Map `host_dir` to `guest_path` with the given access mode
+    pub fn new(host_dir: impl Into<PathBuf>, guest_path: impl Into<String>, mode: PreopenMode) -> Self {
+        Self {
+            host_dir: host_dir.into(),
+            guest_path: guest_path.into(),
+            mode,
+            quota: PreopenQuota::unlimited(),
+        }
+    }
+
+    /// Set the resource quotas of the preopen
+    #[must_use]
+    pub fn with_quota(mut self, quota: PreopenQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Directory on the host
+    pub fn host_dir(&self) -> &Path {
+        &self.host_dir
+    }
+
+    /// Path under which the guest sees the directory
+    pub fn guest_path(&self) -> &str {
+        &self.guest_path
+    }
+
+    /// Access mode
+    pub fn mode(&self) -> PreopenMode {
+        self.mode
+    }
+
+    /// Resource quotas
+    pub fn quota(&self) -> PreopenQuota {
+        self.quota
+    }
+}
+
+/// Resources used below a preopened directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PreopenUsage {
+    /// Bytes written so far
+    pub bytes_written: u64,
+    /// Files currently open
+    pub open_files:    u32,
+}
+
+/// A guest path resolved to a host path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPath {
+    /// Index of the preopen the path lies in
+    pub preopen:   usize,
+    /// Host path, with symbolic links of existing ancestors resolved
+    pub host_path: PathBuf,
+}
+
+/// A preopen in a table, with its canonical host directory and usage
+#[derive(Debug, Clone)]
+struct PreopenEntry {
+    /// The declared preopen
+    preopen:  Preopen,
+    /// Canonical host directory
+    root:     PathBuf,
+    /// Normalized guest path components
+    guest:    Vec<String>,
+    /// Whether writes are allowed, combining mode and capabilities
+    writable: bool,
+    /// Resources used so far
+    usage:    PreopenUsage,
+}
+
+/// Preopened directories of a WASI instance, with their usage
+#[derive(Debug, Clone, Default)]
+pub struct PreopenTable {
+    entries: Vec<PreopenEntry>,
+}
+
+impl PreopenTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a table holding the preopens of `capabilities`
+    ///
+    /// Preopens declared read-write are read-only if the capabilities do not
+    /// grant write access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a preopened host directory does not exist.
+    pub fn from_capabilities(capabilities: &WasiFileSystemCapabilities) -> Result<Self> {
+        let mut table = Self::new();
+        for preopen in capabilities.preopens() {
+            table.insert(preopen.clone(), capabilities.write_access)?;
+        }
+        Ok(table)
+    }
+
+    /// Add a preopen and return its index
+    ///
+    /// The preopen is read-only unless its mode and `allow_write` both
+    /// allow writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is full, the guest path is invalid or
+    /// already mapped, or the host directory does not exist.
+    pub fn insert(&mut self, preopen: Preopen, allow_write: bool) -> Result<usize> {
+        if self.entries.len() >= MAX_PREOPENS {
+            return Err(Error::wasi_resource_limit("Too many preopened directories"));
+        }
+        let guest: Vec<String> = normalize_guest_path(preopen.guest_path())?
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if self.entries.iter().any(|entry| entry.guest == guest) {
+            return Err(Error::wasi_invalid_argument("Guest path is already preopened"));
+        }
+        let root = preopen
+            .host_dir()
+            .canonicalize()
+            .map_err(|_| Error::wasi_invalid_argument("Preopened host directory does not exist"))?;
+        if !root.is_dir() {
+            return Err(Error::wasi_invalid_argument("Preopened host path is not a directory"));
+        }
+
+        let writable = allow_write && preopen.mode().allows_write();
+        self.entries.push(PreopenEntry {
+            preopen,
+            root,
+            guest,
+            writable,
+            usage: PreopenUsage::default(),
+        });
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Number of preopens
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table holds no preopens
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The preopen at `index`
+    pub fn preopen(&self, index: usize) -> Option<&Preopen> {
+        self.entries.get(index).map(|entry| &entry.preopen)
+    }
+
+    /// Canonical host directory of the preopen at `index`
+    pub fn host_root(&self, index: usize) -> Option<&Path> {
+        self.entries.get(index).map(|entry| entry.root.as_path())
+    }
+
+    /// Whether the preopen at `index` allows writes
+    pub fn is_writable(&self, index: usize) -> bool {
+        self.entries.get(index).is_some_and(|entry| entry.writable)
+    }
+
+    /// Resources used below the preopen at `index`
+    pub fn usage(&self, index: usize) -> Option<PreopenUsage> {
+        self.entries.get(index).map(|entry| entry.usage)
+    }
+
+    /// Resolve a guest path against the preopen with the longest matching
+    /// guest path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path lies outside every preopen, escapes its
+    /// preopen, or requests write access to a read-only preopen.
+    pub fn resolve(&self, guest_path: &str, access: PreopenAccess) -> Result<ResolvedPath> {
+        let components = normalize_guest_path(guest_path)?;
+        let (index, entry) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.guest.len() <= components.len()
+                    && entry.guest.iter().zip(&components).all(|(a, b)| a == b)
+            })
+            .max_by_key(|(_, entry)| entry.guest.len())
+            .ok_or_else(|| denied("filesystem.preopens", "Path is not below a preopened directory"))?;
+        let relative = &components[entry.guest.len()..];
+        self.resolve_components(index, relative, access)
+    }
+
+    /// Resolve a path relative to the preopen at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preopen does not exist, the path is absolute
+    /// or escapes the preopen, or write access is requested to a read-only
+    /// preopen.
+    pub fn resolve_at(&self, index: usize, path: &str, access: PreopenAccess) -> Result<ResolvedPath> {
+        if path.starts_with('/') {
+            return Err(denied("filesystem.preopens", "Absolute path escapes preopened directory"));
+        }
+        let components = normalize_guest_path(path)?;
+        self.resolve_components(index, &components, access)
+    }
+
+    /// Resolve normalized components below the preopen at `index`
+    fn resolve_components(&self, index: usize, components: &[&str], access: PreopenAccess) -> Result<ResolvedPath> {
+        let entry = self
+            .entries
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_fd("Unknown preopened directory"))?;
+        if access == PreopenAccess::Write && !entry.writable {
+            return Err(denied("preopen.writable", "Preopened directory is read-only"));
+        }
+
+        let mut host_path = entry.root.clone();
+        host_path.extend(components);
+        let host_path = confine(&entry.root, &host_path)?;
+        audit::check(true, "filesystem.preopens");
+        Ok(ResolvedPath {
+            preopen: index,
+            host_path,
+        })
+    }
+
+    /// Count a file opened below the preopen at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preopen does not exist or its open file quota
+    /// is exhausted.
+    pub fn open_file(&mut self, index: usize) -> Result<()> {
+        let entry = self
+            .entries
+            .get_mut(index)
+            .ok_or_else(|| Error::wasi_invalid_fd("Unknown preopened directory"))?;
+        if entry
+            .preopen
+            .quota()
+            .max_open_files
+            .is_some_and(|max| entry.usage.open_files >= max)
+        {
+            return Err(Error::wasi_resource_limit("Preopen open file quota exceeded"));
+        }
+        entry.usage.open_files += 1;
+        Ok(())
+    }
+
+    /// Count a file closed below the preopen at `index`
+    pub fn close_file(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.usage.open_files = entry.usage.open_files.saturating_sub(1);
+        }
+    }
+
+    /// Check that `len` more bytes may be written below the preopen at
+    /// `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preopen does not exist, is read-only, or the
+    /// write would exceed its byte quota.
+    pub fn check_write(&self, index: usize, len: u64) -> Result<()> {
+        let entry = self
+            .entries
+            .get(index)
+            .ok_or_else(|| Error::wasi_invalid_fd("Unknown preopened directory"))?;
+        if !entry.writable {
+            return Err(denied("preopen.writable", "Preopened directory is read-only"));
+        }
+        if entry
+            .preopen
+            .quota()
+            .max_bytes_written
+            .is_some_and(|max| entry.usage.bytes_written.saturating_add(len) > max)
+        {
+            return Err(Error::wasi_resource_limit("Preopen write quota exceeded"));
+        }
+        Ok(())
+    }
+
+    /// Count `len` bytes written below the preopen at `index`
+    pub fn record_write(&mut self, index: usize, len: u64) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.usage.bytes_written = entry.usage.bytes_written.saturating_add(len);
+        }
+    }
+}
+
+/// Permission error for a path the preopens do not grant, recorded in the
+/// capability audit log under `reason`
+fn denied(reason: &'static str, message: &'static str) -> Error {
+    audit::check(false, reason);
+    Error::wasi_permission_denied(message)
+}
+
+/// Split a guest path into components, applying `.` and `..` lexically
+///
+/// A `..` that would climb above the start of the path is rejected.
+fn normalize_guest_path(path: &str) -> Result<Vec<&str>> {
+    if path.len() > MAX_GUEST_PATH_LENGTH {
+        return Err(Error::wasi_invalid_argument("Guest path too long"));
+    }
+    if path.contains('\0') {
+        return Err(Error::wasi_invalid_argument("Guest path contains a NUL byte"));
+    }
+
+    let mut components = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {},
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(denied("filesystem.preopens", "Path escapes preopened directory"));
+                }
+            },
+            component => components.push(component),
+        }
+    }
+    Ok(components)
+}
+
+/// Resolve symbolic links in the existing part of `path` and check that
+/// the result stays below `root`
+///
+/// Dangling symbolic links are rejected, since creating a file through one
+/// would create it wherever the link points.
+fn confine(root: &Path, path: &Path) -> Result<PathBuf> {
+    let escape = || denied("filesystem.preopens", "Path escapes preopened directory");
+
+    let mut existing = path.to_path_buf();
+    let mut missing: Vec<OsString> = Vec::new();
+    while fs::symlink_metadata(&existing).is_err() {
+        let name = existing.file_name().ok_or_else(escape)?.to_os_string();
+        missing.push(name);
+        if !existing.pop() {
+            return Err(escape());
+        }
+    }
+
+    let mut resolved = existing.canonicalize().map_err(|_| escape())?;
+    if !resolved.starts_with(root) {
+        return Err(escape());
+    }
+    resolved.extend(missing.iter().rev());
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wrt-wasi-preopens-{}-{name}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(dir.join("sandbox/sub")).unwrap();
+        fs::create_dir_all(dir.join("outside")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_maps_guest_paths() -> Result<()> {
+        let dir = scratch_dir("map");
+        let mut table = PreopenTable::new();
+        table.insert(Preopen::new(dir.join("sandbox"), "/data", PreopenMode::ReadWrite), true)?;
+        table.insert(Preopen::new(dir.join("outside"), "/data/other", PreopenMode::ReadOnly), true)?;
+        let root = table.host_root(0).unwrap().to_path_buf();
+
+        let resolved = table.resolve("/data/sub/./new.txt", PreopenAccess::Write)?;
+        assert_eq!(resolved.preopen, 0);
+        assert_eq!(resolved.host_path, root.join("sub/new.txt"));
+        assert_eq!(table.resolve("/data/other/x", PreopenAccess::Read)?.preopen, 1);
+        assert_eq!(table.resolve_at(0, "sub/../a", PreopenAccess::Read)?.host_path, root.join("a"));
+
+        assert!(table.resolve("/data/other/x", PreopenAccess::Write).is_err());
+        assert!(table.resolve("/etc/passwd", PreopenAccess::Read).is_err());
+        assert!(table.resolve_at(0, "../outside/x", PreopenAccess::Read).is_err());
+        assert!(table.resolve_at(0, "/etc/passwd", PreopenAccess::Read).is_err());
+        assert!(table
+            .insert(Preopen::new(dir.join("outside"), "/data/", PreopenMode::ReadOnly), true)
+            .is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_symlink_escapes() -> Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = scratch_dir("symlink");
+        symlink(dir.join("outside"), dir.join("sandbox/escape")).unwrap();
+        symlink(dir.join("outside/missing"), dir.join("sandbox/dangling")).unwrap();
+        symlink(dir.join("sandbox/sub"), dir.join("sandbox/inside")).unwrap();
+        let mut table = PreopenTable::new();
+        table.insert(Preopen::new(dir.join("sandbox"), ".", PreopenMode::ReadWrite), true)?;
+
+        assert!(table.resolve("escape/file", PreopenAccess::Write).is_err());
+        assert!(table.resolve("dangling", PreopenAccess::Write).is_err());
+        let inside = table.resolve("inside/file", PreopenAccess::Write)?;
+        assert_eq!(inside.host_path, table.host_root(0).unwrap().join("sub/file"));
+
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_quotas_and_capability_write_access() -> Result<()> {
+        let dir = scratch_dir("quota");
+        let quota = PreopenQuota::unlimited().with_max_bytes_written(10).with_max_open_files(1);
+        let mut table = PreopenTable::new();
+        table.insert(Preopen::new(dir.join("sandbox"), "/", PreopenMode::ReadWrite).with_quota(quota), true)?;
+        table.insert(Preopen::new(dir.join("outside"), "/ro", PreopenMode::ReadWrite), false)?;
+
+        table.open_file(0)?;
+        assert!(table.open_file(0).is_err());
+        table.close_file(0);
+        table.open_file(0)?;
+
+        table.check_write(0, 6)?;
+        table.record_write(0, 6);
+        assert!(table.check_write(0, 5).is_err());
+        table.check_write(0, 4)?;
+        assert_eq!(table.usage(0), Some(PreopenUsage { bytes_written: 6, open_files: 1 }));
+
+        assert!(!table.is_writable(1));
+        assert!(table.check_write(1, 1).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+}
@@ -8,7 +8,9 @@
 use wrt_platform::time::PlatformTime;
 
 use crate::{
+    audit,
     capabilities::WasiClockCapabilities,
+    determinism::virtual_clock_now,
     host_provider::resource_manager::WasiClockType,
     prelude::*,
     Value,
@@ -23,7 +25,8 @@
 /// This function is infallible and always returns `Ok`.
 pub fn wasi_monotonic_clock_now(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     // Get monotonic time using platform abstraction
-    let nanoseconds: u64 = PlatformTime::monotonic_ns();
+    let nanoseconds: u64 = virtual_clock_now(WasiClockType::Monotonic)
+        .unwrap_or_else(PlatformTime::monotonic_ns);
 
     Ok(vec![Value::U64(nanoseconds)])
 }
@@ -37,8 +40,11 @@ pub fn wasi_monotonic_clock_now(_target: &mut dyn Any, _args: &[Value]) -> Resul
 /// Returns an error if the wall clock is not available on this platform.
 pub fn wasi_wall_clock_now(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     // Get wall clock time using platform abstraction
-    let total_ns = PlatformTime::wall_clock_ns()
-        .map_err(|_| Error::wasi_capability_unavailable("Wall clock not available"))?;
+    let total_ns = match virtual_clock_now(WasiClockType::Realtime) {
+        Some(ns) => ns,
+        None => PlatformTime::wall_clock_ns()
+            .map_err(|_| Error::wasi_capability_unavailable("Wall clock not available"))?,
+    };
 
     // Convert to seconds and nanoseconds
     let seconds = total_ns / 1_000_000_000;
@@ -94,8 +100,11 @@ pub fn wasi_wall_clock_resolution(_target: &mut dyn Any, _args: &[Value]) -> Res
 /// Returns an error if process CPU time is not available on this platform.
 pub fn wasi_process_cpu_time_now(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     // Get process CPU time using platform abstraction
-    let cpu_time = PlatformTime::process_cpu_time_ns()
-        .map_err(|_| Error::wasi_capability_unavailable("Process CPU time not available"))?;
+    let cpu_time = match virtual_clock_now(WasiClockType::ProcessCpuTime) {
+        Some(ns) => ns,
+        None => PlatformTime::process_cpu_time_ns()
+            .map_err(|_| Error::wasi_capability_unavailable("Process CPU time not available"))?,
+    };
 
     Ok(vec![Value::U64(cpu_time)])
 }
@@ -110,8 +119,11 @@ pub fn wasi_process_cpu_time_now(_target: &mut dyn Any, _args: &[Value]) -> Resu
 /// Returns an error if thread CPU time is not available on this platform.
 pub fn wasi_thread_cpu_time_now(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     // Get thread CPU time using platform abstraction
-    let cpu_time = PlatformTime::thread_cpu_time_ns()
-        .map_err(|_| Error::wasi_capability_unavailable("Thread CPU time not available"))?;
+    let cpu_time = match virtual_clock_now(WasiClockType::ThreadCpuTime) {
+        Some(ns) => ns,
+        None => PlatformTime::thread_cpu_time_ns()
+            .map_err(|_| Error::wasi_capability_unavailable("Thread CPU time not available"))?,
+    };
 
     Ok(vec![Value::U64(cpu_time)])
 }
@@ -177,47 +189,57 @@ pub fn get_time_with_capabilities(
     clock_type: WasiClockType,
     capabilities: &WasiClockCapabilities,
 ) -> Result<u64> {
-    use crate::host_provider::resource_manager::WasiClockType;
-
     match clock_type {
         WasiClockType::Realtime => {
-            if !capabilities.realtime_access {
+            if !audit::check(capabilities.realtime_access, "clocks.realtime_access") {
                 return Err(Error::wasi_permission_denied(
                     "Realtime clock access denied",
                 ));
             }
 
+            if let Some(ns) = virtual_clock_now(clock_type) {
+                return Ok(ns);
+            }
+
             let total_ns = PlatformTime::wall_clock_ns()
                 .map_err(|_| Error::wasi_capability_unavailable("Wall clock not available"))?;
 
             Ok(total_ns)
         },
         WasiClockType::Monotonic => {
-            if !capabilities.monotonic_access {
+            if !audit::check(capabilities.monotonic_access, "clocks.monotonic_access") {
                 return Err(Error::wasi_permission_denied(
                     "Monotonic clock access denied",
                 ));
             }
 
-            Ok(PlatformTime::monotonic_ns())
+            Ok(virtual_clock_now(clock_type).unwrap_or_else(PlatformTime::monotonic_ns))
         },
         WasiClockType::ProcessCpuTime => {
-            if !capabilities.process_cputime_access {
+            if !audit::check(capabilities.process_cputime_access, "clocks.process_cputime_access") {
                 return Err(Error::wasi_permission_denied(
                     "Process CPU time access denied",
                 ));
             }
 
+            if let Some(ns) = virtual_clock_now(clock_type) {
+                return Ok(ns);
+            }
+
             PlatformTime::process_cpu_time_ns()
                 .map_err(|_| Error::wasi_capability_unavailable("Process CPU time not available"))
         },
         WasiClockType::ThreadCpuTime => {
-            if !capabilities.thread_cputime_access {
+            if !audit::check(capabilities.thread_cputime_access, "clocks.thread_cputime_access") {
                 return Err(Error::wasi_permission_denied(
                     "Thread CPU time access denied",
                 ));
             }
 
+            if let Some(ns) = virtual_clock_now(clock_type) {
+                return Ok(ns);
+            }
+
             PlatformTime::thread_cpu_time_ns()
                 .map_err(|_| Error::wasi_capability_unavailable("Thread CPU time not available"))
         },
@@ -230,7 +252,7 @@ mod tests {
 
     #[test]
     fn test_wasi_monotonic_clock_now() -> Result<()> {
-        let result = wasi_monotonic_clock_now(&mut (), vec![])?;
+        let result = wasi_monotonic_clock_now(&mut (), &[])?;
         assert_eq!(result.len(), 1);
 
         // Should return a u64 timestamp
@@ -246,7 +268,7 @@ fn test_wasi_monotonic_clock_now() -> Result<()> {
 
     #[test]
     fn test_wasi_wall_clock_now() -> Result<()> {
-        let result = wasi_wall_clock_now(&mut (), vec![])?;
+        let result = wasi_wall_clock_now(&mut (), &[])?;
         assert_eq!(result.len(), 1);
 
         // Should return a tuple of (seconds, nanoseconds)
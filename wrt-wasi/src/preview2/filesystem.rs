@@ -18,11 +18,25 @@
     prelude::*,
     Value,
 };
+#[cfg(feature = "std")]
+use crate::{
+    capabilities::WasiFileSystemCapabilities,
+    preopens::{
+        PreopenAccess,
+        PreopenTable,
+    },
+};
 
 /// File descriptor table for tracking open files
 #[cfg(feature = "std")]
 static FILE_TABLE: RwLock<Option<FileDescriptorTable>> = RwLock::new(None);
 
+/// Preopened directories that paths are resolved against, if installed
+///
+/// Without preopens, paths are opened as given.
+#[cfg(feature = "std")]
+static PREOPEN_TABLE: RwLock<Option<PreopenTable>> = RwLock::new(None);
+
 /// File descriptor table implementation
 #[cfg(feature = "std")]
 pub struct FileDescriptorTable {
@@ -91,6 +105,8 @@ pub struct OpenFile {
     readable: bool,
     /// Whether the file is writable
     writable: bool,
+    /// Index of the preopen the file lies in, if preopens are installed
+    preopen: Option<usize>,
 }
 
 /// Initialize the file descriptor table
@@ -104,6 +120,58 @@ fn ensure_file_table() -> Result<()> {
     Ok(())
 }
 
+/// Install the preopens of `capabilities` for all filesystem operations
+///
+/// Once installed, every path is resolved against the preopens and paths
+/// outside them are refused. Capabilities without preopens uninstall the
+/// table again.
+///
+/// # Errors
+///
+/// Returns an error if a preopened host directory does not exist or the
+/// preopen table lock cannot be acquired.
+#[cfg(feature = "std")]
+pub fn install_preopens(capabilities: &WasiFileSystemCapabilities) -> Result<()> {
+    let table = if capabilities.preopens().is_empty() {
+        None
+    } else {
+        Some(PreopenTable::from_capabilities(capabilities)?)
+    };
+    *PREOPEN_TABLE.write()
+        .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire preopen table lock"))? = table;
+    Ok(())
+}
+
+/// Resolve a guest path against the installed preopens
+///
+/// Returns the path unchanged if no preopens are installed. With
+/// `count_open`, the file is counted against the preopen's open file quota.
+#[cfg(feature = "std")]
+fn resolve_guest_path(path: &str, access: PreopenAccess, count_open: bool) -> Result<(PathBuf, Option<usize>)> {
+    let mut preopens = PREOPEN_TABLE.write()
+        .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire preopen table lock"))?;
+    match preopens.as_mut() {
+        Some(table) => {
+            let resolved = table.resolve(path, access)?;
+            if count_open {
+                table.open_file(resolved.preopen)?;
+            }
+            Ok((resolved.host_path, Some(resolved.preopen)))
+        },
+        None => Ok((PathBuf::from(path), None)),
+    }
+}
+
+/// Release a file's slot in its preopen's open file quota
+#[cfg(feature = "std")]
+fn release_preopen_file(preopen: Option<usize>) {
+    if let (Some(index), Ok(mut preopens)) = (preopen, PREOPEN_TABLE.write()) {
+        if let Some(table) = preopens.as_mut() {
+            table.close_file(index);
+        }
+    }
+}
+
 // ============================================================================
 // WASI Filesystem Operations
 // ============================================================================
@@ -113,12 +181,16 @@ fn ensure_file_table() -> Result<()> {
 /// Opens a file relative to a directory descriptor.
 /// Implements `wasi:filesystem/types.open-at`
 ///
+/// If preopens are installed, the path is resolved against them.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The file table lock cannot be acquired
 /// - The file table is not initialized
 /// - Arguments are missing or have invalid types
+/// - The path lies outside the preopens, or needs write access to a
+///   read-only preopen, or the preopen's open file quota is exhausted
 /// - The file cannot be opened (permission denied, not found, etc.)
 pub fn wasi_filesystem_open_at(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
     #[cfg(feature = "std")]
@@ -156,9 +228,22 @@ pub fn wasi_filesystem_open_at(_target: &mut dyn Any, args: &[Value]) -> Result<
             options.write(true);
         }
 
+        // Resolve the path inside the preopens
+        let access = if writable || open_flags.create || open_flags.truncate {
+            PreopenAccess::Write
+        } else {
+            PreopenAccess::Read
+        };
+        let (host_path, preopen) = resolve_guest_path(path, access, true)?;
+
         // Open the file
-        let file = options.open(path)
-            .map_err(|e| map_io_error(&e, "Failed to open file"))?;
+        let file = match options.open(&host_path) {
+            Ok(file) => file,
+            Err(e) => {
+                release_preopen_file(preopen);
+                return Err(map_io_error(&e, "Failed to open file"));
+            }
+        };
 
         // Register in file table
         let mut table = FILE_TABLE.write()
@@ -168,9 +253,10 @@ pub fn wasi_filesystem_open_at(_target: &mut dyn Any, args: &[Value]) -> Result<
 
         let open_file = OpenFile {
             file,
-            _path: PathBuf::from(path),
+            _path: host_path,
             readable,
             writable,
+            preopen,
         };
 
         let fd = table.insert(open_file);
@@ -266,6 +352,7 @@ pub fn wasi_filesystem_read(_target: &mut dyn Any, args: &[Value]) -> Result<Vec
 /// - The file table lock cannot be acquired
 /// - The file table is not initialized
 /// - The file descriptor is invalid
+/// - The write would exceed the write quota of the file's preopen
 /// - The file is not writable or seek/write operations fail
 pub fn wasi_filesystem_write(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
     #[cfg(feature = "std")]
@@ -298,10 +385,25 @@ pub fn wasi_filesystem_write(_target: &mut dyn Any, args: &[Value]) -> Result<Ve
                 .map_err(|e| map_io_error(&e, "Failed to seek"))?;
         }
 
+        // Check the write quota of the file's preopen
+        let mut preopens = PREOPEN_TABLE.write()
+            .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire preopen table lock"))?;
+        let quota = match (open_file.preopen, preopens.as_mut()) {
+            (Some(index), Some(preopens)) => {
+                preopens.check_write(index, data.len() as u64)?;
+                Some((index, preopens))
+            },
+            _ => None,
+        };
+
         // Write data
         let bytes_written = open_file.file.write(&data)
             .map_err(|e| map_io_error(&e, "Failed to write file"))?;
 
+        if let Some((index, preopens)) = quota {
+            preopens.record_write(index, bytes_written as u64);
+        }
+
         Ok(vec![Value::Result(Ok(Box::new(Value::U64(bytes_written as u64))))])
     }
 
@@ -334,7 +436,8 @@ pub fn wasi_filesystem_close(_target: &mut dyn Any, args: &[Value]) -> Result<Ve
         let table = table.as_mut()
             .ok_or_else(|| Error::wasi_capability_unavailable("File table not initialized"))?;
 
-        if table.remove(fd).is_some() {
+        if let Some(open_file) = table.remove(fd) {
+            release_preopen_file(open_file.preopen);
             Ok(vec![])
         } else {
             Err(Error::wasi_invalid_fd("Invalid file descriptor"))
@@ -394,19 +497,22 @@ pub fn wasi_filesystem_stat(_target: &mut dyn Any, args: &[Value]) -> Result<Vec
 /// Gets file metadata by path relative to a directory.
 /// Implements `wasi:filesystem/types.stat-at`
 ///
+/// If preopens are installed, the path is resolved against them.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The directory descriptor argument is missing or invalid
-/// - The path argument is missing or invalid
+/// - The path argument is missing or invalid, or lies outside the preopens
 /// - Metadata retrieval for the path fails
 pub fn wasi_filesystem_stat_at(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
     #[cfg(feature = "std")]
     {
         let _dir_fd = extract_file_descriptor(args)?;
         let path = extract_string(args, 2)?;
+        let (host_path, _) = resolve_guest_path(path, PreopenAccess::Read, false)?;
 
-        let metadata = std::fs::metadata(path)
+        let metadata = std::fs::metadata(host_path)
             .map_err(|e| map_io_error(&e, "Failed to get file metadata"))?;
 
         let stat = build_descriptor_stat(&metadata);
@@ -522,6 +628,11 @@ pub fn wasi_filesystem_set_times(_target: &mut dyn Any, args: &[Value]) -> Resul
         use std::fs::FileTimes;
         use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+        use crate::{
+            determinism::virtual_clock_now,
+            host_provider::resource_manager::WasiClockType,
+        };
+
         ensure_file_table()?;
 
         let fd = extract_file_descriptor(args)?;
@@ -543,15 +654,19 @@ pub fn wasi_filesystem_set_times(_target: &mut dyn Any, args: &[Value]) -> Resul
         let current_atime = metadata.accessed().unwrap_or(SystemTime::now());
         let current_mtime = metadata.modified().unwrap_or(SystemTime::now());
 
+        // "Now" follows the virtual clock in deterministic runs
+        let now = virtual_clock_now(WasiClockType::Realtime)
+            .map_or_else(SystemTime::now, |ns| UNIX_EPOCH + Duration::from_nanos(ns));
+
         // Convert WASI timestamps to SystemTime
         let atime_system = match atime {
-            TimestampValue::Now => SystemTime::now(),
+            TimestampValue::Now => now,
             TimestampValue::Timestamp(ns) => UNIX_EPOCH + Duration::from_nanos(ns),
             TimestampValue::NoChange => current_atime,
         };
 
         let mtime_system = match mtime {
-            TimestampValue::Now => SystemTime::now(),
+            TimestampValue::Now => now,
             TimestampValue::Timestamp(ns) => UNIX_EPOCH + Duration::from_nanos(ns),
             TimestampValue::NoChange => current_mtime,
         };
@@ -1010,7 +1125,7 @@ mod tests {
     #[test]
     fn test_extract_file_descriptor() {
         let args = vec![Value::U32(42)];
-        assert_eq!(extract_file_descriptor(args).unwrap(), 42);
+        assert_eq!(extract_file_descriptor(&args).unwrap(), 42);
 
         let invalid_args = vec![Value::String("not_a_fd".to_string())];
         assert!(extract_file_descriptor(&invalid_args).is_err());
@@ -1019,7 +1134,7 @@ fn test_extract_file_descriptor() {
     #[test]
     fn test_extract_length() {
         let args = vec![Value::U32(0), Value::U64(1024)];
-        assert_eq!(extract_length(args, 1).unwrap(), 1024);
+        assert_eq!(extract_length(&args, 1).unwrap(), 1024);
 
         let args_u32 = vec![Value::U32(0), Value::U32(512)];
         assert_eq!(extract_length(&args_u32, 1).unwrap(), 512);
@@ -1030,7 +1145,7 @@ fn test_extract_byte_data() -> Result<()> {
         let data = vec![Value::U8(1), Value::U8(2), Value::U8(3)];
         let args = vec![Value::U32(42), Value::List(data)];
 
-        let bytes = extract_byte_data(args, 1)?;
+        let bytes = extract_byte_data(&args, 1)?;
         assert_eq!(bytes, vec![1, 2, 3]);
 
         Ok(())
@@ -1039,7 +1154,7 @@ fn test_extract_byte_data() -> Result<()> {
     #[test]
     fn test_extract_string() -> Result<()> {
         let args = vec![Value::U32(42), Value::String("test.txt".to_string())];
-        let path = extract_string(args, 1)?;
+        let path = extract_string(&args, 1)?;
         assert_eq!(path, "test.txt");
 
         Ok(())
@@ -1049,7 +1164,7 @@ fn test_extract_string() -> Result<()> {
     fn test_open_flags_extraction() {
         // Test bit-based flags
         let args = vec![Value::U32(0), Value::U32(0), Value::String("test".to_string()), Value::U32(0x05)];
-        let flags = extract_open_flags(args, 3).unwrap();
+        let flags = extract_open_flags(&args, 3).unwrap();
         assert!(flags.create);
         assert!(!flags.directory);
         assert!(flags.exclusive);
@@ -1059,7 +1174,7 @@ fn test_open_flags_extraction() {
     #[test]
     fn test_descriptor_flags_extraction() {
         let args = vec![Value::U32(0), Value::U32(0), Value::String("test".to_string()), Value::U32(0), Value::U32(0x03)];
-        let flags = extract_descriptor_flags(args, 4).unwrap();
+        let flags = extract_descriptor_flags(&args, 4).unwrap();
         assert!(flags.read);
         assert!(flags.write);
         assert!(!flags.sync);
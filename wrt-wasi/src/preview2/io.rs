@@ -22,6 +22,11 @@
 
 use crate::{
     prelude::*,
+    stdio::{
+        self,
+        StdStream,
+        StreamTarget,
+    },
     Value,
 };
 
@@ -278,28 +283,13 @@ fn ensure_pollable_table() -> Result<()> {
 /// Check if an input stream is ready for reading
 #[cfg(feature = "std")]
 fn check_input_stream_ready(stream_handle: u32) -> bool {
-    match stream_handle {
-        0 => {
-            // stdin - check with platform-specific mechanism
-            use std::io::IsTerminal;
-            // For terminals, assume potentially ready
-            // For pipes, assume ready (EOF or data)
-            !std::io::stdin().is_terminal()
-        },
-        _ => {
-            // File descriptors from filesystem - assume ready
-            // A full implementation would use select/poll/epoll
-            true
-        }
-    }
+    stdio::ready(StreamTarget::from_handle(stream_handle)).unwrap_or(false)
 }
 
 /// Check if an output stream is ready for writing
 #[cfg(feature = "std")]
-fn check_output_stream_ready(_stream_handle: u32) -> bool {
-    // All streams are currently assumed ready for buffered I/O
-    // A full implementation would check buffer space for non-stdout/stderr
-    true
+fn check_output_stream_ready(stream_handle: u32) -> bool {
+    stdio::ready(StreamTarget::from_handle(stream_handle)).unwrap_or(false)
 }
 
 /// Check if a timer has expired
@@ -307,6 +297,10 @@ fn check_output_stream_ready(_stream_handle: u32) -> bool {
 fn check_timer_ready(deadline_ns: u64) -> bool {
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    if let Some(ready) = crate::determinism::virtual_timer_ready(deadline_ns) {
+        return ready;
+    }
+
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_nanos() as u64)
@@ -574,14 +568,7 @@ pub fn wasi_stream_forward(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<
 ///
 /// Returns an error if the stream handle argument is missing or invalid.
 pub fn wasi_drop_input_stream(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
-    let stream_handle = extract_stream_handle(args)?;
-
-    // For stdin (0), we don't actually close it
-    // For other streams, would need to close the underlying resource
-    if stream_handle >= 3 {
-        // Would close the stream in a full implementation
-    }
-
+    drop_stream(extract_stream_handle(args)?, true)?;
     Ok(vec![])
 }
 
@@ -593,14 +580,7 @@ pub fn wasi_drop_input_stream(_target: &mut dyn Any, args: &[Value]) -> Result<V
 ///
 /// Returns an error if the stream handle argument is missing or invalid.
 pub fn wasi_drop_output_stream(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
-    let stream_handle = extract_stream_handle(args)?;
-
-    // For stdout/stderr (1, 2), we don't actually close them
-    // For other streams, would need to close the underlying resource
-    if stream_handle >= 3 {
-        // Would close the stream in a full implementation
-    }
-
+    drop_stream(extract_stream_handle(args)?, false)?;
     Ok(vec![])
 }
 
@@ -626,10 +606,10 @@ pub fn wasi_stream_subscribe(_target: &mut dyn Any, args: &[Value]) -> Result<Ve
         let table = table.as_mut()
             .ok_or_else(|| Error::wasi_capability_unavailable("Pollable table not initialized"))?;
 
-        // Determine if this is an input or output stream
-        let pollable_handle = match stream_handle {
-            0 => table.create_for_input(stream_handle), // stdin is input
-            _ => table.create_for_output(stream_handle), // stdout/stderr and file handles are output
+        let pollable_handle = if is_input_stream(stream_handle)? {
+            table.create_for_input(stream_handle)
+        } else {
+            table.create_for_output(stream_handle)
         };
 
         Ok(vec![Value::U32(pollable_handle)])
@@ -805,7 +785,7 @@ pub fn wasi_drop_pollable(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<V
 }
 
 /// Helper function to extract stream handle from arguments
-fn extract_stream_handle(args: &[Value]) -> Result<u32> {
+pub(crate) fn extract_stream_handle(args: &[Value]) -> Result<u32> {
     if args.is_empty() {
         return Err(Error::wasi_invalid_fd("Missing stream handle argument"));
     }
@@ -824,7 +804,7 @@ fn extract_stream_handle(args: &[Value]) -> Result<u32> {
 }
 
 /// Helper function to extract read length from arguments
-fn extract_read_length(args: &[Value], index: usize) -> Result<u64> {
+pub(crate) fn extract_read_length(args: &[Value], index: usize) -> Result<u64> {
     if args.len() <= index {
         return Err(Error::wasi_invalid_fd("Missing read length argument"));
     }
@@ -837,7 +817,7 @@ fn extract_read_length(args: &[Value], index: usize) -> Result<u64> {
 }
 
 /// Helper function to extract write data from arguments
-fn extract_write_data(args: &[Value], index: usize) -> Result<Vec<u8>> {
+pub(crate) fn extract_write_data(args: &[Value], index: usize) -> Result<Vec<u8>> {
     if args.len() <= index {
         return Err(Error::wasi_invalid_fd("Missing write data argument"));
     }
@@ -893,152 +873,46 @@ fn extract_timestamp_ns(args: &[Value]) -> Result<u64> {
 
 /// Platform-specific stream read implementation
 fn perform_stream_read(stream_handle: u32, len: u64) -> Result<Vec<u8>> {
-    // In a real implementation, this would:
-    // 1. Look up the stream in the resource manager
-    // 2. Check capabilities (readable)
-    // 3. Perform platform-specific read operation
-    // 4. Update stream position if applicable
-
-    match stream_handle {
-        0 => {
-            // stdin - in a real implementation, read from platform stdin
-            #[cfg(feature = "std")]
-            {
-                use std::io::{
-                    self,
-                    Read,
-                };
-                let mut buffer = vec![0u8; len.min(4096) as usize];
-                let bytes_read = io::stdin()
-                    .read(&mut buffer)
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to read from stdin"))?;
-                buffer.truncate(bytes_read);
-                Ok(buffer)
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                // In no_std environment, return empty data
-                Ok(Vec::new())
-            }
-        },
-        _ => {
-            // Other streams - placeholder implementation
-            Ok(Vec::new())
-        },
-    }
+    let stream = StreamTarget::from_handle(stream_handle);
+    Ok(stdio::read(stream, len.min(usize::MAX as u64) as usize)?.unwrap_or_default())
 }
 
 /// Platform-specific stream write implementation
 fn perform_stream_write(stream_handle: u32, data: &[u8]) -> Result<u64> {
-    // In a real implementation, this would:
-    // 1. Look up the stream in the resource manager
-    // 2. Check capabilities (writable)
-    // 3. Perform platform-specific write operation
-    // 4. Update stream position if applicable
-
-    match stream_handle {
-        1 => {
-            // stdout - in a real implementation, write to platform stdout
-            #[cfg(feature = "std")]
-            {
-                use std::io::{
-                    self,
-                    Write,
-                };
-                io::stdout()
-                    .write_all(data)
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to write to stdout"))?;
-                io::stdout()
-                    .flush()
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to flush stdout"))?;
-                Ok(data.len() as u64)
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                // In no_std environment, return success without actual write
-                Ok(data.len() as u64)
-            }
-        },
-        2 => {
-            // stderr - in a real implementation, write to platform stderr
-            #[cfg(feature = "std")]
-            {
-                use std::io::{
-                    self,
-                    Write,
-                };
-                io::stderr()
-                    .write_all(data)
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to write to stderr"))?;
-                io::stderr()
-                    .flush()
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to flush stderr"))?;
-                Ok(data.len() as u64)
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                // In no_std environment, return success without actual write
-                Ok(data.len() as u64)
-            }
-        },
-        _ => {
-            // Other streams - placeholder implementation
-            Ok(data.len() as u64)
-        },
-    }
+    let stream = StreamTarget::from_handle(stream_handle);
+    stdio::write_all(stream, data)?;
+    stdio::flush(stream)?;
+    Ok(data.len() as u64)
 }
 
 /// Platform-specific stream flush implementation
 fn perform_stream_flush(stream_handle: u32) -> Result<()> {
-    match stream_handle {
-        1 => {
-            // stdout flush
-            #[cfg(feature = "std")]
-            {
-                use std::io::{
-                    self,
-                    Write,
-                };
-                io::stdout()
-                    .flush()
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to flush stdout"))?;
-            }
-            Ok(())
-        },
-        2 => {
-            // stderr flush
-            #[cfg(feature = "std")]
-            {
-                use std::io::{
-                    self,
-                    Write,
-                };
-                io::stderr()
-                    .flush()
-                    .map_err(|_| Error::wasi_capability_unavailable("Failed to flush stderr"))?;
-            }
-            Ok(())
-        },
-        _ => {
-            // Other streams - no-op for now
-            Ok(())
-        },
-    }
+    stdio::flush(StreamTarget::from_handle(stream_handle))
 }
 
 /// Check write capacity for stream
-// Result kept for API consistency and future error handling.
-#[allow(clippy::unnecessary_wraps)]
 fn check_write_capacity(stream_handle: u32) -> Result<u64> {
-    match stream_handle {
-        1 | 2 => {
-            // stdout/stderr - assume always ready for a reasonable amount
-            Ok(4096)
-        },
-        _ => {
-            // Other streams - placeholder implementation
-            Ok(1024)
-        },
+    Ok(stdio::check_write(StreamTarget::from_handle(stream_handle))? as u64)
+}
+
+/// Whether the stream behind a handle is an input stream
+fn is_input_stream(stream_handle: u32) -> Result<bool> {
+    match StreamTarget::from_handle(stream_handle) {
+        StreamTarget::Std(stream) => Ok(stream == StdStream::Stdin),
+        StreamTarget::Host(handle) => stdio::is_input_stream(handle),
+    }
+}
+
+/// Close the host stream behind a handle
+///
+/// The standard streams stay open for the other handles to them.
+fn drop_stream(stream_handle: u32, input: bool) -> Result<()> {
+    if is_input_stream(stream_handle)? != input {
+        return Err(Error::wasi_invalid_fd("Stream has the wrong direction"));
+    }
+    match StreamTarget::from_handle(stream_handle) {
+        StreamTarget::Std(_) => Ok(()),
+        StreamTarget::Host(handle) => stdio::close_stream(handle),
     }
 }
 
@@ -1081,33 +955,10 @@ fn poll_pollables(pollables: &[u32]) -> Result<Vec<bool>> {
 /// - stdin (0): Checks if input is available without blocking
 /// - stdout (1): Always ready for writing
 /// - stderr (2): Always ready for writing
-/// - Other streams: Check based on stream type
+/// - Host streams: Checked through their source or sink
 #[cfg(not(feature = "std"))]
 fn check_stream_ready(stream_handle: u32) -> Result<bool> {
-    match stream_handle {
-        0 => {
-            // stdin - in no_std, assume not ready (conservative)
-            Ok(false)
-        }
-        1 | 2 => {
-            // stdout/stderr - always ready for writing
-            // These are typically buffered and non-blocking
-            Ok(true)
-        }
-        _ => {
-            // For file descriptors from the filesystem, we'd need to check
-            // using platform-specific mechanisms. For now, assume ready
-            // for any handle in the user range (>= 3)
-            if stream_handle >= 3 {
-                // User file handles - assume ready
-                // A full implementation would check the actual file
-                Ok(true)
-            } else {
-                // Unknown handle
-                Ok(false)
-            }
-        }
-    }
+    stdio::ready(StreamTarget::from_handle(stream_handle))
 }
 
 #[cfg(test)]
@@ -1117,16 +968,16 @@ mod tests {
     #[test]
     fn test_extract_stream_handle() -> Result<()> {
         let args = vec![Value::U32(42)];
-        let handle = extract_stream_handle(args)?;
+        let handle = extract_stream_handle(&args)?;
         assert_eq!(handle, 42);
 
         let args = vec![Value::S32(24)];
-        let handle = extract_stream_handle(args)?;
+        let handle = extract_stream_handle(&args)?;
         assert_eq!(handle, 24);
 
         // Test negative handle
         let args = vec![Value::S32(-1)];
-        let result = extract_stream_handle(args);
+        let result = extract_stream_handle(&args);
         assert!(result.is_err());
 
         Ok(())
@@ -1135,11 +986,11 @@ fn test_extract_stream_handle() -> Result<()> {
     #[test]
     fn test_extract_read_length() -> Result<()> {
         let args = vec![Value::U32(42), Value::U64(1024)];
-        let len = extract_read_length(args, 1)?;
+        let len = extract_read_length(&args, 1)?;
         assert_eq!(len, 1024);
 
         let args = vec![Value::U32(42), Value::U32(512)];
-        let len = extract_read_length(args, 1)?;
+        let len = extract_read_length(&args, 1)?;
         assert_eq!(len, 512);
 
         Ok(())
@@ -1150,7 +1001,7 @@ fn test_extract_write_data() -> Result<()> {
         let data = vec![Value::U8(1), Value::U8(2), Value::U8(3)];
         let args = vec![Value::U32(42), Value::List(data)];
 
-        let bytes = extract_write_data(args, 1)?;
+        let bytes = extract_write_data(&args, 1)?;
         assert_eq!(bytes, vec![1, 2, 3]);
 
         Ok(())
@@ -1167,7 +1018,7 @@ fn test_wasi_stream_operations() -> Result<()> {
             Value::U8(111),
         ]; // "Hello"
         let args = vec![Value::U32(1), Value::List(data)];
-        let result = wasi_stream_write(&mut (), args)?;
+        let result = wasi_stream_write(&mut (), &args)?;
         assert_eq!(result.len(), 1);
         if let Value::U64(bytes_written) = &result[0] {
             assert_eq!(*bytes_written, 5);
@@ -1175,12 +1026,12 @@ fn test_wasi_stream_operations() -> Result<()> {
 
         // Test flush operation
         let args = vec![Value::U32(1)];
-        let result = wasi_stream_flush(&mut (), args)?;
+        let result = wasi_stream_flush(&mut (), &args)?;
         assert_eq!(result.len(), 0); // Flush returns unit
 
         // Test check-write operation
         let args = vec![Value::U32(1)];
-        let result = wasi_stream_check_write(&mut (), args)?;
+        let result = wasi_stream_check_write(&mut (), &args)?;
         assert_eq!(result.len(), 1);
         if let Value::U64(capacity) = &result[0] {
             assert!(*capacity > 0);
@@ -1189,11 +1040,44 @@ fn test_wasi_stream_operations() -> Result<()> {
         Ok(())
     }
 
+    #[test]
+    fn test_unknown_stream_handle() {
+        let handle = vec![Value::U32(42)];
+        let write = vec![Value::U32(42), Value::List(vec![Value::U8(1)])];
+        let read = vec![Value::U32(42), Value::U64(16)];
+
+        assert!(wasi_stream_write(&mut (), &write).is_err());
+        assert!(wasi_stream_flush(&mut (), &handle).is_err());
+        assert!(wasi_stream_check_write(&mut (), &handle).is_err());
+        assert!(wasi_stream_read(&mut (), &read).is_err());
+        assert!(wasi_stream_subscribe(&mut (), &handle).is_err());
+        assert!(wasi_drop_output_stream(&mut (), &handle).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_registered_stream_handle() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        let sink = Arc::new(Mutex::new(stdio::BoundedPipe::<8>::new()));
+        let handle = stdio::register_output_stream(Arc::clone(&sink))?;
+
+        let args = vec![Value::U32(handle), Value::List(vec![Value::U8(1), Value::U8(2)])];
+        assert_eq!(wasi_stream_write(&mut (), &args)?, vec![Value::U64(2)]);
+        assert_eq!(wasi_stream_check_write(&mut (), &[Value::U32(handle)])?, vec![Value::U64(6)]);
+        assert_eq!(sink.lock().unwrap().len(), 2);
+        assert!(wasi_drop_input_stream(&mut (), &[Value::U32(handle)]).is_err());
+
+        wasi_drop_output_stream(&mut (), &[Value::U32(handle)])?;
+        assert!(wasi_stream_flush(&mut (), &[Value::U32(handle)]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_pollable_operations() -> Result<()> {
         // Test subscribe operation for stdout (stream 1)
         let args = vec![Value::U32(1)];
-        let result = wasi_stream_subscribe(&mut (), args)?;
+        let result = wasi_stream_subscribe(&mut (), &args)?;
         assert_eq!(result.len(), 1);
         let pollable1 = if let Value::U32(pollable) = &result[0] {
             assert!(*pollable >= POLLABLE_OFFSET); // Should be at or after offset
@@ -1204,7 +1088,7 @@ fn test_pollable_operations() -> Result<()> {
 
         // Subscribe another stream (stderr = 2)
         let args = vec![Value::U32(2)];
-        let result = wasi_stream_subscribe(&mut (), args)?;
+        let result = wasi_stream_subscribe(&mut (), &args)?;
         assert_eq!(result.len(), 1);
         let pollable2 = if let Value::U32(pollable) = &result[0] {
             assert!(*pollable >= POLLABLE_OFFSET);
@@ -1216,7 +1100,7 @@ fn test_pollable_operations() -> Result<()> {
         // Test poll operation with the pollables we created
         let pollables = vec![Value::U32(pollable1), Value::U32(pollable2)];
         let args = vec![Value::List(pollables)];
-        let result = wasi_poll_one_off(&mut (), args)?;
+        let result = wasi_poll_one_off(&mut (), &args)?;
         assert_eq!(result.len(), 1);
         if let Value::List(results) = &result[0] {
             assert_eq!(results.len(), 2);
@@ -1233,7 +1117,7 @@ fn test_pollable_operations() -> Result<()> {
     fn test_timer_pollable() -> Result<()> {
         // Create a timer pollable that should already be expired (deadline in past)
         let args = vec![Value::U64(0)]; // deadline at epoch (already passed)
-        let result = wasi_subscribe_timer(&mut (), args)?;
+        let result = wasi_subscribe_timer(&mut (), &args)?;
         assert_eq!(result.len(), 1);
 
         let timer_pollable = if let Value::U32(p) = &result[0] {
@@ -1245,7 +1129,7 @@ fn test_timer_pollable() -> Result<()> {
         // Poll the timer - should be ready since deadline passed
         let pollables = vec![Value::U32(timer_pollable)];
         let args = vec![Value::List(pollables)];
-        let result = wasi_poll_one_off(&mut (), args)?;
+        let result = wasi_poll_one_off(&mut (), &args)?;
 
         if let Value::List(results) = &result[0] {
             assert_eq!(results.len(), 1);
@@ -1260,7 +1144,7 @@ fn test_timer_pollable() -> Result<()> {
     fn test_drop_pollable() -> Result<()> {
         // Create a pollable
         let args = vec![Value::U32(1)];
-        let result = wasi_stream_subscribe(&mut (), args)?;
+        let result = wasi_stream_subscribe(&mut (), &args)?;
         let pollable = if let Value::U32(p) = &result[0] {
             *p
         } else {
@@ -1269,7 +1153,7 @@ fn test_drop_pollable() -> Result<()> {
 
         // Drop it
         let args = vec![Value::U32(pollable)];
-        let result = wasi_drop_pollable(&mut (), args)?;
+        let result = wasi_drop_pollable(&mut (), &args)?;
         assert!(result.is_empty()); // Drop returns nothing on success
 
         Ok(())
@@ -6,7 +6,9 @@
 use core::any::Any;
 
 use crate::{
+    audit,
     capabilities::WasiRandomCapabilities,
+    determinism::seeded_random_bytes,
     prelude::*,
     Value,
 };
@@ -146,6 +148,10 @@ fn extract_length(args: &[Value]) -> Result<usize> {
 /// - `VxWorks`: `randBytes()`
 /// - Others: `/dev/urandom` fallback or error
 fn generate_secure_random(len: usize) -> Result<Vec<u8>> {
+    if let Some(buffer) = seeded_random_bytes(len) {
+        return Ok(buffer);
+    }
+
     #[cfg(feature = "std")]
     {
         use wrt_platform::random::PlatformRandom;
@@ -174,6 +180,10 @@ fn generate_secure_random(len: usize) -> Result<Vec<u8>> {
 // Result kept for API consistency with secure random functions.
 #[allow(clippy::unnecessary_wraps)]
 fn generate_pseudo_random(len: usize) -> Result<Vec<u8>> {
+    if let Some(buffer) = seeded_random_bytes(len) {
+        return Ok(buffer);
+    }
+
     // Use platform time as seed
     use wrt_platform::time::PlatformTime;
     let seed = PlatformTime::monotonic_ns();
@@ -217,11 +227,11 @@ pub fn validate_random_capabilities(
     secure: bool,
     capabilities: &WasiRandomCapabilities,
 ) -> Result<()> {
-    if secure && !capabilities.secure_random {
+    if secure && !audit::check(capabilities.secure_random, "random.secure_random") {
         return Err(Error::wasi_permission_denied("Secure random access denied"));
     }
 
-    if !secure && !capabilities.pseudo_random {
+    if !secure && !audit::check(capabilities.pseudo_random, "random.pseudo_random") {
         return Err(Error::wasi_permission_denied("Pseudo-random access denied"));
     }
 
@@ -235,20 +245,20 @@ mod tests {
     #[test]
     fn test_extract_length() -> Result<()> {
         let args = vec![Value::U64(1024)];
-        let len = extract_length(args)?;
+        let len = extract_length(&args)?;
         assert_eq!(len, 1024);
 
         let args = vec![Value::U32(512)];
-        let len = extract_length(args)?;
+        let len = extract_length(&args)?;
         assert_eq!(len, 512);
 
         let args = vec![Value::S32(256)];
-        let len = extract_length(args)?;
+        let len = extract_length(&args)?;
         assert_eq!(len, 256);
 
         // Test negative length
         let args = vec![Value::S32(-1)];
-        let result = extract_length(args);
+        let result = extract_length(&args);
         assert!(result.is_err());
 
         Ok(())
@@ -258,7 +268,7 @@ fn test_extract_length() -> Result<()> {
     fn test_wasi_get_random_bytes() -> Result<()> {
         // Test small request
         let args = vec![Value::U64(16)];
-        let result = wasi_get_random_bytes(&mut (), args)?;
+        let result = wasi_get_random_bytes(&mut (), &args)?;
         assert_eq!(result.len(), 1);
 
         if let Value::List(bytes) = &result[0] {
@@ -272,7 +282,7 @@ fn test_wasi_get_random_bytes() -> Result<()> {
 
         // Test large request (should fail)
         let args = vec![Value::U64(2 * 1024 * 1024)]; // 2MB
-        let result = wasi_get_random_bytes(&mut (), args);
+        let result = wasi_get_random_bytes(&mut (), &args);
         assert!(result.is_err());
 
         Ok(())
@@ -282,7 +292,7 @@ fn test_wasi_get_random_bytes() -> Result<()> {
     fn test_wasi_get_insecure_random_bytes() -> Result<()> {
         // Test medium request
         let args = vec![Value::U64(1024)];
-        let result = wasi_get_insecure_random_bytes(&mut (), args)?;
+        let result = wasi_get_insecure_random_bytes(&mut (), &args)?;
         assert_eq!(result.len(), 1);
 
         if let Value::List(bytes) = &result[0] {
@@ -306,7 +316,7 @@ fn test_wasi_get_insecure_random_bytes() -> Result<()> {
 
     #[test]
     fn test_wasi_get_random_u64() -> Result<()> {
-        let result = wasi_get_random_u64(&mut (), vec![])?;
+        let result = wasi_get_random_u64(&mut (), &[])?;
         assert_eq!(result.len(), 1);
 
         if let Value::U64(value) = &result[0] {
@@ -10,8 +10,19 @@
 //!
 //! The socket implementation uses a capability-based security model:
 //! - All socket operations require appropriate capabilities
-//! - Socket handles are managed via a thread-safe socket table
-//! - Address and port restrictions are enforced before any network operation
+//! - Socket handles are owned resources of a thread-safe socket table,
+//!   allocated through a [`WasiResourceManager`] and closed when dropped
+//! - Address family, address and port restrictions are enforced before any
+//!   network operation
+//! - Hostnames are checked against an allow-list before they are resolved,
+//!   and resolved addresses outside the allowed families and addresses are
+//!   dropped
+//!
+//! The table is configured from [`WasiCapabilities::sockets`] by
+//! [`init_socket_table`], which the dispatcher and the component model
+//! provider call on construction.
+//!
+//! [`WasiCapabilities::sockets`]: crate::WasiCapabilities::sockets
 //!
 //! # std vs no_std
 //!
@@ -34,10 +45,16 @@
 #[cfg(feature = "std")]
 use std::sync::RwLock;
 
-use crate::{prelude::*, Value};
+#[cfg(feature = "std")]
+use crate::host_provider::resource_manager::{
+    WasiResourceManager,
+    WasiResourceType,
+    WasiSocketProtocol,
+};
+use crate::{audit, host_provider::resource_manager::WasiAddressFamily, prelude::*, Value};
 
 /// WASI socket capabilities for controlling network access
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct WasiSocketCapabilities {
     /// Allow TCP socket creation
     pub tcp_create: bool,
@@ -53,10 +70,17 @@ pub struct WasiSocketCapabilities {
     pub udp_bind: bool,
     /// Allow DNS resolution
     pub dns_resolve: bool,
-    /// Allowed IP address ranges (empty = all allowed)
+    /// Allowed address families (empty = none allowed)
+    pub allowed_families: Vec<WasiAddressFamily>,
+    /// Allowed IP address ranges (empty = none allowed)
     pub allowed_addresses: Vec<AllowedAddress>,
-    /// Allowed port ranges (empty = all allowed)
+    /// Allowed port ranges (empty = none allowed)
     pub allowed_ports: Vec<(u16, u16)>,
+    /// Hostnames that may be resolved (empty = none allowed)
+    ///
+    /// `*` matches any hostname and `*.example.com` any subdomain of
+    /// `example.com`. Matching ignores ASCII case and a trailing dot.
+    pub allowed_hostnames: Vec<String>,
 }
 
 /// Allowed address specification
@@ -74,22 +98,6 @@ pub enum AllowedAddress {
     Ipv4Subnet(Ipv4Addr, u8),
 }
 
-impl Default for WasiSocketCapabilities {
-    fn default() -> Self {
-        Self {
-            tcp_create: false,
-            tcp_bind: false,
-            tcp_connect: false,
-            tcp_listen: false,
-            udp_create: false,
-            udp_bind: false,
-            dns_resolve: false,
-            allowed_addresses: Vec::new(),
-            allowed_ports: Vec::new(),
-        }
-    }
-}
-
 impl WasiSocketCapabilities {
     /// Create minimal socket capabilities (no access)
     pub fn none() -> Self {
@@ -106,8 +114,10 @@ pub fn client_only() -> Self {
             udp_create: true,
             udp_bind: true,
             dns_resolve: true,
+            allowed_families: vec![WasiAddressFamily::Ipv4, WasiAddressFamily::Ipv6],
             allowed_addresses: vec![AllowedAddress::Any],
             allowed_ports: vec![(1, 65535)],
+            allowed_hostnames: vec!["*".to_string()],
         }
     }
 
@@ -121,8 +131,10 @@ pub fn localhost_only() -> Self {
             udp_create: true,
             udp_bind: true,
             dns_resolve: false,
+            allowed_families: vec![WasiAddressFamily::Ipv4, WasiAddressFamily::Ipv6],
             allowed_addresses: vec![AllowedAddress::Localhost],
             allowed_ports: vec![(1024, 65535)], // Non-privileged ports only
+            allowed_hostnames: Vec::new(),
         }
     }
 
@@ -136,8 +148,10 @@ pub fn full() -> Self {
             udp_create: true,
             udp_bind: true,
             dns_resolve: true,
+            allowed_families: vec![WasiAddressFamily::Ipv4, WasiAddressFamily::Ipv6],
             allowed_addresses: vec![AllowedAddress::Any],
             allowed_ports: vec![(1, 65535)],
+            allowed_hostnames: vec!["*".to_string()],
         }
     }
 
@@ -148,39 +162,17 @@ pub fn is_address_allowed(&self, addr: &IpAddr) -> bool {
             return false;
         }
 
-        for allowed in &self.allowed_addresses {
-            match (allowed, addr) {
-                (AllowedAddress::Any, _) => return true,
-                (AllowedAddress::Localhost, IpAddr::V4(ip)) => {
-                    if ip.is_loopback() {
-                        return true;
-                    }
-                }
-                (AllowedAddress::Localhost, IpAddr::V6(ip)) => {
-                    if ip.is_loopback() {
-                        return true;
-                    }
-                }
-                (AllowedAddress::Ipv4(allowed_ip), IpAddr::V4(ip)) => {
-                    if allowed_ip == ip {
-                        return true;
-                    }
-                }
-                (AllowedAddress::Ipv6(allowed_ip), IpAddr::V6(ip)) => {
-                    if allowed_ip == ip {
-                        return true;
-                    }
-                }
-                (AllowedAddress::Ipv4Subnet(network, prefix_len), IpAddr::V4(ip)) => {
-                    if is_in_subnet(*network, *prefix_len, *ip) {
-                        return true;
-                    }
-                }
-                _ => {}
+        self.allowed_addresses.iter().any(|allowed| match (allowed, addr) {
+            (AllowedAddress::Any, _) => true,
+            (AllowedAddress::Localhost, IpAddr::V4(ip)) => ip.is_loopback(),
+            (AllowedAddress::Localhost, IpAddr::V6(ip)) => ip.is_loopback(),
+            (AllowedAddress::Ipv4(allowed_ip), IpAddr::V4(ip)) => allowed_ip == ip,
+            (AllowedAddress::Ipv6(allowed_ip), IpAddr::V6(ip)) => allowed_ip == ip,
+            (AllowedAddress::Ipv4Subnet(network, prefix_len), IpAddr::V4(ip)) => {
+                is_in_subnet(*network, *prefix_len, *ip)
             }
-        }
-
-        false
+            _ => false,
+        })
     }
 
     /// Check if a port is allowed
@@ -197,6 +189,44 @@ pub fn is_port_allowed(&self, port: u16) -> bool {
 
         false
     }
+
+    /// Check if an address family is allowed
+    pub fn is_family_allowed(&self, family: WasiAddressFamily) -> bool {
+        self.allowed_families.contains(&family)
+    }
+
+    /// Check if a hostname may be resolved
+    pub fn is_hostname_allowed(&self, hostname: &str) -> bool {
+        let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+        if hostname.is_empty() {
+            return false;
+        }
+
+        self.allowed_hostnames.iter().any(|pattern| {
+            let pattern = pattern.strip_suffix('.').unwrap_or(pattern);
+            if pattern == "*" {
+                return true;
+            }
+            match pattern.strip_prefix("*.") {
+                Some(domain) => hostname
+                    .len()
+                    .checked_sub(domain.len() + 1)
+                    .and_then(|dot| hostname.get(dot..))
+                    .and_then(|tail| tail.strip_prefix('.'))
+                    .is_some_and(|tail| tail.eq_ignore_ascii_case(domain)),
+                None => hostname.eq_ignore_ascii_case(pattern),
+            }
+        })
+    }
+}
+
+/// Address family of an IP address
+#[cfg(feature = "std")]
+fn address_family(addr: &IpAddr) -> WasiAddressFamily {
+    match addr {
+        IpAddr::V4(_) => WasiAddressFamily::Ipv4,
+        IpAddr::V6(_) => WasiAddressFamily::Ipv6,
+    }
 }
 
 /// Check if an IPv4 address is in a subnet
@@ -286,13 +316,16 @@ pub enum SocketEntry {
 }
 
 /// Thread-safe socket table for managing socket handles
+///
+/// Socket handles are resources of the table's resource manager. Removing
+/// a socket releases its handle and closes the underlying OS socket.
 #[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct SocketTable {
     /// Map of handles to socket entries
     sockets: HashMap<SocketHandle, SocketEntry>,
-    /// Next available handle
-    next_handle: SocketHandle,
+    /// Resource manager owning the socket handles
+    resources: WasiResourceManager,
     /// Socket capabilities for this table
     capabilities: WasiSocketCapabilities,
 }
@@ -300,49 +333,58 @@ pub struct SocketTable {
 #[cfg(feature = "std")]
 impl SocketTable {
     /// Create a new socket table with the given capabilities
-    pub fn new(capabilities: WasiSocketCapabilities) -> Self {
-        Self {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resource manager cannot be initialized.
+    pub fn new(capabilities: WasiSocketCapabilities) -> Result<Self> {
+        Ok(Self {
             sockets: HashMap::new(),
-            // Start at 1, reserve 0 for invalid
-            next_handle: 1,
+            resources: WasiResourceManager::new()?,
             capabilities,
-        }
+        })
     }
 
-    /// Allocate a new handle
-    fn alloc_handle(&mut self) -> SocketHandle {
-        let handle = self.next_handle;
-        self.next_handle = self.next_handle.wrapping_add(1);
-        if self.next_handle == 0 {
-            self.next_handle = 1;
-        }
-        handle
+    /// Register a socket as a new resource
+    fn insert(
+        &mut self,
+        protocol: WasiSocketProtocol,
+        family: WasiAddressFamily,
+        entry: SocketEntry,
+    ) -> Result<SocketHandle> {
+        let handle = self.resources.create_socket_handle(protocol, family)?;
+        self.sockets.insert(handle, entry);
+        Ok(handle)
     }
 
     /// Create a new TCP socket
-    pub fn create_tcp(&mut self) -> Result<SocketHandle> {
-        if !self.capabilities.tcp_create {
+    pub fn create_tcp(&mut self, family: WasiAddressFamily) -> Result<SocketHandle> {
+        if !audit::check(self.capabilities.tcp_create, "sockets.tcp_create") {
             return Err(Error::wasi_capability_unavailable(
                 "TCP socket creation not permitted",
             ));
         }
-        let handle = self.alloc_handle();
-        self.sockets
-            .insert(handle, SocketEntry::Tcp(TcpSocketState::Initial));
-        Ok(handle)
+        self.check_family_allowed(family)?;
+        self.insert(
+            WasiSocketProtocol::Tcp,
+            family,
+            SocketEntry::Tcp(TcpSocketState::Initial),
+        )
     }
 
     /// Create a new UDP socket
-    pub fn create_udp(&mut self) -> Result<SocketHandle> {
-        if !self.capabilities.udp_create {
+    pub fn create_udp(&mut self, family: WasiAddressFamily) -> Result<SocketHandle> {
+        if !audit::check(self.capabilities.udp_create, "sockets.udp_create") {
             return Err(Error::wasi_capability_unavailable(
                 "UDP socket creation not permitted",
             ));
         }
-        let handle = self.alloc_handle();
-        self.sockets
-            .insert(handle, SocketEntry::Udp(UdpSocketState::Initial));
-        Ok(handle)
+        self.check_family_allowed(family)?;
+        self.insert(
+            WasiSocketProtocol::Udp,
+            family,
+            SocketEntry::Udp(UdpSocketState::Initial),
+        )
     }
 
     /// Get a socket entry by handle
@@ -359,21 +401,69 @@ pub fn get_mut(&mut self, handle: SocketHandle) -> Result<&mut SocketEntry> {
             .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))
     }
 
-    /// Remove a socket from the table
+    /// Remove a socket from the table, releasing its handle
     pub fn remove(&mut self, handle: SocketHandle) -> Result<SocketEntry> {
-        self.sockets
+        let entry = self
+            .sockets
             .remove(&handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))
+            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
+        self.resources.remove_resource(handle)?;
+        Ok(entry)
+    }
+
+    /// Number of open sockets
+    pub fn len(&self) -> usize {
+        self.sockets.len()
+    }
+
+    /// Check if no sockets are open
+    pub fn is_empty(&self) -> bool {
+        self.sockets.is_empty()
+    }
+
+    /// Get the address family a socket was created for
+    pub fn family(&self, handle: SocketHandle) -> Result<WasiAddressFamily> {
+        match self.resources.get_resource(handle)?.resource_type() {
+            WasiResourceType::Socket { family, .. } => Ok(*family),
+            _ => Err(Error::wasi_invalid_fd("Invalid socket handle")),
+        }
+    }
+
+    /// Check if an address family is allowed by capabilities
+    pub fn check_family_allowed(&self, family: WasiAddressFamily) -> Result<()> {
+        let allowed = self.capabilities.is_family_allowed(family);
+        if !audit::check(allowed, "sockets.allowed_families") {
+            return Err(Error::wasi_capability_unavailable(
+                "Address family not permitted",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check if a socket may use an address
+    ///
+    /// The address must match the socket's address family and be allowed
+    /// by capabilities.
+    pub fn check_socket_address(&self, handle: SocketHandle, addr: &SocketAddr) -> Result<()> {
+        if self.family(handle)? != address_family(&addr.ip()) {
+            return Err(Error::wasi_invalid_argument(
+                "Address family does not match socket",
+            ));
+        }
+        self.check_address_allowed(addr)
     }
 
     /// Check if an address/port is allowed by capabilities
     pub fn check_address_allowed(&self, addr: &SocketAddr) -> Result<()> {
-        if !self.capabilities.is_address_allowed(&addr.ip()) {
+        self.check_family_allowed(address_family(&addr.ip()))?;
+        let allowed = self.capabilities.is_address_allowed(&addr.ip());
+        if !audit::check(allowed, "sockets.allowed_addresses") {
             return Err(Error::wasi_capability_unavailable(
                 "Address not in allowed list",
             ));
         }
-        if !self.capabilities.is_port_allowed(addr.port()) {
+        let allowed = self.capabilities.is_port_allowed(addr.port());
+        if !audit::check(allowed, "sockets.allowed_ports") {
             return Err(Error::wasi_capability_unavailable(
                 "Port not in allowed range",
             ));
@@ -387,17 +477,24 @@ pub fn capabilities(&self) -> &WasiSocketCapabilities {
     }
 }
 
-/// Global socket table (thread-safe)
+/// Global socket table (thread-safe), `None` until initialized
 #[cfg(feature = "std")]
-static GLOBAL_SOCKET_TABLE: once_cell::sync::Lazy<RwLock<SocketTable>> =
-    once_cell::sync::Lazy::new(|| RwLock::new(SocketTable::new(WasiSocketCapabilities::none())));
+static GLOBAL_SOCKET_TABLE: RwLock<Option<SocketTable>> = RwLock::new(None);
 
 /// Initialize the global socket table with capabilities
+///
+/// Sockets of a previously installed table are closed.
+///
+/// # Errors
+///
+/// Returns an error if the table cannot be created or its lock is poisoned.
 #[cfg(feature = "std")]
-pub fn init_socket_table(capabilities: WasiSocketCapabilities) {
-    if let Ok(mut table) = GLOBAL_SOCKET_TABLE.write() {
-        *table = SocketTable::new(capabilities);
-    }
+pub fn init_socket_table(capabilities: WasiSocketCapabilities) -> Result<()> {
+    let table = SocketTable::new(capabilities)?;
+    *GLOBAL_SOCKET_TABLE
+        .write()
+        .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))? = Some(table);
+    Ok(())
 }
 
 /// Get read access to the global socket table
@@ -406,7 +503,10 @@ fn with_socket_table<T, F: FnOnce(&SocketTable) -> T>(f: F) -> Result<T> {
     let table = GLOBAL_SOCKET_TABLE
         .read()
         .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-    Ok(f(&table))
+    let table = table
+        .as_ref()
+        .ok_or_else(|| Error::wasi_capability_unavailable("Sockets not configured"))?;
+    Ok(f(table))
 }
 
 /// Get write access to the global socket table
@@ -415,7 +515,35 @@ fn with_socket_table_mut<T, F: FnOnce(&mut SocketTable) -> T>(f: F) -> Result<T>
     let mut table = GLOBAL_SOCKET_TABLE
         .write()
         .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-    Ok(f(&mut table))
+    let table = table
+        .as_mut()
+        .ok_or_else(|| Error::wasi_capability_unavailable("Sockets not configured"))?;
+    Ok(f(table))
+}
+
+/// Clone the stream of a connected TCP socket for I/O outside the table lock
+#[cfg(feature = "std")]
+fn clone_tcp_stream(handle: SocketHandle) -> Result<TcpStream> {
+    with_socket_table(|table| match table.get(handle)? {
+        SocketEntry::Tcp(TcpSocketState::Connected { stream, .. }) => {
+            stream.try_clone().map_err(|e| io_error_to_wasi_error(&e))
+        }
+        SocketEntry::Tcp(_) => Err(Error::wasi_invalid_fd("Socket not connected")),
+        SocketEntry::Udp(_) => Err(Error::wasi_invalid_fd("Expected TCP socket")),
+    })?
+}
+
+/// Clone a bound UDP socket for I/O outside the table lock
+#[cfg(feature = "std")]
+fn clone_udp_socket(handle: SocketHandle) -> Result<UdpSocket> {
+    with_socket_table(|table| match table.get(handle)? {
+        SocketEntry::Udp(UdpSocketState::Bound { socket, .. })
+        | SocketEntry::Udp(UdpSocketState::Connected { socket, .. }) => {
+            socket.try_clone().map_err(|e| io_error_to_wasi_error(&e))
+        }
+        SocketEntry::Udp(UdpSocketState::Initial) => Err(Error::wasi_invalid_fd("Socket not bound")),
+        SocketEntry::Tcp(_) => Err(Error::wasi_invalid_fd("Expected UDP socket")),
+    })?
 }
 
 // ============================================================================
@@ -426,8 +554,9 @@ fn with_socket_table_mut<T, F: FnOnce(&mut SocketTable) -> T>(f: F) -> Result<T>
 ///
 /// Implements `wasi:sockets/tcp.create-tcp-socket`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
-    let handle = with_socket_table_mut(|table| table.create_tcp())??;
+pub fn wasi_tcp_create(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let family = extract_address_family(args, 0)?;
+    let handle = with_socket_table_mut(|table| table.create_tcp(family))??;
     Ok(vec![Value::U32(handle)])
 }
 
@@ -435,10 +564,10 @@ pub fn wasi_tcp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 ///
 /// Implements `wasi:sockets/tcp.start-connect`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_connect(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let ip_bytes = extract_ip_address(&args, 1)?;
-    let port = extract_u16(&args, 2)?;
+pub fn wasi_tcp_connect(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let ip_bytes = extract_ip_address(args, 1)?;
+    let port = extract_u16(args, 2)?;
 
     let addr = SocketAddr::new(bytes_to_ip(&ip_bytes)?, port);
 
@@ -449,7 +578,7 @@ pub fn wasi_tcp_connect(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<V
                 "TCP connect not permitted",
             ));
         }
-        table.check_address_allowed(&addr)
+        table.check_socket_address(socket_handle, &addr)
     })??;
 
     // Attempt the connection
@@ -485,10 +614,10 @@ pub fn wasi_tcp_connect(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<V
 ///
 /// Implements `wasi:sockets/tcp.start-bind`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let ip_bytes = extract_ip_address(&args, 1)?;
-    let port = extract_u16(&args, 2)?;
+pub fn wasi_tcp_bind(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let ip_bytes = extract_ip_address(args, 1)?;
+    let port = extract_u16(args, 2)?;
 
     let addr = SocketAddr::new(bytes_to_ip(&ip_bytes)?, port);
 
@@ -497,7 +626,7 @@ pub fn wasi_tcp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
         if !table.capabilities().tcp_bind {
             return Err(Error::wasi_capability_unavailable("TCP bind not permitted"));
         }
-        table.check_address_allowed(&addr)
+        table.check_socket_address(socket_handle, &addr)
     })??;
 
     // Create the listener
@@ -522,8 +651,8 @@ pub fn wasi_tcp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements `wasi:sockets/tcp.start-listen`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_listen(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
+pub fn wasi_tcp_listen(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
 
     // Check capabilities
     with_socket_table(|table| {
@@ -563,48 +692,37 @@ pub fn wasi_tcp_listen(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Va
 ///
 /// Implements `wasi:sockets/tcp.accept`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_accept(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
+pub fn wasi_tcp_accept(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
 
     // Accept a connection from the listener
     // This needs to borrow the listener, accept, then create a new socket entry
-    let (stream, peer_addr) = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Tcp(TcpSocketState::Listening { listener }) => {
-                listener.accept().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Socket not in listening state"));
-            }
-            SocketEntry::Udp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected TCP socket"));
-            }
+    let (stream, peer_addr) = with_socket_table(|table| match table.get(socket_handle)? {
+        SocketEntry::Tcp(TcpSocketState::Listening { listener }) => {
+            listener.accept().map_err(|e| io_error_to_wasi_error(&e))
         }
-    };
+        SocketEntry::Tcp(_) => Err(Error::wasi_invalid_fd("Socket not in listening state")),
+        SocketEntry::Udp(_) => Err(Error::wasi_invalid_fd("Expected TCP socket")),
+    })??;
 
     let local_addr = stream
         .local_addr()
         .map_err(|e| io_error_to_wasi_error(&e))?;
 
-    // Create a new socket handle for the accepted connection
+    // Register the accepted connection as a new socket resource. Connections
+    // from peers outside the allowed addresses are closed by dropping them.
     let new_handle = with_socket_table_mut(|table| -> Result<SocketHandle> {
-        let handle = table.alloc_handle();
-        table.sockets.insert(
-            handle,
+        table.check_address_allowed(&peer_addr)?;
+        let family = table.family(socket_handle)?;
+        table.insert(
+            WasiSocketProtocol::Tcp,
+            family,
             SocketEntry::Tcp(TcpSocketState::Connected {
                 stream,
                 local_addr,
                 peer_addr,
             }),
-        );
-        Ok(handle)
+        )
     })??;
 
     // Return the new handle and peer address
@@ -619,32 +737,13 @@ pub fn wasi_tcp_accept(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Va
 ///
 /// Implements output stream write for TCP
 #[cfg(feature = "std")]
-pub fn wasi_tcp_send(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let data = extract_bytes(&args, 1)?;
+pub fn wasi_tcp_send(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let data = extract_bytes(args, 1)?;
 
     // We need to write to the stream. Since we can't hold a mutable borrow
     // across the lock, we use try_clone() to get an owned stream for writing.
-    let mut stream = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Tcp(TcpSocketState::Connected { stream, .. }) => {
-                stream.try_clone().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Socket not connected"));
-            }
-            SocketEntry::Udp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected TCP socket"));
-            }
-        }
-    };
+    let mut stream = clone_tcp_stream(socket_handle)?;
 
     // Write the data
     let bytes_written = stream.write(&data).map_err(|e| io_error_to_wasi_error(&e))?;
@@ -656,31 +755,12 @@ pub fn wasi_tcp_send(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements input stream read for TCP
 #[cfg(feature = "std")]
-pub fn wasi_tcp_recv(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let max_len = extract_u64(&args, 1)? as usize;
+pub fn wasi_tcp_recv(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let max_len = extract_u64(args, 1)? as usize;
 
     // Clone the stream for reading
-    let mut stream = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Tcp(TcpSocketState::Connected { stream, .. }) => {
-                stream.try_clone().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Socket not connected"));
-            }
-            SocketEntry::Udp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected TCP socket"));
-            }
-        }
-    };
+    let mut stream = clone_tcp_stream(socket_handle)?;
 
     // Read the data
     let mut buffer = vec![0u8; max_len.min(65536)];
@@ -695,9 +775,9 @@ pub fn wasi_tcp_recv(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements `wasi:sockets/tcp.shutdown`
 #[cfg(feature = "std")]
-pub fn wasi_tcp_shutdown(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let shutdown_type = extract_u8(&args, 1)?; // 0=read, 1=write, 2=both
+pub fn wasi_tcp_shutdown(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let shutdown_type = extract_u8(args, 1)?; // 0=read, 1=write, 2=both
 
     use std::net::Shutdown;
 
@@ -709,36 +789,15 @@ pub fn wasi_tcp_shutdown(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<
     };
 
     // Clone stream and shutdown
-    let stream = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Tcp(TcpSocketState::Connected { stream, .. }) => {
-                stream.try_clone().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Socket not connected"));
-            }
-            SocketEntry::Udp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected TCP socket"));
-            }
-        }
-    };
+    let stream = clone_tcp_stream(socket_handle)?;
 
     stream.shutdown(how).map_err(|e| io_error_to_wasi_error(&e))?;
 
     // Update state to Shutdown if both directions
     if shutdown_type >= 2 {
         with_socket_table_mut(|table| -> Result<()> {
-            if let Some(entry) = table.sockets.get_mut(&socket_handle) {
-                if let SocketEntry::Tcp(state) = entry {
-                    *state = TcpSocketState::Shutdown;
-                }
+            if let Some(SocketEntry::Tcp(state)) = table.sockets.get_mut(&socket_handle) {
+                *state = TcpSocketState::Shutdown;
             }
             Ok(())
         })??;
@@ -755,8 +814,9 @@ pub fn wasi_tcp_shutdown(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<
 ///
 /// Implements `wasi:sockets/udp.create-udp-socket`
 #[cfg(feature = "std")]
-pub fn wasi_udp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
-    let handle = with_socket_table_mut(|table| table.create_udp())??;
+pub fn wasi_udp_create(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let family = extract_address_family(args, 0)?;
+    let handle = with_socket_table_mut(|table| table.create_udp(family))??;
     Ok(vec![Value::U32(handle)])
 }
 
@@ -764,10 +824,10 @@ pub fn wasi_udp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 ///
 /// Implements `wasi:sockets/udp.start-bind`
 #[cfg(feature = "std")]
-pub fn wasi_udp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let ip_bytes = extract_ip_address(&args, 1)?;
-    let port = extract_u16(&args, 2)?;
+pub fn wasi_udp_bind(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let ip_bytes = extract_ip_address(args, 1)?;
+    let port = extract_u16(args, 2)?;
 
     let addr = SocketAddr::new(bytes_to_ip(&ip_bytes)?, port);
 
@@ -776,7 +836,7 @@ pub fn wasi_udp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
         if !table.capabilities().udp_bind {
             return Err(Error::wasi_capability_unavailable("UDP bind not permitted"));
         }
-        table.check_address_allowed(&addr)
+        table.check_socket_address(socket_handle, &addr)
     })??;
 
     // Bind the socket
@@ -804,39 +864,19 @@ pub fn wasi_udp_bind(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements `wasi:sockets/udp.send`
 #[cfg(feature = "std")]
-pub fn wasi_udp_send(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let data = extract_bytes(&args, 1)?;
-    let ip_bytes = extract_ip_address(&args, 2)?;
-    let port = extract_u16(&args, 3)?;
+pub fn wasi_udp_send(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let data = extract_bytes(args, 1)?;
+    let ip_bytes = extract_ip_address(args, 2)?;
+    let port = extract_u16(args, 3)?;
 
     let dest = SocketAddr::new(bytes_to_ip(&ip_bytes)?, port);
 
     // Check destination is allowed
-    with_socket_table(|table| table.check_address_allowed(&dest))??;
+    with_socket_table(|table| table.check_socket_address(socket_handle, &dest))??;
 
     // Clone the socket for sending
-    let socket = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Udp(UdpSocketState::Bound { socket, .. })
-            | SocketEntry::Udp(UdpSocketState::Connected { socket, .. }) => {
-                socket.try_clone().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Udp(UdpSocketState::Initial) => {
-                return Err(Error::wasi_invalid_fd("Socket not bound"));
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected UDP socket"));
-            }
-        }
-    };
+    let socket = clone_udp_socket(socket_handle)?;
 
     let bytes_sent = socket
         .send_to(&data, dest)
@@ -849,32 +889,12 @@ pub fn wasi_udp_send(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements `wasi:sockets/udp.receive`
 #[cfg(feature = "std")]
-pub fn wasi_udp_recv(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let socket_handle = extract_u32(&args, 0)?;
-    let max_len = extract_u64(&args, 1)? as usize;
+pub fn wasi_udp_recv(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    let max_len = extract_u64(args, 1)? as usize;
 
     // Clone the socket for receiving
-    let socket = {
-        let table = GLOBAL_SOCKET_TABLE
-            .read()
-            .map_err(|_| Error::wasi_runtime_error("Socket table lock poisoned"))?;
-
-        let entry = table.sockets.get(&socket_handle)
-            .ok_or_else(|| Error::wasi_invalid_fd("Invalid socket handle"))?;
-
-        match entry {
-            SocketEntry::Udp(UdpSocketState::Bound { socket, .. })
-            | SocketEntry::Udp(UdpSocketState::Connected { socket, .. }) => {
-                socket.try_clone().map_err(|e| io_error_to_wasi_error(&e))?
-            }
-            SocketEntry::Udp(UdpSocketState::Initial) => {
-                return Err(Error::wasi_invalid_fd("Socket not bound"));
-            }
-            SocketEntry::Tcp(_) => {
-                return Err(Error::wasi_invalid_fd("Expected UDP socket"));
-            }
-        }
-    };
+    let socket = clone_udp_socket(socket_handle)?;
 
     // Receive data
     let mut buffer = vec![0u8; max_len.min(65536)];
@@ -890,6 +910,16 @@ pub fn wasi_udp_recv(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
     Ok(vec![Value::Tuple(vec![Value::List(data), addr_value])])
 }
 
+/// Drop a socket, closing it and releasing its handle
+///
+/// Implements `[resource-drop]tcp-socket` and `[resource-drop]udp-socket`
+#[cfg(feature = "std")]
+pub fn wasi_socket_drop(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let socket_handle = extract_u32(args, 0)?;
+    with_socket_table_mut(|table| table.remove(socket_handle))??;
+    Ok(vec![])
+}
+
 // ============================================================================
 // DNS Resolution
 // ============================================================================
@@ -898,16 +928,29 @@ pub fn wasi_udp_recv(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Valu
 ///
 /// Implements `wasi:sockets/ip-name-lookup.resolve-addresses`
 #[cfg(feature = "std")]
-pub fn wasi_resolve_addresses(_target: &mut dyn Any, args: Vec<Value>) -> Result<Vec<Value>> {
-    let hostname = extract_string(&args, 0)?;
+pub fn wasi_resolve_addresses(_target: &mut dyn Any, args: &[Value]) -> Result<Vec<Value>> {
+    let hostname = extract_string(args, 0)?;
 
     use std::net::ToSocketAddrs;
 
+    let capabilities = with_socket_table(|table| table.capabilities().clone())?;
+    if !audit::check(capabilities.dns_resolve, "sockets.dns_resolve") {
+        return Err(Error::wasi_capability_unavailable("DNS resolution not permitted"));
+    }
+    let allowed = capabilities.is_hostname_allowed(&hostname);
+    if !audit::check(allowed, "sockets.allowed_hostnames") {
+        return Err(Error::wasi_capability_unavailable("Hostname not in allowed list"));
+    }
+
     // Try to resolve the hostname
     let addr_str = format!("{}:0", hostname);
     match addr_str.to_socket_addrs() {
         Ok(addrs) => {
             let ip_values: Vec<Value> = addrs
+                .filter(|addr| {
+                    capabilities.is_family_allowed(address_family(&addr.ip()))
+                        && capabilities.is_address_allowed(&addr.ip())
+                })
                 .map(|addr| {
                     match addr.ip() {
                         IpAddr::V4(ip) => {
@@ -1025,6 +1068,15 @@ fn extract_bytes(args: &[Value], index: usize) -> Result<Vec<u8>> {
     }
 }
 
+/// Extract a `wasi:sockets/network.ip-address-family` argument
+fn extract_address_family(args: &[Value], index: usize) -> Result<WasiAddressFamily> {
+    match extract_u8(args, index)? {
+        0 => Ok(WasiAddressFamily::Ipv4),
+        1 => Ok(WasiAddressFamily::Ipv6),
+        _ => Err(Error::wasi_invalid_argument("Invalid address family")),
+    }
+}
+
 fn extract_ip_address(args: &[Value], index: usize) -> Result<Vec<u8>> {
     extract_bytes(args, index)
 }
@@ -1057,7 +1109,7 @@ fn bytes_to_ip(bytes: &[u8]) -> Result<IpAddr> {
 
 /// Create a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_create(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1065,7 +1117,7 @@ pub fn wasi_tcp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 
 /// Connect a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_connect(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_connect(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1073,7 +1125,7 @@ pub fn wasi_tcp_connect(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<
 
 /// Bind a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_bind(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_bind(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1081,7 +1133,7 @@ pub fn wasi_tcp_bind(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Listen on a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_listen(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_listen(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1089,7 +1141,7 @@ pub fn wasi_tcp_listen(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 
 /// Accept a TCP connection (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_accept(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_accept(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1097,7 +1149,7 @@ pub fn wasi_tcp_accept(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 
 /// Send data on a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_send(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_send(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1105,7 +1157,7 @@ pub fn wasi_tcp_send(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Receive data from a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_recv(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_recv(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1113,7 +1165,7 @@ pub fn wasi_tcp_recv(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Shutdown a TCP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_tcp_shutdown(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_tcp_shutdown(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1121,7 +1173,7 @@ pub fn wasi_tcp_shutdown(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec
 
 /// Create a UDP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_udp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_udp_create(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1129,7 +1181,7 @@ pub fn wasi_udp_create(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<V
 
 /// Bind a UDP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_udp_bind(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_udp_bind(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1137,7 +1189,7 @@ pub fn wasi_udp_bind(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Send data on a UDP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_udp_send(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_udp_send(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1145,7 +1197,15 @@ pub fn wasi_udp_send(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Receive data from a UDP socket (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_udp_recv(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_udp_recv(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
+    Err(Error::wasi_unsupported_operation(
+        "Socket operations not available in no_std environment",
+    ))
+}
+
+/// Drop a socket (no_std stub)
+#[cfg(not(feature = "std"))]
+pub fn wasi_socket_drop(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "Socket operations not available in no_std environment",
     ))
@@ -1153,7 +1213,7 @@ pub fn wasi_udp_recv(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Val
 
 /// Resolve a hostname (no_std stub)
 #[cfg(not(feature = "std"))]
-pub fn wasi_resolve_addresses(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
+pub fn wasi_resolve_addresses(_target: &mut dyn Any, _args: &[Value]) -> Result<Vec<Value>> {
     Err(Error::wasi_unsupported_operation(
         "DNS resolution not available in no_std environment",
     ))
@@ -1233,12 +1293,69 @@ fn test_subnet_check() {
         assert!(!caps.is_address_allowed(&IpAddr::V4(Ipv4Addr::new(192, 169, 0, 1))));
     }
 
+    #[test]
+    fn test_hostname_check() {
+        let mut caps = WasiSocketCapabilities::none();
+        assert!(!caps.is_hostname_allowed("localhost"));
+
+        caps.allowed_hostnames = vec!["localhost".to_string(), "*.example.com".to_string()];
+        assert!(caps.is_hostname_allowed("localhost"));
+        assert!(caps.is_hostname_allowed("LocalHost."));
+        assert!(caps.is_hostname_allowed("api.example.com"));
+        assert!(caps.is_hostname_allowed("a.b.Example.COM"));
+        assert!(!caps.is_hostname_allowed("example.com"));
+        assert!(!caps.is_hostname_allowed("badexample.com"));
+        assert!(!caps.is_hostname_allowed("example.com.evil.org"));
+        assert!(!caps.is_hostname_allowed(""));
+
+        assert!(WasiSocketCapabilities::full().is_hostname_allowed("anything.org"));
+    }
+
     #[cfg(feature = "std")]
     #[test]
-    fn test_dns_resolve() {
-        // Test with a known hostname (localhost should always resolve)
+    fn test_socket_table_families() -> Result<()> {
+        let mut caps = WasiSocketCapabilities::localhost_only();
+        caps.allowed_families = vec![WasiAddressFamily::Ipv4];
+        let mut table = SocketTable::new(caps)?;
+
+        assert!(table.create_tcp(WasiAddressFamily::Ipv6).is_err());
+        let tcp = table.create_tcp(WasiAddressFamily::Ipv4)?;
+        let udp = table.create_udp(WasiAddressFamily::Ipv4)?;
+        assert_ne!(tcp, udp);
+        assert_eq!(table.family(tcp)?, WasiAddressFamily::Ipv4);
+
+        let loopback_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080);
+        let loopback_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080);
+        let remote = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)), 8080);
+        assert!(table.check_socket_address(tcp, &loopback_v4).is_ok());
+        assert!(table.check_socket_address(tcp, &loopback_v6).is_err());
+        assert!(table.check_socket_address(tcp, &remote).is_err());
+
+        table.remove(tcp)?;
+        assert!(table.get(tcp).is_err());
+        assert!(table.family(tcp).is_err());
+        assert_eq!(table.len(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dns_resolve() -> Result<()> {
+        init_socket_table(WasiSocketCapabilities::localhost_only())?;
         let args = vec![Value::String("localhost".to_string())];
-        let result = wasi_resolve_addresses(&mut (), args);
-        assert!(result.is_ok());
+        assert!(wasi_resolve_addresses(&mut (), &args).is_err());
+
+        // Test with a known hostname (localhost should always resolve)
+        let mut caps = WasiSocketCapabilities::localhost_only();
+        caps.dns_resolve = true;
+        caps.allowed_hostnames = vec!["localhost".to_string()];
+        init_socket_table(caps)?;
+        assert!(wasi_resolve_addresses(&mut (), &args).is_ok());
+
+        let other = vec![Value::String("example.com".to_string())];
+        assert!(wasi_resolve_addresses(&mut (), &other).is_err());
+
+        Ok(())
     }
 }
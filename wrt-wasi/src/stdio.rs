@@ -0,0 +1,901 @@
+//! Host stdio streams for `wasi:cli`
+//!
+//! The `stdin`, `stdout` and `stderr` streams a component obtains through
+//! `wasi:cli` are routed through this module. By default they are connected
+//! to the stdio of the host process with `std`, and to bounded in-memory
+//! buffers without it, which the embedder drains with [`drain_output`] and
+//! fills with [`feed_input`]. An embedder can instead install its own
+//! [`StdioSink`]s and [`StdioSource`] with [`install_stdio`], and register
+//! [`StdioInterceptor`]s that see all output first and may capture it or keep
+//! it from reaching the sink.
+//!
+//! Further host streams, such as the adapters of
+//! [`stream_adapters`](crate::stream_adapters), are registered with
+//! [`register_input_stream`] and [`register_output_stream`], which hand out
+//! their stream handles. Handles 0, 1 and 2 are the standard streams.
+//!
+//! Sinks apply backpressure by accepting only part of a write. The
+//! `check-write` of a stream reports the space its sink has left, writes are
+//! cut to that space before interceptors see them, and a blocking write fails
+//! once the sink accepts nothing more. [`BoundedPipe`] is a fixed-capacity
+//! buffer that serves as either end of a stream without allocating.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::prelude::*;
+
+/// Capacity of the built-in stream buffers, in bytes
+pub const STDIO_BUFFER_SIZE: usize = 4096;
+
+/// Bytes reported writable on the host process streams
+#[cfg(feature = "std")]
+const HOST_WRITE_CAPACITY: usize = 65536;
+
+/// Handle of the first registered host stream, after the standard streams
+const FIRST_HOST_STREAM: u32 = 3;
+
+/// Standard stream of a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StdStream {
+    /// Standard input
+    Stdin,
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+impl StdStream {
+    /// Name of the stream as used by `wasi:cli`
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stdin => "stdin",
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+
+    /// Stream with the given `wasi:cli` name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stdin" => Some(Self::Stdin),
+            "stdout" => Some(Self::Stdout),
+            "stderr" => Some(Self::Stderr),
+            _ => None,
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Stream a read or write goes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StreamTarget {
+    /// Standard stream
+    Std(StdStream),
+    /// Registered host stream
+    Host(u32),
+}
+
+impl StreamTarget {
+    /// Stream behind a handle of the fixed handle space, where 0, 1 and 2
+    /// are the standard streams
+    pub(crate) const fn from_handle(handle: u32) -> Self {
+        match handle {
+            0 => Self::Std(StdStream::Stdin),
+            1 => Self::Std(StdStream::Stdout),
+            2 => Self::Std(StdStream::Stderr),
+            handle => Self::Host(handle),
+        }
+    }
+}
+
+impl From<StdStream> for StreamTarget {
+    fn from(stream: StdStream) -> Self {
+        Self::Std(stream)
+    }
+}
+
+/// Destination of an output stream
+pub trait StdioSink: Send {
+    /// Write a prefix of `data`, returning the number of bytes accepted
+    ///
+    /// Accepting fewer bytes than offered applies backpressure: the
+    /// component sees a partial write and retries the rest later.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sink is closed or the write fails.
+    fn write(&mut self, data: &[u8]) -> Result<usize>;
+
+    /// Flush buffered output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flush fails.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Number of bytes the sink accepts without blocking
+    fn available(&self) -> usize {
+        STDIO_BUFFER_SIZE
+    }
+}
+
+/// Origin of an input stream
+pub trait StdioSource: Send {
+    /// Read into `buffer`, returning the number of bytes read, or `None` at
+    /// the end of the stream
+    ///
+    /// `Some(0)` means that no data is available yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails.
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>>;
+
+    /// Whether a read returns data or the end of the stream without blocking
+    fn ready(&self) -> bool {
+        true
+    }
+}
+
+/// What happens to output after an interceptor saw it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputAction {
+    /// Pass the output on to the next interceptor and the sink
+    Forward,
+    /// Keep the output from the sink; the write still succeeds
+    Consume,
+}
+
+/// Hook seeing component output before it reaches the sink
+///
+/// Closures `FnMut(StdStream, &[u8]) -> OutputAction` are interceptors.
+pub trait StdioInterceptor: Send {
+    /// Inspect output written to `stream`
+    fn on_output(&mut self, stream: StdStream, data: &[u8]) -> OutputAction;
+}
+
+impl<F> StdioInterceptor for F
+where
+    F: FnMut(StdStream, &[u8]) -> OutputAction + Send,
+{
+    fn on_output(&mut self, stream: StdStream, data: &[u8]) -> OutputAction {
+        self(stream, data)
+    }
+}
+
+/// Fixed-capacity byte ring buffer
+///
+/// Writing to a full pipe accepts only what fits; reading from an empty,
+/// closed pipe reports the end of the stream.
+#[derive(Debug, Clone)]
+pub struct BoundedPipe<const N: usize> {
+    buffer: [u8; N],
+    start:  usize,
+    len:    usize,
+    closed: bool,
+}
+
+impl<const N: usize> BoundedPipe<N> {
+    /// Create an empty, open pipe
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; N],
+            start:  0,
+            len:    0,
+            closed: false,
+        }
+    }
+
+    /// Number of buffered bytes
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no byte is buffered
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes that can still be buffered
+    pub const fn free(&self) -> usize {
+        N - self.len
+    }
+
+    /// Append as much of `data` as fits, returning the number of bytes taken
+    pub fn push(&mut self, data: &[u8]) -> usize {
+        let count = data.len().min(self.free());
+        for (i, &byte) in data[..count].iter().enumerate() {
+            self.buffer[(self.start + self.len + i) % N] = byte;
+        }
+        self.len += count;
+        count
+    }
+
+    /// Move buffered bytes into `out`, returning the number of bytes moved
+    pub fn pop(&mut self, out: &mut [u8]) -> usize {
+        let count = out.len().min(self.len);
+        for (i, byte) in out[..count].iter_mut().enumerate() {
+            *byte = self.buffer[(self.start + i) % N];
+        }
+        if count > 0 {
+            self.start = (self.start + count) % N;
+        }
+        self.len -= count;
+        count
+    }
+
+    /// Mark the end of the stream; buffered bytes can still be read
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Whether the end of the stream was marked
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Drop the buffered bytes and reopen the pipe
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+        self.closed = false;
+    }
+}
+
+impl<const N: usize> Default for BoundedPipe<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> StdioSink for BoundedPipe<N> {
+    fn write(&mut self, data: &[u8]) -> Result<usize> {
+        if self.closed {
+            return Err(Error::wasi_invalid_fd("Output stream is closed"));
+        }
+        Ok(self.push(data))
+    }
+
+    fn available(&self) -> usize {
+        if self.closed {
+            0
+        } else {
+            self.free()
+        }
+    }
+}
+
+impl<const N: usize> StdioSource for BoundedPipe<N> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        let count = self.pop(buffer);
+        if count == 0 && self.closed && !buffer.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(count))
+    }
+
+    fn ready(&self) -> bool {
+        !self.is_empty() || self.closed
+    }
+}
+
+/// A sink shared with the embedder, which keeps a clone to read what the
+/// component wrote
+#[cfg(feature = "std")]
+impl<T: StdioSink> StdioSink for Arc<Mutex<T>> {
+    fn write(&mut self, data: &[u8]) -> Result<usize> {
+        self.lock().map_err(|_| Error::wasi_capability_unavailable("Stdio sink lock poisoned"))?.write(data)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.lock().map_err(|_| Error::wasi_capability_unavailable("Stdio sink lock poisoned"))?.flush()
+    }
+
+    fn available(&self) -> usize {
+        self.lock().map(|sink| sink.available()).unwrap_or(0)
+    }
+}
+
+/// A source shared with the embedder, which keeps a clone to feed input
+#[cfg(feature = "std")]
+impl<T: StdioSource> StdioSource for Arc<Mutex<T>> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        self.lock()
+            .map_err(|_| Error::wasi_capability_unavailable("Stdio source lock poisoned"))?
+            .read(buffer)
+    }
+
+    fn ready(&self) -> bool {
+        self.lock().map(|source| source.ready()).unwrap_or(true)
+    }
+}
+
+/// Where a stream is connected
+enum Route<T: ?Sized> {
+    /// Host process stdio with `std`, built-in buffer without
+    Default,
+    /// Built-in bounded buffer
+    Buffer,
+    /// Embedder-provided end
+    Custom(Box<T>),
+}
+
+impl<T: ?Sized> Route<T> {
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Buffer => "buffer",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+/// Streams and hooks the stdio of components is routed through
+pub struct WasiStdio {
+    stdin:        Route<dyn StdioSource>,
+    stdout:       Route<dyn StdioSink>,
+    stderr:       Route<dyn StdioSink>,
+    interceptors: Vec<Box<dyn StdioInterceptor>>,
+}
+
+impl WasiStdio {
+    /// Default routing: host process stdio with `std`, built-in buffers
+    /// without
+    pub const fn new() -> Self {
+        Self {
+            stdin:        Route::Default,
+            stdout:       Route::Default,
+            stderr:       Route::Default,
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Route all streams to the built-in buffers of
+    /// [`STDIO_BUFFER_SIZE`] bytes, read with [`drain_output`] and fed with
+    /// [`feed_input`]
+    pub const fn buffered() -> Self {
+        Self {
+            stdin:        Route::Buffer,
+            stdout:       Route::Buffer,
+            stderr:       Route::Buffer,
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Read standard input from `source`
+    #[must_use]
+    pub fn with_stdin(mut self, source: impl StdioSource + 'static) -> Self {
+        self.stdin = Route::Custom(Box::new(source));
+        self
+    }
+
+    /// Write standard output to `sink`
+    #[must_use]
+    pub fn with_stdout(mut self, sink: impl StdioSink + 'static) -> Self {
+        self.stdout = Route::Custom(Box::new(sink));
+        self
+    }
+
+    /// Write standard error to `sink`
+    #[must_use]
+    pub fn with_stderr(mut self, sink: impl StdioSink + 'static) -> Self {
+        self.stderr = Route::Custom(Box::new(sink));
+        self
+    }
+
+    /// Let `interceptor` see output before the sink, after the interceptors
+    /// added earlier
+    #[must_use]
+    pub fn with_interceptor(mut self, interceptor: impl StdioInterceptor + 'static) -> Self {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+}
+
+impl Default for WasiStdio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for WasiStdio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasiStdio")
+            .field("stdin", &self.stdin.name())
+            .field("stdout", &self.stdout.name())
+            .field("stderr", &self.stderr.name())
+            .field("interceptors", &self.interceptors.len())
+            .finish()
+    }
+}
+
+/// Registered host stream
+enum HostStream {
+    Input(Box<dyn StdioSource>),
+    Output(Box<dyn StdioSink>),
+}
+
+/// Installed routing, the built-in buffers and the registered host streams
+struct StdioState {
+    routes:      WasiStdio,
+    buffers:     [BoundedPipe<STDIO_BUFFER_SIZE>; 3],
+    streams:     BTreeMap<u32, HostStream>,
+    next_stream: u32,
+}
+
+impl StdioState {
+    const fn new() -> Self {
+        Self {
+            routes:      WasiStdio::new(),
+            buffers:     [BoundedPipe::new(), BoundedPipe::new(), BoundedPipe::new()],
+            streams:     BTreeMap::new(),
+            next_stream: FIRST_HOST_STREAM,
+        }
+    }
+
+    fn register(&mut self, stream: HostStream) -> Result<u32> {
+        let handle = self.next_stream;
+        self.next_stream = handle
+            .checked_add(1)
+            .ok_or_else(|| Error::wasi_resource_exhausted("Stream handles exhausted"))?;
+        self.streams.insert(handle, stream);
+        Ok(handle)
+    }
+
+    fn host_output(&mut self, handle: u32) -> Result<&mut Box<dyn StdioSink>> {
+        match self.streams.get_mut(&handle) {
+            Some(HostStream::Output(sink)) => Ok(sink),
+            Some(HostStream::Input(_)) => Err(Error::wasi_invalid_fd("Stream is not writable")),
+            None => Err(unknown_stream()),
+        }
+    }
+
+    fn host_input(&mut self, handle: u32) -> Result<&mut Box<dyn StdioSource>> {
+        match self.streams.get_mut(&handle) {
+            Some(HostStream::Input(source)) => Ok(source),
+            Some(HostStream::Output(_)) => Err(Error::wasi_invalid_fd("Stream is not readable")),
+            None => Err(unknown_stream()),
+        }
+    }
+
+    /// Route of an output stream with its built-in buffer
+    fn output(
+        &mut self,
+        stream: StdStream,
+    ) -> Result<(&mut Route<dyn StdioSink>, &mut BoundedPipe<STDIO_BUFFER_SIZE>)> {
+        let buffer = &mut self.buffers[stream.index()];
+        match stream {
+            StdStream::Stdout => Ok((&mut self.routes.stdout, buffer)),
+            StdStream::Stderr => Ok((&mut self.routes.stderr, buffer)),
+            StdStream::Stdin => Err(Error::wasi_invalid_fd("Standard input is not writable")),
+        }
+    }
+
+    fn available(&mut self, stream: StdStream) -> Result<usize> {
+        let (route, buffer) = self.output(stream)?;
+        Ok(match route {
+            Route::Custom(sink) => sink.available(),
+            #[cfg(feature = "std")]
+            Route::Default => HOST_WRITE_CAPACITY,
+            _ => StdioSink::available(buffer),
+        })
+    }
+
+    fn write(&mut self, stream: StdStream, data: &[u8]) -> Result<usize> {
+        let data = &data[..data.len().min(self.available(stream)?)];
+        for interceptor in &mut self.routes.interceptors {
+            if interceptor.on_output(stream, data) == OutputAction::Consume {
+                return Ok(data.len());
+            }
+        }
+        match self.output(stream)? {
+            (Route::Custom(sink), _) => sink.write(data),
+            #[cfg(feature = "std")]
+            (Route::Default, _) => host_write(stream, data),
+            (_, buffer) => buffer.write(data),
+        }
+    }
+
+    fn flush(&mut self, stream: StdStream) -> Result<()> {
+        match self.output(stream)? {
+            (Route::Custom(sink), _) => sink.flush(),
+            #[cfg(feature = "std")]
+            (Route::Default, _) => host_flush(stream),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_stdin(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        match &mut self.routes.stdin {
+            Route::Custom(source) => source.read(buffer),
+            #[cfg(feature = "std")]
+            Route::Default => host_read(buffer),
+            _ => self.buffers[StdStream::Stdin.index()].read(buffer),
+        }
+    }
+
+    fn ready_std(&self, stream: StdStream) -> bool {
+        let buffer = &self.buffers[stream.index()];
+        match (stream, &self.routes.stdin) {
+            (StdStream::Stdin, Route::Custom(source)) => source.ready(),
+            #[cfg(feature = "std")]
+            (StdStream::Stdin, Route::Default) => true,
+            (StdStream::Stdin, _) => StdioSource::ready(buffer),
+            // Output streams are ready once there is room to write
+            _ => match stream {
+                StdStream::Stdout => route_has_room(&self.routes.stdout, buffer),
+                _ => route_has_room(&self.routes.stderr, buffer),
+            },
+        }
+    }
+}
+
+fn unknown_stream() -> Error {
+    Error::wasi_invalid_fd("Unknown stream handle")
+}
+
+fn route_has_room(route: &Route<dyn StdioSink>, buffer: &BoundedPipe<STDIO_BUFFER_SIZE>) -> bool {
+    match route {
+        Route::Custom(sink) => sink.available() > 0,
+        #[cfg(feature = "std")]
+        Route::Default => true,
+        _ => buffer.available() > 0,
+    }
+}
+
+#[cfg(feature = "std")]
+fn host_write(stream: StdStream, data: &[u8]) -> Result<usize> {
+    use std::io::Write;
+
+    let written = match stream {
+        StdStream::Stdout => std::io::stdout().write(data),
+        _ => std::io::stderr().write(data),
+    };
+    written.map_err(|_| Error::wasi_capability_unavailable("Failed to write to host stream"))
+}
+
+#[cfg(feature = "std")]
+fn host_flush(stream: StdStream) -> Result<()> {
+    use std::io::Write;
+
+    let flushed = match stream {
+        StdStream::Stdout => std::io::stdout().flush(),
+        _ => std::io::stderr().flush(),
+    };
+    flushed.map_err(|_| Error::wasi_capability_unavailable("Failed to flush host stream"))
+}
+
+#[cfg(feature = "std")]
+fn host_read(buffer: &mut [u8]) -> Result<Option<usize>> {
+    use std::io::Read;
+
+    match std::io::stdin().read(buffer) {
+        Ok(0) if !buffer.is_empty() => Ok(None),
+        Ok(count) => Ok(Some(count)),
+        Err(_) => Err(Error::wasi_capability_unavailable("Failed to read from stdin")),
+    }
+}
+
+/// Installed routing, shared by all dispatchers and providers
+#[cfg(feature = "std")]
+static STDIO: Mutex<StdioState> = Mutex::new(StdioState::new());
+
+/// Installed routing, shared by all dispatchers and providers
+#[cfg(not(feature = "std"))]
+static STDIO: wrt_sync::WrtMutex<StdioState> = wrt_sync::WrtMutex::new(StdioState::new());
+
+fn with_state<R>(f: impl FnOnce(&mut StdioState) -> R) -> Result<R> {
+    #[cfg(feature = "std")]
+    {
+        let mut state = STDIO
+            .lock()
+            .map_err(|_| Error::wasi_capability_unavailable("Failed to acquire stdio lock"))?;
+        Ok(f(&mut state))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        Ok(f(&mut STDIO.lock()))
+    }
+}
+
+/// Route the stdio of components through `stdio`
+///
+/// Replaces the routing installed before and empties the built-in buffers.
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned.
+pub fn install_stdio(stdio: WasiStdio) -> Result<()> {
+    with_state(|state| {
+        state.routes = stdio;
+        for buffer in &mut state.buffers {
+            buffer.clear();
+        }
+    })
+}
+
+/// Restore the default routing
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned.
+pub fn reset_stdio() -> Result<()> {
+    install_stdio(WasiStdio::new())
+}
+
+/// Move output buffered for `stream` into `out`, returning the number of
+/// bytes moved
+///
+/// Only streams routed to the built-in buffers are buffered. Draining frees
+/// room for the component to write more.
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned.
+pub fn drain_output(stream: StdStream, out: &mut [u8]) -> Result<usize> {
+    with_state(|state| state.buffers[stream.index()].pop(out))
+}
+
+/// Buffer `data` as standard input, returning the number of bytes taken
+///
+/// Takes only what fits in the built-in buffer.
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned.
+pub fn feed_input(data: &[u8]) -> Result<usize> {
+    with_state(|state| state.buffers[StdStream::Stdin.index()].push(data))
+}
+
+/// Mark the end of the buffered standard input
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned.
+pub fn close_input() -> Result<()> {
+    with_state(|state| state.buffers[StdStream::Stdin.index()].close())
+}
+
+/// Read component output with `sink`, returning the handle of the new
+/// output stream
+///
+/// The stream stays registered until [`close_stream`], independent of the
+/// installed stdio routing.
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned or the handles are
+/// exhausted.
+pub fn register_output_stream(sink: impl StdioSink + 'static) -> Result<u32> {
+    with_state(|state| state.register(HostStream::Output(Box::new(sink))))?
+}
+
+/// Feed component input from `source`, returning the handle of the new
+/// input stream
+///
+/// The stream stays registered until [`close_stream`], independent of the
+/// installed stdio routing.
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned or the handles are
+/// exhausted.
+pub fn register_input_stream(source: impl StdioSource + 'static) -> Result<u32> {
+    with_state(|state| state.register(HostStream::Input(Box::new(source))))?
+}
+
+/// Close the host stream with `handle`, dropping its sink or source
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned or no host stream has the
+/// handle.
+pub fn close_stream(handle: u32) -> Result<()> {
+    with_state(|state| state.streams.remove(&handle).map(drop).ok_or_else(unknown_stream))?
+}
+
+/// Whether the host stream with `handle` is an input stream
+pub(crate) fn is_input_stream(handle: u32) -> Result<bool> {
+    with_state(|state| match state.streams.get(&handle) {
+        Some(stream) => Ok(matches!(stream, HostStream::Input(_))),
+        None => Err(unknown_stream()),
+    })?
+}
+
+/// Write a prefix of `data` to `stream`, returning the number of bytes
+/// written
+pub(crate) fn write(stream: impl Into<StreamTarget>, data: &[u8]) -> Result<usize> {
+    with_state(|state| match stream.into() {
+        StreamTarget::Std(stream) => state.write(stream, data),
+        StreamTarget::Host(handle) => state.host_output(handle)?.write(data),
+    })?
+}
+
+/// Write all of `data` to `stream`
+///
+/// Fails when the sink stops accepting data before everything was written.
+pub(crate) fn write_all(stream: impl Into<StreamTarget>, mut data: &[u8]) -> Result<()> {
+    let stream = stream.into();
+    while !data.is_empty() {
+        let written = write(stream, data)?;
+        if written == 0 {
+            return Err(Error::wasi_resource_exhausted("Output stream is full"));
+        }
+        data = &data[written..];
+    }
+    Ok(())
+}
+
+/// Flush `stream`
+pub(crate) fn flush(stream: impl Into<StreamTarget>) -> Result<()> {
+    with_state(|state| match stream.into() {
+        StreamTarget::Std(stream) => state.flush(stream),
+        StreamTarget::Host(handle) => state.host_output(handle)?.flush(),
+    })?
+}
+
+/// Number of bytes `stream` accepts without blocking
+pub(crate) fn check_write(stream: impl Into<StreamTarget>) -> Result<usize> {
+    with_state(|state| match stream.into() {
+        StreamTarget::Std(stream) => state.available(stream),
+        StreamTarget::Host(handle) => Ok(state.host_output(handle)?.available()),
+    })?
+}
+
+/// Read up to `len` bytes of `stream`, `None` at the end of the stream
+pub(crate) fn read(stream: impl Into<StreamTarget>, len: usize) -> Result<Option<Vec<u8>>> {
+    let mut buffer = vec![0u8; len.min(STDIO_BUFFER_SIZE)];
+    let count = with_state(|state| match stream.into() {
+        StreamTarget::Std(StdStream::Stdin) => state.read_stdin(&mut buffer),
+        StreamTarget::Std(_) => Err(Error::wasi_invalid_fd("Output stream is not readable")),
+        StreamTarget::Host(handle) => state.host_input(handle)?.read(&mut buffer),
+    })??;
+    Ok(count.map(|count| {
+        buffer.truncate(count);
+        buffer
+    }))
+}
+
+/// Whether `stream` can be read or written without blocking
+///
+/// # Errors
+///
+/// Returns an error if the stdio lock is poisoned or no host stream has the
+/// handle.
+pub(crate) fn ready(stream: impl Into<StreamTarget>) -> Result<bool> {
+    with_state(|state| match stream.into() {
+        StreamTarget::Std(stream) => Ok(state.ready_std(stream)),
+        StreamTarget::Host(handle) => match state.streams.get(&handle) {
+            Some(HostStream::Input(source)) => Ok(source.ready()),
+            Some(HostStream::Output(sink)) => Ok(sink.available() > 0),
+            None => Err(unknown_stream()),
+        },
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_pipe_applies_backpressure() {
+        let mut pipe = BoundedPipe::<4>::new();
+        assert_eq!(pipe.push(b"abcdef"), 4);
+        assert_eq!(StdioSink::available(&pipe), 0);
+
+        let mut out = [0u8; 3];
+        assert_eq!(pipe.pop(&mut out), 3);
+        assert_eq!(&out, b"abc");
+        assert_eq!(pipe.push(b"xyz"), 3);
+
+        let mut out = [0u8; 8];
+        assert_eq!(pipe.read(&mut out).unwrap(), Some(4));
+        assert_eq!(&out[..4], b"dxyz");
+        assert_eq!(pipe.read(&mut out).unwrap(), Some(0));
+
+        pipe.close();
+        assert_eq!(pipe.read(&mut out).unwrap(), None);
+        assert!(StdioSink::write(&mut pipe, b"a").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_routing_through_buffers_and_interceptors() -> Result<()> {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&captured);
+        install_stdio(WasiStdio::buffered().with_interceptor(
+            move |stream: StdStream, data: &[u8]| {
+                if stream != StdStream::Stderr {
+                    return OutputAction::Forward;
+                }
+                log.lock().unwrap().extend_from_slice(data);
+                if data.starts_with(b"#") {
+                    OutputAction::Consume
+                } else {
+                    OutputAction::Forward
+                }
+            },
+        ))?;
+
+        // A consumed write succeeds without reaching the buffer
+        assert_eq!(write(StdStream::Stderr, b"#quiet")?, 6);
+        write_all(StdStream::Stderr, b"hello")?;
+        let mut out = [0u8; 16];
+        assert_eq!(drain_output(StdStream::Stderr, &mut out)?, 5);
+        assert_eq!(&out[..5], b"hello");
+        assert_eq!(captured.lock().unwrap().as_slice(), b"#quiethello");
+
+        // Writes are cut to the room left in the buffer
+        let large = [b'x'; STDIO_BUFFER_SIZE + 10];
+        assert_eq!(write(StdStream::Stderr, &large)?, STDIO_BUFFER_SIZE);
+        assert_eq!(check_write(StdStream::Stderr)?, 0);
+        assert!(!ready(StdStream::Stderr)?);
+        assert!(write_all(StdStream::Stderr, b"more").is_err());
+        assert_eq!(captured.lock().unwrap().len(), 11 + STDIO_BUFFER_SIZE);
+
+        assert_eq!(feed_input(b"in")?, 2);
+        assert_eq!(read(StdStream::Stdin, 16)?, Some(b"in".to_vec()));
+        assert_eq!(read(StdStream::Stdin, 16)?, Some(Vec::new()));
+        close_input()?;
+        assert!(ready(StdStream::Stdin)?);
+        assert_eq!(read(StdStream::Stdin, 16)?, None);
+        assert!(write(StdStream::Stdin, b"no").is_err());
+
+        reset_stdio()
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_host_streams_get_their_own_handles() -> Result<()> {
+        let first = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let second = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let input = Arc::new(Mutex::new(BoundedPipe::<16>::new()));
+        let first_handle = register_output_stream(Arc::clone(&first))?;
+        let second_handle = register_output_stream(Arc::clone(&second))?;
+        let input_handle = register_input_stream(Arc::clone(&input))?;
+        assert!(first_handle >= FIRST_HOST_STREAM);
+        assert_ne!(first_handle, second_handle);
+
+        let first_target = StreamTarget::from_handle(first_handle);
+        let input_target = StreamTarget::from_handle(input_handle);
+        write_all(first_target, b"one")?;
+        write_all(StreamTarget::from_handle(second_handle), b"two!")?;
+        assert_eq!(first.lock().unwrap().len(), 3);
+        assert_eq!(second.lock().unwrap().len(), 4);
+        assert_eq!(check_write(first_target)?, 13);
+
+        input.lock().unwrap().push(b"in");
+        assert!(ready(input_target)?);
+        assert_eq!(read(input_target, 16)?, Some(b"in".to_vec()));
+        assert!(!is_input_stream(first_handle)?);
+        assert!(read(first_target, 16).is_err());
+        assert!(write(input_target, b"no").is_err());
+
+        close_stream(first_handle)?;
+        assert!(write(first_target, b"gone").is_err());
+        assert!(ready(first_target).is_err());
+        assert!(close_stream(first_handle).is_err());
+        close_stream(second_handle)?;
+        close_stream(input_handle)
+    }
+}
@@ -0,0 +1,510 @@
+//! Adapters between host I/O types and `wasi:io` streams
+//!
+//! [`IoSource`] and [`IoSink`] connect any blocking or non-blocking
+//! [`std::io::Read`] and [`std::io::Write`] to a component stream. A
+//! `WouldBlock` from the host end is reported to the component as "no data
+//! yet" or a write accepting nothing, which the component sees as
+//! backpressure through `check-write` and its pollables.
+//!
+//! With the `tokio` feature, [`WasiInputStream::pipe`] and
+//! [`WasiOutputStream::pipe`] create a bounded pipe whose host end is a
+//! tokio `AsyncWrite` or `AsyncRead`, and [`input_from_async_read`] and
+//! [`output_to_async_write`] plumb a socket, file or in-memory pipe into a
+//! component. An adapter is attached as a stream with a handle of its own,
+//! or installed as one of the standard streams:
+//!
+//! ```rust,ignore
+//! let (input, pump) = wrt_wasi::stream_adapters::input_from_async_read(socket);
+//! tokio::spawn(pump);
+//! let handle = dispatcher.attach_input_stream(input)?;
+//!
+//! let (stdin, pump) = wrt_wasi::stream_adapters::input_from_async_read(file);
+//! tokio::spawn(pump);
+//! wrt_wasi::stdio::install_stdio(WasiStdio::new().with_stdin(stdin))?;
+//! ```
+//!
+//! The pipe bounds the bytes in flight: a full pipe makes the component's
+//! writes short and parks the host reader until the component reads, and an
+//! empty pipe parks the host reader until the component writes.
+
+use std::io;
+#[cfg(feature = "tokio")]
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    task::{
+        Context,
+        Poll,
+        Waker,
+    },
+};
+
+#[cfg(feature = "tokio")]
+use tokio::io::{
+    AsyncRead,
+    AsyncWrite,
+    AsyncWriteExt,
+    ReadBuf,
+};
+
+use crate::{
+    prelude::*,
+    stdio::{
+        StdioSink,
+        StdioSource,
+    },
+};
+#[cfg(feature = "tokio")]
+use crate::stdio::{
+    BoundedPipe,
+    STDIO_BUFFER_SIZE,
+};
+
+/// Component input stream reading from a [`std::io::Read`]
+#[derive(Debug)]
+pub struct IoSource<R> {
+    reader: R,
+}
+
+impl<R: io::Read + Send> IoSource<R> {
+    /// Read the stream from `reader`
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Host reader behind the stream
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: io::Read + Send> StdioSource for IoSource<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        loop {
+            match self.reader.read(buffer) {
+                Ok(0) if !buffer.is_empty() => return Ok(None),
+                Ok(count) => return Ok(Some(count)),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {},
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(Some(0)),
+                Err(_) => {
+                    return Err(Error::wasi_capability_unavailable("Failed to read host stream"));
+                },
+            }
+        }
+    }
+}
+
+/// Component output stream writing to a [`std::io::Write`]
+#[derive(Debug)]
+pub struct IoSink<W> {
+    writer: W,
+}
+
+impl<W: io::Write + Send> IoSink<W> {
+    /// Write the stream to `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Host writer behind the stream
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: io::Write + Send> StdioSink for IoSink<W> {
+    fn write(&mut self, data: &[u8]) -> Result<usize> {
+        loop {
+            match self.writer.write(data) {
+                Ok(count) => return Ok(count),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {},
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(0),
+                Err(_) => {
+                    return Err(Error::wasi_capability_unavailable("Failed to write host stream"));
+                },
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self.writer.flush() {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Ok(()),
+            Err(_) => Err(Error::wasi_capability_unavailable("Failed to flush host stream")),
+        }
+    }
+}
+
+/// Bytes in flight between the two ends of a pipe
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+struct PipeState<const N: usize> {
+    /// Buffered bytes; closed once the writing end is gone
+    pipe:        BoundedPipe<N>,
+    /// The reading end is gone and writes fail
+    reader_gone: bool,
+    /// Host task waiting for bytes
+    read_waker:  Option<Waker>,
+    /// Host task waiting for room
+    write_waker: Option<Waker>,
+}
+
+#[cfg(feature = "tokio")]
+type SharedPipe<const N: usize> = Arc<Mutex<PipeState<N>>>;
+
+#[cfg(feature = "tokio")]
+fn shared_pipe<const N: usize>() -> SharedPipe<N> {
+    Arc::new(Mutex::new(PipeState {
+        pipe:        BoundedPipe::new(),
+        reader_gone: false,
+        read_waker:  None,
+        write_waker: None,
+    }))
+}
+
+#[cfg(feature = "tokio")]
+fn lock_pipe<const N: usize>(shared: &SharedPipe<N>) -> Result<MutexGuard<'_, PipeState<N>>> {
+    shared
+        .lock()
+        .map_err(|_| Error::wasi_capability_unavailable("Stream pipe lock poisoned"))
+}
+
+#[cfg(feature = "tokio")]
+fn lock_pipe_io<const N: usize>(
+    shared: &SharedPipe<N>,
+) -> io::Result<MutexGuard<'_, PipeState<N>>> {
+    shared.lock().map_err(|_| io::Error::other("stream pipe lock poisoned"))
+}
+
+/// Component input stream fed by the host through an [`InputStreamWriter`]
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct WasiInputStream<const N: usize = STDIO_BUFFER_SIZE> {
+    shared: SharedPipe<N>,
+}
+
+/// Host end of a [`WasiInputStream`], a tokio `AsyncWrite`
+///
+/// Shutting it down or dropping it ends the component's stream once the
+/// buffered bytes are read.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct InputStreamWriter<const N: usize = STDIO_BUFFER_SIZE> {
+    shared: SharedPipe<N>,
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> WasiInputStream<N> {
+    /// Create an input stream buffering up to `N` bytes and its host end
+    pub fn pipe() -> (Self, InputStreamWriter<N>) {
+        let shared = shared_pipe();
+        (
+            Self {
+                shared: Arc::clone(&shared),
+            },
+            InputStreamWriter { shared },
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> StdioSource for WasiInputStream<N> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>> {
+        let mut state = lock_pipe(&self.shared)?;
+        let read = state.pipe.read(buffer)?;
+        if let Some(waker) = state.write_waker.take() {
+            waker.wake();
+        }
+        Ok(read)
+    }
+
+    fn ready(&self) -> bool {
+        lock_pipe(&self.shared)
+            .map(|state| StdioSource::ready(&state.pipe))
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> Drop for WasiInputStream<N> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.lock() {
+            state.reader_gone = true;
+            if let Some(waker) = state.write_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> AsyncWrite for InputStreamWriter<N> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut state = lock_pipe_io(&self.shared)?;
+        if state.reader_gone {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        let count = state.pipe.push(buf);
+        if count == 0 && !buf.is_empty() {
+            state.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        if let Some(waker) = state.read_waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(count))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        lock_pipe_io(&self.shared)?.pipe.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> Drop for InputStreamWriter<N> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.lock() {
+            state.pipe.close();
+        }
+    }
+}
+
+/// Component output stream drained by the host through an
+/// [`OutputStreamReader`]
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct WasiOutputStream<const N: usize = STDIO_BUFFER_SIZE> {
+    shared: SharedPipe<N>,
+}
+
+/// Host end of a [`WasiOutputStream`], a tokio `AsyncRead`
+///
+/// Reads reach the end of the stream once the component's stream is
+/// dropped and the buffered bytes are read.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct OutputStreamReader<const N: usize = STDIO_BUFFER_SIZE> {
+    shared: SharedPipe<N>,
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> WasiOutputStream<N> {
+    /// Create an output stream buffering up to `N` bytes and its host end
+    pub fn pipe() -> (Self, OutputStreamReader<N>) {
+        let shared = shared_pipe();
+        (
+            Self {
+                shared: Arc::clone(&shared),
+            },
+            OutputStreamReader { shared },
+        )
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> StdioSink for WasiOutputStream<N> {
+    fn write(&mut self, data: &[u8]) -> Result<usize> {
+        let mut state = lock_pipe(&self.shared)?;
+        if state.reader_gone {
+            return Err(Error::wasi_invalid_fd("Output stream is closed"));
+        }
+        let count = state.pipe.push(data);
+        if let Some(waker) = state.read_waker.take() {
+            waker.wake();
+        }
+        Ok(count)
+    }
+
+    fn available(&self) -> usize {
+        lock_pipe(&self.shared)
+            .map(|state| if state.reader_gone { 0 } else { state.pipe.free() })
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> Drop for WasiOutputStream<N> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.lock() {
+            state.pipe.close();
+            if let Some(waker) = state.read_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> AsyncRead for OutputStreamReader<N> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut state = lock_pipe_io(&self.shared)?;
+        let count = state.pipe.pop(buf.initialize_unfilled());
+        buf.advance(count);
+        if count == 0 && buf.remaining() > 0 && !state.pipe.is_closed() {
+            state.read_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> Drop for OutputStreamReader<N> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.lock() {
+            state.reader_gone = true;
+        }
+    }
+}
+
+/// Input stream fed from `reader`, with the future that pumps it
+///
+/// The future copies from `reader` until its end and resolves to the number
+/// of bytes copied, or to a `BrokenPipe` error once the component dropped
+/// the stream; spawn it on the embedder's runtime.
+#[cfg(feature = "tokio")]
+pub fn input_from_async_read<R>(
+    mut reader: R,
+) -> (
+    WasiInputStream,
+    impl Future<Output = io::Result<u64>> + Send,
+)
+where
+    R: AsyncRead + Unpin + Send,
+{
+    let (stream, mut writer) = WasiInputStream::pipe();
+    let pump = async move {
+        let copied = tokio::io::copy(&mut reader, &mut writer).await?;
+        writer.shutdown().await?;
+        Ok(copied)
+    };
+    (stream, pump)
+}
+
+/// Output stream drained into `writer`, with the future that pumps it
+///
+/// The future copies into `writer` until the component drops the stream,
+/// flushes it and resolves to the number of bytes copied; spawn it on the
+/// embedder's runtime.
+#[cfg(feature = "tokio")]
+pub fn output_to_async_write<W>(
+    mut writer: W,
+) -> (
+    WasiOutputStream,
+    impl Future<Output = io::Result<u64>> + Send,
+)
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    let (stream, mut reader) = WasiOutputStream::pipe();
+    let pump = async move {
+        let copied = tokio::io::copy(&mut reader, &mut writer).await?;
+        writer.flush().await?;
+        Ok(copied)
+    };
+    (stream, pump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Host writer accepting `room` bytes before it blocks
+    struct Throttled {
+        written: Vec<u8>,
+        room:    usize,
+    }
+
+    impl io::Write for Throttled {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            if self.room == 0 {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let count = data.len().min(self.room);
+            self.written.extend_from_slice(&data[..count]);
+            self.room -= count;
+            Ok(count)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_io_adapters_map_would_block_to_backpressure() -> Result<()> {
+        let mut sink = IoSink::new(Throttled {
+            written: Vec::new(),
+            room:    4,
+        });
+        assert_eq!(sink.write(b"abcdef")?, 4);
+        assert_eq!(sink.write(b"ef")?, 0);
+        assert_eq!(sink.into_inner().written, b"abcd");
+
+        let mut source = IoSource::new(&b"xy"[..]);
+        let mut buffer = [0u8; 4];
+        assert_eq!(source.read(&mut buffer)?, Some(2));
+        assert_eq!(source.read(&mut buffer)?, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_pipes_apply_backpressure_to_both_ends() -> Result<()> {
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // The host writer parks on a full pipe until the component reads
+        let (mut input, mut writer) = WasiInputStream::<4>::pipe();
+        let poll = Pin::new(&mut writer).poll_write(&mut cx, b"abcdef");
+        assert!(matches!(poll, Poll::Ready(Ok(4))));
+        assert!(Pin::new(&mut writer).poll_write(&mut cx, b"ef").is_pending());
+        let mut buffer = [0u8; 3];
+        assert_eq!(input.read(&mut buffer)?, Some(3));
+        assert!(matches!(
+            Pin::new(&mut writer).poll_write(&mut cx, b"ef"),
+            Poll::Ready(Ok(2))
+        ));
+        drop(writer);
+        let mut buffer = [0u8; 8];
+        assert_eq!(input.read(&mut buffer)?, Some(3));
+        assert_eq!(&buffer[..3], b"def");
+        assert_eq!(input.read(&mut buffer)?, None);
+
+        // Component writes are cut to the room left for the host reader
+        let (mut output, mut reader) = WasiOutputStream::<4>::pipe();
+        assert_eq!(output.write(b"hello")?, 4);
+        assert_eq!(output.available(), 0);
+        let mut out = [0u8; 8];
+        let mut read_buf = ReadBuf::new(&mut out);
+        assert!(Pin::new(&mut reader).poll_read(&mut cx, &mut read_buf).is_ready());
+        assert_eq!(read_buf.filled(), b"hell");
+        let mut read_buf = ReadBuf::new(&mut out);
+        assert!(Pin::new(&mut reader).poll_read(&mut cx, &mut read_buf).is_pending());
+        drop(output);
+        assert!(Pin::new(&mut reader).poll_read(&mut cx, &mut read_buf).is_ready());
+        assert!(read_buf.filled().is_empty());
+
+        let (mut output, reader) = WasiOutputStream::<4>::pipe();
+        drop(reader);
+        assert!(output.write(b"x").is_err());
+        Ok(())
+    }
+}
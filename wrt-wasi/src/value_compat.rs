@@ -3,6 +3,11 @@
 //! This module provides a simplified Value enum that matches the interface
 //! expected by WASI components while being compatible with wrt-foundation's
 //! component value system.
+//!
+//! With `std`, the type implements [`ValueInterop`], so it converts
+//! losslessly to and from `wrt_foundation::values::Value`. This enum is kept
+//! for the existing WASI host functions; new code should use the foundation
+//! `Value` and convert at the boundary instead of adding more adapters.
 
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 use alloc::boxed::Box;
@@ -14,6 +19,21 @@
 use std::vec::Vec;
 
 use wrt_foundation::prelude::*;
+#[cfg(feature = "std")]
+use wrt_foundation::{
+    float_repr::{
+        FloatBits32,
+        FloatBits64,
+    },
+    value_interop::{
+        normalize_core_value,
+        unrepresentable_value,
+        values_from,
+        values_into,
+        ValueInterop,
+    },
+    values::Value as FoundationValue,
+};
 #[cfg(all(not(feature = "std"), not(feature = "alloc")))]
 type Box<T> = T; // Simple workaround for no_std without alloc
 #[cfg(not(feature = "std"))]
@@ -255,3 +275,70 @@ pub fn as_bool(&self) -> bool {
         }
     }
 }
+
+/// Lossless conversion to the shared `wrt_foundation::values::Value`
+///
+/// New host functions should take `wrt_foundation::values::Value` directly;
+/// this implementation lets existing WASI code keep using this type while
+/// talking to them.
+#[cfg(feature = "std")]
+impl ValueInterop for Value {
+    fn into_value(self) -> FoundationValue {
+        match self {
+            Value::Bool(v) => FoundationValue::Bool(v),
+            Value::U8(v) => FoundationValue::U8(v),
+            Value::U16(v) => FoundationValue::U16(v),
+            Value::U32(v) => FoundationValue::U32(v),
+            Value::U64(v) => FoundationValue::U64(v),
+            Value::S8(v) => FoundationValue::S8(v),
+            Value::S16(v) => FoundationValue::S16(v),
+            Value::S32(v) => FoundationValue::S32(v),
+            Value::S64(v) => FoundationValue::S64(v),
+            Value::F32(v) => FoundationValue::F32(FloatBits32::from_float(v)),
+            Value::F64(v) => FoundationValue::F64(FloatBits64::from_float(v)),
+            Value::String(v) => FoundationValue::String(v),
+            Value::List(items) => FoundationValue::List(values_into(items)),
+            Value::Record(fields) => FoundationValue::Record(
+                fields.into_iter().map(|(name, value)| (name, value.into_value())).collect(),
+            ),
+            Value::Option(v) => FoundationValue::Option(v.map(|v| Box::new(v.into_value()))),
+            Value::Result(v) => FoundationValue::Result(match v {
+                Ok(v) => Ok(Box::new(v.into_value())),
+                Err(v) => Err(Box::new(v.into_value())),
+            }),
+            Value::Tuple(items) => FoundationValue::Tuple(values_into(items)),
+        }
+    }
+
+    fn try_from_value(value: FoundationValue) -> Result<Self> {
+        let boxed = |v: Box<FoundationValue>| Self::try_from_value(*v).map(Box::new);
+        Ok(match normalize_core_value(value) {
+            FoundationValue::Bool(v) => Value::Bool(v),
+            FoundationValue::U8(v) => Value::U8(v),
+            FoundationValue::U16(v) => Value::U16(v),
+            FoundationValue::U32(v) => Value::U32(v),
+            FoundationValue::U64(v) => Value::U64(v),
+            FoundationValue::S8(v) => Value::S8(v),
+            FoundationValue::S16(v) => Value::S16(v),
+            FoundationValue::S32(v) => Value::S32(v),
+            FoundationValue::S64(v) => Value::S64(v),
+            FoundationValue::F32(v) => Value::F32(f32::from_bits(v.0)),
+            FoundationValue::F64(v) => Value::F64(f64::from_bits(v.0)),
+            FoundationValue::String(v) => Value::String(v),
+            FoundationValue::List(items) => Value::List(values_from(items)?),
+            FoundationValue::Record(fields) => Value::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, Self::try_from_value(value)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            FoundationValue::Option(v) => Value::Option(v.map(boxed).transpose()?),
+            FoundationValue::Result(v) => Value::Result(match v {
+                Ok(v) => Ok(boxed(v)?),
+                Err(v) => Err(boxed(v)?),
+            }),
+            FoundationValue::Tuple(items) => Value::Tuple(values_from(items)?),
+            _ => return Err(unrepresentable_value()),
+        })
+    }
+}
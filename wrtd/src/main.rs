@@ -627,6 +627,7 @@ fn execute_traditional_module(&mut self, data: &[u8]) -> Result<()> {
                 CapabilityAwareEngine,
                 CapabilityEngine,
                 EnginePreset,
+                ShutdownPolicy,
             };
 
             // Determine engine preset from features
@@ -733,6 +734,15 @@ fn execute_traditional_module(&mut self, data: &[u8]) -> Result<()> {
                 println!("\n✓ Function '{}' completed (no return values)", function_name);
             }
 
+            // Let the instance persist its state before the process exits
+            for (_, outcome) in engine.shutdown(ShutdownPolicy::default()) {
+                if !outcome.is_clean() {
+                    let _ = self
+                        .logger
+                        .handle_minimal_log(LogLevel::Warn, "Instance did not shut down cleanly");
+                }
+            }
+
             self.stats.modules_executed += 1;
         }
 